@@ -13,6 +13,7 @@ export interface ChannelInfo {
 	id: string;
 	platform: string;
 	display_name: string | null;
+	title: string | null;
 	is_active: boolean;
 	last_activity_at: string;
 	created_at: string;
@@ -40,6 +41,14 @@ export interface OutboundMessageEvent {
 	text: string;
 }
 
+export interface ReplyDraftEvent {
+	type: "reply_draft";
+	agent_id: string;
+	channel_id: string;
+	approval_token: string;
+	text: string;
+}
+
 export interface TypingStateEvent {
 	type: "typing_state";
 	agent_id: string;
@@ -71,8 +80,27 @@ export interface WorkerCompletedEvent {
 	worker_id: string;
 	result: string;
 	success?: boolean;
+	status?: WorkerResultStatus;
+	summary?: string;
+	artifacts?: WorkerArtifact[];
+}
+
+export interface ApprovalRequestedEvent {
+	type: "approval_requested";
+	agent_id: string;
+	channel_id: string;
+	worker_id: string;
+	approval_id: string;
+	tool_name: string;
+	args: string;
 }
 
+export type WorkerResultStatus = "success" | "partial" | "failed";
+
+export type WorkerArtifact =
+	| { type: "file"; path: string }
+	| { type: "url"; url: string };
+
 export interface BranchStartedEvent {
 	type: "branch_started";
 	agent_id: string;
@@ -112,10 +140,12 @@ export interface ToolCompletedEvent {
 export type ApiEvent =
 	| InboundMessageEvent
 	| OutboundMessageEvent
+	| ReplyDraftEvent
 	| TypingStateEvent
 	| WorkerStartedEvent
 	| WorkerStatusEvent
 	| WorkerCompletedEvent
+	| ApprovalRequestedEvent
 	| BranchStartedEvent
 	| BranchCompletedEvent
 	| ToolStartedEvent
@@ -514,7 +544,9 @@ export interface RoutingSection {
 	compactor: string;
 	cortex: string;
 	voice: string;
+	vision: string;
 	rate_limit_cooldown_secs: number;
+	retry_base_delay_ms: number;
 	channel_thinking_effort: string;
 	branch_thinking_effort: string;
 	worker_thinking_effort: string;
@@ -590,7 +622,9 @@ export interface RoutingUpdate {
 	compactor?: string;
 	cortex?: string;
 	voice?: string;
+	vision?: string;
 	rate_limit_cooldown_secs?: number;
+	retry_base_delay_ms?: number;
 	channel_thinking_effort?: string;
 	branch_thinking_effort?: string;
 	worker_thinking_effort?: string;
@@ -664,9 +698,11 @@ export interface CronJobWithStats {
 	id: string;
 	prompt: string;
 	interval_secs: number;
+	cron_expr: string | null;
 	delivery_target: string;
 	enabled: boolean;
 	run_once: boolean;
+	stateful: boolean;
 	active_hours: [number, number] | null;
 	success_count: number;
 	failure_count: number;
@@ -697,11 +733,13 @@ export interface CreateCronRequest {
 	id: string;
 	prompt: string;
 	interval_secs: number;
+	cron_expr?: string;
 	delivery_target: string;
 	active_start_hour?: number;
 	active_end_hour?: number;
 	enabled: boolean;
 	run_once: boolean;
+	stateful?: boolean;
 }
 
 export interface CronExecutionsParams {
@@ -26,7 +26,7 @@ const SECTIONS: {
 	{ id: "soul", label: "Soul", group: "identity", description: "SOUL.md", detail: "Defines the agent's personality, values, communication style, and behavioral boundaries. This is the core of who the agent is." },
 	{ id: "identity", label: "Identity", group: "identity", description: "IDENTITY.md", detail: "The agent's name, nature, and purpose. How it introduces itself and what it understands its role to be." },
 	{ id: "user", label: "User", group: "identity", description: "USER.md", detail: "Information about the human this agent interacts with. Name, preferences, context, and anything that helps the agent personalize responses." },
-	{ id: "routing", label: "Model Routing", group: "config", description: "Which models each process uses", detail: "Controls which LLM model is used for each process type. Channels handle user-facing conversation, branches do thinking, workers execute tasks, the compactor summarizes context, cortex observes system state, and voice transcribes audio attachments before the channel turn." },
+	{ id: "routing", label: "Model Routing", group: "config", description: "Which models each process uses", detail: "Controls which LLM model is used for each process type. Channels handle user-facing conversation, branches do thinking, workers execute tasks, the compactor summarizes context, cortex observes system state, voice transcribes audio attachments before the channel turn, and vision captions images for channel models that can't take image input." },
 	{ id: "tuning", label: "Tuning", group: "config", description: "Turn limits, context window, branches", detail: "Core limits that control how much work the agent does per message. Max turns caps LLM iterations per channel message. Context window sets the token budget. Branch limits control parallel thinking." },
 	{ id: "compaction", label: "Compaction", group: "config", description: "Context compaction thresholds", detail: "Thresholds that trigger context summarization as the conversation grows. Background kicks in early, aggressive compresses harder, and emergency truncates without LLM involvement. All values are fractions of the context window." },
 	{ id: "cortex", label: "Cortex", group: "config", description: "System observer settings", detail: "The cortex monitors active processes and generates memory bulletins. Tick interval controls observation frequency. Timeouts determine when stuck workers or branches get cancelled. The circuit breaker auto-disables after consecutive failures." },
@@ -499,6 +499,7 @@ function ConfigSectionEditor({ sectionId, label, description, detail, config, on
 					{ key: "compactor", label: "Compactor Model", description: "Model for summarization" },
 					{ key: "cortex", label: "Cortex Model", description: "Model for system observation" },
 					{ key: "voice", label: "Voice Model", description: "Model for transcribing audio attachments" },
+					{ key: "vision", label: "Vision Model", description: "Model for captioning images when the channel model can't see them" },
 				];
 				return (
 					<div className="grid gap-4">
@@ -541,6 +542,15 @@ function ConfigSectionEditor({ sectionId, label, description, detail, config, on
 							min={0}
 							suffix="s"
 						/>
+						<NumberStepper
+							label="Retry Backoff"
+							description="Base delay before retrying a failed model call, doubled on each attempt"
+							value={localValues.retry_base_delay_ms as number}
+							onChange={(v) => handleChange("retry_base_delay_ms", v)}
+							min={0}
+							step={100}
+							suffix="ms"
+						/>
 					</div>
 				);
 			}
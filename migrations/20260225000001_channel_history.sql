@@ -0,0 +1,7 @@
+-- Serialized LLM-facing history per channel, so channels survive restarts.
+CREATE TABLE IF NOT EXISTS channel_history (
+    channel_id TEXT PRIMARY KEY,
+    messages TEXT NOT NULL,
+    message_count INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,3 @@
+-- Optional cron expression for cron_jobs. When set it replaces interval_secs
+-- as the job's schedule (e.g. "0 8 * * 1-5" for weekday mornings).
+ALTER TABLE cron_jobs ADD COLUMN cron_expr TEXT;
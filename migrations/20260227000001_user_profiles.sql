@@ -0,0 +1,11 @@
+-- Long-term facts about the people the agent talks to, keyed by sender_id.
+-- Shared across every channel the agent is in.
+CREATE TABLE IF NOT EXISTS user_profiles (
+    sender_id TEXT PRIMARY KEY NOT NULL,
+    display_name TEXT,                      -- last platform display name seen
+    preferred_name TEXT,                    -- what the person asked to be called
+    timezone TEXT,                          -- IANA timezone name
+    preferences TEXT NOT NULL DEFAULT '[]', -- JSON array of standing preferences
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
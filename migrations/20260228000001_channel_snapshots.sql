@@ -0,0 +1,7 @@
+-- Channel state written when an idle channel is archived, restored when it is
+-- rehydrated by the next inbound message.
+CREATE TABLE IF NOT EXISTS channel_snapshots (
+    channel_id TEXT PRIMARY KEY,
+    snapshot TEXT NOT NULL,
+    archived_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
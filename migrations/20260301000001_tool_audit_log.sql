@@ -0,0 +1,18 @@
+-- Append-only record of every tool call made by channels, branches, and workers.
+CREATE TABLE IF NOT EXISTS tool_audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    process_id TEXT NOT NULL,
+    process_type TEXT NOT NULL,
+    channel_id TEXT,
+    tool_name TEXT NOT NULL,
+    arguments TEXT NOT NULL,
+    result_summary TEXT NOT NULL,
+    duration_ms INTEGER,
+    input_tokens INTEGER NOT NULL DEFAULT 0,
+    output_tokens INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_tool_audit_log_created_at ON tool_audit_log(created_at);
+CREATE INDEX IF NOT EXISTS idx_tool_audit_log_process_id ON tool_audit_log(process_id);
+CREATE INDEX IF NOT EXISTS idx_tool_audit_log_channel_id ON tool_audit_log(channel_id);
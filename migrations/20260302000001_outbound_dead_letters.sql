@@ -0,0 +1,15 @@
+-- Outbound responses an adapter failed to deliver after retrying. Re-sent once
+-- the adapter is healthy again; rows are deleted on successful delivery.
+CREATE TABLE IF NOT EXISTS outbound_dead_letters (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    adapter TEXT NOT NULL,
+    conversation_id TEXT NOT NULL,
+    message TEXT NOT NULL,
+    response TEXT NOT NULL,
+    last_error TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    last_attempt_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbound_dead_letters_adapter ON outbound_dead_letters(adapter);
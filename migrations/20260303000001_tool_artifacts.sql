@@ -0,0 +1,10 @@
+-- Full tool results that were too large to hand back to the LLM. The LLM gets
+-- a preview plus the artifact id and pages through the rest with read_artifact.
+CREATE TABLE IF NOT EXISTS tool_artifacts (
+    id TEXT PRIMARY KEY,
+    tool_name TEXT NOT NULL,
+    content TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_tool_artifacts_created_at ON tool_artifacts(created_at);
@@ -0,0 +1,11 @@
+-- Documents in the agent's knowledge base. The chunks and their embeddings
+-- live in the LanceDB `knowledge_chunks` table, keyed by document id.
+CREATE TABLE IF NOT EXISTS knowledge_documents (
+    id TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    source TEXT NOT NULL,
+    content_hash TEXT NOT NULL UNIQUE,
+    chunk_count INTEGER NOT NULL,
+    char_count INTEGER NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,20 @@
+-- Supervised channels hold the agent's replies as drafts until an operator
+-- approves (optionally editing) or rejects them. `draft_approvers` is a JSON
+-- array of platform user IDs allowed to decide by reaction.
+ALTER TABLE channels ADD COLUMN supervised INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE channels ADD COLUMN draft_approvers TEXT;
+
+CREATE TABLE IF NOT EXISTS reply_drafts (
+    approval_token TEXT PRIMARY KEY,
+    channel_id TEXT NOT NULL,
+    text TEXT NOT NULL,
+    in_reply_to TEXT,
+    trigger_message_id TEXT,
+    status TEXT NOT NULL DEFAULT 'pending',
+    final_text TEXT,
+    resolved_by TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_reply_drafts_channel ON reply_drafts(channel_id, status);
@@ -0,0 +1,12 @@
+-- One row per text that had sensitive values redacted before reaching the
+-- LLM. Only kinds and counts are recorded, never the redacted values.
+CREATE TABLE IF NOT EXISTS redaction_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source TEXT NOT NULL,
+    channel_id TEXT,
+    kinds TEXT NOT NULL,
+    redacted_count INTEGER NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_redaction_log_created_at ON redaction_log(created_at);
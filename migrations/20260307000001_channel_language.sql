@@ -0,0 +1,3 @@
+-- Language detected from a conversation's first messages (ISO 639-1 code).
+-- NULL until detection succeeds.
+ALTER TABLE channels ADD COLUMN language TEXT;
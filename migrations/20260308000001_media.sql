@@ -0,0 +1,15 @@
+-- Stored attachments: files users sent, worker artifacts, and files the agent
+-- sent. The bytes live in the configured backend (local disk or an S3
+-- bucket) under the row's id; this table holds what's needed to serve them.
+CREATE TABLE IF NOT EXISTS media (
+    id TEXT PRIMARY KEY,
+    channel_id TEXT,
+    source TEXT NOT NULL,
+    filename TEXT NOT NULL,
+    mime_type TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    sha256 TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_media_channel ON media(channel_id, created_at);
@@ -0,0 +1,8 @@
+-- Who a memory is visible to. 'agent' memories surface everywhere,
+-- 'conversation' memories only in the channel that saved them, and 'user'
+-- memories only when the sender who owns them is talking. Existing memories
+-- keep their old everywhere-visible behaviour.
+ALTER TABLE memories ADD COLUMN scope TEXT NOT NULL DEFAULT 'agent';
+ALTER TABLE memories ADD COLUMN owner_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_memories_scope ON memories(scope);
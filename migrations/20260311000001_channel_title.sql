@@ -0,0 +1,2 @@
+-- Title generated from a conversation's first turns. NULL until one is set.
+ALTER TABLE channels ADD COLUMN title TEXT;
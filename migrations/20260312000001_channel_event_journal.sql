@@ -0,0 +1,11 @@
+-- Branch and worker results addressed to a channel, kept until the channel
+-- has handled them so a rehydrated channel can replay what it missed.
+-- `event` is the serialized ProcessEvent; NULL marks a process the channel
+-- cancelled, whose result must not be replayed.
+CREATE TABLE IF NOT EXISTS channel_event_journal (
+    channel_id TEXT NOT NULL,
+    process_id TEXT NOT NULL,
+    event TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (channel_id, process_id)
+);
@@ -0,0 +1,5 @@
+-- Stateful cron jobs run their prompt as a worker that keeps a small state
+-- blob between runs (e.g. which issues it already reported). `state` is the
+-- blob saved by the last successful run; NULL before the first one.
+ALTER TABLE cron_jobs ADD COLUMN stateful INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE cron_jobs ADD COLUMN state TEXT;
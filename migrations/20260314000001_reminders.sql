@@ -0,0 +1,20 @@
+-- Reminders set with the `remind_me` tool. When one is due it's delivered to
+-- its conversation as a message, so the channel writes the reminder itself.
+-- `metadata` is the JSON metadata of the message that set the reminder, which
+-- the messaging adapter needs to post back into the same conversation.
+CREATE TABLE IF NOT EXISTS reminders (
+    id TEXT PRIMARY KEY NOT NULL,
+    conversation_id TEXT NOT NULL,
+    source TEXT NOT NULL,                  -- messaging adapter name
+    sender_id TEXT NOT NULL,               -- who asked for the reminder
+    sender_name TEXT,
+    message TEXT NOT NULL,
+    fire_at INTEGER NOT NULL,              -- next delivery, unix seconds
+    cron_expr TEXT,                        -- set for recurring reminders
+    timezone TEXT,                         -- IANA timezone cron_expr is read in
+    metadata TEXT NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_reminders_fire_at ON reminders(fire_at);
+CREATE INDEX IF NOT EXISTS idx_reminders_conversation ON reminders(conversation_id);
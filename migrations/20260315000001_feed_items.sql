@@ -0,0 +1,12 @@
+-- Entries already seen in each watched feed, so only new ones go into a
+-- digest. `last_seen_at` is bumped on every poll that still lists the entry;
+-- rows that drop out of the feed are pruned after a while.
+CREATE TABLE IF NOT EXISTS feed_items (
+    feed TEXT NOT NULL,                    -- feed name from [[agents.feeds]]
+    item_key TEXT NOT NULL,                -- entry guid/id, else its link, else its title
+    first_seen_at INTEGER NOT NULL,        -- unix seconds
+    last_seen_at INTEGER NOT NULL,         -- unix seconds
+    PRIMARY KEY (feed, item_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_feed_items_last_seen ON feed_items(last_seen_at);
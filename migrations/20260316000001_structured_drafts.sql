@@ -0,0 +1,3 @@
+-- Structured replies held in supervised channels keep their action and
+-- confidence (as JSON) so approval delivers them in the same shape.
+ALTER TABLE reply_drafts ADD COLUMN structured TEXT;
@@ -0,0 +1,11 @@
+-- Running LLM usage per channel, including the channel's branches and
+-- workers, so the hard budget holds across restarts and archiving.
+CREATE TABLE IF NOT EXISTS channel_usage (
+    channel_id TEXT PRIMARY KEY NOT NULL,
+    completion_calls INTEGER NOT NULL DEFAULT 0,
+    input_tokens INTEGER NOT NULL DEFAULT 0,
+    output_tokens INTEGER NOT NULL DEFAULT 0,
+    cached_input_tokens INTEGER NOT NULL DEFAULT 0,
+    estimated_cost_usd REAL NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
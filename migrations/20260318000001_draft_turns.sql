@@ -0,0 +1,3 @@
+-- The turn that wrote a held reply, so the reply is attributed to that turn
+-- when it's approved later.
+ALTER TABLE reply_drafts ADD COLUMN turn_id TEXT;
@@ -0,0 +1,32 @@
+-- Memory embeddings for the `sqlite` storage backend. Embeddings are
+-- little-endian f32 blobs; keyword search goes through an FTS5 index kept
+-- in sync by triggers.
+CREATE TABLE IF NOT EXISTS memory_embeddings (
+    memory_id TEXT PRIMARY KEY,
+    content TEXT NOT NULL,
+    embedding BLOB NOT NULL,
+    scope TEXT NOT NULL DEFAULT 'agent',
+    channel_id TEXT,
+    owner_id TEXT
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS memory_embeddings_fts USING fts5(
+    content,
+    content = 'memory_embeddings',
+    content_rowid = 'rowid'
+);
+
+CREATE TRIGGER IF NOT EXISTS memory_embeddings_ai AFTER INSERT ON memory_embeddings BEGIN
+    INSERT INTO memory_embeddings_fts (rowid, content) VALUES (new.rowid, new.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS memory_embeddings_ad AFTER DELETE ON memory_embeddings BEGIN
+    INSERT INTO memory_embeddings_fts (memory_embeddings_fts, rowid, content)
+    VALUES ('delete', old.rowid, old.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS memory_embeddings_au AFTER UPDATE ON memory_embeddings BEGIN
+    INSERT INTO memory_embeddings_fts (memory_embeddings_fts, rowid, content)
+    VALUES ('delete', old.rowid, old.content);
+    INSERT INTO memory_embeddings_fts (rowid, content) VALUES (new.rowid, new.content);
+END;
@@ -0,0 +1,32 @@
+-- Vector storage for the Postgres backend. Memory and knowledge rows
+-- themselves stay in each agent's SQLite database; these tables hold only
+-- what similarity and keyword search need, keyed by agent.
+
+CREATE EXTENSION IF NOT EXISTS vector;
+
+CREATE TABLE IF NOT EXISTS memory_embeddings (
+    agent_id TEXT NOT NULL,
+    memory_id TEXT NOT NULL,
+    content TEXT NOT NULL,
+    embedding vector(384) NOT NULL,
+    content_tsv tsvector GENERATED ALWAYS AS (to_tsvector('simple', content)) STORED,
+    PRIMARY KEY (agent_id, memory_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_embeddings_hnsw
+    ON memory_embeddings USING hnsw (embedding vector_l2_ops);
+CREATE INDEX IF NOT EXISTS idx_memory_embeddings_fts
+    ON memory_embeddings USING gin (content_tsv);
+
+CREATE TABLE IF NOT EXISTS knowledge_chunks (
+    agent_id TEXT NOT NULL,
+    document_id TEXT NOT NULL,
+    chunk_index INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    content TEXT NOT NULL,
+    embedding vector(384) NOT NULL,
+    PRIMARY KEY (agent_id, document_id, chunk_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_knowledge_chunks_hnsw
+    ON knowledge_chunks USING hnsw (embedding vector_cosine_ops);
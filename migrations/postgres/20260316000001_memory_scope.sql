@@ -0,0 +1,8 @@
+-- Each embedding carries its memory's scope, channel, and owner, so searches
+-- for a conversation or user filter in SQL rather than spending the result
+-- limit on memories the requester can't see. Rows written before this
+-- migration count as agent-scoped until the memory is saved again; retrieval
+-- still checks every memory's scope against the SQLite row afterwards.
+ALTER TABLE memory_embeddings ADD COLUMN IF NOT EXISTS scope TEXT NOT NULL DEFAULT 'agent';
+ALTER TABLE memory_embeddings ADD COLUMN IF NOT EXISTS channel_id TEXT;
+ALTER TABLE memory_embeddings ADD COLUMN IF NOT EXISTS owner_id TEXT;
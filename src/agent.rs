@@ -1,10 +1,20 @@
-//! Agent processes: channels, branches, workers, compactor, cortex.
+//! Agent processes: channels, branches, workers, compactor, cortex, heartbeat.
 
+pub mod approval;
 pub mod branch;
 pub mod channel;
+pub mod commands;
 pub mod compactor;
 pub mod cortex;
 pub mod cortex_chat;
+pub mod cost;
+pub mod feedback;
+pub mod gating;
+pub mod heartbeat;
 pub mod ingestion;
+pub mod lanes;
+pub mod plan;
 pub mod status;
 pub mod worker;
+pub mod worker_pool;
+pub mod worker_workspace;
@@ -0,0 +1,202 @@
+//! Approval of tool calls: workers wait for the user before running tools
+//! that require it.
+//!
+//! Tools listed in `tool_policy.requires_approval` don't run as soon as a
+//! worker calls them. The worker's hook files a request with its channel's
+//! [`Approvals`] and sends `ProcessEvent::ApprovalRequested`; the channel
+//! posts the tool name and the exact arguments, and the worker waits. An
+//! `approve` reply runs the call and the worker carries on. `deny`, or no
+//! answer within [`APPROVAL_TIMEOUT`], stops the worker. Workers without a
+//! conversation to ask in, like stateful cron jobs, never run these tools.
+
+use crate::WorkerId;
+
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// How long a worker waits for an answer before giving up.
+pub const APPROVAL_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// The user's answer to an approval request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Approve,
+    Deny,
+}
+
+/// An `approve` or `deny` message, optionally naming the request it answers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalReply {
+    pub decision: Decision,
+    pub approval_id: Option<String>,
+}
+
+/// Parse `approve`, `deny`, `approve <id>`, or `deny <id>`. Anything else,
+/// like "approve it for now", is an ordinary message.
+pub fn parse_reply(text: &str) -> Option<ApprovalReply> {
+    let mut words = text.split_whitespace();
+    let decision = match words
+        .next()?
+        .trim_end_matches(['.', '!'])
+        .to_lowercase()
+        .as_str()
+    {
+        "approve" => Decision::Approve,
+        "deny" => Decision::Deny,
+        _ => return None,
+    };
+    let approval_id = match words.next() {
+        Some(word) if is_approval_id(word) => Some(word.to_lowercase()),
+        Some(_) => return None,
+        None => None,
+    };
+    if words.next().is_some() {
+        return None;
+    }
+    Some(ApprovalReply {
+        decision,
+        approval_id,
+    })
+}
+
+fn is_approval_id(word: &str) -> bool {
+    word.len() == 8 && word.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// A tool call waiting for approval.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalRequest {
+    /// Short ID the user can name in their reply.
+    pub approval_id: String,
+    pub worker_id: WorkerId,
+    pub tool_name: String,
+    /// The call's JSON arguments, exactly as the model sent them.
+    pub args: String,
+}
+
+#[derive(Debug)]
+struct Pending {
+    request: ApprovalRequest,
+    decision_tx: oneshot::Sender<Decision>,
+}
+
+/// The approval requests of a channel's workers, oldest first.
+#[derive(Debug, Default)]
+pub struct Approvals {
+    pending: Mutex<Vec<Pending>>,
+}
+
+impl Approvals {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// File a request. The receiver yields the user's decision; it errors if
+    /// the request is forgotten without one.
+    pub fn request(
+        &self,
+        worker_id: WorkerId,
+        tool_name: &str,
+        args: &str,
+    ) -> (ApprovalRequest, oneshot::Receiver<Decision>) {
+        let request = ApprovalRequest {
+            approval_id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            worker_id,
+            tool_name: tool_name.to_string(),
+            args: args.to_string(),
+        };
+        let (decision_tx, decision_rx) = oneshot::channel();
+        self.pending
+            .lock()
+            .expect("approvals lock poisoned")
+            .push(Pending {
+                request: request.clone(),
+                decision_tx,
+            });
+        (request, decision_rx)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending
+            .lock()
+            .expect("approvals lock poisoned")
+            .is_empty()
+    }
+
+    /// Answer the request with `approval_id`, or the oldest one when no ID
+    /// is given. Returns the answered request, or `None` if there's no such
+    /// request.
+    pub fn resolve(
+        &self,
+        approval_id: Option<&str>,
+        decision: Decision,
+    ) -> Option<ApprovalRequest> {
+        let mut pending = self.pending.lock().expect("approvals lock poisoned");
+        let index = match approval_id {
+            Some(approval_id) => pending
+                .iter()
+                .position(|entry| entry.request.approval_id == approval_id)?,
+            None if pending.is_empty() => return None,
+            None => 0,
+        };
+        let entry = pending.remove(index);
+        // The worker may have stopped waiting in the meantime.
+        entry.decision_tx.send(decision).ok();
+        Some(entry.request)
+    }
+
+    /// Drop the requests of a worker that stopped waiting.
+    pub fn forget(&self, worker_id: WorkerId) {
+        self.pending
+            .lock()
+            .expect("approvals lock poisoned")
+            .retain(|entry| entry.request.worker_id != worker_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replies_are_parsed() {
+        assert_eq!(
+            parse_reply(" Approve. "),
+            Some(ApprovalReply {
+                decision: Decision::Approve,
+                approval_id: None,
+            })
+        );
+        assert_eq!(
+            parse_reply("deny 1A2B3C4D"),
+            Some(ApprovalReply {
+                decision: Decision::Deny,
+                approval_id: Some("1a2b3c4d".into()),
+            })
+        );
+        assert_eq!(parse_reply("approve it for now"), None);
+        assert_eq!(parse_reply("I approve"), None);
+    }
+
+    #[tokio::test]
+    async fn requests_are_answered_oldest_first_or_by_id() {
+        let approvals = Approvals::new();
+        let (first, second) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        let (shell, shell_rx) = approvals.request(first, "shell", r#"{"command":"make"}"#);
+        let (issue, issue_rx) = approvals.request(second, "github_create_issue", "{}");
+        let (_, later_rx) = approvals.request(second, "exec", "{}");
+
+        let answered = approvals.resolve(Some(&issue.approval_id), Decision::Deny);
+        assert_eq!(answered.as_ref(), Some(&issue));
+        assert_eq!(issue_rx.await, Ok(Decision::Deny));
+
+        assert_eq!(approvals.resolve(None, Decision::Approve), Some(shell));
+        assert_eq!(shell_rx.await, Ok(Decision::Approve));
+
+        approvals.forget(second);
+        assert!(approvals.is_empty());
+        assert!(later_rx.await.is_err());
+        assert_eq!(approvals.resolve(None, Decision::Approve), None);
+    }
+}
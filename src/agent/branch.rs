@@ -1,19 +1,59 @@
 //! Branch: Fork context for thinking and delegation.
 
 use crate::agent::compactor::estimate_history_tokens;
+use crate::agent::cost::CostTracker;
+use crate::conversation::ToolAuditLog;
 use crate::error::Result;
 use crate::hooks::SpacebotHook;
 use crate::llm::SpacebotModel;
 use crate::llm::routing::is_context_overflow_error;
-use crate::{AgentDeps, BranchId, ChannelId, ProcessEvent, ProcessId, ProcessType};
+use crate::{AgentDeps, BranchId, ChannelId, ProcessEvent, ProcessId, ProcessType, TurnId};
 use rig::agent::AgentBuilder;
 use rig::completion::{CompletionModel, Prompt};
 use rig::tool::server::ToolServerHandle;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 /// Max consecutive context overflow recoveries before giving up.
 const MAX_OVERFLOW_RETRIES: usize = 2;
 
+/// A branch's conclusion together with those of the sub-branches it spawned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchConclusion {
+    pub branch_id: BranchId,
+    pub description: String,
+    pub conclusion: String,
+    pub sub_results: Vec<BranchConclusion>,
+}
+
+/// Render a conclusion and its sub-branch conclusions as an indented tree,
+/// so the channel reads one result per top-level branch.
+pub fn render_conclusion_tree(conclusion: &str, sub_results: &[BranchConclusion]) -> String {
+    let mut rendered = conclusion.trim().to_string();
+    if !sub_results.is_empty() {
+        rendered.push_str("\n\nSub-branches:");
+        render_sub_results(&mut rendered, sub_results, 0);
+    }
+    rendered
+}
+
+fn render_sub_results(rendered: &mut String, sub_results: &[BranchConclusion], level: usize) {
+    let indent = "  ".repeat(level);
+    for sub_result in sub_results {
+        let conclusion = sub_result
+            .conclusion
+            .trim()
+            .replace('\n', &format!("\n{indent}  "));
+        rendered.push_str(&format!(
+            "\n{indent}- {}: {conclusion}",
+            sub_result.description
+        ));
+        render_sub_results(rendered, &sub_result.sub_results, level + 1);
+    }
+}
+
 /// A branch is a fork of a channel's context for thinking.
 pub struct Branch {
     pub id: BranchId,
@@ -29,6 +69,20 @@ pub struct Branch {
     pub tool_server: ToolServerHandle,
     /// Maximum LLM turns before the branch is forced to conclude.
     pub max_turns: usize,
+    /// Cancelled by the channel to stop the branch mid-call.
+    pub cancel_token: CancellationToken,
+    /// The branch that spawned this one, if it's a sub-branch.
+    pub parent_id: Option<BranchId>,
+    /// Nesting level: 1 for branches the channel spawned, 2 for their
+    /// sub-branches, and so on.
+    pub depth: usize,
+    /// Conclusions of finished sub-branches, filled in by the `sub_branch`
+    /// tool and reported with this branch's own conclusion.
+    pub sub_results: Arc<Mutex<Vec<BranchConclusion>>>,
+    /// The channel's tracker; the branch's spend counts toward its budget.
+    pub cost_tracker: Option<CostTracker>,
+    /// The channel turn that spawned the branch, reported with its result.
+    pub turn_id: Option<TurnId>,
 }
 
 impl Branch {
@@ -44,13 +98,17 @@ impl Branch {
     ) -> Self {
         let id = Uuid::new_v4();
         let process_id = ProcessId::Branch(id);
+        let cancel_token = CancellationToken::new();
         let hook = SpacebotHook::new(
             deps.agent_id.clone(),
             process_id,
             ProcessType::Branch,
             Some(channel_id.clone()),
             deps.event_tx.clone(),
-        );
+        )
+        .with_cancellation(cancel_token.clone())
+        .with_tool_audit(ToolAuditLog::new(deps.sqlite_pool.clone()))
+        .with_tool_call_repairs(**deps.runtime_config.tool_call_repairs.load());
 
         Self {
             id,
@@ -62,9 +120,44 @@ impl Branch {
             history,
             tool_server,
             max_turns,
+            cancel_token,
+            parent_id: None,
+            depth: 1,
+            sub_results: Arc::new(Mutex::new(Vec::new())),
+            cost_tracker: None,
+            turn_id: None,
         }
     }
 
+    /// Record the branch's token usage in the spawning channel's tracker.
+    pub fn with_cost_tracker(mut self, tracker: CostTracker) -> Self {
+        self.cost_tracker = Some(tracker);
+        self
+    }
+
+    /// Attribute the branch's events, model calls, and result to the channel
+    /// turn that spawned it.
+    pub fn with_turn_id(mut self, turn_id: Option<TurnId>) -> Self {
+        self.turn_id = turn_id;
+        self.hook = self.hook.with_spawning_turn(turn_id);
+        self
+    }
+
+    /// Make this a sub-branch of `parent_id`, one level below a branch at
+    /// `parent_depth`. It is cancelled along with its parent.
+    pub fn as_sub_branch(
+        mut self,
+        parent_id: BranchId,
+        parent_depth: usize,
+        parent_cancel_token: &CancellationToken,
+    ) -> Self {
+        self.parent_id = Some(parent_id);
+        self.depth = parent_depth + 1;
+        self.cancel_token = parent_cancel_token.child_token();
+        self.hook = self.hook.with_cancellation(self.cancel_token.clone());
+        self
+    }
+
     /// Run the branch's LLM agent loop and return a conclusion.
     ///
     /// Each branch has its own isolated ToolServer with `memory_save` and
@@ -74,13 +167,17 @@ impl Branch {
     /// On context overflow, compacts history and retries up to `MAX_OVERFLOW_RETRIES`
     /// times. Branches inherit a full clone of channel history which may already
     /// be large, making them susceptible to overflow on the first LLM call.
-    pub async fn run(mut self, prompt: impl Into<String>) -> Result<String> {
+    ///
+    /// The result includes the conclusions of any sub-branches spawned along
+    /// the way.
+    pub async fn run(mut self, prompt: impl Into<String>) -> Result<BranchConclusion> {
         let prompt = prompt.into();
 
         tracing::info!(
             branch_id = %self.id,
             channel_id = %self.channel_id,
             description = %self.description,
+            depth = self.depth,
             "branch starting"
         );
 
@@ -90,9 +187,23 @@ impl Branch {
 
         let routing = self.deps.runtime_config.routing.load();
         let model_name = routing.resolve(ProcessType::Branch, None).to_string();
+        tracing::Span::current().record("model", model_name.as_str());
+        if let Some(tracker) = &self.cost_tracker {
+            self.hook = self
+                .hook
+                .clone()
+                .with_cost_tracker(tracker.for_model(&model_name));
+        }
         let model = SpacebotModel::make(&self.deps.llm_manager, &model_name)
             .with_context(&*self.deps.agent_id, "branch")
-            .with_routing((**routing).clone());
+            .with_routing((**routing).clone())
+            .with_fallback_events(
+                self.deps.event_tx.clone(),
+                self.deps.agent_id.clone(),
+                ProcessId::Branch(self.id),
+                Some(self.channel_id.clone()),
+            )
+            .with_turn_id(self.turn_id);
 
         let agent = AgentBuilder::new(model)
             .preamble(&self.system_prompt)
@@ -100,14 +211,20 @@ impl Branch {
             .tool_server_handle(self.tool_server.clone())
             .build();
 
+        let tool_concurrency = **self.deps.runtime_config.tool_concurrency.load();
         let mut current_prompt = prompt;
         let mut overflow_retries = 0;
 
         let conclusion = loop {
-            match agent
-                .prompt(&current_prompt)
-                .with_history(&mut self.history)
-                .with_hook(self.hook.clone())
+            match self
+                .hook
+                .cancellable(
+                    agent
+                        .prompt(&current_prompt)
+                        .with_history(&mut self.history)
+                        .with_hook(self.hook.clone())
+                        .with_tool_concurrency(tool_concurrency),
+                )
                 .await
             {
                 Ok(response) => break response,
@@ -152,17 +269,37 @@ impl Branch {
             }
         };
 
-        // Send conclusion back to the channel
-        let _ = self.deps.event_tx.send(ProcessEvent::BranchResult {
+        let sub_results = std::mem::take(
+            &mut *self
+                .sub_results
+                .lock()
+                .expect("sub-branch results lock poisoned"),
+        );
+
+        // Send conclusion back to the channel, journaled first in case the
+        // channel isn't listening right now.
+        let result = ProcessEvent::BranchResult {
             agent_id: self.deps.agent_id.clone(),
             branch_id: self.id,
             channel_id: self.channel_id.clone(),
             conclusion: conclusion.clone(),
-        });
+            parent_branch_id: self.parent_id,
+            sub_results: sub_results.clone(),
+            turn_id: self.turn_id,
+        };
+        crate::conversation::EventJournal::new(self.deps.sqlite_pool.clone())
+            .record(&result)
+            .await;
+        let _ = self.deps.event_tx.send(result);
 
         tracing::info!(branch_id = %self.id, "branch completed");
 
-        Ok(conclusion)
+        Ok(BranchConclusion {
+            branch_id: self.id,
+            description: self.description,
+            conclusion,
+            sub_results,
+        })
     }
 
     /// Compact history if approaching context window limit.
@@ -236,3 +373,49 @@ fn extract_last_assistant_text(history: &[rig::message::Message]) -> Option<Stri
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conclusion(
+        description: &str,
+        text: &str,
+        sub_results: Vec<BranchConclusion>,
+    ) -> BranchConclusion {
+        BranchConclusion {
+            branch_id: Uuid::nil(),
+            description: description.into(),
+            conclusion: text.into(),
+            sub_results,
+        }
+    }
+
+    #[test]
+    fn conclusions_render_as_a_tree() {
+        let sub_results = vec![
+            conclusion(
+                "pricing",
+                "Plan B is cheaper.\nBy about 20%.",
+                vec![conclusion("discounts", "No annual discount.", Vec::new())],
+            ),
+            conclusion("limits", "Both allow 10 seats.", Vec::new()),
+        ];
+
+        let expected = [
+            "Go with plan B.",
+            "",
+            "Sub-branches:",
+            "- pricing: Plan B is cheaper.",
+            "  By about 20%.",
+            "  - discounts: No annual discount.",
+            "- limits: Both allow 10 seats.",
+        ]
+        .join("\n");
+        assert_eq!(
+            render_conclusion_tree("Go with plan B.", &sub_results),
+            expected
+        );
+        assert_eq!(render_conclusion_tree("Done.", &[]), "Done.");
+    }
+}
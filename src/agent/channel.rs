@@ -1,17 +1,32 @@
 //! Channel: User-facing conversation process.
 
+use crate::agent::approval::{ApprovalReply, Approvals, Decision};
 use crate::agent::branch::Branch;
+use crate::agent::commands::AdminCommand;
 use crate::agent::compactor::Compactor;
+use crate::agent::cost::{ChannelUsage, CostTracker, UsageStore};
+use crate::agent::gating::{Gate, Overheard};
+use crate::agent::lanes::{ChannelReceiver, ChannelSender};
+use crate::agent::plan::{Plan, PlanCommand, PlannedCall};
 use crate::agent::status::StatusBlock;
-use crate::agent::worker::Worker;
+use crate::agent::worker::{Worker, WorkerArtifact, WorkerResult, WorkerResultStatus};
+use crate::agent::worker_pool::{Admission, RetryPolicy, WorkerPriority};
+use crate::agent::worker_workspace::WorkerWorkspace;
+use crate::auth::AuthTier;
 use crate::config::ApiType;
-use crate::conversation::{ChannelStore, ConversationLogger, ProcessRunLogger};
+use crate::conversation::history::{ChannelSnapshot, StoreDyn as HistoryStoreDyn};
+use crate::conversation::{
+    ChannelStore, ConversationLogger, DraftDecision, DraftStore, EventJournal, ProcessRunLogger,
+    ReplyDraft, SqliteHistoryStore, ToolAuditEntry, ToolAuditLog,
+};
 use crate::error::{AgentError, Result};
 use crate::hooks::SpacebotHook;
 use crate::llm::SpacebotModel;
+use crate::profiles::ProfileStore;
+use crate::redaction::Redactor;
 use crate::{
-    AgentDeps, BranchId, ChannelId, InboundMessage, OutboundResponse, ProcessEvent, ProcessId,
-    ProcessType, WorkerId,
+    AgentDeps, BranchId, ChannelId, ChannelResponse, InboundMessage, OutboundResponse,
+    ProcessEvent, ProcessId, ProcessType, ResponseSender, TurnId, WorkerId,
 };
 use rig::agent::AgentBuilder;
 use rig::completion::{CompletionModel, Prompt};
@@ -20,9 +35,11 @@ use rig::one_or_many::OneOrMany;
 use rig::tool::server::ToolServer;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::sync::Arc;
 use tokio::sync::broadcast;
 use tokio::sync::{RwLock, mpsc};
+use tokio_util::sync::CancellationToken;
 use tracing::Instrument as _;
 
 /// Debounce window for retriggers: coalesce rapid branch/worker completions
@@ -33,6 +50,79 @@ const RETRIGGER_DEBOUNCE_MS: u64 = 500;
 /// infinite retrigger cascades where each retrigger spawns more work.
 const MAX_RETRIGGERS_PER_TURN: usize = 3;
 
+/// Share of the context window kept free for the response and tool calls when
+/// fitting history before a turn.
+const RESPONSE_HEADROOM_FRACTION: f32 = 0.1;
+
+/// Branch conclusions held back while sibling branches are still running, so
+/// branches that race each other re-trigger the channel once.
+#[derive(Debug, Default)]
+struct PendingBranchResults {
+    conclusions: Vec<String>,
+    /// Reply targets of the held branches, applied to the retrigger.
+    retrigger_metadata: HashMap<String, serde_json::Value>,
+    /// When the held results are flushed even if siblings are still running.
+    deadline: Option<tokio::time::Instant>,
+}
+
+/// Recently seen inbound message IDs, least recently seen first. Platforms and
+/// webhooks that retry deliveries can hand the channel the same message twice.
+#[derive(Debug, Default)]
+struct SeenMessages {
+    order: VecDeque<String>,
+    ids: HashSet<String>,
+}
+
+impl SeenMessages {
+    /// Record `id`, returning `true` if it was already seen. Keeps at most
+    /// `capacity` IDs, evicting the least recently seen; 0 disables tracking.
+    fn check_and_insert(&mut self, id: &str, capacity: usize) -> bool {
+        if capacity == 0 {
+            self.order.clear();
+            self.ids.clear();
+            return false;
+        }
+
+        let seen = self.ids.contains(id);
+        if seen {
+            self.order.retain(|existing| existing != id);
+        } else {
+            self.ids.insert(id.to_string());
+        }
+        self.order.push_back(id.to_string());
+
+        while self.order.len() > capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.ids.remove(&evicted);
+            }
+        }
+        seen
+    }
+}
+
+/// User messages inspected for the conversation language before giving up.
+const LANGUAGE_DETECTION_MESSAGES: usize = 5;
+
+/// User messages a conversation needs before it is given a title.
+const TITLE_MESSAGES: usize = 2;
+
+/// Longest title kept, in characters.
+const TITLE_MAX_CHARS: usize = 80;
+
+/// Recent history messages shown to the cheap model when deciding whether
+/// to chime in on an unaddressed group message.
+const RELEVANCE_CONTEXT_MESSAGES: usize = 10;
+
+/// How long a cancelled branch or worker gets to stop on its own before its
+/// task is aborted.
+const CANCEL_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// A fresh channel ID for a fork that doesn't live on a messaging platform.
+/// Messages reach it through the web chat API, using the ID as the session.
+pub fn new_fork_channel_id() -> ChannelId {
+    Arc::from(format!("portal:fork:{}", uuid::Uuid::new_v4()).as_str())
+}
+
 /// Shared state that channel tools need to act on the channel.
 ///
 /// Wrapped in Arc and passed to tools (branch, spawn_worker, route, cancel)
@@ -48,19 +138,211 @@ pub struct ChannelState {
     /// Input senders for interactive workers, keyed by worker ID.
     /// Used by the route tool to deliver follow-up messages.
     pub worker_inputs: Arc<RwLock<HashMap<WorkerId, tokio::sync::mpsc::Sender<String>>>>,
+    /// Cooperative cancellation tokens for running branches and builtin workers.
+    pub cancel_tokens: Arc<RwLock<HashMap<ProcessId, CancellationToken>>>,
     pub status_block: Arc<RwLock<StatusBlock>>,
     pub deps: AgentDeps,
     pub conversation_logger: ConversationLogger,
     pub process_run_logger: ProcessRunLogger,
     /// Discord message ID to reply to for work spawned in the current turn.
     pub reply_target_message_id: Arc<RwLock<Option<u64>>>,
+    /// The most recent user message handled by this channel. Tools that need
+    /// the platform context of the current turn (e.g. opening a thread) read it.
+    pub latest_message: Arc<RwLock<Option<InboundMessage>>>,
     pub channel_store: ChannelStore,
+    /// Long-term facts about the people in this channel.
+    pub profile_store: ProfileStore,
+    /// Durable copy of `history`, written through after every change so the
+    /// channel can be rehydrated after a restart.
+    pub history_store: Arc<dyn HistoryStoreDyn>,
+    /// Held across snapshot and write so saves land in order and an older
+    /// snapshot never overwrites a newer one.
+    pub history_save_lock: Arc<tokio::sync::Mutex<()>>,
+    /// Token and cost accounting for this channel's turns.
+    pub cost_tracker: CostTracker,
     pub screenshot_dir: std::path::PathBuf,
     pub logs_dir: std::path::PathBuf,
+    /// Pages fetched by this channel's branches and workers.
+    pub web_fetch_cache: crate::tools::WebFetchCache,
+    /// ID of the turn currently being handled, or of the last one once it
+    /// ends. Shared with the channel hook and the outbound loop.
+    pub turn_id: Arc<RwLock<Option<TurnId>>>,
+    /// Span of the turn currently being handled. Channel tools run on the
+    /// tool server's own task, so branches and workers they spawn parent
+    /// their spans to this one explicitly to land in the turn's trace.
+    pub turn_span: Arc<RwLock<tracing::Span>>,
+    /// Replies sent during the turn currently being handled, written to
+    /// history when it ends.
+    pub sent_replies: crate::tools::SentReplies,
+    /// Outbound sender shared with the channel, used to deliver approved
+    /// reply drafts.
+    pub response_tx: ResponseSender,
+    /// Replies held for review while the channel is supervised.
+    pub drafts: DraftStore,
+    /// Language detected for the conversation (ISO 639-1 code). The outbound
+    /// path reads it to localize status strings.
+    pub language: Arc<RwLock<Option<String>>>,
+    /// Plan mode state and the tool calls waiting for `!approve_plan`.
+    pub plan: Arc<Plan>,
+    /// Tool calls of this channel's workers waiting for `approve` or `deny`.
+    pub approvals: Arc<Approvals>,
 }
 
 impl ChannelState {
-    /// Cancel a running worker by aborting its tokio task and cleaning up state.
+    /// Load persisted history into the in-memory history. Returns the number
+    /// of messages restored.
+    pub async fn rehydrate_history(&self) -> usize {
+        match self.history_store.load(&self.channel_id).await {
+            Ok(messages) if !messages.is_empty() => {
+                let count = messages.len();
+                *self.history.write().await = messages;
+                count
+            }
+            Ok(_) => 0,
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.channel_id, "failed to load channel history");
+                0
+            }
+        }
+    }
+
+    /// Snapshot the in-memory history and write it to the history store.
+    /// Saves for a channel are serialized, so the stored copy always ends up
+    /// matching the latest snapshot.
+    pub async fn persist_history(&self) {
+        let _guard = self.history_save_lock.lock().await;
+        let snapshot = self.history.read().await.clone();
+
+        if let Err(error) = self.history_store.save(&self.channel_id, &snapshot).await {
+            tracing::warn!(%error, channel_id = %self.channel_id, "failed to persist channel history");
+        }
+    }
+
+    /// Copy this channel's conversation into a new channel, `fork_id`.
+    ///
+    /// The LLM-facing history is snapshotted into the history store so the
+    /// fork rehydrates it when its first message arrives, and the transcript is
+    /// copied so the fork's timeline starts with the source conversation.
+    /// Returns the number of history messages carried over.
+    pub async fn fork(&self, fork_id: &ChannelId, display_name: Option<&str>) -> Result<usize> {
+        if *fork_id == self.channel_id {
+            return Err(AgentError::Other(anyhow::anyhow!(
+                "a channel can't be forked into itself"
+            ))
+            .into());
+        }
+
+        let snapshot = self.history.read().await.clone();
+        self.history_store.save(fork_id, &snapshot).await?;
+        let copied = self
+            .conversation_logger
+            .copy_messages(&self.channel_id, fork_id)
+            .await?;
+        self.channel_store
+            .record_fork(fork_id, &self.channel_id, display_name);
+
+        tracing::info!(
+            channel_id = %self.channel_id,
+            %fork_id,
+            history_messages = snapshot.len(),
+            transcript_messages = copied,
+            "channel forked"
+        );
+
+        Ok(snapshot.len())
+    }
+
+    /// Approve or reject a pending reply draft. Approved drafts are delivered
+    /// with `text` in place of the drafted text when given. Returns `None` if
+    /// the draft doesn't exist or was already decided.
+    pub async fn resolve_draft(
+        &self,
+        approval_token: &str,
+        decision: DraftDecision,
+        text: Option<&str>,
+        resolved_by: &str,
+    ) -> Result<Option<ReplyDraft>> {
+        let Some(draft) = self
+            .drafts
+            .resolve(approval_token, decision, text, resolved_by)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        tracing::info!(
+            channel_id = %self.channel_id,
+            approval_token,
+            ?decision,
+            resolved_by,
+            "reply draft resolved"
+        );
+
+        if decision == DraftDecision::Approve {
+            let response = draft.approved_response();
+            let agent_name = self.deps.agent_names.get(self.deps.agent_id.as_ref());
+            self.conversation_logger.log_bot_message_with_name(
+                &self.channel_id,
+                draft.final_text.as_deref().unwrap_or(&draft.text),
+                agent_name.map(String::as_str),
+            );
+            self.response_tx
+                .send_for_turn(response, draft.turn_id)
+                .await
+                .map_err(|e| AgentError::Other(anyhow::anyhow!("{e}")))?;
+        }
+
+        Ok(Some(draft))
+    }
+
+    /// Decide a pending draft from an approver's ✅/❌ reaction to the message
+    /// it answers. Returns true if the reaction decided a draft.
+    pub async fn review_draft_reaction(&self, message: &InboundMessage) -> bool {
+        use crate::agent::feedback::{REACTION_EMOJI_KEY, REACTION_TARGET_ID_KEY};
+
+        let metadata_str = |key: &str| message.metadata.get(key).and_then(|value| value.as_str());
+        let Some(decision) = metadata_str(REACTION_EMOJI_KEY).and_then(DraftDecision::from_emoji)
+        else {
+            return false;
+        };
+        let Some(target_id) = metadata_str(REACTION_TARGET_ID_KEY) else {
+            return false;
+        };
+
+        let draft = match self.drafts.supervision(&self.channel_id).await {
+            Ok(supervision) if supervision.approvers.contains(&message.sender_id) => self
+                .drafts
+                .pending_for_message(&self.channel_id, target_id)
+                .await
+                .ok()
+                .flatten(),
+            Ok(_) => None,
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.channel_id, "failed to load channel supervision");
+                None
+            }
+        };
+        let Some(draft) = draft else {
+            return false;
+        };
+
+        match self
+            .resolve_draft(&draft.approval_token, decision, None, &message.sender_id)
+            .await
+        {
+            Ok(resolved) => resolved.is_some(),
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.channel_id, "failed to resolve reply draft");
+                false
+            }
+        }
+    }
+
+    /// Cancel a running worker and clean up its state.
+    ///
+    /// Builtin workers are signalled through their cancellation token so the
+    /// in-flight LLM call is dropped and the transcript still gets written;
+    /// the task is aborted only if it hasn't stopped after a grace period.
     /// Returns an error message if the worker is not found.
     pub async fn cancel_worker(&self, worker_id: WorkerId) -> std::result::Result<(), String> {
         let handle = self.worker_handles.write().await.remove(&worker_id);
@@ -71,10 +353,21 @@ impl ChannelState {
             .remove(&worker_id)
             .is_some();
         self.worker_inputs.write().await.remove(&worker_id);
+        let cancel_token = self
+            .cancel_tokens
+            .write()
+            .await
+            .remove(&ProcessId::Worker(worker_id));
 
+        if handle.is_some() || removed {
+            self.event_journal()
+                .discard(&self.channel_id, &ProcessId::Worker(worker_id))
+                .await;
+        }
         if let Some(handle) = handle {
-            handle.abort();
-            // Mark the DB row as cancelled since the abort prevents WorkerComplete from firing
+            stop_task(handle, cancel_token);
+            // Mark the DB row as cancelled here; the WorkerComplete that follows
+            // is ignored because the worker is no longer tracked.
             self.process_run_logger
                 .log_worker_completed(worker_id, "Worker cancelled", false);
             Ok(())
@@ -87,17 +380,70 @@ impl ChannelState {
         }
     }
 
-    /// Cancel a running branch by aborting its tokio task.
+    /// Cancel a running branch, cooperatively first and by abort as a backstop.
     /// Returns an error message if the branch is not found.
     pub async fn cancel_branch(&self, branch_id: BranchId) -> std::result::Result<(), String> {
         let handle = self.active_branches.write().await.remove(&branch_id);
+        let cancel_token = self
+            .cancel_tokens
+            .write()
+            .await
+            .remove(&ProcessId::Branch(branch_id));
         if let Some(handle) = handle {
-            handle.abort();
+            self.event_journal()
+                .discard(&self.channel_id, &ProcessId::Branch(branch_id))
+                .await;
+            stop_task(handle, cancel_token);
             Ok(())
         } else {
             Err(format!("Branch {branch_id} not found"))
         }
     }
+
+    /// Journal of the branch and worker results addressed to this channel.
+    pub fn event_journal(&self) -> EventJournal {
+        EventJournal::new(self.deps.sqlite_pool.clone())
+    }
+}
+
+/// Render the platform/server/channel fragment for a conversation from the
+/// metadata each adapter attaches to its inbound messages.
+fn build_conversation_context(
+    prompt_engine: &crate::prompts::PromptEngine,
+    message: &InboundMessage,
+) -> Result<String> {
+    let (server_name, channel_name) = conversation_location(message);
+    prompt_engine.render_conversation_context(&message.source, server_name, channel_name)
+}
+
+/// Server and channel names for a message, per platform.
+fn conversation_location(message: &InboundMessage) -> (Option<&str>, Option<&str>) {
+    let get = move |key: &str| message.metadata.get(key).and_then(|v| v.as_str());
+    match message.source.as_str() {
+        "discord" => (get("discord_guild_name"), get("discord_channel_name")),
+        "slack" => (None, get("slack_channel_name")),
+        "telegram" => (get("telegram_chat_title"), get("telegram_chat_type")),
+        "twitch" => (None, get("twitch_channel")),
+        "email" => (None, get("email_subject")),
+        _ => (None, None),
+    }
+}
+
+/// Signal a branch or worker task to stop, aborting it if it hasn't finished
+/// within `CANCEL_GRACE_PERIOD`. Tasks without a token are aborted immediately.
+fn stop_task(handle: tokio::task::JoinHandle<()>, cancel_token: Option<CancellationToken>) {
+    let Some(cancel_token) = cancel_token else {
+        handle.abort();
+        return;
+    };
+
+    cancel_token.cancel();
+    tokio::spawn(async move {
+        tokio::time::sleep(CANCEL_GRACE_PERIOD).await;
+        if !handle.is_finished() {
+            handle.abort();
+        }
+    });
 }
 
 impl std::fmt::Debug for ChannelState {
@@ -117,14 +463,15 @@ pub struct Channel {
     pub state: ChannelState,
     /// Per-channel tool server (isolated from other channels).
     pub tool_server: rig::tool::server::ToolServerHandle,
-    /// Input channel for receiving messages.
-    pub message_rx: mpsc::Receiver<InboundMessage>,
+    /// Input lanes for receiving messages, most urgent first.
+    pub message_rx: ChannelReceiver,
     /// Event receiver for process events.
     pub event_rx: broadcast::Receiver<ProcessEvent>,
     /// Outbound response sender for the messaging layer.
-    pub response_tx: mpsc::Sender<OutboundResponse>,
-    /// Self-sender for re-triggering the channel after background process completion.
-    pub self_tx: mpsc::Sender<InboundMessage>,
+    pub response_tx: ResponseSender,
+    /// Self-sender for re-triggering the channel after background process
+    /// completion. Unbounded, so a re-trigger is never dropped.
+    pub self_tx: mpsc::UnboundedSender<InboundMessage>,
     /// Conversation ID from the first message (for synthetic re-trigger messages).
     pub conversation_id: Option<String>,
     /// Conversation context (platform, channel name, server) captured from the first message.
@@ -141,6 +488,8 @@ pub struct Channel {
     coalesce_buffer: Vec<InboundMessage>,
     /// Deadline for flushing the coalesce buffer.
     coalesce_deadline: Option<tokio::time::Instant>,
+    /// Group messages the gate turned away, kept for passive listening.
+    overheard: Overheard,
     /// Number of retriggers fired since the last real user message.
     retrigger_count: usize,
     /// Whether a retrigger is pending (debounce window active).
@@ -149,6 +498,8 @@ pub struct Channel {
     pending_retrigger_metadata: HashMap<String, serde_json::Value>,
     /// Deadline for firing the pending retrigger (debounce timer).
     retrigger_deadline: Option<tokio::time::Instant>,
+    /// Branch results waiting to be merged with those of sibling branches.
+    pending_branch_results: PendingBranchResults,
     /// Optional send_agent_message tool (only when agent has active links).
     send_agent_message_tool: Option<crate::tools::SendAgentMessageTool>,
     /// Turn counter for link channels (used for safety cap).
@@ -162,6 +513,30 @@ pub struct Channel {
     /// further messages, stopping the ping-pong that happens when both sides
     /// keep responding to each other after the task is done.
     link_concluded: bool,
+    /// Last time the channel received a message or had background work in
+    /// flight. Drives idle archival.
+    last_activity: tokio::time::Instant,
+    /// Inbound message IDs already handled, for dropping redeliveries.
+    seen_messages: SeenMessages,
+    /// Text of the user messages seen while the language is undetected.
+    /// `None` once detection has finished, successfully or not.
+    language_sample: Option<String>,
+    /// User messages inspected for language detection so far.
+    language_messages: usize,
+    /// `sender: text` lines of the first user messages, for generating the
+    /// title. `None` once the channel has a title or one was requested.
+    title_sample: Option<String>,
+    /// User messages collected in `title_sample` so far.
+    title_messages: usize,
+    /// Tier of the sender the current turn answers. Gates channel tools.
+    /// Kept across system re-triggers so a worker result is handled with
+    /// the tools of whoever asked for the worker.
+    sender_tier: AuthTier,
+    /// Whether the current turn answers with a structured reply. Kept across
+    /// system re-triggers like `sender_tier`.
+    structured_turn: bool,
+    /// Model set with `!model`, used instead of the routed channel model.
+    model_override: Option<String>,
 }
 
 impl Channel {
@@ -173,24 +548,32 @@ impl Channel {
     pub fn new(
         id: ChannelId,
         deps: AgentDeps,
-        response_tx: mpsc::Sender<OutboundResponse>,
+        response_tx: mpsc::Sender<ChannelResponse>,
         event_rx: broadcast::Receiver<ProcessEvent>,
         screenshot_dir: std::path::PathBuf,
         logs_dir: std::path::PathBuf,
-    ) -> (Self, mpsc::Sender<InboundMessage>) {
+    ) -> (Self, ChannelSender) {
         let process_id = ProcessId::Channel(id.clone());
+        let cost_tracker = CostTracker::new()
+            .with_store(UsageStore::new(deps.sqlite_pool.clone()), id.to_string());
+        let turn_id = Arc::new(RwLock::new(None));
+        let response_tx = ResponseSender::new(response_tx, turn_id.clone());
         let hook = SpacebotHook::new(
             deps.agent_id.clone(),
             process_id,
             ProcessType::Channel,
             Some(id.clone()),
             deps.event_tx.clone(),
-        );
+        )
+        .with_cost_tracker(cost_tracker.clone())
+        .with_tool_audit(ToolAuditLog::new(deps.sqlite_pool.clone()))
+        .with_presence(response_tx.clone())
+        .with_turn_id(turn_id.clone());
         let status_block = Arc::new(RwLock::new(StatusBlock::new()));
         let history = Arc::new(RwLock::new(Vec::new()));
         let active_branches = Arc::new(RwLock::new(HashMap::new()));
         let active_workers = Arc::new(RwLock::new(HashMap::new()));
-        let (message_tx, message_rx) = mpsc::channel(64);
+        let (message_tx, self_tx, message_rx) = crate::agent::lanes::lanes();
 
         let conversation_logger = ConversationLogger::new(deps.sqlite_pool.clone());
         let process_run_logger = ProcessRunLogger::new(deps.sqlite_pool.clone());
@@ -205,14 +588,29 @@ impl Channel {
             active_workers: active_workers.clone(),
             worker_handles: Arc::new(RwLock::new(HashMap::new())),
             worker_inputs: Arc::new(RwLock::new(HashMap::new())),
+            cancel_tokens: Arc::new(RwLock::new(HashMap::new())),
             status_block: status_block.clone(),
             deps: deps.clone(),
             conversation_logger,
             process_run_logger,
             reply_target_message_id: Arc::new(RwLock::new(None)),
+            latest_message: Arc::new(RwLock::new(None)),
             channel_store: channel_store.clone(),
+            profile_store: ProfileStore::new(deps.sqlite_pool.clone()),
+            history_store: Arc::new(SqliteHistoryStore::new(deps.sqlite_pool.clone())),
+            history_save_lock: Arc::new(tokio::sync::Mutex::new(())),
+            cost_tracker,
             screenshot_dir,
             logs_dir,
+            web_fetch_cache: crate::tools::web_fetch_cache(),
+            turn_id,
+            turn_span: Arc::new(RwLock::new(tracing::Span::none())),
+            sent_replies: Arc::new(RwLock::new(Vec::new())),
+            response_tx: response_tx.clone(),
+            drafts: DraftStore::new(deps.sqlite_pool.clone()),
+            language: Arc::new(RwLock::new(None)),
+            plan: Arc::new(Plan::new()),
+            approvals: Arc::new(Approvals::new()),
         };
 
         // Each channel gets its own isolated tool server to avoid races between
@@ -241,7 +639,6 @@ impl Channel {
             }
         };
 
-        let self_tx = message_tx.clone();
         let channel = Self {
             id: id.clone(),
             title: None,
@@ -260,16 +657,27 @@ impl Channel {
             memory_persistence_branches: HashSet::new(),
             branch_reply_targets: HashMap::new(),
             coalesce_buffer: Vec::new(),
+            overheard: Overheard::default(),
             coalesce_deadline: None,
             retrigger_count: 0,
             pending_retrigger: false,
             pending_retrigger_metadata: HashMap::new(),
             retrigger_deadline: None,
+            pending_branch_results: PendingBranchResults::default(),
             send_agent_message_tool,
             link_turn_count: 0,
             originating_channel: None,
             originating_source: None,
             link_concluded: false,
+            last_activity: tokio::time::Instant::now(),
+            seen_messages: SeenMessages::default(),
+            language_sample: Some(String::new()),
+            language_messages: 0,
+            title_sample: Some(String::new()),
+            title_messages: 0,
+            sender_tier: AuthTier::Admin,
+            structured_turn: false,
+            model_override: None,
         };
 
         (channel, message_tx)
@@ -284,18 +692,499 @@ impl Channel {
             .unwrap_or(self.deps.agent_id.as_ref())
     }
 
+    /// The prompt engine, set to the conversation's language once detected.
+    async fn prompt_engine(&self) -> crate::prompts::PromptEngine {
+        let prompt_engine = self.deps.runtime_config.prompts.load();
+        match self.state.language.read().await.as_deref() {
+            Some(language) => prompt_engine.with_language(language),
+            None => (**prompt_engine).clone(),
+        }
+    }
+
+    /// Load the language detected in a previous run. Returns true when one
+    /// was stored.
+    pub async fn restore_language(&mut self) -> bool {
+        match self.state.channel_store.language(&self.id).await {
+            Ok(Some(language)) => {
+                *self.state.language.write().await = Some(language);
+                self.language_sample = None;
+                true
+            }
+            Ok(None) => false,
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, "failed to load channel language");
+                false
+            }
+        }
+    }
+
+    /// Load the title generated in a previous run.
+    pub async fn restore_title(&mut self) {
+        match self.state.channel_store.title(&self.id).await {
+            Ok(Some(title)) => {
+                self.title = Some(title);
+                self.title_sample = None;
+            }
+            Ok(None) => {}
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, "failed to load channel title");
+            }
+        }
+    }
+
+    /// Collect the conversation's first user messages and, once there are
+    /// enough, generate its title in the background. The title comes back
+    /// as a `ChannelTitled` event.
+    async fn sample_for_title(&mut self, message: &InboundMessage, raw_text: &str) {
+        if message.source == "system" || crate::reminders::is_reminder(message) {
+            return;
+        }
+        let Some(sample) = self.title_sample.as_mut() else {
+            return;
+        };
+
+        let sender_name = message
+            .metadata
+            .get("sender_display_name")
+            .and_then(|v| v.as_str())
+            .unwrap_or(&message.sender_id);
+        sample.push_str(&format!("{sender_name}: {raw_text}\n"));
+        self.title_messages += 1;
+        if self.title_messages < TITLE_MESSAGES {
+            return;
+        }
+
+        let transcript = self.title_sample.take().unwrap_or_default();
+        let prompt_engine = self.prompt_engine().await;
+        let deps = self.deps.clone();
+        let channel_id = self.id.clone();
+        tokio::spawn(
+            async move {
+                generate_channel_title(deps, prompt_engine, channel_id, transcript).await;
+            }
+            .in_current_span(),
+        );
+    }
+
+    /// Detect the conversation's language from its first few user messages.
+    /// Once detected it is stored on the channel, and the conversation
+    /// context is re-rendered so the LLM knows which language to answer in.
+    async fn detect_language(&mut self, message: &InboundMessage, raw_text: &str) -> Result<()> {
+        if message.source == "system" || crate::reminders::is_reminder(message) {
+            return Ok(());
+        }
+        let Some(sample) = self.language_sample.as_mut() else {
+            return Ok(());
+        };
+
+        sample.push_str(raw_text);
+        sample.push('\n');
+        self.language_messages += 1;
+
+        let Some(language) = crate::prompts::language::detect(sample) else {
+            if self.language_messages >= LANGUAGE_DETECTION_MESSAGES {
+                tracing::debug!(channel_id = %self.id, "no conversation language detected");
+                self.language_sample = None;
+            }
+            return Ok(());
+        };
+
+        tracing::info!(channel_id = %self.id, language, "detected conversation language");
+        self.language_sample = None;
+        *self.state.language.write().await = Some(language.to_string());
+        self.state.channel_store.set_language(&self.id, language);
+        if self.conversation_context.is_some() {
+            let prompt_engine = self.prompt_engine().await;
+            self.conversation_context = Some(build_conversation_context(&prompt_engine, message)?);
+        }
+        Ok(())
+    }
+
+    /// Whether the sender's tier is high enough for the agent to respond in
+    /// this conversation. Logs and returns false when it isn't.
+    fn sender_may_respond(&self, message: &InboundMessage) -> bool {
+        let auth = self.deps.runtime_config.auth.load();
+        let tier = crate::auth::tiers::sender_tier(&auth, message);
+        if crate::auth::tiers::may_respond(&auth, &message.conversation_id, tier) {
+            return true;
+        }
+        tracing::info!(
+            channel_id = %self.id,
+            message_id = %message.id,
+            sender_id = %message.sender_id,
+            %tier,
+            "dropping message from sender below the channel's tier"
+        );
+        false
+    }
+
+    /// Whether a group message should get a turn under the agent's gating
+    /// config (see [`crate::agent::gating`]).
+    async fn passes_gate(&self, message: &InboundMessage) -> bool {
+        let config = self.deps.runtime_config.gating.load();
+        let text = message.content.to_string();
+        let agent_name = self.agent_display_name();
+        let open = match crate::agent::gating::check(&config, message, &text, &[agent_name]) {
+            Gate::Open => return true,
+            Gate::Closed => false,
+            Gate::AskRelevance => {
+                let transcript = {
+                    let history = self.state.history.read().await;
+                    let start = history.len().saturating_sub(RELEVANCE_CONTEXT_MESSAGES);
+                    crate::agent::compactor::render_messages_as_transcript(&history[start..])
+                };
+                let prompt_engine = self.prompt_engine().await;
+                crate::agent::gating::should_chime_in(
+                    &self.deps,
+                    &prompt_engine,
+                    agent_name,
+                    &transcript,
+                    &text,
+                )
+                .await
+            }
+        };
+        if !open {
+            tracing::info!(
+                channel_id = %self.id,
+                message_id = %message.id,
+                "group message not addressed to the agent, not answering"
+            );
+        }
+        open
+    }
+
+    /// Keep a message the gate turned away, if passive listening is on. Full
+    /// batches are summarized right away.
+    async fn overhear(&mut self, message: &InboundMessage) {
+        let config = self.deps.runtime_config.gating.load();
+        if !config.listen {
+            return;
+        }
+
+        let text = self.redact_inbound(message, message.content.to_string());
+        let sender_name = message
+            .metadata
+            .get("sender_display_name")
+            .and_then(|v| v.as_str())
+            .unwrap_or(&message.sender_id);
+        self.state.conversation_logger.log_user_message(
+            &self.state.channel_id,
+            sender_name,
+            &message.sender_id,
+            &text,
+            &message.metadata,
+        );
+
+        self.overheard.push(
+            sender_name,
+            &text,
+            std::time::Duration::from_secs(config.listen_interval_secs),
+        );
+        if self.overheard.len() >= config.listen_batch {
+            self.summarize_overheard().await;
+        }
+    }
+
+    /// Put overheard messages in front of the turn the agent was just
+    /// addressed in, and summarize them.
+    async fn recall_overheard(&mut self) {
+        if self.overheard.is_empty() {
+            return;
+        }
+        let transcript = self.overheard.transcript();
+        let prompt_engine = self.prompt_engine().await;
+        let context = prompt_engine
+            .render_system_overheard(&transcript)
+            .unwrap_or(transcript);
+        self.state
+            .history
+            .write()
+            .await
+            .push(rig::message::Message::from(context));
+        self.summarize_overheard().await;
+    }
+
+    /// Summarize the buffered overheard messages into an episodic memory in
+    /// the background.
+    async fn summarize_overheard(&mut self) {
+        let Some(transcript) = self.overheard.take() else {
+            return;
+        };
+        let prompt_engine = self.prompt_engine().await;
+        let deps = self.deps.clone();
+        let channel_id = self.id.clone();
+        let agent_name = self.agent_display_name().to_string();
+        tokio::spawn(
+            async move {
+                crate::agent::gating::summarize_overheard(
+                    deps,
+                    prompt_engine,
+                    channel_id,
+                    agent_name,
+                    transcript,
+                )
+                .await;
+            }
+            .in_current_span(),
+        );
+    }
+
+    /// Run the message as an admin command if it is one. Returns false for
+    /// ordinary messages, which go on to the LLM.
+    async fn handle_admin_command(&mut self, message: &InboundMessage) -> bool {
+        if matches!(message.source.as_str(), "system" | "internal") {
+            return false;
+        }
+        let crate::MessageContent::Text(text) = &message.content else {
+            return false;
+        };
+        let Some(command) = crate::agent::commands::parse(text) else {
+            return false;
+        };
+
+        let prompt_engine = self.prompt_engine().await;
+        let auth = self.deps.runtime_config.auth.load();
+        let tier = crate::auth::tiers::sender_tier(&auth, message);
+        tracing::info!(
+            channel_id = %self.id,
+            sender_id = %message.sender_id,
+            command = command.name(),
+            %tier,
+            "admin command"
+        );
+        // With auth off every sender resolves to admin, so commands would be
+        // open to anyone in the conversation.
+        let reply = if !auth.enabled {
+            prompt_engine.text("commands/auth_disabled").to_string()
+        } else if tier < AuthTier::Admin {
+            prompt_engine.text("commands/not_allowed").to_string()
+        } else {
+            match self.run_admin_command(command, &prompt_engine).await {
+                Ok(reply) => reply,
+                Err(error) => {
+                    tracing::error!(%error, channel_id = %self.id, "admin command failed");
+                    prompt_engine.text("commands/failed").to_string()
+                }
+            }
+        };
+
+        if let Err(error) = self.response_tx.send(OutboundResponse::Text(reply)).await {
+            tracing::error!(%error, channel_id = %self.id, "failed to send command reply");
+        }
+        true
+    }
+
+    async fn run_admin_command(
+        &mut self,
+        command: AdminCommand,
+        prompt_engine: &crate::prompts::PromptEngine,
+    ) -> Result<String> {
+        match command {
+            AdminCommand::Status => {
+                let status = self.state.status_block.read().await;
+                let mut reply = prompt_engine.render_text(
+                    "commands/status",
+                    minijinja::context! {
+                        model => self.current_model(),
+                        workers => status.active_workers.len(),
+                        branches => status.active_branches.len(),
+                    },
+                )?;
+                for worker in &status.active_workers {
+                    reply.push_str(&format!(
+                        "\n- `{}` {} ({}, since {})",
+                        worker.id,
+                        worker.task,
+                        worker.status,
+                        worker.started_at.format("%H:%M"),
+                    ));
+                }
+                Ok(reply)
+            }
+            AdminCommand::Cancel(None) => Ok(prompt_engine.text("commands/cancel_usage").into()),
+            AdminCommand::Cancel(Some(prefix)) => {
+                let matches = self
+                    .state
+                    .active_workers
+                    .read()
+                    .await
+                    .keys()
+                    .filter(|worker_id| worker_id.to_string().starts_with(&prefix))
+                    .copied()
+                    .collect::<Vec<_>>();
+                let key = match matches.as_slice() {
+                    [worker_id] => match self.state.cancel_worker(*worker_id).await {
+                        Ok(()) => "commands/cancelled",
+                        Err(_) => "commands/worker_not_found",
+                    },
+                    [] => "commands/worker_not_found",
+                    _ => "commands/worker_ambiguous",
+                };
+                let worker_id = match matches.as_slice() {
+                    [worker_id] => worker_id.to_string(),
+                    _ => prefix,
+                };
+                prompt_engine.render_text(key, minijinja::context! { worker_id })
+            }
+            AdminCommand::Model(None) => prompt_engine.render_text(
+                "commands/model_current",
+                minijinja::context! { model => self.current_model() },
+            ),
+            AdminCommand::Model(Some(name)) if name == crate::agent::commands::MODEL_RESET => {
+                self.model_override = None;
+                prompt_engine.render_text(
+                    "commands/model_current",
+                    minijinja::context! { model => self.current_model() },
+                )
+            }
+            AdminCommand::Model(Some(name)) => {
+                let provider = match self.deps.llm_manager.resolve_model(&name) {
+                    Ok((provider, _)) => provider,
+                    Err(error) => {
+                        return prompt_engine.render_text(
+                            "commands/model_invalid",
+                            minijinja::context! { model => name, error => error.to_string() },
+                        );
+                    }
+                };
+                if self.deps.llm_manager.get_provider(&provider).is_err() {
+                    return prompt_engine.render_text(
+                        "commands/model_unknown",
+                        minijinja::context! { model => name },
+                    );
+                }
+                tracing::info!(channel_id = %self.id, model = %name, "channel model overridden");
+                self.model_override = Some(name.clone());
+                prompt_engine
+                    .render_text("commands/model_set", minijinja::context! { model => name })
+            }
+            AdminCommand::Forget => {
+                self.state.history.write().await.clear();
+                // Otherwise the saved history comes back on the next restart.
+                self.state.persist_history().await;
+                tracing::info!(channel_id = %self.id, "channel history cleared by command");
+                Ok(prompt_engine.text("commands/forgot").into())
+            }
+            AdminCommand::Usage => {
+                let usage = self.get_usage();
+                let mut reply = prompt_engine.render_text(
+                    "commands/usage",
+                    minijinja::context! {
+                        calls => usage.completion_calls,
+                        input_tokens => usage.input_tokens,
+                        output_tokens => usage.output_tokens,
+                        cost => format!("{:.4}", usage.estimated_cost_usd),
+                    },
+                )?;
+                if let (Some(budget), Some(remaining)) = (usage.budget_usd, usage.remaining_usd()) {
+                    reply.push(' ');
+                    reply.push_str(&prompt_engine.render_text(
+                        "commands/usage_budget",
+                        minijinja::context! {
+                            budget => format!("{budget:.2}"),
+                            remaining => format!("{remaining:.2}"),
+                        },
+                    )?);
+                }
+                Ok(reply)
+            }
+        }
+    }
+
+    /// The model channel turns run on: the `!model` override, or the
+    /// configured channel model.
+    fn current_model(&self) -> String {
+        match &self.model_override {
+            Some(model_name) => model_name.clone(),
+            None => self
+                .deps
+                .runtime_config
+                .routing
+                .load()
+                .resolve(ProcessType::Channel, None)
+                .to_string(),
+        }
+    }
+
+    /// Redact sensitive values from a user's message text. Applied before the
+    /// text is logged, since the log is what later turns are built from.
+    /// System re-triggers carry the agent's own output and are left alone.
+    fn redact_inbound(&self, message: &InboundMessage, raw_text: String) -> String {
+        if message.source == "system" {
+            return raw_text;
+        }
+        Redactor::new(
+            self.deps.sqlite_pool.clone(),
+            self.deps.runtime_config.clone(),
+        )
+        .redact_inbound(&self.id, &raw_text)
+        .unwrap_or(raw_text)
+    }
+
+    /// Send reply text the LLM produced without the reply tool. In a
+    /// supervised channel it is held as a draft instead, and only logged once
+    /// approved. On structured turns it goes out as a structured `reply`
+    /// without a confidence, since the model never rated it.
+    async fn send_text_reply(&self, text: String) -> Result<()> {
+        let response = if self.structured_turn {
+            OutboundResponse::Structured(crate::StructuredReply {
+                action: crate::ReplyAction::Reply,
+                message: text.clone(),
+                confidence: None,
+            })
+        } else {
+            OutboundResponse::Text(text.clone())
+        };
+        let trigger_message_id = self
+            .state
+            .latest_message
+            .read()
+            .await
+            .as_ref()
+            .map(|message| message.id.clone());
+        let response = self
+            .state
+            .drafts
+            .hold(
+                &self.id,
+                trigger_message_id.as_deref(),
+                self.state.response_tx.current_turn().await,
+                response,
+            )
+            .await?;
+        if !matches!(response, OutboundResponse::Draft { .. }) {
+            self.state.conversation_logger.log_bot_message_with_name(
+                &self.state.channel_id,
+                &text,
+                Some(self.agent_display_name()),
+            );
+        }
+        self.response_tx
+            .send(response)
+            .await
+            .map_err(|e| AgentError::Other(anyhow::anyhow!("{e}")))?;
+        Ok(())
+    }
+
     /// Run the channel event loop.
     pub async fn run(mut self) -> Result<()> {
         tracing::info!(channel_id = %self.id, "channel started");
+        if let Err(error) = self.state.cost_tracker.restore().await {
+            tracing::warn!(%error, channel_id = %self.id, "failed to restore channel usage");
+        }
 
         loop {
-            // Compute next deadline from coalesce and retrigger timers
-            let next_deadline = match (self.coalesce_deadline, self.retrigger_deadline) {
-                (Some(a), Some(b)) => Some(a.min(b)),
-                (Some(a), None) => Some(a),
-                (None, Some(b)) => Some(b),
-                (None, None) => None,
-            };
+            // Compute next deadline from coalesce, retrigger, and branch result timers
+            let next_deadline = [
+                self.coalesce_deadline,
+                self.retrigger_deadline,
+                self.pending_branch_results.deadline,
+                self.overheard.deadline,
+            ]
+            .into_iter()
+            .flatten()
+            .min();
             let sleep_duration = next_deadline
                 .map(|deadline| {
                     let now = tokio::time::Instant::now();
@@ -307,8 +1196,19 @@ impl Channel {
                 })
                 .unwrap_or(std::time::Duration::from_secs(3600)); // Default long timeout if no deadline
 
+            let idle_timeout_mins = **self.deps.runtime_config.channel_idle_timeout_mins.load();
+            let idle_deadline = (idle_timeout_mins > 0).then(|| {
+                self.last_activity + std::time::Duration::from_secs(idle_timeout_mins * 60)
+            });
+            let idle_sleep =
+                tokio::time::sleep_until(idle_deadline.unwrap_or_else(tokio::time::Instant::now));
+
             tokio::select! {
                 Some(message) = self.message_rx.recv() => {
+                    self.last_activity = tokio::time::Instant::now();
+                    let Some(message) = self.admit_message(message).await else {
+                        continue;
+                    };
                     let config = self.deps.runtime_config.coalesce.load();
                     if self.should_coalesce(&message, &config) {
                         self.coalesce_buffer.push(message);
@@ -323,7 +1223,20 @@ impl Channel {
                         }
                     }
                 }
-                Ok(event) = self.event_rx.recv() => {
+                event = self.event_rx.recv() => {
+                    let event = match event {
+                        Ok(event) => event,
+                        Err(broadcast::error::RecvError::Lagged(missed)) => {
+                            self.resync_after_lag(missed).await;
+                            continue;
+                        }
+                        // The agent holds the sender for as long as its channels run.
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    };
+                    if matches!(event, ProcessEvent::Shutdown { .. }) {
+                        self.drain_for_shutdown().await;
+                        break;
+                    }
                     // Events bypass coalescing - flush buffer first if needed
                     if let Err(error) = self.flush_coalesce_buffer().await {
                         tracing::error!(%error, channel_id = %self.id, "error flushing coalesce buffer");
@@ -340,10 +1253,30 @@ impl Channel {
                     {
                         tracing::error!(%error, channel_id = %self.id, "error flushing coalesce buffer on deadline");
                     }
+                    // Check branch result window
+                    if self.pending_branch_results.deadline.is_some_and(|d| d <= now) {
+                        self.flush_branch_results().await;
+                    }
                     // Check retrigger deadline
                     if self.retrigger_deadline.is_some_and(|d| d <= now) {
                         self.flush_pending_retrigger().await;
                     }
+                    if self.overheard.deadline.is_some_and(|d| d <= now) {
+                        self.summarize_overheard().await;
+                    }
+                }
+                _ = idle_sleep, if idle_deadline.is_some() => {
+                    if self.archive_if_idle().await {
+                        // Stop accepting messages, but still answer any that
+                        // were queued while the state was being written.
+                        self.message_rx.close();
+                        while let Some(message) = self.message_rx.try_recv() {
+                            self.handle_drained_message(message).await;
+                        }
+                        break;
+                    }
+                    // Background work is still running; count it as activity.
+                    self.last_activity = tokio::time::Instant::now();
                 }
                 else => break,
             }
@@ -358,6 +1291,232 @@ impl Channel {
         Ok(())
     }
 
+    /// Screen a message that just arrived: drop duplicates, take reactions
+    /// as feedback, run inbound middleware, check the sender may talk to the
+    /// agent, run admin commands, and overhear what the gate turns away.
+    /// Returns the message when the channel should answer it.
+    async fn admit_message(&mut self, message: InboundMessage) -> Option<InboundMessage> {
+        let dedup_window = **self.deps.runtime_config.message_dedup_window.load();
+        if self
+            .seen_messages
+            .check_and_insert(&message.id, dedup_window)
+        {
+            tracing::info!(
+                channel_id = %self.id,
+                message_id = %message.id,
+                "dropping duplicate message"
+            );
+            return None;
+        }
+        if crate::agent::feedback::is_reaction(&message) {
+            if !self.state.review_draft_reaction(&message).await {
+                self.record_reaction_feedback(&message);
+            }
+            return None;
+        }
+        // Re-triggers are the channel's own messages; middleware only sees
+        // what arrived from outside.
+        let message = if message.source == "system" {
+            message
+        } else {
+            self.deps.inbound_middleware.run(message).await?
+        };
+        if !self.sender_may_respond(&message)
+            || self.handle_admin_command(&message).await
+            || self.handle_held_reply(&message).await
+        {
+            return None;
+        }
+        if !self.passes_gate(&message).await {
+            self.overhear(&message).await;
+            return None;
+        }
+        self.recall_overheard().await;
+        Some(message)
+    }
+
+    /// Answer a message left in the queue when the channel stops taking new
+    /// ones, without coalescing.
+    async fn handle_drained_message(&mut self, message: InboundMessage) {
+        let Some(message) = self.admit_message(message).await else {
+            return;
+        };
+        if let Err(error) = self.handle_message(message).await {
+            tracing::error!(%error, channel_id = %self.id, "error handling message");
+        }
+    }
+
+    /// Persist the channel's state and report whether it can shut down.
+    ///
+    /// Refuses while branches, workers, buffered messages, a pending retrigger
+    /// or an unconcluded link conversation are in flight. Otherwise the history
+    /// and a [`ChannelSnapshot`] are written to the history store so the next
+    /// message for this conversation can rehydrate a fresh channel.
+    async fn archive_if_idle(&mut self) -> bool {
+        if (self.originating_channel.is_some() && !self.link_concluded)
+            || self.has_work_in_flight().await
+        {
+            return false;
+        }
+
+        self.summarize_overheard().await;
+        if !self.persist_state().await {
+            return false;
+        }
+        tracing::info!(channel_id = %self.id, "archived idle channel");
+        true
+    }
+
+    /// Whether buffered messages, a pending retrigger, or running branches
+    /// and workers still need the channel.
+    async fn has_work_in_flight(&self) -> bool {
+        !self.coalesce_buffer.is_empty()
+            || self.pending_retrigger
+            || !self.pending_branch_results.conclusions.is_empty()
+            || !self.state.active_branches.read().await.is_empty()
+            || !self.state.active_workers.read().await.is_empty()
+    }
+
+    /// Write the history and a [`ChannelSnapshot`] to the history store.
+    async fn persist_state(&self) -> bool {
+        let history = self.state.history.read().await.clone();
+        if let Err(error) = self.state.history_store.save(&self.id, &history).await {
+            tracing::warn!(%error, channel_id = %self.id, "failed to persist history for archival");
+            return false;
+        }
+
+        let snapshot = ChannelSnapshot {
+            status: self.state.status_block.read().await.clone(),
+            conversation_context: self.conversation_context.clone(),
+        };
+        if let Err(error) = self
+            .state
+            .history_store
+            .save_snapshot(&self.id, &snapshot)
+            .await
+        {
+            tracing::warn!(%error, channel_id = %self.id, "failed to persist channel snapshot");
+            return false;
+        }
+
+        tracing::info!(
+            channel_id = %self.id,
+            message_count = history.len(),
+            "persisted channel state"
+        );
+        true
+    }
+
+    /// Export the conversation as a [`ChannelExport`] bundle, with the live
+    /// history and status in place of what was last persisted.
+    pub async fn export(&self) -> Result<crate::conversation::ChannelExport> {
+        let mut export = crate::conversation::ChannelExport::load(
+            &self.deps.sqlite_pool,
+            &self.deps.agent_id,
+            &self.id,
+        )
+        .await?;
+        export.history = self.state.history.read().await.clone();
+        export.status = Some(ChannelSnapshot {
+            status: self.state.status_block.read().await.clone(),
+            conversation_context: self.conversation_context.clone(),
+        });
+        Ok(export)
+    }
+
+    /// Shut down gracefully. The main loop has already stopped routing new
+    /// messages here; this finishes what the channel has accepted, waits for
+    /// running branches and workers (and answers with their results) until
+    /// `shutdown_drain_secs` runs out, then persists state and posts the
+    /// configured `shutdown_notice`.
+    async fn drain_for_shutdown(&mut self) {
+        let drain_secs = **self.deps.runtime_config.shutdown_drain_secs.load();
+        tracing::info!(channel_id = %self.id, drain_secs, "draining channel for shutdown");
+
+        let drain = async {
+            if let Err(error) = self.flush_coalesce_buffer().await {
+                tracing::error!(%error, channel_id = %self.id, "error flushing coalesce buffer on shutdown");
+            }
+            self.summarize_overheard().await;
+            loop {
+                // Messages routed before shutdown, and retriggers carrying
+                // branch and worker results.
+                while let Some(message) = self.message_rx.try_recv() {
+                    self.handle_drained_message(message).await;
+                }
+                if !self.has_work_in_flight().await {
+                    break;
+                }
+
+                // Poll so work that finishes without an event is noticed too.
+                let event = tokio::time::timeout(
+                    std::time::Duration::from_millis(500),
+                    self.event_rx.recv(),
+                )
+                .await;
+                match event {
+                    Ok(Ok(event)) => {
+                        if let Err(error) = self.handle_event(event).await {
+                            tracing::error!(%error, channel_id = %self.id, "error handling event");
+                        }
+                    }
+                    Ok(Err(broadcast::error::RecvError::Closed)) => break,
+                    Ok(Err(broadcast::error::RecvError::Lagged(missed))) => {
+                        self.resync_after_lag(missed).await;
+                    }
+                    Err(_) => {}
+                }
+                // No debouncing while draining; answer as soon as results land.
+                if !self.pending_branch_results.conclusions.is_empty() {
+                    self.flush_branch_results().await;
+                }
+                self.flush_pending_retrigger().await;
+            }
+        };
+
+        if tokio::time::timeout(std::time::Duration::from_secs(drain_secs), drain)
+            .await
+            .is_err()
+        {
+            let branches = self.state.active_branches.read().await.len();
+            let workers = self.state.active_workers.read().await.len();
+            tracing::warn!(
+                channel_id = %self.id,
+                branches,
+                workers,
+                "shutdown deadline reached with work still running"
+            );
+        }
+
+        self.persist_state().await;
+
+        let notice = (**self.deps.runtime_config.shutdown_notice.load()).clone();
+        if let Some(notice) = notice.filter(|notice| !notice.trim().is_empty())
+            && self.conversation_id.is_some()
+            && !self.id.starts_with("link:")
+            && let Err(error) = self.response_tx.send(OutboundResponse::Text(notice)).await
+        {
+            tracing::warn!(%error, channel_id = %self.id, "failed to post shutdown notice");
+        }
+    }
+
+    /// Restore state saved by a previous archival of this channel. Returns
+    /// true when a snapshot was found.
+    pub async fn restore_snapshot(&mut self) -> bool {
+        match self.state.history_store.take_snapshot(&self.id).await {
+            Ok(Some(snapshot)) => {
+                *self.state.status_block.write().await = snapshot.status;
+                self.conversation_context = snapshot.conversation_context;
+                true
+            }
+            Ok(None) => false,
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, "failed to load channel snapshot");
+                false
+            }
+        }
+    }
+
     /// Determine if a message should be coalesced (batched with other messages).
     ///
     /// Returns false for:
@@ -372,7 +1531,8 @@ impl Channel {
         if !config.enabled {
             return false;
         }
-        if message.source == "system" {
+        // Re-triggers and due reminders each get a turn of their own.
+        if message.source == "system" || crate::reminders::is_reminder(message) {
             return false;
         }
         // Internal link channels are stateful handshakes between two agents.
@@ -466,8 +1626,9 @@ impl Channel {
     /// Formats all messages with attribution and timestamps, persists each
     /// individually to conversation history, then presents them as one user turn
     /// with a coalesce hint telling the LLM this is a fast-moving conversation.
-    #[tracing::instrument(skip(self, messages), fields(channel_id = %self.id, agent_id = %self.deps.agent_id, message_count = messages.len()))]
+    #[tracing::instrument(skip(self, messages), fields(channel_id = %self.id, agent_id = %self.deps.agent_id, message_count = messages.len(), turn_id = tracing::field::Empty))]
     async fn handle_message_batch(&mut self, messages: Vec<InboundMessage>) -> Result<()> {
+        self.begin_turn().await;
         let message_count = messages.len();
         let first_timestamp = messages
             .first()
@@ -499,40 +1660,32 @@ impl Channel {
             self.conversation_id = Some(first.conversation_id.clone());
         }
 
+        // One turn answers every sender in the batch, so it gets the tools
+        // of the lowest tier among them.
+        let auth = self.deps.runtime_config.auth.load();
+        if let Some(tier) = messages
+            .iter()
+            .filter(|message| message.source != "system")
+            .map(|message| crate::auth::tiers::sender_tier(&auth, message))
+            .min()
+        {
+            self.sender_tier = tier;
+        }
+        if let Some(last) = messages.iter().rev().find(|m| m.source != "system") {
+            self.structured_turn = last.wants_structured_reply();
+        }
+
         // Capture conversation context from the first message
         if self.conversation_context.is_none()
             && let Some(first) = messages.first()
         {
-            let prompt_engine = self.deps.runtime_config.prompts.load();
-            let server_name = first
-                .metadata
-                .get("discord_guild_name")
-                .and_then(|v| v.as_str())
-                .or_else(|| {
-                    first
-                        .metadata
-                        .get("telegram_chat_title")
-                        .and_then(|v| v.as_str())
-                });
-            let channel_name = first
-                .metadata
-                .get("discord_channel_name")
-                .and_then(|v| v.as_str())
-                .or_else(|| {
-                    first
-                        .metadata
-                        .get("telegram_chat_type")
-                        .and_then(|v| v.as_str())
-                });
-            self.conversation_context = Some(prompt_engine.render_conversation_context(
-                &first.source,
-                server_name,
-                channel_name,
-            )?);
+            let prompt_engine = self.prompt_engine().await;
+            self.conversation_context = Some(build_conversation_context(&prompt_engine, first)?);
         }
 
         // Persist each message to conversation log (individual audit trail)
         let mut user_contents: Vec<UserContent> = Vec::new();
+        let mut retrieval_texts: Vec<String> = Vec::new();
         let mut conversation_id = String::new();
 
         for message in &messages {
@@ -553,6 +1706,9 @@ impl Channel {
                         (message.content.to_string(), Vec::new())
                     }
                 };
+                let raw_text = self.redact_inbound(message, raw_text);
+                self.detect_language(message, &raw_text).await?;
+                self.sample_for_title(message, &raw_text).await;
 
                 self.state.conversation_logger.log_user_message(
                     &self.state.channel_id,
@@ -588,10 +1744,12 @@ impl Channel {
 
                 let formatted_text =
                     format!("[{}] ({}): {}", display_name, relative_text, raw_text);
+                retrieval_texts.push(raw_text);
 
                 // Download attachments for this message
                 if !attachments.is_empty() {
-                    let attachment_content = download_attachments(&self.deps, &attachments).await;
+                    let attachment_content =
+                        download_attachments(&self.deps, &self.id, &attachments).await;
                     for content in attachment_content {
                         user_contents.push(content);
                     }
@@ -616,18 +1774,59 @@ impl Channel {
             text_parts.join("\n")
         );
 
+        if self.refuse_over_budget(true).await {
+            return Ok(());
+        }
+
         // Build system prompt with coalesce hint
-        let system_prompt = self
+        let mut system_prompt = self
             .build_system_prompt_with_coalesce(message_count, elapsed_secs, unique_sender_count)
             .await?;
+        let mut sender_ids: Vec<&str> = Vec::new();
+        for message in messages.iter().filter(|m| m.source != "system") {
+            if !sender_ids.contains(&message.sender_id.as_str()) {
+                sender_ids.push(&message.sender_id);
+            }
+        }
+        // Memories private to a user only come along when that user sent
+        // the whole batch, since the turn answers everyone in it.
+        let memory_sender = {
+            let mut senders = messages
+                .iter()
+                .filter(|m| m.source != "system")
+                .map(InboundMessage::qualified_sender_id);
+            let first = senders.next();
+            first.filter(|first| senders.all(|sender| sender == *first))
+        };
+        let retrieval_query = retrieval_texts.join("\n");
+        self.append_relevant_memories(
+            &mut system_prompt,
+            &retrieval_query,
+            memory_sender.as_deref(),
+        )
+        .await;
+        self.append_relevant_documents(&mut system_prompt, &retrieval_query)
+            .await;
+        self.append_sender_profiles(&mut system_prompt, &sender_ids)
+            .await;
+        self.begin_plan_turn(false, true, &mut system_prompt).await;
+        self.append_structured_reply_note(&mut system_prompt).await;
 
         {
             let mut reply_target = self.state.reply_target_message_id.write().await;
             *reply_target = messages.iter().rev().find_map(extract_discord_message_id);
         }
+        if let Some(last) = messages.iter().rev().find(|m| m.source != "system") {
+            *self.state.latest_message.write().await = Some(last.clone());
+        }
 
         // Run agent turn with any image/audio attachments preserved
         let source = messages.first().map(|m| m.source.clone());
+        let trigger_message_id = messages
+            .iter()
+            .rev()
+            .find(|m| m.source != "system")
+            .map(|m| m.id.clone());
         let (result, skip_flag, replied_flag, _conclude_flag, _conclude_summary) = self
             .run_agent_turn(
                 &combined_text,
@@ -635,6 +1834,7 @@ impl Channel {
                 &conversation_id,
                 attachment_parts,
                 source,
+                trigger_message_id,
             )
             .await?;
 
@@ -668,11 +1868,23 @@ impl Channel {
         let skills_prompt = skills.render_channel_prompt(&prompt_engine)?;
 
         let browser_enabled = rc.browser_config.load().enabled;
-        let web_search_enabled = rc.brave_search_key.load().is_some();
+        let web_search_enabled = rc.web_search.load().is_configured();
+        let container_enabled = rc.container.load().enabled;
+        let github_enabled = rc.github_token.load().is_some();
+        let coolify_enabled = rc.coolify_url.load().is_some() && rc.coolify_token.load().is_some();
+        let sql_enabled = !rc.sql.load().is_empty();
+        let http_request_enabled = !rc.http_request.load().allowed_domains.is_empty();
+        let calendar_enabled = rc.calendar.load().is_configured();
         let opencode_enabled = rc.opencode.load().enabled;
         let worker_capabilities = prompt_engine.render_worker_capabilities(
             browser_enabled,
             web_search_enabled,
+            container_enabled,
+            github_enabled,
+            coolify_enabled,
+            sql_enabled,
+            http_request_enabled,
+            calendar_enabled,
             opencode_enabled,
         )?;
 
@@ -695,6 +1907,7 @@ impl Channel {
         let empty_to_none = |s: String| if s.is_empty() { None } else { Some(s) };
 
         prompt_engine.render_channel_prompt_with_links(
+            &rc.system_prompt.load(),
             empty_to_none(identity_context),
             empty_to_none(memory_bulletin.to_string()),
             empty_to_none(skills_prompt),
@@ -708,13 +1921,60 @@ impl Channel {
         )
     }
 
+    /// Store a 👍/👎 reaction to one of our replies as a memory. Reactions
+    /// never start a turn; other emoji are dropped.
+    fn record_reaction_feedback(&self, message: &InboundMessage) {
+        let Some(feedback) = crate::agent::feedback::ReactionFeedback::from_message(message) else {
+            tracing::debug!(channel_id = %self.id, message_id = %message.id, "ignoring reaction");
+            return;
+        };
+
+        let tool = crate::tools::MemorySaveTool::new(self.deps.memory_search.clone());
+        let args = crate::tools::MemorySaveArgs {
+            content: feedback.memory_content(),
+            memory_type: "observation".into(),
+            importance: None,
+            source: Some("reaction".into()),
+            channel_id: Some(self.id.to_string()),
+            scope: None,
+            owner_id: None,
+            associations: Vec::new(),
+        };
+        let channel_id = self.id.clone();
+
+        // Embedding the memory takes a moment; don't hold up the channel.
+        tokio::spawn(async move {
+            use rig::tool::Tool as _;
+            match tool.call(args).await {
+                Ok(output) => tracing::info!(
+                    %channel_id,
+                    memory_id = %output.memory_id,
+                    sentiment = ?feedback.sentiment,
+                    "saved reaction feedback"
+                ),
+                Err(error) => {
+                    tracing::warn!(%error, %channel_id, "failed to save reaction feedback")
+                }
+            }
+        });
+    }
+
+    /// Give the turn being started a fresh ID and record it on the current
+    /// span, which branches and workers spawned in the turn are children of.
+    async fn begin_turn(&self) {
+        let turn_id = TurnId::new_v4();
+        *self.state.turn_id.write().await = Some(turn_id);
+        tracing::Span::current().record("turn_id", tracing::field::display(turn_id));
+    }
+
     /// Handle an incoming message by running the channel's LLM agent loop.
     ///
     /// The LLM decides which tools to call: reply (to respond), branch (to think),
     /// spawn_worker (to delegate), route (to follow up with a worker), cancel, or
     /// memory_save. The tools act on the channel's shared state directly.
-    #[tracing::instrument(skip(self, message), fields(channel_id = %self.id, agent_id = %self.deps.agent_id, message_id = %message.id))]
+    #[tracing::instrument(skip(self, message), fields(channel_id = %self.id, agent_id = %self.deps.agent_id, message_id = %message.id, turn_id = tracing::field::Empty))]
     async fn handle_message(&mut self, message: InboundMessage) -> Result<()> {
+        self.begin_turn().await;
         tracing::info!(
             channel_id = %self.id,
             message_id = %message.id,
@@ -726,6 +1986,12 @@ impl Channel {
             self.conversation_id = Some(message.conversation_id.clone());
         }
 
+        if message.source != "system" {
+            let auth = self.deps.runtime_config.auth.load();
+            self.sender_tier = crate::auth::tiers::sender_tier(&auth, &message);
+            self.structured_turn = message.wants_structured_reply();
+        }
+
         let (raw_text, attachments) = match &message.content {
             crate::MessageContent::Text(text) => (text.clone(), Vec::new()),
             crate::MessageContent::Media { text, attachments } => {
@@ -734,11 +2000,22 @@ impl Channel {
             // Render interactions as their Display form so the LLM sees plain text.
             crate::MessageContent::Interaction { .. } => (message.content.to_string(), Vec::new()),
         };
+        let raw_text = self.redact_inbound(&message, raw_text);
+        self.detect_language(&message, &raw_text).await?;
+        self.sample_for_title(&message, &raw_text).await;
 
-        let user_text = format_user_message(&raw_text, &message);
+        let plan_command = if message.source != "system" {
+            crate::agent::plan::parse_command(&raw_text)
+        } else {
+            None
+        };
+        let user_text = match &plan_command {
+            Some(PlanCommand::Plan(request)) => format_user_message(request, &message),
+            _ => format_user_message(&raw_text, &message),
+        };
 
         let attachment_content = if !attachments.is_empty() {
-            download_attachments(&self.deps, &attachments).await
+            download_attachments(&self.deps, &self.id, &attachments).await
         } else {
             Vec::new()
         };
@@ -771,6 +2048,10 @@ impl Channel {
                 channel_id = %self.id,
                 "received link conclusion control message"
             );
+        } else if crate::reminders::is_reminder(&message) {
+            // The prompt asking for the reminder isn't something the user
+            // said; only the reply the channel writes is kept.
+            tracing::debug!(channel_id = %self.id, "received due reminder");
         } else if message.source != "system" {
             let sender_name = message
                 .metadata
@@ -791,32 +2072,8 @@ impl Channel {
 
         // Capture conversation context from the first message (platform, channel, server)
         if self.conversation_context.is_none() {
-            let prompt_engine = self.deps.runtime_config.prompts.load();
-            let server_name = message
-                .metadata
-                .get("discord_guild_name")
-                .and_then(|v| v.as_str())
-                .or_else(|| {
-                    message
-                        .metadata
-                        .get("telegram_chat_title")
-                        .and_then(|v| v.as_str())
-                });
-            let channel_name = message
-                .metadata
-                .get("discord_channel_name")
-                .and_then(|v| v.as_str())
-                .or_else(|| {
-                    message
-                        .metadata
-                        .get("telegram_chat_type")
-                        .and_then(|v| v.as_str())
-                });
-            self.conversation_context = Some(prompt_engine.render_conversation_context(
-                &message.source,
-                server_name,
-                channel_name,
-            )?);
+            let prompt_engine = self.prompt_engine().await;
+            self.conversation_context = Some(build_conversation_context(&prompt_engine, &message)?);
         }
 
         // On link channels, seed conversation history with the original outgoing message
@@ -915,19 +2172,42 @@ impl Channel {
             return Ok(());
         }
 
-        let system_prompt = self.build_system_prompt().await?;
+        if self.refuse_over_budget(message.source != "system").await {
+            return Ok(());
+        }
+
+        let mut system_prompt = self.build_system_prompt().await?;
+        if message.source != "system" {
+            self.append_relevant_memories(
+                &mut system_prompt,
+                &raw_text,
+                Some(&message.qualified_sender_id()),
+            )
+            .await;
+            self.append_relevant_documents(&mut system_prompt, &raw_text)
+                .await;
+            self.append_sender_profiles(&mut system_prompt, &[&message.sender_id])
+                .await;
+        }
 
         {
             let mut reply_target = self.state.reply_target_message_id.write().await;
             *reply_target = extract_discord_message_id(&message);
         }
+        if message.source != "system" {
+            *self.state.latest_message.write().await = Some(message.clone());
+        }
 
         let is_retrigger = message.source == "system";
+        let plan_requested = matches!(plan_command, Some(PlanCommand::Plan(_)));
+        self.begin_plan_turn(plan_requested, !is_retrigger, &mut system_prompt)
+            .await;
+        self.append_structured_reply_note(&mut system_prompt).await;
 
-        let message_source = if is_retrigger {
-            None
+        let (message_source, trigger_message_id) = if is_retrigger {
+            (None, None)
         } else {
-            Some(message.source.clone())
+            (Some(message.source.clone()), Some(message.id.clone()))
         };
 
         let (result, skip_flag, replied_flag, conclude_flag, conclude_summary) = self
@@ -937,6 +2217,7 @@ impl Channel {
                 &message.conversation_id,
                 attachment_content,
                 message_source,
+                trigger_message_id,
             )
             .await?;
 
@@ -951,19 +2232,269 @@ impl Channel {
             self.link_concluded = true;
         }
 
-        // Check context size and trigger compaction if needed
-        if let Err(error) = self.compactor.check_and_compact().await {
-            tracing::warn!(channel_id = %self.id, %error, "compaction check failed");
+        // Check context size and trigger compaction if needed
+        if let Err(error) = self.compactor.check_and_compact().await {
+            tracing::warn!(channel_id = %self.id, %error, "compaction check failed");
+        }
+
+        // Increment message counter and spawn memory persistence branch if threshold reached
+        if !is_retrigger {
+            self.retrigger_count = 0;
+            self.message_count += 1;
+            self.check_memory_persistence().await;
+        }
+
+        Ok(())
+    }
+
+    /// Set whether workers spawned this turn run in plan mode, and tell the
+    /// model when they do. A fresh plan-mode turn (one started by a user
+    /// message) discards any plan still waiting for approval.
+    async fn begin_plan_turn(&self, requested: bool, fresh: bool, system_prompt: &mut String) {
+        let config = self.deps.runtime_config.plan_mode.load();
+        let active = requested || config.enabled;
+        self.state.plan.begin_turn(active, &config.tools);
+        if !active {
+            return;
+        }
+        if fresh {
+            self.state.plan.clear();
+        }
+        match self.prompt_engine().await.render_plan_mode(&config.tools) {
+            Ok(note) => {
+                system_prompt.push_str("\n\n");
+                system_prompt.push_str(&note);
+            }
+            Err(error) => tracing::warn!(%error, "failed to render plan mode note"),
+        }
+    }
+
+    /// Tell the model to answer with the structured `reply` on structured turns.
+    async fn append_structured_reply_note(&self, system_prompt: &mut String) {
+        if !self.structured_turn {
+            return;
+        }
+        match self.prompt_engine().await.render_structured_reply() {
+            Ok(note) => {
+                system_prompt.push_str("\n\n");
+                system_prompt.push_str(&note);
+            }
+            Err(error) => tracing::warn!(%error, "failed to render structured reply note"),
+        }
+    }
+
+    /// Upload a file a worker sent with `attach_file`, and note it in the
+    /// history so the channel knows the user already has it.
+    async fn deliver_worker_attachment(
+        &self,
+        worker_id: WorkerId,
+        media_id: &str,
+        caption: Option<String>,
+    ) {
+        let (media, data) = match self.deps.media.read(media_id).await {
+            Ok(Some(found)) => found,
+            Ok(None) => {
+                tracing::warn!(%worker_id, %media_id, "attached file is missing from the media store");
+                return;
+            }
+            Err(error) => {
+                tracing::warn!(%error, %worker_id, %media_id, "failed to load attached file");
+                return;
+            }
+        };
+
+        let filename = media.filename.clone();
+        let response = OutboundResponse::File {
+            filename: media.filename.clone(),
+            data,
+            mime_type: media.mime_type.clone(),
+            caption,
+            media: Some(self.deps.media.reference(&media)),
+        };
+        if let Err(error) = self.response_tx.send(response).await {
+            tracing::warn!(%error, %worker_id, "failed to send attached file");
+            return;
+        }
+
+        self.state
+            .history
+            .write()
+            .await
+            .push(rig::message::Message::from(format!(
+                "[Worker {worker_id} sent the user a file: {filename} (media_id: {media_id})]"
+            )));
+    }
+
+    /// Answer `!approve_plan`, `approve`, or `deny` from `message` without
+    /// starting a turn. Runs on each message before coalescing, so a reply in
+    /// a batch still reaches what it answers. Returns true if the message was
+    /// one.
+    async fn handle_held_reply(&mut self, message: &InboundMessage) -> bool {
+        let Some(reply) = parse_held_reply(message, !self.state.approvals.is_empty()) else {
+            return false;
+        };
+
+        let auth = self.deps.runtime_config.auth.load();
+        let tier = crate::auth::tiers::sender_tier(&auth, message);
+        self.structured_turn = message.wants_structured_reply();
+        let sender_name = message
+            .metadata
+            .get("sender_display_name")
+            .and_then(|v| v.as_str())
+            .unwrap_or(&message.sender_id);
+        self.state.conversation_logger.log_user_message(
+            &self.state.channel_id,
+            sender_name,
+            &message.sender_id,
+            &message.content.to_string(),
+            &message.metadata,
+        );
+
+        let result = match reply {
+            HeldReply::ApprovePlan => self.approve_plan(tier).await,
+            HeldReply::Approval(reply) => self.answer_approval(reply, tier).await,
+        };
+        if let Err(error) = result {
+            tracing::error!(%error, channel_id = %self.id, "failed to answer approval reply");
+        }
+        true
+    }
+
+    /// Run the tool calls plan mode held back, on a fresh worker. Its result
+    /// comes back to the channel like any other worker's.
+    async fn approve_plan(&self, tier: AuthTier) -> Result<()> {
+        let prompt_engine = self.prompt_engine().await;
+        // With auth off every sender resolves to admin, so anyone in the
+        // conversation could approve.
+        let auth = self.deps.runtime_config.auth.load();
+        if !auth.enabled {
+            return self
+                .send_text_reply(prompt_engine.text("commands/approval_auth_disabled").into())
+                .await;
+        }
+        // Approving runs a worker, so it takes the same permission as
+        // `spawn_worker`. The plan stays held for someone who has it.
+        if !auth.permits_tool(tier, "spawn_worker") {
+            return self
+                .send_text_reply(prompt_engine.text("commands/plan_not_allowed").into())
+                .await;
+        }
+
+        let calls = self.state.plan.take();
+        if calls.is_empty() {
+            return self
+                .send_text_reply(prompt_engine.text("commands/plan_none").into())
+                .await;
+        }
+
+        let steps = calls.len();
+        let reply = match spawn_plan_worker(&self.state, calls).await {
+            Ok(worker_id) => {
+                tracing::info!(channel_id = %self.id, %worker_id, steps, "plan approved");
+                prompt_engine.render_text("commands/plan_running", minijinja::context! { steps })?
+            }
+            Err(error) => {
+                // The error can name internal paths and limits, so it stays
+                // in the log.
+                tracing::warn!(channel_id = %self.id, %error, "failed to run approved plan");
+                prompt_engine.text("commands/plan_failed").into()
+            }
+        };
+        self.send_text_reply(reply).await
+    }
+
+    /// Answer a worker's approval request with the user's `approve` or
+    /// `deny`.
+    async fn answer_approval(&self, reply: ApprovalReply, tier: AuthTier) -> Result<()> {
+        let prompt_engine = self.prompt_engine().await;
+        let auth = self.deps.runtime_config.auth.load();
+        if !auth.enabled {
+            return self
+                .send_text_reply(prompt_engine.text("commands/approval_auth_disabled").into())
+                .await;
+        }
+        // Approving lets a worker act, so it takes the same permission as
+        // `spawn_worker`. The request stays open for someone who has it.
+        if !auth.permits_tool(tier, "spawn_worker") {
+            return self
+                .send_text_reply(prompt_engine.text("commands/approval_not_allowed").into())
+                .await;
         }
 
-        // Increment message counter and spawn memory persistence branch if threshold reached
-        if !is_retrigger {
-            self.retrigger_count = 0;
-            self.message_count += 1;
-            self.check_memory_persistence().await;
+        let Some(request) = self
+            .state
+            .approvals
+            .resolve(reply.approval_id.as_deref(), reply.decision)
+        else {
+            return self
+                .send_text_reply(prompt_engine.text("commands/approval_none").into())
+                .await;
+        };
+        tracing::info!(
+            channel_id = %self.id,
+            worker_id = %request.worker_id,
+            approval_id = %request.approval_id,
+            tool_name = %request.tool_name,
+            decision = ?reply.decision,
+            "tool call approval answered"
+        );
+
+        let key = match reply.decision {
+            Decision::Approve => "commands/approval_approved",
+            Decision::Deny => "commands/approval_denied",
+        };
+        let text = prompt_engine.render_text(
+            key,
+            minijinja::context! {
+                tool_name => request.tool_name,
+                worker_id => request.worker_id.to_string(),
+            },
+        )?;
+        self.send_text_reply(text).await
+    }
+
+    /// Post a worker's approval request with the call's exact arguments. It
+    /// goes straight out, even in a supervised channel, since the worker is
+    /// blocked until someone answers.
+    async fn post_approval_request(
+        &self,
+        worker_id: WorkerId,
+        approval_id: &str,
+        tool_name: &str,
+        args: &str,
+    ) {
+        let args = serde_json::from_str::<serde_json::Value>(args)
+            .ok()
+            .and_then(|value| serde_json::to_string_pretty(&value).ok())
+            .unwrap_or_else(|| args.to_string());
+        let text = match self.prompt_engine().await.render_text(
+            "commands/approval_request",
+            minijinja::context! {
+                worker_id => worker_id.to_string(),
+                approval_id,
+                tool_name,
+                args,
+            },
+        ) {
+            Ok(text) => text,
+            Err(error) => {
+                tracing::warn!(%error, %worker_id, "failed to render approval request");
+                return;
+            }
+        };
+        if let Err(error) = self.response_tx.send(OutboundResponse::Text(text)).await {
+            tracing::warn!(%error, %worker_id, "failed to post approval request");
+            return;
         }
 
-        Ok(())
+        self.state
+            .history
+            .write()
+            .await
+            .push(rig::message::Message::from(format!(
+                "[Worker {worker_id} is waiting for the user to approve a {tool_name} call \
+                 (request {approval_id})]"
+            )));
     }
 
     /// Build the rendered available channels fragment for cross-channel awareness.
@@ -1217,11 +2748,23 @@ impl Channel {
         let skills_prompt = skills.render_channel_prompt(&prompt_engine)?;
 
         let browser_enabled = rc.browser_config.load().enabled;
-        let web_search_enabled = rc.brave_search_key.load().is_some();
+        let web_search_enabled = rc.web_search.load().is_configured();
+        let container_enabled = rc.container.load().enabled;
+        let github_enabled = rc.github_token.load().is_some();
+        let coolify_enabled = rc.coolify_url.load().is_some() && rc.coolify_token.load().is_some();
+        let sql_enabled = !rc.sql.load().is_empty();
+        let http_request_enabled = !rc.http_request.load().allowed_domains.is_empty();
+        let calendar_enabled = rc.calendar.load().is_configured();
         let opencode_enabled = rc.opencode.load().enabled;
         let worker_capabilities = prompt_engine.render_worker_capabilities(
             browser_enabled,
             web_search_enabled,
+            container_enabled,
+            github_enabled,
+            coolify_enabled,
+            sql_enabled,
+            http_request_enabled,
+            calendar_enabled,
             opencode_enabled,
         )?;
 
@@ -1238,6 +2781,7 @@ impl Channel {
         let empty_to_none = |s: String| if s.is_empty() { None } else { Some(s) };
 
         prompt_engine.render_channel_prompt_with_links(
+            &rc.system_prompt.load(),
             empty_to_none(identity_context),
             empty_to_none(memory_bulletin.to_string()),
             empty_to_none(skills_prompt),
@@ -1251,11 +2795,273 @@ impl Channel {
         )
     }
 
+    /// Append memory to the system prompt, if memory injection is enabled.
+    /// Semantic memories are searched by relevance to the incoming message;
+    /// episodic ones are the latest events logged in this conversation. Only
+    /// memories scoped to this conversation, to `sender_id`, or to the whole
+    /// agent are considered; without a sender, no user's memories are.
+    /// Failures are logged and the turn proceeds without them.
+    async fn append_relevant_memories(
+        &self,
+        system_prompt: &mut String,
+        query: &str,
+        sender_id: Option<&str>,
+    ) {
+        let config = **self.deps.runtime_config.memory_injection.load();
+        if !config.enabled {
+            return;
+        }
+        let prompt_engine = self.deps.runtime_config.prompts.load();
+
+        if config.max_results > 0 && !query.trim().is_empty() {
+            let search_config = crate::memory::SearchConfig {
+                max_results: config.max_results,
+                min_score: config.min_score,
+                kind: Some(crate::memory::MemoryKind::Semantic),
+                audience: Some(crate::memory::MemoryAudience::new(
+                    self.id.as_ref(),
+                    sender_id.map(str::to_string),
+                )),
+                ..Default::default()
+            };
+            match self.deps.memory_search.search(query, &search_config).await {
+                Ok(results) if !results.is_empty() => {
+                    let memories = results
+                        .into_iter()
+                        .take(config.max_results)
+                        .map(|result| crate::prompts::RelevantMemory {
+                            memory_type: result.memory.memory_type.to_string(),
+                            content: result.memory.content,
+                        })
+                        .collect::<Vec<_>>();
+                    let count = memories.len();
+                    match prompt_engine.render_relevant_memories(memories) {
+                        Ok(section) => {
+                            system_prompt.push_str("\n\n");
+                            system_prompt.push_str(&section);
+                            tracing::debug!(channel_id = %self.id, count, "injected relevant memories");
+                        }
+                        Err(error) => {
+                            tracing::warn!(%error, "failed to render relevant memories");
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(error) => {
+                    tracing::warn!(%error, channel_id = %self.id, "memory injection search failed");
+                }
+            }
+        }
+
+        if config.max_events > 0 {
+            let events = match self
+                .deps
+                .memory_search
+                .store()
+                .get_recent_events(&self.id, sender_id, config.max_events as i64)
+                .await
+            {
+                Ok(events) => events,
+                Err(error) => {
+                    tracing::warn!(%error, channel_id = %self.id, "failed to load recent events");
+                    return;
+                }
+            };
+            if events.is_empty() {
+                return;
+            }
+            let events = events
+                .into_iter()
+                .map(|event| crate::prompts::RecentEvent {
+                    logged_at: event.created_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+                    content: event.content,
+                })
+                .collect::<Vec<_>>();
+            let count = events.len();
+            match prompt_engine.render_recent_events(events) {
+                Ok(section) => {
+                    system_prompt.push_str("\n\n");
+                    system_prompt.push_str(&section);
+                    tracing::debug!(channel_id = %self.id, count, "injected recent events");
+                }
+                Err(error) => {
+                    tracing::warn!(%error, "failed to render recent events");
+                }
+            }
+        }
+    }
+
+    /// Search the knowledge base for passages matching the incoming message and
+    /// append the ones scoring at least `min_score` to the system prompt.
+    /// Skipped entirely when auto-retrieval is off or nothing has been ingested.
+    async fn append_relevant_documents(&self, system_prompt: &mut String, query: &str) {
+        let config = **self.deps.runtime_config.knowledge.load();
+        if !config.auto_retrieve || config.max_results == 0 || query.trim().is_empty() {
+            return;
+        }
+        match self.deps.knowledge.is_empty().await {
+            Ok(false) => {}
+            Ok(true) => return,
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, "failed to check knowledge base");
+                return;
+            }
+        }
+
+        let hits = match self.deps.knowledge.search(query, config.max_results).await {
+            Ok(hits) => hits,
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, "knowledge retrieval failed");
+                return;
+            }
+        };
+        let documents = hits
+            .into_iter()
+            .filter(|hit| hit.score >= config.min_score)
+            .map(|hit| crate::prompts::RelevantDocument {
+                title: hit.title,
+                content: hit.content,
+            })
+            .collect::<Vec<_>>();
+        if documents.is_empty() {
+            return;
+        }
+        let count = documents.len();
+
+        let prompt_engine = self.deps.runtime_config.prompts.load();
+        match prompt_engine.render_relevant_documents(documents) {
+            Ok(section) => {
+                system_prompt.push_str("\n\n");
+                system_prompt.push_str(&section);
+                tracing::debug!(channel_id = %self.id, count, "injected knowledge passages");
+            }
+            Err(error) => {
+                tracing::warn!(%error, "failed to render knowledge passages");
+            }
+        }
+    }
+
+    /// Append what's known about the given senders to the system prompt as a
+    /// "Known About Sender" section. Senders without a profile are skipped.
+    async fn append_sender_profiles(&self, system_prompt: &mut String, sender_ids: &[&str]) {
+        if sender_ids.is_empty() {
+            return;
+        }
+
+        let profiles = match self.state.profile_store.get_many(sender_ids).await {
+            Ok(profiles) if !profiles.is_empty() => profiles,
+            Ok(_) => return,
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, "failed to load sender profiles");
+                return;
+            }
+        };
+
+        let profiles = profiles
+            .into_iter()
+            .map(|profile| crate::prompts::SenderProfile {
+                name: profile.name().to_string(),
+                preferred_name: profile.preferred_name,
+                timezone: profile.timezone,
+                preferences: profile.preferences,
+            })
+            .collect::<Vec<_>>();
+
+        let prompt_engine = self.deps.runtime_config.prompts.load();
+        match prompt_engine.render_sender_profiles(profiles) {
+            Ok(section) => {
+                system_prompt.push_str("\n\n");
+                system_prompt.push_str(&section);
+            }
+            Err(error) => {
+                tracing::warn!(%error, "failed to render sender profiles");
+            }
+        }
+    }
+
+    /// Fork this conversation into a new channel. See [`ChannelState::fork`].
+    pub async fn fork(&self, fork_id: &ChannelId, display_name: Option<&str>) -> Result<usize> {
+        self.state.fork(fork_id, display_name).await
+    }
+
+    /// Accumulated token usage and estimated cost for this channel.
+    pub fn get_usage(&self) -> ChannelUsage {
+        let mut usage = self.state.cost_tracker.usage();
+        usage.budget_usd = **self.deps.runtime_config.channel_budget_usd.load();
+        usage
+    }
+
+    /// Check the hard spend limit before starting a turn. When exhausted,
+    /// skip the LLM call entirely and, for real user messages, say why.
+    async fn refuse_over_budget(&self, notify_user: bool) -> bool {
+        let usage = self.get_usage();
+        if !usage.budget_exceeded() {
+            return false;
+        }
+
+        tracing::warn!(channel_id = %self.id, "channel budget exhausted, refusing turn");
+        self.deps
+            .event_tx
+            .send(ProcessEvent::BudgetExceeded {
+                agent_id: self.deps.agent_id.clone(),
+                channel_id: self.id.clone(),
+                spent_usd: usage.estimated_cost_usd,
+                budget_usd: usage.budget_usd.unwrap_or_default(),
+                turn_id: *self.state.turn_id.read().await,
+            })
+            .ok();
+        if notify_user
+            && let Err(error) = self
+                .response_tx
+                .send(OutboundResponse::Text(
+                    self.prompt_engine()
+                        .await
+                        .text("replies/budget_exceeded")
+                        .into(),
+                ))
+                .await
+        {
+            tracing::error!(%error, channel_id = %self.id, "failed to send budget notice");
+        }
+        true
+    }
+
+    /// Record which model a turn was routed to in the tool audit log.
+    fn log_routing_decision(
+        &self,
+        hints: &crate::llm::routing::RoutingHints,
+        decision: crate::llm::routing::RoutingDecision<'_>,
+    ) {
+        tracing::info!(
+            channel_id = %self.id,
+            model = decision.model,
+            tier = decision.tier.as_str(),
+            reason = decision.reason,
+            "channel turn routed"
+        );
+        ToolAuditLog::new(self.deps.sqlite_pool.clone()).record(ToolAuditEntry {
+            process_id: ProcessId::Channel(self.id.clone()).to_string(),
+            process_type: ProcessType::Channel.to_string(),
+            channel_id: Some(self.id.to_string()),
+            tool_name: crate::llm::routing::ROUTING_AUDIT_NAME.to_string(),
+            arguments: serde_json::to_string(hints).unwrap_or_default(),
+            result_summary: format!(
+                "{} ({}): {}",
+                decision.model,
+                decision.tier.as_str(),
+                decision.reason
+            ),
+            duration_ms: None,
+            input_tokens: 0,
+            output_tokens: 0,
+        });
+    }
+
     /// Register per-turn tools, run the LLM agentic loop, and clean up.
     ///
     /// Returns the prompt result and skip flag for the caller to dispatch.
     #[allow(clippy::type_complexity)]
-    #[tracing::instrument(skip(self, user_text, system_prompt, attachment_content, message_source), fields(channel_id = %self.id, agent_id = %self.deps.agent_id))]
+    #[tracing::instrument(skip(self, user_text, system_prompt, attachment_content, message_source, trigger_message_id), fields(channel_id = %self.id, agent_id = %self.deps.agent_id, model = tracing::field::Empty))]
     async fn run_agent_turn(
         &self,
         user_text: &str,
@@ -1263,6 +3069,7 @@ impl Channel {
         conversation_id: &str,
         attachment_content: Vec<UserContent>,
         message_source: Option<String>,
+        trigger_message_id: Option<String>,
     ) -> Result<(
         std::result::Result<String, rig::completion::PromptError>,
         crate::tools::SkipFlag,
@@ -1293,8 +3100,11 @@ impl Channel {
             self.send_agent_message_tool.clone(),
             conclude_link_args,
             message_source,
+            trigger_message_id,
             self.originating_channel.clone(),
             self.originating_source.clone(),
+            self.sender_tier,
+            self.structured_turn,
         )
         .await
         {
@@ -1305,10 +3115,40 @@ impl Channel {
         let rc = &self.deps.runtime_config;
         let routing = rc.routing.load();
         let max_turns = **rc.max_turns.load();
-        let model_name = routing.resolve(ProcessType::Channel, None);
+        let hints = crate::llm::routing::RoutingHints::from_message(
+            user_text,
+            attachment_content.iter().map(attachment_kind).collect(),
+        );
+        let model_name = match &self.model_override {
+            Some(model_name) => model_name.as_str(),
+            None => {
+                let decision = routing.resolve_with_hints(ProcessType::Channel, None, &hints);
+                if !routing.channel_cheap.is_empty() {
+                    self.log_routing_decision(&hints, decision);
+                }
+                decision.model
+            }
+        };
+        tracing::Span::current().record("model", model_name);
+        *self.state.turn_span.write().await = tracing::Span::current();
+
+        #[cfg(feature = "metrics")]
+        crate::telemetry::Metrics::global().record_channel_turn(&self.deps.agent_id, &self.id);
+
+        self.state
+            .cost_tracker
+            .begin_turn(model_name, **rc.channel_budget_usd.load());
+        self.hook.begin_turn(**rc.tool_call_repairs.load());
         let model = SpacebotModel::make(&self.deps.llm_manager, model_name)
             .with_context(&*self.deps.agent_id, "channel")
-            .with_routing((**routing).clone());
+            .with_routing((**routing).clone())
+            .with_fallback_events(
+                self.deps.event_tx.clone(),
+                self.deps.agent_id.clone(),
+                ProcessId::Channel(self.id.clone()),
+                Some(self.id.clone()),
+            )
+            .with_turn_id(*self.state.turn_id.read().await);
 
         let agent = AgentBuilder::new(model)
             .preamble(system_prompt)
@@ -1316,10 +3156,7 @@ impl Channel {
             .tool_server_handle(self.tool_server.clone())
             .build();
 
-        let _ = self
-            .response_tx
-            .send(OutboundResponse::Status(crate::StatusUpdate::Thinking))
-            .await;
+        self.hook.send_presence(crate::StatusUpdate::Thinking).await;
 
         // Inject attachments as a user message before the text prompt
         if !attachment_content.is_empty() {
@@ -1331,6 +3168,9 @@ impl Channel {
             drop(history);
         }
 
+        self.fit_history_to_context(system_prompt, user_text)
+            .await?;
+
         // Clone history out so the write lock is released before the agentic loop.
         // The branch tool needs a read lock on history to clone it for the branch,
         // and holding a write lock across the entire agentic loop would deadlock.
@@ -1340,11 +3180,17 @@ impl Channel {
         };
         let history_len_before = history.len();
 
-        let mut result = agent
+        #[cfg(feature = "replay")]
+        let recorder = crate::replay::TurnRecorder::start(&self.id);
+
+        let prompt = agent
             .prompt(user_text)
             .with_history(&mut history)
             .with_hook(self.hook.clone())
-            .await;
+            .with_tool_concurrency(**rc.tool_concurrency.load());
+        #[cfg(feature = "replay")]
+        let prompt = recorder.run(prompt);
+        let mut result = prompt.await;
 
         // If the LLM responded with text that looks like tool call syntax, it failed
         // to use the tool calling API. Inject a correction and retry a couple
@@ -1367,17 +3213,30 @@ impl Channel {
 
             let prompt_engine = self.deps.runtime_config.prompts.load();
             let correction = prompt_engine.render_system_tool_syntax_correction()?;
-            result = agent
+            let prompt = agent
                 .prompt(&correction)
                 .with_history(&mut history)
                 .with_hook(self.hook.clone())
-                .await;
+                .with_tool_concurrency(**rc.tool_concurrency.load());
+            #[cfg(feature = "replay")]
+            let prompt = recorder.run(prompt);
+            result = prompt.await;
         }
 
+        #[cfg(feature = "replay")]
+        recorder.finish();
+
+        let sent_replies = std::mem::take(&mut *self.state.sent_replies.write().await);
         {
             let mut guard = self.state.history.write().await;
+            let prompt_message = history.get(history_len_before).cloned();
             apply_history_after_turn(&result, &mut guard, history, history_len_before, &self.id);
+            record_sent_replies(&mut guard, history_len_before, prompt_message, sent_replies);
         }
+        self.state.persist_history().await;
+
+        // Release the turn span so it ends (and exports) with the turn.
+        *self.state.turn_span.write().await = tracing::Span::none();
 
         if let Err(error) = crate::tools::remove_channel_tools(&self.tool_server).await {
             tracing::warn!(%error, "failed to remove channel tools");
@@ -1392,6 +3251,49 @@ impl Channel {
         ))
     }
 
+    /// Drop the oldest history if the system prompt (status block included),
+    /// history, and new message wouldn't fit the context window with room
+    /// left for the response. The compactor works after turns and in the
+    /// background; this guarantees the turn about to run isn't rejected.
+    async fn fit_history_to_context(&self, system_prompt: &str, user_text: &str) -> Result<()> {
+        let context_window = **self.deps.runtime_config.context_window.load();
+        let reserved = (system_prompt.len() + user_text.len()) / 4
+            + (context_window as f32 * RESPONSE_HEADROOM_FRACTION) as usize;
+        let budget = context_window.saturating_sub(reserved);
+
+        let mut history = self.state.history.write().await;
+        let Some((messages_removed, tokens_removed)) =
+            crate::agent::compactor::trim_history_to_budget(&mut history, budget)
+        else {
+            return Ok(());
+        };
+
+        let prompt_engine = self.deps.runtime_config.prompts.load();
+        let marker = prompt_engine.render_system_truncation(messages_removed)?;
+        history.insert(0, rig::message::Message::from(marker));
+        drop(history);
+
+        tracing::warn!(
+            channel_id = %self.id,
+            messages_removed,
+            tokens_removed,
+            budget,
+            "trimmed history to fit the context window"
+        );
+        self.deps
+            .event_tx
+            .send(ProcessEvent::ContextTrimmed {
+                agent_id: self.deps.agent_id.clone(),
+                channel_id: self.id.clone(),
+                messages_removed,
+                tokens_removed,
+                turn_id: *self.state.turn_id.read().await,
+            })
+            .ok();
+
+        Ok(())
+    }
+
     /// Dispatch the LLM result: send fallback text, log errors, clean up typing.
     ///
     /// On retrigger turns (`is_retrigger = true`), fallback text is suppressed
@@ -1443,14 +3345,7 @@ impl Channel {
                                 if extracted.is_some() {
                                     tracing::warn!(channel_id = %self.id, "extracted reply from malformed tool syntax in retrigger fallback");
                                 }
-                                self.state
-                                    .conversation_logger
-                                    .log_bot_message(&self.state.channel_id, &final_text);
-                                if let Err(error) = self
-                                    .response_tx
-                                    .send(OutboundResponse::Text(final_text))
-                                    .await
-                                {
+                                if let Err(error) = self.send_text_reply(final_text).await {
                                     tracing::error!(%error, channel_id = %self.id, "failed to send retrigger fallback reply");
                                 }
                             }
@@ -1492,17 +3387,10 @@ impl Channel {
                                 extracted.as_deref().unwrap_or(text),
                                 source,
                             );
-                            if !final_text.is_empty() {
-                                self.state
-                                    .conversation_logger
-                                    .log_bot_message(&self.state.channel_id, &final_text);
-                                if let Err(error) = self
-                                    .response_tx
-                                    .send(OutboundResponse::Text(final_text))
-                                    .await
-                                {
-                                    tracing::error!(%error, channel_id = %self.id, "failed to send retrigger fallback reply");
-                                }
+                            if !final_text.is_empty()
+                                && let Err(error) = self.send_text_reply(final_text).await
+                            {
+                                tracing::error!(%error, channel_id = %self.id, "failed to send retrigger fallback reply");
                             }
                         }
                     } else {
@@ -1537,16 +3425,7 @@ impl Channel {
                             if extracted.is_some() {
                                 tracing::warn!(channel_id = %self.id, "extracted reply from malformed tool syntax in LLM text output");
                             }
-                            self.state.conversation_logger.log_bot_message_with_name(
-                                &self.state.channel_id,
-                                &final_text,
-                                Some(self.agent_display_name()),
-                            );
-                            if let Err(error) = self
-                                .response_tx
-                                .send(OutboundResponse::Text(final_text))
-                                .await
-                            {
+                            if let Err(error) = self.send_text_reply(final_text).await {
                                 tracing::error!(%error, channel_id = %self.id, "failed to send fallback reply");
                             }
                         }
@@ -1561,6 +3440,8 @@ impl Channel {
             Err(rig::completion::PromptError::PromptCancelled { reason, .. }) => {
                 if reason == "reply delivered" {
                     tracing::debug!(channel_id = %self.id, "channel turn completed via reply tool");
+                } else if reason == crate::hooks::spacebot::TOOL_CALL_REPAIRS_EXHAUSTED {
+                    tracing::warn!(channel_id = %self.id, "channel turn failed: model kept sending malformed tool calls");
                 } else {
                     tracing::info!(channel_id = %self.id, %reason, "channel turn cancelled");
                 }
@@ -1571,19 +3452,93 @@ impl Channel {
         }
 
         // Ensure typing indicator is always cleaned up, even on error paths
-        let _ = self
-            .response_tx
-            .send(OutboundResponse::Status(crate::StatusUpdate::StopTyping))
-            .await;
+        self.send_idle_presence().await;
+    }
+
+    /// Report that the channel is between turns: waiting on its workers if
+    /// any are running, otherwise idle.
+    async fn send_idle_presence(&self) {
+        let count = self.state.worker_handles.read().await.len();
+        let status = if count > 0 {
+            crate::StatusUpdate::WaitingOnWorker { count }
+        } else {
+            crate::StatusUpdate::Idle
+        };
+        self.hook.send_presence(status).await;
     }
 
     /// Handle a process event (branch results, worker completions, status updates).
     async fn handle_event(&mut self, event: ProcessEvent) -> Result<()> {
+        self.apply_event(event, false).await
+    }
+
+    /// Replay branch and worker results that reached the journal while no
+    /// channel for this conversation was listening, e.g. while it was
+    /// archived or the process was restarting. Returns how many were replayed.
+    pub async fn replay_journal(&mut self) -> usize {
+        let events = match self.state.event_journal().pending(&self.id).await {
+            Ok(events) => events,
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, "failed to load event journal");
+                return 0;
+            }
+        };
+
+        let count = events.len();
+        for event in events {
+            if let Err(error) = self.apply_event(event, true).await {
+                tracing::error!(%error, channel_id = %self.id, "error replaying journaled event");
+            }
+        }
+        count
+    }
+
+    /// Recover from the event bus dropping `missed` events because this
+    /// channel fell behind. Branch and worker results among them are still
+    /// in the journal and are applied from there; results that also arrive
+    /// from the bus afterwards find their process gone and are ignored.
+    async fn resync_after_lag(&mut self, missed: u64) {
+        tracing::warn!(
+            channel_id = %self.id,
+            missed,
+            "channel fell behind the event bus, resyncing from the event journal"
+        );
+
+        #[cfg(feature = "metrics")]
+        crate::telemetry::Metrics::global()
+            .channel_events_lagged_total
+            .with_label_values(&[&*self.deps.agent_id])
+            .inc_by(missed);
+
+        let events = match self.state.event_journal().pending(&self.id).await {
+            Ok(events) => events,
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, "failed to load event journal");
+                return;
+            }
+        };
+        for event in events {
+            if let Err(error) = self.apply_event(event, false).await {
+                tracing::error!(%error, channel_id = %self.id, "error applying journaled event");
+            }
+        }
+    }
+
+    /// Apply a process event. `replayed` events come from the journal, for
+    /// branches and workers a previous run of this channel started, so they
+    /// are delivered even though this run doesn't track them.
+    async fn apply_event(&mut self, event: ProcessEvent, replayed: bool) -> Result<()> {
         // Only process events targeted at this channel
         if !event_is_for_channel(&event, &self.id) {
             return Ok(());
         }
 
+        if let Some((channel_id, process_id)) =
+            crate::conversation::event_journal::journal_key(&event)
+        {
+            self.state.event_journal().consume(&channel_id, &process_id);
+        }
+
         // Update status block
         {
             let mut status = self.state.status_block.write().await;
@@ -1591,7 +3546,7 @@ impl Channel {
         }
 
         let mut should_retrigger = false;
-        let mut retrigger_metadata = std::collections::HashMap::new();
+        let mut should_flush_branches = false;
         let run_logger = &self.state.process_run_logger;
 
         match &event {
@@ -1610,13 +3565,40 @@ impl Channel {
             ProcessEvent::BranchResult {
                 branch_id,
                 conclusion,
+                parent_branch_id: Some(parent_branch_id),
+                ..
+            } => {
+                // Sub-branch results reach history through their parent's.
+                run_logger.log_branch_completed(*branch_id, conclusion);
+                tracing::debug!(
+                    branch_id = %branch_id,
+                    parent_branch_id = %parent_branch_id,
+                    "sub-branch result received"
+                );
+            }
+            ProcessEvent::BranchResult {
+                branch_id,
+                conclusion,
+                sub_results,
                 ..
             } => {
                 run_logger.log_branch_completed(*branch_id, conclusion);
 
-                // Remove from active branches
-                let mut branches = self.state.active_branches.write().await;
-                branches.remove(branch_id);
+                // Remove from active branches. A branch that is no longer
+                // tracked was cancelled, and its result should not resurface.
+                let was_active = self
+                    .state
+                    .active_branches
+                    .write()
+                    .await
+                    .remove(branch_id)
+                    .is_some()
+                    || replayed;
+                self.state
+                    .cancel_tokens
+                    .write()
+                    .await
+                    .remove(&ProcessId::Branch(*branch_id));
 
                 #[cfg(feature = "metrics")]
                 crate::telemetry::Metrics::global()
@@ -1630,23 +3612,57 @@ impl Channel {
                 if self.memory_persistence_branches.remove(branch_id) {
                     self.branch_reply_targets.remove(branch_id);
                     tracing::info!(branch_id = %branch_id, "memory persistence branch completed");
+                } else if !was_active {
+                    self.branch_reply_targets.remove(branch_id);
+                    tracing::info!(branch_id = %branch_id, "cancelled branch finished");
                 } else {
-                    // Regular branch: inject conclusion into history
-                    let mut history = self.state.history.write().await;
-                    let branch_message = format!("[Branch result]: {conclusion}");
-                    history.push(rig::message::Message::from(branch_message));
-                    should_retrigger = true;
-
+                    // Regular branch: hold the conclusion until sibling
+                    // branches finish or the window closes, then inject them
+                    // into history together.
+                    let pending = &mut self.pending_branch_results;
+                    pending
+                        .conclusions
+                        .push(crate::agent::branch::render_conclusion_tree(
+                            conclusion,
+                            sub_results,
+                        ));
                     if let Some(message_id) = self.branch_reply_targets.remove(branch_id) {
-                        retrigger_metadata.insert(
+                        pending.retrigger_metadata.insert(
                             "discord_reply_to_message_id".to_string(),
                             serde_json::Value::from(message_id),
                         );
                     }
 
-                    tracing::info!(branch_id = %branch_id, "branch result incorporated");
+                    let window_ms = **self.deps.runtime_config.branch_result_window_ms.load();
+                    if window_ms == 0 || !self.has_running_branches().await {
+                        should_flush_branches = true;
+                    } else if self.pending_branch_results.deadline.is_none() {
+                        self.pending_branch_results.deadline = Some(
+                            tokio::time::Instant::now()
+                                + std::time::Duration::from_millis(window_ms),
+                        );
+                    }
+
+                    tracing::info!(branch_id = %branch_id, "branch result received");
                 }
             }
+            ProcessEvent::BranchTimeout {
+                branch_id,
+                timeout_secs,
+                ..
+            } => {
+                // The watchdog already stopped the task and freed its slot. If
+                // the branch winds down cooperatively, its BranchResult is
+                // dropped like any other cancelled branch.
+                run_logger.log_branch_completed(
+                    *branch_id,
+                    &format!("Branch timed out after {timeout_secs}s"),
+                );
+                self.memory_persistence_branches.remove(branch_id);
+                self.branch_reply_targets.remove(branch_id);
+                should_flush_branches = !self.pending_branch_results.conclusions.is_empty()
+                    && !self.has_running_branches().await;
+            }
             ProcessEvent::WorkerStarted {
                 worker_id,
                 channel_id,
@@ -1667,63 +3683,194 @@ impl Channel {
             } => {
                 run_logger.log_worker_status(*worker_id, status);
             }
+            ProcessEvent::WorkerResponse {
+                worker_id,
+                response,
+                ..
+            } => {
+                // Interactive workers stay alive between turns, so their
+                // intermediate answers are injected here rather than waiting
+                // for WorkerComplete.
+                let mut history = self.state.history.write().await;
+                let worker_message = format!("[Worker {worker_id} responded]: {response}");
+                history.push(rig::message::Message::from(worker_message));
+                should_retrigger = true;
+
+                tracing::info!(worker_id = %worker_id, "interactive worker response incorporated");
+            }
+            ProcessEvent::WorkerAttachment {
+                worker_id,
+                media_id,
+                caption,
+                ..
+            } => {
+                self.deliver_worker_attachment(*worker_id, media_id, caption.clone())
+                    .await;
+            }
+            ProcessEvent::ApprovalRequested {
+                worker_id,
+                approval_id,
+                tool_name,
+                args,
+                ..
+            } => {
+                self.post_approval_request(*worker_id, approval_id, tool_name, args)
+                    .await;
+            }
             ProcessEvent::WorkerComplete {
                 worker_id,
                 result,
                 notify,
-                success,
                 ..
             } => {
-                run_logger.log_worker_completed(*worker_id, result, *success);
-
                 let mut workers = self.state.active_workers.write().await;
                 workers.remove(worker_id);
                 drop(workers);
 
-                self.state.worker_handles.write().await.remove(worker_id);
+                // cancel_worker already removed the handle and logged the run
+                // as cancelled, so a worker that winds down after cancellation
+                // completes silently.
+                let was_active = self
+                    .state
+                    .worker_handles
+                    .write()
+                    .await
+                    .remove(worker_id)
+                    .is_some()
+                    || replayed;
                 self.state.worker_inputs.write().await.remove(worker_id);
+                self.state
+                    .cancel_tokens
+                    .write()
+                    .await
+                    .remove(&ProcessId::Worker(*worker_id));
+
+                if !was_active {
+                    tracing::info!(worker_id = %worker_id, "cancelled worker finished");
+                } else {
+                    run_logger.log_worker_completed(*worker_id, &result.output, result.succeeded());
+
+                    if *notify {
+                        let mut history = self.state.history.write().await;
+                        let worker_message = format_worker_result(*worker_id, result);
+                        history.push(rig::message::Message::from(worker_message));
+                        should_retrigger = true;
+                    }
+
+                    tracing::info!(worker_id = %worker_id, "worker completed");
+                }
+                self.send_idle_presence().await;
+            }
+            ProcessEvent::ChannelTitled {
+                channel_id, title, ..
+            } => {
+                tracing::info!(channel_id = %self.id, title, "channel titled");
+                self.title = Some(title.clone());
+                self.state.channel_store.set_title(channel_id, title);
+
+                let message = self.state.latest_message.read().await.clone();
+                if let (Some(messaging_manager), Some(message)) =
+                    (&self.deps.messaging_manager, message)
+                    && let Err(error) = messaging_manager.rename_conversation(&message, title).await
+                {
+                    tracing::warn!(%error, channel_id = %self.id, "failed to rename conversation");
+                }
+            }
+            _ => {}
+        }
+
+        if should_flush_branches {
+            self.flush_branch_results().await;
+        }
+        if should_retrigger {
+            self.schedule_retrigger(HashMap::new()).await;
+        }
+
+        Ok(())
+    }
 
-                if *notify {
-                    let mut history = self.state.history.write().await;
-                    let worker_message = format!("[Worker {worker_id} completed]: {result}");
-                    history.push(rig::message::Message::from(worker_message));
-                    should_retrigger = true;
-                }
+    /// Persist history after a branch or worker result was injected and
+    /// schedule a retrigger.
+    ///
+    /// Debounced: instead of firing immediately, set a deadline. Multiple
+    /// branch/worker completions within the debounce window are coalesced
+    /// into a single retrigger to prevent message spam.
+    async fn schedule_retrigger(&mut self, metadata: HashMap<String, serde_json::Value>) {
+        self.state.persist_history().await;
 
-                tracing::info!(worker_id = %worker_id, "worker completed");
-            }
-            _ => {}
+        if self.retrigger_count >= MAX_RETRIGGERS_PER_TURN {
+            tracing::warn!(
+                channel_id = %self.id,
+                retrigger_count = self.retrigger_count,
+                max = MAX_RETRIGGERS_PER_TURN,
+                "retrigger cap reached, suppressing further retriggers until next user message"
+            );
+            return;
         }
 
-        // Debounce retriggers: instead of firing immediately, set a deadline.
-        // Multiple branch/worker completions within the debounce window are
-        // coalesced into a single retrigger to prevent message spam.
-        if should_retrigger {
-            if self.retrigger_count >= MAX_RETRIGGERS_PER_TURN {
-                tracing::warn!(
-                    channel_id = %self.id,
-                    retrigger_count = self.retrigger_count,
-                    max = MAX_RETRIGGERS_PER_TURN,
-                    "retrigger cap reached, suppressing further retriggers until next user message"
-                );
-            } else {
-                self.pending_retrigger = true;
-                // Merge metadata (later events override earlier ones for the same key)
-                for (key, value) in retrigger_metadata {
-                    self.pending_retrigger_metadata.insert(key, value);
-                }
-                self.retrigger_deadline = Some(
-                    tokio::time::Instant::now()
-                        + std::time::Duration::from_millis(RETRIGGER_DEBOUNCE_MS),
-                );
+        self.pending_retrigger = true;
+        // Merge metadata (later events override earlier ones for the same key)
+        self.pending_retrigger_metadata.extend(metadata);
+        self.retrigger_deadline = Some(
+            tokio::time::Instant::now() + std::time::Duration::from_millis(RETRIGGER_DEBOUNCE_MS),
+        );
+    }
+
+    /// Whether any branch other than a silent memory persistence branch is
+    /// still running.
+    async fn has_running_branches(&self) -> bool {
+        self.state
+            .active_branches
+            .read()
+            .await
+            .keys()
+            .any(|branch_id| !self.memory_persistence_branches.contains(branch_id))
+    }
+
+    /// Inject held branch conclusions into history and schedule a retrigger.
+    ///
+    /// A lone conclusion is injected as-is. Several are merged into a single
+    /// message, optionally asking the model to rank them.
+    async fn flush_branch_results(&mut self) {
+        let pending = std::mem::take(&mut self.pending_branch_results);
+        let count = pending.conclusions.len();
+        let message = match pending.conclusions.as_slice() {
+            [] => return,
+            [conclusion] => format!("[Branch result]: {conclusion}"),
+            conclusions => {
+                let rank = **self.deps.runtime_config.rank_branch_results.load();
+                self.deps
+                    .runtime_config
+                    .prompts
+                    .load()
+                    .render_system_branch_results(conclusions, rank)
+                    .unwrap_or_else(|error| {
+                        tracing::warn!(%error, "failed to render merged branch results");
+                        conclusions
+                            .iter()
+                            .map(|conclusion| format!("[Branch result]: {conclusion}"))
+                            .collect::<Vec<_>>()
+                            .join("\n\n")
+                    })
             }
-        }
+        };
 
-        Ok(())
+        self.state
+            .history
+            .write()
+            .await
+            .push(rig::message::Message::from(message));
+        tracing::info!(channel_id = %self.id, count, "branch results incorporated");
+
+        self.schedule_retrigger(pending.retrigger_metadata).await;
     }
 
     /// Flush the pending retrigger: send a synthetic system message to re-trigger
     /// the channel LLM so it can process background results and respond.
+    ///
+    /// The message goes on the system lane, which is unbounded and read before
+    /// any other, so a completion notification is never dropped or queued
+    /// behind user messages.
     async fn flush_pending_retrigger(&mut self) {
         self.retrigger_deadline = None;
 
@@ -1769,8 +3916,14 @@ impl Channel {
             metadata,
             formatted_author: None,
         };
-        if let Err(error) = self.self_tx.try_send(synthetic) {
-            tracing::warn!(%error, "failed to re-trigger channel after process completion");
+        // The system lane is unbounded, so this only fails once the channel
+        // has closed its lanes to archive. Run the turn here rather than lose
+        // the result it carries.
+        if let Err(mpsc::error::SendError(synthetic)) = self.self_tx.send(synthetic) {
+            tracing::debug!(channel_id = %self.id, "lanes closed, running retrigger inline");
+            if let Err(error) = self.handle_message(synthetic).await {
+                tracing::error!(%error, channel_id = %self.id, "error handling retrigger");
+            }
         }
     }
 
@@ -1936,13 +4089,6 @@ async fn spawn_branch(
         h.clone()
     };
 
-    let tool_server = crate::tools::create_branch_tool_server(
-        state.deps.memory_search.clone(),
-        state.conversation_logger.clone(),
-        state.channel_store.clone(),
-        crate::conversation::ProcessRunLogger::new(state.deps.sqlite_pool.clone()),
-        &state.deps.agent_id,
-    );
     let branch_max_turns = **state.deps.runtime_config.branch_max_turns.load();
 
     let branch = Branch::new(
@@ -1951,18 +4097,31 @@ async fn spawn_branch(
         state.deps.clone(),
         system_prompt,
         history,
-        tool_server,
+        branch_tool_server(state).await,
         branch_max_turns,
-    );
+    )
+    .with_cost_tracker(state.cost_tracker.clone())
+    .with_turn_id(*state.turn_id.read().await);
+    add_sub_branch_tool(state, &branch).await;
+    add_escalate_to_worker_tool(state, &branch).await;
 
     let branch_id = branch.id;
+    let turn_id = branch.turn_id;
     let prompt = prompt.to_owned();
+    state
+        .cancel_tokens
+        .write()
+        .await
+        .insert(ProcessId::Branch(branch_id), branch.cancel_token.clone());
 
+    let turn_span = state.turn_span.read().await.clone();
     let branch_span = tracing::info_span!(
+        parent: &turn_span,
         "branch.run",
         branch_id = %branch_id,
         channel_id = %state.channel_id,
         description = %description,
+        model = tracing::field::Empty,
     );
     let handle = tokio::spawn(
         async move {
@@ -1978,6 +4137,9 @@ async fn spawn_branch(
         branches.insert(branch_id, handle);
     }
 
+    let timeout_secs = state.deps.runtime_config.cortex.load().branch_timeout_secs;
+    spawn_branch_watchdog(state, branch_id, turn_id, timeout_secs);
+
     {
         let mut status = state.status_block.write().await;
         status.add_branch(branch_id, status_label);
@@ -1998,6 +4160,7 @@ async fn spawn_branch(
             channel_id: state.channel_id.clone(),
             description: status_label.to_string(),
             reply_to_message_id: *state.reply_target_message_id.read().await,
+            turn_id,
         })
         .ok();
 
@@ -2006,31 +4169,233 @@ async fn spawn_branch(
     Ok(branch_id)
 }
 
-/// Check whether the channel has capacity for another worker.
-async fn check_worker_limit(state: &ChannelState) -> std::result::Result<(), AgentError> {
-    let max_workers = **state.deps.runtime_config.max_concurrent_workers.load();
-    let workers = state.active_workers.read().await;
-    if workers.len() >= max_workers {
-        return Err(AgentError::WorkerLimitReached {
-            channel_id: state.channel_id.to_string(),
-            max: max_workers,
-        });
+/// A fresh tool server for a branch of this channel, scoped to the sender of
+/// the latest message.
+async fn branch_tool_server(state: &ChannelState) -> rig::tool::server::ToolServerHandle {
+    let sender_id = state
+        .latest_message
+        .read()
+        .await
+        .as_ref()
+        .map(InboundMessage::qualified_sender_id);
+    crate::tools::create_branch_tool_server(
+        state.deps.memory_search.clone(),
+        Some(state.channel_id.clone()),
+        sender_id,
+        state.deps.knowledge.clone(),
+        state.conversation_logger.clone(),
+        state.channel_store.clone(),
+        crate::conversation::ProcessRunLogger::new(state.deps.sqlite_pool.clone()),
+        &state.deps.agent_id,
+        state.web_fetch_cache.clone(),
+        crate::tools::OutputSpill::new(
+            state.deps.sqlite_pool.clone(),
+            state.deps.runtime_config.clone(),
+        ),
+    )
+}
+
+/// Let a branch spawn sub-branches while it's shallower than `max_branch_depth`.
+async fn add_sub_branch_tool(state: &ChannelState, branch: &Branch) {
+    let max_depth = **state.deps.runtime_config.max_branch_depth.load();
+    if branch.depth >= max_depth {
+        return;
+    }
+    if let Err(error) = branch
+        .tool_server
+        .add_tool(crate::tools::SubBranchTool::new(state.clone(), branch))
+        .await
+    {
+        tracing::warn!(%error, branch_id = %branch.id, "failed to add sub_branch tool");
+    }
+}
+
+/// Tier of the sender of the channel's latest message. Admin before the
+/// first message.
+async fn latest_sender_tier(state: &ChannelState) -> AuthTier {
+    let auth = state.deps.runtime_config.auth.load();
+    state
+        .latest_message
+        .read()
+        .await
+        .as_ref()
+        .map_or(AuthTier::Admin, |message| {
+            crate::auth::tiers::sender_tier(&auth, message)
+        })
+}
+
+/// Let a branch ask the channel for a worker, on behalf of the sender of the
+/// latest message.
+async fn add_escalate_to_worker_tool(state: &ChannelState, branch: &Branch) {
+    let sender_tier = latest_sender_tier(state).await;
+    if let Err(error) = branch
+        .tool_server
+        .add_tool(crate::tools::EscalateToWorkerTool::new(
+            state.clone(),
+            branch,
+            sender_tier,
+        ))
+        .await
+    {
+        tracing::warn!(%error, branch_id = %branch.id, "failed to add escalate_to_worker tool");
     }
-    Ok(())
+}
+
+/// Run a sub-branch to completion for a branch's `sub_branch` tool call.
+///
+/// Sub-branches fork the same channel history as their parent, are
+/// cancelled with it, and belong to the turn that spawned it. They aren't
+/// tracked as channel branches: their conclusions go back to the parent,
+/// which reports them with its own.
+pub async fn run_sub_branch(
+    state: &ChannelState,
+    parent_id: BranchId,
+    parent_depth: usize,
+    parent_cancel_token: &CancellationToken,
+    parent_turn_id: Option<TurnId>,
+    history: Vec<rig::message::Message>,
+    description: &str,
+) -> std::result::Result<crate::agent::branch::BranchConclusion, AgentError> {
+    let rc = &state.deps.runtime_config;
+    let system_prompt = rc
+        .prompts
+        .load()
+        .render_branch_prompt(
+            &rc.instance_dir.display().to_string(),
+            &rc.workspace_dir.display().to_string(),
+        )
+        .map_err(|e| AgentError::Other(anyhow::anyhow!("{e}")))?;
+
+    let branch = Branch::new(
+        state.channel_id.clone(),
+        description,
+        state.deps.clone(),
+        system_prompt,
+        history,
+        branch_tool_server(state).await,
+        **rc.branch_max_turns.load(),
+    )
+    .as_sub_branch(parent_id, parent_depth, parent_cancel_token)
+    .with_cost_tracker(state.cost_tracker.clone())
+    .with_turn_id(parent_turn_id);
+    add_sub_branch_tool(state, &branch).await;
+    add_escalate_to_worker_tool(state, &branch).await;
+
+    let branch_id = branch.id;
+    state
+        .deps
+        .event_tx
+        .send(crate::ProcessEvent::BranchStarted {
+            agent_id: state.deps.agent_id.clone(),
+            branch_id,
+            channel_id: state.channel_id.clone(),
+            description: description.to_string(),
+            reply_to_message_id: None,
+            turn_id: parent_turn_id,
+        })
+        .ok();
+    tracing::info!(
+        branch_id = %branch_id,
+        parent_branch_id = %parent_id,
+        depth = branch.depth,
+        "sub-branch spawned"
+    );
+
+    branch
+        .run(description)
+        .await
+        .map_err(|e| AgentError::Other(anyhow::anyhow!("{e}")))
+}
+
+/// Stop a branch that is still running after `timeout_secs`, freeing its slot
+/// against `max_concurrent_branches`. Emits `BranchTimeout`, attributed to
+/// the branch's `turn_id`, so the channel can note it in the status block.
+fn spawn_branch_watchdog(
+    state: &ChannelState,
+    branch_id: BranchId,
+    turn_id: Option<TurnId>,
+    timeout_secs: u64,
+) {
+    let active_branches = state.active_branches.clone();
+    let cancel_tokens = state.cancel_tokens.clone();
+    let event_tx = state.deps.event_tx.clone();
+    let agent_id = state.deps.agent_id.clone();
+    let channel_id = state.channel_id.clone();
+    let event_journal = state.event_journal();
+
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs(timeout_secs)).await;
+
+        let handle = {
+            let mut branches = active_branches.write().await;
+            // Finished or cancelled in the meantime; its BranchResult (if any)
+            // handles cleanup.
+            if branches
+                .get(&branch_id)
+                .is_none_or(tokio::task::JoinHandle::is_finished)
+            {
+                return;
+            }
+            branches.remove(&branch_id)
+        };
+        let Some(handle) = handle else {
+            return;
+        };
+        let cancel_token = cancel_tokens
+            .write()
+            .await
+            .remove(&ProcessId::Branch(branch_id));
+        event_journal
+            .discard(&channel_id, &ProcessId::Branch(branch_id))
+            .await;
+        stop_task(handle, cancel_token);
+
+        tracing::warn!(branch_id = %branch_id, timeout_secs, "branch timed out");
+
+        event_tx
+            .send(ProcessEvent::BranchTimeout {
+                agent_id,
+                branch_id,
+                channel_id,
+                timeout_secs,
+                turn_id,
+            })
+            .ok();
+    });
 }
 
 /// Spawn a worker from a ChannelState. Used by the SpawnWorkerTool.
+/// `preset` names one of the agent's worker presets to apply. `turn_id` is
+/// the channel turn the worker is spawned for, if any; its events carry it.
 pub async fn spawn_worker_from_state(
     state: &ChannelState,
     task: impl Into<String>,
     interactive: bool,
     suggested_skills: &[&str],
+    retain_workspace: bool,
+    preset: Option<&str>,
+    turn_id: Option<TurnId>,
 ) -> std::result::Result<WorkerId, AgentError> {
-    check_worker_limit(state).await?;
     ensure_dispatch_readiness(state, "worker");
     let task = task.into();
 
     let rc = &state.deps.runtime_config;
+    let presets = rc.worker_presets.load();
+    let preset = match preset {
+        Some(name) => Some(presets.get(name).ok_or_else(|| {
+            let mut available: Vec<&str> = presets.keys().map(String::as_str).collect();
+            available.sort_unstable();
+            AgentError::Other(anyhow::anyhow!(
+                "unknown worker preset '{name}'; available presets: {}",
+                if available.is_empty() {
+                    "none".to_string()
+                } else {
+                    available.join(", ")
+                }
+            ))
+        })?),
+        None => None,
+    };
     let prompt_engine = rc.prompts.load();
     let worker_system_prompt = prompt_engine
         .render_worker_prompt(
@@ -2040,7 +4405,7 @@ pub async fn spawn_worker_from_state(
         .map_err(|e| AgentError::Other(anyhow::anyhow!("{e}")))?;
     let skills = rc.skills.load();
     let browser_config = (**rc.browser_config.load()).clone();
-    let brave_search_key = (**rc.brave_search_key.load()).clone();
+    let web_search = rc.web_search.load().configured();
 
     // Append skills listing to worker system prompt. Suggested skills are
     // flagged so the worker knows the channel's intent, but it can read any
@@ -2056,7 +4421,7 @@ pub async fn spawn_worker_from_state(
         }
     };
 
-    let worker = if interactive {
+    let (worker, input_tx) = if interactive {
         let (worker, input_tx) = Worker::new_interactive(
             Some(state.channel_id.clone()),
             &task,
@@ -2064,69 +4429,239 @@ pub async fn spawn_worker_from_state(
             state.deps.clone(),
             browser_config.clone(),
             state.screenshot_dir.clone(),
-            brave_search_key.clone(),
+            web_search.clone(),
             state.logs_dir.clone(),
         );
-        let worker_id = worker.id;
-        state
-            .worker_inputs
-            .write()
-            .await
-            .insert(worker_id, input_tx);
-        worker
+        (
+            worker.with_web_fetch_cache(state.web_fetch_cache.clone()),
+            Some(input_tx),
+        )
     } else {
-        Worker::new(
+        let worker = Worker::new(
             Some(state.channel_id.clone()),
             &task,
             &system_prompt,
             state.deps.clone(),
             browser_config,
             state.screenshot_dir.clone(),
-            brave_search_key,
+            web_search,
             state.logs_dir.clone(),
         )
+        .with_web_fetch_cache(state.web_fetch_cache.clone());
+        (worker, None)
     };
+    let worker = with_worker_workspace(worker, &rc.worker_workspaces_dir, retain_workspace)
+        .with_sender_tier(latest_sender_tier(state).await);
+    let worker = match preset {
+        Some(preset) => worker.with_preset(preset),
+        None => worker,
+    };
+    let plan = state.plan.is_active().then(|| state.plan.clone());
+    let worker = match &plan {
+        Some(plan) => worker.with_plan(plan.clone()),
+        None => worker,
+    }
+    .with_approvals(state.approvals.clone())
+    .with_cost_tracker(state.cost_tracker.clone())
+    .with_turn_id(turn_id);
 
     let worker_id = worker.id;
+    let admission = admit_worker(state, worker_id, turn_id, interactive)?;
+    if let Some(input_tx) = input_tx {
+        state
+            .worker_inputs
+            .write()
+            .await
+            .insert(worker_id, input_tx);
+    }
+    state
+        .cancel_tokens
+        .write()
+        .await
+        .insert(ProcessId::Worker(worker_id), worker.cancel_token.clone());
 
+    let turn_span = state.turn_span.read().await.clone();
     let worker_span = tracing::info_span!(
+        parent: &turn_span,
         "worker.run",
         worker_id = %worker_id,
         channel_id = %state.channel_id,
         task = %task,
+        model = tracing::field::Empty,
     );
+    let started = ProcessEvent::WorkerStarted {
+        agent_id: state.deps.agent_id.clone(),
+        worker_id,
+        channel_id: Some(state.channel_id.clone()),
+        task: task.clone(),
+        worker_type: "builtin".into(),
+        turn_id,
+    };
+
+    {
+        let mut status = state.status_block.write().await;
+        status.add_worker(worker_id, &task, false);
+    }
+
+    // Interactive workers hold the only receiver for their input channel, so
+    // they run once. Fire-and-forget workers retry per the agent's policy,
+    // each attempt on a fresh copy of the worker.
+    let policy = if interactive {
+        RetryPolicy::none()
+    } else {
+        (**rc.worker_retry.load()).clone()
+    };
+    let pool = state.deps.worker_pool.clone();
+    let retry_channel_id = Some(state.channel_id.clone());
+    let mut template = Some(worker);
+    let attempt_plan = plan.clone();
+    let run = async move {
+        let result = pool
+            .run_with_retry(worker_id, retry_channel_id, turn_id, &policy, move |_| {
+                // Steps held back by a failed attempt are recorded again if the
+                // retry gets that far.
+                if let Some(plan) = &attempt_plan {
+                    plan.forget(worker_id);
+                }
+                let worker = template
+                    .as_ref()
+                    .and_then(Worker::next_attempt)
+                    .or_else(|| template.take());
+                async move {
+                    match worker {
+                        Some(worker) => worker.run().await,
+                        None => Err(AgentError::Other(anyhow::anyhow!(
+                            "interactive workers can't be retried"
+                        ))
+                        .into()),
+                    }
+                }
+            })
+            .await;
+        match plan {
+            Some(plan) => result.map(|result| plan.annotate(worker_id, result)),
+            None => result,
+        }
+    };
+
     let handle = spawn_worker_task(
         worker_id,
-        state.deps.event_tx.clone(),
-        state.deps.agent_id.clone(),
+        &state.deps,
         Some(state.channel_id.clone()),
-        worker.run().instrument(worker_span),
+        admission,
+        started,
+        run.instrument(worker_span),
     );
 
     state.worker_handles.write().await.insert(worker_id, handle);
 
+    tracing::info!(worker_id = %worker_id, task = %task, "worker spawned");
+
+    Ok(worker_id)
+}
+
+/// Spawn a worker that runs the tool calls of an approved plan.
+async fn spawn_plan_worker(
+    state: &ChannelState,
+    calls: Vec<PlannedCall>,
+) -> std::result::Result<WorkerId, AgentError> {
+    ensure_dispatch_readiness(state, "worker");
+    let rc = &state.deps.runtime_config;
+    let task = format!("run the approved plan ({} steps)", calls.len());
+
+    let worker = Worker::new(
+        Some(state.channel_id.clone()),
+        &task,
+        String::new(),
+        state.deps.clone(),
+        (**rc.browser_config.load()).clone(),
+        state.screenshot_dir.clone(),
+        rc.web_search.load().configured(),
+        state.logs_dir.clone(),
+    )
+    .with_web_fetch_cache(state.web_fetch_cache.clone());
+    let turn_id = *state.turn_id.read().await;
+    let worker = with_worker_workspace(worker, &rc.worker_workspaces_dir, false)
+        .with_sender_tier(latest_sender_tier(state).await)
+        .with_cost_tracker(state.cost_tracker.clone())
+        .with_turn_id(turn_id);
+
+    let worker_id = worker.id;
+    let admission = admit_worker(state, worker_id, turn_id, false)?;
+    state
+        .cancel_tokens
+        .write()
+        .await
+        .insert(ProcessId::Worker(worker_id), worker.cancel_token.clone());
+
+    let turn_span = state.turn_span.read().await.clone();
+    let worker_span = tracing::info_span!(
+        parent: &turn_span,
+        "worker.run",
+        worker_id = %worker_id,
+        channel_id = %state.channel_id,
+        task = %task,
+    );
+    let started = ProcessEvent::WorkerStarted {
+        agent_id: state.deps.agent_id.clone(),
+        worker_id,
+        channel_id: Some(state.channel_id.clone()),
+        task: task.clone(),
+        worker_type: "builtin".into(),
+        turn_id,
+    };
+
     {
         let mut status = state.status_block.write().await;
         status.add_worker(worker_id, &task, false);
     }
 
-    state
-        .deps
-        .event_tx
-        .send(crate::ProcessEvent::WorkerStarted {
-            agent_id: state.deps.agent_id.clone(),
-            worker_id,
-            channel_id: Some(state.channel_id.clone()),
-            task: task.clone(),
-            worker_type: "builtin".into(),
-        })
-        .ok();
+    let handle = spawn_worker_task(
+        worker_id,
+        &state.deps,
+        Some(state.channel_id.clone()),
+        admission,
+        started,
+        worker.run_plan(calls).instrument(worker_span),
+    );
 
-    tracing::info!(worker_id = %worker_id, task = %task, "worker spawned");
+    state.worker_handles.write().await.insert(worker_id, handle);
+
+    tracing::info!(worker_id = %worker_id, task = %task, "plan worker spawned");
 
     Ok(worker_id)
 }
 
+/// Give a worker its own working directory and tell it about it in the
+/// system prompt. Falls back to the agent workspace if the directory can't be
+/// created.
+fn with_worker_workspace(mut worker: Worker, root: &std::path::Path, retain: bool) -> Worker {
+    let workspace = match WorkerWorkspace::create(root, worker.id, retain) {
+        Ok(workspace) => workspace,
+        Err(error) => {
+            tracing::warn!(
+                %error,
+                worker_id = %worker.id,
+                "failed to create worker workspace, using the agent workspace"
+            );
+            return worker;
+        }
+    };
+
+    let prompt_engine = worker.deps.runtime_config.prompts.load();
+    match prompt_engine.render_worker_workspace(&workspace.path().display().to_string(), retain) {
+        Ok(note) => {
+            worker.system_prompt.push_str("\n\n");
+            worker.system_prompt.push_str(&note);
+        }
+        Err(error) => {
+            tracing::warn!(%error, "failed to render worker workspace note");
+        }
+    }
+
+    worker.with_workspace(workspace)
+}
+
 /// Spawn an OpenCode-backed worker for coding tasks.
 ///
 /// Instead of a Rig agent loop, this spawns an OpenCode subprocess that has its
@@ -2138,7 +4673,6 @@ pub async fn spawn_opencode_worker_from_state(
     directory: &str,
     interactive: bool,
 ) -> std::result::Result<crate::WorkerId, AgentError> {
-    check_worker_limit(state).await?;
     ensure_dispatch_readiness(state, "opencode_worker");
     let task = task.into();
     let directory = std::path::PathBuf::from(directory);
@@ -2153,8 +4687,9 @@ pub async fn spawn_opencode_worker_from_state(
     }
 
     let server_pool = rc.opencode_server_pool.clone();
+    let turn_id = *state.turn_id.read().await;
 
-    let worker = if interactive {
+    let (worker, input_tx) = if interactive {
         let (worker, input_tx) = crate::opencode::OpenCodeWorker::new_interactive(
             Some(state.channel_id.clone()),
             state.deps.agent_id.clone(),
@@ -2163,15 +4698,9 @@ pub async fn spawn_opencode_worker_from_state(
             server_pool,
             state.deps.event_tx.clone(),
         );
-        let worker_id = worker.id;
-        state
-            .worker_inputs
-            .write()
-            .await
-            .insert(worker_id, input_tx);
-        worker
+        (worker.with_turn_id(turn_id), Some(input_tx))
     } else {
-        crate::opencode::OpenCodeWorker::new(
+        let worker = crate::opencode::OpenCodeWorker::new(
             Some(state.channel_id.clone()),
             state.deps.agent_id.clone(),
             &task,
@@ -2179,71 +4708,123 @@ pub async fn spawn_opencode_worker_from_state(
             server_pool,
             state.deps.event_tx.clone(),
         )
+        .with_turn_id(turn_id);
+        (worker, None)
     };
 
     let worker_id = worker.id;
+    let admission = admit_worker(state, worker_id, turn_id, interactive)?;
+    if let Some(input_tx) = input_tx {
+        state
+            .worker_inputs
+            .write()
+            .await
+            .insert(worker_id, input_tx);
+    }
 
+    let turn_span = state.turn_span.read().await.clone();
     let worker_span = tracing::info_span!(
+        parent: &turn_span,
         "worker.run",
         worker_id = %worker_id,
         channel_id = %state.channel_id,
         task = %task,
         worker_type = "opencode",
     );
+    let opencode_task = format!("[opencode] {task}");
+    {
+        let mut status = state.status_block.write().await;
+        status.add_worker(worker_id, &opencode_task, false);
+    }
+
+    let started = ProcessEvent::WorkerStarted {
+        agent_id: state.deps.agent_id.clone(),
+        worker_id,
+        channel_id: Some(state.channel_id.clone()),
+        task: opencode_task,
+        worker_type: "opencode".into(),
+        turn_id,
+    };
     let handle = spawn_worker_task(
         worker_id,
-        state.deps.event_tx.clone(),
-        state.deps.agent_id.clone(),
+        &state.deps,
         Some(state.channel_id.clone()),
+        admission,
+        started,
         async move {
             let result = worker.run().await?;
-            Ok::<String, anyhow::Error>(result.result_text)
+            Ok::<WorkerResult, anyhow::Error>(
+                WorkerResult::success(result.result_text).mark_delivered(result.delivered),
+            )
         }
         .instrument(worker_span),
     );
 
     state.worker_handles.write().await.insert(worker_id, handle);
 
-    let opencode_task = format!("[opencode] {task}");
-    {
-        let mut status = state.status_block.write().await;
-        status.add_worker(worker_id, &opencode_task, false);
-    }
-
-    state
-        .deps
-        .event_tx
-        .send(crate::ProcessEvent::WorkerStarted {
-            agent_id: state.deps.agent_id.clone(),
-            worker_id,
-            channel_id: Some(state.channel_id.clone()),
-            task: opencode_task,
-            worker_type: "opencode".into(),
-        })
-        .ok();
-
     tracing::info!(worker_id = %worker_id, task = %task, "OpenCode worker spawned");
 
     Ok(worker_id)
 }
 
+/// Ask the agent's worker pool for a slot. Interactive workers are queued
+/// ahead of fire-and-forget ones.
+fn admit_worker(
+    state: &ChannelState,
+    worker_id: WorkerId,
+    turn_id: Option<TurnId>,
+    interactive: bool,
+) -> std::result::Result<Admission, AgentError> {
+    let priority = if interactive {
+        WorkerPriority::High
+    } else {
+        WorkerPriority::Normal
+    };
+    state
+        .deps
+        .worker_pool
+        .admit(worker_id, state.channel_id.clone(), turn_id, priority)
+}
+
 /// Spawn a future as a tokio task that sends a `WorkerComplete` event on completion.
 ///
-/// Handles both success and error cases, logging failures and sending the
-/// appropriate event. Used by both builtin workers and OpenCode workers.
-/// Returns the JoinHandle so the caller can store it for cancellation.
+/// The task first waits for its worker pool slot, then sends `started` and
+/// runs the future; the slot is held until the future finishes. Handles both
+/// success and error cases, logging failures and sending the appropriate
+/// event. Used by both builtin workers and OpenCode workers. Returns the
+/// JoinHandle so the caller can store it for cancellation.
 fn spawn_worker_task<F, E>(
     worker_id: WorkerId,
-    event_tx: broadcast::Sender<ProcessEvent>,
-    agent_id: crate::AgentId,
+    deps: &AgentDeps,
     channel_id: Option<ChannelId>,
+    admission: Admission,
+    started: ProcessEvent,
     future: F,
 ) -> tokio::task::JoinHandle<()>
 where
-    F: std::future::Future<Output = std::result::Result<String, E>> + Send + 'static,
+    F: std::future::Future<Output = std::result::Result<WorkerResult, E>> + Send + 'static,
     E: std::fmt::Display + Send + 'static,
 {
+    let event_tx = deps.event_tx.clone();
+    let agent_id = deps.agent_id.clone();
+    let event_journal = EventJournal::new(deps.sqlite_pool.clone());
+    let turn_id = match &started {
+        ProcessEvent::WorkerStarted { turn_id, .. } => *turn_id,
+        _ => None,
+    };
     tokio::spawn(async move {
+        let _permit = match admission {
+            Admission::Running(permit) => permit,
+            Admission::Queued { permit, .. } => match permit.await {
+                Ok(permit) => permit,
+                Err(_) => {
+                    tracing::warn!(worker_id = %worker_id, "worker dropped from the pool queue");
+                    return;
+                }
+            },
+        };
+        let _ = event_tx.send(started);
+
         #[cfg(feature = "metrics")]
         let worker_start = std::time::Instant::now();
 
@@ -2253,11 +4834,11 @@ where
             .with_label_values(&[&*agent_id])
             .inc();
 
-        let (result_text, notify, success) = match future.await {
-            Ok(text) => (text, true, true),
+        let result = match future.await {
+            Ok(result) => result,
             Err(error) => {
                 tracing::error!(worker_id = %worker_id, %error, "worker failed");
-                (format!("Worker failed: {error}"), true, false)
+                WorkerResult::failed(error.to_string())
             }
         };
         #[cfg(feature = "metrics")]
@@ -2273,17 +4854,45 @@ where
                 .observe(worker_start.elapsed().as_secs_f64());
         }
 
-        let _ = event_tx.send(ProcessEvent::WorkerComplete {
+        let complete = ProcessEvent::WorkerComplete {
             agent_id,
             worker_id,
             channel_id,
-            result: result_text,
-            notify,
-            success,
-        });
+            notify: !result.delivered,
+            result,
+            turn_id,
+        };
+        event_journal.record(&complete).await;
+        let _ = event_tx.send(complete);
     })
 }
 
+/// Render a finished worker's result as the history message the channel sees.
+fn format_worker_result(worker_id: WorkerId, result: &WorkerResult) -> String {
+    let mut message = match result.status {
+        WorkerResultStatus::Success => format!("[Worker {worker_id} completed]: {}", result.output),
+        WorkerResultStatus::Partial => format!(
+            "[Worker {worker_id} stopped before finishing, partial result]: {}",
+            result.output
+        ),
+        WorkerResultStatus::Failed => format!("[Worker {worker_id} failed]: {}", result.output),
+    };
+    if !result.artifacts.is_empty() {
+        message.push_str("\n\nArtifacts:");
+        for artifact in &result.artifacts {
+            match artifact {
+                WorkerArtifact::File {
+                    path,
+                    media_id: Some(media_id),
+                } => message.push_str(&format!("\n- file: {path} (media_id: {media_id})")),
+                WorkerArtifact::File { path, .. } => message.push_str(&format!("\n- file: {path}")),
+                WorkerArtifact::Url { url } => message.push_str(&format!("\n- url: {url}")),
+            }
+        }
+    }
+    message
+}
+
 /// Some models emit tool call syntax as plain text instead of making actual tool calls.
 /// When the text starts with a tool-like prefix (e.g. `[reply]`, `(reply)`), try to
 /// extract the reply content so we can send it cleanly instead of showing raw JSON.
@@ -2339,6 +4948,38 @@ fn extract_reply_from_tool_syntax(text: &str) -> Option<String> {
     None
 }
 
+/// A message that answers something the channel is holding rather than
+/// starting a turn.
+#[derive(Debug, PartialEq)]
+enum HeldReply {
+    /// `!approve_plan`: run the tool calls plan mode held back.
+    ApprovePlan,
+    /// `approve` or `deny` for a worker's pending tool call.
+    Approval(ApprovalReply),
+}
+
+/// Recognise a [`HeldReply`] in a message from outside. `approve` and `deny`
+/// only count while `approvals_pending`; otherwise they're ordinary words.
+fn parse_held_reply(message: &InboundMessage, approvals_pending: bool) -> Option<HeldReply> {
+    if matches!(message.source.as_str(), "system" | "internal") {
+        return None;
+    }
+    let text = match &message.content {
+        crate::MessageContent::Text(text) => text.as_str(),
+        crate::MessageContent::Media {
+            text: Some(text), ..
+        } => text.as_str(),
+        _ => return None,
+    };
+    if crate::agent::plan::parse_command(text) == Some(PlanCommand::Approve) {
+        return Some(HeldReply::ApprovePlan);
+    }
+    if !approvals_pending {
+        return None;
+    }
+    crate::agent::approval::parse_reply(text).map(HeldReply::Approval)
+}
+
 /// Format a user message with sender attribution from message metadata.
 ///
 /// In multi-user channels, this lets the LLM distinguish who said what.
@@ -2428,20 +5069,121 @@ fn event_is_for_channel(event: &ProcessEvent, channel_id: &ChannelId) -> bool {
             channel_id: event_channel,
             ..
         } => event_channel == channel_id,
+        ProcessEvent::BranchTimeout {
+            channel_id: event_channel,
+            ..
+        } => event_channel == channel_id,
         ProcessEvent::WorkerComplete {
             channel_id: event_channel,
             ..
         } => event_channel.as_ref() == Some(channel_id),
+        ProcessEvent::WorkerQueued {
+            channel_id: event_channel,
+            ..
+        } => event_channel.as_ref() == Some(channel_id),
+        ProcessEvent::WorkerRetrying {
+            channel_id: event_channel,
+            ..
+        } => event_channel.as_ref() == Some(channel_id),
         ProcessEvent::WorkerStatus {
             channel_id: event_channel,
             ..
         } => event_channel.as_ref() == Some(channel_id),
+        ProcessEvent::WorkerProgress {
+            channel_id: event_channel,
+            ..
+        } => event_channel.as_ref() == Some(channel_id),
+        ProcessEvent::WorkerResponse {
+            channel_id: event_channel,
+            ..
+        } => event_channel.as_ref() == Some(channel_id),
+        ProcessEvent::WorkerAttachment {
+            channel_id: event_channel,
+            ..
+        } => event_channel == channel_id,
+        ProcessEvent::ApprovalRequested {
+            channel_id: event_channel,
+            ..
+        } => event_channel == channel_id,
+        ProcessEvent::ModelFallback {
+            channel_id: event_channel,
+            ..
+        } => event_channel.as_ref() == Some(channel_id),
+        ProcessEvent::ChannelTitled {
+            channel_id: event_channel,
+            ..
+        } => event_channel == channel_id,
         // Status block updates, tool events, etc. — match on agent_id which
         // is already filtered by the event bus subscription. Let them through.
         _ => true,
     }
 }
 
+/// Ask the cheap channel model (the compactor model when none is routed)
+/// for a short title for the conversation and announce it.
+async fn generate_channel_title(
+    deps: AgentDeps,
+    prompt_engine: crate::prompts::PromptEngine,
+    channel_id: ChannelId,
+    transcript: String,
+) {
+    let prompt = match prompt_engine.render_system_channel_title(&transcript) {
+        Ok(prompt) => prompt,
+        Err(error) => {
+            tracing::warn!(%error, "failed to render channel title prompt");
+            return;
+        }
+    };
+
+    let routing = deps.runtime_config.routing.load();
+    let model_name = if routing.channel_cheap.is_empty() {
+        routing.resolve(ProcessType::Compactor, None).to_string()
+    } else {
+        routing.channel_cheap.clone()
+    };
+    let model = SpacebotModel::make(&deps.llm_manager, &model_name)
+        .with_context(&*deps.agent_id, "channel_title")
+        .with_routing((**routing).clone());
+    let agent = AgentBuilder::new(model).build();
+
+    let title = match agent.prompt(prompt).await {
+        Ok(response) => clean_title(&response),
+        Err(error) => {
+            tracing::warn!(%error, %channel_id, "channel title generation failed");
+            return;
+        }
+    };
+    let Some(title) = title else {
+        tracing::debug!(%channel_id, "empty channel title returned");
+        return;
+    };
+
+    deps.event_tx
+        .send(ProcessEvent::ChannelTitled {
+            agent_id: deps.agent_id.clone(),
+            channel_id,
+            title,
+        })
+        .ok();
+}
+
+/// The first line of a model-written title, without wrapping quotes or
+/// trailing punctuation, cut to `TITLE_MAX_CHARS`.
+fn clean_title(response: &str) -> Option<String> {
+    let line = response.trim().lines().next()?;
+    let line = line.trim_start_matches(['#', ' ']);
+    let line = line.strip_prefix("Title:").unwrap_or(line);
+    let line = line
+        .trim()
+        .trim_matches(['"', '\'', '*', '`'])
+        .trim_end_matches(['.', '!', ':'])
+        .trim();
+    if line.is_empty() {
+        return None;
+    }
+    Some(line.chars().take(TITLE_MAX_CHARS).collect())
+}
+
 /// Image MIME types we support for vision.
 const IMAGE_MIME_PREFIXES: &[&str] = &["image/jpeg", "image/png", "image/gif", "image/webp"];
 
@@ -2456,16 +5198,29 @@ const TEXT_MIME_PREFIXES: &[&str] = &[
     "application/yaml",
 ];
 
+/// Largest attachment downloaded for the LLM, unless the media store takes
+/// bigger files.
+const MAX_ATTACHMENT_BYTES: u64 = 50 * 1024 * 1024;
+
 /// Download attachments and convert them to LLM-ready UserContent parts.
 ///
 /// Images become `UserContent::Image` (base64). Text files get inlined.
-/// Other file types get a metadata-only description.
+/// Other file types get a metadata-only description. Each attachment is
+/// downloaded once, with a size cap, and the same bytes are kept in the
+/// media store; a note lists the stored ids.
 async fn download_attachments(
     deps: &AgentDeps,
+    channel_id: &str,
     attachments: &[crate::Attachment],
 ) -> Vec<UserContent> {
     let http = deps.llm_manager.http_client();
     let mut parts = Vec::new();
+    let mut stored = Vec::new();
+    let channel_sees_images = {
+        let routing = deps.runtime_config.routing.load();
+        crate::llm::routing::supports_image_input(routing.resolve(ProcessType::Channel, None))
+    };
+    let max_bytes = deps.media.max_file_bytes().max(MAX_ATTACHMENT_BYTES);
 
     for attachment in attachments {
         let is_image = IMAGE_MIME_PREFIXES
@@ -2475,98 +5230,210 @@ async fn download_attachments(
             .iter()
             .any(|p| attachment.mime_type.starts_with(p));
 
+        let data = match fetch_attachment(http, attachment, max_bytes).await {
+            Ok(data) => data,
+            Err(error) => {
+                tracing::warn!(%error, filename = %attachment.filename, "failed to download attachment");
+                let is_audio = attachment.mime_type.starts_with("audio/");
+                parts.push(if is_image || is_text || is_audio {
+                    UserContent::text(format!(
+                        "[Failed to download attachment: {}]",
+                        attachment.filename
+                    ))
+                } else {
+                    describe_attachment(attachment, attachment.size_bytes)
+                });
+                continue;
+            }
+        };
+
         let content = if is_image {
-            download_image_attachment(http, attachment).await
+            let image = image_attachment_content(attachment, &data);
+            if channel_sees_images {
+                image
+            } else {
+                caption_image_attachment(deps, attachment, image).await
+            }
         } else if is_text {
-            download_text_attachment(http, attachment).await
+            text_attachment_content(attachment, &data)
         } else if attachment.mime_type.starts_with("audio/") {
-            transcribe_audio_attachment(deps, http, attachment).await
+            transcribe_audio_attachment(deps, http, attachment, &data).await
         } else {
-            let size_str = attachment
-                .size_bytes
-                .map(|s| format!("{:.1} KB", s as f64 / 1024.0))
-                .unwrap_or_else(|| "unknown size".into());
-            UserContent::text(format!(
-                "[Attachment: {} ({}, {})]",
-                attachment.filename, attachment.mime_type, size_str
-            ))
+            describe_attachment(attachment, Some(data.len() as u64))
         };
-
         parts.push(content);
+
+        if let Some(media_id) = store_attachment(deps, channel_id, attachment, data).await {
+            stored.push(format!("{} (media_id: {media_id})", attachment.filename));
+        }
+    }
+
+    if !stored.is_empty() {
+        parts.push(UserContent::text(format!(
+            "[Stored attachments: {}]",
+            stored.join(", ")
+        )));
     }
 
     parts
 }
 
-/// Download an image attachment and encode it as base64 for the LLM.
-async fn download_image_attachment(
+/// Metadata-only description of an attachment the LLM can't read.
+fn describe_attachment(attachment: &crate::Attachment, size_bytes: Option<u64>) -> UserContent {
+    let size_str = size_bytes
+        .map(|s| format!("{:.1} KB", s as f64 / 1024.0))
+        .unwrap_or_else(|| "unknown size".into());
+    UserContent::text(format!(
+        "[Attachment: {} ({}, {})]",
+        attachment.filename, attachment.mime_type, size_str
+    ))
+}
+
+/// Fetch an attachment, giving up as soon as it's known to be larger than
+/// `max_bytes` rather than buffering it whole.
+async fn fetch_attachment(
     http: &reqwest::Client,
     attachment: &crate::Attachment,
-) -> UserContent {
-    let response = match http.get(&attachment.url).send().await {
-        Ok(r) => r,
-        Err(error) => {
-            tracing::warn!(%error, filename = %attachment.filename, "failed to download image");
-            return UserContent::text(format!(
-                "[Failed to download image: {}]",
-                attachment.filename
-            ));
+    max_bytes: u64,
+) -> anyhow::Result<Vec<u8>> {
+    let too_large = || anyhow::anyhow!("attachment is larger than {max_bytes} bytes");
+    if attachment.size_bytes.is_some_and(|size| size > max_bytes) {
+        return Err(too_large());
+    }
+
+    let mut response = http
+        .get(&attachment.url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())?;
+    if response
+        .content_length()
+        .is_some_and(|length| length > max_bytes)
+    {
+        return Err(too_large());
+    }
+
+    let mut data = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if (data.len() + chunk.len()) as u64 > max_bytes {
+            return Err(too_large());
         }
-    };
+        data.extend_from_slice(&chunk);
+    }
+    Ok(data)
+}
 
-    let bytes = match response.bytes().await {
-        Ok(b) => b,
+/// Keep a copy of an attachment in the media store, so later turns and
+/// tools can use it by id after the platform link expires. Returns the id.
+async fn store_attachment(
+    deps: &AgentDeps,
+    channel_id: &str,
+    attachment: &crate::Attachment,
+    data: Vec<u8>,
+) -> Option<String> {
+    if data.len() as u64 > deps.media.max_file_bytes() {
+        return None;
+    }
+    match deps
+        .media
+        .put(
+            crate::media::MediaSource::Inbound,
+            Some(channel_id),
+            &attachment.filename,
+            &attachment.mime_type,
+            data,
+        )
+        .await
+    {
+        Ok(media) => Some(media.id),
         Err(error) => {
-            tracing::warn!(%error, filename = %attachment.filename, "failed to read image bytes");
-            return UserContent::text(format!(
-                "[Failed to download image: {}]",
-                attachment.filename
-            ));
+            tracing::warn!(%error, filename = %attachment.filename, "failed to store attachment");
+            None
         }
-    };
+    }
+}
 
+/// Encode an image attachment as base64 for the LLM.
+fn image_attachment_content(attachment: &crate::Attachment, data: &[u8]) -> UserContent {
     use base64::Engine as _;
-    let base64_data = base64::engine::general_purpose::STANDARD.encode(&bytes);
+    let base64_data = base64::engine::general_purpose::STANDARD.encode(data);
     let media_type = ImageMediaType::from_mime_type(&attachment.mime_type);
 
     tracing::info!(
         filename = %attachment.filename,
         mime = %attachment.mime_type,
-        size = bytes.len(),
+        size = data.len(),
         "downloaded image attachment"
     );
 
     UserContent::image_base64(base64_data, media_type, None)
 }
 
-/// Download an audio attachment and transcribe it with the configured voice model.
-async fn transcribe_audio_attachment(
+/// Describe an image with the `routing.vision` model, for channel models that
+/// can't take image input. Returns the caption as `[Image: ...]` text.
+async fn caption_image_attachment(
     deps: &AgentDeps,
-    http: &reqwest::Client,
     attachment: &crate::Attachment,
+    image: UserContent,
 ) -> UserContent {
-    let response = match http.get(&attachment.url).send().await {
-        Ok(r) => r,
-        Err(error) => {
-            tracing::warn!(%error, filename = %attachment.filename, "failed to download audio");
-            return UserContent::text(format!(
-                "[Failed to download audio: {}]",
+    // Download failures are already rendered as text.
+    if !matches!(image, UserContent::Image(_)) {
+        return image;
+    }
+
+    let routing = deps.runtime_config.routing.load();
+    let vision_model = routing.vision.trim();
+    if vision_model.is_empty() {
+        return UserContent::text(format!(
+            "[Image: {} (the current model can't view images and no vision model is configured in routing.vision)]",
+            attachment.filename
+        ));
+    }
+
+    let model = SpacebotModel::make(&deps.llm_manager, vision_model)
+        .with_context(&*deps.agent_id, "vision")
+        .with_routing((**routing).clone());
+    let agent = AgentBuilder::new(model).build();
+    let mut content = OneOrMany::one(UserContent::text(
+        "Describe this image for someone who can't see it. Transcribe any visible text \
+         verbatim. Be concise and factual. Return only the description.",
+    ));
+    content.push(image);
+    let prompt = rig::message::Message::User { content };
+
+    match agent.prompt(prompt).await {
+        Ok(caption) if !caption.trim().is_empty() => {
+            tracing::info!(
+                filename = %attachment.filename,
+                model = %vision_model,
+                "captioned image attachment"
+            );
+            UserContent::text(format!("[Image: {}]", caption.trim()))
+        }
+        Ok(_) => {
+            tracing::warn!(model = %vision_model, "empty image caption returned");
+            UserContent::text(format!(
+                "[Image captioning failed for {}: empty response]",
                 attachment.filename
-            ));
+            ))
         }
-    };
-
-    let bytes = match response.bytes().await {
-        Ok(b) => b,
         Err(error) => {
-            tracing::warn!(%error, filename = %attachment.filename, "failed to read audio bytes");
-            return UserContent::text(format!(
-                "[Failed to download audio: {}]",
+            tracing::warn!(%error, model = %vision_model, "image captioning failed");
+            UserContent::text(format!(
+                "[Image captioning failed for {}]",
                 attachment.filename
-            ));
+            ))
         }
-    };
+    }
+}
 
+/// Transcribe an audio attachment with the configured voice model.
+async fn transcribe_audio_attachment(
+    deps: &AgentDeps,
+    http: &reqwest::Client,
+    attachment: &crate::Attachment,
+    bytes: &[u8],
+) -> UserContent {
     tracing::info!(
         filename = %attachment.filename,
         mime = %attachment.mime_type,
@@ -2614,7 +5481,7 @@ async fn transcribe_audio_attachment(
 
     let format = audio_format_for_attachment(attachment);
     use base64::Engine as _;
-    let base64_audio = base64::engine::general_purpose::STANDARD.encode(&bytes);
+    let base64_audio = base64::engine::general_purpose::STANDARD.encode(bytes);
 
     let endpoint = format!(
         "{}/v1/chat/completions",
@@ -2702,6 +5569,19 @@ async fn transcribe_audio_attachment(
     ))
 }
 
+/// Kind of an attachment that was added to a turn, for model routing.
+fn attachment_kind(content: &UserContent) -> String {
+    match content {
+        UserContent::Image(_) => "image",
+        UserContent::Audio(_) => "audio",
+        UserContent::Video(_) => "video",
+        UserContent::Document(_) => "document",
+        // Text files and voice transcripts are inlined as text.
+        _ => "text",
+    }
+    .to_string()
+}
+
 fn audio_format_for_attachment(attachment: &crate::Attachment) -> &'static str {
     let mime = attachment.mime_type.to_lowercase();
     if mime.contains("mpeg") || mime.contains("mp3") {
@@ -2764,39 +5644,17 @@ fn extract_transcript_text(body: &serde_json::Value) -> String {
         .join("\n")
 }
 
-/// Download a text attachment and inline its content for the LLM.
-async fn download_text_attachment(
-    http: &reqwest::Client,
-    attachment: &crate::Attachment,
-) -> UserContent {
-    let response = match http.get(&attachment.url).send().await {
-        Ok(r) => r,
-        Err(error) => {
-            tracing::warn!(%error, filename = %attachment.filename, "failed to download text file");
-            return UserContent::text(format!(
-                "[Failed to download file: {}]",
-                attachment.filename
-            ));
-        }
-    };
-
-    let content = match response.text().await {
-        Ok(c) => c,
-        Err(error) => {
-            tracing::warn!(%error, filename = %attachment.filename, "failed to read text file");
-            return UserContent::text(format!("[Failed to read file: {}]", attachment.filename));
-        }
-    };
-
-    // Truncate very large files to avoid blowing up context
+/// Inline a text attachment, truncated to keep the context bounded.
+fn text_attachment_content(attachment: &crate::Attachment, data: &[u8]) -> UserContent {
+    let content = String::from_utf8_lossy(data);
     let truncated = if content.len() > 50_000 {
         format!(
             "{}...\n[truncated — {} bytes total]",
-            &content[..50_000],
+            &content[..content.floor_char_boundary(50_000)],
             content.len()
         )
     } else {
-        content
+        content.into_owned()
     };
 
     tracing::info!(
@@ -2847,9 +5705,34 @@ fn apply_history_after_turn(
     }
 }
 
+/// Write the replies a turn sent to history, after the prompt that asked
+/// for them, so the next turn sees exactly what the user saw.
+///
+/// Only needed when the turn was rolled back, which is how a turn that ends
+/// with the reply tool finishes. A turn whose history was kept already holds
+/// its reply tool calls.
+fn record_sent_replies(
+    guard: &mut Vec<rig::message::Message>,
+    history_len_before: usize,
+    prompt_message: Option<rig::message::Message>,
+    sent_replies: Vec<crate::tools::SentReply>,
+) {
+    if sent_replies.is_empty() || guard.len() != history_len_before {
+        return;
+    }
+    if let Some(prompt_message @ rig::message::Message::User { .. }) = prompt_message {
+        guard.push(prompt_message);
+    }
+    guard.extend(
+        sent_replies
+            .into_iter()
+            .map(crate::tools::SentReply::into_message),
+    );
+}
+
 #[cfg(test)]
 mod tests {
-    use super::apply_history_after_turn;
+    use super::{apply_history_after_turn, record_sent_replies};
     use rig::completion::{CompletionError, PromptError};
     use rig::message::Message;
     use rig::tool::ToolSetError;
@@ -3086,21 +5969,13 @@ mod tests {
     #[test]
     fn format_user_message_handles_empty_text() {
         use super::format_user_message;
-        use crate::{Arc, InboundMessage};
-        use chrono::Utc;
-        use std::collections::HashMap;
+        use crate::InboundMessage;
+        use crate::testing::inbound_message;
 
         // Test empty text with user message
         let message = InboundMessage {
-            id: "test".to_string(),
-            agent_id: Some(Arc::from("test_agent")),
-            sender_id: "user123".to_string(),
-            conversation_id: "conv".to_string(),
-            content: crate::MessageContent::Text("".to_string()),
-            source: "discord".to_string(),
-            metadata: HashMap::new(),
             formatted_author: Some("TestUser".to_string()),
-            timestamp: Utc::now(),
+            ..inbound_message("discord", "user123", "")
         };
 
         let formatted = format_user_message("", &message);
@@ -3121,17 +5996,7 @@ mod tests {
         );
 
         // Test empty system message
-        let system_message = InboundMessage {
-            id: "test".to_string(),
-            agent_id: Some(Arc::from("test_agent")),
-            sender_id: "system".to_string(),
-            conversation_id: "conv".to_string(),
-            content: crate::MessageContent::Text("".to_string()),
-            source: "system".to_string(),
-            metadata: HashMap::new(),
-            formatted_author: None,
-            timestamp: Utc::now(),
-        };
+        let system_message = inbound_message("system", "system", "");
 
         let formatted_sys = format_user_message("", &system_message);
         assert_eq!(
@@ -3150,4 +6015,145 @@ mod tests {
             "normal messages should not use placeholder"
         );
     }
+
+    fn inbound(source: &str, metadata: &[(&str, &str)]) -> crate::InboundMessage {
+        crate::InboundMessage {
+            metadata: metadata
+                .iter()
+                .map(|(key, value)| (key.to_string(), serde_json::Value::from(*value)))
+                .collect(),
+            ..crate::testing::inbound_message(source, "user", "hi")
+        }
+    }
+
+    #[test]
+    fn conversation_location_reads_platform_metadata() {
+        let discord = inbound(
+            "discord",
+            &[
+                ("discord_guild_name", "Spacebot"),
+                ("discord_channel_name", "general"),
+            ],
+        );
+        assert_eq!(
+            super::conversation_location(&discord),
+            (Some("Spacebot"), Some("general"))
+        );
+
+        let slack = inbound("slack", &[("slack_channel_name", "eng")]);
+        assert_eq!(super::conversation_location(&slack), (None, Some("eng")));
+
+        // Keys from another platform are ignored.
+        let webhook = inbound("webhook", &[("discord_channel_name", "general")]);
+        assert_eq!(super::conversation_location(&webhook), (None, None));
+    }
+
+    #[test]
+    fn seen_messages_drops_repeats_and_evicts_least_recent() {
+        let mut seen = super::SeenMessages::default();
+        assert!(!seen.check_and_insert("a", 2));
+        assert!(!seen.check_and_insert("b", 2));
+        assert!(seen.check_and_insert("a", 2));
+
+        // "b" is now the least recently seen and gets evicted.
+        assert!(!seen.check_and_insert("c", 2));
+        assert!(seen.check_and_insert("a", 2));
+        assert!(!seen.check_and_insert("b", 2));
+    }
+
+    #[test]
+    fn seen_messages_window_zero_disables_dedup() {
+        let mut seen = super::SeenMessages::default();
+        assert!(!seen.check_and_insert("a", 0));
+        assert!(!seen.check_and_insert("a", 0));
+    }
+
+    /// A turn ended by the reply tool keeps its prompt and what was sent.
+    #[test]
+    fn reply_tool_turn_records_prompt_and_sent_replies() {
+        let initial = make_history(&["hello", "hi there"]);
+        let mut guard = initial.clone();
+        let mut history = initial.clone();
+        history.push(user_msg("what's the weather?"));
+        history.push(assistant_msg("[reply tool call]"));
+        let len_before = initial.len();
+        let prompt_message = history.get(len_before).cloned();
+
+        let err = Err(PromptError::PromptCancelled {
+            chat_history: Box::new(history.clone()),
+            reason: "reply delivered".to_string(),
+        });
+        apply_history_after_turn(&err, &mut guard, history, len_before, "test");
+        record_sent_replies(
+            &mut guard,
+            len_before,
+            prompt_message,
+            vec![crate::tools::SentReply {
+                message_id: "msg-1".into(),
+                content: "Sunny, 24°C".into(),
+            }],
+        );
+
+        let mut expected = initial.clone();
+        expected.push(user_msg("what's the weather?"));
+        expected.push(Message::Assistant {
+            id: Some("msg-1".into()),
+            content: rig::OneOrMany::one(rig::message::AssistantContent::text("Sunny, 24°C")),
+        });
+        assert_eq!(guard, expected);
+    }
+
+    #[test]
+    fn clean_title_strips_model_decoration() {
+        assert_eq!(
+            super::clean_title("\"Migrating the blog to Astro.\"\n\nHope that helps!").as_deref(),
+            Some("Migrating the blog to Astro")
+        );
+        assert_eq!(
+            super::clean_title("Title: **Quarterly report numbers**").as_deref(),
+            Some("Quarterly report numbers")
+        );
+        assert_eq!(super::clean_title("  \n"), None);
+        assert_eq!(
+            super::clean_title(&"é".repeat(200)).map(|title| title.chars().count()),
+            Some(super::TITLE_MAX_CHARS)
+        );
+    }
+
+    #[test]
+    fn held_replies_are_picked_out_of_a_batch() {
+        use super::{HeldReply, parse_held_reply};
+        use crate::agent::approval::{ApprovalReply, Decision};
+        use crate::testing::inbound_message;
+
+        // Messages as they'd sit in the coalesce buffer: each is screened on
+        // its own, so the replies don't vanish into one combined turn.
+        let batch = [
+            inbound_message("discord", "alice", "can you check the logs?"),
+            inbound_message("discord", "bob", "approve"),
+            inbound_message("discord", "alice", "!approve_plan"),
+        ];
+        let replies: Vec<_> = batch
+            .iter()
+            .map(|message| parse_held_reply(message, true))
+            .collect();
+        assert_eq!(
+            replies,
+            [
+                None,
+                Some(HeldReply::Approval(ApprovalReply {
+                    decision: Decision::Approve,
+                    approval_id: None,
+                })),
+                Some(HeldReply::ApprovePlan),
+            ]
+        );
+
+        // With nothing waiting, "approve" is an ordinary message.
+        assert_eq!(parse_held_reply(&batch[1], false), None);
+        assert_eq!(
+            parse_held_reply(&inbound_message("system", "system", "approve"), true),
+            None
+        );
+    }
 }
@@ -0,0 +1,92 @@
+//! Admin commands: `!`-prefixed messages the channel handles itself.
+//!
+//! `!status`, `!cancel <worker>`, `!model <name>`, `!forget`, and `!usage`
+//! act on channel state directly instead of starting an LLM turn. They are
+//! refused unless the agent has an `[auth]` table, and then only admin-tier
+//! senders (see [`crate::auth::tiers`]) may run them. Replies use the
+//! `commands/*` canned text, so they follow the conversation's language.
+//! Messages that start with `!` but don't name a command go to the LLM as
+//! usual. Plan mode's `!plan` and `!approve_plan` (see [`crate::agent::plan`])
+//! share the prefix.
+
+/// Prefix that marks a message as a command.
+pub const COMMAND_PREFIX: char = '!';
+
+/// `!model` argument that drops the conversation's model override.
+pub const MODEL_RESET: &str = "reset";
+
+/// A parsed admin command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdminCommand {
+    /// `!status`: the model in use and running workers and branches.
+    Status,
+    /// `!cancel <worker>`: cancel a worker by ID or ID prefix.
+    Cancel(Option<String>),
+    /// `!model [name|reset]`: show, set, or clear the conversation's model.
+    Model(Option<String>),
+    /// `!forget`: clear the conversation history.
+    Forget,
+    /// `!usage`: tokens and estimated spend so far.
+    Usage,
+}
+
+impl AdminCommand {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Status => "status",
+            Self::Cancel(_) => "cancel",
+            Self::Model(_) => "model",
+            Self::Forget => "forget",
+            Self::Usage => "usage",
+        }
+    }
+}
+
+/// Parse an admin command from a whole message.
+pub fn parse(text: &str) -> Option<AdminCommand> {
+    let rest = text.trim().strip_prefix(COMMAND_PREFIX)?;
+    let (name, argument) = match rest.split_once(char::is_whitespace) {
+        Some((name, argument)) => (name, argument.trim()),
+        None => (rest, ""),
+    };
+    let argument = (!argument.is_empty()).then(|| argument.to_string());
+
+    match name.to_ascii_lowercase().as_str() {
+        "status" => Some(AdminCommand::Status),
+        "cancel" => Some(AdminCommand::Cancel(argument)),
+        "model" => Some(AdminCommand::Model(argument)),
+        "forget" => Some(AdminCommand::Forget),
+        "usage" => Some(AdminCommand::Usage),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands_and_arguments() {
+        assert_eq!(parse("!status"), Some(AdminCommand::Status));
+        assert_eq!(parse("  !USAGE \n"), Some(AdminCommand::Usage));
+        assert_eq!(
+            parse("!cancel 3f2a"),
+            Some(AdminCommand::Cancel(Some("3f2a".into())))
+        );
+        assert_eq!(parse("!cancel"), Some(AdminCommand::Cancel(None)));
+        assert_eq!(
+            parse("!model  openai/gpt-4.1 "),
+            Some(AdminCommand::Model(Some("openai/gpt-4.1".into())))
+        );
+        assert_eq!(parse("!forget"), Some(AdminCommand::Forget));
+    }
+
+    #[test]
+    fn other_messages_are_not_commands() {
+        assert_eq!(parse("status"), None);
+        assert_eq!(parse("!statuses"), None);
+        assert_eq!(parse("!important: read this"), None);
+        assert_eq!(parse("hey !status"), None);
+        assert_eq!(parse("!"), None);
+    }
+}
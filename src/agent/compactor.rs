@@ -6,7 +6,7 @@
 
 use crate::error::Result;
 use crate::llm::SpacebotModel;
-use crate::{AgentDeps, ChannelId, ProcessType};
+use crate::{AgentDeps, ChannelId, ProcessEvent, ProcessType};
 use rig::agent::AgentBuilder;
 use rig::completion::{CompletionModel as _, Prompt as _};
 use rig::message::{AssistantContent, Message, UserContent};
@@ -71,6 +71,15 @@ impl Compactor {
                 "compaction triggered"
             );
 
+            self.deps
+                .event_tx
+                .send(ProcessEvent::CompactionTriggered {
+                    agent_id: self.deps.agent_id.clone(),
+                    channel_id: self.channel_id.clone(),
+                    threshold_reached: usage,
+                })
+                .ok();
+
             match action {
                 CompactionAction::EmergencyTruncate => {
                     // Emergency is synchronous — fast, no LLM
@@ -119,7 +128,8 @@ impl Compactor {
         };
 
         tokio::spawn(async move {
-            let result = run_compaction(&deps, &compactor_prompt, &history, fraction).await;
+            let result =
+                run_compaction(&deps, &channel_id, &compactor_prompt, &history, fraction).await;
 
             match result {
                 Ok(turns_compacted) => {
@@ -179,6 +189,7 @@ impl Compactor {
 #[tracing::instrument(skip(deps, compactor_prompt, history), fields(agent_id = %deps.agent_id))]
 async fn run_compaction(
     deps: &AgentDeps,
+    channel_id: &ChannelId,
     compactor_prompt: &str,
     history: &Arc<RwLock<Vec<Message>>>,
     fraction: f32,
@@ -190,6 +201,7 @@ async fn run_compaction(
         let remove_count = ((total as f32 * fraction) as usize)
             .max(1)
             .min(total.saturating_sub(2));
+        let remove_count = align_compaction_boundary(&hist, remove_count);
         if remove_count == 0 {
             return Ok(0);
         }
@@ -202,16 +214,18 @@ async fn run_compaction(
 
     // 3. Run the compaction LLM to produce summary + extracted memories
     let routing = deps.runtime_config.routing.load();
-    let model_name = routing.resolve(ProcessType::Worker, None).to_string();
+    let model_name = routing.resolve(ProcessType::Compactor, None).to_string();
     let model = SpacebotModel::make(&deps.llm_manager, &model_name)
         .with_context(&*deps.agent_id, "compactor")
         .with_routing((**routing).clone());
 
-    // Give the compaction worker memory_save so it can directly persist memories
+    // Give the compaction worker memory_save so it can directly persist
+    // memories, attributed to (and, in DMs, scoped to) this channel
     let tool_server: ToolServerHandle = ToolServer::new()
-        .tool(crate::tools::MemorySaveTool::new(
-            deps.memory_search.clone(),
-        ))
+        .tool(
+            crate::tools::MemorySaveTool::new(deps.memory_search.clone())
+                .with_channel(channel_id.clone()),
+        )
         .run();
 
     let agent = AgentBuilder::new(model)
@@ -244,6 +258,65 @@ async fn run_compaction(
     Ok(remove_count)
 }
 
+/// Move a compaction cut point forward so the retained history doesn't start
+/// with a tool result whose tool call was summarized away. Providers reject
+/// orphaned tool results, so the whole call/result pair goes into the summary.
+///
+/// Never advances past `len - 2`, keeping the most recent turns intact; if no
+/// clean boundary exists in that range, returns 0 and compaction is skipped.
+fn align_compaction_boundary(history: &[Message], remove_count: usize) -> usize {
+    let limit = history.len().saturating_sub(2);
+    let mut cut = remove_count;
+
+    while cut < history.len() && starts_with_tool_result(&history[cut]) {
+        cut += 1;
+    }
+
+    if cut > limit { 0 } else { cut }
+}
+
+/// Drop the oldest messages until the history is estimated to fit in
+/// `budget` tokens. Returns how many messages and estimated tokens were
+/// removed, or `None` if it already fits or no clean cut exists.
+///
+/// Unlike compaction this runs before a turn and never calls an LLM. The cut
+/// follows the same rules: the last two messages stay, and a retained history
+/// never starts with an orphaned tool result.
+pub fn trim_history_to_budget(history: &mut Vec<Message>, budget: usize) -> Option<(usize, usize)> {
+    let sizes: Vec<usize> = history
+        .iter()
+        .map(|message| estimate_history_tokens(std::slice::from_ref(message)))
+        .collect();
+    let total: usize = sizes.iter().sum();
+    if total <= budget {
+        return None;
+    }
+
+    let mut cut = 0;
+    let mut removed_tokens = 0;
+    while cut < sizes.len() && total - removed_tokens > budget {
+        removed_tokens += sizes[cut];
+        cut += 1;
+    }
+
+    let cut = align_compaction_boundary(history, cut.min(history.len().saturating_sub(2)));
+    if cut == 0 {
+        return None;
+    }
+
+    history.drain(..cut);
+    Some((cut, sizes[..cut].iter().sum()))
+}
+
+fn starts_with_tool_result(message: &Message) -> bool {
+    match message {
+        Message::User { content } => content
+            .iter()
+            .any(|item| matches!(item, UserContent::ToolResult(_))),
+        Message::Assistant { .. } => false,
+    }
+}
+
 /// Estimate token count for a history using chars/4 heuristic.
 ///
 /// This is intentionally rough — it's only used for threshold checks, not billing.
@@ -302,7 +375,7 @@ fn estimate_assistant_content_chars(content: &AssistantContent) -> usize {
 }
 
 /// Render messages into a human-readable transcript for the compaction LLM.
-fn render_messages_as_transcript(messages: &[Message]) -> String {
+pub(crate) fn render_messages_as_transcript(messages: &[Message]) -> String {
     let mut output = String::new();
 
     for message in messages {
@@ -375,3 +448,77 @@ pub enum CompactionAction {
     /// Emergency truncation (no LLM, drop oldest 50%).
     EmergencyTruncate,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{align_compaction_boundary, trim_history_to_budget};
+    use rig::OneOrMany;
+    use rig::message::{Message, ToolResult, ToolResultContent, UserContent};
+
+    fn text(content: &str) -> Message {
+        Message::from(content.to_string())
+    }
+
+    fn tool_result(id: &str) -> Message {
+        Message::User {
+            content: OneOrMany::one(UserContent::ToolResult(ToolResult {
+                id: id.to_string(),
+                call_id: None,
+                content: OneOrMany::one(ToolResultContent::text("ok")),
+            })),
+        }
+    }
+
+    #[test]
+    fn boundary_unchanged_on_plain_messages() {
+        let history = vec![text("a"), text("b"), text("c"), text("d"), text("e")];
+        assert_eq!(align_compaction_boundary(&history, 2), 2);
+    }
+
+    #[test]
+    fn boundary_skips_orphaned_tool_results() {
+        let history = vec![
+            text("a"),
+            text("[tool call]"),
+            tool_result("1"),
+            tool_result("2"),
+            text("b"),
+            text("c"),
+            text("d"),
+        ];
+        assert_eq!(align_compaction_boundary(&history, 2), 4);
+    }
+
+    #[test]
+    fn boundary_gives_up_instead_of_eating_recent_turns() {
+        let history = vec![text("a"), text("[tool call]"), tool_result("1"), text("b")];
+        assert_eq!(align_compaction_boundary(&history, 2), 0);
+    }
+
+    #[test]
+    fn trim_drops_oldest_until_within_budget() {
+        // 40 chars each, ~10 tokens per message.
+        let mut history: Vec<Message> = (0..6).map(|i| text(&i.to_string().repeat(40))).collect();
+        assert_eq!(trim_history_to_budget(&mut history, 60), None);
+
+        assert_eq!(trim_history_to_budget(&mut history, 35), Some((3, 30)));
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0], text(&"3".repeat(40)));
+    }
+
+    #[test]
+    fn trim_keeps_recent_turns_and_tool_pairs() {
+        let mut history = vec![
+            text(&"a".repeat(400)),
+            text("[tool call]"),
+            tool_result("1"),
+            text("b"),
+            text("c"),
+        ];
+        assert_eq!(
+            trim_history_to_budget(&mut history, 1).map(|(count, _)| count),
+            Some(3)
+        );
+        assert_eq!(history, vec![text("b"), text("c")]);
+    }
+}
@@ -13,7 +13,7 @@ use crate::error::Result;
 use crate::hooks::CortexHook;
 use crate::llm::SpacebotModel;
 use crate::memory::search::{SearchConfig, SearchMode, SearchSort};
-use crate::memory::types::{Association, MemoryType, RelationType};
+use crate::memory::types::{Association, MemoryAudience, MemoryType, RelationType};
 use crate::{AgentDeps, ProcessEvent, ProcessType};
 
 use rig::agent::AgentBuilder;
@@ -233,7 +233,7 @@ impl Cortex {
             }),
             ProcessEvent::WorkerComplete { result, .. } => Some(Signal::WorkerCompleted {
                 task_summary: "completed task".into(),
-                result_summary: result.lines().next().unwrap_or("done").into(),
+                result_summary: result.summary.clone(),
             }),
             ProcessEvent::CompactionTriggered {
                 channel_id,
@@ -551,6 +551,9 @@ async fn gather_bulletin_sections(deps: &AgentDeps) -> String {
             memory_type: section.memory_type,
             sort_by: section.sort_by,
             max_results: section.max_results,
+            // The bulletin reaches every channel, so only agent-scoped
+            // memories may go into it.
+            audience: Some(MemoryAudience::default()),
             ..Default::default()
         };
 
@@ -983,7 +986,7 @@ async fn run_association_pass(
     let is_backfill = since.is_none();
 
     let store = deps.memory_search.store();
-    let embedding_table = deps.memory_search.embedding_table();
+    let embeddings = deps.memory_search.embeddings();
 
     // Get the memories to process
     let memories = match fetch_memories_for_association(&deps.sqlite_pool, since).await {
@@ -1007,7 +1010,7 @@ async fn run_association_pass(
         }
 
         // Find similar memories via embedding search
-        let similar = match embedding_table
+        let similar = match embeddings
             .find_similar(memory_id, similarity_threshold, 10)
             .await
         {
@@ -1095,6 +1098,85 @@ async fn fetch_memories_for_association(
     Ok(rows.iter().map(|row| row.get("id")).collect())
 }
 
+// -- Maintenance loop --
+
+/// Spawn the memory maintenance loop for an agent.
+///
+/// Periodically decays stale memories, promotes frequently recalled ones,
+/// forgets memories whose importance has decayed away, and merges
+/// near-duplicates so retrieval stays precise as the store grows.
+pub fn spawn_maintenance_loop(
+    deps: AgentDeps,
+    logger: CortexLogger,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        tracing::info!("cortex maintenance loop started");
+
+        loop {
+            let config = **deps.runtime_config.memory_maintenance.load();
+            tokio::time::sleep(Duration::from_secs(config.interval_secs)).await;
+
+            // Re-read after sleeping so a hot-reload that disables maintenance
+            // takes effect before the next pass.
+            let config = **deps.runtime_config.memory_maintenance.load();
+            if !config.enabled {
+                continue;
+            }
+
+            run_maintenance_pass(&deps, &logger, &config).await;
+        }
+    })
+}
+
+async fn run_maintenance_pass(
+    deps: &AgentDeps,
+    logger: &CortexLogger,
+    config: &crate::config::MemoryMaintenanceConfig,
+) {
+    let started = Instant::now();
+    let report =
+        match crate::memory::maintenance::run_maintenance(&deps.memory_search, config).await {
+            Ok(report) => report,
+            Err(error) => {
+                tracing::warn!(%error, "memory maintenance pass failed");
+                logger.log(
+                    "maintenance_failed",
+                    &format!("Memory maintenance failed: {error}"),
+                    Some(serde_json::json!({ "error": error.to_string() })),
+                );
+                return;
+            }
+        };
+
+    if report.is_empty() {
+        return;
+    }
+
+    let duration_ms = started.elapsed().as_millis() as u64;
+    tracing::info!(
+        decayed = report.decayed,
+        promoted = report.promoted,
+        pruned = report.pruned,
+        merged = report.merged,
+        duration_ms,
+        "memory maintenance pass complete"
+    );
+    logger.log(
+        "maintenance_completed",
+        &format!(
+            "Maintenance: {} decayed, {} promoted, {} forgotten, {} merged",
+            report.decayed, report.promoted, report.pruned, report.merged
+        ),
+        Some(serde_json::json!({
+            "decayed": report.decayed,
+            "promoted": report.promoted,
+            "pruned": report.pruned,
+            "merged": report.merged,
+            "duration_ms": duration_ms,
+        })),
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::should_execute_warmup;
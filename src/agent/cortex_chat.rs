@@ -282,6 +282,7 @@ impl CortexChatSession {
         let thread_id = thread_id.to_string();
         let channel_context_id = channel_context_id.map(|s| s.to_string());
         let store = self.store.clone();
+        let tool_concurrency = **self.deps.runtime_config.tool_concurrency.load();
 
         tokio::spawn(async move {
             let channel_ref = channel_context_id.as_deref();
@@ -289,6 +290,7 @@ impl CortexChatSession {
             let result = agent
                 .prompt(&user_text)
                 .with_hook(hook)
+                .with_tool_concurrency(tool_concurrency)
                 .with_history(&mut history)
                 .await;
 
@@ -331,11 +333,28 @@ impl CortexChatSession {
         let memory_bulletin = runtime_config.memory_bulletin.load();
 
         let browser_enabled = runtime_config.browser_config.load().enabled;
-        let web_search_enabled = runtime_config.brave_search_key.load().is_some();
+        let web_search_enabled = runtime_config.web_search.load().is_configured();
+        let container_enabled = runtime_config.container.load().enabled;
+        let github_enabled = runtime_config.github_token.load().is_some();
+        let coolify_enabled = runtime_config.coolify_url.load().is_some()
+            && runtime_config.coolify_token.load().is_some();
+        let sql_enabled = !runtime_config.sql.load().is_empty();
+        let http_request_enabled = !runtime_config
+            .http_request
+            .load()
+            .allowed_domains
+            .is_empty();
+        let calendar_enabled = runtime_config.calendar.load().is_configured();
         let opencode_enabled = runtime_config.opencode.load().enabled;
         let worker_capabilities = prompt_engine.render_worker_capabilities(
             browser_enabled,
             web_search_enabled,
+            container_enabled,
+            github_enabled,
+            coolify_enabled,
+            sql_enabled,
+            http_request_enabled,
+            calendar_enabled,
             opencode_enabled,
         )?;
 
@@ -0,0 +1,312 @@
+//! Per-channel token and cost accounting.
+//!
+//! A channel's totals include the completions of the branches and workers it
+//! spawns, and are kept in SQLite so the budget survives restarts and
+//! archiving.
+
+use crate::error::Result;
+
+use anyhow::Context as _;
+use serde::Serialize;
+use sqlx::{Row as _, SqlitePool};
+use std::sync::{Arc, Mutex};
+
+/// Snapshot of a channel's accumulated LLM usage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct ChannelUsage {
+    /// Completion calls recorded.
+    pub completion_calls: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cached_input_tokens: u64,
+    /// Estimated spend in USD (best-effort, see `llm::pricing`).
+    pub estimated_cost_usd: f64,
+    /// Tokens and cost of the most recent turn only.
+    pub last_turn_tokens: u64,
+    pub last_turn_cost_usd: f64,
+    /// Configured hard budget, if any.
+    pub budget_usd: Option<f64>,
+}
+
+impl ChannelUsage {
+    /// USD remaining before the budget is exhausted. None when unlimited.
+    pub fn remaining_usd(&self) -> Option<f64> {
+        self.budget_usd
+            .map(|budget| (budget - self.estimated_cost_usd).max(0.0))
+    }
+
+    /// True once the estimated spend has reached the configured budget.
+    pub fn budget_exceeded(&self) -> bool {
+        self.budget_usd
+            .is_some_and(|budget| self.estimated_cost_usd >= budget)
+    }
+}
+
+#[derive(Debug, Default)]
+struct CostTrackerInner {
+    usage: ChannelUsage,
+    /// Model the current turn is running on, used to price its completions.
+    model_name: String,
+}
+
+/// Reads and writes the `channel_usage` table.
+#[derive(Debug, Clone)]
+pub struct UsageStore {
+    pool: SqlitePool,
+}
+
+impl UsageStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Stored totals for a channel. Zero for a channel with no usage yet;
+    /// the per-turn fields and budget are never stored.
+    pub async fn load(&self, channel_id: &str) -> Result<ChannelUsage> {
+        let row = sqlx::query(
+            "SELECT completion_calls, input_tokens, output_tokens, cached_input_tokens, \
+             estimated_cost_usd FROM channel_usage WHERE channel_id = ?",
+        )
+        .bind(channel_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("failed to load channel usage")?;
+
+        Ok(row
+            .map(|row| ChannelUsage {
+                completion_calls: row.try_get::<i64, _>("completion_calls").unwrap_or(0) as u64,
+                input_tokens: row.try_get::<i64, _>("input_tokens").unwrap_or(0) as u64,
+                output_tokens: row.try_get::<i64, _>("output_tokens").unwrap_or(0) as u64,
+                cached_input_tokens: row.try_get::<i64, _>("cached_input_tokens").unwrap_or(0)
+                    as u64,
+                estimated_cost_usd: row.try_get("estimated_cost_usd").unwrap_or(0.0),
+                ..ChannelUsage::default()
+            })
+            .unwrap_or_default())
+    }
+
+    /// Add one completion's usage to a channel's totals.
+    pub async fn add(
+        &self,
+        channel_id: &str,
+        input_tokens: u64,
+        output_tokens: u64,
+        cached_input_tokens: u64,
+        cost_usd: f64,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO channel_usage \
+             (channel_id, completion_calls, input_tokens, output_tokens, cached_input_tokens, \
+              estimated_cost_usd) \
+             VALUES (?, 1, ?, ?, ?, ?) \
+             ON CONFLICT(channel_id) DO UPDATE SET \
+                 completion_calls = completion_calls + 1, \
+                 input_tokens = input_tokens + excluded.input_tokens, \
+                 output_tokens = output_tokens + excluded.output_tokens, \
+                 cached_input_tokens = cached_input_tokens + excluded.cached_input_tokens, \
+                 estimated_cost_usd = estimated_cost_usd + excluded.estimated_cost_usd, \
+                 updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(channel_id)
+        .bind(input_tokens as i64)
+        .bind(output_tokens as i64)
+        .bind(cached_input_tokens as i64)
+        .bind(cost_usd)
+        .execute(&self.pool)
+        .await
+        .context("failed to record channel usage")?;
+
+        Ok(())
+    }
+}
+
+/// Accumulates token usage and estimated cost for a single channel.
+///
+/// Cheap to clone; all clones share the same counters. The channel's
+/// `SpacebotHook` records every completion response here, and the channel
+/// consults it before each turn to enforce the budget. Branches and workers
+/// record through [`CostTracker::for_model`] clones, which price at their
+/// own model and don't count toward the channel's last turn.
+#[derive(Debug, Clone, Default)]
+pub struct CostTracker {
+    inner: Arc<Mutex<CostTrackerInner>>,
+    /// Model this clone's completions are priced at, instead of the
+    /// channel's current turn model.
+    model_name: Option<String>,
+    store: Option<(UsageStore, String)>,
+}
+
+impl CostTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Persist `channel_id`'s totals in `store`. Call [`Self::restore`]
+    /// before the first turn to pick up where the channel left off.
+    pub fn with_store(mut self, store: UsageStore, channel_id: impl Into<String>) -> Self {
+        self.store = Some((store, channel_id.into()));
+        self
+    }
+
+    /// Load the stored totals, replacing the in-memory ones.
+    pub async fn restore(&self) -> Result<()> {
+        let Some((store, channel_id)) = &self.store else {
+            return Ok(());
+        };
+        let stored = store.load(channel_id).await?;
+        let mut inner = self.inner.lock().expect("cost tracker lock poisoned");
+        let usage = &mut inner.usage;
+        usage.completion_calls = stored.completion_calls;
+        usage.input_tokens = stored.input_tokens;
+        usage.output_tokens = stored.output_tokens;
+        usage.cached_input_tokens = stored.cached_input_tokens;
+        usage.estimated_cost_usd = stored.estimated_cost_usd;
+        Ok(())
+    }
+
+    /// A clone for a branch or worker of the channel running on `model_name`.
+    pub fn for_model(&self, model_name: &str) -> Self {
+        Self {
+            model_name: Some(model_name.to_string()),
+            ..self.clone()
+        }
+    }
+
+    /// Start a new turn on `model_name` with the currently configured budget.
+    pub fn begin_turn(&self, model_name: &str, budget_usd: Option<f64>) {
+        let mut inner = self.inner.lock().expect("cost tracker lock poisoned");
+        inner.model_name = model_name.to_string();
+        inner.usage.budget_usd = budget_usd;
+        inner.usage.last_turn_tokens = 0;
+        inner.usage.last_turn_cost_usd = 0.0;
+    }
+
+    /// Record token usage from one completion call.
+    pub fn record(&self, input_tokens: u64, output_tokens: u64, cached_input_tokens: u64) {
+        let mut inner = self.inner.lock().expect("cost tracker lock poisoned");
+        let cost = crate::llm::pricing::estimate_cost(
+            self.model_name.as_deref().unwrap_or(&inner.model_name),
+            input_tokens,
+            output_tokens,
+            cached_input_tokens,
+        );
+
+        let usage = &mut inner.usage;
+        usage.completion_calls += 1;
+        usage.input_tokens += input_tokens;
+        usage.output_tokens += output_tokens;
+        usage.cached_input_tokens += cached_input_tokens;
+        usage.estimated_cost_usd += cost;
+        if self.model_name.is_none() {
+            usage.last_turn_tokens += input_tokens + output_tokens;
+            usage.last_turn_cost_usd += cost;
+        }
+        drop(inner);
+
+        if let Some((store, channel_id)) = self.store.clone() {
+            tokio::spawn(async move {
+                if let Err(error) = store
+                    .add(
+                        &channel_id,
+                        input_tokens,
+                        output_tokens,
+                        cached_input_tokens,
+                        cost,
+                    )
+                    .await
+                {
+                    tracing::warn!(%error, %channel_id, "failed to persist channel usage");
+                }
+            });
+        }
+    }
+
+    /// Current usage snapshot.
+    pub fn usage(&self) -> ChannelUsage {
+        self.inner.lock().expect("cost tracker lock poisoned").usage
+    }
+
+    pub fn budget_exceeded(&self) -> bool {
+        self.usage().budget_exceeded()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CostTracker, UsageStore};
+
+    #[test]
+    fn accumulates_across_turns() {
+        let tracker = CostTracker::new();
+        tracker.begin_turn("anthropic/claude-sonnet-4-20250514", None);
+        tracker.record(1000, 500, 0);
+        tracker.begin_turn("anthropic/claude-sonnet-4-20250514", None);
+        tracker.record(1000, 500, 0);
+
+        let usage = tracker.usage();
+        assert_eq!(usage.completion_calls, 2);
+        assert_eq!(usage.input_tokens, 2000);
+        assert_eq!(usage.last_turn_tokens, 1500);
+        assert!((usage.estimated_cost_usd - 0.021).abs() < 1e-10);
+        assert!(!usage.budget_exceeded());
+    }
+
+    #[test]
+    fn budget_exceeded_once_spend_reaches_limit() {
+        let tracker = CostTracker::new();
+        tracker.begin_turn("anthropic/claude-sonnet-4-20250514", Some(0.01));
+        tracker.record(1000, 500, 0);
+
+        assert!(tracker.budget_exceeded());
+        assert_eq!(tracker.usage().remaining_usd(), Some(0.0));
+    }
+
+    #[test]
+    fn branch_and_worker_usage_counts_toward_the_budget() {
+        let tracker = CostTracker::new();
+        tracker.begin_turn("anthropic/claude-sonnet-4-20250514", Some(0.02));
+        tracker.record(1000, 500, 0);
+        tracker
+            .for_model("anthropic/claude-sonnet-4-20250514")
+            .record(1000, 500, 0);
+
+        let usage = tracker.usage();
+        assert_eq!(usage.completion_calls, 2);
+        assert_eq!(usage.last_turn_tokens, 1500);
+        assert!(usage.budget_exceeded());
+    }
+
+    #[tokio::test]
+    async fn usage_survives_a_restart() {
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .in_memory(true)
+            .create_if_missing(true);
+        let pool = sqlx::pool::PoolOptions::<sqlx::Sqlite>::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("in-memory SQLite");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+        let store = UsageStore::new(pool);
+        store
+            .add("discord:1:2", 1000, 500, 0, 0.0105)
+            .await
+            .unwrap();
+        store
+            .add("discord:1:2", 1000, 500, 0, 0.0105)
+            .await
+            .unwrap();
+
+        let tracker = CostTracker::new().with_store(store, "discord:1:2");
+        tracker.restore().await.unwrap();
+        tracker.begin_turn("anthropic/claude-sonnet-4-20250514", Some(0.02));
+
+        let usage = tracker.usage();
+        assert_eq!(usage.completion_calls, 2);
+        assert_eq!(usage.output_tokens, 1000);
+        assert!(usage.budget_exceeded());
+    }
+}
@@ -0,0 +1,187 @@
+//! Reactions as feedback.
+//!
+//! When a user reacts 👍 or 👎 to one of the agent's messages, the adapter
+//! forwards the reaction as an `InboundMessage` with `message_kind` metadata
+//! set to `reaction`. The channel stores it as a memory about how that reply
+//! was received instead of running an LLM turn, so the agent learns from
+//! reactions without answering them.
+
+use crate::InboundMessage;
+
+/// Metadata key that marks non-text inbound events.
+pub const MESSAGE_KIND_KEY: &str = "message_kind";
+
+/// `message_kind` value for reactions.
+pub const REACTION_KIND: &str = "reaction";
+
+/// Metadata key holding the reaction emoji (unicode or a Slack-style name).
+pub const REACTION_EMOJI_KEY: &str = "reaction_emoji";
+
+/// Metadata key holding the text of the message that was reacted to.
+pub const REACTION_TARGET_TEXT_KEY: &str = "reaction_target_text";
+
+/// Metadata key holding the platform ID of the message that was reacted to.
+pub const REACTION_TARGET_ID_KEY: &str = "reaction_target_id";
+
+/// Max characters of the reacted-to message quoted in the memory.
+pub const MAX_REACTION_TARGET_CHARS: usize = 200;
+
+/// Whether a user liked or disliked a reply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sentiment {
+    Positive,
+    Negative,
+}
+
+impl Sentiment {
+    /// Thumbs up or down in any skin tone, as unicode or a Slack-style name.
+    /// Other emoji carry no feedback.
+    pub fn from_emoji(emoji: &str) -> Option<Self> {
+        let emoji = emoji.trim();
+        let name = emoji.trim_matches(':');
+        let name = name.split("::").next().unwrap_or(name);
+        if emoji.starts_with('👍') || matches!(name, "+1" | "thumbsup") {
+            Some(Self::Positive)
+        } else if emoji.starts_with('👎') || matches!(name, "-1" | "thumbsdown") {
+            Some(Self::Negative)
+        } else {
+            None
+        }
+    }
+
+    fn emoji(self) -> &'static str {
+        match self {
+            Self::Positive => "👍",
+            Self::Negative => "👎",
+        }
+    }
+}
+
+/// Whether this inbound message is a reaction rather than something to reply to.
+pub fn is_reaction(message: &InboundMessage) -> bool {
+    message
+        .metadata
+        .get(MESSAGE_KIND_KEY)
+        .and_then(|value| value.as_str())
+        == Some(REACTION_KIND)
+}
+
+/// A 👍/👎 reaction to one of the agent's messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReactionFeedback {
+    pub sentiment: Sentiment,
+    pub sender: String,
+    pub target_text: Option<String>,
+}
+
+impl ReactionFeedback {
+    /// Read feedback from a reaction message. `None` for anything that isn't
+    /// a reaction, or a reaction that isn't a thumbs up or down.
+    pub fn from_message(message: &InboundMessage) -> Option<Self> {
+        if !is_reaction(message) {
+            return None;
+        }
+
+        let get = |key: &str| message.metadata.get(key).and_then(|value| value.as_str());
+        let sentiment = Sentiment::from_emoji(get(REACTION_EMOJI_KEY)?)?;
+        let sender = message
+            .formatted_author
+            .clone()
+            .or_else(|| get("sender_display_name").map(str::to_string))
+            .unwrap_or_else(|| message.sender_id.clone());
+        let target_text = get(REACTION_TARGET_TEXT_KEY)
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(|text| {
+                let mut excerpt: String = text.chars().take(MAX_REACTION_TARGET_CHARS).collect();
+                if excerpt.len() < text.len() {
+                    excerpt.push_str("...");
+                }
+                excerpt
+            });
+
+        Some(Self {
+            sentiment,
+            sender,
+            target_text,
+        })
+    }
+
+    /// The memory to store for this reaction.
+    pub fn memory_content(&self) -> String {
+        let received = match self.sentiment {
+            Sentiment::Positive => "was well received",
+            Sentiment::Negative => "was not well received",
+        };
+        match &self.target_text {
+            Some(text) => format!(
+                "{} reacted {} to my reply \"{text}\". That reply {received}.",
+                self.sender,
+                self.sentiment.emoji(),
+            ),
+            None => format!(
+                "{} reacted {} to one of my replies. That reply {received}.",
+                self.sender,
+                self.sentiment.emoji(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn reaction(emoji: &str, target: Option<&str>) -> InboundMessage {
+        let mut metadata = HashMap::new();
+        metadata.insert(MESSAGE_KIND_KEY.into(), REACTION_KIND.into());
+        metadata.insert(REACTION_EMOJI_KEY.into(), emoji.into());
+        if let Some(target) = target {
+            metadata.insert(REACTION_TARGET_TEXT_KEY.into(), target.into());
+        }
+        InboundMessage {
+            conversation_id: "discord:1:2".into(),
+            metadata,
+            formatted_author: Some("Alice".into()),
+            ..crate::testing::inbound_message("discord", "42", emoji)
+        }
+    }
+
+    #[test]
+    fn thumbs_map_to_sentiment() {
+        assert_eq!(Sentiment::from_emoji("👍"), Some(Sentiment::Positive));
+        assert_eq!(Sentiment::from_emoji("👍🏽"), Some(Sentiment::Positive));
+        assert_eq!(
+            Sentiment::from_emoji(":+1::skin-tone-3:"),
+            Some(Sentiment::Positive)
+        );
+        assert_eq!(Sentiment::from_emoji("👎"), Some(Sentiment::Negative));
+        assert_eq!(
+            Sentiment::from_emoji("thumbsdown"),
+            Some(Sentiment::Negative)
+        );
+        assert_eq!(Sentiment::from_emoji("🎉"), None);
+    }
+
+    #[test]
+    fn feedback_quotes_the_reacted_message() {
+        let feedback =
+            ReactionFeedback::from_message(&reaction("👍", Some("Try caching the embeddings.")))
+                .expect("thumbs up is feedback");
+        assert_eq!(
+            feedback.memory_content(),
+            "Alice reacted 👍 to my reply \"Try caching the embeddings.\". That reply was well received."
+        );
+    }
+
+    #[test]
+    fn other_emoji_and_plain_messages_are_not_feedback() {
+        assert!(ReactionFeedback::from_message(&reaction("🎉", None)).is_none());
+
+        let mut message = reaction("👍", None);
+        message.metadata.clear();
+        assert!(!is_reaction(&message));
+        assert!(ReactionFeedback::from_message(&message).is_none());
+    }
+}
@@ -0,0 +1,318 @@
+//! Gating group messages: whether a channel takes a turn for a message that
+//! wasn't sent to the agent one-to-one.
+//!
+//! In `mention` mode the agent answers only when it is mentioned, replied
+//! to, or named (its display name or a configured wake word). `relevance`
+//! mode falls back to asking the cheap model whether an unaddressed message
+//! is worth chiming in on.
+//!
+//! With passive listening on, messages the gate turns away are kept as
+//! [`Overheard`] lines. Every batch is summarized into an episodic memory,
+//! and whatever is still buffered when the agent is addressed is put in
+//! front of the turn as context.
+
+use crate::agent::lanes::{is_direct_message, mentions_bot};
+use crate::config::{GatingConfig, GatingMode};
+use crate::llm::SpacebotModel;
+use crate::tools::{MemorySaveArgs, MemorySaveTool};
+use crate::{AgentDeps, ChannelId, InboundMessage, ProcessType};
+
+use rig::agent::AgentBuilder;
+use rig::completion::Prompt;
+use rig::tool::Tool as _;
+
+/// What the gate decided for a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gate {
+    /// Take a turn.
+    Open,
+    /// Stay quiet.
+    Closed,
+    /// Not addressed; ask the cheap model.
+    AskRelevance,
+}
+
+/// Decide from the message alone. `names` are the agent's display name;
+/// the configured wake words are added to them.
+pub fn check(config: &GatingConfig, message: &InboundMessage, text: &str, names: &[&str]) -> Gate {
+    if config.mode == GatingMode::Always
+        || !is_group_platform(message)
+        // Button presses and menu picks answer something the agent sent.
+        || matches!(message.content, crate::MessageContent::Interaction { .. })
+        || is_direct_message(message)
+        || mentions_bot(message)
+        // Reminders were asked for, so they're addressed to the agent.
+        || crate::reminders::is_reminder(message)
+    {
+        return Gate::Open;
+    }
+
+    let named = names
+        .iter()
+        .copied()
+        .chain(config.wake_words.iter().map(String::as_str))
+        .any(|word| contains_word(text, word));
+    match (named, config.mode) {
+        (true, _) => Gate::Open,
+        (false, GatingMode::Relevance) => Gate::AskRelevance,
+        (false, _) => Gate::Closed,
+    }
+}
+
+/// Whether the message comes from a chat platform with group conversations.
+/// Everything else (webhooks, cron, link channels, re-triggers) is always
+/// answered.
+fn is_group_platform(message: &InboundMessage) -> bool {
+    matches!(
+        message.source.as_str(),
+        "discord" | "slack" | "telegram" | "twitch"
+    )
+}
+
+/// Whether `word` occurs in `text` as a whole word, ignoring case.
+fn contains_word(text: &str, word: &str) -> bool {
+    let word = word.trim().to_lowercase();
+    if word.is_empty() {
+        return false;
+    }
+    let text = text.to_lowercase();
+    text.match_indices(&word).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + word.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Ask the cheap model whether the agent should reply to a message nobody
+/// addressed to it. Errors count as no: staying quiet is the safe default.
+pub async fn should_chime_in(
+    deps: &AgentDeps,
+    prompt_engine: &crate::prompts::PromptEngine,
+    agent_name: &str,
+    transcript: &str,
+    message: &str,
+) -> bool {
+    let prompt =
+        match prompt_engine.render_system_channel_relevance(agent_name, transcript, message) {
+            Ok(prompt) => prompt,
+            Err(error) => {
+                tracing::warn!(%error, "failed to render channel relevance prompt");
+                return false;
+            }
+        };
+
+    let agent = AgentBuilder::new(cheap_model(deps, "channel_relevance")).build();
+    match agent.prompt(prompt).await {
+        Ok(response) => response
+            .trim()
+            .trim_matches(['"', '.', '*'])
+            .to_lowercase()
+            .starts_with("yes"),
+        Err(error) => {
+            tracing::warn!(%error, "channel relevance check failed");
+            false
+        }
+    }
+}
+
+/// Group messages overheard without answering, waiting to be summarized.
+#[derive(Debug, Default)]
+pub struct Overheard {
+    lines: Vec<String>,
+    /// When the oldest buffered message is due to be summarized.
+    pub deadline: Option<tokio::time::Instant>,
+}
+
+impl Overheard {
+    /// Buffer a message. The first one in a batch starts the clock.
+    pub fn push(&mut self, sender: &str, text: &str, interval: std::time::Duration) {
+        if self.lines.is_empty() {
+            self.deadline = Some(tokio::time::Instant::now() + interval);
+        }
+        self.lines.push(format!("{sender}: {text}"));
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// The buffered messages, one `sender: text` line each.
+    pub fn transcript(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// Take the buffered messages as a transcript, if there are any.
+    pub fn take(&mut self) -> Option<String> {
+        self.deadline = None;
+        if self.lines.is_empty() {
+            return None;
+        }
+        let transcript = self.transcript();
+        self.lines.clear();
+        Some(transcript)
+    }
+}
+
+/// Summarize overheard messages with the cheap model and save the summary
+/// as an episodic memory of the channel. Batches with nothing worth
+/// remembering are dropped.
+pub async fn summarize_overheard(
+    deps: AgentDeps,
+    prompt_engine: crate::prompts::PromptEngine,
+    channel_id: ChannelId,
+    agent_name: String,
+    transcript: String,
+) {
+    let prompt = match prompt_engine.render_system_overheard_summary(&agent_name, &transcript) {
+        Ok(prompt) => prompt,
+        Err(error) => {
+            tracing::warn!(%error, "failed to render overheard summary prompt");
+            return;
+        }
+    };
+    let agent = AgentBuilder::new(cheap_model(&deps, "overheard_summary")).build();
+    let summary = match agent.prompt(prompt).await {
+        Ok(summary) => summary.trim().to_string(),
+        Err(error) => {
+            tracing::warn!(%error, %channel_id, "overheard summary failed");
+            return;
+        }
+    };
+    if summary.is_empty() || summary.trim_matches(['.', '"']) == "NOTHING" {
+        tracing::debug!(%channel_id, "nothing worth remembering in overheard messages");
+        return;
+    }
+
+    let tool = MemorySaveTool::new(deps.memory_search.clone());
+    let args = MemorySaveArgs {
+        content: summary,
+        memory_type: "event".into(),
+        importance: None,
+        source: Some("passive_listening".into()),
+        channel_id: Some(channel_id.to_string()),
+        scope: None,
+        owner_id: None,
+        associations: Vec::new(),
+    };
+    match tool.call(args).await {
+        Ok(output) => tracing::info!(
+            %channel_id,
+            memory_id = %output.memory_id,
+            "saved overheard conversation summary"
+        ),
+        Err(error) => {
+            tracing::warn!(%error, %channel_id, "failed to save overheard conversation summary")
+        }
+    }
+}
+
+/// The cheap channel model, or the compactor model if none is configured.
+fn cheap_model(deps: &AgentDeps, context: &str) -> SpacebotModel {
+    let routing = deps.runtime_config.routing.load();
+    let model_name = if routing.channel_cheap.is_empty() {
+        routing.resolve(ProcessType::Compactor, None).to_string()
+    } else {
+        routing.channel_cheap.clone()
+    };
+    SpacebotModel::make(&deps.llm_manager, &model_name)
+        .with_context(&*deps.agent_id, context)
+        .with_routing((**routing).clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn group_message(metadata: serde_json::Value) -> InboundMessage {
+        InboundMessage {
+            conversation_id: "discord:1:2".into(),
+            metadata: serde_json::from_value::<HashMap<_, _>>(metadata).unwrap(),
+            ..crate::testing::inbound_message("discord", "alice", "hi")
+        }
+    }
+
+    #[test]
+    fn mention_mode_answers_only_when_addressed() {
+        let config = GatingConfig {
+            mode: GatingMode::Mention,
+            wake_words: vec!["hey bot".into()],
+            ..GatingConfig::default()
+        };
+        let chatter = group_message(serde_json::json!({"discord_guild_id": 1}));
+        let mention = group_message(serde_json::json!({
+            "discord_guild_id": 1,
+            "discord_mentions_or_replies_to_bot": true,
+        }));
+        let dm = group_message(serde_json::json!({}));
+
+        assert_eq!(
+            check(&config, &chatter, "lunch?", &["Spacebot"]),
+            Gate::Closed
+        );
+        assert_eq!(
+            check(&config, &mention, "lunch?", &["Spacebot"]),
+            Gate::Open
+        );
+        assert_eq!(check(&config, &dm, "lunch?", &["Spacebot"]), Gate::Open);
+        let cron = InboundMessage {
+            source: "cron".into(),
+            ..chatter.clone()
+        };
+        assert_eq!(check(&config, &cron, "lunch?", &["Spacebot"]), Gate::Open);
+        let mut reminder = chatter.clone();
+        reminder.metadata.insert(
+            crate::reminders::REMINDER_METADATA_KEY.into(),
+            "a1b2c3d4".into(),
+        );
+        assert_eq!(
+            check(&config, &reminder, "lunch?", &["Spacebot"]),
+            Gate::Open
+        );
+        assert_eq!(
+            check(&config, &chatter, "spacebot, lunch?", &["Spacebot"]),
+            Gate::Open
+        );
+        assert_eq!(
+            check(&config, &chatter, "Hey bot: lunch?", &["Spacebot"]),
+            Gate::Open
+        );
+        assert_eq!(
+            check(&config, &chatter, "spacebots are cool", &["Spacebot"]),
+            Gate::Closed
+        );
+
+        let relevance = GatingConfig {
+            mode: GatingMode::Relevance,
+            ..config
+        };
+        assert_eq!(
+            check(&relevance, &chatter, "lunch?", &["Spacebot"]),
+            Gate::AskRelevance
+        );
+    }
+
+    #[tokio::test]
+    async fn overheard_batches_carry_a_deadline_until_taken() {
+        let mut overheard = Overheard::default();
+        assert_eq!(overheard.take(), None);
+
+        let interval = std::time::Duration::from_secs(60);
+        overheard.push("alice", "v2 ships friday?", interval);
+        let deadline = overheard.deadline;
+        overheard.push("bob", "if QA signs off", interval);
+        assert_eq!(overheard.deadline, deadline);
+        assert_eq!(overheard.len(), 2);
+
+        assert_eq!(
+            overheard.take().as_deref(),
+            Some("alice: v2 ships friday?\nbob: if QA signs off")
+        );
+        assert!(overheard.is_empty());
+        assert_eq!(overheard.deadline, None);
+    }
+}
@@ -0,0 +1,267 @@
+//! Heartbeat: a periodic check-in the agent sends on its own.
+//!
+//! Every `interval_secs` the heartbeat surveys the memory bulletin, pending
+//! cron jobs, running workers, and upcoming calendar events (kept fresh by the
+//! calendar sync loop), then asks the LLM whether anything is worth raising.
+//! Anything other than `[skip]` is sent to the configured delivery target, the
+//! same way cron results are delivered.
+
+use crate::config::HeartbeatConfig;
+use crate::conversation::ProcessRunLogger;
+use crate::cron::CronConfig;
+use crate::llm::SpacebotModel;
+use crate::messaging::target::parse_delivery_target;
+use crate::{AgentDeps, OutboundResponse, ProcessType};
+
+use rig::agent::AgentBuilder;
+use rig::completion::{CompletionModel, Prompt};
+
+use std::time::Duration;
+
+/// What the LLM answers when nothing is worth raising.
+const SKIP_MARKER: &str = "[skip]";
+
+/// Max running workers listed in the survey.
+const MAX_SURVEY_WORKERS: i64 = 20;
+
+/// Max characters of a cron prompt shown in the survey.
+const MAX_CRON_PROMPT_CHARS: usize = 160;
+
+/// How often a disabled heartbeat re-reads its config.
+const DISABLED_POLL_SECS: u64 = 60;
+
+/// Spawn the heartbeat loop for an agent.
+///
+/// The loop always runs and reads `[heartbeat]` on every tick, so enabling it
+/// or changing the interval takes effect without a restart.
+pub fn spawn_heartbeat_loop(deps: AgentDeps) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let config = (**deps.runtime_config.heartbeat.load()).clone();
+            if !config.enabled || config.delivery_target.is_none() {
+                tokio::time::sleep(Duration::from_secs(DISABLED_POLL_SECS)).await;
+                continue;
+            }
+
+            tokio::time::sleep(Duration::from_secs(config.interval_secs.max(60))).await;
+
+            // The config may have changed while we slept.
+            let config = (**deps.runtime_config.heartbeat.load()).clone();
+            if config.enabled {
+                run_heartbeat(&deps, &config).await;
+            }
+        }
+    })
+}
+
+/// Run a single heartbeat: survey, decide, and deliver.
+#[tracing::instrument(skip(deps, config), fields(agent_id = %deps.agent_id))]
+pub async fn run_heartbeat(deps: &AgentDeps, config: &HeartbeatConfig) {
+    let Some(raw_target) = config.delivery_target.as_deref() else {
+        return;
+    };
+    let Some(target) = parse_delivery_target(raw_target) else {
+        tracing::warn!(delivery_target = %raw_target, "invalid heartbeat delivery target");
+        return;
+    };
+    let Some(messaging_manager) = deps.messaging_manager.clone() else {
+        tracing::warn!("no messaging manager, skipping heartbeat");
+        return;
+    };
+
+    let prompt_engine = deps.runtime_config.prompts.load();
+    let identity_context = {
+        let rendered = deps.runtime_config.identity.load().render();
+        if rendered.is_empty() {
+            None
+        } else {
+            Some(rendered)
+        }
+    };
+    let system_prompt = match prompt_engine.render_heartbeat_prompt(identity_context.as_deref()) {
+        Ok(prompt) => prompt,
+        Err(error) => {
+            tracing::error!(%error, "failed to render heartbeat prompt");
+            return;
+        }
+    };
+
+    let memory_bulletin = {
+        let bulletin = deps.runtime_config.memory_bulletin.load();
+        if bulletin.is_empty() {
+            None
+        } else {
+            Some(bulletin.as_ref().clone())
+        }
+    };
+    let cron_jobs = gather_cron_jobs(deps).await;
+    let workers = gather_running_workers(deps).await;
+    let events = gather_upcoming_events(deps);
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M UTC").to_string();
+
+    let survey = match prompt_engine.render_system_heartbeat_survey(
+        &now,
+        memory_bulletin.as_deref(),
+        &cron_jobs,
+        &workers,
+        events.as_deref(),
+    ) {
+        Ok(survey) => survey,
+        Err(error) => {
+            tracing::error!(%error, "failed to render heartbeat survey");
+            return;
+        }
+    };
+
+    let routing = deps.runtime_config.routing.load();
+    let model_name = routing.resolve(ProcessType::Heartbeat, None).to_string();
+    let model = SpacebotModel::make(&deps.llm_manager, &model_name)
+        .with_context(&*deps.agent_id, "heartbeat")
+        .with_routing((**routing).clone());
+
+    // No tools — the survey is gathered up front.
+    let agent = AgentBuilder::new(model).preamble(&system_prompt).build();
+
+    let response = match agent.prompt(&survey).await {
+        Ok(response) => response,
+        Err(error) => {
+            tracing::warn!(%error, "heartbeat completion failed");
+            return;
+        }
+    };
+
+    let Some(message) = check_in_message(&response) else {
+        tracing::debug!("heartbeat had nothing to raise");
+        return;
+    };
+
+    match messaging_manager
+        .broadcast(
+            &target.adapter,
+            &target.target,
+            OutboundResponse::Text(message.to_string()),
+        )
+        .await
+    {
+        Ok(()) => tracing::info!(target = %target, "heartbeat delivered"),
+        Err(error) => tracing::warn!(%error, target = %target, "failed to deliver heartbeat"),
+    }
+}
+
+/// The message to send, or `None` when the LLM chose to stay quiet.
+fn check_in_message(response: &str) -> Option<&str> {
+    let message = response.trim();
+    if message.is_empty() || message.eq_ignore_ascii_case(SKIP_MARKER) {
+        None
+    } else {
+        Some(message)
+    }
+}
+
+async fn gather_cron_jobs(deps: &AgentDeps) -> Vec<String> {
+    let store = deps.runtime_config.cron_store.load();
+    let Some(store) = store.as_ref() else {
+        return Vec::new();
+    };
+
+    match store.load_all().await {
+        Ok(configs) => configs.iter().map(describe_cron_job).collect(),
+        Err(error) => {
+            tracing::warn!(%error, "failed to load cron jobs for heartbeat");
+            Vec::new()
+        }
+    }
+}
+
+fn describe_cron_job(config: &CronConfig) -> String {
+    let schedule = match &config.cron_expr {
+        Some(expression) => format!("`{expression}`"),
+        None => format!("every {}s", config.interval_secs),
+    };
+    let mut prompt: String = config.prompt.chars().take(MAX_CRON_PROMPT_CHARS).collect();
+    if prompt.len() < config.prompt.len() {
+        prompt.push_str("...");
+    }
+    format!(
+        "{} ({schedule}, to {}): {prompt}",
+        config.id, config.delivery_target
+    )
+}
+
+async fn gather_running_workers(deps: &AgentDeps) -> Vec<String> {
+    let logger = ProcessRunLogger::new(deps.sqlite_pool.clone());
+    match logger
+        .list_worker_runs(&deps.agent_id, MAX_SURVEY_WORKERS, 0, Some("running"))
+        .await
+    {
+        Ok((runs, _)) => runs
+            .into_iter()
+            .map(|run| {
+                let channel = run
+                    .channel_name
+                    .or(run.channel_id)
+                    .unwrap_or_else(|| "no channel".into());
+                format!("{} (in {channel}, since {})", run.task, run.started_at)
+            })
+            .collect(),
+        Err(error) => {
+            tracing::warn!(%error, "failed to list workers for heartbeat");
+            Vec::new()
+        }
+    }
+}
+
+/// Upcoming events from the last calendar sync, in the calendar's timezone,
+/// or `None` when no calendar is configured.
+fn gather_upcoming_events(deps: &AgentDeps) -> Option<Vec<String>> {
+    let calendar = deps.runtime_config.calendar.load();
+    if !calendar.is_configured() {
+        return None;
+    }
+    let tz = calendar.tz();
+    let now = chrono::Utc::now();
+    Some(
+        deps.runtime_config
+            .upcoming_events
+            .load()
+            .iter()
+            .filter(|event| event.end > now)
+            .map(|event| event.describe(tz))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skip_marker_means_no_check_in() {
+        assert_eq!(check_in_message("  [skip]\n"), None);
+        assert_eq!(check_in_message("[SKIP]"), None);
+        assert_eq!(check_in_message(""), None);
+        assert_eq!(
+            check_in_message("Your deploy window opens tomorrow.\n"),
+            Some("Your deploy window opens tomorrow.")
+        );
+    }
+
+    #[test]
+    fn cron_job_description_truncates_long_prompts() {
+        let config = CronConfig {
+            id: "digest".into(),
+            prompt: "x".repeat(500),
+            interval_secs: 3600,
+            cron_expr: None,
+            delivery_target: "discord:1".into(),
+            active_hours: None,
+            enabled: true,
+            run_once: false,
+            timeout_secs: None,
+            stateful: false,
+        };
+        let description = describe_cron_job(&config);
+        assert!(description.starts_with("digest (every 3600s, to discord:1): "));
+        assert!(description.ends_with("..."));
+    }
+}
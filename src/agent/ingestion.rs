@@ -473,10 +473,15 @@ async fn process_chunk(
     let channel_store = crate::conversation::ChannelStore::new(deps.sqlite_pool.clone());
     let tool_server: ToolServerHandle = crate::tools::create_branch_tool_server(
         deps.memory_search.clone(),
+        None,
+        None,
+        deps.knowledge.clone(),
         conversation_logger,
         channel_store,
         crate::conversation::ProcessRunLogger::new(deps.sqlite_pool.clone()),
         &deps.agent_id,
+        crate::tools::web_fetch_cache(),
+        crate::tools::OutputSpill::new(deps.sqlite_pool.clone(), deps.runtime_config.clone()),
     );
 
     let agent = AgentBuilder::new(model)
@@ -0,0 +1,286 @@
+//! Inbound message lanes for a channel.
+//!
+//! A channel reads from three queues. Re-triggers the channel sends itself
+//! go on an unbounded system lane, so a result is never dropped because the
+//! channel is busy. Direct messages and messages that mention the bot go on
+//! the priority lane, and everything else on the normal lane. The channel
+//! always drains system before priority before normal, so a mention isn't
+//! stuck behind a backlog of chatter.
+
+use crate::InboundMessage;
+
+use tokio::sync::mpsc;
+
+/// Capacity of the priority and normal lanes.
+pub const LANE_CAPACITY: usize = 64;
+
+/// Which lane a message travels on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lane {
+    System,
+    Priority,
+    Normal,
+}
+
+impl Lane {
+    /// Pick the lane for an inbound message.
+    pub fn of(message: &InboundMessage) -> Self {
+        if message.source == "system" {
+            Self::System
+        } else if is_direct_message(message) || mentions_bot(message) {
+            Self::Priority
+        } else {
+            Self::Normal
+        }
+    }
+}
+
+/// Whether the message was sent to the bot one-to-one rather than in a
+/// shared channel.
+pub fn is_direct_message(message: &InboundMessage) -> bool {
+    let get_str = |key: &str| message.metadata.get(key).and_then(|value| value.as_str());
+    match message.source.as_str() {
+        "discord" => !message.metadata.contains_key("discord_guild_id"),
+        "slack" => get_str("slack_channel_id").is_some_and(|id| id.starts_with('D')),
+        "telegram" => get_str("telegram_chat_type") == Some("private"),
+        "webchat" | "cli" => true,
+        _ => message.conversation_id.contains(":dm:"),
+    }
+}
+
+/// Whether a conversation is one-to-one with the bot, judged from its ID
+/// alone, for callers that no longer have the message's metadata. Email
+/// threads count as private, and so do the operator's own web chat and
+/// terminal sessions.
+pub fn is_direct_conversation(conversation_id: &str) -> bool {
+    let mut parts = conversation_id.split(':');
+    let platform = parts.next().unwrap_or_default();
+    match platform {
+        "discord" => parts.next() == Some("dm"),
+        // slack:{team}:{channel}[:{thread}], where DM channel IDs start with D.
+        "slack" => parts.nth(1).is_some_and(|channel| channel.starts_with('D')),
+        // Private chat IDs are the user's ID; groups and channels are negative.
+        "telegram" => parts
+            .next()
+            .and_then(|chat_id| chat_id.parse::<i64>().ok())
+            .is_some_and(|chat_id| chat_id > 0),
+        "email" | "portal" | "cli" => true,
+        _ => conversation_id.contains(":dm:"),
+    }
+}
+
+/// Whether the message mentions the bot or replies to it.
+pub fn mentions_bot(message: &InboundMessage) -> bool {
+    [
+        "discord_mentions_or_replies_to_bot",
+        "slack_mentions_bot",
+        "telegram_mentions_or_replies_to_bot",
+    ]
+    .iter()
+    .any(|key| {
+        message
+            .metadata
+            .get(*key)
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false)
+    })
+}
+
+/// Create the lanes for a new channel.
+pub fn lanes() -> (
+    ChannelSender,
+    mpsc::UnboundedSender<InboundMessage>,
+    ChannelReceiver,
+) {
+    let (system_tx, system_rx) = mpsc::unbounded_channel();
+    let (priority_tx, priority_rx) = mpsc::channel(LANE_CAPACITY);
+    let (normal_tx, normal_rx) = mpsc::channel(LANE_CAPACITY);
+    let sender = ChannelSender {
+        system: system_tx.clone(),
+        priority: priority_tx,
+        normal: normal_tx,
+    };
+    let receiver = ChannelReceiver {
+        system: system_rx,
+        priority: priority_rx,
+        normal: normal_rx,
+    };
+    (sender, system_tx, receiver)
+}
+
+/// Sending half of a channel's lanes, used to route inbound messages to it.
+#[derive(Debug, Clone)]
+pub struct ChannelSender {
+    system: mpsc::UnboundedSender<InboundMessage>,
+    priority: mpsc::Sender<InboundMessage>,
+    normal: mpsc::Sender<InboundMessage>,
+}
+
+impl ChannelSender {
+    /// Send a message on its lane, waiting for room if that lane is full.
+    pub async fn send(
+        &self,
+        message: InboundMessage,
+    ) -> Result<(), mpsc::error::SendError<InboundMessage>> {
+        match Lane::of(&message) {
+            Lane::System => self.system.send(message),
+            Lane::Priority => self.priority.send(message).await,
+            Lane::Normal => self.normal.send(message).await,
+        }
+    }
+
+    /// Whether the channel has stopped receiving messages.
+    pub fn is_closed(&self) -> bool {
+        self.normal.is_closed()
+    }
+}
+
+/// Receiving half of a channel's lanes.
+#[derive(Debug)]
+pub struct ChannelReceiver {
+    system: mpsc::UnboundedReceiver<InboundMessage>,
+    priority: mpsc::Receiver<InboundMessage>,
+    normal: mpsc::Receiver<InboundMessage>,
+}
+
+impl ChannelReceiver {
+    /// Receive the next message, from the most urgent lane that has one.
+    /// Returns `None` once every lane is closed and empty.
+    ///
+    /// Cancel safe, like the receivers it wraps.
+    pub async fn recv(&mut self) -> Option<InboundMessage> {
+        tokio::select! {
+            biased;
+            Some(message) = self.system.recv() => Some(message),
+            Some(message) = self.priority.recv() => Some(message),
+            Some(message) = self.normal.recv() => Some(message),
+            else => None,
+        }
+    }
+
+    /// Take the next queued message without waiting, in lane order.
+    pub fn try_recv(&mut self) -> Option<InboundMessage> {
+        self.system
+            .try_recv()
+            .or_else(|_| self.priority.try_recv())
+            .or_else(|_| self.normal.try_recv())
+            .ok()
+    }
+
+    /// Stop accepting messages on every lane. Queued messages can still be
+    /// received.
+    pub fn close(&mut self) {
+        self.system.close();
+        self.priority.close();
+        self.normal.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn message(id: &str, source: &str, metadata: serde_json::Value) -> InboundMessage {
+        let metadata: HashMap<String, serde_json::Value> =
+            serde_json::from_value(metadata).unwrap();
+        InboundMessage {
+            id: id.into(),
+            metadata,
+            ..crate::testing::inbound_message(source, "alice", "hi")
+        }
+    }
+
+    #[test]
+    fn mentions_and_direct_messages_take_the_priority_lane() {
+        let guild = serde_json::json!({"discord_guild_id": 1});
+        let mention = serde_json::json!({
+            "discord_guild_id": 1,
+            "discord_mentions_or_replies_to_bot": true,
+        });
+
+        assert_eq!(Lane::of(&message("1", "discord", guild)), Lane::Normal);
+        assert_eq!(Lane::of(&message("2", "discord", mention)), Lane::Priority);
+        assert_eq!(
+            Lane::of(&message("3", "discord", serde_json::json!({}))),
+            Lane::Priority
+        );
+        assert_eq!(
+            Lane::of(&message(
+                "4",
+                "telegram",
+                serde_json::json!({"telegram_chat_type": "group"})
+            )),
+            Lane::Normal
+        );
+        assert_eq!(
+            Lane::of(&message("5", "system", serde_json::json!({}))),
+            Lane::System
+        );
+    }
+
+    #[test]
+    fn direct_conversations_are_recognized_from_their_ids() {
+        use crate::memory::MemoryScope;
+
+        for direct in [
+            "discord:dm:190291964875374603",
+            "slack:T012AB3C4:D024BE91L",
+            "slack:T012AB3C4:D024BE91L:1712345678.000100",
+            "telegram:123456789",
+            "email:thread-8f2c",
+            "portal:chat:main",
+            "cli:main",
+        ] {
+            assert!(is_direct_conversation(direct), "{direct}");
+            assert_eq!(
+                MemoryScope::default_for(Some(direct)),
+                MemoryScope::Conversation,
+                "{direct}"
+            );
+        }
+        for shared in [
+            "discord:1100:1200",
+            "slack:T012AB3C4:C024BE91L",
+            "telegram:-1001234567890",
+            "twitch:somechannel",
+            "webhook:orders",
+        ] {
+            assert!(!is_direct_conversation(shared), "{shared}");
+            assert_eq!(
+                MemoryScope::default_for(Some(shared)),
+                MemoryScope::Agent,
+                "{shared}"
+            );
+        }
+        assert_eq!(MemoryScope::default_for(None), MemoryScope::Agent);
+    }
+
+    #[tokio::test]
+    async fn urgent_lanes_are_drained_first_and_system_never_blocks() {
+        let (sender, system_tx, mut receiver) = lanes();
+        let chatter = serde_json::json!({"discord_guild_id": 1});
+        for index in 0..LANE_CAPACITY {
+            sender
+                .send(message(
+                    &format!("chatter-{index}"),
+                    "discord",
+                    chatter.clone(),
+                ))
+                .await
+                .unwrap();
+        }
+        sender
+            .send(message("dm", "discord", serde_json::json!({})))
+            .await
+            .unwrap();
+        // The normal lane is full; the channel's own re-trigger still goes through.
+        system_tx
+            .send(message("retrigger", "system", serde_json::json!({})))
+            .unwrap();
+
+        assert_eq!(receiver.recv().await.unwrap().id, "retrigger");
+        assert_eq!(receiver.try_recv().unwrap().id, "dm");
+        assert_eq!(receiver.recv().await.unwrap().id, "chatter-0");
+    }
+}
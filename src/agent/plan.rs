@@ -0,0 +1,231 @@
+//! Plan mode: hold back destructive tool calls until the user approves them.
+//!
+//! While a channel turn runs in plan mode (`plan_mode.enabled` in config, or
+//! a message starting with `!plan`), workers spawned during the turn don't
+//! run shell or exec commands or file writes. Their hook records each such
+//! call in the channel's [`Plan`] and tells the model it was held back, so
+//! the worker finishes by describing what it would have done. The recorded
+//! calls are appended to the worker's result for the channel to present. A
+//! later `!approve_plan` message runs them in order on a fresh worker,
+//! without an LLM.
+
+use crate::WorkerId;
+use crate::agent::worker::WorkerResult;
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Message prefix that runs one turn in plan mode.
+pub const PLAN_COMMAND: &str = "!plan";
+
+/// Message that runs the pending plan.
+pub const APPROVE_COMMAND: &str = "!approve_plan";
+
+/// Tools always held back in plan mode. The file tool is held back for
+/// writes only.
+const DESTRUCTIVE_TOOLS: &[&str] = &["shell", "exec"];
+
+/// Arguments longer than this are cut when the plan is shown.
+const MAX_RENDERED_ARGS: usize = 500;
+
+/// A plan mode message command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanCommand {
+    /// `!plan <request>`: handle the request in plan mode.
+    Plan(String),
+    /// `!approve_plan`: run the pending plan.
+    Approve,
+}
+
+/// Parse a plan mode command from the start of a message.
+pub fn parse_command(text: &str) -> Option<PlanCommand> {
+    let text = text.trim();
+    if text == APPROVE_COMMAND {
+        return Some(PlanCommand::Approve);
+    }
+    let rest = text.strip_prefix(PLAN_COMMAND)?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(PlanCommand::Plan(rest.trim().to_string()))
+}
+
+/// Whether plan mode holds back a call to `tool_name` with `args`.
+/// `extra_tools` are held back in addition to the built-in set.
+pub fn is_destructive(tool_name: &str, args: &str, extra_tools: &[String]) -> bool {
+    if DESTRUCTIVE_TOOLS.contains(&tool_name) || extra_tools.iter().any(|tool| tool == tool_name) {
+        return true;
+    }
+    if tool_name == "file" {
+        // Unparseable arguments are held back too; reads always parse.
+        return serde_json::from_str::<serde_json::Value>(args)
+            .ok()
+            .and_then(|value| {
+                value
+                    .get("operation")
+                    .and_then(|operation| operation.as_str())
+                    .map(|operation| operation != "read" && operation != "list")
+            })
+            .unwrap_or(true);
+    }
+    false
+}
+
+/// A tool call recorded instead of run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedCall {
+    pub worker_id: WorkerId,
+    pub tool_name: String,
+    /// The call's JSON arguments, exactly as the model sent them.
+    pub args: String,
+}
+
+/// A channel's plan mode state: whether the current turn plans, and the
+/// calls recorded and waiting for approval.
+#[derive(Debug, Default)]
+pub struct Plan {
+    active: AtomicBool,
+    extra_tools: Mutex<Vec<String>>,
+    calls: Mutex<Vec<PlannedCall>>,
+}
+
+impl Plan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether workers spawned from now on plan, and which tools they
+    /// hold back besides the built-in set.
+    pub fn begin_turn(&self, active: bool, extra_tools: &[String]) {
+        self.active.store(active, Ordering::Relaxed);
+        *self.extra_tools.lock().expect("plan lock poisoned") = extra_tools.to_vec();
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    /// Whether a call to `tool_name` with `args` is held back.
+    pub fn holds(&self, tool_name: &str, args: &str) -> bool {
+        let extra_tools = self.extra_tools.lock().expect("plan lock poisoned");
+        is_destructive(tool_name, args, &extra_tools)
+    }
+
+    /// Record a held back call. Returns its step number among the worker's
+    /// steps.
+    pub fn record(&self, worker_id: WorkerId, tool_name: &str, args: &str) -> usize {
+        let mut calls = self.calls.lock().expect("plan lock poisoned");
+        calls.push(PlannedCall {
+            worker_id,
+            tool_name: tool_name.to_string(),
+            args: args.to_string(),
+        });
+        calls
+            .iter()
+            .filter(|call| call.worker_id == worker_id)
+            .count()
+    }
+
+    /// Drop the calls one worker recorded, e.g. before it is retried.
+    pub fn forget(&self, worker_id: WorkerId) {
+        self.calls
+            .lock()
+            .expect("plan lock poisoned")
+            .retain(|call| call.worker_id != worker_id);
+    }
+
+    /// Drop every pending call.
+    pub fn clear(&self) {
+        self.calls.lock().expect("plan lock poisoned").clear();
+    }
+
+    /// Take the pending calls for execution.
+    pub fn take(&self) -> Vec<PlannedCall> {
+        std::mem::take(&mut *self.calls.lock().expect("plan lock poisoned"))
+    }
+
+    /// Append the calls `worker_id` recorded to its result, so the channel
+    /// can show the user what approving would run.
+    pub fn annotate(&self, worker_id: WorkerId, mut result: WorkerResult) -> WorkerResult {
+        let calls = self.calls.lock().expect("plan lock poisoned");
+        let steps: Vec<&PlannedCall> = calls
+            .iter()
+            .filter(|call| call.worker_id == worker_id)
+            .collect();
+        if !steps.is_empty() {
+            result.output.push_str(&format!(
+                "\n\nProposed plan (nothing below has run). The user sends `{APPROVE_COMMAND}` to run it:"
+            ));
+            for (index, call) in steps.iter().enumerate() {
+                result.output.push_str(&format!(
+                    "\n{}. {}: {}",
+                    index + 1,
+                    call.tool_name,
+                    crate::tools::truncate_output(&call.args, MAX_RENDERED_ARGS)
+                ));
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_are_parsed() {
+        assert_eq!(
+            parse_command("!plan clean up the build dir"),
+            Some(PlanCommand::Plan("clean up the build dir".into()))
+        );
+        assert_eq!(
+            parse_command("  !approve_plan "),
+            Some(PlanCommand::Approve)
+        );
+        assert_eq!(parse_command("!planet facts"), None);
+        assert_eq!(parse_command("what's the plan?"), None);
+    }
+
+    #[test]
+    fn only_destructive_calls_are_held() {
+        let extra = vec!["github_create_issue".to_string()];
+        assert!(is_destructive("shell", r#"{"command":"ls"}"#, &[]));
+        assert!(is_destructive(
+            "file",
+            r#"{"operation":"write","path":"a"}"#,
+            &[]
+        ));
+        assert!(!is_destructive(
+            "file",
+            r#"{"operation":"read","path":"a"}"#,
+            &[]
+        ));
+        assert!(!is_destructive("web_fetch", "{}", &[]));
+        assert!(is_destructive("github_create_issue", "{}", &extra));
+    }
+
+    #[test]
+    fn plan_is_annotated_per_worker() {
+        let plan = Plan::new();
+        let (first, second) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        assert_eq!(plan.record(first, "shell", r#"{"command":"make"}"#), 1);
+        assert_eq!(plan.record(second, "exec", "{}"), 1);
+        assert_eq!(plan.record(first, "shell", r#"{"command":"make test"}"#), 2);
+
+        let result = plan.annotate(first, WorkerResult::success("would run make"));
+        assert!(result.output.contains("1. shell: {\"command\":\"make\"}"));
+        assert!(!result.output.contains("exec"));
+        assert!(
+            result
+                .output
+                .contains("2. shell: {\"command\":\"make test\"}")
+        );
+
+        plan.forget(first);
+        let calls = plan.take();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].worker_id, second);
+        assert!(plan.take().is_empty());
+    }
+}
@@ -3,8 +3,11 @@
 use crate::{BranchId, ProcessEvent, ProcessId, WorkerId};
 use chrono::{DateTime, Utc};
 
+/// How long the status block reports degraded mode after the last model fallback.
+const DEGRADED_MODE_SECS: i64 = 300;
+
 /// Live status block injected into channel context.
-#[derive(Debug, Clone, Default, serde::Serialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct StatusBlock {
     /// Currently running branches.
     pub active_branches: Vec<BranchStatus>,
@@ -14,10 +17,12 @@ pub struct StatusBlock {
     pub completed_items: Vec<CompletedItem>,
     /// Active link conversations with other agents.
     pub active_link_conversations: Vec<LinkConversationStatus>,
+    /// Most recent model fallback in this channel, if any.
+    pub model_fallback: Option<ModelFallbackStatus>,
 }
 
 /// Status of an active branch.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BranchStatus {
     pub id: BranchId,
     pub started_at: DateTime<Utc>,
@@ -25,7 +30,7 @@ pub struct BranchStatus {
 }
 
 /// Status of an active worker.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct WorkerStatus {
     pub id: WorkerId,
     pub task: String,
@@ -33,10 +38,16 @@ pub struct WorkerStatus {
     pub started_at: DateTime<Utc>,
     pub notify_on_complete: bool,
     pub tool_calls: usize,
+    /// Latest progress step reported by the worker's tool calls.
+    pub step: usize,
+    pub step_detail: Option<String>,
+    /// Current attempt (1-based) once the worker has been retried.
+    #[serde(default)]
+    pub attempt: Option<(usize, usize)>,
 }
 
 /// Recently completed work item.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CompletedItem {
     pub id: String,
     pub item_type: CompletedItemType,
@@ -46,15 +57,24 @@ pub struct CompletedItem {
 }
 
 /// Status of an active link conversation.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct LinkConversationStatus {
     pub peer_agent: String,
     pub started_at: DateTime<Utc>,
     pub turn_count: u32,
 }
 
+/// A model call that was answered by a fallback model.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ModelFallbackStatus {
+    pub from_model: String,
+    pub to_model: String,
+    pub reason: String,
+    pub at: DateTime<Utc>,
+}
+
 /// Type of completed item.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum CompletedItemType {
     Branch,
     Worker,
@@ -77,6 +97,48 @@ impl StatusBlock {
                     worker.status.clone_from(status);
                 }
             }
+            ProcessEvent::WorkerQueued {
+                worker_id,
+                position,
+                ..
+            } => {
+                if let Some(worker) = self.active_workers.iter_mut().find(|w| w.id == *worker_id) {
+                    worker.status = format!("queued (position {position})");
+                }
+            }
+            ProcessEvent::WorkerRetrying {
+                worker_id,
+                attempt,
+                max_attempts,
+                reason,
+                delay_ms,
+                ..
+            } => {
+                if let Some(worker) = self.active_workers.iter_mut().find(|w| w.id == *worker_id) {
+                    worker.attempt = Some((*attempt, *max_attempts));
+                    worker.status =
+                        format!("retrying after {reason} in {}s", delay_ms.div_ceil(1000));
+                    worker.step = 0;
+                    worker.step_detail = None;
+                }
+            }
+            ProcessEvent::WorkerStarted { worker_id, .. } => {
+                if let Some(worker) = self.active_workers.iter_mut().find(|w| w.id == *worker_id) {
+                    worker.status = "starting".to_string();
+                    worker.started_at = Utc::now();
+                }
+            }
+            ProcessEvent::WorkerProgress {
+                worker_id,
+                step,
+                detail,
+                ..
+            } => {
+                if let Some(worker) = self.active_workers.iter_mut().find(|w| w.id == *worker_id) {
+                    worker.step = *step;
+                    worker.step_detail = Some(detail.clone());
+                }
+            }
             ProcessEvent::WorkerComplete {
                 worker_id,
                 result,
@@ -93,7 +155,7 @@ impl StatusBlock {
                             item_type: CompletedItemType::Worker,
                             description: worker.task,
                             completed_at: Utc::now(),
-                            result_summary: result.clone(),
+                            result_summary: result.summary.clone(),
                         });
                     }
                 }
@@ -128,6 +190,41 @@ impl StatusBlock {
                     self.completed_items.remove(0);
                 }
             }
+            ProcessEvent::BranchTimeout {
+                branch_id,
+                timeout_secs,
+                ..
+            } => {
+                if let Some(pos) = self.active_branches.iter().position(|b| b.id == *branch_id) {
+                    let branch = self.active_branches.remove(pos);
+                    self.completed_items.push(CompletedItem {
+                        id: branch_id.to_string(),
+                        item_type: CompletedItemType::Branch,
+                        description: branch.description,
+                        completed_at: Utc::now(),
+                        result_summary: format!(
+                            "timed out after {timeout_secs}s without a conclusion"
+                        ),
+                    });
+                }
+
+                if self.completed_items.len() > 10 {
+                    self.completed_items.remove(0);
+                }
+            }
+            ProcessEvent::ModelFallback {
+                from_model,
+                to_model,
+                reason,
+                ..
+            } => {
+                self.model_fallback = Some(ModelFallbackStatus {
+                    from_model: from_model.clone(),
+                    to_model: to_model.clone(),
+                    reason: reason.clone(),
+                    at: Utc::now(),
+                });
+            }
             ProcessEvent::AgentMessageSent { to_agent_id, .. } => {
                 self.track_link_conversation(to_agent_id.as_ref());
             }
@@ -153,6 +250,9 @@ impl StatusBlock {
             started_at: Utc::now(),
             notify_on_complete,
             tool_calls: 0,
+            step: 0,
+            step_detail: None,
+            attempt: None,
         });
     }
 
@@ -160,6 +260,19 @@ impl StatusBlock {
     pub fn render(&self) -> String {
         let mut output = String::new();
 
+        // Degraded mode: recent calls had to fall back to another model
+        if let Some(fallback) = &self.model_fallback
+            && (Utc::now() - fallback.at).num_seconds() < DEGRADED_MODE_SECS
+        {
+            output.push_str("## Degraded Mode\n");
+            output.push_str(&format!(
+                "- {} is unavailable; calls are falling back to {} (last at {})\n\n",
+                fallback.from_model,
+                fallback.to_model,
+                fallback.at.format("%H:%M"),
+            ));
+        }
+
         // Active workers
         if !self.active_workers.is_empty() {
             output.push_str("## Active Workers\n");
@@ -169,13 +282,25 @@ impl StatusBlock {
                 } else {
                     String::new()
                 };
+                let step_str = match &worker.step_detail {
+                    Some(detail) => format!(" [step {}: {}]", worker.step, detail),
+                    None => String::new(),
+                };
+                let attempt_str = match worker.attempt {
+                    Some((attempt, max_attempts)) => {
+                        format!(", attempt {attempt}/{max_attempts}")
+                    }
+                    None => String::new(),
+                };
                 output.push_str(&format!(
-                    "- [{}] {} ({}{}): {}\n",
+                    "- [{}] {} ({}{}{}): {}{}\n",
                     worker.id,
                     worker.task,
                     worker.started_at.format("%H:%M"),
                     tool_calls_str,
-                    worker.status
+                    attempt_str,
+                    worker.status,
+                    step_str
                 ));
             }
             output.push('\n');
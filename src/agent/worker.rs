@@ -1,17 +1,30 @@
 //! Worker: Independent task execution process.
 
+use crate::agent::approval::Approvals;
 use crate::agent::compactor::estimate_history_tokens;
-use crate::config::BrowserConfig;
+use crate::agent::cost::CostTracker;
+use crate::agent::plan::{Plan, PlannedCall};
+use crate::agent::worker_workspace::WorkerWorkspace;
+use crate::auth::AuthTier;
+use crate::config::{BrowserConfig, WebSearchConfig, WorkerPreset};
+use crate::conversation::ToolAuditLog;
 use crate::error::Result;
 use crate::hooks::SpacebotHook;
 use crate::llm::SpacebotModel;
 use crate::llm::routing::is_context_overflow_error;
-use crate::{AgentDeps, ChannelId, ProcessId, ProcessType, WorkerId};
+use crate::media::MediaSource;
+use crate::tools::{WebFetchCache, web_fetch_cache};
+use crate::{AgentDeps, ChannelId, ProcessEvent, ProcessId, ProcessType, TurnId, WorkerId};
 use rig::agent::AgentBuilder;
 use rig::completion::{CompletionModel, Prompt};
+use rig::tool::Tool as _;
+use rig::tool::server::ToolServerHandle;
 use std::fmt::Write as _;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{mpsc, watch};
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 /// How many turns per segment before we check context and potentially compact.
@@ -39,6 +52,101 @@ pub enum WorkerState {
     Failed,
 }
 
+/// Max characters kept in a `WorkerResult` summary.
+const SUMMARY_MAX_CHARS: usize = 200;
+
+/// Output kept per step when reporting an approved plan's results.
+const PLAN_STEP_OUTPUT_BYTES: usize = 2_000;
+
+/// How a worker's task ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkerResultStatus {
+    /// The task ran to completion.
+    Success,
+    /// The worker stopped before finishing (e.g. it ran out of segments) and
+    /// returned whatever it had.
+    Partial,
+    /// The worker errored out or was cancelled.
+    Failed,
+}
+
+/// Something a worker produced that outlives its transcript.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WorkerArtifact {
+    /// A file the worker wrote. `media_id` is the stored copy, which
+    /// outlives the worker's workspace.
+    File {
+        path: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        media_id: Option<String>,
+    },
+    /// A URL the worker reported in its output.
+    Url { url: String },
+}
+
+/// The outcome of a worker run, carried by `ProcessEvent::WorkerComplete`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WorkerResult {
+    pub status: WorkerResultStatus,
+    /// One-line description of the outcome, for status displays and signals.
+    pub summary: String,
+    pub artifacts: Vec<WorkerArtifact>,
+    /// The worker's full final response.
+    pub output: String,
+    /// The output already reached the channel as an interactive worker's
+    /// `WorkerResponse`, so completion doesn't report it a second time.
+    #[serde(default)]
+    pub delivered: bool,
+}
+
+impl WorkerResult {
+    pub fn new(status: WorkerResultStatus, output: impl Into<String>) -> Self {
+        let output = output.into();
+        Self {
+            status,
+            summary: summarize(&output),
+            artifacts: url_artifacts(&output),
+            output,
+            delivered: false,
+        }
+    }
+
+    pub fn success(output: impl Into<String>) -> Self {
+        Self::new(WorkerResultStatus::Success, output)
+    }
+
+    pub fn partial(output: impl Into<String>) -> Self {
+        Self::new(WorkerResultStatus::Partial, output)
+    }
+
+    pub fn failed(output: impl Into<String>) -> Self {
+        Self::new(WorkerResultStatus::Failed, output)
+    }
+
+    /// Record whether the output was already delivered to the channel.
+    pub fn mark_delivered(mut self, delivered: bool) -> Self {
+        self.delivered = delivered;
+        self
+    }
+
+    /// Add artifacts not already listed.
+    pub fn with_artifacts(mut self, artifacts: impl IntoIterator<Item = WorkerArtifact>) -> Self {
+        for artifact in artifacts {
+            if !self.artifacts.contains(&artifact) {
+                self.artifacts.push(artifact);
+            }
+        }
+        self
+    }
+
+    /// Whether the worker produced a usable result. Partial results count.
+    pub fn succeeded(&self) -> bool {
+        self.status != WorkerResultStatus::Failed
+    }
+}
+
 /// A worker process that executes tasks independently.
 pub struct Worker {
     pub id: WorkerId,
@@ -55,13 +163,36 @@ pub struct Worker {
     pub browser_config: BrowserConfig,
     /// Directory for browser screenshots.
     pub screenshot_dir: PathBuf,
-    /// Brave Search API key for web search tool.
-    pub brave_search_key: Option<String>,
+    /// Backend for the web search tool, when one is configured.
+    pub web_search: Option<WebSearchConfig>,
     /// Directory for writing execution logs on failure.
     pub logs_dir: PathBuf,
     /// Status updates.
     pub status_tx: watch::Sender<String>,
     pub status_rx: watch::Receiver<String>,
+    /// Cancelled by the channel to stop the worker cooperatively: the current
+    /// LLM call is dropped and the transcript is still persisted.
+    pub cancel_token: CancellationToken,
+    /// Page cache for `web_fetch`, shared with the spawning channel when set
+    /// via `with_web_fetch_cache`.
+    pub web_fetch_cache: WebFetchCache,
+    /// Dedicated working directory, removed when the worker is dropped
+    /// unless retained. `None` runs tools from the agent workspace. Shared
+    /// between retry attempts so they pick up where the last one left off.
+    pub workspace: Option<Arc<WorkerWorkspace>>,
+    /// Model to run on instead of the routed worker model.
+    pub model: Option<String>,
+    /// Tools the worker may use, on top of the tool policy. Empty means
+    /// every tool the policy permits.
+    pub tools: Vec<String>,
+    /// Tier of the sender the worker runs for. Tools that need more than
+    /// `spawn_worker` permission, like the Coolify ones, check it.
+    pub sender_tier: AuthTier,
+    /// The spawning channel's tracker; the worker's spend counts toward its
+    /// budget.
+    pub cost_tracker: Option<CostTracker>,
+    /// The channel turn that spawned the worker, stamped on its events.
+    pub turn_id: Option<TurnId>,
 }
 
 impl Worker {
@@ -74,17 +205,28 @@ impl Worker {
         deps: AgentDeps,
         browser_config: BrowserConfig,
         screenshot_dir: PathBuf,
-        brave_search_key: Option<String>,
+        web_search: Option<WebSearchConfig>,
         logs_dir: PathBuf,
     ) -> Self {
         let id = Uuid::new_v4();
         let process_id = ProcessId::Worker(id);
+        let cancel_token = CancellationToken::new();
         let hook = SpacebotHook::new(
             deps.agent_id.clone(),
             process_id,
             ProcessType::Worker,
             channel_id.clone(),
             deps.event_tx.clone(),
+        )
+        .with_cancellation(cancel_token.clone())
+        .with_tool_audit(ToolAuditLog::new(deps.sqlite_pool.clone()))
+        .with_tool_call_repairs(**deps.runtime_config.tool_call_repairs.load())
+        .with_approval_tools(
+            deps.runtime_config
+                .tool_policy
+                .load()
+                .requires_approval
+                .clone(),
         );
         let (status_tx, status_rx) = watch::channel("starting".to_string());
 
@@ -99,10 +241,18 @@ impl Worker {
             input_rx: None,
             browser_config,
             screenshot_dir,
-            brave_search_key,
+            web_search,
             logs_dir,
             status_tx,
             status_rx,
+            cancel_token,
+            web_fetch_cache: web_fetch_cache(),
+            workspace: None,
+            model: None,
+            tools: Vec::new(),
+            sender_tier: AuthTier::Admin,
+            cost_tracker: None,
+            turn_id: None,
         }
     }
 
@@ -115,17 +265,28 @@ impl Worker {
         deps: AgentDeps,
         browser_config: BrowserConfig,
         screenshot_dir: PathBuf,
-        brave_search_key: Option<String>,
+        web_search: Option<WebSearchConfig>,
         logs_dir: PathBuf,
     ) -> (Self, mpsc::Sender<String>) {
         let id = Uuid::new_v4();
         let process_id = ProcessId::Worker(id);
+        let cancel_token = CancellationToken::new();
         let hook = SpacebotHook::new(
             deps.agent_id.clone(),
             process_id,
             ProcessType::Worker,
             channel_id.clone(),
             deps.event_tx.clone(),
+        )
+        .with_cancellation(cancel_token.clone())
+        .with_tool_audit(ToolAuditLog::new(deps.sqlite_pool.clone()))
+        .with_tool_call_repairs(**deps.runtime_config.tool_call_repairs.load())
+        .with_approval_tools(
+            deps.runtime_config
+                .tool_policy
+                .load()
+                .requires_approval
+                .clone(),
         );
         let (status_tx, status_rx) = watch::channel("starting".to_string());
         let (input_tx, input_rx) = mpsc::channel(32);
@@ -141,15 +302,218 @@ impl Worker {
             input_rx: Some(input_rx),
             browser_config,
             screenshot_dir,
-            brave_search_key,
+            web_search,
             logs_dir,
             status_tx,
             status_rx,
+            cancel_token,
+            web_fetch_cache: web_fetch_cache(),
+            workspace: None,
+            model: None,
+            tools: Vec::new(),
+            sender_tier: AuthTier::Admin,
+            cost_tracker: None,
+            turn_id: None,
         };
 
         (worker, input_tx)
     }
 
+    /// Share a `web_fetch` page cache, typically the spawning channel's.
+    pub fn with_web_fetch_cache(mut self, cache: WebFetchCache) -> Self {
+        self.web_fetch_cache = cache;
+        self
+    }
+
+    /// Give the worker a dedicated working directory. Shell and exec commands
+    /// start there, and the sandbox lets the worker write to it.
+    pub fn with_workspace(mut self, workspace: WorkerWorkspace) -> Self {
+        self.workspace = Some(Arc::new(workspace));
+        self
+    }
+
+    /// Run on behalf of a sender of `tier`. Workers default to admin, as
+    /// cron jobs and other internal sources are.
+    pub fn with_sender_tier(mut self, tier: AuthTier) -> Self {
+        self.sender_tier = tier;
+        self
+    }
+
+    /// Record destructive tool calls into `plan` instead of running them.
+    pub fn with_plan(mut self, plan: Arc<Plan>) -> Self {
+        self.hook = self.hook.with_plan(plan);
+        self
+    }
+
+    /// Ask for approval of calls to tools that require it through
+    /// `approvals`, which the spawning channel answers.
+    pub fn with_approvals(mut self, approvals: Arc<Approvals>) -> Self {
+        self.hook = self.hook.with_approvals(approvals);
+        self
+    }
+
+    /// Record the worker's token usage in the spawning channel's tracker.
+    pub fn with_cost_tracker(mut self, tracker: CostTracker) -> Self {
+        self.cost_tracker = Some(tracker);
+        self
+    }
+
+    /// Attribute the worker's events and model calls to the channel turn
+    /// that spawned it.
+    pub fn with_turn_id(mut self, turn_id: Option<TurnId>) -> Self {
+        self.turn_id = turn_id;
+        self.hook = self.hook.with_spawning_turn(turn_id);
+        self
+    }
+
+    /// Apply a worker preset: its instructions, tool allowlist, model, and
+    /// timeout. The timeout covers every retry attempt together.
+    pub fn with_preset(mut self, preset: &WorkerPreset) -> Self {
+        if let Some(prompt) = &preset.prompt {
+            self.system_prompt = format!("{}\n\n{prompt}", self.system_prompt);
+        }
+        self.tools = preset.tools.clone();
+        self.model = preset.model.clone();
+        match preset.timeout_secs {
+            Some(timeout_secs) => self.with_timeout(Duration::from_secs(timeout_secs)),
+            None => self,
+        }
+    }
+
+    /// Cancel the worker once `timeout` has passed, counting from now.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.hook = self
+            .hook
+            .with_deadline(tokio::time::Instant::now() + timeout);
+        self
+    }
+
+    /// A fresh copy of this worker for a retry attempt.
+    ///
+    /// Keeps the id, hook, cancellation token, and workspace so the retry
+    /// reports as the same worker and can be cancelled the same way. Interactive
+    /// workers can't be copied because their input channel has one receiver.
+    pub fn next_attempt(&self) -> Option<Self> {
+        if self.input_rx.is_some() {
+            return None;
+        }
+        let (status_tx, status_rx) = watch::channel("starting".to_string());
+        Some(Self {
+            id: self.id,
+            channel_id: self.channel_id.clone(),
+            task: self.task.clone(),
+            state: WorkerState::Running,
+            deps: self.deps.clone(),
+            hook: self.hook.clone(),
+            system_prompt: self.system_prompt.clone(),
+            input_rx: None,
+            browser_config: self.browser_config.clone(),
+            screenshot_dir: self.screenshot_dir.clone(),
+            web_search: self.web_search.clone(),
+            logs_dir: self.logs_dir.clone(),
+            status_tx,
+            status_rx,
+            cancel_token: self.cancel_token.clone(),
+            web_fetch_cache: self.web_fetch_cache.clone(),
+            workspace: self.workspace.clone(),
+            model: self.model.clone(),
+            tools: self.tools.clone(),
+            sender_tier: self.sender_tier,
+            cost_tracker: self.cost_tracker.clone(),
+            turn_id: self.turn_id,
+        })
+    }
+
+    /// Per-worker ToolServer with the task tools.
+    async fn tool_server(&self) -> ToolServerHandle {
+        let mcp_tools = self.deps.mcp_manager.get_tools().await;
+
+        let worker_workspace = self
+            .workspace
+            .as_ref()
+            .map(|workspace| workspace.path().to_path_buf());
+        let sandbox = match &worker_workspace {
+            Some(path) => Arc::new(self.deps.sandbox.with_writable_dir(path)),
+            None => self.deps.sandbox.clone(),
+        };
+
+        crate::tools::create_worker_tool_server(
+            self.deps.agent_id.clone(),
+            self.id,
+            self.channel_id.clone(),
+            self.turn_id,
+            self.deps.event_tx.clone(),
+            self.browser_config.clone(),
+            self.screenshot_dir.clone(),
+            self.web_search.clone(),
+            self.deps.runtime_config.workspace_dir.clone(),
+            worker_workspace,
+            sandbox,
+            mcp_tools,
+            self.deps.knowledge.clone(),
+            self.deps.media.clone(),
+            self.deps.runtime_config.clone(),
+            self.hook.tool_steps(),
+            self.web_fetch_cache.clone(),
+            &self.tools,
+            self.sender_tier,
+            crate::tools::OutputSpill::new(
+                self.deps.sqlite_pool.clone(),
+                self.deps.runtime_config.clone(),
+            ),
+        )
+    }
+
+    /// Run tool calls that plan mode held back, in order and without an LLM.
+    /// Stops at the first call that fails.
+    pub async fn run_plan(self, calls: Vec<PlannedCall>) -> Result<WorkerResult> {
+        self.hook.send_status("running approved plan").await;
+        tracing::info!(worker_id = %self.id, steps = calls.len(), "running approved plan");
+
+        let tool_server = self.tool_server().await;
+        let total = calls.len();
+        let mut output = format!("Ran the approved plan ({total} steps):");
+        for (index, call) in calls.iter().enumerate() {
+            let step = index + 1;
+            if self.cancel_token.is_cancelled() {
+                let _ = write!(
+                    output,
+                    "\n{step}. {}: cancelled before it ran",
+                    call.tool_name
+                );
+                return Ok(WorkerResult::partial(output));
+            }
+            self.hook
+                .send_status(format!("step {step}/{total}: {}", call.tool_name))
+                .await;
+            match tool_server.call_tool(&call.tool_name, &call.args).await {
+                Ok(result) => {
+                    let _ = write!(
+                        output,
+                        "\n{step}. {}: {}",
+                        call.tool_name,
+                        crate::tools::truncate_output(&result, PLAN_STEP_OUTPUT_BYTES)
+                    );
+                }
+                Err(error) => {
+                    let _ = write!(output, "\n{step}. {} failed: {error}", call.tool_name);
+                    if step < total {
+                        let _ = write!(output, "\nStopped; {} steps did not run.", total - step);
+                    }
+                    self.hook.send_status("failed").await;
+                    return Ok(if index == 0 {
+                        WorkerResult::failed(output)
+                    } else {
+                        WorkerResult::partial(output)
+                    });
+                }
+            }
+        }
+
+        self.hook.send_status("done").await;
+        Ok(WorkerResult::success(output))
+    }
+
     /// Check if the worker can transition to a new state.
     pub fn can_transition_to(&self, target: WorkerState) -> bool {
         use WorkerState::*;
@@ -184,34 +548,36 @@ impl Worker {
     /// and compacts if the worker is approaching the context window limit.
     /// This prevents long-running workers from dying mid-task due to context
     /// exhaustion.
-    pub async fn run(mut self) -> Result<String> {
+    pub async fn run(mut self) -> Result<WorkerResult> {
         self.status_tx.send_modify(|s| *s = "running".to_string());
-        self.hook.send_status("running");
+        self.hook.send_status("running").await;
 
         tracing::info!(worker_id = %self.id, task = %self.task, "worker starting");
 
-        let mcp_tools = self.deps.mcp_manager.get_tools().await;
-
-        // Create per-worker ToolServer with task tools
-        let worker_tool_server = crate::tools::create_worker_tool_server(
-            self.deps.agent_id.clone(),
-            self.id,
-            self.channel_id.clone(),
-            self.deps.event_tx.clone(),
-            self.browser_config.clone(),
-            self.screenshot_dir.clone(),
-            self.brave_search_key.clone(),
-            self.deps.runtime_config.workspace_dir.clone(),
-            self.deps.sandbox.clone(),
-            mcp_tools,
-            self.deps.runtime_config.clone(),
-        );
+        let worker_tool_server = self.tool_server().await;
 
         let routing = self.deps.runtime_config.routing.load();
-        let model_name = routing.resolve(ProcessType::Worker, None).to_string();
+        let model_name = self
+            .model
+            .clone()
+            .unwrap_or_else(|| routing.resolve(ProcessType::Worker, None).to_string());
+        tracing::Span::current().record("model", model_name.as_str());
+        if let Some(tracker) = &self.cost_tracker {
+            self.hook = self
+                .hook
+                .clone()
+                .with_cost_tracker(tracker.for_model(&model_name));
+        }
         let model = SpacebotModel::make(&self.deps.llm_manager, &model_name)
             .with_context(&*self.deps.agent_id, "worker")
-            .with_routing((**routing).clone());
+            .with_routing((**routing).clone())
+            .with_fallback_events(
+                self.deps.event_tx.clone(),
+                self.deps.agent_id.clone(),
+                ProcessId::Worker(self.id),
+                self.channel_id.clone(),
+            )
+            .with_turn_id(self.turn_id);
 
         let agent = AgentBuilder::new(model)
             .preamble(&self.system_prompt)
@@ -219,6 +585,8 @@ impl Worker {
             .tool_server_handle(worker_tool_server)
             .build();
 
+        let tool_concurrency = **self.deps.runtime_config.tool_concurrency.load();
+
         // Fresh history for the worker (no channel context)
         let mut history = Vec::new();
         let mut compacted_history = Vec::new();
@@ -227,14 +595,20 @@ impl Worker {
         let mut prompt = self.task.clone();
         let mut segments_run = 0;
         let mut overflow_retries = 0;
+        let mut partial = false;
 
-        let result = loop {
+        let mut result = loop {
             segments_run += 1;
 
-            match agent
-                .prompt(&prompt)
-                .with_history(&mut history)
-                .with_hook(self.hook.clone())
+            match self
+                .hook
+                .cancellable(
+                    agent
+                        .prompt(&prompt)
+                        .with_history(&mut history)
+                        .with_hook(self.hook.clone())
+                        .with_tool_concurrency(tool_concurrency),
+                )
                 .await
             {
                 Ok(response) => {
@@ -249,7 +623,8 @@ impl Worker {
                             segments = segments_run,
                             "worker hit max segments, returning partial result"
                         );
-                        self.hook.send_status("done (max segments)");
+                        self.hook.send_status("done (max segments)").await;
+                        partial = true;
                         break history
                             .iter()
                             .rev()
@@ -276,7 +651,8 @@ impl Worker {
                         .await;
                     prompt = "Continue where you left off. Do not repeat completed work.".into();
                     self.hook
-                        .send_status(format!("working (segment {segments_run})"));
+                        .send_status(format!("working (segment {segments_run})"))
+                        .await;
 
                     tracing::debug!(
                         worker_id = %self.id,
@@ -287,17 +663,17 @@ impl Worker {
                 }
                 Err(rig::completion::PromptError::PromptCancelled { reason, .. }) => {
                     self.state = WorkerState::Failed;
-                    self.hook.send_status("cancelled");
+                    self.hook.send_status("cancelled").await;
                     self.write_failure_log(&history, &format!("cancelled: {reason}"));
                     self.persist_transcript(&compacted_history, &history);
                     tracing::info!(worker_id = %self.id, %reason, "worker cancelled");
-                    return Ok(format!("Worker cancelled: {reason}"));
+                    return Ok(WorkerResult::failed(format!("Worker cancelled: {reason}")));
                 }
                 Err(error) if is_context_overflow_error(&error.to_string()) => {
                     overflow_retries += 1;
                     if overflow_retries > MAX_OVERFLOW_RETRIES {
                         self.state = WorkerState::Failed;
-                        self.hook.send_status("failed");
+                        self.hook.send_status("failed").await;
                         self.write_failure_log(&history, &format!("context overflow after {MAX_OVERFLOW_RETRIES} compaction attempts: {error}"));
                         self.persist_transcript(&compacted_history, &history);
                         tracing::error!(worker_id = %self.id, %error, "worker context overflow unrecoverable");
@@ -310,7 +686,9 @@ impl Worker {
                         %error,
                         "context overflow, compacting and retrying"
                     );
-                    self.hook.send_status("compacting (overflow recovery)");
+                    self.hook
+                        .send_status("compacting (overflow recovery)")
+                        .await;
                     self.force_compact_history(&mut compacted_history, &mut history)
                         .await;
                     prompt = "Continue where you left off. Do not repeat completed work. \
@@ -320,7 +698,7 @@ impl Worker {
                 }
                 Err(error) => {
                     self.state = WorkerState::Failed;
-                    self.hook.send_status("failed");
+                    self.hook.send_status("failed").await;
                     self.write_failure_log(&history, &error.to_string());
                     self.persist_transcript(&compacted_history, &history);
                     tracing::error!(worker_id = %self.id, %error, "worker LLM call failed");
@@ -329,14 +707,29 @@ impl Worker {
             }
         };
 
-        // For interactive workers, enter a follow-up loop
+        // For interactive workers, enter a follow-up loop. Each answer reaches
+        // the channel as it's given, and the last one is the final result.
+        let mut delivered = false;
+        let mut follow_up_error = None;
         if let Some(mut input_rx) = self.input_rx.take() {
             self.state = WorkerState::WaitingForInput;
-            self.hook.send_status("waiting for input");
+            self.send_response(&result);
+            delivered = true;
+            self.hook.send_status("waiting for input").await;
+
+            loop {
+                let follow_up = tokio::select! {
+                    _ = self.cancel_token.cancelled() => break,
+                    follow_up = input_rx.recv() => match follow_up {
+                        Some(follow_up) => follow_up,
+                        None => break,
+                    },
+                };
 
-            while let Some(follow_up) = input_rx.recv().await {
                 self.state = WorkerState::Running;
-                self.hook.send_status("processing follow-up");
+                self.hook.send_status("processing follow-up").await;
+                self.hook
+                    .begin_turn(**self.deps.runtime_config.tool_call_repairs.load());
 
                 // Compact before follow-up if needed
                 self.maybe_compact_history(&mut compacted_history, &mut history)
@@ -345,20 +738,32 @@ impl Worker {
                 let mut follow_up_prompt = follow_up.clone();
                 let mut follow_up_overflow_retries = 0;
 
-                let follow_up_ok = loop {
-                    match agent
-                        .prompt(&follow_up_prompt)
-                        .with_history(&mut history)
-                        .with_hook(self.hook.clone())
+                let follow_up_result = loop {
+                    match self
+                        .hook
+                        .cancellable(
+                            agent
+                                .prompt(&follow_up_prompt)
+                                .with_history(&mut history)
+                                .with_hook(self.hook.clone())
+                                .with_tool_concurrency(tool_concurrency),
+                        )
                         .await
                     {
-                        Ok(_response) => break true,
+                        Ok(response) => {
+                            self.send_response(&response);
+                            break Ok(response);
+                        }
+                        Err(rig::completion::PromptError::PromptCancelled { reason, .. }) => {
+                            tracing::info!(worker_id = %self.id, %reason, "worker follow-up cancelled");
+                            break Err(format!("Worker cancelled: {reason}"));
+                        }
                         Err(error) if is_context_overflow_error(&error.to_string()) => {
                             follow_up_overflow_retries += 1;
                             if follow_up_overflow_retries > MAX_OVERFLOW_RETRIES {
                                 self.write_failure_log(&history, &format!("follow-up context overflow after {MAX_OVERFLOW_RETRIES} compaction attempts: {error}"));
                                 tracing::error!(worker_id = %self.id, %error, "follow-up context overflow unrecoverable");
-                                break false;
+                                break Err(format!("Follow-up failed: {error}"));
                             }
                             tracing::warn!(
                                 worker_id = %self.id,
@@ -366,7 +771,9 @@ impl Worker {
                                 %error,
                                 "follow-up context overflow, compacting and retrying"
                             );
-                            self.hook.send_status("compacting (overflow recovery)");
+                            self.hook
+                                .send_status("compacting (overflow recovery)")
+                                .await;
                             self.force_compact_history(&mut compacted_history, &mut history)
                                 .await;
                             let prompt_engine = self.deps.runtime_config.prompts.load();
@@ -376,24 +783,32 @@ impl Worker {
                         Err(error) => {
                             self.write_failure_log(&history, &format!("follow-up failed: {error}"));
                             tracing::error!(worker_id = %self.id, %error, "worker follow-up failed");
-                            break false;
+                            break Err(format!("Follow-up failed: {error}"));
                         }
                     }
                 };
 
-                if follow_up_ok {
-                    self.state = WorkerState::WaitingForInput;
-                    self.hook.send_status("waiting for input");
-                } else {
-                    self.state = WorkerState::Failed;
-                    self.hook.send_status("failed");
-                    break;
+                match follow_up_result {
+                    Ok(response) => {
+                        result = response;
+                        partial = false;
+                        self.state = WorkerState::WaitingForInput;
+                        self.hook.send_status("waiting for input").await;
+                    }
+                    Err(error) => {
+                        self.state = WorkerState::Failed;
+                        self.hook.send_status("failed").await;
+                        follow_up_error = Some(error);
+                        break;
+                    }
                 }
             }
         }
 
-        self.state = WorkerState::Done;
-        self.hook.send_status("completed");
+        if follow_up_error.is_none() {
+            self.state = WorkerState::Done;
+            self.hook.send_status("completed").await;
+        }
 
         // Write success log based on the worker log mode setting
         let log_mode = self.get_worker_log_mode();
@@ -405,7 +820,63 @@ impl Worker {
         self.persist_transcript(&compacted_history, &history);
 
         tracing::info!(worker_id = %self.id, "worker completed");
-        Ok(result)
+        let result = match follow_up_error {
+            Some(error) => WorkerResult::failed(error),
+            None if partial => WorkerResult::partial(result).mark_delivered(delivered),
+            None => WorkerResult::success(result).mark_delivered(delivered),
+        };
+        let result = result.with_artifacts(file_artifacts(&compacted_history, &history));
+        Ok(self.store_file_artifacts(result).await)
+    }
+
+    /// Keep a copy of each file the worker wrote in the media store while
+    /// its workspace still exists. Paths outside the workspaces (a write the
+    /// file tool refused) are skipped.
+    async fn store_file_artifacts(&self, mut result: WorkerResult) -> WorkerResult {
+        let workspace_dir = &self.deps.runtime_config.workspace_dir;
+        let roots: Vec<PathBuf> = std::iter::once(workspace_dir.clone())
+            .chain(self.workspace.as_ref().map(|w| w.path().to_path_buf()))
+            .filter_map(|root| root.canonicalize().ok())
+            .collect();
+
+        for artifact in &mut result.artifacts {
+            let WorkerArtifact::File { path, media_id } = artifact else {
+                continue;
+            };
+            let Ok(resolved) = workspace_dir.join(path.as_str()).canonicalize() else {
+                continue;
+            };
+            if !roots.iter().any(|root| resolved.starts_with(root)) {
+                continue;
+            }
+            match self
+                .deps
+                .media
+                .put_file(MediaSource::Worker, self.channel_id.as_deref(), &resolved)
+                .await
+            {
+                Ok(stored) => *media_id = Some(stored.id),
+                Err(error) => {
+                    tracing::warn!(worker_id = %self.id, %error, %path, "failed to store worker file");
+                }
+            }
+        }
+        result
+    }
+
+    /// Deliver a response from an interactive worker back to its channel.
+    /// `WorkerComplete` carries the last one again, marked as delivered.
+    fn send_response(&self, response: &str) {
+        self.deps
+            .event_tx
+            .send(ProcessEvent::WorkerResponse {
+                agent_id: self.deps.agent_id.clone(),
+                worker_id: self.id,
+                channel_id: self.channel_id.clone(),
+                response: response.to_string(),
+                turn_id: self.turn_id,
+            })
+            .ok();
     }
 
     /// Check context usage and compact history if approaching the limit.
@@ -768,6 +1239,76 @@ fn build_worker_recap(messages: &[rig::message::Message]) -> String {
     }
 }
 
+/// First non-empty line of a worker's output, capped at `SUMMARY_MAX_CHARS`.
+fn summarize(output: &str) -> String {
+    let line = output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("done");
+    match line.char_indices().nth(SUMMARY_MAX_CHARS) {
+        Some((end, _)) => format!("{}...", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+/// URLs mentioned in a worker's output, in order of first appearance.
+fn url_artifacts(output: &str) -> Vec<WorkerArtifact> {
+    let mut artifacts = Vec::new();
+    for word in output.split_whitespace() {
+        let Some(start) = word.find("http://").or_else(|| word.find("https://")) else {
+            continue;
+        };
+        let url = word[start..].trim_end_matches(|c: char| {
+            matches!(
+                c,
+                '.' | ',' | ';' | ':' | '!' | '?' | ')' | ']' | '>' | '"' | '\'' | '`'
+            )
+        });
+        let artifact = WorkerArtifact::Url {
+            url: url.to_string(),
+        };
+        if !artifacts.contains(&artifact) {
+            artifacts.push(artifact);
+        }
+    }
+    artifacts
+}
+
+/// Files the worker wrote with the `file` tool.
+fn file_artifacts(
+    compacted_history: &[rig::message::Message],
+    history: &[rig::message::Message],
+) -> Vec<WorkerArtifact> {
+    let mut artifacts = Vec::new();
+    for message in compacted_history.iter().chain(history) {
+        let rig::message::Message::Assistant { content, .. } = message else {
+            continue;
+        };
+        for item in content.iter() {
+            let rig::message::AssistantContent::ToolCall(tool_call) = item else {
+                continue;
+            };
+            let arguments = &tool_call.function.arguments;
+            if tool_call.function.name != crate::tools::FileTool::NAME
+                || arguments.get("operation").and_then(|v| v.as_str()) != Some("write")
+            {
+                continue;
+            }
+            if let Some(path) = arguments.get("path").and_then(|v| v.as_str()) {
+                let artifact = WorkerArtifact::File {
+                    path: path.to_string(),
+                    media_id: None,
+                };
+                if !artifacts.contains(&artifact) {
+                    artifacts.push(artifact);
+                }
+            }
+        }
+    }
+    artifacts
+}
+
 /// Extract the last assistant text message from a history.
 #[allow(dead_code)]
 fn extract_last_assistant_text(history: &[rig::message::Message]) -> Option<String> {
@@ -790,3 +1331,39 @@ fn extract_last_assistant_text(history: &[rig::message::Message]) -> Option<Stri
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn worker_result_summarizes_first_line() {
+        let result = WorkerResult::success("\n  Deployed the site.\nDetails follow.");
+        assert_eq!(result.summary, "Deployed the site.");
+        assert!(result.succeeded());
+
+        let long = "x".repeat(SUMMARY_MAX_CHARS + 10);
+        let result = WorkerResult::failed(long);
+        assert_eq!(result.summary.chars().count(), SUMMARY_MAX_CHARS + 3);
+        assert!(!result.succeeded());
+    }
+
+    #[test]
+    fn worker_result_collects_urls_from_output() {
+        let result = WorkerResult::partial(
+            "Opened a PR (https://github.com/org/repo/pull/1). See https://example.com/docs, \
+             and https://github.com/org/repo/pull/1 again.",
+        );
+        assert_eq!(
+            result.artifacts,
+            vec![
+                WorkerArtifact::Url {
+                    url: "https://github.com/org/repo/pull/1".into()
+                },
+                WorkerArtifact::Url {
+                    url: "https://example.com/docs".into()
+                },
+            ]
+        );
+    }
+}
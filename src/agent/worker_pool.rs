@@ -0,0 +1,601 @@
+//! Agent-wide worker pool: caps how many workers run at once.
+//!
+//! Every channel worker asks the pool for a slot before it starts. At most
+//! `max_running_workers` run across the agent and `max_concurrent_workers`
+//! per channel; the rest wait in a priority queue (FIFO within a priority)
+//! and are started as slots free up. Queued workers are announced with
+//! `ProcessEvent::WorkerQueued` so the channel status block can show their
+//! position.
+//!
+//! The pool also runs failed workers again under a [`RetryPolicy`]: failures
+//! are classified from their error, and retriable ones are retried with
+//! exponential backoff, announced with `ProcessEvent::WorkerRetrying`.
+
+use crate::agent::worker::WorkerResult;
+use crate::config::RuntimeConfig;
+use crate::error::AgentError;
+use crate::{AgentId, ChannelId, ProcessEvent, TurnId, WorkerId};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{broadcast, oneshot};
+
+/// Queue priority of a worker. Higher priorities start first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WorkerPriority {
+    Normal,
+    /// Interactive workers, which a user is usually waiting on.
+    High,
+}
+
+/// Why a worker attempt failed, as far as retrying is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// The provider rate-limited the worker's model calls.
+    RateLimit,
+    /// A model call or tool timed out.
+    Timeout,
+    /// The provider failed or was unavailable.
+    Provider,
+    /// The worker's context outgrew the model's window.
+    ContextOverflow,
+    Other,
+}
+
+impl FailureKind {
+    /// Classify a worker error from its message.
+    pub fn classify(error: &str) -> Self {
+        let error = error.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|needle| error.contains(needle));
+        if has(&["rate limit", "rate_limit", "429", "too many requests"]) {
+            Self::RateLimit
+        } else if has(&["timeout", "timed out", "deadline"]) {
+            Self::Timeout
+        } else if has(&[
+            "context length",
+            "context window",
+            "too long",
+            "context_length",
+        ]) {
+            Self::ContextOverflow
+        } else if has(&[
+            "api error (5",
+            "overloaded",
+            "service unavailable",
+            "bad gateway",
+            "internal server error",
+            "failed to read response body",
+            "connection",
+        ]) {
+            Self::Provider
+        } else {
+            Self::Other
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::RateLimit => "rate_limit",
+            Self::Timeout => "timeout",
+            Self::Provider => "provider",
+            Self::ContextOverflow => "context_overflow",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// When and how often to run a failed worker again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first (1 disables retries).
+    pub max_attempts: usize,
+    /// Wait before the first retry; doubles for each one after.
+    pub initial_backoff_ms: u64,
+    /// Longest wait between attempts.
+    pub max_backoff_ms: u64,
+    /// Failures that are retried. Others fail the worker right away.
+    pub retry_on: Vec<FailureKind>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff_ms: 2_000,
+            max_backoff_ms: 60_000,
+            retry_on: vec![
+                FailureKind::RateLimit,
+                FailureKind::Timeout,
+                FailureKind::Provider,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Whether to run another attempt after `attempt` (1-based) failed.
+    pub fn should_retry(&self, attempt: usize, kind: FailureKind) -> bool {
+        attempt < self.max_attempts && self.retry_on.contains(&kind)
+    }
+
+    /// How long to wait after `attempt` (1-based) failed.
+    pub fn backoff(&self, attempt: usize) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16) as u32;
+        let delay = self
+            .initial_backoff_ms
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff_ms);
+        Duration::from_millis(delay)
+    }
+}
+
+/// Outcome of asking the pool for a slot.
+pub enum Admission {
+    /// A slot was free; the worker can start now.
+    Running(WorkerPermit),
+    /// The worker is queued. The permit arrives once a slot frees up.
+    Queued {
+        position: usize,
+        permit: oneshot::Receiver<WorkerPermit>,
+    },
+}
+
+/// A running slot in the pool. Dropping it frees the slot and starts the
+/// next eligible queued worker.
+pub struct WorkerPermit {
+    pool: WorkerPool,
+    channel_id: ChannelId,
+}
+
+impl Drop for WorkerPermit {
+    fn drop(&mut self) {
+        self.pool.release(&self.channel_id);
+    }
+}
+
+/// Shared worker pool for one agent. Cheap to clone.
+#[derive(Clone)]
+pub struct WorkerPool {
+    inner: Arc<PoolInner>,
+}
+
+struct PoolInner {
+    agent_id: AgentId,
+    runtime_config: Arc<RuntimeConfig>,
+    event_tx: broadcast::Sender<ProcessEvent>,
+    state: Mutex<PoolState>,
+}
+
+#[derive(Default)]
+struct PoolState {
+    running: usize,
+    running_per_channel: HashMap<ChannelId, usize>,
+    queue: Vec<QueuedWorker>,
+    next_seq: u64,
+}
+
+struct QueuedWorker {
+    worker_id: WorkerId,
+    channel_id: ChannelId,
+    /// The channel turn that spawned the worker, stamped on `WorkerQueued`.
+    turn_id: Option<TurnId>,
+    priority: WorkerPriority,
+    seq: u64,
+    /// Last position announced through `WorkerQueued`.
+    position: usize,
+    grant: oneshot::Sender<WorkerPermit>,
+}
+
+/// Concurrency limits read from the runtime config on every decision, so
+/// changes apply without a restart.
+#[derive(Debug, Clone, Copy)]
+struct PoolLimits {
+    max_running: usize,
+    max_per_channel: usize,
+    max_queued_per_channel: usize,
+}
+
+impl PoolState {
+    fn can_run(&self, channel_id: &ChannelId, limits: PoolLimits) -> bool {
+        self.running < limits.max_running
+            && self
+                .running_per_channel
+                .get(channel_id)
+                .copied()
+                .unwrap_or(0)
+                < limits.max_per_channel
+    }
+
+    fn occupy(&mut self, channel_id: &ChannelId) {
+        self.running += 1;
+        *self
+            .running_per_channel
+            .entry(channel_id.clone())
+            .or_default() += 1;
+    }
+
+    fn vacate(&mut self, channel_id: &ChannelId) {
+        self.running = self.running.saturating_sub(1);
+        if let Some(count) = self.running_per_channel.get_mut(channel_id) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                self.running_per_channel.remove(channel_id);
+            }
+        }
+    }
+
+    fn queued_for(&self, channel_id: &ChannelId) -> usize {
+        self.queue
+            .iter()
+            .filter(|queued| queued.channel_id == *channel_id)
+            .count()
+    }
+
+    /// Keep the queue in start order: highest priority first, then FIFO.
+    fn sort_queue(&mut self) {
+        self.queue
+            .sort_by(|a, b| b.priority.cmp(&a.priority).then(a.seq.cmp(&b.seq)));
+    }
+
+    /// Remove and occupy slots for every queued worker that can start now.
+    /// Workers whose spawner went away (cancelled while queued) are dropped.
+    fn take_ready(&mut self, limits: PoolLimits) -> Vec<QueuedWorker> {
+        self.queue.retain(|queued| !queued.grant.is_closed());
+
+        let mut ready = Vec::new();
+        let mut index = 0;
+        while index < self.queue.len() {
+            if self.running >= limits.max_running {
+                break;
+            }
+            if self.can_run(&self.queue[index].channel_id, limits) {
+                let queued = self.queue.remove(index);
+                self.occupy(&queued.channel_id);
+                ready.push(queued);
+            } else {
+                index += 1;
+            }
+        }
+        ready
+    }
+
+    /// Update stored queue positions (1-based), returning the workers whose
+    /// position changed.
+    fn reposition(&mut self) -> Vec<(WorkerId, ChannelId, Option<TurnId>, usize)> {
+        let mut moved = Vec::new();
+        for (index, queued) in self.queue.iter_mut().enumerate() {
+            let position = index + 1;
+            if queued.position != position {
+                queued.position = position;
+                moved.push((
+                    queued.worker_id,
+                    queued.channel_id.clone(),
+                    queued.turn_id,
+                    position,
+                ));
+            }
+        }
+        moved
+    }
+}
+
+impl WorkerPool {
+    pub fn new(
+        agent_id: AgentId,
+        runtime_config: Arc<RuntimeConfig>,
+        event_tx: broadcast::Sender<ProcessEvent>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(PoolInner {
+                agent_id,
+                runtime_config,
+                event_tx,
+                state: Mutex::new(PoolState::default()),
+            }),
+        }
+    }
+
+    fn limits(&self) -> PoolLimits {
+        let rc = &self.inner.runtime_config;
+        PoolLimits {
+            max_running: (**rc.max_running_workers.load()).max(1),
+            max_per_channel: (**rc.max_concurrent_workers.load()).max(1),
+            max_queued_per_channel: **rc.max_queued_workers.load(),
+        }
+    }
+
+    /// Ask for a slot for a worker spawned by `turn_id`. Starts it right away
+    /// if the agent and the channel are under their limits, otherwise queues
+    /// it. Fails when the channel's queue is full.
+    pub fn admit(
+        &self,
+        worker_id: WorkerId,
+        channel_id: ChannelId,
+        turn_id: Option<TurnId>,
+        priority: WorkerPriority,
+    ) -> std::result::Result<Admission, AgentError> {
+        let limits = self.limits();
+        let mut state = self.inner.state.lock().expect("worker pool lock poisoned");
+
+        // Workers already waiting go first; this only admits any when the
+        // limits were raised since the last release.
+        let ready = state.take_ready(limits);
+        if state.can_run(&channel_id, limits) {
+            state.occupy(&channel_id);
+            let moved = state.reposition();
+            drop(state);
+            self.grant(ready, moved);
+            return Ok(Admission::Running(WorkerPermit {
+                pool: self.clone(),
+                channel_id,
+            }));
+        }
+
+        if state.queued_for(&channel_id) >= limits.max_queued_per_channel {
+            let moved = state.reposition();
+            drop(state);
+            self.grant(ready, moved);
+            return Err(AgentError::WorkerLimitReached {
+                channel_id: channel_id.to_string(),
+                max: limits.max_per_channel + limits.max_queued_per_channel,
+            });
+        }
+
+        let (grant, permit) = oneshot::channel();
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.queue.push(QueuedWorker {
+            worker_id,
+            channel_id,
+            turn_id,
+            priority,
+            seq,
+            position: 0,
+            grant,
+        });
+        state.sort_queue();
+        let moved = state.reposition();
+        let position = moved
+            .iter()
+            .find(|(id, ..)| *id == worker_id)
+            .map(|(.., position)| *position)
+            .unwrap_or(state.queue.len());
+        drop(state);
+
+        self.grant(ready, moved);
+        tracing::info!(worker_id = %worker_id, position, "worker queued");
+
+        Ok(Admission::Queued { position, permit })
+    }
+
+    /// Free a running slot and start whatever queued workers now fit.
+    fn release(&self, channel_id: &ChannelId) {
+        let limits = self.limits();
+        let (ready, moved) = {
+            let mut state = self.inner.state.lock().expect("worker pool lock poisoned");
+            state.vacate(channel_id);
+            let ready = state.take_ready(limits);
+            let moved = state.reposition();
+            (ready, moved)
+        };
+        self.grant(ready, moved);
+    }
+
+    /// Hand permits to admitted workers and announce new queue positions.
+    ///
+    /// Must be called without the state lock held: a grant whose receiver is
+    /// already gone hands the permit back, and dropping it re-enters
+    /// `release`.
+    fn grant(
+        &self,
+        ready: Vec<QueuedWorker>,
+        moved: Vec<(WorkerId, ChannelId, Option<TurnId>, usize)>,
+    ) {
+        for queued in ready {
+            let permit = WorkerPermit {
+                pool: self.clone(),
+                channel_id: queued.channel_id,
+            };
+            if queued.grant.send(permit).is_ok() {
+                tracing::info!(worker_id = %queued.worker_id, "queued worker admitted");
+            }
+        }
+        self.announce(moved);
+    }
+
+    /// Run a worker's attempts under `policy`. `attempt` builds and runs one
+    /// attempt, given its 1-based number. The worker keeps its slot while it
+    /// backs off between attempts.
+    pub async fn run_with_retry<F, Fut, E>(
+        &self,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        turn_id: Option<TurnId>,
+        policy: &RetryPolicy,
+        mut attempt: F,
+    ) -> std::result::Result<WorkerResult, E>
+    where
+        F: FnMut(usize) -> Fut,
+        Fut: std::future::Future<Output = std::result::Result<WorkerResult, E>>,
+        E: std::fmt::Display,
+    {
+        let mut number = 1;
+        loop {
+            let error = match attempt(number).await {
+                Ok(result) => return Ok(result),
+                Err(error) => error,
+            };
+
+            let kind = FailureKind::classify(&error.to_string());
+            if !policy.should_retry(number, kind) {
+                return Err(error);
+            }
+
+            let delay = policy.backoff(number);
+            number += 1;
+            tracing::warn!(
+                worker_id = %worker_id,
+                %error,
+                kind = kind.as_str(),
+                attempt = number,
+                max_attempts = policy.max_attempts,
+                delay_ms = delay.as_millis() as u64,
+                "worker failed, retrying"
+            );
+            self.inner
+                .event_tx
+                .send(ProcessEvent::WorkerRetrying {
+                    agent_id: self.inner.agent_id.clone(),
+                    worker_id,
+                    channel_id: channel_id.clone(),
+                    attempt: number,
+                    max_attempts: policy.max_attempts,
+                    reason: kind.as_str().to_string(),
+                    delay_ms: delay.as_millis() as u64,
+                    turn_id,
+                })
+                .ok();
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    fn announce(&self, moved: Vec<(WorkerId, ChannelId, Option<TurnId>, usize)>) {
+        for (worker_id, channel_id, turn_id, position) in moved {
+            self.inner
+                .event_tx
+                .send(ProcessEvent::WorkerQueued {
+                    agent_id: self.inner.agent_id.clone(),
+                    worker_id,
+                    channel_id: Some(channel_id),
+                    position,
+                    turn_id,
+                })
+                .ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: PoolLimits = PoolLimits {
+        max_running: 2,
+        max_per_channel: 1,
+        max_queued_per_channel: 10,
+    };
+
+    fn enqueue(
+        state: &mut PoolState,
+        channel: &str,
+        priority: WorkerPriority,
+    ) -> (WorkerId, oneshot::Receiver<WorkerPermit>) {
+        let (grant, permit) = oneshot::channel();
+        let worker_id = uuid::Uuid::new_v4();
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.queue.push(QueuedWorker {
+            worker_id,
+            channel_id: Arc::from(channel),
+            turn_id: None,
+            priority,
+            seq,
+            position: 0,
+            grant,
+        });
+        state.sort_queue();
+        (worker_id, permit)
+    }
+
+    #[test]
+    fn failures_are_classified() {
+        assert_eq!(
+            FailureKind::classify("ProviderError: 429 Too Many Requests"),
+            FailureKind::RateLimit
+        );
+        assert_eq!(
+            FailureKind::classify("request timed out after 60s"),
+            FailureKind::Timeout
+        );
+        assert_eq!(
+            FailureKind::classify("Anthropic API error (529 <unknown status code>): busy"),
+            FailureKind::Provider
+        );
+        assert_eq!(
+            FailureKind::classify("unknown tool: frobnicate"),
+            FailureKind::Other
+        );
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            initial_backoff_ms: 1_000,
+            max_backoff_ms: 3_000,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(1_000));
+        assert_eq!(policy.backoff(2), Duration::from_millis(2_000));
+        assert_eq!(policy.backoff(3), Duration::from_millis(3_000));
+
+        assert!(policy.should_retry(4, FailureKind::Timeout));
+        assert!(!policy.should_retry(5, FailureKind::Timeout));
+        assert!(!policy.should_retry(1, FailureKind::Other));
+    }
+
+    #[test]
+    fn high_priority_jumps_the_queue() {
+        let mut state = PoolState::default();
+        let (normal, _rx1) = enqueue(&mut state, "a", WorkerPriority::Normal);
+        let (high, _rx2) = enqueue(&mut state, "b", WorkerPriority::High);
+
+        let moved = state.reposition();
+        assert_eq!(moved[0].0, high);
+        assert_eq!(moved[1].0, normal);
+        assert_eq!(moved[1].3, 2);
+    }
+
+    #[test]
+    fn take_ready_respects_channel_quota() {
+        let mut state = PoolState::default();
+        let (first, _rx1) = enqueue(&mut state, "a", WorkerPriority::Normal);
+        let (_second, _rx2) = enqueue(&mut state, "a", WorkerPriority::Normal);
+        let (other, _rx3) = enqueue(&mut state, "b", WorkerPriority::Normal);
+
+        let ready: Vec<_> = state
+            .take_ready(LIMITS)
+            .into_iter()
+            .map(|q| q.worker_id)
+            .collect();
+        assert_eq!(ready, vec![first, other]);
+        assert_eq!(state.queue.len(), 1);
+        assert_eq!(state.running, 2);
+    }
+
+    #[test]
+    fn take_ready_drops_cancelled_workers() {
+        let mut state = PoolState::default();
+        let (_cancelled, rx) = enqueue(&mut state, "a", WorkerPriority::Normal);
+        drop(rx);
+        let (waiting, _rx) = enqueue(&mut state, "a", WorkerPriority::Normal);
+
+        let ready: Vec<_> = state
+            .take_ready(LIMITS)
+            .into_iter()
+            .map(|q| q.worker_id)
+            .collect();
+        assert_eq!(ready, vec![waiting]);
+    }
+}
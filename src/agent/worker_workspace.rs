@@ -0,0 +1,112 @@
+//! Per-worker working directories.
+//!
+//! Each channel worker gets `{worker_workspaces_dir}/{worker_id}` as its
+//! working directory. The directory is removed when the worker finishes
+//! (or is cancelled) unless the channel asked to retain it, in which case
+//! the channel can read what the worker left behind with the
+//! `workspace_list` and `workspace_read` tools.
+
+use crate::WorkerId;
+
+use std::path::{Path, PathBuf};
+
+/// A worker's working directory. Dropping it removes the directory unless
+/// it is retained.
+#[derive(Debug)]
+pub struct WorkerWorkspace {
+    path: PathBuf,
+    retain: bool,
+}
+
+impl WorkerWorkspace {
+    /// Create the working directory for a worker under `root`.
+    pub fn create(root: &Path, worker_id: WorkerId, retain: bool) -> std::io::Result<Self> {
+        let path = workspace_path(root, worker_id);
+        std::fs::create_dir_all(&path)?;
+        Ok(Self { path, retain })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the directory outlives the worker.
+    pub fn retained(&self) -> bool {
+        self.retain
+    }
+}
+
+impl Drop for WorkerWorkspace {
+    fn drop(&mut self) {
+        if self.retain {
+            return;
+        }
+
+        let path = std::mem::take(&mut self.path);
+        let remove = move || {
+            if let Err(error) = std::fs::remove_dir_all(&path)
+                && error.kind() != std::io::ErrorKind::NotFound
+            {
+                tracing::warn!(%error, path = %path.display(), "failed to remove worker workspace");
+            }
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn_blocking(remove);
+            }
+            Err(_) => remove(),
+        }
+    }
+}
+
+/// Where a worker's working directory lives under `root`.
+pub fn workspace_path(root: &Path, worker_id: WorkerId) -> PathBuf {
+    root.join(worker_id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_removed_on_drop() {
+        let root = tempfile::tempdir().unwrap();
+        let workspace = WorkerWorkspace::create(root.path(), uuid::Uuid::new_v4(), false).unwrap();
+        let path = workspace.path().to_path_buf();
+        std::fs::write(path.join("notes.txt"), "scratch").unwrap();
+        assert!(path.is_dir());
+
+        drop(workspace);
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_retained_workspace_survives_drop() {
+        let root = tempfile::tempdir().unwrap();
+        let worker_id = uuid::Uuid::new_v4();
+        let workspace = WorkerWorkspace::create(root.path(), worker_id, true).unwrap();
+        assert!(workspace.retained());
+
+        drop(workspace);
+
+        assert!(workspace_path(root.path(), worker_id).is_dir());
+    }
+
+    #[tokio::test]
+    async fn test_workspace_removed_on_drop_inside_runtime() {
+        let root = tempfile::tempdir().unwrap();
+        let workspace = WorkerWorkspace::create(root.path(), uuid::Uuid::new_v4(), false).unwrap();
+        let path = workspace.path().to_path_buf();
+
+        drop(workspace);
+
+        for _ in 0..50 {
+            if !path.exists() {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("workspace was not removed");
+    }
+}
@@ -13,15 +13,19 @@ mod cron;
 mod ingest;
 mod links;
 mod mcp;
+mod mcp_server;
+mod media;
 mod memories;
 mod messaging;
 mod models;
+mod openai_compat;
 mod providers;
 mod server;
 mod settings;
 mod skills;
 mod state;
 mod system;
+mod tool_audit;
 mod webchat;
 mod workers;
 
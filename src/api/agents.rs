@@ -358,6 +358,8 @@ pub(super) async fn trigger_warmup(
 
     let runtime_configs = state.runtime_configs.load();
     let memory_searches = state.memory_searches.load();
+    let knowledge_bases = state.knowledge_bases.load();
+    let media_stores = state.media_stores.load();
     let mcp_managers = state.mcp_managers.load();
     let pools = state.agent_pools.load();
     let sandboxes = state.sandboxes.load();
@@ -382,6 +384,12 @@ pub(super) async fn trigger_warmup(
         let Some(memory_search) = memory_searches.get(agent_id).cloned() else {
             continue;
         };
+        let Some(knowledge) = knowledge_bases.get(agent_id).cloned() else {
+            continue;
+        };
+        let Some(media) = media_stores.get(agent_id).cloned() else {
+            continue;
+        };
         let Some(mcp_manager) = mcp_managers.get(agent_id).cloned() else {
             continue;
         };
@@ -397,9 +405,16 @@ pub(super) async fn trigger_warmup(
         let agent_id = agent_id.clone();
         tokio::spawn(async move {
             let (event_tx, _event_rx) = tokio::sync::broadcast::channel(16);
+            let agent_id: crate::AgentId = Arc::from(agent_id.as_str());
+            let worker_pool = crate::agent::worker_pool::WorkerPool::new(
+                agent_id.clone(),
+                runtime_config.clone(),
+                event_tx.clone(),
+            );
             let deps = crate::AgentDeps {
-                agent_id: Arc::from(agent_id.as_str()),
+                agent_id,
                 memory_search,
+                knowledge,
                 llm_manager,
                 mcp_manager,
                 cron_tool: None,
@@ -410,6 +425,10 @@ pub(super) async fn trigger_warmup(
                 sandbox,
                 links: Arc::new(arc_swap::ArcSwap::from_pointee(Vec::new())),
                 agent_names: Arc::new(std::collections::HashMap::new()),
+                worker_pool,
+                media,
+                inbound_middleware: Default::default(),
+                outbound_middleware: crate::middleware::OutboundChain::standard(),
             };
             let logger = CortexLogger::new(sqlite_pool);
             crate::agent::cortex::run_warmup_once(&deps, &logger, "api_trigger", force).await;
@@ -522,16 +541,52 @@ pub(super) async fn create_agent(
         context_window: None,
         compaction: None,
         memory_persistence: None,
+        memory_injection: None,
+        knowledge: None,
+        memory_maintenance: None,
+        tool_policy: None,
+        plan_mode: None,
+        container: None,
+        http_request: None,
+        web_search: None,
+        calendar: None,
+        auth: None,
+        redaction: None,
+        system_prompt: None,
+        gating: None,
         coalesce: None,
         ingestion: None,
         cortex: None,
         warmup: None,
+        heartbeat: None,
         browser: None,
         mcp: None,
+        sql: None,
         brave_search_key: None,
+        github_token: None,
+        coolify_url: None,
+        coolify_token: None,
         cron_timezone: None,
+        channel_budget_usd: None,
+        channel_idle_timeout_mins: None,
+        branch_result_window_ms: None,
+        rank_branch_results: None,
+        max_branch_depth: None,
+        max_running_workers: None,
+        max_queued_workers: None,
+        worker_retry: None,
+        worker_presets: None,
+        worker_workspace_root: None,
+        message_dedup_window: None,
+        shutdown_drain_secs: None,
+        shutdown_notice: None,
+        skill_env_allowlist: None,
+        tool_output_max_tokens: None,
+        tool_call_repairs: None,
+        tool_concurrency: None,
         sandbox: None,
         cron: Vec::new(),
+        feeds: Vec::new(),
     };
     let agent_config = raw_config.resolve(&instance_dir, defaults);
     let _ = defaults;
@@ -549,7 +604,13 @@ pub(super) async fn create_agent(
         })?;
     }
 
-    let db = crate::db::Db::connect(&agent_config.data_dir)
+    let storage_config = state
+        .storage_config
+        .read()
+        .await
+        .clone()
+        .unwrap_or_default();
+    let db = crate::db::Db::connect(&agent_config.data_dir, &agent_id, &storage_config)
         .await
         .map_err(|error| {
             tracing::error!(%error, agent_id = %agent_id, "failed to connect agent databases");
@@ -576,26 +637,45 @@ pub(super) async fn create_agent(
     };
 
     let memory_store = crate::memory::MemoryStore::new(db.sqlite.clone());
-    let embedding_table = crate::memory::EmbeddingTable::open_or_create(&db.lance)
-        .await
-        .map_err(|error| {
-            tracing::error!(%error, agent_id = %agent_id, "failed to init embeddings");
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+    let embeddings = db.open_embeddings().await.map_err(|error| {
+        tracing::error!(%error, agent_id = %agent_id, "failed to init embeddings");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
 
-    if let Err(error) = embedding_table.ensure_fts_index().await {
+    if let Err(error) = embeddings.ensure_fts_index().await {
         tracing::warn!(%error, agent_id = %agent_id, "failed to create FTS index");
     }
 
     let memory_search = std::sync::Arc::new(crate::memory::MemorySearch::new(
         memory_store,
-        embedding_table,
-        embedding_model,
+        embeddings,
+        embedding_model.clone(),
     ));
 
+    let knowledge_chunks = db.open_knowledge_chunks().await.map_err(|error| {
+        tracing::error!(%error, agent_id = %agent_id, "failed to init knowledge base");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let knowledge =
+        crate::knowledge::KnowledgeBase::new(db.sqlite.clone(), knowledge_chunks, embedding_model);
+
     let (event_tx, _) = tokio::sync::broadcast::channel(256);
     let arc_agent_id: crate::AgentId = std::sync::Arc::from(agent_id.as_str());
 
+    let media_config = state.media_config.read().await.clone().unwrap_or_default();
+    let media = crate::media::MediaStore::open(
+        db.sqlite.clone(),
+        arc_agent_id.clone(),
+        &media_config,
+        &instance_dir,
+        &agent_config.data_dir,
+    )
+    .await
+    .map_err(|error| {
+        tracing::error!(%error, agent_id = %agent_id, "failed to init media store");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
     crate::identity::scaffold_identity_files(&agent_config.workspace)
         .await
         .map_err(|error| {
@@ -667,6 +747,7 @@ pub(super) async fn create_agent(
     let deps = crate::AgentDeps {
         agent_id: arc_agent_id.clone(),
         memory_search: memory_search.clone(),
+        knowledge: knowledge.clone(),
         llm_manager,
         mcp_manager: mcp_manager.clone(),
         cron_tool: None,
@@ -701,10 +782,21 @@ pub(super) async fn create_agent(
             });
             Arc::new(names)
         },
+        worker_pool: crate::agent::worker_pool::WorkerPool::new(
+            arc_agent_id.clone(),
+            runtime_config.clone(),
+            event_tx.clone(),
+        ),
+        media: media.clone(),
+        inbound_middleware: Default::default(),
+        outbound_middleware: crate::middleware::OutboundChain::standard(),
     };
 
     let event_rx = event_tx.subscribe();
     state.register_agent_events(agent_id.clone(), event_rx);
+    if let Some(event_sinks) = state.event_sinks.read().await.as_ref() {
+        event_sinks.register_agent(agent_id.clone(), event_tx.subscribe());
+    }
 
     let cron_store = std::sync::Arc::new(crate::cron::CronStore::new(db.sqlite.clone()));
     let cron_context = crate::cron::CronContext {
@@ -726,22 +818,25 @@ pub(super) async fn create_agent(
     let cron_tool = crate::tools::CronTool::new(cron_store.clone(), scheduler.clone());
 
     let browser_config = (**runtime_config.browser_config.load()).clone();
-    let brave_search_key = (**runtime_config.brave_search_key.load()).clone();
+    let web_search = runtime_config.web_search.load().configured();
     let conversation_logger =
         crate::conversation::history::ConversationLogger::new(db.sqlite.clone());
     let channel_store = crate::conversation::ChannelStore::new(db.sqlite.clone());
     let run_logger = crate::conversation::ProcessRunLogger::new(db.sqlite.clone());
     let cortex_tool_server = crate::tools::create_cortex_chat_tool_server(
         memory_search.clone(),
+        knowledge.clone(),
         conversation_logger,
         channel_store,
         run_logger,
         &deps.agent_id,
         browser_config,
         agent_config.screenshot_dir(),
-        brave_search_key,
+        web_search,
         runtime_config.workspace_dir.clone(),
         sandbox.clone(),
+        runtime_config.clone(),
+        crate::tools::OutputSpill::new(db.sqlite.clone(), runtime_config.clone()),
     );
     let cortex_store = crate::agent::cortex_chat::CortexChatStore::new(db.sqlite.clone());
     let cortex_session = crate::agent::cortex_chat::CortexChatSession::new(
@@ -755,7 +850,13 @@ pub(super) async fn create_agent(
     let _bulletin_loop =
         crate::agent::cortex::spawn_bulletin_loop(deps.clone(), cortex_logger.clone());
     let _association_loop =
-        crate::agent::cortex::spawn_association_loop(deps.clone(), cortex_logger);
+        crate::agent::cortex::spawn_association_loop(deps.clone(), cortex_logger.clone());
+    let _maintenance_loop =
+        crate::agent::cortex::spawn_maintenance_loop(deps.clone(), cortex_logger);
+    let _heartbeat_loop = crate::agent::heartbeat::spawn_heartbeat_loop(deps.clone());
+    let _calendar_sync_loop = crate::calendar::spawn_sync_loop(deps.clone());
+    let _reminder_loop = crate::reminders::spawn_reminder_loop(deps.clone());
+    let _feed_loop = crate::feeds::spawn_feed_loop(deps.clone());
 
     let ingestion_config = **runtime_config.ingestion.load();
     if ingestion_config.enabled {
@@ -784,6 +885,16 @@ pub(super) async fn create_agent(
         searches.insert(agent_id.clone(), memory_search);
         state.memory_searches.store(std::sync::Arc::new(searches));
 
+        let mut knowledge_bases = (**state.knowledge_bases.load()).clone();
+        knowledge_bases.insert(agent_id.clone(), knowledge);
+        state
+            .knowledge_bases
+            .store(std::sync::Arc::new(knowledge_bases));
+
+        let mut media_stores = (**state.media_stores.load()).clone();
+        media_stores.insert(agent_id.clone(), media);
+        state.media_stores.store(std::sync::Arc::new(media_stores));
+
         let mut workspaces = (**state.agent_workspaces.load()).clone();
         workspaces.insert(agent_id.clone(), agent_config.workspace.clone());
         state
@@ -1021,6 +1132,16 @@ pub(super) async fn delete_agent(
         searches.remove(&agent_id);
         state.memory_searches.store(std::sync::Arc::new(searches));
 
+        let mut knowledge_bases = (**state.knowledge_bases.load()).clone();
+        knowledge_bases.remove(&agent_id);
+        state
+            .knowledge_bases
+            .store(std::sync::Arc::new(knowledge_bases));
+
+        let mut media_stores = (**state.media_stores.load()).clone();
+        media_stores.remove(&agent_id);
+        state.media_stores.store(std::sync::Arc::new(media_stores));
+
         let mut workspaces = (**state.agent_workspaces.load()).clone();
         workspaces.remove(&agent_id);
         state
@@ -1,6 +1,7 @@
 use super::state::ApiState;
 
 use crate::conversation::channels::ChannelStore;
+use crate::conversation::drafts::{DraftDecision, DraftStore, ReplyDraft, Supervision};
 use crate::conversation::history::ProcessRunLogger;
 
 use axum::Json;
@@ -16,6 +17,7 @@ pub(super) struct ChannelResponse {
     id: String,
     platform: String,
     display_name: Option<String>,
+    title: Option<String>,
     is_active: bool,
     last_activity_at: String,
     created_at: String,
@@ -57,6 +59,20 @@ pub(super) struct CancelProcessResponse {
     message: String,
 }
 
+#[derive(Deserialize)]
+pub(super) struct ForkChannelRequest {
+    channel_id: String,
+    #[serde(default)]
+    display_name: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(super) struct ForkChannelResponse {
+    success: bool,
+    channel_id: String,
+    history_messages: usize,
+}
+
 /// List active channels across all agents.
 pub(super) async fn list_channels(State(state): State<Arc<ApiState>>) -> Json<ChannelsResponse> {
     let pools = state.agent_pools.load();
@@ -72,6 +88,7 @@ pub(super) async fn list_channels(State(state): State<Arc<ApiState>>) -> Json<Ch
                         id: channel.id,
                         platform: channel.platform,
                         display_name: channel.display_name,
+                        title: channel.title,
                         is_active: channel.is_active,
                         last_activity_at: channel.last_activity_at.to_rfc3339(),
                         created_at: channel.created_at.to_rfc3339(),
@@ -223,3 +240,220 @@ pub(super) async fn cancel_process(
         _ => Err(StatusCode::BAD_REQUEST),
     }
 }
+
+/// Fork a live channel into a new channel that starts with its history.
+pub(super) async fn fork_channel(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<ForkChannelRequest>,
+) -> Result<Json<ForkChannelResponse>, StatusCode> {
+    let states = state.channel_states.read().await;
+    let channel_state = states
+        .get(&request.channel_id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let fork_id = crate::agent::channel::new_fork_channel_id();
+    let history_messages = channel_state
+        .fork(&fork_id, request.display_name.as_deref())
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, channel_id = %request.channel_id, "failed to fork channel");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(ForkChannelResponse {
+        success: true,
+        channel_id: fork_id.to_string(),
+        history_messages,
+    }))
+}
+
+#[derive(Deserialize)]
+pub(super) struct DraftsQuery {
+    #[serde(default)]
+    agent_id: Option<String>,
+    #[serde(default)]
+    channel_id: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(super) struct DraftResponse {
+    agent_id: String,
+    #[serde(flatten)]
+    draft: ReplyDraft,
+}
+
+#[derive(Serialize)]
+pub(super) struct DraftsResponse {
+    drafts: Vec<DraftResponse>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct ResolveDraftRequest {
+    agent_id: String,
+    approval_token: String,
+    /// Replacement text to send instead of the draft (approve only).
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct SupervisionQuery {
+    agent_id: String,
+    channel_id: String,
+}
+
+#[derive(Deserialize)]
+pub(super) struct SupervisionRequest {
+    agent_id: String,
+    channel_id: String,
+    supervised: bool,
+    #[serde(default)]
+    approvers: Vec<String>,
+}
+
+/// List reply drafts waiting for approval, oldest first.
+pub(super) async fn list_drafts(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<DraftsQuery>,
+) -> Json<DraftsResponse> {
+    let pools = state.agent_pools.load();
+    let mut drafts = Vec::new();
+
+    for (agent_id, pool) in pools.iter() {
+        if query.agent_id.as_ref().is_some_and(|id| id != agent_id) {
+            continue;
+        }
+        match DraftStore::new(pool.clone())
+            .list_pending(query.channel_id.as_deref())
+            .await
+        {
+            Ok(pending) => drafts.extend(pending.into_iter().map(|draft| DraftResponse {
+                agent_id: agent_id.clone(),
+                draft,
+            })),
+            Err(error) => tracing::warn!(%error, agent_id, "failed to list reply drafts"),
+        }
+    }
+
+    Json(DraftsResponse { drafts })
+}
+
+/// Approve a reply draft and deliver it, optionally with edited text.
+pub(super) async fn approve_draft(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<ResolveDraftRequest>,
+) -> Result<Json<DraftResponse>, StatusCode> {
+    resolve_draft(&state, request, DraftDecision::Approve).await
+}
+
+/// Reject a reply draft; nothing is sent.
+pub(super) async fn reject_draft(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<ResolveDraftRequest>,
+) -> Result<Json<DraftResponse>, StatusCode> {
+    resolve_draft(&state, request, DraftDecision::Reject).await
+}
+
+async fn resolve_draft(
+    state: &ApiState,
+    request: ResolveDraftRequest,
+    decision: DraftDecision,
+) -> Result<Json<DraftResponse>, StatusCode> {
+    let pool = state
+        .agent_pools
+        .load()
+        .get(&request.agent_id)
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let drafts = DraftStore::new(pool);
+    let draft = drafts
+        .get(&request.approval_token)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to load reply draft");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let text = request
+        .text
+        .as_deref()
+        .filter(|text| !text.trim().is_empty());
+    let resolved = match decision {
+        // Delivery goes through the live channel's outbound route.
+        DraftDecision::Approve => {
+            let states = state.channel_states.read().await;
+            let channel_state = states.get(&draft.channel_id).ok_or(StatusCode::CONFLICT)?;
+            channel_state
+                .resolve_draft(&request.approval_token, decision, text, "api")
+                .await
+        }
+        DraftDecision::Reject => {
+            drafts
+                .resolve(&request.approval_token, decision, None, "api")
+                .await
+        }
+    }
+    .map_err(|error| {
+        tracing::error!(%error, "failed to resolve reply draft");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    // Already approved or rejected.
+    .ok_or(StatusCode::CONFLICT)?;
+
+    Ok(Json(DraftResponse {
+        agent_id: request.agent_id,
+        draft: resolved,
+    }))
+}
+
+/// Get a channel's supervision settings.
+pub(super) async fn get_supervision(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<SupervisionQuery>,
+) -> Result<Json<Supervision>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+
+    DraftStore::new(pool.clone())
+        .supervision(&query.channel_id)
+        .await
+        .map(Json)
+        .map_err(|error| {
+            tracing::error!(%error, "failed to load channel supervision");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// Turn supervised mode on or off for a channel.
+pub(super) async fn set_supervision(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<SupervisionRequest>,
+) -> Result<Json<Supervision>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools.get(&request.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let supervision = Supervision {
+        supervised: request.supervised,
+        approvers: request.approvers,
+    };
+
+    let updated = DraftStore::new(pool.clone())
+        .set_supervision(&request.channel_id, &supervision)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to update channel supervision");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if !updated {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    tracing::info!(
+        agent_id = %request.agent_id,
+        channel_id = %request.channel_id,
+        supervised = supervision.supervised,
+        "channel supervision updated via API"
+    );
+
+    Ok(Json(supervision))
+}
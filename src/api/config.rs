@@ -14,7 +14,9 @@ pub(super) struct RoutingSection {
     compactor: String,
     cortex: String,
     voice: String,
+    vision: String,
     rate_limit_cooldown_secs: u64,
+    retry_base_delay_ms: u64,
 }
 
 #[derive(Serialize, Debug)]
@@ -130,7 +132,9 @@ pub(super) struct RoutingUpdate {
     compactor: Option<String>,
     cortex: Option<String>,
     voice: Option<String>,
+    vision: Option<String>,
     rate_limit_cooldown_secs: Option<u64>,
+    retry_base_delay_ms: Option<u64>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -223,7 +227,9 @@ pub(super) async fn get_agent_config(
             compactor: routing.compactor.clone(),
             cortex: routing.cortex.clone(),
             voice: routing.voice.clone(),
+            vision: routing.vision.clone(),
             rate_limit_cooldown_secs: routing.rate_limit_cooldown_secs,
+            retry_base_delay_ms: routing.retry_base_delay_ms,
         },
         tuning: TuningSection {
             max_concurrent_branches: **rc.max_concurrent_branches.load(),
@@ -471,9 +477,15 @@ fn update_routing_table(
     if let Some(ref v) = routing.voice {
         table["voice"] = toml_edit::value(v.as_str());
     }
+    if let Some(ref v) = routing.vision {
+        table["vision"] = toml_edit::value(v.as_str());
+    }
     if let Some(v) = routing.rate_limit_cooldown_secs {
         table["rate_limit_cooldown_secs"] = toml_edit::value(v as i64);
     }
+    if let Some(v) = routing.retry_base_delay_ms {
+        table["retry_base_delay_ms"] = toml_edit::value(v as i64);
+    }
     Ok(())
 }
 
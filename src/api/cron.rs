@@ -31,6 +31,8 @@ pub(super) struct CreateCronRequest {
     prompt: String,
     #[serde(default = "default_interval")]
     interval_secs: u64,
+    #[serde(default)]
+    cron_expr: Option<String>,
     delivery_target: String,
     #[serde(default)]
     active_start_hour: Option<u8>,
@@ -42,6 +44,8 @@ pub(super) struct CreateCronRequest {
     run_once: bool,
     #[serde(default)]
     timeout_secs: Option<u64>,
+    #[serde(default)]
+    stateful: bool,
 }
 
 fn default_interval() -> u64 {
@@ -76,11 +80,13 @@ struct CronJobWithStats {
     id: String,
     prompt: String,
     interval_secs: u64,
+    cron_expr: Option<String>,
     delivery_target: String,
     enabled: bool,
     run_once: bool,
     active_hours: Option<(u8, u8)>,
     timeout_secs: Option<u64>,
+    stateful: bool,
     success_count: u64,
     failure_count: u64,
     last_executed_at: Option<String>,
@@ -131,11 +137,13 @@ pub(super) async fn list_cron_jobs(
             id: config.id,
             prompt: config.prompt,
             interval_secs: config.interval_secs,
+            cron_expr: config.cron_expr,
             delivery_target: config.delivery_target,
             enabled: config.enabled,
             run_once: config.run_once,
             active_hours: config.active_hours,
             timeout_secs: config.timeout_secs,
+            stateful: config.stateful,
             success_count: stats.success_count,
             failure_count: stats.failure_count,
             last_executed_at: stats.last_executed_at,
@@ -199,11 +207,13 @@ pub(super) async fn create_or_update_cron(
         id: request.id.clone(),
         prompt: request.prompt,
         interval_secs: request.interval_secs,
+        cron_expr: request.cron_expr,
         delivery_target: request.delivery_target,
         active_hours,
         enabled: request.enabled,
         run_once: request.run_once,
         timeout_secs: request.timeout_secs,
+        stateful: request.stateful,
     };
 
     store.save(&config).await.map_err(|error| {
@@ -0,0 +1,566 @@
+//! MCP server facade: lets other agent frameworks delegate to this instance.
+//!
+//! `POST /api/mcp-server` speaks MCP's JSON-RPC over the Streamable HTTP
+//! transport, answering every request with a single JSON response. There is
+//! no SSE stream and no session state, so `GET` is refused as the spec
+//! allows. Requests go through the same bearer-token auth as the rest of the
+//! API.
+//!
+//! Conversations started with `ask_agent` are ordinary webchat channels with
+//! the conversation ID `mcp:{agent_id}:{conversation}`. `spawn_worker` runs
+//! in one of those channels, so the worker shows up in the dashboard and its
+//! result lands in the channel like any other.
+
+use super::memories::parse_memory_type;
+use super::state::ApiState;
+use crate::conversation::history::ProcessRunLogger;
+use crate::memory::search::{SearchConfig, SearchMode};
+use crate::messaging::webchat::WebChatEvent;
+use crate::{InboundMessage, MessageContent};
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Protocol revisions this facade understands, newest first.
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const DEFAULT_ASK_TIMEOUT_SECS: u64 = 120;
+const MAX_ASK_TIMEOUT_SECS: u64 = 600;
+const MAX_MEMORY_RESULTS: usize = 50;
+
+// JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+#[derive(Deserialize)]
+struct JsonRpcRequest {
+    /// Absent for notifications.
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct ToolCallParams {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+#[derive(Deserialize)]
+struct AskAgentArgs {
+    agent_id: String,
+    message: String,
+    conversation_id: Option<String>,
+    sender_name: Option<String>,
+    timeout_secs: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct SpawnWorkerArgs {
+    agent_id: String,
+    conversation_id: String,
+    task: String,
+    #[serde(default)]
+    suggested_skills: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct GetWorkerArgs {
+    agent_id: String,
+    worker_id: String,
+}
+
+#[derive(Deserialize)]
+struct QueryMemoryArgs {
+    agent_id: String,
+    query: String,
+    limit: Option<usize>,
+    memory_type: Option<String>,
+}
+
+/// Handle one JSON-RPC message.
+pub(super) async fn mcp_post(State(state): State<Arc<ApiState>>, body: String) -> Response {
+    let request: JsonRpcRequest = match serde_json::from_str::<Value>(&body) {
+        Err(error) => return rpc_error(Value::Null, PARSE_ERROR, error.to_string()),
+        Ok(value) => match serde_json::from_value(value) {
+            Ok(request) => request,
+            Err(error) => return rpc_error(Value::Null, INVALID_REQUEST, error.to_string()),
+        },
+    };
+
+    // Notifications (initialized, cancelled, ...) need no answer.
+    let Some(id) = request.id else {
+        return StatusCode::ACCEPTED.into_response();
+    };
+
+    match request.method.as_str() {
+        "initialize" => rpc_result(id, initialize_result(&request.params)),
+        "ping" => rpc_result(id, json!({})),
+        "tools/list" => rpc_result(id, json!({ "tools": tool_definitions() })),
+        "tools/call" => {
+            let params: ToolCallParams = match serde_json::from_value(request.params) {
+                Ok(params) => params,
+                Err(error) => return rpc_error(id, INVALID_PARAMS, error.to_string()),
+            };
+            match call_tool(&state, &params.name, params.arguments).await {
+                Some(outcome) => rpc_result(id, tool_result(outcome)),
+                None => rpc_error(id, INVALID_PARAMS, format!("unknown tool: {}", params.name)),
+            }
+        }
+        method => rpc_error(id, METHOD_NOT_FOUND, format!("method not found: {method}")),
+    }
+}
+
+/// There is no server-initiated stream to open.
+pub(super) async fn mcp_get() -> StatusCode {
+    StatusCode::METHOD_NOT_ALLOWED
+}
+
+fn rpc_result(id: Value, result: Value) -> Response {
+    Json(json!({ "jsonrpc": "2.0", "id": id, "result": result })).into_response()
+}
+
+fn rpc_error(id: Value, code: i64, message: String) -> Response {
+    Json(json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    }))
+    .into_response()
+}
+
+fn initialize_result(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let protocol_version = requested
+        .filter(|version| SUPPORTED_PROTOCOL_VERSIONS.contains(version))
+        .unwrap_or(SUPPORTED_PROTOCOL_VERSIONS[0]);
+
+    json!({
+        "protocolVersion": protocol_version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": {
+            "name": "spacebot",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "instructions": "Delegate to Spacebot agents. Call list_agents first. \
+            ask_agent holds a conversation with an agent; reuse its conversation_id to continue it. \
+            spawn_worker starts a background task in such a conversation; poll it with get_worker.",
+    })
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "list_agents",
+            "description": "List the agents on this Spacebot instance.",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+        {
+            "name": "ask_agent",
+            "description": "Send a message to an agent and wait for its reply. Pass the returned conversation_id again to continue the same conversation.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "agent_id": { "type": "string" },
+                    "message": { "type": "string" },
+                    "conversation_id": {
+                        "type": "string",
+                        "description": "Conversation to continue. Omit to start a new one."
+                    },
+                    "sender_name": {
+                        "type": "string",
+                        "description": "How the agent should refer to you. Defaults to \"mcp\"."
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "How long to wait for the reply (default 120, max 600)."
+                    }
+                },
+                "required": ["agent_id", "message"],
+            },
+        },
+        {
+            "name": "spawn_worker",
+            "description": "Start a background worker for a task in a conversation started with ask_agent. Returns the worker ID; poll it with get_worker.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "agent_id": { "type": "string" },
+                    "conversation_id": { "type": "string" },
+                    "task": { "type": "string" },
+                    "suggested_skills": { "type": "array", "items": { "type": "string" } }
+                },
+                "required": ["agent_id", "conversation_id", "task"],
+            },
+        },
+        {
+            "name": "get_worker",
+            "description": "Get the status and, once finished, the result of a worker.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "agent_id": { "type": "string" },
+                    "worker_id": { "type": "string" }
+                },
+                "required": ["agent_id", "worker_id"],
+            },
+        },
+        {
+            "name": "query_memory",
+            "description": "Search an agent's memories.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "agent_id": { "type": "string" },
+                    "query": { "type": "string" },
+                    "limit": { "type": "integer", "description": "Max results (default 10, max 50)." },
+                    "memory_type": {
+                        "type": "string",
+                        "description": "Only return memories of this type (fact, preference, decision, identity, event, observation, goal, todo)."
+                    }
+                },
+                "required": ["agent_id", "query"],
+            },
+        },
+    ])
+}
+
+/// A tool's outcome. Tool failures are results with `isError`, not JSON-RPC
+/// errors, so the calling model can see them.
+fn tool_result(outcome: Result<Value, String>) -> Value {
+    match outcome {
+        Ok(value) => {
+            let text = serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string());
+            json!({
+                "content": [{ "type": "text", "text": text }],
+                "structuredContent": value,
+                "isError": false,
+            })
+        }
+        Err(message) => json!({
+            "content": [{ "type": "text", "text": message }],
+            "isError": true,
+        }),
+    }
+}
+
+/// Run a tool. `None` when no tool has that name.
+async fn call_tool(
+    state: &ApiState,
+    name: &str,
+    arguments: Value,
+) -> Option<Result<Value, String>> {
+    fn parse<T: serde::de::DeserializeOwned>(arguments: Value) -> Result<T, String> {
+        serde_json::from_value(arguments).map_err(|error| format!("invalid arguments: {error}"))
+    }
+
+    let outcome = match name {
+        "list_agents" => Ok(list_agents(state)),
+        "ask_agent" => match parse(arguments) {
+            Ok(args) => ask_agent(state, args).await,
+            Err(error) => Err(error),
+        },
+        "spawn_worker" => match parse(arguments) {
+            Ok(args) => spawn_worker(state, args).await,
+            Err(error) => Err(error),
+        },
+        "get_worker" => match parse(arguments) {
+            Ok(args) => get_worker(state, args).await,
+            Err(error) => Err(error),
+        },
+        "query_memory" => match parse(arguments) {
+            Ok(args) => query_memory(state, args).await,
+            Err(error) => Err(error),
+        },
+        _ => return None,
+    };
+    Some(outcome)
+}
+
+/// The conversation ID of an MCP conversation with an agent.
+fn mcp_conversation_id(agent_id: &str, conversation: &str) -> String {
+    format!("mcp:{agent_id}:{conversation}")
+}
+
+fn ensure_agent(state: &ApiState, agent_id: &str) -> Result<(), String> {
+    if state
+        .agent_configs
+        .load()
+        .iter()
+        .any(|agent| agent.id == agent_id)
+    {
+        Ok(())
+    } else {
+        Err(format!("unknown agent '{agent_id}'"))
+    }
+}
+
+fn list_agents(state: &ApiState) -> Value {
+    let agents: Vec<Value> = state
+        .agent_configs
+        .load()
+        .iter()
+        .map(|agent| {
+            json!({
+                "id": agent.id,
+                "display_name": agent.display_name,
+                "role": agent.role,
+            })
+        })
+        .collect();
+    json!({ "agents": agents })
+}
+
+async fn ask_agent(state: &ApiState, args: AskAgentArgs) -> Result<Value, String> {
+    ensure_agent(state, &args.agent_id)?;
+    let webchat = state
+        .webchat_adapter
+        .load()
+        .as_ref()
+        .as_ref()
+        .cloned()
+        .ok_or("messaging is not running")?;
+    let manager = state
+        .messaging_manager
+        .read()
+        .await
+        .clone()
+        .ok_or("messaging is not running")?;
+
+    let conversation = args
+        .conversation_id
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let conversation_id = mcp_conversation_id(&args.agent_id, &conversation);
+    let sender_name = args.sender_name.unwrap_or_else(|| "mcp".into());
+
+    let mut events = webchat.register_session(&conversation_id).await;
+
+    let mut metadata = HashMap::new();
+    metadata.insert("display_name".into(), Value::String(sender_name.clone()));
+    let inbound = InboundMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        source: "webchat".into(),
+        conversation_id: conversation_id.clone(),
+        sender_id: sender_name.clone(),
+        agent_id: Some(args.agent_id.clone().into()),
+        content: MessageContent::Text(args.message),
+        timestamp: chrono::Utc::now(),
+        metadata,
+        formatted_author: Some(sender_name),
+    };
+    if let Err(error) = manager.inject_message(inbound).await {
+        webchat.unregister_session(&conversation_id).await;
+        return Err(format!("failed to deliver message: {error}"));
+    }
+
+    let timeout = Duration::from_secs(
+        args.timeout_secs
+            .unwrap_or(DEFAULT_ASK_TIMEOUT_SECS)
+            .clamp(1, MAX_ASK_TIMEOUT_SECS),
+    );
+    let reply = tokio::time::timeout(timeout, collect_reply(&mut events)).await;
+    webchat.unregister_session(&conversation_id).await;
+
+    match reply {
+        Ok(reply) => Ok(json!({ "conversation_id": conversation, "reply": reply })),
+        Err(_) => Ok(json!({
+            "conversation_id": conversation,
+            "reply": null,
+            "note": "The agent did not reply in time. It may still be working; ask again in the same conversation.",
+        })),
+    }
+}
+
+/// Collect a reply until the adapter signals it's complete.
+async fn collect_reply(events: &mut mpsc::Receiver<WebChatEvent>) -> String {
+    let mut reply = String::new();
+    while let Some(event) = events.recv().await {
+        match event {
+            WebChatEvent::Text(text)
+            | WebChatEvent::Structured(crate::StructuredReply { message: text, .. }) => {
+                if !reply.is_empty() {
+                    reply.push_str("\n\n");
+                }
+                reply.push_str(&text);
+            }
+            WebChatEvent::StreamChunk(chunk) => reply.push_str(&chunk),
+            WebChatEvent::Done => break,
+            _ => {}
+        }
+    }
+    reply
+}
+
+async fn spawn_worker(state: &ApiState, args: SpawnWorkerArgs) -> Result<Value, String> {
+    ensure_agent(state, &args.agent_id)?;
+    let channel_id = mcp_conversation_id(&args.agent_id, &args.conversation_id);
+    let channel_state = state
+        .channel_states
+        .read()
+        .await
+        .get(&channel_id)
+        .cloned()
+        .ok_or_else(|| {
+            format!(
+                "no active conversation '{}'. Start one with ask_agent first",
+                args.conversation_id
+            )
+        })?;
+
+    let skills: Vec<&str> = args.suggested_skills.iter().map(String::as_str).collect();
+    let worker_id = crate::agent::channel::spawn_worker_from_state(
+        &channel_state,
+        args.task,
+        false,
+        &skills,
+        false,
+        None,
+        None,
+    )
+    .await
+    .map_err(|error| error.to_string())?;
+
+    Ok(json!({ "worker_id": worker_id.to_string() }))
+}
+
+async fn get_worker(state: &ApiState, args: GetWorkerArgs) -> Result<Value, String> {
+    let pool = state
+        .agent_pools
+        .load()
+        .get(&args.agent_id)
+        .cloned()
+        .ok_or_else(|| format!("unknown agent '{}'", args.agent_id))?;
+
+    let detail = ProcessRunLogger::new(pool)
+        .get_worker_detail(&args.agent_id, &args.worker_id)
+        .await
+        .map_err(|error| error.to_string())?
+        .ok_or_else(|| format!("no worker '{}'", args.worker_id))?;
+
+    Ok(json!({
+        "worker_id": detail.id,
+        "task": detail.task,
+        "status": detail.status,
+        "result": detail.result,
+        "started_at": detail.started_at,
+        "completed_at": detail.completed_at,
+        "tool_calls": detail.tool_calls,
+    }))
+}
+
+async fn query_memory(state: &ApiState, args: QueryMemoryArgs) -> Result<Value, String> {
+    let memory_search = state
+        .memory_searches
+        .load()
+        .get(&args.agent_id)
+        .cloned()
+        .ok_or_else(|| format!("unknown agent '{}'", args.agent_id))?;
+
+    let memory_type = match args.memory_type.as_deref() {
+        Some(name) => {
+            Some(parse_memory_type(name).ok_or_else(|| format!("unknown memory type '{name}'"))?)
+        }
+        None => None,
+    };
+    let config = SearchConfig {
+        mode: SearchMode::Hybrid,
+        memory_type,
+        max_results: args.limit.unwrap_or(10).clamp(1, MAX_MEMORY_RESULTS),
+        ..SearchConfig::default()
+    };
+
+    let results = memory_search
+        .search(&args.query, &config)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    let memories: Vec<Value> = results
+        .into_iter()
+        .map(|result| {
+            json!({
+                "id": result.memory.id,
+                "memory_type": result.memory.memory_type.to_string(),
+                "content": result.memory.content,
+                "score": result.score,
+                "created_at": result.memory.created_at.to_rfc3339(),
+            })
+        })
+        .collect();
+    Ok(json!({ "memories": memories }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn initialize_negotiates_protocol_version() {
+        let result = initialize_result(&json!({ "protocolVersion": "2025-03-26" }));
+        assert_eq!(result["protocolVersion"], "2025-03-26");
+
+        let result = initialize_result(&json!({ "protocolVersion": "1999-01-01" }));
+        assert_eq!(result["protocolVersion"], SUPPORTED_PROTOCOL_VERSIONS[0]);
+        assert!(result["capabilities"]["tools"].is_object());
+    }
+
+    #[test]
+    fn every_tool_has_an_object_schema() {
+        let tools = tool_definitions();
+        let names: Vec<&str> = tools
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| {
+                assert_eq!(tool["inputSchema"]["type"], "object");
+                tool["name"].as_str().unwrap()
+            })
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "list_agents",
+                "ask_agent",
+                "spawn_worker",
+                "get_worker",
+                "query_memory"
+            ]
+        );
+    }
+
+    #[test]
+    fn tool_errors_are_results() {
+        let result = tool_result(Err("unknown agent 'x'".into()));
+        assert_eq!(result["isError"], true);
+        assert_eq!(result["content"][0]["text"], "unknown agent 'x'");
+    }
+
+    #[tokio::test]
+    async fn reply_collects_stream_until_done() {
+        let (tx, mut rx) = mpsc::channel(8);
+        for event in [
+            WebChatEvent::Thinking,
+            WebChatEvent::StreamStart,
+            WebChatEvent::StreamChunk("Hel".into()),
+            WebChatEvent::StreamChunk("lo".into()),
+            WebChatEvent::StreamEnd,
+            WebChatEvent::Done,
+            WebChatEvent::Text("ignored".into()),
+        ] {
+            tx.send(event).await.unwrap();
+        }
+        assert_eq!(collect_reply(&mut rx).await, "Hello");
+    }
+}
@@ -0,0 +1,139 @@
+//! Media endpoints: signed downloads of stored attachments, and a listing
+//! for the control interface.
+
+use super::state::ApiState;
+
+use crate::media::StoredMedia;
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub(super) struct SignedMediaQuery {
+    expires: i64,
+    signature: String,
+}
+
+/// Serve a stored file from a signed link. Mounted outside the API token
+/// check: the signature is the authorization.
+pub(super) async fn download_media(
+    State(state): State<Arc<ApiState>>,
+    Path((agent_id, media_id)): Path<(String, String)>,
+    Query(query): Query<SignedMediaQuery>,
+) -> Result<Response, StatusCode> {
+    let stores = state.media_stores.load();
+    let store = stores.get(&agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    if !store.verify(&media_id, query.expires, &query.signature) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let (media, data) = store
+        .read(&media_id)
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, %agent_id, %media_id, "failed to read media");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    // The MIME type comes from whoever sent the file, and this route shares
+    // an origin with the control interface. Anything but a plain raster
+    // image is downloaded rather than rendered, so HTML or SVG can't run.
+    let disposition = format!(
+        "{}; filename=\"{}\"",
+        if is_inline_safe(&media.mime_type) {
+            "inline"
+        } else {
+            "attachment"
+        },
+        media.filename.replace(['"', '\\', '\r', '\n'], "_")
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, media.mime_type),
+            (header::CONTENT_DISPOSITION, disposition),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+            (header::CACHE_CONTROL, "private, max-age=3600".to_string()),
+        ],
+        data,
+    )
+        .into_response())
+}
+
+/// Image types browsers render without running anything.
+fn is_inline_safe(mime_type: &str) -> bool {
+    matches!(
+        mime_type.trim().to_ascii_lowercase().as_str(),
+        "image/png" | "image/jpeg" | "image/gif" | "image/webp"
+    )
+}
+
+#[derive(Deserialize)]
+pub(super) struct MediaListQuery {
+    agent_id: String,
+    channel_id: String,
+    #[serde(default = "default_limit")]
+    limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+#[derive(Serialize)]
+pub(super) struct MediaEntry {
+    #[serde(flatten)]
+    media: StoredMedia,
+    url: String,
+}
+
+#[derive(Serialize)]
+pub(super) struct MediaListResponse {
+    media: Vec<MediaEntry>,
+}
+
+/// List a channel's stored files, newest first, with fresh signed links.
+pub(super) async fn list_media(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<MediaListQuery>,
+) -> Result<Json<MediaListResponse>, StatusCode> {
+    let stores = state.media_stores.load();
+    let store = stores.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+
+    let media = store
+        .list(&query.channel_id, query.limit.clamp(1, 200))
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, "failed to list media");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(MediaListResponse {
+        media: media
+            .into_iter()
+            .map(|media| MediaEntry {
+                url: store.url(&media),
+                media,
+            })
+            .collect(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_raster_images_are_served_inline() {
+        assert!(is_inline_safe("image/png"));
+        assert!(is_inline_safe("IMAGE/JPEG"));
+        assert!(!is_inline_safe("image/svg+xml"));
+        assert!(!is_inline_safe("text/html"));
+        assert!(!is_inline_safe("application/pdf"));
+        assert!(!is_inline_safe("image/png; charset=utf-8"));
+    }
+}
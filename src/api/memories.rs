@@ -137,7 +137,7 @@ pub(super) async fn list_memories(
 
     let fetch_limit = limit + query.offset as i64;
     let all = store
-        .get_sorted(sort, fetch_limit, memory_type)
+        .get_sorted(sort, fetch_limit, memory_type, None)
         .await
         .map_err(|error| {
             tracing::warn!(%error, agent_id = %query.agent_id, "failed to list memories");
@@ -190,7 +190,7 @@ pub(super) async fn memory_graph(
 
     let fetch_limit = limit + query.offset as i64;
     let all = store
-        .get_sorted(sort, fetch_limit, memory_type)
+        .get_sorted(sort, fetch_limit, memory_type, None)
         .await
         .map_err(|error| {
             tracing::warn!(%error, agent_id = %query.agent_id, "failed to load graph nodes");
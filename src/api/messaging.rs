@@ -387,6 +387,7 @@ pub(super) async fn toggle_platform(
                             webhook_config.port,
                             &webhook_config.bind,
                             webhook_config.auth_token.clone(),
+                            webhook_config.sources.clone(),
                         );
                         if let Err(error) = manager.register_and_start(adapter).await {
                             tracing::error!(%error, "failed to start webhook adapter on toggle");
@@ -418,6 +419,20 @@ pub(super) async fn toggle_platform(
                         }
                     }
                 }
+                "email" => {
+                    if let Some(email_config) = &new_config.messaging.email {
+                        match crate::messaging::email::EmailAdapter::new(email_config.clone()) {
+                            Ok(adapter) => {
+                                if let Err(error) = manager.register_and_start(adapter).await {
+                                    tracing::error!(%error, "failed to start email adapter on toggle");
+                                }
+                            }
+                            Err(error) => {
+                                tracing::error!(%error, "failed to build email adapter on toggle");
+                            }
+                        }
+                    }
+                }
                 _ => {}
             }
         }
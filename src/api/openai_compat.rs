@@ -0,0 +1,481 @@
+//! OpenAI-compatible facade: lets chat clients talk to an agent as a model.
+//!
+//! `POST /v1/chat/completions` accepts the OpenAI chat completions request
+//! format, with `model` naming the agent, and answers with a completion or,
+//! with `stream: true`, a stream of `chat.completion.chunk` events ending in
+//! `data: [DONE]`. `GET /v1/models` lists the agents. Both go through the
+//! same bearer-token auth as the rest of the API, so clients pass the API
+//! token as their API key.
+//!
+//! Each API conversation is an ordinary webchat channel with the
+//! conversation ID `openai:{agent_id}:{conversation}`. Clients resend the
+//! whole message list on every request; the channel already holds the
+//! history, so only the last user message is delivered. The conversation is
+//! taken from the `x-conversation-id` header. Otherwise a request continues
+//! the conversation whose reply its history ends with, and a history without
+//! a reply we gave starts a new one.
+
+use super::state::ApiState;
+use crate::messaging::webchat::WebChatEvent;
+use crate::{InboundMessage, MessageContent};
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::sse::{Event, Sse};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, mpsc};
+
+/// How long to wait for the agent's reply before giving up.
+const REPLY_TIMEOUT_SECS: u64 = 300;
+
+/// Header that pins a request to a conversation.
+const CONVERSATION_HEADER: &str = "x-conversation-id";
+
+/// How long a reply can be continued from without `x-conversation-id`.
+const CONTINUATION_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Most replies remembered for continuation.
+const MAX_CONTINUATIONS: usize = 10_000;
+
+#[derive(Deserialize)]
+pub(super) struct ChatCompletionRequest {
+    /// The agent ID.
+    model: String,
+    messages: Vec<ChatMessage>,
+    #[serde(default)]
+    stream: bool,
+    /// End-user identifier, used as the sender name.
+    user: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    role: String,
+    /// A string, or an array of content parts.
+    #[serde(default)]
+    content: Value,
+}
+
+impl ChatMessage {
+    /// The message's text. Non-text content parts are dropped.
+    fn text(&self) -> String {
+        match &self.content {
+            Value::String(text) => text.clone(),
+            Value::Array(parts) => parts
+                .iter()
+                .filter(|part| part["type"] == "text")
+                .filter_map(|part| part["text"].as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        }
+    }
+}
+
+/// An OpenAI-style error response.
+fn error_response(status: StatusCode, kind: &str, message: impl Into<String>) -> Response {
+    let body = json!({
+        "error": {
+            "message": message.into(),
+            "type": kind,
+            "code": null,
+        }
+    });
+    (status, Json(body)).into_response()
+}
+
+/// List agents in the OpenAI models format.
+pub(super) async fn list_models(State(state): State<Arc<ApiState>>) -> Json<Value> {
+    let models: Vec<Value> = state
+        .agent_configs
+        .load()
+        .iter()
+        .map(|agent| {
+            json!({
+                "id": agent.id,
+                "object": "model",
+                "created": 0,
+                "owned_by": "spacebot",
+            })
+        })
+        .collect();
+    Json(json!({ "object": "list", "data": models }))
+}
+
+/// Send the last user message to the agent and return its reply.
+pub(super) async fn chat_completions(
+    State(state): State<Arc<ApiState>>,
+    headers: HeaderMap,
+    Json(request): Json<ChatCompletionRequest>,
+) -> Response {
+    let agent_id = request.model.clone();
+    if !state
+        .agent_configs
+        .load()
+        .iter()
+        .any(|agent| agent.id == agent_id)
+    {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "invalid_request_error",
+            format!("The model '{agent_id}' does not exist"),
+        );
+    }
+
+    let Some(message) = request
+        .messages
+        .iter()
+        .rev()
+        .find(|message| message.role == "user")
+        .map(ChatMessage::text)
+        .filter(|text| !text.trim().is_empty())
+    else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "invalid_request_error",
+            "messages must contain a user message with text",
+        );
+    };
+
+    let webchat = state.webchat_adapter.load().as_ref().as_ref().cloned();
+    let manager = state.messaging_manager.read().await.clone();
+    let (Some(webchat), Some(manager)) = (webchat, manager) else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "server_error",
+            "messaging is not running",
+        );
+    };
+
+    let pinned = headers
+        .get(CONVERSATION_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned);
+    let conversation = match &pinned {
+        Some(conversation) => conversation.clone(),
+        None => state
+            .openai_conversations
+            .continued(&agent_id, &request.messages)
+            .await
+            .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string()),
+    };
+    let conversation_id = format!("openai:{agent_id}:{conversation}");
+    let sender_name = request.user.clone().unwrap_or_else(|| "openai".into());
+
+    // One request per conversation at a time: a second one would take over
+    // the reply stream and leave the first with nothing.
+    let Some(mut events) = webchat.try_register_session(&conversation_id).await else {
+        return error_response(
+            StatusCode::CONFLICT,
+            "conflict_error",
+            "a request for this conversation is already in progress",
+        );
+    };
+    // Remembered once the reply is complete, so the client's next request,
+    // which ends with this reply, lands in the same conversation.
+    let remember = pinned.is_none().then(|| {
+        let state = state.clone();
+        let agent_id = agent_id.clone();
+        let messages = request.messages;
+        move |reply: String| async move {
+            state
+                .openai_conversations
+                .remember(&agent_id, &messages, &reply, conversation)
+                .await;
+        }
+    });
+
+    let mut metadata = HashMap::new();
+    metadata.insert("display_name".into(), Value::String(sender_name.clone()));
+    let inbound = InboundMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        source: "webchat".into(),
+        conversation_id: conversation_id.clone(),
+        sender_id: sender_name.clone(),
+        agent_id: Some(agent_id.clone().into()),
+        content: MessageContent::Text(message),
+        timestamp: chrono::Utc::now(),
+        metadata,
+        formatted_author: Some(sender_name),
+    };
+    if let Err(error) = manager.inject_message(inbound).await {
+        webchat.unregister_session(&conversation_id).await;
+        tracing::warn!(%error, "failed to inject openai-compatible message");
+        return error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "server_error",
+            "failed to deliver message",
+        );
+    }
+
+    let completion_id = format!("chatcmpl-{}", uuid::Uuid::new_v4().simple());
+    let created = chrono::Utc::now().timestamp();
+    let timeout = Duration::from_secs(REPLY_TIMEOUT_SECS);
+
+    if !request.stream {
+        let reply = tokio::time::timeout(timeout, next_reply_text(&mut events)).await;
+        webchat.unregister_session(&conversation_id).await;
+        let Ok(reply) = reply else {
+            return error_response(
+                StatusCode::GATEWAY_TIMEOUT,
+                "timeout",
+                "the agent did not reply in time",
+            );
+        };
+        if let Some(remember) = remember {
+            remember(reply.clone()).await;
+        }
+        return Json(json!({
+            "id": completion_id,
+            "object": "chat.completion",
+            "created": created,
+            "model": agent_id,
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": reply },
+                "finish_reason": "stop",
+            }],
+        }))
+        .into_response();
+    }
+
+    let chunk = move |delta: Value, finish_reason: Option<&str>| {
+        let data = json!({
+            "id": completion_id,
+            "object": "chat.completion.chunk",
+            "created": created,
+            "model": agent_id,
+            "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }],
+        });
+        Ok::<_, Infallible>(Event::default().data(data.to_string()))
+    };
+
+    let stream = async_stream::stream! {
+        yield chunk(json!({ "role": "assistant" }), None);
+
+        let deadline = tokio::time::sleep(timeout);
+        tokio::pin!(deadline);
+        let mut started = false;
+        let mut reply = String::new();
+        let mut finished = false;
+        loop {
+            let event = tokio::select! {
+                event = events.recv() => event,
+                _ = &mut deadline => None,
+            };
+            let Some(event) = event else { break };
+            match reply_event(event, &mut started) {
+                ReplyEvent::Text(text) => {
+                    reply.push_str(&text);
+                    yield chunk(json!({ "content": text }), None);
+                }
+                ReplyEvent::End => {
+                    finished = true;
+                    break;
+                }
+                ReplyEvent::Ignore => {}
+            }
+        }
+        webchat.unregister_session(&conversation_id).await;
+        if finished && let Some(remember) = remember {
+            remember(reply).await;
+        }
+
+        yield chunk(json!({}), Some("stop"));
+        yield Ok(Event::default().data("[DONE]"));
+    };
+
+    Sse::new(stream).into_response()
+}
+
+/// Conversations of recent replies, for clients that don't send
+/// `x-conversation-id`.
+///
+/// A reply is keyed by a hash of the history that led to it plus the reply
+/// itself. A client's next request resends exactly that, followed by a new
+/// user message, so hashing its history up to the last assistant message
+/// finds the conversation. Two clients only share a conversation if their
+/// histories match up to a reply the agent actually gave one of them.
+#[derive(Default)]
+pub(super) struct ConversationIndex {
+    replies: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+impl ConversationIndex {
+    /// The conversation a request's history continues, if it ends in a
+    /// remembered reply.
+    async fn continued(&self, agent_id: &str, messages: &[ChatMessage]) -> Option<String> {
+        let last_reply = messages
+            .iter()
+            .rposition(|message| message.role == "assistant")?;
+        let key = history_key(agent_id, &messages[..=last_reply], None);
+        self.replies
+            .lock()
+            .await
+            .get(&key)
+            .filter(|(_, at)| at.elapsed() < CONTINUATION_TTL)
+            .map(|(conversation, _)| conversation.clone())
+    }
+
+    /// Remember the conversation `reply` was given in.
+    async fn remember(
+        &self,
+        agent_id: &str,
+        messages: &[ChatMessage],
+        reply: &str,
+        conversation: String,
+    ) {
+        let key = history_key(agent_id, messages, Some(reply));
+        let mut replies = self.replies.lock().await;
+        replies.retain(|_, (_, at)| at.elapsed() < CONTINUATION_TTL);
+        if replies.len() >= MAX_CONTINUATIONS
+            && let Some(oldest) = replies
+                .iter()
+                .min_by_key(|(_, (_, at))| *at)
+                .map(|(key, _)| key.clone())
+        {
+            replies.remove(&oldest);
+        }
+        replies.insert(key, (conversation, Instant::now()));
+    }
+}
+
+/// Hash of a conversation's user and assistant turns, optionally followed
+/// by a reply. System messages are left out, since clients often rewrite
+/// them between requests.
+fn history_key(agent_id: &str, messages: &[ChatMessage], reply: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(agent_id.as_bytes());
+    let turns = messages
+        .iter()
+        .filter(|message| message.role == "user" || message.role == "assistant")
+        .map(|message| (message.role.as_str(), message.text()))
+        .chain(reply.map(|reply| ("assistant", reply.to_string())));
+    for (role, text) in turns {
+        hasher.update([0]);
+        hasher.update(role.as_bytes());
+        hasher.update([0]);
+        hasher.update(text.trim().as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+enum ReplyEvent {
+    Text(String),
+    End,
+    Ignore,
+}
+
+/// What a webchat event means for the reply. A turn that ends without a
+/// reply (the agent skipped, or the turn failed) only sends `StopTyping`,
+/// which counts as the end once the turn has started.
+fn reply_event(event: WebChatEvent, started: &mut bool) -> ReplyEvent {
+    match event {
+        WebChatEvent::Thinking => {
+            *started = true;
+            ReplyEvent::Ignore
+        }
+        WebChatEvent::Text(text)
+        | WebChatEvent::StreamChunk(text)
+        | WebChatEvent::Structured(crate::StructuredReply { message: text, .. }) => {
+            *started = true;
+            ReplyEvent::Text(text)
+        }
+        WebChatEvent::Done => ReplyEvent::End,
+        WebChatEvent::StopTyping if *started => ReplyEvent::End,
+        _ => ReplyEvent::Ignore,
+    }
+}
+
+/// Collect the agent's reply to one message.
+async fn next_reply_text(events: &mut mpsc::Receiver<WebChatEvent>) -> String {
+    let mut reply = String::new();
+    let mut started = false;
+    while let Some(event) = events.recv().await {
+        match reply_event(event, &mut started) {
+            ReplyEvent::Text(text) => reply.push_str(&text),
+            ReplyEvent::End => break,
+            ReplyEvent::Ignore => {}
+        }
+    }
+    reply
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: Value) -> ChatMessage {
+        ChatMessage {
+            role: role.into(),
+            content,
+        }
+    }
+
+    #[test]
+    fn content_parts_are_joined() {
+        let message = message(
+            "user",
+            json!([
+                { "type": "text", "text": "first" },
+                { "type": "image_url", "image_url": { "url": "https://example.com/a.png" } },
+                { "type": "text", "text": "second" },
+            ]),
+        );
+        assert_eq!(message.text(), "first\nsecond");
+    }
+
+    #[tokio::test]
+    async fn conversation_continues_from_its_reply() {
+        let index = ConversationIndex::default();
+        let first = vec![
+            message("system", json!("be brief")),
+            message("user", json!("hello")),
+        ];
+        assert_eq!(index.continued("main", &first).await, None);
+        index.remember("main", &first, "hi!", "abc".into()).await;
+
+        let later = vec![
+            message("system", json!("today is Tuesday")),
+            message("user", json!("hello")),
+            message("assistant", json!("hi! ")),
+            message("user", json!("how are you?")),
+        ];
+        assert_eq!(
+            index.continued("main", &later).await.as_deref(),
+            Some("abc")
+        );
+        assert_eq!(index.continued("other", &later).await, None);
+
+        // Another client opening with the same message gets a different
+        // reply, so it doesn't land in this conversation.
+        let stranger = vec![
+            message("user", json!("hello")),
+            message("assistant", json!("hey there")),
+            message("user", json!("what did I say before?")),
+        ];
+        assert_eq!(index.continued("main", &stranger).await, None);
+    }
+
+    #[tokio::test]
+    async fn skipped_turns_end_with_an_empty_reply() {
+        let (tx, mut rx) = mpsc::channel(8);
+        for event in [
+            WebChatEvent::StopTyping,
+            WebChatEvent::Thinking,
+            WebChatEvent::StopTyping,
+            WebChatEvent::Text("ignored".into()),
+        ] {
+            tx.send(event).await.unwrap();
+        }
+        assert_eq!(next_reply_text(&mut rx).await, "");
+    }
+}
@@ -2,8 +2,9 @@
 
 use super::state::ApiState;
 use super::{
-    agents, bindings, channels, config, cortex, cron, ingest, links, mcp, memories, messaging,
-    models, providers, settings, skills, system, webchat, workers,
+    agents, bindings, channels, config, cortex, cron, ingest, links, mcp, mcp_server, media,
+    memories, messaging, models, openai_compat, providers, settings, skills, system, tool_audit,
+    webchat, workers,
 };
 
 use axum::Json;
@@ -81,6 +82,10 @@ pub async fn start_http_server(
             post(mcp::reconnect_mcp_server),
         )
         .route("/mcp/status", get(mcp::mcp_status))
+        .route(
+            "/mcp-server",
+            post(mcp_server::mcp_post).get(mcp_server::mcp_get),
+        )
         .route("/agents/overview", get(agents::agent_overview))
         .route(
             "/channels",
@@ -90,6 +95,8 @@ pub async fn start_http_server(
         .route("/channels/status", get(channels::channel_status))
         .route("/agents/workers", get(workers::list_workers))
         .route("/agents/workers/detail", get(workers::worker_detail))
+        .route("/agents/tool-audit", get(tool_audit::list_tool_calls))
+        .route("/agents/media", get(media::list_media))
         .route("/agents/memories", get(memories::list_memories))
         .route("/agents/memories/search", get(memories::search_memories))
         .route("/agents/memories/graph", get(memories::memory_graph))
@@ -119,6 +126,14 @@ pub async fn start_http_server(
         .route("/agents/cron/trigger", post(cron::trigger_cron))
         .route("/agents/cron/toggle", put(cron::toggle_cron))
         .route("/channels/cancel", post(channels::cancel_process))
+        .route("/channels/fork", post(channels::fork_channel))
+        .route("/channels/drafts", get(channels::list_drafts))
+        .route("/channels/drafts/approve", post(channels::approve_draft))
+        .route("/channels/drafts/reject", post(channels::reject_draft))
+        .route(
+            "/channels/supervision",
+            get(channels::get_supervision).put(channels::set_supervision),
+        )
         .route(
             "/agents/ingest/files",
             get(ingest::list_ingest_files).delete(ingest::delete_ingest_file),
@@ -196,8 +211,24 @@ pub async fn start_http_server(
             api_auth_middleware,
         ));
 
+    // OpenAI-compatible facade, served at the root so clients can use
+    // `http://host:port/v1` as their base URL.
+    let openai_routes = Router::new()
+        .route("/chat/completions", post(openai_compat::chat_completions))
+        .route("/models", get(openai_compat::list_models))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            api_auth_middleware,
+        ));
+
+    // Signed links to stored attachments. No token check: the link's
+    // signature authorizes the download.
+    let media_routes = Router::new().route("/{agent_id}/{media_id}", get(media::download_media));
+
     let app = Router::new()
         .nest("/api", api_routes)
+        .nest("/v1", openai_routes)
+        .nest("/media", media_routes)
         .fallback(static_handler)
         .layer(cors)
         .layer(DefaultBodyLimit::max(10 * 1024 * 1024)) // 10 MiB
@@ -5,6 +5,7 @@ use axum::extract::{Query, State};
 use axum::http::StatusCode;
 use moka::sync::Cache;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::LazyLock;
 use std::time::Duration;
@@ -26,6 +27,8 @@ pub(super) struct SkillInfo {
     description: String,
     file_path: String,
     base_dir: String,
+    metadata: HashMap<String, String>,
+    tools: Vec<String>,
     source: String,
 }
 
@@ -141,6 +144,8 @@ pub(super) async fn list_skills(
             description: s.description,
             file_path: s.file_path.display().to_string(),
             base_dir: s.base_dir.display().to_string(),
+            metadata: s.metadata,
+            tools: s.tools,
             source: match s.source {
                 crate::skills::SkillSource::Instance => "instance".to_string(),
                 crate::skills::SkillSource::Workspace => "workspace".to_string(),
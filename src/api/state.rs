@@ -3,10 +3,16 @@
 use crate::agent::channel::ChannelState;
 use crate::agent::cortex_chat::CortexChatSession;
 use crate::agent::status::StatusBlock;
-use crate::config::{Binding, DefaultsConfig, DiscordPermissions, RuntimeConfig, SlackPermissions};
+use crate::agent::worker::{WorkerArtifact, WorkerResultStatus};
+use crate::config::{
+    Binding, DefaultsConfig, DiscordPermissions, MediaConfig, RuntimeConfig, SlackPermissions,
+    StorageConfig,
+};
 use crate::cron::{CronStore, Scheduler};
+use crate::knowledge::KnowledgeBase;
 use crate::llm::LlmManager;
 use crate::mcp::McpManager;
+use crate::media::MediaStore;
 use crate::memory::{EmbeddingModel, MemorySearch};
 use crate::messaging::MessagingManager;
 use crate::messaging::webchat::WebChatAdapter;
@@ -50,6 +56,10 @@ pub struct ApiState {
     pub agent_configs: arc_swap::ArcSwap<Vec<AgentInfo>>,
     /// Per-agent memory search instances for the memories API.
     pub memory_searches: arc_swap::ArcSwap<HashMap<String, Arc<MemorySearch>>>,
+    /// Per-agent knowledge bases for the knowledge API.
+    pub knowledge_bases: arc_swap::ArcSwap<HashMap<String, KnowledgeBase>>,
+    /// Per-agent media stores, for serving signed media links.
+    pub media_stores: arc_swap::ArcSwap<HashMap<String, MediaStore>>,
     /// Live status blocks for active channels, keyed by channel_id.
     pub channel_status_blocks: RwLock<HashMap<String, Arc<tokio::sync::RwLock<StatusBlock>>>>,
     /// Live channel states for active channels, keyed by channel_id.
@@ -79,6 +89,8 @@ pub struct ApiState {
     pub bindings: RwLock<Option<Arc<ArcSwap<Vec<Binding>>>>>,
     /// Shared messaging manager for runtime adapter addition.
     pub messaging_manager: RwLock<Option<Arc<MessagingManager>>>,
+    /// Outbound event sinks, so agents created at runtime report to them too.
+    pub event_sinks: RwLock<Option<crate::event_sink::EventSinks>>,
     /// Sender to signal the main event loop that provider keys have been configured.
     pub provider_setup_tx: mpsc::Sender<crate::ProviderSetupEvent>,
     /// Shared update status, populated by the background update checker.
@@ -93,6 +105,10 @@ pub struct ApiState {
     pub prompt_engine: RwLock<Option<PromptEngine>>,
     /// Instance-level defaults for resolving new agent configs.
     pub defaults_config: RwLock<Option<DefaultsConfig>>,
+    /// Instance-level media storage settings for runtime agent creation.
+    pub media_config: RwLock<Option<MediaConfig>>,
+    /// Instance-level vector storage backend for runtime agent creation.
+    pub storage_config: RwLock<Option<StorageConfig>>,
     /// Sender to register newly created agents with the main event loop.
     pub agent_tx: mpsc::Sender<crate::Agent>,
     /// Sender to remove agents from the main event loop.
@@ -105,6 +121,8 @@ pub struct ApiState {
     pub agent_groups: ArcSwap<Vec<crate::config::GroupDef>>,
     /// Org-level humans for the topology UI.
     pub agent_humans: ArcSwap<Vec<crate::config::HumanDef>>,
+    /// Conversations of recent OpenAI-compatible replies.
+    pub(super) openai_conversations: super::openai_compat::ConversationIndex,
 }
 
 /// Events sent to SSE clients. Wraps ProcessEvents with agent context.
@@ -125,6 +143,13 @@ pub enum ApiEvent {
         channel_id: String,
         text: String,
     },
+    /// A reply in a supervised channel is waiting for approval.
+    ReplyDraft {
+        agent_id: String,
+        channel_id: String,
+        approval_token: String,
+        text: String,
+    },
     /// Typing indicator state change.
     TypingState {
         agent_id: String,
@@ -146,13 +171,26 @@ pub enum ApiEvent {
         worker_id: String,
         status: String,
     },
-    /// A worker completed.
+    /// A worker completed. `result` is the worker's full output; `status`,
+    /// `summary` and `artifacts` break it down for machine consumers.
     WorkerCompleted {
         agent_id: String,
         channel_id: Option<String>,
         worker_id: String,
         result: String,
         success: bool,
+        status: WorkerResultStatus,
+        summary: String,
+        artifacts: Vec<WorkerArtifact>,
+    },
+    /// A worker is waiting for the user to approve a tool call.
+    ApprovalRequested {
+        agent_id: String,
+        channel_id: String,
+        worker_id: String,
+        approval_id: String,
+        tool_name: String,
+        args: String,
     },
     /// A branch was started.
     BranchStarted {
@@ -218,6 +256,8 @@ impl ApiState {
             agent_pools: arc_swap::ArcSwap::from_pointee(HashMap::new()),
             agent_configs: arc_swap::ArcSwap::from_pointee(Vec::new()),
             memory_searches: arc_swap::ArcSwap::from_pointee(HashMap::new()),
+            knowledge_bases: arc_swap::ArcSwap::from_pointee(HashMap::new()),
+            media_stores: arc_swap::ArcSwap::from_pointee(HashMap::new()),
             channel_status_blocks: RwLock::new(HashMap::new()),
             channel_states: RwLock::new(HashMap::new()),
             cortex_chat_sessions: arc_swap::ArcSwap::from_pointee(HashMap::new()),
@@ -232,6 +272,7 @@ impl ApiState {
             slack_permissions: RwLock::new(None),
             bindings: RwLock::new(None),
             messaging_manager: RwLock::new(None),
+            event_sinks: RwLock::new(None),
             provider_setup_tx,
             update_status: crate::update::new_shared_status(),
             instance_dir: ArcSwap::from_pointee(PathBuf::new()),
@@ -239,12 +280,15 @@ impl ApiState {
             embedding_model: RwLock::new(None),
             prompt_engine: RwLock::new(None),
             defaults_config: RwLock::new(None),
+            media_config: RwLock::new(None),
+            storage_config: RwLock::new(None),
             agent_tx,
             agent_remove_tx,
             webchat_adapter: ArcSwap::from_pointee(None),
             agent_links: ArcSwap::from_pointee(Vec::new()),
             agent_groups: ArcSwap::from_pointee(Vec::new()),
             agent_humans: ArcSwap::from_pointee(Vec::new()),
+            openai_conversations: Default::default(),
         }
     }
 
@@ -336,11 +380,29 @@ impl ApiState {
                                     })
                                     .ok();
                             }
+                            ProcessEvent::WorkerRetrying {
+                                worker_id,
+                                channel_id,
+                                attempt,
+                                max_attempts,
+                                reason,
+                                ..
+                            } => {
+                                api_tx
+                                    .send(ApiEvent::WorkerStatusUpdate {
+                                        agent_id: agent_id.clone(),
+                                        channel_id: channel_id.as_deref().map(|s| s.to_string()),
+                                        worker_id: worker_id.to_string(),
+                                        status: format!(
+                                            "retrying after {reason} (attempt {attempt}/{max_attempts})"
+                                        ),
+                                    })
+                                    .ok();
+                            }
                             ProcessEvent::WorkerComplete {
                                 worker_id,
                                 channel_id,
                                 result,
-                                success,
                                 ..
                             } => {
                                 api_tx
@@ -348,8 +410,30 @@ impl ApiState {
                                         agent_id: agent_id.clone(),
                                         channel_id: channel_id.as_deref().map(|s| s.to_string()),
                                         worker_id: worker_id.to_string(),
-                                        result: result.clone(),
-                                        success: *success,
+                                        result: result.output.clone(),
+                                        success: result.succeeded(),
+                                        status: result.status,
+                                        summary: result.summary.clone(),
+                                        artifacts: result.artifacts.clone(),
+                                    })
+                                    .ok();
+                            }
+                            ProcessEvent::ApprovalRequested {
+                                worker_id,
+                                channel_id,
+                                approval_id,
+                                tool_name,
+                                args,
+                                ..
+                            } => {
+                                api_tx
+                                    .send(ApiEvent::ApprovalRequested {
+                                        agent_id: agent_id.clone(),
+                                        channel_id: channel_id.to_string(),
+                                        worker_id: worker_id.to_string(),
+                                        approval_id: approval_id.clone(),
+                                        tool_name: tool_name.clone(),
+                                        args: args.clone(),
                                     })
                                     .ok();
                             }
@@ -465,6 +549,16 @@ impl ApiState {
         self.memory_searches.store(Arc::new(searches));
     }
 
+    /// Set the knowledge bases for all agents.
+    pub fn set_knowledge_bases(&self, knowledge_bases: HashMap<String, KnowledgeBase>) {
+        self.knowledge_bases.store(Arc::new(knowledge_bases));
+    }
+
+    /// Set the media stores for all agents.
+    pub fn set_media_stores(&self, media_stores: HashMap<String, MediaStore>) {
+        self.media_stores.store(Arc::new(media_stores));
+    }
+
     /// Set the cortex chat sessions for all agents.
     pub fn set_cortex_chat_sessions(&self, sessions: HashMap<String, Arc<CortexChatSession>>) {
         self.cortex_chat_sessions.store(Arc::new(sessions));
@@ -526,6 +620,11 @@ impl ApiState {
         *self.messaging_manager.write().await = Some(manager);
     }
 
+    /// Share the event sinks so runtime-created agents are registered with them.
+    pub async fn set_event_sinks(&self, event_sinks: crate::event_sink::EventSinks) {
+        *self.event_sinks.write().await = Some(event_sinks);
+    }
+
     /// Set the instance directory path.
     pub fn set_instance_dir(&self, dir: PathBuf) {
         self.instance_dir.store(Arc::new(dir));
@@ -551,6 +650,16 @@ impl ApiState {
         *self.defaults_config.write().await = Some(defaults);
     }
 
+    /// Set the media storage settings for runtime agent creation.
+    pub async fn set_media_config(&self, media: MediaConfig) {
+        *self.media_config.write().await = Some(media);
+    }
+
+    /// Set the vector storage backend for runtime agent creation.
+    pub async fn set_storage_config(&self, storage: StorageConfig) {
+        *self.storage_config.write().await = Some(storage);
+    }
+
     /// Set the shared webchat adapter for API handlers.
     pub fn set_webchat_adapter(&self, adapter: Arc<WebChatAdapter>) {
         self.webchat_adapter.store(Arc::new(Some(adapter)));
@@ -84,10 +84,12 @@ pub(super) async fn events_sse(
                         let event_type = match &event {
                             ApiEvent::InboundMessage { .. } => "inbound_message",
                             ApiEvent::OutboundMessage { .. } => "outbound_message",
+                            ApiEvent::ReplyDraft { .. } => "reply_draft",
                             ApiEvent::TypingState { .. } => "typing_state",
                             ApiEvent::WorkerStarted { .. } => "worker_started",
                             ApiEvent::WorkerStatusUpdate { .. } => "worker_status",
                             ApiEvent::WorkerCompleted { .. } => "worker_completed",
+                            ApiEvent::ApprovalRequested { .. } => "approval_requested",
                             ApiEvent::BranchStarted { .. } => "branch_started",
                             ApiEvent::BranchCompleted { .. } => "branch_completed",
                             ApiEvent::ToolStarted { .. } => "tool_started",
@@ -0,0 +1,164 @@
+//! Tool audit API endpoint: query the append-only tool call log.
+
+use super::state::ApiState;
+
+use crate::conversation::{ToolAuditFilter, ToolAuditLog, ToolAuditRow};
+
+use axum::Json;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub(super) struct ToolAuditQuery {
+    agent_id: String,
+    /// e.g. `worker:<uuid>`, `branch:<uuid>`, `channel:<id>`.
+    process_id: Option<String>,
+    channel_id: Option<String>,
+    tool_name: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: i64,
+    #[serde(default)]
+    offset: i64,
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
+#[derive(Serialize)]
+pub(super) struct ToolAuditResponse {
+    entries: Vec<ToolAuditRow>,
+    total: i64,
+}
+
+/// List recorded tool calls for an agent, newest first.
+pub(super) async fn list_tool_calls(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<ToolAuditQuery>,
+) -> Result<Json<ToolAuditResponse>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let audit_log = ToolAuditLog::new(pool.clone());
+
+    let filter = ToolAuditFilter {
+        process_id: query.process_id,
+        channel_id: query.channel_id,
+        tool_name: query.tool_name,
+    };
+    let limit = query.limit.clamp(1, 500);
+    let offset = query.offset.max(0);
+
+    let (entries, total) = audit_log
+        .list(&filter, limit, offset)
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, "failed to list tool audit log");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(ToolAuditResponse { entries, total }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    async fn state_with_agent(agent_id: &str) -> Arc<ApiState> {
+        let (provider_setup_tx, _provider_setup_rx) = tokio::sync::mpsc::channel(1);
+        let (agent_tx, _agent_rx) = tokio::sync::mpsc::channel(1);
+        let (agent_remove_tx, _agent_remove_rx) = tokio::sync::mpsc::channel(1);
+        let state = Arc::new(ApiState::new_with_provider_sender(
+            provider_setup_tx,
+            agent_tx,
+            agent_remove_tx,
+        ));
+
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .in_memory(true)
+            .create_if_missing(true);
+        let pool = sqlx::pool::PoolOptions::<sqlx::Sqlite>::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("in-memory SQLite");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+        for tool_name in ["shell", "file"] {
+            sqlx::query(
+                "INSERT INTO tool_audit_log (process_id, process_type, tool_name, arguments, result_summary) \
+                 VALUES ('worker:a', 'worker', ?, '{}', 'ok')",
+            )
+            .bind(tool_name)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        state.set_agent_pools(HashMap::from([(agent_id.to_string(), pool)]));
+        state
+    }
+
+    fn query(agent_id: &str) -> ToolAuditQuery {
+        ToolAuditQuery {
+            agent_id: agent_id.into(),
+            process_id: None,
+            channel_id: None,
+            tool_name: None,
+            limit: default_limit(),
+            offset: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_tool_calls_filters_by_tool() {
+        let state = state_with_agent("main").await;
+
+        let response = list_tool_calls(
+            State(state),
+            Query(ToolAuditQuery {
+                tool_name: Some("shell".into()),
+                ..query("main")
+            }),
+        )
+        .await
+        .expect("tool audit request failed")
+        .0;
+
+        assert_eq!(response.total, 1);
+        assert_eq!(response.entries[0].tool_name, "shell");
+    }
+
+    #[tokio::test]
+    async fn test_list_tool_calls_clamps_limit() {
+        let state = state_with_agent("main").await;
+
+        let response = list_tool_calls(
+            State(state),
+            Query(ToolAuditQuery {
+                limit: 0,
+                offset: -5,
+                ..query("main")
+            }),
+        )
+        .await
+        .expect("tool audit request failed")
+        .0;
+
+        assert_eq!(response.total, 2);
+        assert_eq!(response.entries.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_tool_calls_unknown_agent() {
+        let state = state_with_agent("main").await;
+
+        let result = list_tool_calls(State(state), Query(query("missing"))).await;
+
+        assert!(matches!(result, Err(StatusCode::NOT_FOUND)));
+    }
+}
@@ -20,6 +20,9 @@ pub(super) struct WebChatSendRequest {
     #[serde(default = "default_sender_name")]
     sender_name: String,
     message: String,
+    /// Answer with a `structured` event instead of chat text.
+    #[serde(default)]
+    structured: bool,
 }
 
 fn default_sender_name() -> String {
@@ -55,6 +58,9 @@ pub(super) async fn webchat_send(
         "display_name".into(),
         serde_json::Value::String(request.sender_name.clone()),
     );
+    if request.structured {
+        metadata.insert("response_format".into(), "structured".into());
+    }
 
     let inbound = InboundMessage {
         id: uuid::Uuid::new_v4().to_string(),
@@ -81,6 +87,7 @@ pub(super) async fn webchat_send(
             let event_name = match &event {
                 WebChatEvent::Thinking => "thinking",
                 WebChatEvent::Text(_) => "text",
+                WebChatEvent::Structured(_) => "structured",
                 WebChatEvent::StreamStart => "stream_start",
                 WebChatEvent::StreamChunk(_) => "stream_chunk",
                 WebChatEvent::StreamEnd => "stream_end",
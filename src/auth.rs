@@ -1,4 +1,10 @@
 //! Anthropic OAuth authentication: PKCE flow, token exchange, refresh, and storage.
+//!
+//! Authorization of message senders lives in [`tiers`].
+
+pub mod tiers;
+
+pub use tiers::AuthTier;
 
 use anyhow::{Context, Result};
 use base64::Engine;
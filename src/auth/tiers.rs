@@ -0,0 +1,188 @@
+//! Per-sender authorization tiers.
+//!
+//! Every inbound message is mapped to a tier from the agent's `[auth]`
+//! config: first by platform-qualified sender ID, then by the sender's Discord
+//! roles, then the configured default. The tier decides whether the agent
+//! responds in a conversation at all and which channel tools the turn gets.
+//! Workers are started by `spawn_worker` or by approving a held plan, and both
+//! check the same tool rule, so keeping `spawn_worker` from a tier also keeps
+//! shell and file access from it. A worker's Coolify tools are checked against
+//! the tier of the sender it was spawned for, and are admin-only by default.
+
+use crate::InboundMessage;
+use crate::config::AuthConfig;
+
+use serde::{Deserialize, Serialize};
+
+/// Access level of a message sender, lowest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthTier {
+    /// May talk to the agent, which can only reply.
+    Guest,
+    /// Every channel tool except `spawn_worker`, and no Coolify tools.
+    User,
+    /// Everything, including workers and their shell access.
+    Admin,
+}
+
+impl AuthTier {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Guest => "guest",
+            Self::User => "user",
+            Self::Admin => "admin",
+        }
+    }
+
+    /// Built-in channel tool rules, which `[auth.tools.<tier>]` can extend
+    /// or narrow.
+    pub fn permits_by_default(self, tool_name: &str) -> bool {
+        match self {
+            Self::Guest => matches!(tool_name, "reply" | "skip"),
+            Self::User => tool_name != "spawn_worker" && !tool_name.starts_with("coolify_"),
+            Self::Admin => true,
+        }
+    }
+}
+
+impl std::fmt::Display for AuthTier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Sources that are the agent itself, its schedule, or the local operator
+/// rather than a platform user. Always admin.
+const TRUSTED_SOURCES: &[&str] = &["system", "internal", "cron", "cli"];
+
+/// Resolve the tier of a message's sender.
+pub fn sender_tier(config: &AuthConfig, message: &InboundMessage) -> AuthTier {
+    if !config.enabled || TRUSTED_SOURCES.contains(&message.source.as_str()) {
+        return AuthTier::Admin;
+    }
+
+    // Always qualified: on some adapters (webhooks, email, the OpenAI API)
+    // the sender ID is whatever the client claims.
+    let qualified = message.qualified_sender_id();
+    if let Some(tier) = config.senders.get(&qualified) {
+        return *tier;
+    }
+
+    message
+        .metadata
+        .get("discord_role_ids")
+        .and_then(|value| value.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|role_id| role_id.as_str())
+        .filter_map(|role_id| config.roles.get(role_id))
+        .max()
+        .copied()
+        .unwrap_or(config.default_tier)
+}
+
+/// Whether the agent should respond to a sender of `tier` in
+/// `conversation_id`.
+pub fn may_respond(config: &AuthConfig, conversation_id: &str, tier: AuthTier) -> bool {
+    config
+        .channels
+        .get(conversation_id)
+        .is_none_or(|minimum| tier >= *minimum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ToolRules;
+    use std::collections::HashMap;
+
+    fn message(source: &str, sender_id: &str, roles: &[&str]) -> InboundMessage {
+        let mut message = crate::testing::inbound_message(source, sender_id, "hi");
+        if !roles.is_empty() {
+            message
+                .metadata
+                .insert("discord_role_ids".into(), serde_json::json!(roles));
+        }
+        message
+    }
+
+    fn config() -> AuthConfig {
+        AuthConfig {
+            enabled: true,
+            default_tier: AuthTier::Guest,
+            senders: HashMap::from([
+                ("discord:1".into(), AuthTier::Admin),
+                ("slack:2".into(), AuthTier::User),
+                ("discord:3".into(), AuthTier::Guest),
+            ]),
+            roles: HashMap::from([
+                ("mods".into(), AuthTier::Admin),
+                ("members".into(), AuthTier::User),
+            ]),
+            channels: HashMap::from([("discord:42".into(), AuthTier::User)]),
+            tools: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn disabled_config_makes_everyone_admin() {
+        let tier = sender_tier(&AuthConfig::default(), &message("discord", "9", &[]));
+        assert_eq!(tier, AuthTier::Admin);
+    }
+
+    #[test]
+    fn senders_win_over_roles_and_roles_over_default() {
+        let config = config();
+        let tier = |source, sender, roles| sender_tier(&config, &message(source, sender, roles));
+        assert_eq!(tier("discord", "1", &[]), AuthTier::Admin);
+        assert_eq!(tier("slack", "1", &[]), AuthTier::Guest);
+        assert_eq!(tier("slack", "2", &[]), AuthTier::User);
+        assert_eq!(tier("webhook", "2", &[]), AuthTier::Guest);
+        assert_eq!(tier("discord", "3", &["mods"]), AuthTier::Guest);
+        assert_eq!(tier("discord", "4", &["members", "mods"]), AuthTier::Admin);
+        assert_eq!(tier("discord", "4", &["unknown"]), AuthTier::Guest);
+        assert_eq!(tier("cron", "4", &[]), AuthTier::Admin);
+    }
+
+    #[test]
+    fn channels_require_a_minimum_tier() {
+        let config = config();
+        assert!(!may_respond(&config, "discord:42", AuthTier::Guest));
+        assert!(may_respond(&config, "discord:42", AuthTier::User));
+        assert!(may_respond(&config, "discord:7", AuthTier::Guest));
+    }
+
+    #[test]
+    fn tool_rules_extend_the_tier_defaults() {
+        let mut config = config();
+        assert!(config.permits_tool(AuthTier::Guest, "reply"));
+        assert!(!config.permits_tool(AuthTier::Guest, "branch"));
+        assert!(!config.permits_tool(AuthTier::User, "spawn_worker"));
+        assert!(config.permits_tool(AuthTier::Admin, "spawn_worker"));
+        assert!(!config.permits_tool(AuthTier::User, "coolify_deploy"));
+        assert!(config.permits_tool(AuthTier::Admin, "coolify_deploy"));
+
+        config.tools.insert(
+            AuthTier::User,
+            ToolRules {
+                allow: Vec::new(),
+                deny: vec!["send_file".into()],
+            },
+        );
+        assert!(!config.permits_tool(AuthTier::User, "spawn_worker"));
+        assert!(!config.permits_tool(AuthTier::User, "send_file"));
+        assert!(config.permits_tool(AuthTier::User, "branch"));
+
+        config.tools.insert(
+            AuthTier::Guest,
+            ToolRules {
+                allow: vec!["branch".into()],
+                deny: Vec::new(),
+            },
+        );
+        assert!(config.permits_tool(AuthTier::Guest, "branch"));
+        assert!(config.permits_tool(AuthTier::Guest, "reply"));
+        assert!(!config.permits_tool(AuthTier::Guest, "spawn_worker"));
+    }
+}
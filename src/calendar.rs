@@ -0,0 +1,322 @@
+//! Calendar access for the agent's CalDAV or Google calendar.
+//!
+//! [`CalendarClient`] lists, creates, and deletes events for the calendar
+//! tools. The sync loop keeps the next `lookahead_hours` of events in
+//! `RuntimeConfig::upcoming_events`, where the heartbeat picks them up, so a
+//! meeting starting soon can be raised without anyone asking.
+
+mod caldav;
+mod google;
+mod ical;
+
+use crate::AgentDeps;
+use crate::config::{CalendarConfig, CalendarProvider};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the sync loop re-reads its config while no calendar is set up.
+const DISABLED_POLL_SECS: u64 = 60;
+
+/// A calendar event, with times in UTC.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    /// Provider ID used to delete the event: the CalDAV resource path or
+    /// the Google event ID.
+    pub id: String,
+    pub title: String,
+    pub start: DateTime<Utc>,
+    /// Exclusive end; the day after the last day for all-day events.
+    pub end: DateTime<Utc>,
+    pub all_day: bool,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    /// Attendee email addresses.
+    pub attendees: Vec<String>,
+}
+
+impl CalendarEvent {
+    /// A one-line summary in `tz`, e.g.
+    /// `Mon 2026-10-19 14:00-14:30: Design review (Room 4)`.
+    pub fn describe(&self, tz: Tz) -> String {
+        let start = self.start.with_timezone(&tz);
+        let end = self.end.with_timezone(&tz);
+        let when = if self.all_day {
+            format!("{} (all day)", start.format("%a %Y-%m-%d"))
+        } else if start.date_naive() == end.date_naive() {
+            format!(
+                "{}-{}",
+                start.format("%a %Y-%m-%d %H:%M"),
+                end.format("%H:%M")
+            )
+        } else {
+            format!(
+                "{} to {}",
+                start.format("%a %Y-%m-%d %H:%M"),
+                end.format("%a %Y-%m-%d %H:%M")
+            )
+        };
+        match &self.location {
+            Some(location) => format!("{when}: {} ({location})", self.title),
+            None => format!("{when}: {}", self.title),
+        }
+    }
+}
+
+/// An event to create. Only timed events are created; attendees get an
+/// invitation where the provider sends them.
+#[derive(Debug, Clone)]
+pub struct NewEvent {
+    pub title: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    pub attendees: Vec<String>,
+}
+
+/// Error type for calendar access.
+#[derive(Debug, thiserror::Error)]
+pub enum CalendarError {
+    #[error("Calendar request failed: {0}")]
+    RequestFailed(String),
+
+    #[error("Failed to parse calendar response: {0}")]
+    InvalidResponse(String),
+
+    #[error("Calendar is not configured: {0}")]
+    NotConfigured(String),
+
+    #[error("{0}")]
+    InvalidArgument(String),
+}
+
+/// Client for the agent's calendar, whichever provider it's on.
+#[derive(Debug, Clone)]
+pub enum CalendarClient {
+    CalDav(caldav::CalDavClient),
+    Google(google::GoogleClient),
+}
+
+impl CalendarClient {
+    pub fn new(config: &CalendarConfig) -> Result<Self, CalendarError> {
+        if !config.is_configured() {
+            return Err(CalendarError::NotConfigured(match config.provider {
+                CalendarProvider::CalDav => "CalDAV needs a url".into(),
+                CalendarProvider::Google => {
+                    "Google needs client_id, client_secret, and refresh_token".into()
+                }
+            }));
+        }
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("spacebot/", env!("CARGO_PKG_VERSION")))
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("hardcoded reqwest client config");
+
+        Ok(match config.provider {
+            CalendarProvider::CalDav => {
+                Self::CalDav(caldav::CalDavClient::new(client, config, config.tz())?)
+            }
+            CalendarProvider::Google => {
+                Self::Google(google::GoogleClient::new(client, config, config.tz()))
+            }
+        })
+    }
+
+    /// Events overlapping `from..to`, soonest first. Recurring events come
+    /// back as one event per occurrence.
+    pub async fn list_events(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<CalendarEvent>, CalendarError> {
+        let mut events = match self {
+            Self::CalDav(client) => client.list_events(from, to).await?,
+            Self::Google(client) => client.list_events(from, to).await?,
+        };
+        events.sort_by(|a, b| a.start.cmp(&b.start).then_with(|| a.title.cmp(&b.title)));
+        Ok(events)
+    }
+
+    pub async fn create_event(&self, event: &NewEvent) -> Result<CalendarEvent, CalendarError> {
+        if event.end <= event.start {
+            return Err(CalendarError::InvalidArgument(
+                "the event must end after it starts".into(),
+            ));
+        }
+        match self {
+            Self::CalDav(client) => client.create_event(event).await,
+            Self::Google(client) => client.create_event(event).await,
+        }
+    }
+
+    /// Delete the event with `id`. For a recurring event this deletes the
+    /// whole series.
+    pub async fn delete_event(&self, id: &str) -> Result<(), CalendarError> {
+        match self {
+            Self::CalDav(client) => client.delete_event(id).await,
+            Self::Google(client) => client.delete_event(id).await,
+        }
+    }
+}
+
+/// Turn a non-success response into a `RequestFailed` error.
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, CalendarError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "failed to read response body".into());
+    Err(CalendarError::RequestFailed(format!(
+        "HTTP {status}: {body}"
+    )))
+}
+
+/// Parse a time the LLM passed: RFC 3339, a local date and time in `tz`
+/// (`2026-10-19 14:00` or `2026-10-19T14:00`), or a date, meaning midnight.
+pub fn parse_local_time(value: &str, tz: Tz) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let local = [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+    })
+    .ok_or_else(|| {
+        format!("can't read {value:?} as a time; use e.g. \"2026-10-19 14:00\" or RFC 3339")
+    })?;
+    tz.from_local_datetime(&local)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+        .ok_or_else(|| format!("{value:?} doesn't exist in {tz}"))
+}
+
+/// Spawn the calendar sync loop for an agent.
+///
+/// Like the heartbeat, the loop always runs and reads `[calendar]` on every
+/// tick, so adding a calendar or changing the interval takes effect without a
+/// restart.
+pub fn spawn_sync_loop(deps: AgentDeps) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let config = (**deps.runtime_config.calendar.load()).clone();
+            if !config.is_configured() {
+                if !deps.runtime_config.upcoming_events.load().is_empty() {
+                    deps.runtime_config
+                        .upcoming_events
+                        .store(Arc::new(Vec::new()));
+                }
+                tokio::time::sleep(Duration::from_secs(DISABLED_POLL_SECS)).await;
+                continue;
+            }
+
+            sync_upcoming_events(&deps, &config).await;
+            tokio::time::sleep(Duration::from_secs(config.sync_interval_secs.max(60))).await;
+        }
+    })
+}
+
+/// Fetch the next `lookahead_hours` of events into `upcoming_events`. On
+/// failure the previous snapshot is kept.
+#[tracing::instrument(skip(deps, config), fields(agent_id = %deps.agent_id))]
+async fn sync_upcoming_events(deps: &AgentDeps, config: &CalendarConfig) {
+    let client = match CalendarClient::new(config) {
+        Ok(client) => client,
+        Err(error) => {
+            tracing::warn!(%error, "failed to set up calendar client");
+            return;
+        }
+    };
+    let now = Utc::now();
+    let until = now + chrono::Duration::hours(config.lookahead_hours as i64);
+    match client.list_events(now, until).await {
+        Ok(events) => {
+            tracing::debug!(count = events.len(), "calendar synced");
+            deps.runtime_config.upcoming_events.store(Arc::new(events));
+        }
+        Err(error) => tracing::warn!(%error, "calendar sync failed"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(start: DateTime<Utc>, end: DateTime<Utc>, all_day: bool) -> CalendarEvent {
+        CalendarEvent {
+            id: "1".into(),
+            title: "Design review".into(),
+            start,
+            end,
+            all_day,
+            location: Some("Room 4".into()),
+            description: None,
+            attendees: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn events_are_described_in_the_calendar_timezone() {
+        let tz: Tz = "Europe/Budapest".parse().unwrap();
+        let timed = event(
+            Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2026, 10, 19, 12, 30, 0).unwrap(),
+            false,
+        );
+        assert_eq!(
+            timed.describe(tz),
+            "Mon 2026-10-19 14:00-14:30: Design review (Room 4)"
+        );
+
+        let all_day = event(
+            tz.with_ymd_and_hms(2026, 10, 20, 0, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc),
+            tz.with_ymd_and_hms(2026, 10, 21, 0, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc),
+            true,
+        );
+        assert_eq!(
+            all_day.describe(tz),
+            "Tue 2026-10-20 (all day): Design review (Room 4)"
+        );
+    }
+
+    #[test]
+    fn times_without_an_offset_are_read_in_the_given_timezone() {
+        let tz: Tz = "Europe/Budapest".parse().unwrap();
+        let expected = Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap();
+        assert_eq!(parse_local_time("2026-10-19 14:00", tz).unwrap(), expected);
+        assert_eq!(
+            parse_local_time("2026-10-19T14:00:00", tz).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_local_time("2026-10-19T12:00:00Z", tz).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_local_time("2026-10-19", tz).unwrap(),
+            Utc.with_ymd_and_hms(2026, 10, 18, 22, 0, 0).unwrap()
+        );
+        assert!(parse_local_time("tomorrow", tz).is_err());
+    }
+}
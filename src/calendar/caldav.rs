@@ -0,0 +1,273 @@
+//! CalDAV (RFC 4791) calendar collection access.
+
+use super::{CalendarError, CalendarEvent, NewEvent, check_status, ical};
+use crate::config::CalendarConfig;
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use regex::Regex;
+
+use std::sync::LazyLock;
+
+/// One `<response>` element in a multistatus body.
+static RESPONSE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<(?:[\w-]+:)?response\b[^>]*>(.*?)</(?:[\w-]+:)?response>")
+        .expect("hardcoded regex")
+});
+
+/// The resource href inside a `<response>`.
+static HREF_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<(?:[\w-]+:)?href\b[^>]*>(.*?)</(?:[\w-]+:)?href>").expect("hardcoded regex")
+});
+
+/// The iCalendar text inside a `<response>`.
+static CALENDAR_DATA_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<(?:[\w-]+:)?calendar-data\b[^>]*>(.*?)</(?:[\w-]+:)?calendar-data>")
+        .expect("hardcoded regex")
+});
+
+#[derive(Clone)]
+pub struct CalDavClient {
+    client: reqwest::Client,
+    /// The calendar collection URL, always ending in `/`.
+    collection: reqwest::Url,
+    username: Option<String>,
+    password: Option<String>,
+    tz: Tz,
+}
+
+impl std::fmt::Debug for CalDavClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CalDavClient")
+            .field("collection", &self.collection.as_str())
+            .finish_non_exhaustive()
+    }
+}
+
+impl CalDavClient {
+    pub fn new(
+        client: reqwest::Client,
+        config: &CalendarConfig,
+        tz: Tz,
+    ) -> Result<Self, CalendarError> {
+        let url = config
+            .url
+            .as_deref()
+            .ok_or_else(|| CalendarError::NotConfigured("CalDAV needs a url".into()))?;
+        let with_slash = if url.ends_with('/') {
+            url.to_string()
+        } else {
+            format!("{url}/")
+        };
+        let collection = reqwest::Url::parse(&with_slash).map_err(|error| {
+            CalendarError::NotConfigured(format!("invalid CalDAV url {url:?}: {error}"))
+        })?;
+
+        Ok(Self {
+            client,
+            collection,
+            username: config.username.clone(),
+            password: config.password.clone(),
+            tz,
+        })
+    }
+
+    fn request(&self, method: reqwest::Method, url: reqwest::Url) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        match &self.username {
+            Some(username) => request.basic_auth(username, self.password.as_deref()),
+            None => request,
+        }
+    }
+
+    pub async fn list_events(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<CalendarEvent>, CalendarError> {
+        let range = format!(
+            r#"start="{}" end="{}""#,
+            from.format("%Y%m%dT%H%M%SZ"),
+            to.format("%Y%m%dT%H%M%SZ")
+        );
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<C:calendar-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop>
+    <C:calendar-data>
+      <C:expand {range}/>
+    </C:calendar-data>
+  </D:prop>
+  <C:filter>
+    <C:comp-filter name="VCALENDAR">
+      <C:comp-filter name="VEVENT">
+        <C:time-range {range}/>
+      </C:comp-filter>
+    </C:comp-filter>
+  </C:filter>
+</C:calendar-query>"#
+        );
+        let method = reqwest::Method::from_bytes(b"REPORT").expect("valid method name");
+        let response = self
+            .request(method, self.collection.clone())
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(body)
+            .send()
+            .await
+            .map_err(|error| CalendarError::RequestFailed(error.to_string()))?;
+        let text = check_status(response)
+            .await?
+            .text()
+            .await
+            .map_err(|error| CalendarError::InvalidResponse(error.to_string()))?;
+
+        Ok(parse_multistatus(&text, self.tz)
+            .into_iter()
+            // Servers that ignore <expand> return whole recurring series;
+            // keep only the events that land in the window.
+            .filter(|event| event.end > from && event.start < to)
+            .collect())
+    }
+
+    pub async fn create_event(&self, event: &NewEvent) -> Result<CalendarEvent, CalendarError> {
+        let uid = uuid::Uuid::new_v4().to_string();
+        let url = self
+            .collection
+            .join(&format!("{uid}.ics"))
+            .map_err(|error| CalendarError::InvalidArgument(error.to_string()))?;
+        let response = self
+            .request(reqwest::Method::PUT, url.clone())
+            .header("Content-Type", "text/calendar; charset=utf-8")
+            .header("If-None-Match", "*")
+            .body(ical::render_event(&uid, event))
+            .send()
+            .await
+            .map_err(|error| CalendarError::RequestFailed(error.to_string()))?;
+        check_status(response).await?;
+
+        Ok(CalendarEvent {
+            id: url.path().to_string(),
+            title: event.title.clone(),
+            start: event.start,
+            end: event.end,
+            all_day: false,
+            location: event.location.clone(),
+            description: event.description.clone(),
+            attendees: event.attendees.clone(),
+        })
+    }
+
+    pub async fn delete_event(&self, id: &str) -> Result<(), CalendarError> {
+        let url = self
+            .collection
+            .join(id)
+            .map_err(|error| CalendarError::InvalidArgument(error.to_string()))?;
+        if url.origin() != self.collection.origin()
+            || !url.path().starts_with(self.collection.path())
+            || url.path() == self.collection.path()
+        {
+            return Err(CalendarError::InvalidArgument(format!(
+                "{id:?} is not an event in this calendar"
+            )));
+        }
+        let response = self
+            .request(reqwest::Method::DELETE, url)
+            .send()
+            .await
+            .map_err(|error| CalendarError::RequestFailed(error.to_string()))?;
+        check_status(response).await?;
+        Ok(())
+    }
+}
+
+/// The events in a CalDAV multistatus body, each keyed by its resource href.
+fn parse_multistatus(body: &str, tz: Tz) -> Vec<CalendarEvent> {
+    RESPONSE_PATTERN
+        .captures_iter(body)
+        .filter_map(|response| {
+            let response = response.get(1)?.as_str();
+            let href = HREF_PATTERN.captures(response)?;
+            let data = CALENDAR_DATA_PATTERN.captures(response)?;
+            Some(ical::parse_events(
+                &unescape_xml(&data[1]),
+                &unescape_xml(href[1].trim()),
+                tz,
+            ))
+        })
+        .flatten()
+        .collect()
+}
+
+fn unescape_xml(text: &str) -> String {
+    if let Some(inner) = text
+        .trim()
+        .strip_prefix("<![CDATA[")
+        .and_then(|rest| rest.strip_suffix("]]>"))
+    {
+        return inner.to_string();
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#13;", "\r")
+        .replace("&#xD;", "\r")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multistatus_events_are_keyed_by_href() {
+        let body = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav">
+  <d:response>
+    <d:href>/dav/calendars/me/work/abc.ics</d:href>
+    <d:propstat>
+      <d:prop>
+        <cal:calendar-data>BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:abc
+DTSTART:20261019T120000Z
+DTEND:20261019T123000Z
+SUMMARY:Lunch &amp; learn
+END:VEVENT
+END:VCALENDAR
+</cal:calendar-data>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/calendars/me/work/gone.ics</d:href>
+    <d:status>HTTP/1.1 404 Not Found</d:status>
+  </d:response>
+  <d:response>
+    <d:href>/dav/calendars/me/work/def.ics</d:href>
+    <d:propstat>
+      <d:prop>
+        <cal:calendar-data><![CDATA[BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:def
+DTSTART:20261020T090000Z
+DTEND:20261020T100000Z
+SUMMARY:Planning
+END:VEVENT
+END:VCALENDAR
+]]></cal:calendar-data>
+      </d:prop>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+        let events = parse_multistatus(body, chrono_tz::UTC);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].id, "/dav/calendars/me/work/abc.ics");
+        assert_eq!(events[0].title, "Lunch & learn");
+        assert_eq!(events[1].id, "/dav/calendars/me/work/def.ics");
+        assert_eq!(events[1].title, "Planning");
+    }
+}
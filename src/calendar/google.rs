@@ -0,0 +1,275 @@
+//! Google Calendar API v3 access with a stored OAuth refresh token.
+
+use super::{CalendarError, CalendarEvent, NewEvent, check_status};
+use crate::config::CalendarConfig;
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use tokio::sync::Mutex;
+
+use std::sync::Arc;
+
+const API_BASE: &str = "https://www.googleapis.com/calendar/v3";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+
+/// Refresh the access token this long before Google says it expires.
+const TOKEN_EXPIRY_MARGIN_SECS: i64 = 60;
+
+#[derive(Clone)]
+pub struct GoogleClient {
+    client: reqwest::Client,
+    calendar_id: String,
+    client_id: String,
+    client_secret: String,
+    refresh_token: String,
+    tz: Tz,
+    /// Access token and when it expires, shared between clones.
+    token: Arc<Mutex<Option<(String, DateTime<Utc>)>>>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: i64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventList {
+    #[serde(default)]
+    items: Vec<GoogleEvent>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GoogleEvent {
+    id: String,
+    summary: Option<String>,
+    status: Option<String>,
+    location: Option<String>,
+    description: Option<String>,
+    start: Option<EventTime>,
+    end: Option<EventTime>,
+    #[serde(default)]
+    attendees: Vec<Attendee>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventTime {
+    date_time: Option<DateTime<Utc>>,
+    date: Option<NaiveDate>,
+}
+
+#[derive(Deserialize)]
+struct Attendee {
+    email: Option<String>,
+}
+
+impl std::fmt::Debug for GoogleClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GoogleClient")
+            .field("calendar_id", &self.calendar_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GoogleClient {
+    pub fn new(client: reqwest::Client, config: &CalendarConfig, tz: Tz) -> Self {
+        Self {
+            client,
+            calendar_id: config.calendar_id.clone(),
+            client_id: config.client_id.clone().unwrap_or_default(),
+            client_secret: config.client_secret.clone().unwrap_or_default(),
+            refresh_token: config.refresh_token.clone().unwrap_or_default(),
+            tz,
+            token: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// A valid access token, refreshed when the cached one is about to
+    /// expire.
+    async fn access_token(&self) -> Result<String, CalendarError> {
+        let mut token = self.token.lock().await;
+        if let Some((access_token, expires_at)) = token.as_ref()
+            && *expires_at > Utc::now()
+        {
+            return Ok(access_token.clone());
+        }
+
+        let response = self
+            .client
+            .post(TOKEN_URL)
+            .form(&[
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+                ("refresh_token", self.refresh_token.as_str()),
+                ("grant_type", "refresh_token"),
+            ])
+            .send()
+            .await
+            .map_err(|error| CalendarError::RequestFailed(error.to_string()))?;
+        let refreshed: TokenResponse = check_status(response)
+            .await?
+            .json()
+            .await
+            .map_err(|error| CalendarError::InvalidResponse(error.to_string()))?;
+
+        let expires_at =
+            Utc::now() + chrono::Duration::seconds(refreshed.expires_in - TOKEN_EXPIRY_MARGIN_SECS);
+        *token = Some((refreshed.access_token.clone(), expires_at));
+        Ok(refreshed.access_token)
+    }
+
+    /// The events collection URL, or one event's URL when `event_id` is set.
+    fn events_url(&self, event_id: Option<&str>) -> reqwest::Url {
+        let mut url = reqwest::Url::parse(API_BASE).expect("hardcoded API base URL");
+        {
+            let mut segments = url.path_segments_mut().expect("API base URL has a path");
+            segments.extend(["calendars", self.calendar_id.as_str(), "events"]);
+            if let Some(event_id) = event_id {
+                segments.push(event_id);
+            }
+        }
+        url
+    }
+
+    pub async fn list_events(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<CalendarEvent>, CalendarError> {
+        let access_token = self.access_token().await?;
+        let mut events = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let mut query = vec![
+                ("timeMin", from.to_rfc3339()),
+                ("timeMax", to.to_rfc3339()),
+                ("singleEvents", "true".to_string()),
+                ("orderBy", "startTime".to_string()),
+                ("maxResults", "250".to_string()),
+            ];
+            if let Some(page_token) = page_token.take() {
+                query.push(("pageToken", page_token));
+            }
+            let response = self
+                .client
+                .get(self.events_url(None))
+                .bearer_auth(&access_token)
+                .query(&query)
+                .send()
+                .await
+                .map_err(|error| CalendarError::RequestFailed(error.to_string()))?;
+            let page: EventList = check_status(response)
+                .await?
+                .json()
+                .await
+                .map_err(|error| CalendarError::InvalidResponse(error.to_string()))?;
+
+            events.extend(
+                page.items
+                    .into_iter()
+                    .filter_map(|event| self.convert(event)),
+            );
+            match page.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => break,
+            }
+        }
+        Ok(events)
+    }
+
+    pub async fn create_event(&self, event: &NewEvent) -> Result<CalendarEvent, CalendarError> {
+        let access_token = self.access_token().await?;
+        let attendees: Vec<_> = event
+            .attendees
+            .iter()
+            .map(|email| serde_json::json!({ "email": email }))
+            .collect();
+        let body = serde_json::json!({
+            "summary": event.title,
+            "location": event.location,
+            "description": event.description,
+            "start": { "dateTime": event.start.to_rfc3339() },
+            "end": { "dateTime": event.end.to_rfc3339() },
+            "attendees": attendees,
+        });
+        let response = self
+            .client
+            .post(self.events_url(None))
+            .bearer_auth(&access_token)
+            .query(&[("sendUpdates", "all")])
+            .json(&body)
+            .send()
+            .await
+            .map_err(|error| CalendarError::RequestFailed(error.to_string()))?;
+        let created: GoogleEvent = check_status(response)
+            .await?
+            .json()
+            .await
+            .map_err(|error| CalendarError::InvalidResponse(error.to_string()))?;
+
+        self.convert(created)
+            .ok_or_else(|| CalendarError::InvalidResponse("created event has no start time".into()))
+    }
+
+    pub async fn delete_event(&self, id: &str) -> Result<(), CalendarError> {
+        let access_token = self.access_token().await?;
+        let response = self
+            .client
+            .delete(self.events_url(Some(id)))
+            .bearer_auth(&access_token)
+            .query(&[("sendUpdates", "all")])
+            .send()
+            .await
+            .map_err(|error| CalendarError::RequestFailed(error.to_string()))?;
+        check_status(response).await?;
+        Ok(())
+    }
+
+    /// Convert an API event, skipping cancelled ones and ones without times.
+    fn convert(&self, event: GoogleEvent) -> Option<CalendarEvent> {
+        if event.status.as_deref() == Some("cancelled") {
+            return None;
+        }
+        let (start, all_day) = self.event_time(event.start.as_ref()?)?;
+        let end = event
+            .end
+            .as_ref()
+            .and_then(|end| self.event_time(end))
+            .map(|(end, _)| end)
+            .unwrap_or(start);
+        Some(CalendarEvent {
+            id: event.id,
+            title: event.summary.unwrap_or_else(|| "(no title)".into()),
+            start,
+            end,
+            all_day,
+            location: event.location.filter(|location| !location.is_empty()),
+            description: event
+                .description
+                .filter(|description| !description.is_empty()),
+            attendees: event
+                .attendees
+                .into_iter()
+                .filter_map(|attendee| attendee.email)
+                .collect(),
+        })
+    }
+
+    /// An event time, and whether it's an all-day date. Dates are read as
+    /// midnight in the calendar's timezone.
+    fn event_time(&self, time: &EventTime) -> Option<(DateTime<Utc>, bool)> {
+        if let Some(date_time) = time.date_time {
+            return Some((date_time, false));
+        }
+        let midnight = self
+            .tz
+            .from_local_datetime(&time.date?.and_hms_opt(0, 0, 0)?)
+            .earliest()?;
+        Some((midnight.with_timezone(&Utc), true))
+    }
+}
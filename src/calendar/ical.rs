@@ -0,0 +1,382 @@
+//! Just enough iCalendar (RFC 5545) to read the VEVENTs a CalDAV server
+//! returns and write the ones we create.
+//!
+//! Recurrence isn't handled here: CalDAV queries ask the server to expand
+//! recurring events into single occurrences.
+
+use super::{CalendarEvent, NewEvent};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// Longest content line in octets, before folding.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// A VEVENT read from iCalendar text, before it's given an ID.
+#[derive(Debug, Default)]
+struct RawEvent {
+    summary: Option<String>,
+    start: Option<(DateTime<Utc>, bool)>,
+    end: Option<DateTime<Utc>>,
+    duration: Option<chrono::Duration>,
+    location: Option<String>,
+    description: Option<String>,
+    attendees: Vec<String>,
+    cancelled: bool,
+}
+
+/// The events in an iCalendar document. Times without a timezone, and
+/// all-day dates, are read in `tz`. Cancelled events are skipped.
+pub(super) fn parse_events(text: &str, id: &str, tz: Tz) -> Vec<CalendarEvent> {
+    let mut events = Vec::new();
+    let mut current: Option<RawEvent> = None;
+    // Depth of components nested in the VEVENT, like VALARM.
+    let mut nested = 0usize;
+
+    for line in unfold(text) {
+        let Some((name, params, value)) = split_line(&line) else {
+            continue;
+        };
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                current = Some(RawEvent::default());
+                nested = 0;
+            }
+            ("BEGIN", Some(_)) => nested += 1,
+            ("END", Some(_)) if nested > 0 => nested -= 1,
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                if let Some(event) = current.take().and_then(|raw| finish(raw, id)) {
+                    events.push(event);
+                }
+            }
+            (_, Some(_)) if nested > 0 => {}
+            ("SUMMARY", Some(event)) => event.summary = Some(unescape(value)),
+            ("LOCATION", Some(event)) => {
+                event.location = Some(unescape(value)).filter(|v| !v.is_empty())
+            }
+            ("DESCRIPTION", Some(event)) => {
+                event.description = Some(unescape(value)).filter(|v| !v.is_empty())
+            }
+            ("DTSTART", Some(event)) => event.start = parse_time(&params, value, tz),
+            ("DTEND", Some(event)) => event.end = parse_time(&params, value, tz).map(|t| t.0),
+            ("DURATION", Some(event)) => event.duration = parse_duration(value),
+            ("STATUS", Some(event)) => event.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            ("ATTENDEE", Some(event)) => {
+                let address = value.trim();
+                let address = match address.get(..7) {
+                    Some(scheme) if scheme.eq_ignore_ascii_case("mailto:") => &address[7..],
+                    _ => address,
+                };
+                if !address.is_empty() {
+                    event.attendees.push(address.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+fn finish(raw: RawEvent, id: &str) -> Option<CalendarEvent> {
+    let (start, all_day) = raw.start?;
+    if raw.cancelled {
+        return None;
+    }
+    let default_length = if all_day {
+        chrono::Duration::days(1)
+    } else {
+        chrono::Duration::zero()
+    };
+    let end = raw
+        .end
+        .or_else(|| raw.duration.map(|duration| start + duration))
+        .unwrap_or(start + default_length);
+    Some(CalendarEvent {
+        id: id.to_string(),
+        title: raw.summary.unwrap_or_else(|| "(no title)".into()),
+        start,
+        end,
+        all_day,
+        location: raw.location,
+        description: raw.description,
+        attendees: raw.attendees,
+    })
+}
+
+/// Join folded lines: a line starting with a space or tab continues the one
+/// before it.
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Split `NAME;PARAM=value:VALUE` into its uppercased name, parameters, and
+/// value. Colons inside quoted parameter values don't end the name part.
+fn split_line(line: &str) -> Option<(String, Vec<(String, String)>, &str)> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(index, character)| {
+        match character {
+            '"' => in_quotes = !in_quotes,
+            ':' if !in_quotes => return Some(index),
+            _ => {}
+        }
+        None
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            Some((
+                key.trim().to_ascii_uppercase(),
+                value.trim().trim_matches('"').to_string(),
+            ))
+        })
+        .collect();
+    Some((name, params, value))
+}
+
+/// A DTSTART or DTEND value, and whether it's a date without a time.
+fn parse_time(params: &[(String, String)], value: &str, tz: Tz) -> Option<(DateTime<Utc>, bool)> {
+    let param = |key: &str| {
+        params
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    };
+    let value = value.trim();
+
+    if param("VALUE").is_some_and(|kind| kind.eq_ignore_ascii_case("DATE")) || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        let midnight = tz
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+            .earliest()?;
+        return Some((midnight.with_timezone(&Utc), true));
+    }
+
+    if let Some(utc) = value.strip_suffix(['Z', 'z']) {
+        let time = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some((Utc.from_utc_datetime(&time), false));
+    }
+
+    let time = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    // Servers usually use IANA names for TZID; anything else is read in the
+    // calendar's own timezone.
+    let zone = param("TZID")
+        .and_then(|name| name.trim_start_matches('/').parse::<Tz>().ok())
+        .unwrap_or(tz);
+    let local = zone.from_local_datetime(&time).earliest()?;
+    Some((local.with_timezone(&Utc), false))
+}
+
+/// An RFC 5545 duration such as `PT30M`, `P1D`, or `-PT15M`.
+fn parse_duration(value: &str) -> Option<chrono::Duration> {
+    let value = value.trim();
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let mut rest = value.strip_prefix('P')?;
+    let mut seconds = 0i64;
+    let mut in_time = false;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('T') {
+            in_time = true;
+            rest = after;
+            continue;
+        }
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let amount: i64 = rest[..digits].parse().ok()?;
+        let unit = rest[digits..].chars().next()?;
+        seconds += amount
+            * match (unit, in_time) {
+                ('W', false) => 7 * 86_400,
+                ('D', false) => 86_400,
+                ('H', true) => 3_600,
+                ('M', true) => 60,
+                ('S', true) => 1,
+                _ => return None,
+            };
+        rest = &rest[digits + 1..];
+    }
+    Some(chrono::Duration::seconds(if negative {
+        -seconds
+    } else {
+        seconds
+    }))
+}
+
+fn unescape(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(character) = chars.next() {
+        if character != '\\' {
+            output.push(character);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => output.push('\n'),
+            Some(other) => output.push(other),
+            None => output.push('\\'),
+        }
+    }
+    output
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// A VCALENDAR holding one VEVENT for `event`, with CRLF line endings.
+pub(super) fn render_event(uid: &str, event: &NewEvent) -> String {
+    let timestamp = |time: DateTime<Utc>| time.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".into(),
+        "PRODID:-//Spacebot//Calendar//EN".into(),
+        "BEGIN:VEVENT".into(),
+        format!("UID:{uid}"),
+        format!("DTSTAMP:{}", timestamp(Utc::now())),
+        format!("DTSTART:{}", timestamp(event.start)),
+        format!("DTEND:{}", timestamp(event.end)),
+        format!("SUMMARY:{}", escape(&event.title)),
+    ];
+    if let Some(location) = &event.location {
+        lines.push(format!("LOCATION:{}", escape(location)));
+    }
+    if let Some(description) = &event.description {
+        lines.push(format!("DESCRIPTION:{}", escape(description)));
+    }
+    for attendee in &event.attendees {
+        lines.push(format!("ATTENDEE;RSVP=TRUE:mailto:{attendee}"));
+    }
+    lines.extend(["END:VEVENT".into(), "END:VCALENDAR".into()]);
+
+    let mut output = String::new();
+    for line in lines {
+        output.push_str(&fold(&line));
+        output.push_str("\r\n");
+    }
+    output
+}
+
+/// Fold a content line into chunks of at most 75 octets, without splitting
+/// a character.
+fn fold(line: &str) -> String {
+    let mut output = String::with_capacity(line.len() + line.len() / MAX_LINE_OCTETS * 3);
+    let mut width = 0;
+    for character in line.chars() {
+        let octets = character.len_utf8();
+        if width + octets > MAX_LINE_OCTETS {
+            output.push_str("\r\n ");
+            width = 1;
+        }
+        output.push(character);
+        width += octets;
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r\n\
+VERSION:2.0\r\n\
+BEGIN:VEVENT\r\n\
+UID:standup\r\n\
+DTSTART;TZID=Europe/Budapest:20261019T093000\r\n\
+DURATION:PT15M\r\n\
+SUMMARY:Standup\\, daily\r\n\
+ATTENDEE;CN=\"Anna: PM\";RSVP=TRUE:mailto:anna@example.com\r\n\
+BEGIN:VALARM\r\n\
+ACTION:DISPLAY\r\n\
+SUMMARY:Not the event title\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:offsite\r\n\
+DTSTART;VALUE=DATE:20261020\r\n\
+DTEND;VALUE=DATE:20261022\r\n\
+SUMMARY:Offsite\r\n\
+DESCRIPTION:Bring a laptop\\nand a\r\n\
+  charger\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:cancelled\r\n\
+DTSTART:20261019T120000Z\r\n\
+STATUS:CANCELLED\r\n\
+SUMMARY:Cancelled\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+    #[test]
+    fn events_are_parsed_with_timezones_and_durations() {
+        let events = parse_events(CALENDAR, "/cal/a.ics", chrono_tz::UTC);
+        assert_eq!(events.len(), 2);
+
+        let standup = &events[0];
+        assert_eq!(standup.title, "Standup, daily");
+        assert_eq!(standup.start.to_rfc3339(), "2026-10-19T07:30:00+00:00");
+        assert_eq!(standup.end.to_rfc3339(), "2026-10-19T07:45:00+00:00");
+        assert_eq!(standup.attendees, vec!["anna@example.com".to_string()]);
+        assert_eq!(standup.id, "/cal/a.ics");
+
+        let offsite = &events[1];
+        assert!(offsite.all_day);
+        assert_eq!(offsite.end - offsite.start, chrono::Duration::days(2));
+        assert_eq!(
+            offsite.description.as_deref(),
+            Some("Bring a laptop\nand a charger")
+        );
+    }
+
+    #[test]
+    fn rendered_events_parse_back() {
+        let event = NewEvent {
+            title: "Sync with Anna; agenda, notes".into(),
+            start: Utc.with_ymd_and_hms(2026, 10, 19, 13, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2026, 10, 19, 13, 30, 0).unwrap(),
+            location: None,
+            description: Some("x".repeat(200)),
+            attendees: vec!["anna@example.com".into()],
+        };
+        let text = render_event("uid-1", &event);
+        assert!(text.lines().all(|line| line.len() <= MAX_LINE_OCTETS + 1));
+
+        let parsed = parse_events(&text, "id", chrono_tz::UTC);
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].title, event.title);
+        assert_eq!(parsed[0].start, event.start);
+        assert_eq!(parsed[0].end, event.end);
+        assert_eq!(parsed[0].description, event.description);
+        assert_eq!(parsed[0].attendees, event.attendees);
+    }
+
+    #[test]
+    fn durations_parse() {
+        assert_eq!(
+            parse_duration("PT1H30M"),
+            Some(chrono::Duration::minutes(90))
+        );
+        assert_eq!(parse_duration("P1W"), Some(chrono::Duration::days(7)));
+        assert_eq!(
+            parse_duration("-PT15M"),
+            Some(chrono::Duration::minutes(-15))
+        );
+        assert_eq!(parse_duration("1H"), None);
+    }
+}
@@ -1,5 +1,8 @@
 //! Configuration loading and validation.
 
+use crate::ProcessType;
+use crate::agent::worker_pool::{FailureKind, RetryPolicy};
+use crate::auth::AuthTier;
 use crate::error::{ConfigError, Result};
 use crate::llm::routing::RoutingConfig;
 use anyhow::Context as _;
@@ -58,6 +61,189 @@ pub struct Config {
     pub metrics: MetricsConfig,
     /// OpenTelemetry export configuration.
     pub telemetry: TelemetryConfig,
+    /// Webhook URLs that receive selected process events.
+    pub event_sinks: Vec<EventSinkConfig>,
+    /// Storage for attachments: inbound files, worker artifacts, sent files.
+    pub media: MediaConfig,
+    /// Where memory embeddings and knowledge chunks are stored.
+    pub storage: StorageConfig,
+}
+
+/// Where each agent's vector data (memory embeddings and knowledge chunks)
+/// lives. Relational data always stays in the agent's SQLite database.
+#[derive(Debug, Clone, Default)]
+pub struct StorageConfig {
+    pub backend: StorageBackendConfig,
+}
+
+/// Storage backend for vector data.
+#[derive(Debug, Clone, Default)]
+pub enum StorageBackendConfig {
+    /// LanceDB tables under each agent's `data/lancedb`.
+    #[default]
+    Local,
+    /// A Postgres database with the pgvector extension, shared by all agents.
+    Postgres(PostgresStorageConfig),
+    /// A Qdrant server, with a memory and a knowledge collection per agent.
+    Qdrant(QdrantStorageConfig),
+    /// Memory embeddings in each agent's SQLite database; knowledge chunks
+    /// stay in LanceDB.
+    Sqlite,
+}
+
+/// Postgres connection settings.
+#[derive(Clone)]
+pub struct PostgresStorageConfig {
+    /// Connection URL, e.g. `postgres://spacebot:secret@db:5432/spacebot`.
+    pub url: String,
+    /// Pool size per agent.
+    pub max_connections: u32,
+}
+
+impl std::fmt::Debug for PostgresStorageConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PostgresStorageConfig")
+            .field("url", &"[REDACTED]")
+            .field("max_connections", &self.max_connections)
+            .finish()
+    }
+}
+
+/// Qdrant connection settings.
+#[derive(Clone)]
+pub struct QdrantStorageConfig {
+    /// REST endpoint, e.g. `http://qdrant:6333`.
+    pub url: String,
+    pub api_key: Option<String>,
+    /// Collection name prefix. An agent's collections are
+    /// `{prefix}{agent_id}_memories` and `{prefix}{agent_id}_knowledge`.
+    pub collection_prefix: String,
+}
+
+impl std::fmt::Debug for QdrantStorageConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QdrantStorageConfig")
+            .field("url", &self.url)
+            .field("api_key", &self.api_key.as_ref().map(|_| "[REDACTED]"))
+            .field("collection_prefix", &self.collection_prefix)
+            .finish()
+    }
+}
+
+/// Where stored attachments live and how links to them are signed.
+#[derive(Clone)]
+pub struct MediaConfig {
+    pub backend: MediaBackendConfig,
+    /// Base URL of signed links to locally stored files, e.g.
+    /// `https://bot.example.com`. Defaults to the API server address.
+    pub public_url: String,
+    /// Key that signs local links. When unset, a key is generated and kept
+    /// in `{instance_dir}/media_signing_key`.
+    pub signing_key: Option<String>,
+    /// How long a signed link stays valid.
+    pub url_ttl_secs: u64,
+    /// Larger files are refused.
+    pub max_file_bytes: u64,
+}
+
+/// Storage backend for attachments.
+#[derive(Debug, Clone)]
+pub enum MediaBackendConfig {
+    /// Files on disk, under `{data_dir}/media` per agent, or
+    /// `{path}/{agent_id}` when a path is set.
+    Local { path: Option<PathBuf> },
+    /// An S3-compatible bucket (AWS S3, R2, MinIO, ...).
+    S3(S3MediaConfig),
+}
+
+/// S3-compatible bucket settings.
+#[derive(Clone)]
+pub struct S3MediaConfig {
+    pub bucket: String,
+    pub region: String,
+    /// Endpoint for non-AWS providers, e.g. `https://<account>.r2.cloudflarestorage.com`.
+    /// Defaults to `https://s3.{region}.amazonaws.com`.
+    pub endpoint: Option<String>,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Key prefix, e.g. `spacebot/`. Objects are stored at `{prefix}{agent_id}/{id}`.
+    pub prefix: String,
+    /// Address the bucket in the path (`{endpoint}/{bucket}/...`) instead of
+    /// the host. Most self-hosted providers need this.
+    pub path_style: bool,
+}
+
+impl Default for MediaConfig {
+    fn default() -> Self {
+        Self {
+            backend: MediaBackendConfig::Local { path: None },
+            public_url: format!("http://127.0.0.1:{}", default_api_port()),
+            signing_key: None,
+            url_ttl_secs: 3600,
+            max_file_bytes: 25 * 1024 * 1024,
+        }
+    }
+}
+
+impl std::fmt::Debug for MediaConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MediaConfig")
+            .field("backend", &self.backend)
+            .field("public_url", &self.public_url)
+            .field(
+                "signing_key",
+                &self.signing_key.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("url_ttl_secs", &self.url_ttl_secs)
+            .field("max_file_bytes", &self.max_file_bytes)
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for S3MediaConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("S3MediaConfig")
+            .field("bucket", &self.bucket)
+            .field("region", &self.region)
+            .field("endpoint", &self.endpoint)
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &"[REDACTED]")
+            .field("prefix", &self.prefix)
+            .field("path_style", &self.path_style)
+            .finish()
+    }
+}
+
+/// An HTTP endpoint that process events are POSTed to.
+#[derive(Clone)]
+pub struct EventSinkConfig {
+    pub url: String,
+    /// Event names to send (see `event_sink::EVENT_NAMES`), or `*` for all.
+    pub events: Vec<String>,
+    /// Agents whose events are sent. Empty = all agents.
+    pub agents: Vec<String>,
+    /// Extra request headers, e.g. `Authorization`.
+    pub headers: HashMap<String, String>,
+    /// When set, the body is signed with HMAC-SHA256 in
+    /// `X-Spacebot-Signature-256`.
+    pub secret: Option<String>,
+    /// Retries after a failed delivery, with exponential backoff.
+    pub max_retries: u32,
+    pub timeout_secs: u64,
+}
+
+impl std::fmt::Debug for EventSinkConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventSinkConfig")
+            .field("url", &self.url)
+            .field("events", &self.events)
+            .field("agents", &self.agents)
+            .field("headers", &self.headers.keys().collect::<Vec<_>>())
+            .field("secret", &self.secret.as_ref().map(|_| "[REDACTED]"))
+            .field("max_retries", &self.max_retries)
+            .field("timeout_secs", &self.timeout_secs)
+            .finish()
+    }
 }
 
 /// A link definition from config, connecting two nodes (agents or humans).
@@ -122,6 +308,9 @@ pub struct MetricsConfig {
     pub port: u16,
     /// Address to bind the metrics HTTP server on.
     pub bind: String,
+    /// Also count turns per channel. Off by default: every conversation
+    /// adds a series.
+    pub per_channel_turns: bool,
 }
 
 impl Default for MetricsConfig {
@@ -130,6 +319,7 @@ impl Default for MetricsConfig {
             enabled: false,
             port: 9090,
             bind: "0.0.0.0".into(),
+            per_channel_turns: false,
         }
     }
 }
@@ -340,16 +530,73 @@ pub struct DefaultsConfig {
     pub context_window: usize,
     pub compaction: CompactionConfig,
     pub memory_persistence: MemoryPersistenceConfig,
+    pub memory_injection: MemoryInjectionConfig,
+    pub knowledge: KnowledgeConfig,
+    pub memory_maintenance: MemoryMaintenanceConfig,
+    pub tool_policy: ToolPolicyConfig,
+    pub plan_mode: PlanModeConfig,
+    pub container: ContainerConfig,
+    pub http_request: HttpRequestConfig,
+    pub web_search: WebSearchConfig,
+    pub calendar: CalendarConfig,
+    pub auth: AuthConfig,
+    pub redaction: RedactionConfig,
+    pub system_prompt: SystemPromptConfig,
+    pub gating: GatingConfig,
     pub coalesce: CoalesceConfig,
     pub ingestion: IngestionConfig,
     pub cortex: CortexConfig,
     pub warmup: WarmupConfig,
+    pub heartbeat: HeartbeatConfig,
     pub browser: BrowserConfig,
     pub mcp: Vec<McpServerConfig>,
+    /// Databases the worker `sql_query` tool can query.
+    pub sql: Vec<SqlDatabaseConfig>,
     /// Brave Search API key for web search tool. Supports "env:VAR_NAME" references.
     pub brave_search_key: Option<String>,
+    /// GitHub token for the worker GitHub tools. Supports "env:VAR_NAME" references.
+    pub github_token: Option<String>,
+    /// Base URL of the Coolify instance the worker Coolify tools manage.
+    pub coolify_url: Option<String>,
+    /// Coolify API token. Supports "env:VAR_NAME" references.
+    pub coolify_token: Option<String>,
     /// Default timezone used when evaluating cron active hours.
     pub cron_timezone: Option<String>,
+    /// Hard per-channel spend limit in USD. Turns are refused once a channel's estimated cost reaches it. None disables the limit.
+    pub channel_budget_usd: Option<f64>,
+    /// Minutes without messages before an idle channel archives its state and stops. 0 disables archival.
+    pub channel_idle_timeout_mins: u64,
+    /// How long to hold a branch result while sibling branches are still running, so results that finish together reach the channel as one message.
+    pub branch_result_window_ms: u64,
+    /// Ask the channel to compare merged branch results and build its reply on the best-supported one.
+    pub rank_branch_results: bool,
+    /// How deep branches may nest: 1 means only the channel branches, 2 lets
+    /// those branches spawn sub-branches, and so on.
+    pub max_branch_depth: usize,
+    /// Max workers running at once across all of the agent's channels.
+    pub max_running_workers: usize,
+    /// Max workers a channel may have waiting for a worker pool slot.
+    pub max_queued_workers: usize,
+    /// How failed workers are retried.
+    pub worker_retry: RetryPolicy,
+    /// Named worker configurations `spawn_worker` can pick by name.
+    pub worker_presets: HashMap<String, WorkerPreset>,
+    /// Root for per-worker working directories. Defaults to `{data_dir}/workspaces`.
+    pub worker_workspace_root: Option<PathBuf>,
+    /// Recent inbound message IDs each channel remembers so redelivered messages are dropped. 0 disables deduplication.
+    pub message_dedup_window: usize,
+    /// How long a shutdown waits for in-flight turns, branches, and workers before giving up (seconds).
+    pub shutdown_drain_secs: u64,
+    /// Message posted to each active conversation when the instance shuts down. Unset posts nothing.
+    pub shutdown_notice: Option<String>,
+    /// Environment variables skill tools may read through `env:VAR` header values.
+    pub skill_env_allowlist: Vec<String>,
+    /// Tool results estimated above this many tokens are stored as artifacts and replaced with a preview (0 disables).
+    pub tool_output_max_tokens: usize,
+    /// Times a malformed tool call is sent back to the model for repair within one turn before the turn fails.
+    pub tool_call_repairs: usize,
+    /// Tool calls from one model response that run at the same time (1 runs them in order).
+    pub tool_concurrency: usize,
     pub history_backfill_count: usize,
     pub cron: Vec<CronDef>,
     pub opencode: OpenCodeConfig,
@@ -368,16 +615,57 @@ impl std::fmt::Debug for DefaultsConfig {
             .field("context_window", &self.context_window)
             .field("compaction", &self.compaction)
             .field("memory_persistence", &self.memory_persistence)
+            .field("memory_injection", &self.memory_injection)
+            .field("knowledge", &self.knowledge)
+            .field("memory_maintenance", &self.memory_maintenance)
+            .field("tool_policy", &self.tool_policy)
+            .field("plan_mode", &self.plan_mode)
+            .field("container", &self.container)
+            .field("http_request", &self.http_request)
+            .field("web_search", &self.web_search)
+            .field("calendar", &self.calendar)
+            .field("auth", &self.auth)
+            .field("redaction", &self.redaction)
+            .field("system_prompt", &self.system_prompt)
+            .field("gating", &self.gating)
             .field("coalesce", &self.coalesce)
             .field("ingestion", &self.ingestion)
             .field("cortex", &self.cortex)
             .field("warmup", &self.warmup)
+            .field("heartbeat", &self.heartbeat)
             .field("browser", &self.browser)
             .field("mcp", &self.mcp)
+            .field("sql", &self.sql)
             .field(
                 "brave_search_key",
                 &self.brave_search_key.as_ref().map(|_| "[REDACTED]"),
             )
+            .field(
+                "github_token",
+                &self.github_token.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("coolify_url", &self.coolify_url)
+            .field(
+                "coolify_token",
+                &self.coolify_token.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("channel_budget_usd", &self.channel_budget_usd)
+            .field("channel_idle_timeout_mins", &self.channel_idle_timeout_mins)
+            .field("branch_result_window_ms", &self.branch_result_window_ms)
+            .field("rank_branch_results", &self.rank_branch_results)
+            .field("max_branch_depth", &self.max_branch_depth)
+            .field("max_running_workers", &self.max_running_workers)
+            .field("max_queued_workers", &self.max_queued_workers)
+            .field("worker_retry", &self.worker_retry)
+            .field("worker_presets", &self.worker_presets)
+            .field("worker_workspace_root", &self.worker_workspace_root)
+            .field("message_dedup_window", &self.message_dedup_window)
+            .field("shutdown_drain_secs", &self.shutdown_drain_secs)
+            .field("shutdown_notice", &self.shutdown_notice)
+            .field("skill_env_allowlist", &self.skill_env_allowlist)
+            .field("tool_output_max_tokens", &self.tool_output_max_tokens)
+            .field("tool_call_repairs", &self.tool_call_repairs)
+            .field("tool_concurrency", &self.tool_concurrency)
             .field("history_backfill_count", &self.history_backfill_count)
             .field("cron", &self.cron)
             .field("opencode", &self.opencode)
@@ -394,6 +682,33 @@ pub struct McpServerConfig {
     pub enabled: bool,
 }
 
+/// A database the worker `sql_query` tool can query.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SqlDatabaseConfig {
+    /// Name the tool uses to pick the database.
+    pub name: String,
+    /// Connection URL: `sqlite:`, `postgres://`, or `mysql://`.
+    pub url: String,
+    /// Refuse statements that change data (default true).
+    pub read_only: bool,
+    /// Most rows a query returns.
+    pub max_rows: usize,
+    /// Longest a query may run, in seconds.
+    pub timeout_secs: u64,
+}
+
+impl std::fmt::Debug for SqlDatabaseConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqlDatabaseConfig")
+            .field("name", &self.name)
+            .field("url", &"[REDACTED]")
+            .field("read_only", &self.read_only)
+            .field("max_rows", &self.max_rows)
+            .field("timeout_secs", &self.timeout_secs)
+            .finish()
+    }
+}
+
 /// MCP transport configuration.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum McpTransport {
@@ -447,6 +762,598 @@ impl Default for MemoryPersistenceConfig {
     }
 }
 
+/// Automatic memory injection into the channel system prompt.
+///
+/// Before each user turn, the incoming message is used as a hybrid search
+/// query and the best matches are appended to the system prompt under
+/// "Relevant Memories". Off by default; the memory bulletin and explicit
+/// recall through branches cover most conversations.
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryInjectionConfig {
+    /// Whether relevant memories are injected into channel turns.
+    pub enabled: bool,
+    /// Maximum number of memories to inject.
+    pub max_results: usize,
+    /// Minimum RRF score for a memory to be injected.
+    pub min_score: f32,
+    /// Most recent events logged in the conversation to inject. 0 disables
+    /// them.
+    pub max_events: usize,
+}
+
+impl Default for MemoryInjectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_results: 5,
+            min_score: 0.0,
+            max_events: 5,
+        }
+    }
+}
+
+/// Document knowledge base settings.
+///
+/// Documents ingested with `knowledge_ingest` (or `spacebot knowledge ingest`)
+/// are split into overlapping chunks and embedded. When auto-retrieval is on,
+/// the chunks closest to each incoming message are added to the channel's
+/// system prompt as "Relevant Documents".
+#[derive(Debug, Clone, Copy)]
+pub struct KnowledgeConfig {
+    /// Whether matching document chunks are injected into channel turns.
+    pub auto_retrieve: bool,
+    /// Maximum number of chunks to inject.
+    pub max_results: usize,
+    /// Minimum cosine similarity for a chunk to be injected.
+    pub min_score: f32,
+    /// Target chunk size in characters.
+    pub chunk_size: usize,
+    /// Characters of overlap between consecutive chunks.
+    pub chunk_overlap: usize,
+}
+
+impl Default for KnowledgeConfig {
+    fn default() -> Self {
+        Self {
+            auto_retrieve: true,
+            max_results: 3,
+            min_score: 0.35,
+            chunk_size: 1500,
+            chunk_overlap: 200,
+        }
+    }
+}
+
+/// Periodic memory maintenance run by the cortex.
+///
+/// Each pass decays memories nobody has recalled in a while, promotes ones
+/// that keep getting recalled, forgets old memories whose importance has
+/// decayed away, and merges near-duplicates. Identity memories are never
+/// touched.
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryMaintenanceConfig {
+    pub enabled: bool,
+    /// Seconds between passes.
+    pub interval_secs: u64,
+    /// Fraction of importance a stale memory loses per pass.
+    pub decay_rate: f32,
+    /// Days without being recalled before a memory counts as stale.
+    pub stale_after_days: i64,
+    /// Recalls after which a memory recalled within `stale_after_days` is
+    /// promoted.
+    pub promote_min_accesses: i64,
+    /// How far above its type's default importance a promoted memory is
+    /// raised.
+    pub promote_boost: f32,
+    /// Importance below which old memories are forgotten.
+    pub prune_threshold: f32,
+    /// Minimum age in days before a memory can be forgotten.
+    pub prune_min_age_days: i64,
+    /// Embedding similarity at or above which two memories of the same type
+    /// are merged.
+    pub merge_threshold: f32,
+}
+
+impl Default for MemoryMaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 86400,
+            decay_rate: 0.05,
+            stale_after_days: 30,
+            promote_min_accesses: 5,
+            promote_boost: 0.05,
+            prune_threshold: 0.1,
+            prune_min_age_days: 30,
+            merge_threshold: 0.95,
+        }
+    }
+}
+
+/// Sensitive-data redaction applied before text reaches the LLM.
+///
+/// Inbound user text and tool results are scanned for API keys, credit card
+/// numbers, high-entropy tokens, and custom patterns; matches are replaced by
+/// placeholders such as `[REDACTED:api_key:1]`. Placeholders for values the
+/// user sent in a conversation can be restored when the agent's reply goes
+/// back to that same conversation.
+#[derive(Debug, Clone)]
+pub struct RedactionConfig {
+    pub enabled: bool,
+    /// Detect well-known API key and private key formats.
+    pub api_keys: bool,
+    /// Detect Luhn-valid card numbers.
+    pub credit_cards: bool,
+    /// Shannon entropy (bits per character) at or above which a token is
+    /// treated as a secret. 0 disables entropy detection.
+    pub entropy_threshold: f64,
+    /// Shortest token considered for entropy detection.
+    pub min_entropy_length: usize,
+    /// Extra patterns, each redacted under its own name.
+    pub patterns: Vec<RedactionPattern>,
+    /// Restore the user's own redacted values in replies to their conversation.
+    pub restore_outbound: bool,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            api_keys: true,
+            credit_cards: true,
+            entropy_threshold: 4.5,
+            min_entropy_length: 24,
+            patterns: Vec::new(),
+            restore_outbound: true,
+        }
+    }
+}
+
+/// A custom redaction pattern.
+#[derive(Debug, Clone)]
+pub struct RedactionPattern {
+    /// Label used in the placeholder, e.g. `employee_id`.
+    pub name: String,
+    pub regex: regex::Regex,
+}
+
+/// Which tools channels and workers may use.
+///
+/// Rules are applied on top of the tools a process would normally get, so a
+/// policy can only narrow access. An empty `allow` list means "everything not
+/// denied". Per-channel rules, keyed by conversation ID, further restrict the
+/// channel rules for that conversation.
+#[derive(Debug, Clone, Default)]
+pub struct ToolPolicyConfig {
+    pub channel: ToolRules,
+    pub worker: ToolRules,
+    pub channels: HashMap<String, ToolRules>,
+    /// Tools whose worker calls wait for the user to `approve` or `deny`
+    /// them in the channel.
+    pub requires_approval: Vec<String>,
+}
+
+impl ToolPolicyConfig {
+    /// Whether a process of `process_type` may use `tool_name`. For channels,
+    /// `conversation_id` selects any per-channel rules.
+    pub fn permits(
+        &self,
+        process_type: ProcessType,
+        conversation_id: Option<&str>,
+        tool_name: &str,
+    ) -> bool {
+        let rules = match process_type {
+            ProcessType::Channel => &self.channel,
+            ProcessType::Worker => &self.worker,
+            _ => return true,
+        };
+        if !rules.permits(tool_name) {
+            return false;
+        }
+
+        match (process_type, conversation_id) {
+            (ProcessType::Channel, Some(conversation_id)) => self
+                .channels
+                .get(conversation_id)
+                .is_none_or(|rules| rules.permits(tool_name)),
+            _ => true,
+        }
+    }
+}
+
+/// Plan mode: workers hold back destructive tool calls (shell, exec, file
+/// writes) as a plan the user approves with `!approve_plan`.
+#[derive(Debug, Clone, Default)]
+pub struct PlanModeConfig {
+    /// Run every channel turn in plan mode, not just `!plan` messages.
+    pub enabled: bool,
+    /// Tools held back in addition to shell, exec, and file writes, e.g. MCP
+    /// tools that change things.
+    pub tools: Vec<String>,
+}
+
+/// Ephemeral containers for the worker `container_run` tool.
+#[derive(Debug, Clone)]
+pub struct ContainerConfig {
+    /// Give workers the `container_run` tool.
+    pub enabled: bool,
+    /// Container runtime to run, e.g. "docker" or "podman". None uses podman
+    /// if it's installed, docker otherwise.
+    pub runtime: Option<String>,
+    /// Image for calls that don't name one.
+    pub default_image: String,
+    /// Images calls may use, as `name` (any tag) or `name:tag`. Empty allows
+    /// any image.
+    pub allowed_images: Vec<String>,
+    /// Memory limit, in the runtime's `--memory` syntax (e.g. "512m").
+    pub memory: String,
+    /// CPU limit, in cores.
+    pub cpus: f64,
+    /// Maximum number of processes in a container.
+    pub pids_limit: u32,
+    /// Let calls ask for network access. Containers have none otherwise.
+    pub allow_network: bool,
+    /// Longest a call may run, in seconds.
+    pub max_timeout_secs: u64,
+}
+
+impl Default for ContainerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            runtime: None,
+            default_image: "python:3.12-slim".into(),
+            allowed_images: Vec::new(),
+            memory: "512m".into(),
+            cpus: 1.0,
+            pids_limit: 256,
+            allow_network: false,
+            max_timeout_secs: 300,
+        }
+    }
+}
+
+/// Calls to internal APIs through the worker `http_request` tool.
+#[derive(Debug, Clone)]
+pub struct HttpRequestConfig {
+    /// Hosts requests may go to: `api.example.com` for that host only,
+    /// `*.example.com` for its subdomains. Empty leaves the tool out.
+    pub allowed_domains: Vec<String>,
+    /// Most response body bytes returned; the rest is dropped.
+    pub max_response_bytes: usize,
+    /// Longest a request may take, in seconds.
+    pub timeout_secs: u64,
+}
+
+impl Default for HttpRequestConfig {
+    fn default() -> Self {
+        Self {
+            allowed_domains: Vec::new(),
+            max_response_bytes: 1024 * 1024,
+            timeout_secs: 30,
+        }
+    }
+}
+
+/// Search engine behind the `web_search` tool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebSearchProvider {
+    /// Brave Search API; needs an API key.
+    #[default]
+    Brave,
+    /// A SearXNG instance with the JSON output format enabled; needs its URL.
+    Searxng,
+    /// Kagi Search API; needs an API key.
+    Kagi,
+}
+
+/// Backend for the `web_search` tool.
+#[derive(Clone, Default)]
+pub struct WebSearchConfig {
+    pub provider: WebSearchProvider,
+    /// API key for Brave or Kagi. Brave falls back to `brave_search_key`.
+    pub api_key: Option<String>,
+    /// Base URL of the SearXNG instance, e.g. `https://search.example.com`.
+    pub url: Option<String>,
+}
+
+impl WebSearchConfig {
+    /// Use `brave_search_key` as the key when Brave is selected without one.
+    pub fn with_brave_fallback(mut self, brave_search_key: Option<&str>) -> Self {
+        if self.provider == WebSearchProvider::Brave && self.api_key.is_none() {
+            self.api_key = brave_search_key.map(str::to_string);
+        }
+        self
+    }
+
+    /// Whether the provider has what it needs: a key, or a SearXNG URL.
+    pub fn is_configured(&self) -> bool {
+        match self.provider {
+            WebSearchProvider::Brave | WebSearchProvider::Kagi => self.api_key.is_some(),
+            WebSearchProvider::Searxng => self.url.is_some(),
+        }
+    }
+
+    /// A copy of the config if it's usable, for handing to a worker.
+    pub fn configured(&self) -> Option<Self> {
+        self.is_configured().then(|| self.clone())
+    }
+}
+
+impl std::fmt::Debug for WebSearchConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebSearchConfig")
+            .field("provider", &self.provider)
+            .field("api_key", &self.api_key.as_ref().map(|_| "[REDACTED]"))
+            .field("url", &self.url)
+            .finish()
+    }
+}
+
+/// Calendar service behind the calendar tools and sync.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CalendarProvider {
+    /// A CalDAV calendar collection (Nextcloud, Fastmail, iCloud, Radicale).
+    #[default]
+    CalDav,
+    /// Google Calendar, through an OAuth refresh token.
+    Google,
+}
+
+/// The agent's calendar: where it lives, and how often upcoming events are
+/// synced for the heartbeat.
+#[derive(Clone)]
+pub struct CalendarConfig {
+    pub provider: CalendarProvider,
+    /// CalDAV calendar collection URL, e.g.
+    /// `https://cloud.example.com/remote.php/dav/calendars/me/personal/`.
+    pub url: Option<String>,
+    /// CalDAV basic auth credentials.
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Google calendar ID; `primary` is the account's main calendar.
+    pub calendar_id: String,
+    /// Google OAuth client and a refresh token with the calendar scope.
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub refresh_token: Option<String>,
+    /// IANA timezone that times without an offset are read in, and that
+    /// events are shown in. UTC when unset or invalid.
+    pub timezone: Option<String>,
+    /// Seconds between syncs of upcoming events.
+    pub sync_interval_secs: u64,
+    /// How far ahead the sync looks, in hours.
+    pub lookahead_hours: u64,
+    /// Leave out the tools that create and delete events.
+    pub read_only: bool,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            provider: CalendarProvider::default(),
+            url: None,
+            username: None,
+            password: None,
+            calendar_id: "primary".into(),
+            client_id: None,
+            client_secret: None,
+            refresh_token: None,
+            timezone: None,
+            sync_interval_secs: 900,
+            lookahead_hours: 24,
+            read_only: false,
+        }
+    }
+}
+
+impl CalendarConfig {
+    /// Whether the provider has what it needs to connect.
+    pub fn is_configured(&self) -> bool {
+        match self.provider {
+            CalendarProvider::CalDav => self.url.is_some(),
+            CalendarProvider::Google => {
+                self.client_id.is_some()
+                    && self.client_secret.is_some()
+                    && self.refresh_token.is_some()
+            }
+        }
+    }
+
+    /// The configured timezone, falling back to UTC.
+    pub fn tz(&self) -> chrono_tz::Tz {
+        self.timezone
+            .as_deref()
+            .and_then(|name| name.parse().ok())
+            .unwrap_or(chrono_tz::UTC)
+    }
+}
+
+impl std::fmt::Debug for CalendarConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let redacted = |value: &Option<String>| value.as_ref().map(|_| "[REDACTED]");
+        f.debug_struct("CalendarConfig")
+            .field("provider", &self.provider)
+            .field("url", &self.url)
+            .field("username", &self.username)
+            .field("password", &redacted(&self.password))
+            .field("calendar_id", &self.calendar_id)
+            .field("client_id", &self.client_id)
+            .field("client_secret", &redacted(&self.client_secret))
+            .field("refresh_token", &redacted(&self.refresh_token))
+            .field("timezone", &self.timezone)
+            .field("sync_interval_secs", &self.sync_interval_secs)
+            .field("lookahead_hours", &self.lookahead_hours)
+            .field("read_only", &self.read_only)
+            .finish()
+    }
+}
+
+/// Layout of the channel system prompt: which sections it has and in what
+/// order. Sections left out of `sections` are omitted.
+#[derive(Debug, Clone)]
+pub struct SystemPromptConfig {
+    /// Built-in section names (see [`Self::BUILTIN_SECTIONS`]) and names of
+    /// custom blocks, in the order they appear in the prompt.
+    pub sections: Vec<String>,
+    /// Operator-written text, keyed by the name used in `sections`.
+    pub blocks: HashMap<String, String>,
+}
+
+impl SystemPromptConfig {
+    /// Sections of the bundled channel prompt, in their default order.
+    pub const BUILTIN_SECTIONS: &[&str] = &[
+        "identity",
+        "memory_bulletin",
+        "instructions",
+        "skills",
+        "worker_capabilities",
+        "available_channels",
+        "org_context",
+        "link_context",
+        "conversation_context",
+        "status",
+        "coalesce_hint",
+    ];
+}
+
+impl Default for SystemPromptConfig {
+    fn default() -> Self {
+        Self {
+            sections: Self::BUILTIN_SECTIONS
+                .iter()
+                .map(|section| section.to_string())
+                .collect(),
+            blocks: HashMap::new(),
+        }
+    }
+}
+
+/// A named worker configuration, picked with `spawn_worker`'s `preset`
+/// argument instead of improvising one per task.
+#[derive(Debug, Clone)]
+pub struct WorkerPreset {
+    /// What the preset is for, shown to the channel in `spawn_worker`.
+    pub description: String,
+    /// Instructions appended to the worker system prompt.
+    pub prompt: Option<String>,
+    /// Tools the worker gets, on top of the tool policy. Empty means all.
+    pub tools: Vec<String>,
+    /// Model to run on instead of the routed worker model.
+    pub model: Option<String>,
+    /// Wall-clock limit after which the worker is cancelled.
+    pub timeout_secs: Option<u64>,
+}
+
+/// When a channel takes a turn for a message in a group conversation.
+/// Direct messages are always answered.
+#[derive(Debug, Clone)]
+pub struct GatingConfig {
+    pub mode: GatingMode,
+    /// Words that address the agent, besides its name, matched
+    /// case-insensitively as whole words.
+    pub wake_words: Vec<String>,
+    /// Passive listening: keep messages the gate turns away and summarize
+    /// them into episodic memory instead of dropping them.
+    pub listen: bool,
+    /// Overheard messages summarized together.
+    pub listen_batch: usize,
+    /// Longest an overheard message waits to be summarized (seconds).
+    pub listen_interval_secs: u64,
+}
+
+impl Default for GatingConfig {
+    fn default() -> Self {
+        Self {
+            mode: GatingMode::default(),
+            wake_words: Vec::new(),
+            listen: false,
+            listen_batch: 30,
+            listen_interval_secs: 900,
+        }
+    }
+}
+
+/// How group messages are gated (see [`GatingConfig`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GatingMode {
+    /// Take a turn for every message.
+    #[default]
+    Always,
+    /// Only when the agent is mentioned, named, or replied to.
+    Mention,
+    /// As `Mention`, and otherwise ask the cheap model whether the agent
+    /// should chime in.
+    Relevance,
+}
+
+/// Per-sender authorization tiers (see [`crate::auth::tiers`]).
+///
+/// Off unless the agent has an `[auth]` table; until then every sender is
+/// treated as an admin.
+#[derive(Debug, Clone)]
+pub struct AuthConfig {
+    pub enabled: bool,
+    /// Tier for senders not matched by ID or role.
+    pub default_tier: AuthTier,
+    /// Sender IDs prefixed with their platform (`discord:123`), mapped to
+    /// tiers. Takes precedence over roles.
+    pub senders: HashMap<String, AuthTier>,
+    /// Discord role IDs mapped to tiers. A sender gets the highest tier among
+    /// their roles.
+    pub roles: HashMap<String, AuthTier>,
+    /// Lowest tier the agent responds to, keyed by conversation ID. Messages
+    /// from lower tiers are dropped without a reply.
+    pub channels: HashMap<String, AuthTier>,
+    /// Channel tool rules per tier, replacing that tier's built-in rules.
+    pub tools: HashMap<AuthTier, ToolRules>,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            default_tier: AuthTier::User,
+            senders: HashMap::new(),
+            roles: HashMap::new(),
+            channels: HashMap::new(),
+            tools: HashMap::new(),
+        }
+    }
+}
+
+impl AuthConfig {
+    /// Whether a channel turn for a sender of `tier` may use `tool_name`.
+    /// `[auth.tools.<tier>]` adds to and removes from the tier's built-in
+    /// rules rather than replacing them.
+    pub fn permits_tool(&self, tier: AuthTier, tool_name: &str) -> bool {
+        let Some(rules) = self.tools.get(&tier) else {
+            return tier.permits_by_default(tool_name);
+        };
+        (tier.permits_by_default(tool_name) || rules.allow.iter().any(|name| name == tool_name))
+            && !rules.deny.iter().any(|name| name == tool_name)
+    }
+}
+
+/// Allow/deny lists of tool names.
+#[derive(Debug, Clone, Default)]
+pub struct ToolRules {
+    /// If non-empty, only these tools are permitted.
+    pub allow: Vec<String>,
+    /// Tools that are never permitted. Takes precedence over `allow`.
+    pub deny: Vec<String>,
+}
+
+impl ToolRules {
+    pub fn permits(&self, tool_name: &str) -> bool {
+        (self.allow.is_empty() || self.allow.iter().any(|name| name == tool_name))
+            && !self.deny.iter().any(|name| name == tool_name)
+    }
+}
+
 impl Default for CompactionConfig {
     fn default() -> Self {
         Self {
@@ -514,6 +1421,32 @@ impl Default for IngestionConfig {
     }
 }
 
+/// Heartbeat configuration: a periodic check-in the agent sends on its own.
+///
+/// On every tick the heartbeat surveys memories, pending cron jobs, and
+/// running workers, asks the LLM whether anything is worth raising, and sends
+/// the answer to `delivery_target` unless the LLM decides to stay quiet.
+#[derive(Debug, Clone)]
+pub struct HeartbeatConfig {
+    /// Whether the heartbeat runs. Needs a `delivery_target` as well.
+    pub enabled: bool,
+    /// Seconds between heartbeats.
+    pub interval_secs: u64,
+    /// Where check-ins are sent, in cron's `adapter:target` format
+    /// (e.g. `discord:123456789`).
+    pub delivery_target: Option<String>,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 86400,
+            delivery_target: None,
+        }
+    }
+}
+
 /// Browser automation configuration for workers.
 #[derive(Debug, Clone)]
 pub struct BrowserConfig {
@@ -759,20 +1692,61 @@ pub struct AgentConfig {
     pub context_window: Option<usize>,
     pub compaction: Option<CompactionConfig>,
     pub memory_persistence: Option<MemoryPersistenceConfig>,
+    pub memory_injection: Option<MemoryInjectionConfig>,
+    pub knowledge: Option<KnowledgeConfig>,
+    pub memory_maintenance: Option<MemoryMaintenanceConfig>,
+    pub tool_policy: Option<ToolPolicyConfig>,
+    pub plan_mode: Option<PlanModeConfig>,
+    pub container: Option<ContainerConfig>,
+    pub http_request: Option<HttpRequestConfig>,
+    pub web_search: Option<WebSearchConfig>,
+    pub calendar: Option<CalendarConfig>,
+    pub auth: Option<AuthConfig>,
+    pub redaction: Option<RedactionConfig>,
+    pub system_prompt: Option<SystemPromptConfig>,
+    pub gating: Option<GatingConfig>,
     pub coalesce: Option<CoalesceConfig>,
     pub ingestion: Option<IngestionConfig>,
     pub cortex: Option<CortexConfig>,
     pub warmup: Option<WarmupConfig>,
+    pub heartbeat: Option<HeartbeatConfig>,
     pub browser: Option<BrowserConfig>,
     pub mcp: Option<Vec<McpServerConfig>>,
+    /// Per-agent databases, merged with the defaults by name.
+    pub sql: Option<Vec<SqlDatabaseConfig>>,
     /// Per-agent Brave Search API key override. None inherits from defaults.
     pub brave_search_key: Option<String>,
+    /// Per-agent GitHub token override. None inherits from defaults.
+    pub github_token: Option<String>,
+    /// Per-agent Coolify instance override. None inherits from defaults.
+    pub coolify_url: Option<String>,
+    /// Per-agent Coolify API token override. None inherits from defaults.
+    pub coolify_token: Option<String>,
     /// Optional timezone override for cron active-hours evaluation.
     pub cron_timezone: Option<String>,
+    pub channel_budget_usd: Option<f64>,
+    pub channel_idle_timeout_mins: Option<u64>,
+    pub branch_result_window_ms: Option<u64>,
+    pub rank_branch_results: Option<bool>,
+    pub max_branch_depth: Option<usize>,
+    pub max_running_workers: Option<usize>,
+    pub max_queued_workers: Option<usize>,
+    pub worker_retry: Option<RetryPolicy>,
+    pub worker_presets: Option<HashMap<String, WorkerPreset>>,
+    pub worker_workspace_root: Option<PathBuf>,
+    pub message_dedup_window: Option<usize>,
+    pub shutdown_drain_secs: Option<u64>,
+    pub shutdown_notice: Option<String>,
+    pub skill_env_allowlist: Option<Vec<String>>,
+    pub tool_output_max_tokens: Option<usize>,
+    pub tool_call_repairs: Option<usize>,
+    pub tool_concurrency: Option<usize>,
     /// Sandbox configuration for process containment.
     pub sandbox: Option<crate::sandbox::SandboxConfig>,
     /// Cron job definitions for this agent.
     pub cron: Vec<CronDef>,
+    /// Feeds this agent watches.
+    pub feeds: Vec<FeedConfig>,
 }
 
 /// A cron job definition from config.
@@ -781,6 +1755,8 @@ pub struct CronDef {
     pub id: String,
     pub prompt: String,
     pub interval_secs: u64,
+    /// Five-field cron expression. Takes precedence over `interval_secs` when set.
+    pub cron_expr: Option<String>,
     /// Delivery target in "adapter:target" format (e.g. "discord:123456789").
     pub delivery_target: String,
     /// Optional active hours window (start_hour, end_hour) in 24h format.
@@ -790,6 +1766,27 @@ pub struct CronDef {
     /// Maximum wall-clock seconds to wait for the job to complete.
     /// `None` uses the default of 120 seconds.
     pub timeout_secs: Option<u64>,
+    /// Run the prompt as a worker that carries state between runs.
+    pub stateful: bool,
+}
+
+/// An RSS or Atom feed the agent watches, from `[[agents.feeds]]`.
+///
+/// New entries are summarized into a digest by the channel model and sent to
+/// `delivery_target`. The first poll only records what's already in the feed.
+#[derive(Debug, Clone)]
+pub struct FeedConfig {
+    /// Name the feed's seen entries are stored under.
+    pub name: String,
+    pub url: String,
+    /// Where digests are sent, in cron's `adapter:target` format
+    /// (e.g. `discord:123456789`).
+    pub delivery_target: String,
+    /// Seconds between polls.
+    pub interval_secs: u64,
+    /// Most entries in one digest; older new entries are left out.
+    pub max_items: usize,
+    pub enabled: bool,
 }
 
 /// Fully resolved agent config (merged with defaults, paths resolved).
@@ -809,19 +1806,56 @@ pub struct ResolvedAgentConfig {
     pub context_window: usize,
     pub compaction: CompactionConfig,
     pub memory_persistence: MemoryPersistenceConfig,
+    pub memory_injection: MemoryInjectionConfig,
+    pub knowledge: KnowledgeConfig,
+    pub memory_maintenance: MemoryMaintenanceConfig,
+    pub tool_policy: ToolPolicyConfig,
+    pub plan_mode: PlanModeConfig,
+    pub container: ContainerConfig,
+    pub http_request: HttpRequestConfig,
+    pub web_search: WebSearchConfig,
+    pub calendar: CalendarConfig,
+    pub auth: AuthConfig,
+    pub redaction: RedactionConfig,
+    pub system_prompt: SystemPromptConfig,
+    pub gating: GatingConfig,
     pub coalesce: CoalesceConfig,
     pub ingestion: IngestionConfig,
     pub cortex: CortexConfig,
     pub warmup: WarmupConfig,
+    pub heartbeat: HeartbeatConfig,
     pub browser: BrowserConfig,
     pub mcp: Vec<McpServerConfig>,
+    /// Databases the worker `sql_query` tool can query.
+    pub sql: Vec<SqlDatabaseConfig>,
     pub brave_search_key: Option<String>,
+    pub github_token: Option<String>,
+    pub coolify_url: Option<String>,
+    pub coolify_token: Option<String>,
     pub cron_timezone: Option<String>,
     /// Sandbox configuration for process containment.
     pub sandbox: crate::sandbox::SandboxConfig,
+    pub channel_budget_usd: Option<f64>,
+    pub channel_idle_timeout_mins: u64,
+    pub branch_result_window_ms: u64,
+    pub rank_branch_results: bool,
+    pub max_branch_depth: usize,
+    pub max_running_workers: usize,
+    pub max_queued_workers: usize,
+    pub worker_retry: RetryPolicy,
+    pub worker_presets: HashMap<String, WorkerPreset>,
+    pub worker_workspace_root: Option<PathBuf>,
+    pub message_dedup_window: usize,
+    pub shutdown_drain_secs: u64,
+    pub shutdown_notice: Option<String>,
+    pub skill_env_allowlist: Vec<String>,
+    pub tool_output_max_tokens: usize,
+    pub tool_call_repairs: usize,
+    pub tool_concurrency: usize,
     /// Number of messages to fetch from the platform when a new channel is created.
     pub history_backfill_count: usize,
     pub cron: Vec<CronDef>,
+    pub feeds: Vec<FeedConfig>,
 }
 
 impl Default for DefaultsConfig {
@@ -835,14 +1869,49 @@ impl Default for DefaultsConfig {
             context_window: 128_000,
             compaction: CompactionConfig::default(),
             memory_persistence: MemoryPersistenceConfig::default(),
+            memory_injection: MemoryInjectionConfig::default(),
+            knowledge: KnowledgeConfig::default(),
+            memory_maintenance: MemoryMaintenanceConfig::default(),
+            tool_policy: ToolPolicyConfig::default(),
+            plan_mode: PlanModeConfig::default(),
+            container: ContainerConfig::default(),
+            http_request: HttpRequestConfig::default(),
+            web_search: WebSearchConfig::default(),
+            calendar: CalendarConfig::default(),
+            auth: AuthConfig::default(),
+            redaction: RedactionConfig::default(),
+            system_prompt: SystemPromptConfig::default(),
+            gating: GatingConfig::default(),
             coalesce: CoalesceConfig::default(),
             ingestion: IngestionConfig::default(),
             cortex: CortexConfig::default(),
             warmup: WarmupConfig::default(),
+            heartbeat: HeartbeatConfig::default(),
             browser: BrowserConfig::default(),
             mcp: Vec::new(),
+            sql: Vec::new(),
             brave_search_key: None,
+            github_token: None,
+            coolify_url: None,
+            coolify_token: None,
             cron_timezone: None,
+            channel_budget_usd: None,
+            channel_idle_timeout_mins: 60,
+            branch_result_window_ms: 3000,
+            rank_branch_results: true,
+            max_branch_depth: 2,
+            max_running_workers: 10,
+            max_queued_workers: 10,
+            worker_retry: RetryPolicy::default(),
+            worker_presets: HashMap::new(),
+            worker_workspace_root: None,
+            message_dedup_window: 256,
+            shutdown_drain_secs: 30,
+            shutdown_notice: None,
+            skill_env_allowlist: Vec::new(),
+            tool_output_max_tokens: 8000,
+            tool_call_repairs: 2,
+            tool_concurrency: 4,
             history_backfill_count: 50,
             cron: Vec::new(),
             opencode: OpenCodeConfig::default(),
@@ -883,27 +1952,146 @@ impl AgentConfig {
             memory_persistence: self
                 .memory_persistence
                 .unwrap_or(defaults.memory_persistence),
+            memory_injection: self.memory_injection.unwrap_or(defaults.memory_injection),
+            knowledge: self.knowledge.unwrap_or(defaults.knowledge),
+            memory_maintenance: self
+                .memory_maintenance
+                .unwrap_or(defaults.memory_maintenance),
+            tool_policy: self
+                .tool_policy
+                .clone()
+                .unwrap_or_else(|| defaults.tool_policy.clone()),
+            plan_mode: self
+                .plan_mode
+                .clone()
+                .unwrap_or_else(|| defaults.plan_mode.clone()),
+            container: self
+                .container
+                .clone()
+                .unwrap_or_else(|| defaults.container.clone()),
+            http_request: self
+                .http_request
+                .clone()
+                .unwrap_or_else(|| defaults.http_request.clone()),
+            web_search: self
+                .web_search
+                .clone()
+                .unwrap_or_else(|| defaults.web_search.clone())
+                .with_brave_fallback(
+                    self.brave_search_key
+                        .as_deref()
+                        .or(defaults.brave_search_key.as_deref()),
+                ),
+            calendar: self
+                .calendar
+                .clone()
+                .unwrap_or_else(|| defaults.calendar.clone()),
+            auth: self.auth.clone().unwrap_or_else(|| defaults.auth.clone()),
+            redaction: self
+                .redaction
+                .clone()
+                .unwrap_or_else(|| defaults.redaction.clone()),
+            system_prompt: self
+                .system_prompt
+                .clone()
+                .unwrap_or_else(|| defaults.system_prompt.clone()),
+            gating: self
+                .gating
+                .clone()
+                .unwrap_or_else(|| defaults.gating.clone()),
             coalesce: self.coalesce.unwrap_or(defaults.coalesce),
             ingestion: self.ingestion.unwrap_or(defaults.ingestion),
             cortex: self.cortex.unwrap_or(defaults.cortex),
             warmup: self.warmup.unwrap_or(defaults.warmup),
+            heartbeat: self
+                .heartbeat
+                .clone()
+                .unwrap_or_else(|| defaults.heartbeat.clone()),
             browser: self
                 .browser
                 .clone()
                 .unwrap_or_else(|| defaults.browser.clone()),
             mcp: resolve_mcp_configs(&defaults.mcp, self.mcp.as_deref()),
+            sql: resolve_sql_configs(&defaults.sql, self.sql.as_deref()),
             brave_search_key: self
                 .brave_search_key
                 .clone()
                 .or_else(|| defaults.brave_search_key.clone()),
+            github_token: self
+                .github_token
+                .clone()
+                .or_else(|| defaults.github_token.clone()),
+            coolify_url: self
+                .coolify_url
+                .clone()
+                .or_else(|| defaults.coolify_url.clone()),
+            coolify_token: self
+                .coolify_token
+                .clone()
+                .or_else(|| defaults.coolify_token.clone()),
             cron_timezone: resolve_cron_timezone(
                 &self.id,
                 self.cron_timezone.as_deref(),
                 defaults.cron_timezone.as_deref(),
             ),
+            channel_budget_usd: self.channel_budget_usd.or(defaults.channel_budget_usd),
+            channel_idle_timeout_mins: self
+                .channel_idle_timeout_mins
+                .unwrap_or(defaults.channel_idle_timeout_mins),
+            branch_result_window_ms: self
+                .branch_result_window_ms
+                .unwrap_or(defaults.branch_result_window_ms),
+            rank_branch_results: self
+                .rank_branch_results
+                .unwrap_or(defaults.rank_branch_results),
+            max_branch_depth: self
+                .max_branch_depth
+                .unwrap_or(defaults.max_branch_depth)
+                .max(1),
+            max_running_workers: self
+                .max_running_workers
+                .unwrap_or(defaults.max_running_workers),
+            max_queued_workers: self
+                .max_queued_workers
+                .unwrap_or(defaults.max_queued_workers),
+            worker_retry: self
+                .worker_retry
+                .clone()
+                .unwrap_or_else(|| defaults.worker_retry.clone()),
+            worker_presets: self
+                .worker_presets
+                .clone()
+                .unwrap_or_else(|| defaults.worker_presets.clone()),
+            worker_workspace_root: self
+                .worker_workspace_root
+                .clone()
+                .or_else(|| defaults.worker_workspace_root.clone()),
+            message_dedup_window: self
+                .message_dedup_window
+                .unwrap_or(defaults.message_dedup_window),
+            shutdown_drain_secs: self
+                .shutdown_drain_secs
+                .unwrap_or(defaults.shutdown_drain_secs),
+            shutdown_notice: self
+                .shutdown_notice
+                .clone()
+                .or_else(|| defaults.shutdown_notice.clone()),
+            skill_env_allowlist: self
+                .skill_env_allowlist
+                .clone()
+                .unwrap_or_else(|| defaults.skill_env_allowlist.clone()),
+            tool_output_max_tokens: self
+                .tool_output_max_tokens
+                .unwrap_or(defaults.tool_output_max_tokens),
+            tool_call_repairs: self.tool_call_repairs.unwrap_or(defaults.tool_call_repairs),
+            tool_concurrency: self
+                .tool_concurrency
+                .unwrap_or(defaults.tool_concurrency)
+                .max(1),
             sandbox: self.sandbox.clone().unwrap_or_default(),
             history_backfill_count: defaults.history_backfill_count,
             cron: self.cron.clone(),
+            feeds: self.feeds.clone(),
         }
     }
 }
@@ -934,6 +2122,18 @@ impl ResolvedAgentConfig {
         self.data_dir.join("logs")
     }
 
+    /// Root of the agent's file store, shared by its workers and channels.
+    pub fn files_dir(&self) -> PathBuf {
+        self.data_dir.join("files")
+    }
+
+    /// Root for per-worker working directories, falling back to data_dir/workspaces.
+    pub fn worker_workspaces_dir(&self) -> PathBuf {
+        self.worker_workspace_root
+            .clone()
+            .unwrap_or_else(|| self.data_dir.join("workspaces"))
+    }
+
     /// Path to agent workspace skills directory.
     pub fn skills_dir(&self) -> PathBuf {
         self.workspace.join("skills")
@@ -1099,6 +2299,7 @@ pub struct MessagingConfig {
     pub telegram: Option<TelegramConfig>,
     pub webhook: Option<WebhookConfig>,
     pub twitch: Option<TwitchConfig>,
+    pub email: Option<EmailConfig>,
 }
 
 #[derive(Clone)]
@@ -1397,6 +2598,49 @@ impl std::fmt::Debug for TwitchConfig {
     }
 }
 
+#[derive(Clone)]
+pub struct EmailConfig {
+    pub enabled: bool,
+    pub imap_host: String,
+    pub imap_port: u16,
+    pub imap_username: String,
+    pub imap_password: String,
+    /// Mailbox polled for new mail.
+    pub mailbox: String,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_username: String,
+    pub smtp_password: String,
+    /// Address replies are sent from. Mail from this address is ignored.
+    pub from_address: String,
+    pub from_name: Option<String>,
+    pub poll_interval_secs: u64,
+    /// Senders whose mail is handled: full addresses, `@domain` suffixes, or
+    /// `*` for anyone. If empty, all incoming mail is ignored.
+    pub allowed_senders: Vec<String>,
+}
+
+impl std::fmt::Debug for EmailConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmailConfig")
+            .field("enabled", &self.enabled)
+            .field("imap_host", &self.imap_host)
+            .field("imap_port", &self.imap_port)
+            .field("imap_username", &self.imap_username)
+            .field("imap_password", &"[REDACTED]")
+            .field("mailbox", &self.mailbox)
+            .field("smtp_host", &self.smtp_host)
+            .field("smtp_port", &self.smtp_port)
+            .field("smtp_username", &self.smtp_username)
+            .field("smtp_password", &"[REDACTED]")
+            .field("from_address", &self.from_address)
+            .field("from_name", &self.from_name)
+            .field("poll_interval_secs", &self.poll_interval_secs)
+            .field("allowed_senders", &self.allowed_senders)
+            .finish()
+    }
+}
+
 /// Hot-reloadable Twitch permission filters.
 ///
 /// Shared with the Twitch adapter via `Arc<ArcSwap<..>>` for hot-reloading.
@@ -1448,6 +2692,57 @@ pub struct WebhookConfig {
     pub port: u16,
     pub bind: String,
     pub auth_token: Option<String>,
+    /// Signed event sources accepted at `/hooks/{name}`.
+    pub sources: Vec<WebhookSourceConfig>,
+}
+
+/// How a webhook source signs its payloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookSignature {
+    /// `X-Hub-Signature-256: sha256=<hex>` over the body.
+    Github,
+    /// `Stripe-Signature: t=<timestamp>,v1=<hex>` over `<timestamp>.<body>`.
+    Stripe,
+    /// Hex HMAC-SHA256 of the body in a configurable header, optionally
+    /// prefixed with `sha256=`.
+    Hmac,
+}
+
+/// An external service that posts signed JSON events to the webhook adapter.
+#[derive(Clone)]
+pub struct WebhookSourceConfig {
+    /// URL path segment: events are posted to `/hooks/{name}`.
+    pub name: String,
+    pub signature: WebhookSignature,
+    pub secret: String,
+    /// Header carrying the signature for `hmac` sources.
+    pub signature_header: String,
+    /// Header carrying a Unix timestamp for `hmac` sources. When set, the
+    /// signature covers `<timestamp>.<body>` and stale timestamps are
+    /// rejected.
+    pub timestamp_header: Option<String>,
+    /// Template for the conversation the event lands in. Defaults to the
+    /// source name, so all events from a source share one conversation.
+    pub conversation: Option<String>,
+    /// Agent that handles the events. Falls back to bindings.
+    pub agent_id: Option<String>,
+    /// Template turning the payload into the message the agent sees.
+    pub template: Option<String>,
+}
+
+impl std::fmt::Debug for WebhookSourceConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookSourceConfig")
+            .field("name", &self.name)
+            .field("signature", &self.signature)
+            .field("secret", &"[REDACTED]")
+            .field("signature_header", &self.signature_header)
+            .field("timestamp_header", &self.timestamp_header)
+            .field("conversation", &self.conversation)
+            .field("agent_id", &self.agent_id)
+            .field("template", &self.template)
+            .finish()
+    }
 }
 
 // -- TOML deserialization types --
@@ -1476,6 +2771,103 @@ struct TomlConfig {
     metrics: TomlMetricsConfig,
     #[serde(default)]
     telemetry: TomlTelemetryConfig,
+    #[serde(default)]
+    event_sinks: Vec<TomlEventSinkConfig>,
+    #[serde(default)]
+    media: TomlMediaConfig,
+    #[serde(default)]
+    storage: TomlStorageConfig,
+}
+
+#[derive(Deserialize, Default)]
+struct TomlStorageConfig {
+    /// `local` (default), `sqlite`, `postgres`, or `qdrant`.
+    backend: Option<String>,
+    postgres: Option<TomlPostgresStorageConfig>,
+    qdrant: Option<TomlQdrantStorageConfig>,
+}
+
+#[derive(Deserialize)]
+struct TomlQdrantStorageConfig {
+    url: String,
+    api_key: Option<String>,
+    #[serde(default = "default_qdrant_collection_prefix")]
+    collection_prefix: String,
+}
+
+fn default_qdrant_collection_prefix() -> String {
+    "spacebot_".into()
+}
+
+#[derive(Deserialize)]
+struct TomlPostgresStorageConfig {
+    url: String,
+    #[serde(default = "default_postgres_max_connections")]
+    max_connections: u32,
+}
+
+fn default_postgres_max_connections() -> u32 {
+    5
+}
+
+#[derive(Deserialize, Default)]
+struct TomlMediaConfig {
+    /// `local` (default) or `s3`.
+    backend: Option<String>,
+    path: Option<String>,
+    public_url: Option<String>,
+    signing_key: Option<String>,
+    url_ttl_secs: Option<u64>,
+    max_file_bytes: Option<u64>,
+    s3: Option<TomlS3MediaConfig>,
+}
+
+#[derive(Deserialize)]
+struct TomlS3MediaConfig {
+    bucket: String,
+    #[serde(default = "default_s3_region")]
+    region: String,
+    endpoint: Option<String>,
+    access_key_id: String,
+    secret_access_key: String,
+    #[serde(default)]
+    prefix: String,
+    #[serde(default)]
+    path_style: bool,
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".into()
+}
+
+#[derive(Deserialize)]
+struct TomlEventSinkConfig {
+    url: String,
+    #[serde(default = "default_event_sink_events")]
+    events: Vec<String>,
+    #[serde(default)]
+    agents: Vec<String>,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    secret: Option<String>,
+    #[serde(default = "default_event_sink_max_retries")]
+    max_retries: u32,
+    #[serde(default = "default_event_sink_timeout_secs")]
+    timeout_secs: u64,
+}
+
+fn default_event_sink_events() -> Vec<String> {
+    vec![
+        "worker_complete".into(),
+        "branch_timeout".into(),
+        "budget_exceeded".into(),
+    ]
+}
+fn default_event_sink_max_retries() -> u32 {
+    3
+}
+fn default_event_sink_timeout_secs() -> u64 {
+    10
 }
 
 #[derive(Deserialize)]
@@ -1571,6 +2963,8 @@ struct TomlMetricsConfig {
     port: u16,
     #[serde(default = "default_metrics_bind")]
     bind: String,
+    #[serde(default)]
+    per_channel_turns: bool,
 }
 
 impl Default for TomlMetricsConfig {
@@ -1579,6 +2973,7 @@ impl Default for TomlMetricsConfig {
             enabled: false,
             port: default_metrics_port(),
             bind: default_metrics_bind(),
+            per_channel_turns: false,
         }
     }
 }
@@ -1715,16 +3110,52 @@ struct TomlDefaultsConfig {
     context_window: Option<usize>,
     compaction: Option<TomlCompactionConfig>,
     memory_persistence: Option<TomlMemoryPersistenceConfig>,
+    memory_injection: Option<TomlMemoryInjectionConfig>,
+    knowledge: Option<TomlKnowledgeConfig>,
+    memory_maintenance: Option<TomlMemoryMaintenanceConfig>,
+    tool_policy: Option<TomlToolPolicyConfig>,
+    plan_mode: Option<TomlPlanModeConfig>,
+    container: Option<TomlContainerConfig>,
+    http_request: Option<TomlHttpRequestConfig>,
+    web_search: Option<TomlWebSearchConfig>,
+    calendar: Option<TomlCalendarConfig>,
+    auth: Option<TomlAuthConfig>,
+    redaction: Option<TomlRedactionConfig>,
+    system_prompt: Option<TomlSystemPromptConfig>,
+    gating: Option<TomlGatingConfig>,
     coalesce: Option<TomlCoalesceConfig>,
     ingestion: Option<TomlIngestionConfig>,
     cortex: Option<TomlCortexConfig>,
     warmup: Option<TomlWarmupConfig>,
+    heartbeat: Option<TomlHeartbeatConfig>,
     browser: Option<TomlBrowserConfig>,
     #[serde(default)]
     mcp: Vec<TomlMcpServerConfig>,
+    #[serde(default)]
+    sql: Vec<TomlSqlDatabaseConfig>,
     brave_search_key: Option<String>,
+    github_token: Option<String>,
+    coolify_url: Option<String>,
+    coolify_token: Option<String>,
     cron_timezone: Option<String>,
     opencode: Option<TomlOpenCodeConfig>,
+    channel_budget_usd: Option<f64>,
+    channel_idle_timeout_mins: Option<u64>,
+    branch_result_window_ms: Option<u64>,
+    rank_branch_results: Option<bool>,
+    max_branch_depth: Option<usize>,
+    max_running_workers: Option<usize>,
+    max_queued_workers: Option<usize>,
+    worker_retry: Option<TomlWorkerRetryConfig>,
+    worker_presets: Option<HashMap<String, TomlWorkerPreset>>,
+    worker_workspace_root: Option<PathBuf>,
+    message_dedup_window: Option<usize>,
+    shutdown_drain_secs: Option<u64>,
+    shutdown_notice: Option<String>,
+    skill_env_allowlist: Option<Vec<String>>,
+    tool_output_max_tokens: Option<usize>,
+    tool_call_repairs: Option<usize>,
+    tool_concurrency: Option<usize>,
     worker_log_mode: Option<String>,
 }
 
@@ -1736,7 +3167,11 @@ struct TomlRoutingConfig {
     compactor: Option<String>,
     cortex: Option<String>,
     voice: Option<String>,
+    vision: Option<String>,
+    channel_cheap: Option<String>,
+    cheap_max_chars: Option<usize>,
     rate_limit_cooldown_secs: Option<u64>,
+    retry_base_delay_ms: Option<u64>,
     channel_thinking_effort: Option<String>,
     branch_thinking_effort: Option<String>,
     worker_thinking_effort: Option<String>,
@@ -1753,6 +3188,450 @@ struct TomlMemoryPersistenceConfig {
     message_interval: Option<usize>,
 }
 
+#[derive(Deserialize)]
+struct TomlMemoryInjectionConfig {
+    enabled: Option<bool>,
+    max_results: Option<usize>,
+    min_score: Option<f32>,
+    max_events: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct TomlKnowledgeConfig {
+    auto_retrieve: Option<bool>,
+    max_results: Option<usize>,
+    min_score: Option<f32>,
+    chunk_size: Option<usize>,
+    chunk_overlap: Option<usize>,
+}
+
+impl TomlKnowledgeConfig {
+    fn resolve(self, base: KnowledgeConfig) -> KnowledgeConfig {
+        KnowledgeConfig {
+            auto_retrieve: self.auto_retrieve.unwrap_or(base.auto_retrieve),
+            max_results: self.max_results.unwrap_or(base.max_results),
+            min_score: self.min_score.unwrap_or(base.min_score),
+            chunk_size: self.chunk_size.unwrap_or(base.chunk_size).max(100),
+            chunk_overlap: self.chunk_overlap.unwrap_or(base.chunk_overlap),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TomlMemoryMaintenanceConfig {
+    enabled: Option<bool>,
+    interval_secs: Option<u64>,
+    decay_rate: Option<f32>,
+    stale_after_days: Option<i64>,
+    promote_min_accesses: Option<i64>,
+    promote_boost: Option<f32>,
+    prune_threshold: Option<f32>,
+    prune_min_age_days: Option<i64>,
+    merge_threshold: Option<f32>,
+}
+
+impl TomlMemoryMaintenanceConfig {
+    fn resolve(self, base: MemoryMaintenanceConfig) -> MemoryMaintenanceConfig {
+        MemoryMaintenanceConfig {
+            enabled: self.enabled.unwrap_or(base.enabled),
+            interval_secs: self.interval_secs.unwrap_or(base.interval_secs).max(60),
+            decay_rate: self.decay_rate.unwrap_or(base.decay_rate).clamp(0.0, 1.0),
+            stale_after_days: self.stale_after_days.unwrap_or(base.stale_after_days),
+            promote_min_accesses: self
+                .promote_min_accesses
+                .unwrap_or(base.promote_min_accesses),
+            promote_boost: self
+                .promote_boost
+                .unwrap_or(base.promote_boost)
+                .clamp(0.0, 1.0),
+            prune_threshold: self.prune_threshold.unwrap_or(base.prune_threshold),
+            prune_min_age_days: self.prune_min_age_days.unwrap_or(base.prune_min_age_days),
+            merge_threshold: self
+                .merge_threshold
+                .unwrap_or(base.merge_threshold)
+                .clamp(0.0, 1.0),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TomlRedactionConfig {
+    enabled: Option<bool>,
+    api_keys: Option<bool>,
+    credit_cards: Option<bool>,
+    entropy_threshold: Option<f64>,
+    min_entropy_length: Option<usize>,
+    patterns: Option<Vec<TomlRedactionPattern>>,
+    restore_outbound: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct TomlRedactionPattern {
+    name: String,
+    pattern: String,
+}
+
+impl TomlRedactionConfig {
+    fn resolve(self, base: &RedactionConfig) -> Result<RedactionConfig> {
+        let patterns = match self.patterns {
+            Some(patterns) => patterns
+                .into_iter()
+                .map(parse_redaction_pattern)
+                .collect::<Result<Vec<_>>>()?,
+            None => base.patterns.clone(),
+        };
+
+        Ok(RedactionConfig {
+            enabled: self.enabled.unwrap_or(base.enabled),
+            api_keys: self.api_keys.unwrap_or(base.api_keys),
+            credit_cards: self.credit_cards.unwrap_or(base.credit_cards),
+            entropy_threshold: self
+                .entropy_threshold
+                .unwrap_or(base.entropy_threshold)
+                .max(0.0),
+            min_entropy_length: self
+                .min_entropy_length
+                .unwrap_or(base.min_entropy_length)
+                .max(8),
+            patterns,
+            restore_outbound: self.restore_outbound.unwrap_or(base.restore_outbound),
+        })
+    }
+}
+
+fn parse_redaction_pattern(raw: TomlRedactionPattern) -> Result<RedactionPattern> {
+    if raw.name.is_empty()
+        || !raw
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(ConfigError::Invalid(format!(
+            "redaction pattern name '{}' must be non-empty and use only letters, digits, and underscores",
+            raw.name
+        ))
+        .into());
+    }
+
+    let regex = regex::Regex::new(&raw.pattern).map_err(|error| {
+        ConfigError::Invalid(format!(
+            "redaction pattern '{}' is not a valid regex: {error}",
+            raw.name
+        ))
+    })?;
+
+    Ok(RedactionPattern {
+        name: raw.name,
+        regex,
+    })
+}
+
+#[derive(Deserialize)]
+struct TomlToolPolicyConfig {
+    #[serde(default)]
+    channel: TomlToolRules,
+    #[serde(default)]
+    worker: TomlToolRules,
+    #[serde(default)]
+    channels: HashMap<String, TomlToolRules>,
+    #[serde(default)]
+    requires_approval: Vec<String>,
+}
+
+impl TomlToolPolicyConfig {
+    fn resolve(self) -> ToolPolicyConfig {
+        ToolPolicyConfig {
+            channel: self.channel.into(),
+            worker: self.worker.into(),
+            channels: self
+                .channels
+                .into_iter()
+                .map(|(conversation_id, rules)| (conversation_id, rules.into()))
+                .collect(),
+            requires_approval: self.requires_approval,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TomlPlanModeConfig {
+    enabled: Option<bool>,
+    tools: Option<Vec<String>>,
+}
+
+impl TomlPlanModeConfig {
+    fn resolve(self, base: &PlanModeConfig) -> PlanModeConfig {
+        PlanModeConfig {
+            enabled: self.enabled.unwrap_or(base.enabled),
+            tools: self.tools.unwrap_or_else(|| base.tools.clone()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TomlContainerConfig {
+    enabled: Option<bool>,
+    runtime: Option<String>,
+    default_image: Option<String>,
+    allowed_images: Option<Vec<String>>,
+    memory: Option<String>,
+    cpus: Option<f64>,
+    pids_limit: Option<u32>,
+    allow_network: Option<bool>,
+    max_timeout_secs: Option<u64>,
+}
+
+impl TomlContainerConfig {
+    fn resolve(self, base: &ContainerConfig) -> ContainerConfig {
+        ContainerConfig {
+            enabled: self.enabled.unwrap_or(base.enabled),
+            runtime: self.runtime.or_else(|| base.runtime.clone()),
+            default_image: self
+                .default_image
+                .unwrap_or_else(|| base.default_image.clone()),
+            allowed_images: self
+                .allowed_images
+                .unwrap_or_else(|| base.allowed_images.clone()),
+            memory: self.memory.unwrap_or_else(|| base.memory.clone()),
+            cpus: self.cpus.unwrap_or(base.cpus),
+            pids_limit: self.pids_limit.unwrap_or(base.pids_limit),
+            allow_network: self.allow_network.unwrap_or(base.allow_network),
+            max_timeout_secs: self.max_timeout_secs.unwrap_or(base.max_timeout_secs),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TomlHttpRequestConfig {
+    allowed_domains: Option<Vec<String>>,
+    max_response_bytes: Option<usize>,
+    timeout_secs: Option<u64>,
+}
+
+impl TomlHttpRequestConfig {
+    fn resolve(self, base: &HttpRequestConfig) -> HttpRequestConfig {
+        HttpRequestConfig {
+            allowed_domains: self
+                .allowed_domains
+                .unwrap_or_else(|| base.allowed_domains.clone()),
+            max_response_bytes: self.max_response_bytes.unwrap_or(base.max_response_bytes),
+            timeout_secs: self.timeout_secs.unwrap_or(base.timeout_secs),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TomlWebSearchConfig {
+    provider: Option<WebSearchProvider>,
+    api_key: Option<String>,
+    url: Option<String>,
+}
+
+impl TomlWebSearchConfig {
+    fn resolve(self, base: &WebSearchConfig) -> WebSearchConfig {
+        let provider = self.provider.unwrap_or(base.provider);
+        // Credentials belong to a provider, so switching provider drops them.
+        let same_provider = provider == base.provider;
+        WebSearchConfig {
+            provider,
+            api_key: self
+                .api_key
+                .as_deref()
+                .and_then(resolve_env_value)
+                .or_else(|| base.api_key.clone().filter(|_| same_provider)),
+            url: self
+                .url
+                .as_deref()
+                .and_then(resolve_env_value)
+                .or_else(|| base.url.clone().filter(|_| same_provider)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TomlCalendarConfig {
+    provider: Option<CalendarProvider>,
+    url: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    calendar_id: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    refresh_token: Option<String>,
+    timezone: Option<String>,
+    sync_interval_secs: Option<u64>,
+    lookahead_hours: Option<u64>,
+    read_only: Option<bool>,
+}
+
+impl TomlCalendarConfig {
+    fn resolve(self, base: &CalendarConfig) -> CalendarConfig {
+        let provider = self.provider.unwrap_or(base.provider);
+        // Connection settings belong to a provider, so switching provider
+        // drops the inherited ones.
+        let same_provider = provider == base.provider;
+        let secret = |value: Option<String>, inherited: &Option<String>| {
+            value
+                .as_deref()
+                .and_then(resolve_env_value)
+                .or_else(|| inherited.clone().filter(|_| same_provider))
+        };
+        CalendarConfig {
+            provider,
+            url: secret(self.url, &base.url),
+            username: secret(self.username, &base.username),
+            password: secret(self.password, &base.password),
+            calendar_id: self.calendar_id.unwrap_or_else(|| base.calendar_id.clone()),
+            client_id: secret(self.client_id, &base.client_id),
+            client_secret: secret(self.client_secret, &base.client_secret),
+            refresh_token: secret(self.refresh_token, &base.refresh_token),
+            timezone: self.timezone.or_else(|| base.timezone.clone()),
+            sync_interval_secs: self
+                .sync_interval_secs
+                .unwrap_or(base.sync_interval_secs)
+                .max(60),
+            lookahead_hours: self.lookahead_hours.unwrap_or(base.lookahead_hours),
+            read_only: self.read_only.unwrap_or(base.read_only),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TomlGatingConfig {
+    mode: Option<GatingMode>,
+    wake_words: Option<Vec<String>>,
+    listen: Option<bool>,
+    listen_batch: Option<usize>,
+    listen_interval_secs: Option<u64>,
+}
+
+impl TomlGatingConfig {
+    fn resolve(self, base: &GatingConfig) -> GatingConfig {
+        GatingConfig {
+            mode: self.mode.unwrap_or(base.mode),
+            wake_words: self.wake_words.unwrap_or_else(|| base.wake_words.clone()),
+            listen: self.listen.unwrap_or(base.listen),
+            listen_batch: self.listen_batch.unwrap_or(base.listen_batch).max(1),
+            listen_interval_secs: self
+                .listen_interval_secs
+                .unwrap_or(base.listen_interval_secs),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TomlSystemPromptConfig {
+    sections: Option<Vec<String>>,
+    blocks: Option<HashMap<String, String>>,
+}
+
+impl TomlSystemPromptConfig {
+    fn resolve(self, base: &SystemPromptConfig) -> Result<SystemPromptConfig> {
+        let blocks = self.blocks.unwrap_or_else(|| base.blocks.clone());
+        if let Some(name) = blocks
+            .keys()
+            .find(|name| SystemPromptConfig::BUILTIN_SECTIONS.contains(&name.as_str()))
+        {
+            return Err(ConfigError::Invalid(format!(
+                "system prompt block '{name}' has the name of a built-in section"
+            ))
+            .into());
+        }
+
+        let sections = self.sections.unwrap_or_else(|| base.sections.clone());
+        if let Some(name) = sections.iter().find(|name| {
+            !SystemPromptConfig::BUILTIN_SECTIONS.contains(&name.as_str())
+                && !blocks.contains_key(name.as_str())
+        }) {
+            return Err(ConfigError::Invalid(format!(
+                "system prompt section '{name}' is neither a built-in section nor a block"
+            ))
+            .into());
+        }
+
+        Ok(SystemPromptConfig { sections, blocks })
+    }
+}
+
+#[derive(Deserialize)]
+struct TomlAuthConfig {
+    default_tier: Option<AuthTier>,
+    #[serde(default)]
+    admins: Vec<String>,
+    #[serde(default)]
+    users: Vec<String>,
+    #[serde(default)]
+    guests: Vec<String>,
+    #[serde(default)]
+    roles: HashMap<String, AuthTier>,
+    #[serde(default)]
+    channels: HashMap<String, AuthTier>,
+    #[serde(default)]
+    tools: HashMap<AuthTier, TomlToolRules>,
+}
+
+impl TomlAuthConfig {
+    /// Layer this table over `base`: maps are merged, with entries here
+    /// winning. A sender listed under several tiers gets the highest.
+    fn resolve(self, base: &AuthConfig) -> AuthConfig {
+        let mut senders = base.senders.clone();
+        for (tier, ids) in [
+            (AuthTier::Guest, self.guests),
+            (AuthTier::User, self.users),
+            (AuthTier::Admin, self.admins),
+        ] {
+            for id in ids {
+                // A bare ID would match the same string on every platform,
+                // including adapters where the sender picks their own ID.
+                if !id.contains(':') {
+                    tracing::warn!(
+                        sender = %id,
+                        "ignoring auth sender without a platform prefix (expected e.g. `discord:{id}`)"
+                    );
+                    continue;
+                }
+                senders.insert(id, tier);
+            }
+        }
+        let mut roles = base.roles.clone();
+        roles.extend(self.roles);
+        let mut channels = base.channels.clone();
+        channels.extend(self.channels);
+        let mut tools = base.tools.clone();
+        tools.extend(
+            self.tools
+                .into_iter()
+                .map(|(tier, rules)| (tier, rules.into())),
+        );
+
+        AuthConfig {
+            enabled: true,
+            default_tier: self.default_tier.unwrap_or(base.default_tier),
+            senders,
+            roles,
+            channels,
+            tools,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct TomlToolRules {
+    #[serde(default)]
+    allow: Vec<String>,
+    #[serde(default)]
+    deny: Vec<String>,
+}
+
+impl From<TomlToolRules> for ToolRules {
+    fn from(rules: TomlToolRules) -> Self {
+        Self {
+            allow: rules.allow,
+            deny: rules.deny,
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct TomlCoalesceConfig {
     enabled: Option<bool>,
@@ -1762,6 +3641,57 @@ struct TomlCoalesceConfig {
     multi_user_only: Option<bool>,
 }
 
+#[derive(Deserialize)]
+struct TomlWorkerRetryConfig {
+    max_attempts: Option<usize>,
+    initial_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    retry_on: Option<Vec<FailureKind>>,
+}
+
+impl TomlWorkerRetryConfig {
+    fn resolve(self, base: &RetryPolicy) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.max_attempts.unwrap_or(base.max_attempts).max(1),
+            initial_backoff_ms: self.initial_backoff_ms.unwrap_or(base.initial_backoff_ms),
+            max_backoff_ms: self.max_backoff_ms.unwrap_or(base.max_backoff_ms),
+            retry_on: self.retry_on.unwrap_or_else(|| base.retry_on.clone()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TomlWorkerPreset {
+    description: String,
+    prompt: Option<String>,
+    #[serde(default)]
+    tools: Vec<String>,
+    model: Option<String>,
+    timeout_secs: Option<u64>,
+}
+
+/// Presets declared at this level, added to (and replacing by name) the
+/// ones inherited from `base`.
+fn resolve_worker_presets(
+    toml: Option<HashMap<String, TomlWorkerPreset>>,
+    base: &HashMap<String, WorkerPreset>,
+) -> HashMap<String, WorkerPreset> {
+    let mut presets = base.clone();
+    for (name, preset) in toml.unwrap_or_default() {
+        presets.insert(
+            name,
+            WorkerPreset {
+                description: preset.description,
+                prompt: preset.prompt,
+                tools: preset.tools,
+                model: preset.model,
+                timeout_secs: preset.timeout_secs.filter(|secs| *secs > 0),
+            },
+        );
+    }
+    presets
+}
+
 #[derive(Deserialize)]
 struct TomlIngestionConfig {
     enabled: Option<bool>,
@@ -1799,6 +3729,13 @@ struct TomlWarmupConfig {
     startup_delay_secs: Option<u64>,
 }
 
+#[derive(Deserialize)]
+struct TomlHeartbeatConfig {
+    enabled: Option<bool>,
+    interval_secs: Option<u64>,
+    delivery_target: Option<String>,
+}
+
 #[derive(Deserialize)]
 struct TomlBrowserConfig {
     enabled: Option<bool>,
@@ -1845,6 +3782,15 @@ fn default_mcp_enabled() -> bool {
     true
 }
 
+#[derive(Deserialize, Clone)]
+struct TomlSqlDatabaseConfig {
+    name: String,
+    url: String,
+    read_only: Option<bool>,
+    max_rows: Option<usize>,
+    timeout_secs: Option<u64>,
+}
+
 #[derive(Deserialize)]
 struct TomlAgentConfig {
     id: String,
@@ -1861,17 +3807,54 @@ struct TomlAgentConfig {
     context_window: Option<usize>,
     compaction: Option<TomlCompactionConfig>,
     memory_persistence: Option<TomlMemoryPersistenceConfig>,
+    memory_injection: Option<TomlMemoryInjectionConfig>,
+    knowledge: Option<TomlKnowledgeConfig>,
+    memory_maintenance: Option<TomlMemoryMaintenanceConfig>,
+    tool_policy: Option<TomlToolPolicyConfig>,
+    plan_mode: Option<TomlPlanModeConfig>,
+    container: Option<TomlContainerConfig>,
+    http_request: Option<TomlHttpRequestConfig>,
+    web_search: Option<TomlWebSearchConfig>,
+    calendar: Option<TomlCalendarConfig>,
+    auth: Option<TomlAuthConfig>,
+    redaction: Option<TomlRedactionConfig>,
+    system_prompt: Option<TomlSystemPromptConfig>,
+    gating: Option<TomlGatingConfig>,
     coalesce: Option<TomlCoalesceConfig>,
     ingestion: Option<TomlIngestionConfig>,
     cortex: Option<TomlCortexConfig>,
     warmup: Option<TomlWarmupConfig>,
+    heartbeat: Option<TomlHeartbeatConfig>,
     browser: Option<TomlBrowserConfig>,
     mcp: Option<Vec<TomlMcpServerConfig>>,
+    sql: Option<Vec<TomlSqlDatabaseConfig>>,
     brave_search_key: Option<String>,
+    github_token: Option<String>,
+    coolify_url: Option<String>,
+    coolify_token: Option<String>,
     cron_timezone: Option<String>,
+    channel_budget_usd: Option<f64>,
+    channel_idle_timeout_mins: Option<u64>,
+    branch_result_window_ms: Option<u64>,
+    rank_branch_results: Option<bool>,
+    max_branch_depth: Option<usize>,
+    max_running_workers: Option<usize>,
+    max_queued_workers: Option<usize>,
+    worker_retry: Option<TomlWorkerRetryConfig>,
+    worker_presets: Option<HashMap<String, TomlWorkerPreset>>,
+    worker_workspace_root: Option<PathBuf>,
+    message_dedup_window: Option<usize>,
+    shutdown_drain_secs: Option<u64>,
+    shutdown_notice: Option<String>,
+    skill_env_allowlist: Option<Vec<String>>,
+    tool_output_max_tokens: Option<usize>,
+    tool_call_repairs: Option<usize>,
+    tool_concurrency: Option<usize>,
     sandbox: Option<crate::sandbox::SandboxConfig>,
     #[serde(default)]
     cron: Vec<TomlCronDef>,
+    #[serde(default)]
+    feeds: Vec<TomlFeedConfig>,
 }
 
 #[derive(Deserialize)]
@@ -1879,6 +3862,7 @@ struct TomlCronDef {
     id: String,
     prompt: String,
     interval_secs: Option<u64>,
+    cron_expr: Option<String>,
     delivery_target: String,
     active_start_hour: Option<u8>,
     active_end_hour: Option<u8>,
@@ -1887,6 +3871,19 @@ struct TomlCronDef {
     #[serde(default)]
     run_once: bool,
     timeout_secs: Option<u64>,
+    #[serde(default)]
+    stateful: bool,
+}
+
+#[derive(Deserialize)]
+struct TomlFeedConfig {
+    name: String,
+    url: String,
+    delivery_target: String,
+    interval_secs: Option<u64>,
+    max_items: Option<usize>,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
 }
 
 fn default_enabled() -> bool {
@@ -1900,6 +3897,7 @@ struct TomlMessagingConfig {
     telegram: Option<TomlTelegramConfig>,
     webhook: Option<TomlWebhookConfig>,
     twitch: Option<TomlTwitchConfig>,
+    email: Option<TomlEmailConfig>,
 }
 
 #[derive(Deserialize)]
@@ -1950,6 +3948,29 @@ struct TomlWebhookConfig {
     #[serde(default = "default_webhook_bind")]
     bind: String,
     auth_token: Option<String>,
+    #[serde(default)]
+    sources: Vec<TomlWebhookSourceConfig>,
+}
+
+#[derive(Deserialize)]
+struct TomlWebhookSourceConfig {
+    name: String,
+    #[serde(default = "default_webhook_signature")]
+    signature: String,
+    secret: Option<String>,
+    #[serde(default = "default_webhook_signature_header")]
+    signature_header: String,
+    timestamp_header: Option<String>,
+    conversation: Option<String>,
+    agent_id: Option<String>,
+    template: Option<String>,
+}
+
+fn default_webhook_signature() -> String {
+    "hmac".into()
+}
+fn default_webhook_signature_header() -> String {
+    "X-Signature-256".into()
 }
 
 #[derive(Deserialize)]
@@ -1966,6 +3987,43 @@ struct TomlTwitchConfig {
     trigger_prefix: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct TomlEmailConfig {
+    #[serde(default)]
+    enabled: bool,
+    imap_host: Option<String>,
+    #[serde(default = "default_imap_port")]
+    imap_port: u16,
+    username: Option<String>,
+    password: Option<String>,
+    #[serde(default = "default_email_mailbox")]
+    mailbox: String,
+    smtp_host: Option<String>,
+    #[serde(default = "default_smtp_port")]
+    smtp_port: u16,
+    smtp_username: Option<String>,
+    smtp_password: Option<String>,
+    from_address: Option<String>,
+    from_name: Option<String>,
+    #[serde(default = "default_email_poll_interval_secs")]
+    poll_interval_secs: u64,
+    #[serde(default)]
+    allowed_senders: Vec<String>,
+}
+
+fn default_imap_port() -> u16 {
+    993
+}
+fn default_smtp_port() -> u16 {
+    587
+}
+fn default_email_mailbox() -> String {
+    "INBOX".into()
+}
+fn default_email_poll_interval_secs() -> u64 {
+    30
+}
+
 fn default_webhook_port() -> u16 {
     18789
 }
@@ -2067,6 +4125,246 @@ fn parse_otlp_headers(value: Option<String>) -> Result<HashMap<String, String>>
     Ok(headers)
 }
 
+/// Deserialize config TOML. When a value has the wrong shape the error names
+/// its key path (e.g. `agents[1].routing.channel`) and line.
+fn parse_toml_config(content: &str) -> Result<TomlConfig> {
+    serde_path_to_error::deserialize(toml::Deserializer::new(content))
+        .map_err(|error| {
+            let path = error.path().to_string();
+            let error = error.into_inner();
+            if path == "." {
+                // Syntax errors have no key path; toml's message points at the line.
+                return ConfigError::Invalid(error.to_string());
+            }
+            let line = error.span().map(|span| {
+                content[..span.start.min(content.len())]
+                    .matches('\n')
+                    .count()
+                    + 1
+            });
+            let message = match line {
+                Some(line) => format!("`{path}` (line {line}): {}", error.message()),
+                None => format!("`{path}`: {}", error.message()),
+            };
+            ConfigError::Invalid(message)
+        })
+        .map_err(Into::into)
+}
+
+fn parse_event_sink_config(index: usize, raw: TomlEventSinkConfig) -> Result<EventSinkConfig> {
+    let url = resolve_env_value(&raw.url).unwrap_or_default();
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(ConfigError::Invalid(format!(
+            "`event_sinks[{index}].url` must be an http(s) URL"
+        ))
+        .into());
+    }
+
+    for event in &raw.events {
+        if event != "*" && !crate::event_sink::EVENT_NAMES.contains(&event.as_str()) {
+            return Err(ConfigError::Invalid(format!(
+                "`event_sinks[{index}].events` has unknown event '{event}', expected '*' or one of: {}",
+                crate::event_sink::EVENT_NAMES.join(", ")
+            ))
+            .into());
+        }
+    }
+
+    let headers = raw
+        .headers
+        .into_iter()
+        .filter_map(|(name, value)| resolve_env_value(&value).map(|value| (name, value)))
+        .collect();
+
+    Ok(EventSinkConfig {
+        url,
+        events: raw.events,
+        agents: raw.agents,
+        headers,
+        secret: raw.secret.as_deref().and_then(resolve_env_value),
+        max_retries: raw.max_retries,
+        timeout_secs: raw.timeout_secs.max(1),
+    })
+}
+
+fn parse_media_config(raw: TomlMediaConfig, api: &ApiConfig) -> Result<MediaConfig> {
+    let defaults = MediaConfig::default();
+    let backend = match raw.backend.as_deref().unwrap_or("local") {
+        "local" => MediaBackendConfig::Local {
+            path: raw
+                .path
+                .as_deref()
+                .and_then(resolve_env_value)
+                .map(PathBuf::from),
+        },
+        "s3" => {
+            let Some(s3) = raw.s3 else {
+                return Err(ConfigError::Invalid(
+                    "`media.backend = \"s3\"` needs a `[media.s3]` table".into(),
+                )
+                .into());
+            };
+            let access_key_id = resolve_env_value(&s3.access_key_id).unwrap_or_default();
+            let secret_access_key = resolve_env_value(&s3.secret_access_key).unwrap_or_default();
+            if access_key_id.is_empty() || secret_access_key.is_empty() {
+                return Err(ConfigError::Invalid(
+                    "`media.s3.access_key_id` and `media.s3.secret_access_key` must be set".into(),
+                )
+                .into());
+            }
+            MediaBackendConfig::S3(S3MediaConfig {
+                bucket: s3.bucket,
+                region: s3.region,
+                endpoint: s3
+                    .endpoint
+                    .as_deref()
+                    .and_then(resolve_env_value)
+                    .map(|endpoint| endpoint.trim_end_matches('/').to_string()),
+                access_key_id,
+                secret_access_key,
+                prefix: s3.prefix,
+                path_style: s3.path_style,
+            })
+        }
+        other => {
+            return Err(ConfigError::Invalid(format!(
+                "`media.backend` must be \"local\" or \"s3\", got \"{other}\""
+            ))
+            .into());
+        }
+    };
+
+    let public_url = raw
+        .public_url
+        .as_deref()
+        .and_then(resolve_env_value)
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| {
+            let host = if api.bind == "0.0.0.0" || api.bind == "::" {
+                "127.0.0.1"
+            } else {
+                api.bind.as_str()
+            };
+            format!("http://{host}:{}", api.port)
+        });
+
+    Ok(MediaConfig {
+        backend,
+        public_url,
+        signing_key: raw.signing_key.as_deref().and_then(resolve_env_value),
+        url_ttl_secs: raw.url_ttl_secs.unwrap_or(defaults.url_ttl_secs).max(1),
+        max_file_bytes: raw.max_file_bytes.unwrap_or(defaults.max_file_bytes),
+    })
+}
+
+fn parse_storage_config(raw: TomlStorageConfig) -> Result<StorageConfig> {
+    let backend = match raw.backend.as_deref().unwrap_or("local") {
+        "local" => StorageBackendConfig::Local,
+        "sqlite" => StorageBackendConfig::Sqlite,
+        "postgres" => {
+            if !cfg!(feature = "postgres") {
+                return Err(ConfigError::Invalid(
+                    "`storage.backend = \"postgres\"` needs spacebot built with the `postgres` feature"
+                        .into(),
+                )
+                .into());
+            }
+            let Some(postgres) = raw.postgres else {
+                return Err(ConfigError::Invalid(
+                    "`storage.backend = \"postgres\"` needs a `[storage.postgres]` table".into(),
+                )
+                .into());
+            };
+            let url = resolve_env_value(&postgres.url).unwrap_or_default();
+            if url.is_empty() {
+                return Err(
+                    ConfigError::Invalid("`storage.postgres.url` must be set".into()).into(),
+                );
+            }
+            StorageBackendConfig::Postgres(PostgresStorageConfig {
+                url,
+                max_connections: postgres.max_connections.max(1),
+            })
+        }
+        "qdrant" => {
+            let Some(qdrant) = raw.qdrant else {
+                return Err(ConfigError::Invalid(
+                    "`storage.backend = \"qdrant\"` needs a `[storage.qdrant]` table".into(),
+                )
+                .into());
+            };
+            let url = resolve_env_value(&qdrant.url).unwrap_or_default();
+            if url.is_empty() {
+                return Err(ConfigError::Invalid("`storage.qdrant.url` must be set".into()).into());
+            }
+            StorageBackendConfig::Qdrant(QdrantStorageConfig {
+                url: url.trim_end_matches('/').to_string(),
+                api_key: qdrant
+                    .api_key
+                    .as_deref()
+                    .and_then(resolve_env_value)
+                    .filter(|key| !key.is_empty()),
+                collection_prefix: qdrant.collection_prefix,
+            })
+        }
+        other => {
+            return Err(ConfigError::Invalid(format!(
+                "`storage.backend` must be \"local\", \"sqlite\", \"postgres\", or \"qdrant\", got \"{other}\""
+            ))
+            .into());
+        }
+    };
+
+    Ok(StorageConfig { backend })
+}
+
+fn parse_webhook_source_config(raw: TomlWebhookSourceConfig) -> Result<WebhookSourceConfig> {
+    let name = raw.name.trim().to_string();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(ConfigError::Invalid(format!(
+            "webhook source name '{}' must be non-empty and contain only letters, digits, '-' and '_'",
+            raw.name
+        ))
+        .into());
+    }
+
+    let signature = match raw.signature.as_str() {
+        "github" => WebhookSignature::Github,
+        "stripe" => WebhookSignature::Stripe,
+        "hmac" => WebhookSignature::Hmac,
+        other => {
+            return Err(ConfigError::Invalid(format!(
+                "webhook source '{name}' has invalid signature '{other}', expected 'github', 'stripe' or 'hmac'"
+            ))
+            .into());
+        }
+    };
+
+    let secret = raw
+        .secret
+        .as_deref()
+        .and_then(resolve_env_value)
+        .filter(|secret| !secret.is_empty())
+        .ok_or_else(|| {
+            ConfigError::Invalid(format!("webhook source '{name}' requires a 'secret'"))
+        })?;
+
+    Ok(WebhookSourceConfig {
+        name,
+        signature,
+        secret,
+        signature_header: raw.signature_header,
+        timestamp_header: raw.timestamp_header,
+        conversation: raw.conversation,
+        agent_id: raw.agent_id,
+        template: raw.template,
+    })
+}
+
 fn parse_mcp_server_config(raw: TomlMcpServerConfig) -> Result<McpServerConfig> {
     if raw.name.trim().is_empty() {
         return Err(ConfigError::Invalid("mcp server name cannot be empty".into()).into());
@@ -2114,6 +4412,70 @@ fn parse_mcp_server_config(raw: TomlMcpServerConfig) -> Result<McpServerConfig>
     })
 }
 
+fn parse_sql_database_config(raw: TomlSqlDatabaseConfig) -> Result<SqlDatabaseConfig> {
+    if raw.name.trim().is_empty() {
+        return Err(ConfigError::Invalid("sql database name cannot be empty".into()).into());
+    }
+    let url = resolve_env_value(&raw.url).ok_or_else(|| {
+        ConfigError::Invalid(format!(
+            "sql database '{}' has url '{}', but that environment variable isn't set",
+            raw.name, raw.url
+        ))
+    })?;
+    let scheme = url.split(':').next().unwrap_or_default();
+    if !["sqlite", "postgres", "postgresql", "mysql", "mariadb"].contains(&scheme) {
+        return Err(ConfigError::Invalid(format!(
+            "sql database '{}' has unsupported url scheme '{scheme}', expected sqlite, postgres, or mysql",
+            raw.name
+        ))
+        .into());
+    }
+
+    Ok(SqlDatabaseConfig {
+        name: raw.name,
+        url,
+        read_only: raw.read_only.unwrap_or(true),
+        max_rows: raw.max_rows.unwrap_or(200),
+        timeout_secs: raw.timeout_secs.unwrap_or(30),
+    })
+}
+
+fn parse_feed_configs(raw: Vec<TomlFeedConfig>) -> Result<Vec<FeedConfig>> {
+    let mut feeds: Vec<FeedConfig> = Vec::with_capacity(raw.len());
+    for feed in raw {
+        let name = feed.name.trim().to_string();
+        if name.is_empty() {
+            return Err(ConfigError::Invalid("feed name cannot be empty".into()).into());
+        }
+        if feeds.iter().any(|existing| existing.name == name) {
+            return Err(ConfigError::Invalid(format!("duplicate feed name '{name}'")).into());
+        }
+        if !(feed.url.starts_with("https://") || feed.url.starts_with("http://")) {
+            return Err(ConfigError::Invalid(format!(
+                "feed '{name}' has url '{}', expected an http or https URL",
+                feed.url
+            ))
+            .into());
+        }
+        if crate::messaging::target::parse_delivery_target(&feed.delivery_target).is_none() {
+            return Err(ConfigError::Invalid(format!(
+                "feed '{name}' has delivery_target '{}', expected adapter:target",
+                feed.delivery_target
+            ))
+            .into());
+        }
+        feeds.push(FeedConfig {
+            name,
+            url: feed.url,
+            delivery_target: feed.delivery_target,
+            interval_secs: feed.interval_secs.unwrap_or(3600).max(300),
+            max_items: feed.max_items.unwrap_or(10).max(1),
+            enabled: feed.enabled,
+        });
+    }
+    Ok(feeds)
+}
+
 /// Resolve a TomlRoutingConfig against a base RoutingConfig.
 fn resolve_routing(toml: Option<TomlRoutingConfig>, base: &RoutingConfig) -> RoutingConfig {
     let Some(t) = toml else { return base.clone() };
@@ -2133,11 +4495,17 @@ fn resolve_routing(toml: Option<TomlRoutingConfig>, base: &RoutingConfig) -> Rou
         compactor: t.compactor.unwrap_or_else(|| base.compactor.clone()),
         cortex: t.cortex.unwrap_or_else(|| base.cortex.clone()),
         voice: t.voice.unwrap_or_else(|| base.voice.clone()),
+        vision: t.vision.unwrap_or_else(|| base.vision.clone()),
+        channel_cheap: t
+            .channel_cheap
+            .unwrap_or_else(|| base.channel_cheap.clone()),
+        cheap_max_chars: t.cheap_max_chars.unwrap_or(base.cheap_max_chars),
         task_overrides,
         fallbacks,
         rate_limit_cooldown_secs: t
             .rate_limit_cooldown_secs
             .unwrap_or(base.rate_limit_cooldown_secs),
+        retry_base_delay_ms: t.retry_base_delay_ms.unwrap_or(base.retry_base_delay_ms),
         channel_thinking_effort: t
             .channel_thinking_effort
             .unwrap_or_else(|| base.channel_thinking_effort.clone()),
@@ -2178,6 +4546,25 @@ fn resolve_mcp_configs(
     merged
 }
 
+/// Merge agent databases into the defaults; an agent database replaces the
+/// default with the same name.
+fn resolve_sql_configs(
+    default_configs: &[SqlDatabaseConfig],
+    agent_configs: Option<&[SqlDatabaseConfig]>,
+) -> Vec<SqlDatabaseConfig> {
+    let mut merged = default_configs.to_vec();
+    for agent_config in agent_configs.unwrap_or_default() {
+        match merged
+            .iter_mut()
+            .find(|existing| existing.name == agent_config.name)
+        {
+            Some(existing) => *existing = agent_config.clone(),
+            None => merged.push(agent_config.clone()),
+        }
+    }
+    merged
+}
+
 impl Config {
     /// Resolve the instance directory from env or default (~/.spacebot).
     pub fn default_instance_dir() -> PathBuf {
@@ -2268,7 +4655,7 @@ impl Config {
         let content = std::fs::read_to_string(path)
             .with_context(|| format!("failed to read config from {}", path.display()))?;
 
-        let toml_config: TomlConfig = toml::from_str(&content)
+        let toml_config = parse_toml_config(&content)
             .with_context(|| format!("failed to parse config from {}", path.display()))?;
 
         Self::from_toml(toml_config, instance_dir)
@@ -2561,6 +4948,9 @@ impl Config {
         if let Ok(voice_model) = std::env::var("SPACEBOT_VOICE_MODEL") {
             routing.voice = voice_model;
         }
+        if let Ok(vision_model) = std::env::var("SPACEBOT_VISION_MODEL") {
+            routing.vision = vision_model;
+        }
 
         let agents = vec![AgentConfig {
             id: "main".into(),
@@ -2576,20 +4966,60 @@ impl Config {
             context_window: None,
             compaction: None,
             memory_persistence: None,
+            memory_injection: None,
+            knowledge: None,
+            memory_maintenance: None,
+            tool_policy: None,
+            plan_mode: None,
+            container: None,
+            http_request: None,
+            web_search: None,
+            calendar: None,
+            auth: None,
+            redaction: None,
+            system_prompt: None,
+            gating: None,
             coalesce: None,
             ingestion: None,
             cortex: None,
             warmup: None,
+            heartbeat: None,
             browser: None,
             mcp: None,
+            sql: None,
             brave_search_key: None,
+            github_token: None,
+            coolify_url: None,
+            coolify_token: None,
             cron_timezone: None,
+            channel_budget_usd: None,
+            channel_idle_timeout_mins: None,
+            branch_result_window_ms: None,
+            rank_branch_results: None,
+            max_branch_depth: None,
+            max_running_workers: None,
+            max_queued_workers: None,
+            worker_retry: None,
+            worker_presets: None,
+            worker_workspace_root: None,
+            message_dedup_window: None,
+            shutdown_drain_secs: None,
+            shutdown_notice: None,
+            skill_env_allowlist: None,
+            tool_output_max_tokens: None,
+            tool_call_repairs: None,
+            tool_concurrency: None,
             sandbox: None,
             cron: Vec::new(),
+            feeds: Vec::new(),
         }];
 
         let mut api = ApiConfig::default();
         api.bind = hosted_api_bind(api.bind);
+        let media = MediaConfig {
+            public_url: format!("http://127.0.0.1:{}", api.port),
+            ..MediaConfig::default()
+        };
 
         Ok(Self {
             instance_dir: instance_dir.to_path_buf(),
@@ -2615,14 +5045,16 @@ impl Config {
                     .unwrap_or_else(|_| "spacebot".into()),
                 sample_rate: 1.0,
             },
+            event_sinks: Vec::new(),
+            media,
+            storage: StorageConfig::default(),
         })
     }
 
     /// Validate a raw TOML string as a valid Spacebot config.
     /// Returns Ok(()) if the config is structurally valid, or an error describing what's wrong.
     pub fn validate_toml(content: &str) -> Result<()> {
-        let toml_config: TomlConfig =
-            toml::from_str(content).context("failed to parse config TOML")?;
+        let toml_config = parse_toml_config(content)?;
         // Run full conversion to catch semantic errors (env resolution, defaults, etc.)
         let instance_dir = Self::default_instance_dir();
         Self::from_toml(toml_config, instance_dir)?;
@@ -3041,6 +5473,12 @@ impl Config {
             .into_iter()
             .map(parse_mcp_server_config)
             .collect::<Result<Vec<_>>>()?;
+        let default_sql = toml
+            .defaults
+            .sql
+            .into_iter()
+            .map(parse_sql_database_config)
+            .collect::<Result<Vec<_>>>()?;
 
         let base_defaults = DefaultsConfig::default();
         let defaults = DefaultsConfig {
@@ -3089,6 +5527,84 @@ impl Config {
                         .unwrap_or(base_defaults.memory_persistence.message_interval),
                 })
                 .unwrap_or(base_defaults.memory_persistence),
+            memory_injection: toml
+                .defaults
+                .memory_injection
+                .map(|mi| MemoryInjectionConfig {
+                    enabled: mi.enabled.unwrap_or(base_defaults.memory_injection.enabled),
+                    max_results: mi
+                        .max_results
+                        .unwrap_or(base_defaults.memory_injection.max_results),
+                    min_score: mi
+                        .min_score
+                        .unwrap_or(base_defaults.memory_injection.min_score),
+                    max_events: mi
+                        .max_events
+                        .unwrap_or(base_defaults.memory_injection.max_events),
+                })
+                .unwrap_or(base_defaults.memory_injection),
+            knowledge: toml
+                .defaults
+                .knowledge
+                .map(|k| k.resolve(base_defaults.knowledge))
+                .unwrap_or(base_defaults.knowledge),
+            memory_maintenance: toml
+                .defaults
+                .memory_maintenance
+                .map(|m| m.resolve(base_defaults.memory_maintenance))
+                .unwrap_or(base_defaults.memory_maintenance),
+            tool_policy: toml
+                .defaults
+                .tool_policy
+                .map(TomlToolPolicyConfig::resolve)
+                .unwrap_or_else(|| base_defaults.tool_policy.clone()),
+            plan_mode: toml
+                .defaults
+                .plan_mode
+                .map(|p| p.resolve(&base_defaults.plan_mode))
+                .unwrap_or_else(|| base_defaults.plan_mode.clone()),
+            container: toml
+                .defaults
+                .container
+                .map(|c| c.resolve(&base_defaults.container))
+                .unwrap_or_else(|| base_defaults.container.clone()),
+            http_request: toml
+                .defaults
+                .http_request
+                .map(|h| h.resolve(&base_defaults.http_request))
+                .unwrap_or_else(|| base_defaults.http_request.clone()),
+            web_search: toml
+                .defaults
+                .web_search
+                .map(|w| w.resolve(&base_defaults.web_search))
+                .unwrap_or_else(|| base_defaults.web_search.clone()),
+            calendar: toml
+                .defaults
+                .calendar
+                .map(|c| c.resolve(&base_defaults.calendar))
+                .unwrap_or_else(|| base_defaults.calendar.clone()),
+            auth: toml
+                .defaults
+                .auth
+                .map(|a| a.resolve(&base_defaults.auth))
+                .unwrap_or_else(|| base_defaults.auth.clone()),
+            redaction: toml
+                .defaults
+                .redaction
+                .map(|r| r.resolve(&base_defaults.redaction))
+                .transpose()?
+                .unwrap_or_else(|| base_defaults.redaction.clone()),
+            system_prompt: toml
+                .defaults
+                .system_prompt
+                .map(|p| p.resolve(&base_defaults.system_prompt))
+                .transpose()?
+                .unwrap_or_else(|| base_defaults.system_prompt.clone()),
+            gating: toml
+                .defaults
+                .gating
+                .map(|g| g.resolve(&base_defaults.gating))
+                .unwrap_or_else(|| base_defaults.gating.clone()),
             coalesce: toml
                 .defaults
                 .coalesce
@@ -3168,6 +5684,19 @@ impl Config {
                         .unwrap_or(base_defaults.warmup.startup_delay_secs),
                 })
                 .unwrap_or(base_defaults.warmup),
+            heartbeat: toml
+                .defaults
+                .heartbeat
+                .map(|h| HeartbeatConfig {
+                    enabled: h.enabled.unwrap_or(base_defaults.heartbeat.enabled),
+                    interval_secs: h
+                        .interval_secs
+                        .unwrap_or(base_defaults.heartbeat.interval_secs),
+                    delivery_target: h
+                        .delivery_target
+                        .or_else(|| base_defaults.heartbeat.delivery_target.clone()),
+                })
+                .unwrap_or_else(|| base_defaults.heartbeat.clone()),
             browser: toml
                 .defaults
                 .browser
@@ -3186,17 +5715,109 @@ impl Config {
                 })
                 .unwrap_or_else(|| base_defaults.browser.clone()),
             mcp: default_mcp,
+            sql: default_sql,
             brave_search_key: toml
                 .defaults
                 .brave_search_key
                 .as_deref()
                 .and_then(resolve_env_value)
                 .or_else(|| std::env::var("BRAVE_SEARCH_API_KEY").ok()),
+            github_token: toml
+                .defaults
+                .github_token
+                .as_deref()
+                .and_then(resolve_env_value)
+                .or_else(|| std::env::var("GITHUB_TOKEN").ok()),
+            coolify_url: toml
+                .defaults
+                .coolify_url
+                .as_deref()
+                .and_then(resolve_env_value)
+                .or_else(|| std::env::var("COOLIFY_URL").ok()),
+            coolify_token: toml
+                .defaults
+                .coolify_token
+                .as_deref()
+                .and_then(resolve_env_value)
+                .or_else(|| std::env::var("COOLIFY_TOKEN").ok()),
             cron_timezone: toml
                 .defaults
                 .cron_timezone
                 .as_deref()
                 .and_then(resolve_env_value),
+            channel_budget_usd: toml
+                .defaults
+                .channel_budget_usd
+                .or(base_defaults.channel_budget_usd),
+            channel_idle_timeout_mins: toml
+                .defaults
+                .channel_idle_timeout_mins
+                .unwrap_or(base_defaults.channel_idle_timeout_mins),
+            branch_result_window_ms: toml
+                .defaults
+                .branch_result_window_ms
+                .unwrap_or(base_defaults.branch_result_window_ms),
+            rank_branch_results: toml
+                .defaults
+                .rank_branch_results
+                .unwrap_or(base_defaults.rank_branch_results),
+            max_branch_depth: toml
+                .defaults
+                .max_branch_depth
+                .unwrap_or(base_defaults.max_branch_depth)
+                .max(1),
+            max_running_workers: toml
+                .defaults
+                .max_running_workers
+                .unwrap_or(base_defaults.max_running_workers),
+            max_queued_workers: toml
+                .defaults
+                .max_queued_workers
+                .unwrap_or(base_defaults.max_queued_workers),
+            worker_retry: toml
+                .defaults
+                .worker_retry
+                .map(|r| r.resolve(&base_defaults.worker_retry))
+                .unwrap_or_else(|| base_defaults.worker_retry.clone()),
+            worker_presets: resolve_worker_presets(
+                toml.defaults.worker_presets,
+                &base_defaults.worker_presets,
+            ),
+            worker_workspace_root: toml
+                .defaults
+                .worker_workspace_root
+                .clone()
+                .or_else(|| base_defaults.worker_workspace_root.clone()),
+            message_dedup_window: toml
+                .defaults
+                .message_dedup_window
+                .unwrap_or(base_defaults.message_dedup_window),
+            shutdown_drain_secs: toml
+                .defaults
+                .shutdown_drain_secs
+                .unwrap_or(base_defaults.shutdown_drain_secs),
+            shutdown_notice: toml
+                .defaults
+                .shutdown_notice
+                .clone()
+                .or_else(|| base_defaults.shutdown_notice.clone()),
+            skill_env_allowlist: toml
+                .defaults
+                .skill_env_allowlist
+                .clone()
+                .unwrap_or_else(|| base_defaults.skill_env_allowlist.clone()),
+            tool_output_max_tokens: toml
+                .defaults
+                .tool_output_max_tokens
+                .unwrap_or(base_defaults.tool_output_max_tokens),
+            tool_call_repairs: toml
+                .defaults
+                .tool_call_repairs
+                .unwrap_or(base_defaults.tool_call_repairs),
+            tool_concurrency: toml
+                .defaults
+                .tool_concurrency
+                .unwrap_or(base_defaults.tool_concurrency),
             history_backfill_count: base_defaults.history_backfill_count,
             cron: Vec::new(),
             opencode: toml
@@ -3254,6 +5875,7 @@ impl Config {
                         id: h.id,
                         prompt: h.prompt,
                         interval_secs: h.interval_secs.unwrap_or(3600),
+                        cron_expr: h.cron_expr,
                         delivery_target: h.delivery_target,
                         active_hours: match (h.active_start_hour, h.active_end_hour) {
                             (Some(s), Some(e)) => Some((s, e)),
@@ -3262,8 +5884,10 @@ impl Config {
                         enabled: h.enabled,
                         run_once: h.run_once,
                         timeout_secs: h.timeout_secs,
+                        stateful: h.stateful,
                     })
                     .collect();
+                let feeds = parse_feed_configs(a.feeds)?;
 
                 Ok(AgentConfig {
                     id: a.id,
@@ -3294,6 +5918,36 @@ impl Config {
                             .message_interval
                             .unwrap_or(defaults.memory_persistence.message_interval),
                     }),
+                    memory_injection: a.memory_injection.map(|mi| MemoryInjectionConfig {
+                        enabled: mi.enabled.unwrap_or(defaults.memory_injection.enabled),
+                        max_results: mi
+                            .max_results
+                            .unwrap_or(defaults.memory_injection.max_results),
+                        min_score: mi.min_score.unwrap_or(defaults.memory_injection.min_score),
+                        max_events: mi
+                            .max_events
+                            .unwrap_or(defaults.memory_injection.max_events),
+                    }),
+                    knowledge: a.knowledge.map(|k| k.resolve(defaults.knowledge)),
+                    memory_maintenance: a
+                        .memory_maintenance
+                        .map(|m| m.resolve(defaults.memory_maintenance)),
+                    tool_policy: a.tool_policy.map(TomlToolPolicyConfig::resolve),
+                    plan_mode: a.plan_mode.map(|p| p.resolve(&defaults.plan_mode)),
+                    container: a.container.map(|c| c.resolve(&defaults.container)),
+                    http_request: a.http_request.map(|h| h.resolve(&defaults.http_request)),
+                    web_search: a.web_search.map(|w| w.resolve(&defaults.web_search)),
+                    calendar: a.calendar.map(|c| c.resolve(&defaults.calendar)),
+                    auth: a.auth.map(|auth| auth.resolve(&defaults.auth)),
+                    redaction: a
+                        .redaction
+                        .map(|r| r.resolve(&defaults.redaction))
+                        .transpose()?,
+                    system_prompt: a
+                        .system_prompt
+                        .map(|p| p.resolve(&defaults.system_prompt))
+                        .transpose()?,
+                    gating: a.gating.map(|g| g.resolve(&defaults.gating)),
                     coalesce: a.coalesce.map(|c| CoalesceConfig {
                         enabled: c.enabled.unwrap_or(defaults.coalesce.enabled),
                         debounce_ms: c.debounce_ms.unwrap_or(defaults.coalesce.debounce_ms),
@@ -3355,6 +6009,13 @@ impl Config {
                             .startup_delay_secs
                             .unwrap_or(defaults.warmup.startup_delay_secs),
                     }),
+                    heartbeat: a.heartbeat.map(|h| HeartbeatConfig {
+                        enabled: h.enabled.unwrap_or(defaults.heartbeat.enabled),
+                        interval_secs: h.interval_secs.unwrap_or(defaults.heartbeat.interval_secs),
+                        delivery_target: h
+                            .delivery_target
+                            .or_else(|| defaults.heartbeat.delivery_target.clone()),
+                    }),
                     browser: a.browser.map(|b| BrowserConfig {
                         enabled: b.enabled.unwrap_or(defaults.browser.enabled),
                         headless: b.headless.unwrap_or(defaults.browser.headless),
@@ -3378,10 +6039,42 @@ impl Config {
                         ),
                         None => None,
                     },
+                    sql: a
+                        .sql
+                        .map(|databases| {
+                            databases
+                                .into_iter()
+                                .map(parse_sql_database_config)
+                                .collect::<Result<Vec<_>>>()
+                        })
+                        .transpose()?,
                     brave_search_key: a.brave_search_key.as_deref().and_then(resolve_env_value),
+                    github_token: a.github_token.as_deref().and_then(resolve_env_value),
+                    coolify_url: a.coolify_url.as_deref().and_then(resolve_env_value),
+                    coolify_token: a.coolify_token.as_deref().and_then(resolve_env_value),
                     cron_timezone: a.cron_timezone.as_deref().and_then(resolve_env_value),
+                    channel_budget_usd: a.channel_budget_usd,
+                    channel_idle_timeout_mins: a.channel_idle_timeout_mins,
+                    branch_result_window_ms: a.branch_result_window_ms,
+                    rank_branch_results: a.rank_branch_results,
+                    max_branch_depth: a.max_branch_depth,
+                    max_running_workers: a.max_running_workers,
+                    max_queued_workers: a.max_queued_workers,
+                    worker_retry: a.worker_retry.map(|r| r.resolve(&defaults.worker_retry)),
+                    worker_presets: a.worker_presets.map(|presets| {
+                        resolve_worker_presets(Some(presets), &defaults.worker_presets)
+                    }),
+                    worker_workspace_root: a.worker_workspace_root,
+                    message_dedup_window: a.message_dedup_window,
+                    shutdown_drain_secs: a.shutdown_drain_secs,
+                    shutdown_notice: a.shutdown_notice,
+                    skill_env_allowlist: a.skill_env_allowlist,
+                    tool_output_max_tokens: a.tool_output_max_tokens,
+                    tool_call_repairs: a.tool_call_repairs,
+                    tool_concurrency: a.tool_concurrency,
                     sandbox: a.sandbox,
                     cron,
+                    feeds,
                 })
             })
             .collect::<Result<Vec<_>>>()?;
@@ -3401,16 +6094,52 @@ impl Config {
                 context_window: None,
                 compaction: None,
                 memory_persistence: None,
+                memory_injection: None,
+                knowledge: None,
+                memory_maintenance: None,
+                tool_policy: None,
+                plan_mode: None,
+                container: None,
+                http_request: None,
+                web_search: None,
+                calendar: None,
+                auth: None,
+                redaction: None,
+                system_prompt: None,
+                gating: None,
                 coalesce: None,
                 ingestion: None,
                 cortex: None,
                 warmup: None,
+                heartbeat: None,
                 browser: None,
                 mcp: None,
+                sql: None,
                 brave_search_key: None,
+                github_token: None,
+                coolify_url: None,
+                coolify_token: None,
                 cron_timezone: None,
+                channel_budget_usd: None,
+                channel_idle_timeout_mins: None,
+                branch_result_window_ms: None,
+                rank_branch_results: None,
+                max_branch_depth: None,
+                max_running_workers: None,
+                max_queued_workers: None,
+                worker_retry: None,
+                worker_presets: None,
+                worker_workspace_root: None,
+                message_dedup_window: None,
+                shutdown_drain_secs: None,
+                shutdown_notice: None,
+                skill_env_allowlist: None,
+                tool_output_max_tokens: None,
+                tool_call_repairs: None,
+                tool_concurrency: None,
                 sandbox: None,
                 cron: Vec::new(),
+                feeds: Vec::new(),
             });
         }
 
@@ -3420,6 +6149,21 @@ impl Config {
             first.default = true;
         }
 
+        let webhook = match toml.messaging.webhook {
+            Some(w) => Some(WebhookConfig {
+                enabled: w.enabled,
+                port: w.port,
+                bind: w.bind,
+                auth_token: w.auth_token.as_deref().and_then(resolve_env_value),
+                sources: w
+                    .sources
+                    .into_iter()
+                    .map(parse_webhook_source_config)
+                    .collect::<Result<Vec<_>>>()?,
+            }),
+            None => None,
+        };
+
         let messaging = MessagingConfig {
             discord: toml.messaging.discord.and_then(|d| {
                 let token = d
@@ -3473,12 +6217,7 @@ impl Config {
                     dm_allowed_users: t.dm_allowed_users,
                 })
             }),
-            webhook: toml.messaging.webhook.map(|w| WebhookConfig {
-                enabled: w.enabled,
-                port: w.port,
-                bind: w.bind,
-                auth_token: w.auth_token.as_deref().and_then(resolve_env_value),
-            }),
+            webhook,
             twitch: toml.messaging.twitch.and_then(|t| {
                 let username = t
                     .username
@@ -3516,6 +6255,39 @@ impl Config {
                     trigger_prefix: t.trigger_prefix,
                 })
             }),
+            email: toml.messaging.email.and_then(|e| {
+                let resolve = |value: Option<String>, env_var: &str| {
+                    value
+                        .as_deref()
+                        .and_then(resolve_env_value)
+                        .or_else(|| std::env::var(env_var).ok())
+                };
+                let imap_host = resolve(e.imap_host, "EMAIL_IMAP_HOST")?;
+                let username = resolve(e.username, "EMAIL_USERNAME")?;
+                let password = resolve(e.password, "EMAIL_PASSWORD")?;
+                let smtp_host = resolve(e.smtp_host, "EMAIL_SMTP_HOST")?;
+                let smtp_username =
+                    resolve(e.smtp_username, "EMAIL_SMTP_USERNAME").unwrap_or(username.clone());
+                let smtp_password =
+                    resolve(e.smtp_password, "EMAIL_SMTP_PASSWORD").unwrap_or(password.clone());
+                let from_address = e.from_address.unwrap_or(username.clone());
+                Some(EmailConfig {
+                    enabled: e.enabled,
+                    imap_host,
+                    imap_port: e.imap_port,
+                    imap_username: username,
+                    imap_password: password,
+                    mailbox: e.mailbox,
+                    smtp_host,
+                    smtp_port: e.smtp_port,
+                    smtp_username,
+                    smtp_password,
+                    from_address,
+                    from_name: e.from_name,
+                    poll_interval_secs: e.poll_interval_secs,
+                    allowed_senders: e.allowed_senders,
+                })
+            }),
         };
 
         let bindings = toml
@@ -3540,10 +6312,21 @@ impl Config {
             auth_token: toml.api.auth_token.as_deref().and_then(resolve_env_value),
         };
 
+        let event_sinks = toml
+            .event_sinks
+            .into_iter()
+            .enumerate()
+            .map(|(index, sink)| parse_event_sink_config(index, sink))
+            .collect::<Result<Vec<_>>>()?;
+
+        let media = parse_media_config(toml.media, &api)?;
+        let storage = parse_storage_config(toml.storage)?;
+
         let metrics = MetricsConfig {
             enabled: toml.metrics.enabled,
             port: toml.metrics.port,
             bind: toml.metrics.bind,
+            per_channel_turns: toml.metrics.per_channel_turns,
         };
 
         let telemetry = {
@@ -3619,20 +6402,84 @@ impl Config {
             });
         }
 
-        Ok(Config {
-            instance_dir,
-            llm,
-            defaults,
-            agents,
-            links,
-            groups,
-            humans,
-            messaging,
-            bindings,
-            api,
-            metrics,
-            telemetry,
-        })
+        let config = Config {
+            instance_dir,
+            llm,
+            defaults,
+            agents,
+            links,
+            groups,
+            humans,
+            messaging,
+            bindings,
+            api,
+            metrics,
+            telemetry,
+            event_sinks,
+            media,
+            storage,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check references between sections, naming the offending key.
+    ///
+    /// Broken agent IDs are errors. References to agents that don't exist are
+    /// only logged: deleting an agent through the API leaves its bindings and
+    /// links in config.toml, and that shouldn't stop the instance from starting.
+    fn validate(&self) -> Result<()> {
+        let mut agent_ids = std::collections::HashSet::new();
+        for (index, agent) in self.agents.iter().enumerate() {
+            let id = agent.id.as_str();
+            if id.trim().is_empty() {
+                return Err(
+                    ConfigError::Invalid(format!("`agents[{index}].id` cannot be empty")).into(),
+                );
+            }
+            if id.contains(['/', '\\']) || id == "." || id == ".." {
+                return Err(ConfigError::Invalid(format!(
+                    "`agents[{index}].id` '{id}' is not a valid directory name"
+                ))
+                .into());
+            }
+            if !agent_ids.insert(id) {
+                return Err(ConfigError::Invalid(format!(
+                    "`agents[{index}].id` '{id}' is already used by another agent"
+                ))
+                .into());
+            }
+        }
+
+        let default_count = self.agents.iter().filter(|agent| agent.default).count();
+        if default_count > 1 {
+            tracing::warn!(
+                count = default_count,
+                default_agent = self.default_agent_id(),
+                "more than one agent has `default = true`, using the first"
+            );
+        }
+
+        let mut references = Vec::new();
+        for (index, binding) in self.bindings.iter().enumerate() {
+            references.push((format!("bindings[{index}].agent_id"), &binding.agent_id));
+        }
+        for (index, link) in self.links.iter().enumerate() {
+            references.push((format!("links[{index}].from"), &link.from));
+            references.push((format!("links[{index}].to"), &link.to));
+        }
+        for (index, group) in self.groups.iter().enumerate() {
+            for (member, agent_id) in group.agent_ids.iter().enumerate() {
+                references.push((format!("groups[{index}].agent_ids[{member}]"), agent_id));
+            }
+        }
+        for (key, agent_id) in references {
+            if !agent_ids.contains(agent_id.as_str()) {
+                tracing::warn!(%key, %agent_id, "config references an unknown agent");
+            }
+        }
+
+        Ok(())
     }
 
     /// Get the default agent ID.
@@ -3668,9 +6515,28 @@ pub struct RuntimeConfig {
     pub instance_dir: PathBuf,
     /// Agent workspace directory (e.g., ~/.spacebot/agents/{id}/workspace). Immutable after startup.
     pub workspace_dir: PathBuf,
+    /// Root for per-worker working directories. Immutable after startup.
+    pub worker_workspaces_dir: PathBuf,
+    /// Root of the agent's file store. Immutable after startup.
+    pub files_dir: PathBuf,
     pub routing: ArcSwap<RoutingConfig>,
     pub compaction: ArcSwap<CompactionConfig>,
     pub memory_persistence: ArcSwap<MemoryPersistenceConfig>,
+    pub memory_injection: ArcSwap<MemoryInjectionConfig>,
+    pub knowledge: ArcSwap<KnowledgeConfig>,
+    pub memory_maintenance: ArcSwap<MemoryMaintenanceConfig>,
+    pub tool_policy: ArcSwap<ToolPolicyConfig>,
+    pub plan_mode: ArcSwap<PlanModeConfig>,
+    pub container: ArcSwap<ContainerConfig>,
+    pub http_request: ArcSwap<HttpRequestConfig>,
+    /// Backend for `web_search`, with `brave_search_key` already folded in.
+    pub web_search: ArcSwap<WebSearchConfig>,
+    pub calendar: ArcSwap<CalendarConfig>,
+    pub feeds: ArcSwap<Vec<FeedConfig>>,
+    pub auth: ArcSwap<AuthConfig>,
+    pub redaction: ArcSwap<RedactionConfig>,
+    pub system_prompt: ArcSwap<SystemPromptConfig>,
+    pub gating: ArcSwap<GatingConfig>,
     pub coalesce: ArcSwap<CoalesceConfig>,
     pub ingestion: ArcSwap<IngestionConfig>,
     pub max_turns: ArcSwap<usize>,
@@ -3680,18 +6546,44 @@ pub struct RuntimeConfig {
     pub max_concurrent_workers: ArcSwap<usize>,
     pub browser_config: ArcSwap<BrowserConfig>,
     pub mcp: ArcSwap<Vec<McpServerConfig>>,
+    pub sql: ArcSwap<Vec<SqlDatabaseConfig>>,
     pub history_backfill_count: ArcSwap<usize>,
     pub brave_search_key: ArcSwap<Option<String>>,
+    pub github_token: ArcSwap<Option<String>>,
+    pub coolify_url: ArcSwap<Option<String>>,
+    pub coolify_token: ArcSwap<Option<String>>,
     pub cron_timezone: ArcSwap<Option<String>>,
+    pub channel_budget_usd: ArcSwap<Option<f64>>,
+    pub channel_idle_timeout_mins: ArcSwap<u64>,
+    pub branch_result_window_ms: ArcSwap<u64>,
+    pub rank_branch_results: ArcSwap<bool>,
+    pub max_branch_depth: ArcSwap<usize>,
+    pub max_running_workers: ArcSwap<usize>,
+    pub max_queued_workers: ArcSwap<usize>,
+    pub worker_retry: ArcSwap<RetryPolicy>,
+    pub worker_presets: ArcSwap<HashMap<String, WorkerPreset>>,
+    pub message_dedup_window: ArcSwap<usize>,
+    pub shutdown_drain_secs: ArcSwap<u64>,
+    pub shutdown_notice: ArcSwap<Option<String>>,
+    pub skill_env_allowlist: ArcSwap<Vec<String>>,
+    pub tool_output_max_tokens: ArcSwap<usize>,
+    pub tool_call_repairs: ArcSwap<usize>,
+    pub tool_concurrency: ArcSwap<usize>,
     pub cortex: ArcSwap<CortexConfig>,
     pub warmup: ArcSwap<WarmupConfig>,
+    pub heartbeat: ArcSwap<HeartbeatConfig>,
     /// Current warmup lifecycle status for API and observability.
     pub warmup_status: ArcSwap<WarmupStatus>,
     /// Synchronizes warmup passes so periodic and API-triggered runs don't overlap.
     pub warmup_lock: Arc<tokio::sync::Mutex<()>>,
+    /// Originals behind redaction placeholders, for restoring them in replies.
+    pub redaction_vault: crate::redaction::RedactionVault,
     /// Cached memory bulletin generated by the cortex. Injected into every
     /// channel's system prompt. Empty string until the first cortex run.
     pub memory_bulletin: ArcSwap<String>,
+    /// Upcoming calendar events from the last calendar sync, soonest first.
+    /// Empty until the first sync, or when no calendar is configured.
+    pub upcoming_events: ArcSwap<Vec<crate::calendar::CalendarEvent>>,
     pub prompts: ArcSwap<crate::prompts::PromptEngine>,
     pub identity: ArcSwap<crate::identity::Identity>,
     pub skills: ArcSwap<crate::skills::SkillSet>,
@@ -3728,9 +6620,25 @@ impl RuntimeConfig {
         Self {
             instance_dir: instance_dir.to_path_buf(),
             workspace_dir: agent_config.workspace.clone(),
+            worker_workspaces_dir: agent_config.worker_workspaces_dir(),
+            files_dir: agent_config.files_dir(),
             routing: ArcSwap::from_pointee(agent_config.routing.clone()),
             compaction: ArcSwap::from_pointee(agent_config.compaction),
             memory_persistence: ArcSwap::from_pointee(agent_config.memory_persistence),
+            memory_injection: ArcSwap::from_pointee(agent_config.memory_injection),
+            knowledge: ArcSwap::from_pointee(agent_config.knowledge),
+            memory_maintenance: ArcSwap::from_pointee(agent_config.memory_maintenance),
+            tool_policy: ArcSwap::from_pointee(agent_config.tool_policy.clone()),
+            plan_mode: ArcSwap::from_pointee(agent_config.plan_mode.clone()),
+            container: ArcSwap::from_pointee(agent_config.container.clone()),
+            http_request: ArcSwap::from_pointee(agent_config.http_request.clone()),
+            web_search: ArcSwap::from_pointee(agent_config.web_search.clone()),
+            calendar: ArcSwap::from_pointee(agent_config.calendar.clone()),
+            feeds: ArcSwap::from_pointee(agent_config.feeds.clone()),
+            auth: ArcSwap::from_pointee(agent_config.auth.clone()),
+            redaction: ArcSwap::from_pointee(agent_config.redaction.clone()),
+            system_prompt: ArcSwap::from_pointee(agent_config.system_prompt.clone()),
+            gating: ArcSwap::from_pointee(agent_config.gating.clone()),
             coalesce: ArcSwap::from_pointee(agent_config.coalesce),
             ingestion: ArcSwap::from_pointee(agent_config.ingestion),
             max_turns: ArcSwap::from_pointee(agent_config.max_turns),
@@ -3740,14 +6648,39 @@ impl RuntimeConfig {
             max_concurrent_workers: ArcSwap::from_pointee(agent_config.max_concurrent_workers),
             browser_config: ArcSwap::from_pointee(agent_config.browser.clone()),
             mcp: ArcSwap::from_pointee(agent_config.mcp.clone()),
+            sql: ArcSwap::from_pointee(agent_config.sql.clone()),
             history_backfill_count: ArcSwap::from_pointee(agent_config.history_backfill_count),
             brave_search_key: ArcSwap::from_pointee(agent_config.brave_search_key.clone()),
+            github_token: ArcSwap::from_pointee(agent_config.github_token.clone()),
+            coolify_url: ArcSwap::from_pointee(agent_config.coolify_url.clone()),
+            coolify_token: ArcSwap::from_pointee(agent_config.coolify_token.clone()),
             cron_timezone: ArcSwap::from_pointee(agent_config.cron_timezone.clone()),
+            channel_budget_usd: ArcSwap::from_pointee(agent_config.channel_budget_usd),
+            channel_idle_timeout_mins: ArcSwap::from_pointee(
+                agent_config.channel_idle_timeout_mins,
+            ),
+            branch_result_window_ms: ArcSwap::from_pointee(agent_config.branch_result_window_ms),
+            rank_branch_results: ArcSwap::from_pointee(agent_config.rank_branch_results),
+            max_branch_depth: ArcSwap::from_pointee(agent_config.max_branch_depth),
+            max_running_workers: ArcSwap::from_pointee(agent_config.max_running_workers),
+            max_queued_workers: ArcSwap::from_pointee(agent_config.max_queued_workers),
+            worker_retry: ArcSwap::from_pointee(agent_config.worker_retry.clone()),
+            worker_presets: ArcSwap::from_pointee(agent_config.worker_presets.clone()),
+            message_dedup_window: ArcSwap::from_pointee(agent_config.message_dedup_window),
+            shutdown_drain_secs: ArcSwap::from_pointee(agent_config.shutdown_drain_secs),
+            shutdown_notice: ArcSwap::from_pointee(agent_config.shutdown_notice.clone()),
+            skill_env_allowlist: ArcSwap::from_pointee(agent_config.skill_env_allowlist.clone()),
+            tool_output_max_tokens: ArcSwap::from_pointee(agent_config.tool_output_max_tokens),
+            tool_call_repairs: ArcSwap::from_pointee(agent_config.tool_call_repairs),
+            tool_concurrency: ArcSwap::from_pointee(agent_config.tool_concurrency),
             cortex: ArcSwap::from_pointee(agent_config.cortex),
             warmup: ArcSwap::from_pointee(agent_config.warmup),
+            heartbeat: ArcSwap::from_pointee(agent_config.heartbeat.clone()),
             warmup_status: ArcSwap::from_pointee(WarmupStatus::default()),
             warmup_lock: Arc::new(tokio::sync::Mutex::new(())),
+            redaction_vault: crate::redaction::RedactionVault::default(),
             memory_bulletin: ArcSwap::from_pointee(String::new()),
+            upcoming_events: ArcSwap::from_pointee(Vec::new()),
             prompts: ArcSwap::from_pointee(prompts),
             identity: ArcSwap::from_pointee(identity),
             skills: ArcSwap::from_pointee(skills),
@@ -3813,6 +6746,22 @@ impl RuntimeConfig {
         self.compaction.store(Arc::new(resolved.compaction));
         self.memory_persistence
             .store(Arc::new(resolved.memory_persistence));
+        self.memory_injection
+            .store(Arc::new(resolved.memory_injection));
+        self.knowledge.store(Arc::new(resolved.knowledge));
+        self.memory_maintenance
+            .store(Arc::new(resolved.memory_maintenance));
+        self.tool_policy.store(Arc::new(resolved.tool_policy));
+        self.plan_mode.store(Arc::new(resolved.plan_mode));
+        self.container.store(Arc::new(resolved.container));
+        self.http_request.store(Arc::new(resolved.http_request));
+        self.web_search.store(Arc::new(resolved.web_search));
+        self.calendar.store(Arc::new(resolved.calendar));
+        self.feeds.store(Arc::new(resolved.feeds));
+        self.auth.store(Arc::new(resolved.auth));
+        self.redaction.store(Arc::new(resolved.redaction));
+        self.system_prompt.store(Arc::new(resolved.system_prompt));
+        self.gating.store(Arc::new(resolved.gating));
         self.coalesce.store(Arc::new(resolved.coalesce));
         self.ingestion.store(Arc::new(resolved.ingestion));
         self.max_turns.store(Arc::new(resolved.max_turns));
@@ -3825,13 +6774,48 @@ impl RuntimeConfig {
             .store(Arc::new(resolved.max_concurrent_workers));
         self.browser_config.store(Arc::new(resolved.browser));
         self.mcp.store(Arc::new(new_mcp.clone()));
+        self.sql.store(Arc::new(resolved.sql));
         self.history_backfill_count
             .store(Arc::new(resolved.history_backfill_count));
         self.brave_search_key
             .store(Arc::new(resolved.brave_search_key));
+        self.github_token.store(Arc::new(resolved.github_token));
+        self.coolify_url.store(Arc::new(resolved.coolify_url));
+        self.coolify_token.store(Arc::new(resolved.coolify_token));
         self.cron_timezone.store(Arc::new(resolved.cron_timezone));
+        self.channel_budget_usd
+            .store(Arc::new(resolved.channel_budget_usd));
+        self.channel_idle_timeout_mins
+            .store(Arc::new(resolved.channel_idle_timeout_mins));
+        self.branch_result_window_ms
+            .store(Arc::new(resolved.branch_result_window_ms));
+        self.rank_branch_results
+            .store(Arc::new(resolved.rank_branch_results));
+        self.max_branch_depth
+            .store(Arc::new(resolved.max_branch_depth));
+        self.max_running_workers
+            .store(Arc::new(resolved.max_running_workers));
+        self.max_queued_workers
+            .store(Arc::new(resolved.max_queued_workers));
+        self.worker_retry.store(Arc::new(resolved.worker_retry));
+        self.worker_presets.store(Arc::new(resolved.worker_presets));
+        self.message_dedup_window
+            .store(Arc::new(resolved.message_dedup_window));
+        self.shutdown_drain_secs
+            .store(Arc::new(resolved.shutdown_drain_secs));
+        self.shutdown_notice
+            .store(Arc::new(resolved.shutdown_notice));
+        self.skill_env_allowlist
+            .store(Arc::new(resolved.skill_env_allowlist));
+        self.tool_output_max_tokens
+            .store(Arc::new(resolved.tool_output_max_tokens));
+        self.tool_call_repairs
+            .store(Arc::new(resolved.tool_call_repairs));
+        self.tool_concurrency
+            .store(Arc::new(resolved.tool_concurrency));
         self.cortex.store(Arc::new(resolved.cortex));
         self.warmup.store(Arc::new(resolved.warmup));
+        self.heartbeat.store(Arc::new(resolved.heartbeat));
         // sandbox config is not hot-reloaded here because the Sandbox instance
         // is constructed once at startup and shared via Arc. Changing sandbox
         // settings requires an agent restart.
@@ -3852,6 +6836,13 @@ impl RuntimeConfig {
         self.skills.store(Arc::new(skills));
         tracing::info!("skills reloaded");
     }
+
+    /// Swap in a prompt engine with freshly loaded overrides. Channels render
+    /// with it from their next turn.
+    pub fn reload_prompts(&self, prompts: crate::prompts::PromptEngine) {
+        self.prompts.store(Arc::new(prompts));
+        tracing::info!("prompts reloaded");
+    }
 }
 
 impl std::fmt::Debug for RuntimeConfig {
@@ -3931,6 +6922,14 @@ pub fn spawn_file_watcher(
             tracing::warn!(%error, path = %instance_skills_dir.display(), "failed to watch instance skills dir");
         }
 
+        // Watch instance-level prompt overrides
+        let prompts_dir = instance_dir.join("prompts");
+        if prompts_dir.is_dir()
+            && let Err(error) = watcher.watch(&prompts_dir, RecursiveMode::Recursive)
+        {
+            tracing::warn!(%error, path = %prompts_dir.display(), "failed to watch prompts dir");
+        }
+
         // Watch per-agent workspace directories (skills, identity)
         for (_, workspace, _, _) in &agents {
             {
@@ -3978,9 +6977,10 @@ pub fn spawn_file_watcher(
             let skills_changed = changed_paths
                 .iter()
                 .any(|p| p.to_string_lossy().contains("skills"));
+            let prompts_changed = changed_paths.iter().any(|p| p.starts_with(&prompts_dir));
 
             // Skip entirely if nothing relevant changed
-            if !config_changed && !identity_changed && !skills_changed {
+            if !config_changed && !identity_changed && !skills_changed && !prompts_changed {
                 continue;
             }
 
@@ -3997,7 +6997,7 @@ pub fn spawn_file_watcher(
                 if current_hash == last_config_hash {
                     config_changed = false;
                     // If config was the only thing that "changed", skip entirely
-                    if !identity_changed && !skills_changed {
+                    if !identity_changed && !skills_changed && !prompts_changed {
                         continue;
                     }
                 } else {
@@ -4009,6 +7009,7 @@ pub fn spawn_file_watcher(
                 config_changed.then_some("config"),
                 identity_changed.then_some("identity"),
                 skills_changed.then_some("skills"),
+                prompts_changed.then_some("prompts"),
             ]
             .into_iter()
             .flatten()
@@ -4186,6 +7187,21 @@ pub fn spawn_file_watcher(
                                     tracing::error!(%error, "failed to hot-start twitch adapter from config change");
                                 }
                             }
+
+                        // Email: start if enabled and not already running
+                        if let Some(email_config) = &config.messaging.email
+                            && email_config.enabled && !manager.has_adapter("email").await {
+                                match crate::messaging::email::EmailAdapter::new(email_config.clone()) {
+                                    Ok(adapter) => {
+                                        if let Err(error) = manager.register_and_start(adapter).await {
+                                            tracing::error!(%error, "failed to hot-start email adapter from config change");
+                                        }
+                                    }
+                                    Err(error) => {
+                                        tracing::error!(%error, "failed to build email adapter from config change");
+                                    }
+                                }
+                            }
                     });
                 }
             }
@@ -4211,6 +7227,14 @@ pub fn spawn_file_watcher(
                     ));
                     runtime_config.reload_skills(skills);
                 }
+
+                if prompts_changed {
+                    let prompts = runtime_config
+                        .prompts
+                        .load()
+                        .with_overrides_from(&prompts_dir);
+                    runtime_config.reload_prompts(prompts);
+                }
             }
         }
 
@@ -4730,6 +7754,341 @@ api_key = "static-provider-key"
         assert_eq!(second_provider.api_key, "static-provider-key");
     }
 
+    #[test]
+    fn test_tool_policy_parses_and_narrows_tools() {
+        let _lock = env_test_lock().lock().unwrap_or_else(|e| e.into_inner());
+        let _env = EnvGuard::new();
+
+        let toml = r#"
+[llm]
+anthropic_key = "test-key"
+
+[defaults.tool_policy]
+requires_approval = ["shell"]
+
+[defaults.tool_policy.channel]
+deny = ["react"]
+
+[defaults.tool_policy.worker]
+allow = ["shell", "file", "set_status"]
+deny = ["shell"]
+
+[defaults.tool_policy.channels."discord:42"]
+deny = ["spawn_worker"]
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+        let policy = &config.defaults.tool_policy;
+
+        assert!(policy.permits(ProcessType::Channel, None, "reply"));
+        assert!(!policy.permits(ProcessType::Channel, None, "react"));
+        assert!(policy.permits(ProcessType::Channel, Some("discord:7"), "spawn_worker"));
+        assert!(!policy.permits(ProcessType::Channel, Some("discord:42"), "spawn_worker"));
+
+        assert!(policy.permits(ProcessType::Worker, None, "file"));
+        assert!(!policy.permits(ProcessType::Worker, None, "shell"));
+        assert!(!policy.permits(ProcessType::Worker, None, "browser"));
+
+        assert!(policy.permits(ProcessType::Branch, None, "memory_recall"));
+        assert_eq!(policy.requires_approval, ["shell"]);
+    }
+
+    #[test]
+    fn test_auth_parses_and_merges_agent_tables() {
+        let _lock = env_test_lock().lock().unwrap_or_else(|e| e.into_inner());
+        let _env = EnvGuard::new();
+
+        let toml = r#"
+[llm]
+anthropic_key = "test-key"
+
+[defaults.auth]
+default_tier = "guest"
+admins = ["discord:1"]
+users = ["slack:2", "3"]
+
+[defaults.auth.roles]
+"900" = "user"
+
+[defaults.auth.tools.user]
+deny = ["send_file"]
+
+[[agents]]
+id = "main"
+
+[agents.auth]
+admins = ["slack:2"]
+
+[agents.auth.channels]
+"discord:42" = "admin"
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+
+        assert!(!AuthConfig::default().enabled);
+        let defaults = &config.defaults.auth;
+        assert!(defaults.enabled);
+        assert_eq!(defaults.default_tier, AuthTier::Guest);
+        assert_eq!(defaults.senders.get("slack:2"), Some(&AuthTier::User));
+        assert!(!defaults.senders.contains_key("3"));
+        assert_eq!(defaults.roles.get("900"), Some(&AuthTier::User));
+        assert!(!defaults.permits_tool(AuthTier::User, "spawn_worker"));
+        assert!(defaults.permits_tool(AuthTier::User, "branch"));
+        assert!(!defaults.permits_tool(AuthTier::User, "send_file"));
+
+        let agent = config.agents[0].resolve(&config.instance_dir, &config.defaults);
+        assert_eq!(agent.auth.default_tier, AuthTier::Guest);
+        assert_eq!(agent.auth.senders.get("discord:1"), Some(&AuthTier::Admin));
+        assert_eq!(agent.auth.senders.get("slack:2"), Some(&AuthTier::Admin));
+        assert_eq!(
+            agent.auth.channels.get("discord:42"),
+            Some(&AuthTier::Admin)
+        );
+    }
+
+    #[test]
+    fn test_system_prompt_sections_and_blocks() {
+        let _lock = env_test_lock().lock().unwrap_or_else(|e| e.into_inner());
+        let _env = EnvGuard::new();
+
+        let toml = r#"
+[llm]
+anthropic_key = "test-key"
+
+[defaults.system_prompt]
+sections = ["house_rules", "identity", "instructions", "conversation_context"]
+
+[defaults.system_prompt.blocks]
+house_rules = "Never promise delivery dates."
+
+[[agents]]
+id = "main"
+
+[agents.system_prompt]
+sections = ["instructions", "house_rules"]
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+
+        let defaults = &config.defaults.system_prompt;
+        assert_eq!(defaults.sections[0], "house_rules");
+        assert!(!defaults.sections.iter().any(|section| section == "status"));
+
+        let agent = config.agents[0].resolve(&config.instance_dir, &config.defaults);
+        assert_eq!(
+            agent.system_prompt.sections,
+            ["instructions", "house_rules"]
+        );
+        assert_eq!(
+            agent
+                .system_prompt
+                .blocks
+                .get("house_rules")
+                .map(String::as_str),
+            Some("Never promise delivery dates.")
+        );
+
+        let unknown = r#"
+[llm]
+anthropic_key = "test-key"
+
+[defaults.system_prompt]
+sections = ["identity", "missing"]
+"#;
+        let parsed: TomlConfig = toml::from_str(unknown).expect("failed to parse test TOML");
+        assert!(Config::from_toml(parsed, PathBuf::from(".")).is_err());
+    }
+
+    #[test]
+    fn test_worker_presets_merge_by_name() {
+        let _lock = env_test_lock().lock().unwrap_or_else(|e| e.into_inner());
+        let _env = EnvGuard::new();
+
+        let toml = r#"
+[llm]
+anthropic_key = "test-key"
+
+[defaults.worker_presets.research]
+description = "Web research"
+tools = ["web_search", "web_fetch"]
+timeout_secs = 600
+
+[defaults.worker_presets.summarize]
+description = "Summarize a repo"
+timeout_secs = 0
+
+[[agents]]
+id = "main"
+
+[agents.worker_presets.research]
+description = "Research with sources"
+model = "anthropic/claude-sonnet-4-20250514"
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+
+        let defaults = &config.defaults.worker_presets;
+        assert_eq!(defaults["research"].tools, ["web_search", "web_fetch"]);
+        assert_eq!(defaults["research"].timeout_secs, Some(600));
+        assert_eq!(defaults["summarize"].timeout_secs, None);
+
+        let agent = config.agents[0].resolve(&config.instance_dir, &config.defaults);
+        assert_eq!(agent.worker_presets.len(), 2);
+        let research = &agent.worker_presets["research"];
+        assert_eq!(research.description, "Research with sources");
+        assert!(research.tools.is_empty());
+        assert_eq!(
+            research.model.as_deref(),
+            Some("anthropic/claude-sonnet-4-20250514")
+        );
+    }
+
+    #[test]
+    fn test_sql_databases_merge_by_name() {
+        let _lock = env_test_lock().lock().unwrap_or_else(|e| e.into_inner());
+        let _env = EnvGuard::new();
+
+        let toml = r#"
+[llm]
+anthropic_key = "test-key"
+
+[[defaults.sql]]
+name = "analytics"
+url = "postgres://reader@db/analytics"
+
+[[defaults.sql]]
+name = "app"
+url = "sqlite:/srv/app.db"
+
+[[agents]]
+id = "main"
+
+[[agents.sql]]
+name = "app"
+url = "sqlite:/srv/app.db"
+read_only = false
+max_rows = 50
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+
+        let analytics = &config.defaults.sql[0];
+        assert!(analytics.read_only);
+        assert_eq!(analytics.max_rows, 200);
+        assert!(!format!("{analytics:?}").contains("reader@db"));
+
+        let agent = config.agents[0].resolve(&config.instance_dir, &config.defaults);
+        assert_eq!(agent.sql.len(), 2);
+        assert_eq!(agent.sql[0].name, "analytics");
+        assert!(!agent.sql[1].read_only);
+        assert_eq!(agent.sql[1].max_rows, 50);
+
+        let bad_scheme = r#"
+[llm]
+anthropic_key = "test-key"
+
+[[defaults.sql]]
+name = "legacy"
+url = "mssql://db"
+"#;
+        let parsed: TomlConfig = toml::from_str(bad_scheme).expect("failed to parse test TOML");
+        assert!(Config::from_toml(parsed, PathBuf::from(".")).is_err());
+    }
+
+    #[test]
+    fn test_feeds_are_parsed_and_validated() {
+        let _lock = env_test_lock().lock().unwrap_or_else(|e| e.into_inner());
+        let _env = EnvGuard::new();
+
+        let toml = r#"
+[llm]
+anthropic_key = "test-key"
+
+[[agents]]
+id = "main"
+
+[[agents.feeds]]
+name = "rust-blog"
+url = "https://blog.rust-lang.org/feed.xml"
+delivery_target = "discord:123456789"
+interval_secs = 60
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+        let agent = config.agents[0].resolve(&config.instance_dir, &config.defaults);
+        assert_eq!(agent.feeds.len(), 1);
+        assert_eq!(agent.feeds[0].interval_secs, 300);
+        assert_eq!(agent.feeds[0].max_items, 10);
+        assert!(agent.feeds[0].enabled);
+
+        let bad_target = r#"
+[llm]
+anthropic_key = "test-key"
+
+[[agents]]
+id = "main"
+
+[[agents.feeds]]
+name = "rust-blog"
+url = "https://blog.rust-lang.org/feed.xml"
+delivery_target = "general"
+"#;
+        let parsed: TomlConfig = toml::from_str(bad_target).expect("failed to parse test TOML");
+        assert!(Config::from_toml(parsed, PathBuf::from(".")).is_err());
+    }
+
+    #[test]
+    fn test_web_search_provider_falls_back_to_brave_key() {
+        let _lock = env_test_lock().lock().unwrap_or_else(|e| e.into_inner());
+        let _env = EnvGuard::new();
+
+        let toml = r#"
+[llm]
+anthropic_key = "test-key"
+
+[defaults]
+brave_search_key = "brave-key"
+
+[[agents]]
+id = "main"
+
+[[agents]]
+id = "research"
+
+[agents.web_search]
+provider = "searxng"
+url = "https://search.example.com"
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+
+        let main = config.agents[0].resolve(&config.instance_dir, &config.defaults);
+        assert_eq!(main.web_search.provider, WebSearchProvider::Brave);
+        assert_eq!(main.web_search.api_key.as_deref(), Some("brave-key"));
+        assert!(main.web_search.is_configured());
+        assert!(!format!("{:?}", main.web_search).contains("brave-key"));
+
+        let research = config.agents[1].resolve(&config.instance_dir, &config.defaults);
+        assert_eq!(research.web_search.provider, WebSearchProvider::Searxng);
+        assert_eq!(research.web_search.api_key, None);
+        assert!(research.web_search.is_configured());
+
+        let kagi = TomlWebSearchConfig {
+            provider: Some(WebSearchProvider::Kagi),
+            api_key: None,
+            url: None,
+        }
+        .resolve(&main.web_search);
+        assert!(!kagi.is_configured());
+    }
+
     #[test]
     fn test_legacy_llm_keys_auto_migrate_to_providers() {
         let toml = r#"
@@ -5231,6 +8590,129 @@ startup_delay_secs = 2
         assert_eq!(resolved.warmup.startup_delay_secs, 2);
     }
 
+    #[test]
+    fn test_heartbeat_default_and_agent_override_resolution() {
+        let toml = r#"
+[defaults.heartbeat]
+interval_secs = 3600
+delivery_target = "discord:123"
+
+[[agents]]
+id = "main"
+
+[agents.heartbeat]
+enabled = true
+"#;
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+        let resolved = config.agents[0].resolve(&config.instance_dir, &config.defaults);
+
+        assert!(!config.defaults.heartbeat.enabled);
+        assert!(resolved.heartbeat.enabled);
+        assert_eq!(resolved.heartbeat.interval_secs, 3600);
+        assert_eq!(
+            resolved.heartbeat.delivery_target.as_deref(),
+            Some("discord:123")
+        );
+    }
+
+    #[test]
+    fn test_parse_error_names_offending_key() {
+        let toml = r#"
+[[agents]]
+id = "main"
+
+[[agents]]
+id = "research"
+max_turns = "ten"
+"#;
+        let error = parse_toml_config(toml)
+            .err()
+            .expect("string max_turns should fail to parse")
+            .to_string();
+        assert!(error.contains("`agents[1].max_turns` (line 7)"), "{error}");
+    }
+
+    #[test]
+    fn test_duplicate_agent_id_is_rejected() {
+        let toml = r#"
+[[agents]]
+id = "main"
+
+[[agents]]
+id = "main"
+"#;
+        let parsed = parse_toml_config(toml).expect("failed to parse test TOML");
+        let error = Config::from_toml(parsed, PathBuf::from("."))
+            .expect_err("duplicate agent ids should be rejected")
+            .to_string();
+        assert!(error.contains("`agents[1].id` 'main'"), "{error}");
+    }
+
+    #[test]
+    fn test_storage_backend_parsing() {
+        let parsed = parse_toml_config("[[agents]]\nid = \"main\"\n").unwrap();
+        let config = Config::from_toml(parsed, PathBuf::from(".")).unwrap();
+        assert!(matches!(
+            config.storage.backend,
+            StorageBackendConfig::Local
+        ));
+
+        let toml = r#"
+[storage]
+backend = "sqlite"
+
+[[agents]]
+id = "main"
+"#;
+        let parsed = parse_toml_config(toml).unwrap();
+        let config = Config::from_toml(parsed, PathBuf::from(".")).unwrap();
+        assert!(matches!(
+            config.storage.backend,
+            StorageBackendConfig::Sqlite
+        ));
+
+        let toml = r#"
+[storage]
+backend = "postgres"
+
+[[agents]]
+id = "main"
+"#;
+        let parsed = parse_toml_config(toml).unwrap();
+        let error = Config::from_toml(parsed, PathBuf::from("."))
+            .expect_err("postgres without a [storage.postgres] table should be rejected")
+            .to_string();
+        let expected = if cfg!(feature = "postgres") {
+            "[storage.postgres]"
+        } else {
+            "`postgres` feature"
+        };
+        assert!(error.contains(expected), "{error}");
+
+        let toml = r#"
+[storage]
+backend = "qdrant"
+
+[storage.qdrant]
+url = "http://qdrant:6333/"
+
+[[agents]]
+id = "main"
+"#;
+        let parsed = parse_toml_config(toml).unwrap();
+        let config = Config::from_toml(parsed, PathBuf::from(".")).unwrap();
+        let StorageBackendConfig::Qdrant(qdrant) = &config.storage.backend else {
+            panic!(
+                "expected the qdrant backend, got {:?}",
+                config.storage.backend
+            );
+        };
+        assert_eq!(qdrant.url, "http://qdrant:6333");
+        assert_eq!(qdrant.api_key, None);
+        assert_eq!(qdrant.collection_prefix, "spacebot_");
+    }
+
     #[test]
     fn test_work_readiness_requires_warm_state() {
         let readiness = evaluate_work_readiness(
@@ -2,11 +2,22 @@
 
 pub mod channels;
 pub mod context;
+pub mod drafts;
+pub mod event_journal;
+pub mod export;
 pub mod history;
+pub mod tool_artifacts;
+pub mod tool_audit;
 pub mod worker_transcript;
 
 pub use channels::ChannelStore;
+pub use drafts::{DraftDecision, DraftStore, ReplyDraft, Supervision};
+pub use event_journal::EventJournal;
+pub use export::{ChannelExport, ImportSummary};
 pub use history::{
-    ConversationLogger, ProcessRunLogger, TimelineItem, WorkerDetailRow, WorkerRunRow,
+    ConversationLogger, ProcessRunLogger, SqliteHistoryStore, TimelineItem, WorkerDetailRow,
+    WorkerRunRow,
 };
+pub use tool_artifacts::{ToolArtifact, ToolArtifactStore};
+pub use tool_audit::{ToolAuditEntry, ToolAuditFilter, ToolAuditLog, ToolAuditRow};
 pub use worker_transcript::{ActionContent, TranscriptStep};
@@ -19,6 +19,8 @@ pub struct ChannelInfo {
     pub id: String,
     pub platform: String,
     pub display_name: Option<String>,
+    /// Title generated from the conversation, if one has been set.
+    pub title: Option<String>,
     pub platform_meta: Option<serde_json::Value>,
     pub is_active: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
@@ -64,6 +66,37 @@ impl ChannelStore {
         });
     }
 
+    /// Record a channel forked from `source_id`. The source is kept in
+    /// `platform_meta.forked_from`. Fire-and-forget.
+    pub fn record_fork(&self, fork_id: &str, source_id: &str, display_name: Option<&str>) {
+        let pool = self.pool.clone();
+        let fork_id = fork_id.to_string();
+        let platform = extract_platform(&fork_id);
+        let display_name = display_name.map(String::from);
+        let platform_meta = serde_json::json!({ "forked_from": source_id }).to_string();
+
+        tokio::spawn(async move {
+            if let Err(error) = sqlx::query(
+                "INSERT INTO channels (id, platform, display_name, platform_meta, last_activity_at) \
+                 VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP) \
+                 ON CONFLICT(id) DO UPDATE SET \
+                     display_name = COALESCE(excluded.display_name, channels.display_name), \
+                     platform_meta = excluded.platform_meta, \
+                     is_active = 1, \
+                     last_activity_at = CURRENT_TIMESTAMP",
+            )
+            .bind(&fork_id)
+            .bind(&platform)
+            .bind(&display_name)
+            .bind(&platform_meta)
+            .execute(&pool)
+            .await
+            {
+                tracing::warn!(%error, %fork_id, "failed to record forked channel");
+            }
+        });
+    }
+
     /// Update last_activity_at for a channel. Fire-and-forget.
     pub fn touch(&self, channel_id: &str) {
         let pool = self.pool.clone();
@@ -81,10 +114,70 @@ impl ChannelStore {
         });
     }
 
+    /// The language detected for a channel, if any.
+    pub async fn language(&self, channel_id: &str) -> crate::error::Result<Option<String>> {
+        let language =
+            sqlx::query_scalar::<_, Option<String>>("SELECT language FROM channels WHERE id = ?")
+                .bind(channel_id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(language.flatten())
+    }
+
+    /// Record the language detected for a channel. Fire-and-forget.
+    pub fn set_language(&self, channel_id: &str, language: &str) {
+        let pool = self.pool.clone();
+        let channel_id = channel_id.to_string();
+        let language = language.to_string();
+
+        tokio::spawn(async move {
+            if let Err(error) = sqlx::query("UPDATE channels SET language = ? WHERE id = ?")
+                .bind(&language)
+                .bind(&channel_id)
+                .execute(&pool)
+                .await
+            {
+                tracing::warn!(%error, %channel_id, "failed to store channel language");
+            }
+        });
+    }
+
+    /// The generated title of a channel, if any.
+    pub async fn title(&self, channel_id: &str) -> crate::error::Result<Option<String>> {
+        let title =
+            sqlx::query_scalar::<_, Option<String>>("SELECT title FROM channels WHERE id = ?")
+                .bind(channel_id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(title.flatten())
+    }
+
+    /// Record the generated title of a channel. Fire-and-forget.
+    pub fn set_title(&self, channel_id: &str, title: &str) {
+        let pool = self.pool.clone();
+        let channel_id = channel_id.to_string();
+        let title = title.to_string();
+
+        tokio::spawn(async move {
+            if let Err(error) = sqlx::query("UPDATE channels SET title = ? WHERE id = ?")
+                .bind(&title)
+                .bind(&channel_id)
+                .execute(&pool)
+                .await
+            {
+                tracing::warn!(%error, %channel_id, "failed to store channel title");
+            }
+        });
+    }
+
     /// List all active channels, most recently active first.
     pub async fn list_active(&self) -> crate::error::Result<Vec<ChannelInfo>> {
         let rows = sqlx::query(
-            "SELECT id, platform, display_name, platform_meta, is_active, created_at, last_activity_at \
+            "SELECT id, platform, display_name, title, platform_meta, is_active, created_at, last_activity_at \
              FROM channels \
              WHERE is_active = 1 \
              ORDER BY last_activity_at DESC"
@@ -141,7 +234,7 @@ impl ChannelStore {
     /// Get a single channel by exact ID.
     pub async fn get(&self, channel_id: &str) -> crate::error::Result<Option<ChannelInfo>> {
         let row = sqlx::query(
-            "SELECT id, platform, display_name, platform_meta, is_active, created_at, last_activity_at \
+            "SELECT id, platform, display_name, title, platform_meta, is_active, created_at, last_activity_at \
              FROM channels \
              WHERE id = ?"
         )
@@ -173,6 +266,18 @@ impl ChannelStore {
             .await
             .map_err(|e| anyhow::anyhow!(e))?;
 
+        sqlx::query("DELETE FROM channel_history WHERE channel_id = ?")
+            .bind(channel_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        sqlx::query("DELETE FROM reply_drafts WHERE channel_id = ?")
+            .bind(channel_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
         let result = sqlx::query("DELETE FROM channels WHERE id = ?")
             .bind(channel_id)
             .execute(&mut *tx)
@@ -193,6 +298,7 @@ fn row_to_channel_info(row: sqlx::sqlite::SqliteRow) -> ChannelInfo {
         id: row.try_get("id").unwrap_or_default(),
         platform: row.try_get("platform").unwrap_or_default(),
         display_name: row.try_get("display_name").ok().flatten(),
+        title: row.try_get("title").ok().flatten(),
         platform_meta,
         is_active: row.try_get::<i32, _>("is_active").unwrap_or(1) == 1,
         created_at: row
@@ -240,6 +346,11 @@ fn extract_display_name(
             .get("display_name")
             .and_then(|v| v.as_str())
             .map(|s| s.to_string()),
+        "email" => metadata
+            .get("email_subject")
+            .and_then(|v| v.as_str())
+            .filter(|subject| !subject.is_empty())
+            .map(|s| s.to_string()),
         "portal" => Some("portal:chat".to_string()),
         _ => None,
     }
@@ -285,6 +396,13 @@ fn extract_platform_meta(
                 meta.insert("twitch_channel".to_string(), value.clone());
             }
         }
+        "email" => {
+            for key in ["email_thread_id", "email_from", "email_subject"] {
+                if let Some(value) = metadata.get(key) {
+                    meta.insert(key.to_string(), value.clone());
+                }
+            }
+        }
         _ => {}
     }
 
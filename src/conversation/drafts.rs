@@ -0,0 +1,412 @@
+//! Reply drafts for supervised channels (SQLite).
+//!
+//! A supervised channel doesn't send the agent's replies straight to the
+//! platform. Each reply is stored here as a pending draft and emitted as
+//! `OutboundResponse::Draft`; an operator then approves it (optionally with
+//! edited text) or rejects it through the API, or by reacting ✅/❌ to the
+//! message it answers. Only approved drafts are delivered.
+
+use crate::{OutboundResponse, StructuredReply, TurnId};
+
+use serde::Serialize;
+use sqlx::{Row as _, SqlitePool};
+
+/// Per-channel supervision settings, stored on the `channels` row.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Supervision {
+    pub supervised: bool,
+    /// Platform user IDs allowed to approve or reject drafts by reaction.
+    pub approvers: Vec<String>,
+}
+
+/// Lifecycle of a draft.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DraftStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl DraftStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "approved" => Self::Approved,
+            "rejected" => Self::Rejected,
+            _ => Self::Pending,
+        }
+    }
+}
+
+/// An operator's decision on a draft.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DraftDecision {
+    Approve,
+    Reject,
+}
+
+impl DraftDecision {
+    /// ✅/✔️ approve and ❌/✖️ reject, as unicode or Slack-style names.
+    /// Other emoji make no decision.
+    pub fn from_emoji(emoji: &str) -> Option<Self> {
+        let emoji = emoji.trim();
+        let name = emoji.trim_matches(':');
+        if emoji.starts_with('✅')
+            || emoji.starts_with('✔')
+            || matches!(name, "white_check_mark" | "heavy_check_mark")
+        {
+            Some(Self::Approve)
+        } else if emoji.starts_with('❌')
+            || emoji.starts_with('✖')
+            || matches!(name, "x" | "heavy_multiplication_x")
+        {
+            Some(Self::Reject)
+        } else {
+            None
+        }
+    }
+
+    fn status(self) -> DraftStatus {
+        match self {
+            Self::Approve => DraftStatus::Approved,
+            Self::Reject => DraftStatus::Rejected,
+        }
+    }
+}
+
+/// A held reply.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplyDraft {
+    pub approval_token: String,
+    pub channel_id: String,
+    /// What the agent wrote.
+    pub text: String,
+    /// Platform message ID to quote when delivered, for replies that asked to.
+    pub in_reply_to: Option<String>,
+    /// The inbound message the draft answers; reactions to it decide the draft.
+    pub trigger_message_id: Option<String>,
+    /// The structured reply the draft holds, for structured turns.
+    pub structured: Option<StructuredReply>,
+    /// The turn that wrote the reply; delivery is attributed to it.
+    pub turn_id: Option<TurnId>,
+    pub status: DraftStatus,
+    /// What was (or will be) delivered: the operator's edit, else `text`.
+    pub final_text: Option<String>,
+    pub resolved_by: Option<String>,
+    pub created_at: String,
+    pub resolved_at: Option<String>,
+}
+
+impl ReplyDraft {
+    /// The response to deliver once the draft is approved.
+    pub fn approved_response(&self) -> OutboundResponse {
+        let text = self.final_text.clone().unwrap_or_else(|| self.text.clone());
+        if let Some(structured) = &self.structured {
+            return OutboundResponse::Structured(StructuredReply {
+                message: text,
+                ..structured.clone()
+            });
+        }
+        match &self.in_reply_to {
+            Some(in_reply_to) => OutboundResponse::Reply {
+                in_reply_to: in_reply_to.clone(),
+                text,
+            },
+            None => OutboundResponse::Text(text),
+        }
+    }
+}
+
+/// Reads and writes `reply_drafts` and the supervision columns of `channels`.
+#[derive(Debug, Clone)]
+pub struct DraftStore {
+    pool: SqlitePool,
+}
+
+const DRAFT_COLUMNS: &str = "approval_token, channel_id, text, in_reply_to, trigger_message_id, \
+     structured, turn_id, status, final_text, resolved_by, created_at, resolved_at";
+
+impl DraftStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Supervision settings for a channel. Unknown channels are unsupervised.
+    pub async fn supervision(&self, channel_id: &str) -> crate::error::Result<Supervision> {
+        let row = sqlx::query("SELECT supervised, draft_approvers FROM channels WHERE id = ?")
+            .bind(channel_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(row
+            .map(|row| Supervision {
+                supervised: row.try_get::<i64, _>("supervised").unwrap_or(0) != 0,
+                approvers: row
+                    .try_get::<Option<String>, _>("draft_approvers")
+                    .ok()
+                    .flatten()
+                    .and_then(|raw| serde_json::from_str(&raw).ok())
+                    .unwrap_or_default(),
+            })
+            .unwrap_or_default())
+    }
+
+    /// Turn supervision on or off for a channel. Returns false if the channel
+    /// is unknown.
+    pub async fn set_supervision(
+        &self,
+        channel_id: &str,
+        supervision: &Supervision,
+    ) -> crate::error::Result<bool> {
+        let approvers = serde_json::to_string(&supervision.approvers).unwrap_or_default();
+        let result =
+            sqlx::query("UPDATE channels SET supervised = ?, draft_approvers = ? WHERE id = ?")
+                .bind(supervision.supervised)
+                .bind(approvers)
+                .bind(channel_id)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Hold a reply for review if the channel is supervised.
+    ///
+    /// Text-bearing and structured responses are stored as a pending draft
+    /// and replaced by `OutboundResponse::Draft`; everything else, and every
+    /// response in an unsupervised channel, is returned unchanged.
+    pub async fn hold(
+        &self,
+        channel_id: &str,
+        trigger_message_id: Option<&str>,
+        turn_id: Option<TurnId>,
+        response: OutboundResponse,
+    ) -> crate::error::Result<OutboundResponse> {
+        let (text, in_reply_to, structured) = match &response {
+            OutboundResponse::Text(text)
+            | OutboundResponse::ThreadReply { text, .. }
+            | OutboundResponse::RichMessage { text, .. } => (text, None, None),
+            OutboundResponse::Reply { in_reply_to, text } => {
+                (text, Some(in_reply_to.as_str()), None)
+            }
+            OutboundResponse::Structured(reply) => {
+                (&reply.message, None, serde_json::to_string(reply).ok())
+            }
+            _ => return Ok(response),
+        };
+
+        if !self.supervision(channel_id).await?.supervised {
+            return Ok(response);
+        }
+
+        let approval_token = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO reply_drafts \
+             (approval_token, channel_id, text, in_reply_to, trigger_message_id, structured, \
+              turn_id) \
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&approval_token)
+        .bind(channel_id)
+        .bind(text)
+        .bind(in_reply_to)
+        .bind(trigger_message_id)
+        .bind(structured)
+        .bind(turn_id.map(|turn_id| turn_id.to_string()))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(OutboundResponse::Draft {
+            text: text.clone(),
+            approval_token,
+        })
+    }
+
+    /// Load a draft by its approval token.
+    pub async fn get(&self, approval_token: &str) -> crate::error::Result<Option<ReplyDraft>> {
+        let row = sqlx::query(&format!(
+            "SELECT {DRAFT_COLUMNS} FROM reply_drafts WHERE approval_token = ?"
+        ))
+        .bind(approval_token)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(row.map(row_to_draft))
+    }
+
+    /// Pending drafts, oldest first, optionally for one channel.
+    pub async fn list_pending(
+        &self,
+        channel_id: Option<&str>,
+    ) -> crate::error::Result<Vec<ReplyDraft>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {DRAFT_COLUMNS} FROM reply_drafts \
+             WHERE status = 'pending' AND (? IS NULL OR channel_id = ?) \
+             ORDER BY created_at ASC"
+        ))
+        .bind(channel_id)
+        .bind(channel_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(rows.into_iter().map(row_to_draft).collect())
+    }
+
+    /// The oldest pending draft answering a given inbound message.
+    pub async fn pending_for_message(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+    ) -> crate::error::Result<Option<ReplyDraft>> {
+        let row = sqlx::query(&format!(
+            "SELECT {DRAFT_COLUMNS} FROM reply_drafts \
+             WHERE status = 'pending' AND channel_id = ? AND trigger_message_id = ? \
+             ORDER BY created_at ASC LIMIT 1"
+        ))
+        .bind(channel_id)
+        .bind(message_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(row.map(row_to_draft))
+    }
+
+    /// Record a decision on a pending draft. `text` replaces the draft's text
+    /// on approval. Returns `None` if the draft doesn't exist or was already
+    /// decided, so each draft is delivered at most once.
+    pub async fn resolve(
+        &self,
+        approval_token: &str,
+        decision: DraftDecision,
+        text: Option<&str>,
+        resolved_by: &str,
+    ) -> crate::error::Result<Option<ReplyDraft>> {
+        let result = sqlx::query(
+            "UPDATE reply_drafts \
+             SET status = ?, final_text = COALESCE(?, text), resolved_by = ?, \
+                 resolved_at = CURRENT_TIMESTAMP \
+             WHERE approval_token = ? AND status = 'pending'",
+        )
+        .bind(decision.status().as_str())
+        .bind(text)
+        .bind(resolved_by)
+        .bind(approval_token)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        self.get(approval_token).await
+    }
+}
+
+fn row_to_draft(row: sqlx::sqlite::SqliteRow) -> ReplyDraft {
+    let timestamp = |column: &str| {
+        row.try_get::<Option<chrono::DateTime<chrono::Utc>>, _>(column)
+            .ok()
+            .flatten()
+            .map(|t| t.to_rfc3339())
+    };
+
+    ReplyDraft {
+        approval_token: row.try_get("approval_token").unwrap_or_default(),
+        channel_id: row.try_get("channel_id").unwrap_or_default(),
+        text: row.try_get("text").unwrap_or_default(),
+        in_reply_to: row.try_get("in_reply_to").ok().flatten(),
+        trigger_message_id: row.try_get("trigger_message_id").ok().flatten(),
+        structured: row
+            .try_get::<Option<String>, _>("structured")
+            .ok()
+            .flatten()
+            .and_then(|raw| serde_json::from_str(&raw).ok()),
+        turn_id: row
+            .try_get::<Option<String>, _>("turn_id")
+            .ok()
+            .flatten()
+            .and_then(|raw| raw.parse().ok()),
+        status: DraftStatus::parse(&row.try_get::<String, _>("status").unwrap_or_default()),
+        final_text: row.try_get("final_text").ok().flatten(),
+        resolved_by: row.try_get("resolved_by").ok().flatten(),
+        created_at: timestamp("created_at").unwrap_or_default(),
+        resolved_at: timestamp("resolved_at"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decisions_from_emoji() {
+        assert_eq!(
+            DraftDecision::from_emoji("✅"),
+            Some(DraftDecision::Approve)
+        );
+        assert_eq!(
+            DraftDecision::from_emoji(":white_check_mark:"),
+            Some(DraftDecision::Approve)
+        );
+        assert_eq!(DraftDecision::from_emoji("❌"), Some(DraftDecision::Reject));
+        assert_eq!(DraftDecision::from_emoji("x"), Some(DraftDecision::Reject));
+        assert_eq!(DraftDecision::from_emoji("👍"), None);
+    }
+
+    #[test]
+    fn approved_drafts_prefer_the_edit_and_keep_the_quote() {
+        let mut draft = ReplyDraft {
+            approval_token: "token".into(),
+            channel_id: "discord:1:2".into(),
+            text: "original".into(),
+            in_reply_to: None,
+            trigger_message_id: Some("42".into()),
+            structured: None,
+            turn_id: None,
+            status: DraftStatus::Approved,
+            final_text: Some("edited".into()),
+            resolved_by: Some("api".into()),
+            created_at: String::new(),
+            resolved_at: None,
+        };
+        assert!(matches!(
+            draft.approved_response(),
+            OutboundResponse::Text(text) if text == "edited"
+        ));
+
+        draft.in_reply_to = Some("42".into());
+        draft.final_text = None;
+        assert!(matches!(
+            draft.approved_response(),
+            OutboundResponse::Reply { in_reply_to, text } if in_reply_to == "42" && text == "original"
+        ));
+
+        draft.final_text = Some("edited".into());
+        draft.structured = Some(StructuredReply {
+            action: crate::ReplyAction::Clarify,
+            message: "original".into(),
+            confidence: Some(0.4),
+        });
+        assert!(matches!(
+            draft.approved_response(),
+            OutboundResponse::Structured(reply)
+                if reply.action == crate::ReplyAction::Clarify
+                    && reply.message == "edited"
+                    && reply.confidence == Some(0.4)
+        ));
+    }
+}
@@ -0,0 +1,257 @@
+//! Journal of branch and worker results addressed to a channel (SQLite).
+//!
+//! Results travel to channels over the agent's broadcast event bus, which
+//! only reaches channels that are running and subscribed. A result that
+//! lands while its channel is archived, or after a restart, would be lost.
+//! Producers record the result here before broadcasting it, the channel
+//! marks it consumed once handled, and a rehydrated channel replays whatever
+//! is left. Cancelling a process leaves a marker first, so a result it sends
+//! while winding down is never replayed.
+
+use crate::{ChannelId, ProcessEvent, ProcessId};
+
+use sqlx::{Row as _, SqlitePool};
+
+/// How long a cancellation marker is kept for a process that never reports
+/// back, e.g. because its task was aborted.
+const DISCARD_RETENTION: &str = "-1 hour";
+
+/// Unconsumed `BranchResult` and `WorkerComplete` events, per channel.
+#[derive(Debug, Clone)]
+pub struct EventJournal {
+    pool: SqlitePool,
+}
+
+impl EventJournal {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Record an event before it is broadcast. Events other than top-level
+    /// branch results and channel worker completions, and events from
+    /// processes that were cancelled, are ignored.
+    ///
+    /// Awaited rather than fire-and-forget, so the entry exists by the time
+    /// the channel handles the event and consumes it.
+    pub async fn record(&self, event: &ProcessEvent) {
+        let Some((channel_id, process_id)) = journal_key(event) else {
+            return;
+        };
+        let payload = match serde_json::to_string(event) {
+            Ok(payload) => payload,
+            Err(error) => {
+                tracing::warn!(%error, %process_id, "failed to serialize journaled event");
+                return;
+            }
+        };
+
+        if let Err(error) = sqlx::query(
+            "INSERT INTO channel_event_journal (channel_id, process_id, event) \
+             VALUES (?, ?, ?) \
+             ON CONFLICT(channel_id, process_id) DO NOTHING",
+        )
+        .bind(channel_id.as_ref())
+        .bind(process_id.to_string())
+        .bind(&payload)
+        .execute(&self.pool)
+        .await
+        {
+            tracing::warn!(%error, %channel_id, %process_id, "failed to journal event");
+        }
+    }
+
+    /// Mark a process as cancelled, so its result is not journaled.
+    pub async fn discard(&self, channel_id: &ChannelId, process_id: &ProcessId) {
+        if let Err(error) = sqlx::query(
+            "INSERT OR REPLACE INTO channel_event_journal (channel_id, process_id, event) \
+             VALUES (?, ?, NULL)",
+        )
+        .bind(channel_id.as_ref())
+        .bind(process_id.to_string())
+        .execute(&self.pool)
+        .await
+        {
+            tracing::warn!(%error, %channel_id, %process_id, "failed to journal cancellation");
+        }
+    }
+
+    /// Mark the event a process sent to a channel as handled. Fire-and-forget.
+    pub fn consume(&self, channel_id: &ChannelId, process_id: &ProcessId) {
+        let pool = self.pool.clone();
+        let channel_id = channel_id.to_string();
+        let process_id = process_id.to_string();
+
+        tokio::spawn(async move {
+            if let Err(error) = sqlx::query(
+                "DELETE FROM channel_event_journal WHERE channel_id = ? AND process_id = ?",
+            )
+            .bind(&channel_id)
+            .bind(&process_id)
+            .execute(&pool)
+            .await
+            {
+                tracing::warn!(%error, %channel_id, %process_id, "failed to consume journaled event");
+            }
+        });
+    }
+
+    /// Events recorded for a channel and not consumed yet, oldest first.
+    /// Entries that no longer deserialize, and stale cancellation markers,
+    /// are dropped.
+    pub async fn pending(&self, channel_id: &ChannelId) -> crate::error::Result<Vec<ProcessEvent>> {
+        sqlx::query(
+            "DELETE FROM channel_event_journal \
+             WHERE channel_id = ? AND event IS NULL AND created_at < datetime('now', ?)",
+        )
+        .bind(channel_id.as_ref())
+        .bind(DISCARD_RETENTION)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        let rows = sqlx::query(
+            "SELECT process_id, event FROM channel_event_journal \
+             WHERE channel_id = ? AND event IS NOT NULL \
+             ORDER BY created_at ASC, rowid ASC",
+        )
+        .bind(channel_id.as_ref())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        let mut events = Vec::with_capacity(rows.len());
+        for row in rows {
+            let process_id: String = row.try_get("process_id").unwrap_or_default();
+            let payload: String = row.try_get("event").unwrap_or_default();
+            match serde_json::from_str(&payload) {
+                Ok(event) => events.push(event),
+                Err(error) => {
+                    tracing::warn!(%error, %channel_id, %process_id, "dropping unreadable journaled event");
+                    sqlx::query(
+                        "DELETE FROM channel_event_journal WHERE channel_id = ? AND process_id = ?",
+                    )
+                    .bind(channel_id.as_ref())
+                    .bind(&process_id)
+                    .execute(&self.pool)
+                    .await
+                    .ok();
+                }
+            }
+        }
+        Ok(events)
+    }
+}
+
+/// The channel an event is journaled for and the process that sent it.
+///
+/// Sub-branch results reach the channel through their parent's, so only
+/// top-level branch results are kept.
+pub fn journal_key(event: &ProcessEvent) -> Option<(ChannelId, ProcessId)> {
+    match event {
+        ProcessEvent::BranchResult {
+            channel_id,
+            branch_id,
+            parent_branch_id: None,
+            ..
+        } => Some((channel_id.clone(), ProcessId::Branch(*branch_id))),
+        ProcessEvent::WorkerComplete {
+            channel_id: Some(channel_id),
+            worker_id,
+            ..
+        } => Some((channel_id.clone(), ProcessId::Worker(*worker_id))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::worker::WorkerResult;
+    use std::sync::Arc;
+
+    async fn connect_in_memory() -> SqlitePool {
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .in_memory(true)
+            .create_if_missing(true);
+        let pool = sqlx::pool::PoolOptions::<sqlx::Sqlite>::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("in-memory SQLite");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+        pool
+    }
+
+    fn worker_complete(channel_id: &ChannelId) -> (ProcessEvent, ProcessId) {
+        let worker_id = uuid::Uuid::new_v4();
+        let event = ProcessEvent::WorkerComplete {
+            agent_id: Arc::from("main"),
+            worker_id,
+            channel_id: Some(channel_id.clone()),
+            result: WorkerResult::success("done"),
+            notify: true,
+            turn_id: None,
+        };
+        (event, ProcessId::Worker(worker_id))
+    }
+
+    #[tokio::test]
+    async fn unconsumed_events_are_replayed_in_order() {
+        let journal = EventJournal::new(connect_in_memory().await);
+        let channel_id: ChannelId = Arc::from("discord:1");
+        let (first, first_id) = worker_complete(&channel_id);
+        let (second, second_id) = worker_complete(&channel_id);
+        let (third, third_id) = worker_complete(&channel_id);
+        for event in [&first, &second, &third] {
+            journal.record(event).await;
+        }
+        journal
+            .record(&worker_complete(&Arc::from("discord:2")).0)
+            .await;
+
+        journal.consume(&channel_id, &second_id);
+
+        let mut pending = Vec::new();
+        for _ in 0..50 {
+            pending = journal.pending(&channel_id).await.unwrap();
+            if pending.len() == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let keys: Vec<ProcessId> = pending
+            .iter()
+            .filter_map(|event| journal_key(event).map(|(_, process_id)| process_id))
+            .collect();
+        assert_eq!(keys, [first_id, third_id]);
+    }
+
+    #[tokio::test]
+    async fn results_of_cancelled_processes_are_not_replayed() {
+        let journal = EventJournal::new(connect_in_memory().await);
+        let channel_id: ChannelId = Arc::from("discord:1");
+        let (event, process_id) = worker_complete(&channel_id);
+
+        journal.discard(&channel_id, &process_id).await;
+        journal.record(&event).await;
+
+        assert!(journal.pending(&channel_id).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn sub_branch_results_are_not_journaled() {
+        let event = ProcessEvent::BranchResult {
+            agent_id: Arc::from("main"),
+            branch_id: uuid::Uuid::new_v4(),
+            channel_id: Arc::from("discord:1"),
+            conclusion: "done".into(),
+            parent_branch_id: Some(uuid::Uuid::new_v4()),
+            sub_results: Vec::new(),
+            turn_id: None,
+        };
+        assert!(journal_key(&event).is_none());
+    }
+}
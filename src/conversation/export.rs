@@ -0,0 +1,421 @@
+//! Conversation export and import (JSON bundles).
+//!
+//! A bundle holds everything needed to pick a conversation up elsewhere: the
+//! LLM-facing history, the human-readable transcript, the memories created
+//! in the channel, worker results, and the status snapshot. Bundles are
+//! versioned so older ones keep importing as the format grows; a bundle
+//! newer than this build is refused rather than half-imported.
+
+use crate::conversation::history::{ChannelSnapshot, SqliteHistoryStore, Store as _};
+use crate::memory::{Memory, MemorySearch, MemoryStore};
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Row as _, SqlitePool};
+
+/// Current bundle format version.
+pub const EXPORT_VERSION: u32 = 1;
+
+/// A conversation, exported.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelExport {
+    pub version: u32,
+    pub agent_id: String,
+    pub channel_id: String,
+    pub exported_at: chrono::DateTime<chrono::Utc>,
+    /// The channel's row, if it was tracked.
+    pub channel: Option<ExportedChannel>,
+    /// The exact message list the channel prompts with.
+    pub history: Vec<rig::message::Message>,
+    /// User and assistant messages as logged, oldest first.
+    pub transcript: Vec<ExportedMessage>,
+    /// Memories saved while in this channel.
+    pub memories: Vec<Memory>,
+    pub worker_results: Vec<ExportedWorkerRun>,
+    /// Status block and conversation context.
+    pub status: Option<ChannelSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedChannel {
+    pub platform: String,
+    pub display_name: Option<String>,
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedMessage {
+    pub id: String,
+    pub role: String,
+    pub sender_name: Option<String>,
+    pub sender_id: Option<String>,
+    pub content: String,
+    pub metadata: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedWorkerRun {
+    pub id: String,
+    pub task: String,
+    pub result: Option<String>,
+    pub status: String,
+    pub worker_type: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// What an import wrote.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ImportSummary {
+    pub history_messages: usize,
+    pub transcript_messages: usize,
+    /// Memories added; ones already present are left alone.
+    pub memories: usize,
+    pub worker_results: usize,
+}
+
+impl ChannelExport {
+    /// Export a channel from its stored state. A live channel has newer
+    /// history and status in memory; see `Channel::export`.
+    pub async fn load(
+        pool: &SqlitePool,
+        agent_id: &str,
+        channel_id: &str,
+    ) -> crate::error::Result<Self> {
+        let channel =
+            sqlx::query("SELECT platform, display_name, language FROM channels WHERE id = ?")
+                .bind(channel_id)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?
+                .map(|row| ExportedChannel {
+                    platform: row.try_get("platform").unwrap_or_default(),
+                    display_name: row.try_get("display_name").ok().flatten(),
+                    language: row.try_get("language").ok().flatten(),
+                });
+
+        let history = SqliteHistoryStore::new(pool.clone())
+            .load(&channel_id.into())
+            .await?;
+
+        let transcript = sqlx::query(
+            "SELECT id, role, sender_name, sender_id, content, metadata, created_at \
+             FROM conversation_messages WHERE channel_id = ? ORDER BY created_at ASC",
+        )
+        .bind(channel_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?
+        .into_iter()
+        .map(|row| ExportedMessage {
+            id: row.try_get("id").unwrap_or_default(),
+            role: row.try_get("role").unwrap_or_default(),
+            sender_name: row.try_get("sender_name").ok().flatten(),
+            sender_id: row.try_get("sender_id").ok().flatten(),
+            content: row.try_get("content").unwrap_or_default(),
+            metadata: row.try_get("metadata").ok().flatten(),
+            created_at: row
+                .try_get("created_at")
+                .unwrap_or_else(|_| chrono::Utc::now()),
+        })
+        .collect();
+
+        let memories = MemoryStore::new(pool.clone())
+            .get_by_channel(channel_id)
+            .await?;
+
+        let worker_results = sqlx::query(
+            "SELECT id, task, result, status, worker_type, started_at, completed_at \
+             FROM worker_runs WHERE channel_id = ? ORDER BY started_at ASC",
+        )
+        .bind(channel_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?
+        .into_iter()
+        .map(|row| ExportedWorkerRun {
+            id: row.try_get("id").unwrap_or_default(),
+            task: row.try_get("task").unwrap_or_default(),
+            result: row.try_get("result").ok().flatten(),
+            status: row.try_get("status").unwrap_or_default(),
+            worker_type: row
+                .try_get("worker_type")
+                .unwrap_or_else(|_| "builtin".into()),
+            started_at: row
+                .try_get("started_at")
+                .unwrap_or_else(|_| chrono::Utc::now()),
+            completed_at: row.try_get("completed_at").ok().flatten(),
+        })
+        .collect();
+
+        // Read without taking: the snapshot stays for the channel's next start.
+        let status = sqlx::query_scalar::<_, String>(
+            "SELECT snapshot FROM channel_snapshots WHERE channel_id = ?",
+        )
+        .bind(channel_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?
+        .and_then(|json| serde_json::from_str(&json).ok());
+
+        Ok(Self {
+            version: EXPORT_VERSION,
+            agent_id: agent_id.to_string(),
+            channel_id: channel_id.to_string(),
+            exported_at: chrono::Utc::now(),
+            channel,
+            history,
+            transcript,
+            memories,
+            worker_results,
+            status,
+        })
+    }
+
+    /// Parse a bundle, refusing versions this build doesn't know.
+    pub fn from_json(json: &str) -> crate::error::Result<Self> {
+        #[derive(Deserialize)]
+        struct Version {
+            version: u32,
+        }
+
+        let Version { version } = serde_json::from_str(json)
+            .map_err(|error| anyhow::anyhow!("not a channel export: {error}"))?;
+        if version > EXPORT_VERSION {
+            return Err(anyhow::anyhow!(
+                "channel export version {version} is newer than this build supports \
+                 ({EXPORT_VERSION})"
+            )
+            .into());
+        }
+
+        serde_json::from_str(json)
+            .map_err(|error| anyhow::anyhow!("invalid channel export: {error}").into())
+    }
+
+    pub fn to_json(&self) -> crate::error::Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| anyhow::anyhow!(e).into())
+    }
+
+    /// Write the bundle into `channel_id`, which may differ from the channel
+    /// it was exported from.
+    ///
+    /// Replaces the channel's stored history and status snapshot, and adds
+    /// the transcript, worker results, and memories (embedding them for
+    /// search). A channel that already has history is refused unless `force`
+    /// is set. Importing into the same channel again doesn't duplicate
+    /// anything. All SQLite writes happen in one transaction; embeddings are
+    /// computed before it and stored after it commits. The channel should
+    /// not be running: a live channel keeps its own history and overwrites
+    /// the import on its next turn.
+    pub async fn import(
+        &self,
+        pool: &SqlitePool,
+        memory_search: &MemorySearch,
+        channel_id: &str,
+        force: bool,
+    ) -> crate::error::Result<ImportSummary> {
+        let has_history: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM channel_history WHERE channel_id = ?) \
+                 OR EXISTS(SELECT 1 FROM conversation_messages WHERE channel_id = ?)",
+        )
+        .bind(channel_id)
+        .bind(channel_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+        if has_history && !force {
+            return Err(anyhow::anyhow!(
+                "channel '{channel_id}' already has history; import with --force to replace it"
+            )
+            .into());
+        }
+
+        // Row IDs are kept for idempotent re-imports, and fresh when the
+        // bundle goes into another channel so it can sit beside the original.
+        let same_channel = channel_id == self.channel_id;
+        let row_id = |id: &str| {
+            if same_channel {
+                id.to_string()
+            } else {
+                uuid::Uuid::new_v4().to_string()
+            }
+        };
+
+        let target: crate::ChannelId = channel_id.into();
+
+        // Embed new memories up front, so a model failure leaves nothing
+        // half-written.
+        let mut new_memories = Vec::new();
+        for memory in &self.memories {
+            if memory_search.store().load(&memory.id).await?.is_some() {
+                continue;
+            }
+            let embedding = memory_search
+                .embedding_model_arc()
+                .embed_one(&memory.content)
+                .await?;
+            let memory = Memory {
+                channel_id: Some(target.clone()),
+                ..memory.clone()
+            };
+            new_memories.push((memory, embedding));
+        }
+
+        let mut transaction = pool.begin().await.map_err(|e| anyhow::anyhow!(e))?;
+
+        let channel = self.channel.as_ref();
+        sqlx::query(
+            "INSERT INTO channels (id, platform, display_name, language, last_activity_at) \
+             VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP) \
+             ON CONFLICT(id) DO UPDATE SET \
+                 display_name = COALESCE(excluded.display_name, channels.display_name), \
+                 language = COALESCE(excluded.language, channels.language)",
+        )
+        .bind(channel_id)
+        .bind(
+            channel
+                .map(|channel| channel.platform.clone())
+                .unwrap_or_else(|| {
+                    channel_id
+                        .split(':')
+                        .next()
+                        .unwrap_or("unknown")
+                        .to_string()
+                }),
+        )
+        .bind(channel.and_then(|channel| channel.display_name.as_deref()))
+        .bind(channel.and_then(|channel| channel.language.as_deref()))
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        let mut summary = ImportSummary {
+            history_messages: self.history.len(),
+            ..ImportSummary::default()
+        };
+
+        for message in &self.transcript {
+            let result = sqlx::query(
+                "INSERT OR IGNORE INTO conversation_messages \
+                     (id, channel_id, role, sender_name, sender_id, content, metadata, created_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(row_id(&message.id))
+            .bind(channel_id)
+            .bind(&message.role)
+            .bind(&message.sender_name)
+            .bind(&message.sender_id)
+            .bind(&message.content)
+            .bind(&message.metadata)
+            .bind(message.created_at)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+            summary.transcript_messages += result.rows_affected() as usize;
+        }
+
+        for run in &self.worker_results {
+            let result = sqlx::query(
+                "INSERT OR IGNORE INTO worker_runs \
+                     (id, channel_id, task, result, status, worker_type, agent_id, \
+                      started_at, completed_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(row_id(&run.id))
+            .bind(channel_id)
+            .bind(&run.task)
+            .bind(&run.result)
+            .bind(&run.status)
+            .bind(&run.worker_type)
+            .bind(&self.agent_id)
+            .bind(run.started_at)
+            .bind(run.completed_at)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+            summary.worker_results += result.rows_affected() as usize;
+        }
+
+        let history = serde_json::to_string(&self.history).map_err(|e| anyhow::anyhow!(e))?;
+        sqlx::query(
+            "INSERT INTO channel_history (channel_id, messages, message_count, updated_at) \
+             VALUES (?, ?, ?, CURRENT_TIMESTAMP) \
+             ON CONFLICT(channel_id) DO UPDATE SET \
+                 messages = excluded.messages, \
+                 message_count = excluded.message_count, \
+                 updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(channel_id)
+        .bind(&history)
+        .bind(self.history.len() as i64)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        if let Some(status) = &self.status {
+            let snapshot = serde_json::to_string(status).map_err(|e| anyhow::anyhow!(e))?;
+            sqlx::query(
+                "INSERT INTO channel_snapshots (channel_id, snapshot, archived_at) \
+                 VALUES (?, ?, CURRENT_TIMESTAMP) \
+                 ON CONFLICT(channel_id) DO UPDATE SET \
+                     snapshot = excluded.snapshot, \
+                     archived_at = CURRENT_TIMESTAMP",
+            )
+            .bind(channel_id)
+            .bind(&snapshot)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+        }
+
+        for (memory, _) in &new_memories {
+            MemoryStore::insert(&mut *transaction, memory).await?;
+        }
+
+        transaction.commit().await.map_err(|e| anyhow::anyhow!(e))?;
+
+        memory_search.embeddings().store_many(&new_memories).await?;
+        summary.memories += new_memories.len();
+
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export() -> ChannelExport {
+        ChannelExport {
+            version: EXPORT_VERSION,
+            agent_id: "main".into(),
+            channel_id: "discord:1:2".into(),
+            exported_at: chrono::Utc::now(),
+            channel: None,
+            history: vec![rig::message::Message::from("hello")],
+            transcript: Vec::new(),
+            memories: Vec::new(),
+            worker_results: Vec::new(),
+            status: None,
+        }
+    }
+
+    #[test]
+    fn bundles_round_trip() {
+        let json = export().to_json().unwrap();
+        let parsed = ChannelExport::from_json(&json).unwrap();
+        assert_eq!(parsed.channel_id, "discord:1:2");
+        assert_eq!(parsed.history.len(), 1);
+    }
+
+    #[test]
+    fn newer_bundles_are_refused() {
+        let mut value = serde_json::to_value(export()).unwrap();
+        value["version"] = (EXPORT_VERSION + 1).into();
+        let error = ChannelExport::from_json(&value.to_string()).unwrap_err();
+        assert!(error.to_string().contains("newer than this build"));
+
+        assert!(ChannelExport::from_json("{}").is_err());
+    }
+}
@@ -2,9 +2,11 @@
 
 use crate::{BranchId, ChannelId, WorkerId};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlx::{Row as _, SqlitePool};
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 
 /// Persists conversation messages (user and assistant) to SQLite.
 ///
@@ -74,18 +76,20 @@ impl ConversationLogger {
         self.log_bot_message_with_name(channel_id, content, None);
     }
 
-    /// Log a bot (assistant) message with an agent display name. Fire-and-forget.
+    /// Log a bot (assistant) message with an agent display name. Returns the
+    /// ID the message is stored under. Fire-and-forget.
     pub fn log_bot_message_with_name(
         &self,
         channel_id: &ChannelId,
         content: &str,
         sender_name: Option<&str>,
-    ) {
+    ) -> String {
         let pool = self.pool.clone();
         let id = uuid::Uuid::new_v4().to_string();
         let channel_id = channel_id.to_string();
         let content = content.to_string();
         let sender_name = sender_name.map(String::from);
+        let message_id = id.clone();
 
         tokio::spawn(async move {
             if let Err(error) = sqlx::query(
@@ -102,6 +106,8 @@ impl ConversationLogger {
                 tracing::warn!(%error, "failed to persist bot message");
             }
         });
+
+        message_id
     }
 
     /// Load recent messages for a channel (oldest first).
@@ -145,6 +151,29 @@ impl ConversationLogger {
         Ok(messages)
     }
 
+    /// Copy every message logged for `from` into `to`, keeping timestamps.
+    /// Used when forking a conversation. Returns the number of messages copied.
+    pub async fn copy_messages(
+        &self,
+        from: &ChannelId,
+        to: &ChannelId,
+    ) -> crate::error::Result<u64> {
+        let result = sqlx::query(
+            "INSERT INTO conversation_messages \
+                 (id, channel_id, role, sender_name, sender_id, content, metadata, created_at) \
+             SELECT lower(hex(randomblob(16))), ?, role, sender_name, sender_id, content, metadata, created_at \
+             FROM conversation_messages \
+             WHERE channel_id = ?",
+        )
+        .bind(to.as_ref())
+        .bind(from.as_ref())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(result.rows_affected())
+    }
+
     /// Load recent messages from any channel (not just the current one).
     pub async fn load_channel_transcript(
         &self,
@@ -185,6 +214,244 @@ impl ConversationLogger {
     }
 }
 
+/// Channel state that lives outside the LLM-facing history, written when an
+/// idle channel is archived so the next message can pick up where it left off.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChannelSnapshot {
+    pub status: crate::agent::status::StatusBlock,
+    pub conversation_context: Option<String>,
+}
+
+/// Backing store for a channel's LLM-facing history.
+///
+/// Unlike `ConversationLogger`, which records the human-readable transcript,
+/// this holds the exact `rig` message list the channel prompts with (tool
+/// calls, tool results, injected branch/worker results), so a channel can
+/// resume where it left off after a restart.
+pub trait Store: Send + Sync + 'static {
+    /// Load the persisted history for a channel. Returns an empty list when
+    /// nothing has been stored yet.
+    fn load(
+        &self,
+        channel_id: &ChannelId,
+    ) -> impl std::future::Future<Output = crate::error::Result<Vec<rig::message::Message>>> + Send;
+
+    /// Replace the persisted history for a channel with `messages`.
+    fn save(
+        &self,
+        channel_id: &ChannelId,
+        messages: &[rig::message::Message],
+    ) -> impl std::future::Future<Output = crate::error::Result<()>> + Send;
+
+    /// Drop the persisted history for a channel.
+    fn clear(
+        &self,
+        channel_id: &ChannelId,
+    ) -> impl std::future::Future<Output = crate::error::Result<()>> + Send;
+
+    /// Store the state of a channel that is being archived.
+    fn save_snapshot(
+        &self,
+        channel_id: &ChannelId,
+        snapshot: &ChannelSnapshot,
+    ) -> impl std::future::Future<Output = crate::error::Result<()>> + Send;
+
+    /// Remove and return the archived state for a channel, if any.
+    fn take_snapshot(
+        &self,
+        channel_id: &ChannelId,
+    ) -> impl std::future::Future<Output = crate::error::Result<Option<ChannelSnapshot>>> + Send;
+}
+
+/// Dynamic companion to [`Store`] for holding `Arc<dyn StoreDyn>`.
+pub trait StoreDyn: Send + Sync + 'static {
+    fn load<'a>(
+        &'a self,
+        channel_id: &'a ChannelId,
+    ) -> Pin<Box<dyn Future<Output = crate::error::Result<Vec<rig::message::Message>>> + Send + 'a>>;
+
+    fn save<'a>(
+        &'a self,
+        channel_id: &'a ChannelId,
+        messages: &'a [rig::message::Message],
+    ) -> Pin<Box<dyn Future<Output = crate::error::Result<()>> + Send + 'a>>;
+
+    fn clear<'a>(
+        &'a self,
+        channel_id: &'a ChannelId,
+    ) -> Pin<Box<dyn Future<Output = crate::error::Result<()>> + Send + 'a>>;
+
+    fn save_snapshot<'a>(
+        &'a self,
+        channel_id: &'a ChannelId,
+        snapshot: &'a ChannelSnapshot,
+    ) -> Pin<Box<dyn Future<Output = crate::error::Result<()>> + Send + 'a>>;
+
+    fn take_snapshot<'a>(
+        &'a self,
+        channel_id: &'a ChannelId,
+    ) -> Pin<Box<dyn Future<Output = crate::error::Result<Option<ChannelSnapshot>>> + Send + 'a>>;
+}
+
+impl<T: Store> StoreDyn for T {
+    fn load<'a>(
+        &'a self,
+        channel_id: &'a ChannelId,
+    ) -> Pin<Box<dyn Future<Output = crate::error::Result<Vec<rig::message::Message>>> + Send + 'a>>
+    {
+        Box::pin(Store::load(self, channel_id))
+    }
+
+    fn save<'a>(
+        &'a self,
+        channel_id: &'a ChannelId,
+        messages: &'a [rig::message::Message],
+    ) -> Pin<Box<dyn Future<Output = crate::error::Result<()>> + Send + 'a>> {
+        Box::pin(Store::save(self, channel_id, messages))
+    }
+
+    fn clear<'a>(
+        &'a self,
+        channel_id: &'a ChannelId,
+    ) -> Pin<Box<dyn Future<Output = crate::error::Result<()>> + Send + 'a>> {
+        Box::pin(Store::clear(self, channel_id))
+    }
+
+    fn save_snapshot<'a>(
+        &'a self,
+        channel_id: &'a ChannelId,
+        snapshot: &'a ChannelSnapshot,
+    ) -> Pin<Box<dyn Future<Output = crate::error::Result<()>> + Send + 'a>> {
+        Box::pin(Store::save_snapshot(self, channel_id, snapshot))
+    }
+
+    fn take_snapshot<'a>(
+        &'a self,
+        channel_id: &'a ChannelId,
+    ) -> Pin<Box<dyn Future<Output = crate::error::Result<Option<ChannelSnapshot>>> + Send + 'a>>
+    {
+        Box::pin(Store::take_snapshot(self, channel_id))
+    }
+}
+
+/// SQLite-backed [`Store`]. One row per channel holding the JSON-serialized
+/// message list.
+#[derive(Debug, Clone)]
+pub struct SqliteHistoryStore {
+    pool: SqlitePool,
+}
+
+impl SqliteHistoryStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl Store for SqliteHistoryStore {
+    async fn load(
+        &self,
+        channel_id: &ChannelId,
+    ) -> crate::error::Result<Vec<rig::message::Message>> {
+        let row = sqlx::query("SELECT messages FROM channel_history WHERE channel_id = ?")
+            .bind(channel_id.as_ref())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        let Some(row) = row else {
+            return Ok(Vec::new());
+        };
+
+        let json: String = row.try_get("messages").map_err(|e| anyhow::anyhow!(e))?;
+        let messages = serde_json::from_str(&json).map_err(|e| anyhow::anyhow!(e))?;
+        Ok(messages)
+    }
+
+    async fn save(
+        &self,
+        channel_id: &ChannelId,
+        messages: &[rig::message::Message],
+    ) -> crate::error::Result<()> {
+        let json = serde_json::to_string(messages).map_err(|e| anyhow::anyhow!(e))?;
+
+        sqlx::query(
+            "INSERT INTO channel_history (channel_id, messages, message_count, updated_at) \
+             VALUES (?, ?, ?, CURRENT_TIMESTAMP) \
+             ON CONFLICT(channel_id) DO UPDATE SET \
+                 messages = excluded.messages, \
+                 message_count = excluded.message_count, \
+                 updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(channel_id.as_ref())
+        .bind(&json)
+        .bind(messages.len() as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(())
+    }
+
+    async fn clear(&self, channel_id: &ChannelId) -> crate::error::Result<()> {
+        sqlx::query("DELETE FROM channel_history WHERE channel_id = ?")
+            .bind(channel_id.as_ref())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        sqlx::query("DELETE FROM channel_snapshots WHERE channel_id = ?")
+            .bind(channel_id.as_ref())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(())
+    }
+
+    async fn save_snapshot(
+        &self,
+        channel_id: &ChannelId,
+        snapshot: &ChannelSnapshot,
+    ) -> crate::error::Result<()> {
+        let json = serde_json::to_string(snapshot).map_err(|e| anyhow::anyhow!(e))?;
+
+        sqlx::query(
+            "INSERT INTO channel_snapshots (channel_id, snapshot, archived_at) \
+             VALUES (?, ?, CURRENT_TIMESTAMP) \
+             ON CONFLICT(channel_id) DO UPDATE SET \
+                 snapshot = excluded.snapshot, \
+                 archived_at = CURRENT_TIMESTAMP",
+        )
+        .bind(channel_id.as_ref())
+        .bind(&json)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(())
+    }
+
+    async fn take_snapshot(
+        &self,
+        channel_id: &ChannelId,
+    ) -> crate::error::Result<Option<ChannelSnapshot>> {
+        let row =
+            sqlx::query("DELETE FROM channel_snapshots WHERE channel_id = ? RETURNING snapshot")
+                .bind(channel_id.as_ref())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let json: String = row.try_get("snapshot").map_err(|e| anyhow::anyhow!(e))?;
+        let snapshot = serde_json::from_str(&json).map_err(|e| anyhow::anyhow!(e))?;
+        Ok(Some(snapshot))
+    }
+}
+
 /// A unified timeline item combining messages, branch runs, and worker runs.
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -598,3 +865,90 @@ pub struct WorkerDetailRow {
     pub transcript_blob: Option<Vec<u8>>,
     pub tool_calls: i64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Behind the object-safe trait, the way channels hold it.
+    async fn connect_in_memory() -> Box<dyn StoreDyn> {
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .in_memory(true)
+            .create_if_missing(true);
+        let pool = sqlx::pool::PoolOptions::<sqlx::Sqlite>::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("in-memory SQLite");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+        Box::new(SqliteHistoryStore::new(pool))
+    }
+
+    fn channel(id: &str) -> ChannelId {
+        ChannelId::from(id)
+    }
+
+    #[tokio::test]
+    async fn test_history_round_trip() {
+        let store = connect_in_memory().await;
+        let channel_id = channel("discord:1");
+        let messages = vec![
+            rig::message::Message::from("hello"),
+            rig::message::Message::assistant("hi there"),
+        ];
+
+        assert!(store.load(&channel_id).await.unwrap().is_empty());
+
+        store.save(&channel_id, &messages).await.unwrap();
+        let loaded = store.load(&channel_id).await.unwrap();
+        assert_eq!(loaded, messages);
+
+        // A later save replaces the stored list rather than appending to it.
+        let shorter = vec![rig::message::Message::from("compacted")];
+        store.save(&channel_id, &shorter).await.unwrap();
+        assert_eq!(store.load(&channel_id).await.unwrap(), shorter);
+
+        // Other channels are untouched.
+        assert!(store.load(&channel("discord:2")).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_is_taken_once() {
+        let store = connect_in_memory().await;
+        let channel_id = channel("discord:1");
+        let snapshot = ChannelSnapshot {
+            conversation_context: Some("context".into()),
+            ..Default::default()
+        };
+
+        store.save_snapshot(&channel_id, &snapshot).await.unwrap();
+        let taken = store.take_snapshot(&channel_id).await.unwrap();
+        assert_eq!(
+            taken.and_then(|snapshot| snapshot.conversation_context),
+            Some("context".to_string())
+        );
+        assert!(store.take_snapshot(&channel_id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_clear_drops_history_and_snapshot() {
+        let store = connect_in_memory().await;
+        let channel_id = channel("discord:1");
+
+        store
+            .save(&channel_id, &[rig::message::Message::from("hello")])
+            .await
+            .unwrap();
+        store
+            .save_snapshot(&channel_id, &ChannelSnapshot::default())
+            .await
+            .unwrap();
+        store.clear(&channel_id).await.unwrap();
+
+        assert!(store.load(&channel_id).await.unwrap().is_empty());
+        assert!(store.take_snapshot(&channel_id).await.unwrap().is_none());
+    }
+}
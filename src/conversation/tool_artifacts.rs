@@ -0,0 +1,88 @@
+//! Full tool results that were too large for the context window (SQLite).
+//!
+//! When a tool result is larger than `tool_output_max_tokens`, the tool server
+//! stores the whole thing here and hands the LLM a preview and the artifact
+//! id. The LLM reads the rest with the `read_artifact` tool. Artifacts are
+//! pruned after [`ARTIFACT_RETENTION_DAYS`].
+
+use sqlx::{Row as _, SqlitePool};
+
+/// How long artifacts are kept before they are pruned.
+pub const ARTIFACT_RETENTION_DAYS: i64 = 7;
+
+/// A stored tool result.
+#[derive(Debug, Clone)]
+pub struct ToolArtifact {
+    pub id: String,
+    pub tool_name: String,
+    pub content: String,
+    pub created_at: String,
+}
+
+/// Writes and reads the `tool_artifacts` table.
+#[derive(Debug, Clone)]
+pub struct ToolArtifactStore {
+    pool: SqlitePool,
+}
+
+impl ToolArtifactStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a tool result and return its artifact id.
+    ///
+    /// Unlike the audit log this waits for the write: the LLM may ask for the
+    /// artifact on its very next turn.
+    pub async fn save(&self, tool_name: &str, content: &str) -> crate::error::Result<String> {
+        let id = uuid::Uuid::new_v4().to_string();
+
+        sqlx::query("INSERT INTO tool_artifacts (id, tool_name, content) VALUES (?, ?, ?)")
+            .bind(&id)
+            .bind(tool_name)
+            .bind(content)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        self.prune();
+        Ok(id)
+    }
+
+    /// Load an artifact by id.
+    pub async fn get(&self, id: &str) -> crate::error::Result<Option<ToolArtifact>> {
+        let row = sqlx::query(
+            "SELECT id, tool_name, content, created_at FROM tool_artifacts WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(row.map(|row| ToolArtifact {
+            id: row.try_get("id").unwrap_or_default(),
+            tool_name: row.try_get("tool_name").unwrap_or_default(),
+            content: row.try_get("content").unwrap_or_default(),
+            created_at: row
+                .try_get::<chrono::DateTime<chrono::Utc>, _>("created_at")
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default(),
+        }))
+    }
+
+    /// Delete artifacts past retention. Fire-and-forget.
+    fn prune(&self) {
+        let pool = self.pool.clone();
+
+        tokio::spawn(async move {
+            if let Err(error) =
+                sqlx::query("DELETE FROM tool_artifacts WHERE created_at < datetime('now', ?)")
+                    .bind(format!("-{ARTIFACT_RETENTION_DAYS} days"))
+                    .execute(&pool)
+                    .await
+            {
+                tracing::warn!(%error, "failed to prune tool artifacts");
+            }
+        });
+    }
+}
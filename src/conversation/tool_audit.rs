@@ -0,0 +1,263 @@
+//! Append-only audit log of tool calls (SQLite).
+//!
+//! Every tool call made by a channel, branch, or worker is recorded by
+//! `SpacebotHook` with its arguments, a short result summary, how long it
+//! ran, and the tokens of the completion that requested it. Rows are never
+//! updated or deleted; operators read them through the API.
+
+use serde::Serialize;
+use sqlx::{Row as _, SqlitePool};
+
+/// Max bytes of tool arguments kept per row.
+pub const MAX_AUDIT_ARGS_BYTES: usize = 2_000;
+
+/// Max bytes of tool output kept as the result summary.
+pub const MAX_AUDIT_RESULT_BYTES: usize = 500;
+
+/// A tool call to record.
+#[derive(Debug, Clone)]
+pub struct ToolAuditEntry {
+    pub process_id: String,
+    pub process_type: String,
+    pub channel_id: Option<String>,
+    pub tool_name: String,
+    pub arguments: String,
+    pub result_summary: String,
+    /// None when the call never ran (e.g. blocked before execution).
+    pub duration_ms: Option<i64>,
+    /// Tokens of the completion that requested this call, split evenly
+    /// between the tool calls it made.
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// A recorded tool call, as returned to operators.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolAuditRow {
+    pub id: i64,
+    pub process_id: String,
+    pub process_type: String,
+    pub channel_id: Option<String>,
+    pub tool_name: String,
+    pub arguments: String,
+    pub result_summary: String,
+    pub duration_ms: Option<i64>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub created_at: String,
+}
+
+/// Filters for [`ToolAuditLog::list`]. `None` fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct ToolAuditFilter {
+    pub process_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub tool_name: Option<String>,
+}
+
+/// Writes and queries the `tool_audit_log` table.
+///
+/// Writes are fire-and-forget, same pattern as `ProcessRunLogger`.
+#[derive(Debug, Clone)]
+pub struct ToolAuditLog {
+    pool: SqlitePool,
+}
+
+impl ToolAuditLog {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Append a tool call to the log. Fire-and-forget.
+    pub fn record(&self, entry: ToolAuditEntry) {
+        let pool = self.pool.clone();
+
+        tokio::spawn(async move {
+            if let Err(error) = insert(&pool, &entry).await {
+                tracing::warn!(%error, tool_name = %entry.tool_name, "failed to write tool audit entry");
+            }
+        });
+    }
+
+    /// List recorded tool calls, newest first, with the total matching count.
+    pub async fn list(
+        &self,
+        filter: &ToolAuditFilter,
+        limit: i64,
+        offset: i64,
+    ) -> crate::error::Result<(Vec<ToolAuditRow>, i64)> {
+        const WHERE_CLAUSE: &str = "WHERE (?1 IS NULL OR process_id = ?1) \
+             AND (?2 IS NULL OR channel_id = ?2) \
+             AND (?3 IS NULL OR tool_name = ?3)";
+
+        let total: i64 = sqlx::query(&format!(
+            "SELECT COUNT(*) as total FROM tool_audit_log {WHERE_CLAUSE}"
+        ))
+        .bind(&filter.process_id)
+        .bind(&filter.channel_id)
+        .bind(&filter.tool_name)
+        .fetch_one(&self.pool)
+        .await
+        .map(|row| row.try_get("total").unwrap_or(0))
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        let rows = sqlx::query(&format!(
+            "SELECT id, process_id, process_type, channel_id, tool_name, arguments, \
+                    result_summary, duration_ms, input_tokens, output_tokens, created_at \
+             FROM tool_audit_log {WHERE_CLAUSE} \
+             ORDER BY id DESC \
+             LIMIT ?4 OFFSET ?5"
+        ))
+        .bind(&filter.process_id)
+        .bind(&filter.channel_id)
+        .bind(&filter.tool_name)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        let items = rows
+            .into_iter()
+            .map(|row| ToolAuditRow {
+                id: row.try_get("id").unwrap_or_default(),
+                process_id: row.try_get("process_id").unwrap_or_default(),
+                process_type: row.try_get("process_type").unwrap_or_default(),
+                channel_id: row.try_get("channel_id").ok().flatten(),
+                tool_name: row.try_get("tool_name").unwrap_or_default(),
+                arguments: row.try_get("arguments").unwrap_or_default(),
+                result_summary: row.try_get("result_summary").unwrap_or_default(),
+                duration_ms: row.try_get("duration_ms").ok().flatten(),
+                input_tokens: row.try_get("input_tokens").unwrap_or_default(),
+                output_tokens: row.try_get("output_tokens").unwrap_or_default(),
+                created_at: row
+                    .try_get::<chrono::DateTime<chrono::Utc>, _>("created_at")
+                    .map(|t| t.to_rfc3339())
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        Ok((items, total))
+    }
+}
+
+async fn insert(pool: &SqlitePool, entry: &ToolAuditEntry) -> sqlx::Result<()> {
+    sqlx::query(
+        "INSERT INTO tool_audit_log \
+         (process_id, process_type, channel_id, tool_name, arguments, result_summary, \
+          duration_ms, input_tokens, output_tokens) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&entry.process_id)
+    .bind(&entry.process_type)
+    .bind(&entry.channel_id)
+    .bind(&entry.tool_name)
+    .bind(&entry.arguments)
+    .bind(&entry.result_summary)
+    .bind(entry.duration_ms)
+    .bind(entry.input_tokens as i64)
+    .bind(entry.output_tokens as i64)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn connect_in_memory() -> SqlitePool {
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .in_memory(true)
+            .create_if_missing(true);
+        let pool = sqlx::pool::PoolOptions::<sqlx::Sqlite>::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("in-memory SQLite");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+        pool
+    }
+
+    fn entry(process_id: &str, tool_name: &str) -> ToolAuditEntry {
+        ToolAuditEntry {
+            process_id: process_id.into(),
+            process_type: "worker".into(),
+            channel_id: Some("discord:1".into()),
+            tool_name: tool_name.into(),
+            arguments: r#"{"command":"ls"}"#.into(),
+            result_summary: "ok".into(),
+            duration_ms: Some(12),
+            input_tokens: 100,
+            output_tokens: 20,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_is_written() {
+        let pool = connect_in_memory().await;
+        let log = ToolAuditLog::new(pool);
+
+        log.record(entry("worker:a", "shell"));
+
+        let mut rows = Vec::new();
+        for _ in 0..50 {
+            (rows, _) = log.list(&ToolAuditFilter::default(), 10, 0).await.unwrap();
+            if !rows.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(rows.len(), 1);
+        let row = &rows[0];
+        assert_eq!(row.process_id, "worker:a");
+        assert_eq!(row.tool_name, "shell");
+        assert_eq!(row.arguments, r#"{"command":"ls"}"#);
+        assert_eq!(row.duration_ms, Some(12));
+        assert_eq!((row.input_tokens, row.output_tokens), (100, 20));
+        assert!(!row.created_at.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_filters_and_pages_newest_first() {
+        let pool = connect_in_memory().await;
+        for (process_id, tool_name) in [
+            ("worker:a", "shell"),
+            ("worker:a", "file"),
+            ("worker:b", "shell"),
+            ("worker:a", "shell"),
+        ] {
+            insert(&pool, &entry(process_id, tool_name)).await.unwrap();
+        }
+        let log = ToolAuditLog::new(pool);
+
+        let (rows, total) = log.list(&ToolAuditFilter::default(), 10, 0).await.unwrap();
+        assert_eq!(total, 4);
+        let ids: Vec<i64> = rows.iter().map(|row| row.id).collect();
+        assert!(ids.windows(2).all(|pair| pair[0] > pair[1]));
+
+        let filter = ToolAuditFilter {
+            process_id: Some("worker:a".into()),
+            tool_name: Some("shell".into()),
+            ..Default::default()
+        };
+        let (rows, total) = log.list(&filter, 1, 0).await.unwrap();
+        assert_eq!(total, 2);
+        assert_eq!(rows.len(), 1);
+        let (next_page, _) = log.list(&filter, 1, 1).await.unwrap();
+        assert_eq!(next_page.len(), 1);
+        assert!(next_page[0].id < rows[0].id);
+
+        let filter = ToolAuditFilter {
+            channel_id: Some("slack:2".into()),
+            ..Default::default()
+        };
+        let (rows, total) = log.list(&filter, 10, 0).await.unwrap();
+        assert_eq!(total, 0);
+        assert!(rows.is_empty());
+    }
+}
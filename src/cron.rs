@@ -1,7 +1,9 @@
 //! Cron scheduler for timed tasks.
 
+pub mod expression;
 pub mod scheduler;
 pub mod store;
 
+pub use expression::CronExpression;
 pub use scheduler::{CronConfig, CronContext, Scheduler};
 pub use store::{CronExecutionEntry, CronExecutionStats, CronStore};
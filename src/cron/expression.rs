@@ -0,0 +1,262 @@
+//! Five-field cron expressions (`minute hour day-of-month month day-of-week`).
+//!
+//! Supports `*`, single values, ranges (`1-5`), steps (`*/15`, `0-30/10`),
+//! comma-separated lists, and the `@hourly`/`@daily`/`@weekly`/`@monthly`
+//! shorthands. Day-of-week accepts 0-7 with both 0 and 7 meaning Sunday.
+//! When both day fields are restricted a day matches if either does, as in
+//! classic cron.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+
+/// Upper bound on search steps in `next_after`. Each step advances at least
+/// a minute and usually a whole day or month, so this covers decades.
+const MAX_SEARCH_STEPS: usize = 100_000;
+
+/// A parsed cron expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpression {
+    source: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronExpression {
+    /// Parse a cron expression. Returns a human-readable error on failure.
+    pub fn parse(expression: &str) -> std::result::Result<Self, String> {
+        let trimmed = expression.trim();
+        let expanded = match trimmed {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields.as_slice() else {
+            return Err(format!(
+                "expected 5 fields (minute hour day-of-month month day-of-week), got {}",
+                fields.len()
+            ));
+        };
+
+        let mut days_of_week = parse_field(day_of_week, 0, 7, "day-of-week")?;
+        // Fold 7 (Sunday) onto 0.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            source: trimmed.to_string(),
+            minutes: parse_field(minute, 0, 59, "minute")?,
+            hours: parse_field(hour, 0, 23, "hour")?,
+            days_of_month: parse_field(day_of_month, 1, 31, "day-of-month")?,
+            months: parse_field(month, 1, 12, "month")?,
+            days_of_week,
+            day_of_month_restricted: *day_of_month != "*",
+            day_of_week_restricted: *day_of_week != "*",
+        })
+    }
+
+    /// The expression as originally written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// The first matching minute strictly after `after`, in `after`'s timezone.
+    ///
+    /// Local times skipped by a DST transition never match; ambiguous ones
+    /// resolve to the earlier instant. Returns `None` if nothing matches
+    /// within the search bound (e.g. `0 0 31 2 *`).
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let timezone = after.timezone();
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)?;
+        let mut candidate = start + Duration::minutes(1);
+
+        for _ in 0..MAX_SEARCH_STEPS {
+            if !bit(self.months, candidate.month()) {
+                candidate = first_of_next_month(candidate)?;
+                continue;
+            }
+            if !self.matches_day(candidate) {
+                candidate = start_of_day(candidate) + Duration::days(1);
+                continue;
+            }
+            if !bit(self.hours, candidate.hour()) {
+                candidate = candidate.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !bit(self.minutes, candidate.minute()) {
+                candidate += Duration::minutes(1);
+                continue;
+            }
+
+            if let Some(resolved) = timezone.from_local_datetime(&candidate).earliest() {
+                return Some(resolved);
+            }
+            candidate += Duration::minutes(1);
+        }
+
+        None
+    }
+
+    fn matches_day(&self, candidate: NaiveDateTime) -> bool {
+        let day_of_month = bit(self.days_of_month, candidate.day());
+        let day_of_week = bit(
+            self.days_of_week,
+            candidate.weekday().num_days_from_sunday(),
+        );
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            _ => day_of_month && day_of_week,
+        }
+    }
+}
+
+impl std::fmt::Display for CronExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn bit(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+fn start_of_day(candidate: NaiveDateTime) -> NaiveDateTime {
+    candidate.date().and_hms_opt(0, 0, 0).unwrap_or(candidate)
+}
+
+fn first_of_next_month(candidate: NaiveDateTime) -> Option<NaiveDateTime> {
+    let (year, month) = if candidate.month() == 12 {
+        (candidate.year() + 1, 1)
+    } else {
+        (candidate.year(), candidate.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)
+}
+
+/// Parse one comma-separated field into a bitmask of allowed values.
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> std::result::Result<u64, String> {
+    let mut mask = 0u64;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("invalid step '{step}' in {name} field"))?;
+                if step == 0 {
+                    return Err(format!("step must be positive in {name} field"));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                parse_value(start, min, max, name)?,
+                parse_value(end, min, max, name)?,
+            )
+        } else {
+            let value = parse_value(range, min, max, name)?;
+            // `5/15` means "from 5 to the end, every 15".
+            if step > 1 {
+                (value, max)
+            } else {
+                (value, value)
+            }
+        };
+
+        if start > end {
+            return Err(format!("range {start}-{end} is reversed in {name} field"));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+
+    Ok(mask)
+}
+
+fn parse_value(raw: &str, min: u32, max: u32, name: &str) -> std::result::Result<u32, String> {
+    let value: u32 = raw
+        .parse()
+        .map_err(|_| format!("invalid value '{raw}' in {name} field"))?;
+    if value < min || value > max {
+        return Err(format!(
+            "{name} value {value} is out of range ({min}-{max})"
+        ));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CronExpression;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        assert!(CronExpression::parse("* * * *").is_err());
+        assert!(CronExpression::parse("60 * * * *").is_err());
+        assert!(CronExpression::parse("*/0 * * * *").is_err());
+        assert!(CronExpression::parse("0 9-5 * * *").is_err());
+        assert!(CronExpression::parse("0 9 * * mon").is_err());
+    }
+
+    #[test]
+    fn finds_next_daily_and_stepped_runs() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 8, 30, 15).unwrap();
+
+        let daily = CronExpression::parse("0 8 * * *").unwrap();
+        assert_eq!(
+            daily.next_after(&now),
+            Some(Utc.with_ymd_and_hms(2026, 3, 11, 8, 0, 0).unwrap())
+        );
+
+        let quarter_hour = CronExpression::parse("*/15 * * * *").unwrap();
+        assert_eq!(
+            quarter_hour.next_after(&now),
+            Some(Utc.with_ymd_and_hms(2026, 3, 10, 8, 45, 0).unwrap())
+        );
+
+        let new_year = CronExpression::parse("@yearly").unwrap();
+        assert_eq!(
+            new_year.next_after(&now),
+            Some(Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn day_fields_combine_like_classic_cron() {
+        // 2026-03-10 is a Tuesday.
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+
+        let weekdays = CronExpression::parse("0 9 * * 1-5").unwrap();
+        assert_eq!(
+            weekdays.next_after(&now),
+            Some(Utc.with_ymd_and_hms(2026, 3, 11, 9, 0, 0).unwrap())
+        );
+
+        // Sunday as 7, or the 1st of the month, whichever comes first.
+        let either = CronExpression::parse("0 0 1 * 7").unwrap();
+        assert_eq!(
+            either.next_after(&now),
+            Some(Utc.with_ymd_and_hms(2026, 3, 15, 0, 0, 0).unwrap())
+        );
+
+        let never = CronExpression::parse("0 0 31 2 *").unwrap();
+        assert_eq!(never.next_after(&now), None);
+    }
+}
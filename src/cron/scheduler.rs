@@ -1,11 +1,14 @@
 //! Cron scheduler: timer management and execution.
 //!
-//! Each cron job gets its own tokio task that fires on an interval.
+//! Each cron job gets its own tokio task that fires on an interval or on a
+//! cron expression evaluated in the configured cron timezone.
 //! When a job fires, it creates a fresh short-lived channel,
 //! runs the job's prompt through the LLM, and delivers the result
 //! to the delivery target via the messaging system.
 
 use crate::agent::channel::Channel;
+use crate::agent::worker::Worker;
+use crate::cron::expression::CronExpression;
 use crate::cron::store::CronStore;
 use crate::error::Result;
 use crate::messaging::MessagingManager;
@@ -24,6 +27,8 @@ pub struct CronJob {
     pub id: String,
     pub prompt: String,
     pub interval_secs: u64,
+    /// When set, the job fires on this schedule instead of `interval_secs`.
+    pub schedule: Option<CronExpression>,
     pub delivery_target: BroadcastTarget,
     pub active_hours: Option<(u8, u8)>,
     pub enabled: bool,
@@ -32,6 +37,9 @@ pub struct CronJob {
     /// Maximum wall-clock seconds to wait for the job to complete.
     /// `None` uses the default of 120 seconds.
     pub timeout_secs: Option<u64>,
+    /// Run the prompt as a worker that carries state between runs and
+    /// delivers only new findings.
+    pub stateful: bool,
 }
 
 /// Serializable cron job config (for storage and TOML parsing).
@@ -41,6 +49,10 @@ pub struct CronConfig {
     pub prompt: String,
     #[serde(default = "default_interval")]
     pub interval_secs: u64,
+    /// Five-field cron expression (e.g. "0 8 * * *"). Takes precedence over
+    /// `interval_secs` when set.
+    #[serde(default)]
+    pub cron_expr: Option<String>,
     /// Delivery target in "adapter:target" format (e.g. "discord:123456789").
     pub delivery_target: String,
     pub active_hours: Option<(u8, u8)>,
@@ -51,6 +63,10 @@ pub struct CronConfig {
     /// Maximum wall-clock seconds to wait for the job to complete.
     /// `None` uses the default of 120 seconds.
     pub timeout_secs: Option<u64>,
+    /// Run the prompt as a worker that carries state between runs and
+    /// delivers only new findings.
+    #[serde(default)]
+    pub stateful: bool,
 }
 
 fn default_interval() -> u64 {
@@ -113,16 +129,20 @@ impl Scheduler {
             ))
         })?;
 
+        let schedule = parse_schedule(config.cron_expr.as_deref())?;
+
         let job = CronJob {
             id: config.id.clone(),
             prompt: config.prompt,
             interval_secs: config.interval_secs,
+            schedule,
             delivery_target,
             active_hours: config.active_hours,
             enabled: config.enabled,
             run_once: config.run_once,
             consecutive_failures: 0,
             timeout_secs: config.timeout_secs,
+            stateful: config.stateful,
         };
 
         {
@@ -134,7 +154,7 @@ impl Scheduler {
             self.start_timer(&config.id).await;
         }
 
-        tracing::info!(cron_id = %config.id, interval_secs = config.interval_secs, run_once = config.run_once, "cron job registered");
+        tracing::info!(cron_id = %config.id, interval_secs = config.interval_secs, cron_expr = ?config.cron_expr, run_once = config.run_once, "cron job registered");
         Ok(())
     }
 
@@ -159,46 +179,23 @@ impl Scheduler {
         }
 
         let handle = tokio::spawn(async move {
-            // Look up interval before entering the loop
-            let interval_secs = {
+            // Look up the schedule before entering the loop
+            let (interval_secs, schedule) = {
                 let j = jobs.read().await;
-                j.get(&job_id).map(|j| j.interval_secs).unwrap_or(3600)
+                j.get(&job_id)
+                    .map(|j| (j.interval_secs, j.schedule.clone()))
+                    .unwrap_or((3600, None))
             };
 
-            // For sub-daily intervals that divide evenly into 86400 (e.g. 1800s, 3600s, 21600s),
-            // align the first tick to the next UTC clock boundary so the job fires on clean marks
-            // like :00 and :30 rather than at an arbitrary offset from service start.
-            // Daily/weekly jobs are left on relative timing (interval_at with one interval offset)
-            // to avoid overcomplicating scheduling for jobs with active_hours constraints.
-            let first_tick = if interval_secs < 86400 && 86400 % interval_secs == 0 {
-                let now_unix = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs();
-                let remainder = now_unix % interval_secs;
-                let secs_until = if remainder == 0 {
-                    interval_secs
-                } else {
-                    interval_secs - remainder
-                };
-                tracing::info!(
-                    cron_id = %job_id,
-                    interval_secs,
-                    secs_until_first_tick = secs_until,
-                    "clock-aligned timer: first tick in {secs_until}s"
-                );
-                tokio::time::Instant::now() + Duration::from_secs(secs_until)
-            } else {
-                tokio::time::Instant::now() + Duration::from_secs(interval_secs)
+            let mut ticker = match schedule {
+                Some(expression) => CronTicker::Expression(expression),
+                None => CronTicker::Interval(interval_ticker(&job_id, interval_secs)),
             };
 
-            let mut ticker =
-                tokio::time::interval_at(first_tick, Duration::from_secs(interval_secs));
-            // Skip catch-up ticks if processing falls behind — maintain original cadence.
-            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
-
             loop {
-                ticker.tick().await;
+                if !ticker.tick(&context, &job_id).await {
+                    break;
+                }
 
                 let job = {
                     let j = jobs.read().await;
@@ -415,6 +412,8 @@ impl Scheduler {
                     ))
                 })?;
 
+            let schedule = parse_schedule(config.cron_expr.as_deref())?;
+
             {
                 let mut jobs = self.jobs.write().await;
                 jobs.insert(
@@ -423,12 +422,14 @@ impl Scheduler {
                         id: config.id.clone(),
                         prompt: config.prompt,
                         interval_secs: config.interval_secs,
+                        schedule,
                         delivery_target,
                         active_hours: config.active_hours,
                         enabled: true,
                         run_once: config.run_once,
                         consecutive_failures: 0,
                         timeout_secs: config.timeout_secs,
+                        stateful: config.stateful,
                     },
                 );
             }
@@ -474,6 +475,123 @@ impl Scheduler {
     }
 }
 
+/// Parse an optional cron expression from a stored config.
+fn parse_schedule(cron_expr: Option<&str>) -> Result<Option<CronExpression>> {
+    cron_expr
+        .filter(|expression| !expression.trim().is_empty())
+        .map(|expression| {
+            CronExpression::parse(expression).map_err(|error| {
+                crate::error::Error::Other(anyhow::anyhow!(
+                    "invalid cron expression '{expression}': {error}"
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// Drives a job's timer loop from either a fixed interval or a cron expression.
+enum CronTicker {
+    Interval(tokio::time::Interval),
+    Expression(CronExpression),
+}
+
+impl CronTicker {
+    /// Wait until the job should next fire. Returns false when a cron
+    /// expression has no future occurrence and the timer should stop.
+    async fn tick(&mut self, context: &CronContext, cron_id: &str) -> bool {
+        match self {
+            Self::Interval(ticker) => {
+                ticker.tick().await;
+                true
+            }
+            Self::Expression(expression) => {
+                let Some(delay) = next_expression_delay(context, cron_id, expression) else {
+                    tracing::warn!(
+                        cron_id,
+                        cron_expr = %expression,
+                        "cron expression has no upcoming run, stopping timer"
+                    );
+                    return false;
+                };
+                tracing::debug!(
+                    cron_id,
+                    secs_until_next = delay.as_secs(),
+                    "next cron run scheduled"
+                );
+                tokio::time::sleep(delay).await;
+                true
+            }
+        }
+    }
+}
+
+fn interval_ticker(job_id: &str, interval_secs: u64) -> tokio::time::Interval {
+    // For sub-daily intervals that divide evenly into 86400 (e.g. 1800s, 3600s, 21600s),
+    // align the first tick to the next UTC clock boundary so the job fires on clean marks
+    // like :00 and :30 rather than at an arbitrary offset from service start.
+    // Daily/weekly jobs are left on relative timing (interval_at with one interval offset)
+    // to avoid overcomplicating scheduling for jobs with active_hours constraints.
+    let first_tick = if interval_secs < 86400 && 86400 % interval_secs == 0 {
+        let now_unix = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let remainder = now_unix % interval_secs;
+        let secs_until = if remainder == 0 {
+            interval_secs
+        } else {
+            interval_secs - remainder
+        };
+        tracing::info!(
+            cron_id = %job_id,
+            interval_secs,
+            secs_until_first_tick = secs_until,
+            "clock-aligned timer: first tick in {secs_until}s"
+        );
+        tokio::time::Instant::now() + Duration::from_secs(secs_until)
+    } else {
+        tokio::time::Instant::now() + Duration::from_secs(interval_secs)
+    };
+
+    let mut ticker = tokio::time::interval_at(first_tick, Duration::from_secs(interval_secs));
+    // Skip catch-up ticks if processing falls behind — maintain original cadence.
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    ticker
+}
+
+/// Time until the expression's next occurrence, evaluated in the configured
+/// cron timezone (server local time when unset or invalid).
+fn next_expression_delay(
+    context: &CronContext,
+    cron_id: &str,
+    expression: &CronExpression,
+) -> Option<Duration> {
+    let now = chrono::Utc::now();
+    let timezone = context.deps.runtime_config.cron_timezone.load();
+    let next = match timezone.as_deref().map(|name| (name, name.parse::<Tz>())) {
+        Some((_, Ok(timezone))) => expression
+            .next_after(&now.with_timezone(&timezone))?
+            .with_timezone(&chrono::Utc),
+        Some((name, Err(error))) => {
+            tracing::warn!(
+                agent_id = %context.deps.agent_id,
+                cron_id,
+                cron_timezone = %name,
+                %error,
+                "invalid cron timezone in runtime config, falling back to system timezone"
+            );
+            expression
+                .next_after(&now.with_timezone(&chrono::Local))?
+                .with_timezone(&chrono::Utc)
+        }
+        None => expression
+            .next_after(&now.with_timezone(&chrono::Local))?
+            .with_timezone(&chrono::Utc),
+    };
+
+    Some((next - now).to_std().unwrap_or_default())
+}
+
 fn cron_timezone_label(context: &CronContext) -> String {
     let timezone = context.deps.runtime_config.cron_timezone.load();
     match timezone.as_deref() {
@@ -561,10 +679,13 @@ fn ensure_cron_dispatch_readiness(context: &CronContext, cron_id: &str) {
 #[tracing::instrument(skip(context), fields(cron_id = %job.id, agent_id = %context.deps.agent_id))]
 async fn run_cron_job(job: &CronJob, context: &CronContext) -> Result<()> {
     ensure_cron_dispatch_readiness(context, &job.id);
+    if job.stateful {
+        return run_stateful_job(job, context).await;
+    }
     let channel_id: crate::ChannelId = Arc::from(format!("cron:{}", job.id).as_str());
 
     // Create the outbound response channel to collect whatever the channel produces
-    let (response_tx, mut response_rx) = tokio::sync::mpsc::channel::<OutboundResponse>(32);
+    let (response_tx, mut response_rx) = tokio::sync::mpsc::channel::<crate::ChannelResponse>(32);
 
     // Subscribe to the agent's event bus (the channel needs this for branch/worker events)
     let event_rx = context.deps.event_tx.subscribe();
@@ -614,11 +735,15 @@ async fn run_cron_job(job: &CronJob, context: &CronContext) -> Result<()> {
     drop(channel_tx);
 
     loop {
-        match tokio::time::timeout(timeout, response_rx.recv()).await {
+        match tokio::time::timeout(timeout, response_rx.recv())
+            .await
+            .map(|response| response.map(|response| response.response))
+        {
             Ok(Some(OutboundResponse::Text(text))) => {
                 collected_text.push(text);
             }
-            Ok(Some(OutboundResponse::RichMessage { text, .. })) => {
+            Ok(Some(OutboundResponse::RichMessage { text, .. }))
+            | Ok(Some(OutboundResponse::Reply { text, .. })) => {
                 collected_text.push(text);
             }
             Ok(Some(_)) => {
@@ -639,7 +764,123 @@ async fn run_cron_job(job: &CronJob, context: &CronContext) -> Result<()> {
     // Wait for the channel task to finish (it should already be done since we dropped channel_tx)
     let _ = channel_handle.await;
 
-    let result_text = collected_text.join("\n\n");
+    deliver_result(job, context, collected_text.join("\n\n")).await
+}
+
+/// Execute a stateful cron job: run the prompt as a worker with the state
+/// saved by the last run, save the state it hands back, and deliver only
+/// what it reports as new.
+async fn run_stateful_job(job: &CronJob, context: &CronContext) -> Result<()> {
+    let rc = &context.deps.runtime_config;
+    let previous_state = context.store.load_state(&job.id).await?;
+
+    let prompt_engine = rc.prompts.load();
+    let mut system_prompt = prompt_engine.render_worker_prompt(
+        &rc.instance_dir.display().to_string(),
+        &rc.workspace_dir.display().to_string(),
+    )?;
+    match rc.skills.load().render_worker_skills(&[], &prompt_engine) {
+        Ok(skills_prompt) if !skills_prompt.is_empty() => {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(&skills_prompt);
+        }
+        Ok(_) => {}
+        Err(error) => {
+            tracing::warn!(cron_id = %job.id, %error, "failed to render worker skills listing");
+        }
+    }
+    system_prompt.push_str("\n\n");
+    system_prompt.push_str(
+        &prompt_engine.render_recurring_worker(previous_state.as_deref(), MAX_STATE_BYTES)?,
+    );
+
+    let worker = Worker::new(
+        Some(Arc::from(format!("cron:{}", job.id).as_str())),
+        &job.prompt,
+        system_prompt,
+        context.deps.clone(),
+        (**rc.browser_config.load()).clone(),
+        context.screenshot_dir.clone(),
+        rc.web_search.load().configured(),
+        context.logs_dir.clone(),
+    )
+    .with_timeout(Duration::from_secs(job.timeout_secs.unwrap_or(120)));
+
+    let result = worker.run().await?;
+    if !result.succeeded() {
+        if let Err(error) = context
+            .store
+            .log_execution(&job.id, false, Some(&result.summary))
+            .await
+        {
+            tracing::warn!(%error, "failed to log cron execution");
+        }
+        return Err(crate::error::Error::Other(anyhow::anyhow!(
+            "stateful cron worker failed: {}",
+            result.summary
+        )));
+    }
+
+    let (findings, state) = split_state(&result.output);
+    match state {
+        Some(state) => {
+            let state = truncate_state(state);
+            context.store.save_state(&job.id, state).await?;
+        }
+        None => {
+            tracing::warn!(cron_id = %job.id, "stateful cron worker returned no state, keeping the previous one");
+        }
+    }
+
+    let findings = if is_nothing_new(&findings) {
+        String::new()
+    } else {
+        findings
+    };
+    deliver_result(job, context, findings).await
+}
+
+/// Longest state a stateful job may carry between runs, in bytes.
+const MAX_STATE_BYTES: usize = 4000;
+
+/// Split a stateful worker's result into its findings and the state block
+/// at the end. The last `<state>` block wins; an unclosed one runs to the
+/// end of the output.
+fn split_state(output: &str) -> (String, Option<&str>) {
+    let Some(start) = output.rfind("<state>") else {
+        return (output.trim().to_string(), None);
+    };
+    let body = &output[start + "<state>".len()..];
+    let (state, rest) = match body.find("</state>") {
+        Some(end) => (&body[..end], &body[end + "</state>".len()..]),
+        None => (body, ""),
+    };
+    let findings = format!("{}{}", &output[..start], rest);
+    (findings.trim().to_string(), Some(state.trim()))
+}
+
+/// Cut an oversized state down to `MAX_STATE_BYTES`.
+fn truncate_state(state: &str) -> &str {
+    if state.len() <= MAX_STATE_BYTES {
+        return state;
+    }
+    tracing::warn!(
+        len = state.len(),
+        max = MAX_STATE_BYTES,
+        "stateful cron worker state too long, truncating"
+    );
+    &state[..state.floor_char_boundary(MAX_STATE_BYTES)]
+}
+
+/// Whether a stateful worker reported nothing new.
+fn is_nothing_new(findings: &str) -> bool {
+    let findings = findings.trim().trim_matches(['`', '*', '.', '"']);
+    findings.is_empty() || findings == "NOTHING_NEW"
+}
+
+/// Deliver a cron job's result to its target, skipping empty results, and
+/// log the execution.
+async fn deliver_result(job: &CronJob, context: &CronContext, result_text: String) -> Result<()> {
     let has_result = !result_text.trim().is_empty();
 
     // Deliver result to target (only if there's something to say)
@@ -692,7 +933,7 @@ async fn run_cron_job(job: &CronJob, context: &CronContext) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::hour_in_active_window;
+    use super::{hour_in_active_window, is_nothing_new, split_state};
 
     #[test]
     fn test_hour_in_active_window_non_wrapping() {
@@ -708,4 +949,22 @@ mod tests {
         assert!(hour_in_active_window(3, 22, 6));
         assert!(!hour_in_active_window(12, 22, 6));
     }
+
+    #[test]
+    fn test_split_state_separates_findings_from_state() {
+        let (findings, state) = split_state(
+            "New issue: #42 crashes on start.\n\n<state>\nreported: 41, 42\n</state>\n",
+        );
+        assert_eq!(findings, "New issue: #42 crashes on start.");
+        assert_eq!(state, Some("reported: 41, 42"));
+
+        let (findings, state) = split_state("NOTHING_NEW\n<state>reported: 41, 42");
+        assert!(is_nothing_new(&findings));
+        assert_eq!(state, Some("reported: 41, 42"));
+
+        let (findings, state) = split_state("Couldn't reach GitHub.");
+        assert_eq!(findings, "Couldn't reach GitHub.");
+        assert_eq!(state, None);
+        assert!(!is_nothing_new(&findings));
+    }
 }
@@ -24,28 +24,32 @@ impl CronStore {
 
         sqlx::query(
             r#"
-            INSERT INTO cron_jobs (id, prompt, interval_secs, delivery_target, active_start_hour, active_end_hour, enabled, run_once, timeout_secs)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO cron_jobs (id, prompt, interval_secs, cron_expr, delivery_target, active_start_hour, active_end_hour, enabled, run_once, timeout_secs, stateful)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             ON CONFLICT(id) DO UPDATE SET
                 prompt = excluded.prompt,
                 interval_secs = excluded.interval_secs,
+                cron_expr = excluded.cron_expr,
                 delivery_target = excluded.delivery_target,
                 active_start_hour = excluded.active_start_hour,
                 active_end_hour = excluded.active_end_hour,
                 enabled = excluded.enabled,
                 run_once = excluded.run_once,
-                timeout_secs = excluded.timeout_secs
+                timeout_secs = excluded.timeout_secs,
+                stateful = excluded.stateful
             "#
         )
         .bind(&config.id)
         .bind(&config.prompt)
         .bind(config.interval_secs as i64)
+        .bind(&config.cron_expr)
         .bind(&config.delivery_target)
         .bind(active_start)
         .bind(active_end)
         .bind(config.enabled as i64)
         .bind(config.run_once as i64)
         .bind(config.timeout_secs.map(|t| t as i64))
+        .bind(config.stateful as i64)
         .execute(&self.pool)
         .await
         .context("failed to save cron job")?;
@@ -57,7 +61,7 @@ impl CronStore {
     pub async fn load_all(&self) -> Result<Vec<CronConfig>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, prompt, interval_secs, delivery_target, active_start_hour, active_end_hour, enabled, run_once, timeout_secs
+            SELECT id, prompt, interval_secs, cron_expr, delivery_target, active_start_hour, active_end_hour, enabled, run_once, timeout_secs, stateful
             FROM cron_jobs
             WHERE enabled = 1
             ORDER BY created_at ASC
@@ -73,6 +77,7 @@ impl CronStore {
                 id: row.try_get("id").unwrap_or_default(),
                 prompt: row.try_get("prompt").unwrap_or_default(),
                 interval_secs: row.try_get::<i64, _>("interval_secs").unwrap_or(3600) as u64,
+                cron_expr: row.try_get::<Option<String>, _>("cron_expr").ok().flatten(),
                 delivery_target: row.try_get("delivery_target").unwrap_or_default(),
                 active_hours: {
                     let start: Option<i64> = row.try_get("active_start_hour").ok();
@@ -89,6 +94,7 @@ impl CronStore {
                     .ok()
                     .flatten()
                     .map(|t| t as u64),
+                stateful: row.try_get::<i64, _>("stateful").unwrap_or(0) != 0,
             })
             .collect();
 
@@ -118,6 +124,30 @@ impl CronStore {
         Ok(())
     }
 
+    /// Load the state a stateful job saved on its last run.
+    pub async fn load_state(&self, id: &str) -> Result<Option<String>> {
+        let state =
+            sqlx::query_scalar::<_, Option<String>>("SELECT state FROM cron_jobs WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await
+                .context("failed to load cron job state")?;
+
+        Ok(state.flatten())
+    }
+
+    /// Save the state a stateful job carries into its next run.
+    pub async fn save_state(&self, id: &str, state: &str) -> Result<()> {
+        sqlx::query("UPDATE cron_jobs SET state = ? WHERE id = ?")
+            .bind(state)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("failed to save cron job state")?;
+
+        Ok(())
+    }
+
     /// Log a cron job execution result.
     pub async fn log_execution(
         &self,
@@ -148,7 +178,7 @@ impl CronStore {
     pub async fn load_all_unfiltered(&self) -> Result<Vec<CronConfig>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, prompt, interval_secs, delivery_target, active_start_hour, active_end_hour, enabled, run_once, timeout_secs
+            SELECT id, prompt, interval_secs, cron_expr, delivery_target, active_start_hour, active_end_hour, enabled, run_once, timeout_secs, stateful
             FROM cron_jobs
             ORDER BY created_at ASC
             "#,
@@ -163,6 +193,7 @@ impl CronStore {
                 id: row.try_get("id").unwrap_or_default(),
                 prompt: row.try_get("prompt").unwrap_or_default(),
                 interval_secs: row.try_get::<i64, _>("interval_secs").unwrap_or(3600) as u64,
+                cron_expr: row.try_get::<Option<String>, _>("cron_expr").ok().flatten(),
                 delivery_target: row.try_get("delivery_target").unwrap_or_default(),
                 active_hours: {
                     let start: Option<i64> = row.try_get("active_start_hour").ok();
@@ -179,6 +210,7 @@ impl CronStore {
                     .ok()
                     .flatten()
                     .map(|t| t as u64),
+                stateful: row.try_get::<i64, _>("stateful").unwrap_or(0) != 0,
             })
             .collect();
 
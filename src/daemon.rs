@@ -459,9 +459,10 @@ fn cleanup_stale_files(paths: &DaemonPaths) {
 }
 
 /// Wait for the daemon process to exit after sending a shutdown command.
-/// Polls the PID with a short interval, times out after 10 seconds.
+/// Polls the PID with a short interval, times out after 60 seconds, which
+/// leaves room for channels to drain (`shutdown_drain_secs`).
 pub fn wait_for_exit(pid: u32) -> bool {
-    for _ in 0..100 {
+    for _ in 0..600 {
         if !is_process_alive(pid) {
             return true;
         }
@@ -1,6 +1,12 @@
 //! Database connection management and migrations.
 
+use crate::config::{StorageBackendConfig, StorageConfig};
 use crate::error::{DbError, Result};
+use crate::knowledge::qdrant::QdrantChunkStore;
+use crate::knowledge::{ChunkStoreDyn, KnowledgeIndex};
+use crate::memory::qdrant::{QdrantClient, QdrantEmbeddingStore};
+use crate::memory::sqlite::SqliteEmbeddingStore;
+use crate::memory::{EmbeddingStoreDyn, EmbeddingTable};
 use anyhow::Context as _;
 use sqlx::SqlitePool;
 use std::path::Path;
@@ -15,13 +21,29 @@ pub struct Db {
 
     /// Redb database for key-value config.
     pub redb: Arc<redb::Database>,
+
+    /// Postgres pool holding vector data instead of LanceDB, when the
+    /// `[storage]` backend is `postgres`.
+    #[cfg(feature = "postgres")]
+    pub postgres: Option<sqlx::PgPool>,
+
+    /// Qdrant client holding vector data instead of LanceDB, when the
+    /// `[storage]` backend is `qdrant`.
+    pub qdrant: Option<QdrantClient>,
+
+    /// Whether memory embeddings live in `sqlite` rather than LanceDB, when
+    /// the `[storage]` backend is `sqlite`.
+    sqlite_embeddings: bool,
+
+    /// Agent whose rows this is, for storage shared between agents.
+    agent_id: String,
 }
 
 use std::sync::Arc;
 
 impl Db {
     /// Connect to all databases and run migrations.
-    pub async fn connect(data_dir: &Path) -> Result<Self> {
+    pub async fn connect(data_dir: &Path, agent_id: &str, storage: &StorageConfig) -> Result<Self> {
         // SQLite
         let sqlite_url = format!("sqlite:{}?mode=rwc", data_dir.join("spacebot.db").display());
         let sqlite = SqlitePool::connect(&sqlite_url)
@@ -53,16 +75,100 @@ impl Db {
         let redb = redb::Database::create(&redb_path)
             .with_context(|| format!("failed to create redb at: {}", redb_path.display()))?;
 
+        // Postgres
+        #[cfg(feature = "postgres")]
+        let postgres = match &storage.backend {
+            StorageBackendConfig::Local
+            | StorageBackendConfig::Sqlite
+            | StorageBackendConfig::Qdrant(_) => None,
+            StorageBackendConfig::Postgres(postgres) => {
+                let pool = sqlx::postgres::PgPoolOptions::new()
+                    .max_connections(postgres.max_connections)
+                    .connect(&postgres.url)
+                    .await
+                    .map_err(|e| DbError::PostgresConnect(e.to_string()))?;
+                sqlx::migrate!("./migrations/postgres")
+                    .run(&pool)
+                    .await
+                    .with_context(|| "failed to run Postgres migrations")?;
+                Some(pool)
+            }
+        };
+        #[cfg(not(feature = "postgres"))]
+        if let StorageBackendConfig::Postgres(_) = &storage.backend {
+            return Err(DbError::PostgresConnect(
+                "this build doesn't include the postgres feature".into(),
+            )
+            .into());
+        }
+
+        // Qdrant
+        let qdrant = match &storage.backend {
+            StorageBackendConfig::Qdrant(qdrant) => Some(QdrantClient::new(qdrant.clone())),
+            _ => None,
+        };
+
         Ok(Self {
             sqlite,
             lance,
             redb: Arc::new(redb),
+            #[cfg(feature = "postgres")]
+            postgres,
+            qdrant,
+            sqlite_embeddings: matches!(storage.backend, StorageBackendConfig::Sqlite),
+            agent_id: agent_id.to_string(),
         })
     }
 
+    /// Open the memory embedding store on the configured backend.
+    pub async fn open_embeddings(&self) -> Result<Arc<dyn EmbeddingStoreDyn>> {
+        #[cfg(feature = "postgres")]
+        if let Some(pool) = &self.postgres {
+            return Ok(Arc::new(crate::memory::postgres::PgEmbeddingStore::new(
+                pool.clone(),
+                &self.agent_id,
+            )));
+        }
+
+        if let Some(client) = &self.qdrant {
+            return Ok(Arc::new(
+                QdrantEmbeddingStore::open(client.clone(), &self.agent_id).await?,
+            ));
+        }
+
+        if self.sqlite_embeddings {
+            return Ok(Arc::new(SqliteEmbeddingStore::new(self.sqlite.clone())));
+        }
+
+        Ok(Arc::new(EmbeddingTable::open_or_create(&self.lance).await?))
+    }
+
+    /// Open the knowledge chunk store on the configured backend.
+    pub async fn open_knowledge_chunks(&self) -> Result<Arc<dyn ChunkStoreDyn>> {
+        #[cfg(feature = "postgres")]
+        if let Some(pool) = &self.postgres {
+            return Ok(Arc::new(crate::knowledge::postgres::PgChunkStore::new(
+                pool.clone(),
+                &self.agent_id,
+            )));
+        }
+
+        if let Some(client) = &self.qdrant {
+            return Ok(Arc::new(
+                QdrantChunkStore::open(client.clone(), &self.agent_id).await?,
+            ));
+        }
+
+        Ok(Arc::new(KnowledgeIndex::open_or_create(&self.lance).await?))
+    }
+
     /// Close all database connections gracefully.
     pub async fn close(self) {
         self.sqlite.close().await;
+        #[cfg(feature = "postgres")]
+        if let Some(postgres) = self.postgres {
+            postgres.close().await;
+        }
         // LanceDB and redb close automatically when dropped
     }
 }
@@ -106,6 +106,12 @@ pub enum DbError {
     #[error("LanceDB operation failed: {0}")]
     LanceDb(String),
 
+    #[error("failed to connect to Postgres: {0}")]
+    PostgresConnect(String),
+
+    #[error("Qdrant request failed: {0}")]
+    Qdrant(String),
+
     #[error("failed to connect to redb: {0}")]
     RedbConnect(#[from] redb::Error),
 
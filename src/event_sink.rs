@@ -0,0 +1,318 @@
+//! Event sinks: POST selected process events to external webhook URLs.
+//!
+//! Each configured sink subscribes to every agent's event bus and delivers
+//! matching events as JSON, retrying with exponential backoff. This lets
+//! operators wire worker completions, timeouts, and budget alerts into
+//! paging or automation without polling the API.
+
+use crate::ProcessEvent;
+use crate::agent::worker::WorkerResultStatus;
+use crate::config::EventSinkConfig;
+
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Names accepted in a sink's `events` list: one per `ProcessEvent` variant,
+/// plus `worker_failed` for worker completions whose status is `failed`.
+pub const EVENT_NAMES: &[&str] = &[
+    "branch_started",
+    "branch_result",
+    "branch_timeout",
+    "worker_started",
+    "worker_queued",
+    "worker_status",
+    "worker_progress",
+    "worker_response",
+    "worker_attachment",
+    "worker_complete",
+    "worker_failed",
+    "tool_started",
+    "tool_completed",
+    "model_fallback",
+    "memory_saved",
+    "compaction_triggered",
+    "context_trimmed",
+    "budget_exceeded",
+    "channel_titled",
+    "status_update",
+    "worker_permission",
+    "worker_question",
+    "agent_message_sent",
+    "agent_message_received",
+    "shutdown",
+];
+
+/// Header carrying the hex HMAC-SHA256 of the body for sinks with a secret.
+pub const SIGNATURE_HEADER: &str = "X-Spacebot-Signature-256";
+
+/// Header carrying the event name, so receivers can route without parsing.
+pub const EVENT_HEADER: &str = "X-Spacebot-Event";
+
+/// Delay before the first retry; doubled for each one after.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Body POSTed to a sink.
+#[derive(Debug, Serialize)]
+pub struct EventPayload<'a> {
+    /// Most specific event name, e.g. `worker_failed` rather than
+    /// `worker_complete`.
+    pub event: &'a str,
+    pub agent_id: &'a str,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// The event itself, tagged with its variant in `type`.
+    pub data: &'a serde_json::Value,
+}
+
+/// Delivers process events to the configured sinks.
+#[derive(Clone)]
+pub struct EventSinks {
+    sinks: Arc<Vec<EventSinkConfig>>,
+    client: reqwest::Client,
+}
+
+impl EventSinks {
+    pub fn new(sinks: Vec<EventSinkConfig>) -> Self {
+        Self {
+            sinks: Arc::new(sinks),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    /// Forward an agent's events to every sink that wants them. Spawns a task
+    /// that lives as long as the agent's event bus.
+    pub fn register_agent(
+        &self,
+        agent_id: String,
+        mut event_rx: broadcast::Receiver<ProcessEvent>,
+    ) {
+        if !self
+            .sinks
+            .iter()
+            .any(|sink| sink.agents.is_empty() || sink.agents.contains(&agent_id))
+        {
+            return;
+        }
+
+        let this = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match event_rx.recv().await {
+                    Ok(event) => this.dispatch(&agent_id, &event),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(%agent_id, skipped, "event sink lagged, events dropped");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    /// Send one event to each matching sink in the background.
+    fn dispatch(&self, agent_id: &str, event: &ProcessEvent) {
+        let data = match serde_json::to_value(event) {
+            Ok(data) => data,
+            Err(error) => {
+                tracing::warn!(%error, "failed to serialize process event for sinks");
+                return;
+            }
+        };
+        let names = event_names(&data);
+        let Some(&event_name) = names.last() else {
+            return;
+        };
+
+        let mut body = None;
+        for sink in self.sinks.iter() {
+            if !sink_accepts(sink, agent_id, &names) {
+                continue;
+            }
+
+            let body = body
+                .get_or_insert_with(|| {
+                    serde_json::to_vec(&EventPayload {
+                        event: event_name,
+                        agent_id,
+                        timestamp: chrono::Utc::now(),
+                        data: &data,
+                    })
+                    .unwrap_or_default()
+                })
+                .clone();
+            let client = self.client.clone();
+            let sink = sink.clone();
+            tokio::spawn(async move {
+                deliver(&client, &sink, event_name, body).await;
+            });
+        }
+    }
+}
+
+/// Names an event matches, least specific first.
+fn event_names(data: &serde_json::Value) -> Vec<&'static str> {
+    let Some(tag) = data.get("type").and_then(|tag| tag.as_str()) else {
+        return Vec::new();
+    };
+    let Some(&name) = EVENT_NAMES.iter().find(|name| **name == tag) else {
+        return Vec::new();
+    };
+
+    let failed = serde_json::to_value(WorkerResultStatus::Failed).unwrap_or_default();
+    if name == "worker_complete" && data.pointer("/result/status") == Some(&failed) {
+        vec![name, "worker_failed"]
+    } else {
+        vec![name]
+    }
+}
+
+fn sink_accepts(sink: &EventSinkConfig, agent_id: &str, names: &[&str]) -> bool {
+    (sink.agents.is_empty() || sink.agents.iter().any(|agent| agent == agent_id))
+        && sink
+            .events
+            .iter()
+            .any(|event| event == "*" || names.contains(&event.as_str()))
+}
+
+/// Hex HMAC-SHA256 of the body, as sent in [`SIGNATURE_HEADER`].
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// POST the body, retrying network errors, 429s and 5xx responses.
+async fn deliver(client: &reqwest::Client, sink: &EventSinkConfig, event: &str, body: Vec<u8>) {
+    let signature = sink.secret.as_deref().map(|secret| sign(secret, &body));
+    let mut backoff = INITIAL_BACKOFF;
+
+    for attempt in 0..=sink.max_retries {
+        if attempt > 0 {
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+
+        let mut request = client
+            .post(&sink.url)
+            .timeout(Duration::from_secs(sink.timeout_secs))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, event)
+            .body(body.clone());
+        for (name, value) in &sink.headers {
+            request = request.header(name, value);
+        }
+        if let Some(signature) = &signature {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+
+        match request.send().await {
+            Ok(response) if response.status().is_success() => return,
+            Ok(response)
+                if !response.status().is_server_error()
+                    && response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS =>
+            {
+                tracing::warn!(
+                    url = %sink.url,
+                    event,
+                    status = %response.status(),
+                    "event sink rejected event, not retrying"
+                );
+                return;
+            }
+            Ok(response) => {
+                tracing::debug!(url = %sink.url, event, attempt, status = %response.status(), "event sink delivery failed");
+            }
+            Err(error) => {
+                tracing::debug!(url = %sink.url, event, attempt, %error, "event sink delivery failed");
+            }
+        }
+    }
+
+    tracing::warn!(
+        url = %sink.url,
+        event,
+        attempts = sink.max_retries + 1,
+        "event sink delivery failed, dropping event"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::worker::WorkerResult;
+
+    fn sink(events: &[&str], agents: &[&str]) -> EventSinkConfig {
+        EventSinkConfig {
+            url: "https://example.com/hook".into(),
+            events: events.iter().map(|event| event.to_string()).collect(),
+            agents: agents.iter().map(|agent| agent.to_string()).collect(),
+            headers: Default::default(),
+            secret: None,
+            max_retries: 0,
+            timeout_secs: 1,
+        }
+    }
+
+    fn worker_complete(result: WorkerResult) -> serde_json::Value {
+        serde_json::to_value(ProcessEvent::WorkerComplete {
+            agent_id: Arc::from("main"),
+            worker_id: uuid::Uuid::nil(),
+            channel_id: None,
+            result,
+            notify: true,
+            turn_id: None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn failed_workers_match_both_names() {
+        let failed = event_names(&worker_complete(WorkerResult::failed("boom")));
+        assert_eq!(failed, ["worker_complete", "worker_failed"]);
+
+        let succeeded = event_names(&worker_complete(WorkerResult::success("done")));
+        assert_eq!(succeeded, ["worker_complete"]);
+    }
+
+    #[test]
+    fn budget_events_are_named() {
+        let budget = serde_json::to_value(ProcessEvent::BudgetExceeded {
+            agent_id: Arc::from("main"),
+            channel_id: Arc::from("discord:1"),
+            spent_usd: 5.2,
+            budget_usd: 5.0,
+            turn_id: None,
+        })
+        .unwrap();
+        assert_eq!(event_names(&budget), ["budget_exceeded"]);
+    }
+
+    #[test]
+    fn sinks_filter_by_event_and_agent() {
+        let names = ["worker_complete", "worker_failed"];
+        assert!(sink_accepts(&sink(&["worker_failed"], &[]), "main", &names));
+        assert!(sink_accepts(&sink(&["*"], &["main"]), "main", &names));
+        assert!(!sink_accepts(&sink(&["*"], &["ops"]), "main", &names));
+        assert!(!sink_accepts(
+            &sink(&["budget_exceeded"], &[]),
+            "main",
+            &names
+        ));
+    }
+
+    #[test]
+    fn signature_is_prefixed_hex_hmac() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
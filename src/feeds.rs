@@ -0,0 +1,361 @@
+//! Feed watcher: polls the RSS and Atom feeds in `[[agents.feeds]]` and sends
+//! new entries to each feed's channel as a digest.
+//!
+//! Every feed is polled on its own `interval_secs`. Entries are keyed by their
+//! guid or id (falling back to the link, then the title) and remembered in the
+//! `feed_items` table, so an entry is only ever sent once. The first poll of a
+//! feed only records what's already there. New entries are written up by the
+//! channel model with the agent's identity, like a heartbeat, so the digest
+//! reads in the agent's voice rather than as a list of headlines. If the
+//! digest can't be written or delivered, its entries stay unseen and are
+//! retried on the next poll.
+
+mod parse;
+
+pub use parse::parse_feed;
+
+use crate::config::FeedConfig;
+use crate::error::Result;
+use crate::llm::SpacebotModel;
+use crate::messaging::target::parse_delivery_target;
+use crate::{AgentDeps, OutboundResponse, ProcessType};
+
+use anyhow::Context as _;
+use chrono::{DateTime, Utc};
+use rig::agent::AgentBuilder;
+use rig::completion::Prompt;
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// How often the loop checks whether a feed is due.
+const TICK_SECS: u64 = 60;
+
+/// Largest feed document fetched.
+const MAX_FEED_BYTES: usize = 5 * 1024 * 1024;
+
+/// Seen entries that have dropped out of their feed are forgotten after this
+/// many days.
+const SEEN_RETENTION_DAYS: i64 = 30;
+
+/// An entry in a feed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeedItem {
+    /// What the entry is remembered by once seen.
+    #[serde(skip)]
+    pub key: String,
+    pub title: String,
+    pub link: Option<String>,
+    /// Publication or update time, as `2026-10-19 14:00 UTC` when the feed's
+    /// date could be read.
+    pub published: Option<String>,
+    /// The entry's summary or content as plain text, shortened.
+    pub summary: Option<String>,
+}
+
+/// Remembers which feed entries have been seen.
+#[derive(Debug, Clone)]
+pub struct FeedStore {
+    pool: SqlitePool,
+}
+
+impl FeedStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// The keys of every entry seen in a feed. Empty for a feed that has
+    /// never been polled.
+    pub async fn seen_keys(&self, feed: &str) -> Result<HashSet<String>> {
+        let keys: Vec<String> =
+            sqlx::query_scalar("SELECT item_key FROM feed_items WHERE feed = ?")
+                .bind(feed)
+                .fetch_all(&self.pool)
+                .await
+                .context("failed to load seen feed items")?;
+
+        Ok(keys.into_iter().collect())
+    }
+
+    /// Record entries as seen at `now`, then forget entries of the feed that
+    /// haven't been listed for `SEEN_RETENTION_DAYS`.
+    pub async fn mark_seen(&self, feed: &str, keys: &[&str], now: DateTime<Utc>) -> Result<()> {
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start feed item transaction")?;
+        for key in keys {
+            sqlx::query(
+                "INSERT INTO feed_items (feed, item_key, first_seen_at, last_seen_at) \
+                 VALUES (?, ?, ?, ?) \
+                 ON CONFLICT(feed, item_key) DO UPDATE SET last_seen_at = excluded.last_seen_at",
+            )
+            .bind(feed)
+            .bind(key)
+            .bind(now.timestamp())
+            .bind(now.timestamp())
+            .execute(&mut *transaction)
+            .await
+            .context("failed to save feed item")?;
+        }
+        let cutoff = now - chrono::Duration::days(SEEN_RETENTION_DAYS);
+        sqlx::query("DELETE FROM feed_items WHERE feed = ? AND last_seen_at < ?")
+            .bind(feed)
+            .bind(cutoff.timestamp())
+            .execute(&mut *transaction)
+            .await
+            .context("failed to prune feed items")?;
+        transaction
+            .commit()
+            .await
+            .context("failed to commit feed items")?;
+
+        Ok(())
+    }
+}
+
+/// The entries of `items` not in `seen`, capped at `max_items`, and how many
+/// more new entries were left out. Feeds list their newest entries first, so
+/// the ones left out are the oldest.
+fn select_new<'a>(
+    items: &'a [FeedItem],
+    seen: &HashSet<String>,
+    max_items: usize,
+) -> (Vec<&'a FeedItem>, usize) {
+    let mut keys = HashSet::new();
+    let new: Vec<_> = items
+        .iter()
+        .filter(|item| !seen.contains(&item.key) && keys.insert(item.key.as_str()))
+        .collect();
+    let omitted = new.len().saturating_sub(max_items);
+    (new.into_iter().take(max_items).collect(), omitted)
+}
+
+/// Spawn the feed watcher loop for an agent.
+///
+/// The loop always runs and reads `[[agents.feeds]]` on every tick, so adding,
+/// removing, or retuning a feed takes effect without a restart.
+pub fn spawn_feed_loop(deps: AgentDeps) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("spacebot/", env!("CARGO_PKG_VERSION")))
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("hardcoded reqwest client config");
+        let store = FeedStore::new(deps.sqlite_pool.clone());
+        let mut last_polled: HashMap<String, Instant> = HashMap::new();
+
+        loop {
+            let feeds = deps.runtime_config.feeds.load_full();
+            last_polled.retain(|name, _| feeds.iter().any(|feed| &feed.name == name));
+            for feed in feeds.iter().filter(|feed| feed.enabled) {
+                let due = last_polled.get(&feed.name).is_none_or(|polled| {
+                    polled.elapsed() >= Duration::from_secs(feed.interval_secs)
+                });
+                if due {
+                    last_polled.insert(feed.name.clone(), Instant::now());
+                    poll_feed(&deps, &client, &store, feed).await;
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(TICK_SECS)).await;
+        }
+    })
+}
+
+/// Poll one feed and send a digest of its new entries.
+#[tracing::instrument(skip(deps, client, store, feed), fields(agent_id = %deps.agent_id, feed = %feed.name))]
+async fn poll_feed(
+    deps: &AgentDeps,
+    client: &reqwest::Client,
+    store: &FeedStore,
+    feed: &FeedConfig,
+) {
+    let items = match fetch_feed(client, &feed.url).await {
+        Ok(items) => items,
+        Err(error) => {
+            tracing::warn!(%error, url = %feed.url, "failed to fetch feed");
+            return;
+        }
+    };
+    let seen = match store.seen_keys(&feed.name).await {
+        Ok(seen) => seen,
+        Err(error) => {
+            tracing::warn!(%error, "failed to load seen feed items");
+            return;
+        }
+    };
+    let keys: Vec<&str> = items.iter().map(|item| item.key.as_str()).collect();
+
+    if seen.is_empty() {
+        match store.mark_seen(&feed.name, &keys, Utc::now()).await {
+            Ok(()) => tracing::info!(count = keys.len(), "feed seeded with its current entries"),
+            Err(error) => tracing::warn!(%error, "failed to seed feed"),
+        }
+        return;
+    }
+
+    let (new, omitted) = select_new(&items, &seen, feed.max_items);
+    if !new.is_empty() {
+        if let Err(error) = send_digest(deps, feed, &new, omitted).await {
+            tracing::warn!(%error, "failed to send feed digest, will retry next poll");
+            return;
+        }
+        tracing::info!(count = new.len(), omitted, "feed digest delivered");
+    }
+    if let Err(error) = store.mark_seen(&feed.name, &keys, Utc::now()).await {
+        tracing::warn!(%error, "failed to record seen feed items");
+    }
+}
+
+async fn fetch_feed(client: &reqwest::Client, url: &str) -> Result<Vec<FeedItem>> {
+    let response = client
+        .get(url)
+        .header(
+            reqwest::header::ACCEPT,
+            "application/rss+xml, application/atom+xml, application/xml;q=0.9, text/xml;q=0.8",
+        )
+        .send()
+        .await
+        .context("request failed")?
+        .error_for_status()
+        .context("feed returned an error status")?;
+    if response
+        .content_length()
+        .is_some_and(|length| length as usize > MAX_FEED_BYTES)
+    {
+        return Err(anyhow::anyhow!("feed is larger than {MAX_FEED_BYTES} bytes").into());
+    }
+    let body = response.bytes().await.context("failed to read feed")?;
+    if body.len() > MAX_FEED_BYTES {
+        return Err(anyhow::anyhow!("feed is larger than {MAX_FEED_BYTES} bytes").into());
+    }
+
+    Ok(parse_feed(&String::from_utf8_lossy(&body)))
+}
+
+/// Have the channel model write a digest of `items` and send it to the feed's
+/// delivery target.
+async fn send_digest(
+    deps: &AgentDeps,
+    feed: &FeedConfig,
+    items: &[&FeedItem],
+    omitted: usize,
+) -> Result<()> {
+    let target = parse_delivery_target(&feed.delivery_target)
+        .ok_or_else(|| anyhow::anyhow!("invalid delivery target '{}'", feed.delivery_target))?;
+    let messaging_manager = deps
+        .messaging_manager
+        .as_ref()
+        .context("no messaging manager")?;
+
+    let prompt_engine = deps.runtime_config.prompts.load();
+    let identity_context = {
+        let rendered = deps.runtime_config.identity.load().render();
+        if rendered.is_empty() {
+            None
+        } else {
+            Some(rendered)
+        }
+    };
+    let system_prompt = prompt_engine.render_feed_digest_prompt(identity_context.as_deref())?;
+    let items: Vec<FeedItem> = items.iter().map(|item| (*item).clone()).collect();
+    let entries = prompt_engine.render_system_feed_items(&feed.name, &feed.url, &items, omitted)?;
+
+    let routing = deps.runtime_config.routing.load();
+    let model_name = routing.resolve(ProcessType::Channel, None).to_string();
+    let model = SpacebotModel::make(&deps.llm_manager, &model_name)
+        .with_context(&*deps.agent_id, "feeds")
+        .with_routing((**routing).clone());
+
+    // No tools: the entries are untrusted text from a third-party site.
+    let agent = AgentBuilder::new(model).preamble(&system_prompt).build();
+    let digest = agent
+        .prompt(&entries)
+        .await
+        .context("digest completion failed")?;
+    let digest = digest.trim();
+    if digest.is_empty() {
+        return Err(anyhow::anyhow!("the model returned an empty digest").into());
+    }
+
+    messaging_manager
+        .broadcast(
+            &target.adapter,
+            &target.target,
+            OutboundResponse::Text(digest.to_string()),
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(key: &str) -> FeedItem {
+        FeedItem {
+            key: key.into(),
+            title: key.to_uppercase(),
+            link: Some(format!("https://example.com/{key}")),
+            published: None,
+            summary: None,
+        }
+    }
+
+    #[test]
+    fn new_items_are_capped_keeping_the_newest() {
+        let items = vec![item("d"), item("c"), item("c"), item("b"), item("a")];
+        let seen = HashSet::from(["a".to_string()]);
+
+        let (new, omitted) = select_new(&items, &seen, 2);
+        let keys: Vec<_> = new.iter().map(|item| item.key.as_str()).collect();
+        assert_eq!(keys, ["d", "c"]);
+        assert_eq!(omitted, 1);
+    }
+
+    #[tokio::test]
+    async fn seen_items_are_remembered_per_feed() {
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .in_memory(true)
+            .create_if_missing(true);
+        let pool = sqlx::pool::PoolOptions::<sqlx::Sqlite>::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("in-memory SQLite");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+        let store = FeedStore::new(pool);
+
+        let long_ago = Utc::now() - chrono::Duration::days(SEEN_RETENTION_DAYS + 1);
+        store.mark_seen("blog", &["old"], long_ago).await.unwrap();
+        store
+            .mark_seen("blog", &["a", "b"], Utc::now())
+            .await
+            .unwrap();
+
+        let seen = store.seen_keys("blog").await.unwrap();
+        assert_eq!(seen, HashSet::from(["a".to_string(), "b".to_string()]));
+        assert!(store.seen_keys("changelog").await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn digest_entries_render_with_their_fields() {
+        let engine = crate::prompts::PromptEngine::new("en").unwrap();
+        let mut first = item("a");
+        first.summary = Some("Faster builds.".into());
+        let rendered = engine
+            .render_system_feed_items("blog", "https://example.com/feed", &[first], 3)
+            .unwrap();
+
+        assert!(rendered.starts_with("1 new entry in the feed \"blog\""));
+        assert!(rendered.contains("3 older new entries were left out"));
+        assert!(rendered.contains("## A"));
+        assert!(rendered.contains("- Link: https://example.com/a"));
+        assert!(rendered.contains("Faster builds."));
+    }
+}
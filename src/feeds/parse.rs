@@ -0,0 +1,258 @@
+//! RSS 2.0, RSS 1.0, and Atom entry extraction.
+//!
+//! Feeds are scanned with regexes rather than a full XML parser, the same way
+//! the CalDAV client reads multistatus bodies. Only the handful of entry
+//! fields a digest needs are read.
+
+use super::FeedItem;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Longest summary kept per entry, in characters.
+const MAX_SUMMARY_CHARS: usize = 600;
+
+/// One `<item>` (RSS) or `<entry>` (Atom).
+static ITEM_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<(?:[\w-]+:)?(?:item|entry)\b[^>]*>(.*?)</(?:[\w-]+:)?(?:item|entry)>")
+        .expect("hardcoded regex")
+});
+
+/// An entry field, optionally namespaced, either self-closing or with a body.
+/// Group 1 is the prefix, 2 the name, 3 the attributes, 4 the body, and 5 the
+/// closing tag's name.
+static FIELD_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    const NAMES: &str =
+        "title|link|guid|id|pubDate|published|updated|date|description|summary|content|encoded";
+    Regex::new(&format!(
+        r"(?s)<(?:([\w-]+):)?({NAMES})\b([^>]*?)(?:/>|>(.*?)</(?:[\w-]+:)?({NAMES})>)"
+    ))
+    .expect("hardcoded regex")
+});
+
+static HREF_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\bhref\s*=\s*["']([^"']*)["']"#).expect("hardcoded regex"));
+
+static REL_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\brel\s*=\s*["']([^"']*)["']"#).expect("hardcoded regex"));
+
+static TAG_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<[^>]*>").expect("hardcoded regex"));
+
+static NUMERIC_ENTITY_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&#(x[0-9a-fA-F]+|[0-9]+);").expect("hardcoded regex"));
+
+/// The entries in a feed document, in document order. Entries with neither a
+/// title nor a link are skipped.
+pub fn parse_feed(body: &str) -> Vec<FeedItem> {
+    ITEM_PATTERN
+        .captures_iter(body)
+        .filter_map(|item| parse_item(item.get(1)?.as_str()))
+        .collect()
+}
+
+fn parse_item(body: &str) -> Option<FeedItem> {
+    let mut fields: HashMap<&str, String> = HashMap::new();
+    let mut link = None;
+    let mut link_is_alternate = false;
+
+    for field in FIELD_PATTERN.captures_iter(body) {
+        // Skip fields from extension namespaces, like `media:title`.
+        if !matches!(
+            field.get(1).map(|prefix| prefix.as_str()),
+            None | Some("dc" | "content" | "atom")
+        ) {
+            continue;
+        }
+        let name = field.get(2).map_or("", |name| name.as_str());
+        if field.get(5).is_some_and(|closing| closing.as_str() != name) {
+            continue;
+        }
+        let attributes = field.get(3).map_or("", |attributes| attributes.as_str());
+        let text = field.get(4).map(|text| unescape_xml(text.as_str()));
+
+        if name == "link" {
+            // Atom links are attributes; prefer the entry's own page over
+            // enclosures and related links.
+            let href = HREF_PATTERN
+                .captures(attributes)
+                .map(|href| unescape_xml(&href[1]))
+                .or(text);
+            let rel = REL_PATTERN
+                .captures(attributes)
+                .map(|rel| rel[1].to_string());
+            let is_alternate = rel.as_deref().is_none_or(|rel| rel == "alternate");
+            if let Some(href) = href.filter(|href| !href.trim().is_empty())
+                && (link.is_none() || is_alternate && !link_is_alternate)
+            {
+                link = Some(href.trim().to_string());
+                link_is_alternate = is_alternate;
+            }
+            continue;
+        }
+        if let Some(text) = text.filter(|text| !text.trim().is_empty()) {
+            fields.entry(name).or_insert(text);
+        }
+    }
+
+    let title = fields.get("title").map(|title| plain_text(title));
+    if title.is_none() && link.is_none() {
+        return None;
+    }
+    let key = ["guid", "id"]
+        .iter()
+        .find_map(|name| fields.get(name))
+        .map(|key| key.trim().to_string())
+        .or_else(|| link.clone())
+        .or_else(|| title.clone())?;
+    let published = ["pubDate", "published", "updated", "date"]
+        .iter()
+        .find_map(|name| fields.get(name))
+        .map(|date| format_date(date.trim()));
+    let summary = ["summary", "description", "encoded", "content"]
+        .iter()
+        .find_map(|name| fields.get(name))
+        .map(|summary| truncate(&plain_text(summary), MAX_SUMMARY_CHARS))
+        .filter(|summary| !summary.is_empty());
+
+    Some(FeedItem {
+        key,
+        title: title
+            .filter(|title| !title.is_empty())
+            .unwrap_or_else(|| "(untitled)".into()),
+        link,
+        published,
+        summary,
+    })
+}
+
+/// RFC 2822 and RFC 3339 dates as `2026-10-19 14:00 UTC`; anything else as
+/// given.
+fn format_date(date: &str) -> String {
+    DateTime::parse_from_rfc2822(date)
+        .or_else(|_| DateTime::parse_from_rfc3339(date))
+        .map(|date| {
+            date.with_timezone(&Utc)
+                .format("%Y-%m-%d %H:%M UTC")
+                .to_string()
+        })
+        .unwrap_or_else(|_| date.to_string())
+}
+
+/// Element text as XML text: CDATA is taken as is, entities are decoded.
+fn unescape_xml(text: &str) -> String {
+    if let Some(inner) = text
+        .trim()
+        .strip_prefix("<![CDATA[")
+        .and_then(|rest| rest.strip_suffix("]]>"))
+    {
+        return inner.to_string();
+    }
+    decode_entities(text)
+}
+
+/// HTML (or plain text) reduced to one line of plain text.
+fn plain_text(html: &str) -> String {
+    let text = decode_entities(&TAG_PATTERN.replace_all(html, " "));
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn decode_entities(text: &str) -> String {
+    let text = NUMERIC_ENTITY_PATTERN.replace_all(text, |entity: &regex::Captures| {
+        let code = &entity[1];
+        let value = match code.strip_prefix('x') {
+            Some(hex) => u32::from_str_radix(hex, 16).ok(),
+            None => code.parse().ok(),
+        };
+        value
+            .and_then(char::from_u32)
+            .map(String::from)
+            .unwrap_or_default()
+    });
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", text[..end].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rss_items_are_read_with_plain_text_summaries() {
+        let body = r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:media="http://search.yahoo.com/mrss/">
+  <channel>
+    <title>Example Blog</title>
+    <link>https://example.com/</link>
+    <item>
+      <title>Release 1.2 &amp; more</title>
+      <link>https://example.com/posts/1-2</link>
+      <guid isPermaLink="false">post-12</guid>
+      <pubDate>Mon, 19 Oct 2026 14:00:00 +0200</pubDate>
+      <media:title>Thumbnail</media:title>
+      <description>&lt;p&gt;Faster builds and &lt;b&gt;new&lt;/b&gt; docs.&lt;/p&gt;</description>
+    </item>
+    <item>
+      <title><![CDATA[Notes from the <em>Rust</em> meetup]]></title>
+      <link>https://example.com/posts/meetup</link>
+    </item>
+  </channel>
+</rss>"#;
+
+        let items = parse_feed(body);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].key, "post-12");
+        assert_eq!(items[0].title, "Release 1.2 & more");
+        assert_eq!(items[0].published.as_deref(), Some("2026-10-19 12:00 UTC"));
+        assert_eq!(
+            items[0].summary.as_deref(),
+            Some("Faster builds and new docs.")
+        );
+        assert_eq!(items[1].key, "https://example.com/posts/meetup");
+        assert_eq!(items[1].title, "Notes from the Rust meetup");
+        assert_eq!(items[1].summary, None);
+    }
+
+    #[test]
+    fn atom_entries_prefer_the_alternate_link() {
+        let body = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Changelog</title>
+  <entry>
+    <title type="html">v2.0 released</title>
+    <link rel="enclosure" href="https://example.com/v2.tar.gz"/>
+    <link rel="alternate" type="text/html" href="https://example.com/releases/v2"/>
+    <id>tag:example.com,2026:v2</id>
+    <updated>2026-10-18T09:30:00Z</updated>
+    <summary>Breaking: the config format changed.</summary>
+  </entry>
+</feed>"#;
+
+        let items = parse_feed(body);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].key, "tag:example.com,2026:v2");
+        assert_eq!(
+            items[0].link.as_deref(),
+            Some("https://example.com/releases/v2")
+        );
+        assert_eq!(items[0].published.as_deref(), Some("2026-10-18 09:30 UTC"));
+        assert_eq!(
+            items[0].summary.as_deref(),
+            Some("Breaking: the config format changed.")
+        );
+    }
+}
@@ -1,9 +1,34 @@
 //! SpacebotHook: Prompt hook for channels, branches, and workers.
 
-use crate::{AgentId, ChannelId, ProcessEvent, ProcessId, ProcessType};
+use crate::agent::approval::{APPROVAL_TIMEOUT, Approvals, Decision};
+use crate::agent::cost::CostTracker;
+use crate::agent::plan::Plan;
+use crate::conversation::tool_audit::{
+    MAX_AUDIT_ARGS_BYTES, MAX_AUDIT_RESULT_BYTES, ToolAuditEntry, ToolAuditLog,
+};
+use crate::{
+    AgentId, ChannelId, OutboundResponse, ProcessEvent, ProcessId, ProcessType, ResponseSender,
+    StatusUpdate, TurnId, WorkerId,
+};
 use rig::agent::{HookAction, PromptHook, ToolCallHookAction};
-use rig::completion::{CompletionModel, CompletionResponse, Message};
+use rig::completion::{
+    AssistantContent, CompletionModel, CompletionResponse, Message, PromptError,
+};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
 use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+
+/// Termination reason once a turn has used up its tool call repairs.
+pub const TOOL_CALL_REPAIRS_EXHAUSTED: &str = "too many malformed tool calls";
+
+/// Termination reason once the user denies a tool call.
+pub const APPROVAL_DENIED: &str = "tool call denied";
+
+/// Termination reason once an approval request goes unanswered.
+pub const APPROVAL_TIMED_OUT: &str = "approval timed out";
 
 /// Hook for observing agent behavior and sending events.
 #[derive(Clone)]
@@ -13,6 +38,130 @@ pub struct SpacebotHook {
     process_type: ProcessType,
     channel_id: Option<ChannelId>,
     event_tx: broadcast::Sender<ProcessEvent>,
+    /// Per-channel usage accounting. Only set on channel hooks.
+    cost_tracker: Option<CostTracker>,
+    /// Cooperative cancellation for branches and workers.
+    cancel_token: Option<CancellationToken>,
+    /// Wall-clock limit, after which the process stops as if cancelled.
+    /// Only set on workers spawned from a preset with a timeout.
+    deadline: Option<tokio::time::Instant>,
+    /// Tool calls started so far, shared across clones of this hook.
+    tool_steps: Arc<ToolSteps>,
+    /// Tool call audit log. Set on channel, branch, and worker hooks.
+    tool_audit: Option<ToolAudit>,
+    /// Malformed tool calls sent back for repair this turn, and the limit.
+    tool_call_repairs: Arc<ToolCallRepairs>,
+    /// Presence updates for the conversation. Only set on channel hooks.
+    presence: Option<Presence>,
+    /// Plan that destructive tool calls are recorded into instead of run.
+    /// Only set on workers spawned in plan mode.
+    plan: Option<Arc<Plan>>,
+    /// Tools whose calls wait for the user's approval. Only set on workers.
+    approval_tools: Vec<String>,
+    /// Where approval requests are filed. Only set on workers spawned by a
+    /// channel; without it, calls to `approval_tools` are refused.
+    approvals: Option<Arc<Approvals>>,
+    /// Why the process must stop after a tool call wasn't approved.
+    refusal: Arc<OnceLock<&'static str>>,
+    /// The channel turn stamped on events: the channel's current turn on
+    /// channel hooks, the spawning turn on branch and worker hooks.
+    turn_id: Arc<tokio::sync::RwLock<Option<TurnId>>>,
+}
+
+/// Where presence updates go, and the last one sent.
+#[derive(Clone)]
+struct Presence {
+    response_tx: ResponseSender,
+    last: Arc<Mutex<Option<StatusUpdate>>>,
+}
+
+/// Step numbers of a worker's tool calls, shared between the hook that
+/// counts them and the shell tool that reports output under them.
+#[derive(Debug, Default)]
+pub struct ToolSteps {
+    count: AtomicUsize,
+    /// In-flight shell calls, by internal call ID. Calls from one response
+    /// run concurrently and the tool never sees its call ID, so a command
+    /// claims the step of the matching call instead of the latest step.
+    shell_calls: Mutex<HashMap<String, ShellStep>>,
+}
+
+#[derive(Debug)]
+struct ShellStep {
+    command: String,
+    step: usize,
+    claimed: bool,
+}
+
+impl ToolSteps {
+    /// Count a new tool call and return its step.
+    fn start(&self, internal_call_id: &str, tool_name: &str, args: &str) -> usize {
+        let step = self.count.fetch_add(1, Ordering::Relaxed) + 1;
+        if tool_name == "shell"
+            && let Some(command) = serde_json::from_str::<serde_json::Value>(args)
+                .ok()
+                .and_then(|args| args.get("command")?.as_str().map(String::from))
+        {
+            self.shell_calls
+                .lock()
+                .expect("tool steps lock poisoned")
+                .insert(
+                    internal_call_id.to_string(),
+                    ShellStep {
+                        command,
+                        step,
+                        claimed: false,
+                    },
+                );
+        }
+        step
+    }
+
+    /// Forget a finished call.
+    fn finish(&self, internal_call_id: &str) {
+        self.shell_calls
+            .lock()
+            .expect("tool steps lock poisoned")
+            .remove(internal_call_id);
+    }
+
+    /// Step of the earliest unclaimed shell call running `command`. Falls
+    /// back to the latest step for commands the hook never saw.
+    pub fn claim_shell(&self, command: &str) -> usize {
+        let mut shell_calls = self.shell_calls.lock().expect("tool steps lock poisoned");
+        shell_calls
+            .values_mut()
+            .filter(|call| !call.claimed && call.command == command)
+            .min_by_key(|call| call.step)
+            .map(|call| {
+                call.claimed = true;
+                call.step
+            })
+            .unwrap_or_else(|| self.count.load(Ordering::Relaxed))
+    }
+}
+
+#[derive(Default)]
+struct ToolCallRepairs {
+    used: AtomicUsize,
+    max: AtomicUsize,
+}
+
+/// Audit log handle plus the per-call state needed to fill in an entry
+/// between `on_tool_call` and `on_tool_result`.
+#[derive(Clone)]
+struct ToolAudit {
+    log: ToolAuditLog,
+    pending: Arc<Mutex<PendingToolCalls>>,
+}
+
+#[derive(Default)]
+struct PendingToolCalls {
+    /// Start time of each in-flight tool call, by internal call ID.
+    started: HashMap<String, Instant>,
+    /// Input and output tokens of the most recent completion, split evenly
+    /// between the tool calls it requested.
+    token_share: (u64, u64),
 }
 
 impl SpacebotHook {
@@ -30,15 +179,362 @@ impl SpacebotHook {
             process_type,
             channel_id,
             event_tx,
+            cost_tracker: None,
+            cancel_token: None,
+            deadline: None,
+            tool_steps: Arc::new(ToolSteps::default()),
+            tool_audit: None,
+            tool_call_repairs: Arc::new(ToolCallRepairs::default()),
+            presence: None,
+            plan: None,
+            approval_tools: Vec::new(),
+            approvals: None,
+            refusal: Arc::new(OnceLock::new()),
+            turn_id: Arc::default(),
+        }
+    }
+
+    /// Stamp events with the channel turn held in `turn_id`, which the
+    /// channel updates as turns start.
+    pub fn with_turn_id(mut self, turn_id: Arc<tokio::sync::RwLock<Option<TurnId>>>) -> Self {
+        self.turn_id = turn_id;
+        self
+    }
+
+    /// Stamp events with `turn_id`, the channel turn that spawned this
+    /// branch or worker.
+    pub fn with_spawning_turn(self, turn_id: Option<TurnId>) -> Self {
+        self.with_turn_id(Arc::new(tokio::sync::RwLock::new(turn_id)))
+    }
+
+    async fn current_turn_id(&self) -> Option<TurnId> {
+        *self.turn_id.read().await
+    }
+
+    /// Send presence updates (thinking, running a tool, replying, waiting on
+    /// workers, idle) to the conversation through `response_tx`.
+    pub fn with_presence(mut self, response_tx: ResponseSender) -> Self {
+        self.presence = Some(Presence {
+            response_tx,
+            last: Arc::new(Mutex::new(None)),
+        });
+        self
+    }
+
+    /// Send a presence update, unless it's the one last sent. Adapters make
+    /// a platform call per update, so repeats are dropped here.
+    pub async fn send_presence(&self, status: StatusUpdate) {
+        let Some(presence) = &self.presence else {
+            return;
+        };
+        {
+            let mut last = presence.last.lock().expect("presence lock poisoned");
+            if last.as_ref() == Some(&status) {
+                return;
+            }
+            *last = Some(status.clone());
+        }
+        presence
+            .response_tx
+            .send(OutboundResponse::Status(status))
+            .await
+            .ok();
+    }
+
+    /// Allow up to `max` malformed tool calls to be sent back to the model
+    /// for repair before the run is terminated.
+    pub fn with_tool_call_repairs(self, max: usize) -> Self {
+        self.tool_call_repairs.max.store(max, Ordering::Relaxed);
+        self
+    }
+
+    /// Start a new turn on a long-lived hook: reset the repair count and
+    /// pick up the current limit.
+    pub fn begin_turn(&self, max_tool_call_repairs: usize) {
+        self.tool_call_repairs.used.store(0, Ordering::Relaxed);
+        self.tool_call_repairs
+            .max
+            .store(max_tool_call_repairs, Ordering::Relaxed);
+    }
+
+    fn tool_call_repairs_exhausted(&self) -> bool {
+        self.tool_call_repairs.used.load(Ordering::Relaxed)
+            > self.tool_call_repairs.max.load(Ordering::Relaxed)
+    }
+
+    /// Record every tool call made through this hook into `log`.
+    pub fn with_tool_audit(mut self, log: ToolAuditLog) -> Self {
+        self.tool_audit = Some(ToolAudit {
+            log,
+            pending: Arc::new(Mutex::new(PendingToolCalls::default())),
+        });
+        self
+    }
+
+    /// Append a tool call to the audit log, if one is attached.
+    fn audit_tool_call(
+        &self,
+        internal_call_id: &str,
+        tool_name: &str,
+        arguments: String,
+        result: &str,
+    ) {
+        let Some(audit) = &self.tool_audit else {
+            return;
+        };
+
+        let (started, (input_tokens, output_tokens)) = {
+            let mut pending = audit.pending.lock().expect("tool audit lock poisoned");
+            (
+                pending.started.remove(internal_call_id),
+                pending.token_share,
+            )
+        };
+
+        audit.log.record(ToolAuditEntry {
+            process_id: self.process_id.to_string(),
+            process_type: self.process_type.to_string(),
+            channel_id: self.channel_id.as_ref().map(|id| id.to_string()),
+            tool_name: tool_name.to_string(),
+            arguments,
+            result_summary: crate::tools::truncate_output(result, MAX_AUDIT_RESULT_BYTES),
+            duration_ms: started.map(|start| start.elapsed().as_millis() as i64),
+            input_tokens,
+            output_tokens,
+        });
+    }
+
+    /// Record destructive tool calls into `plan` instead of running them.
+    pub fn with_plan(mut self, plan: Arc<Plan>) -> Self {
+        self.plan = Some(plan);
+        self
+    }
+
+    /// Make calls to `tools` wait for the user's approval.
+    pub fn with_approval_tools(mut self, tools: Vec<String>) -> Self {
+        self.approval_tools = tools;
+        self
+    }
+
+    /// File approval requests with `approvals`, for the channel to ask.
+    pub fn with_approvals(mut self, approvals: Arc<Approvals>) -> Self {
+        self.approvals = Some(approvals);
+        self
+    }
+
+    /// Terminate the agent loop at the next hook point once `token` is
+    /// cancelled. Pair with [`SpacebotHook::cancellable`] to also interrupt
+    /// an in-flight completion call.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel_token = Some(token);
+        self
+    }
+
+    /// Stop the agent loop once `deadline` passes, like a cancellation with
+    /// "timed out" as the reason.
+    pub fn with_deadline(mut self, deadline: tokio::time::Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Race a prompt request against this hook's cancellation token and
+    /// deadline.
+    ///
+    /// Hook-level termination only fires between completion calls and tool
+    /// calls, which can be minutes apart on a slow model. Dropping the request
+    /// future aborts the HTTP call immediately. The cancelled result carries
+    /// an empty history; callers already treat `PromptCancelled` history as
+    /// untrustworthy.
+    pub async fn cancellable<F>(&self, request: F) -> Result<String, PromptError>
+    where
+        F: std::future::IntoFuture<Output = Result<String, PromptError>>,
+    {
+        if self.cancel_token.is_none() && self.deadline.is_none() {
+            return request.await;
+        }
+        let cancelled = async {
+            match &self.cancel_token {
+                Some(token) => token.cancelled().await,
+                None => std::future::pending().await,
+            }
+        };
+        let timed_out = async {
+            match self.deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        let reason = tokio::select! {
+            biased;
+            _ = cancelled => "cancelled",
+            _ = timed_out => "timed out",
+            result = request => return result,
+        };
+        Err(PromptError::PromptCancelled {
+            chat_history: Box::new(Vec::new()),
+            reason: reason.into(),
+        })
+    }
+
+    /// Why the process should stop at the next hook point, if it should.
+    fn stop_reason(&self) -> Option<&'static str> {
+        if let Some(refusal) = self.refusal.get() {
+            Some(refusal)
+        } else if self
+            .cancel_token
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            Some("cancelled")
+        } else if self
+            .deadline
+            .is_some_and(|deadline| deadline <= tokio::time::Instant::now())
+        {
+            Some("timed out")
+        } else {
+            None
         }
     }
 
+    /// Record completion usage into `tracker` and refuse further completion
+    /// calls once its budget is exhausted.
+    pub fn with_cost_tracker(mut self, tracker: CostTracker) -> Self {
+        self.cost_tracker = Some(tracker);
+        self
+    }
+
+    /// Ask the channel for approval of a tool call and wait for the answer.
+    /// Returns why the call must not run, or `None` once it's approved. A
+    /// call that isn't approved stops the worker at the next hook point.
+    async fn await_approval(
+        &self,
+        worker_id: WorkerId,
+        internal_call_id: &str,
+        tool_name: &str,
+        args: &str,
+    ) -> Option<String> {
+        let (Some(approvals), Some(channel_id)) = (&self.approvals, &self.channel_id) else {
+            self.audit_tool_call(
+                internal_call_id,
+                tool_name,
+                crate::tools::truncate_output(args, MAX_AUDIT_ARGS_BYTES),
+                "blocked: requires approval, and there's no conversation to ask in",
+            );
+            return Some(format!(
+                "Not run: {tool_name} requires the user's approval, and this worker has no \
+                 conversation to ask in. Don't retry it; finish by saying what you would have run."
+            ));
+        };
+
+        let (request, decision) = approvals.request(worker_id, tool_name, args);
+        tracing::info!(
+            process_id = %self.process_id,
+            tool_name = %tool_name,
+            approval_id = %request.approval_id,
+            "tool call waiting for approval"
+        );
+        self.event_tx
+            .send(ProcessEvent::ApprovalRequested {
+                agent_id: self.agent_id.clone(),
+                worker_id,
+                channel_id: channel_id.clone(),
+                approval_id: request.approval_id.clone(),
+                tool_name: tool_name.to_string(),
+                args: args.to_string(),
+                turn_id: self.current_turn_id().await,
+            })
+            .ok();
+        self.send_status(format!("waiting for approval: {tool_name}"))
+            .await;
+
+        let cancelled = async {
+            match &self.cancel_token {
+                Some(token) => token.cancelled().await,
+                None => std::future::pending().await,
+            }
+        };
+        let timed_out = async {
+            match self.deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+        let refusal = tokio::select! {
+            biased;
+            _ = cancelled => "cancelled",
+            _ = timed_out => "timed out",
+            decision = decision => match decision {
+                // A call approved after another was denied still doesn't run.
+                Ok(Decision::Approve) if self.refusal.get().is_none() => {
+                    tracing::info!(
+                        process_id = %self.process_id,
+                        approval_id = %request.approval_id,
+                        "tool call approved"
+                    );
+                    self.send_status("running").await;
+                    return None;
+                }
+                // Forgotten requests count as denied.
+                _ => APPROVAL_DENIED,
+            },
+            _ = tokio::time::sleep(APPROVAL_TIMEOUT) => APPROVAL_TIMED_OUT,
+        };
+
+        tracing::info!(
+            process_id = %self.process_id,
+            approval_id = %request.approval_id,
+            reason = refusal,
+            "tool call not approved, stopping worker"
+        );
+        approvals.forget(worker_id);
+        self.refusal.set(refusal).ok();
+        self.audit_tool_call(
+            internal_call_id,
+            tool_name,
+            crate::tools::truncate_output(args, MAX_AUDIT_ARGS_BYTES),
+            &format!("not run: {refusal}"),
+        );
+        Some(format!(
+            "Not run: {tool_name} was not approved ({refusal}). Don't retry it; the worker stops here."
+        ))
+    }
+
     /// Send a status update event.
-    pub fn send_status(&self, status: impl Into<String>) {
+    pub async fn send_status(&self, status: impl Into<String>) {
         let event = ProcessEvent::StatusUpdate {
             agent_id: self.agent_id.clone(),
             process_id: self.process_id.clone(),
             status: status.into(),
+            turn_id: self.current_turn_id().await,
+        };
+        self.event_tx.send(event).ok();
+    }
+
+    /// Steps of tool calls started through this hook. Shared so tools can
+    /// attribute their own progress reports to the step that started them.
+    pub fn tool_steps(&self) -> Arc<ToolSteps> {
+        self.tool_steps.clone()
+    }
+
+    /// Report a worker tool call as a progress step for the channel's status
+    /// block. `set_status` is skipped since it already reports its own status.
+    async fn send_worker_progress(&self, internal_call_id: &str, tool_name: &str, args: &str) {
+        let ProcessId::Worker(worker_id) = &self.process_id else {
+            return;
+        };
+        if tool_name == "set_status" {
+            return;
+        }
+
+        let step = self.tool_steps.start(internal_call_id, tool_name, args);
+        let event = ProcessEvent::WorkerProgress {
+            agent_id: self.agent_id.clone(),
+            worker_id: *worker_id,
+            channel_id: self.channel_id.clone(),
+            step,
+            detail: describe_tool_call(tool_name, args),
+            turn_id: self.current_turn_id().await,
         };
         self.event_tx.send(event).ok();
     }
@@ -135,6 +631,20 @@ impl SpacebotHook {
     }
 }
 
+/// Short description of a tool call for progress reporting, e.g.
+/// `browser: navigate`. Only the `action` argument is surfaced; other
+/// arguments can be long or sensitive.
+fn describe_tool_call(tool_name: &str, args: &str) -> String {
+    let action = serde_json::from_str::<serde_json::Value>(args)
+        .ok()
+        .and_then(|value| value.get("action")?.as_str().map(str::to_owned));
+
+    match action {
+        Some(action) => format!("{tool_name}: {action}"),
+        None => tool_name.to_string(),
+    }
+}
+
 // Timer map for tool call duration measurement. Entries are inserted in
 // on_tool_call and removed in on_tool_result. If the agent terminates between
 // the two hooks (e.g. leak detection), orphaned entries stay in the map.
@@ -149,10 +659,43 @@ where
     M: CompletionModel,
 {
     async fn on_completion_call(&self, _prompt: &Message, _history: &[Message]) -> HookAction {
+        if let Some(reason) = self.stop_reason() {
+            return HookAction::Terminate {
+                reason: reason.into(),
+            };
+        }
+
+        if self.tool_call_repairs_exhausted() {
+            tracing::warn!(
+                process_id = %self.process_id,
+                "model kept sending malformed tool calls, terminating"
+            );
+            return HookAction::Terminate {
+                reason: TOOL_CALL_REPAIRS_EXHAUSTED.into(),
+            };
+        }
+
+        // A turn can span many completion calls; stop mid-turn rather than
+        // overrunning the budget by a whole agentic loop.
+        if let Some(tracker) = &self.cost_tracker
+            && tracker.budget_exceeded()
+        {
+            tracing::warn!(
+                process_id = %self.process_id,
+                "channel budget exhausted, terminating turn"
+            );
+            return HookAction::Terminate {
+                reason: "channel budget exhausted".into(),
+            };
+        }
+
+        self.send_presence(StatusUpdate::Thinking).await;
+
         // Log the completion call but don't block it
         tracing::debug!(
             process_id = %self.process_id,
             process_type = %self.process_type,
+            turn_id = ?self.current_turn_id().await,
             "completion call started"
         );
 
@@ -162,10 +705,38 @@ where
     async fn on_completion_response(
         &self,
         _prompt: &Message,
-        _response: &CompletionResponse<M::Response>,
+        response: &CompletionResponse<M::Response>,
     ) -> HookAction {
+        if let Some(tracker) = &self.cost_tracker {
+            let usage = &response.usage;
+            tracker.record(
+                usage.input_tokens,
+                usage.output_tokens,
+                usage.cached_input_tokens,
+            );
+        }
+
+        if let Some(audit) = &self.tool_audit {
+            let tool_calls = response
+                .choice
+                .iter()
+                .filter(|content| matches!(content, AssistantContent::ToolCall(_)))
+                .count()
+                .max(1) as u64;
+            let usage = &response.usage;
+            audit
+                .pending
+                .lock()
+                .expect("tool audit lock poisoned")
+                .token_share = (
+                usage.input_tokens / tool_calls,
+                usage.output_tokens / tool_calls,
+            );
+        }
+
         tracing::debug!(
             process_id = %self.process_id,
+            turn_id = ?self.current_turn_id().await,
             "completion response received"
         );
 
@@ -176,7 +747,7 @@ where
         &self,
         tool_name: &str,
         _tool_call_id: Option<String>,
-        _internal_call_id: &str,
+        internal_call_id: &str,
         args: &str,
     ) -> ToolCallHookAction {
         // Scan tool arguments for secrets before execution
@@ -187,11 +758,88 @@ where
                 leak_prefix = %&leak[..leak.len().min(8)],
                 "secret leak detected in tool arguments, blocking call"
             );
+            self.audit_tool_call(
+                internal_call_id,
+                tool_name,
+                "[redacted: contained a secret]".into(),
+                "blocked: arguments contained a secret",
+            );
             return ToolCallHookAction::Skip {
                 reason: "Tool call blocked: arguments contained a secret.".into(),
             };
         }
 
+        // Arguments the model layer couldn't parse. Skipping hands the error
+        // back as the tool result so the model can re-issue the call; once the
+        // repairs run out the next completion call terminates the run.
+        if let Some(error) = crate::llm::model::malformed_tool_arguments(args) {
+            let attempt = self.tool_call_repairs.used.fetch_add(1, Ordering::Relaxed) + 1;
+            tracing::warn!(
+                process_id = %self.process_id,
+                tool_name = %tool_name,
+                attempt,
+                %error,
+                "malformed tool call arguments, asking the model to repair"
+            );
+            self.audit_tool_call(
+                internal_call_id,
+                tool_name,
+                "[malformed arguments]".into(),
+                &format!("rejected: {error}"),
+            );
+
+            #[cfg(feature = "metrics")]
+            crate::telemetry::Metrics::global()
+                .tool_call_repairs_total
+                .with_label_values(&[&*self.agent_id, tool_name])
+                .inc();
+
+            return ToolCallHookAction::Skip {
+                reason: format!(
+                    "Error: the arguments for {tool_name} were not valid JSON ({error}). \
+                     Call {tool_name} again with a single JSON object that matches its schema."
+                ),
+            };
+        }
+
+        // Serve the recorded result instead of running the tool.
+        #[cfg(feature = "replay")]
+        if let Some(result) =
+            crate::replay::current().and_then(|session| session.replay_tool_call(tool_name, args))
+        {
+            return ToolCallHookAction::Skip { reason: result };
+        }
+
+        // Plan mode: record the call for the user to approve instead.
+        if let (Some(plan), ProcessId::Worker(worker_id)) = (&self.plan, &self.process_id)
+            && plan.holds(tool_name, args)
+        {
+            let step = plan.record(*worker_id, tool_name, args);
+            self.audit_tool_call(
+                internal_call_id,
+                tool_name,
+                crate::tools::truncate_output(args, MAX_AUDIT_ARGS_BYTES),
+                &format!("held: plan step {step}"),
+            );
+            return ToolCallHookAction::Skip {
+                reason: format!(
+                    "Not run: plan mode is on, so this call was recorded as step {step} of a plan \
+                     for the user to approve. Carry on as if it succeeded, without retrying it, \
+                     and finish by summarizing what the plan would do."
+                ),
+            };
+        }
+
+        // Tools that require approval wait for the user's answer.
+        if let ProcessId::Worker(worker_id) = &self.process_id
+            && self.approval_tools.iter().any(|tool| tool == tool_name)
+            && let Some(reason) = self
+                .await_approval(*worker_id, internal_call_id, tool_name, args)
+                .await
+        {
+            return ToolCallHookAction::Skip { reason };
+        }
+
         // Send event without blocking. Truncate args to keep broadcast payloads bounded.
         let capped_args = crate::tools::truncate_output(args, 2_000);
         let event = ProcessEvent::ToolStarted {
@@ -200,8 +848,22 @@ where
             channel_id: self.channel_id.clone(),
             tool_name: tool_name.to_string(),
             args: capped_args,
+            turn_id: self.current_turn_id().await,
         };
         self.event_tx.send(event).ok();
+        self.send_worker_progress(internal_call_id, tool_name, args)
+            .await;
+        match tool_name {
+            "reply" => self.send_presence(StatusUpdate::Streaming).await,
+            // Skipping clears the typing indicator itself.
+            "skip" => {}
+            _ => {
+                self.send_presence(StatusUpdate::RunningTool {
+                    name: tool_name.to_string(),
+                })
+                .await
+            }
+        }
 
         tracing::debug!(
             process_id = %self.process_id,
@@ -209,9 +871,18 @@ where
             "tool call started"
         );
 
+        if let Some(audit) = &self.tool_audit {
+            audit
+                .pending
+                .lock()
+                .expect("tool audit lock poisoned")
+                .started
+                .insert(internal_call_id.to_string(), Instant::now());
+        }
+
         #[cfg(feature = "metrics")]
         if let Ok(mut timers) = TOOL_CALL_TIMERS.lock() {
-            timers.insert(_internal_call_id.to_string(), std::time::Instant::now());
+            timers.insert(internal_call_id.to_string(), std::time::Instant::now());
         }
 
         ToolCallHookAction::Continue
@@ -221,8 +892,8 @@ where
         &self,
         tool_name: &str,
         _tool_call_id: Option<String>,
-        _internal_call_id: &str,
-        _args: &str,
+        internal_call_id: &str,
+        args: &str,
         result: &str,
     ) -> HookAction {
         // Scan for potential leaks in tool output and terminate if found.
@@ -236,12 +907,23 @@ where
                 leak_prefix = %&leak[..leak.len().min(8)],
                 "secret leak detected in tool output, terminating agent"
             );
+            self.audit_tool_call(
+                internal_call_id,
+                tool_name,
+                crate::tools::truncate_output(args, MAX_AUDIT_ARGS_BYTES),
+                "[redacted: output contained a secret]",
+            );
             return HookAction::Terminate {
                 reason: "Tool output contained a secret. Agent terminated to prevent exfiltration."
                     .into(),
             };
         }
 
+        #[cfg(feature = "replay")]
+        if let Some(session) = crate::replay::current() {
+            session.record_tool_result(tool_name, args, result);
+        }
+
         // Cap the result stored in the broadcast event to avoid blowing up
         // event subscribers with multi-MB tool results.
         let capped_result =
@@ -252,8 +934,16 @@ where
             channel_id: self.channel_id.clone(),
             tool_name: tool_name.to_string(),
             result: capped_result,
+            turn_id: self.current_turn_id().await,
         };
         self.event_tx.send(event).ok();
+        self.tool_steps.finish(internal_call_id);
+        self.audit_tool_call(
+            internal_call_id,
+            tool_name,
+            crate::tools::truncate_output(args, MAX_AUDIT_ARGS_BYTES),
+            result,
+        );
 
         tracing::debug!(
             process_id = %self.process_id,
@@ -272,7 +962,7 @@ where
             if let Some(start) = TOOL_CALL_TIMERS
                 .lock()
                 .ok()
-                .and_then(|mut timers| timers.remove(_internal_call_id))
+                .and_then(|mut timers| timers.remove(internal_call_id))
             {
                 metrics
                     .tool_call_duration_seconds
@@ -280,6 +970,12 @@ where
             }
         }
 
+        if let Some(reason) = self.stop_reason() {
+            return HookAction::Terminate {
+                reason: reason.into(),
+            };
+        }
+
         // Channel turns should end immediately after a successful reply tool call.
         // This avoids extra post-reply LLM iterations that add latency, cost, and
         // noisy logs when providers return empty trailing responses.
@@ -292,3 +988,160 @@ where
         HookAction::Continue
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation::ToolAuditFilter;
+    use crate::testing::ScriptedModel;
+
+    async fn audited_hook() -> (SpacebotHook, ToolAuditLog) {
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .in_memory(true)
+            .create_if_missing(true);
+        let pool = sqlx::pool::PoolOptions::<sqlx::Sqlite>::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("in-memory SQLite");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+        let log = ToolAuditLog::new(pool);
+
+        let (event_tx, _event_rx) = broadcast::channel(16);
+        let hook = SpacebotHook::new(
+            Arc::from("main"),
+            ProcessId::Worker(uuid::Uuid::new_v4()),
+            ProcessType::Worker,
+            None,
+            event_tx,
+        )
+        .with_tool_audit(log.clone());
+        (hook, log)
+    }
+
+    /// Audit writes are fire-and-forget, so poll until the row lands.
+    async fn recorded(log: &ToolAuditLog) -> Vec<crate::conversation::ToolAuditRow> {
+        for _ in 0..50 {
+            let (rows, _) = log.list(&ToolAuditFilter::default(), 10, 0).await.unwrap();
+            if !rows.is_empty() {
+                return rows;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        Vec::new()
+    }
+
+    #[tokio::test]
+    async fn test_completed_tool_call_is_audited() {
+        let (hook, log) = audited_hook().await;
+        let args = r#"{"command":"ls"}"#;
+
+        let action =
+            PromptHook::<ScriptedModel>::on_tool_call(&hook, "shell", None, "call-1", args).await;
+        assert!(matches!(action, ToolCallHookAction::Continue));
+        PromptHook::<ScriptedModel>::on_tool_result(&hook, "shell", None, "call-1", args, "done")
+            .await;
+
+        let rows = recorded(&log).await;
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].tool_name, "shell");
+        assert_eq!(rows[0].arguments, args);
+        assert_eq!(rows[0].result_summary, "done");
+        assert!(rows[0].duration_ms.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_secret_arguments_are_redacted_in_audit() {
+        let (hook, log) = audited_hook().await;
+        let args = r#"{"command":"curl -H 'x-api-key: sk-ant-REDACTED'"}"#;
+
+        let action =
+            PromptHook::<ScriptedModel>::on_tool_call(&hook, "shell", None, "call-1", args).await;
+        assert!(matches!(action, ToolCallHookAction::Skip { .. }));
+
+        let rows = recorded(&log).await;
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].arguments, "[redacted: contained a secret]");
+        assert!(!rows[0].result_summary.contains("sk-ant-"));
+        assert_eq!(rows[0].duration_ms, None);
+    }
+
+    #[tokio::test]
+    async fn test_secret_output_is_redacted_in_audit() {
+        let (hook, log) = audited_hook().await;
+
+        PromptHook::<ScriptedModel>::on_tool_call(&hook, "file", None, "call-1", "{}").await;
+        let action = PromptHook::<ScriptedModel>::on_tool_result(
+            &hook,
+            "file",
+            None,
+            "call-1",
+            "{}",
+            "OPENAI_API_KEY=sk-abcdefghijklmnopqrstuvwxyz",
+        )
+        .await;
+        assert!(matches!(action, HookAction::Terminate { .. }));
+
+        let rows = recorded(&log).await;
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            rows[0].result_summary,
+            "[redacted: output contained a secret]"
+        );
+    }
+
+    #[tokio::test]
+    async fn spawned_worker_events_carry_the_parent_turn() {
+        let turn_id = uuid::Uuid::new_v4();
+        let worker_id = uuid::Uuid::new_v4();
+        let (event_tx, mut event_rx) = broadcast::channel(16);
+        let hook = SpacebotHook::new(
+            Arc::from("main"),
+            ProcessId::Worker(worker_id),
+            ProcessType::Worker,
+            Some(Arc::from("discord:1")),
+            event_tx,
+        )
+        .with_spawning_turn(Some(turn_id));
+
+        hook.send_status("running").await;
+        PromptHook::<ScriptedModel>::on_tool_call(
+            &hook,
+            "shell",
+            None,
+            "call-1",
+            r#"{"command":"ls"}"#,
+        )
+        .await;
+
+        let mut kinds = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            let event = serde_json::to_value(event).unwrap();
+            assert_eq!(event["turn_id"], turn_id.to_string(), "{event}");
+            kinds.push(event["type"].as_str().unwrap().to_string());
+        }
+        assert_eq!(kinds, ["status_update", "tool_started", "worker_progress"]);
+    }
+
+    #[test]
+    fn concurrent_shell_calls_keep_their_own_steps() {
+        let steps = ToolSteps::default();
+        steps.start("a", "shell", r#"{"command":"cargo build"}"#);
+        steps.start("b", "file", r#"{"path":"Cargo.toml"}"#);
+        steps.start("c", "shell", r#"{"command":"cargo test"}"#);
+        steps.start("d", "shell", r#"{"command":"cargo build"}"#);
+
+        // Commands start in whatever order the tool server runs them.
+        assert_eq!(steps.claim_shell("cargo test"), 3);
+        assert_eq!(steps.claim_shell("cargo build"), 1);
+        assert_eq!(steps.claim_shell("cargo build"), 4);
+        assert_eq!(steps.claim_shell("ls"), 4);
+
+        steps.finish("a");
+        steps.start("e", "shell", r#"{"command":"cargo build"}"#);
+        assert_eq!(steps.claim_shell("cargo build"), 5);
+    }
+}
@@ -0,0 +1,294 @@
+//! Knowledge base: retrieval over documents the user hands the agent.
+//!
+//! Memories are what the agent learned; the knowledge base is reference
+//! material kept verbatim. A document (PDF, markdown, HTML, plain text) is
+//! split into overlapping chunks, each chunk is embedded with the same model
+//! as memories, and the chunks are stored in a per-agent LanceDB table, or in
+//! Postgres with pgvector when `[storage]` says so. The document list lives in
+//! SQLite so re-ingesting unchanged content is a no-op.
+//!
+//! Documents come in through the `knowledge_ingest` tool, the
+//! `spacebot knowledge ingest` command, or the API. They're searched with
+//! `knowledge_search`, and the best matches for each incoming message can be
+//! added to the channel prompt automatically (see `KnowledgeConfig`).
+
+pub mod extract;
+pub mod index;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod qdrant;
+pub mod vector_store;
+
+pub use index::KnowledgeIndex;
+pub use vector_store::{ChunkStore, ChunkStoreDyn};
+
+use crate::agent::ingestion::content_hash;
+use crate::config::KnowledgeConfig;
+use crate::error::Result;
+use crate::memory::EmbeddingModel;
+use index::ChunkRow;
+
+use anyhow::Context as _;
+use serde::Serialize;
+use sqlx::{Row as _, SqlitePool};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Chunks embedded per model call while ingesting.
+const EMBED_BATCH_SIZE: usize = 32;
+
+/// A document in the knowledge base.
+#[derive(Debug, Clone, Serialize)]
+pub struct KnowledgeDocument {
+    pub id: String,
+    pub title: String,
+    /// Where the document came from: a file path or a name given at upload.
+    pub source: String,
+    pub chunk_count: i64,
+    pub char_count: i64,
+    pub created_at: String,
+}
+
+/// Result of ingesting a document.
+#[derive(Debug, Clone, Serialize)]
+pub struct IngestedDocument {
+    pub document: KnowledgeDocument,
+    /// The same content was already in the knowledge base; nothing was added.
+    pub already_indexed: bool,
+}
+
+/// A chunk matching a search.
+#[derive(Debug, Clone, Serialize)]
+pub struct KnowledgeHit {
+    pub document_id: String,
+    pub title: String,
+    pub chunk_index: i32,
+    pub content: String,
+    /// Cosine similarity to the query, 1.0 being identical.
+    pub score: f32,
+}
+
+/// Per-agent knowledge base. Cheap to clone.
+#[derive(Clone)]
+pub struct KnowledgeBase {
+    pool: SqlitePool,
+    chunks: Arc<dyn ChunkStoreDyn>,
+    embedding_model: Arc<EmbeddingModel>,
+}
+
+impl std::fmt::Debug for KnowledgeBase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KnowledgeBase").finish_non_exhaustive()
+    }
+}
+
+impl KnowledgeBase {
+    /// Open the knowledge base over an agent's document list and chunk store.
+    pub fn new(
+        pool: SqlitePool,
+        chunks: Arc<dyn ChunkStoreDyn>,
+        embedding_model: Arc<EmbeddingModel>,
+    ) -> Self {
+        Self {
+            pool,
+            chunks,
+            embedding_model,
+        }
+    }
+
+    /// Read, chunk, embed and index a file.
+    pub async fn ingest_file(
+        &self,
+        path: &Path,
+        config: &KnowledgeConfig,
+    ) -> Result<IngestedDocument> {
+        if !extract::is_supported(path) {
+            return Err(anyhow::anyhow!(
+                "unsupported file type: {} (supported: {})",
+                path.display(),
+                extract::SUPPORTED_EXTENSIONS.join(", ")
+            )
+            .into());
+        }
+        let text = extract::read_document(path).await?;
+        let title = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+
+        self.ingest_text(&title, &path.display().to_string(), &text, config)
+            .await
+    }
+
+    /// Chunk, embed and index text that was already extracted.
+    pub async fn ingest_text(
+        &self,
+        title: &str,
+        source: &str,
+        text: &str,
+        config: &KnowledgeConfig,
+    ) -> Result<IngestedDocument> {
+        let content_hash = content_hash(text);
+        if let Some(document) = self.find_by_hash(&content_hash).await? {
+            return Ok(IngestedDocument {
+                document,
+                already_indexed: true,
+            });
+        }
+
+        let chunks = extract::chunk_text(text, config.chunk_size, config.chunk_overlap);
+        if chunks.is_empty() {
+            return Err(anyhow::anyhow!("{title} contains no text").into());
+        }
+
+        let document_id = uuid::Uuid::new_v4().to_string();
+        for (batch_number, batch) in chunks.chunks(EMBED_BATCH_SIZE).enumerate() {
+            let embeddings = self.embedding_model.embed_many(batch.to_vec()).await?;
+            let rows = batch
+                .iter()
+                .zip(embeddings)
+                .enumerate()
+                .map(|(offset, (content, embedding))| ChunkRow {
+                    document_id: document_id.clone(),
+                    title: title.to_string(),
+                    chunk_index: (batch_number * EMBED_BATCH_SIZE + offset) as i32,
+                    content: content.clone(),
+                    embedding,
+                })
+                .collect();
+            if let Err(error) = self.chunks.add(rows).await {
+                // Don't leave a half-indexed document behind.
+                self.chunks.delete_document(&document_id).await.ok();
+                return Err(error);
+            }
+        }
+
+        let inserted = sqlx::query(
+            "INSERT INTO knowledge_documents (id, title, source, content_hash, chunk_count, char_count) \
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&document_id)
+        .bind(title)
+        .bind(source)
+        .bind(&content_hash)
+        .bind(chunks.len() as i64)
+        .bind(text.len() as i64)
+        .execute(&self.pool)
+        .await;
+        if let Err(error) = inserted {
+            self.chunks.delete_document(&document_id).await.ok();
+            return Err(anyhow::anyhow!(error).into());
+        }
+
+        tracing::info!(
+            %document_id,
+            title,
+            chunks = chunks.len(),
+            "document added to knowledge base"
+        );
+
+        let document = self
+            .get(&document_id)
+            .await?
+            .context("document missing right after insert")?;
+        Ok(IngestedDocument {
+            document,
+            already_indexed: false,
+        })
+    }
+
+    /// Chunks closest to the query, best first.
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<KnowledgeHit>> {
+        let embedding = self.embedding_model.embed_one(query).await?;
+        let matches = self.chunks.search(&embedding, limit).await?;
+
+        Ok(matches
+            .into_iter()
+            .map(|chunk| KnowledgeHit {
+                document_id: chunk.document_id,
+                title: chunk.title,
+                chunk_index: chunk.chunk_index,
+                content: chunk.content,
+                score: 1.0 - chunk.distance,
+            })
+            .collect())
+    }
+
+    /// Whether any documents have been ingested. Lets callers skip the
+    /// embedding call for agents that don't use the knowledge base.
+    pub async fn is_empty(&self) -> Result<bool> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM knowledge_documents")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+        Ok(count == 0)
+    }
+
+    /// All documents, newest first.
+    pub async fn list(&self) -> Result<Vec<KnowledgeDocument>> {
+        let rows = sqlx::query(
+            "SELECT id, title, source, chunk_count, char_count, created_at \
+             FROM knowledge_documents ORDER BY created_at DESC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(rows.iter().map(row_to_document).collect())
+    }
+
+    /// Look up a document by ID.
+    pub async fn get(&self, id: &str) -> Result<Option<KnowledgeDocument>> {
+        let row = sqlx::query(
+            "SELECT id, title, source, chunk_count, char_count, created_at \
+             FROM knowledge_documents WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(row.as_ref().map(row_to_document))
+    }
+
+    /// Remove a document and its chunks. Returns whether it existed.
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        if self.get(id).await?.is_none() {
+            return Ok(false);
+        }
+        self.chunks.delete_document(id).await?;
+        sqlx::query("DELETE FROM knowledge_documents WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+        Ok(true)
+    }
+
+    async fn find_by_hash(&self, content_hash: &str) -> Result<Option<KnowledgeDocument>> {
+        let row = sqlx::query(
+            "SELECT id, title, source, chunk_count, char_count, created_at \
+             FROM knowledge_documents WHERE content_hash = ?",
+        )
+        .bind(content_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(row.as_ref().map(row_to_document))
+    }
+}
+
+fn row_to_document(row: &sqlx::sqlite::SqliteRow) -> KnowledgeDocument {
+    KnowledgeDocument {
+        id: row.try_get("id").unwrap_or_default(),
+        title: row.try_get("title").unwrap_or_default(),
+        source: row.try_get("source").unwrap_or_default(),
+        chunk_count: row.try_get("chunk_count").unwrap_or_default(),
+        char_count: row.try_get("char_count").unwrap_or_default(),
+        created_at: row
+            .try_get::<chrono::DateTime<chrono::Utc>, _>("created_at")
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default(),
+    }
+}
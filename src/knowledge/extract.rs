@@ -0,0 +1,233 @@
+//! Text extraction and chunking for knowledge documents.
+
+use anyhow::Context as _;
+use std::path::Path;
+
+/// File extensions the knowledge base can read.
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "pdf", "md", "markdown", "txt", "rst", "org", "html", "htm", "csv", "json", "yaml", "yml",
+    "toml",
+];
+
+/// Whether a file looks like something the knowledge base can read.
+pub fn is_supported(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            SUPPORTED_EXTENSIONS
+                .iter()
+                .any(|supported| extension.eq_ignore_ascii_case(supported))
+        })
+}
+
+/// Read a document as plain text. PDFs go through the PDF extractor, HTML is
+/// reduced to its visible text, everything else is read as UTF-8.
+pub async fn read_document(path: &Path) -> anyhow::Result<String> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_lowercase);
+
+    match extension.as_deref() {
+        Some("pdf") => {
+            let bytes = tokio::fs::read(path)
+                .await
+                .with_context(|| format!("failed to read pdf file: {}", path.display()))?;
+            tokio::task::spawn_blocking(move || pdf_extract::extract_text_from_mem(&bytes))
+                .await
+                .context("pdf extraction task failed")?
+                .with_context(|| format!("failed to extract text from pdf: {}", path.display()))
+        }
+        Some("html" | "htm") => {
+            let html = tokio::fs::read_to_string(path)
+                .await
+                .with_context(|| format!("failed to read file: {}", path.display()))?;
+            Ok(html_to_text(&html))
+        }
+        _ => tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("failed to read file: {}", path.display())),
+    }
+}
+
+/// Visible text of an HTML document: scripts, styles and tags removed, block
+/// elements turned into line breaks, common entities decoded.
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len() / 2);
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = rest[1..end].trim().to_lowercase();
+        rest = &rest[end + 1..];
+
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        if !tag.starts_with('/') && matches!(name, "script" | "style" | "head") {
+            // Skip everything up to the closing tag.
+            let closing = format!("</{name}");
+            match rest.to_ascii_lowercase().find(&closing) {
+                Some(position) => {
+                    rest = &rest[position..];
+                    if let Some(close_end) = rest.find('>') {
+                        rest = &rest[close_end + 1..];
+                    }
+                }
+                None => rest = "",
+            }
+            continue;
+        }
+        if matches!(
+            name,
+            "p" | "br"
+                | "div"
+                | "li"
+                | "tr"
+                | "h1"
+                | "h2"
+                | "h3"
+                | "h4"
+                | "h5"
+                | "h6"
+                | "section"
+                | "article"
+                | "pre"
+                | "blockquote"
+                | "table"
+        ) {
+            text.push('\n');
+        }
+    }
+    text.push_str(rest);
+
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+
+    // Collapse runs of whitespace within lines and drop blank lines.
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Split text into chunks of about `chunk_size` characters, breaking between
+/// paragraphs or lines where possible. Each chunk after the first starts with
+/// the last `overlap` characters of the previous one, so a passage cut at a
+/// boundary is still whole in one of them.
+pub fn chunk_text(text: &str, chunk_size: usize, overlap: usize) -> Vec<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Vec::new();
+    }
+    let chunk_size = chunk_size.max(1);
+    let overlap = overlap.min(chunk_size / 2);
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let mut end = floor_char_boundary(text, (start + chunk_size).min(text.len()));
+        if end < text.len() {
+            // Prefer a paragraph break, then a line break, then a space, as
+            // long as it's in the second half of the chunk.
+            let window = &text[start..end];
+            let min_break = window.len() / 2;
+            if let Some(position) = ["\n\n", "\n", " "]
+                .iter()
+                .find_map(|separator| window.rfind(separator).filter(|&p| p > min_break))
+            {
+                end = start + position;
+            }
+        }
+        if end <= start {
+            end = ceil_char_boundary(text, start + 1);
+        }
+
+        let chunk = text[start..end].trim();
+        if !chunk.is_empty() {
+            chunks.push(chunk.to_string());
+        }
+        if end >= text.len() {
+            break;
+        }
+
+        let next = floor_char_boundary(text, end.saturating_sub(overlap));
+        start = if next > start { next } else { end };
+    }
+
+    chunks
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_char_boundary(text: &str, mut index: usize) -> usize {
+    while index < text.len() && !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_keeps_visible_text_only() {
+        let html = "<html><head><title>x</title></head><body><script>var a = 1;</script>\
+                    <h1>Title</h1><p>Fish &amp; chips</p><style>p{}</style><p>two</p></body></html>";
+        assert_eq!(html_to_text(html), "Title\nFish & chips\ntwo");
+    }
+
+    #[test]
+    fn chunks_overlap_and_cover_the_text() {
+        let text = (0..40)
+            .map(|i| format!("Sentence number {i}."))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let chunks = chunk_text(&text, 200, 40);
+        assert!(chunks.len() > 3);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 200));
+        assert!(chunks[0].starts_with("Sentence number 0."));
+        assert!(chunks.last().unwrap().ends_with("Sentence number 39."));
+
+        // The start of each chunk repeats the end of the previous one.
+        let tail = &chunks[0][chunks[0].len() - 10..];
+        assert!(chunks[1].contains(tail));
+    }
+
+    #[test]
+    fn short_and_multibyte_text() {
+        assert_eq!(chunk_text("  hello  ", 100, 10), vec!["hello"]);
+        assert!(chunk_text("   ", 100, 10).is_empty());
+
+        let text = "é".repeat(300);
+        let chunks = chunk_text(&text, 101, 20);
+        assert!(chunks.iter().all(|chunk| chunk.chars().all(|c| c == 'é')));
+    }
+
+    #[test]
+    fn supported_extensions() {
+        assert!(is_supported(Path::new("manual.PDF")));
+        assert!(is_supported(Path::new("notes.md")));
+        assert!(!is_supported(Path::new("image.png")));
+        assert!(!is_supported(Path::new("README")));
+    }
+}
@@ -0,0 +1,406 @@
+//! LanceDB table of document chunks and their embeddings.
+
+use crate::error::{DbError, Result};
+use crate::knowledge::vector_store::ChunkStore;
+use crate::memory::lance::VectorIndex;
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float32Type, Int32Type};
+use arrow_array::{Array, RecordBatch, RecordBatchIterator, StringArray};
+use futures::TryStreamExt;
+use std::sync::Arc;
+
+const TABLE_NAME: &str = "knowledge_chunks";
+const EMBEDDING_DIM: i32 = 384; // all-MiniLM-L6-v2 dimension, same model as memories
+
+/// A chunk ready to be indexed.
+pub struct ChunkRow {
+    pub document_id: String,
+    pub title: String,
+    pub chunk_index: i32,
+    pub content: String,
+    pub embedding: Vec<f32>,
+}
+
+/// A chunk returned by a search, with its cosine distance to the query.
+#[derive(Debug, Clone)]
+pub struct ChunkMatch {
+    pub document_id: String,
+    pub title: String,
+    pub chunk_index: i32,
+    pub content: String,
+    pub distance: f32,
+}
+
+/// LanceDB table for knowledge chunks.
+#[derive(Clone)]
+pub struct KnowledgeIndex {
+    table: lancedb::Table,
+    vector_index: VectorIndex,
+}
+
+impl KnowledgeIndex {
+    /// Open the chunk table, creating it if needed.
+    pub async fn open_or_create(connection: &lancedb::Connection) -> Result<Self> {
+        if let Ok(table) = connection.open_table(TABLE_NAME).execute().await {
+            let index = Self::new(table);
+            index.vector_index.refresh_in_background();
+            return Ok(index);
+        }
+
+        let batches = RecordBatchIterator::new(vec![].into_iter().map(Ok), Arc::new(schema()));
+        let table = connection
+            .create_table(TABLE_NAME, Box::new(batches))
+            .execute()
+            .await
+            .map_err(|e| DbError::LanceDb(e.to_string()))?;
+
+        Ok(Self::new(table))
+    }
+
+    fn new(table: lancedb::Table) -> Self {
+        let vector_index = VectorIndex::new(table.clone(), lancedb::DistanceType::Cosine);
+        Self {
+            table,
+            vector_index,
+        }
+    }
+
+    /// Add chunks in a single write.
+    pub async fn add(&self, rows: Vec<ChunkRow>) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        if let Some(row) = rows
+            .iter()
+            .find(|row| row.embedding.len() != EMBEDDING_DIM as usize)
+        {
+            return Err(DbError::LanceDb(format!(
+                "Embedding dimension mismatch: expected {}, got {}",
+                EMBEDDING_DIM,
+                row.embedding.len()
+            ))
+            .into());
+        }
+
+        let ids: Vec<String> = rows
+            .iter()
+            .map(|row| format!("{}:{}", row.document_id, row.chunk_index))
+            .collect();
+        let embeddings = arrow_array::FixedSizeListArray::from_iter_primitive::<Float32Type, _, _>(
+            rows.iter()
+                .map(|row| Some(row.embedding.iter().map(|v| Some(*v)).collect::<Vec<_>>())),
+            EMBEDDING_DIM,
+        );
+
+        let batch = RecordBatch::try_new(
+            Arc::new(schema()),
+            vec![
+                Arc::new(StringArray::from(ids)) as arrow_array::ArrayRef,
+                Arc::new(StringArray::from_iter_values(
+                    rows.iter().map(|row| row.document_id.as_str()),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    rows.iter().map(|row| row.title.as_str()),
+                )),
+                Arc::new(arrow_array::Int32Array::from_iter_values(
+                    rows.iter().map(|row| row.chunk_index),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    rows.iter().map(|row| row.content.as_str()),
+                )),
+                Arc::new(embeddings),
+            ],
+        )
+        .map_err(|e| DbError::LanceDb(e.to_string()))?;
+
+        let batches = RecordBatchIterator::new(vec![Ok(batch)], Arc::new(schema()));
+        self.table
+            .add(Box::new(batches))
+            .execute()
+            .await
+            .map_err(|e| DbError::LanceDb(e.to_string()))?;
+        self.vector_index.note_added(rows.len());
+
+        Ok(())
+    }
+
+    /// Nearest chunks to a query embedding, closest first.
+    pub async fn search(&self, query_embedding: &[f32], limit: usize) -> Result<Vec<ChunkMatch>> {
+        use lancedb::query::{ExecutableQuery, QueryBase};
+
+        let batches: Vec<RecordBatch> = self
+            .table
+            .query()
+            .nearest_to(query_embedding)
+            .map_err(|e| DbError::LanceDb(e.to_string()))?
+            .distance_type(lancedb::DistanceType::Cosine)
+            .limit(limit)
+            .execute()
+            .await
+            .map_err(|e| DbError::LanceDb(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| DbError::LanceDb(e.to_string()))?;
+
+        let mut matches = Vec::new();
+        for batch in batches {
+            let (
+                Some(document_ids),
+                Some(titles),
+                Some(chunk_indexes),
+                Some(contents),
+                Some(distances),
+            ) = (
+                batch.column_by_name("document_id"),
+                batch.column_by_name("title"),
+                batch.column_by_name("chunk_index"),
+                batch.column_by_name("content"),
+                batch.column_by_name("_distance"),
+            )
+            else {
+                continue;
+            };
+            let document_ids = document_ids.as_string::<i32>();
+            let titles = titles.as_string::<i32>();
+            let chunk_indexes = chunk_indexes.as_primitive::<Int32Type>();
+            let contents = contents.as_string::<i32>();
+            let distances = distances.as_primitive::<Float32Type>();
+
+            for i in 0..batch.num_rows() {
+                if document_ids.is_valid(i) && contents.is_valid(i) && distances.is_valid(i) {
+                    matches.push(ChunkMatch {
+                        document_id: document_ids.value(i).to_string(),
+                        title: titles.value(i).to_string(),
+                        chunk_index: chunk_indexes.value(i),
+                        content: contents.value(i).to_string(),
+                        distance: distances.value(i),
+                    });
+                }
+            }
+        }
+
+        Ok(matches)
+    }
+
+    /// Delete every chunk of a document.
+    pub async fn delete_document(&self, document_id: &str) -> Result<()> {
+        validate_document_id(document_id)?;
+        self.table
+            .delete(&format!("document_id = '{document_id}'"))
+            .await
+            .map_err(|e| DbError::LanceDb(e.to_string()))?;
+        Ok(())
+    }
+}
+
+impl ChunkStore for KnowledgeIndex {
+    async fn add(&self, rows: Vec<ChunkRow>) -> Result<()> {
+        KnowledgeIndex::add(self, rows).await
+    }
+
+    async fn search(&self, query_embedding: &[f32], limit: usize) -> Result<Vec<ChunkMatch>> {
+        KnowledgeIndex::search(self, query_embedding, limit).await
+    }
+
+    async fn delete_document(&self, document_id: &str) -> Result<()> {
+        KnowledgeIndex::delete_document(self, document_id).await
+    }
+}
+
+fn schema() -> arrow_schema::Schema {
+    use arrow_schema::{DataType, Field};
+
+    arrow_schema::Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("document_id", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("chunk_index", DataType::Int32, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new(
+            "embedding",
+            DataType::FixedSizeList(
+                Arc::new(Field::new("item", DataType::Float32, true)),
+                EMBEDDING_DIM,
+            ),
+            false,
+        ),
+    ])
+}
+
+/// Document IDs are UUIDs; anything else could inject into the delete predicate.
+fn validate_document_id(document_id: &str) -> Result<()> {
+    if uuid::Uuid::parse_str(document_id).is_err() {
+        return Err(DbError::LanceDb(format!("invalid document ID format: {document_id}")).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unit vector along one axis, so each chunk is orthogonal to the others.
+    fn axis(i: usize) -> Vec<f32> {
+        let mut embedding = vec![0.0; EMBEDDING_DIM as usize];
+        embedding[i] = 1.0;
+        embedding
+    }
+
+    fn chunk(document_id: &str, chunk_index: i32, embedding: Vec<f32>) -> ChunkRow {
+        ChunkRow {
+            document_id: document_id.to_string(),
+            title: "handbook.md".into(),
+            chunk_index,
+            content: format!("chunk {chunk_index}"),
+            embedding,
+        }
+    }
+
+    async fn open_index(dir: &std::path::Path) -> KnowledgeIndex {
+        let connection = lancedb::connect(dir.to_str().unwrap())
+            .execute()
+            .await
+            .unwrap();
+        KnowledgeIndex::open_or_create(&connection).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_search_returns_nearest_chunks_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = open_index(dir.path()).await;
+        let document_id = uuid::Uuid::new_v4().to_string();
+        index
+            .add(
+                (0..3)
+                    .map(|i| chunk(&document_id, i as i32, axis(i)))
+                    .collect(),
+            )
+            .await
+            .unwrap();
+
+        let mut query = axis(1);
+        query[2] = 0.5;
+        let matches = index.search(&query, 2).await.unwrap();
+
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].chunk_index, 1);
+        assert_eq!(matches[1].chunk_index, 2);
+        assert!(matches[0].distance < matches[1].distance);
+        assert_eq!(matches[0].document_id, document_id);
+        assert_eq!(matches[0].content, "chunk 1");
+    }
+
+    #[tokio::test]
+    async fn test_reopening_keeps_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let document_id = uuid::Uuid::new_v4().to_string();
+        open_index(dir.path())
+            .await
+            .add(vec![chunk(&document_id, 0, axis(0))])
+            .await
+            .unwrap();
+
+        let matches = open_index(dir.path())
+            .await
+            .search(&axis(0), 5)
+            .await
+            .unwrap();
+
+        assert_eq!(matches.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_delete_document_removes_only_its_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = open_index(dir.path()).await;
+        let kept = uuid::Uuid::new_v4().to_string();
+        let deleted = uuid::Uuid::new_v4().to_string();
+        index
+            .add(vec![
+                chunk(&kept, 0, axis(0)),
+                chunk(&deleted, 0, axis(1)),
+                chunk(&deleted, 1, axis(2)),
+            ])
+            .await
+            .unwrap();
+
+        index.delete_document(&deleted).await.unwrap();
+
+        let matches = index.search(&axis(1), 10).await.unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].document_id, kept);
+    }
+
+    #[tokio::test]
+    async fn test_vector_index_is_built_once_large_enough_and_persists() {
+        use crate::memory::VectorIndexStatus;
+
+        let dir = tempfile::tempdir().unwrap();
+        let index = open_index(dir.path()).await;
+        let vector_index = index.vector_index.clone().with_min_rows(300);
+        let document_id = uuid::Uuid::new_v4().to_string();
+        // Spread-out pseudo-random vectors; orthogonal axes are all equally
+        // far apart and give the graph nothing to navigate.
+        let embedding = |seed: usize| {
+            let mut state = seed as u64 * 2_654_435_761 + 1;
+            (0..EMBEDDING_DIM)
+                .map(|_| {
+                    state = state
+                        .wrapping_mul(6_364_136_223_846_793_005)
+                        .wrapping_add(1_442_695_040_888_963_407);
+                    (state >> 40) as f32 / (1u64 << 24) as f32 - 0.5
+                })
+                .collect::<Vec<f32>>()
+        };
+        let chunks = |range: std::ops::Range<usize>| {
+            range
+                .map(|i| chunk(&document_id, i as i32, embedding(i)))
+                .collect::<Vec<_>>()
+        };
+
+        index.add(chunks(0..100)).await.unwrap();
+        assert_eq!(
+            vector_index.refresh().await.unwrap(),
+            VectorIndexStatus::TooSmall
+        );
+
+        index.add(chunks(100..350)).await.unwrap();
+        assert_eq!(
+            vector_index.refresh().await.unwrap(),
+            VectorIndexStatus::Created
+        );
+        let matches = index.search(&embedding(200), 1).await.unwrap();
+        assert_eq!(matches[0].chunk_index, 200);
+
+        // The index is on disk: a reopened table updates it instead of
+        // training a new one.
+        let reopened = open_index(dir.path()).await;
+        reopened.add(chunks(350..351)).await.unwrap();
+        assert_eq!(
+            reopened
+                .vector_index
+                .clone()
+                .with_min_rows(300)
+                .refresh()
+                .await
+                .unwrap(),
+            VectorIndexStatus::Updated
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_bad_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = open_index(dir.path()).await;
+        let document_id = uuid::Uuid::new_v4().to_string();
+
+        assert!(
+            index
+                .add(vec![chunk(&document_id, 0, vec![1.0; 3])])
+                .await
+                .is_err()
+        );
+        assert!(index.delete_document("x' OR '1'='1").await.is_err());
+        assert!(index.add(Vec::new()).await.is_ok());
+    }
+}
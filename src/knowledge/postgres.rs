@@ -0,0 +1,99 @@
+//! Knowledge chunks in Postgres with pgvector.
+
+use crate::error::{DbError, Result};
+use crate::knowledge::index::{ChunkMatch, ChunkRow};
+use crate::knowledge::vector_store::ChunkStore;
+use crate::memory::postgres::vector_literal;
+
+use sqlx::PgPool;
+
+/// Knowledge chunks for one agent in the shared `knowledge_chunks` table,
+/// searched by cosine distance like the LanceDB index.
+#[derive(Clone)]
+pub struct PgChunkStore {
+    pool: PgPool,
+    agent_id: String,
+}
+
+impl PgChunkStore {
+    pub fn new(pool: PgPool, agent_id: impl Into<String>) -> Self {
+        Self {
+            pool,
+            agent_id: agent_id.into(),
+        }
+    }
+}
+
+impl ChunkStore for PgChunkStore {
+    async fn add(&self, rows: Vec<ChunkRow>) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))?;
+        for row in &rows {
+            sqlx::query(
+                "INSERT INTO knowledge_chunks \
+                 (agent_id, document_id, chunk_index, title, content, embedding) \
+                 VALUES ($1, $2, $3, $4, $5, $6::vector)",
+            )
+            .bind(&self.agent_id)
+            .bind(&row.document_id)
+            .bind(row.chunk_index)
+            .bind(&row.title)
+            .bind(&row.content)
+            .bind(vector_literal(&row.embedding))
+            .execute(&mut *transaction)
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))?;
+        }
+        transaction
+            .commit()
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn search(&self, query_embedding: &[f32], limit: usize) -> Result<Vec<ChunkMatch>> {
+        let rows: Vec<(String, String, i32, String, f32)> = sqlx::query_as(
+            "SELECT document_id, title, chunk_index, content, (embedding <=> $2::vector)::real \
+             FROM knowledge_chunks WHERE agent_id = $1 \
+             ORDER BY embedding <=> $2::vector LIMIT $3",
+        )
+        .bind(&self.agent_id)
+        .bind(vector_literal(query_embedding))
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(document_id, title, chunk_index, content, distance)| ChunkMatch {
+                    document_id,
+                    title,
+                    chunk_index,
+                    content,
+                    distance,
+                },
+            )
+            .collect())
+    }
+
+    async fn delete_document(&self, document_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM knowledge_chunks WHERE agent_id = $1 AND document_id = $2")
+            .bind(&self.agent_id)
+            .bind(document_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+}
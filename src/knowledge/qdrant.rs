@@ -0,0 +1,86 @@
+//! Knowledge chunks in Qdrant.
+
+use crate::error::Result;
+use crate::knowledge::index::{ChunkMatch, ChunkRow};
+use crate::knowledge::vector_store::ChunkStore;
+use crate::memory::qdrant::{QdrantClient, points};
+
+use serde_json::json;
+
+/// Knowledge chunks for one agent in its own `{prefix}{agent_id}_knowledge`
+/// collection, searched by cosine similarity like the LanceDB index.
+#[derive(Debug, Clone)]
+pub struct QdrantChunkStore {
+    client: QdrantClient,
+    collection: String,
+}
+
+impl QdrantChunkStore {
+    /// Open the agent's knowledge collection, creating it if needed.
+    pub async fn open(client: QdrantClient, agent_id: &str) -> Result<Self> {
+        let collection = client.collection_name(agent_id, "knowledge");
+        client
+            .ensure_collection(&collection, "Cosine", &["document_id"], None)
+            .await?;
+        Ok(Self { client, collection })
+    }
+}
+
+impl ChunkStore for QdrantChunkStore {
+    async fn add(&self, rows: Vec<ChunkRow>) -> Result<()> {
+        let points = rows
+            .into_iter()
+            .map(|row| {
+                json!({
+                    "id": uuid::Uuid::new_v4().to_string(),
+                    "vector": row.embedding,
+                    "payload": {
+                        "document_id": row.document_id,
+                        "title": row.title,
+                        "chunk_index": row.chunk_index,
+                        "content": row.content,
+                    },
+                })
+            })
+            .collect();
+        self.client.upsert(&self.collection, points).await
+    }
+
+    async fn search(&self, query_embedding: &[f32], limit: usize) -> Result<Vec<ChunkMatch>> {
+        let result = self
+            .client
+            .request(
+                reqwest::Method::POST,
+                &format!("collections/{}/points/search", self.collection),
+                Some(json!({ "vector": query_embedding, "limit": limit, "with_payload": true })),
+            )
+            .await?;
+
+        Ok(points(&result)
+            .filter_map(|point| {
+                let payload = &point["payload"];
+                Some(ChunkMatch {
+                    document_id: payload["document_id"].as_str()?.to_string(),
+                    title: payload["title"].as_str().unwrap_or_default().to_string(),
+                    chunk_index: payload["chunk_index"].as_i64().unwrap_or_default() as i32,
+                    content: payload["content"].as_str()?.to_string(),
+                    // Qdrant reports cosine similarity; callers expect distance.
+                    distance: 1.0 - point["score"].as_f64()? as f32,
+                })
+            })
+            .collect())
+    }
+
+    async fn delete_document(&self, document_id: &str) -> Result<()> {
+        self.client
+            .request(
+                reqwest::Method::POST,
+                &format!("collections/{}/points/delete?wait=true", self.collection),
+                Some(json!({
+                    "filter": { "must": [{ "key": "document_id", "match": { "value": document_id } }] },
+                })),
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+//! Storage backend for knowledge chunks.
+
+use crate::error::Result;
+use crate::knowledge::index::{ChunkMatch, ChunkRow};
+
+use std::future::Future;
+use std::pin::Pin;
+
+/// Boxed future returned by the dynamic store trait.
+type StoreFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// Document chunks and their embeddings. The document list itself stays in
+/// SQLite; this is only what search needs.
+pub trait ChunkStore: Send + Sync + 'static {
+    /// Add chunks in a single write.
+    fn add(&self, rows: Vec<ChunkRow>) -> impl Future<Output = Result<()>> + Send;
+
+    /// Nearest chunks to a query embedding by cosine distance, closest first.
+    fn search(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<ChunkMatch>>> + Send;
+
+    /// Delete every chunk of a document.
+    fn delete_document(&self, document_id: &str) -> impl Future<Output = Result<()>> + Send;
+}
+
+/// Dynamic companion to [`ChunkStore`] for holding `Arc<dyn ChunkStoreDyn>`.
+pub trait ChunkStoreDyn: Send + Sync + 'static {
+    fn add<'a>(&'a self, rows: Vec<ChunkRow>) -> StoreFuture<'a, ()>;
+
+    fn search<'a>(
+        &'a self,
+        query_embedding: &'a [f32],
+        limit: usize,
+    ) -> StoreFuture<'a, Vec<ChunkMatch>>;
+
+    fn delete_document<'a>(&'a self, document_id: &'a str) -> StoreFuture<'a, ()>;
+}
+
+impl<T: ChunkStore> ChunkStoreDyn for T {
+    fn add<'a>(&'a self, rows: Vec<ChunkRow>) -> StoreFuture<'a, ()> {
+        Box::pin(ChunkStore::add(self, rows))
+    }
+
+    fn search<'a>(
+        &'a self,
+        query_embedding: &'a [f32],
+        limit: usize,
+    ) -> StoreFuture<'a, Vec<ChunkMatch>> {
+        Box::pin(ChunkStore::search(self, query_embedding, limit))
+    }
+
+    fn delete_document<'a>(&'a self, document_id: &'a str) -> StoreFuture<'a, ()> {
+        Box::pin(ChunkStore::delete_document(self, document_id))
+    }
+}
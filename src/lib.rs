@@ -3,28 +3,41 @@
 pub mod agent;
 pub mod api;
 pub mod auth;
+pub mod calendar;
 pub mod config;
 pub mod conversation;
 pub mod cron;
 pub mod daemon;
 pub mod db;
 pub mod error;
+pub mod event_sink;
+pub mod feeds;
 pub mod hooks;
 pub mod identity;
+pub mod knowledge;
 pub mod links;
 pub mod llm;
 pub mod mcp;
+pub mod media;
 pub mod memory;
 pub mod messaging;
+pub mod middleware;
 pub mod openai_auth;
 pub mod opencode;
+pub mod profiles;
 pub mod prompts;
+pub mod redaction;
+pub mod reminders;
+#[cfg(feature = "replay")]
+pub mod replay;
 pub mod sandbox;
 pub mod secrets;
 pub mod settings;
 pub mod skills;
 #[cfg(feature = "metrics")]
 pub mod telemetry;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod tools;
 pub mod update;
 
@@ -53,8 +66,16 @@ pub type WorkerId = uuid::Uuid;
 /// Branch identifier type.
 pub type BranchId = uuid::Uuid;
 
+/// Channel turn identifier type. Recorded on the turn's span and carried by
+/// its events and replies, so a reported reply can be traced to its turn.
+pub type TurnId = uuid::Uuid;
+
+/// Metadata key that outbound delivery sets to the ID of the turn that
+/// produced a response.
+pub const TURN_ID_METADATA_KEY: &str = "turn_id";
+
 /// Process identifier type (union of channel, worker, branch IDs).
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum ProcessId {
     Channel(ChannelId),
     Worker(WorkerId),
@@ -80,6 +101,7 @@ pub enum ProcessType {
     Worker,
     Compactor,
     Cortex,
+    Heartbeat,
 }
 
 impl std::fmt::Display for ProcessType {
@@ -90,6 +112,7 @@ impl std::fmt::Display for ProcessType {
             ProcessType::Worker => write!(f, "worker"),
             ProcessType::Compactor => write!(f, "compactor"),
             ProcessType::Cortex => write!(f, "cortex"),
+            ProcessType::Heartbeat => write!(f, "heartbeat"),
         }
     }
 }
@@ -104,12 +127,35 @@ pub enum ProcessEvent {
         channel_id: ChannelId,
         description: String,
         reply_to_message_id: Option<u64>,
+        /// The channel turn that spawned the branch.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
     },
     BranchResult {
         agent_id: AgentId,
         branch_id: BranchId,
         channel_id: ChannelId,
         conclusion: String,
+        /// The branch that spawned this one, for sub-branches. Their results
+        /// reach the channel folded into the parent's, never on their own.
+        #[serde(default)]
+        parent_branch_id: Option<BranchId>,
+        /// Conclusions of the sub-branches this branch spawned, nested.
+        #[serde(default)]
+        sub_results: Vec<agent::branch::BranchConclusion>,
+        /// The channel turn that spawned the branch.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
+    },
+    /// A branch ran past `branch_timeout_secs` and was stopped by its channel.
+    BranchTimeout {
+        agent_id: AgentId,
+        branch_id: BranchId,
+        channel_id: ChannelId,
+        timeout_secs: u64,
+        /// The channel turn that spawned the branch.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
     },
     WorkerStarted {
         agent_id: AgentId,
@@ -117,20 +163,112 @@ pub enum ProcessEvent {
         channel_id: Option<ChannelId>,
         task: String,
         worker_type: String,
+        /// The channel turn that spawned the worker, for workers spawned by
+        /// a channel.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
+    },
+    /// A worker is waiting in the worker pool queue. Re-sent whenever its
+    /// 1-based `position` changes; `WorkerStarted` follows once it runs.
+    WorkerQueued {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        position: usize,
+        /// The channel turn that spawned the worker, for workers spawned by
+        /// a channel.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
+    },
+    /// A worker attempt failed and will be retried after `delay_ms`.
+    /// `attempt` is the 1-based number of the attempt about to run.
+    WorkerRetrying {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        attempt: usize,
+        max_attempts: usize,
+        /// The failure's classification, e.g. `rate_limit`.
+        reason: String,
+        delay_ms: u64,
+        /// The channel turn that spawned the worker, for workers spawned by
+        /// a channel.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
     },
     WorkerStatus {
         agent_id: AgentId,
         worker_id: WorkerId,
         channel_id: Option<ChannelId>,
         status: String,
+        /// The channel turn that spawned the worker, for workers spawned by
+        /// a channel.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
+    },
+    /// A worker started a tool call. `step` counts tool calls since the
+    /// worker began; `detail` is a short description of the call.
+    WorkerProgress {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        step: usize,
+        detail: String,
+        /// The channel turn that spawned the worker, for workers spawned by
+        /// a channel.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
+    },
+    /// An interactive worker finished a turn (its initial task or a routed
+    /// follow-up) and is waiting for more input.
+    WorkerResponse {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        response: String,
+        /// The channel turn that spawned the worker, for workers spawned by
+        /// a channel.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
+    },
+    /// A worker sent a file to its channel with `attach_file`. The file is
+    /// in the media store; the channel uploads it.
+    WorkerAttachment {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: ChannelId,
+        media_id: String,
+        caption: Option<String>,
+        /// The channel turn that spawned the worker, for workers spawned by
+        /// a channel.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
+    },
+    /// A worker called a tool that requires approval and is waiting for the
+    /// user's `approve` or `deny`. The channel posts the request.
+    ApprovalRequested {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: ChannelId,
+        approval_id: String,
+        tool_name: String,
+        /// The call's JSON arguments, exactly as the model sent them.
+        args: String,
+        /// The channel turn that spawned the worker, for workers spawned by
+        /// a channel.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
     },
     WorkerComplete {
         agent_id: AgentId,
         worker_id: WorkerId,
         channel_id: Option<ChannelId>,
-        result: String,
+        result: agent::worker::WorkerResult,
         notify: bool,
-        success: bool,
+        /// The channel turn that spawned the worker, for workers spawned by
+        /// a channel.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
     },
     ToolStarted {
         agent_id: AgentId,
@@ -138,6 +276,9 @@ pub enum ProcessEvent {
         channel_id: Option<ChannelId>,
         tool_name: String,
         args: String,
+        /// The channel turn the call belongs to, for channel tool calls.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
     },
     ToolCompleted {
         agent_id: AgentId,
@@ -145,6 +286,23 @@ pub enum ProcessEvent {
         channel_id: Option<ChannelId>,
         tool_name: String,
         result: String,
+        /// The channel turn the call belongs to, for channel tool calls.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
+    },
+    /// A model call was answered by a fallback model because the models
+    /// before it in the chain failed or were in rate-limit cooldown.
+    ModelFallback {
+        agent_id: AgentId,
+        process_id: ProcessId,
+        channel_id: Option<ChannelId>,
+        from_model: String,
+        to_model: String,
+        reason: String,
+        /// The channel turn the call was made in, for channels and the
+        /// branches and workers they spawned.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
     },
     MemorySaved {
         agent_id: AgentId,
@@ -156,10 +314,41 @@ pub enum ProcessEvent {
         channel_id: ChannelId,
         threshold_reached: f32,
     },
+    /// Old history was dropped before a channel turn so the prompt fits the
+    /// context window.
+    ContextTrimmed {
+        agent_id: AgentId,
+        channel_id: ChannelId,
+        messages_removed: usize,
+        tokens_removed: usize,
+        /// The turn the history was trimmed for.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
+    },
+    /// A channel was given a title generated from its first turns.
+    ChannelTitled {
+        agent_id: AgentId,
+        channel_id: ChannelId,
+        title: String,
+    },
+    /// A channel refused a turn because it reached `channel_budget_usd`.
+    BudgetExceeded {
+        agent_id: AgentId,
+        channel_id: ChannelId,
+        spent_usd: f64,
+        budget_usd: f64,
+        /// The refused turn.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
+    },
     StatusUpdate {
         agent_id: AgentId,
         process_id: ProcessId,
         status: String,
+        /// The channel turn the process belongs to, for channels and the
+        /// branches and workers they spawned.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
     },
     WorkerPermission {
         agent_id: AgentId,
@@ -168,6 +357,10 @@ pub enum ProcessEvent {
         permission_id: String,
         description: String,
         patterns: Vec<String>,
+        /// The channel turn that spawned the worker, for workers spawned by
+        /// a channel.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
     },
     WorkerQuestion {
         agent_id: AgentId,
@@ -175,6 +368,10 @@ pub enum ProcessEvent {
         channel_id: Option<ChannelId>,
         question_id: String,
         questions: Vec<opencode::QuestionInfo>,
+        /// The channel turn that spawned the worker, for workers spawned by
+        /// a channel.
+        #[serde(default)]
+        turn_id: Option<TurnId>,
     },
     AgentMessageSent {
         from_agent_id: AgentId,
@@ -188,6 +385,9 @@ pub enum ProcessEvent {
         link_id: String,
         channel_id: ChannelId,
     },
+    /// The instance is shutting down. Channels drain in-flight work, persist
+    /// their state, and post the agent's `shutdown_notice`.
+    Shutdown { agent_id: AgentId },
 }
 
 /// Shared dependency bundle for agent processes.
@@ -195,6 +395,7 @@ pub enum ProcessEvent {
 pub struct AgentDeps {
     pub agent_id: AgentId,
     pub memory_search: Arc<memory::MemorySearch>,
+    pub knowledge: knowledge::KnowledgeBase,
     pub llm_manager: Arc<llm::LlmManager>,
     pub mcp_manager: Arc<mcp::McpManager>,
     pub cron_tool: Option<tools::CronTool>,
@@ -206,6 +407,14 @@ pub struct AgentDeps {
     pub links: Arc<arc_swap::ArcSwap<Vec<links::AgentLink>>>,
     /// Map of all agent IDs to display names, for inter-agent message routing.
     pub agent_names: Arc<std::collections::HashMap<String, String>>,
+    /// Caps how many of the agent's workers run at once.
+    pub worker_pool: agent::worker_pool::WorkerPool,
+    /// Stored attachments: inbound files, worker artifacts, sent files.
+    pub media: media::MediaStore,
+    /// Middleware run on every inbound message before the channel handles it.
+    pub inbound_middleware: middleware::InboundChain,
+    /// Middleware run on every response before the adapter sends it.
+    pub outbound_middleware: middleware::OutboundChain,
 }
 
 impl AgentDeps {
@@ -220,6 +429,18 @@ impl AgentDeps {
     pub fn routing(&self) -> arc_swap::Guard<Arc<llm::RoutingConfig>> {
         self.runtime_config.routing.load()
     }
+
+    /// Add middleware to the end of the agent's inbound chain. It applies to
+    /// running channels from their next message.
+    pub fn register_inbound_middleware(&self, middleware: impl middleware::InboundMiddleware) {
+        self.inbound_middleware.register(middleware);
+    }
+
+    /// Add middleware to the end of the agent's outbound chain. It applies to
+    /// running channels from their next response.
+    pub fn register_outbound_middleware(&self, middleware: impl middleware::OutboundMiddleware) {
+        self.outbound_middleware.register(middleware);
+    }
 }
 
 /// A running agent instance with all its isolated resources.
@@ -247,6 +468,23 @@ pub struct InboundMessage {
     pub formatted_author: Option<String>,
 }
 
+impl InboundMessage {
+    /// The sender ID prefixed with the platform it came from, e.g.
+    /// `discord:1234`. Raw sender IDs are only unique per platform.
+    pub fn qualified_sender_id(&self) -> String {
+        format!("{}:{}", self.source, self.sender_id)
+    }
+
+    /// Whether the sender asked for a [`StructuredReply`] instead of chat
+    /// text, by setting the `response_format` metadata to `structured`.
+    pub fn wants_structured_reply(&self) -> bool {
+        self.metadata
+            .get("response_format")
+            .and_then(|value| value.as_str())
+            == Some("structured")
+    }
+}
+
 /// Message content variants.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -318,6 +556,14 @@ pub struct Attachment {
 #[serde(rename_all = "snake_case")]
 pub enum OutboundResponse {
     Text(String),
+    /// Reply to a specific inbound message: a quoted reply on Discord and
+    /// Telegram, a thread reply on Slack, a reply-parent tag on Twitch.
+    /// `in_reply_to` is the platform message ID (`InboundMessage::id`).
+    /// Falls back to a regular text message elsewhere.
+    Reply {
+        in_reply_to: String,
+        text: String,
+    },
     /// Create a new thread and send a reply in it. On platforms that don't
     /// support threads this falls back to a regular text message.
     ThreadReply {
@@ -331,12 +577,29 @@ pub enum OutboundResponse {
         data: Vec<u8>,
         mime_type: String,
         caption: Option<String>,
+        /// The stored copy, when the file is in the media store. Adapters
+        /// that can't upload files link to it instead.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        media: Option<media::MediaRef>,
     },
     /// Add a reaction emoji to the triggering message.
     Reaction(String),
     /// Remove a reaction emoji from the triggering message.
     /// No-op on platforms that don't support reaction removal.
     RemoveReaction(String),
+    /// Replace the text of a message the bot already sent.
+    /// `None` targets the bot's most recent message in the conversation.
+    /// No-op on platforms that can't edit sent messages.
+    Edit {
+        message_id: Option<String>,
+        text: String,
+    },
+    /// Delete a message the bot already sent.
+    /// `None` targets the bot's most recent message in the conversation.
+    /// No-op on platforms that can't delete sent messages.
+    Delete {
+        message_id: Option<String>,
+    },
     /// Send a message visible only to the triggering user (ephemeral).
     /// Falls back to a regular `Text` message on platforms that don't support it.
     Ephemeral {
@@ -374,12 +637,122 @@ pub enum OutboundResponse {
         /// Unix epoch seconds when the message should be delivered.
         post_at: i64,
     },
+    /// A reply held for operator review in a supervised channel. Never
+    /// delivered by adapters; the text is sent only once the draft identified
+    /// by `approval_token` is approved.
+    Draft {
+        text: String,
+        approval_token: String,
+    },
+    /// A reply in a fixed shape for programs rather than people, sent on
+    /// structured turns. Adapters without a structured form send `message`
+    /// as text.
+    Structured(StructuredReply),
     StreamStart,
     StreamChunk(String),
     StreamEnd,
     Status(StatusUpdate),
 }
 
+impl OutboundResponse {
+    /// The text the user reads, for variants that carry any.
+    pub fn text_mut(&mut self) -> Option<&mut String> {
+        match self {
+            OutboundResponse::Text(text)
+            | OutboundResponse::Reply { text, .. }
+            | OutboundResponse::ThreadReply { text, .. }
+            | OutboundResponse::RichMessage { text, .. }
+            | OutboundResponse::Ephemeral { text, .. }
+            | OutboundResponse::ScheduledMessage { text, .. }
+            | OutboundResponse::Edit { text, .. }
+            | OutboundResponse::StreamChunk(text) => Some(text),
+            OutboundResponse::Structured(reply) => Some(&mut reply.message),
+            _ => None,
+        }
+    }
+}
+
+/// A response leaving a channel, tagged with the turn that produced it.
+#[derive(Debug, Clone)]
+pub struct ChannelResponse {
+    pub response: OutboundResponse,
+    pub turn_id: Option<TurnId>,
+}
+
+/// Sends a channel's responses to outbound delivery.
+///
+/// Each response is tagged with the channel's turn at the moment it's sent,
+/// so delivery reports the right turn even when it runs behind the channel.
+/// Responses decided after their turn, like approved drafts, name their
+/// turn with [`ResponseSender::send_for_turn`].
+#[derive(Debug, Clone)]
+pub struct ResponseSender {
+    tx: tokio::sync::mpsc::Sender<ChannelResponse>,
+    turn_id: Arc<tokio::sync::RwLock<Option<TurnId>>>,
+}
+
+impl ResponseSender {
+    pub fn new(
+        tx: tokio::sync::mpsc::Sender<ChannelResponse>,
+        turn_id: Arc<tokio::sync::RwLock<Option<TurnId>>>,
+    ) -> Self {
+        Self { tx, turn_id }
+    }
+
+    /// The turn the channel is currently running, if any.
+    pub async fn current_turn(&self) -> Option<TurnId> {
+        *self.turn_id.read().await
+    }
+
+    /// Send a response produced by the current turn.
+    pub async fn send(
+        &self,
+        response: OutboundResponse,
+    ) -> std::result::Result<(), tokio::sync::mpsc::error::SendError<OutboundResponse>> {
+        let turn_id = self.current_turn().await;
+        self.send_for_turn(response, turn_id).await
+    }
+
+    /// Send a response produced by `turn_id`.
+    pub async fn send_for_turn(
+        &self,
+        response: OutboundResponse,
+        turn_id: Option<TurnId>,
+    ) -> std::result::Result<(), tokio::sync::mpsc::error::SendError<OutboundResponse>> {
+        self.tx
+            .send(ChannelResponse { response, turn_id })
+            .await
+            .map_err(|error| tokio::sync::mpsc::error::SendError(error.0.response))
+    }
+}
+
+/// The channel's answer on a structured turn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct StructuredReply {
+    /// What the agent is doing with the request.
+    pub action: ReplyAction,
+    /// The message for the user: the answer, the question, or the reason.
+    pub message: String,
+    /// How sure the agent is, from 0 to 1. Absent when the model answered
+    /// in plain text and never rated its answer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+}
+
+/// What a [`StructuredReply`] does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplyAction {
+    /// Answers the request.
+    Reply,
+    /// Asks the user for something needed to answer.
+    Clarify,
+    /// Won't or can't help with the request.
+    Decline,
+    /// Needs a human to take over.
+    Handoff,
+}
+
 /// A generic rich-formatted card (maps to Embeds in Discord).
 #[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 pub struct Card {
@@ -480,12 +853,28 @@ mod base64_bytes {
 }
 
 /// Status updates for messaging platforms.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Channels send `Thinking`, `RunningTool`, `Streaming`, `WaitingOnWorker`
+/// and `Idle` through their hook as a turn progresses, so adapters can show
+/// presence beyond a typing indicator. Each is sent only when it changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum StatusUpdate {
     Thinking,
     /// Cancel the typing indicator (e.g. when the skip tool fires).
     StopTyping,
+    /// The channel is running a tool call.
+    RunningTool {
+        name: String,
+    },
+    /// The reply is being written out to the conversation.
+    Streaming,
+    /// The turn is over but workers spawned by the channel are still running.
+    WaitingOnWorker {
+        count: usize,
+    },
+    /// The turn is over and nothing is running.
+    Idle,
     ToolStarted {
         tool_name: String,
     },
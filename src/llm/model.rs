@@ -2,9 +2,8 @@
 
 use crate::config::{ApiType, ProviderConfig};
 use crate::llm::manager::LlmManager;
-use crate::llm::routing::{
-    self, MAX_FALLBACK_ATTEMPTS, MAX_RETRIES_PER_MODEL, RETRY_BASE_DELAY_MS, RoutingConfig,
-};
+use crate::llm::routing::{self, MAX_FALLBACK_ATTEMPTS, MAX_RETRIES_PER_MODEL, RoutingConfig};
+use crate::{AgentId, ChannelId, ProcessEvent, ProcessId, TurnId};
 
 use rig::completion::{self, CompletionError, CompletionModel, CompletionRequest, GetTokenUsage};
 use rig::message::{
@@ -15,6 +14,7 @@ use rig::one_or_many::OneOrMany;
 use rig::streaming::StreamingCompletionResponse;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use tracing::Instrument as _;
 
 /// Raw provider response. Wraps the JSON so Rig can carry it through.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +48,19 @@ pub struct SpacebotModel {
     routing: Option<RoutingConfig>,
     agent_id: Option<String>,
     process_type: Option<String>,
+    fallback_events: Option<FallbackEvents>,
+    /// The channel turn the calls are made for, recorded on each call's span
+    /// and fallback events.
+    turn_id: Option<TurnId>,
+}
+
+/// Where to report `ProcessEvent::ModelFallback` when a fallback model answers.
+#[derive(Clone)]
+struct FallbackEvents {
+    event_tx: tokio::sync::broadcast::Sender<ProcessEvent>,
+    agent_id: AgentId,
+    process_id: ProcessId,
+    channel_id: Option<ChannelId>,
 }
 
 impl SpacebotModel {
@@ -78,6 +91,31 @@ impl SpacebotModel {
         self
     }
 
+    /// Attribute calls to the channel turn `turn_id`: the turn itself for a
+    /// channel, the spawning turn for its branches and workers.
+    pub fn with_turn_id(mut self, turn_id: Option<TurnId>) -> Self {
+        self.turn_id = turn_id;
+        self
+    }
+
+    /// Emit `ProcessEvent::ModelFallback` on `event_tx` whenever a fallback
+    /// model answers in place of this one.
+    pub fn with_fallback_events(
+        mut self,
+        event_tx: tokio::sync::broadcast::Sender<ProcessEvent>,
+        agent_id: AgentId,
+        process_id: ProcessId,
+        channel_id: Option<ChannelId>,
+    ) -> Self {
+        self.fallback_events = Some(FallbackEvents {
+            event_tx,
+            agent_id,
+            process_id,
+            channel_id,
+        });
+        self
+    }
+
     /// Direct call to the provider (no fallback logic).
     async fn attempt_completion(
         &self,
@@ -152,6 +190,7 @@ impl SpacebotModel {
         &self,
         model_name: &str,
         request: &CompletionRequest,
+        base_delay_ms: u64,
     ) -> Result<completion::CompletionResponse<RawResponse>, (CompletionError, bool)> {
         let model = if model_name == self.full_model_name {
             self.clone()
@@ -162,7 +201,7 @@ impl SpacebotModel {
         let mut last_error = None;
         for attempt in 0..MAX_RETRIES_PER_MODEL {
             if attempt > 0 {
-                let delay_ms = RETRY_BASE_DELAY_MS * 2u64.pow((attempt - 1) as u32);
+                let delay_ms = base_delay_ms.saturating_mul(2u64.pow((attempt - 1) as u32));
                 tracing::debug!(
                     model = %model_name,
                     attempt = attempt + 1,
@@ -200,6 +239,25 @@ impl SpacebotModel {
             was_rate_limit,
         ))
     }
+
+    fn emit_fallback(&self, to_model: &str, reason: String) {
+        let Some(events) = &self.fallback_events else {
+            return;
+        };
+        // No subscribers is fine — the event is informational.
+        events
+            .event_tx
+            .send(ProcessEvent::ModelFallback {
+                agent_id: events.agent_id.clone(),
+                process_id: events.process_id.clone(),
+                channel_id: events.channel_id.clone(),
+                from_model: self.full_model_name.clone(),
+                to_model: to_model.to_string(),
+                reason,
+                turn_id: self.turn_id,
+            })
+            .ok();
+    }
 }
 
 impl CompletionModel for SpacebotModel {
@@ -230,16 +288,43 @@ impl CompletionModel for SpacebotModel {
             routing: None,
             agent_id: None,
             process_type: None,
+            fallback_events: None,
+            turn_id: None,
         }
     }
 
     async fn completion(
         &self,
-        request: CompletionRequest,
+        mut request: CompletionRequest,
     ) -> Result<completion::CompletionResponse<RawResponse>, CompletionError> {
         #[cfg(feature = "metrics")]
         let start = std::time::Instant::now();
 
+        let mut messages: Vec<Message> = request.chat_history.into_iter().collect();
+        order_tool_results(&mut messages);
+        request.chat_history = OneOrMany::many(messages)
+            .map_err(|_| CompletionError::ProviderError("empty chat history".into()))?;
+
+        #[cfg(feature = "replay")]
+        let replay = crate::replay::current().map(|session| {
+            (
+                session,
+                crate::replay::RecordedRequest::from_request(&request),
+            )
+        });
+        #[cfg(feature = "replay")]
+        if let Some((session, recorded)) = &replay
+            && session.is_replaying()
+        {
+            return session.replay_completion(recorded);
+        }
+
+        let span = tracing::info_span!(
+            "llm.completion",
+            model = %self.full_model_name,
+            process_type = self.process_type.as_deref(),
+            turn_id = self.turn_id.map(tracing::field::display),
+        );
         let result = async move {
             let Some(routing) = &self.routing else {
                 // No routing config — just call the model directly, no fallback/retry
@@ -247,6 +332,7 @@ impl CompletionModel for SpacebotModel {
             };
 
             let cooldown = routing.rate_limit_cooldown_secs;
+            let base_delay_ms = routing.retry_base_delay_ms;
             let fallbacks = routing.get_fallbacks(&self.full_model_name);
             let mut last_error: Option<CompletionError> = None;
 
@@ -266,7 +352,7 @@ impl CompletionModel for SpacebotModel {
                 );
             } else {
                 match self
-                    .attempt_with_retries(&self.full_model_name, &request)
+                    .attempt_with_retries(&self.full_model_name, &request, base_delay_ms)
                     .await
                 {
                     Ok(response) => return Ok(response),
@@ -303,7 +389,10 @@ impl CompletionModel for SpacebotModel {
                     continue;
                 }
 
-                match self.attempt_with_retries(fallback_name, &request).await {
+                match self
+                    .attempt_with_retries(fallback_name, &request, base_delay_ms)
+                    .await
+                {
                     Ok(response) => {
                         tracing::info!(
                             original = %self.full_model_name,
@@ -311,6 +400,11 @@ impl CompletionModel for SpacebotModel {
                             attempt = index + 1,
                             "fallback model succeeded"
                         );
+                        let reason = match &last_error {
+                            Some(error) => error.to_string(),
+                            None => format!("{} is in rate-limit cooldown", self.full_model_name),
+                        };
+                        self.emit_fallback(fallback_name, reason);
                         return Ok(response);
                     }
                     Err((error, was_rate_limit)) => {
@@ -330,6 +424,7 @@ impl CompletionModel for SpacebotModel {
                 CompletionError::ProviderError("all models in fallback chain failed".into())
             }))
         }
+        .instrument(span)
         .await;
 
         #[cfg(feature = "metrics")]
@@ -417,6 +512,11 @@ impl CompletionModel for SpacebotModel {
             }
         }
 
+        #[cfg(feature = "replay")]
+        if let (Some((session, recorded)), Ok(response)) = (replay, &result) {
+            session.record_completion(&self.full_model_name, recorded, response);
+        }
+
         result
     }
 
@@ -1259,8 +1359,91 @@ fn truncate_body(body: &str) -> &str {
     }
 }
 
+// --- Request preparation ---
+
+/// Put each batch of tool results in the order the model made the calls.
+///
+/// Tool calls from one response run concurrently (`tool_concurrency`) and
+/// their results land in history in completion order. Sorting them back by
+/// call ID keeps the prompt deterministic, which prompt caching relies on.
+fn order_tool_results(messages: &mut [Message]) {
+    let mut call_ids: Vec<String> = Vec::new();
+    for message in messages.iter_mut() {
+        match message {
+            Message::Assistant { content, .. } => {
+                call_ids = content
+                    .iter()
+                    .filter_map(|item| match item {
+                        AssistantContent::ToolCall(call) => Some(call.id.clone()),
+                        _ => None,
+                    })
+                    .collect();
+            }
+            Message::User { content } if call_ids.len() > 1 => {
+                // Results first, in call order; anything else keeps its
+                // relative order after them.
+                let position = |item: &UserContent| match item {
+                    UserContent::ToolResult(result) => call_ids
+                        .iter()
+                        .position(|id| *id == result.id)
+                        .unwrap_or(call_ids.len()),
+                    _ => usize::MAX,
+                };
+                let positions: Vec<usize> = content.iter().map(position).collect();
+                if positions.is_sorted() {
+                    continue;
+                }
+                let mut items: Vec<UserContent> = content.iter().cloned().collect();
+                items.sort_by_key(position);
+                if let Ok(sorted) = OneOrMany::many(items) {
+                    *content = sorted;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 // --- Response parsing ---
 
+/// Key of the placeholder arguments object used when a model's tool call
+/// arguments aren't valid JSON. [`SpacebotHook`](crate::hooks::SpacebotHook)
+/// spots it and sends the parse error back to the model instead of running
+/// the tool with empty arguments.
+pub const MALFORMED_ARGUMENTS_KEY: &str = "__malformed_tool_arguments";
+
+/// Parse a tool call's arguments string. Invalid JSON becomes a
+/// [`MALFORMED_ARGUMENTS_KEY`] placeholder carrying the parse error.
+fn parse_tool_arguments(raw: &str) -> serde_json::Value {
+    if raw.trim().is_empty() {
+        return serde_json::json!({});
+    }
+    match serde_json::from_str(raw) {
+        Ok(arguments) => arguments,
+        Err(error) => {
+            let mut placeholder = serde_json::Map::new();
+            placeholder.insert(
+                MALFORMED_ARGUMENTS_KEY.into(),
+                serde_json::Value::String(error.to_string()),
+            );
+            serde_json::Value::Object(placeholder)
+        }
+    }
+}
+
+/// The parse error recorded in a tool call's serialized arguments, if they
+/// are a [`MALFORMED_ARGUMENTS_KEY`] placeholder.
+pub fn malformed_tool_arguments(args: &str) -> Option<String> {
+    if !args.contains(MALFORMED_ARGUMENTS_KEY) {
+        return None;
+    }
+    let value: serde_json::Value = serde_json::from_str(args).ok()?;
+    value
+        .get(MALFORMED_ARGUMENTS_KEY)?
+        .as_str()
+        .map(str::to_owned)
+}
+
 fn make_tool_call(id: String, name: String, arguments: serde_json::Value) -> ToolCall {
     ToolCall {
         id,
@@ -1383,11 +1566,11 @@ fn parse_openai_response(
             // OpenAI-compatible APIs usually return arguments as a JSON string.
             // Some providers return it as a raw JSON object instead.
             let arguments_field = &tc["function"]["arguments"];
-            let arguments = arguments_field
-                .as_str()
-                .and_then(|raw| serde_json::from_str(raw).ok())
-                .or_else(|| arguments_field.as_object().map(|_| arguments_field.clone()))
-                .unwrap_or(serde_json::json!({}));
+            let arguments = match arguments_field {
+                serde_json::Value::String(raw) => parse_tool_arguments(raw),
+                serde_json::Value::Object(_) => arguments_field.clone(),
+                _ => serde_json::json!({}),
+            };
             assistant_content.push(AssistantContent::ToolCall(make_tool_call(
                 id, name, arguments,
             )));
@@ -1455,7 +1638,7 @@ fn parse_openai_responses_response(
                 let name = output_item["name"].as_str().unwrap_or("").to_string();
                 let arguments = output_item["arguments"]
                     .as_str()
-                    .and_then(|arguments| serde_json::from_str(arguments).ok())
+                    .map(parse_tool_arguments)
                     .unwrap_or(serde_json::json!({}));
 
                 assistant_content.push(AssistantContent::ToolCall(make_tool_call(
@@ -1568,4 +1751,61 @@ mod tests {
             panic!("expected ToolCall");
         }
     }
+
+    #[test]
+    fn tool_results_are_ordered_by_call() {
+        let call = |id: &str| {
+            AssistantContent::ToolCall(make_tool_call(
+                id.into(),
+                "memory_recall".into(),
+                serde_json::json!({}),
+            ))
+        };
+        let result = |id: &str| {
+            UserContent::ToolResult(rig::message::ToolResult {
+                id: id.into(),
+                call_id: None,
+                content: OneOrMany::one(rig::message::ToolResultContent::text("ok")),
+            })
+        };
+        let result_ids = |message: &Message| match message {
+            Message::User { content } => content
+                .iter()
+                .filter_map(|item| match item {
+                    UserContent::ToolResult(result) => Some(result.id.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+            _ => Vec::new(),
+        };
+
+        let mut messages = vec![
+            Message::Assistant {
+                id: None,
+                content: OneOrMany::many(vec![call("a"), call("b"), call("c")]).unwrap(),
+            },
+            Message::User {
+                content: OneOrMany::many(vec![result("c"), result("a"), result("b")]).unwrap(),
+            },
+        ];
+        order_tool_results(&mut messages);
+
+        assert_eq!(result_ids(&messages[1]), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn malformed_tool_arguments_round_trip_through_placeholder() {
+        assert_eq!(
+            parse_tool_arguments(r#"{"path": "a.txt"}"#),
+            serde_json::json!({"path": "a.txt"})
+        );
+        assert_eq!(parse_tool_arguments("  "), serde_json::json!({}));
+
+        let placeholder = parse_tool_arguments(r#"{"path": "a.txt""#);
+        let error = malformed_tool_arguments(&placeholder.to_string())
+            .expect("placeholder should be detected");
+        assert!(error.contains("EOF"), "unexpected error: {error}");
+
+        assert_eq!(malformed_tool_arguments(r#"{"path": "a.txt"}"#), None);
+    }
 }
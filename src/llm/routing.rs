@@ -1,6 +1,7 @@
 //! Model routing configuration and resolution.
 
 use crate::ProcessType;
+use serde::Serialize;
 use std::collections::HashMap;
 
 /// Model routing configuration. Lives on the agent config (via defaults).
@@ -15,6 +16,16 @@ pub struct RoutingConfig {
     pub compactor: String,
     pub cortex: String,
     pub voice: String,
+    /// Model that captions image attachments when the channel model can't
+    /// take image input. Empty disables captioning.
+    pub vision: String,
+
+    /// Cheaper model for light channel turns. When set, each channel turn is
+    /// routed between this and `channel` by [`RoutingHints`]. Empty disables
+    /// per-turn routing.
+    pub channel_cheap: String,
+    /// Longest message (in characters) that can go to `channel_cheap`.
+    pub cheap_max_chars: usize,
 
     /// Task-type overrides (e.g. "coding" → "anthropic/claude-sonnet-4").
     /// Applied to workers and branches when a task_type is specified at spawn.
@@ -27,6 +38,10 @@ pub struct RoutingConfig {
     /// How long to deprioritize a rate-limited model (seconds).
     pub rate_limit_cooldown_secs: u64,
 
+    /// Base delay for exponential backoff between retries of the same model
+    /// (milliseconds). Doubles after each failed attempt.
+    pub retry_base_delay_ms: u64,
+
     pub channel_thinking_effort: String,
     pub branch_thinking_effort: String,
     pub worker_thinking_effort: String,
@@ -50,9 +65,13 @@ impl RoutingConfig {
             compactor: model.clone(),
             cortex: model,
             voice: String::new(),
+            vision: String::new(),
+            channel_cheap: String::new(),
+            cheap_max_chars: DEFAULT_CHEAP_MAX_CHARS,
             task_overrides: HashMap::new(),
             fallbacks: HashMap::new(),
             rate_limit_cooldown_secs: 60,
+            retry_base_delay_ms: RETRY_BASE_DELAY_MS,
             channel_thinking_effort: "auto".into(),
             branch_thinking_effort: "auto".into(),
             worker_thinking_effort: "auto".into(),
@@ -78,7 +97,47 @@ impl RoutingConfig {
             ProcessType::Branch => &self.branch,
             ProcessType::Worker => &self.worker,
             ProcessType::Compactor => &self.compactor,
-            ProcessType::Cortex => &self.cortex,
+            // The heartbeat is a background process like the cortex and
+            // shares its model.
+            ProcessType::Cortex | ProcessType::Heartbeat => &self.cortex,
+        }
+    }
+
+    /// Resolve the model for a turn, picking between `channel_cheap` and the
+    /// channel model from what the turn looks like. Other process types, and
+    /// channels without a cheap model, resolve as [`RoutingConfig::resolve`].
+    pub fn resolve_with_hints(
+        &self,
+        process_type: ProcessType,
+        task_type: Option<&str>,
+        hints: &RoutingHints,
+    ) -> RoutingDecision<'_> {
+        let frontier = |reason| RoutingDecision {
+            model: self.resolve(process_type, task_type),
+            tier: ModelTier::Frontier,
+            reason,
+        };
+
+        if process_type != ProcessType::Channel || self.channel_cheap.is_empty() {
+            return frontier("per-turn routing off");
+        }
+        if hints.think_hard {
+            return frontier("asked to think hard");
+        }
+        if hints.has_code_block {
+            return frontier("code block");
+        }
+        if !hints.attachment_kinds.is_empty() {
+            return frontier("attachments");
+        }
+        if hints.message_chars > self.cheap_max_chars {
+            return frontier("long message");
+        }
+
+        RoutingDecision {
+            model: &self.channel_cheap,
+            tier: ModelTier::Cheap,
+            reason: "short message",
         }
     }
 
@@ -110,6 +169,74 @@ impl RoutingConfig {
     }
 }
 
+/// Default for [`RoutingConfig::cheap_max_chars`].
+pub const DEFAULT_CHEAP_MAX_CHARS: usize = 500;
+
+/// Tool name under which per-turn routing decisions are written to the tool
+/// audit log.
+pub const ROUTING_AUDIT_NAME: &str = "model_routing";
+
+/// Phrases that ask for the frontier model regardless of message length.
+const THINK_HARD_PHRASES: &[&str] = &[
+    "think hard",
+    "think harder",
+    "think carefully",
+    "think deeply",
+    "think it through",
+    "ultrathink",
+    "take your time",
+];
+
+/// What a channel turn looks like, for picking a model per turn.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RoutingHints {
+    /// Length of the user text in characters.
+    pub message_chars: usize,
+    pub has_code_block: bool,
+    /// Kinds of attached content, e.g. `image`, `document`, `audio`.
+    pub attachment_kinds: Vec<String>,
+    /// The user explicitly asked the agent to think hard.
+    pub think_hard: bool,
+}
+
+impl RoutingHints {
+    pub fn from_message(text: &str, attachment_kinds: Vec<String>) -> Self {
+        let lowercase = text.to_lowercase();
+        Self {
+            message_chars: text.chars().count(),
+            has_code_block: text.contains("```"),
+            attachment_kinds,
+            think_hard: THINK_HARD_PHRASES
+                .iter()
+                .any(|phrase| lowercase.contains(phrase)),
+        }
+    }
+}
+
+/// Which of a channel's models a turn was routed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelTier {
+    Cheap,
+    Frontier,
+}
+
+impl ModelTier {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cheap => "cheap",
+            Self::Frontier => "frontier",
+        }
+    }
+}
+
+/// The model picked for a turn, and why.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoutingDecision<'a> {
+    pub model: &'a str,
+    pub tier: ModelTier,
+    pub reason: &'static str,
+}
+
 /// Whether an HTTP status code should trigger a fallback to the next model.
 pub fn is_retriable_status(status: u16) -> bool {
     matches!(status, 429 | 502 | 503 | 504)
@@ -150,6 +277,39 @@ pub fn is_context_overflow_error(error_message: &str) -> bool {
         || (lower.contains("maximum") && lower.contains("tokens"))
 }
 
+/// Model name fragments of families that only accept text input.
+const TEXT_ONLY_MODEL_PATTERNS: &[&str] = &[
+    "deepseek-chat",
+    "deepseek-reasoner",
+    "deepseek-r1",
+    "deepseek-v3",
+    "gpt-3.5",
+    "o1-mini",
+    "o3-mini",
+    "codestral",
+    "mixtral",
+    "mistral-7b",
+    "llama-3.1",
+    "llama-3.3",
+    "qwq",
+    "kimi-k2",
+];
+
+/// Whether a model accepts image input.
+///
+/// Models are assumed to be multimodal unless they belong to a known
+/// text-only family. Names mentioning "vision" or "-vl" always count as
+/// multimodal.
+pub fn supports_image_input(model: &str) -> bool {
+    let lower = model.to_lowercase();
+    if lower.contains("vision") || lower.contains("-vl") {
+        return true;
+    }
+    !TEXT_ONLY_MODEL_PATTERNS
+        .iter()
+        .any(|pattern| lower.contains(pattern))
+}
+
 /// Returns routing defaults appropriate for a given provider.
 ///
 /// When a user sets up OpenRouter but routing still points to `anthropic/...`,
@@ -403,7 +563,7 @@ pub const MAX_FALLBACK_ATTEMPTS: usize = 3;
 /// Max retries per model (primary or fallback) on retriable errors.
 pub const MAX_RETRIES_PER_MODEL: usize = 3;
 
-/// Base delay for exponential backoff between retries (milliseconds).
+/// Default base delay for exponential backoff between retries (milliseconds).
 pub const RETRY_BASE_DELAY_MS: u64 = 500;
 
 /// Whether an error indicates an actual rate limit (429) vs other transient failures.
@@ -413,3 +573,78 @@ pub fn is_rate_limit_error(error_message: &str) -> bool {
     let lower = error_message.to_lowercase();
     lower.contains("429") || lower.contains("rate limit")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn routing() -> RoutingConfig {
+        RoutingConfig {
+            channel_cheap: "anthropic/claude-haiku-4.5".into(),
+            cheap_max_chars: 100,
+            ..RoutingConfig::default()
+        }
+    }
+
+    fn route(text: &str, attachment_kinds: Vec<String>) -> (ModelTier, &'static str) {
+        let routing = routing();
+        let decision = routing.resolve_with_hints(
+            ProcessType::Channel,
+            None,
+            &RoutingHints::from_message(text, attachment_kinds),
+        );
+        (decision.tier, decision.reason)
+    }
+
+    #[test]
+    fn light_turns_go_to_the_cheap_model() {
+        let routing = routing();
+        let decision = routing.resolve_with_hints(
+            ProcessType::Channel,
+            None,
+            &RoutingHints::from_message("thanks, that works", Vec::new()),
+        );
+        assert_eq!(decision.model, "anthropic/claude-haiku-4.5");
+        assert_eq!(decision.tier, ModelTier::Cheap);
+    }
+
+    #[test]
+    fn heavy_turns_go_to_the_frontier_model() {
+        assert_eq!(
+            route("Think hard: is this safe?", Vec::new()),
+            (ModelTier::Frontier, "asked to think hard")
+        );
+        assert_eq!(
+            route("```rust\nfn main() {}\n```", Vec::new()),
+            (ModelTier::Frontier, "code block")
+        );
+        assert_eq!(
+            route("what's this?", vec!["image".into()]),
+            (ModelTier::Frontier, "attachments")
+        );
+        assert_eq!(
+            route(&"word ".repeat(30), Vec::new()),
+            (ModelTier::Frontier, "long message")
+        );
+    }
+
+    #[test]
+    fn routing_off_without_a_cheap_model() {
+        let default_routing = RoutingConfig::default();
+        let decision = default_routing.resolve_with_hints(
+            ProcessType::Channel,
+            None,
+            &RoutingHints::from_message("hi", Vec::new()),
+        );
+        assert_eq!(decision.model, default_routing.channel);
+        assert_eq!(decision.tier, ModelTier::Frontier);
+
+        let routing = routing();
+        let decision = routing.resolve_with_hints(
+            ProcessType::Worker,
+            None,
+            &RoutingHints::from_message("hi", Vec::new()),
+        );
+        assert_eq!(decision.tier, ModelTier::Frontier);
+    }
+}
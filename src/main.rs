@@ -43,9 +43,43 @@ enum Command {
     },
     /// Show status of the running daemon
     Status,
+    /// Chat with an agent in the terminal (for local development)
+    Chat {
+        /// Agent to talk to (defaults to the default agent)
+        #[arg(short, long)]
+        agent: Option<String>,
+    },
     /// Manage skills
     #[command(subcommand)]
     Skill(SkillCommand),
+    /// Manage an agent's knowledge base
+    #[command(subcommand)]
+    Knowledge(KnowledgeCommand),
+    /// Export a conversation to a JSON bundle
+    Export {
+        /// Channel (conversation) ID
+        channel_id: String,
+        /// Agent ID (defaults to first agent)
+        #[arg(short, long)]
+        agent: Option<String>,
+        /// File to write (defaults to stdout)
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Import a conversation from a JSON bundle
+    Import {
+        /// Bundle written by `export`
+        path: std::path::PathBuf,
+        /// Agent ID (defaults to first agent)
+        #[arg(short, long)]
+        agent: Option<String>,
+        /// Channel to import into (defaults to the exported channel)
+        #[arg(long)]
+        channel: Option<String>,
+        /// Replace the history of a channel that already has one
+        #[arg(long)]
+        force: bool,
+    },
     /// Manage authentication
     #[command(subcommand)]
     Auth(AuthCommand),
@@ -115,15 +149,68 @@ enum SkillCommand {
     },
 }
 
+#[derive(Subcommand)]
+enum KnowledgeCommand {
+    /// Add documents (PDF, markdown, HTML, text) to the knowledge base
+    Ingest {
+        /// Files to ingest. Directories are scanned for supported files.
+        #[arg(required = true)]
+        paths: Vec<std::path::PathBuf>,
+        /// Agent ID (defaults to first agent)
+        #[arg(short, long)]
+        agent: Option<String>,
+    },
+    /// List ingested documents
+    List {
+        /// Agent ID (defaults to first agent)
+        #[arg(short, long)]
+        agent: Option<String>,
+    },
+    /// Remove a document and its passages
+    Remove {
+        /// Document ID, as shown by `knowledge list`
+        id: String,
+        /// Agent ID (defaults to first agent)
+        #[arg(short, long)]
+        agent: Option<String>,
+    },
+}
+
 /// Tracks an active conversation channel and its message sender.
 struct ActiveChannel {
-    message_tx: mpsc::Sender<spacebot::InboundMessage>,
+    /// Routes messages onto the channel's priority or normal lane.
+    message_tx: spacebot::agent::lanes::ChannelSender,
     /// Latest inbound message for this conversation, shared with the outbound
     /// routing task so status updates (e.g. typing indicators) target the
     /// most recent message rather than the first one the channel ever received.
     latest_message: Arc<tokio::sync::RwLock<spacebot::InboundMessage>>,
-    /// Retained so the outbound routing task stays alive.
-    _outbound_handle: tokio::task::JoinHandle<()>,
+    /// Retained so the outbound routing task stays alive. Aborted when the
+    /// channel is released after archiving itself.
+    outbound_handle: tokio::task::JoinHandle<()>,
+    /// The channel's event loop, awaited on shutdown so it can drain.
+    channel_handle: tokio::task::JoinHandle<()>,
+}
+
+impl ActiveChannel {
+    /// Whether the channel task has exited and dropped its receiver, which is
+    /// what an idle channel does once it has archived its state.
+    fn is_archived(&self) -> bool {
+        self.message_tx.is_closed()
+    }
+}
+
+/// Tear down the main loop's bookkeeping for a channel that archived itself.
+/// The next message for the conversation spawns a fresh channel that
+/// rehydrates from the history store.
+async fn release_archived_channel(
+    api_state: &spacebot::api::ApiState,
+    conversation_id: &str,
+    active: ActiveChannel,
+) {
+    active.outbound_handle.abort();
+    api_state.unregister_channel_status(conversation_id).await;
+    api_state.unregister_channel_state(conversation_id).await;
+    tracing::info!(conversation_id, "released archived channel");
 }
 
 fn main() -> anyhow::Result<()> {
@@ -142,7 +229,20 @@ fn main() -> anyhow::Result<()> {
             cmd_start(cli.config, cli.debug, foreground)
         }
         Command::Status => cmd_status(),
+        Command::Chat { agent } => cmd_chat(cli.config, cli.debug, agent),
         Command::Skill(skill_cmd) => cmd_skill(cli.config, skill_cmd),
+        Command::Knowledge(knowledge_cmd) => cmd_knowledge(cli.config, knowledge_cmd),
+        Command::Export {
+            channel_id,
+            agent,
+            output,
+        } => cmd_export(cli.config, channel_id, agent, output),
+        Command::Import {
+            path,
+            agent,
+            channel,
+            force,
+        } => cmd_import(cli.config, path, agent, channel, force),
         Command::Auth(auth_cmd) => cmd_auth(cli.config, auth_cmd),
     }
 }
@@ -202,7 +302,48 @@ fn cmd_start(
             spacebot::daemon::init_background_tracing(&paths, debug, &config.telemetry)
         };
 
-        run(config, foreground, otel_provider).await
+        run(config, foreground, otel_provider, None).await
+    })
+}
+
+fn cmd_chat(
+    config_path: Option<std::path::PathBuf>,
+    debug: bool,
+    agent: Option<String>,
+) -> anyhow::Result<()> {
+    let paths = spacebot::daemon::DaemonPaths::from_default();
+
+    // The chat session runs a full instance, so it can't share the data
+    // directory with a running daemon.
+    if let Some(pid) = spacebot::daemon::is_running(&paths) {
+        eprintln!("spacebot is already running (pid {pid}), stop it first");
+        std::process::exit(1);
+    }
+
+    let config = load_config(&config_path)?;
+    if !has_provider_credentials(&config.llm, &config.instance_dir) {
+        anyhow::bail!("no LLM provider configured, run `spacebot start` to set one up");
+    }
+
+    let agent_id = agent.unwrap_or_else(|| config.default_agent_id().to_string());
+    if !config.agents.iter().any(|a| a.id == agent_id) {
+        anyhow::bail!("unknown agent '{agent_id}'");
+    }
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .context("failed to build Tokio runtime")?;
+
+    runtime.block_on(async {
+        // Logs go to the log file so stdout carries only the conversation.
+        let paths = spacebot::daemon::DaemonPaths::new(&config.instance_dir);
+        let otel_provider =
+            spacebot::daemon::init_background_tracing(&paths, debug, &config.telemetry);
+
+        eprintln!("chatting with {agent_id} (type /exit or press ctrl-d to quit)\n");
+        let cli_adapter = Arc::new(spacebot::messaging::cli::CliAdapter::new(&agent_id));
+        run(config, true, otel_provider, Some(cli_adapter)).await
     })
 }
 
@@ -236,7 +377,7 @@ async fn cmd_stop() -> anyhow::Result<()> {
     if spacebot::daemon::wait_for_exit(pid) {
         eprintln!("spacebot stopped");
     } else {
-        eprintln!("spacebot did not stop within 10 seconds (pid {pid})");
+        eprintln!("spacebot did not stop within 60 seconds (pid {pid})");
         std::process::exit(1);
     }
 
@@ -532,6 +673,234 @@ fn cmd_skill(
     })
 }
 
+fn cmd_knowledge(
+    config_path: Option<std::path::PathBuf>,
+    knowledge_cmd: KnowledgeCommand,
+) -> anyhow::Result<()> {
+    let config = load_config(&config_path)?;
+    let agent_id = match &knowledge_cmd {
+        KnowledgeCommand::Ingest { agent, .. }
+        | KnowledgeCommand::List { agent }
+        | KnowledgeCommand::Remove { agent, .. } => agent.clone(),
+    };
+    let agent_config = get_agent_config(&config, agent_id.as_deref())?
+        .resolve(&config.instance_dir, &config.defaults);
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("failed to build tokio runtime")?;
+
+    runtime.block_on(async {
+        let db =
+            spacebot::db::Db::connect(&agent_config.data_dir, &agent_config.id, &config.storage)
+                .await
+                .with_context(|| {
+                    format!("failed to open databases for agent '{}'", agent_config.id)
+                })?;
+        let embedding_model = Arc::new(
+            spacebot::memory::EmbeddingModel::new(&config.instance_dir.join("embedding_cache"))
+                .context("failed to initialize embedding model")?,
+        );
+        let knowledge = spacebot::knowledge::KnowledgeBase::new(
+            db.sqlite.clone(),
+            db.open_knowledge_chunks()
+                .await
+                .context("failed to open knowledge base")?,
+            embedding_model,
+        );
+
+        match knowledge_cmd {
+            KnowledgeCommand::Ingest { paths, .. } => {
+                let mut files = Vec::new();
+                for path in paths {
+                    if path.is_dir() {
+                        let mut entries = std::fs::read_dir(&path)
+                            .with_context(|| format!("failed to read {}", path.display()))?
+                            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                            .filter(|entry| {
+                                entry.is_file() && spacebot::knowledge::extract::is_supported(entry)
+                            })
+                            .collect::<Vec<_>>();
+                        entries.sort();
+                        files.extend(entries);
+                    } else {
+                        files.push(path);
+                    }
+                }
+
+                let mut failed = 0;
+                for file in &files {
+                    match knowledge.ingest_file(file, &agent_config.knowledge).await {
+                        Ok(ingested) if ingested.already_indexed => {
+                            println!("  = {} (already indexed)", file.display());
+                        }
+                        Ok(ingested) => {
+                            println!(
+                                "  + {} ({} passages, id {})",
+                                file.display(),
+                                ingested.document.chunk_count,
+                                ingested.document.id
+                            );
+                        }
+                        Err(error) => {
+                            eprintln!("  ! {}: {error}", file.display());
+                            failed += 1;
+                        }
+                    }
+                }
+
+                if failed > 0 {
+                    anyhow::bail!("{failed} of {} file(s) failed to ingest", files.len());
+                }
+                Ok(())
+            }
+            KnowledgeCommand::List { .. } => {
+                let documents = knowledge.list().await?;
+                if documents.is_empty() {
+                    println!("No documents in the knowledge base");
+                    return Ok(());
+                }
+
+                println!("Documents ({}):\n", documents.len());
+                for document in documents {
+                    println!("  {} ({})", document.title, document.id);
+                    println!(
+                        "    {} passages, {} characters, added {}",
+                        document.chunk_count, document.char_count, document.created_at
+                    );
+                    println!("    Source: {}", document.source);
+                    println!();
+                }
+                Ok(())
+            }
+            KnowledgeCommand::Remove { id, .. } => {
+                if knowledge.delete(&id).await? {
+                    println!("Removed document: {id}");
+                } else {
+                    eprintln!("Document not found: {id}");
+                    std::process::exit(1);
+                }
+                Ok(())
+            }
+        }
+    })
+}
+
+fn cmd_export(
+    config_path: Option<std::path::PathBuf>,
+    channel_id: String,
+    agent_id: Option<String>,
+    output: Option<std::path::PathBuf>,
+) -> anyhow::Result<()> {
+    let config = load_config(&config_path)?;
+    let agent_config = get_agent_config(&config, agent_id.as_deref())?
+        .resolve(&config.instance_dir, &config.defaults);
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("failed to build tokio runtime")?;
+
+    runtime.block_on(async {
+        let db =
+            spacebot::db::Db::connect(&agent_config.data_dir, &agent_config.id, &config.storage)
+                .await
+                .with_context(|| {
+                    format!("failed to open databases for agent '{}'", agent_config.id)
+                })?;
+        let export =
+            spacebot::conversation::ChannelExport::load(&db.sqlite, &agent_config.id, &channel_id)
+                .await
+                .with_context(|| format!("failed to export channel '{channel_id}'"))?;
+        if export.channel.is_none() && export.history.is_empty() && export.transcript.is_empty() {
+            anyhow::bail!("channel not found: {channel_id}");
+        }
+
+        let json = export.to_json()?;
+        match output {
+            Some(path) => {
+                std::fs::write(&path, json)
+                    .with_context(|| format!("failed to write {}", path.display()))?;
+                eprintln!(
+                    "Exported {channel_id}: {} messages, {} memories, {} worker results",
+                    export.transcript.len(),
+                    export.memories.len(),
+                    export.worker_results.len()
+                );
+            }
+            None => println!("{json}"),
+        }
+        Ok(())
+    })
+}
+
+fn cmd_import(
+    config_path: Option<std::path::PathBuf>,
+    path: std::path::PathBuf,
+    agent_id: Option<String>,
+    channel_id: Option<String>,
+    force: bool,
+) -> anyhow::Result<()> {
+    let paths = spacebot::daemon::DaemonPaths::from_default();
+
+    // A running instance holds the channel's history in memory and the
+    // LanceDB tables open, so it would overwrite or race the import.
+    if let Some(pid) = spacebot::daemon::is_running(&paths) {
+        eprintln!("spacebot is running (pid {pid}), stop it before importing");
+        std::process::exit(1);
+    }
+
+    let config = load_config(&config_path)?;
+    let agent_config = get_agent_config(&config, agent_id.as_deref())?
+        .resolve(&config.instance_dir, &config.defaults);
+
+    let json = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let export = spacebot::conversation::ChannelExport::from_json(&json)?;
+    let channel_id = channel_id.unwrap_or_else(|| export.channel_id.clone());
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("failed to build tokio runtime")?;
+
+    runtime.block_on(async {
+        let db =
+            spacebot::db::Db::connect(&agent_config.data_dir, &agent_config.id, &config.storage)
+                .await
+                .with_context(|| {
+                    format!("failed to open databases for agent '{}'", agent_config.id)
+                })?;
+        let embedding_model = Arc::new(
+            spacebot::memory::EmbeddingModel::new(&config.instance_dir.join("embedding_cache"))
+                .context("failed to initialize embedding model")?,
+        );
+        let embeddings = db.open_embeddings().await.with_context(|| {
+            format!("failed to init embeddings for agent '{}'", agent_config.id)
+        })?;
+        let memory_search = spacebot::memory::MemorySearch::new(
+            spacebot::memory::MemoryStore::with_agent_id(db.sqlite.clone(), &agent_config.id),
+            embeddings,
+            embedding_model,
+        );
+
+        let summary = export
+            .import(&db.sqlite, &memory_search, &channel_id, force)
+            .await
+            .with_context(|| format!("failed to import into channel '{channel_id}'"))?;
+        println!(
+            "Imported into {channel_id}: {} history messages, {} transcript messages, \
+             {} memories, {} worker results",
+            summary.history_messages,
+            summary.transcript_messages,
+            summary.memories,
+            summary.worker_results
+        );
+        Ok(())
+    })
+}
+
 fn resolve_skills_dir(
     config: &spacebot::config::Config,
     agent_id: Option<&str>,
@@ -600,6 +969,7 @@ async fn run(
     config: spacebot::config::Config,
     foreground: bool,
     otel_provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+    cli_adapter: Option<Arc<spacebot::messaging::cli::CliAdapter>>,
 ) -> anyhow::Result<()> {
     let paths = spacebot::daemon::DaemonPaths::new(&config.instance_dir);
 
@@ -700,9 +1070,11 @@ async fn run(
     // Initialize the language for all text lookups (must happen before PromptEngine/tools)
     spacebot::prompts::text::init("en").with_context(|| "failed to initialize language")?;
 
-    // Create the PromptEngine with bundled templates (no file watching, no user overrides)
+    // Create the PromptEngine with bundled templates, plus any overrides in the
+    // instance's prompts/ directory (reloaded by the file watcher)
     let prompt_engine = spacebot::prompts::PromptEngine::new("en")
-        .with_context(|| "failed to initialize prompt engine")?;
+        .with_context(|| "failed to initialize prompt engine")?
+        .with_overrides_from(&config.instance_dir.join("prompts"));
 
     // Parse config links into shared agent links (hot-reloadable via ArcSwap)
     let agent_links = Arc::new(ArcSwap::from_pointee(
@@ -736,6 +1108,8 @@ async fn run(
     api_state.set_embedding_model(embedding_model.clone()).await;
     api_state.set_prompt_engine(prompt_engine.clone()).await;
     api_state.set_defaults_config(config.defaults.clone()).await;
+    api_state.set_media_config(config.media.clone()).await;
+    api_state.set_storage_config(config.storage.clone()).await;
     api_state.set_agent_links((**agent_links.load()).clone());
     api_state.set_agent_groups(config.groups.clone());
     api_state.set_agent_humans(config.humans.clone());
@@ -771,6 +1145,7 @@ async fn run(
             &mut telegram_permissions,
             &mut twitch_permissions,
             agent_links.clone(),
+            cli_adapter.clone(),
         )
         .await?;
         agents_initialized = true;
@@ -818,6 +1193,15 @@ async fn run(
     // Active conversation channels: conversation_id -> ActiveChannel
     let mut active_channels: HashMap<String, ActiveChannel> = HashMap::new();
 
+    // Channels archive themselves after going idle; this sweep releases their
+    // entries so idle conversations don't pin API state indefinitely.
+    let mut archive_sweep = tokio::time::interval(std::time::Duration::from_secs(60));
+
+    // `spacebot stop` goes through IPC, but service managers and `docker stop`
+    // send SIGTERM.
+    let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+        .context("failed to install SIGTERM handler")?;
+
     // Main event loop: route inbound messages to agent channels
     loop {
         // Poll the inbound stream if it exists, otherwise yield a never-resolving future
@@ -844,6 +1228,15 @@ async fn run(
 
                 let conversation_id = message.conversation_id.clone();
 
+                // An archived channel is respawned below and rehydrated from the store
+                if active_channels
+                    .get(&conversation_id)
+                    .is_some_and(ActiveChannel::is_archived)
+                    && let Some(active) = active_channels.remove(&conversation_id)
+                {
+                    release_archived_channel(&api_state, &conversation_id, active).await;
+                }
+
                 // Find or create a channel for this conversation
                 if !active_channels.contains_key(&conversation_id) {
                     let Some(agent) = agents.get(&agent_id) else {
@@ -856,14 +1249,14 @@ async fn run(
                     };
 
                     // Create outbound response channel
-                    let (response_tx, mut response_rx) = mpsc::channel::<spacebot::OutboundResponse>(32);
+                    let (response_tx, mut response_rx) = mpsc::channel::<spacebot::ChannelResponse>(32);
 
                     // Subscribe to the agent's event bus
                     let event_rx = agent.deps.event_tx.subscribe();
 
                     let channel_id: spacebot::ChannelId = Arc::from(conversation_id.as_str());
 
-                    let (channel, channel_tx) = spacebot::agent::channel::Channel::new(
+                    let (mut channel, channel_tx) = spacebot::agent::channel::Channel::new(
                         channel_id,
                         agent.deps.clone(),
                         response_tx,
@@ -884,9 +1277,37 @@ async fn run(
                         channel.state.clone(),
                     ).await;
 
+                    // Restore persisted history from a previous run. When that
+                    // succeeds the platform backfill is redundant.
+                    let restored = channel.state.rehydrate_history().await;
+                    if restored > 0 {
+                        tracing::info!(
+                            conversation_id = %conversation_id,
+                            message_count = restored,
+                            "rehydrated channel history"
+                        );
+                    }
+                    if channel.restore_snapshot().await {
+                        tracing::info!(
+                            conversation_id = %conversation_id,
+                            "restored archived channel state"
+                        );
+                    }
+                    channel.restore_language().await;
+                    channel.restore_title().await;
+                    let replayed = channel.replay_journal().await;
+                    if replayed > 0 {
+                        tracing::info!(
+                            conversation_id = %conversation_id,
+                            event_count = replayed,
+                            "replayed journaled branch and worker results"
+                        );
+                    }
+                    let outbound_language = channel.state.language.clone();
+
                     // Backfill recent message history from the platform
                     let backfill_count = agent.config.history_backfill_count();
-                    if backfill_count > 0 {
+                    if backfill_count > 0 && restored == 0 {
                         match messaging_manager.fetch_history(&message, backfill_count).await {
                             Ok(history_messages) if !history_messages.is_empty() => {
                                 let mut transcript = String::new();
@@ -918,7 +1339,7 @@ async fn run(
                     }
 
                     // Spawn the channel's event loop
-                    tokio::spawn(async move {
+                    let channel_handle = tokio::spawn(async move {
                         if let Err(error) = channel.run().await {
                             tracing::error!(%error, "channel event loop failed");
                         }
@@ -927,6 +1348,9 @@ async fn run(
                     // Spawn outbound response routing: reads from response_rx,
                     // sends to the messaging adapter and forwards to SSE
                     let messaging_for_outbound = messaging_manager.clone();
+                    let dead_letters = spacebot::messaging::delivery::DeadLetterStore::new(
+                        agent.deps.sqlite_pool.clone(),
+                    );
                     let latest_message = Arc::new(tokio::sync::RwLock::new(message.clone()));
                     let outbound_message = latest_message.clone();
                     let outbound_conversation_id = conversation_id.clone();
@@ -934,8 +1358,15 @@ async fn run(
                     let sse_agent_id = agent_id.to_string();
                     let sse_channel_id = conversation_id.clone();
                     let outbound_agent_names = agent.deps.agent_names.clone();
+                    let outbound_redactor = spacebot::redaction::Redactor::new(
+                        agent.deps.sqlite_pool.clone(),
+                        agent.deps.runtime_config.clone(),
+                    );
+                    let outbound_middleware = agent.deps.outbound_middleware.clone();
                     let outbound_handle = tokio::spawn(async move {
-                        while let Some(response) = response_rx.recv().await {
+                        while let Some(spacebot::ChannelResponse { response, turn_id }) =
+                            response_rx.recv().await
+                        {
                             // Forward relevant events to SSE clients
                             match &response {
                                 spacebot::OutboundResponse::Text(text) => {
@@ -952,21 +1383,37 @@ async fn run(
                                         text: text.clone(),
                                     }).ok();
                                 }
-                                spacebot::OutboundResponse::ThreadReply { text, .. } => {
+                                spacebot::OutboundResponse::Reply { text, .. }
+                                | spacebot::OutboundResponse::ThreadReply { text, .. } => {
                                     api_event_tx.send(spacebot::api::ApiEvent::OutboundMessage {
                                         agent_id: sse_agent_id.clone(),
                                         channel_id: sse_channel_id.clone(),
                                         text: text.clone(),
                                     }).ok();
                                 }
-                                spacebot::OutboundResponse::Status(spacebot::StatusUpdate::Thinking) => {
+                                spacebot::OutboundResponse::Draft { text, approval_token } => {
+                                    api_event_tx.send(spacebot::api::ApiEvent::ReplyDraft {
+                                        agent_id: sse_agent_id.clone(),
+                                        channel_id: sse_channel_id.clone(),
+                                        approval_token: approval_token.clone(),
+                                        text: text.clone(),
+                                    }).ok();
+                                }
+                                spacebot::OutboundResponse::Status(
+                                    spacebot::StatusUpdate::Thinking
+                                    | spacebot::StatusUpdate::RunningTool { .. },
+                                ) => {
                                     api_event_tx.send(spacebot::api::ApiEvent::TypingState {
                                         agent_id: sse_agent_id.clone(),
                                         channel_id: sse_channel_id.clone(),
                                         is_typing: true,
                                     }).ok();
                                 }
-                                spacebot::OutboundResponse::Status(spacebot::StatusUpdate::StopTyping) => {
+                                spacebot::OutboundResponse::Status(
+                                    spacebot::StatusUpdate::StopTyping
+                                    | spacebot::StatusUpdate::Idle
+                                    | spacebot::StatusUpdate::WaitingOnWorker { .. },
+                                ) => {
                                     api_event_tx.send(spacebot::api::ApiEvent::TypingState {
                                         agent_id: sse_agent_id.clone(),
                                         channel_id: sse_channel_id.clone(),
@@ -976,13 +1423,28 @@ async fn run(
                                 _ => {}
                             }
 
-                            let current_message = outbound_message.read().await.clone();
+                            // Drafts wait for operator approval; once approved the
+                            // text comes back through here as a regular reply.
+                            if matches!(response, spacebot::OutboundResponse::Draft { .. }) {
+                                continue;
+                            }
+
+                            let mut current_message = outbound_message.read().await.clone();
+                            // Adapters and middleware can surface the turn that
+                            // produced the response without showing it in the text.
+                            if let Some(turn_id) = turn_id {
+                                current_message.metadata.insert(
+                                    spacebot::TURN_ID_METADATA_KEY.into(),
+                                    serde_json::json!(turn_id.to_string()),
+                                );
+                            }
 
                             // Internal link channels: route replies back to the sender's link channel
                             if current_message.source == "internal" {
                                 let reply_text = match &response {
                                     spacebot::OutboundResponse::Text(t) => Some(t.clone()),
                                     spacebot::OutboundResponse::RichMessage { text, .. } => Some(text.clone()),
+                                    spacebot::OutboundResponse::Reply { text, .. } => Some(text.clone()),
                                     spacebot::OutboundResponse::ThreadReply { text, .. } => Some(text.clone()),
                                     spacebot::OutboundResponse::Status(_) => None,
                                     _ => None,
@@ -1074,23 +1536,43 @@ async fn run(
 
                             match response {
                                 spacebot::OutboundResponse::Status(status) => {
+                                    // Adapters that show status text localize it
+                                    // to the conversation language.
+                                    let mut status_message = current_message;
+                                    if let Some(language) = outbound_language.read().await.clone() {
+                                        status_message.metadata.insert(
+                                            spacebot::prompts::language::METADATA_KEY.into(),
+                                            serde_json::json!(language),
+                                        );
+                                    }
                                     if let Err(error) = messaging_for_outbound
-                                        .send_status(&current_message, status)
+                                        .send_status(&status_message, status)
                                         .await
                                     {
                                         tracing::warn!(%error, "failed to send status update");
                                     }
                                 }
-                                response => {
+                                mut response => {
+                                    // Put back values the user shared that were
+                                    // redacted before reaching the LLM.
+                                    outbound_redactor
+                                        .restore_response(&outbound_conversation_id, &mut response);
                                     tracing::info!(
                                         conversation_id = %outbound_conversation_id,
+                                        turn_id = ?turn_id,
                                         "routing outbound response to messaging adapter"
                                     );
-                                    if let Err(error) = messaging_for_outbound
-                                        .respond(&current_message, response)
+                                    for response in outbound_middleware.run(&current_message, response).await {
+                                        if let Err(error) = spacebot::messaging::delivery::deliver(
+                                            &messaging_for_outbound,
+                                            &dead_letters,
+                                            &current_message,
+                                            response,
+                                        )
                                         .await
-                                    {
-                                        tracing::error!(%error, "failed to send outbound response");
+                                        {
+                                            tracing::error!(%error, "failed to send outbound response");
+                                        }
                                     }
                                 }
                             }
@@ -1100,7 +1582,8 @@ async fn run(
                     active_channels.insert(conversation_id.clone(), ActiveChannel {
                         message_tx: channel_tx,
                         latest_message,
-                        _outbound_handle: outbound_handle,
+                        outbound_handle,
+                        channel_handle,
                     });
 
                     tracing::info!(
@@ -1114,7 +1597,9 @@ async fn run(
                 if let Some(active) = active_channels.get(&conversation_id) {
                     // Update the shared message reference so outbound routing
                     // (typing indicators, reactions) targets this message
-                    *active.latest_message.write().await = message.clone();
+                    if !spacebot::agent::feedback::is_reaction(&message) {
+                        *active.latest_message.write().await = message.clone();
+                    }
 
                     // Emit inbound message to SSE clients
                     let sender_name = message.formatted_author.clone().or_else(|| {
@@ -1142,6 +1627,18 @@ async fn run(
                     }
                 }
             }
+            _ = archive_sweep.tick() => {
+                let archived: Vec<String> = active_channels
+                    .iter()
+                    .filter(|(_, active)| active.is_archived())
+                    .map(|(conversation_id, _)| conversation_id.clone())
+                    .collect();
+                for conversation_id in archived {
+                    if let Some(active) = active_channels.remove(&conversation_id) {
+                        release_archived_channel(&api_state, &conversation_id, active).await;
+                    }
+                }
+            }
             Some(agent) = agent_rx.recv() => {
                 tracing::info!(agent_id = %agent.id, "registering new agent in main loop");
                 agents.insert(agent.id.clone(), agent);
@@ -1204,6 +1701,7 @@ async fn run(
                                     &mut new_telegram_permissions,
                                     &mut new_twitch_permissions,
                                     agent_links.clone(),
+                                    cli_adapter.clone(),
                                 ).await {
                                     Ok(()) => {
                                         agents_initialized = true;
@@ -1249,11 +1747,64 @@ async fn run(
                 tracing::info!("shutdown signal received");
                 break;
             }
+            _ = sigterm.recv() => {
+                tracing::info!("SIGTERM received");
+                break;
+            }
+            _ = async {
+                match cli_adapter.as_ref() {
+                    Some(adapter) => adapter.closed().await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                tracing::info!("chat session ended");
+                break;
+            }
         }
     }
 
-    // Graceful shutdown
-    drop(active_channels);
+    // Graceful shutdown. The loop above no longer routes messages; channels
+    // finish in-flight work, persist their state, and post any shutdown
+    // notice before the adapters go away.
+    let drain_secs = agents
+        .values()
+        .map(|agent| **agent.deps.runtime_config.shutdown_drain_secs.load())
+        .max()
+        .unwrap_or(0);
+    for (agent_id, agent) in &agents {
+        agent
+            .deps
+            .event_tx
+            .send(spacebot::ProcessEvent::Shutdown {
+                agent_id: agent_id.clone(),
+            })
+            .ok();
+    }
+    let (channel_handles, mut outbound_handles): (Vec<_>, Vec<_>) = active_channels
+        .into_values()
+        .map(|active| (active.channel_handle, active.outbound_handle))
+        .unzip();
+    if !channel_handles.is_empty() {
+        tracing::info!(
+            channels = channel_handles.len(),
+            drain_secs,
+            "draining channels"
+        );
+        // A little past the channels' own deadline, so they can persist.
+        let deadline = std::time::Duration::from_secs(drain_secs + 5);
+        if tokio::time::timeout(deadline, futures::future::join_all(channel_handles))
+            .await
+            .is_err()
+        {
+            tracing::warn!("channels did not stop before the shutdown deadline");
+        }
+        // Give outbound routing a moment to deliver the final replies.
+        let flush = futures::future::join_all(outbound_handles.iter_mut());
+        let _ = tokio::time::timeout(std::time::Duration::from_secs(2), flush).await;
+    }
+    for handle in outbound_handles {
+        handle.abort();
+    }
 
     for scheduler in &cron_schedulers_for_shutdown {
         scheduler.shutdown().await;
@@ -1313,6 +1864,7 @@ async fn initialize_agents(
     telegram_permissions: &mut Option<Arc<ArcSwap<spacebot::config::TelegramPermissions>>>,
     twitch_permissions: &mut Option<Arc<ArcSwap<spacebot::config::TwitchPermissions>>>,
     agent_links: Arc<ArcSwap<Vec<spacebot::links::AgentLink>>>,
+    cli_adapter: Option<Arc<spacebot::messaging::cli::CliAdapter>>,
 ) -> anyhow::Result<()> {
     let resolved_agents = config.resolve_agents();
 
@@ -1363,14 +1915,15 @@ async fn initialize_agents(
         })?;
 
         // Per-agent database connections
-        let db = spacebot::db::Db::connect(&agent_config.data_dir)
-            .await
-            .with_context(|| {
-                format!(
-                    "failed to connect databases for agent '{}'",
-                    agent_config.id
-                )
-            })?;
+        let db =
+            spacebot::db::Db::connect(&agent_config.data_dir, &agent_config.id, &config.storage)
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed to connect databases for agent '{}'",
+                        agent_config.id
+                    )
+                })?;
 
         // Per-agent settings store (redb-backed)
         let settings_path = agent_config.data_dir.join("settings.redb");
@@ -1386,23 +1939,33 @@ async fn initialize_agents(
         // Per-agent memory system
         let memory_store =
             spacebot::memory::MemoryStore::with_agent_id(db.sqlite.clone(), &agent_config.id);
-        let embedding_table = spacebot::memory::EmbeddingTable::open_or_create(&db.lance)
-            .await
-            .with_context(|| {
-                format!("failed to init embeddings for agent '{}'", agent_config.id)
-            })?;
+        let embeddings = db.open_embeddings().await.with_context(|| {
+            format!("failed to init embeddings for agent '{}'", agent_config.id)
+        })?;
 
         // Ensure FTS index exists for full-text search queries
-        if let Err(error) = embedding_table.ensure_fts_index().await {
+        if let Err(error) = embeddings.ensure_fts_index().await {
             tracing::warn!(%error, agent = %agent_config.id, "failed to create FTS index");
         }
 
         let memory_search = Arc::new(spacebot::memory::MemorySearch::new(
             memory_store,
-            embedding_table,
+            embeddings,
             embedding_model.clone(),
         ));
 
+        let knowledge_chunks = db.open_knowledge_chunks().await.with_context(|| {
+            format!(
+                "failed to init knowledge base for agent '{}'",
+                agent_config.id
+            )
+        })?;
+        let knowledge = spacebot::knowledge::KnowledgeBase::new(
+            db.sqlite.clone(),
+            knowledge_chunks,
+            embedding_model.clone(),
+        );
+
         // Per-agent event bus (broadcast for fan-out to multiple channels)
         let (event_tx, _event_rx) = tokio::sync::broadcast::channel(256);
 
@@ -1459,9 +2022,25 @@ async fn initialize_agents(
             .await,
         );
 
+        let media = spacebot::media::MediaStore::open(
+            db.sqlite.clone(),
+            agent_id.clone(),
+            &config.media,
+            &config.instance_dir,
+            &agent_config.data_dir,
+        )
+        .await
+        .with_context(|| format!("failed to init media store for agent '{}'", agent_config.id))?;
+
+        let worker_pool = spacebot::agent::worker_pool::WorkerPool::new(
+            agent_id.clone(),
+            runtime_config.clone(),
+            event_tx.clone(),
+        );
         let deps = spacebot::AgentDeps {
             agent_id: agent_id.clone(),
             memory_search,
+            knowledge,
             llm_manager: llm_manager.clone(),
             mcp_manager,
             cron_tool: None,
@@ -1472,6 +2051,10 @@ async fn initialize_agents(
             sandbox,
             links: agent_links.clone(),
             agent_names: agent_name_map.clone(),
+            worker_pool,
+            media,
+            inbound_middleware: Default::default(),
+            outbound_middleware: spacebot::middleware::OutboundChain::standard(),
         };
 
         let agent = spacebot::Agent {
@@ -1492,6 +2075,8 @@ async fn initialize_agents(
         let mut agent_pools = std::collections::HashMap::new();
         let mut agent_configs = Vec::new();
         let mut memory_searches = std::collections::HashMap::new();
+        let mut knowledge_bases = std::collections::HashMap::new();
+        let mut media_stores = std::collections::HashMap::new();
         let mut mcp_managers = std::collections::HashMap::new();
         let mut agent_workspaces = std::collections::HashMap::new();
         let mut runtime_configs = std::collections::HashMap::new();
@@ -1501,6 +2086,8 @@ async fn initialize_agents(
             api_state.register_agent_events(agent_id.to_string(), event_rx);
             agent_pools.insert(agent_id.to_string(), agent.db.sqlite.clone());
             memory_searches.insert(agent_id.to_string(), agent.deps.memory_search.clone());
+            knowledge_bases.insert(agent_id.to_string(), agent.deps.knowledge.clone());
+            media_stores.insert(agent_id.to_string(), agent.deps.media.clone());
             mcp_managers.insert(agent_id.to_string(), agent.deps.mcp_manager.clone());
             agent_workspaces.insert(agent_id.to_string(), agent.config.workspace.clone());
             runtime_configs.insert(agent_id.to_string(), agent.deps.runtime_config.clone());
@@ -1519,6 +2106,8 @@ async fn initialize_agents(
         api_state.set_agent_pools(agent_pools);
         api_state.set_agent_configs(agent_configs);
         api_state.set_memory_searches(memory_searches);
+        api_state.set_knowledge_bases(knowledge_bases);
+        api_state.set_media_stores(media_stores);
         api_state.set_mcp_managers(mcp_managers);
         api_state.set_runtime_configs(runtime_configs);
         api_state.set_agent_workspaces(agent_workspaces);
@@ -1526,6 +2115,16 @@ async fn initialize_agents(
         api_state.set_instance_dir(config.instance_dir.clone());
     }
 
+    // Forward selected process events to the configured webhook sinks
+    let event_sinks = spacebot::event_sink::EventSinks::new(config.event_sinks.clone());
+    if !event_sinks.is_empty() {
+        for (agent_id, agent) in agents.iter() {
+            event_sinks.register_agent(agent_id.to_string(), agent.deps.event_tx.subscribe());
+        }
+        tracing::info!(sinks = config.event_sinks.len(), "event sinks enabled");
+    }
+    api_state.set_event_sinks(event_sinks).await;
+
     // Initialize messaging adapters
     let new_messaging_manager = spacebot::messaging::MessagingManager::new();
 
@@ -1606,6 +2205,7 @@ async fn initialize_agents(
             webhook_config.port,
             &webhook_config.bind,
             webhook_config.auth_token.clone(),
+            webhook_config.sources.clone(),
         );
         new_messaging_manager.register(adapter).await;
     }
@@ -1637,12 +2237,29 @@ async fn initialize_agents(
         new_messaging_manager.register(adapter).await;
     }
 
+    if let Some(email_config) = &config.messaging.email
+        && email_config.enabled
+    {
+        match spacebot::messaging::email::EmailAdapter::new(email_config.clone()) {
+            Ok(adapter) => {
+                new_messaging_manager.register(adapter).await;
+            }
+            Err(error) => {
+                tracing::error!(%error, "failed to build email adapter");
+            }
+        }
+    }
+
     let webchat_adapter = Arc::new(spacebot::messaging::webchat::WebChatAdapter::new());
     new_messaging_manager
         .register_shared(webchat_adapter.clone())
         .await;
     api_state.set_webchat_adapter(webchat_adapter);
 
+    if let Some(adapter) = cli_adapter {
+        new_messaging_manager.register_shared(adapter).await;
+    }
+
     *messaging_manager = Arc::new(new_messaging_manager);
     api_state
         .set_messaging_manager(messaging_manager.clone())
@@ -1671,11 +2288,13 @@ async fn initialize_agents(
                 id: cron_def.id.clone(),
                 prompt: cron_def.prompt.clone(),
                 interval_secs: cron_def.interval_secs,
+                cron_expr: cron_def.cron_expr.clone(),
                 delivery_target: cron_def.delivery_target.clone(),
                 active_hours: cron_def.active_hours,
                 enabled: cron_def.enabled,
                 run_once: cron_def.run_once,
                 timeout_secs: cron_def.timeout_secs,
+                stateful: cron_def.stateful,
             };
             if let Err(error) = store.save(&cron_config).await {
                 tracing::warn!(
@@ -1732,6 +2351,15 @@ async fn initialize_agents(
     api_state.set_cron_schedulers(cron_schedulers_map);
     tracing::info!("cron stores and schedulers registered with API state");
 
+    // Re-send outbound responses that were dead-lettered while an adapter was down
+    for (agent_id, agent) in agents.iter() {
+        spacebot::messaging::delivery::spawn_redelivery_loop(
+            messaging_manager.clone(),
+            spacebot::messaging::delivery::DeadLetterStore::new(agent.db.sqlite.clone()),
+        );
+        tracing::info!(agent_id = %agent_id, "dead-letter redelivery loop started");
+    }
+
     // Start memory ingestion loops for each agent
     for (agent_id, agent) in agents.iter() {
         let ingestion_config = **agent.deps.runtime_config.ingestion.load();
@@ -1745,7 +2373,7 @@ async fn initialize_agents(
         }
     }
 
-    // Start cortex warmup, bulletin loops, and association loops for each agent
+    // Start cortex warmup, bulletin, association, maintenance, heartbeat, calendar sync, reminder, and feed watcher loops for each agent
     for (agent_id, agent) in agents.iter() {
         let cortex_logger = spacebot::agent::cortex::CortexLogger::new(agent.db.sqlite.clone());
         let warmup_handle =
@@ -1758,10 +2386,33 @@ async fn initialize_agents(
         cortex_handles.push(bulletin_handle);
         tracing::info!(agent_id = %agent_id, "cortex bulletin loop started");
 
-        let association_handle =
-            spacebot::agent::cortex::spawn_association_loop(agent.deps.clone(), cortex_logger);
+        let association_handle = spacebot::agent::cortex::spawn_association_loop(
+            agent.deps.clone(),
+            cortex_logger.clone(),
+        );
         cortex_handles.push(association_handle);
         tracing::info!(agent_id = %agent_id, "cortex association loop started");
+
+        let maintenance_handle =
+            spacebot::agent::cortex::spawn_maintenance_loop(agent.deps.clone(), cortex_logger);
+        cortex_handles.push(maintenance_handle);
+        tracing::info!(agent_id = %agent_id, "cortex maintenance loop started");
+
+        let heartbeat_handle = spacebot::agent::heartbeat::spawn_heartbeat_loop(agent.deps.clone());
+        cortex_handles.push(heartbeat_handle);
+        tracing::info!(agent_id = %agent_id, "heartbeat loop started");
+
+        let calendar_handle = spacebot::calendar::spawn_sync_loop(agent.deps.clone());
+        cortex_handles.push(calendar_handle);
+        tracing::info!(agent_id = %agent_id, "calendar sync loop started");
+
+        let reminder_handle = spacebot::reminders::spawn_reminder_loop(agent.deps.clone());
+        cortex_handles.push(reminder_handle);
+        tracing::info!(agent_id = %agent_id, "reminder loop started");
+
+        let feed_handle = spacebot::feeds::spawn_feed_loop(agent.deps.clone());
+        cortex_handles.push(feed_handle);
+        tracing::info!(agent_id = %agent_id, "feed watcher loop started");
     }
 
     // Create cortex chat sessions for each agent
@@ -1769,22 +2420,28 @@ async fn initialize_agents(
         let mut sessions = std::collections::HashMap::new();
         for (agent_id, agent) in agents.iter() {
             let browser_config = (**agent.deps.runtime_config.browser_config.load()).clone();
-            let brave_search_key = (**agent.deps.runtime_config.brave_search_key.load()).clone();
+            let web_search = agent.deps.runtime_config.web_search.load().configured();
             let conversation_logger =
                 spacebot::conversation::history::ConversationLogger::new(agent.db.sqlite.clone());
             let channel_store = spacebot::conversation::ChannelStore::new(agent.db.sqlite.clone());
             let run_logger = spacebot::conversation::ProcessRunLogger::new(agent.db.sqlite.clone());
             let tool_server = spacebot::tools::create_cortex_chat_tool_server(
                 agent.deps.memory_search.clone(),
+                agent.deps.knowledge.clone(),
                 conversation_logger,
                 channel_store,
                 run_logger,
                 &agent.deps.agent_id,
                 browser_config,
                 agent.config.screenshot_dir(),
-                brave_search_key,
+                web_search,
                 agent.deps.runtime_config.workspace_dir.clone(),
                 agent.deps.sandbox.clone(),
+                agent.deps.runtime_config.clone(),
+                spacebot::tools::OutputSpill::new(
+                    agent.db.sqlite.clone(),
+                    agent.deps.runtime_config.clone(),
+                ),
             );
             let store = spacebot::agent::cortex_chat::CortexChatStore::new(agent.db.sqlite.clone());
             let session = spacebot::agent::cortex_chat::CortexChatSession::new(
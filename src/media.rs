@@ -0,0 +1,15 @@
+//! Attachment storage: files users send, files workers produce, and files
+//! the agent sends back.
+//!
+//! Each agent has a [`MediaStore`] that keeps file bytes on local disk or in
+//! an S3-compatible bucket and indexes them in the agent's SQLite database.
+//! Stored files get a stable id that tools can reference in later turns
+//! (`send_file` accepts a `media_id`), and a signed, expiring URL that can be
+//! handed to platforms or users. Local files are served from
+//! `/media/{agent_id}/{id}` on the API server, which checks the signature
+//! instead of the API token.
+
+pub mod s3;
+pub mod store;
+
+pub use store::{MediaRef, MediaSource, MediaStore, StoredMedia};
@@ -0,0 +1,458 @@
+//! Per-agent attachment store (SQLite index + local disk or S3 bytes).
+
+use super::s3::S3Client;
+use crate::AgentId;
+use crate::config::{MediaBackendConfig, MediaConfig};
+use crate::error::Result;
+
+use anyhow::Context as _;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Row as _, SqlitePool};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Generated signing key, kept in the instance directory.
+const SIGNING_KEY_FILE: &str = "media_signing_key";
+
+const MEDIA_COLUMNS: &str =
+    "id, channel_id, source, filename, mime_type, size_bytes, sha256, created_at";
+
+/// Where a stored file came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaSource {
+    /// An attachment on an inbound message.
+    Inbound,
+    /// A file a worker produced.
+    Worker,
+    /// A file the agent sent with `send_file`.
+    Tool,
+}
+
+impl MediaSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Inbound => "inbound",
+            Self::Worker => "worker",
+            Self::Tool => "tool",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "inbound" => Self::Inbound,
+            "worker" => Self::Worker,
+            _ => Self::Tool,
+        }
+    }
+}
+
+/// A stored file's metadata.
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredMedia {
+    pub id: String,
+    pub channel_id: Option<String>,
+    pub source: MediaSource,
+    pub filename: String,
+    pub mime_type: String,
+    pub size_bytes: u64,
+    /// Hex SHA-256 of the content.
+    pub sha256: String,
+    pub created_at: String,
+}
+
+/// Stable id of a stored file plus a link to fetch it, carried on outbound
+/// file responses so adapters can link instead of upload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaRef {
+    pub id: String,
+    /// Signed link, valid for `media.url_ttl_secs` after it was made.
+    pub url: String,
+}
+
+enum Backend {
+    Local(PathBuf),
+    S3 { client: S3Client, prefix: String },
+}
+
+/// Per-agent attachment store. Cheap to clone.
+#[derive(Clone)]
+pub struct MediaStore {
+    pool: SqlitePool,
+    agent_id: AgentId,
+    backend: Arc<Backend>,
+    signing_key: Arc<[u8]>,
+    public_url: Arc<str>,
+    url_ttl_secs: u64,
+    max_file_bytes: u64,
+}
+
+impl std::fmt::Debug for MediaStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MediaStore")
+            .field("agent_id", &self.agent_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MediaStore {
+    /// Open the store for one agent. Local files go under `data_dir/media`
+    /// unless the config names another root.
+    pub async fn open(
+        pool: SqlitePool,
+        agent_id: AgentId,
+        config: &MediaConfig,
+        instance_dir: &Path,
+        data_dir: &Path,
+    ) -> Result<Self> {
+        let backend = match &config.backend {
+            MediaBackendConfig::Local { path } => {
+                let dir = match path {
+                    Some(root) => root.join(agent_id.as_ref()),
+                    None => data_dir.join("media"),
+                };
+                tokio::fs::create_dir_all(&dir)
+                    .await
+                    .with_context(|| format!("failed to create {}", dir.display()))?;
+                Backend::Local(dir)
+            }
+            MediaBackendConfig::S3(s3) => Backend::S3 {
+                prefix: format!("{}{agent_id}/", s3.prefix),
+                client: S3Client::new(s3.clone()),
+            },
+        };
+
+        Ok(Self {
+            pool,
+            agent_id,
+            backend: Arc::new(backend),
+            signing_key: load_signing_key(config, instance_dir).await?.into(),
+            public_url: config.public_url.as_str().into(),
+            url_ttl_secs: config.url_ttl_secs,
+            max_file_bytes: config.max_file_bytes,
+        })
+    }
+
+    pub fn max_file_bytes(&self) -> u64 {
+        self.max_file_bytes
+    }
+
+    /// Store `data` and return its metadata.
+    pub async fn put(
+        &self,
+        source: MediaSource,
+        channel_id: Option<&str>,
+        filename: &str,
+        mime_type: &str,
+        data: Vec<u8>,
+    ) -> Result<StoredMedia> {
+        let size_bytes = data.len() as u64;
+        if size_bytes > self.max_file_bytes {
+            return Err(anyhow::anyhow!(
+                "{filename} is {size_bytes} bytes, over the {} byte media limit",
+                self.max_file_bytes
+            )
+            .into());
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let sha256 = hex::encode(Sha256::digest(&data));
+        match self.backend.as_ref() {
+            Backend::Local(dir) => {
+                tokio::fs::write(dir.join(&id), &data)
+                    .await
+                    .with_context(|| format!("failed to write media file {id}"))?;
+            }
+            Backend::S3 { client, prefix } => {
+                client
+                    .put(&format!("{prefix}{id}"), mime_type, data)
+                    .await?;
+            }
+        }
+
+        sqlx::query(
+            "INSERT INTO media (id, channel_id, source, filename, mime_type, size_bytes, sha256) \
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(channel_id)
+        .bind(source.as_str())
+        .bind(filename)
+        .bind(mime_type)
+        .bind(size_bytes as i64)
+        .bind(&sha256)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        tracing::debug!(media_id = %id, %filename, size_bytes, source = source.as_str(), "media stored");
+
+        Ok(StoredMedia {
+            id,
+            channel_id: channel_id.map(String::from),
+            source,
+            filename: filename.to_string(),
+            mime_type: mime_type.to_string(),
+            size_bytes,
+            sha256,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        })
+    }
+
+    /// Store a file from disk, guessing its type from the extension.
+    pub async fn put_file(
+        &self,
+        source: MediaSource,
+        channel_id: Option<&str>,
+        path: &Path,
+    ) -> Result<StoredMedia> {
+        let metadata = tokio::fs::metadata(path)
+            .await
+            .with_context(|| format!("failed to stat {}", path.display()))?;
+        if metadata.len() > self.max_file_bytes {
+            return Err(anyhow::anyhow!(
+                "{} is {} bytes, over the {} byte media limit",
+                path.display(),
+                metadata.len(),
+                self.max_file_bytes
+            )
+            .into());
+        }
+        let data = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "file".into());
+        let mime_type = mime_guess::from_path(path)
+            .first_or_octet_stream()
+            .to_string();
+        self.put(source, channel_id, &filename, &mime_type, data)
+            .await
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<StoredMedia>> {
+        let row = sqlx::query(&format!("SELECT {MEDIA_COLUMNS} FROM media WHERE id = ?"))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+        Ok(row.map(row_to_media))
+    }
+
+    /// Metadata and content of a stored file.
+    pub async fn read(&self, id: &str) -> Result<Option<(StoredMedia, Vec<u8>)>> {
+        let Some(media) = self.get(id).await? else {
+            return Ok(None);
+        };
+        let data = match self.backend.as_ref() {
+            Backend::Local(dir) => match tokio::fs::read(dir.join(&media.id)).await {
+                Ok(data) => Some(data),
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+                Err(error) => {
+                    return Err(anyhow::Error::new(error)
+                        .context(format!("failed to read media file {id}"))
+                        .into());
+                }
+            },
+            Backend::S3 { client, prefix } => client.get(&format!("{prefix}{}", media.id)).await?,
+        };
+        Ok(data.map(|data| (media, data)))
+    }
+
+    /// Files stored for a channel, newest first.
+    pub async fn list(&self, channel_id: &str, limit: i64) -> Result<Vec<StoredMedia>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {MEDIA_COLUMNS} FROM media WHERE channel_id = ? \
+             ORDER BY created_at DESC LIMIT ?"
+        ))
+        .bind(channel_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+        Ok(rows.into_iter().map(row_to_media).collect())
+    }
+
+    /// A link to the file that expires after `media.url_ttl_secs`. S3 files
+    /// get a presigned bucket link; local files a signed link to this
+    /// instance's `/media` route.
+    pub fn url(&self, media: &StoredMedia) -> String {
+        let now = chrono::Utc::now();
+        match self.backend.as_ref() {
+            Backend::S3 { client, prefix } => {
+                client.presigned_get(&format!("{prefix}{}", media.id), self.url_ttl_secs, now)
+            }
+            Backend::Local(_) => {
+                let expires = now.timestamp() + self.url_ttl_secs as i64;
+                format!(
+                    "{}/media/{}/{}?expires={expires}&signature={}",
+                    self.public_url,
+                    self.agent_id,
+                    media.id,
+                    self.signature(&media.id, expires)
+                )
+            }
+        }
+    }
+
+    pub fn reference(&self, media: &StoredMedia) -> MediaRef {
+        MediaRef {
+            id: media.id.clone(),
+            url: self.url(media),
+        }
+    }
+
+    /// Check a signed local link's expiry and signature.
+    pub fn verify(&self, id: &str, expires: i64, signature: &str) -> bool {
+        if expires < chrono::Utc::now().timestamp() {
+            return false;
+        }
+        let Ok(signature) = hex::decode(signature) else {
+            return false;
+        };
+        self.mac(id, expires).verify_slice(&signature).is_ok()
+    }
+
+    fn signature(&self, id: &str, expires: i64) -> String {
+        hex::encode(self.mac(id, expires).finalize().into_bytes())
+    }
+
+    fn mac(&self, id: &str, expires: i64) -> HmacSha256 {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.signing_key).expect("HMAC accepts keys of any length");
+        mac.update(format!("{}/{id}/{expires}", self.agent_id).as_bytes());
+        mac
+    }
+}
+
+/// The configured signing key, or the instance's generated one.
+async fn load_signing_key(config: &MediaConfig, instance_dir: &Path) -> Result<Vec<u8>> {
+    if let Some(key) = &config.signing_key {
+        return Ok(key.as_bytes().to_vec());
+    }
+
+    let path = instance_dir.join(SIGNING_KEY_FILE);
+    match tokio::fs::read_to_string(&path).await {
+        Ok(existing) if !existing.trim().is_empty() => {
+            return Ok(existing.trim().as_bytes().to_vec());
+        }
+        Ok(_) => {}
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+        Err(error) => {
+            return Err(anyhow::Error::new(error)
+                .context(format!("failed to read {}", path.display()))
+                .into());
+        }
+    }
+
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    let key = hex::encode(bytes);
+    tokio::fs::write(&path, &key)
+        .await
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(key.into_bytes())
+}
+
+fn row_to_media(row: sqlx::sqlite::SqliteRow) -> StoredMedia {
+    StoredMedia {
+        id: row.try_get("id").unwrap_or_default(),
+        channel_id: row.try_get("channel_id").ok().flatten(),
+        source: MediaSource::parse(&row.try_get::<String, _>("source").unwrap_or_default()),
+        filename: row.try_get("filename").unwrap_or_default(),
+        mime_type: row.try_get("mime_type").unwrap_or_default(),
+        size_bytes: row.try_get::<i64, _>("size_bytes").unwrap_or(0).max(0) as u64,
+        sha256: row.try_get("sha256").unwrap_or_default(),
+        created_at: row
+            .try_get::<Option<chrono::DateTime<chrono::Utc>>, _>("created_at")
+            .ok()
+            .flatten()
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn store(dir: &Path) -> MediaStore {
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .in_memory(true)
+            .create_if_missing(true);
+        let pool = sqlx::pool::PoolOptions::<sqlx::Sqlite>::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("in-memory SQLite");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+        let config = MediaConfig {
+            public_url: "https://bot.example.com".into(),
+            max_file_bytes: 16,
+            ..MediaConfig::default()
+        };
+        MediaStore::open(pool, Arc::from("main"), &config, dir, dir)
+            .await
+            .expect("media store")
+    }
+
+    #[tokio::test]
+    async fn stored_files_round_trip_behind_signed_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path()).await;
+
+        let media = store
+            .put(
+                MediaSource::Inbound,
+                Some("discord:1:2"),
+                "notes.txt",
+                "text/plain",
+                b"hello".to_vec(),
+            )
+            .await
+            .unwrap();
+        let (stored, data) = store.read(&media.id).await.unwrap().unwrap();
+        assert_eq!(stored.filename, "notes.txt");
+        assert_eq!(stored.source, MediaSource::Inbound);
+        assert_eq!(data, b"hello");
+        assert_eq!(store.list("discord:1:2", 10).await.unwrap().len(), 1);
+
+        let url = store.url(&media);
+        assert!(url.starts_with(&format!("https://bot.example.com/media/main/{}?", media.id)));
+        let query: std::collections::HashMap<_, _> = url
+            .split_once('?')
+            .unwrap()
+            .1
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .collect();
+        let expires: i64 = query["expires"].parse().unwrap();
+        assert!(store.verify(&media.id, expires, query["signature"]));
+        assert!(!store.verify(&media.id, expires + 1, query["signature"]));
+        assert!(!store.verify("other", expires, query["signature"]));
+        assert!(!store.verify(&media.id, 0, query["signature"]));
+
+        assert!(
+            store
+                .put(
+                    MediaSource::Tool,
+                    None,
+                    "big.bin",
+                    "application/octet-stream",
+                    vec![0; 17]
+                )
+                .await
+                .is_err()
+        );
+    }
+}
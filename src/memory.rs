@@ -3,12 +3,20 @@
 pub mod embedding;
 pub mod lance;
 pub mod maintenance;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod qdrant;
 pub mod search;
+pub mod sqlite;
 pub mod store;
 pub mod types;
+pub mod vector_store;
 
 pub use embedding::EmbeddingModel;
-pub use lance::EmbeddingTable;
+pub use lance::{EmbeddingTable, VectorIndexStatus};
 pub use search::{MemorySearch, SearchConfig, SearchMode, SearchSort, curate_results};
 pub use store::MemoryStore;
-pub use types::{Association, Memory, MemoryType, RelationType};
+pub use types::{
+    Association, Memory, MemoryAudience, MemoryKind, MemoryScope, MemoryType, RelationType,
+};
+pub use vector_store::{EmbeddingStore, EmbeddingStoreDyn};
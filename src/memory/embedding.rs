@@ -54,6 +54,14 @@ impl EmbeddingModel {
 
         Ok(result.into_iter().next().unwrap_or_default())
     }
+
+    /// Generate embeddings for multiple texts (async, spawns blocking task).
+    pub async fn embed_many(self: &Arc<Self>, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let model = self.clone();
+        tokio::task::spawn_blocking(move || model.embed(texts))
+            .await
+            .map_err(|e| crate::Error::Other(anyhow::anyhow!("embedding task failed: {}", e)))?
+    }
 }
 
 /// Async function to embed text using a shared model.
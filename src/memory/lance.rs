@@ -1,25 +1,38 @@
 //! LanceDB table management and embedding storage with HNSW vector index and FTS.
 
 use crate::error::{DbError, Result};
+use crate::memory::vector_store::EmbeddingStore;
+use crate::memory::{Memory, MemoryAudience};
 use arrow_array::cast::AsArray;
 use arrow_array::types::Float32Type;
 use arrow_array::{Array, RecordBatchIterator};
 use futures::TryStreamExt;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 /// Schema constants for the embeddings table.
 const TABLE_NAME: &str = "memory_embeddings";
 const EMBEDDING_DIM: i32 = 384; // all-MiniLM-L6-v2 dimension
 
+/// Rows a table needs before an ANN index is worth building. Below this a
+/// flat scan is fast, and IVF training has too few vectors to cluster.
+const VECTOR_INDEX_MIN_ROWS: usize = 1_000;
+
+/// Rows added before an existing index is brought up to date. Until then
+/// they are still found, by a flat scan alongside the index.
+const VECTOR_INDEX_REFRESH_ROWS: usize = 256;
+
 /// LanceDB table for memory embeddings with HNSW index and FTS.
 pub struct EmbeddingTable {
     table: lancedb::Table,
+    vector_index: VectorIndex,
 }
 
 impl Clone for EmbeddingTable {
     fn clone(&self) -> Self {
         Self {
             table: self.table.clone(),
+            vector_index: self.vector_index.clone(),
         }
     }
 }
@@ -30,9 +43,14 @@ impl EmbeddingTable {
     /// If the table exists but is corrupted (e.g. process killed mid-write),
     /// it is dropped and recreated. Embeddings can be regenerated from SQLite.
     pub async fn open_or_create(connection: &lancedb::Connection) -> Result<Self> {
-        // Try to open existing table
+        // Try to open existing table, catching its index up with any rows
+        // written since the last refresh
         match connection.open_table(TABLE_NAME).execute().await {
-            Ok(table) => return Ok(Self { table }),
+            Ok(table) => {
+                let table = Self::new(table);
+                table.vector_index.refresh_in_background();
+                return Ok(table);
+            }
             Err(error) => {
                 tracing::debug!(%error, "failed to open embeddings table, will create");
             }
@@ -40,7 +58,7 @@ impl EmbeddingTable {
 
         // Table doesn't exist or is unreadable — try creating it
         match Self::create_empty_table(connection).await {
-            Ok(table) => return Ok(Self { table }),
+            Ok(table) => return Ok(Self::new(table)),
             Err(error) => {
                 tracing::warn!(
                     %error,
@@ -58,7 +76,17 @@ impl EmbeddingTable {
         let table = Self::create_empty_table(connection).await?;
         tracing::info!("embeddings table recovered — embeddings will be rebuilt from memory store");
 
-        Ok(Self { table })
+        Ok(Self::new(table))
+    }
+
+    fn new(table: lancedb::Table) -> Self {
+        // vector_search doesn't set a metric, so queries use LanceDB's
+        // default; the index has to be built for the same one.
+        let vector_index = VectorIndex::new(table.clone(), lancedb::DistanceType::L2);
+        Self {
+            table,
+            vector_index,
+        }
     }
 
     /// Create an empty embeddings table.
@@ -118,6 +146,7 @@ impl EmbeddingTable {
             .execute()
             .await
             .map_err(|e| DbError::LanceDb(e.to_string()))?;
+        self.vector_index.note_added(1);
 
         Ok(())
     }
@@ -295,19 +324,10 @@ impl EmbeddingTable {
         Ok(matches)
     }
 
-    /// Create HNSW vector index and FTS index for better performance.
-    /// Should be called after enough data accumulates.
-    pub async fn create_indexes(&self) -> Result<()> {
-        // Create HNSW vector index on embedding column
-        self.table
-            .create_index(&["embedding"], lancedb::index::Index::Auto)
-            .execute()
-            .await
-            .map_err(|e| DbError::LanceDb(format!("Failed to create vector index: {}", e)))?;
-
-        self.ensure_fts_index().await?;
-
-        Ok(())
+    /// Build the vector index now if the table is large enough, or fold
+    /// new rows into it. Writes already do this in the background.
+    pub async fn refresh_vector_index(&self) -> Result<VectorIndexStatus> {
+        self.vector_index.refresh().await
     }
 
     /// Ensure the FTS index exists on the content column.
@@ -369,3 +389,157 @@ impl EmbeddingTable {
         Ok(())
     }
 }
+
+impl EmbeddingStore for EmbeddingTable {
+    async fn store(&self, memory: &Memory, embedding: &[f32]) -> Result<()> {
+        EmbeddingTable::store(self, &memory.id, &memory.content, embedding).await
+    }
+
+    async fn delete(&self, memory_id: &str) -> Result<()> {
+        EmbeddingTable::delete(self, memory_id).await
+    }
+
+    async fn vector_search(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        _audience: Option<&MemoryAudience>,
+    ) -> Result<Vec<(String, f32)>> {
+        EmbeddingTable::vector_search(self, query_embedding, limit).await
+    }
+
+    async fn find_similar(
+        &self,
+        memory_id: &str,
+        threshold: f32,
+        limit: usize,
+    ) -> Result<Vec<(String, f32)>> {
+        EmbeddingTable::find_similar(self, memory_id, threshold, limit).await
+    }
+
+    async fn text_search(
+        &self,
+        query: &str,
+        limit: usize,
+        _audience: Option<&MemoryAudience>,
+    ) -> Result<Vec<(String, f32)>> {
+        EmbeddingTable::text_search(self, query, limit).await
+    }
+
+    async fn ensure_fts_index(&self) -> Result<()> {
+        EmbeddingTable::ensure_fts_index(self).await
+    }
+}
+
+/// What a vector index refresh did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorIndexStatus {
+    /// The table is still too small to be worth indexing.
+    TooSmall,
+    /// The index was trained and written for the first time.
+    Created,
+    /// Rows added since the last refresh were folded into the index.
+    Updated,
+}
+
+/// Approximate nearest neighbour index over a table's `embedding` column.
+///
+/// This is LanceDB's IVF_HNSW_SQ index: vectors are clustered into IVF
+/// partitions, each searched through an HNSW graph of scalar-quantized
+/// vectors. It is written into the table's directory next to the data and
+/// read back from disk on open, so it survives restarts without a rebuild
+/// and needs no external service.
+#[derive(Clone)]
+pub(crate) struct VectorIndex {
+    table: lancedb::Table,
+    distance_type: lancedb::DistanceType,
+    min_rows: usize,
+    pending_rows: Arc<AtomicUsize>,
+    refreshing: Arc<AtomicBool>,
+}
+
+impl VectorIndex {
+    /// `distance_type` must match the metric the table is queried with.
+    pub(crate) fn new(table: lancedb::Table, distance_type: lancedb::DistanceType) -> Self {
+        Self {
+            table,
+            distance_type,
+            min_rows: VECTOR_INDEX_MIN_ROWS,
+            pending_rows: Arc::new(AtomicUsize::new(0)),
+            refreshing: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_min_rows(mut self, min_rows: usize) -> Self {
+        self.min_rows = min_rows;
+        self
+    }
+
+    /// Record `rows` new rows, refreshing the index in the background once
+    /// enough have piled up.
+    pub(crate) fn note_added(&self, rows: usize) {
+        let pending = self.pending_rows.fetch_add(rows, Ordering::Relaxed) + rows;
+        if pending >= VECTOR_INDEX_REFRESH_ROWS {
+            self.refresh_in_background();
+        }
+    }
+
+    /// Refresh on a background task, unless one is already running.
+    pub(crate) fn refresh_in_background(&self) {
+        if self.refreshing.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let index = self.clone();
+        tokio::spawn(async move {
+            match index.refresh().await {
+                Ok(status) => tracing::debug!(?status, "vector index refreshed"),
+                Err(error) => tracing::warn!(%error, "vector index refresh failed"),
+            }
+            index.refreshing.store(false, Ordering::Release);
+        });
+    }
+
+    /// Build the index once the table has `min_rows` rows, and afterwards
+    /// fold newly added rows into it. Folding doesn't retrain the IVF
+    /// partitions, which is fine while the data keeps its overall shape.
+    pub(crate) async fn refresh(&self) -> Result<VectorIndexStatus> {
+        self.pending_rows.store(0, Ordering::Relaxed);
+
+        let rows = self
+            .table
+            .count_rows(None)
+            .await
+            .map_err(|e| DbError::LanceDb(e.to_string()))?;
+        if rows < self.min_rows {
+            return Ok(VectorIndexStatus::TooSmall);
+        }
+
+        let indexed = self
+            .table
+            .list_indices()
+            .await
+            .map_err(|e| DbError::LanceDb(e.to_string()))?
+            .iter()
+            .any(|index| index.columns.iter().any(|column| column == "embedding"));
+        if indexed {
+            self.table
+                .optimize(lancedb::table::OptimizeAction::Index(
+                    lancedb::table::OptimizeOptions::default(),
+                ))
+                .await
+                .map_err(|e| DbError::LanceDb(format!("Failed to update vector index: {e}")))?;
+            return Ok(VectorIndexStatus::Updated);
+        }
+
+        let builder = lancedb::index::vector::IvfHnswSqIndexBuilder::default()
+            .distance_type(self.distance_type);
+        self.table
+            .create_index(&["embedding"], lancedb::index::Index::IvfHnswSq(builder))
+            .execute()
+            .await
+            .map_err(|e| DbError::LanceDb(format!("Failed to create vector index: {e}")))?;
+        tracing::info!(rows, "vector index created");
+        Ok(VectorIndexStatus::Created)
+    }
+}
@@ -1,145 +1,285 @@
-//! Memory maintenance: decay, prune, merge, reindex.
+//! Memory maintenance: decay, promote, prune, merge.
 
+use crate::config::MemoryMaintenanceConfig;
 use crate::error::Result;
-use crate::memory::MemoryStore;
-use crate::memory::types::MemoryType;
-
-/// Maintenance configuration.
-#[derive(Debug, Clone)]
-pub struct MaintenanceConfig {
-    /// Importance below which memories are considered for pruning.
-    pub prune_threshold: f32,
-    /// Decay rate per day (0.0 - 1.0).
-    pub decay_rate: f32,
-    /// Minimum age in days before a memory can be pruned.
-    pub min_age_days: i64,
-    /// Similarity threshold for merging memories (0.0 - 1.0).
-    pub merge_similarity_threshold: f32,
-}
+use crate::memory::MemorySearch;
+use crate::memory::types::{Memory, MemoryScope, MemoryType};
 
-impl Default for MaintenanceConfig {
-    fn default() -> Self {
-        Self {
-            prune_threshold: 0.1,
-            decay_rate: 0.05,
-            min_age_days: 30,
-            merge_similarity_threshold: 0.95,
-        }
-    }
-}
+use std::collections::HashSet;
+
+/// Most memories of one type inspected per pass.
+const MAX_MEMORIES_PER_TYPE: i64 = 1000;
 
-/// Run maintenance tasks on the memory store.
+/// Similar candidates considered for each memory during merging.
+const MERGE_CANDIDATES: usize = 5;
+
+/// Run one maintenance pass over the memory store.
 pub async fn run_maintenance(
-    memory_store: &MemoryStore,
-    config: &MaintenanceConfig,
+    memory_search: &MemorySearch,
+    config: &MemoryMaintenanceConfig,
 ) -> Result<MaintenanceReport> {
     let mut report = MaintenanceReport::default();
 
-    // Apply decay to all non-identity memories
-    // Fields are assigned sequentially because the values are async — can't use struct literal.
-    #[allow(clippy::field_reassign_with_default)]
+    for memory_type in MemoryType::ALL
+        .iter()
+        .copied()
+        .filter(|memory_type| *memory_type != MemoryType::Identity)
     {
-        report.decayed = apply_decay(memory_store, config.decay_rate).await?;
-        report.pruned = prune_memories(memory_store, config).await?;
-        report.merged =
-            merge_similar_memories(memory_store, config.merge_similarity_threshold).await?;
+        let memories = memory_search
+            .store()
+            .get_by_type(memory_type, MAX_MEMORIES_PER_TYPE)
+            .await?;
+
+        let now = chrono::Utc::now();
+        let mut survivors = Vec::with_capacity(memories.len());
+        for mut memory in memories {
+            match adjust_importance(&memory, config, now) {
+                Adjustment::Decay(importance) => {
+                    memory_search
+                        .store()
+                        .set_importance(&memory.id, importance)
+                        .await?;
+                    memory.importance = importance;
+                    report.decayed += 1;
+                }
+                Adjustment::Promote(importance) => {
+                    memory_search
+                        .store()
+                        .set_importance(&memory.id, importance)
+                        .await?;
+                    memory.importance = importance;
+                    report.promoted += 1;
+                }
+                Adjustment::None => {}
+            }
+
+            if should_prune(&memory, config, now) {
+                memory_search.store().forget(&memory.id).await?;
+                report.pruned += 1;
+            } else {
+                survivors.push(memory);
+            }
+        }
+
+        report.merged += merge_similar_memories(memory_search, survivors, config).await?;
     }
 
     Ok(report)
 }
 
-/// Apply importance decay based on recency and access patterns.
-async fn apply_decay(memory_store: &MemoryStore, decay_rate: f32) -> Result<usize> {
-    // Get all non-identity memories
-    let all_types: Vec<_> = MemoryType::ALL
-        .iter()
-        .copied()
-        .filter(|t| *t != MemoryType::Identity)
-        .collect();
+enum Adjustment {
+    Decay(f32),
+    Promote(f32),
+    None,
+}
 
-    let mut decayed_count = 0;
+/// Decide how a memory's importance changes this pass. Memories nobody has
+/// recalled within `stale_after_days` decay; recently recalled ones with
+/// enough accesses are raised to `promote_boost` above their type's default.
+/// Promotion targets a fixed level, so repeated passes don't compound it.
+fn adjust_importance(
+    memory: &Memory,
+    config: &MemoryMaintenanceConfig,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Adjustment {
+    let days_since_access = (now - memory.last_accessed_at).num_days();
 
-    for mem_type in all_types {
-        let memories = memory_store.get_by_type(mem_type, 1000).await?;
+    if days_since_access >= config.stale_after_days {
+        let importance = memory.importance * (1.0 - config.decay_rate);
+        if memory.importance - importance > f32::EPSILON {
+            return Adjustment::Decay(importance);
+        }
+    } else if memory.access_count >= config.promote_min_accesses {
+        let importance = (memory.memory_type.default_importance() + config.promote_boost).min(1.0);
+        if importance - memory.importance > f32::EPSILON {
+            return Adjustment::Promote(importance);
+        }
+    }
 
-        for mut memory in memories {
-            let now = chrono::Utc::now();
-            let days_old = (now - memory.updated_at).num_days();
-            let days_since_access = (now - memory.last_accessed_at).num_days();
-
-            // Calculate decay multiplier
-            let age_decay = 1.0 - (days_old as f32 * decay_rate).min(0.5);
-            let access_boost = if days_since_access < 7 {
-                1.1 // Recent access boosts importance
-            } else if days_since_access > 30 {
-                0.9 // Long time since access reduces importance
-            } else {
-                1.0
-            };
+    Adjustment::None
+}
 
-            let new_importance = memory.importance * age_decay * access_boost;
+/// Old memories whose importance has decayed below the threshold are
+/// forgotten.
+fn should_prune(
+    memory: &Memory,
+    config: &MemoryMaintenanceConfig,
+    now: chrono::DateTime<chrono::Utc>,
+) -> bool {
+    memory.importance < config.prune_threshold
+        && (now - memory.created_at).num_days() >= config.prune_min_age_days
+}
 
-            if (new_importance - memory.importance).abs() > 0.01 {
-                memory.importance = new_importance.clamp(0.0, 1.0);
-                memory.updated_at = now;
-                memory_store.update(&memory).await?;
-                decayed_count += 1;
-            }
+/// Merge near-duplicate memories of the same type.
+///
+/// The most important memory of each group is kept. It inherits every
+/// duplicate's access count and associations; the duplicates are forgotten
+/// and dropped from the embedding table.
+async fn merge_similar_memories(
+    memory_search: &MemorySearch,
+    memories: Vec<Memory>,
+    config: &MemoryMaintenanceConfig,
+) -> Result<usize> {
+    let store = memory_search.store();
+    let candidate_ids: HashSet<&str> = memories.iter().map(|memory| memory.id.as_str()).collect();
+    // Memories already kept or merged away. Sorted by importance, so earlier
+    // memories are always the keepers and are never folded into later ones.
+    let mut settled: HashSet<String> = HashSet::new();
+    let mut merged = 0;
+
+    for memory in &memories {
+        if !settled.insert(memory.id.clone()) {
+            continue;
         }
-    }
 
-    Ok(decayed_count)
-}
+        let similar = match memory_search
+            .embeddings()
+            .find_similar(&memory.id, config.merge_threshold, MERGE_CANDIDATES)
+            .await
+        {
+            Ok(similar) => similar,
+            Err(error) => {
+                tracing::debug!(memory_id = %memory.id, %error, "similarity search failed during merge");
+                continue;
+            }
+        };
+
+        let mut keeper = memory.clone();
+        for (duplicate_id, similarity) in similar {
+            if !candidate_ids.contains(duplicate_id.as_str()) || settled.contains(&duplicate_id) {
+                continue;
+            }
+            let Some(duplicate) = memories.iter().find(|other| other.id == duplicate_id) else {
+                continue;
+            };
+            // Folding a memory into one with a different audience would
+            // either hide it or leak it, so only same-scope copies merge.
+            if !same_audience(&keeper, duplicate) {
+                continue;
+            }
 
-/// Prune memories that have fallen below the importance threshold.
-async fn prune_memories(memory_store: &MemoryStore, config: &MaintenanceConfig) -> Result<usize> {
-    let now = chrono::Utc::now();
-    let min_age = chrono::Duration::days(config.min_age_days);
-    let cutoff_date = now - min_age;
-
-    // Get all memories below threshold that are old enough
-    let candidates = sqlx::query(
-        r#"
-        SELECT id FROM memories
-        WHERE importance < ? 
-        AND memory_type != 'identity'
-        AND created_at < ?
-        "#,
-    )
-    .bind(config.prune_threshold)
-    .bind(cutoff_date)
-    .fetch_all(memory_store.pool())
-    .await?;
-
-    let mut pruned_count = 0;
-
-    for row in candidates {
-        let id: String = sqlx::Row::try_get(&row, "id")?;
-        memory_store.delete(&id).await?;
-        pruned_count += 1;
+            keeper.importance = keeper.importance.max(duplicate.importance);
+            keeper.access_count += duplicate.access_count;
+            keeper.last_accessed_at = keeper.last_accessed_at.max(duplicate.last_accessed_at);
+            store.absorb_duplicate(&keeper).await?;
+            store
+                .reassign_associations(&duplicate.id, &keeper.id)
+                .await?;
+            store.forget(&duplicate.id).await?;
+            if let Err(error) = memory_search.embeddings().delete(&duplicate.id).await {
+                tracing::debug!(memory_id = %duplicate.id, %error, "failed to drop merged embedding");
+            }
+
+            tracing::debug!(
+                keeper = %keeper.id,
+                duplicate = %duplicate.id,
+                similarity,
+                "merged duplicate memory"
+            );
+            settled.insert(duplicate_id);
+            merged += 1;
+        }
     }
 
-    Ok(pruned_count)
+    Ok(merged)
 }
 
-/// Merge near-duplicate memories.
-async fn merge_similar_memories(
-    _memory_store: &MemoryStore,
-    similarity_threshold: f32,
-) -> Result<usize> {
-    // For now, this is a placeholder
-    // Full implementation would:
-    // 1. Find pairs of memories with high embedding similarity
-    // 2. Merge them, keeping the higher importance one
-    // 3. Update associations to point to the merged memory
-    let _ = similarity_threshold;
-    Ok(0)
+fn same_audience(a: &Memory, b: &Memory) -> bool {
+    a.scope == b.scope
+        && match a.scope {
+            MemoryScope::Agent => true,
+            MemoryScope::Conversation => a.channel_id == b.channel_id,
+            MemoryScope::User => a.owner_id == b.owner_id,
+        }
 }
 
 /// Maintenance report.
 #[derive(Debug, Default)]
 pub struct MaintenanceReport {
     pub decayed: usize,
+    pub promoted: usize,
     pub pruned: usize,
     pub merged: usize,
 }
+
+impl MaintenanceReport {
+    /// Whether the pass changed anything.
+    pub fn is_empty(&self) -> bool {
+        self.decayed + self.promoted + self.pruned + self.merged == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory_accessed(days_ago: i64, access_count: i64) -> Memory {
+        let mut memory = Memory::new("content", MemoryType::Fact);
+        let now = chrono::Utc::now();
+        memory.importance = 0.5;
+        memory.created_at = now - chrono::Duration::days(days_ago);
+        memory.last_accessed_at = now - chrono::Duration::days(days_ago);
+        memory.access_count = access_count;
+        memory
+    }
+
+    #[test]
+    fn stale_memories_decay() {
+        let config = MemoryMaintenanceConfig::default();
+        let memory = memory_accessed(config.stale_after_days + 1, 0);
+
+        let Adjustment::Decay(importance) = adjust_importance(&memory, &config, chrono::Utc::now())
+        else {
+            panic!("expected decay");
+        };
+        assert!((importance - 0.475).abs() < 1e-6);
+    }
+
+    #[test]
+    fn frequently_recalled_memories_are_promoted() {
+        let config = MemoryMaintenanceConfig::default();
+        let mut memory = memory_accessed(1, config.promote_min_accesses);
+
+        let Adjustment::Promote(importance) =
+            adjust_importance(&memory, &config, chrono::Utc::now())
+        else {
+            panic!("expected promotion");
+        };
+        assert!((importance - 0.65).abs() < 1e-6);
+
+        // A second pass leaves the promoted memory where it is.
+        memory.importance = importance;
+        assert!(matches!(
+            adjust_importance(&memory, &config, chrono::Utc::now()),
+            Adjustment::None
+        ));
+    }
+
+    #[test]
+    fn rarely_recalled_recent_memories_are_left_alone() {
+        let config = MemoryMaintenanceConfig::default();
+        let memory = memory_accessed(1, 1);
+
+        assert!(matches!(
+            adjust_importance(&memory, &config, chrono::Utc::now()),
+            Adjustment::None
+        ));
+    }
+
+    #[test]
+    fn only_old_unimportant_memories_are_pruned() {
+        let config = MemoryMaintenanceConfig::default();
+        let now = chrono::Utc::now();
+
+        let mut old = memory_accessed(config.prune_min_age_days + 1, 0);
+        old.importance = 0.05;
+        assert!(should_prune(&old, &config, now));
+
+        let mut young = memory_accessed(1, 0);
+        young.importance = 0.05;
+        assert!(!should_prune(&young, &config, now));
+
+        let important = memory_accessed(config.prune_min_age_days + 1, 0);
+        assert!(!should_prune(&important, &config, now));
+    }
+}
@@ -0,0 +1,163 @@
+//! Memory embeddings in Postgres with pgvector.
+
+use crate::error::{DbError, Result};
+use crate::memory::vector_store::EmbeddingStore;
+use crate::memory::{Memory, MemoryAudience};
+
+use sqlx::PgPool;
+
+/// Rows an audience may see. `$4` says whether there is an audience at all,
+/// `$5` is its conversation and `$6` its sender; mirrors
+/// [`MemoryAudience::can_see`].
+const AUDIENCE_FILTER: &str = "($4 = FALSE OR scope = 'agent' \
+     OR (scope = 'conversation' AND channel_id = $5) \
+     OR (scope = 'user' AND owner_id = $6))";
+
+/// Memory embeddings for one agent in the shared `memory_embeddings` table.
+///
+/// Similarity uses squared L2 distance, matching what LanceDB reports, so
+/// search thresholds mean the same thing on either backend. Keyword search
+/// runs against a generated `tsvector` column with the `simple` dictionary.
+/// Each row carries the memory's scope, channel, and owner, so searches for
+/// an audience filter in SQL (see [`AUDIENCE_FILTER`]).
+#[derive(Clone)]
+pub struct PgEmbeddingStore {
+    pool: PgPool,
+    agent_id: String,
+}
+
+impl PgEmbeddingStore {
+    pub fn new(pool: PgPool, agent_id: impl Into<String>) -> Self {
+        Self {
+            pool,
+            agent_id: agent_id.into(),
+        }
+    }
+}
+
+impl EmbeddingStore for PgEmbeddingStore {
+    async fn store(&self, memory: &Memory, embedding: &[f32]) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO memory_embeddings \
+             (agent_id, memory_id, content, embedding, scope, channel_id, owner_id) \
+             VALUES ($1, $2, $3, $4::vector, $5, $6, $7) \
+             ON CONFLICT (agent_id, memory_id) \
+             DO UPDATE SET content = EXCLUDED.content, embedding = EXCLUDED.embedding, \
+                 scope = EXCLUDED.scope, channel_id = EXCLUDED.channel_id, \
+                 owner_id = EXCLUDED.owner_id",
+        )
+        .bind(&self.agent_id)
+        .bind(&memory.id)
+        .bind(&memory.content)
+        .bind(vector_literal(embedding))
+        .bind(memory.scope.as_str())
+        .bind(memory.channel_id.as_deref())
+        .bind(memory.owner_id.as_deref())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, memory_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM memory_embeddings WHERE agent_id = $1 AND memory_id = $2")
+            .bind(&self.agent_id)
+            .bind(memory_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn vector_search(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        audience: Option<&MemoryAudience>,
+    ) -> Result<Vec<(String, f32)>> {
+        let rows: Vec<(String, f32)> = sqlx::query_as(&format!(
+            "SELECT memory_id, power(embedding <-> $2::vector, 2)::real \
+             FROM memory_embeddings WHERE agent_id = $1 AND {AUDIENCE_FILTER} \
+             ORDER BY embedding <-> $2::vector LIMIT $3"
+        ))
+        .bind(&self.agent_id)
+        .bind(vector_literal(query_embedding))
+        .bind(limit as i64)
+        .bind(audience.is_some())
+        .bind(audience.and_then(|audience| audience.conversation_id.as_deref()))
+        .bind(audience.and_then(|audience| audience.sender_id.as_deref()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(rows)
+    }
+
+    async fn find_similar(
+        &self,
+        memory_id: &str,
+        threshold: f32,
+        limit: usize,
+    ) -> Result<Vec<(String, f32)>> {
+        let rows: Vec<(String, f32)> = sqlx::query_as(
+            "SELECT other.memory_id, (1 - power(other.embedding <-> source.embedding, 2))::real \
+             FROM memory_embeddings source \
+             JOIN memory_embeddings other \
+               ON other.agent_id = source.agent_id AND other.memory_id <> source.memory_id \
+             WHERE source.agent_id = $1 AND source.memory_id = $2 \
+             ORDER BY other.embedding <-> source.embedding LIMIT $3",
+        )
+        .bind(&self.agent_id)
+        .bind(memory_id)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .filter(|(_, similarity)| *similarity >= threshold)
+            .collect())
+    }
+
+    async fn text_search(
+        &self,
+        query: &str,
+        limit: usize,
+        audience: Option<&MemoryAudience>,
+    ) -> Result<Vec<(String, f32)>> {
+        let rows: Vec<(String, f32)> = sqlx::query_as(&format!(
+            "SELECT memory_id, ts_rank(content_tsv, query) \
+             FROM memory_embeddings, plainto_tsquery('simple', $2) query \
+             WHERE agent_id = $1 AND content_tsv @@ query AND {AUDIENCE_FILTER} \
+             ORDER BY 2 DESC LIMIT $3"
+        ))
+        .bind(&self.agent_id)
+        .bind(query)
+        .bind(limit as i64)
+        .bind(audience.is_some())
+        .bind(audience.and_then(|audience| audience.conversation_id.as_deref()))
+        .bind(audience.and_then(|audience| audience.sender_id.as_deref()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(rows)
+    }
+}
+
+/// pgvector's text form, `[0.1,0.2,...]`, for binding as `$n::vector`.
+pub(crate) fn vector_literal(embedding: &[f32]) -> String {
+    let mut literal = String::with_capacity(embedding.len() * 12 + 2);
+    literal.push('[');
+    for (i, value) in embedding.iter().enumerate() {
+        if i > 0 {
+            literal.push(',');
+        }
+        literal.push_str(&value.to_string());
+    }
+    literal.push(']');
+    literal
+}
@@ -0,0 +1,427 @@
+//! Memory embeddings in Qdrant, over its REST API.
+
+use crate::config::QdrantStorageConfig;
+use crate::error::{DbError, Result};
+use crate::memory::vector_store::EmbeddingStore;
+use crate::memory::{Memory, MemoryAudience, MemoryScope};
+
+use serde_json::{Value, json};
+
+/// Vector size of every collection, the all-MiniLM-L6-v2 dimension.
+const EMBEDDING_DIM: usize = 384;
+
+/// Points written per upsert request.
+const UPSERT_BATCH_SIZE: usize = 64;
+
+/// Keyword matches fetched per requested result, before ranking locally.
+/// Qdrant's text filter doesn't score, so a few extra are pulled to rank.
+const TEXT_CANDIDATES_PER_RESULT: usize = 4;
+
+/// Talks to one Qdrant server. Cheap to clone.
+#[derive(Debug, Clone)]
+pub struct QdrantClient {
+    config: QdrantStorageConfig,
+    http: reqwest::Client,
+}
+
+impl QdrantClient {
+    pub fn new(config: QdrantStorageConfig) -> Self {
+        Self {
+            config,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Name of an agent's collection of the given kind, e.g. `memories`.
+    pub fn collection_name(&self, agent_id: &str, kind: &str) -> String {
+        format!("{}{agent_id}_{kind}", self.config.collection_prefix)
+    }
+
+    /// Create a collection and its payload indexes unless it already exists.
+    ///
+    /// `distance` is Qdrant's name for the metric (`Euclid`, `Cosine`).
+    /// Keyword fields get exact-match indexes for filtering, and the text
+    /// field, if any, a full-text index.
+    pub(crate) async fn ensure_collection(
+        &self,
+        name: &str,
+        distance: &str,
+        keyword_fields: &[&str],
+        text_field: Option<&str>,
+    ) -> Result<()> {
+        let exists = self
+            .request(
+                reqwest::Method::GET,
+                &format!("collections/{name}/exists"),
+                None,
+            )
+            .await?;
+        if exists["exists"].as_bool() == Some(true) {
+            return Ok(());
+        }
+
+        let created = self
+            .request(
+                reqwest::Method::PUT,
+                &format!("collections/{name}"),
+                Some(json!({ "vectors": { "size": EMBEDDING_DIM, "distance": distance } })),
+            )
+            .await;
+        if let Err(error) = created {
+            // Another agent process may have created it in the meantime.
+            if !error.to_string().contains("already exists") {
+                return Err(error);
+            }
+        }
+
+        for field in keyword_fields {
+            self.request(
+                reqwest::Method::PUT,
+                &format!("collections/{name}/index?wait=true"),
+                Some(json!({ "field_name": field, "field_schema": "keyword" })),
+            )
+            .await?;
+        }
+        if let Some(field) = text_field {
+            self.request(
+                reqwest::Method::PUT,
+                &format!("collections/{name}/index?wait=true"),
+                Some(json!({
+                    "field_name": field,
+                    "field_schema": { "type": "text", "tokenizer": "word", "lowercase": true },
+                })),
+            )
+            .await?;
+        }
+
+        tracing::info!(collection = name, "created Qdrant collection");
+        Ok(())
+    }
+
+    /// Upsert points, `UPSERT_BATCH_SIZE` per request.
+    pub(crate) async fn upsert(&self, collection: &str, points: Vec<Value>) -> Result<()> {
+        for batch in points.chunks(UPSERT_BATCH_SIZE) {
+            self.request(
+                reqwest::Method::PUT,
+                &format!("collections/{collection}/points?wait=true"),
+                Some(json!({ "points": batch })),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Send a request and return the `result` field of the response.
+    pub(crate) async fn request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Value> {
+        let mut request = self
+            .http
+            .request(method, format!("{}/{path}", self.config.url));
+        if let Some(api_key) = &self.config.api_key {
+            request = request.header("api-key", api_key);
+        }
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| DbError::Qdrant(e.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(DbError::Qdrant(format!("{path} returned {status}: {body}")).into());
+        }
+
+        let mut body: Value = response
+            .json()
+            .await
+            .map_err(|e| DbError::Qdrant(e.to_string()))?;
+        Ok(body["result"].take())
+    }
+}
+
+/// Memory embeddings for one agent in its own `{prefix}{agent_id}_memories`
+/// collection.
+///
+/// Points are keyed by memory ID and carry the memory's scope, channel and
+/// owner, so searches for an audience filter in Qdrant instead of after the
+/// fact. The collection uses Euclidean distance; it's squared on the way out
+/// to match what LanceDB reports.
+#[derive(Debug, Clone)]
+pub struct QdrantEmbeddingStore {
+    client: QdrantClient,
+    collection: String,
+}
+
+impl QdrantEmbeddingStore {
+    /// Open the agent's memory collection, creating it if needed.
+    pub async fn open(client: QdrantClient, agent_id: &str) -> Result<Self> {
+        let collection = client.collection_name(agent_id, "memories");
+        client
+            .ensure_collection(
+                &collection,
+                "Euclid",
+                &["scope", "channel_id", "owner_id"],
+                Some("content"),
+            )
+            .await?;
+        Ok(Self { client, collection })
+    }
+
+    async fn search(
+        &self,
+        vector: &[f32],
+        limit: usize,
+        filter: Option<Value>,
+    ) -> Result<Vec<(String, f32)>> {
+        let mut body = json!({ "vector": vector, "limit": limit, "with_payload": false });
+        if let Some(filter) = filter {
+            body["filter"] = filter;
+        }
+        let result = self
+            .client
+            .request(
+                reqwest::Method::POST,
+                &format!("collections/{}/points/search", self.collection),
+                Some(body),
+            )
+            .await?;
+
+        Ok(points(&result)
+            .filter_map(|point| {
+                let id = point_id(point)?;
+                let distance = point["score"].as_f64()? as f32;
+                Some((id, distance * distance))
+            })
+            .collect())
+    }
+}
+
+impl EmbeddingStore for QdrantEmbeddingStore {
+    async fn store(&self, memory: &Memory, embedding: &[f32]) -> Result<()> {
+        self.client
+            .upsert(&self.collection, vec![memory_point(memory, embedding)])
+            .await
+    }
+
+    async fn store_many(&self, memories: &[(Memory, Vec<f32>)]) -> Result<()> {
+        let points = memories
+            .iter()
+            .map(|(memory, embedding)| memory_point(memory, embedding))
+            .collect();
+        self.client.upsert(&self.collection, points).await
+    }
+
+    async fn delete(&self, memory_id: &str) -> Result<()> {
+        self.client
+            .request(
+                reqwest::Method::POST,
+                &format!("collections/{}/points/delete?wait=true", self.collection),
+                Some(json!({ "points": [memory_id] })),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn vector_search(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        audience: Option<&MemoryAudience>,
+    ) -> Result<Vec<(String, f32)>> {
+        self.search(query_embedding, limit, audience.map(audience_filter))
+            .await
+    }
+
+    async fn find_similar(
+        &self,
+        memory_id: &str,
+        threshold: f32,
+        limit: usize,
+    ) -> Result<Vec<(String, f32)>> {
+        let result = self
+            .client
+            .request(
+                reqwest::Method::POST,
+                &format!("collections/{}/points", self.collection),
+                Some(json!({ "ids": [memory_id], "with_vector": true, "with_payload": false })),
+            )
+            .await?;
+        let Some(vector) = points(&result).next().and_then(|point| {
+            point["vector"].as_array().map(|values| {
+                values
+                    .iter()
+                    .filter_map(|value| value.as_f64().map(|value| value as f32))
+                    .collect::<Vec<_>>()
+            })
+        }) else {
+            return Ok(Vec::new());
+        };
+
+        let exclude_self = json!({ "must_not": [{ "has_id": [memory_id] }] });
+        Ok(self
+            .search(&vector, limit, Some(exclude_self))
+            .await?
+            .into_iter()
+            .map(|(id, distance)| (id, 1.0 - distance))
+            .filter(|(_, similarity)| *similarity >= threshold)
+            .collect())
+    }
+
+    async fn text_search(
+        &self,
+        query: &str,
+        limit: usize,
+        audience: Option<&MemoryAudience>,
+    ) -> Result<Vec<(String, f32)>> {
+        let terms = query_terms(query);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let any_term: Vec<Value> = terms
+            .iter()
+            .map(|term| json!({ "key": "content", "match": { "text": term } }))
+            .collect();
+        let mut must = vec![json!({ "should": any_term })];
+        if let Some(audience) = audience {
+            must.push(audience_filter(audience));
+        }
+
+        let result = self
+            .client
+            .request(
+                reqwest::Method::POST,
+                &format!("collections/{}/points/scroll", self.collection),
+                Some(json!({
+                    "filter": { "must": must },
+                    "limit": limit * TEXT_CANDIDATES_PER_RESULT,
+                    "with_payload": ["content"],
+                    "with_vector": false,
+                })),
+            )
+            .await?;
+
+        let mut matches: Vec<(String, f32)> = result["points"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|point| {
+                let id = point_id(point)?;
+                let content = point["payload"]["content"].as_str()?;
+                Some((id, term_coverage(&terms, content)))
+            })
+            .collect();
+        matches.sort_by(|a, b| b.1.total_cmp(&a.1));
+        matches.truncate(limit);
+
+        Ok(matches)
+    }
+}
+
+fn memory_point(memory: &Memory, embedding: &[f32]) -> Value {
+    json!({
+        "id": memory.id,
+        "vector": embedding,
+        "payload": {
+            "content": memory.content,
+            "scope": memory.scope.as_str(),
+            "channel_id": memory.channel_id,
+            "owner_id": memory.owner_id,
+        },
+    })
+}
+
+/// Payload filter matching the memories an audience may see, mirroring
+/// [`MemoryAudience::can_see`].
+fn audience_filter(audience: &MemoryAudience) -> Value {
+    let mut visible = vec![json!({
+        "key": "scope", "match": { "value": MemoryScope::Agent.as_str() },
+    })];
+    if let Some(conversation_id) = &audience.conversation_id {
+        visible.push(json!({ "must": [
+            { "key": "scope", "match": { "value": MemoryScope::Conversation.as_str() } },
+            { "key": "channel_id", "match": { "value": conversation_id } },
+        ]}));
+    }
+    if let Some(sender_id) = &audience.sender_id {
+        visible.push(json!({ "must": [
+            { "key": "scope", "match": { "value": MemoryScope::User.as_str() } },
+            { "key": "owner_id", "match": { "value": sender_id } },
+        ]}));
+    }
+    json!({ "should": visible })
+}
+
+/// Points in a search or retrieve result.
+pub(crate) fn points(result: &Value) -> impl Iterator<Item = &Value> {
+    result.as_array().into_iter().flatten()
+}
+
+/// A point's ID as a string; Qdrant IDs are UUIDs or integers.
+fn point_id(point: &Value) -> Option<String> {
+    match &point["id"] {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+/// Distinct lowercase words of a keyword query.
+fn query_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    terms.sort();
+    terms.dedup();
+    terms
+}
+
+/// Share of the query terms that appear as words in `content`.
+fn term_coverage(terms: &[String], content: &str) -> f32 {
+    let words = query_terms(content);
+    let found = terms
+        .iter()
+        .filter(|term| words.binary_search(term).is_ok())
+        .count();
+    found as f32 / terms.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audience_filter_only_admits_scopes_the_audience_can_see() {
+        let anonymous = audience_filter(&MemoryAudience::default());
+        assert_eq!(anonymous["should"].as_array().unwrap().len(), 1);
+
+        let filter = audience_filter(&MemoryAudience::new(
+            "discord:dm:1",
+            Some("discord:42".into()),
+        ));
+        let clauses = filter["should"].as_array().unwrap();
+        assert_eq!(clauses.len(), 3);
+        assert_eq!(clauses[0]["match"]["value"], "agent");
+        assert_eq!(clauses[1]["must"][1]["key"], "channel_id");
+        assert_eq!(clauses[1]["must"][1]["match"]["value"], "discord:dm:1");
+        assert_eq!(clauses[2]["must"][1]["key"], "owner_id");
+        assert_eq!(clauses[2]["must"][1]["match"]["value"], "discord:42");
+    }
+
+    #[test]
+    fn keyword_matches_rank_by_query_terms_found() {
+        let terms = query_terms("Rust async, rust!");
+        assert_eq!(terms, vec!["async", "rust"]);
+        assert_eq!(term_coverage(&terms, "Async Rust is fun"), 1.0);
+        assert_eq!(term_coverage(&terms, "rusty async code"), 0.5);
+    }
+}
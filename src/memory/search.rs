@@ -1,8 +1,10 @@
 //! Memory search: hybrid (vector + FTS + RRF + graph), temporal, importance, and typed queries.
 
 use crate::error::Result;
-use crate::memory::types::{Memory, MemorySearchResult, MemoryType, RelationType};
-use crate::memory::{EmbeddingModel, EmbeddingTable, MemoryStore};
+use crate::memory::types::{
+    Memory, MemoryAudience, MemoryKind, MemorySearchResult, MemoryType, RelationType,
+};
+use crate::memory::{EmbeddingModel, EmbeddingStoreDyn, MemoryStore};
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -36,7 +38,7 @@ pub enum SearchSort {
 /// Bundles all memory search dependencies.
 pub struct MemorySearch {
     store: Arc<MemoryStore>,
-    embedding_table: EmbeddingTable,
+    embeddings: Arc<dyn EmbeddingStoreDyn>,
     embedding_model: Arc<EmbeddingModel>,
 }
 
@@ -44,7 +46,7 @@ impl Clone for MemorySearch {
     fn clone(&self) -> Self {
         Self {
             store: Arc::clone(&self.store),
-            embedding_table: self.embedding_table.clone(),
+            embeddings: Arc::clone(&self.embeddings),
             embedding_model: Arc::clone(&self.embedding_model),
         }
     }
@@ -62,12 +64,12 @@ impl MemorySearch {
     /// Create a new MemorySearch instance.
     pub fn new(
         store: Arc<MemoryStore>,
-        embedding_table: EmbeddingTable,
+        embeddings: Arc<dyn EmbeddingStoreDyn>,
         embedding_model: Arc<EmbeddingModel>,
     ) -> Self {
         Self {
             store,
-            embedding_table,
+            embeddings,
             embedding_model,
         }
     }
@@ -77,9 +79,9 @@ impl MemorySearch {
         &self.store
     }
 
-    /// Get a reference to the embedding table.
-    pub fn embedding_table(&self) -> &EmbeddingTable {
-        &self.embedding_table
+    /// Get a reference to the embedding store.
+    pub fn embeddings(&self) -> &dyn EmbeddingStoreDyn {
+        self.embeddings.as_ref()
     }
 
     /// Get a reference to the embedding model.
@@ -116,7 +118,12 @@ impl MemorySearch {
     ) -> Result<Vec<MemorySearchResult>> {
         let memories = self
             .store
-            .get_sorted(sort, config.max_results as i64, config.memory_type)
+            .get_sorted(
+                sort,
+                config.max_results as i64,
+                config.memory_type,
+                config.audience.as_ref(),
+            )
             .await?;
 
         let total = memories.len();
@@ -153,12 +160,16 @@ impl MemorySearch {
         let mut fts_results = Vec::new();
         let mut graph_results = Vec::new();
 
-        // 1. Full-text search via LanceDB
+        // 1. Full-text search via the embedding store
         // FTS requires an inverted index. If the index doesn't exist yet (empty
         // table, first run) this will fail — fall back to vector + graph search.
         match self
-            .embedding_table
-            .text_search(query, config.max_results_per_source)
+            .embeddings
+            .text_search(
+                query,
+                config.max_results_per_source,
+                config.audience.as_ref(),
+            )
             .await
         {
             Ok(fts_matches) => {
@@ -178,11 +189,15 @@ impl MemorySearch {
             }
         }
 
-        // 2. Vector similarity search via LanceDB
+        // 2. Vector similarity search via the embedding store
         let query_embedding = self.embedding_model.embed_one(query).await?;
         match self
-            .embedding_table
-            .vector_search(&query_embedding, config.max_results_per_source)
+            .embeddings
+            .vector_search(
+                &query_embedding,
+                config.max_results_per_source,
+                config.audience.as_ref(),
+            )
             .await
         {
             Ok(vector_matches) => {
@@ -229,13 +244,21 @@ impl MemorySearch {
         let fused_results =
             reciprocal_rank_fusion(&vector_results, &fts_results, &graph_results, config.rrf_k);
 
-        // Convert to MemorySearchResult with ranks, applying optional type filter
+        // Convert to MemorySearchResult with ranks, applying optional type,
+        // kind, and audience filters
         let results: Vec<MemorySearchResult> = fused_results
             .into_iter()
             .filter(|scored| {
                 config
                     .memory_type
                     .is_none_or(|t| scored.memory.memory_type == t)
+                    && config
+                        .kind
+                        .is_none_or(|kind| scored.memory.memory_type.kind() == kind)
+                    && config
+                        .audience
+                        .as_ref()
+                        .is_none_or(|audience| audience.can_see(&scored.memory))
             })
             .enumerate()
             .map(|(rank, scored)| MemorySearchResult {
@@ -328,6 +351,11 @@ pub struct SearchConfig {
     pub mode: SearchMode,
     /// Optional memory type filter. Required for `Typed` mode, optional for others.
     pub memory_type: Option<MemoryType>,
+    /// Optional episodic/semantic filter. Only used in hybrid mode.
+    pub kind: Option<MemoryKind>,
+    /// Conversation the search runs in. Memories scoped to other
+    /// conversations or senders are dropped. `None` sees every memory.
+    pub audience: Option<MemoryAudience>,
     /// Sort order for non-hybrid modes.
     pub sort_by: SearchSort,
     /// Maximum number of results to return.
@@ -347,6 +375,8 @@ impl Default for SearchConfig {
         Self {
             mode: SearchMode::Hybrid,
             memory_type: None,
+            kind: None,
+            audience: None,
             sort_by: SearchSort::Recent,
             max_results: 10,
             max_results_per_source: 50,
@@ -426,6 +456,7 @@ pub fn curate_results(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::memory::EmbeddingTable;
     use crate::memory::types::MemoryType;
     use chrono::{Duration, Utc};
 
@@ -565,7 +596,7 @@ mod tests {
             .unwrap();
         let embedding_table = EmbeddingTable::open_or_create(&lance_conn).await.unwrap();
         let embedding_model = Arc::new(EmbeddingModel::new(lance_dir.path()).unwrap());
-        let search = MemorySearch::new(store, embedding_table, embedding_model);
+        let search = MemorySearch::new(store, Arc::new(embedding_table), embedding_model);
 
         let config = SearchConfig {
             mode: SearchMode::Recent,
@@ -593,7 +624,7 @@ mod tests {
             .unwrap();
         let embedding_table = EmbeddingTable::open_or_create(&lance_conn).await.unwrap();
         let embedding_model = Arc::new(EmbeddingModel::new(lance_dir.path()).unwrap());
-        let search = MemorySearch::new(store, embedding_table, embedding_model);
+        let search = MemorySearch::new(store, Arc::new(embedding_table), embedding_model);
 
         let config = SearchConfig {
             mode: SearchMode::Important,
@@ -618,7 +649,7 @@ mod tests {
             .unwrap();
         let embedding_table = EmbeddingTable::open_or_create(&lance_conn).await.unwrap();
         let embedding_model = Arc::new(EmbeddingModel::new(lance_dir.path()).unwrap());
-        let search = MemorySearch::new(store, embedding_table, embedding_model);
+        let search = MemorySearch::new(store, Arc::new(embedding_table), embedding_model);
 
         let config = SearchConfig {
             mode: SearchMode::Typed,
@@ -643,7 +674,7 @@ mod tests {
             .unwrap();
         let embedding_table = EmbeddingTable::open_or_create(&lance_conn).await.unwrap();
         let embedding_model = Arc::new(EmbeddingModel::new(lance_dir.path()).unwrap());
-        let search = MemorySearch::new(store, embedding_table, embedding_model);
+        let search = MemorySearch::new(store, Arc::new(embedding_table), embedding_model);
 
         let config = SearchConfig {
             mode: SearchMode::Typed,
@@ -0,0 +1,312 @@
+//! Memory embeddings in the agent's SQLite database.
+
+use crate::error::{DbError, Result};
+use crate::memory::vector_store::EmbeddingStore;
+use crate::memory::{Memory, MemoryAudience};
+
+use sqlx::SqlitePool;
+
+/// Rows an audience may see. `?1` says whether there is an audience at all,
+/// `?2` is its conversation and `?3` its sender; mirrors
+/// [`MemoryAudience::can_see`].
+const AUDIENCE_FILTER: &str = "(?1 = 0 OR scope = 'agent' \
+     OR (scope = 'conversation' AND channel_id = ?2) \
+     OR (scope = 'user' AND owner_id = ?3))";
+
+/// Memory embeddings in the `memory_embeddings` table, next to the memory
+/// rows themselves.
+///
+/// Vector search is an exact scan computed in Rust, which keeps the backend
+/// free of extensions and is fast enough for the few thousand memories a
+/// typical agent holds. Distances are squared L2, matching LanceDB. Keyword
+/// search uses an FTS5 index ranked by BM25. Each row carries the memory's
+/// scope, channel, and owner, so searches for an audience filter in SQL (see
+/// [`AUDIENCE_FILTER`]).
+#[derive(Clone)]
+pub struct SqliteEmbeddingStore {
+    pool: SqlitePool,
+}
+
+impl SqliteEmbeddingStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Every visible embedding, for scanning.
+    async fn embeddings(
+        &self,
+        audience: Option<&MemoryAudience>,
+    ) -> Result<Vec<(String, Vec<u8>)>> {
+        let rows = sqlx::query_as(&format!(
+            "SELECT memory_id, embedding FROM memory_embeddings WHERE {AUDIENCE_FILTER}"
+        ))
+        .bind(audience.is_some())
+        .bind(audience.and_then(|audience| audience.conversation_id.as_deref()))
+        .bind(audience.and_then(|audience| audience.sender_id.as_deref()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(rows)
+    }
+}
+
+impl EmbeddingStore for SqliteEmbeddingStore {
+    async fn store(&self, memory: &Memory, embedding: &[f32]) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO memory_embeddings \
+             (memory_id, content, embedding, scope, channel_id, owner_id) \
+             VALUES (?, ?, ?, ?, ?, ?) \
+             ON CONFLICT (memory_id) \
+             DO UPDATE SET content = excluded.content, embedding = excluded.embedding, \
+                 scope = excluded.scope, channel_id = excluded.channel_id, \
+                 owner_id = excluded.owner_id",
+        )
+        .bind(&memory.id)
+        .bind(&memory.content)
+        .bind(encode_embedding(embedding))
+        .bind(memory.scope.as_str())
+        .bind(memory.channel_id.as_deref())
+        .bind(memory.owner_id.as_deref())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, memory_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM memory_embeddings WHERE memory_id = ?")
+            .bind(memory_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn vector_search(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        audience: Option<&MemoryAudience>,
+    ) -> Result<Vec<(String, f32)>> {
+        let mut results: Vec<(String, f32)> = self
+            .embeddings(audience)
+            .await?
+            .into_iter()
+            .map(|(memory_id, embedding)| {
+                let distance = squared_distance(query_embedding, &decode_embedding(&embedding));
+                (memory_id, distance)
+            })
+            .collect();
+        results.sort_by(|a, b| a.1.total_cmp(&b.1));
+        results.truncate(limit);
+
+        Ok(results)
+    }
+
+    async fn find_similar(
+        &self,
+        memory_id: &str,
+        threshold: f32,
+        limit: usize,
+    ) -> Result<Vec<(String, f32)>> {
+        let source: Option<Vec<u8>> =
+            sqlx::query_scalar("SELECT embedding FROM memory_embeddings WHERE memory_id = ?")
+                .bind(memory_id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| DbError::Query(e.to_string()))?;
+        let Some(source) = source.map(|embedding| decode_embedding(&embedding)) else {
+            return Ok(Vec::new());
+        };
+
+        let mut results: Vec<(String, f32)> = self
+            .embeddings(None)
+            .await?
+            .into_iter()
+            .filter(|(other_id, _)| other_id != memory_id)
+            .map(|(other_id, embedding)| {
+                let similarity = 1.0 - squared_distance(&source, &decode_embedding(&embedding));
+                (other_id, similarity)
+            })
+            .filter(|(_, similarity)| *similarity >= threshold)
+            .collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1));
+        results.truncate(limit);
+
+        Ok(results)
+    }
+
+    async fn text_search(
+        &self,
+        query: &str,
+        limit: usize,
+        audience: Option<&MemoryAudience>,
+    ) -> Result<Vec<(String, f32)>> {
+        let Some(match_query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let rows: Vec<(String, f64)> = sqlx::query_as(&format!(
+            "SELECT memory_id, -bm25(memory_embeddings_fts) \
+             FROM memory_embeddings_fts \
+             JOIN memory_embeddings ON memory_embeddings.rowid = memory_embeddings_fts.rowid \
+             WHERE memory_embeddings_fts MATCH ?4 AND {AUDIENCE_FILTER} \
+             ORDER BY 2 DESC LIMIT ?5"
+        ))
+        .bind(audience.is_some())
+        .bind(audience.and_then(|audience| audience.conversation_id.as_deref()))
+        .bind(audience.and_then(|audience| audience.sender_id.as_deref()))
+        .bind(match_query)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(memory_id, score)| (memory_id, score as f32))
+            .collect())
+    }
+}
+
+fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+    embedding
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+fn decode_embedding(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+fn squared_distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum()
+}
+
+/// An FTS5 query matching any word of `query`. Words are quoted so
+/// punctuation and FTS5 operators in user text are taken literally.
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{word}\""))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" OR "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{MemoryScope, MemoryType};
+
+    async fn store() -> SqliteEmbeddingStore {
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .in_memory(true)
+            .create_if_missing(true);
+        let pool = sqlx::pool::PoolOptions::<sqlx::Sqlite>::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("in-memory SQLite");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+        SqliteEmbeddingStore::new(pool)
+    }
+
+    fn memory(content: &str) -> Memory {
+        Memory::new(content, MemoryType::Fact)
+    }
+
+    #[tokio::test]
+    async fn vector_search_returns_nearest_first() {
+        let store = store().await;
+        let near = memory("likes tea");
+        let far = memory("lives in Lisbon");
+        store.store(&near, &[1.0, 0.0]).await.unwrap();
+        store.store(&far, &[0.0, 1.0]).await.unwrap();
+
+        let results = store.vector_search(&[0.9, 0.1], 2, None).await.unwrap();
+
+        assert_eq!(results[0].0, near.id);
+        assert_eq!(results[1].0, far.id);
+        assert!((results[0].1 - 0.02).abs() < 1e-6);
+
+        let similar = store.find_similar(&near.id, 0.5, 10).await.unwrap();
+        assert!(similar.is_empty());
+    }
+
+    #[tokio::test]
+    async fn text_search_follows_updates_and_deletes() {
+        let store = store().await;
+        let mut tea = memory("likes green tea");
+        store.store(&tea, &[1.0, 0.0]).await.unwrap();
+
+        let hits = store.text_search("tea?", 10, None).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0, tea.id);
+
+        tea.content = "likes coffee".into();
+        store.store(&tea, &[1.0, 0.0]).await.unwrap();
+        assert!(store.text_search("tea", 10, None).await.unwrap().is_empty());
+        assert_eq!(
+            store.text_search("coffee", 10, None).await.unwrap().len(),
+            1
+        );
+
+        store.delete(&tea.id).await.unwrap();
+        assert!(
+            store
+                .text_search("coffee", 10, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            store
+                .vector_search(&[1.0, 0.0], 10, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn searches_filter_by_audience() {
+        let store = store().await;
+        let mut private = memory("alice's tea order");
+        private.scope = MemoryScope::User;
+        private.owner_id = Some("discord:alice".into());
+        let shared = memory("the office tea kettle");
+        store.store(&private, &[1.0, 0.0]).await.unwrap();
+        store.store(&shared, &[1.0, 0.0]).await.unwrap();
+
+        let bob = MemoryAudience::new("discord:1", Some("discord:bob".into()));
+        let hits = store.text_search("tea", 10, Some(&bob)).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0, shared.id);
+        let nearest = store
+            .vector_search(&[1.0, 0.0], 10, Some(&bob))
+            .await
+            .unwrap();
+        assert_eq!(nearest.len(), 1);
+
+        let alice = MemoryAudience::new("discord:1", Some("discord:alice".into()));
+        assert_eq!(
+            store
+                .text_search("tea", 10, Some(&alice))
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
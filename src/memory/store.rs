@@ -2,7 +2,9 @@
 
 use crate::error::Result;
 use crate::memory::search::SearchSort;
-use crate::memory::types::{Association, Memory, MemoryType, RelationType};
+use crate::memory::types::{
+    Association, Memory, MemoryAudience, MemoryScope, MemoryType, RelationType,
+};
 
 use anyhow::Context as _;
 use sqlx::{Row, SqlitePool};
@@ -41,34 +43,9 @@ impl MemoryStore {
         })
     }
 
-    /// Get a reference to the SQLite pool.
-    pub(crate) fn pool(&self) -> &SqlitePool {
-        &self.pool
-    }
-
     /// Save a new memory to the store.
     pub async fn save(&self, memory: &Memory) -> Result<()> {
-        sqlx::query(
-            r#"
-            INSERT INTO memories (id, content, memory_type, importance, created_at, updated_at,
-                                 last_accessed_at, access_count, source, channel_id, forgotten)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(&memory.id)
-        .bind(&memory.content)
-        .bind(memory.memory_type.to_string())
-        .bind(memory.importance)
-        .bind(memory.created_at)
-        .bind(memory.updated_at)
-        .bind(memory.last_accessed_at)
-        .bind(memory.access_count)
-        .bind(&memory.source)
-        .bind(memory.channel_id.as_ref().map(|id| id.as_ref()))
-        .bind(memory.forgotten)
-        .execute(&self.pool)
-        .await
-        .with_context(|| format!("failed to save memory {}", memory.id))?;
+        Self::insert(&self.pool, memory).await?;
 
         #[cfg(feature = "metrics")]
         {
@@ -91,12 +68,46 @@ impl MemoryStore {
         Ok(())
     }
 
+    /// Insert a memory row through `executor`, so callers can save memories
+    /// inside their own transaction.
+    pub async fn insert<'e>(
+        executor: impl sqlx::SqliteExecutor<'e>,
+        memory: &Memory,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO memories (id, content, memory_type, importance, created_at, updated_at,
+                                 last_accessed_at, access_count, source, channel_id, forgotten,
+                                 scope, owner_id)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&memory.id)
+        .bind(&memory.content)
+        .bind(memory.memory_type.to_string())
+        .bind(memory.importance)
+        .bind(memory.created_at)
+        .bind(memory.updated_at)
+        .bind(memory.last_accessed_at)
+        .bind(memory.access_count)
+        .bind(&memory.source)
+        .bind(memory.channel_id.as_ref().map(|id| id.as_ref()))
+        .bind(memory.forgotten)
+        .bind(memory.scope.as_str())
+        .bind(&memory.owner_id)
+        .execute(executor)
+        .await
+        .with_context(|| format!("failed to save memory {}", memory.id))?;
+
+        Ok(())
+    }
+
     /// Load a memory by ID.
     pub async fn load(&self, id: &str) -> Result<Option<Memory>> {
         let row = sqlx::query(
             r#"
             SELECT id, content, memory_type, importance, created_at, updated_at,
-                   last_accessed_at, access_count, source, channel_id, forgotten
+                   last_accessed_at, access_count, source, channel_id, forgotten, scope, owner_id
             FROM memories
             WHERE id = ?
             "#,
@@ -116,7 +127,7 @@ impl MemoryStore {
             UPDATE memories 
             SET content = ?, memory_type = ?, importance = ?, updated_at = ?, 
                 last_accessed_at = ?, access_count = ?, source = ?, channel_id = ?,
-                forgotten = ?
+                forgotten = ?, scope = ?, owner_id = ?
             WHERE id = ?
             "#,
         )
@@ -129,6 +140,8 @@ impl MemoryStore {
         .bind(&memory.source)
         .bind(memory.channel_id.as_ref().map(|id| id.as_ref()))
         .bind(memory.forgotten)
+        .bind(memory.scope.as_str())
+        .bind(&memory.owner_id)
         .bind(&memory.id)
         .execute(&self.pool)
         .await
@@ -201,6 +214,66 @@ impl MemoryStore {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Set a memory's importance without touching `updated_at`, so
+    /// maintenance doesn't make every memory look freshly edited.
+    pub async fn set_importance(&self, id: &str, importance: f32) -> Result<()> {
+        sqlx::query("UPDATE memories SET importance = ? WHERE id = ?")
+            .bind(importance.clamp(0.0, 1.0))
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .with_context(|| format!("failed to set importance for memory {}", id))?;
+
+        Ok(())
+    }
+
+    /// Fold a merged duplicate's recall statistics into the memory that kept
+    /// it. Like `set_importance`, this leaves `updated_at` alone.
+    pub async fn absorb_duplicate(&self, keeper: &Memory) -> Result<()> {
+        sqlx::query(
+            "UPDATE memories SET importance = ?, access_count = ?, last_accessed_at = ? WHERE id = ?",
+        )
+        .bind(keeper.importance.clamp(0.0, 1.0))
+        .bind(keeper.access_count)
+        .bind(keeper.last_accessed_at)
+        .bind(&keeper.id)
+        .execute(&self.pool)
+        .await
+        .with_context(|| format!("failed to merge into memory {}", keeper.id))?;
+
+        Ok(())
+    }
+
+    /// Move every association of `from_id` onto `to_id`, for merging
+    /// memories. Edges `to_id` already has, and edges that would point at
+    /// itself, are dropped.
+    pub async fn reassign_associations(&self, from_id: &str, to_id: &str) -> Result<()> {
+        let context = || format!("failed to move associations from {} to {}", from_id, to_id);
+        let mut transaction = self.pool.begin().await.with_context(context)?;
+        for statement in [
+            "UPDATE OR IGNORE associations SET source_id = ? WHERE source_id = ?",
+            "UPDATE OR IGNORE associations SET target_id = ? WHERE target_id = ?",
+        ] {
+            sqlx::query(statement)
+                .bind(to_id)
+                .bind(from_id)
+                .execute(&mut *transaction)
+                .await
+                .with_context(context)?;
+        }
+        sqlx::query(
+            "DELETE FROM associations WHERE source_id = ?1 OR target_id = ?1 OR (source_id = ?2 AND target_id = ?2)",
+        )
+        .bind(from_id)
+        .bind(to_id)
+        .execute(&mut *transaction)
+        .await
+        .with_context(context)?;
+        transaction.commit().await.with_context(context)?;
+
+        Ok(())
+    }
+
     /// Create an association between two memories.
     pub async fn create_association(&self, association: &Association) -> Result<()> {
         sqlx::query(
@@ -359,7 +432,7 @@ impl MemoryStore {
         let rows = sqlx::query(
             r#"
             SELECT id, content, memory_type, importance, created_at, updated_at,
-                   last_accessed_at, access_count, source, channel_id, forgotten
+                   last_accessed_at, access_count, source, channel_id, forgotten, scope, owner_id
             FROM memories
             WHERE memory_type = ? AND forgotten = 0
             ORDER BY importance DESC, updated_at DESC
@@ -375,12 +448,60 @@ impl MemoryStore {
         Ok(rows.into_iter().map(|row| row_to_memory(&row)).collect())
     }
 
+    /// Get the memories created in a channel, oldest first.
+    pub async fn get_by_channel(&self, channel_id: &str) -> Result<Vec<Memory>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, content, memory_type, importance, created_at, updated_at,
+                   last_accessed_at, access_count, source, channel_id, forgotten, scope, owner_id
+            FROM memories
+            WHERE channel_id = ? AND forgotten = 0
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(channel_id)
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| format!("failed to get memories for channel {channel_id}"))?;
+
+        Ok(rows.into_iter().map(|row| row_to_memory(&row)).collect())
+    }
+
+    /// Get the latest events logged in a channel, newest first, skipping
+    /// user-scoped events that belong to someone other than `sender_id`.
+    pub async fn get_recent_events(
+        &self,
+        channel_id: &str,
+        sender_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Memory>> {
+        let query_str = format!(
+            "SELECT id, content, memory_type, importance, created_at, updated_at, \
+             last_accessed_at, access_count, source, channel_id, forgotten, scope, owner_id \
+             FROM memories \
+             WHERE channel_id = ? AND memory_type = ? AND forgotten = 0 {AUDIENCE_FILTER} \
+             ORDER BY created_at DESC \
+             LIMIT ?"
+        );
+        let rows = sqlx::query(&query_str)
+            .bind(channel_id)
+            .bind(MemoryType::Event.to_string())
+            .bind(channel_id)
+            .bind(sender_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .with_context(|| format!("failed to get recent events for channel {channel_id}"))?;
+
+        Ok(rows.into_iter().map(|row| row_to_memory(&row)).collect())
+    }
+
     /// Get high-importance memories for injection into context.
     pub async fn get_high_importance(&self, threshold: f32, limit: i64) -> Result<Vec<Memory>> {
         let rows = sqlx::query(
             r#"
             SELECT id, content, memory_type, importance, created_at, updated_at,
-                   last_accessed_at, access_count, source, channel_id, forgotten
+                   last_accessed_at, access_count, source, channel_id, forgotten, scope, owner_id
             FROM memories
             WHERE importance >= ? AND forgotten = 0
             ORDER BY importance DESC, updated_at DESC
@@ -399,52 +520,53 @@ impl MemoryStore {
     /// Get memories sorted by a flexible criterion with optional type filter.
     ///
     /// Used by non-hybrid search modes (Recent, Important, Typed) to retrieve
-    /// memories directly from SQLite without vector/FTS overhead.
+    /// memories directly from SQLite without vector/FTS overhead. With an
+    /// `audience`, memories scoped to other conversations or senders are
+    /// skipped before the limit is applied.
     pub async fn get_sorted(
         &self,
         sort: SearchSort,
         limit: i64,
         memory_type: Option<MemoryType>,
+        audience: Option<&MemoryAudience>,
     ) -> Result<Vec<Memory>> {
         let order_clause = match sort {
             SearchSort::Recent => "ORDER BY created_at DESC",
             SearchSort::Importance => "ORDER BY importance DESC, created_at DESC",
             SearchSort::MostAccessed => "ORDER BY access_count DESC, created_at DESC",
         };
-
-        let (query_str, type_filter) = if let Some(ref memory_type) = memory_type {
-            (
-                format!(
-                    "SELECT id, content, memory_type, importance, created_at, updated_at, \
-                     last_accessed_at, access_count, source, channel_id, forgotten \
-                     FROM memories WHERE memory_type = ? AND forgotten = 0 {order_clause} LIMIT ?"
-                ),
-                Some(memory_type.to_string()),
-            )
+        let type_clause = if memory_type.is_some() {
+            "AND memory_type = ?"
         } else {
-            (
-                format!(
-                    "SELECT id, content, memory_type, importance, created_at, updated_at, \
-                     last_accessed_at, access_count, source, channel_id, forgotten \
-                     FROM memories WHERE forgotten = 0 {order_clause} LIMIT ?"
-                ),
-                None,
-            )
+            ""
         };
-
-        let rows = if let Some(type_str) = type_filter {
-            sqlx::query(&query_str)
-                .bind(type_str)
-                .bind(limit)
-                .fetch_all(&self.pool)
-                .await
+        let audience_clause = if audience.is_some() {
+            AUDIENCE_FILTER
         } else {
-            sqlx::query(&query_str)
-                .bind(limit)
-                .fetch_all(&self.pool)
-                .await
+            ""
+        };
+
+        let query_str = format!(
+            "SELECT id, content, memory_type, importance, created_at, updated_at, \
+             last_accessed_at, access_count, source, channel_id, forgotten, scope, owner_id \
+             FROM memories WHERE forgotten = 0 {type_clause} {audience_clause} \
+             {order_clause} LIMIT ?"
+        );
+
+        let mut query = sqlx::query(&query_str);
+        if let Some(memory_type) = memory_type {
+            query = query.bind(memory_type.to_string());
+        }
+        if let Some(audience) = audience {
+            query = query
+                .bind(audience.conversation_id.clone())
+                .bind(audience.sender_id.clone());
         }
-        .with_context(|| format!("failed to get sorted memories ({sort:?})"))?;
+        let rows = query
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .with_context(|| format!("failed to get sorted memories ({sort:?})"))?;
 
         Ok(rows.into_iter().map(|row| row_to_memory(&row)).collect())
     }
@@ -476,6 +598,13 @@ impl MemoryStore {
     }
 }
 
+/// SQL form of [`MemoryAudience::can_see`]. Binds the audience's
+/// conversation ID, then its sender ID; a NULL bind matches nothing, so an
+/// empty audience sees only agent-scoped memories.
+const AUDIENCE_FILTER: &str = "AND (scope = 'agent' \
+     OR (scope = 'conversation' AND channel_id = ?) \
+     OR (scope = 'user' AND owner_id = ?))";
+
 /// Helper: Convert a database row to a Memory.
 fn row_to_memory(row: &sqlx::sqlite::SqliteRow) -> Memory {
     let mem_type_str: String = row.try_get("memory_type").unwrap_or_default();
@@ -501,6 +630,12 @@ fn row_to_memory(row: &sqlx::sqlite::SqliteRow) -> Memory {
         source: row.try_get("source").ok(),
         channel_id: channel_id.map(|id| Arc::from(id) as crate::ChannelId),
         forgotten: row.try_get::<bool, _>("forgotten").unwrap_or(false),
+        scope: row
+            .try_get::<String, _>("scope")
+            .ok()
+            .and_then(|scope| MemoryScope::parse(&scope))
+            .unwrap_or_default(),
+        owner_id: row.try_get("owner_id").ok().flatten(),
     }
 }
 
@@ -570,6 +705,89 @@ mod tests {
         memory
     }
 
+    #[tokio::test]
+    async fn test_reassign_associations_merges_edges() {
+        let store = MemoryStore::connect_in_memory().await;
+        let keeper = Memory::new("keeper", MemoryType::Fact);
+        let duplicate = Memory::new("duplicate", MemoryType::Fact);
+        let other = Memory::new("other", MemoryType::Fact);
+        for memory in [&keeper, &duplicate, &other] {
+            store.save(memory).await.unwrap();
+        }
+        for (source, target) in [
+            (&duplicate.id, &other.id),
+            (&keeper.id, &other.id),
+            (&duplicate.id, &keeper.id),
+            (&other.id, &other.id),
+        ] {
+            store
+                .create_association(&Association::new(source, target, RelationType::RelatedTo))
+                .await
+                .unwrap();
+        }
+
+        store
+            .reassign_associations(&duplicate.id, &keeper.id)
+            .await
+            .unwrap();
+
+        assert!(
+            store
+                .get_associations(&duplicate.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let edges = store.get_associations(&keeper.id).await.unwrap();
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].target_id, other.id);
+        // Self-loops unrelated to the merge are left alone.
+        let other_edges = store.get_associations(&other.id).await.unwrap();
+        assert!(
+            other_edges
+                .iter()
+                .any(|edge| edge.source_id == other.id && edge.target_id == other.id)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_recent_events_is_per_channel_and_newest_first() {
+        let store = MemoryStore::connect_in_memory().await;
+        let now = Utc::now();
+        let channel: crate::ChannelId = Arc::from("discord:1");
+
+        for (content, memory_type, hours_ago, channel_id) in [
+            ("shipped v1", MemoryType::Event, 3, Some(channel.clone())),
+            ("shipped v2", MemoryType::Event, 1, Some(channel.clone())),
+            ("prefers rust", MemoryType::Fact, 0, Some(channel.clone())),
+            (
+                "elsewhere",
+                MemoryType::Event,
+                0,
+                Some(Arc::from("discord:2")),
+            ),
+            ("unscoped", MemoryType::Event, 0, None),
+        ] {
+            let mut memory = Memory::new(content, memory_type);
+            memory.created_at = now - Duration::hours(hours_ago);
+            memory.channel_id = channel_id;
+            store.save(&memory).await.unwrap();
+        }
+
+        let events = store
+            .get_recent_events("discord:1", None, 10)
+            .await
+            .unwrap();
+        let contents: Vec<_> = events
+            .iter()
+            .map(|memory| memory.content.as_str())
+            .collect();
+        assert_eq!(contents, ["shipped v2", "shipped v1"]);
+
+        let events = store.get_recent_events("discord:1", None, 1).await.unwrap();
+        assert_eq!(events.len(), 1);
+    }
+
     #[tokio::test]
     async fn test_save_and_load() {
         let store = MemoryStore::connect_in_memory().await;
@@ -605,7 +823,7 @@ mod tests {
         let new = insert_memory_at(&store, "new", MemoryType::Fact, 0.5, now).await;
 
         let results = store
-            .get_sorted(SearchSort::Recent, 10, None)
+            .get_sorted(SearchSort::Recent, 10, None, None)
             .await
             .unwrap();
         assert_eq!(results.len(), 3);
@@ -624,7 +842,7 @@ mod tests {
         let medium = insert_memory_at(&store, "medium", MemoryType::Fact, 0.5, now).await;
 
         let results = store
-            .get_sorted(SearchSort::Importance, 10, None)
+            .get_sorted(SearchSort::Importance, 10, None, None)
             .await
             .unwrap();
         assert_eq!(results[0].id, high.id);
@@ -647,7 +865,7 @@ mod tests {
         }
 
         let results = store
-            .get_sorted(SearchSort::MostAccessed, 10, None)
+            .get_sorted(SearchSort::MostAccessed, 10, None, None)
             .await
             .unwrap();
         assert_eq!(results[0].id, b.id);
@@ -665,7 +883,7 @@ mod tests {
         insert_memory_at(&store, "an event", MemoryType::Event, 0.5, now).await;
 
         let results = store
-            .get_sorted(SearchSort::Recent, 10, Some(MemoryType::Decision))
+            .get_sorted(SearchSort::Recent, 10, Some(MemoryType::Decision), None)
             .await
             .unwrap();
         assert_eq!(results.len(), 1);
@@ -688,7 +906,10 @@ mod tests {
             .await;
         }
 
-        let results = store.get_sorted(SearchSort::Recent, 3, None).await.unwrap();
+        let results = store
+            .get_sorted(SearchSort::Recent, 3, None, None)
+            .await
+            .unwrap();
         assert_eq!(results.len(), 3);
     }
 
@@ -702,10 +923,68 @@ mod tests {
         store.forget(&forgotten.id).await.unwrap();
 
         let results = store
-            .get_sorted(SearchSort::Recent, 10, None)
+            .get_sorted(SearchSort::Recent, 10, None, None)
             .await
             .unwrap();
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].id, visible.id);
     }
+
+    #[tokio::test]
+    async fn test_get_sorted_filters_by_audience() {
+        let store = MemoryStore::connect_in_memory().await;
+        let dm: crate::ChannelId = Arc::from("discord:dm:1");
+
+        for (content, scope, channel_id, owner_id) in [
+            ("global", MemoryScope::Agent, None, None),
+            ("dm only", MemoryScope::Conversation, Some(dm.clone()), None),
+            ("alice only", MemoryScope::User, None, Some("discord:alice")),
+        ] {
+            let mut memory = Memory::new(content, MemoryType::Fact)
+                .with_scope(scope, owner_id.map(String::from));
+            memory.channel_id = channel_id;
+            store.save(&memory).await.unwrap();
+        }
+
+        let visible = |audience: MemoryAudience| {
+            let store = store.clone();
+            async move {
+                let mut contents: Vec<_> = store
+                    .get_sorted(SearchSort::Recent, 10, None, Some(&audience))
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|memory| memory.content)
+                    .collect();
+                contents.sort();
+                contents
+            }
+        };
+
+        assert_eq!(
+            visible(MemoryAudience::new(
+                "discord:dm:1",
+                Some("discord:alice".into())
+            ))
+            .await,
+            vec!["alice only", "dm only", "global"]
+        );
+        assert_eq!(
+            visible(MemoryAudience::new(
+                "discord:42",
+                Some("discord:bob".into())
+            ))
+            .await,
+            vec!["global"]
+        );
+        assert_eq!(visible(MemoryAudience::default()).await, vec!["global"]);
+        assert_eq!(
+            store
+                .get_sorted(SearchSort::Recent, 10, None, None)
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+    }
 }
@@ -19,6 +19,13 @@ pub struct Memory {
     /// Soft-delete flag. Forgotten memories are excluded from search and recall
     /// but remain in the database.
     pub forgotten: bool,
+    /// Who this memory is visible to at retrieval time.
+    #[serde(default)]
+    pub scope: MemoryScope,
+    /// Platform-qualified sender the memory belongs to, for user-scoped
+    /// memories.
+    #[serde(default)]
+    pub owner_id: Option<String>,
 }
 
 impl Memory {
@@ -40,6 +47,8 @@ impl Memory {
             source: None,
             channel_id: None,
             forgotten: false,
+            scope: MemoryScope::Agent,
+            owner_id: None,
         }
     }
 
@@ -61,6 +70,13 @@ impl Memory {
         self
     }
 
+    /// Set the visibility scope and, for user-scoped memories, the owner.
+    pub fn with_scope(mut self, scope: MemoryScope, owner_id: Option<String>) -> Self {
+        self.scope = scope;
+        self.owner_id = owner_id;
+        self
+    }
+
     /// Identity memories have maximum importance and don't decay.
     pub const fn identity_importance() -> f32 {
         1.0
@@ -124,6 +140,114 @@ impl MemoryType {
     ];
 }
 
+impl MemoryType {
+    /// Whether memories of this type record something that happened or
+    /// something that holds.
+    pub fn kind(&self) -> MemoryKind {
+        match self {
+            MemoryType::Event => MemoryKind::Episodic,
+            _ => MemoryKind::Semantic,
+        }
+    }
+}
+
+/// The two halves of the memory store.
+///
+/// Episodic memories are timestamped records of what happened in a
+/// conversation and are recalled by recency within it. Semantic memories are
+/// facts, preferences, and decisions that hold regardless of when they were
+/// learned and are recalled by relevance from anywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryKind {
+    Episodic,
+    Semantic,
+}
+
+/// How far a memory travels beyond the conversation that saved it.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryScope {
+    /// Only visible in the channel the memory was saved from.
+    Conversation,
+    /// Only visible while the owning sender is the one talking.
+    User,
+    /// Visible everywhere the agent runs.
+    #[default]
+    Agent,
+}
+
+impl MemoryScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MemoryScope::Conversation => "conversation",
+            MemoryScope::User => "user",
+            MemoryScope::Agent => "agent",
+        }
+    }
+
+    /// Scope for a memory saved without an explicit one: whatever is said in
+    /// a DM stays in that DM, everything else is shared.
+    pub fn default_for(channel_id: Option<&str>) -> Self {
+        match channel_id {
+            Some(channel_id) if crate::agent::lanes::is_direct_conversation(channel_id) => {
+                MemoryScope::Conversation
+            }
+            _ => MemoryScope::Agent,
+        }
+    }
+
+    /// Parse a stored or tool-supplied scope name.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "conversation" => Some(MemoryScope::Conversation),
+            "user" => Some(MemoryScope::User),
+            "agent" => Some(MemoryScope::Agent),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for MemoryScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The conversation a retrieval happens in, used to decide which scoped
+/// memories are visible.
+///
+/// An audience with neither field set sees only agent-scoped memories.
+/// Callers that should see everything (the API, operators) pass no audience
+/// at all rather than an empty one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryAudience {
+    pub conversation_id: Option<String>,
+    pub sender_id: Option<String>,
+}
+
+impl MemoryAudience {
+    pub fn new(conversation_id: impl Into<String>, sender_id: Option<String>) -> Self {
+        Self {
+            conversation_id: Some(conversation_id.into()),
+            sender_id,
+        }
+    }
+
+    /// Whether `memory` may be shown to this audience.
+    pub fn can_see(&self, memory: &Memory) -> bool {
+        match memory.scope {
+            MemoryScope::Agent => true,
+            MemoryScope::Conversation => {
+                self.conversation_id.is_some()
+                    && memory.channel_id.as_deref() == self.conversation_id.as_deref()
+            }
+            MemoryScope::User => {
+                self.sender_id.is_some() && memory.owner_id.as_deref() == self.sender_id.as_deref()
+            }
+        }
+    }
+}
+
 impl std::fmt::Display for MemoryType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
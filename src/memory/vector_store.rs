@@ -0,0 +1,161 @@
+//! Storage backend for memory embeddings.
+
+use crate::error::Result;
+use crate::memory::{Memory, MemoryAudience};
+
+use std::future::Future;
+use std::pin::Pin;
+
+/// Boxed future returned by the dynamic store trait.
+type StoreFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// Embeddings and searchable text for memories, keyed by memory ID.
+///
+/// The memory rows themselves live in SQLite ([`MemoryStore`]); this holds
+/// what's needed to find them by meaning or by keyword. Distances are what
+/// the LanceDB table reports, squared L2, so `1.0 - distance` is the
+/// similarity used throughout search and maintenance.
+///
+/// Searches take the audience they're for. Backends that keep each memory's
+/// scope alongside its embedding filter on it so the limit isn't spent on
+/// memories the caller can't see; the others ignore it. Callers still check
+/// [`MemoryAudience::can_see`] on what they load.
+///
+/// [`MemoryStore`]: crate::memory::MemoryStore
+pub trait EmbeddingStore: Send + Sync + 'static {
+    /// Store the embedding and text of a memory.
+    fn store(&self, memory: &Memory, embedding: &[f32]) -> impl Future<Output = Result<()>> + Send;
+
+    /// Store several memories. Backends that can write them in one request
+    /// override this.
+    fn store_many(
+        &self,
+        memories: &[(Memory, Vec<f32>)],
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            for (memory, embedding) in memories {
+                self.store(memory, embedding).await?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Remove a memory's embedding.
+    fn delete(&self, memory_id: &str) -> impl Future<Output = Result<()>> + Send;
+
+    /// Nearest memories to an embedding as `(memory_id, distance)` pairs,
+    /// closest first.
+    fn vector_search(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        audience: Option<&MemoryAudience>,
+    ) -> impl Future<Output = Result<Vec<(String, f32)>>> + Send;
+
+    /// Memories at least `threshold` similar to `memory_id`, as
+    /// `(memory_id, similarity)` pairs, excluding the memory itself.
+    fn find_similar(
+        &self,
+        memory_id: &str,
+        threshold: f32,
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<(String, f32)>>> + Send;
+
+    /// Keyword matches as `(memory_id, score)` pairs, best first.
+    fn text_search(
+        &self,
+        query: &str,
+        limit: usize,
+        audience: Option<&MemoryAudience>,
+    ) -> impl Future<Output = Result<Vec<(String, f32)>>> + Send;
+
+    /// Make sure keyword search has an index to use. Backends that index
+    /// text on write have nothing to do.
+    fn ensure_fts_index(&self) -> impl Future<Output = Result<()>> + Send {
+        async { Ok(()) }
+    }
+}
+
+/// Dynamic companion to [`EmbeddingStore`] for holding `Arc<dyn EmbeddingStoreDyn>`.
+pub trait EmbeddingStoreDyn: Send + Sync + 'static {
+    fn store<'a>(&'a self, memory: &'a Memory, embedding: &'a [f32]) -> StoreFuture<'a, ()>;
+
+    fn store_many<'a>(&'a self, memories: &'a [(Memory, Vec<f32>)]) -> StoreFuture<'a, ()>;
+
+    fn delete<'a>(&'a self, memory_id: &'a str) -> StoreFuture<'a, ()>;
+
+    fn vector_search<'a>(
+        &'a self,
+        query_embedding: &'a [f32],
+        limit: usize,
+        audience: Option<&'a MemoryAudience>,
+    ) -> StoreFuture<'a, Vec<(String, f32)>>;
+
+    fn find_similar<'a>(
+        &'a self,
+        memory_id: &'a str,
+        threshold: f32,
+        limit: usize,
+    ) -> StoreFuture<'a, Vec<(String, f32)>>;
+
+    fn text_search<'a>(
+        &'a self,
+        query: &'a str,
+        limit: usize,
+        audience: Option<&'a MemoryAudience>,
+    ) -> StoreFuture<'a, Vec<(String, f32)>>;
+
+    fn ensure_fts_index<'a>(&'a self) -> StoreFuture<'a, ()>;
+}
+
+impl<T: EmbeddingStore> EmbeddingStoreDyn for T {
+    fn store<'a>(&'a self, memory: &'a Memory, embedding: &'a [f32]) -> StoreFuture<'a, ()> {
+        Box::pin(EmbeddingStore::store(self, memory, embedding))
+    }
+
+    fn store_many<'a>(&'a self, memories: &'a [(Memory, Vec<f32>)]) -> StoreFuture<'a, ()> {
+        Box::pin(EmbeddingStore::store_many(self, memories))
+    }
+
+    fn delete<'a>(&'a self, memory_id: &'a str) -> StoreFuture<'a, ()> {
+        Box::pin(EmbeddingStore::delete(self, memory_id))
+    }
+
+    fn vector_search<'a>(
+        &'a self,
+        query_embedding: &'a [f32],
+        limit: usize,
+        audience: Option<&'a MemoryAudience>,
+    ) -> StoreFuture<'a, Vec<(String, f32)>> {
+        Box::pin(EmbeddingStore::vector_search(
+            self,
+            query_embedding,
+            limit,
+            audience,
+        ))
+    }
+
+    fn find_similar<'a>(
+        &'a self,
+        memory_id: &'a str,
+        threshold: f32,
+        limit: usize,
+    ) -> StoreFuture<'a, Vec<(String, f32)>> {
+        Box::pin(EmbeddingStore::find_similar(
+            self, memory_id, threshold, limit,
+        ))
+    }
+
+    fn text_search<'a>(
+        &'a self,
+        query: &'a str,
+        limit: usize,
+        audience: Option<&'a MemoryAudience>,
+    ) -> StoreFuture<'a, Vec<(String, f32)>> {
+        Box::pin(EmbeddingStore::text_search(self, query, limit, audience))
+    }
+
+    fn ensure_fts_index<'a>(&'a self) -> StoreFuture<'a, ()> {
+        Box::pin(EmbeddingStore::ensure_fts_index(self))
+    }
+}
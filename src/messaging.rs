@@ -1,6 +1,10 @@
-//! Messaging adapters (Discord, Slack, Telegram, Twitch, Webhook, WebChat).
+//! Messaging adapters (Discord, Slack, Telegram, Twitch, Email, Webhook, WebChat, CLI).
 
+pub mod chunking;
+pub mod cli;
+pub mod delivery;
 pub mod discord;
+pub mod email;
 pub mod manager;
 pub mod slack;
 pub mod target;
@@ -0,0 +1,254 @@
+//! Splitting long replies into messages that fit a platform's length limit.
+//!
+//! Text is split between paragraphs where it can, then between lines, then
+//! between words, and only cut mid-word as a last resort. A code block is
+//! kept whole when it fits; when it doesn't, each piece is closed and the
+//! next one reopens it with the same fence, so every message renders as
+//! code. Continuation messages are numbered, e.g. `(2/3)`.
+
+/// Room kept at the start of each chunk for its number.
+const NUMBER_RESERVE: usize = "(999/999)\n".len();
+
+/// Split `text` into messages of at most `max_len` bytes, numbering every
+/// message after the first.
+pub fn split_message(text: &str, max_len: usize) -> Vec<String> {
+    if text.len() <= max_len {
+        return vec![text.to_string()];
+    }
+
+    let chunks = chunk_markdown(text, max_len.saturating_sub(NUMBER_RESERVE).max(1));
+    let total = chunks.len();
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, chunk)| {
+            if index == 0 {
+                chunk
+            } else {
+                format!("({}/{total})\n{chunk}", index + 1)
+            }
+        })
+        .collect()
+}
+
+/// Split `text` into chunks of at most `max_len` bytes along markdown
+/// boundaries, without numbering them.
+pub fn chunk_markdown(text: &str, max_len: usize) -> Vec<String> {
+    if text.len() <= max_len {
+        return vec![text.to_string()];
+    }
+
+    let mut pieces = Vec::new();
+    for block in blocks(text) {
+        if block.text.len() <= max_len {
+            pieces.push(block.text);
+        } else if let Some(fence) = &block.fence {
+            pieces.extend(split_code_block(&block.text, fence, max_len));
+        } else {
+            pieces.extend(split_lines(&block.text, max_len));
+        }
+    }
+    pack(pieces, "\n\n", max_len)
+}
+
+/// A paragraph, or a fenced code block with its opening fence line.
+struct Block {
+    text: String,
+    fence: Option<String>,
+}
+
+/// Break text into paragraphs and code blocks. Blank lines inside a code
+/// block belong to it.
+fn blocks(text: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut fence: Option<String> = None;
+
+    for line in text.lines() {
+        let is_fence = line.trim_start().starts_with("```");
+        match &fence {
+            Some(_) => {
+                current.push(line);
+                if is_fence {
+                    blocks.push(Block {
+                        text: current.join("\n"),
+                        fence: fence.take(),
+                    });
+                    current.clear();
+                }
+            }
+            None if is_fence => {
+                if !current.is_empty() {
+                    blocks.push(Block {
+                        text: current.join("\n"),
+                        fence: None,
+                    });
+                    current.clear();
+                }
+                fence = Some(line.trim().to_string());
+                current.push(line);
+            }
+            None if line.trim().is_empty() => {
+                if !current.is_empty() {
+                    blocks.push(Block {
+                        text: current.join("\n"),
+                        fence: None,
+                    });
+                    current.clear();
+                }
+            }
+            None => current.push(line),
+        }
+    }
+    if !current.is_empty() {
+        blocks.push(Block {
+            text: current.join("\n"),
+            fence,
+        });
+    }
+    blocks
+}
+
+/// Split an oversized code block into pieces that each open with `fence`
+/// and close with ```` ``` ````.
+fn split_code_block(block: &str, fence: &str, max_len: usize) -> Vec<String> {
+    let mut lines: Vec<&str> = block.lines().skip(1).collect();
+    if lines
+        .last()
+        .is_some_and(|line| line.trim_start().starts_with("```"))
+    {
+        lines.pop();
+    }
+
+    let overhead = fence.len() + "\n".len() + "\n```".len();
+    let budget = max_len.saturating_sub(overhead).max(1);
+    let mut parts = Vec::new();
+    for line in lines {
+        if line.len() <= budget {
+            parts.push(line.to_string());
+        } else {
+            parts.extend(hard_split(line, budget));
+        }
+    }
+    pack(parts, "\n", budget)
+        .into_iter()
+        .map(|body| format!("{fence}\n{body}\n```"))
+        .collect()
+}
+
+/// Split a paragraph between lines, breaking long lines between words.
+fn split_lines(text: &str, max_len: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    for line in text.lines() {
+        if line.len() <= max_len {
+            parts.push(line.to_string());
+        } else {
+            parts.extend(split_words(line, max_len));
+        }
+    }
+    pack(parts, "\n", max_len)
+}
+
+/// Split a line between words, cutting words longer than `max_len`.
+fn split_words(line: &str, max_len: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    for word in line.split(' ') {
+        if word.len() <= max_len {
+            parts.push(word.to_string());
+        } else {
+            parts.extend(hard_split(word, max_len));
+        }
+    }
+    pack(parts, " ", max_len)
+}
+
+/// Cut text into pieces of at most `max_len` bytes on char boundaries.
+fn hard_split(text: &str, max_len: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut remaining = text;
+    while !remaining.is_empty() {
+        let mut end = remaining.floor_char_boundary(max_len.min(remaining.len()));
+        if end == 0 {
+            // A single character wider than the budget still has to go somewhere.
+            end = remaining.ceil_char_boundary(1);
+        }
+        pieces.push(remaining[..end].to_string());
+        remaining = &remaining[end..];
+    }
+    pieces
+}
+
+/// Join parts with `separator` into as few chunks of at most `max_len` bytes
+/// as possible, keeping their order.
+fn pack(parts: Vec<String>, separator: &str, max_len: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for part in parts {
+        if current.is_empty() {
+            current = part;
+        } else if current.len() + separator.len() + part.len() <= max_len {
+            current.push_str(separator);
+            current.push_str(&part);
+        } else {
+            chunks.push(std::mem::replace(&mut current, part));
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_text_is_sent_as_is() {
+        assert_eq!(split_message("hello", 2000), ["hello"]);
+    }
+
+    #[test]
+    fn splits_between_paragraphs_and_numbers_continuations() {
+        let first = "a".repeat(60);
+        let second = "b".repeat(60);
+        let text = format!("{first}\n\n{second}");
+
+        let chunks = split_message(&text, 100);
+        assert_eq!(chunks, [first, format!("(2/2)\n{second}")]);
+    }
+
+    #[test]
+    fn oversized_code_blocks_keep_their_fences_balanced() {
+        let code: Vec<String> = (0..40)
+            .map(|line| format!("let x{line} = {line};"))
+            .collect();
+        let text = format!("Here you go:\n\n```rust\n{}\n```\n\nDone.", code.join("\n"));
+
+        let chunks = split_message(&text, 200);
+        assert!(chunks.len() > 2);
+        for chunk in &chunks {
+            assert!(chunk.len() <= 200, "chunk too long: {}", chunk.len());
+            assert_eq!(chunk.matches("```").count() % 2, 0, "unbalanced: {chunk}");
+        }
+        let code_chunks = chunks.iter().filter(|chunk| chunk.contains("```rust\n"));
+        assert!(code_chunks.count() > 1);
+        assert!(chunks.last().unwrap().ends_with("Done."));
+    }
+
+    #[test]
+    fn blank_lines_inside_code_blocks_do_not_split_them() {
+        let text = "```\nfn a() {}\n\nfn b() {}\n```";
+        let blocks = blocks(text);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].fence.as_deref(), Some("```"));
+    }
+
+    #[test]
+    fn long_words_are_cut_on_char_boundaries() {
+        let text = "é".repeat(150);
+        let chunks = chunk_markdown(&text, 101);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 101));
+        assert_eq!(chunks.concat(), text);
+    }
+}
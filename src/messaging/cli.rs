@@ -0,0 +1,381 @@
+//! Terminal messaging adapter for `spacebot chat`.
+//!
+//! Reads lines from stdin as messages in one local conversation and prints
+//! responses to stdout. Status updates (thinking, tool calls, branches,
+//! workers) are rendered inline on stderr, so piping stdout captures only the
+//! agent's replies. The adapter goes through the same `InboundMessage` /
+//! `OutboundResponse` contract as every platform adapter.
+
+use crate::messaging::traits::{InboundStream, Messaging};
+use crate::{AgentId, InboundMessage, MessageContent, OutboundResponse, StatusUpdate};
+
+use std::collections::HashMap;
+use std::io::Write as _;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::io::AsyncBufReadExt as _;
+use tokio::sync::{Notify, mpsc};
+
+/// Lines that end the chat session.
+const EXIT_COMMANDS: &[&str] = &["/exit", "/quit"];
+
+/// Stdin/stdout adapter for local development.
+pub struct CliAdapter {
+    /// Agent the conversation is pinned to, bypassing bindings.
+    agent_id: AgentId,
+    conversation_id: String,
+    sender_id: String,
+    started: AtomicBool,
+    /// Whether a streamed response is being printed, so the next status line
+    /// starts on a fresh line.
+    streaming: AtomicBool,
+    closed: Arc<Notify>,
+}
+
+impl CliAdapter {
+    pub fn new(agent_id: &str) -> Self {
+        let sender_id = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_else(|_| "local".into());
+        Self {
+            agent_id: Arc::from(agent_id),
+            conversation_id: format!("cli:{agent_id}"),
+            sender_id,
+            started: AtomicBool::new(false),
+            streaming: AtomicBool::new(false),
+            closed: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Resolves once stdin is closed or the user typed an exit command.
+    pub async fn closed(&self) {
+        self.closed.notified().await;
+    }
+
+    fn render(&self, response: OutboundResponse) {
+        for output in terminal_output(response) {
+            match output {
+                TerminalOutput::Reply(text) => self.print_reply(&text),
+                TerminalOutput::Status(line) => self.print_status(&line),
+                TerminalOutput::StreamStart => {
+                    self.end_stream();
+                    self.streaming.store(true, Ordering::Relaxed);
+                }
+                TerminalOutput::StreamChunk(chunk) => {
+                    let mut stdout = std::io::stdout().lock();
+                    let _ = write!(stdout, "{chunk}");
+                    let _ = stdout.flush();
+                }
+                TerminalOutput::StreamEnd => self.end_stream(),
+            }
+        }
+    }
+
+    fn render_status(&self, status: StatusUpdate) {
+        if let Some(line) = status_line(status) {
+            self.print_status(&line);
+        }
+    }
+
+    fn print_reply(&self, text: &str) {
+        self.end_stream();
+        let mut stdout = std::io::stdout().lock();
+        let _ = writeln!(stdout, "{}\n", text.trim_end());
+        let _ = stdout.flush();
+    }
+
+    fn print_status(&self, line: &str) {
+        self.end_stream();
+        eprintln!("  · {line}");
+    }
+
+    fn end_stream(&self) {
+        if self.streaming.swap(false, Ordering::Relaxed) {
+            println!("\n");
+        }
+    }
+}
+
+impl Messaging for CliAdapter {
+    fn name(&self) -> &str {
+        "cli"
+    }
+
+    async fn start(&self) -> crate::Result<InboundStream> {
+        // Agents can be re-initialized (e.g. after provider setup), which
+        // starts adapters again. Only one task may own stdin.
+        if self.started.swap(true, Ordering::SeqCst) {
+            return Ok(Box::pin(futures::stream::pending()));
+        }
+
+        let (inbound_tx, inbound_rx) = mpsc::channel(32);
+        let agent_id = self.agent_id.clone();
+        let conversation_id = self.conversation_id.clone();
+        let sender_id = self.sender_id.clone();
+        let closed = self.closed.clone();
+
+        tokio::spawn(async move {
+            let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+            loop {
+                let line = match lines.next_line().await {
+                    Ok(Some(line)) => line,
+                    Ok(None) => break,
+                    Err(error) => {
+                        tracing::warn!(%error, "failed to read from stdin");
+                        break;
+                    }
+                };
+                let message = match parse_line(&line, &agent_id, &conversation_id, &sender_id) {
+                    LineInput::Message(message) => *message,
+                    LineInput::Skip => continue,
+                    LineInput::Exit => break,
+                };
+                if inbound_tx.send(message).await.is_err() {
+                    break;
+                }
+            }
+            closed.notify_one();
+        });
+
+        let stream = tokio_stream::wrappers::ReceiverStream::new(inbound_rx);
+        Ok(Box::pin(stream))
+    }
+
+    async fn respond(
+        &self,
+        _message: &InboundMessage,
+        response: OutboundResponse,
+    ) -> crate::Result<()> {
+        self.render(response);
+        Ok(())
+    }
+
+    async fn send_status(
+        &self,
+        _message: &InboundMessage,
+        status: StatusUpdate,
+    ) -> crate::Result<()> {
+        self.render_status(status);
+        Ok(())
+    }
+
+    async fn broadcast(&self, _target: &str, response: OutboundResponse) -> crate::Result<()> {
+        // Cron results and heartbeats addressed to `cli:*` land in the terminal.
+        self.render(response);
+        Ok(())
+    }
+
+    async fn health_check(&self) -> crate::Result<()> {
+        Ok(())
+    }
+}
+
+/// What a line typed at the prompt amounts to.
+enum LineInput {
+    Message(Box<InboundMessage>),
+    /// Blank line.
+    Skip,
+    /// An exit command.
+    Exit,
+}
+
+fn parse_line(line: &str, agent_id: &AgentId, conversation_id: &str, sender_id: &str) -> LineInput {
+    let text = line.trim();
+    if text.is_empty() {
+        return LineInput::Skip;
+    }
+    if EXIT_COMMANDS.contains(&text) {
+        return LineInput::Exit;
+    }
+
+    let mut metadata = HashMap::new();
+    metadata.insert("sender_display_name".into(), sender_id.into());
+    LineInput::Message(Box::new(InboundMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        source: "cli".into(),
+        conversation_id: conversation_id.to_string(),
+        sender_id: sender_id.to_string(),
+        agent_id: Some(agent_id.clone()),
+        content: MessageContent::Text(text.to_string()),
+        timestamp: chrono::Utc::now(),
+        metadata,
+        formatted_author: None,
+    }))
+}
+
+/// How a response shows up in the terminal. Replies go to stdout, status
+/// lines to stderr.
+#[derive(Debug, PartialEq)]
+enum TerminalOutput {
+    Reply(String),
+    Status(String),
+    StreamStart,
+    StreamChunk(String),
+    StreamEnd,
+}
+
+fn terminal_output(response: OutboundResponse) -> Vec<TerminalOutput> {
+    match response {
+        OutboundResponse::Text(text)
+        | OutboundResponse::Reply { text, .. }
+        | OutboundResponse::ThreadReply { text, .. }
+        | OutboundResponse::Ephemeral { text, .. }
+        | OutboundResponse::RichMessage { text, .. }
+        | OutboundResponse::ScheduledMessage { text, .. } => vec![TerminalOutput::Reply(text)],
+        OutboundResponse::Structured(reply) => {
+            let label = match reply.confidence {
+                Some(confidence) => format!("{:?}, confidence {confidence:.2}", reply.action),
+                None => format!("{:?}", reply.action),
+            };
+            vec![TerminalOutput::Reply(format!(
+                "[{label}] {}",
+                reply.message
+            ))]
+        }
+        OutboundResponse::Edit { text, .. } => {
+            vec![TerminalOutput::Reply(format!("(edited) {text}"))]
+        }
+        OutboundResponse::File {
+            filename,
+            data,
+            caption,
+            media,
+            ..
+        } => {
+            let mut output: Vec<TerminalOutput> =
+                caption.into_iter().map(TerminalOutput::Reply).collect();
+            output.push(TerminalOutput::Status(format!(
+                "file: {filename} ({} bytes)",
+                data.len()
+            )));
+            output.extend(media.map(|media| TerminalOutput::Status(media.url)));
+            output
+        }
+        OutboundResponse::Reaction(emoji) => {
+            vec![TerminalOutput::Status(format!("reacted {emoji}"))]
+        }
+        OutboundResponse::StreamStart => vec![TerminalOutput::StreamStart],
+        OutboundResponse::StreamChunk(chunk) => vec![TerminalOutput::StreamChunk(chunk)],
+        OutboundResponse::StreamEnd => vec![TerminalOutput::StreamEnd],
+        OutboundResponse::Status(status) => status_line(status)
+            .map(TerminalOutput::Status)
+            .into_iter()
+            .collect(),
+        OutboundResponse::RemoveReaction(_)
+        | OutboundResponse::Delete { .. }
+        | OutboundResponse::Draft { .. } => Vec::new(),
+    }
+}
+
+/// The status line printed for an update, if it gets one.
+fn status_line(status: StatusUpdate) -> Option<String> {
+    let line = match status {
+        StatusUpdate::Thinking => "thinking".to_string(),
+        StatusUpdate::ToolStarted { tool_name } => tool_name,
+        StatusUpdate::RunningTool { name } => format!("running {name}"),
+        StatusUpdate::WaitingOnWorker { count } => {
+            format!("waiting on {count} worker(s)")
+        }
+        StatusUpdate::BranchStarted { branch_id } => format!("branch {branch_id} started"),
+        StatusUpdate::WorkerStarted { worker_id, task } => {
+            format!("worker {worker_id} started: {task}")
+        }
+        StatusUpdate::WorkerCompleted { worker_id, .. } => {
+            format!("worker {worker_id} finished")
+        }
+        StatusUpdate::StopTyping
+        | StatusUpdate::ToolCompleted { .. }
+        | StatusUpdate::Streaming
+        | StatusUpdate::Idle => return None,
+    };
+    Some(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> LineInput {
+        parse_line(line, &Arc::from("main"), "cli:main", "alice")
+    }
+
+    #[test]
+    fn test_line_becomes_inbound_message() {
+        let LineInput::Message(message) = parse("  what's on my calendar?  ") else {
+            panic!("expected a message");
+        };
+
+        assert_eq!(message.source, "cli");
+        assert_eq!(message.conversation_id, "cli:main");
+        assert_eq!(message.sender_id, "alice");
+        assert_eq!(message.agent_id.as_deref(), Some("main"));
+        assert!(
+            matches!(&message.content, MessageContent::Text(text) if text == "what's on my calendar?")
+        );
+        assert_eq!(
+            message.metadata.get("sender_display_name"),
+            Some(&serde_json::Value::from("alice"))
+        );
+    }
+
+    #[test]
+    fn test_blank_lines_and_exit_commands() {
+        assert!(matches!(parse("   "), LineInput::Skip));
+        assert!(matches!(parse("/exit"), LineInput::Exit));
+        assert!(matches!(parse(" /quit "), LineInput::Exit));
+        assert!(matches!(parse("/exitnow"), LineInput::Message(_)));
+    }
+
+    #[test]
+    fn test_text_responses_are_replies() {
+        assert_eq!(
+            terminal_output(OutboundResponse::Text("hello".into())),
+            [TerminalOutput::Reply("hello".into())]
+        );
+        assert_eq!(
+            terminal_output(OutboundResponse::Reaction("👍".into())),
+            [TerminalOutput::Status("reacted 👍".into())]
+        );
+        assert_eq!(
+            terminal_output(OutboundResponse::StreamChunk("par".into())),
+            [TerminalOutput::StreamChunk("par".into())]
+        );
+        assert!(terminal_output(OutboundResponse::RemoveReaction("👍".into())).is_empty());
+    }
+
+    #[test]
+    fn test_file_response_prints_caption_and_size() {
+        let output = terminal_output(OutboundResponse::File {
+            filename: "report.csv".into(),
+            data: vec![0; 42],
+            mime_type: "text/csv".into(),
+            caption: Some("Here it is".into()),
+            media: None,
+        });
+
+        assert_eq!(
+            output,
+            [
+                TerminalOutput::Reply("Here it is".into()),
+                TerminalOutput::Status("file: report.csv (42 bytes)".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_status_lines() {
+        assert_eq!(
+            status_line(StatusUpdate::Thinking).as_deref(),
+            Some("thinking")
+        );
+        assert_eq!(
+            status_line(StatusUpdate::WaitingOnWorker { count: 2 }).as_deref(),
+            Some("waiting on 2 worker(s)")
+        );
+        assert_eq!(status_line(StatusUpdate::StopTyping), None);
+        assert_eq!(
+            terminal_output(OutboundResponse::Status(StatusUpdate::Idle)),
+            []
+        );
+    }
+}
@@ -0,0 +1,598 @@
+//! Outbound delivery with retries and a persisted dead-letter queue.
+//!
+//! Adapter sends can fail transiently (a Discord 5xx, a dropped Slack
+//! socket). [`deliver`] retries with exponential backoff; a reply that still
+//! can't be sent is written to the agent's `outbound_dead_letters` table
+//! instead of being dropped. [`spawn_redelivery_loop`] re-sends dead letters
+//! once the adapter's health check passes again.
+//!
+//! Failures the platform won't accept on any attempt (a 4xx, a Slack or
+//! Telegram API error) skip the retries and are dead-lettered as parked:
+//! they stay in the table for an operator but are never redelivered.
+
+use crate::messaging::MessagingManager;
+use crate::{InboundMessage, OutboundResponse};
+
+use sqlx::{Row as _, SqlitePool};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Send attempts before a response is dead-lettered.
+pub const MAX_DELIVERY_ATTEMPTS: u32 = 4;
+
+/// Delay before the first retry. Doubles after each failed attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// How often the redelivery loop checks for dead letters.
+const REDELIVERY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Redelivery attempts after which a dead letter is left for an operator.
+const MAX_REDELIVERY_ATTEMPTS: i64 = 50;
+
+/// Dead letters re-sent per adapter per redelivery pass.
+const REDELIVERY_BATCH: i64 = 50;
+
+/// How a failed send should be handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// Timeouts, dropped connections, rate limits, server errors. Retried.
+    Transient,
+    /// The platform rejected the request: unknown target, missing
+    /// permission, malformed payload. Retrying won't help.
+    Permanent,
+}
+
+/// Classify an adapter error by the platform error it wraps. Errors that
+/// can't be recognized are treated as transient, so they keep being retried.
+pub fn classify_failure(error: &crate::Error) -> FailureKind {
+    let causes: Vec<&(dyn std::error::Error + 'static)> = match error {
+        crate::Error::Other(error) => error.chain().collect(),
+        crate::Error::Io(error) => vec![error],
+        _ => Vec::new(),
+    };
+
+    causes
+        .into_iter()
+        .find_map(classify_cause)
+        .unwrap_or(FailureKind::Transient)
+}
+
+fn classify_cause(cause: &(dyn std::error::Error + 'static)) -> Option<FailureKind> {
+    if let Some(error) = cause.downcast_ref::<reqwest::Error>() {
+        return Some(error.status().map_or(FailureKind::Transient, |status| {
+            classify_status(status.as_u16())
+        }));
+    }
+
+    if let Some(serenity::Error::Http(serenity::http::HttpError::UnsuccessfulRequest(response))) =
+        cause.downcast_ref::<serenity::Error>()
+    {
+        return Some(classify_status(response.status_code.as_u16()));
+    }
+
+    if let Some(error) = cause.downcast_ref::<teloxide::RequestError>() {
+        return Some(match error {
+            teloxide::RequestError::Api(_) | teloxide::RequestError::MigrateToChatId(_) => {
+                FailureKind::Permanent
+            }
+            _ => FailureKind::Transient,
+        });
+    }
+
+    if let Some(error) = cause.downcast_ref::<slack_morphism::errors::SlackClientError>() {
+        use slack_morphism::errors::SlackClientError;
+        return Some(match error {
+            SlackClientError::ApiError(error)
+                if !matches!(
+                    error.code.as_str(),
+                    "internal_error" | "fatal_error" | "service_unavailable" | "request_timeout"
+                ) =>
+            {
+                FailureKind::Permanent
+            }
+            SlackClientError::HttpError(error) => classify_status(error.status_code.as_u16()),
+            _ => FailureKind::Transient,
+        });
+    }
+
+    if cause.is::<std::io::Error>() {
+        return Some(FailureKind::Transient);
+    }
+
+    None
+}
+
+/// 4xx responses are permanent, except timeouts and rate limits.
+fn classify_status(status: u16) -> FailureKind {
+    match status {
+        408 | 425 | 429 => FailureKind::Transient,
+        400..=499 => FailureKind::Permanent,
+        _ => FailureKind::Transient,
+    }
+}
+
+/// Delay before retrying after failed attempt number `attempt` (1-based).
+fn retry_delay(initial: Duration, attempt: u32) -> Duration {
+    initial * 2u32.saturating_pow(attempt - 1)
+}
+
+/// Whether a response carries content the user would miss if it were lost.
+///
+/// Only these are retried and dead-lettered. Status updates, stream frames,
+/// reactions, and edits are stale by the time a retry would land.
+pub fn is_durable(response: &OutboundResponse) -> bool {
+    matches!(
+        response,
+        OutboundResponse::Text(_)
+            | OutboundResponse::Reply { .. }
+            | OutboundResponse::ThreadReply { .. }
+            | OutboundResponse::RichMessage { .. }
+            | OutboundResponse::File { .. }
+            | OutboundResponse::Ephemeral { .. }
+            | OutboundResponse::ScheduledMessage { .. }
+            | OutboundResponse::Structured(_)
+    )
+}
+
+/// A response that failed delivery.
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    pub id: i64,
+    pub message: InboundMessage,
+    pub response: OutboundResponse,
+    pub attempts: i64,
+}
+
+/// Persists outbound responses that exhausted their delivery retries.
+#[derive(Debug, Clone)]
+pub struct DeadLetterStore {
+    pool: SqlitePool,
+}
+
+impl DeadLetterStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a response that couldn't be delivered to `message`'s adapter.
+    /// Permanent failures are parked: kept for an operator, never redelivered.
+    pub async fn push(
+        &self,
+        message: &InboundMessage,
+        response: &OutboundResponse,
+        error: &str,
+        attempts: u32,
+        kind: FailureKind,
+    ) -> crate::Result<()> {
+        let message_json = serde_json::to_string(message).map_err(|e| anyhow::anyhow!(e))?;
+        let response_json = serde_json::to_string(response).map_err(|e| anyhow::anyhow!(e))?;
+
+        sqlx::query(
+            "INSERT INTO outbound_dead_letters \
+             (adapter, conversation_id, message, response, last_error, attempts) \
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&message.source)
+        .bind(&message.conversation_id)
+        .bind(&message_json)
+        .bind(&response_json)
+        .bind(error)
+        .bind(match kind {
+            FailureKind::Transient => attempts as i64,
+            FailureKind::Permanent => MAX_REDELIVERY_ATTEMPTS,
+        })
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(())
+    }
+
+    /// Adapters that have dead letters still eligible for redelivery.
+    pub async fn pending_adapters(&self) -> crate::Result<Vec<String>> {
+        let rows =
+            sqlx::query("SELECT DISTINCT adapter FROM outbound_dead_letters WHERE attempts < ?")
+                .bind(MAX_REDELIVERY_ATTEMPTS)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| row.try_get("adapter").ok())
+            .collect())
+    }
+
+    /// Oldest dead letters for `adapter`, in the order they failed.
+    pub async fn pending(&self, adapter: &str, limit: i64) -> crate::Result<Vec<DeadLetter>> {
+        let rows = sqlx::query(
+            "SELECT id, message, response, attempts FROM outbound_dead_letters \
+             WHERE adapter = ? AND attempts < ? \
+             ORDER BY id ASC \
+             LIMIT ?",
+        )
+        .bind(adapter)
+        .bind(MAX_REDELIVERY_ATTEMPTS)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        let mut letters = Vec::with_capacity(rows.len());
+        for row in rows {
+            let id: i64 = row.try_get("id").map_err(|e| anyhow::anyhow!(e))?;
+            let message: String = row.try_get("message").map_err(|e| anyhow::anyhow!(e))?;
+            let response: String = row.try_get("response").map_err(|e| anyhow::anyhow!(e))?;
+            match (
+                serde_json::from_str(&message),
+                serde_json::from_str(&response),
+            ) {
+                (Ok(message), Ok(response)) => letters.push(DeadLetter {
+                    id,
+                    message,
+                    response,
+                    attempts: row.try_get("attempts").unwrap_or_default(),
+                }),
+                _ => {
+                    tracing::warn!(dead_letter_id = id, "dropping unreadable dead letter");
+                    self.remove(id).await?;
+                }
+            }
+        }
+
+        Ok(letters)
+    }
+
+    /// Delete a dead letter after it has been delivered.
+    pub async fn remove(&self, id: i64) -> crate::Result<()> {
+        sqlx::query("DELETE FROM outbound_dead_letters WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(())
+    }
+
+    /// Record a failed redelivery attempt. A permanent failure parks the
+    /// dead letter.
+    pub async fn record_failure(
+        &self,
+        id: i64,
+        error: &str,
+        kind: FailureKind,
+    ) -> crate::Result<()> {
+        sqlx::query(
+            "UPDATE outbound_dead_letters \
+             SET attempts = CASE WHEN ? THEN MAX(attempts + 1, ?) ELSE attempts + 1 END, \
+                 last_error = ?, last_attempt_at = CURRENT_TIMESTAMP \
+             WHERE id = ?",
+        )
+        .bind(kind == FailureKind::Permanent)
+        .bind(MAX_REDELIVERY_ATTEMPTS)
+        .bind(error)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(())
+    }
+}
+
+/// Send a response through its adapter, retrying durable responses with
+/// exponential backoff and dead-lettering them if every attempt fails.
+///
+/// Non-durable responses (see [`is_durable`]) are sent once. The caller's
+/// outbound task awaits this, so later responses for the conversation queue
+/// behind a retry and keep their order.
+pub async fn deliver(
+    manager: &MessagingManager,
+    dead_letters: &DeadLetterStore,
+    message: &InboundMessage,
+    response: OutboundResponse,
+) -> crate::Result<()> {
+    deliver_with_backoff(manager, dead_letters, message, response, INITIAL_BACKOFF).await
+}
+
+async fn deliver_with_backoff(
+    manager: &MessagingManager,
+    dead_letters: &DeadLetterStore,
+    message: &InboundMessage,
+    response: OutboundResponse,
+    initial_backoff: Duration,
+) -> crate::Result<()> {
+    if !is_durable(&response) {
+        return manager.respond(message, response).await;
+    }
+
+    let mut attempt = 1;
+    loop {
+        let error = match manager.respond(message, response.clone()).await {
+            Ok(()) => return Ok(()),
+            Err(error) => error,
+        };
+
+        let kind = classify_failure(&error);
+        if kind == FailureKind::Permanent || attempt >= MAX_DELIVERY_ATTEMPTS {
+            tracing::error!(
+                %error,
+                adapter = %message.source,
+                conversation_id = %message.conversation_id,
+                attempts = attempt,
+                ?kind,
+                "outbound delivery failed, moving response to dead-letter queue"
+            );
+            if let Err(store_error) = dead_letters
+                .push(message, &response, &error.to_string(), attempt, kind)
+                .await
+            {
+                tracing::error!(%store_error, "failed to persist dead letter, response lost");
+            }
+            return Err(error);
+        }
+
+        let backoff = retry_delay(initial_backoff, attempt);
+        tracing::warn!(
+            %error,
+            adapter = %message.source,
+            attempt,
+            retry_in_ms = backoff.as_millis() as u64,
+            "outbound delivery failed, retrying"
+        );
+        tokio::time::sleep(backoff).await;
+        attempt += 1;
+    }
+}
+
+/// Periodically re-send dead letters for adapters that are healthy again.
+///
+/// Letters for an adapter are replayed oldest first. The first transient
+/// failure stops that adapter's pass so later replies don't overtake earlier
+/// ones; a permanent failure parks that letter and the pass moves on.
+pub fn spawn_redelivery_loop(
+    manager: Arc<MessagingManager>,
+    dead_letters: DeadLetterStore,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REDELIVERY_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(error) = redeliver(&manager, &dead_letters).await {
+                tracing::warn!(%error, "dead-letter redelivery pass failed");
+            }
+        }
+    })
+}
+
+async fn redeliver(
+    manager: &MessagingManager,
+    dead_letters: &DeadLetterStore,
+) -> crate::Result<()> {
+    for adapter in dead_letters.pending_adapters().await? {
+        if let Err(error) = manager.health_check(&adapter).await {
+            tracing::debug!(%error, %adapter, "adapter still unhealthy, keeping dead letters");
+            continue;
+        }
+
+        let mut delivered = 0;
+        for letter in dead_letters.pending(&adapter, REDELIVERY_BATCH).await? {
+            match manager.respond(&letter.message, letter.response).await {
+                Ok(()) => {
+                    dead_letters.remove(letter.id).await?;
+                    delivered += 1;
+                }
+                Err(error) => {
+                    let kind = classify_failure(&error);
+                    tracing::warn!(
+                        %error,
+                        %adapter,
+                        dead_letter_id = letter.id,
+                        attempts = letter.attempts + 1,
+                        ?kind,
+                        "dead-letter redelivery failed"
+                    );
+                    dead_letters
+                        .record_failure(letter.id, &error.to_string(), kind)
+                        .await?;
+                    if kind == FailureKind::Transient {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if delivered > 0 {
+            tracing::info!(%adapter, delivered, "redelivered dead-lettered responses");
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OutboundResponse;
+    use crate::messaging::traits::{InboundStream, Messaging};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Adapter whose sends always fail with the error `fail` builds.
+    struct FailingAdapter {
+        sends: Arc<AtomicU32>,
+        fail: fn() -> anyhow::Error,
+    }
+
+    impl Messaging for FailingAdapter {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        async fn start(&self) -> crate::Result<InboundStream> {
+            Ok(Box::pin(futures::stream::empty()))
+        }
+
+        async fn respond(
+            &self,
+            _message: &InboundMessage,
+            _response: OutboundResponse,
+        ) -> crate::Result<()> {
+            self.sends.fetch_add(1, Ordering::Relaxed);
+            Err((self.fail)().into())
+        }
+
+        async fn health_check(&self) -> crate::Result<()> {
+            Ok(())
+        }
+    }
+
+    async fn setup(
+        fail: fn() -> anyhow::Error,
+    ) -> (MessagingManager, DeadLetterStore, Arc<AtomicU32>) {
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .in_memory(true)
+            .create_if_missing(true);
+        let pool = sqlx::pool::PoolOptions::<sqlx::Sqlite>::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("in-memory SQLite");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+
+        let sends = Arc::new(AtomicU32::new(0));
+        let manager = MessagingManager::new();
+        manager
+            .register(FailingAdapter {
+                sends: sends.clone(),
+                fail,
+            })
+            .await;
+        (manager, DeadLetterStore::new(pool), sends)
+    }
+
+    fn message() -> InboundMessage {
+        crate::testing::inbound_message("test", "user", "hello")
+    }
+
+    async fn dead_letter_attempts(store: &DeadLetterStore) -> Vec<i64> {
+        sqlx::query("SELECT attempts FROM outbound_dead_letters ORDER BY id")
+            .fetch_all(&store.pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.get("attempts"))
+            .collect()
+    }
+
+    #[test]
+    fn test_retry_delay_doubles() {
+        let initial = Duration::from_millis(500);
+        let delays: Vec<Duration> = (1..MAX_DELIVERY_ATTEMPTS)
+            .map(|attempt| retry_delay(initial, attempt))
+            .collect();
+        assert_eq!(
+            delays,
+            [500, 1000, 2000].map(Duration::from_millis).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_classify_status() {
+        assert_eq!(classify_status(403), FailureKind::Permanent);
+        assert_eq!(classify_status(404), FailureKind::Permanent);
+        assert_eq!(classify_status(429), FailureKind::Transient);
+        assert_eq!(classify_status(408), FailureKind::Transient);
+        assert_eq!(classify_status(502), FailureKind::Transient);
+    }
+
+    #[test]
+    fn test_classify_failure() {
+        let api_error = anyhow::Error::new(teloxide::RequestError::Api(
+            teloxide::ApiError::ChatNotFound,
+        ))
+        .context("failed to send telegram message");
+        assert_eq!(classify_failure(&api_error.into()), FailureKind::Permanent);
+
+        let retry = anyhow::Error::new(teloxide::RequestError::RetryAfter(
+            teloxide::types::Seconds::from_seconds(5),
+        ));
+        assert_eq!(classify_failure(&retry.into()), FailureKind::Transient);
+
+        let io = std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset");
+        assert_eq!(
+            classify_failure(&anyhow::Error::new(io).into()),
+            FailureKind::Transient
+        );
+
+        // Unrecognized errors keep being retried.
+        assert_eq!(
+            classify_failure(&anyhow::anyhow!("something went wrong").into()),
+            FailureKind::Transient
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transient_failures_retry_then_dead_letter() {
+        let (manager, dead_letters, sends) = setup(|| anyhow::anyhow!("connection dropped")).await;
+
+        let result = deliver_with_backoff(
+            &manager,
+            &dead_letters,
+            &message(),
+            OutboundResponse::Text("hi".into()),
+            Duration::from_millis(1),
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(sends.load(Ordering::Relaxed), MAX_DELIVERY_ATTEMPTS);
+        assert_eq!(
+            dead_letter_attempts(&dead_letters).await,
+            [MAX_DELIVERY_ATTEMPTS as i64]
+        );
+        assert_eq!(dead_letters.pending("test", 10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_permanent_failure_is_parked_without_retrying() {
+        let (manager, dead_letters, sends) = setup(|| {
+            anyhow::Error::new(teloxide::RequestError::Api(teloxide::ApiError::BotBlocked))
+        })
+        .await;
+
+        let result = deliver_with_backoff(
+            &manager,
+            &dead_letters,
+            &message(),
+            OutboundResponse::Text("hi".into()),
+            Duration::from_millis(1),
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(sends.load(Ordering::Relaxed), 1);
+        assert_eq!(dead_letter_attempts(&dead_letters).await.len(), 1);
+        assert!(dead_letters.pending("test", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_non_durable_responses_are_not_retried() {
+        let (manager, dead_letters, sends) = setup(|| anyhow::anyhow!("connection dropped")).await;
+
+        let result = deliver_with_backoff(
+            &manager,
+            &dead_letters,
+            &message(),
+            OutboundResponse::Status(crate::StatusUpdate::Thinking),
+            Duration::from_millis(1),
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(sends.load(Ordering::Relaxed), 1);
+        assert!(dead_letter_attempts(&dead_letters).await.is_empty());
+    }
+}
@@ -1,6 +1,9 @@
 //! Discord messaging adapter using serenity.
 
+use crate::agent::feedback;
 use crate::config::DiscordPermissions;
+use crate::conversation::DraftDecision;
+use crate::messaging::chunking::split_message;
 use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging};
 use crate::{InboundMessage, MessageContent, OutboundResponse, StatusUpdate};
 
@@ -11,8 +14,9 @@ use serenity::all::{
     ButtonStyle, ChannelId, ChannelType, Context, CreateActionRow, CreateAttachment, CreateButton,
     CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage,
     CreateMessage, CreatePoll, CreatePollAnswer, CreateSelectMenu, CreateSelectMenuKind,
-    CreateSelectMenuOption, CreateThread, EditMessage, EventHandler, GatewayIntents, GetMessages,
-    Http, Interaction, Message, MessageId, ReactionType, Ready, ShardManager, User, UserId,
+    CreateSelectMenuOption, CreateThread, EditMessage, EditThread, EventHandler, GatewayIntents,
+    GetMessages, Http, Interaction, Message, MessageId, Reaction, ReactionType, Ready,
+    ShardManager, User, UserId,
 };
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -26,6 +30,8 @@ pub struct DiscordAdapter {
     bot_user_id: Arc<RwLock<Option<UserId>>>,
     /// Maps InboundMessage.id to the Discord MessageId being edited during streaming.
     active_messages: Arc<RwLock<HashMap<String, serenity::all::MessageId>>>,
+    /// Maps conversation_id to the bot's most recent message, for edits and deletes.
+    sent_messages: Arc<RwLock<HashMap<String, MessageId>>>,
     /// Typing handles per message. Typing stops when the handle is dropped.
     typing_tasks: Arc<RwLock<HashMap<String, serenity::http::Typing>>>,
     shard_manager: Arc<RwLock<Option<Arc<ShardManager>>>>,
@@ -39,6 +45,7 @@ impl DiscordAdapter {
             http: Arc::new(RwLock::new(None)),
             bot_user_id: Arc::new(RwLock::new(None)),
             active_messages: Arc::new(RwLock::new(HashMap::new())),
+            sent_messages: Arc::new(RwLock::new(HashMap::new())),
             typing_tasks: Arc::new(RwLock::new(HashMap::new())),
             shard_manager: Arc::new(RwLock::new(None)),
         }
@@ -85,6 +92,37 @@ impl DiscordAdapter {
             .and_then(|value| value.as_u64())
             .map(MessageId::new)
     }
+
+    async fn remember_sent(&self, message: &InboundMessage, sent_id: MessageId) {
+        self.sent_messages
+            .write()
+            .await
+            .insert(message.conversation_id.clone(), sent_id);
+    }
+
+    /// Resolve the target of an edit or delete: an explicit message ID, or
+    /// the bot's most recent message in this conversation.
+    async fn resolve_sent_message(
+        &self,
+        message: &InboundMessage,
+        message_id: Option<String>,
+    ) -> anyhow::Result<MessageId> {
+        match message_id {
+            Some(id) => id
+                .parse::<u64>()
+                .ok()
+                .filter(|value| *value != 0)
+                .map(MessageId::new)
+                .with_context(|| format!("invalid discord message id '{id}'")),
+            None => self
+                .sent_messages
+                .read()
+                .await
+                .get(&message.conversation_id)
+                .copied()
+                .context("no message has been sent in this conversation yet"),
+        }
+    }
 }
 
 impl Messaging for DiscordAdapter {
@@ -105,6 +143,8 @@ impl Messaging for DiscordAdapter {
         let intents = GatewayIntents::GUILD_MESSAGES
             | GatewayIntents::DIRECT_MESSAGES
             | GatewayIntents::MESSAGE_CONTENT
+            | GatewayIntents::GUILD_MESSAGE_REACTIONS
+            | GatewayIntents::DIRECT_MESSAGE_REACTIONS
             | GatewayIntents::GUILDS;
 
         let mut client = serenity::Client::builder(&self.token, intents)
@@ -145,10 +185,33 @@ impl Messaging for DiscordAdapter {
                     {
                         builder = builder.reference_message((channel_id, reply_message_id));
                     }
-                    channel_id
+                    let sent = channel_id
                         .send_message(&*http, builder)
                         .await
                         .context("failed to send discord message")?;
+                    self.remember_sent(message, sent.id).await;
+                }
+            }
+            OutboundResponse::Reply { in_reply_to, text } => {
+                self.stop_typing(message).await;
+                let reply_to = in_reply_to
+                    .parse::<u64>()
+                    .ok()
+                    .map(MessageId::new)
+                    .or_else(|| Self::extract_reply_message_id(message));
+
+                for (index, chunk) in split_message(&text, 2000).into_iter().enumerate() {
+                    let mut builder = CreateMessage::new().content(chunk);
+                    if index == 0
+                        && let Some(reply_message_id) = reply_to
+                    {
+                        builder = builder.reference_message((channel_id, reply_message_id));
+                    }
+                    let sent = channel_id
+                        .send_message(&*http, builder)
+                        .await
+                        .context("failed to send discord reply")?;
+                    self.remember_sent(message, sent.id).await;
                 }
             }
             OutboundResponse::RichMessage {
@@ -196,10 +259,11 @@ impl Messaging for DiscordAdapter {
                         msg = msg.reference_message((channel_id, reply_message_id));
                     }
 
-                    channel_id
+                    let sent = channel_id
                         .send_message(&*http, msg)
                         .await
                         .context("failed to send discord rich message")?;
+                    self.remember_sent(message, sent.id).await;
                 }
             }
             OutboundResponse::ThreadReply { thread_name, text } => {
@@ -260,6 +324,7 @@ impl Messaging for DiscordAdapter {
                 data,
                 mime_type: _,
                 caption,
+                media: _,
             } => {
                 self.stop_typing(message).await;
                 let reply_to = Self::extract_reply_message_id(message);
@@ -273,10 +338,11 @@ impl Messaging for DiscordAdapter {
                     builder = builder.reference_message((channel_id, reply_message_id));
                 }
 
-                channel_id
+                let sent = channel_id
                     .send_message(&*http, builder)
                     .await
                     .context("failed to send file attachment")?;
+                self.remember_sent(message, sent.id).await;
             }
             OutboundResponse::Reaction(emoji) => {
                 let message_id = message
@@ -294,6 +360,31 @@ impl Messaging for DiscordAdapter {
                     .await
                     .context("failed to add reaction")?;
             }
+            OutboundResponse::Edit { message_id, text } => {
+                let target = self.resolve_sent_message(message, message_id).await?;
+                let display_text = if text.len() > 2000 {
+                    let end = text.floor_char_boundary(1997);
+                    format!("{}...", &text[..end])
+                } else {
+                    text
+                };
+                channel_id
+                    .edit_message(&*http, target, EditMessage::new().content(display_text))
+                    .await
+                    .context("failed to edit discord message")?;
+            }
+            OutboundResponse::Delete { message_id } => {
+                let target = self.resolve_sent_message(message, message_id).await?;
+                channel_id
+                    .delete_message(&*http, target)
+                    .await
+                    .context("failed to delete discord message")?;
+
+                let mut sent_messages = self.sent_messages.write().await;
+                if sent_messages.get(&message.conversation_id) == Some(&target) {
+                    sent_messages.remove(&message.conversation_id);
+                }
+            }
             OutboundResponse::StreamStart => {
                 self.stop_typing(message).await;
 
@@ -328,6 +419,8 @@ impl Messaging for DiscordAdapter {
             OutboundResponse::Status(status) => {
                 self.send_status(message, status).await?;
             }
+            // Drafts wait for operator approval and are never posted
+            OutboundResponse::Draft { .. } => {}
             // Slack-specific variants — graceful fallbacks for Discord
             OutboundResponse::RemoveReaction(_) => {} // no-op
             OutboundResponse::Ephemeral { text, .. } => {
@@ -350,6 +443,11 @@ impl Messaging for DiscordAdapter {
                         .context("failed to send scheduled message fallback on discord")?;
                 }
             }
+            OutboundResponse::Structured(reply) => {
+                // Chat has no structured form; the message is the reply
+                return Box::pin(self.respond(message, OutboundResponse::Text(reply.message)))
+                    .await;
+            }
         }
 
         Ok(())
@@ -361,15 +459,17 @@ impl Messaging for DiscordAdapter {
         status: StatusUpdate,
     ) -> crate::Result<()> {
         match status {
-            StatusUpdate::Thinking => {
+            // Keep typing through tool calls; a running indicator is left as is.
+            StatusUpdate::Thinking | StatusUpdate::RunningTool { .. } => {
+                let key = Self::channel_key(message);
+                if self.typing_tasks.read().await.contains_key(&key) {
+                    return Ok(());
+                }
                 let http = self.get_http().await?;
                 let channel_id = self.extract_channel_id(message)?;
 
                 let typing = channel_id.start_typing(&http);
-                self.typing_tasks
-                    .write()
-                    .await
-                    .insert(Self::channel_key(message), typing);
+                self.typing_tasks.write().await.insert(key, typing);
             }
             _ => {
                 self.stop_typing(message).await;
@@ -532,6 +632,92 @@ impl Messaging for DiscordAdapter {
         Ok(history)
     }
 
+    async fn open_thread(
+        &self,
+        message: &InboundMessage,
+        name: &str,
+    ) -> crate::Result<Option<String>> {
+        // Threads only exist in guild channels and can't be nested.
+        let Some(guild_id) = message
+            .metadata
+            .get("discord_guild_id")
+            .and_then(|v| v.as_u64())
+        else {
+            return Ok(None);
+        };
+        if message
+            .metadata
+            .get("discord_is_thread")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            return Ok(None);
+        }
+
+        let http = self.get_http().await?;
+        let channel_id = self.extract_channel_id(message)?;
+
+        // Discord caps thread names at 100 characters.
+        let name: String = name.chars().take(100).collect();
+        let builder = CreateThread::new(name).kind(ChannelType::PublicThread);
+        let source_message_id = message
+            .metadata
+            .get("discord_message_id")
+            .and_then(|v| v.as_u64())
+            .map(MessageId::new);
+
+        let thread = match source_message_id {
+            Some(source_message_id) => {
+                channel_id
+                    .create_thread_from_message(&*http, source_message_id, builder)
+                    .await
+            }
+            None => channel_id.create_thread(&*http, builder).await,
+        }
+        .context("failed to create discord thread")?;
+
+        Ok(Some(format!("discord:{guild_id}:{}", thread.id)))
+    }
+
+    async fn rename_conversation(
+        &self,
+        message: &InboundMessage,
+        title: &str,
+    ) -> crate::Result<()> {
+        // Only threads the bot opened are renamed; channels and threads
+        // people started keep the names they were given.
+        if !message
+            .metadata
+            .get("discord_is_thread")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            return Ok(());
+        }
+        let Some(bot_user_id) = *self.bot_user_id.read().await else {
+            return Ok(());
+        };
+
+        let http = self.get_http().await?;
+        let channel_id = self.extract_channel_id(message)?;
+        let thread = channel_id
+            .to_channel(&*http)
+            .await
+            .context("failed to fetch discord thread")?
+            .guild();
+        if thread.and_then(|thread| thread.owner_id) != Some(bot_user_id) {
+            return Ok(());
+        }
+
+        // Discord caps thread names at 100 characters.
+        let name: String = title.chars().take(100).collect();
+        channel_id
+            .edit_thread(&*http, EditThread::new().name(name))
+            .await
+            .context("failed to rename discord thread")?;
+        Ok(())
+    }
+
     async fn health_check(&self) -> crate::Result<()> {
         let http = self.get_http().await?;
         http.get_current_user()
@@ -644,6 +830,144 @@ impl EventHandler for Handler {
         }
     }
 
+    /// Forward 👍/👎 on the bot's own messages as reaction feedback, and ✅/❌
+    /// on any message as a possible decision on a supervised reply draft.
+    async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
+        // Only these emoji mean anything; skip the API lookups for the rest.
+        let ReactionType::Unicode(emoji) = &reaction.emoji else {
+            return;
+        };
+        let is_feedback = feedback::Sentiment::from_emoji(emoji).is_some();
+        let is_draft_decision = DraftDecision::from_emoji(emoji).is_some();
+        if !is_feedback && !is_draft_decision {
+            return;
+        }
+
+        let Some(bot_user_id) = *self.bot_user_id_slot.read().await else {
+            return;
+        };
+        let Some(user_id) = reaction.user_id else {
+            return;
+        };
+        if user_id == bot_user_id {
+            return;
+        }
+
+        let permissions = self.permissions.load();
+
+        if reaction.guild_id.is_none()
+            && (permissions.dm_allowed_users.is_empty()
+                || !permissions.dm_allowed_users.contains(&user_id.get()))
+        {
+            return;
+        }
+
+        if let Some(filter) = &permissions.guild_filter
+            && let Some(guild_id) = reaction.guild_id
+            && !filter.contains(&guild_id.get())
+        {
+            return;
+        }
+
+        if let Some(guild_id) = reaction.guild_id
+            && let Some(allowed_channels) = permissions.channel_filter.get(&guild_id.get())
+            && !allowed_channels.is_empty()
+            && !allowed_channels.contains(&reaction.channel_id.get())
+        {
+            // Threads are allowed through their parent channel
+            let parent_channel_id = reaction
+                .channel_id
+                .to_channel(&ctx.http)
+                .await
+                .ok()
+                .and_then(|channel| channel.guild())
+                .and_then(|channel| channel.parent_id);
+            if !parent_channel_id.is_some_and(|id| allowed_channels.contains(&id.get())) {
+                return;
+            }
+        }
+
+        let message = match reaction.message(&ctx).await {
+            Ok(message) => message,
+            Err(error) => {
+                tracing::warn!(%error, "failed to fetch reacted Discord message");
+                return;
+            }
+        };
+        // Draft decisions target the user message the draft answers.
+        if message.author.id != bot_user_id && !is_draft_decision {
+            return;
+        }
+
+        let display_name = match &reaction.member {
+            Some(member) => member.nick.clone().unwrap_or_else(|| {
+                member
+                    .user
+                    .global_name
+                    .clone()
+                    .unwrap_or_else(|| member.user.name.clone())
+            }),
+            None => match reaction.user(&ctx).await {
+                Ok(user) => user.global_name.unwrap_or(user.name),
+                Err(_) => user_id.to_string(),
+            },
+        };
+        let formatted_author = format!("{} (<@{}>)", display_name, user_id);
+
+        let conversation_id = match reaction.guild_id {
+            Some(guild_id) => format!("discord:{}:{}", guild_id, reaction.channel_id),
+            None => format!("discord:dm:{}", user_id),
+        };
+
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            feedback::MESSAGE_KIND_KEY.into(),
+            feedback::REACTION_KIND.into(),
+        );
+        metadata.insert(feedback::REACTION_EMOJI_KEY.into(), emoji.clone().into());
+        metadata.insert(
+            feedback::REACTION_TARGET_TEXT_KEY.into(),
+            resolve_mentions(&message.content, &message.mentions).into(),
+        );
+        metadata.insert(
+            feedback::REACTION_TARGET_ID_KEY.into(),
+            reaction.message_id.to_string().into(),
+        );
+        metadata.insert(
+            "discord_channel_id".into(),
+            reaction.channel_id.get().into(),
+        );
+        metadata.insert(
+            "discord_message_id".into(),
+            reaction.message_id.get().into(),
+        );
+        if let Some(guild_id) = reaction.guild_id {
+            metadata.insert("discord_guild_id".into(), guild_id.get().into());
+        }
+        metadata.insert("sender_display_name".into(), display_name.into());
+        metadata.insert("sender_id".into(), user_id.get().into());
+        metadata.insert("discord_mentions_or_replies_to_bot".into(), true.into());
+
+        let inbound = InboundMessage {
+            id: format!("reaction:{}:{}:{}", reaction.message_id, user_id, emoji),
+            source: "discord".into(),
+            conversation_id,
+            sender_id: user_id.to_string(),
+            agent_id: None,
+            content: MessageContent::Text(emoji.clone()),
+            timestamp: chrono::Utc::now(),
+            metadata,
+            formatted_author: Some(formatted_author),
+        };
+
+        if let Err(error) = self.inbound_tx.send(inbound).await {
+            tracing::warn!(
+                %error,
+                "failed to send inbound reaction from Discord (receiver dropped)"
+            );
+        }
+    }
+
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
         let component = match interaction {
             Interaction::Component(c) => c,
@@ -849,6 +1173,15 @@ async fn build_metadata(
     };
     metadata.insert("sender_display_name".into(), display_name.clone().into());
     metadata.insert("sender_id".into(), message.author.id.get().into());
+    if let Some(member) = &message.member {
+        // Role IDs as strings, for mapping senders to authorization tiers.
+        let role_ids: Vec<serde_json::Value> = member
+            .roles
+            .iter()
+            .map(|role_id| role_id.get().to_string().into())
+            .collect();
+        metadata.insert("discord_role_ids".into(), role_ids.into());
+    }
     metadata.insert(
         "discord_user_mention".into(),
         serde_json::Value::String(format!("<@{}>", message.author.id)),
@@ -916,42 +1249,6 @@ async fn build_metadata(
     (metadata, formatted_author)
 }
 
-/// Split a message into chunks that fit within Discord's 2000 char limit.
-/// Tries to split at newlines, then spaces, then hard-cuts.
-fn split_message(text: &str, max_len: usize) -> Vec<String> {
-    if text.len() <= max_len {
-        return vec![text.to_string()];
-    }
-
-    let mut chunks = Vec::new();
-    let mut remaining = text;
-
-    while !remaining.is_empty() {
-        if remaining.len() <= max_len {
-            chunks.push(remaining.to_string());
-            break;
-        }
-
-        let safe_max = {
-            let mut i = max_len.min(remaining.len());
-            while !remaining.is_char_boundary(i) {
-                i -= 1;
-            }
-            i
-        };
-
-        let split_at = remaining[..safe_max]
-            .rfind('\n')
-            .or_else(|| remaining[..safe_max].rfind(' '))
-            .unwrap_or(safe_max);
-
-        chunks.push(remaining[..split_at].to_string());
-        remaining = remaining[split_at..].trim_start();
-    }
-
-    chunks
-}
-
 // --- Rich Message Builders ---
 
 fn build_embed(card: &crate::Card) -> CreateEmbed {
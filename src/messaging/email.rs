@@ -0,0 +1,684 @@
+//! Email messaging adapter: polls an IMAP mailbox and replies over SMTP.
+//!
+//! Each email thread is a conversation. The thread is identified by the first
+//! Message-ID in the `References` header (falling back to `In-Reply-To`, then
+//! the message's own ID), so every reply in a thread lands in the same
+//! channel. Replies go out with `In-Reply-To`/`References` set and the
+//! incoming message quoted below, so mail clients thread them correctly.
+
+use crate::config::EmailConfig;
+use crate::messaging::traits::{InboundStream, Messaging};
+use crate::{InboundMessage, MessageContent, OutboundResponse};
+
+use anyhow::Context as _;
+use futures::TryStreamExt as _;
+use lettre::message::header::{ContentType, HeaderName, HeaderValue};
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use mail_parser::{HeaderValue, MessageParser, MimeHeaders as _};
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{RwLock, mpsc};
+
+/// Messages fetched per poll, so a backlog doesn't flood the agent at once.
+const MAX_MESSAGES_PER_POLL: usize = 20;
+
+/// Email adapter state.
+pub struct EmailAdapter {
+    config: EmailConfig,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    shutdown_tx: Arc<RwLock<Option<mpsc::Sender<()>>>>,
+}
+
+impl EmailAdapter {
+    pub fn new(config: EmailConfig) -> anyhow::Result<Self> {
+        let builder = if config.smtp_port == 465 {
+            AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)
+        }
+        .with_context(|| format!("invalid smtp host: {}", config.smtp_host))?;
+
+        let transport = builder
+            .port(config.smtp_port)
+            .credentials(Credentials::new(
+                config.smtp_username.clone(),
+                config.smtp_password.clone(),
+            ))
+            .build();
+
+        if config.allowed_senders.is_empty() {
+            tracing::warn!(
+                "email adapter has no allowed_senders configured; all incoming mail will be ignored"
+            );
+        }
+
+        Ok(Self {
+            config,
+            transport,
+            shutdown_tx: Arc::new(RwLock::new(None)),
+        })
+    }
+
+    fn sender_mailbox(&self) -> anyhow::Result<Mailbox> {
+        let address = self
+            .config
+            .from_address
+            .parse()
+            .with_context(|| format!("invalid from_address: {}", self.config.from_address))?;
+        Ok(Mailbox::new(self.config.from_name.clone(), address))
+    }
+
+    /// Send a reply to an inbound email, threaded under it with the original
+    /// message quoted below.
+    async fn send_reply(
+        &self,
+        message: &InboundMessage,
+        text: &str,
+        attachment: Option<(String, Vec<u8>, String)>,
+    ) -> anyhow::Result<()> {
+        let get = |key: &str| message.metadata.get(key).and_then(|value| value.as_str());
+
+        let to = get("email_from").context("missing email_from in metadata")?;
+        let to: Mailbox = to
+            .parse()
+            .with_context(|| format!("invalid recipient address: {to}"))?;
+        let subject = reply_subject(get("email_subject").unwrap_or_default());
+
+        let mut body = text.to_string();
+        if let Some(quoted) = get("email_body") {
+            body.push_str("\n\n");
+            body.push_str(&quote_message(
+                get("email_date"),
+                message
+                    .formatted_author
+                    .as_deref()
+                    .unwrap_or(to.email.as_ref()),
+                quoted,
+            ));
+        }
+
+        let mut builder = lettre::Message::builder()
+            .from(self.sender_mailbox()?)
+            .to(to)
+            .subject(subject);
+        if let Some(message_id) = get("email_message_id") {
+            let mut references = get("email_references")
+                .map(str::to_string)
+                .unwrap_or_default();
+            if !references.is_empty() {
+                references.push(' ');
+            }
+            references.push_str(&format!("<{message_id}>"));
+            builder = builder
+                .in_reply_to(format!("<{message_id}>"))
+                .references(references);
+        }
+        // Names the turn that wrote the reply without showing it to the reader.
+        if let Some(turn_id) = get(crate::TURN_ID_METADATA_KEY) {
+            builder = builder.raw_header(HeaderValue::new(
+                HeaderName::new_from_ascii_str("X-Spacebot-Turn-Id"),
+                turn_id.to_string(),
+            ));
+        }
+
+        let email = build_body(builder, body, attachment)?;
+        self.transport
+            .send(email)
+            .await
+            .context("failed to send email")?;
+        Ok(())
+    }
+}
+
+impl Messaging for EmailAdapter {
+    fn name(&self) -> &str {
+        "email"
+    }
+
+    async fn start(&self) -> crate::Result<InboundStream> {
+        let (inbound_tx, inbound_rx) = mpsc::channel(256);
+        let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
+
+        *self.shutdown_tx.write().await = Some(shutdown_tx);
+
+        tracing::info!(
+            imap_host = %self.config.imap_host,
+            mailbox = %self.config.mailbox,
+            poll_interval_secs = self.config.poll_interval_secs,
+            "email polling started"
+        );
+
+        let config = self.config.clone();
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(config.poll_interval_secs.max(5)));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.recv() => {
+                        tracing::info!("email poll loop shutting down");
+                        break;
+                    }
+                    _ = interval.tick() => {
+                        let messages = match fetch_unseen(&config).await {
+                            Ok(messages) => messages,
+                            Err(error) => {
+                                tracing::warn!(%error, "email poll failed");
+                                continue;
+                            }
+                        };
+
+                        for raw in messages {
+                            let Some(parsed) = parse_email(&raw) else {
+                                tracing::debug!("skipping unparseable email");
+                                continue;
+                            };
+                            if parsed.from_address.eq_ignore_ascii_case(&config.from_address) {
+                                continue;
+                            }
+                            if parsed.auto_generated {
+                                tracing::debug!(from = %parsed.from_address, "skipping automated email");
+                                continue;
+                            }
+                            if !sender_allowed(&config.allowed_senders, &parsed.from_address) {
+                                tracing::debug!(from = %parsed.from_address, "ignoring email from sender not in allowed_senders");
+                                continue;
+                            }
+
+                            if let Err(error) = inbound_tx.send(parsed.into_inbound()).await {
+                                tracing::warn!(
+                                    %error,
+                                    "failed to send inbound message from email (receiver dropped)"
+                                );
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        let stream = tokio_stream::wrappers::ReceiverStream::new(inbound_rx);
+        Ok(Box::pin(stream))
+    }
+
+    async fn respond(
+        &self,
+        message: &InboundMessage,
+        response: OutboundResponse,
+    ) -> crate::Result<()> {
+        match response {
+            OutboundResponse::Text(text)
+            | OutboundResponse::RichMessage { text, .. }
+            | OutboundResponse::Reply { text, .. }
+            | OutboundResponse::ThreadReply { text, .. }
+            | OutboundResponse::Ephemeral { text, .. }
+            | OutboundResponse::ScheduledMessage { text, .. } => {
+                self.send_reply(message, &text, None).await?;
+            }
+            OutboundResponse::Structured(reply) => {
+                self.send_reply(message, &reply.message, None).await?;
+            }
+            OutboundResponse::File {
+                filename,
+                data,
+                mime_type,
+                caption,
+                ..
+            } => {
+                let text = caption.unwrap_or_else(|| format!("Attached: {filename}"));
+                self.send_reply(message, &text, Some((filename, data, mime_type)))
+                    .await?;
+            }
+            // Email can't be edited or streamed; the final text arrives as a
+            // regular response once the stream ends.
+            OutboundResponse::StreamStart
+            | OutboundResponse::StreamChunk(_)
+            | OutboundResponse::StreamEnd
+            | OutboundResponse::Reaction(_)
+            | OutboundResponse::RemoveReaction(_)
+            | OutboundResponse::Edit { .. }
+            | OutboundResponse::Delete { .. }
+            | OutboundResponse::Status(_)
+            | OutboundResponse::Draft { .. } => {}
+        }
+
+        Ok(())
+    }
+
+    async fn broadcast(&self, target: &str, response: OutboundResponse) -> crate::Result<()> {
+        let text = match response {
+            OutboundResponse::Text(text) | OutboundResponse::RichMessage { text, .. } => text,
+            _ => return Ok(()),
+        };
+
+        let to: Mailbox = target
+            .parse()
+            .with_context(|| format!("invalid email target: {target}"))?;
+        let subject = match &self.config.from_name {
+            Some(name) => format!("Message from {name}"),
+            None => "Message".to_string(),
+        };
+        let builder = lettre::Message::builder()
+            .from(self.sender_mailbox()?)
+            .to(to)
+            .subject(subject);
+        let email = build_body(builder, text, None)?;
+        self.transport
+            .send(email)
+            .await
+            .context("failed to send email")?;
+
+        Ok(())
+    }
+
+    async fn health_check(&self) -> crate::Result<()> {
+        let connected = self
+            .transport
+            .test_connection()
+            .await
+            .context("smtp connection test failed")?;
+        if !connected {
+            return Err(anyhow::anyhow!("smtp server not reachable").into());
+        }
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> crate::Result<()> {
+        if let Some(tx) = self.shutdown_tx.read().await.as_ref() {
+            tx.send(()).await.ok();
+        }
+        tracing::info!("email adapter shut down");
+        Ok(())
+    }
+}
+
+fn build_body(
+    builder: lettre::message::MessageBuilder,
+    body: String,
+    attachment: Option<(String, Vec<u8>, String)>,
+) -> anyhow::Result<lettre::Message> {
+    let email = match attachment {
+        None => builder.header(ContentType::TEXT_PLAIN).body(body),
+        Some((filename, data, mime_type)) => {
+            let content_type = ContentType::parse(&mime_type)
+                .or_else(|_| ContentType::parse("application/octet-stream"))
+                .context("invalid attachment content type")?;
+            builder.multipart(
+                MultiPart::mixed()
+                    .singlepart(SinglePart::plain(body))
+                    .singlepart(Attachment::new(filename).body(data, content_type)),
+            )
+        }
+    };
+    email.context("failed to build email")
+}
+
+/// Log in, fetch unseen messages in the configured mailbox, mark them seen,
+/// and log out. Returns the raw RFC 822 messages.
+async fn fetch_unseen(config: &EmailConfig) -> anyhow::Result<Vec<Vec<u8>>> {
+    let tcp = tokio::net::TcpStream::connect((config.imap_host.as_str(), config.imap_port))
+        .await
+        .with_context(|| format!("failed to connect to {}", config.imap_host))?;
+
+    let roots = rustls::RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let tls_config = rustls::ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let server_name = rustls::pki_types::ServerName::try_from(config.imap_host.clone())
+        .with_context(|| format!("invalid imap host: {}", config.imap_host))?;
+    let tls = tokio_rustls::TlsConnector::from(Arc::new(tls_config))
+        .connect(server_name, tcp)
+        .await
+        .context("imap tls handshake failed")?;
+
+    let client = async_imap::Client::new(tls);
+    let mut session = client
+        .login(&config.imap_username, &config.imap_password)
+        .await
+        .map_err(|(error, _)| anyhow::anyhow!("imap login failed: {error}"))?;
+
+    session
+        .select(&config.mailbox)
+        .await
+        .with_context(|| format!("failed to select mailbox {}", config.mailbox))?;
+
+    let mut uids = session
+        .uid_search("UNSEEN")
+        .await
+        .context("imap search failed")?
+        .into_iter()
+        .collect::<Vec<_>>();
+    uids.sort_unstable();
+    uids.truncate(MAX_MESSAGES_PER_POLL);
+
+    let mut messages = Vec::with_capacity(uids.len());
+    if !uids.is_empty() {
+        let uid_set = uids
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+
+        // BODY.PEEK doesn't set \Seen; it's set explicitly below once the
+        // messages have been read, so a failed fetch is retried next poll.
+        let fetches = session
+            .uid_fetch(&uid_set, "BODY.PEEK[]")
+            .await
+            .context("imap fetch failed")?
+            .try_collect::<Vec<_>>()
+            .await
+            .context("imap fetch failed")?;
+        for fetch in &fetches {
+            if let Some(body) = fetch.body() {
+                messages.push(body.to_vec());
+            }
+        }
+
+        session
+            .uid_store(&uid_set, "+FLAGS (\\Seen)")
+            .await
+            .context("failed to mark messages seen")?
+            .try_collect::<Vec<_>>()
+            .await
+            .context("failed to mark messages seen")?;
+    }
+
+    session.logout().await.ok();
+    Ok(messages)
+}
+
+/// The parts of an incoming email the adapter uses.
+#[derive(Debug)]
+struct ParsedEmail {
+    message_id: String,
+    thread_id: String,
+    /// `References` of the incoming message, in order, without brackets.
+    references: Vec<String>,
+    subject: String,
+    from_address: String,
+    from_name: Option<String>,
+    date: Option<chrono::DateTime<chrono::Utc>>,
+    /// Full plain-text body, including any quoted history.
+    body: String,
+    attachment_names: Vec<String>,
+    /// Auto-replies, bounces, and list mail, which must never be answered.
+    auto_generated: bool,
+}
+
+fn parse_email(raw: &[u8]) -> Option<ParsedEmail> {
+    let message = MessageParser::default().parse(raw)?;
+
+    let from = message.from()?.first()?;
+    let from_address = from.address()?.trim().to_lowercase();
+    let from_name = from
+        .name()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+
+    let message_id = message
+        .message_id()
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{}@spacebot.local", uuid::Uuid::new_v4()));
+    let references = header_ids(message.references());
+    let in_reply_to = header_ids(message.in_reply_to());
+    let thread_id = references
+        .first()
+        .or(in_reply_to.first())
+        .cloned()
+        .unwrap_or_else(|| message_id.clone());
+
+    let auto_submitted = message
+        .header_raw("Auto-Submitted")
+        .is_some_and(|value| !value.trim().eq_ignore_ascii_case("no"));
+    let bulk = message.header_raw("Precedence").is_some_and(|value| {
+        matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "bulk" | "list" | "junk"
+        )
+    });
+    let auto_generated = auto_submitted
+        || bulk
+        || message.header_raw("List-Id").is_some()
+        || from_address.starts_with("mailer-daemon@")
+        || from_address.starts_with("postmaster@");
+
+    Some(ParsedEmail {
+        message_id,
+        thread_id,
+        references,
+        subject: message.subject().unwrap_or_default().trim().to_string(),
+        from_address,
+        from_name,
+        date: message
+            .date()
+            .and_then(|date| chrono::DateTime::from_timestamp(date.to_timestamp(), 0)),
+        body: message
+            .body_text(0)
+            .map(|body| body.replace("\r\n", "\n"))
+            .unwrap_or_default(),
+        attachment_names: message
+            .attachments()
+            .filter_map(|part| part.attachment_name().map(str::to_string))
+            .collect(),
+        auto_generated,
+    })
+}
+
+impl ParsedEmail {
+    fn into_inbound(self) -> InboundMessage {
+        let mut text = strip_quoted_reply(&self.body);
+        if !self.attachment_names.is_empty() {
+            text.push_str(&format!(
+                "\n\n[Attachments not shown: {}]",
+                self.attachment_names.join(", ")
+            ));
+        }
+        if text.trim().is_empty() {
+            text = self.subject.clone();
+        }
+
+        let formatted_author = match &self.from_name {
+            Some(name) => format!("{name} <{}>", self.from_address),
+            None => self.from_address.clone(),
+        };
+
+        let mut metadata = HashMap::new();
+        let mut insert = |key: &str, value: String| {
+            metadata.insert(key.to_string(), serde_json::Value::String(value));
+        };
+        insert("email_message_id", self.message_id.clone());
+        insert("email_thread_id", self.thread_id.clone());
+        insert(
+            "email_references",
+            self.references
+                .iter()
+                .map(|id| format!("<{id}>"))
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+        insert("email_subject", self.subject);
+        insert("email_from", self.from_address.clone());
+        insert("email_body", self.body);
+        if let Some(date) = self.date {
+            insert("email_date", date.to_rfc2822());
+        }
+        insert(
+            "sender_display_name",
+            self.from_name.unwrap_or_else(|| self.from_address.clone()),
+        );
+
+        InboundMessage {
+            id: self.message_id,
+            source: "email".into(),
+            conversation_id: format!("email:{}", self.thread_id),
+            sender_id: self.from_address,
+            agent_id: None,
+            content: MessageContent::Text(text),
+            timestamp: self.date.unwrap_or_else(chrono::Utc::now),
+            metadata,
+            formatted_author: Some(formatted_author),
+        }
+    }
+}
+
+fn header_ids(value: &HeaderValue) -> Vec<String> {
+    match value {
+        HeaderValue::Text(id) => vec![id.to_string()],
+        HeaderValue::TextList(ids) => ids.iter().map(|id| id.to_string()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Whether a sender matches `allowed_senders`. Entries are full addresses or
+/// `@domain` suffixes; `*` allows everyone.
+fn sender_allowed(allowed_senders: &[String], address: &str) -> bool {
+    allowed_senders.iter().any(|allowed| {
+        let allowed = allowed.trim();
+        allowed == "*"
+            || allowed.eq_ignore_ascii_case(address)
+            || (allowed.starts_with('@')
+                && address
+                    .to_ascii_lowercase()
+                    .ends_with(&allowed.to_ascii_lowercase()))
+    })
+}
+
+/// Drop the quoted history from a reply: everything from the first
+/// "On ... wrote:" line or a run of `>` lines onwards. The history is already
+/// in the channel; the agent only needs what's new.
+fn strip_quoted_reply(body: &str) -> String {
+    let mut kept = Vec::new();
+    for line in body.lines() {
+        let trimmed = line.trim();
+        let starts_quote = trimmed.starts_with('>')
+            || (trimmed.starts_with("On ") && trimmed.ends_with("wrote:"))
+            || trimmed == "-----Original Message-----";
+        if starts_quote {
+            break;
+        }
+        kept.push(line);
+    }
+    // Signature separator.
+    if let Some(position) = kept.iter().position(|line| *line == "-- ") {
+        kept.truncate(position);
+    }
+    kept.join("\n").trim().to_string()
+}
+
+fn reply_subject(subject: &str) -> String {
+    let subject = subject.trim();
+    if subject.is_empty() {
+        "Re: (no subject)".to_string()
+    } else if subject.len() >= 3 && subject[..3].eq_ignore_ascii_case("re:") {
+        subject.to_string()
+    } else {
+        format!("Re: {subject}")
+    }
+}
+
+/// The incoming message as a quoted block for the bottom of a reply.
+fn quote_message(date: Option<&str>, author: &str, body: &str) -> String {
+    let header = match date {
+        Some(date) => format!("On {date}, {author} wrote:"),
+        None => format!("{author} wrote:"),
+    };
+    let quoted = body
+        .trim_end()
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                ">".to_string()
+            } else {
+                format!("> {line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("{header}\n{quoted}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPLY: &str = "From: Alice Example <Alice@Example.com>\r\n\
+To: bot@example.org\r\n\
+Subject: Re: Quarterly numbers\r\n\
+Message-ID: <reply-2@example.com>\r\n\
+In-Reply-To: <bot-1@example.org>\r\n\
+References: <root-0@example.com> <bot-1@example.org>\r\n\
+Date: Tue, 3 Mar 2026 10:00:00 +0000\r\n\
+\r\n\
+Thanks, can you break it down by region?\r\n\
+\r\n\
+On Mon, 2 Mar 2026, Bot wrote:\r\n\
+> Revenue was up 4%.\r\n";
+
+    #[test]
+    fn reply_joins_the_thread_of_its_root() {
+        let parsed = parse_email(REPLY.as_bytes()).unwrap();
+        assert_eq!(parsed.thread_id, "root-0@example.com");
+        assert_eq!(parsed.from_address, "alice@example.com");
+        assert!(!parsed.auto_generated);
+
+        let inbound = parsed.into_inbound();
+        assert_eq!(inbound.conversation_id, "email:root-0@example.com");
+        assert_eq!(
+            inbound.content.to_string(),
+            "Thanks, can you break it down by region?"
+        );
+        assert_eq!(
+            inbound.metadata["email_references"],
+            "<root-0@example.com> <bot-1@example.org>"
+        );
+        assert_eq!(
+            inbound.formatted_author.as_deref(),
+            Some("Alice Example <alice@example.com>")
+        );
+    }
+
+    #[test]
+    fn first_message_starts_its_own_thread() {
+        let raw = "From: bob@example.com\r\nSubject: Hello\r\nMessage-ID: <first@example.com>\r\n\r\nHi there\r\n";
+        let parsed = parse_email(raw.as_bytes()).unwrap();
+        assert_eq!(parsed.thread_id, "first@example.com");
+        assert!(parsed.references.is_empty());
+    }
+
+    #[test]
+    fn auto_replies_are_flagged() {
+        let raw = "From: alice@example.com\r\nAuto-Submitted: auto-replied\r\nSubject: Out of office\r\n\r\nAway\r\n";
+        assert!(parse_email(raw.as_bytes()).unwrap().auto_generated);
+    }
+
+    #[test]
+    fn sender_allow_list() {
+        let allowed = vec!["alice@example.com".to_string(), "@corp.example".to_string()];
+        assert!(sender_allowed(&allowed, "alice@example.com"));
+        assert!(sender_allowed(&allowed, "bob@corp.example"));
+        assert!(!sender_allowed(&allowed, "mallory@example.com"));
+        assert!(!sender_allowed(&[], "alice@example.com"));
+        assert!(sender_allowed(&["*".to_string()], "anyone@example.net"));
+    }
+
+    #[test]
+    fn reply_formatting() {
+        assert_eq!(reply_subject("Quarterly numbers"), "Re: Quarterly numbers");
+        assert_eq!(
+            reply_subject("RE: Quarterly numbers"),
+            "RE: Quarterly numbers"
+        );
+        assert_eq!(
+            quote_message(Some("Tue, 3 Mar 2026"), "Alice", "one\n\ntwo\n"),
+            "On Tue, 3 Mar 2026, Alice wrote:\n> one\n>\n> two"
+        );
+    }
+}
@@ -252,6 +252,43 @@ impl MessagingManager {
         adapter.fetch_history(message, limit).await
     }
 
+    /// Open a thread off a message on the adapter it came from. Returns the
+    /// thread's conversation ID, or `None` if the adapter has no threads.
+    pub async fn open_thread(
+        &self,
+        message: &InboundMessage,
+        name: &str,
+    ) -> crate::Result<Option<String>> {
+        let adapters = self.adapters.read().await;
+        let adapter = adapters
+            .get(&message.source)
+            .with_context(|| format!("no messaging adapter named '{}'", message.source))?;
+        adapter.open_thread(message, name).await
+    }
+
+    /// Rename the conversation a message came from, on the adapter that
+    /// received it.
+    pub async fn rename_conversation(
+        &self,
+        message: &InboundMessage,
+        title: &str,
+    ) -> crate::Result<()> {
+        let adapters = self.adapters.read().await;
+        let adapter = adapters
+            .get(&message.source)
+            .with_context(|| format!("no messaging adapter named '{}'", message.source))?;
+        adapter.rename_conversation(message, title).await
+    }
+
+    /// Run an adapter's health check. Errors if the adapter isn't registered.
+    pub async fn health_check(&self, adapter_name: &str) -> crate::Result<()> {
+        let adapters = self.adapters.read().await;
+        let adapter = adapters
+            .get(adapter_name)
+            .with_context(|| format!("no messaging adapter named '{adapter_name}'"))?;
+        adapter.health_check().await
+    }
+
     /// Remove and shut down a single adapter by name.
     pub async fn remove_adapter(&self, name: &str) -> crate::Result<()> {
         let adapter = self.adapters.write().await.remove(name);
@@ -18,10 +18,12 @@
 //! - Block Kit rich messages with plain-text fallback
 //! - Scheduled messages (`chat.scheduleMessage`)
 //! - Streaming via `chat.update` edits
+//! - Editing and deleting the bot's own messages (`chat.update` / `chat.delete`)
 //! - Typing indicator via `assistant.threads.setStatus`
 //! - DM broadcast via `conversations.open`
 
 use crate::config::{SlackCommandConfig, SlackPermissions};
+use crate::messaging::chunking::split_message;
 use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging};
 use crate::{InboundMessage, MessageContent, OutboundResponse, StatusUpdate};
 
@@ -66,6 +68,8 @@ pub struct SlackAdapter {
     token: SlackApiToken,
     /// Maps InboundMessage.id → Slack ts for streaming edits.
     active_messages: Arc<RwLock<HashMap<String, String>>>,
+    /// Maps conversation_id → ts of the bot's most recent message, for edits and deletes.
+    sent_messages: Arc<RwLock<HashMap<String, String>>>,
     shutdown_tx: Arc<RwLock<Option<mpsc::Sender<()>>>>,
     /// Slash command routing: command string → agent_id.
     commands: Arc<HashMap<String, String>>,
@@ -94,6 +98,7 @@ impl SlackAdapter {
             client,
             token,
             active_messages: Arc::new(RwLock::new(HashMap::new())),
+            sent_messages: Arc::new(RwLock::new(HashMap::new())),
             shutdown_tx: Arc::new(RwLock::new(None)),
             commands: Arc::new(commands_map),
         })
@@ -103,6 +108,33 @@ impl SlackAdapter {
     fn session(&self) -> SlackClientSession<'_, SlackClientHyperHttpsConnector> {
         self.client.open_session(&self.token)
     }
+
+    async fn remember_sent(&self, message: &InboundMessage, ts: SlackTs) {
+        self.sent_messages
+            .write()
+            .await
+            .insert(message.conversation_id.clone(), ts.0);
+    }
+
+    /// Resolve the target of an edit or delete: an explicit message ts, or
+    /// the bot's most recent message in this conversation.
+    async fn resolve_sent_message(
+        &self,
+        message: &InboundMessage,
+        message_id: Option<String>,
+    ) -> anyhow::Result<SlackTs> {
+        match message_id {
+            Some(ts) => Ok(SlackTs(ts)),
+            None => self
+                .sent_messages
+                .read()
+                .await
+                .get(&message.conversation_id)
+                .cloned()
+                .map(SlackTs)
+                .context("no message has been sent in this conversation yet"),
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -320,7 +352,7 @@ async fn handle_app_mention_event(
     let content = MessageContent::Text(text);
 
     let slack_uid = SlackUserId(user_id.clone());
-    let (metadata, formatted_author) = build_metadata_and_author(
+    let (mut metadata, formatted_author) = build_metadata_and_author(
         &team_id_str,
         &channel_id,
         &ts,
@@ -333,6 +365,7 @@ async fn handle_app_mention_event(
         &adapter_state.channel_name_cache,
     )
     .await;
+    metadata.insert("slack_mentions_bot".into(), true.into());
 
     send_inbound(
         &adapter_state.inbound_tx,
@@ -782,44 +815,23 @@ impl Messaging for SlackAdapter {
     /// support `setStatus`; this function no-ops for them rather than erroring. If you are
     /// not seeing typing indicators, verify the conversation is inside an Assistant thread.
     ///
-    /// Pass an empty string to clear the status (e.g. on `StopTyping`).
+    /// Pass an empty string to clear the status (e.g. on `StopTyping` or `Idle`).
     async fn send_status(
         &self,
         message: &InboundMessage,
         status: StatusUpdate,
     ) -> crate::Result<()> {
-        let thread_ts = match extract_thread_ts(message) {
-            Some(ts) => ts,
-            None => {
-                tracing::debug!(
-                    message_id = %message.id,
-                    "skipping assistant.threads.setStatus — message has no thread_ts \
-                     (typing indicators only work in Slack Assistant threads)"
-                );
-                return Ok(());
-            }
-        };
-        let channel_id = match extract_channel_id(message) {
-            Ok(id) => id,
-            Err(_) => return Ok(()),
-        };
-
-        let status_text = match &status {
-            StatusUpdate::Thinking => "Thinking…".to_string(),
-            StatusUpdate::StopTyping => String::new(), // empty string clears the status
-            StatusUpdate::ToolStarted { .. } => "Working…".to_string(),
-            StatusUpdate::ToolCompleted { .. } => "Working…".to_string(),
-            _ => "Working…".to_string(),
+        let Some(req) = thread_status_request(message, &status) else {
+            tracing::debug!(
+                message_id = %message.id,
+                "skipping assistant.threads.setStatus — message has no thread_ts \
+                 (typing indicators only work in Slack Assistant threads)"
+            );
+            return Ok(());
         };
 
         let session = self.session();
 
-        let req = SlackApiAssistantThreadsSetStatusRequest {
-            channel_id,
-            thread_ts,
-            status: status_text,
-        };
-
         // Best-effort — don't propagate status errors into the main response pipeline.
         if let Err(err) = session.assistant_threads_set_status(&req).await {
             tracing::debug!(error = %err, "failed to set slack assistant thread status (non-fatal)");
@@ -835,39 +847,53 @@ impl Messaging for SlackAdapter {
     ) -> crate::Result<()> {
         let session = self.session();
         let channel_id = extract_channel_id(message)?;
+        let turn_metadata = turn_metadata(message);
+        let thread_ts = response_thread_ts(message, &response);
 
         match response {
             OutboundResponse::Text(text) => {
-                let thread_ts = extract_thread_ts(message);
-
                 for chunk in split_message(&text, 12_000) {
                     let mut req = SlackApiChatPostMessageRequest::new(
                         channel_id.clone(),
-                        markdown_content(chunk),
+                        markdown_content(chunk).opt_metadata(turn_metadata.clone()),
                     );
                     req = req.opt_thread_ts(thread_ts.clone());
-                    session
+                    let sent = session
                         .chat_post_message(&req)
                         .await
                         .context("failed to send slack message")?;
+                    self.remember_sent(message, sent.ts).await;
+                }
+            }
+            OutboundResponse::Reply { text, .. } => {
+                for chunk in split_message(&text, 12_000) {
+                    let mut req = SlackApiChatPostMessageRequest::new(
+                        channel_id.clone(),
+                        markdown_content(chunk).opt_metadata(turn_metadata.clone()),
+                    );
+                    req = req.opt_thread_ts(thread_ts.clone());
+                    let sent = session
+                        .chat_post_message(&req)
+                        .await
+                        .context("failed to send slack reply")?;
+                    self.remember_sent(message, sent.ts).await;
                 }
             }
             OutboundResponse::ThreadReply {
                 thread_name: _,
                 text,
             } => {
-                let thread_ts = extract_thread_ts(message).or_else(|| extract_message_ts(message));
-
                 for chunk in split_message(&text, 12_000) {
                     let mut req = SlackApiChatPostMessageRequest::new(
                         channel_id.clone(),
-                        markdown_content(chunk),
+                        markdown_content(chunk).opt_metadata(turn_metadata.clone()),
                     );
                     req = req.opt_thread_ts(thread_ts.clone());
-                    session
+                    let sent = session
                         .chat_post_message(&req)
                         .await
                         .context("failed to send slack thread reply")?;
+                    self.remember_sent(message, sent.ts).await;
                 }
             }
 
@@ -876,6 +902,7 @@ impl Messaging for SlackAdapter {
                 data,
                 mime_type,
                 caption,
+                ..
             } => {
                 let upload_url_response = session
                     .get_upload_url_external(&SlackApiFilesGetUploadUrlExternalRequest::new(
@@ -894,14 +921,13 @@ impl Messaging for SlackAdapter {
                     .await
                     .context("failed to upload file to slack")?;
 
-                let thread_ts = extract_thread_ts(message);
                 let file_complete =
                     SlackApiFilesComplete::new(upload_url_response.file_id).with_title(filename);
                 let mut complete_request =
                     SlackApiFilesCompleteUploadExternalRequest::new(vec![file_complete])
                         .with_channel_id(channel_id.clone());
                 complete_request = complete_request.opt_initial_comment(caption);
-                complete_request = complete_request.opt_thread_ts(thread_ts);
+                complete_request = complete_request.opt_thread_ts(thread_ts.clone());
                 session
                     .files_complete_upload_external(&complete_request)
                     .await
@@ -938,13 +964,12 @@ impl Messaging for SlackAdapter {
             }
 
             OutboundResponse::Ephemeral { text, user_id } => {
-                let thread_ts = extract_thread_ts(message);
                 let req = SlackApiChatPostEphemeralRequest::new(
                     channel_id.clone(),
                     SlackUserId(user_id),
                     SlackMessageContent::new().with_text(text),
                 )
-                .opt_thread_ts(thread_ts);
+                .opt_thread_ts(thread_ts.clone());
                 session
                     .chat_post_ephemeral(&req)
                     .await
@@ -952,7 +977,6 @@ impl Messaging for SlackAdapter {
             }
 
             OutboundResponse::RichMessage { text, blocks, .. } => {
-                let thread_ts = extract_thread_ts(message);
                 let attempted = blocks.len();
                 let slack_blocks = deserialize_blocks(&blocks);
                 let dropped = attempted - slack_blocks.len();
@@ -980,15 +1004,48 @@ impl Messaging for SlackAdapter {
                         .with_blocks(slack_blocks)
                 };
                 let mut req = SlackApiChatPostMessageRequest::new(channel_id.clone(), content);
-                req = req.opt_thread_ts(thread_ts);
-                session
+                req = req.opt_thread_ts(thread_ts.clone());
+                let sent = session
                     .chat_post_message(&req)
                     .await
                     .context("failed to send slack rich message")?;
+                self.remember_sent(message, sent.ts).await;
+            }
+
+            OutboundResponse::Edit { message_id, text } => {
+                let ts = self.resolve_sent_message(message, message_id).await?;
+                let display_text = if text.len() > 12_000 {
+                    let end = text.floor_char_boundary(11_997);
+                    format!("{}...", &text[..end])
+                } else {
+                    text
+                };
+                let req = SlackApiChatUpdateRequest::new(
+                    channel_id.clone(),
+                    markdown_content(display_text),
+                    ts,
+                );
+                session
+                    .chat_update(&req)
+                    .await
+                    .context("failed to edit slack message")?;
+            }
+
+            OutboundResponse::Delete { message_id } => {
+                let ts = self.resolve_sent_message(message, message_id).await?;
+                let req = SlackApiChatDeleteRequest::new(channel_id.clone(), ts.clone());
+                session
+                    .chat_delete(&req)
+                    .await
+                    .context("failed to delete slack message")?;
+
+                let mut sent_messages = self.sent_messages.write().await;
+                if sent_messages.get(&message.conversation_id) == Some(&ts.0) {
+                    sent_messages.remove(&message.conversation_id);
+                }
             }
 
             OutboundResponse::ScheduledMessage { text, post_at } => {
-                let thread_ts = extract_thread_ts(message);
                 let post_at_dt = chrono::DateTime::<chrono::Utc>::from_timestamp(post_at, 0)
                     .context("invalid post_at unix timestamp for scheduled message")?;
                 let req = SlackApiChatScheduleMessageRequest::new(
@@ -996,7 +1053,7 @@ impl Messaging for SlackAdapter {
                     SlackMessageContent::new().with_text(text),
                     SlackDateTime(post_at_dt),
                 )
-                .opt_thread_ts(thread_ts);
+                .opt_thread_ts(thread_ts.clone());
                 session
                     .chat_schedule_message(&req)
                     .await
@@ -1045,6 +1102,16 @@ impl Messaging for SlackAdapter {
             OutboundResponse::Status(_) => {
                 // Status updates are handled via send_status(); ignored here.
             }
+
+            OutboundResponse::Draft { .. } => {
+                // Drafts wait for operator approval and are never posted.
+            }
+
+            OutboundResponse::Structured(reply) => {
+                // Chat has no structured form; the message is the reply
+                return Box::pin(self.respond(message, OutboundResponse::Text(reply.message)))
+                    .await;
+            }
         }
 
         Ok(())
@@ -1199,6 +1266,7 @@ impl Messaging for SlackAdapter {
 
     async fn shutdown(&self) -> crate::Result<()> {
         self.active_messages.write().await.clear();
+        self.sent_messages.write().await.clear();
         if let Some(tx) = self.shutdown_tx.write().await.take() {
             let _ = tx.send(()).await;
         }
@@ -1236,6 +1304,58 @@ fn extract_thread_ts(message: &InboundMessage) -> Option<SlackTs> {
         .map(|s| SlackTs(s.to_string()))
 }
 
+/// The thread a response is posted in, if any.
+///
+/// Slack threads don't nest: a message that's already in a thread is answered
+/// in that thread. Otherwise replies thread under the message they answer and
+/// thread replies under the triggering message; everything else goes to the
+/// channel.
+fn response_thread_ts(message: &InboundMessage, response: &OutboundResponse) -> Option<SlackTs> {
+    let thread_ts = extract_thread_ts(message);
+    match response {
+        OutboundResponse::Reply { in_reply_to, .. } => {
+            thread_ts.or_else(|| Some(SlackTs(in_reply_to.clone())))
+        }
+        OutboundResponse::ThreadReply { .. } => thread_ts.or_else(|| extract_message_ts(message)),
+        _ => thread_ts,
+    }
+}
+
+/// The `assistant.threads.setStatus` request showing `status` in the thread
+/// `message` belongs to, or `None` when it isn't in a thread.
+///
+/// The status text follows the message's language. `StopTyping` and `Idle`
+/// send an empty status, which clears it.
+fn thread_status_request(
+    message: &InboundMessage,
+    status: &StatusUpdate,
+) -> Option<SlackApiAssistantThreadsSetStatusRequest> {
+    let thread_ts = extract_thread_ts(message)?;
+    let channel_id = extract_channel_id(message).ok()?;
+
+    let language = message
+        .metadata
+        .get(crate::prompts::language::METADATA_KEY)
+        .and_then(|value| value.as_str());
+    let text = |key| match language {
+        Some(language) => crate::prompts::get_text_for(language, key),
+        None => crate::prompts::get_text(key),
+    };
+    let status = match status {
+        StatusUpdate::Thinking => text("status/thinking").to_string(),
+        StatusUpdate::Streaming => text("status/writing").to_string(),
+        StatusUpdate::WaitingOnWorker { .. } => text("status/waiting_on_workers").to_string(),
+        StatusUpdate::StopTyping | StatusUpdate::Idle => String::new(),
+        _ => text("status/working").to_string(),
+    };
+
+    Some(SlackApiAssistantThreadsSetStatusRequest {
+        channel_id,
+        thread_ts,
+        status,
+    })
+}
+
 /// Build a `SlackMessageContent` using a Markdown block with plain text fallback.
 ///
 /// The Markdown block supports standard markdown (bold, italic, lists, code,
@@ -1258,6 +1378,19 @@ fn markdown_content(text: impl Into<String>) -> SlackMessageContent {
     }
 }
 
+/// Message metadata naming the turn that produced a reply. Slack keeps it
+/// on the message, out of sight, and returns it from the history APIs.
+fn turn_metadata(message: &InboundMessage) -> Option<SlackMessageMetadata> {
+    let turn_id = message
+        .metadata
+        .get(crate::TURN_ID_METADATA_KEY)
+        .and_then(|value| value.as_str())?;
+    Some(SlackMessageMetadata {
+        event_type: "spacebot_turn".into(),
+        event_payload: Some(HashMap::from([("turn_id".into(), turn_id.into())])),
+    })
+}
+
 /// Extract `MessageContent` from an optional `SlackMessageContent`.
 fn extract_message_content(content: &Option<SlackMessageContent>) -> MessageContent {
     let Some(msg_content) = content else {
@@ -1483,53 +1616,25 @@ fn strip_bot_mention(text: &str, bot_user_id: &str) -> String {
 fn variant_name(response: &OutboundResponse) -> &'static str {
     match response {
         OutboundResponse::Text(_) => "Text",
+        OutboundResponse::Reply { .. } => "Reply",
         OutboundResponse::ThreadReply { .. } => "ThreadReply",
         OutboundResponse::File { .. } => "File",
         OutboundResponse::Reaction(_) => "Reaction",
         OutboundResponse::RemoveReaction(_) => "RemoveReaction",
+        OutboundResponse::Edit { .. } => "Edit",
+        OutboundResponse::Delete { .. } => "Delete",
         OutboundResponse::Ephemeral { .. } => "Ephemeral",
         OutboundResponse::RichMessage { .. } => "RichMessage",
         OutboundResponse::ScheduledMessage { .. } => "ScheduledMessage",
+        OutboundResponse::Structured(_) => "Structured",
         OutboundResponse::StreamStart => "StreamStart",
         OutboundResponse::StreamChunk(_) => "StreamChunk",
         OutboundResponse::StreamEnd => "StreamEnd",
         OutboundResponse::Status(_) => "Status",
+        OutboundResponse::Draft { .. } => "Draft",
     }
 }
 
-/// Split a message into UTF-8-safe chunks at line/word boundaries.
-fn split_message(text: &str, max_len: usize) -> Vec<String> {
-    if text.len() <= max_len {
-        return vec![text.to_string()];
-    }
-
-    let mut chunks = Vec::new();
-    let mut remaining = text;
-
-    while !remaining.is_empty() {
-        if remaining.len() <= max_len {
-            chunks.push(remaining.to_string());
-            break;
-        }
-
-        // Walk back to a valid char boundary before slicing
-        let mut safe_max = max_len.min(remaining.len());
-        while !remaining.is_char_boundary(safe_max) {
-            safe_max -= 1;
-        }
-
-        let split_at = remaining[..safe_max]
-            .rfind('\n')
-            .or_else(|| remaining[..safe_max].rfind(' '))
-            .unwrap_or(safe_max);
-
-        chunks.push(remaining[..split_at].to_string());
-        remaining = remaining[split_at..].trim_start();
-    }
-
-    chunks
-}
-
 /// Convert an emoji input to a Slack reaction short-code name.
 ///
 /// Handles three input forms:
@@ -1657,4 +1762,99 @@ mod tests {
         let result = sanitize_reaction_name(":partyparrot:");
         assert_eq!(result, "partyparrot");
     }
+
+    fn slack_message(thread_ts: Option<&str>) -> InboundMessage {
+        let mut message = crate::testing::inbound_message("slack", "U1", "hi");
+        message
+            .metadata
+            .insert("slack_channel_id".into(), "C1".into());
+        message
+            .metadata
+            .insert("slack_message_ts".into(), "111.000".into());
+        if let Some(thread_ts) = thread_ts {
+            message
+                .metadata
+                .insert("slack_thread_ts".into(), thread_ts.into());
+        }
+        message
+    }
+
+    fn thread_of(message: &InboundMessage, response: OutboundResponse) -> Option<String> {
+        response_thread_ts(message, &response).map(|ts| ts.0)
+    }
+
+    #[test]
+    fn responses_stay_in_the_incoming_thread() {
+        let message = slack_message(Some("100.000"));
+        let reply = OutboundResponse::Reply {
+            in_reply_to: "111.000".into(),
+            text: "hi".into(),
+        };
+        let thread_reply = OutboundResponse::ThreadReply {
+            thread_name: "topic".into(),
+            text: "hi".into(),
+        };
+
+        assert_eq!(
+            thread_of(&message, OutboundResponse::Text("hi".into())).as_deref(),
+            Some("100.000")
+        );
+        assert_eq!(thread_of(&message, reply).as_deref(), Some("100.000"));
+        assert_eq!(
+            thread_of(&message, thread_reply).as_deref(),
+            Some("100.000")
+        );
+    }
+
+    #[test]
+    fn replies_outside_a_thread_start_one() {
+        let message = slack_message(None);
+        let reply = OutboundResponse::Reply {
+            in_reply_to: "105.000".into(),
+            text: "hi".into(),
+        };
+        let thread_reply = OutboundResponse::ThreadReply {
+            thread_name: "topic".into(),
+            text: "hi".into(),
+        };
+
+        assert_eq!(
+            thread_of(&message, OutboundResponse::Text("hi".into())),
+            None
+        );
+        assert_eq!(thread_of(&message, reply).as_deref(), Some("105.000"));
+        assert_eq!(
+            thread_of(&message, thread_reply).as_deref(),
+            Some("111.000")
+        );
+    }
+
+    #[test]
+    fn thinking_sets_the_thread_status() {
+        let message = slack_message(Some("100.000"));
+        let request = thread_status_request(&message, &StatusUpdate::Thinking).unwrap();
+
+        assert_eq!(request.channel_id.0, "C1");
+        assert_eq!(request.thread_ts.0, "100.000");
+        assert_eq!(request.status, crate::prompts::get_text("status/thinking"));
+
+        let cleared = thread_status_request(&message, &StatusUpdate::StopTyping).unwrap();
+        assert!(cleared.status.is_empty());
+    }
+
+    #[test]
+    fn thread_status_follows_the_message_language() {
+        let mut message = slack_message(Some("100.000"));
+        message
+            .metadata
+            .insert(crate::prompts::language::METADATA_KEY.into(), "es".into());
+
+        let request = thread_status_request(&message, &StatusUpdate::Thinking).unwrap();
+        assert_eq!(request.status, "Pensando…");
+    }
+
+    #[test]
+    fn no_thread_status_outside_threads() {
+        assert!(thread_status_request(&slack_message(None), &StatusUpdate::Thinking).is_none());
+    }
 }
@@ -101,6 +101,11 @@ pub fn resolve_broadcast_target(channel: &ChannelInfo) -> Option<BroadcastTarget
                 }
             }
         }
+        "email" => channel
+            .platform_meta
+            .as_ref()
+            .and_then(|meta| meta.get("email_from"))
+            .and_then(json_value_to_string)?,
         _ => return None,
     };
 
@@ -1,6 +1,7 @@
 //! Telegram messaging adapter using teloxide.
 
 use crate::config::TelegramPermissions;
+use crate::messaging::chunking::{chunk_markdown, split_message};
 use crate::messaging::traits::{InboundStream, Messaging};
 use crate::{Attachment, InboundMessage, MessageContent, OutboundResponse, StatusUpdate};
 
@@ -32,6 +33,8 @@ pub struct TelegramAdapter {
     bot_username: Arc<RwLock<Option<String>>>,
     /// Maps conversation_id to the message_id being edited during streaming.
     active_messages: Arc<RwLock<HashMap<String, ActiveStream>>>,
+    /// Maps conversation_id to the bot's most recent message, for edits and deletes.
+    sent_messages: Arc<RwLock<HashMap<String, MessageId>>>,
     /// Repeating typing indicator tasks per conversation_id.
     typing_tasks: Arc<RwLock<HashMap<String, JoinHandle<()>>>>,
     /// Shutdown signal for the polling loop.
@@ -64,6 +67,7 @@ impl TelegramAdapter {
             bot_user_id: Arc::new(RwLock::new(None)),
             bot_username: Arc::new(RwLock::new(None)),
             active_messages: Arc::new(RwLock::new(HashMap::new())),
+            sent_messages: Arc::new(RwLock::new(HashMap::new())),
             typing_tasks: Arc::new(RwLock::new(HashMap::new())),
             shutdown_tx: Arc::new(RwLock::new(None)),
         }
@@ -88,6 +92,37 @@ impl TelegramAdapter {
         Ok(MessageId(id))
     }
 
+    async fn remember_sent(&self, message: &InboundMessage, sent_id: Option<MessageId>) {
+        if let Some(sent_id) = sent_id {
+            self.sent_messages
+                .write()
+                .await
+                .insert(message.conversation_id.clone(), sent_id);
+        }
+    }
+
+    /// Resolve the target of an edit or delete: an explicit message ID, or
+    /// the bot's most recent message in this conversation.
+    async fn resolve_sent_message(
+        &self,
+        message: &InboundMessage,
+        message_id: Option<String>,
+    ) -> anyhow::Result<MessageId> {
+        match message_id {
+            Some(id) => id
+                .parse::<i32>()
+                .map(MessageId)
+                .with_context(|| format!("invalid telegram message id '{id}'")),
+            None => self
+                .sent_messages
+                .read()
+                .await
+                .get(&message.conversation_id)
+                .copied()
+                .context("no message has been sent in this conversation yet"),
+        }
+    }
+
     async fn stop_typing(&self, conversation_id: &str) {
         if let Some(handle) = self.typing_tasks.write().await.remove(conversation_id) {
             handle.abort();
@@ -289,16 +324,29 @@ impl Messaging for TelegramAdapter {
         match response {
             OutboundResponse::Text(text) => {
                 self.stop_typing(&message.conversation_id).await;
-                send_formatted(&self.bot, chat_id, &text, None).await?;
+                let sent_id = send_formatted(&self.bot, chat_id, &text, None).await?;
+                self.remember_sent(message, sent_id).await;
             }
             OutboundResponse::RichMessage { text, poll, .. } => {
                 self.stop_typing(&message.conversation_id).await;
-                send_formatted(&self.bot, chat_id, &text, None).await?;
+                let sent_id = send_formatted(&self.bot, chat_id, &text, None).await?;
+                self.remember_sent(message, sent_id).await;
 
                 if let Some(poll_data) = poll {
                     send_poll(&self.bot, chat_id, &poll_data).await?;
                 }
             }
+            OutboundResponse::Reply { in_reply_to, text } => {
+                self.stop_typing(&message.conversation_id).await;
+
+                let reply_to = in_reply_to
+                    .parse::<i32>()
+                    .ok()
+                    .map(MessageId)
+                    .or_else(|| self.extract_message_id(message).ok());
+                let sent_id = send_formatted(&self.bot, chat_id, &text, reply_to).await?;
+                self.remember_sent(message, sent_id).await;
+            }
             OutboundResponse::ThreadReply {
                 thread_name: _,
                 text,
@@ -307,13 +355,15 @@ impl Messaging for TelegramAdapter {
 
                 // Telegram doesn't have named threads. Reply to the source message instead.
                 let reply_to = self.extract_message_id(message).ok();
-                send_formatted(&self.bot, chat_id, &text, reply_to).await?;
+                let sent_id = send_formatted(&self.bot, chat_id, &text, reply_to).await?;
+                self.remember_sent(message, sent_id).await;
             }
             OutboundResponse::File {
                 filename,
                 data,
                 mime_type,
                 caption,
+                ..
             } => {
                 self.stop_typing(&message.conversation_id).await;
 
@@ -411,6 +461,44 @@ impl Messaging for TelegramAdapter {
                     );
                 }
             }
+            OutboundResponse::Edit { message_id, text } => {
+                let target = self.resolve_sent_message(message, message_id).await?;
+                let display_text = if text.len() > MAX_MESSAGE_LENGTH {
+                    let end = text.floor_char_boundary(MAX_MESSAGE_LENGTH - 3);
+                    format!("{}...", &text[..end])
+                } else {
+                    text
+                };
+
+                let html = markdown_to_telegram_html(&display_text);
+                if let Err(html_error) = self
+                    .bot
+                    .edit_message_text(chat_id, target, &html)
+                    .parse_mode(ParseMode::Html)
+                    .send()
+                    .await
+                {
+                    tracing::debug!(%html_error, "HTML edit failed, retrying as plain text");
+                    self.bot
+                        .edit_message_text(chat_id, target, &display_text)
+                        .send()
+                        .await
+                        .context("failed to edit telegram message")?;
+                }
+            }
+            OutboundResponse::Delete { message_id } => {
+                let target = self.resolve_sent_message(message, message_id).await?;
+                self.bot
+                    .delete_message(chat_id, target)
+                    .send()
+                    .await
+                    .context("failed to delete telegram message")?;
+
+                let mut sent_messages = self.sent_messages.write().await;
+                if sent_messages.get(&message.conversation_id) == Some(&target) {
+                    sent_messages.remove(&message.conversation_id);
+                }
+            }
             OutboundResponse::StreamStart => {
                 self.stop_typing(&message.conversation_id).await;
 
@@ -474,6 +562,8 @@ impl Messaging for TelegramAdapter {
             OutboundResponse::Status(status) => {
                 self.send_status(message, status).await?;
             }
+            // Drafts wait for operator approval and are never posted
+            OutboundResponse::Draft { .. } => {}
             // Slack-specific variants — graceful fallbacks for Telegram
             OutboundResponse::RemoveReaction(_) => {} // no-op
             OutboundResponse::Ephemeral { text, .. } => {
@@ -484,6 +574,11 @@ impl Messaging for TelegramAdapter {
                 // Telegram has no scheduled messages — send immediately
                 send_formatted(&self.bot, chat_id, &text, None).await?;
             }
+            OutboundResponse::Structured(reply) => {
+                // Chat has no structured form; the message is the reply
+                return Box::pin(self.respond(message, OutboundResponse::Text(reply.message)))
+                    .await;
+            }
         }
 
         Ok(())
@@ -495,7 +590,16 @@ impl Messaging for TelegramAdapter {
         status: StatusUpdate,
     ) -> crate::Result<()> {
         match status {
-            StatusUpdate::Thinking => {
+            // Keep typing through tool calls; a running indicator is left as is.
+            StatusUpdate::Thinking | StatusUpdate::RunningTool { .. } => {
+                if self
+                    .typing_tasks
+                    .read()
+                    .await
+                    .contains_key(&message.conversation_id)
+                {
+                    return Ok(());
+                }
                 let chat_id = self.extract_chat_id(message)?;
                 let bot = self.bot.clone();
                 let conversation_id = message.conversation_id.clone();
@@ -822,6 +926,19 @@ fn build_metadata(
 
     if let Some(bot_username) = bot_username {
         metadata.insert("telegram_bot_username".into(), bot_username.clone().into());
+
+        let mention = format!("@{bot_username}").to_lowercase();
+        let mentions_bot =
+            extract_text(message).is_some_and(|text| text.to_lowercase().contains(&mention));
+        let replies_to_bot = message
+            .reply_to_message()
+            .and_then(|reply| reply.from.as_ref())
+            .and_then(|from| from.username.as_ref())
+            .is_some_and(|username| username.eq_ignore_ascii_case(bot_username));
+        metadata.insert(
+            "telegram_mentions_or_replies_to_bot".into(),
+            (mentions_bot || replies_to_bot).into(),
+        );
     }
 
     // Reply-to context for threading
@@ -913,34 +1030,6 @@ async fn send_poll(bot: &Bot, chat_id: ChatId, poll: &crate::Poll) -> anyhow::Re
     Ok(())
 }
 
-/// Split a message into chunks that fit within Telegram's character limit.
-/// Tries to split at newlines, then spaces, then hard-cuts.
-fn split_message(text: &str, max_len: usize) -> Vec<String> {
-    if text.len() <= max_len {
-        return vec![text.to_string()];
-    }
-
-    let mut chunks = Vec::new();
-    let mut remaining = text;
-
-    while !remaining.is_empty() {
-        if remaining.len() <= max_len {
-            chunks.push(remaining.to_string());
-            break;
-        }
-
-        let split_at = remaining[..max_len]
-            .rfind('\n')
-            .or_else(|| remaining[..max_len].rfind(' '))
-            .unwrap_or(max_len);
-
-        chunks.push(remaining[..split_at].to_string());
-        remaining = remaining[split_at..].trim_start();
-    }
-
-    chunks
-}
-
 /// Return true when Telegram rejected rich text entities and a plain-caption retry is safe.
 fn should_retry_plain_caption(error: &RequestError) -> bool {
     matches!(error, RequestError::Api(ApiError::CantParseEntities(_)))
@@ -1122,33 +1211,35 @@ async fn send_plain_text(
     chat_id: ChatId,
     text: &str,
     reply_to: Option<MessageId>,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<MessageId> {
     let mut request = bot.send_message(chat_id, text);
     if let Some(reply_id) = reply_to {
         request = request.reply_parameters(ReplyParameters::new(reply_id));
     }
-    request
+    let sent = request
         .send()
         .await
         .context("failed to send telegram message")?;
-    Ok(())
+    Ok(sent.id)
 }
 
 /// Send a message with Telegram HTML formatting, splitting at the message
 /// length limit. Falls back to plain text if the API rejects the HTML.
+/// Returns the ID of the last message sent.
 async fn send_formatted(
     bot: &Bot,
     chat_id: ChatId,
     text: &str,
     reply_to: Option<MessageId>,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Option<MessageId>> {
+    let mut last_sent = None;
     let mut pending_chunks: VecDeque<String> =
         VecDeque::from(split_message(text, MAX_MESSAGE_LENGTH));
     while let Some(markdown_chunk) = pending_chunks.pop_front() {
         let html_chunk = markdown_to_telegram_html(&markdown_chunk);
 
         if html_chunk.len() > MAX_MESSAGE_LENGTH {
-            let smaller_chunks = split_message(&markdown_chunk, FORMATTED_SPLIT_LENGTH);
+            let smaller_chunks = chunk_markdown(&markdown_chunk, FORMATTED_SPLIT_LENGTH);
             if smaller_chunks.len() > 1 {
                 for chunk in smaller_chunks.into_iter().rev() {
                     pending_chunks.push_front(chunk);
@@ -1157,7 +1248,7 @@ async fn send_formatted(
             }
 
             let plain_chunk = strip_html_tags(&html_chunk);
-            send_plain_text(bot, chat_id, &plain_chunk, reply_to).await?;
+            last_sent = Some(send_plain_text(bot, chat_id, &plain_chunk, reply_to).await?);
             continue;
         }
 
@@ -1167,13 +1258,16 @@ async fn send_formatted(
         if let Some(reply_id) = reply_to {
             request = request.reply_parameters(ReplyParameters::new(reply_id));
         }
-        if let Err(error) = request.send().await {
-            tracing::debug!(%error, "HTML send failed, retrying as plain text");
-            let plain_chunk = strip_html_tags(&html_chunk);
-            send_plain_text(bot, chat_id, &plain_chunk, reply_to).await?;
+        match request.send().await {
+            Ok(sent) => last_sent = Some(sent.id),
+            Err(error) => {
+                tracing::debug!(%error, "HTML send failed, retrying as plain text");
+                let plain_chunk = strip_html_tags(&html_chunk);
+                last_sent = Some(send_plain_text(bot, chat_id, &plain_chunk, reply_to).await?);
+            }
         }
     }
-    Ok(())
+    Ok(last_sent)
 }
 
 #[cfg(test)]
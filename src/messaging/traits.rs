@@ -62,6 +62,30 @@ pub trait Messaging: Send + Sync + 'static {
         async { Ok(Vec::new()) }
     }
 
+    /// Open a new thread off `message` and return the conversation ID that
+    /// messages posted in it will arrive under. Returns `None` when the
+    /// platform, or this conversation, has no threads.
+    fn open_thread(
+        &self,
+        message: &InboundMessage,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<Option<String>>> + Send {
+        let _ = (message, name);
+        async { Ok(None) }
+    }
+
+    /// Rename the conversation `message` came from to `title`, where the
+    /// platform lets the bot do so (e.g. a thread it created). No-op by
+    /// default.
+    fn rename_conversation(
+        &self,
+        message: &InboundMessage,
+        title: &str,
+    ) -> impl std::future::Future<Output = Result<()>> + Send {
+        let _ = (message, title);
+        async { Ok(()) }
+    }
+
     /// Health check.
     fn health_check(&self) -> impl std::future::Future<Output = Result<()>> + Send;
 
@@ -104,6 +128,18 @@ pub trait MessagingDyn: Send + Sync + 'static {
         limit: usize,
     ) -> Pin<Box<dyn std::future::Future<Output = Result<Vec<HistoryMessage>>> + Send + 'a>>;
 
+    fn open_thread<'a>(
+        &'a self,
+        message: &'a InboundMessage,
+        name: &'a str,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<Option<String>>> + Send + 'a>>;
+
+    fn rename_conversation<'a>(
+        &'a self,
+        message: &'a InboundMessage,
+        title: &'a str,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>>;
+
     fn health_check<'a>(
         &'a self,
     ) -> Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>>;
@@ -156,6 +192,22 @@ impl<T: Messaging> MessagingDyn for T {
         Box::pin(Messaging::fetch_history(self, message, limit))
     }
 
+    fn open_thread<'a>(
+        &'a self,
+        message: &'a InboundMessage,
+        name: &'a str,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<Option<String>>> + Send + 'a>> {
+        Box::pin(Messaging::open_thread(self, message, name))
+    }
+
+    fn rename_conversation<'a>(
+        &'a self,
+        message: &'a InboundMessage,
+        title: &'a str,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(Messaging::rename_conversation(self, message, title))
+    }
+
     fn health_check<'a>(
         &'a self,
     ) -> Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
@@ -1,6 +1,7 @@
 //! Twitch chat messaging adapter using twitch-irc.
 
 use crate::config::TwitchPermissions;
+use crate::messaging::chunking::split_message;
 use crate::messaging::traits::{InboundStream, Messaging};
 use crate::{InboundMessage, MessageContent, OutboundResponse};
 
@@ -340,6 +341,15 @@ impl Messaging for TwitchAdapter {
                         .context("failed to send twitch message")?;
                 }
             }
+            OutboundResponse::Reply { in_reply_to, text } => {
+                let reply_ref = (channel, in_reply_to.as_str());
+                for chunk in split_message(&text, MAX_MESSAGE_LENGTH) {
+                    client
+                        .say_in_reply_to(&reply_ref, chunk)
+                        .await
+                        .context("failed to send twitch reply")?;
+                }
+            }
             OutboundResponse::ThreadReply { text, .. } => {
                 // Twitch has no threads — reply to the source message instead
                 let reply_to_id = message
@@ -363,13 +373,21 @@ impl Messaging for TwitchAdapter {
                 }
             }
             OutboundResponse::File {
-                filename, caption, ..
+                filename,
+                caption,
+                media,
+                ..
             } => {
-                // Twitch is text-only — send a note about the file
-                let text = match caption {
+                // Twitch is text-only — send a note about the file, with a
+                // link when it's in the media store
+                let mut text = match caption {
                     Some(caption) => format!("[File: {filename}] {caption}"),
                     None => format!("[File: {filename}]"),
                 };
+                if let Some(media) = media {
+                    text.push(' ');
+                    text.push_str(&media.url);
+                }
                 client
                     .say(channel.to_owned(), text)
                     .await
@@ -383,10 +401,13 @@ impl Messaging for TwitchAdapter {
                 // which sends a Text response after StreamEnd.
             }
             OutboundResponse::StreamEnd => {}
-            // Reactions, status updates, and Slack-specific variants aren't meaningful in Twitch chat
+            // Reactions, edits, status updates, and Slack-specific variants aren't meaningful in Twitch chat
             OutboundResponse::Reaction(_)
             | OutboundResponse::RemoveReaction(_)
-            | OutboundResponse::Status(_) => {}
+            | OutboundResponse::Edit { .. }
+            | OutboundResponse::Delete { .. }
+            | OutboundResponse::Status(_)
+            | OutboundResponse::Draft { .. } => {}
             OutboundResponse::Ephemeral { text, .. } => {
                 // No ephemeral concept in Twitch — send as regular chat message
                 client
@@ -401,6 +422,15 @@ impl Messaging for TwitchAdapter {
                     .await
                     .context("failed to send scheduled message fallback on twitch")?;
             }
+            OutboundResponse::Structured(reply) => {
+                // Chat has no structured form; the message is the reply
+                for chunk in split_message(&reply.message, MAX_MESSAGE_LENGTH) {
+                    client
+                        .say(channel.to_owned(), chunk)
+                        .await
+                        .context("failed to send twitch message")?;
+                }
+            }
         }
 
         Ok(())
@@ -454,31 +484,3 @@ impl Messaging for TwitchAdapter {
         Ok(())
     }
 }
-
-/// Split a message into chunks that fit within Twitch's character limit.
-/// Tries to split at newlines, then spaces, then hard-cuts.
-fn split_message(text: &str, max_len: usize) -> Vec<String> {
-    if text.len() <= max_len {
-        return vec![text.to_string()];
-    }
-
-    let mut chunks = Vec::new();
-    let mut remaining = text;
-
-    while !remaining.is_empty() {
-        if remaining.len() <= max_len {
-            chunks.push(remaining.to_string());
-            break;
-        }
-
-        let split_at = remaining[..max_len]
-            .rfind('\n')
-            .or_else(|| remaining[..max_len].rfind(' '))
-            .unwrap_or(max_len);
-
-        chunks.push(remaining[..split_at].to_string());
-        remaining = remaining[split_at..].trim_start();
-    }
-
-    chunks
-}
@@ -20,11 +20,17 @@ pub struct WebChatAdapter {
 pub enum WebChatEvent {
     Thinking,
     Text(String),
+    /// The answer on a structured turn.
+    Structured(crate::StructuredReply),
     StreamStart,
     StreamChunk(String),
     StreamEnd,
-    ToolStarted { tool_name: String },
-    ToolCompleted { tool_name: String },
+    ToolStarted {
+        tool_name: String,
+    },
+    ToolCompleted {
+        tool_name: String,
+    },
     StopTyping,
     Done,
 }
@@ -52,6 +58,25 @@ impl WebChatAdapter {
         rx
     }
 
+    /// Register a session unless one is already open for the conversation.
+    /// A session whose receiver was dropped counts as closed.
+    pub async fn try_register_session(
+        &self,
+        conversation_id: &str,
+    ) -> Option<mpsc::Receiver<WebChatEvent>> {
+        let mut sessions = self.sessions.write().await;
+        if sessions
+            .get(conversation_id)
+            .is_some_and(|session| !session.is_closed())
+        {
+            return None;
+        }
+        let (tx, rx) = mpsc::channel(256);
+        sessions.insert(conversation_id.to_string(), tx);
+        tracing::debug!(%conversation_id, "webchat session registered");
+        Some(rx)
+    }
+
     pub async fn unregister_session(&self, conversation_id: &str) {
         self.sessions.write().await.remove(conversation_id);
         tracing::debug!(%conversation_id, "webchat session unregistered");
@@ -82,17 +107,36 @@ impl Messaging for WebChatAdapter {
 
         let (event, signals_done) = match response {
             OutboundResponse::Text(text) => (WebChatEvent::Text(text), true),
+            OutboundResponse::Reply { text, .. } => (WebChatEvent::Text(text), true),
             OutboundResponse::ThreadReply { text, .. } => (WebChatEvent::Text(text), true),
+            OutboundResponse::Structured(reply) => (WebChatEvent::Structured(reply), true),
             OutboundResponse::StreamStart => (WebChatEvent::StreamStart, false),
             OutboundResponse::StreamChunk(text) => (WebChatEvent::StreamChunk(text), false),
             OutboundResponse::StreamEnd => (WebChatEvent::StreamEnd, true),
+            // The web UI can't take uploads from the agent; link the stored copy.
+            OutboundResponse::File {
+                filename,
+                caption,
+                media: Some(media),
+                ..
+            } => {
+                let link = format!("[{filename}]({})", media.url);
+                let text = match caption {
+                    Some(caption) => format!("{caption}\n\n{link}"),
+                    None => link,
+                };
+                (WebChatEvent::Text(text), true)
+            }
             OutboundResponse::File { .. }
             | OutboundResponse::Reaction(_)
             | OutboundResponse::RemoveReaction(_)
+            | OutboundResponse::Edit { .. }
+            | OutboundResponse::Delete { .. }
             | OutboundResponse::Ephemeral { .. }
             | OutboundResponse::ScheduledMessage { .. }
             | OutboundResponse::RichMessage { .. }
-            | OutboundResponse::Status(_) => return Ok(()),
+            | OutboundResponse::Status(_)
+            | OutboundResponse::Draft { .. } => return Ok(()),
         };
 
         let _ = tx.send(event).await;
@@ -114,7 +158,9 @@ impl Messaging for WebChatAdapter {
 
         let event = match status {
             StatusUpdate::Thinking => WebChatEvent::Thinking,
-            StatusUpdate::StopTyping => WebChatEvent::StopTyping,
+            StatusUpdate::StopTyping
+            | StatusUpdate::Idle
+            | StatusUpdate::WaitingOnWorker { .. } => WebChatEvent::StopTyping,
             StatusUpdate::ToolStarted { tool_name } => WebChatEvent::ToolStarted { tool_name },
             StatusUpdate::ToolCompleted { tool_name } => WebChatEvent::ToolCompleted { tool_name },
             _ => return Ok(()),
@@ -4,30 +4,41 @@
 //! delivers responses via a per-conversation polling endpoint. This is
 //! the integration point for scripts, CI pipelines, and other programs
 //! that need to interact with Spacebot programmatically.
+//!
+//! Configured sources (GitHub, Stripe, or anything that signs with
+//! HMAC-SHA256) post events to `/hooks/{source}`. The signature is verified
+//! against the source's secret and the JSON payload is rendered through a
+//! template into a readable summary before it reaches the agent.
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use anyhow::Context as _;
 use axum::Router;
+use axum::body::Bytes;
 use axum::extract::{Json, State};
 use axum::http::header::AUTHORIZATION;
 use axum::http::{HeaderMap, StatusCode};
 use axum::routing::{get, post};
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
-use tokio::sync::{RwLock, mpsc};
+use sha2::{Digest as _, Sha256};
+use tokio::sync::{Mutex, RwLock, mpsc};
 
+use crate::config::{WebhookSignature, WebhookSourceConfig};
 use crate::messaging::traits::{InboundStream, Messaging};
-use crate::{InboundMessage, MessageContent, OutboundResponse};
+use crate::{InboundMessage, MessageContent, OutboundResponse, StructuredReply};
 
 /// Webhook adapter state.
 pub struct WebhookAdapter {
     port: u16,
     bind: String,
     auth_token: Option<String>,
+    sources: Arc<HashMap<String, WebhookSourceConfig>>,
     inbound_tx: Arc<RwLock<Option<mpsc::Sender<InboundMessage>>>>,
     /// Buffered responses per conversation_id, waiting to be polled.
     response_buffers: Arc<RwLock<HashMap<String, Vec<WebhookResponse>>>>,
+    seen_events: Arc<Mutex<SeenEvents>>,
     shutdown_tx: Arc<RwLock<Option<mpsc::Sender<()>>>>,
 }
 
@@ -37,6 +48,8 @@ struct AppState {
     inbound_tx: Arc<RwLock<Option<mpsc::Sender<InboundMessage>>>>,
     response_buffers: Arc<RwLock<HashMap<String, Vec<WebhookResponse>>>>,
     auth_token: Option<String>,
+    sources: Arc<HashMap<String, WebhookSourceConfig>>,
+    seen_events: Arc<Mutex<SeenEvents>>,
 }
 
 /// Inbound webhook request body.
@@ -51,6 +64,13 @@ struct WebhookRequest {
     content: String,
     /// Optional agent to route to (overrides binding resolution).
     agent_id: Option<String>,
+    /// Optional message ID. A retried request with the same ID is dropped as
+    /// a duplicate instead of reaching the agent twice.
+    message_id: Option<String>,
+    /// Ask for a structured reply (`action`, `message`, `confidence`)
+    /// instead of chat text.
+    #[serde(default)]
+    structured: bool,
 }
 
 fn default_sender() -> String {
@@ -66,6 +86,13 @@ struct WebhookResponse {
     filename: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     caption: Option<String>,
+    /// The answer on a structured turn, for `type: "structured"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    structured: Option<StructuredReply>,
+    /// The turn that produced the response, for matching a reply to its
+    /// trace in the logs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    turn_id: Option<String>,
 }
 
 /// Response from the poll endpoint.
@@ -75,13 +102,24 @@ struct PollResponse {
 }
 
 impl WebhookAdapter {
-    pub fn new(port: u16, bind: impl Into<String>, auth_token: Option<String>) -> Self {
+    pub fn new(
+        port: u16,
+        bind: impl Into<String>,
+        auth_token: Option<String>,
+        sources: Vec<WebhookSourceConfig>,
+    ) -> Self {
+        let sources = sources
+            .into_iter()
+            .map(|source| (source.name.clone(), source))
+            .collect();
         Self {
             port,
             bind: bind.into(),
             auth_token,
+            sources: Arc::new(sources),
             inbound_tx: Arc::new(RwLock::new(None)),
             response_buffers: Arc::new(RwLock::new(HashMap::new())),
+            seen_events: Arc::new(Mutex::new(SeenEvents::default())),
             shutdown_tx: Arc::new(RwLock::new(None)),
         }
     }
@@ -103,6 +141,8 @@ impl Messaging for WebhookAdapter {
             inbound_tx: self.inbound_tx.clone(),
             response_buffers: self.response_buffers.clone(),
             auth_token: self.auth_token.clone(),
+            sources: self.sources.clone(),
+            seen_events: self.seen_events.clone(),
         };
 
         if self.auth_token.is_none() {
@@ -114,6 +154,7 @@ impl Messaging for WebhookAdapter {
         let app = Router::new()
             .route("/send", post(handle_send))
             .route("/poll/{conversation_id}", get(handle_poll))
+            .route("/hooks/{source}", post(handle_hook))
             .route("/health", get(handle_health))
             .with_state(state);
 
@@ -147,69 +188,123 @@ impl Messaging for WebhookAdapter {
         message: &InboundMessage,
         response: OutboundResponse,
     ) -> crate::Result<()> {
-        let webhook_response = match response {
+        // Events from signed sources have nobody polling for a reply. The
+        // agent acts on them through its tools instead.
+        if message.metadata.contains_key("webhook_source") {
+            return Ok(());
+        }
+
+        let mut webhook_response = match response {
             OutboundResponse::Text(text) => WebhookResponse {
                 response_type: "text".into(),
                 content: Some(text),
                 filename: None,
                 caption: None,
+                structured: None,
+                turn_id: None,
             },
             OutboundResponse::RichMessage { text, .. } => WebhookResponse {
                 response_type: "text".into(),
                 content: Some(text),
                 filename: None,
                 caption: None,
+                structured: None,
+                turn_id: None,
+            },
+            OutboundResponse::Reply { text, .. } => WebhookResponse {
+                response_type: "text".into(),
+                content: Some(text),
+                filename: None,
+                caption: None,
+                structured: None,
+                turn_id: None,
             },
             OutboundResponse::ThreadReply { text, .. } => WebhookResponse {
                 response_type: "text".into(),
                 content: Some(text),
                 filename: None,
                 caption: None,
+                structured: None,
+                turn_id: None,
             },
+            // `content` carries a link to the stored copy, when there is one.
             OutboundResponse::File {
-                filename, caption, ..
+                filename,
+                caption,
+                structured: None,
+                turn_id: None,
+                media,
+                ..
             } => WebhookResponse {
                 response_type: "file".into(),
-                content: None,
+                content: media.map(|media| media.url),
                 filename: Some(filename),
                 caption,
+                structured: None,
+                turn_id: None,
+            },
+            OutboundResponse::Structured(reply) => WebhookResponse {
+                response_type: "structured".into(),
+                content: Some(reply.message.clone()),
+                filename: None,
+                caption: None,
+                structured: Some(reply),
+                turn_id: None,
             },
             OutboundResponse::StreamStart => WebhookResponse {
                 response_type: "stream_start".into(),
                 content: None,
                 filename: None,
                 caption: None,
+                structured: None,
+                turn_id: None,
             },
             OutboundResponse::StreamChunk(text) => WebhookResponse {
                 response_type: "stream_chunk".into(),
                 content: Some(text),
                 filename: None,
                 caption: None,
+                structured: None,
+                turn_id: None,
             },
             OutboundResponse::StreamEnd => WebhookResponse {
                 response_type: "stream_end".into(),
                 content: None,
                 filename: None,
                 caption: None,
+                structured: None,
+                turn_id: None,
             },
             // Reactions, status updates, and remove-reaction aren't meaningful over webhook
             OutboundResponse::Reaction(_)
             | OutboundResponse::RemoveReaction(_)
-            | OutboundResponse::Status(_) => return Ok(()),
+            | OutboundResponse::Edit { .. }
+            | OutboundResponse::Delete { .. }
+            | OutboundResponse::Status(_)
+            | OutboundResponse::Draft { .. } => return Ok(()),
             // Slack-specific rich variants — fall back to plain text
             OutboundResponse::Ephemeral { text, .. } => WebhookResponse {
                 response_type: "text".into(),
                 content: Some(text),
                 filename: None,
                 caption: None,
+                structured: None,
+                turn_id: None,
             },
             OutboundResponse::ScheduledMessage { text, .. } => WebhookResponse {
                 response_type: "text".into(),
                 content: Some(text),
                 filename: None,
                 caption: None,
+                structured: None,
+                turn_id: None,
             },
         };
+        webhook_response.turn_id = message
+            .metadata
+            .get(crate::TURN_ID_METADATA_KEY)
+            .and_then(|turn_id| turn_id.as_str())
+            .map(String::from);
 
         self.response_buffers
             .write()
@@ -262,11 +357,16 @@ async fn handle_send(
         "display_name".into(),
         serde_json::Value::String(request.sender_id.clone()),
     );
+    if request.structured {
+        metadata.insert("response_format".into(), "structured".into());
+    }
 
     let conversation_id = format!("webhook:{}", request.conversation_id);
 
     let inbound = InboundMessage {
-        id: uuid::Uuid::new_v4().to_string(),
+        id: request
+            .message_id
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
         source: "webhook".into(),
         conversation_id,
         sender_id: request.sender_id.clone(),
@@ -304,6 +404,60 @@ async fn handle_poll(
     Ok(Json(PollResponse { messages }))
 }
 
+async fn handle_hook(
+    headers: HeaderMap,
+    State(state): State<AppState>,
+    axum::extract::Path(source_name): axum::extract::Path<String>,
+    body: Bytes,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let Some(source) = state.sources.get(&source_name) else {
+        return Err((StatusCode::NOT_FOUND, "unknown webhook source".into()));
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    if let Err(reason) = verify_signature(source, &headers, &body, now) {
+        tracing::warn!(source = %source.name, reason, "rejected webhook event");
+        return Err((StatusCode::UNAUTHORIZED, "invalid signature".into()));
+    }
+
+    let payload: serde_json::Value = serde_json::from_slice(&body)
+        .map_err(|error| (StatusCode::BAD_REQUEST, format!("invalid JSON: {error}")))?;
+    let event = event_name(source.signature, &headers, &payload);
+
+    let event_id = event_id(source, &headers, &payload, &body);
+    if state
+        .seen_events
+        .lock()
+        .await
+        .check_and_insert(&event_id, now)
+    {
+        tracing::debug!(source = %source.name, %event_id, "dropping replayed webhook event");
+        return Ok(StatusCode::OK);
+    }
+
+    let tx = state.inbound_tx.read().await;
+    let Some(tx) = tx.as_ref() else {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            "webhook not initialized".into(),
+        ));
+    };
+
+    let inbound = event_message(source, event_id, event.as_deref(), &payload);
+    tracing::debug!(
+        source = %source.name,
+        event = event.as_deref().unwrap_or("-"),
+        conversation_id = %inbound.conversation_id,
+        "webhook event received"
+    );
+
+    tx.send(inbound)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "channel closed".into()))?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
 async fn handle_health() -> StatusCode {
     StatusCode::OK
 }
@@ -327,3 +481,524 @@ fn is_authorized(headers: &HeaderMap, expected_token: Option<&str>) -> bool {
         .and_then(|value| value.strip_prefix("Bearer "))
         .is_some_and(|token| token == expected_token)
 }
+
+// -- Signed event sources --
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How far a signed timestamp may drift from now before the event is treated
+/// as a replay.
+const SIGNATURE_TOLERANCE_SECS: i64 = 300;
+
+/// How long an event ID is remembered. GitHub doesn't sign a timestamp, so
+/// this window is what stops a captured delivery from being replayed.
+const REPLAY_WINDOW_SECS: i64 = 24 * 60 * 60;
+
+/// Cap on the pretty-printed payload exposed to templates as `payload_json`.
+const MAX_PAYLOAD_JSON_BYTES: usize = 8 * 1024;
+
+const DEFAULT_GITHUB_TEMPLATE: &str = "\
+GitHub {{ event }}{% if payload.action %} ({{ payload.action }}){% endif %}\
+{% if payload.repository %} in {{ payload.repository.full_name }}{% endif %}\
+{% if payload.sender %} by {{ payload.sender.login }}{% endif %}
+{%- if payload.pull_request %}
+Pull request #{{ payload.pull_request.number }}: {{ payload.pull_request.title }}
+{{ payload.pull_request.html_url }}
+{%- elif payload.issue %}
+Issue #{{ payload.issue.number }}: {{ payload.issue.title }}
+{{ payload.issue.html_url }}
+{%- endif %}
+{%- if payload.comment %}
+Comment: {{ payload.comment.body }}
+{%- endif %}
+{%- if payload.commits %}
+Pushed {{ payload.commits | length }} commit(s) to {{ payload.ref }}:
+{%- for commit in payload.commits %}
+- {{ commit.message }}{% if commit.author %} ({{ commit.author.name }}){% endif %}
+{%- endfor %}
+{%- endif %}";
+
+const DEFAULT_STRIPE_TEMPLATE: &str = "\
+Stripe {{ event }}\
+{% if payload.data.object.id %} for {{ payload.data.object.object }} {{ payload.data.object.id }}{% endif %}
+{%- if payload.data.object.amount is defined %}
+Amount: {{ payload.data.object.amount }} {{ payload.data.object.currency | upper }} (minor units)
+{%- endif %}
+{%- if payload.data.object.customer %}
+Customer: {{ payload.data.object.customer }}
+{%- endif %}
+{%- if payload.data.object.status %}
+Status: {{ payload.data.object.status }}
+{%- endif %}";
+
+const DEFAULT_HMAC_TEMPLATE: &str = "\
+Webhook event from {{ source }}{% if event %}: {{ event }}{% endif %}
+```json
+{{ payload_json }}
+```";
+
+/// Check the request signature against the source's secret.
+///
+/// Returns a short reason on failure, for logging.
+fn verify_signature(
+    source: &WebhookSourceConfig,
+    headers: &HeaderMap,
+    body: &[u8],
+    now: i64,
+) -> Result<(), &'static str> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+    match source.signature {
+        WebhookSignature::Github => {
+            let signature = header("x-hub-signature-256")
+                .ok_or("missing X-Hub-Signature-256 header")?
+                .strip_prefix("sha256=")
+                .ok_or("malformed X-Hub-Signature-256 header")?;
+            verify_hmac(&source.secret, &[body], signature)
+        }
+        WebhookSignature::Stripe => {
+            let header = header("stripe-signature").ok_or("missing Stripe-Signature header")?;
+            let mut timestamp = None;
+            let mut signatures = Vec::new();
+            for part in header.split(',') {
+                match part.trim().split_once('=') {
+                    Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+                    Some(("v1", value)) => signatures.push(value),
+                    _ => {}
+                }
+            }
+
+            let timestamp = timestamp.ok_or("malformed Stripe-Signature header")?;
+            if (now - timestamp).abs() > SIGNATURE_TOLERANCE_SECS {
+                return Err("Stripe signature timestamp outside tolerance");
+            }
+
+            // Stripe sends several v1 signatures while a secret is being rolled.
+            let signed_prefix = format!("{timestamp}.");
+            signatures
+                .into_iter()
+                .find(|signature| {
+                    verify_hmac(&source.secret, &[signed_prefix.as_bytes(), body], signature)
+                        .is_ok()
+                })
+                .map(|_| ())
+                .ok_or("signature mismatch")
+        }
+        WebhookSignature::Hmac => {
+            let signature = header(&source.signature_header).ok_or("missing signature header")?;
+            let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+            let Some(timestamp_header) = source.timestamp_header.as_deref() else {
+                return verify_hmac(&source.secret, &[body], signature);
+            };
+
+            let raw_timestamp = header(timestamp_header)
+                .ok_or("missing timestamp header")?
+                .trim();
+            let timestamp = raw_timestamp
+                .parse::<i64>()
+                .map_err(|_| "malformed timestamp header")?;
+            if (now - timestamp).abs() > SIGNATURE_TOLERANCE_SECS {
+                return Err("signature timestamp outside tolerance");
+            }
+            let signed_prefix = format!("{raw_timestamp}.");
+            verify_hmac(&source.secret, &[signed_prefix.as_bytes(), body], signature)
+        }
+    }
+}
+
+/// Constant-time comparison of a hex HMAC-SHA256 against `parts`.
+fn verify_hmac(secret: &str, parts: &[&[u8]], signature_hex: &str) -> Result<(), &'static str> {
+    let expected = hex::decode(signature_hex.trim()).map_err(|_| "signature is not hex")?;
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).map_err(|_| "invalid HMAC secret")?;
+    for part in parts {
+        mac.update(part);
+    }
+    mac.verify_slice(&expected)
+        .map_err(|_| "signature mismatch")
+}
+
+/// Stable ID for an event, so retries and replays of one delivery map to the
+/// same inbound message. GitHub and Stripe name their deliveries; other
+/// sources are identified by a hash of the signed content.
+fn event_id(
+    source: &WebhookSourceConfig,
+    headers: &HeaderMap,
+    payload: &serde_json::Value,
+    body: &[u8],
+) -> String {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let provider_id = match source.signature {
+        WebhookSignature::Github => header("x-github-delivery").map(String::from),
+        WebhookSignature::Stripe => payload.get("id").and_then(|v| v.as_str()).map(String::from),
+        WebhookSignature::Hmac => None,
+    };
+
+    let id = provider_id.unwrap_or_else(|| {
+        let mut hasher = Sha256::new();
+        if let Some(timestamp) = source.timestamp_header.as_deref().and_then(header) {
+            hasher.update(timestamp.trim().as_bytes());
+            hasher.update(b".");
+        }
+        hasher.update(body);
+        hex::encode(hasher.finalize())
+    });
+    format!("webhook:{}:{id}", source.name)
+}
+
+/// Event IDs accepted within the replay window.
+#[derive(Default)]
+struct SeenEvents {
+    accepted_at: HashMap<String, i64>,
+}
+
+impl SeenEvents {
+    /// Record `event_id`, returning true if it was already accepted within
+    /// the replay window.
+    fn check_and_insert(&mut self, event_id: &str, now: i64) -> bool {
+        self.accepted_at
+            .retain(|_, accepted_at| now - *accepted_at < REPLAY_WINDOW_SECS);
+        if self.accepted_at.contains_key(event_id) {
+            return true;
+        }
+        self.accepted_at.insert(event_id.to_string(), now);
+        false
+    }
+}
+
+/// Event type, from the header or payload field each provider uses.
+fn event_name(
+    signature: WebhookSignature,
+    headers: &HeaderMap,
+    payload: &serde_json::Value,
+) -> Option<String> {
+    let from_payload = |key: &str| payload.get(key).and_then(|v| v.as_str()).map(String::from);
+    match signature {
+        WebhookSignature::Github => headers
+            .get("x-github-event")
+            .and_then(|value| value.to_str().ok())
+            .map(String::from),
+        WebhookSignature::Stripe => from_payload("type"),
+        WebhookSignature::Hmac => from_payload("event").or_else(|| from_payload("type")),
+    }
+}
+
+fn default_template(signature: WebhookSignature) -> &'static str {
+    match signature {
+        WebhookSignature::Github => DEFAULT_GITHUB_TEMPLATE,
+        WebhookSignature::Stripe => DEFAULT_STRIPE_TEMPLATE,
+        WebhookSignature::Hmac => DEFAULT_HMAC_TEMPLATE,
+    }
+}
+
+/// Render a template against the event, returning `None` if it fails or
+/// renders empty.
+fn render_template(
+    source: &WebhookSourceConfig,
+    template: &str,
+    context: &minijinja::Value,
+) -> Option<String> {
+    // Payloads vary by event, so missing fields render empty instead of
+    // failing the whole template.
+    let mut env = minijinja::Environment::new();
+    env.set_undefined_behavior(minijinja::UndefinedBehavior::Chainable);
+    match env.render_str(template, context) {
+        Ok(rendered) => {
+            let rendered = rendered.trim();
+            (!rendered.is_empty()).then(|| rendered.to_string())
+        }
+        Err(error) => {
+            tracing::warn!(source = %source.name, %error, "failed to render webhook template");
+            None
+        }
+    }
+}
+
+/// Build the inbound message for a verified event.
+fn event_message(
+    source: &WebhookSourceConfig,
+    id: String,
+    event: Option<&str>,
+    payload: &serde_json::Value,
+) -> InboundMessage {
+    let payload_json = serde_json::to_string_pretty(payload).unwrap_or_default();
+    let context = minijinja::context! {
+        source => source.name.as_str(),
+        event => event,
+        payload => payload,
+        payload_json => crate::tools::truncate_output(&payload_json, MAX_PAYLOAD_JSON_BYTES),
+    };
+
+    let default = default_template(source.signature);
+    let summary = source
+        .template
+        .as_deref()
+        .and_then(|template| render_template(source, template, &context))
+        .or_else(|| render_template(source, default, &context))
+        .unwrap_or_else(|| format!("Webhook event from {}", source.name));
+
+    // Events from one source share a conversation unless the template splits
+    // them further, e.g. one per repository or per customer.
+    let webhook_conversation_id = match source
+        .conversation
+        .as_deref()
+        .and_then(|template| render_template(source, template, &context))
+    {
+        Some(key) => format!("{}:{}", source.name, key.replace(char::is_whitespace, "-")),
+        None => source.name.clone(),
+    };
+
+    let mut metadata = HashMap::new();
+    metadata.insert(
+        "webhook_source".into(),
+        serde_json::Value::String(source.name.clone()),
+    );
+    if let Some(event) = event {
+        metadata.insert(
+            "webhook_event".into(),
+            serde_json::Value::String(event.to_string()),
+        );
+    }
+    metadata.insert(
+        "webhook_conversation_id".into(),
+        serde_json::Value::String(webhook_conversation_id.clone()),
+    );
+    metadata.insert(
+        "display_name".into(),
+        serde_json::Value::String(source.name.clone()),
+    );
+
+    InboundMessage {
+        id,
+        source: "webhook".into(),
+        conversation_id: format!("webhook:{webhook_conversation_id}"),
+        sender_id: source.name.clone(),
+        agent_id: source.agent_id.clone().map(Into::into),
+        content: MessageContent::Text(summary),
+        timestamp: chrono::Utc::now(),
+        metadata,
+        formatted_author: Some(source.name.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(signature: WebhookSignature) -> WebhookSourceConfig {
+        WebhookSourceConfig {
+            name: "events".into(),
+            signature,
+            secret: "It's a Secret to Everybody".into(),
+            signature_header: "X-Signature-256".into(),
+            timestamp_header: None,
+            conversation: None,
+            agent_id: None,
+            template: None,
+        }
+    }
+
+    fn sign(secret: &str, parts: &[&[u8]]) -> String {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        for part in parts {
+            mac.update(part);
+        }
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn github_signature_matches_documented_example() {
+        // Example from GitHub's "Validating webhook deliveries" docs.
+        let github = source(WebhookSignature::Github);
+        let valid = headers(&[(
+            "x-hub-signature-256",
+            "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17",
+        )]);
+        assert!(verify_signature(&github, &valid, b"Hello, World!", 0).is_ok());
+        assert!(verify_signature(&github, &valid, b"Hello, World?", 0).is_err());
+        assert!(verify_signature(&github, &HeaderMap::new(), b"Hello, World!", 0).is_err());
+    }
+
+    #[test]
+    fn stripe_signature_checks_timestamp_and_any_v1() {
+        let stripe = source(WebhookSignature::Stripe);
+        let body = br#"{"type":"invoice.paid"}"#;
+        let valid = sign(&stripe.secret, &[b"1700000000.", body]);
+        let header = format!("t=1700000000,v1={},v1={valid}", "00".repeat(32));
+        let request = headers(&[("stripe-signature", &header)]);
+
+        assert!(verify_signature(&stripe, &request, body, 1_700_000_100).is_ok());
+        assert_eq!(
+            verify_signature(&stripe, &request, body, 1_700_001_000),
+            Err("Stripe signature timestamp outside tolerance")
+        );
+        assert!(verify_signature(&stripe, &request, b"{}", 1_700_000_100).is_err());
+    }
+
+    #[test]
+    fn hmac_signature_accepts_optional_prefix() {
+        let custom = source(WebhookSignature::Hmac);
+        let body = b"{}";
+        let signature = sign(&custom.secret, &[body]);
+
+        for value in [signature.clone(), format!("sha256={signature}")] {
+            let request = headers(&[("x-signature-256", &value)]);
+            assert!(verify_signature(&custom, &request, body, 0).is_ok());
+        }
+        let wrong = headers(&[("x-signature-256", "not-hex")]);
+        assert!(verify_signature(&custom, &wrong, body, 0).is_err());
+    }
+
+    #[test]
+    fn github_event_renders_readable_summary() {
+        let github = source(WebhookSignature::Github);
+        let payload = serde_json::json!({
+            "action": "opened",
+            "repository": { "full_name": "acme/widgets" },
+            "sender": { "login": "octocat" },
+            "pull_request": {
+                "number": 42,
+                "title": "Add sprockets",
+                "html_url": "https://github.com/acme/widgets/pull/42"
+            }
+        });
+
+        let message = event_message(&github, "id".into(), Some("pull_request"), &payload);
+        let MessageContent::Text(text) = &message.content else {
+            panic!("expected text content");
+        };
+        assert_eq!(
+            text,
+            "GitHub pull_request (opened) in acme/widgets by octocat\n\
+             Pull request #42: Add sprockets\n\
+             https://github.com/acme/widgets/pull/42"
+        );
+        assert_eq!(message.conversation_id, "webhook:events");
+        assert_eq!(message.metadata["webhook_event"], "pull_request");
+    }
+
+    #[test]
+    fn custom_templates_with_fallback() {
+        let mut custom = source(WebhookSignature::Hmac);
+        custom.template = Some("Deploy of {{ payload.service }} {{ payload.status }}".into());
+        custom.conversation = Some("{{ payload.service }}".into());
+        let payload = serde_json::json!({ "service": "api", "status": "failed" });
+
+        let message = event_message(&custom, "id".into(), None, &payload);
+        assert!(
+            matches!(&message.content, MessageContent::Text(text) if text == "Deploy of api failed")
+        );
+        assert_eq!(message.conversation_id, "webhook:events:api");
+
+        // A broken template falls back to the built-in one.
+        custom.template = Some("{{ payload.service".into());
+        let message = event_message(&custom, "id".into(), None, &payload);
+        assert!(
+            matches!(&message.content, MessageContent::Text(text) if text.contains("\"service\": \"api\""))
+        );
+    }
+
+    #[test]
+    fn hmac_signature_with_timestamp_header_rejects_stale_events() {
+        let mut custom = source(WebhookSignature::Hmac);
+        custom.timestamp_header = Some("X-Timestamp".into());
+        let body = b"{}";
+        let signature = sign(&custom.secret, &[b"1700000000.", body]);
+        let request = headers(&[
+            ("x-signature-256", &signature),
+            ("x-timestamp", "1700000000"),
+        ]);
+
+        assert!(verify_signature(&custom, &request, body, 1_700_000_100).is_ok());
+        assert_eq!(
+            verify_signature(&custom, &request, body, 1_700_001_000),
+            Err("signature timestamp outside tolerance")
+        );
+        // The body alone no longer verifies.
+        let unsigned_timestamp = headers(&[
+            ("x-signature-256", &sign(&custom.secret, &[body])),
+            ("x-timestamp", "1700000000"),
+        ]);
+        assert!(verify_signature(&custom, &unsigned_timestamp, body, 1_700_000_000).is_err());
+    }
+
+    #[test]
+    fn event_ids_come_from_the_provider_or_the_signed_content() {
+        let github = source(WebhookSignature::Github);
+        let delivery = headers(&[("x-github-delivery", "72d3162e-cc78-11e3-81ab-4c9367dc0958")]);
+        assert_eq!(
+            event_id(&github, &delivery, &serde_json::json!({}), b"{}"),
+            "webhook:events:72d3162e-cc78-11e3-81ab-4c9367dc0958"
+        );
+
+        let stripe = source(WebhookSignature::Stripe);
+        let payload = serde_json::json!({ "id": "evt_123" });
+        assert_eq!(
+            event_id(&stripe, &HeaderMap::new(), &payload, b"{}"),
+            "webhook:events:evt_123"
+        );
+
+        let mut custom = source(WebhookSignature::Hmac);
+        let empty = serde_json::json!({});
+        let first = event_id(&custom, &HeaderMap::new(), &empty, b"{}");
+        assert_eq!(first, event_id(&custom, &HeaderMap::new(), &empty, b"{}"));
+        assert_ne!(first, event_id(&custom, &HeaderMap::new(), &empty, b"[]"));
+
+        // With a timestamp header, identical bodies sent at different times
+        // are different events.
+        custom.timestamp_header = Some("X-Timestamp".into());
+        let at = |timestamp| headers(&[("x-timestamp", timestamp)]);
+        assert_ne!(
+            event_id(&custom, &at("1"), &empty, b"{}"),
+            event_id(&custom, &at("2"), &empty, b"{}")
+        );
+    }
+
+    #[test]
+    fn replayed_events_are_dropped_within_the_window() {
+        let mut seen = SeenEvents::default();
+        assert!(!seen.check_and_insert("a", 0));
+        assert!(seen.check_and_insert("a", 60));
+        assert!(!seen.check_and_insert("b", 60));
+        assert!(!seen.check_and_insert("a", REPLAY_WINDOW_SECS));
+    }
+
+    #[tokio::test]
+    async fn structured_replies_are_polled_with_their_fields() {
+        let adapter = WebhookAdapter::new(0, "127.0.0.1", None, Vec::new());
+        let message = InboundMessage {
+            conversation_id: "webhook:test".into(),
+            metadata: HashMap::from([
+                ("response_format".into(), "structured".into()),
+                (crate::TURN_ID_METADATA_KEY.into(), "turn-1".into()),
+            ]),
+            ..crate::testing::inbound_message("webhook", "script", "can I get a refund?")
+        };
+        assert!(message.wants_structured_reply());
+
+        let reply = StructuredReply {
+            action: crate::ReplyAction::Clarify,
+            message: "Which order?".into(),
+            confidence: Some(0.8),
+        };
+        adapter
+            .respond(&message, OutboundResponse::Structured(reply))
+            .await
+            .unwrap();
+
+        let buffers = adapter.response_buffers.read().await;
+        let polled = serde_json::to_value(&buffers["webhook:test"][0]).unwrap();
+        assert_eq!(polled["type"], "structured");
+        assert_eq!(polled["content"], "Which order?");
+        assert_eq!(polled["structured"]["action"], "clarify");
+        assert_eq!(polled["turn_id"], "turn-1");
+    }
+}
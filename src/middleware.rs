@@ -0,0 +1,14 @@
+//! Middleware chains that run around the channel loop.
+//!
+//! Middleware lets an embedding program adjust messages without forking the
+//! channel: filters, mention stripping, translation, custom routing on the
+//! way in; formatting, splitting, and safety filtering on the way out. Each
+//! chain is registered on [`AgentDeps`](crate::AgentDeps) and shared by every
+//! channel of the agent, so middleware registered after startup applies to
+//! channels that are already running.
+
+pub mod inbound;
+pub mod outbound;
+
+pub use inbound::{InboundAction, InboundChain, InboundMiddleware, InboundMiddlewareDyn};
+pub use outbound::{EmojiShortcodes, OutboundChain, OutboundMiddleware, OutboundMiddlewareDyn};
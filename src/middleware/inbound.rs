@@ -0,0 +1,197 @@
+//! Inbound middleware: rewrite, annotate, or drop a message before the
+//! channel handles it.
+
+use crate::InboundMessage;
+use crate::error::Result;
+
+use arc_swap::ArcSwap;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// What an [`InboundMiddleware`] does with a message.
+#[derive(Debug)]
+pub enum InboundAction {
+    /// Pass the message, changed or not, to the next middleware.
+    Continue(InboundMessage),
+    /// Drop the message. The channel never sees it.
+    Drop { reason: String },
+}
+
+/// Static trait for inbound middleware.
+/// Use this for type-safe implementations.
+pub trait InboundMiddleware: Send + Sync + 'static {
+    /// Name used in logs.
+    fn name(&self) -> &str;
+
+    /// Process a message on its way to the channel.
+    fn process(
+        &self,
+        message: InboundMessage,
+    ) -> impl std::future::Future<Output = Result<InboundAction>> + Send;
+}
+
+/// Dynamic trait for runtime polymorphism.
+/// Use this when you need `Arc<dyn InboundMiddlewareDyn>` for storing different middleware.
+pub trait InboundMiddlewareDyn: Send + Sync + 'static {
+    fn name(&self) -> &str;
+
+    fn process<'a>(
+        &'a self,
+        message: InboundMessage,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<InboundAction>> + Send + 'a>>;
+}
+
+/// Blanket implementation: any type implementing InboundMiddleware automatically implements InboundMiddlewareDyn.
+impl<T: InboundMiddleware> InboundMiddlewareDyn for T {
+    fn name(&self) -> &str {
+        InboundMiddleware::name(self)
+    }
+
+    fn process<'a>(
+        &'a self,
+        message: InboundMessage,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<InboundAction>> + Send + 'a>> {
+        Box::pin(InboundMiddleware::process(self, message))
+    }
+}
+
+/// The inbound middleware of an agent, run in registration order.
+#[derive(Clone, Default)]
+pub struct InboundChain {
+    middleware: Arc<ArcSwap<Vec<Arc<dyn InboundMiddlewareDyn>>>>,
+}
+
+impl InboundChain {
+    /// Append middleware to the end of the chain.
+    pub fn register(&self, middleware: impl InboundMiddleware) {
+        let middleware: Arc<dyn InboundMiddlewareDyn> = Arc::new(middleware);
+        self.middleware.rcu(|current| {
+            let mut next = Vec::clone(current);
+            next.push(middleware.clone());
+            next
+        });
+    }
+
+    /// Whether any middleware is registered.
+    pub fn is_empty(&self) -> bool {
+        self.middleware.load().is_empty()
+    }
+
+    /// Run the chain over a message. Returns `None` when middleware drops it.
+    ///
+    /// Middleware that fails is skipped with a warning and the message it
+    /// was given moves on, so a broken plug-in can't silence the channel.
+    pub async fn run(&self, message: InboundMessage) -> Option<InboundMessage> {
+        let chain = self.middleware.load_full();
+        let mut message = message;
+        for middleware in chain.iter() {
+            match middleware.process(message.clone()).await {
+                Ok(InboundAction::Continue(next)) => message = next,
+                Ok(InboundAction::Drop { reason }) => {
+                    tracing::info!(
+                        middleware = middleware.name(),
+                        message_id = %message.id,
+                        %reason,
+                        "inbound middleware dropped message"
+                    );
+                    return None;
+                }
+                Err(error) => {
+                    tracing::warn!(
+                        middleware = middleware.name(),
+                        message_id = %message.id,
+                        %error,
+                        "inbound middleware failed, passing message through"
+                    );
+                }
+            }
+        }
+        Some(message)
+    }
+}
+
+impl std::fmt::Debug for InboundChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<String> = self
+            .middleware
+            .load()
+            .iter()
+            .map(|middleware| middleware.name().to_string())
+            .collect();
+        f.debug_struct("InboundChain")
+            .field("middleware", &names)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MessageContent;
+
+    fn message(text: &str) -> InboundMessage {
+        crate::testing::inbound_message("discord", "alice", text)
+    }
+
+    struct StripMention;
+
+    impl InboundMiddleware for StripMention {
+        fn name(&self) -> &str {
+            "strip_mention"
+        }
+
+        async fn process(&self, mut message: InboundMessage) -> Result<InboundAction> {
+            if let MessageContent::Text(text) = &mut message.content {
+                *text = text.trim_start_matches("@bot ").to_string();
+            }
+            message
+                .metadata
+                .insert("mention_stripped".into(), true.into());
+            Ok(InboundAction::Continue(message))
+        }
+    }
+
+    struct DropWord(&'static str);
+
+    impl InboundMiddleware for DropWord {
+        fn name(&self) -> &str {
+            "drop_word"
+        }
+
+        async fn process(&self, message: InboundMessage) -> Result<InboundAction> {
+            if message.content.to_string().contains(self.0) {
+                return Ok(InboundAction::Drop {
+                    reason: format!("contains {}", self.0),
+                });
+            }
+            Ok(InboundAction::Continue(message))
+        }
+    }
+
+    struct Broken;
+
+    impl InboundMiddleware for Broken {
+        fn name(&self) -> &str {
+            "broken"
+        }
+
+        async fn process(&self, _message: InboundMessage) -> Result<InboundAction> {
+            Err(anyhow::anyhow!("translation service unavailable").into())
+        }
+    }
+
+    #[tokio::test]
+    async fn middleware_runs_in_order_and_can_drop() {
+        let chain = InboundChain::default();
+        assert!(chain.is_empty());
+        chain.register(StripMention);
+        chain.register(Broken);
+        chain.register(DropWord("spam"));
+
+        let passed = chain.run(message("@bot hello")).await.unwrap();
+        assert_eq!(passed.content.to_string(), "hello");
+        assert_eq!(passed.metadata["mention_stripped"], true);
+
+        assert!(chain.run(message("@bot buy spam")).await.is_none());
+    }
+}
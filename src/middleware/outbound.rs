@@ -0,0 +1,264 @@
+//! Outbound middleware: rewrite, split, or drop a response before the
+//! adapter sends it.
+
+use crate::error::Result;
+use crate::{InboundMessage, OutboundResponse};
+
+use arc_swap::ArcSwap;
+use regex::Regex;
+use std::pin::Pin;
+use std::sync::{Arc, LazyLock};
+
+/// Static trait for outbound middleware.
+/// Use this for type-safe implementations.
+pub trait OutboundMiddleware: Send + Sync + 'static {
+    /// Name used in logs.
+    fn name(&self) -> &str;
+
+    /// Process a response on its way to the adapter. `message` is the one
+    /// being answered. Return the response, changed or not; several
+    /// responses to split it; or none to drop it.
+    fn process(
+        &self,
+        message: &InboundMessage,
+        response: OutboundResponse,
+    ) -> impl std::future::Future<Output = Result<Vec<OutboundResponse>>> + Send;
+}
+
+/// Dynamic trait for runtime polymorphism.
+/// Use this when you need `Arc<dyn OutboundMiddlewareDyn>` for storing different middleware.
+pub trait OutboundMiddlewareDyn: Send + Sync + 'static {
+    fn name(&self) -> &str;
+
+    fn process<'a>(
+        &'a self,
+        message: &'a InboundMessage,
+        response: OutboundResponse,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<Vec<OutboundResponse>>> + Send + 'a>>;
+}
+
+/// Blanket implementation: any type implementing OutboundMiddleware automatically implements OutboundMiddlewareDyn.
+impl<T: OutboundMiddleware> OutboundMiddlewareDyn for T {
+    fn name(&self) -> &str {
+        OutboundMiddleware::name(self)
+    }
+
+    fn process<'a>(
+        &'a self,
+        message: &'a InboundMessage,
+        response: OutboundResponse,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<Vec<OutboundResponse>>> + Send + 'a>> {
+        Box::pin(OutboundMiddleware::process(self, message, response))
+    }
+}
+
+/// The outbound middleware of an agent, run in registration order.
+#[derive(Clone, Default)]
+pub struct OutboundChain {
+    middleware: Arc<ArcSwap<Vec<Arc<dyn OutboundMiddlewareDyn>>>>,
+}
+
+impl OutboundChain {
+    /// The chain agents start with: emoji shortcodes are turned into emoji.
+    pub fn standard() -> Self {
+        let chain = Self::default();
+        chain.register(EmojiShortcodes);
+        chain
+    }
+
+    /// Append middleware to the end of the chain.
+    pub fn register(&self, middleware: impl OutboundMiddleware) {
+        let middleware: Arc<dyn OutboundMiddlewareDyn> = Arc::new(middleware);
+        self.middleware.rcu(|current| {
+            let mut next = Vec::clone(current);
+            next.push(middleware.clone());
+            next
+        });
+    }
+
+    /// Whether any middleware is registered.
+    pub fn is_empty(&self) -> bool {
+        self.middleware.load().is_empty()
+    }
+
+    /// Run the chain over a response. Each response a middleware returns
+    /// goes through the rest of the chain on its own.
+    ///
+    /// Middleware that fails is skipped with a warning and the response it
+    /// was given moves on, so a broken plug-in can't swallow replies.
+    pub async fn run(
+        &self,
+        message: &InboundMessage,
+        response: OutboundResponse,
+    ) -> Vec<OutboundResponse> {
+        let chain = self.middleware.load_full();
+        let mut responses = vec![response];
+        for middleware in chain.iter() {
+            let mut next = Vec::with_capacity(responses.len());
+            for response in responses {
+                match middleware.process(message, response.clone()).await {
+                    Ok(processed) => {
+                        if processed.is_empty() {
+                            tracing::info!(
+                                middleware = middleware.name(),
+                                conversation_id = %message.conversation_id,
+                                "outbound middleware dropped response"
+                            );
+                        }
+                        next.extend(processed);
+                    }
+                    Err(error) => {
+                        tracing::warn!(
+                            middleware = middleware.name(),
+                            conversation_id = %message.conversation_id,
+                            %error,
+                            "outbound middleware failed, passing response through"
+                        );
+                        next.push(response);
+                    }
+                }
+            }
+            responses = next;
+        }
+        responses
+    }
+}
+
+impl std::fmt::Debug for OutboundChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<String> = self
+            .middleware
+            .load()
+            .iter()
+            .map(|middleware| middleware.name().to_string())
+            .collect();
+        f.debug_struct("OutboundChain")
+            .field("middleware", &names)
+            .finish()
+    }
+}
+
+static SHORTCODE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r":([a-z0-9_+-]+):").expect("hardcoded shortcode regex"));
+
+/// Turns emoji shortcodes the model writes (`:tada:`) into the emoji itself,
+/// for platforms that show shortcodes as plain text. Unknown shortcodes are
+/// left alone.
+#[derive(Debug, Clone, Default)]
+pub struct EmojiShortcodes;
+
+impl OutboundMiddleware for EmojiShortcodes {
+    fn name(&self) -> &str {
+        "emoji_shortcodes"
+    }
+
+    async fn process(
+        &self,
+        _message: &InboundMessage,
+        mut response: OutboundResponse,
+    ) -> Result<Vec<OutboundResponse>> {
+        if let Some(text) = response.text_mut() {
+            *text = replace_shortcodes(text);
+        }
+        Ok(vec![response])
+    }
+}
+
+fn replace_shortcodes(text: &str) -> String {
+    SHORTCODE
+        .replace_all(
+            text,
+            |captures: &regex::Captures| match emojis::get_by_shortcode(&captures[1]) {
+                Some(emoji) => emoji.as_str().to_string(),
+                None => captures[0].to_string(),
+            },
+        )
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message() -> InboundMessage {
+        crate::testing::inbound_message("telegram", "alice", "hi")
+    }
+
+    struct SplitLines;
+
+    impl OutboundMiddleware for SplitLines {
+        fn name(&self) -> &str {
+            "split_lines"
+        }
+
+        async fn process(
+            &self,
+            _message: &InboundMessage,
+            response: OutboundResponse,
+        ) -> Result<Vec<OutboundResponse>> {
+            match response {
+                OutboundResponse::Text(text) => Ok(text
+                    .lines()
+                    .map(|line| OutboundResponse::Text(line.to_string()))
+                    .collect()),
+                other => Ok(vec![other]),
+            }
+        }
+    }
+
+    struct DropSecrets;
+
+    impl OutboundMiddleware for DropSecrets {
+        fn name(&self) -> &str {
+            "drop_secrets"
+        }
+
+        async fn process(
+            &self,
+            _message: &InboundMessage,
+            mut response: OutboundResponse,
+        ) -> Result<Vec<OutboundResponse>> {
+            if response
+                .text_mut()
+                .is_some_and(|text| text.contains("secret"))
+            {
+                return Ok(Vec::new());
+            }
+            Ok(vec![response])
+        }
+    }
+
+    fn texts(responses: &[OutboundResponse]) -> Vec<&str> {
+        responses
+            .iter()
+            .map(|response| match response {
+                OutboundResponse::Text(text) => text.as_str(),
+                other => panic!("unexpected response {other:?}"),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn split_responses_each_run_through_the_rest_of_the_chain() {
+        let chain = OutboundChain::default();
+        chain.register(SplitLines);
+        chain.register(DropSecrets);
+        chain.register(EmojiShortcodes);
+
+        let responses = chain
+            .run(
+                &message(),
+                OutboundResponse::Text("done :tada:\nthe secret is 42\nbye".into()),
+            )
+            .await;
+        assert_eq!(texts(&responses), ["done 🎉", "bye"]);
+    }
+
+    #[test]
+    fn unknown_shortcodes_and_times_are_left_alone() {
+        assert_eq!(
+            replace_shortcodes(":thumbsup: at 12:30:45 :not_an_emoji:"),
+            "👍 at 12:30:45 :not_an_emoji:"
+        );
+    }
+}
@@ -6,7 +6,7 @@
 
 use crate::opencode::server::OpenCodeServerPool;
 use crate::opencode::types::*;
-use crate::{AgentId, ChannelId, ProcessEvent, WorkerId};
+use crate::{AgentId, ChannelId, ProcessEvent, TurnId, WorkerId};
 
 use anyhow::{Context as _, bail};
 use futures::StreamExt as _;
@@ -30,12 +30,18 @@ pub struct OpenCodeWorker {
     pub system_prompt: Option<String>,
     /// Model override (provider/model format like "anthropic/claude-sonnet-4").
     pub model: Option<String>,
+    /// The channel turn that spawned the worker, stamped on its events.
+    pub turn_id: Option<TurnId>,
 }
 
 /// Result of an OpenCode worker run.
 pub struct OpenCodeWorkerResult {
     pub session_id: String,
+    /// The last response: the task's, or the last follow-up's.
     pub result_text: String,
+    /// Whether `result_text` already reached the channel as a
+    /// `WorkerResponse` (interactive workers).
+    pub delivered: bool,
 }
 
 impl OpenCodeWorker {
@@ -59,6 +65,7 @@ impl OpenCodeWorker {
             input_rx: None,
             system_prompt: None,
             model: None,
+            turn_id: None,
         }
     }
 
@@ -89,6 +96,12 @@ impl OpenCodeWorker {
         self
     }
 
+    /// Attribute the worker's events to the channel turn that spawned it.
+    pub fn with_turn_id(mut self, turn_id: Option<TurnId>) -> Self {
+        self.turn_id = turn_id;
+        self
+    }
+
     /// Run the worker: spawn/reuse an OpenCode server, create a session,
     /// send the task, monitor via SSE, and return the result.
     pub async fn run(mut self) -> anyhow::Result<OpenCodeWorkerResult> {
@@ -152,12 +165,16 @@ impl OpenCodeWorker {
         }
 
         // Process SSE events until session goes idle or errors
-        let result_text = self
+        let mut result_text = self
             .process_events(event_response, &session_id, &server)
             .await?;
 
-        // Interactive follow-up loop
+        // Interactive follow-up loop. Each answer reaches the channel as it's
+        // given, and the last one is the final result.
+        let mut delivered = false;
         if let Some(mut input_rx) = self.input_rx.take() {
+            self.send_response(&result_text);
+            delivered = true;
             self.send_status("waiting for follow-up");
 
             while let Some(follow_up) = input_rx.recv().await {
@@ -190,7 +207,9 @@ impl OpenCodeWorker {
                     .process_events(event_response, &session_id, &server)
                     .await
                 {
-                    Ok(_) => {
+                    Ok(response) => {
+                        self.send_response(&response);
+                        result_text = response;
                         self.send_status("waiting for follow-up");
                     }
                     Err(error) => {
@@ -200,7 +219,7 @@ impl OpenCodeWorker {
                             "OpenCode follow-up failed"
                         );
                         self.send_status("failed");
-                        break;
+                        return Err(error.context("OpenCode follow-up failed"));
                     }
                 }
             }
@@ -217,6 +236,7 @@ impl OpenCodeWorker {
         Ok(OpenCodeWorkerResult {
             session_id,
             result_text,
+            delivered,
         })
     }
 
@@ -424,6 +444,7 @@ impl OpenCodeWorker {
                         permission.patterns.join(", ")
                     ),
                     patterns: permission.patterns.clone(),
+                    turn_id: self.turn_id,
                 });
 
                 // Auto-allow (OPENCODE_CONFIG_CONTENT should prevent most prompts)
@@ -469,6 +490,7 @@ impl OpenCodeWorker {
                             options: q.options.clone(),
                         })
                         .collect(),
+                    turn_id: self.turn_id,
                 });
 
                 // Auto-select first option
@@ -529,6 +551,17 @@ impl OpenCodeWorker {
         }
     }
 
+    /// Deliver a response from an interactive session back to the channel.
+    fn send_response(&self, response: &str) {
+        let _ = self.event_tx.send(ProcessEvent::WorkerResponse {
+            agent_id: self.agent_id.clone(),
+            worker_id: self.id,
+            channel_id: self.channel_id.clone(),
+            response: response.to_string(),
+            turn_id: self.turn_id,
+        });
+    }
+
     /// Send a status update via the process event bus.
     fn send_status(&self, status: &str) {
         let _ = self.event_tx.send(ProcessEvent::WorkerStatus {
@@ -536,6 +569,7 @@ impl OpenCodeWorker {
             worker_id: self.id,
             channel_id: self.channel_id.clone(),
             status: status.to_string(),
+            turn_id: self.turn_id,
         });
     }
 }
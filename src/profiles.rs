@@ -0,0 +1,223 @@
+//! Long-term user profiles: facts about a sender that carry across channels.
+//!
+//! Profiles are keyed by `sender_id` and hold what the person wants to be
+//! called, their timezone, and standing preferences. The channel injects the
+//! profiles of the people in the current turn into its system prompt, and the
+//! `profile_update` tool edits them.
+
+use crate::error::Result;
+
+use serde::Serialize;
+use sqlx::{Row as _, SqlitePool};
+
+/// Max standing preferences kept per profile. Oldest are dropped first.
+pub const MAX_PREFERENCES: usize = 20;
+
+/// What the agent knows about one sender.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UserProfile {
+    pub sender_id: String,
+    /// Last platform display name seen for this sender.
+    pub display_name: Option<String>,
+    pub preferred_name: Option<String>,
+    /// IANA timezone name, e.g. "Europe/Budapest".
+    pub timezone: Option<String>,
+    pub preferences: Vec<String>,
+}
+
+impl UserProfile {
+    /// Whether the profile holds anything worth showing the LLM.
+    pub fn is_empty(&self) -> bool {
+        self.preferred_name.is_none() && self.timezone.is_none() && self.preferences.is_empty()
+    }
+
+    /// Name to address the sender by.
+    pub fn name(&self) -> &str {
+        self.preferred_name
+            .as_deref()
+            .or(self.display_name.as_deref())
+            .unwrap_or(&self.sender_id)
+    }
+}
+
+/// A change to a profile. `None` fields are left alone; an empty string
+/// clears the field.
+#[derive(Debug, Clone, Default)]
+pub struct ProfileUpdate {
+    pub display_name: Option<String>,
+    pub preferred_name: Option<String>,
+    pub timezone: Option<String>,
+    pub add_preferences: Vec<String>,
+    /// Preferences to drop, matched case-insensitively.
+    pub remove_preferences: Vec<String>,
+}
+
+impl ProfileUpdate {
+    /// Apply this update to `profile` in place.
+    pub fn apply(&self, profile: &mut UserProfile) {
+        let set = |field: &mut Option<String>, value: &Option<String>| {
+            if let Some(value) = value {
+                let value = value.trim();
+                *field = (!value.is_empty()).then(|| value.to_string());
+            }
+        };
+        set(&mut profile.display_name, &self.display_name);
+        set(&mut profile.preferred_name, &self.preferred_name);
+        set(&mut profile.timezone, &self.timezone);
+
+        profile.preferences.retain(|existing| {
+            !self
+                .remove_preferences
+                .iter()
+                .any(|remove| remove.trim().eq_ignore_ascii_case(existing))
+        });
+        for preference in &self.add_preferences {
+            let preference = preference.trim();
+            if preference.is_empty()
+                || profile
+                    .preferences
+                    .iter()
+                    .any(|existing| existing.eq_ignore_ascii_case(preference))
+            {
+                continue;
+            }
+            profile.preferences.push(preference.to_string());
+        }
+        if profile.preferences.len() > MAX_PREFERENCES {
+            let excess = profile.preferences.len() - MAX_PREFERENCES;
+            profile.preferences.drain(..excess);
+        }
+    }
+}
+
+/// Reads and writes user profiles in SQLite.
+#[derive(Debug, Clone)]
+pub struct ProfileStore {
+    pool: SqlitePool,
+}
+
+impl ProfileStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Load a sender's profile, if one exists.
+    pub async fn get(&self, sender_id: &str) -> Result<Option<UserProfile>> {
+        let row = sqlx::query(
+            "SELECT sender_id, display_name, preferred_name, timezone, preferences \
+             FROM user_profiles \
+             WHERE sender_id = ?",
+        )
+        .bind(sender_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(row.map(row_to_profile))
+    }
+
+    /// Load the non-empty profiles for a set of senders, in the given order.
+    pub async fn get_many(&self, sender_ids: &[&str]) -> Result<Vec<UserProfile>> {
+        let mut profiles = Vec::new();
+        for sender_id in sender_ids {
+            if let Some(profile) = self.get(sender_id).await?
+                && !profile.is_empty()
+            {
+                profiles.push(profile);
+            }
+        }
+        Ok(profiles)
+    }
+
+    /// Apply an update to a sender's profile, creating it if needed.
+    /// Returns the profile as stored.
+    pub async fn update(&self, sender_id: &str, update: &ProfileUpdate) -> Result<UserProfile> {
+        let mut profile = self.get(sender_id).await?.unwrap_or_else(|| UserProfile {
+            sender_id: sender_id.to_string(),
+            ..Default::default()
+        });
+        update.apply(&mut profile);
+
+        let preferences =
+            serde_json::to_string(&profile.preferences).map_err(|e| anyhow::anyhow!(e))?;
+        sqlx::query(
+            "INSERT INTO user_profiles \
+                 (sender_id, display_name, preferred_name, timezone, preferences, updated_at) \
+             VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP) \
+             ON CONFLICT(sender_id) DO UPDATE SET \
+                 display_name = excluded.display_name, \
+                 preferred_name = excluded.preferred_name, \
+                 timezone = excluded.timezone, \
+                 preferences = excluded.preferences, \
+                 updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(&profile.sender_id)
+        .bind(&profile.display_name)
+        .bind(&profile.preferred_name)
+        .bind(&profile.timezone)
+        .bind(&preferences)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(profile)
+    }
+}
+
+fn row_to_profile(row: sqlx::sqlite::SqliteRow) -> UserProfile {
+    let preferences: String = row
+        .try_get("preferences")
+        .unwrap_or_else(|_| "[]".to_string());
+
+    UserProfile {
+        sender_id: row.try_get("sender_id").unwrap_or_default(),
+        display_name: row.try_get("display_name").ok().flatten(),
+        preferred_name: row.try_get("preferred_name").ok().flatten(),
+        timezone: row.try_get("timezone").ok().flatten(),
+        preferences: serde_json::from_str(&preferences).unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_sets_and_clears_fields() {
+        let mut profile = UserProfile {
+            sender_id: "42".into(),
+            timezone: Some("UTC".into()),
+            ..Default::default()
+        };
+        ProfileUpdate {
+            preferred_name: Some(" Andy ".into()),
+            timezone: Some(String::new()),
+            ..Default::default()
+        }
+        .apply(&mut profile);
+
+        assert_eq!(profile.preferred_name.as_deref(), Some("Andy"));
+        assert_eq!(profile.timezone, None);
+        assert_eq!(profile.name(), "Andy");
+    }
+
+    #[test]
+    fn update_dedupes_and_caps_preferences() {
+        let mut profile = UserProfile::default();
+        ProfileUpdate {
+            add_preferences: vec!["Short answers".into(), "short answers".into()],
+            ..Default::default()
+        }
+        .apply(&mut profile);
+        assert_eq!(profile.preferences, vec!["Short answers"]);
+
+        ProfileUpdate {
+            remove_preferences: vec!["SHORT ANSWERS".into()],
+            add_preferences: (0..MAX_PREFERENCES + 2).map(|i| format!("p{i}")).collect(),
+            ..Default::default()
+        }
+        .apply(&mut profile);
+        assert_eq!(profile.preferences.len(), MAX_PREFERENCES);
+        assert_eq!(profile.preferences[0], "p2");
+    }
+}
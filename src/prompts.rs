@@ -1,5 +1,8 @@
 pub mod engine;
+pub mod language;
 pub mod text;
 
-pub use engine::{PromptEngine, SkillInfo};
-pub use text::{get as get_text, init as init_language};
+pub use engine::{
+    PromptEngine, RecentEvent, RelevantDocument, RelevantMemory, SenderProfile, SkillInfo,
+};
+pub use text::{get as get_text, get_for as get_text_for, init as init_language};
@@ -1,19 +1,30 @@
+use crate::config::SystemPromptConfig;
 use crate::error::Result;
 use anyhow::Context;
 use minijinja::{Environment, Value, context};
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 
+/// File extension of prompt override files.
+const OVERRIDE_EXTENSION: &str = "md.j2";
+
 /// Template engine for rendering system prompts with dynamic variables.
 ///
 /// Prompts are bundled in the binary as `include_str!` embedded templates.
-/// Language selection is done at initialization and templates are not
-/// reloadable at runtime (no file watching, no hot reload).
+/// Any of them can be overridden by a file in the instance's `prompts/`
+/// directory, named after the template (e.g. `prompts/channel.md.j2`,
+/// `prompts/fragments/coalesce_hint.md.j2`). The file watcher rebuilds the
+/// engine with `with_overrides_from` when those files change, and channels
+/// pick up the new engine on their next turn.
 #[derive(Clone)]
 pub struct PromptEngine {
     /// The MiniJinja environment holding all templates for the configured language.
     /// Wrapped in Arc to make PromptEngine Clone.
     env: Arc<Environment<'static>>,
+    /// Template sources loaded from the override directory, keyed by
+    /// template name. Rendered in place of the bundled template.
+    overrides: Arc<HashMap<String, String>>,
     /// Selected language code (e.g., "en").
     language: String,
 }
@@ -54,6 +65,8 @@ impl PromptEngine {
             "cortex_profile",
             crate::prompts::text::get("cortex_profile"),
         )?;
+        env.add_template("heartbeat", crate::prompts::text::get("heartbeat"))?;
+        env.add_template("feed_digest", crate::prompts::text::get("feed_digest"))?;
 
         // Fragment templates
         env.add_template(
@@ -72,6 +85,26 @@ impl PromptEngine {
             "fragments/skills_worker",
             crate::prompts::text::get("fragments/skills_worker"),
         )?;
+        env.add_template(
+            "fragments/worker_workspace",
+            crate::prompts::text::get("fragments/worker_workspace"),
+        )?;
+        env.add_template(
+            "fragments/recurring_worker",
+            crate::prompts::text::get("fragments/recurring_worker"),
+        )?;
+        env.add_template(
+            "fragments/channel_instructions",
+            crate::prompts::text::get("fragments/channel_instructions"),
+        )?;
+        env.add_template(
+            "fragments/plan_mode",
+            crate::prompts::text::get("fragments/plan_mode"),
+        )?;
+        env.add_template(
+            "fragments/structured_reply",
+            crate::prompts::text::get("fragments/structured_reply"),
+        )?;
         env.add_template(
             "fragments/available_channels",
             crate::prompts::text::get("fragments/available_channels"),
@@ -84,12 +117,32 @@ impl PromptEngine {
             "fragments/link_context",
             crate::prompts::text::get("fragments/link_context"),
         )?;
+        env.add_template(
+            "fragments/relevant_memories",
+            crate::prompts::text::get("fragments/relevant_memories"),
+        )?;
+        env.add_template(
+            "fragments/recent_events",
+            crate::prompts::text::get("fragments/recent_events"),
+        )?;
+        env.add_template(
+            "fragments/relevant_documents",
+            crate::prompts::text::get("fragments/relevant_documents"),
+        )?;
+        env.add_template(
+            "fragments/sender_profiles",
+            crate::prompts::text::get("fragments/sender_profiles"),
+        )?;
 
         // System message fragments
         env.add_template(
             "fragments/system/retrigger",
             crate::prompts::text::get("fragments/system/retrigger"),
         )?;
+        env.add_template(
+            "fragments/system/branch_results",
+            crate::prompts::text::get("fragments/system/branch_results"),
+        )?;
         env.add_template(
             "fragments/system/truncation",
             crate::prompts::text::get("fragments/system/truncation"),
@@ -114,6 +167,18 @@ impl PromptEngine {
             "fragments/system/profile_synthesis",
             crate::prompts::text::get("fragments/system/profile_synthesis"),
         )?;
+        env.add_template(
+            "fragments/system/heartbeat_survey",
+            crate::prompts::text::get("fragments/system/heartbeat_survey"),
+        )?;
+        env.add_template(
+            "fragments/system/feed_items",
+            crate::prompts::text::get("fragments/system/feed_items"),
+        )?;
+        env.add_template(
+            "fragments/system/reminder_due",
+            crate::prompts::text::get("fragments/system/reminder_due"),
+        )?;
         env.add_template(
             "fragments/system/ingestion_chunk",
             crate::prompts::text::get("fragments/system/ingestion_chunk"),
@@ -122,6 +187,22 @@ impl PromptEngine {
             "fragments/system/history_backfill",
             crate::prompts::text::get("fragments/system/history_backfill"),
         )?;
+        env.add_template(
+            "fragments/system/channel_title",
+            crate::prompts::text::get("fragments/system/channel_title"),
+        )?;
+        env.add_template(
+            "fragments/system/channel_relevance",
+            crate::prompts::text::get("fragments/system/channel_relevance"),
+        )?;
+        env.add_template(
+            "fragments/system/overheard",
+            crate::prompts::text::get("fragments/system/overheard"),
+        )?;
+        env.add_template(
+            "fragments/system/overheard_summary",
+            crate::prompts::text::get("fragments/system/overheard_summary"),
+        )?;
         env.add_template(
             "fragments/system/tool_syntax_correction",
             crate::prompts::text::get("fragments/system/tool_syntax_correction"),
@@ -133,10 +214,77 @@ impl PromptEngine {
 
         Ok(Self {
             env: Arc::new(env),
+            overrides: Arc::new(HashMap::new()),
             language: language.to_string(),
         })
     }
 
+    /// Return a copy of this engine with overrides loaded from `dir`.
+    ///
+    /// Replaces any previously loaded overrides. Files that don't match a
+    /// bundled template or fail to parse are skipped with a warning, so a typo
+    /// in one override never takes down the others.
+    pub fn with_overrides_from(&self, dir: &Path) -> Self {
+        let mut overrides = HashMap::new();
+
+        if dir.is_dir() {
+            for (name, _) in self.env.templates() {
+                let path = dir.join(format!("{name}.{OVERRIDE_EXTENSION}"));
+                let source = match std::fs::read_to_string(&path) {
+                    Ok(source) => source,
+                    Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+                    Err(error) => {
+                        tracing::warn!(%error, path = %path.display(), "failed to read prompt override");
+                        continue;
+                    }
+                };
+                if let Err(error) = self.env.template_from_str(&source) {
+                    tracing::warn!(%error, path = %path.display(), "prompt override doesn't parse, using bundled template");
+                    continue;
+                }
+                overrides.insert(name.to_string(), source);
+            }
+        }
+
+        if !overrides.is_empty() {
+            let mut names = overrides.keys().map(String::as_str).collect::<Vec<_>>();
+            names.sort_unstable();
+            tracing::info!(templates = %names.join(", "), "loaded prompt overrides");
+        }
+
+        Self {
+            env: self.env.clone(),
+            overrides: Arc::new(overrides),
+            language: self.language.clone(),
+        }
+    }
+
+    /// Return a copy of this engine for a conversation in `language`.
+    ///
+    /// Templates stay in the bundled language; the conversation language
+    /// selects canned text via [`PromptEngine::text`] and is named in the
+    /// conversation context so the LLM answers in it.
+    pub fn with_language(&self, language: &str) -> Self {
+        Self {
+            env: self.env.clone(),
+            overrides: self.overrides.clone(),
+            language: language.to_string(),
+        }
+    }
+
+    /// Canned text (replies, status strings) in this engine's language.
+    pub fn text(&self, key: &str) -> &'static str {
+        crate::prompts::text::get_for(&self.language, key)
+    }
+
+    /// Canned text with `{{ placeholders }}`, rendered with `context`.
+    pub fn render_text(&self, key: &str, context: Value) -> Result<String> {
+        self.env
+            .render_str(self.text(key), context)
+            .with_context(|| format!("failed to render text '{key}'"))
+            .map_err(Into::into)
+    }
+
     /// Render a template by name with the given context variables.
     ///
     /// # Arguments
@@ -155,6 +303,14 @@ impl PromptEngine {
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn render(&self, template_name: &str, context: Value) -> Result<String> {
+        if let Some(source) = self.overrides.get(template_name) {
+            return self
+                .env
+                .render_str(source, context)
+                .with_context(|| format!("failed to render prompt override '{}'", template_name))
+                .map_err(Into::into);
+        }
+
         let template = self
             .env
             .get_template(template_name)
@@ -182,6 +338,12 @@ impl PromptEngine {
         &self,
         browser_enabled: bool,
         web_search_enabled: bool,
+        container_enabled: bool,
+        github_enabled: bool,
+        coolify_enabled: bool,
+        sql_enabled: bool,
+        http_request_enabled: bool,
+        calendar_enabled: bool,
         opencode_enabled: bool,
     ) -> Result<String> {
         self.render(
@@ -189,6 +351,12 @@ impl PromptEngine {
             context! {
                 browser_enabled => browser_enabled,
                 web_search_enabled => web_search_enabled,
+                container_enabled => container_enabled,
+                github_enabled => github_enabled,
+                coolify_enabled => coolify_enabled,
+                sql_enabled => sql_enabled,
+                http_request_enabled => http_request_enabled,
+                calendar_enabled => calendar_enabled,
                 opencode_enabled => opencode_enabled,
             },
         )
@@ -207,6 +375,50 @@ impl PromptEngine {
                 platform => platform,
                 server_name => server_name,
                 channel_name => channel_name,
+                language => (self.language != "en")
+                    .then(|| crate::prompts::language::name(&self.language))
+                    .flatten(),
+            },
+        )
+    }
+
+    /// Render the relevant memories section appended to channel turns.
+    pub fn render_relevant_memories(&self, memories: Vec<RelevantMemory>) -> Result<String> {
+        self.render(
+            "fragments/relevant_memories",
+            context! {
+                memories => memories,
+            },
+        )
+    }
+
+    /// Render the conversation's latest logged events, appended to channel
+    /// turns.
+    pub fn render_recent_events(&self, events: Vec<RecentEvent>) -> Result<String> {
+        self.render(
+            "fragments/recent_events",
+            context! {
+                events => events,
+            },
+        )
+    }
+
+    /// Render the knowledge base passages appended to channel turns.
+    pub fn render_relevant_documents(&self, documents: Vec<RelevantDocument>) -> Result<String> {
+        self.render(
+            "fragments/relevant_documents",
+            context! {
+                documents => documents,
+            },
+        )
+    }
+
+    /// Render the "Known About Sender" section appended to channel turns.
+    pub fn render_sender_profiles(&self, profiles: Vec<SenderProfile>) -> Result<String> {
+        self.render(
+            "fragments/sender_profiles",
+            context! {
+                profiles => profiles,
             },
         )
     }
@@ -266,11 +478,67 @@ impl PromptEngine {
         )
     }
 
+    /// Tells a worker about its dedicated working directory and whether it
+    /// outlives the worker.
+    pub fn render_worker_workspace(&self, path: &str, retained: bool) -> Result<String> {
+        self.render(
+            "fragments/worker_workspace",
+            context! {
+                path => path,
+                retained => retained,
+            },
+        )
+    }
+
+    /// Worker note for a stateful cron run: the state saved by the last run
+    /// and how to report only what's new.
+    pub fn render_recurring_worker(
+        &self,
+        state: Option<&str>,
+        max_state_bytes: usize,
+    ) -> Result<String> {
+        self.render(
+            "fragments/recurring_worker",
+            context! {
+                state => state,
+                max_state_bytes => max_state_bytes,
+            },
+        )
+    }
+
+    /// Channel note for a turn in plan mode. `tools` are the configured
+    /// extra tools held back.
+    pub fn render_plan_mode(&self, tools: &[String]) -> Result<String> {
+        self.render(
+            "fragments/plan_mode",
+            context! {
+                tools => tools,
+            },
+        )
+    }
+
+    /// Channel note for a turn that must answer with a structured reply.
+    pub fn render_structured_reply(&self) -> Result<String> {
+        self.render_static("fragments/structured_reply")
+    }
+
     /// Convenience method for rendering system retrigger message.
     pub fn render_system_retrigger(&self) -> Result<String> {
         self.render_static("fragments/system/retrigger")
     }
 
+    /// Merged conclusions of branches that finished within the same window.
+    /// `rank` adds an instruction to pick the best-supported conclusion.
+    pub fn render_system_branch_results(&self, results: &[String], rank: bool) -> Result<String> {
+        self.render(
+            "fragments/system/branch_results",
+            context! {
+                results => results,
+                rank => rank,
+            },
+        )
+    }
+
     /// Correction message when the LLM outputs tool call syntax as plain text.
     pub fn render_system_tool_syntax_correction(&self) -> Result<String> {
         self.render_static("fragments/system/tool_syntax_correction")
@@ -322,6 +590,91 @@ impl PromptEngine {
         )
     }
 
+    /// Render the heartbeat system prompt with the agent's identity.
+    pub fn render_heartbeat_prompt(&self, identity_context: Option<&str>) -> Result<String> {
+        self.render(
+            "heartbeat",
+            context! {
+                identity_context => identity_context,
+            },
+        )
+    }
+
+    /// Render the survey a heartbeat decides whether to check in on.
+    pub fn render_system_heartbeat_survey(
+        &self,
+        now: &str,
+        memory_bulletin: Option<&str>,
+        cron_jobs: &[String],
+        workers: &[String],
+        events: Option<&[String]>,
+    ) -> Result<String> {
+        self.render(
+            "fragments/system/heartbeat_survey",
+            context! {
+                now => now,
+                memory_bulletin => memory_bulletin,
+                cron_jobs => cron_jobs,
+                workers => workers,
+                events => events,
+            },
+        )
+    }
+
+    /// Render the feed digest system prompt with the agent's identity.
+    pub fn render_feed_digest_prompt(&self, identity_context: Option<&str>) -> Result<String> {
+        self.render(
+            "feed_digest",
+            context! {
+                identity_context => identity_context,
+            },
+        )
+    }
+
+    /// Render the new entries of a feed for the digest writer. `omitted` is
+    /// how many further new entries didn't fit in the digest.
+    pub fn render_system_feed_items(
+        &self,
+        feed: &str,
+        url: &str,
+        items: &[crate::feeds::FeedItem],
+        omitted: usize,
+    ) -> Result<String> {
+        self.render(
+            "fragments/system/feed_items",
+            context! {
+                feed => feed,
+                url => url,
+                count => items.len(),
+                items => items,
+                omitted => omitted,
+            },
+        )
+    }
+
+    /// The message that asks a channel to deliver a due reminder. `late` and
+    /// `next` are human-readable, e.g. "12 minutes" and "Mon 2026-10-19 09:00
+    /// Europe/Budapest".
+    pub fn render_system_reminder_due(
+        &self,
+        requester: &str,
+        message: &str,
+        set_at: Option<&str>,
+        late: Option<&str>,
+        next: Option<&str>,
+    ) -> Result<String> {
+        self.render(
+            "fragments/system/reminder_due",
+            context! {
+                requester => requester,
+                message => message,
+                set_at => set_at,
+                late => late,
+                next => next,
+            },
+        )
+    }
+
     /// Convenience method for rendering cortex synthesis prompt.
     pub fn render_system_cortex_synthesis(
         &self,
@@ -366,6 +719,61 @@ impl PromptEngine {
         )
     }
 
+    /// Render the request for a short title summarizing a conversation's
+    /// first messages.
+    pub fn render_system_channel_title(&self, transcript: &str) -> Result<String> {
+        self.render(
+            "fragments/system/channel_title",
+            context! {
+                transcript => transcript,
+            },
+        )
+    }
+
+    /// Render the question asked of the cheap model when a group message
+    /// doesn't address the agent: should it chime in anyway?
+    pub fn render_system_channel_relevance(
+        &self,
+        agent_name: &str,
+        transcript: &str,
+        message: &str,
+    ) -> Result<String> {
+        self.render(
+            "fragments/system/channel_relevance",
+            context! {
+                agent_name => agent_name,
+                transcript => transcript,
+                message => message,
+            },
+        )
+    }
+
+    /// Render overheard group messages as context-only history.
+    pub fn render_system_overheard(&self, transcript: &str) -> Result<String> {
+        self.render(
+            "fragments/system/overheard",
+            context! {
+                transcript => transcript,
+            },
+        )
+    }
+
+    /// Render the request to summarize overheard group messages into an
+    /// episodic memory.
+    pub fn render_system_overheard_summary(
+        &self,
+        agent_name: &str,
+        transcript: &str,
+    ) -> Result<String> {
+        self.render(
+            "fragments/system/overheard_summary",
+            context! {
+                agent_name => agent_name,
+                transcript => transcript,
+            },
+        )
+    }
+
     /// Render the coalesce hint fragment for batched messages.
     pub fn render_coalesce_hint(
         &self,
@@ -383,7 +791,8 @@ impl PromptEngine {
         )
     }
 
-    /// Render the complete channel system prompt with all dynamic components.
+    /// Render the complete channel system prompt with all dynamic components,
+    /// in the default section layout.
     #[allow(clippy::too_many_arguments)]
     pub fn render_channel_prompt(
         &self,
@@ -397,6 +806,7 @@ impl PromptEngine {
         available_channels: Option<String>,
     ) -> Result<String> {
         self.render_channel_prompt_with_links(
+            &SystemPromptConfig::default(),
             identity_context,
             memory_bulletin,
             skills_prompt,
@@ -449,10 +859,17 @@ impl PromptEngine {
         )
     }
 
-    /// Render the channel system prompt with all dynamic components including org/link context.
+    /// Render the channel system prompt with all dynamic components including
+    /// org/link context.
+    ///
+    /// `layout` picks the sections and their order. Empty sections are left
+    /// out. The `channel` template gets them as `sections`, plus each built-in
+    /// section under its own name for overrides that lay the prompt out
+    /// themselves.
     #[allow(clippy::too_many_arguments)]
     pub fn render_channel_prompt_with_links(
         &self,
+        layout: &SystemPromptConfig,
         identity_context: Option<String>,
         memory_bulletin: Option<String>,
         skills_prompt: Option<String>,
@@ -464,9 +881,34 @@ impl PromptEngine {
         org_context: Option<String>,
         link_context: Option<String>,
     ) -> Result<String> {
+        let mut sections = Vec::with_capacity(layout.sections.len());
+        for name in &layout.sections {
+            let content = match name.as_str() {
+                "identity" => identity_context.clone(),
+                "memory_bulletin" => memory_bulletin.clone(),
+                "instructions" => Some(self.render_static("fragments/channel_instructions")?),
+                "skills" => skills_prompt.clone(),
+                "worker_capabilities" => Some(worker_capabilities.clone()),
+                "available_channels" => available_channels.clone(),
+                "org_context" => org_context.clone(),
+                "link_context" => link_context.clone(),
+                "conversation_context" => conversation_context.clone(),
+                "status" => status_text.clone(),
+                "coalesce_hint" => coalesce_hint.clone(),
+                block => layout.blocks.get(block).cloned(),
+            };
+            if let Some(content) = content.filter(|content| !content.trim().is_empty()) {
+                sections.push(PromptSection {
+                    name: name.clone(),
+                    content,
+                });
+            }
+        }
+
         self.render(
             "channel",
             context! {
+                sections => sections,
                 identity_context => identity_context,
                 memory_bulletin => memory_bulletin,
                 skills_prompt => skills_prompt,
@@ -487,6 +929,13 @@ impl PromptEngine {
     }
 }
 
+/// A non-empty section of the channel system prompt.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PromptSection {
+    pub name: String,
+    pub content: String,
+}
+
 /// Organizational context for an agent — grouped by relationship.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct OrgContext {
@@ -520,6 +969,40 @@ pub struct SkillInfo {
     /// Whether the spawning channel suggested this skill for the current task.
     /// Workers should prioritise suggested skills but may read others too.
     pub suggested: bool,
+    /// Names of the HTTP tools the skill brings.
+    pub tools: Vec<String>,
+}
+
+/// A memory retrieved for the current message, for template rendering.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RelevantMemory {
+    pub memory_type: String,
+    pub content: String,
+}
+
+/// An event logged in the current conversation, for template rendering.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RecentEvent {
+    /// When the event was logged, formatted for display.
+    pub logged_at: String,
+    pub content: String,
+}
+
+/// A knowledge base passage retrieved for the current message, for template
+/// rendering.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RelevantDocument {
+    pub title: String,
+    pub content: String,
+}
+
+/// A sender's profile, for template rendering.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SenderProfile {
+    pub name: String,
+    pub preferred_name: Option<String>,
+    pub timezone: Option<String>,
+    pub preferences: Vec<String>,
 }
 
 /// Information about a channel for template rendering.
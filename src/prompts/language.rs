@@ -0,0 +1,149 @@
+//! Conversation language detection.
+//!
+//! Channels detect the language of a conversation from its first few user
+//! messages by counting common function words. The result is stored on the
+//! channel and selects the language of canned replies and status strings,
+//! and tells the LLM which language to answer in.
+
+/// Metadata key carrying the conversation language to adapters, for
+/// localizing status text.
+pub const METADATA_KEY: &str = "conversation_language";
+
+/// Languages that can be detected, as (ISO 639-1 code, English name).
+pub const SUPPORTED: &[(&str, &str)] = &[
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fr", "French"),
+    ("de", "German"),
+    ("pt", "Portuguese"),
+    ("it", "Italian"),
+    ("nl", "Dutch"),
+];
+
+/// Frequent words that are rare in the other supported languages.
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "you", "what", "with", "this", "that", "have", "for", "it",
+            "can", "how", "my", "was", "not", "of", "to", "please", "thanks", "i'm",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "los", "las", "es", "y", "que", "por", "para", "con", "una", "está", "cómo",
+            "qué", "gracias", "pero", "mi", "tengo", "puedes", "hola", "del", "muy", "también",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "les", "est", "et", "je", "vous", "pour", "avec", "une", "des", "pas", "c'est",
+            "merci", "bonjour", "mon", "dans", "qui", "sur", "comment", "j'ai", "aussi", "très",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "ich", "nicht", "mit", "ein", "eine", "du", "sie",
+            "wie", "danke", "bitte", "auch", "für", "auf", "kannst", "mein", "hallo", "sehr",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "os", "as", "é", "não", "uma", "com", "para", "você", "obrigado", "obrigada", "olá",
+            "meu", "também", "mas", "isso", "está", "muito", "como", "tenho", "pode", "do", "da",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "gli", "è", "non", "che", "sono", "per", "con", "una", "grazie", "ciao", "mio",
+            "anche", "della", "questo", "come", "molto", "ho", "puoi", "sei", "perché", "cosa",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "is", "niet", "ik", "je", "van", "met", "voor", "dat",
+            "dank", "bedankt", "hoe", "mijn", "ook", "zijn", "kun", "hallo", "wat", "graag",
+        ],
+    ),
+];
+
+/// Fewest stopword hits needed before a language is reported.
+const MIN_HITS: usize = 3;
+
+/// The English name of a supported language code.
+pub fn name(code: &str) -> Option<&'static str> {
+    SUPPORTED
+        .iter()
+        .find(|(supported, _)| *supported == code)
+        .map(|(_, name)| *name)
+}
+
+/// Detect the language of `text`. Returns `None` when there's too little
+/// text, or no language clearly leads, so callers can wait for more.
+pub fn detect(text: &str) -> Option<&'static str> {
+    let words: Vec<String> = text
+        .split(|c: char| !(c.is_alphabetic() || c == '\''))
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    let mut scores: Vec<(&'static str, usize)> = STOPWORDS
+        .iter()
+        .map(|(code, stopwords)| {
+            let hits = words
+                .iter()
+                .filter(|word| stopwords.contains(&word.as_str()))
+                .count();
+            (*code, hits)
+        })
+        .collect();
+    scores.sort_by_key(|score| std::cmp::Reverse(score.1));
+
+    let (best, best_hits) = scores[0];
+    let runner_up_hits = scores[1].1;
+    // Short words overlap between languages, so require a clear lead.
+    (best_hits >= MIN_HITS && best_hits * 2 > runner_up_hits * 3).then_some(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_common_languages() {
+        assert_eq!(
+            detect("Hi, can you tell me what the weather is like in Berlin?"),
+            Some("en")
+        );
+        assert_eq!(
+            detect("Hola, ¿puedes decirme qué tiempo hace en Madrid? Gracias"),
+            Some("es")
+        );
+        assert_eq!(
+            detect("Bonjour, est-ce que vous pouvez m'aider avec mon code ? Merci"),
+            Some("fr")
+        );
+        assert_eq!(
+            detect("Hallo, kannst du mir bitte mit der Konfiguration helfen? Danke"),
+            Some("de")
+        );
+    }
+
+    #[test]
+    fn waits_for_more_text() {
+        assert_eq!(detect("ok"), None);
+        assert_eq!(detect("deploy v2.3.1 to prod"), None);
+    }
+
+    #[test]
+    fn names_supported_codes() {
+        assert_eq!(name("pt"), Some("Portuguese"));
+        assert_eq!(name("xx"), None);
+    }
+}
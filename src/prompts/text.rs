@@ -49,6 +49,12 @@ pub fn get(key: &str) -> &'static str {
     lookup(language(), key)
 }
 
+/// Get text for the given key in a specific language, such as a
+/// conversation's detected language. Falls back to English like [`get`].
+pub fn get_for(language: &str, key: &str) -> &'static str {
+    lookup(language, key)
+}
+
 /// Lookup function generated by the macro.
 /// Matches on (language, key) pairs.
 fn lookup(lang: &str, key: &str) -> &'static str {
@@ -64,6 +70,8 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "memory_persistence") => include_str!("../../prompts/en/memory_persistence.md.j2"),
         ("en", "ingestion") => include_str!("../../prompts/en/ingestion.md.j2"),
         ("en", "cortex_chat") => include_str!("../../prompts/en/cortex_chat.md.j2"),
+        ("en", "heartbeat") => include_str!("../../prompts/en/heartbeat.md.j2"),
+        ("en", "feed_digest") => include_str!("../../prompts/en/feed_digest.md.j2"),
 
         // Fragment Templates
         ("en", "fragments/worker_capabilities") => {
@@ -78,6 +86,21 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "fragments/skills_worker") => {
             include_str!("../../prompts/en/fragments/skills_worker.md.j2")
         }
+        ("en", "fragments/worker_workspace") => {
+            include_str!("../../prompts/en/fragments/worker_workspace.md.j2")
+        }
+        ("en", "fragments/recurring_worker") => {
+            include_str!("../../prompts/en/fragments/recurring_worker.md.j2")
+        }
+        ("en", "fragments/channel_instructions") => {
+            include_str!("../../prompts/en/fragments/channel_instructions.md.j2")
+        }
+        ("en", "fragments/plan_mode") => {
+            include_str!("../../prompts/en/fragments/plan_mode.md.j2")
+        }
+        ("en", "fragments/structured_reply") => {
+            include_str!("../../prompts/en/fragments/structured_reply.md.j2")
+        }
         ("en", "fragments/available_channels") => {
             include_str!("../../prompts/en/fragments/available_channels.md.j2")
         }
@@ -86,6 +109,9 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "fragments/system/retrigger") => {
             include_str!("../../prompts/en/fragments/system/retrigger.md.j2")
         }
+        ("en", "fragments/system/branch_results") => {
+            include_str!("../../prompts/en/fragments/system/branch_results.md.j2")
+        }
         ("en", "fragments/system/truncation") => {
             include_str!("../../prompts/en/fragments/system/truncation.md.j2")
         }
@@ -104,12 +130,33 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "fragments/system/profile_synthesis") => {
             include_str!("../../prompts/en/fragments/system/profile_synthesis.md.j2")
         }
+        ("en", "fragments/system/heartbeat_survey") => {
+            include_str!("../../prompts/en/fragments/system/heartbeat_survey.md.j2")
+        }
+        ("en", "fragments/system/feed_items") => {
+            include_str!("../../prompts/en/fragments/system/feed_items.md.j2")
+        }
+        ("en", "fragments/system/reminder_due") => {
+            include_str!("../../prompts/en/fragments/system/reminder_due.md.j2")
+        }
         ("en", "fragments/system/ingestion_chunk") => {
             include_str!("../../prompts/en/fragments/system/ingestion_chunk.md.j2")
         }
         ("en", "fragments/system/history_backfill") => {
             include_str!("../../prompts/en/fragments/system/history_backfill.md.j2")
         }
+        ("en", "fragments/system/channel_title") => {
+            include_str!("../../prompts/en/fragments/system/channel_title.md.j2")
+        }
+        ("en", "fragments/system/channel_relevance") => {
+            include_str!("../../prompts/en/fragments/system/channel_relevance.md.j2")
+        }
+        ("en", "fragments/system/overheard") => {
+            include_str!("../../prompts/en/fragments/system/overheard.md.j2")
+        }
+        ("en", "fragments/system/overheard_summary") => {
+            include_str!("../../prompts/en/fragments/system/overheard_summary.md.j2")
+        }
         ("en", "fragments/system/tool_syntax_correction") => {
             include_str!("../../prompts/en/fragments/system/tool_syntax_correction.md.j2")
         }
@@ -121,6 +168,18 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "fragments/link_context") => {
             include_str!("../../prompts/en/fragments/link_context.md.j2")
         }
+        ("en", "fragments/relevant_memories") => {
+            include_str!("../../prompts/en/fragments/relevant_memories.md.j2")
+        }
+        ("en", "fragments/recent_events") => {
+            include_str!("../../prompts/en/fragments/recent_events.md.j2")
+        }
+        ("en", "fragments/relevant_documents") => {
+            include_str!("../../prompts/en/fragments/relevant_documents.md.j2")
+        }
+        ("en", "fragments/sender_profiles") => {
+            include_str!("../../prompts/en/fragments/sender_profiles.md.j2")
+        }
 
         // Coalesce Hint
         ("en", "fragments/coalesce_hint") => {
@@ -129,6 +188,9 @@ fn lookup(lang: &str, key: &str) -> &'static str {
 
         // Tool Descriptions
         ("en", "tools/reply") => include_str!("../../prompts/en/tools/reply_description.md.j2"),
+        ("en", "tools/structured_reply") => {
+            include_str!("../../prompts/en/tools/structured_reply_description.md.j2")
+        }
         ("en", "tools/branch") => include_str!("../../prompts/en/tools/branch_description.md.j2"),
         ("en", "tools/spawn_worker") => {
             include_str!("../../prompts/en/tools/spawn_worker_description.md.j2")
@@ -137,22 +199,137 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "tools/cancel") => include_str!("../../prompts/en/tools/cancel_description.md.j2"),
         ("en", "tools/skip") => include_str!("../../prompts/en/tools/skip_description.md.j2"),
         ("en", "tools/react") => include_str!("../../prompts/en/tools/react_description.md.j2"),
+        ("en", "tools/edit_message") => {
+            include_str!("../../prompts/en/tools/edit_message_description.md.j2")
+        }
+        ("en", "tools/delete_message") => {
+            include_str!("../../prompts/en/tools/delete_message_description.md.j2")
+        }
+        ("en", "tools/fork_channel") => {
+            include_str!("../../prompts/en/tools/fork_channel_description.md.j2")
+        }
+        ("en", "tools/profile_update") => {
+            include_str!("../../prompts/en/tools/profile_update_description.md.j2")
+        }
+        ("en", "tools/remind_me") => {
+            include_str!("../../prompts/en/tools/remind_me_description.md.j2")
+        }
+        ("en", "tools/usage") => include_str!("../../prompts/en/tools/usage_description.md.j2"),
+        ("en", "tools/workspace_list") => {
+            include_str!("../../prompts/en/tools/workspace_list_description.md.j2")
+        }
+        ("en", "tools/workspace_read") => {
+            include_str!("../../prompts/en/tools/workspace_read_description.md.j2")
+        }
+        ("en", "tools/files_read") => {
+            include_str!("../../prompts/en/tools/files_read_description.md.j2")
+        }
+        ("en", "tools/files_write") => {
+            include_str!("../../prompts/en/tools/files_write_description.md.j2")
+        }
+        ("en", "tools/files_list") => {
+            include_str!("../../prompts/en/tools/files_list_description.md.j2")
+        }
+        ("en", "tools/files_diff") => {
+            include_str!("../../prompts/en/tools/files_diff_description.md.j2")
+        }
+        ("en", "tools/attach_file") => {
+            include_str!("../../prompts/en/tools/attach_file_description.md.j2")
+        }
         ("en", "tools/set_status") => {
             include_str!("../../prompts/en/tools/set_status_description.md.j2")
         }
+        ("en", "tools/sub_branch") => {
+            include_str!("../../prompts/en/tools/sub_branch_description.md.j2")
+        }
+        ("en", "tools/escalate_to_worker") => {
+            include_str!("../../prompts/en/tools/escalate_to_worker_description.md.j2")
+        }
         ("en", "tools/shell") => include_str!("../../prompts/en/tools/shell_description.md.j2"),
         ("en", "tools/file") => include_str!("../../prompts/en/tools/file_description.md.j2"),
         ("en", "tools/exec") => include_str!("../../prompts/en/tools/exec_description.md.j2"),
+        ("en", "tools/calculator") => {
+            include_str!("../../prompts/en/tools/calculator_description.md.j2")
+        }
+        ("en", "tools/calendar_list") => {
+            include_str!("../../prompts/en/tools/calendar_list_description.md.j2")
+        }
+        ("en", "tools/calendar_create") => {
+            include_str!("../../prompts/en/tools/calendar_create_description.md.j2")
+        }
+        ("en", "tools/calendar_delete") => {
+            include_str!("../../prompts/en/tools/calendar_delete_description.md.j2")
+        }
+        ("en", "tools/http_request") => {
+            include_str!("../../prompts/en/tools/http_request_description.md.j2")
+        }
+        ("en", "tools/sql_query") => {
+            include_str!("../../prompts/en/tools/sql_query_description.md.j2")
+        }
+        ("en", "tools/container_run") => {
+            include_str!("../../prompts/en/tools/container_run_description.md.j2")
+        }
         ("en", "tools/browser") => include_str!("../../prompts/en/tools/browser_description.md.j2"),
+        ("en", "tools/web_fetch") => {
+            include_str!("../../prompts/en/tools/web_fetch_description.md.j2")
+        }
+        ("en", "tools/use_skill") => {
+            include_str!("../../prompts/en/tools/use_skill_description.md.j2")
+        }
+        ("en", "tools/read_artifact") => {
+            include_str!("../../prompts/en/tools/read_artifact_description.md.j2")
+        }
         ("en", "tools/web_search") => {
             include_str!("../../prompts/en/tools/web_search_description.md.j2")
         }
+        ("en", "tools/github_list_issues") => {
+            include_str!("../../prompts/en/tools/github_list_issues_description.md.j2")
+        }
+        ("en", "tools/github_list_pulls") => {
+            include_str!("../../prompts/en/tools/github_list_pulls_description.md.j2")
+        }
+        ("en", "tools/github_pr_diff") => {
+            include_str!("../../prompts/en/tools/github_pr_diff_description.md.j2")
+        }
+        ("en", "tools/github_create_issue") => {
+            include_str!("../../prompts/en/tools/github_create_issue_description.md.j2")
+        }
+        ("en", "tools/github_comment") => {
+            include_str!("../../prompts/en/tools/github_comment_description.md.j2")
+        }
+        ("en", "tools/github_search_code") => {
+            include_str!("../../prompts/en/tools/github_search_code_description.md.j2")
+        }
+        ("en", "tools/coolify_list_applications") => {
+            include_str!("../../prompts/en/tools/coolify_list_applications_description.md.j2")
+        }
+        ("en", "tools/coolify_deploy") => {
+            include_str!("../../prompts/en/tools/coolify_deploy_description.md.j2")
+        }
+        ("en", "tools/coolify_deployment_logs") => {
+            include_str!("../../prompts/en/tools/coolify_deployment_logs_description.md.j2")
+        }
+        ("en", "tools/coolify_restart") => {
+            include_str!("../../prompts/en/tools/coolify_restart_description.md.j2")
+        }
         ("en", "tools/memory_save") => {
             include_str!("../../prompts/en/tools/memory_save_description.md.j2")
         }
+        ("en", "tools/memory_save_fact") => {
+            include_str!("../../prompts/en/tools/memory_save_fact_description.md.j2")
+        }
+        ("en", "tools/memory_log_event") => {
+            include_str!("../../prompts/en/tools/memory_log_event_description.md.j2")
+        }
         ("en", "tools/memory_recall") => {
             include_str!("../../prompts/en/tools/memory_recall_description.md.j2")
         }
+        ("en", "tools/knowledge_search") => {
+            include_str!("../../prompts/en/tools/knowledge_search_description.md.j2")
+        }
+        ("en", "tools/knowledge_ingest") => {
+            include_str!("../../prompts/en/tools/knowledge_ingest_description.md.j2")
+        }
         ("en", "tools/memory_delete") => {
             include_str!("../../prompts/en/tools/memory_delete_description.md.j2")
         }
@@ -166,6 +343,12 @@ fn lookup(lang: &str, key: &str) -> &'static str {
             include_str!("../../prompts/en/tools/send_file_description.md.j2")
         }
         ("en", "tools/cron") => include_str!("../../prompts/en/tools/cron_description.md.j2"),
+        ("en", "tools/schedule") => {
+            include_str!("../../prompts/en/tools/schedule_description.md.j2")
+        }
+        ("en", "tools/unschedule") => {
+            include_str!("../../prompts/en/tools/unschedule_description.md.j2")
+        }
         ("en", "tools/send_message_to_another_channel") => {
             include_str!("../../prompts/en/tools/send_message_description.md.j2")
         }
@@ -176,6 +359,388 @@ fn lookup(lang: &str, key: &str) -> &'static str {
             include_str!("../../prompts/en/tools/conclude_link_description.md.j2")
         }
 
+        // Canned Replies (shown to users, so translated)
+        ("en", "replies/budget_exceeded") => "This conversation has reached its spending limit.",
+        ("es", "replies/budget_exceeded") => "Esta conversación ha alcanzado su límite de gasto.",
+        ("fr", "replies/budget_exceeded") => "Cette conversation a atteint sa limite de dépenses.",
+        ("de", "replies/budget_exceeded") => "Dieses Gespräch hat sein Ausgabenlimit erreicht.",
+        ("pt", "replies/budget_exceeded") => "Esta conversa atingiu o limite de gastos.",
+        ("it", "replies/budget_exceeded") => {
+            "Questa conversazione ha raggiunto il limite di spesa."
+        }
+        ("nl", "replies/budget_exceeded") => "Dit gesprek heeft de uitgavenlimiet bereikt.",
+
+        // Admin Command Replies (shown to users, so translated)
+        ("en", "commands/not_allowed") => "Only admins can use commands.",
+        ("es", "commands/not_allowed") => "Solo los administradores pueden usar comandos.",
+        ("fr", "commands/not_allowed") => {
+            "Seuls les administrateurs peuvent utiliser les commandes."
+        }
+        ("de", "commands/not_allowed") => "Nur Admins können Befehle verwenden.",
+        ("pt", "commands/not_allowed") => "Apenas administradores podem usar comandos.",
+        ("it", "commands/not_allowed") => "Solo gli amministratori possono usare i comandi.",
+        ("nl", "commands/not_allowed") => "Alleen beheerders kunnen commando's gebruiken.",
+        ("en", "commands/auth_disabled") => {
+            "Commands are off until the agent has an `[auth]` table naming its admins."
+        }
+        ("es", "commands/auth_disabled") => {
+            "Los comandos están desactivados hasta que el agente tenga una tabla `[auth]` con sus administradores."
+        }
+        ("fr", "commands/auth_disabled") => {
+            "Les commandes sont désactivées tant que l'agent n'a pas de table `[auth]` désignant ses administrateurs."
+        }
+        ("de", "commands/auth_disabled") => {
+            "Befehle sind deaktiviert, bis der Agent eine `[auth]`-Tabelle mit seinen Admins hat."
+        }
+        ("pt", "commands/auth_disabled") => {
+            "Os comandos ficam desativados até o agente ter uma tabela `[auth]` com seus administradores."
+        }
+        ("it", "commands/auth_disabled") => {
+            "I comandi sono disattivati finché l'agente non ha una tabella `[auth]` con i suoi amministratori."
+        }
+        ("nl", "commands/auth_disabled") => {
+            "Commando's staan uit totdat de agent een `[auth]`-tabel met zijn beheerders heeft."
+        }
+        ("en", "commands/failed") => "That command failed. The details are in the log.",
+        ("es", "commands/failed") => "El comando falló. Los detalles están en el registro.",
+        ("fr", "commands/failed") => "La commande a échoué. Les détails sont dans le journal.",
+        ("de", "commands/failed") => "Der Befehl ist fehlgeschlagen. Details stehen im Log.",
+        ("pt", "commands/failed") => "O comando falhou. Os detalhes estão no log.",
+        ("it", "commands/failed") => "Il comando non è riuscito. I dettagli sono nel log.",
+        ("nl", "commands/failed") => "Het commando is mislukt. De details staan in het logboek.",
+        ("en", "commands/status") => {
+            "Replying with {{ model }}. {{ workers }} worker(s) and {{ branches }} branch(es) running."
+        }
+        ("es", "commands/status") => {
+            "Respondiendo con {{ model }}. {{ workers }} worker(s) y {{ branches }} rama(s) en curso."
+        }
+        ("fr", "commands/status") => {
+            "Réponses avec {{ model }}. {{ workers }} worker(s) et {{ branches }} branche(s) en cours."
+        }
+        ("de", "commands/status") => {
+            "Antworte mit {{ model }}. {{ workers }} Worker und {{ branches }} Branch(es) laufen."
+        }
+        ("pt", "commands/status") => {
+            "Respondendo com {{ model }}. {{ workers }} worker(s) e {{ branches }} ramo(s) em execução."
+        }
+        ("it", "commands/status") => {
+            "Rispondo con {{ model }}. {{ workers }} worker e {{ branches }} ramo/i in esecuzione."
+        }
+        ("nl", "commands/status") => {
+            "Antwoorden met {{ model }}. {{ workers }} worker(s) en {{ branches }} branch(es) actief."
+        }
+        ("en", "commands/cancel_usage") => "Usage: !cancel <worker id>",
+        ("es", "commands/cancel_usage") => "Uso: !cancel <id del worker>",
+        ("fr", "commands/cancel_usage") => "Utilisation : !cancel <id du worker>",
+        ("de", "commands/cancel_usage") => "Verwendung: !cancel <Worker-ID>",
+        ("pt", "commands/cancel_usage") => "Uso: !cancel <id do worker>",
+        ("it", "commands/cancel_usage") => "Uso: !cancel <id del worker>",
+        ("nl", "commands/cancel_usage") => "Gebruik: !cancel <worker-id>",
+        ("en", "commands/cancelled") => "Cancelled worker {{ worker_id }}.",
+        ("es", "commands/cancelled") => "Worker {{ worker_id }} cancelado.",
+        ("fr", "commands/cancelled") => "Worker {{ worker_id }} annulé.",
+        ("de", "commands/cancelled") => "Worker {{ worker_id }} abgebrochen.",
+        ("pt", "commands/cancelled") => "Worker {{ worker_id }} cancelado.",
+        ("it", "commands/cancelled") => "Worker {{ worker_id }} annullato.",
+        ("nl", "commands/cancelled") => "Worker {{ worker_id }} geannuleerd.",
+        ("en", "commands/worker_not_found") => "No running worker matches {{ worker_id }}.",
+        ("es", "commands/worker_not_found") => {
+            "Ningún worker en curso coincide con {{ worker_id }}."
+        }
+        ("fr", "commands/worker_not_found") => {
+            "Aucun worker en cours ne correspond à {{ worker_id }}."
+        }
+        ("de", "commands/worker_not_found") => "Kein laufender Worker passt zu {{ worker_id }}.",
+        ("pt", "commands/worker_not_found") => {
+            "Nenhum worker em execução corresponde a {{ worker_id }}."
+        }
+        ("it", "commands/worker_not_found") => {
+            "Nessun worker in esecuzione corrisponde a {{ worker_id }}."
+        }
+        ("nl", "commands/worker_not_found") => {
+            "Geen actieve worker komt overeen met {{ worker_id }}."
+        }
+        ("en", "commands/worker_ambiguous") => {
+            "More than one worker matches {{ worker_id }}. Use more of the ID."
+        }
+        ("es", "commands/worker_ambiguous") => {
+            "Más de un worker coincide con {{ worker_id }}. Usa más caracteres del ID."
+        }
+        ("fr", "commands/worker_ambiguous") => {
+            "Plusieurs workers correspondent à {{ worker_id }}. Utilisez une plus grande partie de l'ID."
+        }
+        ("de", "commands/worker_ambiguous") => {
+            "Mehrere Worker passen zu {{ worker_id }}. Gib mehr von der ID an."
+        }
+        ("pt", "commands/worker_ambiguous") => {
+            "Mais de um worker corresponde a {{ worker_id }}. Use mais do ID."
+        }
+        ("it", "commands/worker_ambiguous") => {
+            "Più worker corrispondono a {{ worker_id }}. Usa una parte più lunga dell'ID."
+        }
+        ("nl", "commands/worker_ambiguous") => {
+            "Meer dan één worker komt overeen met {{ worker_id }}. Gebruik meer van de ID."
+        }
+        ("en", "commands/model_current") => "This conversation uses {{ model }}.",
+        ("es", "commands/model_current") => "Esta conversación usa {{ model }}.",
+        ("fr", "commands/model_current") => "Cette conversation utilise {{ model }}.",
+        ("de", "commands/model_current") => "Dieses Gespräch verwendet {{ model }}.",
+        ("pt", "commands/model_current") => "Esta conversa usa {{ model }}.",
+        ("it", "commands/model_current") => "Questa conversazione usa {{ model }}.",
+        ("nl", "commands/model_current") => "Dit gesprek gebruikt {{ model }}.",
+        ("en", "commands/model_set") => {
+            "This conversation now uses {{ model }}. Send !model reset to go back."
+        }
+        ("es", "commands/model_set") => {
+            "Esta conversación ahora usa {{ model }}. Envía !model reset para volver."
+        }
+        ("fr", "commands/model_set") => {
+            "Cette conversation utilise maintenant {{ model }}. Envoyez !model reset pour revenir."
+        }
+        ("de", "commands/model_set") => {
+            "Dieses Gespräch verwendet jetzt {{ model }}. Sende !model reset, um zurückzukehren."
+        }
+        ("pt", "commands/model_set") => {
+            "Esta conversa agora usa {{ model }}. Envie !model reset para voltar."
+        }
+        ("it", "commands/model_set") => {
+            "Questa conversazione ora usa {{ model }}. Invia !model reset per tornare indietro."
+        }
+        ("nl", "commands/model_set") => {
+            "Dit gesprek gebruikt nu {{ model }}. Stuur !model reset om terug te gaan."
+        }
+        ("en", "commands/model_unknown") => "No configured provider for {{ model }}.",
+        ("es", "commands/model_unknown") => "No hay un proveedor configurado para {{ model }}.",
+        ("fr", "commands/model_unknown") => "Aucun fournisseur configuré pour {{ model }}.",
+        ("de", "commands/model_unknown") => "Kein konfigurierter Anbieter für {{ model }}.",
+        ("pt", "commands/model_unknown") => "Nenhum provedor configurado para {{ model }}.",
+        ("it", "commands/model_unknown") => "Nessun provider configurato per {{ model }}.",
+        ("nl", "commands/model_unknown") => "Geen geconfigureerde provider voor {{ model }}.",
+        ("en", "commands/model_invalid") => "Can't use {{ model }}: {{ error }}",
+        ("es", "commands/model_invalid") => "No se puede usar {{ model }}: {{ error }}",
+        ("fr", "commands/model_invalid") => "Impossible d'utiliser {{ model }} : {{ error }}",
+        ("de", "commands/model_invalid") => "{{ model }} kann nicht verwendet werden: {{ error }}",
+        ("pt", "commands/model_invalid") => "Não é possível usar {{ model }}: {{ error }}",
+        ("it", "commands/model_invalid") => "Impossibile usare {{ model }}: {{ error }}",
+        ("nl", "commands/model_invalid") => "Kan {{ model }} niet gebruiken: {{ error }}",
+        ("en", "commands/forgot") => "Conversation history cleared.",
+        ("es", "commands/forgot") => "Historial de la conversación borrado.",
+        ("fr", "commands/forgot") => "Historique de la conversation effacé.",
+        ("de", "commands/forgot") => "Gesprächsverlauf gelöscht.",
+        ("pt", "commands/forgot") => "Histórico da conversa apagado.",
+        ("it", "commands/forgot") => "Cronologia della conversazione cancellata.",
+        ("nl", "commands/forgot") => "Gespreksgeschiedenis gewist.",
+        ("en", "commands/usage") => {
+            "{{ calls }} LLM calls, {{ input_tokens }} input and {{ output_tokens }} output tokens, about ${{ cost }}."
+        }
+        ("es", "commands/usage") => {
+            "{{ calls }} llamadas al LLM, {{ input_tokens }} tokens de entrada y {{ output_tokens }} de salida, unos ${{ cost }}."
+        }
+        ("fr", "commands/usage") => {
+            "{{ calls }} appels au LLM, {{ input_tokens }} tokens en entrée et {{ output_tokens }} en sortie, environ {{ cost }} $."
+        }
+        ("de", "commands/usage") => {
+            "{{ calls }} LLM-Aufrufe, {{ input_tokens }} Eingabe- und {{ output_tokens }} Ausgabe-Tokens, etwa ${{ cost }}."
+        }
+        ("pt", "commands/usage") => {
+            "{{ calls }} chamadas ao LLM, {{ input_tokens }} tokens de entrada e {{ output_tokens }} de saída, cerca de ${{ cost }}."
+        }
+        ("it", "commands/usage") => {
+            "{{ calls }} chiamate all'LLM, {{ input_tokens }} token in input e {{ output_tokens }} in output, circa ${{ cost }}."
+        }
+        ("nl", "commands/usage") => {
+            "{{ calls }} LLM-aanroepen, {{ input_tokens }} invoer- en {{ output_tokens }} uitvoertokens, ongeveer ${{ cost }}."
+        }
+        ("en", "commands/usage_budget") => "${{ remaining }} of the ${{ budget }} budget left.",
+        ("es", "commands/usage_budget") => {
+            "Quedan ${{ remaining }} de un presupuesto de ${{ budget }}."
+        }
+        ("fr", "commands/usage_budget") => {
+            "Il reste {{ remaining }} $ sur un budget de {{ budget }} $."
+        }
+        ("de", "commands/usage_budget") => "${{ remaining }} von ${{ budget }} Budget übrig.",
+        ("pt", "commands/usage_budget") => {
+            "Restam ${{ remaining }} de um orçamento de ${{ budget }}."
+        }
+        ("it", "commands/usage_budget") => {
+            "Restano ${{ remaining }} su un budget di ${{ budget }}."
+        }
+        ("nl", "commands/usage_budget") => {
+            "${{ remaining }} van het budget van ${{ budget }} over."
+        }
+        ("en", "commands/plan_not_allowed") => "You're not allowed to approve plans.",
+        ("es", "commands/plan_not_allowed") => "No tienes permiso para aprobar planes.",
+        ("fr", "commands/plan_not_allowed") => "Vous n'êtes pas autorisé à approuver des plans.",
+        ("de", "commands/plan_not_allowed") => "Du darfst keine Pläne freigeben.",
+        ("pt", "commands/plan_not_allowed") => "Você não tem permissão para aprovar planos.",
+        ("it", "commands/plan_not_allowed") => "Non hai il permesso di approvare piani.",
+        ("nl", "commands/plan_not_allowed") => "Je mag geen plannen goedkeuren.",
+        ("en", "commands/plan_none") => "There's no plan waiting for approval.",
+        ("es", "commands/plan_none") => "No hay ningún plan esperando aprobación.",
+        ("fr", "commands/plan_none") => "Aucun plan n'attend d'approbation.",
+        ("de", "commands/plan_none") => "Es wartet kein Plan auf Freigabe.",
+        ("pt", "commands/plan_none") => "Não há nenhum plano aguardando aprovação.",
+        ("it", "commands/plan_none") => "Non c'è nessun piano in attesa di approvazione.",
+        ("nl", "commands/plan_none") => "Er wacht geen plan op goedkeuring.",
+        ("en", "commands/plan_running") => "Running the approved plan ({{ steps }} steps).",
+        ("es", "commands/plan_running") => "Ejecutando el plan aprobado ({{ steps }} pasos).",
+        ("fr", "commands/plan_running") => "Exécution du plan approuvé ({{ steps }} étapes).",
+        ("de", "commands/plan_running") => {
+            "Führe den freigegebenen Plan aus ({{ steps }} Schritte)."
+        }
+        ("pt", "commands/plan_running") => "Executando o plano aprovado ({{ steps }} passos).",
+        ("it", "commands/plan_running") => "Eseguo il piano approvato ({{ steps }} passaggi).",
+        ("nl", "commands/plan_running") => {
+            "Het goedgekeurde plan wordt uitgevoerd ({{ steps }} stappen)."
+        }
+        ("en", "commands/plan_failed") => "Couldn't run the plan. Try again in a moment.",
+        ("es", "commands/plan_failed") => {
+            "No se pudo ejecutar el plan. Inténtalo de nuevo en un momento."
+        }
+        ("fr", "commands/plan_failed") => {
+            "Impossible d'exécuter le plan. Réessayez dans un instant."
+        }
+        ("de", "commands/plan_failed") => {
+            "Der Plan konnte nicht ausgeführt werden. Versuch es gleich noch einmal."
+        }
+        ("pt", "commands/plan_failed") => {
+            "Não foi possível executar o plano. Tente novamente em instantes."
+        }
+        ("it", "commands/plan_failed") => "Impossibile eseguire il piano. Riprova tra un momento.",
+        ("nl", "commands/plan_failed") => {
+            "Het plan kon niet worden uitgevoerd. Probeer het zo opnieuw."
+        }
+        ("en", "commands/approval_request") => {
+            "Worker {{ worker_id }} wants to run `{{ tool_name }}` with:\n```\n{{ args }}\n```\nReply `approve` to run it or `deny` to stop the worker. With several requests waiting, add the ID: `approve {{ approval_id }}`."
+        }
+        ("es", "commands/approval_request") => {
+            "El worker {{ worker_id }} quiere ejecutar `{{ tool_name }}` con:\n```\n{{ args }}\n```\nResponde `approve` para ejecutarlo o `deny` para detener el worker. Si hay varias solicitudes, añade el ID: `approve {{ approval_id }}`."
+        }
+        ("fr", "commands/approval_request") => {
+            "Le worker {{ worker_id }} veut exécuter `{{ tool_name }}` avec :\n```\n{{ args }}\n```\nRépondez `approve` pour l'exécuter ou `deny` pour arrêter le worker. Si plusieurs demandes attendent, ajoutez l'ID : `approve {{ approval_id }}`."
+        }
+        ("de", "commands/approval_request") => {
+            "Worker {{ worker_id }} möchte `{{ tool_name }}` ausführen mit:\n```\n{{ args }}\n```\nAntworte mit `approve`, um es auszuführen, oder mit `deny`, um den Worker zu stoppen. Wenn mehrere Anfragen warten, hänge die ID an: `approve {{ approval_id }}`."
+        }
+        ("pt", "commands/approval_request") => {
+            "O worker {{ worker_id }} quer executar `{{ tool_name }}` com:\n```\n{{ args }}\n```\nResponda `approve` para executar ou `deny` para parar o worker. Com várias solicitações pendentes, adicione o ID: `approve {{ approval_id }}`."
+        }
+        ("it", "commands/approval_request") => {
+            "Il worker {{ worker_id }} vuole eseguire `{{ tool_name }}` con:\n```\n{{ args }}\n```\nRispondi `approve` per eseguirlo o `deny` per fermare il worker. Con più richieste in attesa, aggiungi l'ID: `approve {{ approval_id }}`."
+        }
+        ("nl", "commands/approval_request") => {
+            "Worker {{ worker_id }} wil `{{ tool_name }}` uitvoeren met:\n```\n{{ args }}\n```\nAntwoord `approve` om het uit te voeren of `deny` om de worker te stoppen. Wachten er meerdere verzoeken, voeg dan de ID toe: `approve {{ approval_id }}`."
+        }
+        ("en", "commands/approval_approved") => "Approved. Running `{{ tool_name }}`.",
+        ("es", "commands/approval_approved") => "Aprobado. Ejecutando `{{ tool_name }}`.",
+        ("fr", "commands/approval_approved") => "Approuvé. Exécution de `{{ tool_name }}`.",
+        ("de", "commands/approval_approved") => "Freigegeben. Führe `{{ tool_name }}` aus.",
+        ("pt", "commands/approval_approved") => "Aprovado. Executando `{{ tool_name }}`.",
+        ("it", "commands/approval_approved") => "Approvato. Eseguo `{{ tool_name }}`.",
+        ("nl", "commands/approval_approved") => "Goedgekeurd. `{{ tool_name }}` wordt uitgevoerd.",
+        ("en", "commands/approval_denied") => {
+            "Denied. `{{ tool_name }}` didn't run and worker {{ worker_id }} is stopping."
+        }
+        ("es", "commands/approval_denied") => {
+            "Denegado. `{{ tool_name }}` no se ejecutó y el worker {{ worker_id }} se detiene."
+        }
+        ("fr", "commands/approval_denied") => {
+            "Refusé. `{{ tool_name }}` n'a pas été exécuté et le worker {{ worker_id }} s'arrête."
+        }
+        ("de", "commands/approval_denied") => {
+            "Abgelehnt. `{{ tool_name }}` wurde nicht ausgeführt und Worker {{ worker_id }} stoppt."
+        }
+        ("pt", "commands/approval_denied") => {
+            "Negado. `{{ tool_name }}` não foi executado e o worker {{ worker_id }} está parando."
+        }
+        ("it", "commands/approval_denied") => {
+            "Negato. `{{ tool_name }}` non è stato eseguito e il worker {{ worker_id }} si ferma."
+        }
+        ("nl", "commands/approval_denied") => {
+            "Geweigerd. `{{ tool_name }}` is niet uitgevoerd en worker {{ worker_id }} stopt."
+        }
+        ("en", "commands/approval_not_allowed") => "You're not allowed to approve tool calls.",
+        ("es", "commands/approval_not_allowed") => {
+            "No tienes permiso para aprobar llamadas a herramientas."
+        }
+        ("fr", "commands/approval_not_allowed") => {
+            "Vous n'êtes pas autorisé à approuver des appels d'outils."
+        }
+        ("de", "commands/approval_not_allowed") => "Du darfst keine Tool-Aufrufe freigeben.",
+        ("pt", "commands/approval_not_allowed") => {
+            "Você não tem permissão para aprovar chamadas de ferramentas."
+        }
+        ("it", "commands/approval_not_allowed") => {
+            "Non hai il permesso di approvare chiamate agli strumenti."
+        }
+        ("nl", "commands/approval_not_allowed") => "Je mag geen toolaanroepen goedkeuren.",
+        ("en", "commands/approval_auth_disabled") => {
+            "Approvals are off until the agent has an `[auth]` table naming who may give them."
+        }
+        ("es", "commands/approval_auth_disabled") => {
+            "Las aprobaciones están desactivadas hasta que el agente tenga una tabla `[auth]` que indique quién puede darlas."
+        }
+        ("fr", "commands/approval_auth_disabled") => {
+            "Les approbations sont désactivées tant que l'agent n'a pas de table `[auth]` désignant qui peut les donner."
+        }
+        ("de", "commands/approval_auth_disabled") => {
+            "Freigaben sind deaktiviert, bis der Agent eine `[auth]`-Tabelle hat, die festlegt, wer sie erteilen darf."
+        }
+        ("pt", "commands/approval_auth_disabled") => {
+            "As aprovações ficam desativadas até o agente ter uma tabela `[auth]` indicando quem pode dá-las."
+        }
+        ("it", "commands/approval_auth_disabled") => {
+            "Le approvazioni sono disattivate finché l'agente non ha una tabella `[auth]` che indichi chi può darle."
+        }
+        ("nl", "commands/approval_auth_disabled") => {
+            "Goedkeuringen staan uit totdat de agent een `[auth]`-tabel heeft die aangeeft wie ze mag geven."
+        }
+        ("en", "commands/approval_none") => {
+            "There's no tool call with that ID waiting for approval."
+        }
+        ("es", "commands/approval_none") => {
+            "No hay ninguna llamada con ese ID esperando aprobación."
+        }
+        ("fr", "commands/approval_none") => "Aucun appel avec cet ID n'attend d'approbation.",
+        ("de", "commands/approval_none") => "Kein Tool-Aufruf mit dieser ID wartet auf Freigabe.",
+        ("pt", "commands/approval_none") => {
+            "Não há nenhuma chamada com esse ID aguardando aprovação."
+        }
+        ("it", "commands/approval_none") => {
+            "Nessuna chiamata con questo ID è in attesa di approvazione."
+        }
+        ("nl", "commands/approval_none") => "Er wacht geen toolaanroep met die ID op goedkeuring.",
+
+        // Status Strings (typing indicators on platforms that show text)
+        ("en", "status/thinking") => "Thinking…",
+        ("es", "status/thinking") => "Pensando…",
+        ("fr", "status/thinking") => "Réflexion…",
+        ("de", "status/thinking") => "Denke nach…",
+        ("pt", "status/thinking") => "Pensando…",
+        ("it", "status/thinking") => "Sto pensando…",
+        ("nl", "status/thinking") => "Aan het nadenken…",
+        ("en", "status/working") => "Working…",
+        ("es", "status/working") => "Trabajando…",
+        ("fr", "status/working") => "Au travail…",
+        ("de", "status/working") => "Arbeite…",
+        ("pt", "status/working") => "Trabalhando…",
+        ("it", "status/working") => "Sto lavorando…",
+        ("nl", "status/working") => "Bezig…",
+        ("en", "status/writing") => "Writing…",
+        ("es", "status/writing") => "Escribiendo…",
+        ("fr", "status/writing") => "Rédaction…",
+        ("de", "status/writing") => "Schreibe…",
+        ("pt", "status/writing") => "Escrevendo…",
+        ("it", "status/writing") => "Sto scrivendo…",
+        ("nl", "status/writing") => "Aan het schrijven…",
+        ("en", "status/waiting_on_workers") => "Waiting on background work…",
+        ("es", "status/waiting_on_workers") => "Esperando tareas en segundo plano…",
+        ("fr", "status/waiting_on_workers") => "En attente de tâches en arrière-plan…",
+        ("de", "status/waiting_on_workers") => "Warte auf Hintergrundaufgaben…",
+        ("pt", "status/waiting_on_workers") => "Aguardando tarefas em segundo plano…",
+        ("it", "status/waiting_on_workers") => "In attesa di attività in background…",
+        ("nl", "status/waiting_on_workers") => "Wachten op achtergrondtaken…",
+
         // Fallback: unknown language or key -> try English
         (lang, key) if lang != "en" => {
             tracing::warn!(
@@ -0,0 +1,465 @@
+//! Reminders set with the `remind_me` tool.
+//!
+//! A reminder is stored with the conversation it was set in and the metadata
+//! of the message that set it. When it falls due, the reminder loop injects a
+//! synthetic [`InboundMessage`] into that conversation on behalf of the
+//! original sender. The channel treats it like any other message from them,
+//! so the reminder the user gets is written by the agent with the
+//! conversation in mind rather than posted from a template. One-off reminders
+//! are deleted once delivered; recurring ones are moved to their next
+//! occurrence.
+
+use crate::cron::CronExpression;
+use crate::error::Result;
+use crate::prompts::PromptEngine;
+use crate::{AgentDeps, InboundMessage};
+
+use anyhow::Context as _;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use sqlx::{Row as _, SqlitePool};
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Metadata key marking an inbound message as a due reminder. Holds the
+/// reminder's ID.
+pub const REMINDER_METADATA_KEY: &str = "reminder_id";
+
+/// How often the reminder loop checks for due reminders.
+const POLL_INTERVAL_SECS: u64 = 15;
+
+/// A reminder delivered more than this long after its time says so.
+const LATE_AFTER_SECS: i64 = 120;
+
+/// Most reminders delivered in one tick.
+const DELIVERY_BATCH: i64 = 50;
+
+/// A stored reminder.
+#[derive(Debug, Clone)]
+pub struct Reminder {
+    pub id: String,
+    pub conversation_id: String,
+    /// Messaging adapter the reminder is delivered through.
+    pub source: String,
+    /// Who asked for the reminder.
+    pub sender_id: String,
+    pub sender_name: Option<String>,
+    /// What to remind them of, as they put it.
+    pub message: String,
+    /// Next delivery.
+    pub fire_at: DateTime<Utc>,
+    /// Set for recurring reminders.
+    pub cron_expr: Option<String>,
+    /// IANA timezone `cron_expr` is read in and times are shown in.
+    pub timezone: Option<String>,
+    /// Metadata of the message that set the reminder, which the adapter needs
+    /// to post back into the conversation.
+    pub metadata: HashMap<String, serde_json::Value>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl Reminder {
+    /// The reminder's timezone, UTC when unset or unknown.
+    pub fn tz(&self) -> Tz {
+        self.timezone
+            .as_deref()
+            .and_then(|timezone| timezone.parse().ok())
+            .unwrap_or(chrono_tz::UTC)
+    }
+
+    /// When a recurring reminder fires next after `after`. `None` for one-off
+    /// reminders and schedules with no further occurrence.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let expression = CronExpression::parse(self.cron_expr.as_deref()?).ok()?;
+        expression
+            .next_after(&after.with_timezone(&self.tz()))
+            .map(|next| next.with_timezone(&Utc))
+    }
+
+    /// A time formatted in the reminder's timezone, e.g.
+    /// "Mon 2026-10-19 09:00 Europe/Budapest".
+    pub fn format_time(&self, time: DateTime<Utc>) -> String {
+        let tz = self.tz();
+        format!(
+            "{} {tz}",
+            time.with_timezone(&tz).format("%a %Y-%m-%d %H:%M")
+        )
+    }
+
+    /// The message that delivers this reminder to its conversation, sent as
+    /// if by the person who set it. `next` is the following occurrence of a
+    /// recurring reminder.
+    pub fn due_message(
+        &self,
+        deps: &AgentDeps,
+        prompt_engine: &PromptEngine,
+        now: DateTime<Utc>,
+        next: Option<DateTime<Utc>>,
+    ) -> Result<InboundMessage> {
+        let requester = self.sender_name.as_deref().unwrap_or(&self.sender_id);
+        let set_at = self
+            .created_at
+            .map(|created_at| self.format_time(created_at));
+        let delay = (now - self.fire_at).num_seconds();
+        let late = (delay > LATE_AFTER_SECS).then(|| describe_delay(delay));
+        let next = next.map(|next| self.format_time(next));
+        let content = prompt_engine.render_system_reminder_due(
+            requester,
+            &self.message,
+            set_at.as_deref(),
+            late.as_deref(),
+            next.as_deref(),
+        )?;
+
+        // Reply context belongs to the message that set the reminder, not to
+        // this one.
+        let mut metadata: HashMap<_, _> = self
+            .metadata
+            .iter()
+            .filter(|(key, _)| !key.starts_with("reply_to_"))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        metadata.insert(REMINDER_METADATA_KEY.into(), self.id.clone().into());
+
+        Ok(InboundMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            source: self.source.clone(),
+            conversation_id: self.conversation_id.clone(),
+            sender_id: self.sender_id.clone(),
+            agent_id: Some(deps.agent_id.clone()),
+            content: crate::MessageContent::Text(content),
+            timestamp: now,
+            metadata,
+            formatted_author: Some("[reminder]".into()),
+        })
+    }
+}
+
+/// Whether an inbound message is a due reminder rather than something a
+/// user sent.
+pub fn is_reminder(message: &InboundMessage) -> bool {
+    message.metadata.contains_key(REMINDER_METADATA_KEY)
+}
+
+/// "12 minutes", "3 hours", "2 days".
+fn describe_delay(seconds: i64) -> String {
+    let minutes = seconds / 60;
+    if minutes < 120 {
+        format!("{minutes} minutes")
+    } else if minutes < 48 * 60 {
+        format!("{} hours", minutes / 60)
+    } else {
+        format!("{} days", minutes / (24 * 60))
+    }
+}
+
+/// Reads and writes reminders in SQLite.
+#[derive(Debug, Clone)]
+pub struct ReminderStore {
+    pool: SqlitePool,
+}
+
+impl ReminderStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Save a new reminder.
+    pub async fn insert(&self, reminder: &Reminder) -> Result<()> {
+        let metadata =
+            serde_json::to_string(&reminder.metadata).context("failed to encode metadata")?;
+        sqlx::query(
+            "INSERT INTO reminders \
+                 (id, conversation_id, source, sender_id, sender_name, message, fire_at, \
+                  cron_expr, timezone, metadata) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&reminder.id)
+        .bind(&reminder.conversation_id)
+        .bind(&reminder.source)
+        .bind(&reminder.sender_id)
+        .bind(&reminder.sender_name)
+        .bind(&reminder.message)
+        .bind(reminder.fire_at.timestamp())
+        .bind(&reminder.cron_expr)
+        .bind(&reminder.timezone)
+        .bind(&metadata)
+        .execute(&self.pool)
+        .await
+        .context("failed to save reminder")?;
+
+        Ok(())
+    }
+
+    /// The reminders set in a conversation, soonest first.
+    pub async fn list_for_conversation(&self, conversation_id: &str) -> Result<Vec<Reminder>> {
+        let rows = sqlx::query(
+            "SELECT * FROM reminders WHERE conversation_id = ? ORDER BY fire_at ASC, id ASC",
+        )
+        .bind(conversation_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to load reminders")?;
+
+        Ok(rows.into_iter().map(row_to_reminder).collect())
+    }
+
+    /// How many reminders are set in a conversation.
+    pub async fn count_for_conversation(&self, conversation_id: &str) -> Result<i64> {
+        let count = sqlx::query_scalar("SELECT COUNT(*) FROM reminders WHERE conversation_id = ?")
+            .bind(conversation_id)
+            .fetch_one(&self.pool)
+            .await
+            .context("failed to count reminders")?;
+
+        Ok(count)
+    }
+
+    /// Delete a reminder, but only from the given conversation. Returns
+    /// whether one was deleted.
+    pub async fn delete_in_conversation(&self, conversation_id: &str, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM reminders WHERE id = ? AND conversation_id = ?")
+            .bind(id)
+            .bind(conversation_id)
+            .execute(&self.pool)
+            .await
+            .context("failed to delete reminder")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Reminders due at `now`, oldest first.
+    pub async fn due(&self, now: DateTime<Utc>, limit: i64) -> Result<Vec<Reminder>> {
+        let rows =
+            sqlx::query("SELECT * FROM reminders WHERE fire_at <= ? ORDER BY fire_at ASC LIMIT ?")
+                .bind(now.timestamp())
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await
+                .context("failed to load due reminders")?;
+
+        Ok(rows.into_iter().map(row_to_reminder).collect())
+    }
+
+    /// Move a recurring reminder to its next occurrence.
+    pub async fn reschedule(&self, id: &str, fire_at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE reminders SET fire_at = ? WHERE id = ?")
+            .bind(fire_at.timestamp())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("failed to reschedule reminder")?;
+
+        Ok(())
+    }
+
+    /// Delete a reminder.
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM reminders WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("failed to delete reminder")?;
+
+        Ok(())
+    }
+}
+
+fn row_to_reminder(row: sqlx::sqlite::SqliteRow) -> Reminder {
+    let metadata: String = row.try_get("metadata").unwrap_or_else(|_| "{}".to_string());
+    let fire_at: i64 = row.try_get("fire_at").unwrap_or_default();
+
+    Reminder {
+        id: row.try_get("id").unwrap_or_default(),
+        conversation_id: row.try_get("conversation_id").unwrap_or_default(),
+        source: row.try_get("source").unwrap_or_default(),
+        sender_id: row.try_get("sender_id").unwrap_or_default(),
+        sender_name: row.try_get("sender_name").ok().flatten(),
+        message: row.try_get("message").unwrap_or_default(),
+        fire_at: DateTime::from_timestamp(fire_at, 0).unwrap_or_default(),
+        cron_expr: row.try_get("cron_expr").ok().flatten(),
+        timezone: row.try_get("timezone").ok().flatten(),
+        metadata: serde_json::from_str(&metadata).unwrap_or_default(),
+        created_at: row.try_get("created_at").ok().flatten(),
+    }
+}
+
+/// Spawn the reminder loop for an agent.
+///
+/// Due reminders are injected through the messaging manager like any inbound
+/// message. A reminder that can't be delivered is left in place and retried on
+/// the next tick.
+pub fn spawn_reminder_loop(deps: AgentDeps) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let store = ReminderStore::new(deps.sqlite_pool.clone());
+        loop {
+            deliver_due_reminders(&deps, &store).await;
+            tokio::time::sleep(Duration::from_secs(POLL_INTERVAL_SECS)).await;
+        }
+    })
+}
+
+#[tracing::instrument(skip(deps, store), fields(agent_id = %deps.agent_id))]
+async fn deliver_due_reminders(deps: &AgentDeps, store: &ReminderStore) {
+    let Some(messaging_manager) = deps.messaging_manager.as_ref() else {
+        return;
+    };
+    let now = Utc::now();
+    let due = match store.due(now, DELIVERY_BATCH).await {
+        Ok(due) => due,
+        Err(error) => {
+            tracing::warn!(%error, "failed to load due reminders");
+            return;
+        }
+    };
+    if due.is_empty() {
+        return;
+    }
+
+    let prompt_engine = deps.runtime_config.prompts.load();
+    for reminder in due {
+        // A recurring reminder that was missed, e.g. while the agent was
+        // down, is delivered once and then moves past now.
+        let next = reminder.next_after(now);
+        let message = match reminder.due_message(deps, &prompt_engine, now, next) {
+            Ok(message) => message,
+            Err(error) => {
+                tracing::warn!(%error, reminder_id = %reminder.id, "failed to render reminder");
+                continue;
+            }
+        };
+        if let Err(error) = messaging_manager.inject_message(message).await {
+            tracing::warn!(%error, reminder_id = %reminder.id, "failed to deliver reminder");
+            return;
+        }
+        tracing::info!(
+            reminder_id = %reminder.id,
+            conversation_id = %reminder.conversation_id,
+            "reminder delivered"
+        );
+
+        let result = match next {
+            Some(next) => store.reschedule(&reminder.id, next).await,
+            None => store.delete(&reminder.id).await,
+        };
+        if let Err(error) = result {
+            tracing::warn!(%error, reminder_id = %reminder.id, "failed to update delivered reminder");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone as _;
+
+    async fn store() -> ReminderStore {
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .in_memory(true)
+            .create_if_missing(true);
+        let pool = sqlx::pool::PoolOptions::<sqlx::Sqlite>::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("in-memory SQLite");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+        ReminderStore::new(pool)
+    }
+
+    fn reminder(id: &str, conversation_id: &str, fire_at: DateTime<Utc>) -> Reminder {
+        Reminder {
+            id: id.into(),
+            conversation_id: conversation_id.into(),
+            source: "discord".into(),
+            sender_id: "42".into(),
+            sender_name: Some("Andy".into()),
+            message: "water the plants".into(),
+            fire_at,
+            cron_expr: None,
+            timezone: None,
+            metadata: HashMap::from([("discord_channel_id".into(), serde_json::json!(7))]),
+            created_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn due_reminders_are_returned_and_deleted_per_conversation() {
+        let store = store().await;
+        let now = Utc.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap();
+        store
+            .insert(&reminder(
+                "a",
+                "discord:1",
+                now - chrono::Duration::minutes(1),
+            ))
+            .await
+            .unwrap();
+        store
+            .insert(&reminder(
+                "b",
+                "discord:1",
+                now + chrono::Duration::hours(1),
+            ))
+            .await
+            .unwrap();
+        store
+            .insert(&reminder("c", "discord:2", now))
+            .await
+            .unwrap();
+
+        let due = store.due(now, 10).await.unwrap();
+        let ids: Vec<_> = due.iter().map(|reminder| reminder.id.as_str()).collect();
+        assert_eq!(ids, ["a", "c"]);
+        assert_eq!(due[0].metadata["discord_channel_id"], 7);
+        assert_eq!(due[0].fire_at, now - chrono::Duration::minutes(1));
+
+        assert!(
+            !store
+                .delete_in_conversation("discord:2", "a")
+                .await
+                .unwrap()
+        );
+        assert!(
+            store
+                .delete_in_conversation("discord:1", "a")
+                .await
+                .unwrap()
+        );
+        assert_eq!(store.count_for_conversation("discord:1").await.unwrap(), 1);
+
+        store
+            .reschedule("c", now + chrono::Duration::days(1))
+            .await
+            .unwrap();
+        assert!(store.due(now, 10).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn recurring_reminders_follow_their_timezone() {
+        let mut recurring = reminder("a", "discord:1", Utc::now());
+        recurring.cron_expr = Some("0 9 * * 1".into());
+        recurring.timezone = Some("Europe/Budapest".into());
+
+        let sunday = Utc.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+        let next = recurring.next_after(sunday).unwrap();
+        assert_eq!(next, Utc.with_ymd_and_hms(2026, 10, 19, 7, 0, 0).unwrap());
+        assert_eq!(
+            recurring.format_time(next),
+            "Mon 2026-10-19 09:00 Europe/Budapest"
+        );
+
+        let one_off = reminder("b", "discord:1", Utc::now());
+        assert_eq!(one_off.next_after(sunday), None);
+    }
+
+    #[test]
+    fn delays_are_described_in_the_largest_sensible_unit() {
+        assert_eq!(describe_delay(5 * 60), "5 minutes");
+        assert_eq!(describe_delay(3 * 3600 + 59), "3 hours");
+        assert_eq!(describe_delay(3 * 86400), "3 days");
+    }
+}
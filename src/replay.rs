@@ -0,0 +1,635 @@
+//! Record and replay LLM turns for regression testing (`replay` feature).
+//!
+//! A [`ReplaySession`] in recording mode captures every completion request
+//! and response, and every tool result, made inside its scope into a
+//! [`Cassette`]. A session in replaying mode serves a cassette back: each
+//! completion request is checked against the recorded one and answered with
+//! the recorded response, and tool calls get their recorded results without
+//! running. Changes to prompt assembly, history handling, or tool
+//! registration show up as [`Divergence`]s instead of silently changing
+//! what the model sees, and no provider is called.
+//!
+//! Sessions are task-local. Wrap the work to capture in
+//! [`ReplaySession::scope`]; branches and workers spawned from inside run on
+//! their own tasks and are not captured. With `SPACEBOT_REPLAY_DIR` set,
+//! channels record each turn to `{dir}/{channel_id}/{timestamp}.json`.
+
+use crate::llm::model::RawResponse;
+
+use rig::completion::{self, CompletionError, CompletionRequest};
+use rig::message::{AssistantContent, Message};
+use rig::one_or_many::OneOrMany;
+use serde::{Deserialize, Serialize};
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Current cassette format version.
+pub const CASSETTE_VERSION: u32 = 1;
+
+/// Environment variable naming the directory channels record turns into.
+pub const RECORD_DIR_ENV: &str = "SPACEBOT_REPLAY_DIR";
+
+/// Tools that run for real during replay by default. They only act on the
+/// channel itself (the reply is what a regression test checks), and their
+/// results are still compared against the recording.
+pub const DEFAULT_LIVE_TOOLS: &[&str] = &["reply", "skip", "react"];
+
+tokio::task_local! {
+    static SESSION: ReplaySession;
+}
+
+/// The session for the current task, if any.
+pub fn current() -> Option<ReplaySession> {
+    SESSION.try_with(Clone::clone).ok()
+}
+
+/// The directory channels record turns into, from `SPACEBOT_REPLAY_DIR`.
+pub fn record_dir() -> Option<PathBuf> {
+    std::env::var_os(RECORD_DIR_ENV)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Where to record a turn for `channel_id` under `dir`.
+pub fn recording_path(dir: &Path, channel_id: &str) -> PathBuf {
+    let channel_dir: String = channel_id
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ");
+    dir.join(channel_dir).join(format!("{timestamp}.json"))
+}
+
+/// Records a channel turn to `SPACEBOT_REPLAY_DIR`. Does nothing when the
+/// variable is unset, or when the turn already runs inside a session (a
+/// replay test, say).
+pub struct TurnRecorder {
+    recording: Option<(ReplaySession, PathBuf)>,
+}
+
+impl TurnRecorder {
+    pub fn start(channel_id: &str) -> Self {
+        let recording = record_dir()
+            .filter(|_| current().is_none())
+            .map(|dir| (ReplaySession::record(), recording_path(&dir, channel_id)));
+        Self { recording }
+    }
+
+    /// Run part of the turn inside the recording.
+    pub async fn run<F: std::future::IntoFuture>(&self, future: F) -> F::Output {
+        match &self.recording {
+            Some((session, _)) => session.scope(future.into_future()).await,
+            None => future.await,
+        }
+    }
+
+    /// Write the cassette, if anything was recorded.
+    pub fn finish(self) {
+        let Some((session, path)) = self.recording else {
+            return;
+        };
+        let cassette = session.cassette();
+        if cassette.interactions.is_empty() {
+            return;
+        }
+        match cassette.save(&path) {
+            Ok(()) => tracing::debug!(path = %path.display(), "recorded turn"),
+            Err(error) => tracing::warn!(%error, path = %path.display(), "failed to record turn"),
+        }
+    }
+}
+
+/// A recorded turn.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub version: u32,
+    /// Completions and tool results, in the order they happened.
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Interaction {
+    Completion {
+        model: String,
+        request: RecordedRequest,
+        response: RecordedResponse,
+    },
+    Tool {
+        name: String,
+        args: String,
+        result: String,
+    },
+}
+
+/// The parts of a completion request a refactor can change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub preamble: Option<String>,
+    /// The history, ending with the prompt.
+    pub history: Vec<Message>,
+    /// Names of the tools offered, sorted.
+    pub tools: Vec<String>,
+}
+
+impl RecordedRequest {
+    pub fn from_request(request: &CompletionRequest) -> Self {
+        let mut tools: Vec<String> = request.tools.iter().map(|tool| tool.name.clone()).collect();
+        tools.sort();
+        Self {
+            preamble: request.preamble.clone(),
+            history: request.chat_history.iter().cloned().collect(),
+            tools,
+        }
+    }
+
+    /// Describe the first difference from `recorded`, if any.
+    fn diff(&self, recorded: &Self) -> Option<String> {
+        if self.preamble != recorded.preamble {
+            return Some("system prompt changed".into());
+        }
+        if self.tools != recorded.tools {
+            let added: Vec<_> = self
+                .tools
+                .iter()
+                .filter(|tool| !recorded.tools.contains(tool))
+                .collect();
+            let removed: Vec<_> = recorded
+                .tools
+                .iter()
+                .filter(|tool| !self.tools.contains(tool))
+                .collect();
+            return Some(format!(
+                "tools changed (added {added:?}, removed {removed:?})"
+            ));
+        }
+        if self.history.len() != recorded.history.len() {
+            return Some(format!(
+                "history has {} messages, recorded {}",
+                self.history.len(),
+                recorded.history.len()
+            ));
+        }
+        self.history
+            .iter()
+            .zip(&recorded.history)
+            .position(|(message, recorded)| message != recorded)
+            .map(|index| format!("history message {index} changed"))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub choice: Vec<AssistantContent>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cached_input_tokens: u64,
+    pub raw: serde_json::Value,
+}
+
+impl RecordedResponse {
+    fn from_response(response: &completion::CompletionResponse<RawResponse>) -> Self {
+        Self {
+            choice: response.choice.iter().cloned().collect(),
+            input_tokens: response.usage.input_tokens,
+            output_tokens: response.usage.output_tokens,
+            cached_input_tokens: response.usage.cached_input_tokens,
+            raw: response.raw_response.body.clone(),
+        }
+    }
+
+    fn to_response(&self) -> Result<completion::CompletionResponse<RawResponse>, CompletionError> {
+        Ok(completion::CompletionResponse {
+            choice: OneOrMany::many(self.choice.clone()).map_err(|_| {
+                CompletionError::ResponseError("recorded response has no content".into())
+            })?,
+            usage: completion::Usage {
+                input_tokens: self.input_tokens,
+                output_tokens: self.output_tokens,
+                total_tokens: self.input_tokens + self.output_tokens,
+                cached_input_tokens: self.cached_input_tokens,
+            },
+            raw_response: RawResponse {
+                body: self.raw.clone(),
+            },
+        })
+    }
+}
+
+impl Cassette {
+    pub fn load(path: &Path) -> crate::error::Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|error| anyhow::anyhow!("failed to read {}: {error}", path.display()))?;
+        let cassette: Self = serde_json::from_str(&json)
+            .map_err(|error| anyhow::anyhow!("invalid cassette {}: {error}", path.display()))?;
+        if cassette.version > CASSETTE_VERSION {
+            return Err(anyhow::anyhow!(
+                "cassette version {} is newer than this build supports ({CASSETTE_VERSION})",
+                cassette.version
+            )
+            .into());
+        }
+        Ok(cassette)
+    }
+
+    pub fn save(&self, path: &Path) -> crate::error::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| anyhow::anyhow!(e))?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| anyhow::anyhow!(e))?;
+        std::fs::write(path, json).map_err(|e| anyhow::anyhow!(e))?;
+        Ok(())
+    }
+}
+
+/// A point where replayed code stopped matching the recording.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Divergence {
+    /// A completion request differs from the recorded one.
+    Request { index: usize, detail: String },
+    /// More completions were requested than were recorded.
+    ExtraCompletion { index: usize },
+    /// A tool was called that wasn't recorded with these arguments.
+    UnexpectedTool { name: String, args: String },
+    /// A live tool returned something other than the recorded result.
+    ToolResult { name: String, detail: String },
+}
+
+impl std::fmt::Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Request { index, detail } => write!(f, "completion {index}: {detail}"),
+            Self::ExtraCompletion { index } => {
+                write!(f, "completion {index} was not recorded")
+            }
+            Self::UnexpectedTool { name, args } => {
+                write!(f, "unrecorded tool call {name}({args})")
+            }
+            Self::ToolResult { name, detail } => write!(f, "tool {name}: {detail}"),
+        }
+    }
+}
+
+/// How a replay went.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayReport {
+    pub completions: usize,
+    pub tool_calls: usize,
+    pub divergences: Vec<Divergence>,
+    /// Recorded interactions that were never requested.
+    pub unused: usize,
+}
+
+impl ReplayReport {
+    /// Whether the replay matched the recording exactly.
+    pub fn is_clean(&self) -> bool {
+        self.divergences.is_empty() && self.unused == 0
+    }
+}
+
+/// Records or replays the LLM traffic of work run in its scope.
+#[derive(Debug, Clone)]
+pub struct ReplaySession {
+    inner: Arc<Mutex<SessionState>>,
+}
+
+#[derive(Debug)]
+struct SessionState {
+    replaying: bool,
+    cassette: Cassette,
+    /// Whether each recorded interaction has been served (replay only).
+    used: Vec<bool>,
+    live_tools: Vec<String>,
+    completions: usize,
+    tool_calls: usize,
+    divergences: Vec<Divergence>,
+}
+
+impl ReplaySession {
+    /// A session that records into an empty cassette.
+    pub fn record() -> Self {
+        Self::new(
+            false,
+            Cassette {
+                version: CASSETTE_VERSION,
+                interactions: Vec::new(),
+            },
+        )
+    }
+
+    /// A session that serves `cassette` back.
+    pub fn replay(cassette: Cassette) -> Self {
+        Self::new(true, cassette)
+    }
+
+    fn new(replaying: bool, cassette: Cassette) -> Self {
+        let used = vec![false; cassette.interactions.len()];
+        Self {
+            inner: Arc::new(Mutex::new(SessionState {
+                replaying,
+                cassette,
+                used,
+                live_tools: DEFAULT_LIVE_TOOLS
+                    .iter()
+                    .map(|tool| tool.to_string())
+                    .collect(),
+                completions: 0,
+                tool_calls: 0,
+                divergences: Vec::new(),
+            })),
+        }
+    }
+
+    /// Replace the tools that run for real during replay.
+    pub fn with_live_tools(self, tools: &[&str]) -> Self {
+        self.state().live_tools = tools.iter().map(|tool| tool.to_string()).collect();
+        self
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, SessionState> {
+        self.inner.lock().expect("replay session lock poisoned")
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.state().replaying
+    }
+
+    /// Run `future` with this session as the current one.
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        SESSION.scope(self.clone(), future).await
+    }
+
+    /// Record a completion (recording only).
+    pub fn record_completion(
+        &self,
+        model: &str,
+        request: RecordedRequest,
+        response: &completion::CompletionResponse<RawResponse>,
+    ) {
+        let mut state = self.state();
+        if state.replaying {
+            return;
+        }
+        state.completions += 1;
+        state.cassette.interactions.push(Interaction::Completion {
+            model: model.to_string(),
+            request,
+            response: RecordedResponse::from_response(response),
+        });
+    }
+
+    /// Answer a completion from the recording (replaying only). A request
+    /// that doesn't match the recording fails, ending the turn at the first
+    /// divergence.
+    pub fn replay_completion(
+        &self,
+        request: &RecordedRequest,
+    ) -> Result<completion::CompletionResponse<RawResponse>, CompletionError> {
+        let mut state = self.state();
+        let index = state.completions;
+        state.completions += 1;
+
+        let next =
+            state
+                .cassette
+                .interactions
+                .iter()
+                .enumerate()
+                .find(|(position, interaction)| {
+                    !state.used[*position] && matches!(interaction, Interaction::Completion { .. })
+                });
+        let Some((
+            position,
+            Interaction::Completion {
+                request: recorded,
+                response,
+                ..
+            },
+        )) = next
+        else {
+            state
+                .divergences
+                .push(Divergence::ExtraCompletion { index });
+            return Err(CompletionError::ProviderError(format!(
+                "replay: completion {index} was not recorded"
+            )));
+        };
+
+        if let Some(detail) = request.diff(recorded) {
+            state.divergences.push(Divergence::Request {
+                index,
+                detail: detail.clone(),
+            });
+            return Err(CompletionError::ProviderError(format!(
+                "replay: completion {index} diverged: {detail}"
+            )));
+        }
+
+        let response = response.to_response();
+        state.used[position] = true;
+        response
+    }
+
+    /// The recorded result for a tool call that shouldn't run (replaying
+    /// only). `None` means run the tool.
+    pub fn replay_tool_call(&self, name: &str, args: &str) -> Option<String> {
+        let mut state = self.state();
+        if !state.replaying || state.live_tools.iter().any(|tool| tool == name) {
+            return None;
+        }
+        state.tool_calls += 1;
+        match state.take_tool(name, args) {
+            Some(result) => Some(result),
+            None => {
+                state.divergences.push(Divergence::UnexpectedTool {
+                    name: name.to_string(),
+                    args: args.to_string(),
+                });
+                Some(format!(
+                    "Error: replay has no recorded result for this {name} call."
+                ))
+            }
+        }
+    }
+
+    /// Record a tool result, or compare a live tool's result against the
+    /// recording when replaying.
+    pub fn record_tool_result(&self, name: &str, args: &str, result: &str) {
+        let mut state = self.state();
+        if !state.replaying {
+            state.tool_calls += 1;
+            state.cassette.interactions.push(Interaction::Tool {
+                name: name.to_string(),
+                args: args.to_string(),
+                result: result.to_string(),
+            });
+            return;
+        }
+        if !state.live_tools.iter().any(|tool| tool == name) {
+            return;
+        }
+
+        state.tool_calls += 1;
+        match state.take_tool(name, args) {
+            Some(recorded) if recorded == result => {}
+            Some(_) => state.divergences.push(Divergence::ToolResult {
+                name: name.to_string(),
+                detail: "result changed".into(),
+            }),
+            None => state.divergences.push(Divergence::UnexpectedTool {
+                name: name.to_string(),
+                args: args.to_string(),
+            }),
+        }
+    }
+
+    /// The cassette recorded so far.
+    pub fn cassette(&self) -> Cassette {
+        self.state().cassette.clone()
+    }
+
+    /// Summarize the session.
+    pub fn report(&self) -> ReplayReport {
+        let state = self.state();
+        ReplayReport {
+            completions: state.completions,
+            tool_calls: state.tool_calls,
+            divergences: state.divergences.clone(),
+            unused: if state.replaying {
+                state.used.iter().filter(|used| !**used).count()
+            } else {
+                0
+            },
+        }
+    }
+}
+
+impl SessionState {
+    /// Serve the first unused recorded result for this tool call. Matched by
+    /// name and arguments, since concurrent tool calls finish in any order.
+    fn take_tool(&mut self, name: &str, args: &str) -> Option<String> {
+        let (position, result) =
+            self.cassette
+                .interactions
+                .iter()
+                .enumerate()
+                .find_map(|(position, interaction)| match interaction {
+                    Interaction::Tool {
+                        name: recorded_name,
+                        args: recorded_args,
+                        result,
+                    } if !self.used[position] && recorded_name == name && recorded_args == args => {
+                        Some((position, result.clone()))
+                    }
+                    _ => None,
+                })?;
+        self.used[position] = true;
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(preamble: &str) -> RecordedRequest {
+        RecordedRequest {
+            preamble: Some(preamble.into()),
+            history: vec![Message::from("hello")],
+            tools: vec!["reply".into()],
+        }
+    }
+
+    fn response(text: &str) -> completion::CompletionResponse<RawResponse> {
+        completion::CompletionResponse {
+            choice: OneOrMany::one(AssistantContent::text(text)),
+            usage: completion::Usage::default(),
+            raw_response: RawResponse {
+                body: serde_json::Value::Null,
+            },
+        }
+    }
+
+    fn recorded() -> Cassette {
+        let session = ReplaySession::record();
+        session.record_completion("anthropic/test", request("you are a bot"), &response("hi"));
+        session.record_tool_result("shell", r#"{"command":"ls"}"#, "Cargo.toml");
+        session.cassette()
+    }
+
+    #[test]
+    fn matching_replay_is_clean() {
+        let session = ReplaySession::replay(recorded());
+        let response = session
+            .replay_completion(&request("you are a bot"))
+            .unwrap();
+        assert_eq!(response.choice.first(), AssistantContent::text("hi"));
+        assert_eq!(
+            session.replay_tool_call("shell", r#"{"command":"ls"}"#),
+            Some("Cargo.toml".into())
+        );
+        assert!(session.report().is_clean());
+    }
+
+    #[test]
+    fn changed_prompt_diverges() {
+        let session = ReplaySession::replay(recorded());
+        assert!(
+            session
+                .replay_completion(&request("you are a different bot"))
+                .is_err()
+        );
+        assert!(session.replay_completion(&request("you are a bot")).is_ok());
+        assert!(
+            session
+                .replay_completion(&request("you are a bot"))
+                .is_err()
+        );
+
+        let report = session.report();
+        assert_eq!(
+            report.divergences,
+            vec![
+                Divergence::Request {
+                    index: 0,
+                    detail: "system prompt changed".into(),
+                },
+                Divergence::ExtraCompletion { index: 2 },
+            ]
+        );
+        // The recorded shell call was never made.
+        assert_eq!(report.unused, 1);
+    }
+
+    #[test]
+    fn live_tools_are_compared() {
+        let session = ReplaySession::replay(recorded()).with_live_tools(&["shell"]);
+        assert_eq!(
+            session.replay_tool_call("shell", r#"{"command":"ls"}"#),
+            None
+        );
+        session.record_tool_result("shell", r#"{"command":"ls"}"#, "Cargo.lock");
+        assert_eq!(
+            session.report().divergences,
+            vec![Divergence::ToolResult {
+                name: "shell".into(),
+                detail: "result changed".into(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn sessions_are_task_local() {
+        assert!(current().is_none());
+        let session = ReplaySession::record();
+        session.scope(async { assert!(current().is_some()) }).await;
+    }
+}
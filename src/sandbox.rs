@@ -64,6 +64,8 @@ pub struct Sandbox {
     data_dir: PathBuf,
     tools_bin: PathBuf,
     writable_paths: Vec<PathBuf>,
+    /// Writable even inside the protected data dir (e.g. a worker workspace).
+    writable_overrides: Vec<PathBuf>,
     backend: SandboxBackend,
 }
 
@@ -125,10 +127,19 @@ impl Sandbox {
             data_dir,
             tools_bin,
             writable_paths,
+            writable_overrides: Vec::new(),
             backend,
         }
     }
 
+    /// A copy of this sandbox that can also write to `path`, even when it
+    /// lies inside the agent's data directory. Used for worker workspaces.
+    pub fn with_writable_dir(&self, path: &Path) -> Self {
+        let mut sandbox = self.clone();
+        sandbox.writable_overrides.push(canonicalize_or_self(path));
+        sandbox
+    }
+
     /// Wrap a command for sandboxed execution.
     ///
     /// Returns a `Command` ready to spawn, potentially prefixed with bwrap or
@@ -196,6 +207,11 @@ impl Sandbox {
         // 7. Re-protect agent data dir (may overlap with workspace parent)
         cmd.arg("--ro-bind").arg(&self.data_dir).arg(&self.data_dir);
 
+        // 7b. Writable overrides punch through the data dir protection
+        for writable in &self.writable_overrides {
+            cmd.arg("--bind").arg(writable).arg(writable);
+        }
+
         // 8. Isolation flags
         cmd.arg("--unshare-pid");
         cmd.arg("--new-session");
@@ -300,6 +316,14 @@ impl Sandbox {
             data_dir.display()
         ));
 
+        // Writable overrides come after the data dir rule so they win
+        for (index, writable) in self.writable_overrides.iter().enumerate() {
+            profile.push_str(&format!(
+                "; writable override {index}\n(allow file-write* (subpath \"{}\"))\n",
+                writable.display()
+            ));
+        }
+
         // /tmp writable
         let tmp = canonicalize_or_self(Path::new("/tmp"));
         profile.push_str(&format!(
@@ -8,12 +8,15 @@
 //! 1. Instance-level: `{instance_dir}/skills/`
 //! 2. Agent workspace: `{workspace}/skills/`
 //!
-//! The channel sees a summary of available skills and is instructed to
-//! delegate skill work to workers. Workers receive the full skill content
-//! in their system prompt.
+//! The channel sees a summary of available skills. It can activate one for
+//! the current turn with `use_skill`, which returns the instructions and
+//! unlocks any HTTP tools the skill declares in `tools.toml`, or delegate the
+//! work to a worker. Workers read the full skill content with `read_skill`.
 
+pub mod http_tool;
 mod installer;
 
+pub use http_tool::{SkillToolDef, SkillToolParam};
 pub use installer::{install_from_file, install_from_github};
 
 use anyhow::Context as _;
@@ -33,10 +36,25 @@ pub struct Skill {
     pub base_dir: PathBuf,
     /// Full rendered content (frontmatter stripped, `{baseDir}` resolved).
     pub content: String,
+    /// Other scalar frontmatter fields (license, homepage, version, ...).
+    pub metadata: HashMap<String, String>,
+    /// HTTP tools declared in the skill's `tools.toml`.
+    pub tools: Vec<SkillToolDef>,
     /// Where this skill was loaded from.
     pub source: SkillSource,
 }
 
+impl Skill {
+    /// Get one of the skill's tools by name.
+    pub fn tool(&self, name: &str) -> Option<&SkillToolDef> {
+        self.tools.iter().find(|tool| tool.name == name)
+    }
+
+    fn tool_names(&self) -> Vec<String> {
+        self.tools.iter().map(|tool| tool.name.clone()).collect()
+    }
+}
+
 /// Where a skill was loaded from, used for precedence tracking.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SkillSource {
@@ -133,6 +151,7 @@ impl SkillSet {
                 description: s.description.clone(),
                 location: s.file_path.display().to_string(),
                 suggested: false,
+                tools: s.tool_names(),
             })
             .collect();
 
@@ -164,6 +183,7 @@ impl SkillSet {
                 name: s.name.clone(),
                 description: s.description.clone(),
                 location: s.file_path.display().to_string(),
+                tools: s.tool_names(),
             })
             .collect();
 
@@ -212,6 +232,8 @@ impl SkillSet {
                 description: s.description.clone(),
                 file_path: s.file_path.clone(),
                 base_dir: s.base_dir.clone(),
+                metadata: s.metadata.clone(),
+                tools: s.tool_names(),
                 source: s.source.clone(),
             })
             .collect()
@@ -225,6 +247,8 @@ pub struct SkillInfo {
     pub description: String,
     pub file_path: PathBuf,
     pub base_dir: PathBuf,
+    pub metadata: HashMap<String, String>,
+    pub tools: Vec<String>,
     pub source: SkillSource,
 }
 
@@ -281,9 +305,9 @@ async fn load_skill(
         .await
         .with_context(|| format!("failed to read {}", file_path.display()))?;
 
-    let (frontmatter, body) = parse_frontmatter(&raw)?;
+    let (mut frontmatter, body) = parse_frontmatter(&raw)?;
 
-    let name = frontmatter.get("name").cloned().unwrap_or_else(|| {
+    let name = frontmatter.remove("name").unwrap_or_else(|| {
         // Fall back to directory name if no name in frontmatter
         base_dir
             .file_name()
@@ -292,18 +316,28 @@ async fn load_skill(
             .to_string()
     });
 
-    let description = frontmatter.get("description").cloned().unwrap_or_default();
+    let description = frontmatter.remove("description").unwrap_or_default();
 
     // Resolve {baseDir} template variable in the body
     let base_dir_str = base_dir.to_string_lossy();
     let content = body.replace("{baseDir}", &base_dir_str);
 
+    // A broken tools.toml costs the skill its tools, not its instructions.
+    let tools = http_tool::load_skill_tools(base_dir)
+        .await
+        .unwrap_or_else(|error| {
+            tracing::warn!(%error, "failed to load skill tools, loading skill without them");
+            Vec::new()
+        });
+
     Ok(Skill {
         name,
         description,
         file_path: file_path.to_path_buf(),
         base_dir: base_dir.to_path_buf(),
         content,
+        metadata: frontmatter,
+        tools,
         source,
     })
 }
@@ -464,6 +498,8 @@ mod tests {
                 file_path: PathBuf::from("/skills/weather/SKILL.md"),
                 base_dir: PathBuf::from("/skills/weather"),
                 content: "# Weather\n\nUse curl.".into(),
+                metadata: HashMap::new(),
+                tools: Vec::new(),
                 source: SkillSource::Instance,
             },
         );
@@ -473,6 +509,38 @@ mod tests {
         assert!(prompt.contains("<available_skills>"));
         assert!(prompt.contains("<name>weather</name>"));
         assert!(prompt.contains("<description>Get weather forecasts</description>"));
+        assert!(prompt.contains("use_skill"));
+        assert!(!prompt.contains("<tools>"));
+    }
+
+    #[test]
+    fn test_skill_set_channel_prompt_lists_tools() {
+        let tools: http_tool::SkillToolsFile = toml::from_str(indoc::indoc! {r#"
+            [[tool]]
+            name = "forecast"
+            description = "Forecast for a city."
+            url = "https://wttr.in/{city}"
+        "#})
+        .unwrap();
+        let mut set = SkillSet::default();
+        set.skills.insert(
+            "weather".into(),
+            Skill {
+                name: "weather".into(),
+                description: "Get weather forecasts".into(),
+                file_path: PathBuf::from("/skills/weather/SKILL.md"),
+                base_dir: PathBuf::from("/skills/weather"),
+                content: "# Weather".into(),
+                metadata: HashMap::new(),
+                tools: tools.tool,
+                source: SkillSource::Workspace,
+            },
+        );
+
+        let engine = crate::prompts::PromptEngine::new("en").unwrap();
+        let prompt = set.render_channel_prompt(&engine).unwrap();
+        assert!(prompt.contains("<tools>forecast</tools>"));
+        assert!(set.get("Weather").unwrap().tool("forecast").is_some());
     }
 
     #[test]
@@ -486,6 +554,8 @@ mod tests {
                 file_path: PathBuf::from("/skills/weather/SKILL.md"),
                 base_dir: PathBuf::from("/skills/weather"),
                 content: "# Weather\n\nUse curl.".into(),
+                metadata: HashMap::new(),
+                tools: Vec::new(),
                 source: SkillSource::Instance,
             },
         );
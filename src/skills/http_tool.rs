@@ -0,0 +1,331 @@
+//! Declarative HTTP tools bundled with a skill.
+//!
+//! A skill directory may contain a `tools.toml` next to its `SKILL.md`. Each
+//! `[[tool]]` entry describes one HTTP request: the method, a URL template
+//! with `{param}` placeholders, static headers, and typed parameters. Once
+//! the channel activates the skill with `use_skill`, it can call these tools
+//! through the same tool. Arguments only ever fill in the template; the
+//! request target is fixed by the skill author.
+//!
+//! ```toml
+//! [[tool]]
+//! name = "forecast"
+//! description = "Three-day forecast for a city."
+//! url = "https://wttr.in/{city}?format=3"
+//!
+//! [tool.parameters.city]
+//! type = "string"
+//! description = "City name, e.g. Berlin"
+//! required = true
+//! ```
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// File in a skill directory that declares its tools.
+pub const SKILL_TOOLS_FILE: &str = "tools.toml";
+
+#[derive(Debug, Deserialize)]
+pub(super) struct SkillToolsFile {
+    #[serde(default)]
+    pub(super) tool: Vec<SkillToolDef>,
+}
+
+/// One HTTP tool declared by a skill.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SkillToolDef {
+    pub name: String,
+    pub description: String,
+    /// Only `http` is supported. Anything else (e.g. `wasm`) is skipped at
+    /// load time.
+    #[serde(default = "default_kind")]
+    pub kind: String,
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// Request URL. `{param}` placeholders are replaced with URL-encoded
+    /// arguments.
+    #[serde(default)]
+    pub url: String,
+    /// Static headers. Values may be `env:VAR_NAME` references to variables
+    /// the operator listed in `skill_env_allowlist`.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub parameters: BTreeMap<String, SkillToolParam>,
+}
+
+/// A parameter of a skill tool.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SkillToolParam {
+    /// JSON schema type: `string`, `integer`, `number`, or `boolean`.
+    #[serde(rename = "type", default = "default_param_type")]
+    pub param_type: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub required: bool,
+}
+
+fn default_kind() -> String {
+    "http".into()
+}
+
+fn default_method() -> String {
+    "GET".into()
+}
+
+fn default_param_type() -> String {
+    "string".into()
+}
+
+/// Load the tools declared in a skill directory. A skill without a
+/// `tools.toml` has no tools; unsupported or malformed entries are skipped
+/// with a warning.
+pub async fn load_skill_tools(base_dir: &Path) -> anyhow::Result<Vec<SkillToolDef>> {
+    let path = base_dir.join(SKILL_TOOLS_FILE);
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+
+    let raw = tokio::fs::read_to_string(&path)
+        .await
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let file: SkillToolsFile =
+        toml::from_str(&raw).with_context(|| format!("failed to parse {}", path.display()))?;
+
+    let mut tools: Vec<SkillToolDef> = Vec::new();
+    for tool in file.tool {
+        if let Err(reason) = tool.validate() {
+            tracing::warn!(tool = %tool.name, path = %path.display(), %reason, "skipping skill tool");
+        } else if tools.iter().any(|existing| existing.name == tool.name) {
+            tracing::warn!(tool = %tool.name, path = %path.display(), "duplicate skill tool, skipping");
+        } else {
+            tools.push(tool);
+        }
+    }
+    Ok(tools)
+}
+
+impl SkillToolDef {
+    fn validate(&self) -> Result<(), String> {
+        if self.kind != "http" {
+            return Err(format!("unsupported tool kind '{}'", self.kind));
+        }
+        if self.name.trim().is_empty() {
+            return Err("missing name".into());
+        }
+        if reqwest::Method::from_bytes(self.method.to_uppercase().as_bytes()).is_err() {
+            return Err(format!("invalid method '{}'", self.method));
+        }
+        if !(self.url.starts_with("http://") || self.url.starts_with("https://")) {
+            return Err(format!("url must be http(s), got '{}'", self.url));
+        }
+        Ok(())
+    }
+
+    /// JSON schema of the tool's arguments, shown to the LLM.
+    pub fn parameters_schema(&self) -> Value {
+        let properties: serde_json::Map<String, Value> = self
+            .parameters
+            .iter()
+            .map(|(name, param)| {
+                (
+                    name.clone(),
+                    serde_json::json!({
+                        "type": param.param_type,
+                        "description": param.description,
+                    }),
+                )
+            })
+            .collect();
+        let required: Vec<&String> = self
+            .parameters
+            .iter()
+            .filter(|(_, param)| param.required)
+            .map(|(name, _)| name)
+            .collect();
+
+        serde_json::json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        })
+    }
+
+    /// Build the request for a call: fill URL placeholders and put the
+    /// remaining declared arguments in the query (GET/DELETE) or a JSON body.
+    /// `env:VAR` headers resolve only for variables in `allowed_env`, so a
+    /// skill can't send arbitrary process environment to its endpoint.
+    pub fn build_request(
+        &self,
+        client: &reqwest::Client,
+        arguments: &Value,
+        allowed_env: &[String],
+    ) -> Result<reqwest::RequestBuilder, String> {
+        let arguments = match arguments {
+            Value::Object(map) => map.clone(),
+            Value::Null => serde_json::Map::new(),
+            _ => return Err("arguments must be an object".into()),
+        };
+
+        for (name, param) in &self.parameters {
+            if param.required && arguments.get(name).is_none_or(Value::is_null) {
+                return Err(format!("missing required argument '{name}'"));
+            }
+        }
+
+        let (url, remaining) = self.fill_url(&arguments);
+        let method = reqwest::Method::from_bytes(self.method.to_uppercase().as_bytes())
+            .map_err(|error| error.to_string())?;
+        let url = reqwest::Url::parse(&url).map_err(|error| format!("invalid url: {error}"))?;
+
+        let mut request = client.request(method.clone(), url);
+        for (name, value) in &self.headers {
+            let value = match value.strip_prefix("env:") {
+                Some(var_name) if !allowed_env.iter().any(|allowed| allowed == var_name) => {
+                    return Err(format!(
+                        "header {name} reads ${var_name}, which is not in skill_env_allowlist"
+                    ));
+                }
+                Some(var_name) => std::env::var(var_name)
+                    .map_err(|_| format!("header {name} needs ${var_name}, which is not set"))?,
+                None => value.clone(),
+            };
+            request = request.header(name, value);
+        }
+
+        if !remaining.is_empty() {
+            if matches!(method, reqwest::Method::GET | reqwest::Method::DELETE) {
+                let query: Vec<(String, String)> = remaining
+                    .iter()
+                    .map(|(name, value)| (name.clone(), plain_value(value)))
+                    .collect();
+                request = request.query(&query);
+            } else {
+                let body: serde_json::Map<String, Value> = remaining.into_iter().collect();
+                request = request.json(&body);
+            }
+        }
+        Ok(request)
+    }
+
+    /// Replace `{param}` placeholders in the URL and return the declared
+    /// arguments that weren't used. Undeclared arguments are dropped.
+    fn fill_url(
+        &self,
+        arguments: &serde_json::Map<String, Value>,
+    ) -> (String, Vec<(String, Value)>) {
+        let mut url = self.url.clone();
+        let mut remaining = Vec::new();
+        for name in self.parameters.keys() {
+            let placeholder = format!("{{{name}}}");
+            match arguments.get(name) {
+                Some(value) if !value.is_null() => {
+                    if url.contains(&placeholder) {
+                        url = url.replace(&placeholder, &urlencoding::encode(&plain_value(value)));
+                    } else {
+                        remaining.push((name.clone(), value.clone()));
+                    }
+                }
+                _ => url = url.replace(&placeholder, ""),
+            }
+        }
+        (url, remaining)
+    }
+}
+
+/// An argument as it appears in a URL: strings unquoted, everything else as
+/// JSON.
+fn plain_value(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forecast_tool() -> SkillToolDef {
+        let file: SkillToolsFile = toml::from_str(indoc::indoc! {r#"
+            [[tool]]
+            name = "forecast"
+            description = "Forecast for a city."
+            url = "https://wttr.in/{city}"
+
+            [tool.parameters.city]
+            description = "City name"
+            required = true
+
+            [tool.parameters.days]
+            type = "integer"
+        "#})
+        .unwrap();
+        file.tool.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn parses_tool_with_defaults() {
+        let tool = forecast_tool();
+        assert_eq!(tool.method, "GET");
+        assert_eq!(tool.kind, "http");
+        assert!(tool.validate().is_ok());
+        assert_eq!(tool.parameters["city"].param_type, "string");
+        assert_eq!(
+            tool.parameters_schema()["required"],
+            serde_json::json!(["city"])
+        );
+    }
+
+    #[test]
+    fn url_placeholders_are_encoded_and_the_rest_is_left_over() {
+        let tool = forecast_tool();
+        let arguments = serde_json::json!({"city": "New York", "days": 3, "extra": true});
+        let (url, remaining) = tool.fill_url(arguments.as_object().unwrap());
+        assert_eq!(url, "https://wttr.in/New%20York");
+        assert_eq!(remaining, vec![("days".to_string(), serde_json::json!(3))]);
+    }
+
+    #[test]
+    fn missing_required_argument_is_rejected() {
+        let tool = forecast_tool();
+        let client = reqwest::Client::new();
+        let error = tool
+            .build_request(&client, &serde_json::json!({"days": 2}), &[])
+            .unwrap_err();
+        assert!(error.contains("city"));
+    }
+
+    #[test]
+    fn env_headers_only_read_allowlisted_variables() {
+        let mut tool = forecast_tool();
+        tool.headers
+            .insert("Authorization".into(), "env:PATH".into());
+        let client = reqwest::Client::new();
+        let arguments = serde_json::json!({"city": "Berlin"});
+
+        let error = tool.build_request(&client, &arguments, &[]).unwrap_err();
+        assert!(error.contains("skill_env_allowlist"));
+
+        let request = tool
+            .build_request(&client, &arguments, &["PATH".to_string()])
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.headers()["Authorization"],
+            std::env::var("PATH").unwrap().as_str()
+        );
+    }
+
+    #[test]
+    fn non_http_tools_are_invalid() {
+        let mut tool = forecast_tool();
+        tool.kind = "wasm".into();
+        assert!(tool.validate().is_err());
+    }
+}
@@ -6,6 +6,7 @@ use prometheus::{
 };
 
 use std::sync::LazyLock;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Global metrics instance. Initialized once, accessed from any call site.
 static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
@@ -22,10 +23,31 @@ pub struct Metrics {
     /// Labels: agent_id, model, tier (e.g. "channel", "branch", "worker").
     pub llm_requests_total: IntCounterVec,
 
+    /// Total channel turns (one per LLM agent loop run by a channel).
+    /// Labels: agent_id.
+    pub channel_turns_total: IntCounterVec,
+
+    /// Channel turns per channel, counted only with `per_channel_turns`.
+    /// Labels: agent_id, channel_id.
+    pub channel_turns_by_channel_total: IntCounterVec,
+    per_channel_turns: AtomicBool,
+
+    /// Process events a channel missed because it fell behind the event bus.
+    /// Labels: agent_id.
+    pub channel_events_lagged_total: IntCounterVec,
+
     /// Total tool calls executed across all processes.
     /// Labels: agent_id, tool_name.
     pub tool_calls_total: IntCounterVec,
 
+    /// Tool calls with malformed arguments sent back to the model for repair.
+    /// Labels: agent_id, tool_name.
+    pub tool_call_repairs_total: IntCounterVec,
+
+    /// Values replaced by redaction placeholders before reaching the LLM.
+    /// Labels: agent_id, kind, source.
+    pub redactions_total: IntCounterVec,
+
     /// Total memory recall (read) operations.
     pub memory_reads_total: IntCounter,
 
@@ -97,12 +119,54 @@ impl Metrics {
         )
         .expect("hardcoded metric descriptor");
 
+        let channel_turns_total = IntCounterVec::new(
+            Opts::new("spacebot_channel_turns_total", "Total channel turns"),
+            &["agent_id"],
+        )
+        .expect("hardcoded metric descriptor");
+
+        let channel_turns_by_channel_total = IntCounterVec::new(
+            Opts::new(
+                "spacebot_channel_turns_by_channel_total",
+                "Channel turns per channel (opt-in)",
+            ),
+            &["agent_id", "channel_id"],
+        )
+        .expect("hardcoded metric descriptor");
+
+        let channel_events_lagged_total = IntCounterVec::new(
+            Opts::new(
+                "spacebot_channel_events_lagged_total",
+                "Process events channels missed by falling behind the event bus",
+            ),
+            &["agent_id"],
+        )
+        .expect("hardcoded metric descriptor");
+
         let tool_calls_total = IntCounterVec::new(
             Opts::new("spacebot_tool_calls_total", "Total tool calls executed"),
             &["agent_id", "tool_name"],
         )
         .expect("hardcoded metric descriptor");
 
+        let tool_call_repairs_total = IntCounterVec::new(
+            Opts::new(
+                "spacebot_tool_call_repairs_total",
+                "Malformed tool calls sent back to the model for repair",
+            ),
+            &["agent_id", "tool_name"],
+        )
+        .expect("hardcoded metric descriptor");
+
+        let redactions_total = IntCounterVec::new(
+            Opts::new(
+                "spacebot_redactions_total",
+                "Sensitive values redacted before reaching the LLM",
+            ),
+            &["kind", "source"],
+        )
+        .expect("hardcoded metric descriptor");
+
         let memory_reads_total = IntCounter::new(
             "spacebot_memory_reads_total",
             "Total memory recall operations",
@@ -223,9 +287,24 @@ impl Metrics {
         registry
             .register(Box::new(llm_requests_total.clone()))
             .expect("hardcoded metric");
+        registry
+            .register(Box::new(channel_turns_total.clone()))
+            .expect("hardcoded metric");
+        registry
+            .register(Box::new(channel_turns_by_channel_total.clone()))
+            .expect("hardcoded metric");
+        registry
+            .register(Box::new(channel_events_lagged_total.clone()))
+            .expect("hardcoded metric");
         registry
             .register(Box::new(tool_calls_total.clone()))
             .expect("hardcoded metric");
+        registry
+            .register(Box::new(redactions_total.clone()))
+            .expect("hardcoded metric");
+        registry
+            .register(Box::new(tool_call_repairs_total.clone()))
+            .expect("hardcoded metric");
         registry
             .register(Box::new(memory_reads_total.clone()))
             .expect("hardcoded metric");
@@ -272,7 +351,13 @@ impl Metrics {
         Self {
             registry,
             llm_requests_total,
+            channel_turns_total,
+            channel_turns_by_channel_total,
+            per_channel_turns: AtomicBool::new(false),
+            channel_events_lagged_total,
             tool_calls_total,
+            tool_call_repairs_total,
+            redactions_total,
             memory_reads_total,
             memory_writes_total,
             llm_request_duration_seconds,
@@ -294,4 +379,21 @@ impl Metrics {
     pub fn global() -> &'static Self {
         &METRICS
     }
+
+    /// Turn the per-channel turn breakdown on or off.
+    pub fn set_per_channel_turns(&self, enabled: bool) {
+        self.per_channel_turns.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Count a channel turn, per channel too when that's enabled.
+    pub fn record_channel_turn(&self, agent_id: &str, channel_id: &str) {
+        self.channel_turns_total
+            .with_label_values(&[agent_id])
+            .inc();
+        if self.per_channel_turns.load(Ordering::Relaxed) {
+            self.channel_turns_by_channel_total
+                .with_label_values(&[agent_id, channel_id])
+                .inc();
+        }
+    }
 }
@@ -30,6 +30,8 @@ pub async fn start_metrics_server(
         anyhow::anyhow!("invalid metrics bind address '{}': {}", bind_str, error)
     })?;
 
+    Metrics::global().set_per_channel_turns(config.per_channel_turns);
+
     let app = Router::new()
         .route("/metrics", get(metrics_handler))
         .route("/health", get(health_handler));
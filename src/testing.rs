@@ -0,0 +1,362 @@
+//! Test utilities: a scripted completion model and a minimal agent (`testing`
+//! feature).
+//!
+//! [`ScriptedModel`] answers completion requests from a script of canned
+//! responses instead of calling a provider, and keeps the requests it saw so
+//! tests can assert on prompts and offered tools. Pass it to
+//! `AgentBuilder::new` in place of a `SpacebotModel`, alongside the tools or
+//! tool server under test. [`test_agent`] builds an [`Agent`] with its own instance directory
+//! and databases, for driving channels and tools end to end, and
+//! [`inbound_message`] builds the messages to feed them.
+
+use crate::llm::model::RawResponse;
+use crate::{Agent, AgentDeps, InboundMessage, MessageContent};
+
+use anyhow::Context as _;
+use rig::completion::{self, CompletionError, CompletionModel, CompletionRequest};
+use rig::message::{AssistantContent, ToolCall, ToolFunction};
+use rig::one_or_many::OneOrMany;
+use rig::streaming::StreamingCompletionResponse;
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// One scripted model response.
+#[derive(Debug, Clone)]
+pub struct Step {
+    content: Vec<AssistantContent>,
+}
+
+impl Step {
+    /// Respond with text.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            content: vec![AssistantContent::text(text.into())],
+        }
+    }
+
+    /// Respond with a single tool call.
+    pub fn tool_call(name: impl Into<String>, arguments: serde_json::Value) -> Self {
+        Self {
+            content: Vec::new(),
+        }
+        .and_tool_call(name, arguments)
+    }
+
+    /// Add a tool call to this response. Calls in one step run concurrently.
+    pub fn and_tool_call(mut self, name: impl Into<String>, arguments: serde_json::Value) -> Self {
+        let id = format!("call_{}", self.content.len());
+        self.content.push(AssistantContent::ToolCall(ToolCall {
+            id,
+            call_id: None,
+            function: ToolFunction {
+                name: name.into(),
+                arguments,
+            },
+            signature: None,
+            additional_params: None,
+        }));
+        self
+    }
+
+    /// Shorthand for the channel's `reply` tool.
+    pub fn reply(content: impl Into<String>) -> Self {
+        Self::tool_call("reply", serde_json::json!({ "content": content.into() }))
+    }
+}
+
+/// A completion model that answers from a script.
+#[derive(Debug, Clone)]
+pub struct ScriptedModel {
+    inner: Arc<Mutex<ScriptState>>,
+}
+
+#[derive(Debug, Default)]
+struct ScriptState {
+    steps: VecDeque<Step>,
+    requests: Vec<CompletionRequest>,
+}
+
+impl ScriptedModel {
+    pub fn new(steps: impl IntoIterator<Item = Step>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(ScriptState {
+                steps: steps.into_iter().collect(),
+                requests: Vec::new(),
+            })),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, ScriptState> {
+        self.inner.lock().expect("scripted model lock poisoned")
+    }
+
+    /// Append steps to the script.
+    pub fn push(&self, steps: impl IntoIterator<Item = Step>) {
+        self.state().steps.extend(steps);
+    }
+
+    /// Requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<CompletionRequest> {
+        self.state().requests.clone()
+    }
+
+    /// Steps not yet used.
+    pub fn remaining(&self) -> usize {
+        self.state().steps.len()
+    }
+
+    /// Answer a request with the next step.
+    pub fn next_response(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<completion::CompletionResponse<RawResponse>, CompletionError> {
+        let mut state = self.state();
+        state.requests.push(request);
+        let step = state.steps.pop_front().ok_or_else(|| {
+            CompletionError::ProviderError(format!(
+                "script exhausted after {} completions",
+                state.requests.len() - 1
+            ))
+        })?;
+
+        Ok(completion::CompletionResponse {
+            choice: OneOrMany::many(step.content).map_err(|_| {
+                CompletionError::ResponseError("scripted step has no content".into())
+            })?,
+            usage: completion::Usage::default(),
+            raw_response: RawResponse {
+                body: serde_json::Value::Null,
+            },
+        })
+    }
+}
+
+impl CompletionModel for ScriptedModel {
+    type Response = RawResponse;
+    type StreamingResponse = crate::llm::model::RawStreamingResponse;
+    type Client = ScriptedModel;
+
+    fn make(client: &Self::Client, _model: impl Into<String>) -> Self {
+        client.clone()
+    }
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<completion::CompletionResponse<RawResponse>, CompletionError> {
+        self.next_response(request)
+    }
+
+    async fn stream(
+        &self,
+        _request: CompletionRequest,
+    ) -> Result<StreamingCompletionResponse<Self::StreamingResponse>, CompletionError> {
+        Err(CompletionError::ProviderError(
+            "streaming not supported by the scripted model".into(),
+        ))
+    }
+}
+
+/// A text message from `sender_id` on `source`, in conversation `<source>:1`,
+/// with no metadata. Tests set the other fields they care about.
+pub fn inbound_message(source: &str, sender_id: &str, text: &str) -> InboundMessage {
+    InboundMessage {
+        id: "1".into(),
+        source: source.into(),
+        conversation_id: format!("{source}:1"),
+        sender_id: sender_id.into(),
+        agent_id: None,
+        content: MessageContent::Text(text.into()),
+        timestamp: chrono::Utc::now(),
+        metadata: std::collections::HashMap::new(),
+        formatted_author: None,
+    }
+}
+
+/// Build an agent with default config under `instance_dir`, which should be
+/// an empty temporary directory.
+///
+/// Databases, workspace, and identity files are created there. The
+/// embedding model is passed in because loading one downloads model files;
+/// share a single instance across tests. No provider keys are needed when
+/// completions come from a [`ScriptedModel`].
+pub async fn test_agent(
+    instance_dir: &Path,
+    embedding_model: Arc<crate::memory::EmbeddingModel>,
+) -> anyhow::Result<Agent> {
+    let config_path = instance_dir.join("config.toml");
+    if !config_path.exists() {
+        std::fs::write(&config_path, "").context("failed to write test config")?;
+    }
+    let config = crate::config::Config::load_from_path(&config_path)?;
+    let agent_config = config
+        .resolve_agents()
+        .into_iter()
+        .next()
+        .context("default config has no agent")?;
+
+    for dir in [
+        &agent_config.workspace,
+        &agent_config.data_dir,
+        &agent_config.archives_dir,
+    ] {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+
+    let db =
+        crate::db::Db::connect(&agent_config.data_dir, &agent_config.id, &config.storage).await?;
+
+    let memory_search = Arc::new(crate::memory::MemorySearch::new(
+        crate::memory::MemoryStore::with_agent_id(db.sqlite.clone(), &agent_config.id),
+        db.open_embeddings().await?,
+        embedding_model.clone(),
+    ));
+    let knowledge = crate::knowledge::KnowledgeBase::new(
+        db.sqlite.clone(),
+        db.open_knowledge_chunks().await?,
+        embedding_model,
+    );
+
+    crate::identity::scaffold_identity_files(&agent_config.workspace).await?;
+    let identity = crate::identity::Identity::load(&agent_config.workspace).await;
+    let skills =
+        crate::skills::SkillSet::load(&config.skills_dir(), &agent_config.skills_dir()).await;
+    let prompts = crate::prompts::PromptEngine::new("en")?;
+    let runtime_config = Arc::new(crate::config::RuntimeConfig::new(
+        &config.instance_dir,
+        &agent_config,
+        &config.defaults,
+        prompts,
+        identity,
+        skills,
+    ));
+
+    let llm_manager = Arc::new(crate::llm::LlmManager::new(config.llm.clone()).await?);
+    let sandbox = Arc::new(
+        crate::sandbox::Sandbox::new(
+            &agent_config.sandbox,
+            agent_config.workspace.clone(),
+            &config.instance_dir,
+            agent_config.data_dir.clone(),
+        )
+        .await,
+    );
+
+    let (event_tx, _) = tokio::sync::broadcast::channel(256);
+    let agent_id: crate::AgentId = Arc::from(agent_config.id.as_str());
+    let media = crate::media::MediaStore::open(
+        db.sqlite.clone(),
+        agent_id.clone(),
+        &config.media,
+        &config.instance_dir,
+        &agent_config.data_dir,
+    )
+    .await?;
+    let deps = AgentDeps {
+        agent_id: agent_id.clone(),
+        memory_search,
+        knowledge,
+        llm_manager,
+        mcp_manager: Arc::new(crate::mcp::McpManager::new(Vec::new())),
+        cron_tool: None,
+        runtime_config: runtime_config.clone(),
+        event_tx: event_tx.clone(),
+        sqlite_pool: db.sqlite.clone(),
+        messaging_manager: None,
+        sandbox,
+        links: Arc::new(arc_swap::ArcSwap::from_pointee(Vec::new())),
+        agent_names: Arc::new(
+            [(agent_config.id.clone(), agent_config.id.clone())]
+                .into_iter()
+                .collect(),
+        ),
+        worker_pool: crate::agent::worker_pool::WorkerPool::new(
+            agent_id.clone(),
+            runtime_config,
+            event_tx,
+        ),
+        media,
+        inbound_middleware: Default::default(),
+        outbound_middleware: Default::default(),
+    };
+
+    Ok(Agent {
+        id: agent_id,
+        config: agent_config,
+        db,
+        deps,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(model: &ScriptedModel, prompt: &str) -> CompletionRequest {
+        rig::completion::CompletionRequestBuilder::new(model.clone(), prompt)
+            .preamble("be friendly".to_string())
+            .build()
+    }
+
+    #[test]
+    fn script_is_served_in_order() {
+        let model = ScriptedModel::new([
+            Step::reply("hello").and_tool_call("react", serde_json::json!({ "emoji": "👋" })),
+            Step::text("done"),
+        ]);
+
+        let first = model.next_response(request(&model, "hi")).unwrap();
+        let names: Vec<String> = first
+            .choice
+            .iter()
+            .filter_map(|content| match content {
+                AssistantContent::ToolCall(call) => Some(call.function.name.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(names, ["reply", "react"]);
+
+        let second = model.next_response(request(&model, "again")).unwrap();
+        assert_eq!(second.choice.first(), AssistantContent::text("done"));
+        assert_eq!(model.remaining(), 0);
+        assert_eq!(model.requests()[0].preamble.as_deref(), Some("be friendly"));
+    }
+
+    #[test]
+    fn exhausted_script_errors() {
+        let model = ScriptedModel::new([Step::text("only")]);
+        assert!(model.next_response(request(&model, "hi")).is_ok());
+        let error = model.next_response(request(&model, "hi")).unwrap_err();
+        assert!(error.to_string().contains("script exhausted after 1"));
+        assert_eq!(model.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn drives_an_agent_through_tool_calls() {
+        use rig::completion::Prompt as _;
+
+        let model = ScriptedModel::new([
+            Step::tool_call("react", serde_json::json!({ "emoji": "👋" })),
+            Step::text("done"),
+        ]);
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let response_tx = crate::ResponseSender::new(tx, Default::default());
+        let agent = rig::agent::AgentBuilder::new(model.clone())
+            .tool(crate::tools::ReactTool::new(response_tx))
+            .default_max_turns(2)
+            .build();
+
+        let answer = agent.prompt("hi").await.unwrap();
+
+        assert_eq!(answer, "done");
+        assert!(matches!(
+            rx.try_recv().unwrap().response,
+            crate::OutboundResponse::Reaction(emoji) if emoji == "👋"
+        ));
+        assert_eq!(model.requests().len(), 2);
+        assert_eq!(model.requests()[0].tools[0].name, "react");
+    }
+}
@@ -6,37 +6,104 @@
 //! ## ToolServer Topology
 //!
 //! **Channel ToolServer** (one per channel):
-//! - `reply`, `branch`, `spawn_worker`, `route`, `cancel`, `skip`, `react` — added
-//!   dynamically per conversation turn via `add_channel_tools()` /
-//!   `remove_channel_tools()` because they hold per-channel state.
+//! - `reply`, `branch`, `spawn_worker`, `route`, `cancel`, `skip`, `react`,
+//!   `edit_message`, `delete_message`, `fork_channel`, `profile_update`,
+//!   `remind_me`, `usage`, `workspace_list`, `workspace_read`, `files_read`,
+//!   `files_list`, `calculator` — added dynamically per conversation turn via `add_channel_tools()` /
+//!   `remove_channel_tools()` because they hold per-channel state. On structured
+//!   turns `reply` is the `StructuredReplyTool`, which takes a fixed shape.
+//! - `schedule`, `unschedule`, `cron` — added alongside them when the agent has a
+//!   cron scheduler.
+//! - `use_skill` — added alongside them when the agent has skills.
 //! - No memory tools — the channel delegates memory work to branches.
 //!
 //! **Branch ToolServer** (one per branch, isolated):
-//! - `memory_save` + `memory_recall` + `memory_delete` — registered at creation
+//! - `memory_save_fact` + `memory_log_event` + `memory_recall` +
+//!   `memory_delete` — registered at creation. Facts are semantic memories;
+//!   events are episodic and logged against the branch's channel
+//! - `knowledge_search` — searches documents in the agent's knowledge base
+//! - `web_fetch` — shares a page cache with the rest of the channel
+//! - `calculator` — arithmetic, date math and unit conversions
+//! - `read_artifact` — reads results too large to show inline (see `spill`)
+//! - `sub_branch` — added after creation when the branch is shallower than
+//!   `max_branch_depth`
+//! - `escalate_to_worker` — added after creation; asks the channel to spawn a
+//!   worker when the branch needs side effects it can't produce itself
 //!
 //! **Worker ToolServer** (one per worker, created at spawn time):
 //! - `shell`, `file`, `exec` — stateless, registered at creation
+//! - `container_run` — runs code in a throwaway container, when containers
+//!   are enabled
 //! - `set_status` — per-worker instance, registered at creation
+//! - `attach_file` — sends a file to the spawning channel, for workers that
+//!   have one
+//! - `knowledge_search` + `knowledge_ingest` — query and add to the knowledge base
+//! - `web_search` — searches through the agent's configured provider, when
+//!   one is set up
+//! - `web_fetch` — shares a page cache with the rest of the channel
+//! - `http_request` — GET and POST to the agent's allowed domains, when
+//!   any are configured
+//! - `sql_query` — queries the agent's SQL databases, when any are configured
+//! - `github_*` — issues, pull requests, diffs and code search, when a
+//!   GitHub token is configured
+//! - `coolify_*` — applications, deployments and restarts, when a Coolify
+//!   instance is configured and the worker's sender tier permits them
+//! - `files_read`, `files_write`, `files_list`, `files_diff` — the agent's
+//!   file store, for documents that outlive the worker
+//! - `calendar_list`, `calendar_create`, `calendar_delete` — the agent's
+//!   CalDAV or Google calendar, when one is configured; create and delete
+//!   are left out when it's read-only
+//! - `calculator` — arithmetic, date math and unit conversions
+//! - `read_artifact` — reads results too large to show inline
+//!
+//! Branch, worker, and cortex chat tools with open-ended output (shell, exec,
+//! container_run, file, file store reads and diffs, browser, web
+//! search/fetch, HTTP requests, GitHub reads, SQL queries, calendar
+//! listings, Coolify logs, MCP) are wrapped by `OutputSpill`, which stores results above
+//! `tool_output_max_tokens` as artifacts.
 //!
 //! **Cortex ToolServer** (one per agent):
 //! - `memory_save` — registered at startup
+//!
+//! The agent's `ToolPolicyConfig` can narrow the channel and worker sets, and
+//! its `AuthConfig` narrows the channel set by the sender's tier.
 
+pub mod agent_files;
+pub mod attach_file;
 pub mod branch_tool;
 pub mod browser;
+pub mod calculator;
+pub mod calendar;
 pub mod cancel;
 pub mod channel_recall;
 pub mod conclude_link;
+pub mod container_run;
+pub mod coolify;
 pub mod cron;
+pub mod delete_message;
+pub mod edit_message;
+pub mod escalate_to_worker;
 pub mod exec;
 pub mod file;
+pub mod fork_channel;
+pub mod github;
+pub mod http_request;
+pub mod knowledge_ingest;
+pub mod knowledge_search;
 pub mod mcp;
 pub mod memory_delete;
+pub mod memory_log_event;
 pub mod memory_recall;
 pub mod memory_save;
+pub mod memory_save_fact;
+pub mod profile_update;
 pub mod react;
+pub mod read_artifact;
 pub mod read_skill;
+pub mod remind_me;
 pub mod reply;
 pub mod route;
+pub mod schedule;
 pub mod send_agent_message;
 pub mod send_file;
 pub mod send_message_to_another_channel;
@@ -44,14 +111,36 @@ pub mod set_status;
 pub mod shell;
 pub mod skip;
 pub mod spawn_worker;
+pub mod spill;
+pub mod sql;
+pub mod structured_reply;
+pub mod sub_branch;
+pub mod unschedule;
+pub mod usage;
+pub mod use_skill;
+pub mod web_fetch;
 pub mod web_search;
 pub mod worker_inspect;
+pub mod workspace_list;
+pub mod workspace_read;
 
+pub use agent_files::{
+    AgentFileEntry, AgentFiles, AgentFilesError, FilesDiffArgs, FilesDiffOutput, FilesDiffTool,
+    FilesListArgs, FilesListOutput, FilesListTool, FilesReadArgs, FilesReadOutput, FilesReadTool,
+    FilesWriteArgs, FilesWriteOutput, FilesWriteTool,
+};
+pub use attach_file::{AttachFileArgs, AttachFileError, AttachFileOutput, AttachFileTool};
 pub use branch_tool::{BranchArgs, BranchError, BranchOutput, BranchTool};
 pub use browser::{
     ActKind, BrowserAction, BrowserArgs, BrowserError, BrowserOutput, BrowserTool, ElementSummary,
     TabInfo,
 };
+pub use calculator::{CalculatorArgs, CalculatorError, CalculatorOutput, CalculatorTool};
+pub use calendar::{
+    CalendarCreateArgs, CalendarCreateOutput, CalendarCreateTool, CalendarDeleteArgs,
+    CalendarDeleteOutput, CalendarDeleteTool, CalendarEventOutput, CalendarListArgs,
+    CalendarListOutput, CalendarListTool,
+};
 pub use cancel::{CancelArgs, CancelError, CancelOutput, CancelTool};
 pub use channel_recall::{
     ChannelRecallArgs, ChannelRecallError, ChannelRecallOutput, ChannelRecallTool,
@@ -60,23 +149,68 @@ pub use conclude_link::{
     ConcludeLinkArgs, ConcludeLinkError, ConcludeLinkFlag, ConcludeLinkOutput, ConcludeLinkSummary,
     ConcludeLinkTool, new_conclude_link,
 };
+pub use container_run::{
+    ContainerRunArgs, ContainerRunError, ContainerRunOutput, ContainerRunTool,
+};
+pub use coolify::{
+    CoolifyApplication, CoolifyClient, CoolifyDeployArgs, CoolifyDeployOutput, CoolifyDeployTool,
+    CoolifyDeploymentLogsArgs, CoolifyDeploymentLogsOutput, CoolifyDeploymentLogsTool,
+    CoolifyError, CoolifyListApplicationsArgs, CoolifyListApplicationsOutput,
+    CoolifyListApplicationsTool, CoolifyQueuedDeployment, CoolifyRestartArgs, CoolifyRestartOutput,
+    CoolifyRestartTool,
+};
 pub use cron::{CronArgs, CronError, CronOutput, CronTool};
+pub use delete_message::{
+    DeleteMessageArgs, DeleteMessageError, DeleteMessageOutput, DeleteMessageTool,
+};
+pub use edit_message::{EditMessageArgs, EditMessageError, EditMessageOutput, EditMessageTool};
+pub use escalate_to_worker::{
+    EscalateToWorkerArgs, EscalateToWorkerError, EscalateToWorkerOutput, EscalateToWorkerTool,
+};
 pub use exec::{EnvVar, ExecArgs, ExecError, ExecOutput, ExecResult, ExecTool};
 pub use file::{FileArgs, FileEntry, FileEntryOutput, FileError, FileOutput, FileTool, FileType};
+pub use fork_channel::{ForkChannelArgs, ForkChannelError, ForkChannelOutput, ForkChannelTool};
+pub use github::{
+    GitHubClient, GitHubCodeMatch, GitHubCommentArgs, GitHubCommentTool, GitHubCreateIssueArgs,
+    GitHubCreateIssueTool, GitHubCreatedOutput, GitHubError, GitHubItem, GitHubListIssuesArgs,
+    GitHubListIssuesTool, GitHubListOutput, GitHubListPullsArgs, GitHubListPullsTool,
+    GitHubPullDiffArgs, GitHubPullDiffOutput, GitHubPullDiffTool, GitHubSearchCodeArgs,
+    GitHubSearchCodeOutput, GitHubSearchCodeTool,
+};
+pub use http_request::{HttpRequestArgs, HttpRequestError, HttpRequestOutput, HttpRequestTool};
+pub use knowledge_ingest::{
+    KnowledgeIngestArgs, KnowledgeIngestError, KnowledgeIngestOutput, KnowledgeIngestTool,
+};
+pub use knowledge_search::{
+    KnowledgeSearchArgs, KnowledgeSearchError, KnowledgeSearchOutput, KnowledgeSearchTool,
+};
 pub use mcp::{McpToolAdapter, McpToolError, McpToolOutput};
 pub use memory_delete::{
     MemoryDeleteArgs, MemoryDeleteError, MemoryDeleteOutput, MemoryDeleteTool,
 };
+pub use memory_log_event::{MemoryLogEventArgs, MemoryLogEventError, MemoryLogEventTool};
 pub use memory_recall::{
     MemoryOutput, MemoryRecallArgs, MemoryRecallError, MemoryRecallOutput, MemoryRecallTool,
 };
 pub use memory_save::{
     AssociationInput, MemorySaveArgs, MemorySaveError, MemorySaveOutput, MemorySaveTool,
 };
+pub use memory_save_fact::{MemorySaveFactArgs, MemorySaveFactError, MemorySaveFactTool};
+pub use profile_update::{
+    ProfileUpdateArgs, ProfileUpdateError, ProfileUpdateOutput, ProfileUpdateTool,
+};
 pub use react::{ReactArgs, ReactError, ReactOutput, ReactTool};
+pub use read_artifact::{
+    ReadArtifactArgs, ReadArtifactError, ReadArtifactOutput, ReadArtifactTool,
+};
 pub use read_skill::{ReadSkillArgs, ReadSkillError, ReadSkillOutput, ReadSkillTool};
-pub use reply::{RepliedFlag, ReplyArgs, ReplyError, ReplyOutput, ReplyTool, new_replied_flag};
+pub use remind_me::{RemindMeArgs, RemindMeError, RemindMeOutput, RemindMeTool};
+pub use reply::{
+    RepliedFlag, ReplyArgs, ReplyError, ReplyOutput, ReplyTool, SentReplies, SentReply,
+    new_replied_flag,
+};
 pub use route::{RouteArgs, RouteError, RouteOutput, RouteTool};
+pub use schedule::{ScheduleArgs, ScheduleError, ScheduleOutput, ScheduleTool};
 pub use send_agent_message::{
     SendAgentMessageArgs, SendAgentMessageError, SendAgentMessageOutput, SendAgentMessageTool,
 };
@@ -85,24 +219,48 @@ pub use send_message_to_another_channel::{
     SendMessageArgs, SendMessageError, SendMessageOutput, SendMessageTool,
 };
 pub use set_status::{SetStatusArgs, SetStatusError, SetStatusOutput, SetStatusTool};
-pub use shell::{ShellArgs, ShellError, ShellOutput, ShellResult, ShellTool};
+pub use shell::{ShellArgs, ShellError, ShellOutput, ShellProgress, ShellResult, ShellTool};
 pub use skip::{SkipArgs, SkipError, SkipFlag, SkipOutput, SkipTool, new_skip_flag};
 pub use spawn_worker::{SpawnWorkerArgs, SpawnWorkerError, SpawnWorkerOutput, SpawnWorkerTool};
+pub use spill::{OutputSpill, Spilling};
+pub use sql::{SqlError, SqlQueryArgs, SqlQueryOutput, SqlQueryTool};
+pub use structured_reply::{StructuredReplyError, StructuredReplyOutput, StructuredReplyTool};
+pub use sub_branch::{SubBranchArgs, SubBranchError, SubBranchOutput, SubBranchTool};
+pub use unschedule::{UnscheduleArgs, UnscheduleError, UnscheduleOutput, UnscheduleTool};
+pub use usage::{UsageArgs, UsageError, UsageOutput, UsageTool};
+pub use use_skill::{SkillToolSummary, UseSkillArgs, UseSkillError, UseSkillOutput, UseSkillTool};
+pub use web_fetch::{
+    WebFetchArgs, WebFetchCache, WebFetchError, WebFetchOutput, WebFetchTool, web_fetch_cache,
+};
 pub use web_search::{SearchResult, WebSearchArgs, WebSearchError, WebSearchOutput, WebSearchTool};
 pub use worker_inspect::{
     WorkerInspectArgs, WorkerInspectError, WorkerInspectOutput, WorkerInspectTool,
 };
+pub use workspace_list::{
+    WorkspaceEntry, WorkspaceListArgs, WorkspaceListError, WorkspaceListOutput, WorkspaceListTool,
+};
+pub use workspace_read::{
+    WorkspaceReadArgs, WorkspaceReadError, WorkspaceReadOutput, WorkspaceReadTool,
+};
 
 use crate::agent::channel::ChannelState;
-use crate::config::{BrowserConfig, RuntimeConfig};
-use crate::memory::MemorySearch;
+use crate::auth::AuthTier;
+use crate::calendar::CalendarClient;
+use crate::config::{BrowserConfig, RuntimeConfig, WebSearchConfig};
+use crate::hooks::ToolSteps;
+use crate::knowledge::KnowledgeBase;
+use crate::media::MediaStore;
+use crate::memory::{MemoryAudience, MemorySearch};
 use crate::sandbox::Sandbox;
-use crate::{AgentId, ChannelId, OutboundResponse, ProcessEvent, WorkerId};
+use crate::{
+    AgentId, ChannelId, OutboundResponse, ProcessEvent, ProcessType, ResponseSender, TurnId,
+    WorkerId,
+};
 use rig::tool::Tool as _;
 use rig::tool::server::{ToolServer, ToolServerHandle};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::{broadcast, mpsc};
+use tokio::sync::broadcast;
 
 /// Deserialize a `u64` that may arrive as either a JSON number or a JSON string.
 ///
@@ -172,12 +330,15 @@ pub fn truncate_output(value: &str, max_bytes: usize) -> String {
         end -= 1;
     }
 
-    let total = value.len();
-    let truncated_bytes = total - end;
+    format!("{}{}", &value[..end], truncation_notice(end, value.len()))
+}
+
+/// Notice appended to output cut down to `shown` of `total` bytes.
+pub(crate) fn truncation_notice(shown: usize, total: usize) -> String {
     format!(
-        "{}\n\n[output truncated: showed {end} of {total} bytes ({truncated_bytes} bytes omitted). \
+        "\n\n[output truncated: showed {shown} of {total} bytes ({} bytes omitted). \
          Use head/tail/offset to read specific sections]",
-        &value[..end]
+        total - shown
     )
 }
 
@@ -222,12 +383,13 @@ pub fn should_block_user_visible_text(value: &str) -> bool {
 ///
 /// Called when a conversation turn begins. These tools hold per-turn state
 /// (response sender, skip flag) that changes between turns. Cleaned up via
-/// `remove_channel_tools()` when the turn ends.
+/// `remove_channel_tools()` when the turn ends. `structured` swaps `reply` for
+/// the structured form.
 #[allow(clippy::too_many_arguments)]
 pub async fn add_channel_tools(
     handle: &ToolServerHandle,
     state: ChannelState,
-    response_tx: mpsc::Sender<OutboundResponse>,
+    response_tx: ResponseSender,
     conversation_id: impl Into<String>,
     skip_flag: SkipFlag,
     replied_flag: RepliedFlag,
@@ -235,8 +397,11 @@ pub async fn add_channel_tools(
     send_agent_message_tool: Option<SendAgentMessageTool>,
     conclude_link: Option<(ConcludeLinkFlag, ConcludeLinkSummary)>,
     message_source: Option<String>,
+    trigger_message_id: Option<String>,
     originating_channel_override: Option<String>,
     originating_source_override: Option<String>,
+    sender_tier: AuthTier,
+    structured: bool,
 ) -> Result<(), rig::tool::server::ToolServerError> {
     let conversation_id = conversation_id.into();
     let is_link_channel = conversation_id.starts_with("link:");
@@ -277,16 +442,35 @@ pub async fn add_channel_tools(
         .get(state.deps.agent_id.as_ref())
         .cloned()
         .unwrap_or_else(|| state.deps.agent_id.to_string());
-    handle
-        .add_tool(ReplyTool::new(
-            response_tx.clone(),
-            conversation_id.clone(),
-            state.conversation_logger.clone(),
-            state.channel_id.clone(),
-            replied_flag.clone(),
-            agent_display_name,
-        ))
-        .await?;
+    if structured {
+        handle
+            .add_tool(StructuredReplyTool::new(
+                response_tx.clone(),
+                conversation_id.clone(),
+                state.conversation_logger.clone(),
+                state.channel_id.clone(),
+                replied_flag.clone(),
+                agent_display_name,
+                trigger_message_id,
+                state.drafts.clone(),
+                state.sent_replies.clone(),
+            ))
+            .await?;
+    } else {
+        handle
+            .add_tool(ReplyTool::new(
+                response_tx.clone(),
+                conversation_id.clone(),
+                state.conversation_logger.clone(),
+                state.channel_id.clone(),
+                replied_flag.clone(),
+                agent_display_name,
+                trigger_message_id,
+                state.drafts.clone(),
+                state.sent_replies.clone(),
+            ))
+            .await?;
+    }
     handle.add_tool(BranchTool::new(state.clone())).await?;
     handle.add_tool(SpawnWorkerTool::new(state.clone())).await?;
     handle.add_tool(RouteTool::new(state.clone())).await?;
@@ -302,13 +486,64 @@ pub async fn add_channel_tools(
         .add_tool(SendFileTool::new(
             response_tx.clone(),
             state.deps.runtime_config.workspace_dir.clone(),
+            state.deps.media.clone(),
+            state.channel_id.to_string(),
+        ))
+        .await?;
+    handle
+        .add_tool(UsageTool::new(
+            state.cost_tracker.clone(),
+            **state.deps.runtime_config.channel_budget_usd.load(),
         ))
         .await?;
-    handle.add_tool(CancelTool::new(state)).await?;
+    if let Some(cron) = &cron_tool {
+        handle
+            .add_tool(ScheduleTool::new(
+                cron.clone(),
+                state.channel_store.clone(),
+                state.channel_id.clone(),
+            ))
+            .await?;
+        handle
+            .add_tool(UnscheduleTool::new(
+                cron.clone(),
+                state.channel_store.clone(),
+                state.channel_id.clone(),
+            ))
+            .await?;
+    }
+    handle.add_tool(ForkChannelTool::new(state.clone())).await?;
+    handle
+        .add_tool(ProfileUpdateTool::new(state.clone()))
+        .await?;
+    handle.add_tool(RemindMeTool::new(state.clone())).await?;
+    handle.add_tool(CancelTool::new(state.clone())).await?;
+    if !state.deps.runtime_config.skills.load().is_empty() {
+        handle
+            .add_tool(UseSkillTool::new(state.deps.runtime_config.clone()))
+            .await?;
+    }
+    let workspaces_dir = &state.deps.runtime_config.worker_workspaces_dir;
+    handle
+        .add_tool(WorkspaceListTool::new(workspaces_dir.clone()))
+        .await?;
+    handle
+        .add_tool(WorkspaceReadTool::new(workspaces_dir.clone()))
+        .await?;
+    let files = AgentFiles::new(state.deps.runtime_config.files_dir.clone());
+    handle.add_tool(FilesReadTool::new(files.clone())).await?;
+    handle.add_tool(FilesListTool::new(files)).await?;
+    handle.add_tool(CalculatorTool::new()).await?;
     handle
         .add_tool(SkipTool::new(skip_flag.clone(), response_tx.clone()))
         .await?;
     handle.add_tool(ReactTool::new(response_tx.clone())).await?;
+    handle
+        .add_tool(EditMessageTool::new(response_tx.clone()))
+        .await?;
+    handle
+        .add_tool(DeleteMessageTool::new(response_tx.clone()))
+        .await?;
     if let Some(cron) = cron_tool {
         handle.add_tool(cron).await?;
     }
@@ -334,9 +569,51 @@ pub async fn add_channel_tools(
             .add_tool(ConcludeLinkTool::new(flag, summary, response_tx))
             .await?;
     }
+
+    // Drop whatever the agent's tool policy doesn't permit in this
+    // conversation, or the sender's tier doesn't allow.
+    let tool_policy = state.deps.runtime_config.tool_policy.load();
+    let auth = state.deps.runtime_config.auth.load();
+    for &tool_name in CHANNEL_TOOL_NAMES {
+        if !tool_policy.permits(ProcessType::Channel, Some(&conversation_id), tool_name)
+            || !auth.permits_tool(sender_tier, tool_name)
+        {
+            let _ = handle.remove_tool(tool_name).await;
+        }
+    }
     Ok(())
 }
 
+/// Every tool `add_channel_tools()` can register.
+const CHANNEL_TOOL_NAMES: &[&str] = &[
+    ReplyTool::NAME,
+    BranchTool::NAME,
+    SpawnWorkerTool::NAME,
+    RouteTool::NAME,
+    CancelTool::NAME,
+    SkipTool::NAME,
+    SendFileTool::NAME,
+    ReactTool::NAME,
+    EditMessageTool::NAME,
+    DeleteMessageTool::NAME,
+    ForkChannelTool::NAME,
+    ProfileUpdateTool::NAME,
+    RemindMeTool::NAME,
+    UsageTool::NAME,
+    UseSkillTool::NAME,
+    WorkspaceListTool::NAME,
+    WorkspaceReadTool::NAME,
+    FilesReadTool::NAME,
+    FilesListTool::NAME,
+    CalculatorTool::NAME,
+    CronTool::NAME,
+    ScheduleTool::NAME,
+    UnscheduleTool::NAME,
+    SendMessageTool::NAME,
+    SendAgentMessageTool::NAME,
+    ConcludeLinkTool::NAME,
+];
+
 fn link_counterparty_for_agent(conversation_id: &str, agent_id: &str) -> Option<String> {
     let rest = conversation_id.strip_prefix("link:")?;
     let (self_id, counterparty_id) = rest.split_once(':')?;
@@ -354,84 +631,297 @@ fn link_counterparty_for_agent(conversation_id: &str, agent_id: &str) -> Option<
 pub async fn remove_channel_tools(
     handle: &ToolServerHandle,
 ) -> Result<(), rig::tool::server::ToolServerError> {
-    handle.remove_tool(ReplyTool::NAME).await?;
-    handle.remove_tool(BranchTool::NAME).await?;
-    handle.remove_tool(SpawnWorkerTool::NAME).await?;
-    handle.remove_tool(RouteTool::NAME).await?;
-    handle.remove_tool(CancelTool::NAME).await?;
-    handle.remove_tool(SkipTool::NAME).await?;
-    handle.remove_tool(SendFileTool::NAME).await?;
-    handle.remove_tool(ReactTool::NAME).await?;
-    // Cron, send_message, send_agent_message, and conclude_link removal is best-effort since not all channels have them
-    let _ = handle.remove_tool(CronTool::NAME).await;
-    let _ = handle.remove_tool(SendMessageTool::NAME).await;
-    let _ = handle.remove_tool(SendAgentMessageTool::NAME).await;
-    let _ = handle.remove_tool(ConcludeLinkTool::NAME).await;
+    // Best-effort: which tools are present depends on the agent's config, the
+    // conversation, and its tool policy.
+    for &tool_name in CHANNEL_TOOL_NAMES {
+        let _ = handle.remove_tool(tool_name).await;
+    }
     Ok(())
 }
 
 /// Create a per-branch ToolServer with memory tools.
 ///
 /// Each branch gets its own isolated ToolServer so `memory_recall` is never
-/// visible to the channel. Both `memory_save` and `memory_recall` are
-/// registered at creation.
+/// visible to the channel. The memory tools are registered at creation, with
+/// events logged against `channel_id` and recall limited to the memories
+/// `channel_id` and `sender_id` may see, along with `knowledge_search`,
+/// `calculator`, and `web_fetch` backed by the channel's page cache. Oversized `web_fetch` results are
+/// spilled to artifacts.
+#[allow(clippy::too_many_arguments)]
 pub fn create_branch_tool_server(
     memory_search: Arc<MemorySearch>,
+    channel_id: Option<crate::ChannelId>,
+    sender_id: Option<String>,
+    knowledge: KnowledgeBase,
     conversation_logger: crate::conversation::history::ConversationLogger,
     channel_store: crate::conversation::ChannelStore,
     run_logger: crate::conversation::history::ProcessRunLogger,
     agent_id: &str,
+    web_fetch_cache: WebFetchCache,
+    spill: OutputSpill,
 ) -> ToolServerHandle {
+    let mut recall = MemoryRecallTool::new(memory_search.clone());
+    if let Some(channel_id) = &channel_id {
+        recall = recall.with_audience(MemoryAudience::new(channel_id.as_ref(), sender_id.clone()));
+    }
+
     ToolServer::new()
-        .tool(MemorySaveTool::new(memory_search.clone()))
-        .tool(MemoryRecallTool::new(memory_search.clone()))
+        .tool(MemorySaveFactTool::new(
+            memory_search.clone(),
+            channel_id.clone(),
+            sender_id.clone(),
+        ))
+        .tool(MemoryLogEventTool::new(
+            memory_search.clone(),
+            channel_id,
+            sender_id,
+        ))
+        .tool(recall)
         .tool(MemoryDeleteTool::new(memory_search))
+        .tool(KnowledgeSearchTool::new(knowledge))
         .tool(ChannelRecallTool::new(conversation_logger, channel_store))
         .tool(WorkerInspectTool::new(run_logger, agent_id.to_string()))
+        .tool(spill.wrap(WebFetchTool::new(web_fetch_cache)))
+        .tool(CalculatorTool::new())
+        .tool(ReadArtifactTool::new(spill.store()))
         .run()
 }
 
 /// Create a per-worker ToolServer with task-appropriate tools.
 ///
 /// Each worker gets its own isolated ToolServer. The `set_status` tool is bound to
-/// the specific worker's ID so status updates route correctly. The browser tool
-/// is included when browser automation is enabled in the agent config.
+/// the specific worker's ID so status updates route correctly, and tools that
+/// emit events stamp them with `turn_id`, the channel turn that spawned the
+/// worker. The browser tool is included when browser automation is enabled in
+/// the agent config.
 ///
-/// Shell and exec commands are sandboxed via the `Sandbox` backend.
-/// File operations are restricted to `workspace` via path validation.
+/// Shell and exec commands are sandboxed via the `Sandbox` backend. Shell output
+/// is streamed to the channel as progress, attributed to the step counted in
+/// `tool_steps`. File operations are restricted to `workspace` via path
+/// validation. When the worker has its own `worker_workspace`, shell and exec
+/// commands start there and all three tools accept paths inside it. Results
+/// too large for the context are spilled to artifacts readable with
+/// `read_artifact`. Tools denied by the agent's tool policy are left out, as
+/// are tools missing from a non-empty `allowed_tools` (a worker preset's
+/// allowlist); `set_status` and `read_artifact` are always kept. The Coolify
+/// tools also need the `[auth]` rules to permit them for `sender_tier`.
 #[allow(clippy::too_many_arguments)]
 pub fn create_worker_tool_server(
     agent_id: AgentId,
     worker_id: WorkerId,
     channel_id: Option<ChannelId>,
+    turn_id: Option<TurnId>,
     event_tx: broadcast::Sender<ProcessEvent>,
     browser_config: BrowserConfig,
     screenshot_dir: PathBuf,
-    brave_search_key: Option<String>,
+    web_search: Option<WebSearchConfig>,
     workspace: PathBuf,
+    worker_workspace: Option<PathBuf>,
     sandbox: Arc<Sandbox>,
     mcp_tools: Vec<McpToolAdapter>,
+    knowledge: KnowledgeBase,
+    media: MediaStore,
     runtime_config: Arc<RuntimeConfig>,
+    tool_steps: Arc<ToolSteps>,
+    web_fetch_cache: WebFetchCache,
+    allowed_tools: &[String],
+    sender_tier: AuthTier,
+    spill: OutputSpill,
 ) -> ToolServerHandle {
-    let mut server = ToolServer::new()
-        .tool(ShellTool::new(workspace.clone(), sandbox.clone()))
-        .tool(FileTool::new(workspace.clone()))
-        .tool(ExecTool::new(workspace, sandbox))
-        .tool(SetStatusTool::new(
-            agent_id, worker_id, channel_id, event_tx,
-        ))
-        .tool(ReadSkillTool::new(runtime_config));
+    let tool_policy = runtime_config.tool_policy.load_full();
+    let auth = runtime_config.auth.load_full();
+    let container = runtime_config.container.load_full();
+    let http_request = runtime_config.http_request.load_full();
+    let calendar = runtime_config.calendar.load_full();
+    let github_token = (**runtime_config.github_token.load()).clone();
+    let coolify = match (
+        (**runtime_config.coolify_url.load()).clone(),
+        (**runtime_config.coolify_token.load()).clone(),
+    ) {
+        (Some(url), Some(token)) => Some(CoolifyClient::new(&url, token)),
+        _ => None,
+    };
+    let permits = |tool_name: &str| {
+        let allowed = allowed_tools.is_empty()
+            || [SetStatusTool::NAME, ReadArtifactTool::NAME].contains(&tool_name)
+            || allowed_tools.iter().any(|allowed| allowed == tool_name);
+        allowed && tool_policy.permits(ProcessType::Worker, None, tool_name)
+    };
 
-    if browser_config.enabled {
-        server = server.tool(BrowserTool::new(browser_config, screenshot_dir));
+    let mut server = ToolServer::new();
+    if permits(ShellTool::NAME) {
+        let progress = ShellProgress::new(
+            agent_id.clone(),
+            worker_id,
+            channel_id.clone(),
+            turn_id,
+            event_tx.clone(),
+            tool_steps,
+        );
+        server = server.tool(
+            spill.wrap(
+                ShellTool::new(workspace.clone(), sandbox.clone())
+                    .with_worker_workspace(worker_workspace.clone())
+                    .with_progress(progress),
+            ),
+        );
+    }
+    if permits(FileTool::NAME) {
+        server = server.tool(spill.wrap(
+            FileTool::new(workspace.clone()).with_worker_workspace(worker_workspace.clone()),
+        ));
+    }
+    if permits(KnowledgeIngestTool::NAME) {
+        server = server.tool(KnowledgeIngestTool::new(
+            knowledge.clone(),
+            workspace.clone(),
+            runtime_config.clone(),
+        ));
+    }
+    if let Some(channel_id) = &channel_id
+        && permits(AttachFileTool::NAME)
+    {
+        server = server.tool(AttachFileTool::new(
+            agent_id.clone(),
+            worker_id,
+            channel_id.clone(),
+            turn_id,
+            event_tx.clone(),
+            media,
+            workspace.clone(),
+            worker_workspace.clone(),
+        ));
+    }
+    if permits(ExecTool::NAME) {
+        server = server.tool(
+            spill.wrap(ExecTool::new(workspace, sandbox).with_worker_workspace(worker_workspace)),
+        );
+    }
+    if container.enabled && permits(ContainerRunTool::NAME) {
+        server = server.tool(spill.wrap(ContainerRunTool::new((*container).clone())));
+    }
+    let files = AgentFiles::new(runtime_config.files_dir.clone());
+    let sql = runtime_config.sql.load_full();
+    if !sql.is_empty() && permits(SqlQueryTool::NAME) {
+        match SqlQueryTool::new(&sql) {
+            Ok(tool) => server = server.tool(spill.wrap(tool)),
+            Err(error) => tracing::warn!(%error, "failed to set up sql_query tool"),
+        }
+    }
+    if permits(FilesReadTool::NAME) {
+        server = server.tool(spill.wrap(FilesReadTool::new(files.clone())));
+    }
+    if permits(FilesWriteTool::NAME) {
+        server = server.tool(FilesWriteTool::new(files.clone()));
+    }
+    if permits(FilesListTool::NAME) {
+        server = server.tool(FilesListTool::new(files.clone()));
+    }
+    if permits(FilesDiffTool::NAME) {
+        server = server.tool(spill.wrap(FilesDiffTool::new(files)));
+    }
+    if permits(KnowledgeSearchTool::NAME) {
+        server = server.tool(KnowledgeSearchTool::new(knowledge));
+    }
+    if permits(CalculatorTool::NAME) {
+        server = server.tool(CalculatorTool::new());
+    }
+    if permits(SetStatusTool::NAME) {
+        server = server.tool(SetStatusTool::new(
+            agent_id, worker_id, channel_id, turn_id, event_tx,
+        ));
+    }
+    if permits(ReadSkillTool::NAME) {
+        server = server.tool(ReadSkillTool::new(runtime_config));
     }
 
-    if let Some(key) = brave_search_key {
-        server = server.tool(WebSearchTool::new(key));
+    if browser_config.enabled && permits(BrowserTool::NAME) {
+        server = server.tool(spill.wrap(BrowserTool::new(browser_config, screenshot_dir)));
+    }
+
+    if let Some(config) = web_search
+        && permits(WebSearchTool::NAME)
+    {
+        let mut web_search = WebSearchTool::new(config);
+        if permits(WebFetchTool::NAME) {
+            web_search = web_search.with_web_fetch();
+        }
+        server = server.tool(spill.wrap(web_search));
+    }
+
+    if permits(WebFetchTool::NAME) {
+        server = server.tool(spill.wrap(WebFetchTool::new(web_fetch_cache)));
+    }
+
+    if !http_request.allowed_domains.is_empty() && permits(HttpRequestTool::NAME) {
+        server = server.tool(spill.wrap(HttpRequestTool::new(&http_request)));
+    }
+
+    if calendar.is_configured() {
+        let tz = calendar.tz();
+        match CalendarClient::new(&calendar) {
+            Ok(client) => {
+                if permits(CalendarListTool::NAME) {
+                    server = server.tool(spill.wrap(CalendarListTool::new(client.clone(), tz)));
+                }
+                if !calendar.read_only && permits(CalendarCreateTool::NAME) {
+                    server = server.tool(CalendarCreateTool::new(client.clone(), tz));
+                }
+                if !calendar.read_only && permits(CalendarDeleteTool::NAME) {
+                    server = server.tool(CalendarDeleteTool::new(client));
+                }
+            }
+            Err(error) => tracing::warn!(%error, "failed to set up calendar tools"),
+        }
+    }
+
+    if let Some(token) = github_token {
+        let github = GitHubClient::new(token);
+        if permits(GitHubListIssuesTool::NAME) {
+            server = server.tool(spill.wrap(GitHubListIssuesTool::new(github.clone())));
+        }
+        if permits(GitHubListPullsTool::NAME) {
+            server = server.tool(spill.wrap(GitHubListPullsTool::new(github.clone())));
+        }
+        if permits(GitHubPullDiffTool::NAME) {
+            server = server.tool(spill.wrap(GitHubPullDiffTool::new(github.clone())));
+        }
+        if permits(GitHubSearchCodeTool::NAME) {
+            server = server.tool(spill.wrap(GitHubSearchCodeTool::new(github.clone())));
+        }
+        if permits(GitHubCreateIssueTool::NAME) {
+            server = server.tool(GitHubCreateIssueTool::new(github.clone()));
+        }
+        if permits(GitHubCommentTool::NAME) {
+            server = server.tool(GitHubCommentTool::new(github));
+        }
+    }
+
+    if let Some(coolify) = coolify {
+        let permits_coolify =
+            |tool_name: &str| permits(tool_name) && auth.permits_tool(sender_tier, tool_name);
+        if permits_coolify(CoolifyListApplicationsTool::NAME) {
+            server = server.tool(CoolifyListApplicationsTool::new(coolify.clone()));
+        }
+        if permits_coolify(CoolifyDeploymentLogsTool::NAME) {
+            server = server.tool(spill.wrap(CoolifyDeploymentLogsTool::new(coolify.clone())));
+        }
+        if permits_coolify(CoolifyDeployTool::NAME) {
+            server = server.tool(CoolifyDeployTool::new(coolify.clone()));
+        }
+        if permits_coolify(CoolifyRestartTool::NAME) {
+            server = server.tool(CoolifyRestartTool::new(coolify));
+        }
+    }
+
+    if permits(ReadArtifactTool::NAME) {
+        server = server.tool(ReadArtifactTool::new(spill.store()));
     }
 
     for mcp_tool in mcp_tools {
-        server = server.tool(mcp_tool);
+        if permits(&mcp_tool.name()) {
+            server = server.tool(spill.wrap(mcp_tool));
+        }
     }
 
     server.run()
@@ -450,37 +940,52 @@ pub fn create_cortex_tool_server(memory_search: Arc<MemorySearch>) -> ToolServer
 /// Create a ToolServer for cortex chat sessions.
 ///
 /// Combines branch tools (memory) with worker tools (shell, file, exec) to give
-/// the interactive cortex full capabilities. Does not include channel-specific
+/// the interactive cortex full capabilities. `web_search` comes with its own
+/// `web_fetch` for reading results in full. Does not include channel-specific
 /// tools (reply, react, skip) since the cortex chat doesn't talk to platforms.
 #[allow(clippy::too_many_arguments)]
 pub fn create_cortex_chat_tool_server(
     memory_search: Arc<MemorySearch>,
+    knowledge: KnowledgeBase,
     conversation_logger: crate::conversation::history::ConversationLogger,
     channel_store: crate::conversation::ChannelStore,
     run_logger: crate::conversation::history::ProcessRunLogger,
     agent_id: &str,
     browser_config: BrowserConfig,
     screenshot_dir: PathBuf,
-    brave_search_key: Option<String>,
+    web_search: Option<WebSearchConfig>,
     workspace: PathBuf,
     sandbox: Arc<Sandbox>,
+    runtime_config: Arc<RuntimeConfig>,
+    spill: OutputSpill,
 ) -> ToolServerHandle {
     let mut server = ToolServer::new()
         .tool(MemorySaveTool::new(memory_search.clone()))
         .tool(MemoryRecallTool::new(memory_search.clone()))
         .tool(MemoryDeleteTool::new(memory_search))
+        .tool(KnowledgeSearchTool::new(knowledge.clone()))
+        .tool(KnowledgeIngestTool::new(
+            knowledge,
+            workspace.clone(),
+            runtime_config,
+        ))
         .tool(ChannelRecallTool::new(conversation_logger, channel_store))
         .tool(WorkerInspectTool::new(run_logger, agent_id.to_string()))
-        .tool(ShellTool::new(workspace.clone(), sandbox.clone()))
-        .tool(FileTool::new(workspace.clone()))
-        .tool(ExecTool::new(workspace, sandbox));
+        .tool(spill.wrap(ShellTool::new(workspace.clone(), sandbox.clone())))
+        .tool(spill.wrap(FileTool::new(workspace.clone())))
+        .tool(spill.wrap(ExecTool::new(workspace, sandbox)))
+        .tool(ReadArtifactTool::new(spill.store()));
 
     if browser_config.enabled {
-        server = server.tool(BrowserTool::new(browser_config, screenshot_dir));
+        server = server.tool(spill.wrap(BrowserTool::new(browser_config, screenshot_dir)));
     }
 
-    if let Some(key) = brave_search_key {
-        server = server.tool(WebSearchTool::new(key));
+    // Search results are only snippets, so pair search with a fetcher for
+    // reading them in full.
+    if let Some(config) = web_search {
+        server = server
+            .tool(spill.wrap(WebSearchTool::new(config).with_web_fetch()))
+            .tool(spill.wrap(WebFetchTool::new(web_fetch_cache())));
     }
 
     server.run()
@@ -0,0 +1,815 @@
+//! File store tools for documents an agent builds up over time: read,
+//! write, list, and diff files under the agent's `files` directory.
+//!
+//! Paths are virtual. `/notes/plan.md` names a file relative to the store
+//! root wherever that lives on the host, and host paths never show up in
+//! results or errors. Paths that would leave the root (`..`, symlinks) are
+//! refused, and single files and the store as a whole are capped in size.
+//! Workers get all four tools; channels get `files_read` and `files_list` to
+//! read back what workers wrote.
+
+use crate::tools::{MAX_DIR_ENTRIES, MAX_TOOL_OUTPUT_BYTES, truncate_output};
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt as _;
+
+/// Largest file the store accepts, in bytes.
+pub const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Largest total size of all files in the store, in bytes.
+pub const MAX_STORE_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Unchanged lines shown around each change in a diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// Largest table the diff fills before it gives up on finding common lines
+/// inside a changed region and shows the whole region as replaced.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// An agent's file store, rooted at its data directory's `files` folder.
+#[derive(Debug, Clone)]
+pub struct AgentFiles {
+    root: PathBuf,
+}
+
+impl AgentFiles {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Resolve a virtual path to its normalized form and host path. The root
+    /// itself resolves to `/`.
+    async fn resolve(&self, path: &str) -> Result<(String, PathBuf), AgentFilesError> {
+        let parts = virtual_components(path)?;
+        let virtual_path = format!("/{}", parts.join("/"));
+        let mut host_path = self.root.clone();
+        for part in &parts {
+            host_path.push(part);
+            let is_symlink = tokio::fs::symlink_metadata(&host_path)
+                .await
+                .is_ok_and(|metadata| metadata.file_type().is_symlink());
+            if is_symlink {
+                return Err(AgentFilesError(format!(
+                    "'{virtual_path}' goes through a symlink, which the file store doesn't follow"
+                )));
+            }
+        }
+        Ok((virtual_path, host_path))
+    }
+
+    /// Resolve a virtual path that has to name a file rather than the root.
+    async fn resolve_file(&self, path: &str) -> Result<(String, PathBuf), AgentFilesError> {
+        let (virtual_path, host_path) = self.resolve(path).await?;
+        if virtual_path == "/" {
+            return Err(AgentFilesError("a file path is required, not '/'".into()));
+        }
+        Ok((virtual_path, host_path))
+    }
+}
+
+/// Error type for the file store tools.
+#[derive(Debug, thiserror::Error)]
+#[error("File store operation failed: {0}")]
+pub struct AgentFilesError(String);
+
+/// Split a virtual path into its components, refusing anything that could
+/// step outside the store.
+fn virtual_components(path: &str) -> Result<Vec<String>, AgentFilesError> {
+    if path.contains(['\0', '\\']) {
+        return Err(AgentFilesError(format!(
+            "'{}' contains characters that aren't allowed in file store paths",
+            path.escape_debug()
+        )));
+    }
+    let mut parts = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => continue,
+            ".." => {
+                return Err(AgentFilesError(format!(
+                    "'{path}' uses '..', which isn't allowed in file store paths"
+                )));
+            }
+            part => parts.push(part.to_string()),
+        }
+    }
+    Ok(parts)
+}
+
+/// Read a text file, or `None` when it doesn't exist.
+async fn read_text(
+    virtual_path: &str,
+    host_path: &Path,
+) -> Result<Option<String>, AgentFilesError> {
+    let metadata = match tokio::fs::metadata(host_path).await {
+        Ok(metadata) => metadata,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(AgentFilesError(format!("{virtual_path}: {error}"))),
+    };
+    if metadata.is_dir() {
+        return Err(AgentFilesError(format!(
+            "'{virtual_path}' is a directory; use files_list to see what's in it"
+        )));
+    }
+    let bytes = tokio::fs::read(host_path)
+        .await
+        .map_err(|error| AgentFilesError(format!("{virtual_path}: {error}")))?;
+    String::from_utf8(bytes)
+        .map(Some)
+        .map_err(|_| AgentFilesError(format!("'{virtual_path}' isn't a text file")))
+}
+
+/// Tool for reading a file from the agent's file store.
+#[derive(Debug, Clone)]
+pub struct FilesReadTool {
+    files: AgentFiles,
+}
+
+impl FilesReadTool {
+    pub fn new(files: AgentFiles) -> Self {
+        Self { files }
+    }
+}
+
+/// Arguments for files read tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FilesReadArgs {
+    /// Virtual path of the file, e.g. `/reports/weekly.md`.
+    pub path: String,
+}
+
+/// Output from files read tool.
+#[derive(Debug, Serialize)]
+pub struct FilesReadOutput {
+    pub path: String,
+    /// Size of the whole file in bytes.
+    pub size: u64,
+    pub content: String,
+}
+
+impl Tool for FilesReadTool {
+    const NAME: &'static str = "files_read";
+
+    type Error = AgentFilesError;
+    type Args = FilesReadArgs;
+    type Output = FilesReadOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/files_read").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path of the file in the file store, e.g. /reports/weekly.md."
+                    }
+                },
+                "required": ["path"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (path, host_path) = self.files.resolve_file(&args.path).await?;
+        let content = read_text(&path, &host_path)
+            .await?
+            .ok_or_else(|| AgentFilesError(format!("no file at '{path}'")))?;
+        Ok(FilesReadOutput {
+            path,
+            size: content.len() as u64,
+            content: truncate_output(&content, MAX_TOOL_OUTPUT_BYTES),
+        })
+    }
+}
+
+/// Tool for writing a file in the agent's file store.
+#[derive(Debug, Clone)]
+pub struct FilesWriteTool {
+    files: AgentFiles,
+}
+
+impl FilesWriteTool {
+    pub fn new(files: AgentFiles) -> Self {
+        Self { files }
+    }
+}
+
+/// Arguments for files write tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FilesWriteArgs {
+    /// Virtual path of the file. Missing directories are created.
+    pub path: String,
+    /// Text to write.
+    pub content: String,
+    /// Add to the end of the file instead of replacing it.
+    #[serde(default)]
+    pub append: bool,
+}
+
+/// Output from files write tool.
+#[derive(Debug, Serialize)]
+pub struct FilesWriteOutput {
+    pub path: String,
+    /// Size of the file in bytes after the write.
+    pub size: u64,
+    /// Whether the file didn't exist before.
+    pub created: bool,
+}
+
+impl Tool for FilesWriteTool {
+    const NAME: &'static str = "files_write";
+
+    type Error = AgentFilesError;
+    type Args = FilesWriteArgs;
+    type Output = FilesWriteOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/files_write").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path of the file in the file store, e.g. /reports/weekly.md. Missing directories are created."
+                    },
+                    "content": {
+                        "type": "string",
+                        "description": "Text to write."
+                    },
+                    "append": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Add the content to the end of the file instead of replacing it."
+                    }
+                },
+                "required": ["path", "content"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (path, host_path) = self.files.resolve_file(&args.path).await?;
+        let existing = match tokio::fs::metadata(&host_path).await {
+            Ok(metadata) if metadata.is_dir() => {
+                return Err(AgentFilesError(format!("'{path}' is a directory")));
+            }
+            Ok(metadata) => Some(metadata.len()),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+            Err(error) => return Err(AgentFilesError(format!("{path}: {error}"))),
+        };
+
+        let added = args.content.len() as u64;
+        let size = if args.append {
+            existing.unwrap_or(0) + added
+        } else {
+            added
+        };
+        if size > MAX_FILE_BYTES {
+            return Err(AgentFilesError(format!(
+                "'{path}' would be {size} bytes; files are limited to {MAX_FILE_BYTES} bytes"
+            )));
+        }
+        let store_size = store_size(&self.files.root)
+            .await
+            .map_err(|error| AgentFilesError(error.to_string()))?;
+        if store_size.saturating_sub(existing.unwrap_or(0)) + size > MAX_STORE_BYTES {
+            return Err(AgentFilesError(format!(
+                "the file store is limited to {MAX_STORE_BYTES} bytes and holds {store_size}; delete or shorten files first"
+            )));
+        }
+
+        if let Some(parent) = host_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|error| AgentFilesError(format!("{path}: {error}")))?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(args.append)
+            .truncate(!args.append)
+            .open(&host_path)
+            .await
+            .map_err(|error| AgentFilesError(format!("{path}: {error}")))?;
+        file.write_all(args.content.as_bytes())
+            .await
+            .map_err(|error| AgentFilesError(format!("{path}: {error}")))?;
+        file.flush()
+            .await
+            .map_err(|error| AgentFilesError(format!("{path}: {error}")))?;
+
+        Ok(FilesWriteOutput {
+            path,
+            size,
+            created: existing.is_none(),
+        })
+    }
+}
+
+/// Tool for listing files in the agent's file store.
+#[derive(Debug, Clone)]
+pub struct FilesListTool {
+    files: AgentFiles,
+}
+
+impl FilesListTool {
+    pub fn new(files: AgentFiles) -> Self {
+        Self { files }
+    }
+}
+
+/// Arguments for files list tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FilesListArgs {
+    /// Directory to list, recursively. Defaults to the whole store.
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+/// A file in the agent's file store.
+#[derive(Debug, Serialize)]
+pub struct AgentFileEntry {
+    pub path: String,
+    pub size: u64,
+    pub modified: Option<String>,
+}
+
+/// Output from files list tool.
+#[derive(Debug, Serialize)]
+pub struct FilesListOutput {
+    pub entries: Vec<AgentFileEntry>,
+    /// True when the listing was cut off at the entry limit.
+    pub truncated: bool,
+}
+
+impl Tool for FilesListTool {
+    const NAME: &'static str = "files_list";
+
+    type Error = AgentFilesError;
+    type Args = FilesListArgs;
+    type Output = FilesListOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/files_list").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Directory to list, including subdirectories. Defaults to the whole file store."
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (path, host_path) = self
+            .files
+            .resolve(args.path.as_deref().unwrap_or("/"))
+            .await?;
+        let mut entries = Vec::new();
+        match tokio::fs::metadata(&host_path).await {
+            Ok(metadata) if metadata.is_dir() => {}
+            Ok(_) => {
+                return Err(AgentFilesError(format!(
+                    "'{path}' is a file; use files_read to read it"
+                )));
+            }
+            // An empty store has no directory yet.
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound && path == "/" => {
+                return Ok(FilesListOutput {
+                    entries,
+                    truncated: false,
+                });
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Err(AgentFilesError(format!("no directory at '{path}'")));
+            }
+            Err(error) => return Err(AgentFilesError(format!("{path}: {error}"))),
+        }
+
+        let truncated = list_files(&host_path, &path, &mut entries)
+            .await
+            .map_err(|error| AgentFilesError(error.to_string()))?;
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(FilesListOutput { entries, truncated })
+    }
+}
+
+/// Walk `dir`, collecting files with virtual paths under `virtual_dir`.
+/// Symlinks are skipped. Returns true when the listing hit `MAX_DIR_ENTRIES`.
+async fn list_files(
+    dir: &Path,
+    virtual_dir: &str,
+    entries: &mut Vec<AgentFileEntry>,
+) -> std::io::Result<bool> {
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let mut reader = tokio::fs::read_dir(&current).await?;
+        while let Some(entry) = reader.next_entry().await? {
+            let metadata = entry.metadata().await?;
+            if metadata.is_symlink() {
+                continue;
+            }
+            if metadata.is_dir() {
+                pending.push(entry.path());
+                continue;
+            }
+            if entries.len() >= MAX_DIR_ENTRIES {
+                return Ok(true);
+            }
+            let host_path = entry.path();
+            let relative = host_path.strip_prefix(dir).unwrap_or(&host_path);
+            entries.push(AgentFileEntry {
+                path: format!(
+                    "{}/{}",
+                    virtual_dir.trim_end_matches('/'),
+                    relative.display()
+                ),
+                size: metadata.len(),
+                modified: metadata
+                    .modified()
+                    .ok()
+                    .map(|time| chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339()),
+            });
+        }
+    }
+    Ok(false)
+}
+
+/// Total size of the files under `root`, skipping symlinks.
+async fn store_size(root: &Path) -> std::io::Result<u64> {
+    let mut total = 0;
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let mut reader = match tokio::fs::read_dir(&dir).await {
+            Ok(reader) => reader,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(error) => return Err(error),
+        };
+        while let Some(entry) = reader.next_entry().await? {
+            let metadata = entry.metadata().await?;
+            if metadata.is_symlink() {
+                continue;
+            }
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else {
+                total += metadata.len();
+            }
+        }
+    }
+    Ok(total)
+}
+
+/// Tool for diffing files in the agent's file store.
+#[derive(Debug, Clone)]
+pub struct FilesDiffTool {
+    files: AgentFiles,
+}
+
+impl FilesDiffTool {
+    pub fn new(files: AgentFiles) -> Self {
+        Self { files }
+    }
+}
+
+/// Arguments for files diff tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FilesDiffArgs {
+    /// Virtual path of the file to compare from.
+    pub path: String,
+    /// Another file in the store to compare against.
+    #[serde(default)]
+    pub other_path: Option<String>,
+    /// Proposed new content to compare against, instead of another file.
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+/// Output from files diff tool.
+#[derive(Debug, Serialize)]
+pub struct FilesDiffOutput {
+    /// Whether the two sides differ.
+    pub changed: bool,
+    /// Unified diff, empty when nothing changed.
+    pub diff: String,
+}
+
+impl Tool for FilesDiffTool {
+    const NAME: &'static str = "files_diff";
+
+    type Error = AgentFilesError;
+    type Args = FilesDiffArgs;
+    type Output = FilesDiffOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/files_diff").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path of the file to compare from."
+                    },
+                    "other_path": {
+                        "type": "string",
+                        "description": "Another file in the store to compare against."
+                    },
+                    "content": {
+                        "type": "string",
+                        "description": "Proposed new content to compare against, instead of another file. The file at 'path' may not exist yet."
+                    }
+                },
+                "required": ["path"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (path, host_path) = self.files.resolve_file(&args.path).await?;
+        let old = read_text(&path, &host_path).await?;
+        let (old, new, new_label) = match (args.other_path, args.content) {
+            (Some(other_path), None) => {
+                let (other_path, other_host_path) = self.files.resolve_file(&other_path).await?;
+                let old = old.ok_or_else(|| AgentFilesError(format!("no file at '{path}'")))?;
+                let new = read_text(&other_path, &other_host_path)
+                    .await?
+                    .ok_or_else(|| AgentFilesError(format!("no file at '{other_path}'")))?;
+                (old, new, other_path)
+            }
+            (None, Some(content)) => (
+                old.unwrap_or_default(),
+                content,
+                format!("{path} (proposed)"),
+            ),
+            _ => {
+                return Err(AgentFilesError(
+                    "pass exactly one of 'other_path' and 'content'".into(),
+                ));
+            }
+        };
+
+        let diff = unified_diff(&old, &new, &path, &new_label);
+        Ok(FilesDiffOutput {
+            changed: !diff.is_empty(),
+            diff: truncate_output(&diff, MAX_TOOL_OUTPUT_BYTES),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    Keep,
+    Remove,
+    Add,
+}
+
+/// Line edits that turn `old` into `new`, keeping as many lines as it can.
+fn edit_script(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old = &old[prefix..old.len() - suffix];
+    let new = &new[prefix..new.len() - suffix];
+
+    let mut script = vec![Edit::Keep; prefix];
+    let (mut i, mut j) = (0, 0);
+    if old.len().saturating_mul(new.len()) <= MAX_DIFF_CELLS {
+        // lengths[i * width + j] is the longest common subsequence of
+        // old[i..] and new[j..].
+        let width = new.len() + 1;
+        let mut lengths = vec![0u32; (old.len() + 1) * width];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lengths[i * width + j] = if old[i] == new[j] {
+                    lengths[(i + 1) * width + j + 1] + 1
+                } else {
+                    lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+                };
+            }
+        }
+        while i < old.len() && j < new.len() {
+            if old[i] == new[j] {
+                script.push(Edit::Keep);
+                i += 1;
+                j += 1;
+            } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+                script.push(Edit::Remove);
+                i += 1;
+            } else {
+                script.push(Edit::Add);
+                j += 1;
+            }
+        }
+    }
+    script.extend(std::iter::repeat_n(Edit::Remove, old.len() - i));
+    script.extend(std::iter::repeat_n(Edit::Add, new.len() - j));
+    script.extend(std::iter::repeat_n(Edit::Keep, suffix));
+    script
+}
+
+/// Unified diff of two texts, or an empty string when they're the same.
+fn unified_diff(old_text: &str, new_text: &str, old_label: &str, new_label: &str) -> String {
+    let old: Vec<&str> = old_text.lines().collect();
+    let new: Vec<&str> = new_text.lines().collect();
+    let script = edit_script(&old, &new);
+
+    // Line positions in each text before every edit.
+    let mut positions = Vec::with_capacity(script.len() + 1);
+    let (mut i, mut j) = (0, 0);
+    for edit in &script {
+        positions.push((i, j));
+        match edit {
+            Edit::Keep => (i, j) = (i + 1, j + 1),
+            Edit::Remove => i += 1,
+            Edit::Add => j += 1,
+        }
+    }
+
+    // Changes close enough that their context would overlap share a hunk.
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (index, _) in script
+        .iter()
+        .enumerate()
+        .filter(|(_, edit)| **edit != Edit::Keep)
+    {
+        match hunks.last_mut() {
+            Some((_, last)) if index - *last <= 2 * DIFF_CONTEXT + 1 => *last = index,
+            _ => hunks.push((index, index)),
+        }
+    }
+    if hunks.is_empty() {
+        return String::new();
+    }
+
+    let mut diff = format!("--- {old_label}\n+++ {new_label}\n");
+    for (first, last) in hunks {
+        let start = first.saturating_sub(DIFF_CONTEXT);
+        let end = (last + DIFF_CONTEXT + 1).min(script.len());
+        let edits = &script[start..end];
+        let old_count = edits.iter().filter(|edit| **edit != Edit::Add).count();
+        let new_count = edits.iter().filter(|edit| **edit != Edit::Remove).count();
+        let (old_start, new_start) = positions[start];
+        diff.push_str(&format!(
+            "@@ -{},{old_count} +{},{new_count} @@\n",
+            old_start + usize::from(old_count > 0),
+            new_start + usize::from(new_count > 0),
+        ));
+        for (edit, &(i, j)) in edits.iter().zip(&positions[start..end]) {
+            let line = match edit {
+                Edit::Keep => format!(" {}", old[i]),
+                Edit::Remove => format!("-{}", old[i]),
+                Edit::Add => format!("+{}", new[j]),
+            };
+            diff.push_str(&line);
+            diff.push('\n');
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn paths_stay_inside_the_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = AgentFiles::new(dir.path().join("files"));
+
+        let (path, host_path) = files.resolve("notes//./plan.md").await.unwrap();
+        assert_eq!(path, "/notes/plan.md");
+        assert_eq!(host_path, dir.path().join("files/notes/plan.md"));
+        assert_eq!(files.resolve("/").await.unwrap().0, "/");
+
+        for escape in ["../secrets", "/notes/../../secrets", "notes\\..\\x", "a\0b"] {
+            assert!(files.resolve(escape).await.is_err(), "{escape:?}");
+        }
+        assert!(files.resolve_file("/").await.is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn paths_through_symlinks_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("files");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "hunter2").unwrap();
+        std::os::unix::fs::symlink(dir.path(), root.join("escape")).unwrap();
+
+        let read = FilesReadTool::new(AgentFiles::new(root));
+        let error = read
+            .call(FilesReadArgs {
+                path: "/escape/secret.txt".into(),
+            })
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("symlink"));
+        assert!(
+            !error
+                .to_string()
+                .contains(&dir.path().display().to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn written_files_can_be_read_listed_and_appended() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = AgentFiles::new(dir.path().join("files"));
+        let write = FilesWriteTool::new(files.clone());
+        let list = FilesListTool::new(files.clone());
+
+        let empty = list.call(FilesListArgs { path: None }).await.unwrap();
+        assert!(empty.entries.is_empty());
+
+        let created = write
+            .call(FilesWriteArgs {
+                path: "/reports/weekly.md".into(),
+                content: "# Week 1\n".into(),
+                append: false,
+            })
+            .await
+            .unwrap();
+        assert!(created.created);
+        let appended = write
+            .call(FilesWriteArgs {
+                path: "reports/weekly.md".into(),
+                content: "- shipped\n".into(),
+                append: true,
+            })
+            .await
+            .unwrap();
+        assert!(!appended.created);
+        assert_eq!(appended.size, 19);
+
+        let read = FilesReadTool::new(files)
+            .call(FilesReadArgs {
+                path: "/reports/weekly.md".into(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(read.content, "# Week 1\n- shipped\n");
+
+        let listed = list
+            .call(FilesListArgs {
+                path: Some("/reports".into()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(listed.entries.len(), 1);
+        assert_eq!(listed.entries[0].path, "/reports/weekly.md");
+        assert_eq!(listed.entries[0].size, 19);
+    }
+
+    #[tokio::test]
+    async fn oversized_files_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = FilesWriteTool::new(AgentFiles::new(dir.path().join("files")));
+
+        let result = write
+            .call(FilesWriteArgs {
+                path: "/big.txt".into(),
+                content: "x".repeat(MAX_FILE_BYTES as usize + 1),
+                append: false,
+            })
+            .await;
+        assert!(result.is_err());
+        assert!(!dir.path().join("files/big.txt").exists());
+    }
+
+    #[test]
+    fn diffs_show_changes_with_context() {
+        let old = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\neleven\ntwelve\n";
+        let new = old.replace("four", "FOUR") + "thirteen\n";
+
+        assert_eq!(
+            unified_diff(old, &new, "/a.txt", "/b.txt"),
+            "--- /a.txt\n+++ /b.txt\n\
+             @@ -1,7 +1,7 @@\n one\n two\n three\n-four\n+FOUR\n five\n six\n seven\n\
+             @@ -10,3 +10,4 @@\n ten\n eleven\n twelve\n+thirteen\n"
+        );
+        assert_eq!(unified_diff(old, old, "/a.txt", "/a.txt"), "");
+        assert_eq!(
+            unified_diff("", "hello\n", "/new.txt", "/new.txt (proposed)"),
+            "--- /new.txt\n+++ /new.txt (proposed)\n@@ -0,0 +1,1 @@\n+hello\n"
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! Attach file tool for delivering worker output files to the user.
+
+use crate::media::{MediaSource, MediaStore};
+use crate::tools::send_file::MAX_FILE_SIZE_BYTES;
+use crate::{AgentId, ChannelId, ProcessEvent, TurnId, WorkerId};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::sync::broadcast;
+
+/// Tool for sending a file a worker produced (a CSV, an image, a log) to the
+/// conversation that spawned it.
+///
+/// Workers don't talk to the platform directly. The file is copied into the
+/// media store and announced with `ProcessEvent::WorkerAttachment`; the
+/// channel uploads it as an `OutboundResponse::File`. Only files inside the
+/// agent workspace or the worker's own working directory can be attached.
+#[derive(Debug, Clone)]
+pub struct AttachFileTool {
+    agent_id: AgentId,
+    worker_id: WorkerId,
+    channel_id: ChannelId,
+    turn_id: Option<TurnId>,
+    event_tx: broadcast::Sender<ProcessEvent>,
+    media: MediaStore,
+    workspace: PathBuf,
+    worker_workspace: Option<PathBuf>,
+}
+
+impl AttachFileTool {
+    pub fn new(
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: ChannelId,
+        turn_id: Option<TurnId>,
+        event_tx: broadcast::Sender<ProcessEvent>,
+        media: MediaStore,
+        workspace: PathBuf,
+        worker_workspace: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            agent_id,
+            worker_id,
+            channel_id,
+            turn_id,
+            event_tx,
+            media,
+            workspace,
+            worker_workspace,
+        }
+    }
+
+    /// Resolve `raw` against the workspace and check it stays inside the
+    /// workspace or the worker's working directory.
+    fn resolve_path(&self, raw: &str) -> Result<PathBuf, AttachFileError> {
+        let canonical = self
+            .workspace
+            .join(raw)
+            .canonicalize()
+            .map_err(|error| AttachFileError(format!("can't resolve path '{raw}': {error}")))?;
+
+        let allowed = std::iter::once(&self.workspace)
+            .chain(&self.worker_workspace)
+            .map(|root| root.canonicalize().unwrap_or_else(|_| root.clone()))
+            .any(|root| canonical.starts_with(root));
+        if !allowed {
+            return Err(AttachFileError(format!(
+                "ACCESS DENIED: Path is outside the workspace boundary. \
+                 Only files under {} can be attached.",
+                self.workspace.display()
+            )));
+        }
+
+        Ok(canonical)
+    }
+}
+
+/// Error type for attach_file tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Attach file failed: {0}")]
+pub struct AttachFileError(String);
+
+/// Arguments for attach_file tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AttachFileArgs {
+    /// Path to the file, absolute or relative to the workspace.
+    pub path: String,
+    /// Optional message to accompany the file.
+    #[serde(default)]
+    pub caption: Option<String>,
+}
+
+/// Output from attach_file tool.
+#[derive(Debug, Serialize)]
+pub struct AttachFileOutput {
+    pub success: bool,
+    pub filename: String,
+    pub size_bytes: u64,
+    /// Id of the stored copy.
+    pub media_id: String,
+}
+
+impl Tool for AttachFileTool {
+    const NAME: &'static str = "attach_file";
+
+    type Error = AttachFileError;
+    type Args = AttachFileArgs;
+    type Output = AttachFileOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/attach_file").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path to the file, absolute or relative to the workspace."
+                    },
+                    "caption": {
+                        "type": "string",
+                        "description": "Optional message to accompany the file."
+                    }
+                },
+                "required": ["path"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let path = self.resolve_path(&args.path)?;
+
+        let metadata = tokio::fs::metadata(&path).await.map_err(|error| {
+            AttachFileError(format!("can't read file '{}': {error}", path.display()))
+        })?;
+        if !metadata.is_file() {
+            return Err(AttachFileError(format!(
+                "'{}' is not a file",
+                path.display()
+            )));
+        }
+        if metadata.len() > MAX_FILE_SIZE_BYTES {
+            return Err(AttachFileError(format!(
+                "file is too large ({} bytes, max {} bytes)",
+                metadata.len(),
+                MAX_FILE_SIZE_BYTES,
+            )));
+        }
+
+        let stored = self
+            .media
+            .put_file(
+                MediaSource::Worker,
+                Some(&self.channel_id),
+                Path::new(&path),
+            )
+            .await
+            .map_err(|error| AttachFileError(format!("failed to store file: {error}")))?;
+
+        tracing::info!(
+            worker_id = %self.worker_id,
+            file_path = %path.display(),
+            media_id = %stored.id,
+            size_bytes = stored.size_bytes,
+            "attach_file tool called"
+        );
+
+        self.event_tx
+            .send(ProcessEvent::WorkerAttachment {
+                agent_id: self.agent_id.clone(),
+                worker_id: self.worker_id,
+                channel_id: self.channel_id.clone(),
+                media_id: stored.id.clone(),
+                caption: args.caption,
+                turn_id: self.turn_id,
+            })
+            .map_err(|_| AttachFileError("the channel is no longer listening".into()))?;
+
+        Ok(AttachFileOutput {
+            success: true,
+            filename: stored.filename,
+            size_bytes: stored.size_bytes,
+            media_id: stored.id,
+        })
+    }
+}
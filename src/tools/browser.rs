@@ -80,8 +80,9 @@ fn validate_url(url: &str) -> Result<(), BrowserError> {
 }
 
 /// Returns true if the IP address belongs to a private, loopback, or
-/// link-local range that should not be reachable from the browser tool.
-fn is_blocked_ip(ip: IpAddr) -> bool {
+/// link-local range that should not be reachable from the browser or
+/// web_fetch tools.
+pub(crate) fn is_blocked_ip(ip: IpAddr) -> bool {
     match ip {
         IpAddr::V4(v4) => {
             v4.is_loopback()                             // 127.0.0.0/8
@@ -0,0 +1,943 @@
+//! Calculator tool for arithmetic, date math, and unit conversions (channel,
+//! branch, and worker).
+//!
+//! Evaluates a single expression with a small recursive-descent evaluator, so
+//! numbers in replies come from arithmetic rather than the model's guess.
+//! Values are numbers, dates, or text: a date plus or minus a number of days
+//! is a date, and the difference of two dates is a number of days.
+//! `convert(value, "from", "to")` converts between units of the same kind.
+
+use chrono::{Datelike as _, Months, NaiveDate};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Longest expression accepted, in bytes.
+const MAX_EXPRESSION_LEN: usize = 2000;
+
+/// Deepest nesting of parentheses and unary operators accepted.
+const MAX_DEPTH: usize = 64;
+
+/// Tool for evaluating expressions deterministically.
+#[derive(Debug, Clone, Default)]
+pub struct CalculatorTool;
+
+impl CalculatorTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Error type for calculator tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Calculation failed: {0}")]
+pub struct CalculatorError(String);
+
+/// Arguments for calculator tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CalculatorArgs {
+    /// The expression to evaluate, e.g. `(19.99 * 3) * 1.2` or
+    /// `date("2026-03-01") + 90`.
+    pub expression: String,
+}
+
+/// Output from calculator tool.
+#[derive(Debug, Serialize)]
+pub struct CalculatorOutput {
+    pub expression: String,
+    pub result: String,
+}
+
+impl Tool for CalculatorTool {
+    const NAME: &'static str = "calculator";
+
+    type Error = CalculatorError;
+    type Args = CalculatorArgs;
+    type Output = CalculatorOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/calculator").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "expression": {
+                        "type": "string",
+                        "description": "One expression, e.g. \"(19.99 * 3) * 1.2\", \"date(\\\"2026-03-01\\\") + 90\", or \"convert(72, \\\"f\\\", \\\"c\\\")\"."
+                    }
+                },
+                "required": ["expression"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let result = evaluate(&args.expression).map_err(CalculatorError)?;
+        Ok(CalculatorOutput {
+            expression: args.expression,
+            result: result.to_string(),
+        })
+    }
+}
+
+/// Evaluate `expression` to a value.
+fn evaluate(expression: &str) -> Result<Value, String> {
+    if expression.len() > MAX_EXPRESSION_LEN {
+        return Err(format!(
+            "expression is longer than {MAX_EXPRESSION_LEN} characters"
+        ));
+    }
+    let tokens = tokenize(expression)?;
+    if tokens.is_empty() {
+        return Err("expression is empty".into());
+    }
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+        depth: 0,
+    };
+    let value = parser.expression()?;
+    match parser.peek() {
+        None => Ok(value),
+        Some(token) => Err(format!(
+            "unexpected {token} after the end of the expression"
+        )),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    Date(NaiveDate),
+    Text(String),
+}
+
+impl Value {
+    fn kind(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "a number",
+            Value::Date(_) => "a date",
+            Value::Text(_) => "text",
+        }
+    }
+
+    fn number(&self, context: &str) -> Result<f64, String> {
+        match self {
+            Value::Number(number) => Ok(*number),
+            other => Err(format!("{context} needs a number, got {}", other.kind())),
+        }
+    }
+
+    fn date(&self, context: &str) -> Result<NaiveDate, String> {
+        match self {
+            Value::Date(date) => Ok(*date),
+            other => Err(format!("{context} needs a date, got {}", other.kind())),
+        }
+    }
+
+    fn text(&self, context: &str) -> Result<&str, String> {
+        match self {
+            Value::Text(text) => Ok(text),
+            other => Err(format!("{context} needs quoted text, got {}", other.kind())),
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Number(number) => formatter.write_str(&format_number(*number)),
+            Value::Date(date) => write!(formatter, "{} ({})", date, date.format("%A")),
+            Value::Text(text) => formatter.write_str(text),
+        }
+    }
+}
+
+/// Format a number with up to 12 significant digits, dropping the float noise
+/// in results like `0.1 + 0.2`.
+fn format_number(number: f64) -> String {
+    if number == 0.0 {
+        return "0".into();
+    }
+    let magnitude = number.abs().log10().floor() as i32;
+    if !(-6..15).contains(&magnitude) {
+        let formatted = format!("{number:.11e}");
+        let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+        return format!("{}e{exponent}", trim_fraction(mantissa));
+    }
+    let decimals = (11 - magnitude).max(0) as usize;
+    let formatted = format!("{number:.decimals$}");
+    match trim_fraction(&formatted) {
+        "-0" => "0".into(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// Drop trailing zeros after the decimal point, and the point itself if
+/// nothing is left after it.
+fn trim_fraction(number: &str) -> &str {
+    if number.contains('.') {
+        number.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        number
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+    Name(String),
+    Operator(char),
+    OpenParen,
+    CloseParen,
+    Comma,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(number) => write!(formatter, "'{}'", format_number(*number)),
+            Token::Text(text) => write!(formatter, "\"{text}\""),
+            Token::Name(name) => write!(formatter, "'{name}'"),
+            Token::Operator(operator) => write!(formatter, "'{operator}'"),
+            Token::OpenParen => formatter.write_str("'('"),
+            Token::CloseParen => formatter.write_str("')'"),
+            Token::Comma => formatter.write_str("','"),
+        }
+    }
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.char_indices().peekable();
+    while let Some((start, character)) = chars.next() {
+        match character {
+            _ if character.is_whitespace() => {}
+            '0'..='9' | '.' => {
+                let mut end = start + character.len_utf8();
+                let mut previous = character;
+                while let Some(&(index, next)) = chars.peek() {
+                    let exponent_sign = matches!(next, '+' | '-') && matches!(previous, 'e' | 'E');
+                    if next.is_ascii_digit()
+                        || matches!(next, '.' | '_' | 'e' | 'E')
+                        || exponent_sign
+                    {
+                        chars.next();
+                        end = index + next.len_utf8();
+                        previous = next;
+                    } else {
+                        break;
+                    }
+                }
+                let literal = expression[start..end].replace('_', "");
+                let number = literal
+                    .parse::<f64>()
+                    .map_err(|_| format!("'{}' isn't a number", &expression[start..end]))?;
+                tokens.push(Token::Number(number));
+            }
+            '"' | '\'' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, next)) if next == character => break,
+                        Some((_, next)) => text.push(next),
+                        None => return Err("unterminated quoted text".into()),
+                    }
+                }
+                tokens.push(Token::Text(text));
+            }
+            _ if character.is_alphabetic() || character == '_' => {
+                let mut name = String::from(character);
+                while let Some(&(_, next)) = chars.peek() {
+                    if next.is_alphanumeric() || next == '_' {
+                        name.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Name(name.to_lowercase()));
+            }
+            '*' if chars.peek().is_some_and(|&(_, next)| next == '*') => {
+                chars.next();
+                tokens.push(Token::Operator('^'));
+            }
+            '+' | '-' | '*' | '/' | '%' | '^' => tokens.push(Token::Operator(character)),
+            '×' => tokens.push(Token::Operator('*')),
+            '÷' => tokens.push(Token::Operator('/')),
+            '(' => tokens.push(Token::OpenParen),
+            ')' => tokens.push(Token::CloseParen),
+            ',' => tokens.push(Token::Comma),
+            other => return Err(format!("unexpected character '{other}'")),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent evaluator. Precedence, loosest first: `+ -`, `* / %`,
+/// unary minus, then `^`, which is right-associative.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.advance() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format!("expected {expected}, found {token}")),
+            None => Err(format!("expected {expected} at the end of the expression")),
+        }
+    }
+
+    fn descend(&mut self) -> Result<(), String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            Err("expression is nested too deeply".into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn expression(&mut self) -> Result<Value, String> {
+        let mut value = self.term()?;
+        while let Some(Token::Operator(operator @ ('+' | '-'))) = self.peek().cloned() {
+            self.position += 1;
+            let right = self.term()?;
+            value = add(value, operator, right)?;
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<Value, String> {
+        let mut value = self.unary()?;
+        while let Some(Token::Operator(operator @ ('*' | '/' | '%'))) = self.peek().cloned() {
+            self.position += 1;
+            let left = value.number(&format!("'{operator}'"))?;
+            let right = self.unary()?.number(&format!("'{operator}'"))?;
+            value = Value::Number(match operator {
+                '*' => left * right,
+                _ if right == 0.0 => return Err("division by zero".into()),
+                '/' => left / right,
+                _ => left % right,
+            });
+        }
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<Value, String> {
+        match self.peek() {
+            Some(Token::Operator(operator @ ('-' | '+'))) => {
+                let negate = *operator == '-';
+                self.position += 1;
+                self.descend()?;
+                let number = self.unary()?.number("unary minus")?;
+                self.depth -= 1;
+                Ok(Value::Number(if negate { -number } else { number }))
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Value, String> {
+        let base = self.primary()?;
+        if self.peek() != Some(&Token::Operator('^')) {
+            return Ok(base);
+        }
+        self.position += 1;
+        self.descend()?;
+        let exponent = self.unary()?.number("'^'")?;
+        self.depth -= 1;
+        finite(base.number("'^'")?.powf(exponent))
+    }
+
+    fn primary(&mut self) -> Result<Value, String> {
+        match self.advance() {
+            Some(Token::Number(number)) => Ok(Value::Number(number)),
+            Some(Token::Text(text)) => Ok(Value::Text(text)),
+            Some(Token::OpenParen) => {
+                self.descend()?;
+                let value = self.expression()?;
+                self.expect(Token::CloseParen)?;
+                self.depth -= 1;
+                Ok(value)
+            }
+            Some(Token::Name(name)) => {
+                if self.peek() != Some(&Token::OpenParen) {
+                    return constant(&name);
+                }
+                self.position += 1;
+                self.descend()?;
+                let mut arguments = Vec::new();
+                if self.peek() != Some(&Token::CloseParen) {
+                    loop {
+                        arguments.push(self.expression()?);
+                        if self.peek() == Some(&Token::Comma) {
+                            self.position += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                self.expect(Token::CloseParen)?;
+                self.depth -= 1;
+                call_function(&name, &arguments)
+            }
+            Some(token) => Err(format!("unexpected {token}")),
+            None => Err("expression ends too early".into()),
+        }
+    }
+}
+
+fn finite(number: f64) -> Result<Value, String> {
+    if number.is_finite() {
+        Ok(Value::Number(number))
+    } else {
+        Err("the result isn't a finite number".into())
+    }
+}
+
+/// `+` and `-`, which also shift dates by whole days.
+fn add(left: Value, operator: char, right: Value) -> Result<Value, String> {
+    let days = |number: f64| -> Result<chrono::Duration, String> {
+        if number.fract() != 0.0 || number.abs() > 1e7 {
+            return Err("dates can only move by a whole number of days".into());
+        }
+        Ok(chrono::Duration::days(number as i64))
+    };
+    let out_of_range = || "the resulting date is out of range".to_string();
+    match (left, operator, right) {
+        (Value::Number(left), '+', Value::Number(right)) => finite(left + right),
+        (Value::Number(left), _, Value::Number(right)) => finite(left - right),
+        (Value::Date(date), '+', Value::Number(number))
+        | (Value::Number(number), '+', Value::Date(date)) => date
+            .checked_add_signed(days(number)?)
+            .map(Value::Date)
+            .ok_or_else(out_of_range),
+        (Value::Date(date), '-', Value::Number(number)) => date
+            .checked_sub_signed(days(number)?)
+            .map(Value::Date)
+            .ok_or_else(out_of_range),
+        (Value::Date(left), '-', Value::Date(right)) => {
+            Ok(Value::Number((left - right).num_days() as f64))
+        }
+        (left, operator, right) => Err(format!(
+            "can't use '{operator}' with {} and {}",
+            left.kind(),
+            right.kind()
+        )),
+    }
+}
+
+fn constant(name: &str) -> Result<Value, String> {
+    match name {
+        "pi" => Ok(Value::Number(std::f64::consts::PI)),
+        "tau" => Ok(Value::Number(std::f64::consts::TAU)),
+        "e" => Ok(Value::Number(std::f64::consts::E)),
+        _ => Err(format!(
+            "unknown name '{name}'; quote dates and unit names, e.g. convert(5, \"km\", \"mi\")"
+        )),
+    }
+}
+
+fn call_function(name: &str, arguments: &[Value]) -> Result<Value, String> {
+    let arity = |expected: std::ops::RangeInclusive<usize>| {
+        if expected.contains(&arguments.len()) {
+            Ok(())
+        } else if expected.start() == expected.end() {
+            Err(format!(
+                "{name}() takes {} argument(s), got {}",
+                expected.start(),
+                arguments.len()
+            ))
+        } else {
+            Err(format!(
+                "{name}() takes {} to {} arguments, got {}",
+                expected.start(),
+                expected.end(),
+                arguments.len()
+            ))
+        }
+    };
+    let context = format!("{name}()");
+    let number = |index: usize| arguments[index].number(&context);
+    let numbers = || -> Result<Vec<f64>, String> {
+        if arguments.is_empty() {
+            return Err(format!("{name}() needs at least one argument"));
+        }
+        arguments
+            .iter()
+            .map(|value| value.number(&context))
+            .collect()
+    };
+    let unary = |function: fn(f64) -> f64| -> Result<Value, String> {
+        arity(1..=1)?;
+        finite(function(number(0)?))
+    };
+
+    match name {
+        "sqrt" => unary(f64::sqrt),
+        "cbrt" => unary(f64::cbrt),
+        "abs" => unary(f64::abs),
+        "floor" => unary(f64::floor),
+        "ceil" => unary(f64::ceil),
+        "trunc" => unary(f64::trunc),
+        "exp" => unary(f64::exp),
+        "ln" => unary(f64::ln),
+        "log10" => unary(f64::log10),
+        "log2" => unary(f64::log2),
+        "sin" => unary(f64::sin),
+        "cos" => unary(f64::cos),
+        "tan" => unary(f64::tan),
+        "asin" => unary(f64::asin),
+        "acos" => unary(f64::acos),
+        "atan" => unary(f64::atan),
+        "radians" => unary(f64::to_radians),
+        "degrees" => unary(f64::to_degrees),
+        "round" => {
+            arity(1..=2)?;
+            let digits = if arguments.len() == 2 {
+                number(1)?
+            } else {
+                0.0
+            };
+            if digits.fract() != 0.0 || !(0.0..=12.0).contains(&digits) {
+                return Err("round() takes 0 to 12 decimal places".into());
+            }
+            let scale = 10f64.powi(digits as i32);
+            finite((number(0)? * scale).round() / scale)
+        }
+        "log" => {
+            arity(1..=2)?;
+            let base = if arguments.len() == 2 {
+                number(1)?
+            } else {
+                10.0
+            };
+            finite(number(0)?.log(base))
+        }
+        "pow" => {
+            arity(2..=2)?;
+            finite(number(0)?.powf(number(1)?))
+        }
+        "atan2" => {
+            arity(2..=2)?;
+            finite(number(0)?.atan2(number(1)?))
+        }
+        "hypot" => {
+            arity(2..=2)?;
+            finite(number(0)?.hypot(number(1)?))
+        }
+        "min" => finite(numbers()?.into_iter().fold(f64::INFINITY, f64::min)),
+        "max" => finite(numbers()?.into_iter().fold(f64::NEG_INFINITY, f64::max)),
+        "sum" => finite(numbers()?.into_iter().sum()),
+        "avg" | "mean" => {
+            let numbers = numbers()?;
+            finite(numbers.iter().sum::<f64>() / numbers.len() as f64)
+        }
+        "percent" => {
+            arity(2..=2)?;
+            let whole = number(1)?;
+            if whole == 0.0 {
+                return Err("division by zero".into());
+            }
+            finite(number(0)? / whole * 100.0)
+        }
+        "date" => {
+            arity(1..=1)?;
+            let text = arguments[0].text(&context)?;
+            NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d")
+                .map(Value::Date)
+                .map_err(|_| format!("'{text}' isn't a date in YYYY-MM-DD form"))
+        }
+        "today" => {
+            arity(0..=0)?;
+            Ok(Value::Date(chrono::Local::now().date_naive()))
+        }
+        "add_months" | "add_years" => {
+            arity(2..=2)?;
+            let date = arguments[0].date(&context)?;
+            let count = number(1)?;
+            if count.fract() != 0.0 || count.abs() > 1e6 {
+                return Err(format!("{name}() needs a whole number"));
+            }
+            let months = count as i64 * if name == "add_years" { 12 } else { 1 };
+            let shifted = if months >= 0 {
+                date.checked_add_months(Months::new(months as u32))
+            } else {
+                date.checked_sub_months(Months::new(months.unsigned_abs() as u32))
+            };
+            shifted
+                .map(Value::Date)
+                .ok_or_else(|| "the resulting date is out of range".into())
+        }
+        "days_between" => {
+            arity(2..=2)?;
+            let from = arguments[0].date(&context)?;
+            let to = arguments[1].date(&context)?;
+            Ok(Value::Number((to - from).num_days() as f64))
+        }
+        "weekday" => {
+            arity(1..=1)?;
+            Ok(Value::Text(
+                arguments[0].date(&context)?.format("%A").to_string(),
+            ))
+        }
+        "year" | "month" | "day" => {
+            arity(1..=1)?;
+            let date = arguments[0].date(&context)?;
+            Ok(Value::Number(match name {
+                "year" => date.year() as f64,
+                "month" => date.month() as f64,
+                _ => date.day() as f64,
+            }))
+        }
+        "convert" => {
+            arity(3..=3)?;
+            let value = number(0)?;
+            let from = arguments[1].text(&context)?;
+            let to = arguments[2].text(&context)?;
+            convert(value, from, to).and_then(finite)
+        }
+        _ => Err(format!("unknown function '{name}'")),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Length,
+    Area,
+    Volume,
+    Mass,
+    Time,
+    Speed,
+    Temperature,
+    Data,
+}
+
+/// A unit: `(value + offset) * factor` gives the value in the dimension's
+/// base unit. Only temperatures have an offset.
+struct Unit {
+    names: &'static [&'static str],
+    dimension: Dimension,
+    factor: f64,
+    offset: f64,
+}
+
+const fn unit(names: &'static [&'static str], dimension: Dimension, factor: f64) -> Unit {
+    Unit {
+        names,
+        dimension,
+        factor,
+        offset: 0.0,
+    }
+}
+
+const UNITS: &[Unit] = &[
+    unit(
+        &["m", "meter", "meters", "metre", "metres"],
+        Dimension::Length,
+        1.0,
+    ),
+    unit(
+        &["km", "kilometer", "kilometers", "kilometre", "kilometres"],
+        Dimension::Length,
+        1000.0,
+    ),
+    unit(
+        &[
+            "cm",
+            "centimeter",
+            "centimeters",
+            "centimetre",
+            "centimetres",
+        ],
+        Dimension::Length,
+        0.01,
+    ),
+    unit(
+        &[
+            "mm",
+            "millimeter",
+            "millimeters",
+            "millimetre",
+            "millimetres",
+        ],
+        Dimension::Length,
+        0.001,
+    ),
+    unit(&["mi", "mile", "miles"], Dimension::Length, 1609.344),
+    unit(&["yd", "yard", "yards"], Dimension::Length, 0.9144),
+    unit(&["ft", "foot", "feet"], Dimension::Length, 0.3048),
+    unit(&["in", "inch", "inches"], Dimension::Length, 0.0254),
+    unit(
+        &["nmi", "nautical mile", "nautical miles"],
+        Dimension::Length,
+        1852.0,
+    ),
+    unit(
+        &["m2", "sqm", "square meter", "square meters"],
+        Dimension::Area,
+        1.0,
+    ),
+    unit(
+        &["km2", "square kilometer", "square kilometers"],
+        Dimension::Area,
+        1e6,
+    ),
+    unit(
+        &["cm2", "square centimeter", "square centimeters"],
+        Dimension::Area,
+        1e-4,
+    ),
+    unit(&["ha", "hectare", "hectares"], Dimension::Area, 1e4),
+    unit(&["acre", "acres"], Dimension::Area, 4_046.856_422_4),
+    unit(
+        &["ft2", "sqft", "square foot", "square feet"],
+        Dimension::Area,
+        0.092_903_04,
+    ),
+    unit(
+        &["mi2", "square mile", "square miles"],
+        Dimension::Area,
+        2_589_988.110_336,
+    ),
+    unit(
+        &["l", "liter", "liters", "litre", "litres"],
+        Dimension::Volume,
+        1.0,
+    ),
+    unit(
+        &[
+            "ml",
+            "milliliter",
+            "milliliters",
+            "millilitre",
+            "millilitres",
+        ],
+        Dimension::Volume,
+        0.001,
+    ),
+    unit(
+        &["m3", "cubic meter", "cubic meters"],
+        Dimension::Volume,
+        1000.0,
+    ),
+    unit(
+        &["gal", "gallon", "gallons"],
+        Dimension::Volume,
+        3.785_411_784,
+    ),
+    unit(&["qt", "quart", "quarts"], Dimension::Volume, 0.946_352_946),
+    unit(&["pt", "pint", "pints"], Dimension::Volume, 0.473_176_473),
+    unit(&["cup", "cups"], Dimension::Volume, 0.236_588_236_5),
+    unit(
+        &["floz", "fl oz", "fluid ounce", "fluid ounces"],
+        Dimension::Volume,
+        0.029_573_529_562_5,
+    ),
+    unit(
+        &["tbsp", "tablespoon", "tablespoons"],
+        Dimension::Volume,
+        0.014_786_764_781_25,
+    ),
+    unit(
+        &["tsp", "teaspoon", "teaspoons"],
+        Dimension::Volume,
+        0.004_928_921_593_75,
+    ),
+    unit(&["kg", "kilogram", "kilograms"], Dimension::Mass, 1.0),
+    unit(&["g", "gram", "grams"], Dimension::Mass, 0.001),
+    unit(&["mg", "milligram", "milligrams"], Dimension::Mass, 1e-6),
+    unit(&["t", "tonne", "tonnes"], Dimension::Mass, 1000.0),
+    unit(
+        &["lb", "lbs", "pound", "pounds"],
+        Dimension::Mass,
+        0.453_592_37,
+    ),
+    unit(
+        &["oz", "ounce", "ounces"],
+        Dimension::Mass,
+        0.028_349_523_125,
+    ),
+    unit(&["st", "stone", "stones"], Dimension::Mass, 6.350_293_18),
+    unit(
+        &["s", "sec", "secs", "second", "seconds"],
+        Dimension::Time,
+        1.0,
+    ),
+    unit(
+        &["ms", "millisecond", "milliseconds"],
+        Dimension::Time,
+        0.001,
+    ),
+    unit(&["min", "mins", "minute", "minutes"], Dimension::Time, 60.0),
+    unit(
+        &["h", "hr", "hrs", "hour", "hours"],
+        Dimension::Time,
+        3600.0,
+    ),
+    unit(&["d", "day", "days"], Dimension::Time, 86_400.0),
+    unit(&["wk", "week", "weeks"], Dimension::Time, 604_800.0),
+    unit(&["yr", "year", "years"], Dimension::Time, 31_557_600.0),
+    unit(&["m/s", "mps"], Dimension::Speed, 1.0),
+    unit(&["km/h", "kph", "kmh"], Dimension::Speed, 1000.0 / 3600.0),
+    unit(&["mph", "mi/h"], Dimension::Speed, 1609.344 / 3600.0),
+    unit(
+        &["kn", "kt", "knot", "knots"],
+        Dimension::Speed,
+        1852.0 / 3600.0,
+    ),
+    unit(&["ft/s", "fps"], Dimension::Speed, 0.3048),
+    Unit {
+        names: &["k", "kelvin"],
+        dimension: Dimension::Temperature,
+        factor: 1.0,
+        offset: 0.0,
+    },
+    Unit {
+        names: &["c", "°c", "celsius"],
+        dimension: Dimension::Temperature,
+        factor: 1.0,
+        offset: 273.15,
+    },
+    Unit {
+        names: &["f", "°f", "fahrenheit"],
+        dimension: Dimension::Temperature,
+        factor: 5.0 / 9.0,
+        offset: 459.67,
+    },
+    unit(&["bit", "bits"], Dimension::Data, 0.125),
+    unit(&["b", "byte", "bytes"], Dimension::Data, 1.0),
+    unit(&["kb", "kilobyte", "kilobytes"], Dimension::Data, 1e3),
+    unit(&["mb", "megabyte", "megabytes"], Dimension::Data, 1e6),
+    unit(&["gb", "gigabyte", "gigabytes"], Dimension::Data, 1e9),
+    unit(&["tb", "terabyte", "terabytes"], Dimension::Data, 1e12),
+    unit(&["kib", "kibibyte", "kibibytes"], Dimension::Data, 1024.0),
+    unit(
+        &["mib", "mebibyte", "mebibytes"],
+        Dimension::Data,
+        1_048_576.0,
+    ),
+    unit(
+        &["gib", "gibibyte", "gibibytes"],
+        Dimension::Data,
+        1_073_741_824.0,
+    ),
+    unit(
+        &["tib", "tebibyte", "tebibytes"],
+        Dimension::Data,
+        1_099_511_627_776.0,
+    ),
+];
+
+fn find_unit(name: &str) -> Result<&'static Unit, String> {
+    let name = name.trim().to_lowercase();
+    UNITS
+        .iter()
+        .find(|unit| unit.names.contains(&name.as_str()))
+        .ok_or_else(|| format!("unknown unit '{name}'"))
+}
+
+fn convert(value: f64, from: &str, to: &str) -> Result<f64, String> {
+    let from_unit = find_unit(from)?;
+    let to_unit = find_unit(to)?;
+    if from_unit.dimension != to_unit.dimension {
+        return Err(format!(
+            "can't convert {:?} ('{from}') to {:?} ('{to}')",
+            from_unit.dimension, to_unit.dimension
+        )
+        .to_lowercase());
+    }
+    let base = (value + from_unit.offset) * from_unit.factor;
+    Ok(base / to_unit.factor - to_unit.offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(expression: &str) -> String {
+        evaluate(expression)
+            .unwrap_or_else(|error| panic!("{expression}: {error}"))
+            .to_string()
+    }
+
+    #[test]
+    fn arithmetic_follows_precedence() {
+        assert_eq!(eval("1 + 2 * 3"), "7");
+        assert_eq!(eval("(1 + 2) * 3"), "9");
+        assert_eq!(eval("2 ^ 3 ^ 2"), "512");
+        assert_eq!(eval("2 ** 10"), "1024");
+        assert_eq!(eval("-2 ^ 2"), "-4");
+        assert_eq!(eval("10 % 4 - 3"), "-1");
+        assert_eq!(eval("0.1 + 0.2"), "0.3");
+        assert_eq!(eval("1_000_000 / 3"), "333333.333333");
+        assert_eq!(eval("1.5e3 * 2"), "3000");
+        assert_eq!(eval("round(19.99 * 3 * 1.2, 2)"), "71.96");
+        assert_eq!(eval("max(3, sqrt(16), abs(-2))"), "4");
+        assert_eq!(eval("percent(45, 180)"), "25");
+        assert_eq!(eval("round(2 * pi, 4)"), "6.2832");
+    }
+
+    #[test]
+    fn dates_shift_by_days_and_months() {
+        assert_eq!(eval("date(\"2026-10-17\") + 30"), "2026-11-16 (Monday)");
+        assert_eq!(eval("date('2026-03-01') - date('2026-01-01')"), "59");
+        assert_eq!(
+            eval("days_between(date(\"2026-01-01\"), date(\"2027-01-01\"))"),
+            "365"
+        );
+        assert_eq!(
+            eval("add_months(date(\"2026-01-31\"), 1)"),
+            "2026-02-28 (Saturday)"
+        );
+        assert_eq!(eval("weekday(date(\"2026-12-25\"))"), "Friday");
+    }
+
+    #[test]
+    fn units_convert_within_a_dimension() {
+        assert_eq!(eval("convert(100, \"c\", \"f\")"), "212");
+        assert_eq!(eval("convert(72, \"F\", \"C\")"), "22.2222222222");
+        assert_eq!(eval("convert(10, \"km\", \"mi\")"), "6.21371192237");
+        assert_eq!(eval("convert(1, \"GiB\", \"MB\")"), "1073.741824");
+        assert_eq!(eval("convert(90, \"minutes\", \"h\")"), "1.5");
+        assert!(evaluate("convert(1, \"kg\", \"m\")").is_err());
+        assert!(evaluate("convert(1, \"parsec\", \"m\")").is_err());
+    }
+
+    #[test]
+    fn bad_expressions_are_errors() {
+        for expression in [
+            "",
+            "1 / 0",
+            "1 +",
+            "(1 + 2",
+            "1 2",
+            "sqrt(-1)",
+            "foo(1)",
+            "km",
+            "date(\"2026-13-01\")",
+            "date(\"2026-01-01\") + 1.5",
+            "date(\"2026-01-01\") * 2",
+            "1 ; 2",
+        ] {
+            assert!(evaluate(expression).is_err(), "{expression}");
+        }
+        assert!(evaluate(&"(".repeat(100)).is_err());
+    }
+}
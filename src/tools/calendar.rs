@@ -0,0 +1,365 @@
+//! Calendar tools: list, create, and delete events on the agent's CalDAV or
+//! Google calendar (task workers only).
+//!
+//! The tools are only registered when `[calendar]` is configured, and
+//! `calendar_create` / `calendar_delete` are left out when it's `read_only`.
+//! Times the LLM passes without an offset are read in the calendar's
+//! timezone, and results are shown in it.
+
+use crate::calendar::{CalendarClient, CalendarError, CalendarEvent, NewEvent};
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Longest window `calendar_list` covers.
+const MAX_LIST_DAYS: i64 = 31;
+
+/// Length of a new event when neither `end` nor `duration_minutes` is given.
+const DEFAULT_DURATION_MINUTES: i64 = 30;
+
+/// Parse a time argument in the calendar's timezone.
+fn parse_time(value: &str, tz: Tz) -> Result<DateTime<Utc>, CalendarError> {
+    crate::calendar::parse_local_time(value, tz).map_err(CalendarError::InvalidArgument)
+}
+
+/// An event as the tools return it, with times in the calendar's timezone.
+#[derive(Debug, Serialize)]
+pub struct CalendarEventOutput {
+    /// ID for `calendar_delete`.
+    pub id: String,
+    pub title: String,
+    pub start: String,
+    pub end: String,
+    pub all_day: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attendees: Vec<String>,
+}
+
+impl CalendarEventOutput {
+    fn new(event: CalendarEvent, tz: Tz) -> Self {
+        let format = |time: DateTime<Utc>| {
+            let local = time.with_timezone(&tz);
+            if event.all_day {
+                local.format("%a %Y-%m-%d").to_string()
+            } else {
+                local.format("%a %Y-%m-%d %H:%M").to_string()
+            }
+        };
+        Self {
+            start: format(event.start),
+            end: format(event.end),
+            id: event.id,
+            title: event.title,
+            all_day: event.all_day,
+            location: event.location,
+            description: event.description,
+            attendees: event.attendees,
+        }
+    }
+}
+
+// -- calendar_list --
+
+/// Tool for listing upcoming events.
+#[derive(Debug, Clone)]
+pub struct CalendarListTool {
+    client: CalendarClient,
+    tz: Tz,
+}
+
+impl CalendarListTool {
+    pub fn new(client: CalendarClient, tz: Tz) -> Self {
+        Self { client, tz }
+    }
+}
+
+/// Arguments for the calendar list tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CalendarListArgs {
+    /// Start of the window; now when omitted.
+    pub from: Option<String>,
+    /// End of the window; 24 hours after `from` when omitted.
+    pub to: Option<String>,
+}
+
+/// Output from the calendar list tool.
+#[derive(Debug, Serialize)]
+pub struct CalendarListOutput {
+    /// Timezone the times are shown in.
+    pub timezone: String,
+    pub from: String,
+    pub to: String,
+    pub events: Vec<CalendarEventOutput>,
+    pub count: usize,
+}
+
+impl Tool for CalendarListTool {
+    const NAME: &'static str = "calendar_list";
+
+    type Error = CalendarError;
+    type Args = CalendarListArgs;
+    type Output = CalendarListOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/calendar_list").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "string",
+                        "description": format!("Start of the window, e.g. \"2026-10-19\" or \"2026-10-19 09:00\" ({}), or RFC 3339. Defaults to now.", self.tz)
+                    },
+                    "to": {
+                        "type": "string",
+                        "description": "End of the window, in the same formats. Defaults to 24 hours after `from`; at most 31 days after it."
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let from = match &args.from {
+            Some(from) => parse_time(from, self.tz)?,
+            None => Utc::now(),
+        };
+        let to = match &args.to {
+            Some(to) => parse_time(to, self.tz)?,
+            None => from + chrono::Duration::hours(24),
+        };
+        if to <= from {
+            return Err(CalendarError::InvalidArgument(
+                "'to' must be after 'from'".into(),
+            ));
+        }
+        if to - from > chrono::Duration::days(MAX_LIST_DAYS) {
+            return Err(CalendarError::InvalidArgument(format!(
+                "the window can be at most {MAX_LIST_DAYS} days"
+            )));
+        }
+
+        let events: Vec<_> = self
+            .client
+            .list_events(from, to)
+            .await?
+            .into_iter()
+            .map(|event| CalendarEventOutput::new(event, self.tz))
+            .collect();
+        let local = |time: DateTime<Utc>| time.with_timezone(&self.tz).format("%a %Y-%m-%d %H:%M");
+
+        Ok(CalendarListOutput {
+            timezone: self.tz.to_string(),
+            from: local(from).to_string(),
+            to: local(to).to_string(),
+            count: events.len(),
+            events,
+        })
+    }
+}
+
+// -- calendar_create --
+
+/// Tool for creating an event.
+#[derive(Debug, Clone)]
+pub struct CalendarCreateTool {
+    client: CalendarClient,
+    tz: Tz,
+}
+
+impl CalendarCreateTool {
+    pub fn new(client: CalendarClient, tz: Tz) -> Self {
+        Self { client, tz }
+    }
+}
+
+/// Arguments for the calendar create tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CalendarCreateArgs {
+    pub title: String,
+    /// When the event starts.
+    pub start: String,
+    /// When the event ends. Takes precedence over `duration_minutes`.
+    pub end: Option<String>,
+    /// Length of the event, when `end` is omitted (default 30).
+    pub duration_minutes: Option<u32>,
+    /// Email addresses to invite.
+    #[serde(default)]
+    pub attendees: Vec<String>,
+    pub location: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Output from the calendar create tool.
+#[derive(Debug, Serialize)]
+pub struct CalendarCreateOutput {
+    pub timezone: String,
+    pub event: CalendarEventOutput,
+}
+
+impl Tool for CalendarCreateTool {
+    const NAME: &'static str = "calendar_create";
+
+    type Error = CalendarError;
+    type Args = CalendarCreateArgs;
+    type Output = CalendarCreateOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/calendar_create").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "title": {
+                        "type": "string",
+                        "description": "Event title."
+                    },
+                    "start": {
+                        "type": "string",
+                        "description": format!("Start time, e.g. \"2026-10-19 14:00\" ({}), or RFC 3339.", self.tz)
+                    },
+                    "end": {
+                        "type": "string",
+                        "description": "End time, in the same formats. Use this or duration_minutes."
+                    },
+                    "duration_minutes": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "default": 30,
+                        "description": "Length of the event when `end` is omitted."
+                    },
+                    "attendees": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Email addresses to invite."
+                    },
+                    "location": {
+                        "type": "string",
+                        "description": "Where the event takes place, or a meeting link."
+                    },
+                    "description": {
+                        "type": "string",
+                        "description": "Agenda or notes."
+                    }
+                },
+                "required": ["title", "start"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let title = args.title.trim();
+        if title.is_empty() {
+            return Err(CalendarError::InvalidArgument(
+                "'title' must not be empty".into(),
+            ));
+        }
+        let start = parse_time(&args.start, self.tz)?;
+        let end = match (&args.end, args.duration_minutes) {
+            (Some(end), _) => parse_time(end, self.tz)?,
+            (None, Some(minutes)) => start + chrono::Duration::minutes(minutes as i64),
+            (None, None) => start + chrono::Duration::minutes(DEFAULT_DURATION_MINUTES),
+        };
+        if let Some(invalid) = args.attendees.iter().find(|email| !email.contains('@')) {
+            return Err(CalendarError::InvalidArgument(format!(
+                "attendee {invalid:?} is not an email address"
+            )));
+        }
+
+        let event = NewEvent {
+            title: title.to_string(),
+            start,
+            end,
+            location: args.location.filter(|location| !location.trim().is_empty()),
+            description: args
+                .description
+                .filter(|description| !description.trim().is_empty()),
+            attendees: args
+                .attendees
+                .iter()
+                .map(|email| email.trim().to_string())
+                .collect(),
+        };
+        let created = self.client.create_event(&event).await?;
+
+        Ok(CalendarCreateOutput {
+            timezone: self.tz.to_string(),
+            event: CalendarEventOutput::new(created, self.tz),
+        })
+    }
+}
+
+// -- calendar_delete --
+
+/// Tool for deleting an event.
+#[derive(Debug, Clone)]
+pub struct CalendarDeleteTool {
+    client: CalendarClient,
+}
+
+impl CalendarDeleteTool {
+    pub fn new(client: CalendarClient) -> Self {
+        Self { client }
+    }
+}
+
+/// Arguments for the calendar delete tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CalendarDeleteArgs {
+    /// Event ID from `calendar_list` or `calendar_create`.
+    pub id: String,
+}
+
+/// Output from the calendar delete tool.
+#[derive(Debug, Serialize)]
+pub struct CalendarDeleteOutput {
+    pub deleted: String,
+}
+
+impl Tool for CalendarDeleteTool {
+    const NAME: &'static str = "calendar_delete";
+
+    type Error = CalendarError;
+    type Args = CalendarDeleteArgs;
+    type Output = CalendarDeleteOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/calendar_delete").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "The event's id, as returned by calendar_list or calendar_create."
+                    }
+                },
+                "required": ["id"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let id = args.id.trim();
+        if id.is_empty() {
+            return Err(CalendarError::InvalidArgument(
+                "'id' must not be empty".into(),
+            ));
+        }
+        self.client.delete_event(id).await?;
+        Ok(CalendarDeleteOutput {
+            deleted: id.to_string(),
+        })
+    }
+}
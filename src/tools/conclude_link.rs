@@ -4,14 +4,14 @@
 //! tool with a summary. The channel checks the flag after the LLM turn and
 //! routes the summary back to the originating channel as a system message.
 
-use crate::OutboundResponse;
+use crate::{OutboundResponse, ResponseSender};
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use tokio::sync::{RwLock, mpsc};
+use tokio::sync::RwLock;
 
 /// Shared flag between the ConcludeLinkTool and the channel event loop.
 pub type ConcludeLinkFlag = Arc<AtomicBool>;
@@ -32,14 +32,14 @@ pub fn new_conclude_link() -> (ConcludeLinkFlag, ConcludeLinkSummary) {
 pub struct ConcludeLinkTool {
     flag: ConcludeLinkFlag,
     summary: ConcludeLinkSummary,
-    response_tx: mpsc::Sender<OutboundResponse>,
+    response_tx: ResponseSender,
 }
 
 impl ConcludeLinkTool {
     pub fn new(
         flag: ConcludeLinkFlag,
         summary: ConcludeLinkSummary,
-        response_tx: mpsc::Sender<OutboundResponse>,
+        response_tx: ResponseSender,
     ) -> Self {
         Self {
             flag,
@@ -0,0 +1,422 @@
+//! Container run tool for executing untrusted code in throwaway containers
+//! (task workers only).
+//!
+//! Each call starts a fresh container with Docker or Podman and removes it
+//! when the call ends. Containers get the resource limits from the agent's
+//! `[container]` config, a read-only root filesystem with a scratch `/tmp`,
+//! no capabilities, an unprivileged user, and no network unless the call
+//! asks for it and the config allows it.
+
+use crate::config::ContainerConfig;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt as _;
+use tokio::process::Command;
+
+/// Runtimes tried, in order, when the config doesn't name one.
+const RUNTIMES: &[&str] = &["podman", "docker"];
+
+/// Size of the writable `/tmp` in each container.
+const TMPFS_SIZE: &str = "64m";
+
+/// Tool for running code in an ephemeral container.
+#[derive(Debug, Clone)]
+pub struct ContainerRunTool {
+    config: ContainerConfig,
+}
+
+impl ContainerRunTool {
+    pub fn new(config: ContainerConfig) -> Self {
+        Self { config }
+    }
+}
+
+/// Error type for container run tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Container run failed: {0}")]
+pub struct ContainerRunError(String);
+
+/// Arguments for container run tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ContainerRunArgs {
+    /// Source code to run with the `language` interpreter.
+    #[serde(default)]
+    pub code: Option<String>,
+    /// Interpreter for `code`: "python" (default), "node", "sh", or "bash".
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// Shell command to run instead of `code`.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Image to run in. Defaults to the configured image.
+    #[serde(default)]
+    pub image: Option<String>,
+    /// Give the container network access, if the config allows it.
+    #[serde(default)]
+    pub network: bool,
+    /// Timeout in seconds (default: 60).
+    #[serde(
+        default = "default_timeout",
+        deserialize_with = "crate::tools::deserialize_string_or_u64"
+    )]
+    pub timeout_seconds: u64,
+}
+
+fn default_language() -> String {
+    "python".into()
+}
+
+fn default_timeout() -> u64 {
+    60
+}
+
+/// Output from container run tool.
+#[derive(Debug, Serialize)]
+pub struct ContainerRunOutput {
+    /// Whether the code exited with status 0.
+    pub success: bool,
+    /// The exit code. The runtime itself reports 125 when the container
+    /// couldn't start, e.g. because the image doesn't exist.
+    pub exit_code: i32,
+    pub stdout: String,
+    pub stderr: String,
+    /// The image the code ran in.
+    pub image: String,
+    /// Formatted summary.
+    pub summary: String,
+}
+
+impl Tool for ContainerRunTool {
+    const NAME: &'static str = "container_run";
+
+    type Error = ContainerRunError;
+    type Args = ContainerRunArgs;
+    type Output = ContainerRunOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/container_run").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "code": {
+                        "type": "string",
+                        "description": "Source code to run with the interpreter given by 'language'."
+                    },
+                    "language": {
+                        "type": "string",
+                        "enum": ["python", "node", "sh", "bash"],
+                        "default": "python",
+                        "description": "Interpreter for 'code'. The image must have it installed."
+                    },
+                    "command": {
+                        "type": "string",
+                        "description": "Shell command to run instead of 'code'."
+                    },
+                    "image": {
+                        "type": "string",
+                        "description": format!("Container image to run in (default: {}).", self.config.default_image)
+                    },
+                    "network": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Give the container network access. Refused unless the agent allows it."
+                    },
+                    "timeout_seconds": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": self.config.max_timeout_secs,
+                        "default": 60,
+                        "description": "Maximum time to wait, including pulling the image."
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (entrypoint, input) = match (args.code, args.command) {
+            (Some(code), None) => (interpreter(&args.language)?, Some(code)),
+            (None, Some(command)) => (vec!["sh".into(), "-c".into(), command], None),
+            _ => {
+                return Err(ContainerRunError(
+                    "pass exactly one of 'code' and 'command'".into(),
+                ));
+            }
+        };
+        let image = args
+            .image
+            .map(|image| image.trim().to_string())
+            .filter(|image| !image.is_empty())
+            .unwrap_or_else(|| self.config.default_image.clone());
+        if !is_image_reference(&image) {
+            return Err(ContainerRunError(format!(
+                "'{image}' isn't a valid image reference"
+            )));
+        }
+        if !image_allowed(&self.config, &image) {
+            let allowed = if self.config.allowed_images.is_empty() {
+                self.config.default_image.clone()
+            } else {
+                self.config.allowed_images.join(", ")
+            };
+            return Err(ContainerRunError(format!(
+                "image '{image}' isn't allowed; allowed images: {allowed}"
+            )));
+        }
+        if args.network && !self.config.allow_network {
+            return Err(ContainerRunError(
+                "network access is disabled for containers on this agent".into(),
+            ));
+        }
+
+        let runtime = match &self.config.runtime {
+            Some(runtime) => runtime.clone(),
+            None => detect_runtime().await.ok_or_else(|| {
+                ContainerRunError("neither podman nor docker is installed".into())
+            })?,
+        };
+        let name = format!("spacebot-{}", uuid::Uuid::new_v4());
+        let mut command = Command::new(&runtime);
+        command
+            .args(run_args(
+                &self.config,
+                &name,
+                &image,
+                args.network,
+                &entrypoint,
+            ))
+            .stdin(if input.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let mut child = command
+            .spawn()
+            .map_err(|error| ContainerRunError(format!("failed to start {runtime}: {error}")))?;
+        let stdin = child.stdin.take();
+
+        let timeout = std::time::Duration::from_secs(
+            args.timeout_seconds
+                .clamp(1, self.config.max_timeout_secs.max(1)),
+        );
+        // The code is written while the output is read, and both count
+        // against the timeout: a program that never reads its stdin can't
+        // hold the call open, and one that prints before reading everything
+        // can't fill the pipe and stall the write.
+        let run = async {
+            let send = async {
+                if let (Some(input), Some(mut stdin)) = (input, stdin) {
+                    // Dropping `stdin` afterwards closes it, so the program
+                    // sees the end of its code.
+                    stdin.write_all(input.as_bytes()).await?;
+                }
+                Ok::<_, std::io::Error>(())
+            };
+            let (sent, output) = tokio::join!(send, child.wait_with_output());
+            sent.map_err(|error| ContainerRunError(format!("failed to send code: {error}")))?;
+            output.map_err(|error| ContainerRunError(format!("failed to run: {error}")))
+        };
+        let output = match tokio::time::timeout(timeout, run).await {
+            Ok(output) => output?,
+            Err(_) => {
+                // Killing the client doesn't stop the container; remove it.
+                let _ = Command::new(&runtime)
+                    .args(["rm", "--force", &name])
+                    .output()
+                    .await;
+                return Err(ContainerRunError(format!(
+                    "timed out after {} seconds",
+                    timeout.as_secs()
+                )));
+            }
+        };
+
+        let stdout = crate::tools::truncate_output(
+            &String::from_utf8_lossy(&output.stdout),
+            crate::tools::MAX_TOOL_OUTPUT_BYTES,
+        );
+        let stderr = crate::tools::truncate_output(
+            &String::from_utf8_lossy(&output.stderr),
+            crate::tools::MAX_TOOL_OUTPUT_BYTES,
+        );
+        let exit_code = output.status.code().unwrap_or(-1);
+        let summary = crate::tools::exec::format_exec_output(exit_code, &stdout, &stderr);
+
+        Ok(ContainerRunOutput {
+            success: output.status.success(),
+            exit_code,
+            stdout,
+            stderr,
+            image,
+            summary,
+        })
+    }
+}
+
+/// The command that runs code read from stdin in `language`.
+fn interpreter(language: &str) -> Result<Vec<String>, ContainerRunError> {
+    let command: &[&str] = match language {
+        "python" => &["python3", "-"],
+        "node" => &["node", "-"],
+        "sh" => &["sh", "-s"],
+        "bash" => &["bash", "-s"],
+        other => {
+            return Err(ContainerRunError(format!(
+                "unsupported language '{other}'; use python, node, sh, or bash, or pass a command"
+            )));
+        }
+    };
+    Ok(command.iter().map(|part| part.to_string()).collect())
+}
+
+/// Whether `image` looks like `[registry/]name[:tag][@digest]`. The image is
+/// a positional argument to the runtime, so anything that could parse as a
+/// flag, like `--volume=/:/host`, must never get through.
+fn is_image_reference(image: &str) -> bool {
+    image.len() <= 255
+        && image.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && image
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/' | ':' | '@'))
+}
+
+/// Whether `image` matches the allowlist. `name` entries allow any tag of
+/// the image, `name:tag` entries only that tag. Without an allowlist only
+/// the default image may be used.
+fn image_allowed(config: &ContainerConfig, image: &str) -> bool {
+    if config.allowed_images.is_empty() {
+        return image == config.default_image;
+    }
+    config.allowed_images.iter().any(|entry| {
+        image == entry
+            || image
+                .strip_prefix(entry.as_str())
+                .is_some_and(|rest| !entry.contains(':') && rest.starts_with(':'))
+    })
+}
+
+/// Arguments to the runtime for one locked-down, self-removing container.
+fn run_args(
+    config: &ContainerConfig,
+    name: &str,
+    image: &str,
+    network: bool,
+    entrypoint: &[String],
+) -> Vec<String> {
+    let mut args: Vec<String> = vec![
+        "run".into(),
+        "--rm".into(),
+        "--interactive".into(),
+        "--name".into(),
+        name.into(),
+        "--network".into(),
+        if network { "bridge" } else { "none" }.into(),
+        "--memory".into(),
+        config.memory.clone(),
+        "--memory-swap".into(),
+        config.memory.clone(),
+        "--cpus".into(),
+        config.cpus.to_string(),
+        "--pids-limit".into(),
+        config.pids_limit.to_string(),
+        "--read-only".into(),
+        "--tmpfs".into(),
+        format!("/tmp:rw,size={TMPFS_SIZE}"),
+        "--workdir".into(),
+        "/tmp".into(),
+        "--cap-drop".into(),
+        "ALL".into(),
+        "--security-opt".into(),
+        "no-new-privileges".into(),
+        "--user".into(),
+        "65534:65534".into(),
+        image.into(),
+    ];
+    args.extend(entrypoint.iter().cloned());
+    args
+}
+
+/// The first container runtime found on this machine.
+async fn detect_runtime() -> Option<String> {
+    for runtime in RUNTIMES {
+        let found = Command::new(runtime)
+            .arg("--version")
+            .output()
+            .await
+            .is_ok_and(|output| output.status.success());
+        if found {
+            return Some(runtime.to_string());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn containers_are_locked_down_and_offline_by_default() {
+        let config = ContainerConfig::default();
+        let entrypoint = interpreter("python").unwrap();
+        let args = run_args(
+            &config,
+            "spacebot-1",
+            "python:3.12-slim",
+            false,
+            &entrypoint,
+        );
+        let joined = args.join(" ");
+
+        assert!(joined.starts_with("run --rm --interactive --name spacebot-1"));
+        assert!(joined.contains("--network none"));
+        assert!(joined.contains("--memory 512m --memory-swap 512m"));
+        assert!(joined.contains("--pids-limit 256"));
+        assert!(joined.contains("--read-only"));
+        assert!(joined.contains("--cap-drop ALL"));
+        assert!(joined.ends_with("python:3.12-slim python3 -"));
+
+        let online = run_args(&config, "spacebot-2", "alpine", true, &entrypoint);
+        assert!(online.join(" ").contains("--network bridge"));
+        assert!(interpreter("ruby").is_err());
+    }
+
+    #[test]
+    fn image_allowlist_matches_names_and_tags() {
+        let mut config = ContainerConfig {
+            allowed_images: vec!["python".to_string(), "node:22-slim".to_string()],
+            ..ContainerConfig::default()
+        };
+
+        assert!(image_allowed(&config, "python"));
+        assert!(image_allowed(&config, "python:3.12-slim"));
+        assert!(image_allowed(&config, "node:22-slim"));
+        assert!(!image_allowed(&config, "node:20"));
+        assert!(!image_allowed(&config, "pythonista"));
+
+        config.allowed_images.clear();
+        let default_image = config.default_image.clone();
+        assert!(image_allowed(&config, &default_image));
+        assert!(!image_allowed(&config, "anything:latest"));
+    }
+
+    #[test]
+    fn flag_shaped_images_are_refused() {
+        assert!(is_image_reference("python:3.12-slim"));
+        assert!(is_image_reference("ghcr.io/org/tool@sha256:0123abcd"));
+        assert!(!is_image_reference("--volume=/:/host"));
+        assert!(!is_image_reference("-v"));
+        assert!(!is_image_reference("--privileged"));
+        assert!(!is_image_reference("alpine --user=0"));
+        assert!(!is_image_reference(""));
+    }
+}
@@ -0,0 +1,596 @@
+//! Coolify tools: applications, deployments, and restarts through the
+//! Coolify API (task workers only).
+//!
+//! All tools share one [`CoolifyClient`] for the agent's `coolify_url`,
+//! authenticated with its `coolify_token`. They are only registered when both
+//! are configured, and only for workers spawned for a sender whose tier
+//! permits them (admin by default).
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Log lines a deployment log read returns by default.
+const DEFAULT_LOG_LINES: usize = 200;
+
+/// Authenticated Coolify API client shared by the Coolify tools.
+#[derive(Clone)]
+pub struct CoolifyClient {
+    client: reqwest::Client,
+    /// API root, e.g. `https://coolify.example.com/api/v1`.
+    api_url: Arc<str>,
+    token: Arc<str>,
+}
+
+impl std::fmt::Debug for CoolifyClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CoolifyClient")
+            .field("api_url", &self.api_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CoolifyClient {
+    /// `url` is the Coolify instance, with or without the `/api/v1` suffix.
+    pub fn new(url: &str, token: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("spacebot/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .expect("hardcoded reqwest client config");
+
+        Self {
+            client,
+            api_url: Arc::from(api_url(url)),
+            token: Arc::from(token.into()),
+        }
+    }
+
+    fn get(&self, path: &str) -> reqwest::RequestBuilder {
+        self.client
+            .get(format!("{}{path}", self.api_url))
+            .bearer_auth(&*self.token)
+            .header("Accept", "application/json")
+    }
+
+    async fn json<T: serde::de::DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T, CoolifyError> {
+        let response = request
+            .send()
+            .await
+            .map_err(|error| CoolifyError::RequestFailed(error.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "failed to read response body".into());
+            return Err(CoolifyError::RequestFailed(format!(
+                "HTTP {status}: {body}"
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|error| CoolifyError::InvalidResponse(error.to_string()))
+    }
+}
+
+/// Error type for the Coolify tools.
+#[derive(Debug, thiserror::Error)]
+pub enum CoolifyError {
+    #[error("Coolify request failed: {0}")]
+    RequestFailed(String),
+
+    #[error("Failed to parse Coolify response: {0}")]
+    InvalidResponse(String),
+
+    #[error("{0}")]
+    InvalidArgument(String),
+}
+
+/// The API root for a Coolify instance URL.
+fn api_url(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix("/api/v1").unwrap_or(url);
+    format!("{url}/api/v1")
+}
+
+/// Check that `uuid` is a Coolify resource ID, so it can go in a path.
+fn validate_uuid<'a>(field: &str, uuid: &'a str) -> Result<&'a str, CoolifyError> {
+    let uuid = uuid.trim();
+    if !uuid.is_empty() && uuid.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        Ok(uuid)
+    } else {
+        Err(CoolifyError::InvalidArgument(format!(
+            "'{field}' must be a Coolify resource UUID, got '{uuid}'"
+        )))
+    }
+}
+
+fn default_log_lines() -> usize {
+    DEFAULT_LOG_LINES
+}
+
+// -- Coolify API response types (private, only model what we need) --
+
+#[derive(Debug, Deserialize)]
+struct ApiApplication {
+    uuid: String,
+    name: String,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    fqdn: Option<String>,
+    #[serde(default)]
+    git_repository: Option<String>,
+    #[serde(default)]
+    git_branch: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiDeployResponse {
+    #[serde(default)]
+    deployments: Vec<ApiQueuedDeployment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiQueuedDeployment {
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    resource_uuid: Option<String>,
+    #[serde(default)]
+    deployment_uuid: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiDeployment {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    commit: Option<String>,
+    /// A JSON-encoded array of [`ApiLogEntry`].
+    #[serde(default)]
+    logs: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiLogEntry {
+    #[serde(default)]
+    output: String,
+    /// Internal commands Coolify runs; not shown in its own UI either.
+    #[serde(default)]
+    hidden: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiRestartResponse {
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    deployment_uuid: Option<String>,
+}
+
+/// The last `lines` visible lines of a deployment's encoded log.
+fn tail_logs(logs: &str, lines: usize) -> Result<String, CoolifyError> {
+    let entries: Vec<ApiLogEntry> = serde_json::from_str(logs)
+        .map_err(|error| CoolifyError::InvalidResponse(error.to_string()))?;
+    let visible: Vec<&str> = entries
+        .iter()
+        .filter(|entry| !entry.hidden)
+        .flat_map(|entry| entry.output.lines())
+        .collect();
+    let start = visible.len().saturating_sub(lines);
+    Ok(visible[start..].join("\n"))
+}
+
+// -- coolify_list_applications --
+
+/// Tool for listing the applications on the Coolify instance.
+#[derive(Debug, Clone)]
+pub struct CoolifyListApplicationsTool {
+    client: CoolifyClient,
+}
+
+impl CoolifyListApplicationsTool {
+    pub fn new(client: CoolifyClient) -> Self {
+        Self { client }
+    }
+}
+
+/// Arguments for the list applications tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CoolifyListApplicationsArgs {
+    /// Only applications whose name contains this, ignoring case.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// An application on the Coolify instance.
+#[derive(Debug, Serialize)]
+pub struct CoolifyApplication {
+    pub uuid: String,
+    pub name: String,
+    /// Coolify's status, e.g. "running:healthy" or "exited".
+    pub status: Option<String>,
+    /// The application's domains, comma-separated.
+    pub fqdn: Option<String>,
+    pub git_repository: Option<String>,
+    pub git_branch: Option<String>,
+}
+
+/// Output from the list applications tool.
+#[derive(Debug, Serialize)]
+pub struct CoolifyListApplicationsOutput {
+    pub applications: Vec<CoolifyApplication>,
+    pub count: usize,
+}
+
+impl Tool for CoolifyListApplicationsTool {
+    const NAME: &'static str = "coolify_list_applications";
+
+    type Error = CoolifyError;
+    type Args = CoolifyListApplicationsArgs;
+    type Output = CoolifyListApplicationsOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/coolify_list_applications").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Only list applications whose name contains this (case-insensitive)."
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let applications: Vec<ApiApplication> =
+            self.client.json(self.client.get("/applications")).await?;
+        let filter = args.name.map(|name| name.trim().to_lowercase());
+
+        let applications: Vec<CoolifyApplication> = applications
+            .into_iter()
+            .filter(|application| {
+                filter
+                    .as_deref()
+                    .is_none_or(|filter| application.name.to_lowercase().contains(filter))
+            })
+            .map(|application| CoolifyApplication {
+                uuid: application.uuid,
+                name: application.name,
+                status: application.status,
+                fqdn: application.fqdn,
+                git_repository: application.git_repository,
+                git_branch: application.git_branch,
+            })
+            .collect();
+
+        Ok(CoolifyListApplicationsOutput {
+            count: applications.len(),
+            applications,
+        })
+    }
+}
+
+// -- coolify_deploy --
+
+/// Tool for deploying an application, service, or database.
+#[derive(Debug, Clone)]
+pub struct CoolifyDeployTool {
+    client: CoolifyClient,
+}
+
+impl CoolifyDeployTool {
+    pub fn new(client: CoolifyClient) -> Self {
+        Self { client }
+    }
+}
+
+/// Arguments for the deploy tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CoolifyDeployArgs {
+    /// UUID of the resource to deploy.
+    pub uuid: String,
+    /// Rebuild without the build cache.
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// A deployment Coolify queued.
+#[derive(Debug, Serialize)]
+pub struct CoolifyQueuedDeployment {
+    pub resource_uuid: Option<String>,
+    /// Pass to `coolify_deployment_logs` to follow the deployment.
+    pub deployment_uuid: Option<String>,
+    pub message: String,
+}
+
+/// Output from the deploy tool.
+#[derive(Debug, Serialize)]
+pub struct CoolifyDeployOutput {
+    pub deployments: Vec<CoolifyQueuedDeployment>,
+}
+
+impl Tool for CoolifyDeployTool {
+    const NAME: &'static str = "coolify_deploy";
+
+    type Error = CoolifyError;
+    type Args = CoolifyDeployArgs;
+    type Output = CoolifyDeployOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/coolify_deploy").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "uuid": {
+                        "type": "string",
+                        "description": "UUID of the application, service, or database to deploy."
+                    },
+                    "force": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Rebuild without the build cache."
+                    }
+                },
+                "required": ["uuid"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let uuid = validate_uuid("uuid", &args.uuid)?;
+        let force = args.force.to_string();
+        let request = self
+            .client
+            .get("/deploy")
+            .query(&[("uuid", uuid), ("force", force.as_str())]);
+        let response: ApiDeployResponse = self.client.json(request).await?;
+
+        tracing::info!(%uuid, force = args.force, "coolify deployment triggered");
+
+        Ok(CoolifyDeployOutput {
+            deployments: response
+                .deployments
+                .into_iter()
+                .map(|deployment| CoolifyQueuedDeployment {
+                    resource_uuid: deployment.resource_uuid,
+                    deployment_uuid: deployment.deployment_uuid,
+                    message: deployment.message,
+                })
+                .collect(),
+        })
+    }
+}
+
+// -- coolify_deployment_logs --
+
+/// Tool for reading a deployment's status and build log.
+#[derive(Debug, Clone)]
+pub struct CoolifyDeploymentLogsTool {
+    client: CoolifyClient,
+}
+
+impl CoolifyDeploymentLogsTool {
+    pub fn new(client: CoolifyClient) -> Self {
+        Self { client }
+    }
+}
+
+/// Arguments for the deployment logs tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CoolifyDeploymentLogsArgs {
+    /// UUID of the deployment, as returned by `coolify_deploy`.
+    pub deployment_uuid: String,
+    /// How many of the last log lines to return (default 200).
+    #[serde(default = "default_log_lines")]
+    pub lines: usize,
+}
+
+/// Output from the deployment logs tool.
+#[derive(Debug, Serialize)]
+pub struct CoolifyDeploymentLogsOutput {
+    pub deployment_uuid: String,
+    /// "queued", "in_progress", "finished", "failed", or "cancelled-by-user".
+    pub status: Option<String>,
+    pub commit: Option<String>,
+    /// The last lines of the build log.
+    pub logs: String,
+}
+
+impl Tool for CoolifyDeploymentLogsTool {
+    const NAME: &'static str = "coolify_deployment_logs";
+
+    type Error = CoolifyError;
+    type Args = CoolifyDeploymentLogsArgs;
+    type Output = CoolifyDeploymentLogsOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/coolify_deployment_logs").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "deployment_uuid": {
+                        "type": "string",
+                        "description": "UUID of the deployment, as returned by coolify_deploy."
+                    },
+                    "lines": {
+                        "type": "integer",
+                        "default": DEFAULT_LOG_LINES,
+                        "description": "How many of the last log lines to return."
+                    }
+                },
+                "required": ["deployment_uuid"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let deployment_uuid = validate_uuid("deployment_uuid", &args.deployment_uuid)?;
+        let deployment: ApiDeployment = self
+            .client
+            .json(self.client.get(&format!("/deployments/{deployment_uuid}")))
+            .await?;
+        let logs = match deployment.logs.as_deref() {
+            Some(logs) if !logs.trim().is_empty() => tail_logs(logs, args.lines)?,
+            _ => String::new(),
+        };
+
+        Ok(CoolifyDeploymentLogsOutput {
+            deployment_uuid: deployment_uuid.to_string(),
+            status: deployment.status,
+            commit: deployment.commit,
+            logs,
+        })
+    }
+}
+
+// -- coolify_restart --
+
+/// Tool for restarting an application or service.
+#[derive(Debug, Clone)]
+pub struct CoolifyRestartTool {
+    client: CoolifyClient,
+}
+
+impl CoolifyRestartTool {
+    pub fn new(client: CoolifyClient) -> Self {
+        Self { client }
+    }
+}
+
+/// Arguments for the restart tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CoolifyRestartArgs {
+    /// UUID of the application or service.
+    pub uuid: String,
+    /// "application" (default) or "service".
+    #[serde(default = "default_kind")]
+    pub kind: String,
+}
+
+fn default_kind() -> String {
+    "application".into()
+}
+
+/// Output from the restart tool.
+#[derive(Debug, Serialize)]
+pub struct CoolifyRestartOutput {
+    pub uuid: String,
+    pub message: String,
+    /// Set for applications, whose restart runs as a deployment.
+    pub deployment_uuid: Option<String>,
+}
+
+impl Tool for CoolifyRestartTool {
+    const NAME: &'static str = "coolify_restart";
+
+    type Error = CoolifyError;
+    type Args = CoolifyRestartArgs;
+    type Output = CoolifyRestartOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/coolify_restart").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "uuid": {
+                        "type": "string",
+                        "description": "UUID of the application or service to restart."
+                    },
+                    "kind": {
+                        "type": "string",
+                        "enum": ["application", "service"],
+                        "default": "application",
+                        "description": "Whether the UUID is an application or a service."
+                    }
+                },
+                "required": ["uuid"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let uuid = validate_uuid("uuid", &args.uuid)?;
+        let collection = match args.kind.as_str() {
+            "application" => "applications",
+            "service" => "services",
+            other => {
+                return Err(CoolifyError::InvalidArgument(format!(
+                    "'kind' must be 'application' or 'service', got '{other}'"
+                )));
+            }
+        };
+        let response: ApiRestartResponse = self
+            .client
+            .json(self.client.get(&format!("/{collection}/{uuid}/restart")))
+            .await?;
+
+        tracing::info!(%uuid, kind = %args.kind, "coolify restart triggered");
+
+        Ok(CoolifyRestartOutput {
+            uuid: uuid.to_string(),
+            message: response.message,
+            deployment_uuid: response.deployment_uuid,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instance_urls_resolve_to_the_api_root() {
+        assert_eq!(
+            api_url("https://coolify.example.com/"),
+            "https://coolify.example.com/api/v1"
+        );
+        assert_eq!(
+            api_url("https://coolify.example.com/api/v1/"),
+            "https://coolify.example.com/api/v1"
+        );
+        assert!(validate_uuid("uuid", "kg4cco8").is_ok());
+        assert!(validate_uuid("uuid", "../servers").is_err());
+        assert!(validate_uuid("uuid", "").is_err());
+    }
+
+    #[test]
+    fn deployment_logs_skip_hidden_entries_and_keep_the_tail() {
+        let logs = serde_json::json!([
+            {"output": "docker login", "hidden": true},
+            {"output": "Cloning repository\nChecking out main", "hidden": false},
+            {"output": "Building image", "hidden": false},
+            {"output": "Deployment finished"}
+        ])
+        .to_string();
+
+        assert_eq!(
+            tail_logs(&logs, 2).unwrap(),
+            "Building image\nDeployment finished"
+        );
+        assert_eq!(
+            tail_logs(&logs, 100).unwrap(),
+            "Cloning repository\nChecking out main\nBuilding image\nDeployment finished"
+        );
+        assert!(tail_logs("not json", 10).is_err());
+    }
+}
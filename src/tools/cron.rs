@@ -1,7 +1,9 @@
 //! Cron job management tool for creating, listing, and deleting scheduled tasks.
 
+use crate::cron::expression::CronExpression;
 use crate::cron::scheduler::{CronConfig, Scheduler};
 use crate::cron::store::CronStore;
+use crate::messaging::target::{BroadcastTarget, parse_delivery_target};
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
@@ -41,9 +43,12 @@ pub struct CronArgs {
     /// Required for "create": the prompt/instruction to execute on each run.
     #[serde(default)]
     pub prompt: Option<String>,
-    /// Required for "create": interval in seconds between runs.
+    /// For "create": interval in seconds between runs. Required unless `cron_expr` is set.
     #[serde(default)]
     pub interval_secs: Option<u64>,
+    /// For "create": five-field cron expression (e.g. "0 8 * * 1-5"). Overrides `interval_secs`.
+    #[serde(default)]
+    pub cron_expr: Option<String>,
     /// Required for "create": where to deliver results, in "adapter:target" format (e.g. "discord:123456789").
     #[serde(default)]
     pub delivery_target: Option<String>,
@@ -63,6 +68,10 @@ pub struct CronArgs {
     /// Optional for "create": if true, run only once and disable after first execution attempt.
     #[serde(default)]
     pub run_once: Option<bool>,
+    /// Optional for "create": if true, run as a worker that keeps state between runs
+    /// and delivers only what is new since the last run.
+    #[serde(default)]
+    pub stateful: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -79,8 +88,11 @@ pub struct CronEntry {
     pub id: String,
     pub prompt: String,
     pub interval_secs: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cron_expr: Option<String>,
     pub delivery_target: String,
     pub run_once: bool,
+    pub stateful: bool,
     pub active_hours: Option<String>,
 }
 
@@ -113,7 +125,11 @@ impl Tool for CronTool {
                     },
                     "interval_secs": {
                         "type": "integer",
-                        "description": "For 'create': seconds between runs (e.g. 3600 = hourly, 86400 = daily)."
+                        "description": "For 'create': seconds between runs (e.g. 3600 = hourly, 86400 = daily). Required unless cron_expr is set."
+                    },
+                    "cron_expr": {
+                        "type": "string",
+                        "description": "For 'create': cron expression 'minute hour day-of-month month day-of-week' (e.g. '0 8 * * *' = every day at 08:00). Use instead of interval_secs for wall-clock schedules."
                     },
                     "delivery_target": {
                         "type": "string",
@@ -138,6 +154,10 @@ impl Tool for CronTool {
                     "run_once": {
                         "type": "boolean",
                         "description": "For 'create': if true, run this job once and auto-disable after the first execution attempt."
+                    },
+                    "stateful": {
+                        "type": "boolean",
+                        "description": "For 'create': if true, each run is a worker that remembers what it found last time and only delivers new findings. Use for watch tasks like 'tell me about new issues in this repo'."
                     }
                 },
                 "required": ["action"]
@@ -160,16 +180,29 @@ impl Tool for CronTool {
 }
 
 impl CronTool {
-    async fn create(&self, args: CronArgs) -> Result<CronOutput, CronError> {
+    pub(crate) async fn create(&self, args: CronArgs) -> Result<CronOutput, CronError> {
         let id = args
             .id
             .ok_or_else(|| CronError("'id' is required for create".into()))?;
         let prompt = args
             .prompt
             .ok_or_else(|| CronError("'prompt' is required for create".into()))?;
-        let interval_secs = args
-            .interval_secs
-            .ok_or_else(|| CronError("'interval_secs' is required for create".into()))?;
+        let schedule = args
+            .cron_expr
+            .as_deref()
+            .map(CronExpression::parse)
+            .transpose()
+            .map_err(|error| CronError(format!("invalid 'cron_expr': {error}")))?;
+        let interval_secs = match (args.interval_secs, &schedule) {
+            (Some(interval_secs), _) => interval_secs,
+            // Stored for display only; the expression drives the timer.
+            (None, Some(_)) => 3600,
+            (None, None) => {
+                return Err(CronError(
+                    "'interval_secs' or 'cron_expr' is required for create".into(),
+                ));
+            }
+        };
         let delivery_target = args
             .delivery_target
             .ok_or_else(|| CronError("'delivery_target' is required for create".into()))?;
@@ -188,7 +221,7 @@ impl CronTool {
         }
 
         // Prevent excessively short intervals that could cause resource exhaustion
-        if interval_secs < MIN_CRON_INTERVAL_SECS {
+        if schedule.is_none() && interval_secs < MIN_CRON_INTERVAL_SECS {
             return Err(CronError(format!(
                 "'interval_secs' must be at least {MIN_CRON_INTERVAL_SECS} (got {interval_secs})"
             )));
@@ -220,16 +253,19 @@ impl CronTool {
             _ => None,
         };
         let run_once = args.run_once.unwrap_or(false);
+        let stateful = args.stateful.unwrap_or(false);
 
         let config = CronConfig {
             id: id.clone(),
             prompt: prompt.clone(),
             interval_secs,
+            cron_expr: schedule.as_ref().map(|expression| expression.to_string()),
             delivery_target: delivery_target.clone(),
             active_hours,
             enabled: true,
             run_once,
             timeout_secs: args.timeout_secs,
+            stateful,
         };
 
         // Persist to database
@@ -244,13 +280,20 @@ impl CronTool {
             .await
             .map_err(|error| CronError(format!("failed to register: {error}")))?;
 
-        let interval_desc = format_interval(interval_secs);
+        let interval_desc = match &schedule {
+            Some(expression) => format!("on schedule '{expression}'"),
+            None => format_interval(interval_secs),
+        };
         let timezone = self.scheduler.cron_timezone_label();
         let mut message = if run_once {
             format!("Cron job '{id}' created. First run {interval_desc}; it then disables itself.")
         } else {
             format!("Cron job '{id}' created. Runs {interval_desc}.")
         };
+        if stateful {
+            message
+                .push_str(" Each run remembers what it found before and only reports what's new.");
+        }
         if let Some((start, end)) = active_hours {
             if timezone == "system" {
                 message.push_str(&format!(
@@ -287,8 +330,10 @@ impl CronTool {
                 id: config.id,
                 prompt: config.prompt,
                 interval_secs: config.interval_secs,
+                cron_expr: config.cron_expr,
                 delivery_target: config.delivery_target,
                 run_once: config.run_once,
+                stateful: config.stateful,
                 active_hours: config
                     .active_hours
                     .map(|(s, e)| format!("{s:02}:00-{e:02}:00")),
@@ -332,6 +377,38 @@ impl CronTool {
     }
 }
 
+impl CronTool {
+    /// Delete a cron job only if it delivers to `delivery_target`. Returns
+    /// false when no such job exists for that target.
+    pub(crate) async fn delete_for_target(
+        &self,
+        id: &str,
+        delivery_target: &BroadcastTarget,
+    ) -> Result<bool, CronError> {
+        let configs = self
+            .store
+            .load_all_unfiltered()
+            .await
+            .map_err(|error| CronError(format!("failed to load cron jobs: {error}")))?;
+
+        let owned = configs.iter().any(|config| {
+            config.id == id
+                && parse_delivery_target(&config.delivery_target).as_ref() == Some(delivery_target)
+        });
+        if !owned {
+            return Ok(false);
+        }
+
+        self.scheduler.unregister(id).await;
+        self.store
+            .delete(id)
+            .await
+            .map_err(|error| CronError(format!("failed to delete: {error}")))?;
+
+        Ok(true)
+    }
+}
+
 fn format_interval(secs: u64) -> String {
     if secs.is_multiple_of(86400) {
         let days = secs / 86400;
@@ -0,0 +1,64 @@
+//! Delete tool for redacting the bot's last message (channel only).
+
+use crate::{OutboundResponse, ResponseSender};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool for deleting the last message sent in this conversation.
+#[derive(Debug, Clone)]
+pub struct DeleteMessageTool {
+    response_tx: ResponseSender,
+}
+
+impl DeleteMessageTool {
+    pub fn new(response_tx: ResponseSender) -> Self {
+        Self { response_tx }
+    }
+}
+
+/// Error type for delete_message tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Delete failed: {0}")]
+pub struct DeleteMessageError(String);
+
+/// Arguments for delete_message tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DeleteMessageArgs {}
+
+/// Output from delete_message tool.
+#[derive(Debug, Serialize)]
+pub struct DeleteMessageOutput {
+    pub success: bool,
+}
+
+impl Tool for DeleteMessageTool {
+    const NAME: &'static str = "delete_message";
+
+    type Error = DeleteMessageError;
+    type Args = DeleteMessageArgs;
+    type Output = DeleteMessageOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/delete_message").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {}
+            }),
+        }
+    }
+
+    async fn call(&self, _args: Self::Args) -> Result<Self::Output, Self::Error> {
+        tracing::info!("delete_message tool called");
+
+        self.response_tx
+            .send(OutboundResponse::Delete { message_id: None })
+            .await
+            .map_err(|error| DeleteMessageError(format!("failed to send delete: {error}")))?;
+
+        Ok(DeleteMessageOutput { success: true })
+    }
+}
@@ -0,0 +1,82 @@
+//! Edit tool for correcting the bot's last message (channel only).
+
+use crate::{OutboundResponse, ResponseSender};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool for replacing the text of the last message sent in this conversation.
+#[derive(Debug, Clone)]
+pub struct EditMessageTool {
+    response_tx: ResponseSender,
+}
+
+impl EditMessageTool {
+    pub fn new(response_tx: ResponseSender) -> Self {
+        Self { response_tx }
+    }
+}
+
+/// Error type for edit_message tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Edit failed: {0}")]
+pub struct EditMessageError(String);
+
+/// Arguments for edit_message tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EditMessageArgs {
+    /// The full corrected text that replaces the previous message.
+    pub text: String,
+}
+
+/// Output from edit_message tool.
+#[derive(Debug, Serialize)]
+pub struct EditMessageOutput {
+    pub success: bool,
+}
+
+impl Tool for EditMessageTool {
+    const NAME: &'static str = "edit_message";
+
+    type Error = EditMessageError;
+    type Args = EditMessageArgs;
+    type Output = EditMessageOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/edit_message").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "text": {
+                        "type": "string",
+                        "description": "The full corrected message. Replaces the previous text entirely."
+                    }
+                },
+                "required": ["text"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        if args.text.trim().is_empty() {
+            return Err(EditMessageError(
+                "text cannot be empty; use delete_message to remove a message".into(),
+            ));
+        }
+
+        tracing::info!(text_len = args.text.len(), "edit_message tool called");
+
+        self.response_tx
+            .send(OutboundResponse::Edit {
+                message_id: None,
+                text: args.text,
+            })
+            .await
+            .map_err(|error| EditMessageError(format!("failed to send edit: {error}")))?;
+
+        Ok(EditMessageOutput { success: true })
+    }
+}
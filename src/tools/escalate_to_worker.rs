@@ -0,0 +1,155 @@
+//! Escalate-to-worker tool for handing a task with side effects to the
+//! channel (branch only).
+
+use crate::agent::branch::Branch;
+use crate::agent::channel::{ChannelState, spawn_worker_from_state};
+use crate::auth::AuthTier;
+use crate::{BranchId, TurnId, WorkerId};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Tool for asking the parent channel to spawn a worker.
+///
+/// Branches think; they don't run shell commands or touch files. When a
+/// branch decides the task needs real side effects it proposes a worker
+/// task here instead of concluding that it can't help. The worker is
+/// spawned on the channel, reports back to it like any other worker, and
+/// needs the same permission as the channel's `spawn_worker`. A branch can
+/// escalate once.
+#[derive(Clone)]
+pub struct EscalateToWorkerTool {
+    state: ChannelState,
+    branch_id: BranchId,
+    /// The channel turn that spawned the branch, which the worker joins.
+    turn_id: Option<TurnId>,
+    /// Tier of the sender whose message the branch was spawned for.
+    sender_tier: AuthTier,
+    escalated: Arc<AtomicBool>,
+}
+
+impl std::fmt::Debug for EscalateToWorkerTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EscalateToWorkerTool")
+            .field("branch_id", &self.branch_id)
+            .field("sender_tier", &self.sender_tier)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EscalateToWorkerTool {
+    /// Create the tool for `branch`, before it starts running.
+    pub fn new(state: ChannelState, branch: &Branch, sender_tier: AuthTier) -> Self {
+        Self {
+            state,
+            branch_id: branch.id,
+            turn_id: branch.turn_id,
+            sender_tier,
+            escalated: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+/// Error type for escalate-to-worker tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Escalation failed: {0}")]
+pub struct EscalateToWorkerError(String);
+
+/// Arguments for escalate-to-worker tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EscalateToWorkerArgs {
+    /// The task the worker should carry out, with all the context it needs.
+    pub task: String,
+    /// Why the task needs a worker rather than a conclusion.
+    pub reason: String,
+}
+
+/// Output from escalate-to-worker tool.
+#[derive(Debug, Serialize)]
+pub struct EscalateToWorkerOutput {
+    /// The ID of the spawned worker.
+    pub worker_id: WorkerId,
+    /// Status message.
+    pub message: String,
+}
+
+impl Tool for EscalateToWorkerTool {
+    const NAME: &'static str = "escalate_to_worker";
+
+    type Error = EscalateToWorkerError;
+    type Args = EscalateToWorkerArgs;
+    type Output = EscalateToWorkerOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/escalate_to_worker").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "task": {
+                        "type": "string",
+                        "description": "Clear, specific description of what the worker should do. Include all context needed since the worker can't see the conversation."
+                    },
+                    "reason": {
+                        "type": "string",
+                        "description": "Why this needs a worker: the side effect or execution tool the task depends on."
+                    }
+                },
+                "required": ["task", "reason"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let auth = self.state.deps.runtime_config.auth.load();
+        if !auth.permits_tool(self.sender_tier, "spawn_worker") {
+            return Err(EscalateToWorkerError(
+                "the person this branch is working for can't start workers; conclude with what they'd need to do instead".into(),
+            ));
+        }
+        if self.escalated.swap(true, Ordering::SeqCst) {
+            return Err(EscalateToWorkerError(
+                "this branch already escalated a task; put anything else in your conclusion".into(),
+            ));
+        }
+
+        let worker_id = match spawn_worker_from_state(
+            &self.state,
+            &args.task,
+            false,
+            &[],
+            false,
+            None,
+            self.turn_id,
+        )
+        .await
+        {
+            Ok(worker_id) => worker_id,
+            Err(error) => {
+                // Let the branch try again, e.g. once a worker slot frees up.
+                self.escalated.store(false, Ordering::SeqCst);
+                return Err(EscalateToWorkerError(error.to_string()));
+            }
+        };
+
+        tracing::info!(
+            branch_id = %self.branch_id,
+            channel_id = %self.state.channel_id,
+            %worker_id,
+            reason = %args.reason,
+            "branch escalated to worker"
+        );
+
+        Ok(EscalateToWorkerOutput {
+            worker_id,
+            message: format!(
+                "Worker {worker_id} spawned on the channel for: {}. It reports back to the channel when done; say in your conclusion that it was started.",
+                args.task
+            ),
+        })
+    }
+}
@@ -14,13 +14,24 @@ use tokio::process::Command;
 #[derive(Debug, Clone)]
 pub struct ExecTool {
     workspace: PathBuf,
+    worker_workspace: Option<PathBuf>,
     sandbox: Arc<Sandbox>,
 }
 
 impl ExecTool {
     /// Create a new exec tool with sandbox containment.
     pub fn new(workspace: PathBuf, sandbox: Arc<Sandbox>) -> Self {
-        Self { workspace, sandbox }
+        Self {
+            workspace,
+            worker_workspace: None,
+            sandbox,
+        }
+    }
+
+    /// Run programs from the worker's own working directory by default.
+    pub fn with_worker_workspace(mut self, worker_workspace: Option<PathBuf>) -> Self {
+        self.worker_workspace = worker_workspace;
+        self
     }
 }
 
@@ -163,11 +174,11 @@ impl Tool for ExecTool {
         let working_dir = if let Some(ref dir) = args.working_dir {
             let path = std::path::Path::new(dir);
             let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
-            let workspace_canonical = self
-                .workspace
-                .canonicalize()
-                .unwrap_or_else(|_| self.workspace.clone());
-            if !canonical.starts_with(&workspace_canonical) {
+            let within_workspace = std::iter::once(&self.workspace)
+                .chain(&self.worker_workspace)
+                .map(|root| root.canonicalize().unwrap_or_else(|_| root.clone()))
+                .any(|root| canonical.starts_with(root));
+            if !within_workspace {
                 return Err(ExecError {
                     message: format!(
                         "working_dir must be within the workspace ({}).",
@@ -178,7 +189,9 @@ impl Tool for ExecTool {
             }
             canonical
         } else {
-            self.workspace.clone()
+            self.worker_workspace
+                .clone()
+                .unwrap_or_else(|| self.workspace.clone())
         };
 
         // Block env vars that enable library injection or alter runtime
@@ -246,7 +259,7 @@ impl Tool for ExecTool {
 }
 
 /// Format exec output for display.
-fn format_exec_output(exit_code: i32, stdout: &str, stderr: &str) -> String {
+pub(crate) fn format_exec_output(exit_code: i32, stdout: &str, stderr: &str) -> String {
     let mut output = String::new();
 
     output.push_str(&format!("Exit code: {}\n", exit_code));
@@ -10,19 +10,29 @@ use std::path::{Path, PathBuf};
 #[derive(Debug, Clone)]
 pub struct FileTool {
     workspace: PathBuf,
+    worker_workspace: Option<PathBuf>,
 }
 
 impl FileTool {
     /// Create a new file tool restricted to the given workspace directory.
     pub fn new(workspace: PathBuf) -> Self {
-        Self { workspace }
+        Self {
+            workspace,
+            worker_workspace: None,
+        }
+    }
+
+    /// Also allow paths inside the worker's own working directory.
+    pub fn with_worker_workspace(mut self, worker_workspace: Option<PathBuf>) -> Self {
+        self.worker_workspace = worker_workspace;
+        self
     }
 
     /// Resolve and validate a path, ensuring it stays within the workspace boundary.
     ///
     /// Relative paths are resolved against the workspace root. Absolute paths are
-    /// accepted only if they fall within the workspace. Symlink traversal and `..`
-    /// components are handled via canonicalization.
+    /// accepted only if they fall within the workspace or the worker workspace.
+    /// Symlink traversal and `..` components are handled via canonicalization.
     fn resolve_path(&self, raw: &str) -> Result<PathBuf, FileError> {
         let path = Path::new(raw);
         let resolved = if path.is_absolute() {
@@ -35,10 +45,11 @@ impl FileTool {
         // existing ancestor and append the remaining components.
         let canonical = best_effort_canonicalize(&resolved);
 
-        let workspace_canonical = self
-            .workspace
-            .canonicalize()
-            .unwrap_or_else(|_| self.workspace.clone());
+        let workspace_canonical = std::iter::once(&self.workspace)
+            .chain(&self.worker_workspace)
+            .map(|root| root.canonicalize().unwrap_or_else(|_| root.clone()))
+            .find(|root| canonical.starts_with(root))
+            .unwrap_or_else(|| self.workspace.clone());
 
         if !canonical.starts_with(&workspace_canonical) {
             return Err(FileError(format!(
@@ -0,0 +1,122 @@
+//! Fork tool for splitting a side topic into its own conversation (channel only).
+
+use crate::ChannelId;
+use crate::agent::channel::{ChannelState, new_fork_channel_id};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Tool for forking the current conversation into a new channel.
+///
+/// On platforms with threads the fork opens as a thread off the current
+/// message; elsewhere it gets a standalone channel ID reachable via the API.
+/// Either way the new channel starts with this conversation's history.
+#[derive(Debug, Clone)]
+pub struct ForkChannelTool {
+    state: ChannelState,
+}
+
+impl ForkChannelTool {
+    pub fn new(state: ChannelState) -> Self {
+        Self { state }
+    }
+}
+
+/// Error type for fork_channel tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Fork failed: {0}")]
+pub struct ForkChannelError(String);
+
+/// Arguments for fork_channel tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ForkChannelArgs {
+    /// Short name for the side topic, used as the thread or channel name.
+    pub topic: String,
+}
+
+/// Output from fork_channel tool.
+#[derive(Debug, Serialize)]
+pub struct ForkChannelOutput {
+    pub success: bool,
+    pub channel_id: String,
+    /// Whether the fork was opened as a platform thread.
+    pub thread: bool,
+    pub history_messages: usize,
+    pub message: String,
+}
+
+impl Tool for ForkChannelTool {
+    const NAME: &'static str = "fork_channel";
+
+    type Error = ForkChannelError;
+    type Args = ForkChannelArgs;
+    type Output = ForkChannelOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/fork_channel").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "topic": {
+                        "type": "string",
+                        "description": "Short name for the side topic (e.g. \"Deployment questions\"). Becomes the thread name."
+                    }
+                },
+                "required": ["topic"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let topic = args.topic.trim();
+        if topic.is_empty() {
+            return Err(ForkChannelError("topic cannot be empty".into()));
+        }
+
+        let latest_message = self.state.latest_message.read().await.clone();
+        let thread_id = match (&self.state.deps.messaging_manager, &latest_message) {
+            (Some(manager), Some(message)) => match manager.open_thread(message, topic).await {
+                Ok(thread_id) => thread_id,
+                Err(error) => {
+                    tracing::warn!(%error, channel_id = %self.state.channel_id, "failed to open thread for fork");
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        let thread = thread_id.is_some();
+        let fork_id: ChannelId = match thread_id {
+            Some(thread_id) => Arc::from(thread_id.as_str()),
+            None => new_fork_channel_id(),
+        };
+
+        let history_messages = self
+            .state
+            .fork(&fork_id, Some(topic))
+            .await
+            .map_err(|error| ForkChannelError(error.to_string()))?;
+
+        let message = if thread {
+            format!(
+                "Opened a thread for '{topic}' carrying this conversation's history. Point the user to it."
+            )
+        } else {
+            format!(
+                "This platform has no threads here, so '{topic}' was forked into channel '{fork_id}'. It can be continued through the web chat API."
+            )
+        };
+
+        Ok(ForkChannelOutput {
+            success: true,
+            channel_id: fork_id.to_string(),
+            thread,
+            history_messages,
+            message,
+        })
+    }
+}
@@ -0,0 +1,910 @@
+//! GitHub tools: issues, pull requests, and code search through the REST API
+//! (task workers only).
+//!
+//! All tools share one [`GitHubClient`] authenticated with the agent's
+//! `github_token`. They are only registered when a token is configured.
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+const GITHUB_API_VERSION: &str = "2022-11-28";
+
+/// Most items a list or search call returns.
+const MAX_LIMIT: u8 = 100;
+
+/// Authenticated GitHub REST client shared by the GitHub tools.
+#[derive(Clone)]
+pub struct GitHubClient {
+    client: reqwest::Client,
+    token: Arc<str>,
+}
+
+impl std::fmt::Debug for GitHubClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GitHubClient").finish_non_exhaustive()
+    }
+}
+
+impl GitHubClient {
+    pub fn new(token: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .gzip(true)
+            .user_agent(concat!("spacebot/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .expect("hardcoded reqwest client config");
+
+        Self {
+            client,
+            token: Arc::from(token.into()),
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, format!("{GITHUB_API_URL}{path}"))
+            .bearer_auth(&*self.token)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", GITHUB_API_VERSION)
+    }
+
+    /// Send a request and return the successful response.
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, GitHubError> {
+        let response = request
+            .send()
+            .await
+            .map_err(|error| GitHubError::RequestFailed(error.to_string()))?;
+
+        let status = response.status();
+        let rate_limited = status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            || (status == reqwest::StatusCode::FORBIDDEN
+                && response
+                    .headers()
+                    .get("x-ratelimit-remaining")
+                    .is_some_and(|remaining| remaining == "0"));
+        if rate_limited {
+            return Err(GitHubError::RateLimited);
+        }
+        if !status.is_success() {
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "failed to read response body".into());
+            return Err(GitHubError::RequestFailed(format!("HTTP {status}: {body}")));
+        }
+        Ok(response)
+    }
+
+    async fn json<T: serde::de::DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T, GitHubError> {
+        self.send(request)
+            .await?
+            .json()
+            .await
+            .map_err(|error| GitHubError::InvalidResponse(error.to_string()))
+    }
+}
+
+/// Error type for the GitHub tools.
+#[derive(Debug, thiserror::Error)]
+pub enum GitHubError {
+    #[error("GitHub request failed: {0}")]
+    RequestFailed(String),
+
+    #[error("Failed to parse GitHub response: {0}")]
+    InvalidResponse(String),
+
+    #[error("Rate limited by the GitHub API")]
+    RateLimited,
+
+    #[error("{0}")]
+    InvalidArgument(String),
+}
+
+/// Check that `repo` is an `owner/name` pair.
+fn validate_repo(repo: &str) -> Result<&str, GitHubError> {
+    let repo = repo.trim().trim_matches('/');
+    let valid_part = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    match repo.split_once('/') {
+        Some((owner, name)) if valid_part(owner) && valid_part(name) => Ok(repo),
+        _ => Err(GitHubError::InvalidArgument(format!(
+            "'repo' must be 'owner/name', got '{repo}'"
+        ))),
+    }
+}
+
+/// Check a list `state` filter.
+fn validate_state(state: &str) -> Result<&str, GitHubError> {
+    match state {
+        "open" | "closed" | "all" => Ok(state),
+        other => Err(GitHubError::InvalidArgument(format!(
+            "'state' must be 'open', 'closed', or 'all', got '{other}'"
+        ))),
+    }
+}
+
+/// Whole days since an RFC 3339 timestamp, if it parses.
+fn days_since(timestamp: &str) -> Option<i64> {
+    let time = chrono::DateTime::parse_from_rfc3339(timestamp).ok()?;
+    Some((chrono::Utc::now() - time.with_timezone(&chrono::Utc)).num_days())
+}
+
+fn default_state() -> String {
+    "open".into()
+}
+
+fn default_limit() -> u8 {
+    30
+}
+
+// -- GitHub API response types (private, only model what we need) --
+
+#[derive(Debug, Deserialize)]
+struct ApiUser {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiLabel {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiIssue {
+    number: u64,
+    title: String,
+    state: String,
+    html_url: String,
+    user: Option<ApiUser>,
+    #[serde(default)]
+    labels: Vec<ApiLabel>,
+    #[serde(default)]
+    comments: u64,
+    created_at: String,
+    updated_at: String,
+    /// Set when the "issue" is a pull request.
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiPull {
+    number: u64,
+    title: String,
+    state: String,
+    html_url: String,
+    user: Option<ApiUser>,
+    #[serde(default)]
+    draft: bool,
+    created_at: String,
+    updated_at: String,
+    #[serde(default)]
+    requested_reviewers: Vec<ApiUser>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCodeSearch {
+    total_count: u64,
+    items: Vec<ApiCodeItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCodeItem {
+    path: String,
+    html_url: String,
+    repository: ApiRepository,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiRepository {
+    full_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCreated {
+    number: Option<u64>,
+    html_url: String,
+}
+
+/// An issue or pull request in a list result.
+#[derive(Debug, Serialize)]
+pub struct GitHubItem {
+    pub number: u64,
+    pub title: String,
+    pub state: String,
+    pub author: Option<String>,
+    pub url: String,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Days since the last update, for spotting stale items.
+    pub days_since_update: Option<i64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    /// Comment count (issues only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<u64>,
+    /// Whether the pull request is a draft (pull requests only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub draft: Option<bool>,
+    /// Requested reviewers (pull requests only).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub requested_reviewers: Vec<String>,
+}
+
+/// Output from the list tools.
+#[derive(Debug, Serialize)]
+pub struct GitHubListOutput {
+    pub repo: String,
+    pub items: Vec<GitHubItem>,
+    pub count: usize,
+}
+
+// -- github_list_issues --
+
+/// Tool for listing a repository's issues.
+#[derive(Debug, Clone)]
+pub struct GitHubListIssuesTool {
+    client: GitHubClient,
+}
+
+impl GitHubListIssuesTool {
+    pub fn new(client: GitHubClient) -> Self {
+        Self { client }
+    }
+}
+
+/// Arguments for the list issues tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GitHubListIssuesArgs {
+    /// Repository as "owner/name".
+    pub repo: String,
+    /// "open" (default), "closed", or "all".
+    #[serde(default = "default_state")]
+    pub state: String,
+    /// Only issues with all of these labels.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Maximum number of issues (1-100, default 30).
+    #[serde(default = "default_limit")]
+    pub limit: u8,
+}
+
+impl Tool for GitHubListIssuesTool {
+    const NAME: &'static str = "github_list_issues";
+
+    type Error = GitHubError;
+    type Args = GitHubListIssuesArgs;
+    type Output = GitHubListOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/github_list_issues").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository as \"owner/name\"."
+                    },
+                    "state": {
+                        "type": "string",
+                        "enum": ["open", "closed", "all"],
+                        "default": "open",
+                        "description": "Which issues to list."
+                    },
+                    "labels": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Only list issues that have all of these labels."
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": 100,
+                        "default": 30,
+                        "description": "Maximum number of issues, most recently updated first."
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let repo = validate_repo(&args.repo)?;
+        let state = validate_state(&args.state)?;
+        let limit = args.limit.clamp(1, MAX_LIMIT);
+
+        let mut request = self
+            .client
+            .request(reqwest::Method::GET, &format!("/repos/{repo}/issues"))
+            .query(&[("state", state), ("sort", "updated")])
+            .query(&[("per_page", limit.to_string())]);
+        if !args.labels.is_empty() {
+            request = request.query(&[("labels", args.labels.join(","))]);
+        }
+
+        let issues: Vec<ApiIssue> = self.client.json(request).await?;
+        let items: Vec<GitHubItem> = issues
+            .into_iter()
+            // The issues endpoint lists pull requests too.
+            .filter(|issue| issue.pull_request.is_none())
+            .map(|issue| GitHubItem {
+                number: issue.number,
+                title: issue.title,
+                state: issue.state,
+                author: issue.user.map(|user| user.login),
+                url: issue.html_url,
+                days_since_update: days_since(&issue.updated_at),
+                created_at: issue.created_at,
+                updated_at: issue.updated_at,
+                labels: issue.labels.into_iter().map(|label| label.name).collect(),
+                comments: Some(issue.comments),
+                draft: None,
+                requested_reviewers: Vec::new(),
+            })
+            .collect();
+
+        Ok(GitHubListOutput {
+            repo: repo.to_string(),
+            count: items.len(),
+            items,
+        })
+    }
+}
+
+// -- github_list_pulls --
+
+/// Tool for listing a repository's pull requests.
+#[derive(Debug, Clone)]
+pub struct GitHubListPullsTool {
+    client: GitHubClient,
+}
+
+impl GitHubListPullsTool {
+    pub fn new(client: GitHubClient) -> Self {
+        Self { client }
+    }
+}
+
+/// Arguments for the list pull requests tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GitHubListPullsArgs {
+    /// Repository as "owner/name".
+    pub repo: String,
+    /// "open" (default), "closed", or "all".
+    #[serde(default = "default_state")]
+    pub state: String,
+    /// Maximum number of pull requests (1-100, default 30).
+    #[serde(default = "default_limit")]
+    pub limit: u8,
+}
+
+impl Tool for GitHubListPullsTool {
+    const NAME: &'static str = "github_list_pulls";
+
+    type Error = GitHubError;
+    type Args = GitHubListPullsArgs;
+    type Output = GitHubListOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/github_list_pulls").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository as \"owner/name\"."
+                    },
+                    "state": {
+                        "type": "string",
+                        "enum": ["open", "closed", "all"],
+                        "default": "open",
+                        "description": "Which pull requests to list."
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": 100,
+                        "default": 30,
+                        "description": "Maximum number of pull requests, most recently updated first."
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let repo = validate_repo(&args.repo)?;
+        let state = validate_state(&args.state)?;
+        let limit = args.limit.clamp(1, MAX_LIMIT);
+
+        let request = self
+            .client
+            .request(reqwest::Method::GET, &format!("/repos/{repo}/pulls"))
+            .query(&[("state", state), ("sort", "updated"), ("direction", "desc")])
+            .query(&[("per_page", limit.to_string())]);
+
+        let pulls: Vec<ApiPull> = self.client.json(request).await?;
+        let items: Vec<GitHubItem> = pulls
+            .into_iter()
+            .map(|pull| GitHubItem {
+                number: pull.number,
+                title: pull.title,
+                state: pull.state,
+                author: pull.user.map(|user| user.login),
+                url: pull.html_url,
+                days_since_update: days_since(&pull.updated_at),
+                created_at: pull.created_at,
+                updated_at: pull.updated_at,
+                labels: Vec::new(),
+                comments: None,
+                draft: Some(pull.draft),
+                requested_reviewers: pull
+                    .requested_reviewers
+                    .into_iter()
+                    .map(|user| user.login)
+                    .collect(),
+            })
+            .collect();
+
+        Ok(GitHubListOutput {
+            repo: repo.to_string(),
+            count: items.len(),
+            items,
+        })
+    }
+}
+
+// -- github_pr_diff --
+
+/// Tool for reading a pull request's diff.
+#[derive(Debug, Clone)]
+pub struct GitHubPullDiffTool {
+    client: GitHubClient,
+}
+
+impl GitHubPullDiffTool {
+    pub fn new(client: GitHubClient) -> Self {
+        Self { client }
+    }
+}
+
+/// Arguments for the pull request diff tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GitHubPullDiffArgs {
+    /// Repository as "owner/name".
+    pub repo: String,
+    /// Pull request number.
+    #[serde(deserialize_with = "crate::tools::deserialize_string_or_u64")]
+    pub number: u64,
+}
+
+/// Output from the pull request diff tool.
+#[derive(Debug, Serialize)]
+pub struct GitHubPullDiffOutput {
+    pub repo: String,
+    pub number: u64,
+    /// The unified diff.
+    pub diff: String,
+}
+
+impl Tool for GitHubPullDiffTool {
+    const NAME: &'static str = "github_pr_diff";
+
+    type Error = GitHubError;
+    type Args = GitHubPullDiffArgs;
+    type Output = GitHubPullDiffOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/github_pr_diff").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository as \"owner/name\"."
+                    },
+                    "number": {
+                        "type": "integer",
+                        "description": "Pull request number."
+                    }
+                },
+                "required": ["repo", "number"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let repo = validate_repo(&args.repo)?;
+        let request = self
+            .client
+            .request(
+                reqwest::Method::GET,
+                &format!("/repos/{repo}/pulls/{}", args.number),
+            )
+            .header("Accept", "application/vnd.github.diff");
+
+        let diff = self
+            .client
+            .send(request)
+            .await?
+            .text()
+            .await
+            .map_err(|error| GitHubError::InvalidResponse(error.to_string()))?;
+
+        Ok(GitHubPullDiffOutput {
+            repo: repo.to_string(),
+            number: args.number,
+            diff,
+        })
+    }
+}
+
+// -- github_create_issue --
+
+/// Tool for opening an issue.
+#[derive(Debug, Clone)]
+pub struct GitHubCreateIssueTool {
+    client: GitHubClient,
+}
+
+impl GitHubCreateIssueTool {
+    pub fn new(client: GitHubClient) -> Self {
+        Self { client }
+    }
+}
+
+/// Arguments for the create issue tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GitHubCreateIssueArgs {
+    /// Repository as "owner/name".
+    pub repo: String,
+    /// Issue title.
+    pub title: String,
+    /// Issue body, in Markdown.
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Labels to apply. Labels that don't exist are created.
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+/// Output from the create issue and comment tools.
+#[derive(Debug, Serialize)]
+pub struct GitHubCreatedOutput {
+    pub repo: String,
+    /// The issue or pull request number.
+    pub number: u64,
+    /// Link to the new issue or comment.
+    pub url: String,
+}
+
+impl Tool for GitHubCreateIssueTool {
+    const NAME: &'static str = "github_create_issue";
+
+    type Error = GitHubError;
+    type Args = GitHubCreateIssueArgs;
+    type Output = GitHubCreatedOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/github_create_issue").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository as \"owner/name\"."
+                    },
+                    "title": {
+                        "type": "string",
+                        "description": "Issue title."
+                    },
+                    "body": {
+                        "type": "string",
+                        "description": "Issue body, in Markdown."
+                    },
+                    "labels": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Labels to apply."
+                    }
+                },
+                "required": ["repo", "title"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let repo = validate_repo(&args.repo)?;
+        if args.title.trim().is_empty() {
+            return Err(GitHubError::InvalidArgument(
+                "'title' can't be empty".into(),
+            ));
+        }
+
+        let request = self
+            .client
+            .request(reqwest::Method::POST, &format!("/repos/{repo}/issues"))
+            .json(&serde_json::json!({
+                "title": args.title,
+                "body": args.body.unwrap_or_default(),
+                "labels": args.labels,
+            }));
+        let created: ApiCreated = self.client.json(request).await?;
+
+        tracing::info!(%repo, number = ?created.number, "github issue created");
+
+        Ok(GitHubCreatedOutput {
+            repo: repo.to_string(),
+            number: created.number.unwrap_or_default(),
+            url: created.html_url,
+        })
+    }
+}
+
+// -- github_comment --
+
+/// Tool for commenting on an issue or pull request.
+#[derive(Debug, Clone)]
+pub struct GitHubCommentTool {
+    client: GitHubClient,
+}
+
+impl GitHubCommentTool {
+    pub fn new(client: GitHubClient) -> Self {
+        Self { client }
+    }
+}
+
+/// Arguments for the comment tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GitHubCommentArgs {
+    /// Repository as "owner/name".
+    pub repo: String,
+    /// Issue or pull request number.
+    #[serde(deserialize_with = "crate::tools::deserialize_string_or_u64")]
+    pub number: u64,
+    /// Comment body, in Markdown.
+    pub body: String,
+}
+
+impl Tool for GitHubCommentTool {
+    const NAME: &'static str = "github_comment";
+
+    type Error = GitHubError;
+    type Args = GitHubCommentArgs;
+    type Output = GitHubCreatedOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/github_comment").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository as \"owner/name\"."
+                    },
+                    "number": {
+                        "type": "integer",
+                        "description": "Issue or pull request number."
+                    },
+                    "body": {
+                        "type": "string",
+                        "description": "Comment body, in Markdown."
+                    }
+                },
+                "required": ["repo", "number", "body"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let repo = validate_repo(&args.repo)?;
+        if args.body.trim().is_empty() {
+            return Err(GitHubError::InvalidArgument("'body' can't be empty".into()));
+        }
+
+        // Pull requests are issues as far as conversation comments go.
+        let request = self
+            .client
+            .request(
+                reqwest::Method::POST,
+                &format!("/repos/{repo}/issues/{}/comments", args.number),
+            )
+            .json(&serde_json::json!({ "body": args.body }));
+        let created: ApiCreated = self.client.json(request).await?;
+
+        tracing::info!(%repo, number = args.number, "github comment posted");
+
+        Ok(GitHubCreatedOutput {
+            repo: repo.to_string(),
+            number: args.number,
+            url: created.html_url,
+        })
+    }
+}
+
+// -- github_search_code --
+
+/// Tool for searching code across GitHub or within a repository.
+#[derive(Debug, Clone)]
+pub struct GitHubSearchCodeTool {
+    client: GitHubClient,
+}
+
+impl GitHubSearchCodeTool {
+    pub fn new(client: GitHubClient) -> Self {
+        Self { client }
+    }
+}
+
+/// Arguments for the code search tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GitHubSearchCodeArgs {
+    /// Search query, in GitHub code search syntax.
+    pub query: String,
+    /// Limit the search to this repository ("owner/name").
+    #[serde(default)]
+    pub repo: Option<String>,
+    /// Maximum number of results (1-100, default 30).
+    #[serde(default = "default_limit")]
+    pub limit: u8,
+}
+
+/// A file matching a code search.
+#[derive(Debug, Serialize)]
+pub struct GitHubCodeMatch {
+    pub repo: String,
+    pub path: String,
+    pub url: String,
+}
+
+/// Output from the code search tool.
+#[derive(Debug, Serialize)]
+pub struct GitHubSearchCodeOutput {
+    pub query: String,
+    /// Total matches on GitHub, which may be more than returned.
+    pub total_count: u64,
+    pub matches: Vec<GitHubCodeMatch>,
+}
+
+impl Tool for GitHubSearchCodeTool {
+    const NAME: &'static str = "github_search_code";
+
+    type Error = GitHubError;
+    type Args = GitHubSearchCodeArgs;
+    type Output = GitHubSearchCodeOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/github_search_code").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Search terms, in GitHub code search syntax (e.g. \"parse_config language:rust\")."
+                    },
+                    "repo": {
+                        "type": "string",
+                        "description": "Only search this repository, as \"owner/name\"."
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": 100,
+                        "default": 30,
+                        "description": "Maximum number of matching files."
+                    }
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let query = match &args.repo {
+            Some(repo) => format!("{} repo:{}", args.query, validate_repo(repo)?),
+            None => args.query.clone(),
+        };
+        let limit = args.limit.clamp(1, MAX_LIMIT);
+
+        let request = self
+            .client
+            .request(reqwest::Method::GET, "/search/code")
+            .query(&[("q", &query)])
+            .query(&[("per_page", limit.to_string())]);
+        let search: ApiCodeSearch = self.client.json(request).await?;
+
+        Ok(GitHubSearchCodeOutput {
+            query,
+            total_count: search.total_count,
+            matches: search
+                .items
+                .into_iter()
+                .map(|item| GitHubCodeMatch {
+                    repo: item.repository.full_name,
+                    path: item.path,
+                    url: item.html_url,
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repos_must_be_owner_and_name() {
+        assert_eq!(
+            validate_repo(" spacedriveapp/spacebot/ ").unwrap(),
+            "spacedriveapp/spacebot"
+        );
+        assert!(validate_repo("spacebot").is_err());
+        assert!(validate_repo("a/b/c").is_err());
+        assert!(validate_repo("owner/").is_err());
+        assert!(validate_repo("owner/name?state=all").is_err());
+    }
+
+    #[test]
+    fn issue_lists_skip_pull_requests() {
+        let issues: Vec<ApiIssue> = serde_json::from_value(serde_json::json!([
+            {
+                "number": 1,
+                "title": "Crash on start",
+                "state": "open",
+                "html_url": "https://github.com/o/r/issues/1",
+                "user": {"login": "alice"},
+                "labels": [{"name": "bug"}],
+                "comments": 2,
+                "created_at": "2026-01-01T00:00:00Z",
+                "updated_at": "2026-01-02T00:00:00Z"
+            },
+            {
+                "number": 2,
+                "title": "Fix crash",
+                "state": "open",
+                "html_url": "https://github.com/o/r/pull/2",
+                "user": {"login": "bob"},
+                "created_at": "2026-01-01T00:00:00Z",
+                "updated_at": "2026-01-02T00:00:00Z",
+                "pull_request": {"url": "https://api.github.com/repos/o/r/pulls/2"}
+            }
+        ]))
+        .unwrap();
+
+        let numbers: Vec<u64> = issues
+            .iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .map(|issue| issue.number)
+            .collect();
+        assert_eq!(numbers, [1]);
+        assert!(days_since(&issues[0].updated_at).is_some_and(|days| days > 0));
+    }
+}
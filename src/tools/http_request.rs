@@ -0,0 +1,299 @@
+//! HTTP request tool for calling APIs on an operator-approved list of hosts
+//! (task workers only).
+//!
+//! Unlike `web_fetch`, which reads public pages, this tool sends GET and POST
+//! requests with headers and a JSON body, and returns the raw response. It
+//! only talks to hosts in the agent's `[http_request] allowed_domains`,
+//! including internal ones, and checks every redirect hop against the same
+//! list. Response bodies are cut off at `max_response_bytes`.
+
+use crate::config::HttpRequestConfig;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Redirects followed before a request gives up.
+const MAX_REDIRECTS: usize = 5;
+
+/// Headers the client sets itself.
+const RESERVED_HEADERS: &[&str] = &["host", "content-length", "transfer-encoding", "connection"];
+
+/// Response headers passed back to the worker.
+const RETURNED_HEADERS: &[&str] = &[
+    "content-type",
+    "location",
+    "retry-after",
+    "etag",
+    "last-modified",
+    "link",
+];
+
+/// Tool for sending HTTP requests to allowlisted hosts.
+#[derive(Debug, Clone)]
+pub struct HttpRequestTool {
+    client: reqwest::Client,
+    allowed_domains: Arc<[String]>,
+    max_response_bytes: usize,
+}
+
+impl HttpRequestTool {
+    pub fn new(config: &HttpRequestConfig) -> Self {
+        let allowed_domains: Arc<[String]> = config.allowed_domains.clone().into();
+        let redirect_domains = allowed_domains.clone();
+        let client = reqwest::Client::builder()
+            .gzip(true)
+            .user_agent(concat!("spacebot/", env!("CARGO_PKG_VERSION")))
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+                let allowed = attempt
+                    .url()
+                    .host_str()
+                    .is_some_and(|host| domain_allowed(&redirect_domains, host));
+                if attempt.previous().len() > MAX_REDIRECTS {
+                    attempt.error(format!("more than {MAX_REDIRECTS} redirects"))
+                } else if !allowed {
+                    attempt.error(format!(
+                        "redirected to {}, which isn't an allowed domain",
+                        attempt.url()
+                    ))
+                } else {
+                    attempt.follow()
+                }
+            }))
+            .build()
+            .expect("hardcoded reqwest client config");
+
+        Self {
+            client,
+            allowed_domains,
+            max_response_bytes: config.max_response_bytes,
+        }
+    }
+}
+
+/// Error type for HTTP request tool.
+#[derive(Debug, thiserror::Error)]
+#[error("HTTP request failed: {0}")]
+pub struct HttpRequestError(String);
+
+/// Arguments for HTTP request tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct HttpRequestArgs {
+    /// "GET" (default) or "POST".
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// Full http(s) URL, including any query string.
+    pub url: String,
+    /// Extra request headers.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// JSON body, sent with `Content-Type: application/json` (POST only).
+    #[serde(default)]
+    pub body: Option<serde_json::Value>,
+}
+
+fn default_method() -> String {
+    "GET".into()
+}
+
+/// Output from HTTP request tool.
+#[derive(Debug, Serialize)]
+pub struct HttpRequestOutput {
+    pub status: u16,
+    /// Whether the status is 2xx.
+    pub success: bool,
+    /// Selected response headers, such as `content-type`.
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+    /// True when the body was cut off at the size limit.
+    pub truncated: bool,
+}
+
+impl Tool for HttpRequestTool {
+    const NAME: &'static str = "http_request";
+
+    type Error = HttpRequestError;
+    type Args = HttpRequestArgs;
+    type Output = HttpRequestOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        let description = format!(
+            "{}\n\nAllowed domains: {}",
+            crate::prompts::text::get("tools/http_request"),
+            self.allowed_domains.join(", ")
+        );
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description,
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "method": {
+                        "type": "string",
+                        "enum": ["GET", "POST"],
+                        "default": "GET"
+                    },
+                    "url": {
+                        "type": "string",
+                        "description": "Full http or https URL on an allowed domain, including any query string."
+                    },
+                    "headers": {
+                        "type": "object",
+                        "additionalProperties": { "type": "string" },
+                        "description": "Extra request headers, e.g. {\"Accept\": \"application/json\"}."
+                    },
+                    "body": {
+                        "description": "JSON body to send with a POST."
+                    }
+                },
+                "required": ["url"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let url = reqwest::Url::parse(&args.url)
+            .map_err(|error| HttpRequestError(format!("invalid URL '{}': {error}", args.url)))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(HttpRequestError(format!(
+                "only http and https URLs are supported, got '{url}'"
+            )));
+        }
+        let host = url.host_str().unwrap_or_default();
+        if !domain_allowed(&self.allowed_domains, host) {
+            return Err(HttpRequestError(format!(
+                "'{host}' isn't an allowed domain; allowed: {}",
+                self.allowed_domains.join(", ")
+            )));
+        }
+
+        let method = match args.method.to_ascii_uppercase().as_str() {
+            "GET" => reqwest::Method::GET,
+            "POST" => reqwest::Method::POST,
+            other => {
+                return Err(HttpRequestError(format!(
+                    "unsupported method '{other}'; use GET or POST"
+                )));
+            }
+        };
+        let mut request = self.client.request(method.clone(), url);
+        for (name, value) in &args.headers {
+            if RESERVED_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+                return Err(HttpRequestError(format!(
+                    "the '{name}' header is set automatically"
+                )));
+            }
+            request = request.header(name, value);
+        }
+        if let Some(body) = &args.body {
+            if method != reqwest::Method::POST {
+                return Err(HttpRequestError("only POST requests take a body".into()));
+            }
+            request = request.json(body);
+        }
+
+        let mut response = request
+            .send()
+            .await
+            .map_err(|error| HttpRequestError(error.to_string()))?;
+        let status = response.status();
+        let headers = response
+            .headers()
+            .iter()
+            .filter(|(name, _)| RETURNED_HEADERS.contains(&name.as_str()))
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+
+        let mut body = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|error| HttpRequestError(error.to_string()))?
+        {
+            let remaining = self.max_response_bytes - body.len();
+            if chunk.len() > remaining {
+                body.extend_from_slice(&chunk[..remaining]);
+                truncated = true;
+                break;
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(HttpRequestOutput {
+            status: status.as_u16(),
+            success: status.is_success(),
+            headers,
+            body: String::from_utf8_lossy(&body).into_owned(),
+            truncated,
+        })
+    }
+}
+
+/// Whether `host` is on the allowlist. `example.com` allows that host only,
+/// `*.example.com` any of its subdomains.
+fn domain_allowed(allowed: &[String], host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allowed.iter().any(|entry| {
+        let entry = entry.trim().to_ascii_lowercase();
+        match entry.strip_prefix("*.") {
+            Some(suffix) => host
+                .strip_suffix(suffix)
+                .is_some_and(|rest| rest.len() > 1 && rest.ends_with('.')),
+            None => host == entry,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn domains_match_exactly_or_by_wildcard() {
+        let allowed = vec!["api.example.com".to_string(), "*.internal.test".to_string()];
+
+        assert!(domain_allowed(&allowed, "api.example.com"));
+        assert!(domain_allowed(&allowed, "API.Example.com."));
+        assert!(domain_allowed(&allowed, "billing.internal.test"));
+        assert!(domain_allowed(&allowed, "a.b.internal.test"));
+
+        assert!(!domain_allowed(&allowed, "example.com"));
+        assert!(!domain_allowed(&allowed, "api.example.com.evil.test"));
+        assert!(!domain_allowed(&allowed, "internal.test"));
+        assert!(!domain_allowed(&allowed, "evilinternal.test"));
+        assert!(!domain_allowed(&[], "api.example.com"));
+    }
+
+    #[tokio::test]
+    async fn requests_outside_the_allowlist_are_refused() {
+        let tool = HttpRequestTool::new(&HttpRequestConfig {
+            allowed_domains: vec!["api.example.com".into()],
+            ..HttpRequestConfig::default()
+        });
+        let request = |url: &str, method: &str| HttpRequestArgs {
+            method: method.into(),
+            url: url.into(),
+            headers: BTreeMap::new(),
+            body: None,
+        };
+
+        for (url, method) in [
+            ("https://other.example.com/v1", "GET"),
+            ("https://api.example.com@evil.test/v1", "GET"),
+            ("ftp://api.example.com/file", "GET"),
+            ("https://api.example.com/v1", "DELETE"),
+        ] {
+            assert!(tool.call(request(url, method)).await.is_err(), "{url}");
+        }
+
+        let mut with_host = request("https://api.example.com/v1", "GET");
+        with_host.headers.insert("Host".into(), "evil.test".into());
+        assert!(tool.call(with_host).await.is_err());
+    }
+}
@@ -0,0 +1,139 @@
+//! Knowledge ingest tool: add a workspace file to the knowledge base (workers,
+//! cortex chat).
+
+use crate::config::RuntimeConfig;
+use crate::knowledge::{IngestedDocument, KnowledgeBase, extract};
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Tool for ingesting documents into the agent's knowledge base.
+#[derive(Debug, Clone)]
+pub struct KnowledgeIngestTool {
+    knowledge: KnowledgeBase,
+    workspace: PathBuf,
+    runtime_config: Arc<RuntimeConfig>,
+}
+
+impl KnowledgeIngestTool {
+    /// Create a new knowledge ingest tool that reads files from `workspace`.
+    pub fn new(
+        knowledge: KnowledgeBase,
+        workspace: PathBuf,
+        runtime_config: Arc<RuntimeConfig>,
+    ) -> Self {
+        Self {
+            knowledge,
+            workspace,
+            runtime_config,
+        }
+    }
+
+    /// Resolve a path against the workspace, refusing anything outside it.
+    fn resolve_path(&self, raw: &str) -> Result<PathBuf, KnowledgeIngestError> {
+        let path = Path::new(raw);
+        let resolved = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.workspace.join(path)
+        };
+
+        let canonical = resolved
+            .canonicalize()
+            .map_err(|error| KnowledgeIngestError(format!("cannot open {raw}: {error}")))?;
+        let workspace = self
+            .workspace
+            .canonicalize()
+            .unwrap_or_else(|_| self.workspace.clone());
+
+        if !canonical.starts_with(&workspace) {
+            return Err(KnowledgeIngestError(format!(
+                "ACCESS DENIED: {raw} is outside the workspace. Only files under {} can be \
+                 ingested.",
+                self.workspace.display()
+            )));
+        }
+        if !canonical.is_file() {
+            return Err(KnowledgeIngestError(format!("{raw} is not a file")));
+        }
+        Ok(canonical)
+    }
+}
+
+/// Error type for knowledge ingest tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Knowledge ingest failed: {0}")]
+pub struct KnowledgeIngestError(String);
+
+impl From<crate::error::Error> for KnowledgeIngestError {
+    fn from(e: crate::error::Error) -> Self {
+        KnowledgeIngestError(format!("{e}"))
+    }
+}
+
+/// Arguments for knowledge ingest tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct KnowledgeIngestArgs {
+    /// Path of the document, relative to the workspace.
+    pub path: String,
+}
+
+/// Output from knowledge ingest tool.
+#[derive(Debug, Serialize)]
+pub struct KnowledgeIngestOutput {
+    #[serde(flatten)]
+    pub ingested: IngestedDocument,
+    pub message: String,
+}
+
+impl Tool for KnowledgeIngestTool {
+    const NAME: &'static str = "knowledge_ingest";
+
+    type Error = KnowledgeIngestError;
+    type Args = KnowledgeIngestArgs;
+    type Output = KnowledgeIngestOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/knowledge_ingest").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": format!(
+                            "Path of the document, relative to the workspace. Supported types: {}",
+                            extract::SUPPORTED_EXTENSIONS.join(", ")
+                        )
+                    }
+                },
+                "required": ["path"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let path = self.resolve_path(&args.path)?;
+        let config = **self.runtime_config.knowledge.load();
+        let ingested = self.knowledge.ingest_file(&path, &config).await?;
+
+        let message = if ingested.already_indexed {
+            format!(
+                "{} was already in the knowledge base (document {}).",
+                ingested.document.title, ingested.document.id
+            )
+        } else {
+            format!(
+                "Added {} to the knowledge base as {} passages (document {}).",
+                ingested.document.title, ingested.document.chunk_count, ingested.document.id
+            )
+        };
+
+        Ok(KnowledgeIngestOutput { ingested, message })
+    }
+}
@@ -0,0 +1,127 @@
+//! Knowledge search tool: semantic search over ingested documents (branches,
+//! workers, cortex chat).
+
+use crate::knowledge::{KnowledgeBase, KnowledgeHit};
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on `limit`, to keep results within a reasonable context budget.
+const MAX_LIMIT: usize = 20;
+
+/// Tool for searching the agent's knowledge base.
+#[derive(Debug, Clone)]
+pub struct KnowledgeSearchTool {
+    knowledge: KnowledgeBase,
+}
+
+impl KnowledgeSearchTool {
+    pub fn new(knowledge: KnowledgeBase) -> Self {
+        Self { knowledge }
+    }
+}
+
+/// Error type for knowledge search tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Knowledge search failed: {0}")]
+pub struct KnowledgeSearchError(String);
+
+impl From<crate::error::Error> for KnowledgeSearchError {
+    fn from(e: crate::error::Error) -> Self {
+        KnowledgeSearchError(format!("{e}"))
+    }
+}
+
+/// Arguments for knowledge search tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct KnowledgeSearchArgs {
+    /// What to look for, phrased as a question or a description of the passage.
+    pub query: String,
+    /// Maximum number of passages to return.
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize {
+    5
+}
+
+/// Output from knowledge search tool.
+#[derive(Debug, Serialize)]
+pub struct KnowledgeSearchOutput {
+    pub results: Vec<KnowledgeHit>,
+    /// Formatted passages with their document titles.
+    pub summary: String,
+}
+
+impl Tool for KnowledgeSearchTool {
+    const NAME: &'static str = "knowledge_search";
+
+    type Error = KnowledgeSearchError;
+    type Args = KnowledgeSearchArgs;
+    type Output = KnowledgeSearchOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/knowledge_search").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "What to look for. Phrase it as a question or describe the passage you need."
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_LIMIT,
+                        "default": 5,
+                        "description": "Maximum number of passages to return"
+                    }
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let query = args.query.trim();
+        if query.is_empty() {
+            return Err(KnowledgeSearchError("query must not be empty".into()));
+        }
+
+        let results = self
+            .knowledge
+            .search(query, args.limit.clamp(1, MAX_LIMIT))
+            .await?;
+
+        let summary = if results.is_empty() {
+            "No matching passages. The knowledge base may be empty; documents are added with knowledge_ingest.".to_string()
+        } else {
+            format_hits(&results)
+        };
+
+        Ok(KnowledgeSearchOutput { results, summary })
+    }
+}
+
+/// Render hits as numbered passages with their source document.
+pub fn format_hits(hits: &[KnowledgeHit]) -> String {
+    hits.iter()
+        .enumerate()
+        .map(|(i, hit)| {
+            format!(
+                "[{}] {} (part {}, score {:.2})\n{}",
+                i + 1,
+                hit.title,
+                hit.chunk_index + 1,
+                hit.score,
+                hit.content
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
@@ -0,0 +1,140 @@
+//! Episodic memory tool for branches: timestamped records of what happened
+//! in the conversation.
+
+use crate::memory::{MemorySearch, MemoryType};
+use crate::tools::memory_save::{
+    AssociationInput, MemorySaveArgs, MemorySaveOutput, MemorySaveTool,
+};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Tool for logging events. Events are stored against the branch's channel,
+/// which is where they are recalled.
+#[derive(Debug, Clone)]
+pub struct MemoryLogEventTool {
+    save: MemorySaveTool,
+    channel_id: Option<crate::ChannelId>,
+    sender_id: Option<String>,
+}
+
+impl MemoryLogEventTool {
+    /// Create a new event log tool. `channel_id` is the conversation events
+    /// are logged in, if the branch has one, and `sender_id` the
+    /// platform-qualified sender of the current turn.
+    pub fn new(
+        memory_search: Arc<MemorySearch>,
+        channel_id: Option<crate::ChannelId>,
+        sender_id: Option<String>,
+    ) -> Self {
+        Self {
+            save: MemorySaveTool::new(memory_search),
+            channel_id,
+            sender_id,
+        }
+    }
+}
+
+/// Error type for the event log tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Event log failed: {0}")]
+pub struct MemoryLogEventError(String);
+
+/// Arguments for the event log tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MemoryLogEventArgs {
+    /// What happened.
+    pub content: String,
+    /// Optional importance score (0.0-1.0). If not provided, uses the event default.
+    pub importance: Option<f32>,
+    /// Optional source information (e.g., "user", "system").
+    pub source: Option<String>,
+    /// Who the event is visible to: conversation, user, or agent.
+    pub scope: Option<String>,
+    /// Optional associations to create with other memories.
+    #[serde(default)]
+    pub associations: Vec<AssociationInput>,
+}
+
+impl Tool for MemoryLogEventTool {
+    const NAME: &'static str = "memory_log_event";
+
+    type Error = MemoryLogEventError;
+    type Args = MemoryLogEventArgs;
+    type Output = MemorySaveOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/memory_log_event").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "content": {
+                        "type": "string",
+                        "description": "What happened, in one or two sentences. The time is recorded automatically."
+                    },
+                    "importance": {
+                        "type": "number",
+                        "minimum": 0.0,
+                        "maximum": 1.0,
+                        "description": "Optional importance score from 0.0 to 1.0. Higher values are recalled more easily."
+                    },
+                    "source": {
+                        "type": "string",
+                        "description": "Optional source of the information (e.g., 'user stated', 'system')"
+                    },
+                    "scope": {
+                        "type": "string",
+                        "enum": ["conversation", "user", "agent"],
+                        "description": "Who may see this event later: only this conversation, only the person involved, or everyone. Defaults to conversation in DMs and agent elsewhere."
+                    },
+                    "associations": {
+                        "type": "array",
+                        "description": "Optional associations to link this event to other memories",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "target_id": {
+                                    "type": "string",
+                                    "description": "The ID of the memory to associate with"
+                                },
+                                "relation_type": {
+                                    "type": "string",
+                                    "enum": ["related_to", "updates", "contradicts", "caused_by", "result_of", "part_of"],
+                                    "description": "The type of relationship"
+                                },
+                                "weight": {
+                                    "type": "number",
+                                    "minimum": 0.0,
+                                    "maximum": 1.0,
+                                    "description": "Strength of the association (0.0-1.0)"
+                                }
+                            },
+                            "required": ["target_id"]
+                        }
+                    }
+                },
+                "required": ["content"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> std::result::Result<Self::Output, Self::Error> {
+        self.save
+            .call(MemorySaveArgs {
+                content: args.content,
+                memory_type: MemoryType::Event.to_string(),
+                importance: args.importance,
+                source: args.source,
+                channel_id: self.channel_id.as_ref().map(|id| id.to_string()),
+                scope: args.scope,
+                owner_id: self.sender_id.clone(),
+                associations: args.associations,
+            })
+            .await
+            .map_err(|error| MemoryLogEventError(error.to_string()))
+    }
+}
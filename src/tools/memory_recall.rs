@@ -1,9 +1,9 @@
 //! Memory recall tool for branches.
 
 use crate::error::Result;
-use crate::memory::MemorySearch;
 use crate::memory::search::{SearchConfig, SearchMode, SearchSort, curate_results};
 use crate::memory::types::Memory;
+use crate::memory::{MemoryAudience, MemorySearch};
 
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
@@ -16,12 +16,22 @@ use std::sync::Arc;
 #[derive(Debug, Clone)]
 pub struct MemoryRecallTool {
     memory_search: Arc<MemorySearch>,
+    audience: Option<MemoryAudience>,
 }
 
 impl MemoryRecallTool {
     /// Create a new memory recall tool.
     pub fn new(memory_search: Arc<MemorySearch>) -> Self {
-        Self { memory_search }
+        Self {
+            memory_search,
+            audience: None,
+        }
+    }
+
+    /// Only recall memories visible to `audience`.
+    pub fn with_audience(mut self, audience: MemoryAudience) -> Self {
+        self.audience = Some(audience);
+        self
     }
 }
 
@@ -217,6 +227,7 @@ impl Tool for MemoryRecallTool {
             sort_by,
             max_results: args.max_results,
             max_results_per_source: args.max_results * 2,
+            audience: self.audience.clone(),
             ..Default::default()
         };
 
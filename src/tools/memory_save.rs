@@ -2,7 +2,7 @@
 
 use crate::error::Result;
 use crate::memory::types::Association;
-use crate::memory::{Memory, MemorySearch, MemoryType};
+use crate::memory::{Memory, MemoryScope, MemorySearch, MemoryType};
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
@@ -17,12 +17,23 @@ const MAX_MEMORY_CONTENT_BYTES: usize = 50_000;
 #[derive(Debug, Clone)]
 pub struct MemorySaveTool {
     memory_search: Arc<MemorySearch>,
+    channel_id: Option<crate::ChannelId>,
 }
 
 impl MemorySaveTool {
     /// Create a new memory save tool.
     pub fn new(memory_search: Arc<MemorySearch>) -> Self {
-        Self { memory_search }
+        Self {
+            memory_search,
+            channel_id: None,
+        }
+    }
+
+    /// Attribute saved memories to `channel_id` unless a call names another
+    /// channel. This also decides their default scope.
+    pub fn with_channel(mut self, channel_id: crate::ChannelId) -> Self {
+        self.channel_id = Some(channel_id);
+        self
     }
 }
 
@@ -51,6 +62,14 @@ pub struct MemorySaveArgs {
     pub source: Option<String>,
     /// Optional channel ID to associate this memory with the conversation it came from.
     pub channel_id: Option<String>,
+    /// Who the memory is visible to (conversation, user, agent). Defaults to
+    /// conversation for DMs and agent everywhere else.
+    #[serde(default)]
+    pub scope: Option<String>,
+    /// Platform-qualified sender that owns a user-scoped memory. Filled in
+    /// by the caller, never by the model.
+    #[serde(skip)]
+    pub owner_id: Option<String>,
     /// Optional associations to create with other memories.
     #[serde(default)]
     pub associations: Vec<AssociationInput>,
@@ -206,9 +225,37 @@ impl Tool for MemorySaveTool {
             memory = memory.with_source(source);
         }
 
-        if let Some(channel_id) = args.channel_id {
-            memory = memory.with_channel_id(Arc::from(channel_id.as_str()));
+        if let Some(channel_id) = args
+            .channel_id
+            .map(|id| Arc::from(id.as_str()) as crate::ChannelId)
+            .or_else(|| self.channel_id.clone())
+        {
+            memory = memory.with_channel_id(channel_id);
+        }
+
+        let scope = match args.scope.as_deref() {
+            Some(scope) => MemoryScope::parse(scope).ok_or_else(|| {
+                MemorySaveError(format!(
+                    "unknown scope \"{scope}\". Valid scopes: conversation, user, agent"
+                ))
+            })?,
+            None => MemoryScope::default_for(memory.channel_id.as_deref()),
+        };
+        match scope {
+            MemoryScope::Conversation if memory.channel_id.is_none() => {
+                return Err(MemorySaveError(
+                    "conversation scope needs a channel to save the memory against".into(),
+                ));
+            }
+            MemoryScope::User if args.owner_id.is_none() => {
+                return Err(MemorySaveError(
+                    "user scope needs a known sender to own the memory".into(),
+                ));
+            }
+            _ => {}
         }
+        let owner_id = args.owner_id.filter(|_| scope == MemoryScope::User);
+        memory = memory.with_scope(scope, owner_id);
 
         // Save to SQLite database
         let store = self.memory_search.store();
@@ -274,19 +321,14 @@ impl Tool for MemorySaveTool {
             .map_err(|e| MemorySaveError(format!("Failed to generate embedding: {e}")))?;
 
         self.memory_search
-            .embedding_table()
-            .store(&memory.id, &args.content, &embedding)
+            .embeddings()
+            .store(&memory, &embedding)
             .await
             .map_err(|e| MemorySaveError(format!("Failed to store embedding: {e}")))?;
 
         // Ensure the FTS index exists so full_text_search queries work.
         // Safe to call repeatedly — no-ops if the index already exists.
-        if let Err(error) = self
-            .memory_search
-            .embedding_table()
-            .ensure_fts_index()
-            .await
-        {
+        if let Err(error) = self.memory_search.embeddings().ensure_fts_index().await {
             tracing::warn!(%error, "failed to ensure FTS index after memory save");
         }
 
@@ -321,6 +363,8 @@ pub async fn save_fact(
         importance: None,
         source: None,
         channel_id: channel_id.map(|id| id.to_string()),
+        scope: None,
+        owner_id: None,
         associations: vec![],
     };
 
@@ -0,0 +1,181 @@
+//! Semantic memory tool for branches: facts, preferences, decisions, and
+//! other things that hold regardless of when they were learned.
+
+use crate::memory::{MemoryKind, MemoryScope, MemorySearch, MemoryType};
+use crate::tools::memory_save::{
+    AssociationInput, MemorySaveArgs, MemorySaveOutput, MemorySaveTool,
+};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Tool for saving semantic memories. Facts are only tied to the branch's
+/// channel when they are scoped to it.
+#[derive(Debug, Clone)]
+pub struct MemorySaveFactTool {
+    save: MemorySaveTool,
+    channel_id: Option<crate::ChannelId>,
+    sender_id: Option<String>,
+}
+
+impl MemorySaveFactTool {
+    /// Create a new fact save tool. `channel_id` and `sender_id` are the
+    /// conversation and platform-qualified sender of the current turn, used
+    /// for conversation- and user-scoped facts.
+    pub fn new(
+        memory_search: Arc<MemorySearch>,
+        channel_id: Option<crate::ChannelId>,
+        sender_id: Option<String>,
+    ) -> Self {
+        Self {
+            save: MemorySaveTool::new(memory_search),
+            channel_id,
+            sender_id,
+        }
+    }
+}
+
+/// Error type for the fact save tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Fact save failed: {0}")]
+pub struct MemorySaveFactError(String);
+
+/// Arguments for the fact save tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MemorySaveFactArgs {
+    /// The fact to save.
+    pub content: String,
+    /// The type of memory (fact, preference, decision, identity, observation, goal, todo).
+    #[serde(default = "default_memory_type")]
+    pub memory_type: String,
+    /// Optional importance score (0.0-1.0). If not provided, uses type default.
+    pub importance: Option<f32>,
+    /// Optional source information (e.g., "user", "inferred").
+    pub source: Option<String>,
+    /// Who the fact is visible to: conversation, user, or agent.
+    pub scope: Option<String>,
+    /// Optional associations to create with other memories.
+    #[serde(default)]
+    pub associations: Vec<AssociationInput>,
+}
+
+fn default_memory_type() -> String {
+    MemoryType::Fact.to_string()
+}
+
+/// Semantic memory types, in definition order.
+fn semantic_types() -> impl Iterator<Item = MemoryType> {
+    MemoryType::ALL
+        .iter()
+        .copied()
+        .filter(|memory_type| memory_type.kind() == MemoryKind::Semantic)
+}
+
+impl Tool for MemorySaveFactTool {
+    const NAME: &'static str = "memory_save_fact";
+
+    type Error = MemorySaveFactError;
+    type Args = MemorySaveFactArgs;
+    type Output = MemorySaveOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/memory_save_fact").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "content": {
+                        "type": "string",
+                        "description": "The fact to save, phrased so it reads correctly out of context. Be concise but complete."
+                    },
+                    "memory_type": {
+                        "type": "string",
+                        "enum": semantic_types().map(|t| t.to_string()).collect::<Vec<_>>(),
+                        "description": "The kind of fact. Defaults to fact."
+                    },
+                    "importance": {
+                        "type": "number",
+                        "minimum": 0.0,
+                        "maximum": 1.0,
+                        "description": "Optional importance score from 0.0 to 1.0. Higher values are recalled more easily."
+                    },
+                    "source": {
+                        "type": "string",
+                        "description": "Optional source of the information (e.g., 'user stated', 'inferred')"
+                    },
+                    "scope": {
+                        "type": "string",
+                        "enum": ["conversation", "user", "agent"],
+                        "description": "Who may see this fact later: only this conversation, only the person who said it, or everyone. Defaults to conversation in DMs and agent elsewhere."
+                    },
+                    "associations": {
+                        "type": "array",
+                        "description": "Optional associations to link this memory to other memories",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "target_id": {
+                                    "type": "string",
+                                    "description": "The ID of the memory to associate with"
+                                },
+                                "relation_type": {
+                                    "type": "string",
+                                    "enum": ["related_to", "updates", "contradicts", "caused_by", "result_of", "part_of"],
+                                    "description": "The type of relationship"
+                                },
+                                "weight": {
+                                    "type": "number",
+                                    "minimum": 0.0,
+                                    "maximum": 1.0,
+                                    "description": "Strength of the association (0.0-1.0)"
+                                }
+                            },
+                            "required": ["target_id"]
+                        }
+                    }
+                },
+                "required": ["content"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> std::result::Result<Self::Output, Self::Error> {
+        if !semantic_types().any(|memory_type| memory_type.to_string() == args.memory_type) {
+            return Err(MemorySaveFactError(format!(
+                "'{}' is not a fact type; use memory_log_event for things that happened",
+                args.memory_type
+            )));
+        }
+
+        let scope = match args.scope.as_deref() {
+            Some(scope) => MemoryScope::parse(scope).ok_or_else(|| {
+                MemorySaveFactError(format!(
+                    "unknown scope \"{scope}\". Valid scopes: conversation, user, agent"
+                ))
+            })?,
+            None => MemoryScope::default_for(self.channel_id.as_deref()),
+        };
+        let channel_id = self
+            .channel_id
+            .as_ref()
+            .filter(|_| scope == MemoryScope::Conversation)
+            .map(|id| id.to_string());
+
+        self.save
+            .call(MemorySaveArgs {
+                content: args.content,
+                memory_type: args.memory_type,
+                importance: args.importance,
+                source: args.source,
+                channel_id,
+                scope: Some(scope.to_string()),
+                owner_id: self.sender_id.clone(),
+                associations: args.associations,
+            })
+            .await
+            .map_err(|error| MemorySaveFactError(error.to_string()))
+    }
+}
@@ -0,0 +1,144 @@
+//! Profile update tool for recording long-term facts about a user (channel only).
+
+use crate::agent::channel::ChannelState;
+use crate::profiles::ProfileUpdate;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool for updating the profile of the person who sent the latest message.
+///
+/// The profile follows the sender across every channel the agent is in and is
+/// shown to the channel as "Known About Sender" whenever they speak.
+#[derive(Debug, Clone)]
+pub struct ProfileUpdateTool {
+    state: ChannelState,
+}
+
+impl ProfileUpdateTool {
+    pub fn new(state: ChannelState) -> Self {
+        Self { state }
+    }
+}
+
+/// Error type for profile_update tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Profile update failed: {0}")]
+pub struct ProfileUpdateError(String);
+
+/// Arguments for profile_update tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ProfileUpdateArgs {
+    /// What the user wants to be called. Empty string clears it.
+    #[serde(default)]
+    pub preferred_name: Option<String>,
+    /// IANA timezone name (e.g. "Europe/Budapest"). Empty string clears it.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Standing preferences to remember (e.g. "prefers short answers").
+    #[serde(default)]
+    pub add_preferences: Vec<String>,
+    /// Previously saved preferences that no longer hold, written exactly as shown.
+    #[serde(default)]
+    pub remove_preferences: Vec<String>,
+}
+
+/// Output from profile_update tool.
+#[derive(Debug, Serialize)]
+pub struct ProfileUpdateOutput {
+    pub success: bool,
+    pub name: String,
+    pub preferred_name: Option<String>,
+    pub timezone: Option<String>,
+    pub preferences: Vec<String>,
+}
+
+impl Tool for ProfileUpdateTool {
+    const NAME: &'static str = "profile_update";
+
+    type Error = ProfileUpdateError;
+    type Args = ProfileUpdateArgs;
+    type Output = ProfileUpdateOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/profile_update").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "preferred_name": {
+                        "type": "string",
+                        "description": "What the user wants to be called. Pass an empty string to forget it."
+                    },
+                    "timezone": {
+                        "type": "string",
+                        "description": "IANA timezone name, e.g. \"America/New_York\" or \"Europe/Budapest\". Pass an empty string to forget it."
+                    },
+                    "add_preferences": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Standing preferences to remember, each a short sentence (e.g. \"Prefers answers without emoji\")."
+                    },
+                    "remove_preferences": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Saved preferences that no longer apply, written as they appear under Known About Sender."
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let Some(message) = self.state.latest_message.read().await.clone() else {
+            return Err(ProfileUpdateError(
+                "there is no user message in this conversation yet".into(),
+            ));
+        };
+
+        if let Some(timezone) = args.timezone.as_deref().map(str::trim)
+            && !timezone.is_empty()
+            && timezone.parse::<chrono_tz::Tz>().is_err()
+        {
+            return Err(ProfileUpdateError(format!(
+                "'{timezone}' is not an IANA timezone name"
+            )));
+        }
+
+        let display_name = message
+            .metadata
+            .get("sender_display_name")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        let update = ProfileUpdate {
+            display_name,
+            preferred_name: args.preferred_name,
+            timezone: args.timezone,
+            add_preferences: args.add_preferences,
+            remove_preferences: args.remove_preferences,
+        };
+
+        let profile = self
+            .state
+            .profile_store
+            .update(&message.sender_id, &update)
+            .await
+            .map_err(|error| ProfileUpdateError(error.to_string()))?;
+
+        tracing::info!(
+            channel_id = %self.state.channel_id,
+            sender_id = %message.sender_id,
+            "user profile updated"
+        );
+
+        Ok(ProfileUpdateOutput {
+            success: true,
+            name: profile.name().to_string(),
+            preferred_name: profile.preferred_name,
+            timezone: profile.timezone,
+            preferences: profile.preferences,
+        })
+    }
+}
@@ -0,0 +1,146 @@
+//! Read artifact tool: page through a tool result that was too large to show
+//! inline (branches, workers, cortex chat).
+
+use crate::conversation::ToolArtifactStore;
+use crate::tools::{MAX_TOOL_OUTPUT_BYTES, truncate_output};
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Lines returned when the LLM doesn't ask for a specific amount.
+const DEFAULT_LINE_LIMIT: usize = 200;
+
+/// Tool for reading a stored tool artifact.
+#[derive(Debug, Clone)]
+pub struct ReadArtifactTool {
+    store: ToolArtifactStore,
+}
+
+impl ReadArtifactTool {
+    pub fn new(store: ToolArtifactStore) -> Self {
+        Self { store }
+    }
+}
+
+/// Error type for read artifact tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Read artifact failed: {0}")]
+pub struct ReadArtifactError(String);
+
+/// Arguments for read artifact tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReadArtifactArgs {
+    /// The artifact id from the truncated tool result.
+    pub artifact_id: String,
+    /// Line to start reading from (0-based).
+    #[serde(default)]
+    pub offset: usize,
+    /// Max lines to return.
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize {
+    DEFAULT_LINE_LIMIT
+}
+
+/// Output from read artifact tool.
+#[derive(Debug, Serialize)]
+pub struct ReadArtifactOutput {
+    pub artifact_id: String,
+    pub tool_name: String,
+    pub content: String,
+    /// First line returned (0-based).
+    pub offset: usize,
+    pub lines_returned: usize,
+    pub total_lines: usize,
+    /// Whether there are lines after the ones returned.
+    pub has_more: bool,
+}
+
+impl Tool for ReadArtifactTool {
+    const NAME: &'static str = "read_artifact";
+
+    type Error = ReadArtifactError;
+    type Args = ReadArtifactArgs;
+    type Output = ReadArtifactOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/read_artifact").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "artifact_id": {
+                        "type": "string",
+                        "description": "The artifact id named in the truncated tool result."
+                    },
+                    "offset": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "default": 0,
+                        "description": "Line to start reading from (0-based)."
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "default": DEFAULT_LINE_LIMIT,
+                        "description": "Maximum number of lines to return."
+                    }
+                },
+                "required": ["artifact_id"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let artifact_id = args.artifact_id.trim();
+        let artifact = self
+            .store
+            .get(artifact_id)
+            .await
+            .map_err(|error| ReadArtifactError(error.to_string()))?
+            .ok_or_else(|| {
+                ReadArtifactError(format!(
+                    "no artifact with id '{artifact_id}'. It may have expired"
+                ))
+            })?;
+
+        let (content, lines_returned, total_lines) =
+            line_window(&artifact.content, args.offset, args.limit.max(1));
+
+        Ok(ReadArtifactOutput {
+            artifact_id: artifact.id,
+            tool_name: artifact.tool_name,
+            content: truncate_output(&content, MAX_TOOL_OUTPUT_BYTES),
+            offset: args.offset,
+            lines_returned,
+            total_lines,
+            has_more: args.offset + lines_returned < total_lines,
+        })
+    }
+}
+
+/// Lines `offset..offset + limit` of `content`, with how many were returned
+/// and the total line count.
+fn line_window(content: &str, offset: usize, limit: usize) -> (String, usize, usize) {
+    let total_lines = content.lines().count();
+    let window: Vec<&str> = content.lines().skip(offset).take(limit).collect();
+    (window.join("\n"), window.len(), total_lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_window_pages_through_content() {
+        let content = "a\nb\nc\nd\ne";
+        assert_eq!(line_window(content, 0, 2), ("a\nb".to_string(), 2, 5));
+        assert_eq!(line_window(content, 3, 10), ("d\ne".to_string(), 2, 5));
+        assert_eq!(line_window(content, 9, 10), (String::new(), 0, 5));
+    }
+}
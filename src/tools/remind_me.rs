@@ -0,0 +1,329 @@
+//! Remind-me tool for setting, listing, and cancelling reminders (channel only).
+
+use crate::agent::channel::ChannelState;
+use crate::reminders::{REMINDER_METADATA_KEY, Reminder, ReminderStore};
+
+use chrono::Utc;
+use chrono_tz::Tz;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Most reminders one conversation can have pending.
+const MAX_REMINDERS_PER_CONVERSATION: i64 = 50;
+
+/// Tool for reminding the people in this conversation of something later.
+///
+/// Reminders are delivered back into the conversation they were set in, as a
+/// message the channel answers in its own words. See [`crate::reminders`].
+#[derive(Debug, Clone)]
+pub struct RemindMeTool {
+    state: ChannelState,
+}
+
+impl RemindMeTool {
+    pub fn new(state: ChannelState) -> Self {
+        Self { state }
+    }
+}
+
+/// Error type for remind_me tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Reminder failed: {0}")]
+pub struct RemindMeError(String);
+
+/// Arguments for remind_me tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RemindMeArgs {
+    /// "create" (default), "list", or "cancel".
+    #[serde(default = "default_action")]
+    pub action: String,
+    /// What to remind them of (create).
+    pub message: Option<String>,
+    /// When to remind them, as a local time or RFC 3339 (create).
+    pub at: Option<String>,
+    /// Minutes from now, instead of `at` (create).
+    pub in_minutes: Option<u32>,
+    /// Cron expression for a recurring reminder (create).
+    pub cron_expr: Option<String>,
+    /// IANA timezone `at` and `cron_expr` are read in.
+    pub timezone: Option<String>,
+    /// Reminder ID (cancel).
+    pub id: Option<String>,
+}
+
+fn default_action() -> String {
+    "create".into()
+}
+
+/// A pending reminder, as the tool shows it.
+#[derive(Debug, Serialize)]
+pub struct ReminderSummary {
+    pub id: String,
+    pub message: String,
+    /// Next delivery, in the reminder's timezone.
+    pub next: String,
+    /// Cron expression, for recurring reminders.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeats: Option<String>,
+}
+
+impl From<&Reminder> for ReminderSummary {
+    fn from(reminder: &Reminder) -> Self {
+        Self {
+            id: reminder.id.clone(),
+            message: reminder.message.clone(),
+            next: reminder.format_time(reminder.fire_at),
+            repeats: reminder.cron_expr.clone(),
+        }
+    }
+}
+
+/// Output from remind_me tool.
+#[derive(Debug, Serialize)]
+pub struct RemindMeOutput {
+    pub success: bool,
+    pub message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reminders: Vec<ReminderSummary>,
+}
+
+impl Tool for RemindMeTool {
+    const NAME: &'static str = "remind_me";
+
+    type Error = RemindMeError;
+    type Args = RemindMeArgs;
+    type Output = RemindMeOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/remind_me").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["create", "list", "cancel"],
+                        "default": "create",
+                        "description": "Set a reminder, list this conversation's reminders, or cancel one."
+                    },
+                    "message": {
+                        "type": "string",
+                        "description": "What to remind them of, e.g. \"call the dentist\". Required for create."
+                    },
+                    "at": {
+                        "type": "string",
+                        "description": "When to remind them, e.g. \"2026-10-19 14:00\" in their timezone, or RFC 3339."
+                    },
+                    "in_minutes": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Minutes from now, for relative requests like \"in 20 minutes\". Use instead of `at`."
+                    },
+                    "cron_expr": {
+                        "type": "string",
+                        "description": "Five-field cron expression for a recurring reminder, e.g. \"0 9 * * 1\" for Mondays at 09:00. Use instead of `at` and `in_minutes`."
+                    },
+                    "timezone": {
+                        "type": "string",
+                        "description": "IANA timezone to read `at` and `cron_expr` in. Defaults to the sender's profile timezone."
+                    },
+                    "id": {
+                        "type": "string",
+                        "description": "The reminder to cancel, as shown by list or create. Required for cancel."
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let Some(message) = self.state.latest_message.read().await.clone() else {
+            return Err(RemindMeError(
+                "there is no user message in this conversation yet".into(),
+            ));
+        };
+        let store = self.store();
+
+        match args.action.as_str() {
+            "create" => self.create(args, message, &store).await,
+            "list" => {
+                let reminders = store
+                    .list_for_conversation(&message.conversation_id)
+                    .await
+                    .map_err(|error| RemindMeError(error.to_string()))?;
+                Ok(RemindMeOutput {
+                    success: true,
+                    message: format!("{} pending reminder(s)", reminders.len()),
+                    reminders: reminders.iter().map(ReminderSummary::from).collect(),
+                })
+            }
+            "cancel" => {
+                let id = args.id.as_deref().map(str::trim).unwrap_or_default();
+                if id.is_empty() {
+                    return Err(RemindMeError("'id' is required to cancel".into()));
+                }
+                let deleted = store
+                    .delete_in_conversation(&message.conversation_id, id)
+                    .await
+                    .map_err(|error| RemindMeError(error.to_string()))?;
+                if !deleted {
+                    return Err(RemindMeError(format!(
+                        "no reminder '{id}' in this conversation"
+                    )));
+                }
+                tracing::info!(channel_id = %self.state.channel_id, reminder_id = id, "reminder cancelled");
+                Ok(RemindMeOutput {
+                    success: true,
+                    message: format!("Reminder '{id}' cancelled"),
+                    reminders: Vec::new(),
+                })
+            }
+            other => Err(RemindMeError(format!(
+                "unknown action '{other}'; use create, list, or cancel"
+            ))),
+        }
+    }
+}
+
+impl RemindMeTool {
+    fn store(&self) -> ReminderStore {
+        ReminderStore::new(self.state.deps.sqlite_pool.clone())
+    }
+
+    async fn create(
+        &self,
+        args: RemindMeArgs,
+        message: crate::InboundMessage,
+        store: &ReminderStore,
+    ) -> Result<RemindMeOutput, RemindMeError> {
+        // Reminders are delivered through the adapter the request came in on,
+        // which synthetic and agent-to-agent messages don't have.
+        if matches!(message.source.as_str(), "system" | "internal" | "cron") {
+            return Err(RemindMeError(
+                "reminders can only be set from a user conversation".into(),
+            ));
+        }
+        let text = args.message.as_deref().map(str::trim).unwrap_or_default();
+        if text.is_empty() {
+            return Err(RemindMeError("'message' is required to create".into()));
+        }
+
+        let tz = self.timezone(args.timezone.as_deref(), &message).await?;
+        let now = Utc::now();
+        let mut reminder = Reminder {
+            id: uuid::Uuid::new_v4().to_string()[..8].to_string(),
+            conversation_id: message.conversation_id.clone(),
+            source: message.source.clone(),
+            sender_id: message.sender_id.clone(),
+            sender_name: message
+                .metadata
+                .get("sender_display_name")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            message: text.to_string(),
+            fire_at: now,
+            cron_expr: None,
+            timezone: Some(tz.to_string()),
+            metadata: message.metadata.clone(),
+            created_at: Some(now),
+        };
+        reminder.metadata.remove(REMINDER_METADATA_KEY);
+
+        reminder.fire_at = match (
+            args.at.as_deref(),
+            args.in_minutes,
+            args.cron_expr.as_deref(),
+        ) {
+            (Some(at), None, None) => {
+                crate::calendar::parse_local_time(at, tz).map_err(RemindMeError)?
+            }
+            (None, Some(minutes), None) => now + chrono::Duration::minutes(minutes.max(1) as i64),
+            (None, None, Some(cron_expr)) => {
+                crate::cron::CronExpression::parse(cron_expr)
+                    .map_err(|error| RemindMeError(format!("invalid cron_expr: {error}")))?;
+                reminder.cron_expr = Some(cron_expr.trim().to_string());
+                reminder
+                    .next_after(now)
+                    .ok_or_else(|| RemindMeError(format!("'{cron_expr}' never fires")))?
+            }
+            (None, None, None) => {
+                return Err(RemindMeError(
+                    "give one of 'at', 'in_minutes', or 'cron_expr'".into(),
+                ));
+            }
+            _ => {
+                return Err(RemindMeError(
+                    "give only one of 'at', 'in_minutes', or 'cron_expr'".into(),
+                ));
+            }
+        };
+        if reminder.fire_at <= now {
+            return Err(RemindMeError(format!(
+                "{} is in the past",
+                reminder.format_time(reminder.fire_at)
+            )));
+        }
+
+        let pending = store
+            .count_for_conversation(&reminder.conversation_id)
+            .await
+            .map_err(|error| RemindMeError(error.to_string()))?;
+        if pending >= MAX_REMINDERS_PER_CONVERSATION {
+            return Err(RemindMeError(format!(
+                "this conversation already has {pending} reminders; cancel some first"
+            )));
+        }
+        store
+            .insert(&reminder)
+            .await
+            .map_err(|error| RemindMeError(error.to_string()))?;
+
+        tracing::info!(
+            channel_id = %self.state.channel_id,
+            reminder_id = %reminder.id,
+            fire_at = %reminder.fire_at,
+            recurring = reminder.cron_expr.is_some(),
+            "reminder set"
+        );
+
+        Ok(RemindMeOutput {
+            success: true,
+            message: format!(
+                "Reminder set for {}",
+                reminder.format_time(reminder.fire_at)
+            ),
+            reminders: vec![ReminderSummary::from(&reminder)],
+        })
+    }
+
+    /// The timezone to read times in: the one given, else the sender's
+    /// profile timezone, else the agent's cron timezone, else UTC.
+    async fn timezone(
+        &self,
+        requested: Option<&str>,
+        message: &crate::InboundMessage,
+    ) -> Result<Tz, RemindMeError> {
+        if let Some(timezone) = requested.map(str::trim).filter(|tz| !tz.is_empty()) {
+            return timezone
+                .parse()
+                .map_err(|_| RemindMeError(format!("'{timezone}' is not an IANA timezone name")));
+        }
+
+        let profile_timezone = match self.state.profile_store.get(&message.sender_id).await {
+            Ok(profile) => profile.and_then(|profile| profile.timezone),
+            Err(error) => {
+                tracing::warn!(%error, "failed to load sender profile for reminder timezone");
+                None
+            }
+        };
+        let agent_timezone = self.state.deps.runtime_config.cron_timezone.load();
+        Ok([profile_timezone.as_deref(), agent_timezone.as_deref()]
+            .into_iter()
+            .flatten()
+            .find_map(|timezone| timezone.parse::<Tz>().ok())
+            .unwrap_or(chrono_tz::UTC))
+    }
+}
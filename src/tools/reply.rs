@@ -1,8 +1,8 @@
 //! Reply tool for sending messages to users (channel only).
 
-use crate::conversation::ConversationLogger;
+use crate::conversation::{ConversationLogger, DraftStore};
 
-use crate::{ChannelId, OutboundResponse};
+use crate::{ChannelId, OutboundResponse, ResponseSender};
 use regex::Regex;
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
@@ -12,7 +12,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::LazyLock;
 use std::sync::atomic::{AtomicBool, Ordering};
-use tokio::sync::mpsc;
+use tokio::sync::RwLock;
 
 static BROKEN_DISCORD_MENTION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"<{2,}@(!?)>\s*(\d{15,22})>").expect("hardcoded broken mention regex")
@@ -32,6 +32,31 @@ pub fn new_replied_flag() -> RepliedFlag {
     Arc::new(AtomicBool::new(false))
 }
 
+/// A reply sent to the user during the current turn.
+#[derive(Debug, Clone)]
+pub struct SentReply {
+    /// ID of the reply in the conversation log.
+    pub message_id: String,
+    /// The text the user received, after mention conversion.
+    pub content: String,
+}
+
+impl SentReply {
+    /// The reply as an assistant message for channel history.
+    pub fn into_message(self) -> rig::message::Message {
+        rig::message::Message::Assistant {
+            id: Some(self.message_id),
+            content: rig::OneOrMany::one(rig::message::AssistantContent::text(self.content)),
+        }
+    }
+}
+
+/// Replies sent during a turn, shared between the reply tools and the channel.
+///
+/// A reply ends the turn and the turn's history is rolled back, tool call
+/// included, so the channel writes these to history itself once it's over.
+pub type SentReplies = Arc<RwLock<Vec<SentReply>>>;
+
 /// Tool for replying to users.
 ///
 /// Holds a sender channel rather than a specific InboundMessage. The channel
@@ -40,23 +65,33 @@ pub fn new_replied_flag() -> RepliedFlag {
 /// tools once and shares them across calls.
 #[derive(Debug, Clone)]
 pub struct ReplyTool {
-    response_tx: mpsc::Sender<OutboundResponse>,
+    response_tx: ResponseSender,
     conversation_id: String,
     conversation_logger: ConversationLogger,
     channel_id: ChannelId,
     replied_flag: RepliedFlag,
     agent_display_name: String,
+    /// Platform ID of the message that triggered this turn, used by `quote`.
+    /// None on retriggers and synthetic messages.
+    trigger_message_id: Option<String>,
+    /// Holds replies as drafts while the channel is supervised.
+    drafts: DraftStore,
+    sent_replies: SentReplies,
 }
 
 impl ReplyTool {
     /// Create a new reply tool bound to a conversation's response channel.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        response_tx: mpsc::Sender<OutboundResponse>,
+        response_tx: ResponseSender,
         conversation_id: impl Into<String>,
         conversation_logger: ConversationLogger,
         channel_id: ChannelId,
         replied_flag: RepliedFlag,
         agent_display_name: impl Into<String>,
+        trigger_message_id: Option<String>,
+        drafts: DraftStore,
+        sent_replies: SentReplies,
     ) -> Self {
         Self {
             response_tx,
@@ -65,6 +100,9 @@ impl ReplyTool {
             channel_id,
             replied_flag,
             agent_display_name: agent_display_name.into(),
+            trigger_message_id,
+            drafts,
+            sent_replies,
         }
     }
 }
@@ -84,6 +122,11 @@ pub struct ReplyArgs {
     /// reply is posted there. Thread names are capped at 100 characters.
     #[serde(default)]
     pub thread_name: Option<String>,
+    /// Optional: reply directly to the message that triggered this turn
+    /// (a quoted reply on Discord and Telegram, a thread reply on Slack).
+    /// Ignored when `thread_name` or rich content is set.
+    #[serde(default)]
+    pub quote: bool,
     /// Optional: formatted cards (e.g. Discord embeds) to attach to the message.
     /// Great for structured reports, summaries, or visually distinct content.
     #[serde(default)]
@@ -239,6 +282,10 @@ impl Tool for ReplyTool {
                     "type": "string",
                     "description": "If provided, creates a new public thread with this name and posts the reply inside it. Max 100 characters."
                 },
+                "quote": {
+                    "type": "boolean",
+                    "description": "Reply directly to the message you're answering (quoted reply on Discord/Telegram, thread reply on Slack). Use it in busy conversations where it would otherwise be unclear what you're responding to."
+                },
                 "cards": {
                     "type": "array",
                     "description": "Optional: formatted cards (e.g. Discord embeds) to attach. Great for structured reports, summaries, or visually distinct content. Max 10 cards.",
@@ -339,6 +386,7 @@ impl Tool for ReplyTool {
             conversation_id = %self.conversation_id,
             content_len = args.content.len(),
             thread_name = args.thread_name.as_deref(),
+            quote = args.quote,
             "reply tool called"
         );
 
@@ -364,12 +412,6 @@ impl Tool for ReplyTool {
             ));
         }
 
-        self.conversation_logger.log_bot_message_with_name(
-            &self.channel_id,
-            &converted_content,
-            Some(&self.agent_display_name),
-        );
-
         let response = if let Some(ref name) = args.thread_name {
             // Cap thread names at 100 characters (Discord limit)
             let thread_name = if name.len() > 100 {
@@ -390,10 +432,41 @@ impl Tool for ReplyTool {
                 interactive_elements: args.interactive_elements.unwrap_or_default(),
                 poll: args.poll,
             }
+        } else if args.quote
+            && let Some(in_reply_to) = self.trigger_message_id.clone()
+        {
+            OutboundResponse::Reply {
+                in_reply_to,
+                text: converted_content.clone(),
+            }
         } else {
             OutboundResponse::Text(converted_content.clone())
         };
 
+        // Supervised channels hold the reply until an operator approves it;
+        // it's logged once it's actually sent.
+        let response = self
+            .drafts
+            .hold(
+                &self.channel_id,
+                self.trigger_message_id.as_deref(),
+                self.response_tx.current_turn().await,
+                response,
+            )
+            .await
+            .map_err(|e| ReplyError(format!("failed to hold reply for review: {e}")))?;
+        if !matches!(response, OutboundResponse::Draft { .. }) {
+            let message_id = self.conversation_logger.log_bot_message_with_name(
+                &self.channel_id,
+                &converted_content,
+                Some(&self.agent_display_name),
+            );
+            self.sent_replies.write().await.push(SentReply {
+                message_id,
+                content: converted_content.clone(),
+            });
+        }
+
         self.response_tx
             .send(response)
             .await
@@ -0,0 +1,188 @@
+//! Schedule tool for channels: run a prompt in this conversation on a timer.
+
+use crate::ChannelId;
+use crate::conversation::ChannelStore;
+use crate::messaging::target::{BroadcastTarget, resolve_broadcast_target};
+use crate::tools::cron::{CronArgs, CronTool};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool for scheduling recurring or one-off tasks that report back to the
+/// current channel.
+///
+/// A thin wrapper over the cron subsystem: the delivery target is resolved
+/// from the channel the tool runs in, so the LLM never has to spell out an
+/// `adapter:target` pair.
+#[derive(Debug, Clone)]
+pub struct ScheduleTool {
+    cron: CronTool,
+    channel_store: ChannelStore,
+    channel_id: ChannelId,
+}
+
+impl ScheduleTool {
+    pub fn new(cron: CronTool, channel_store: ChannelStore, channel_id: ChannelId) -> Self {
+        Self {
+            cron,
+            channel_store,
+            channel_id,
+        }
+    }
+}
+
+/// Error type for schedule tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Schedule failed: {0}")]
+pub struct ScheduleError(String);
+
+/// Arguments for schedule tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ScheduleArgs {
+    /// Short unique ID for the job (e.g. "morning-rss").
+    pub id: String,
+    /// The instruction to run each time the job fires.
+    pub prompt: String,
+    /// Five-field cron expression, e.g. "0 8 * * *" for every day at 08:00.
+    #[serde(default)]
+    pub cron_expr: Option<String>,
+    /// Seconds between runs, for schedules that aren't tied to the clock.
+    #[serde(default)]
+    pub interval_secs: Option<u64>,
+    /// Run once and then disable (for reminders).
+    #[serde(default)]
+    pub run_once: bool,
+    /// Max seconds to wait for each run to finish (default 120).
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Run as a worker that remembers what it found last time and reports
+    /// only what's new.
+    #[serde(default)]
+    pub stateful: bool,
+}
+
+/// Output from schedule tool.
+#[derive(Debug, Serialize)]
+pub struct ScheduleOutput {
+    pub success: bool,
+    pub id: String,
+    pub delivery_target: String,
+    pub message: String,
+}
+
+impl Tool for ScheduleTool {
+    const NAME: &'static str = "schedule";
+
+    type Error = ScheduleError;
+    type Args = ScheduleArgs;
+    type Output = ScheduleOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/schedule").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Short unique ID for the job (e.g. 'morning-rss'). Reusing an ID replaces that job."
+                    },
+                    "prompt": {
+                        "type": "string",
+                        "description": "The instruction to run each time, written as a request to yourself (e.g. 'Check my RSS feeds and summarize anything new')."
+                    },
+                    "cron_expr": {
+                        "type": "string",
+                        "description": "Cron expression 'minute hour day-of-month month day-of-week' (e.g. '0 8 * * *' = daily at 08:00, '30 9 * * 1-5' = weekdays at 09:30). Also accepts @hourly, @daily, @weekly."
+                    },
+                    "interval_secs": {
+                        "type": "integer",
+                        "description": "Seconds between runs, if the schedule isn't tied to wall-clock time. Minimum 60."
+                    },
+                    "run_once": {
+                        "type": "boolean",
+                        "description": "If true, run once at the next matching time and then disable. Use for reminders."
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "Max seconds to wait for each run (default 120). Use 600 for research-heavy tasks."
+                    },
+                    "stateful": {
+                        "type": "boolean",
+                        "description": "If true, each run is a worker that remembers what it found last time and only posts new findings. Use for watching things, e.g. 'report new issues in owner/repo'."
+                    }
+                },
+                "required": ["id", "prompt"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        if args.cron_expr.is_none() && args.interval_secs.is_none() {
+            return Err(ScheduleError(
+                "either 'cron_expr' or 'interval_secs' is required".into(),
+            ));
+        }
+
+        let delivery_target = current_delivery_target(&self.channel_store, &self.channel_id)
+            .await
+            .map_err(ScheduleError)?
+            .to_string();
+
+        let output = self
+            .cron
+            .create(CronArgs {
+                action: "create".into(),
+                id: Some(args.id.clone()),
+                prompt: Some(args.prompt),
+                interval_secs: args.interval_secs,
+                cron_expr: args.cron_expr,
+                delivery_target: Some(delivery_target.clone()),
+                active_start_hour: None,
+                active_end_hour: None,
+                delete_id: None,
+                timeout_secs: args.timeout_secs,
+                run_once: Some(args.run_once),
+                stateful: Some(args.stateful),
+            })
+            .await
+            .map_err(|error| ScheduleError(error.to_string()))?;
+
+        tracing::info!(
+            cron_id = %args.id,
+            channel_id = %self.channel_id,
+            %delivery_target,
+            "task scheduled from channel"
+        );
+
+        Ok(ScheduleOutput {
+            success: output.success,
+            id: args.id,
+            delivery_target,
+            message: output.message,
+        })
+    }
+}
+
+/// Resolve the broadcast target for the channel a tool is running in.
+pub(crate) async fn current_delivery_target(
+    channel_store: &ChannelStore,
+    channel_id: &ChannelId,
+) -> Result<BroadcastTarget, String> {
+    let channel = channel_store
+        .get(channel_id)
+        .await
+        .map_err(|error| format!("failed to look up this channel: {error}"))?
+        .ok_or_else(|| {
+            format!("channel '{channel_id}' is not tracked, so nothing can be scheduled here")
+        })?;
+
+    resolve_broadcast_target(&channel).ok_or_else(|| {
+        format!(
+            "scheduled results can't be delivered to {} channels",
+            channel.platform
+        )
+    })
+}
@@ -1,30 +1,41 @@
 //! Send file tool for delivering file attachments to users (channel only).
 
-use crate::OutboundResponse;
+use crate::media::{MediaSource, MediaStore};
+use crate::{OutboundResponse, ResponseSender};
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use tokio::sync::mpsc;
 
 /// Tool for sending files to users.
 ///
-/// Reads a file from the local filesystem and sends it as an attachment
-/// in the conversation. The channel process creates a response sender per
-/// conversation turn and this tool routes file responses through it.
-/// File access is restricted to the agent's workspace boundary.
+/// Reads a file from the local filesystem, or a stored file from the media
+/// store, and sends it as an attachment in the conversation. The channel
+/// process creates a response sender per conversation turn and this tool
+/// routes file responses through it. File access is restricted to the
+/// agent's workspace boundary. Files read from disk are stored too, so they
+/// can be sent again by id in later turns.
 #[derive(Debug, Clone)]
 pub struct SendFileTool {
-    response_tx: mpsc::Sender<OutboundResponse>,
+    response_tx: ResponseSender,
     workspace: PathBuf,
+    media: MediaStore,
+    channel_id: String,
 }
 
 impl SendFileTool {
-    pub fn new(response_tx: mpsc::Sender<OutboundResponse>, workspace: PathBuf) -> Self {
+    pub fn new(
+        response_tx: ResponseSender,
+        workspace: PathBuf,
+        media: MediaStore,
+        channel_id: impl Into<String>,
+    ) -> Self {
         Self {
             response_tx,
             workspace,
+            media,
+            channel_id: channel_id.into(),
         }
     }
 
@@ -60,7 +71,11 @@ pub struct SendFileError(String);
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SendFileArgs {
     /// The absolute path to the file to send.
-    pub file_path: String,
+    #[serde(default)]
+    pub file_path: Option<String>,
+    /// Id of a stored file to send instead of a path.
+    #[serde(default)]
+    pub media_id: Option<String>,
     /// Optional caption/message to accompany the file.
     #[serde(default)]
     pub caption: Option<String>,
@@ -72,10 +87,13 @@ pub struct SendFileOutput {
     pub success: bool,
     pub filename: String,
     pub size_bytes: u64,
+    /// Id of the stored copy, for sending the file again later.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_id: Option<String>,
 }
 
 /// Maximum file size: 25 MB (Discord's limit for non-boosted servers).
-const MAX_FILE_SIZE_BYTES: u64 = 25 * 1024 * 1024;
+pub(crate) const MAX_FILE_SIZE_BYTES: u64 = 25 * 1024 * 1024;
 
 impl Tool for SendFileTool {
     const NAME: &'static str = "send_file";
@@ -95,18 +113,72 @@ impl Tool for SendFileTool {
                         "type": "string",
                         "description": "The absolute path to the file to send."
                     },
+                    "media_id": {
+                        "type": "string",
+                        "description": "Id of a stored file to send instead of a path."
+                    },
                     "caption": {
                         "type": "string",
                         "description": "Optional caption or message to accompany the file."
                     }
-                },
-                "required": ["file_path"]
+                }
             }),
         }
     }
 
     async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
-        let raw_path = PathBuf::from(&args.file_path);
+        let (filename, data, mime_type, media) = match (&args.file_path, &args.media_id) {
+            (Some(_), Some(_)) => {
+                return Err(SendFileError(
+                    "give either file_path or media_id, not both".into(),
+                ));
+            }
+            (None, None) => {
+                return Err(SendFileError("file_path or media_id is required".into()));
+            }
+            (None, Some(media_id)) => self.read_stored(media_id).await?,
+            (Some(file_path), None) => self.read_path(file_path).await?,
+        };
+
+        let size_bytes = data.len() as u64;
+
+        tracing::info!(
+            filename = %filename,
+            mime_type = %mime_type,
+            size_bytes,
+            media_id = media.as_ref().map(|media| media.id.as_str()),
+            "send_file tool called"
+        );
+
+        let media_id = media.as_ref().map(|media| media.id.clone());
+        let response = OutboundResponse::File {
+            filename: filename.clone(),
+            data,
+            mime_type,
+            caption: args.caption,
+            media,
+        };
+
+        self.response_tx
+            .send(response)
+            .await
+            .map_err(|error| SendFileError(format!("failed to send file: {error}")))?;
+
+        Ok(SendFileOutput {
+            success: true,
+            filename,
+            size_bytes,
+            media_id,
+        })
+    }
+}
+
+type LoadedFile = (String, Vec<u8>, String, Option<crate::media::MediaRef>);
+
+impl SendFileTool {
+    /// Read a workspace file and store a copy of it.
+    async fn read_path(&self, file_path: &str) -> Result<LoadedFile, SendFileError> {
+        let raw_path = PathBuf::from(file_path);
 
         if !raw_path.is_absolute() {
             return Err(SendFileError("file_path must be an absolute path".into()));
@@ -143,32 +215,45 @@ impl Tool for SendFileTool {
             .first_or_octet_stream()
             .to_string();
 
-        let size_bytes = data.len() as u64;
-
-        tracing::info!(
-            file_path = %path.display(),
-            filename = %filename,
-            mime_type = %mime_type,
-            size_bytes,
-            "send_file tool called"
-        );
-
-        let response = OutboundResponse::File {
-            filename: filename.clone(),
-            data,
-            mime_type,
-            caption: args.caption,
+        // Sending works without the stored copy; it only enables resending.
+        let media = match self
+            .media
+            .put(
+                MediaSource::Tool,
+                Some(&self.channel_id),
+                &filename,
+                &mime_type,
+                data.clone(),
+            )
+            .await
+        {
+            Ok(stored) => Some(self.media.reference(&stored)),
+            Err(error) => {
+                tracing::warn!(%error, %filename, "failed to store sent file");
+                None
+            }
         };
 
-        self.response_tx
-            .send(response)
+        Ok((filename, data, mime_type, media))
+    }
+
+    /// Load a file from the media store.
+    async fn read_stored(&self, media_id: &str) -> Result<LoadedFile, SendFileError> {
+        let (stored, data) = self
+            .media
+            .read(media_id.trim())
             .await
-            .map_err(|error| SendFileError(format!("failed to send file: {error}")))?;
+            .map_err(|error| SendFileError(format!("failed to load media {media_id}: {error}")))?
+            .ok_or_else(|| SendFileError(format!("no stored file with id '{media_id}'")))?;
 
-        Ok(SendFileOutput {
-            success: true,
-            filename,
-            size_bytes,
-        })
+        if stored.size_bytes > MAX_FILE_SIZE_BYTES {
+            return Err(SendFileError(format!(
+                "file is too large ({} bytes, max {} bytes)",
+                stored.size_bytes, MAX_FILE_SIZE_BYTES,
+            )));
+        }
+
+        let media = self.media.reference(&stored);
+        Ok((stored.filename, data, stored.mime_type, Some(media)))
     }
 }
@@ -1,6 +1,6 @@
 //! Set status tool for workers.
 
-use crate::{AgentId, ChannelId, ProcessEvent, WorkerId};
+use crate::{AgentId, ChannelId, ProcessEvent, TurnId, WorkerId};
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
@@ -13,6 +13,7 @@ pub struct SetStatusTool {
     agent_id: AgentId,
     worker_id: WorkerId,
     channel_id: Option<ChannelId>,
+    turn_id: Option<TurnId>,
     event_tx: broadcast::Sender<ProcessEvent>,
 }
 
@@ -22,12 +23,14 @@ impl SetStatusTool {
         agent_id: AgentId,
         worker_id: WorkerId,
         channel_id: Option<ChannelId>,
+        turn_id: Option<TurnId>,
         event_tx: broadcast::Sender<ProcessEvent>,
     ) -> Self {
         Self {
             agent_id,
             worker_id,
             channel_id,
+            turn_id,
             event_tx,
         }
     }
@@ -96,6 +99,7 @@ impl Tool for SetStatusTool {
             worker_id: self.worker_id,
             channel_id: self.channel_id.clone(),
             status: status.clone(),
+            turn_id: self.turn_id,
         };
 
         let _ = self.event_tx.send(event);
@@ -120,6 +124,7 @@ pub fn set_status(
         worker_id,
         channel_id: None,
         status: status.into(),
+        turn_id: None,
     };
 
     let _ = event_tx.send(event);
@@ -1,6 +1,8 @@
 //! Shell tool for executing shell commands (task workers only).
 
+use crate::hooks::ToolSteps;
 use crate::sandbox::Sandbox;
+use crate::{AgentId, ChannelId, ProcessEvent, TurnId, WorkerId};
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
@@ -8,19 +10,114 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::process::Stdio;
 use std::sync::Arc;
-use tokio::process::Command;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt as _, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::broadcast;
+
+/// Minimum time between progress events for one command's output.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Max bytes of an output line carried in a progress event.
+const PROGRESS_LINE_BYTES: usize = 200;
+
+/// Longest run of output without a newline that is buffered as one line.
+const MAX_LINE_BYTES: usize = 16 * 1024;
 
 /// Tool for executing shell commands within a sandboxed environment.
 #[derive(Debug, Clone)]
 pub struct ShellTool {
     workspace: PathBuf,
+    worker_workspace: Option<PathBuf>,
     sandbox: Arc<Sandbox>,
+    progress: Option<ShellProgress>,
 }
 
 impl ShellTool {
     /// Create a new shell tool with sandbox containment.
     pub fn new(workspace: PathBuf, sandbox: Arc<Sandbox>) -> Self {
-        Self { workspace, sandbox }
+        Self {
+            workspace,
+            worker_workspace: None,
+            sandbox,
+            progress: None,
+        }
+    }
+
+    /// Run commands from the worker's own working directory by default.
+    pub fn with_worker_workspace(mut self, worker_workspace: Option<PathBuf>) -> Self {
+        self.worker_workspace = worker_workspace;
+        self
+    }
+
+    /// Stream output lines to the worker's channel as `WorkerProgress` events
+    /// while a command runs.
+    pub fn with_progress(mut self, progress: ShellProgress) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+}
+
+/// Reports a running command's output as progress for a worker.
+#[derive(Debug, Clone)]
+pub struct ShellProgress {
+    agent_id: AgentId,
+    worker_id: WorkerId,
+    channel_id: Option<ChannelId>,
+    turn_id: Option<TurnId>,
+    event_tx: broadcast::Sender<ProcessEvent>,
+    /// The worker hook's tool steps, so output is attributed to the step
+    /// that started the command.
+    tool_steps: Arc<ToolSteps>,
+    /// Step claimed by the running command.
+    step: usize,
+}
+
+impl ShellProgress {
+    pub fn new(
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        turn_id: Option<TurnId>,
+        event_tx: broadcast::Sender<ProcessEvent>,
+        tool_steps: Arc<ToolSteps>,
+    ) -> Self {
+        Self {
+            agent_id,
+            worker_id,
+            channel_id,
+            turn_id,
+            event_tx,
+            tool_steps,
+            step: 0,
+        }
+    }
+
+    /// Progress for one run of `command`, under the step of its tool call.
+    fn for_command(&self, command: &str) -> Self {
+        Self {
+            step: self.tool_steps.claim_shell(command),
+            ..self.clone()
+        }
+    }
+
+    fn report(&self, line: &[u8]) {
+        let line = String::from_utf8_lossy(line);
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+        let end = line.floor_char_boundary(PROGRESS_LINE_BYTES);
+
+        let event = ProcessEvent::WorkerProgress {
+            agent_id: self.agent_id.clone(),
+            worker_id: self.worker_id,
+            channel_id: self.channel_id.clone(),
+            step: self.step,
+            detail: format!("shell: {}", &line[..end]),
+            turn_id: self.turn_id,
+        };
+        self.event_tx.send(event).ok();
     }
 }
 
@@ -106,11 +203,11 @@ impl Tool for ShellTool {
         let working_dir = if let Some(ref dir) = args.working_dir {
             let path = std::path::Path::new(dir);
             let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
-            let workspace_canonical = self
-                .workspace
-                .canonicalize()
-                .unwrap_or_else(|_| self.workspace.clone());
-            if !canonical.starts_with(&workspace_canonical) {
+            let within_workspace = std::iter::once(&self.workspace)
+                .chain(&self.worker_workspace)
+                .map(|root| root.canonicalize().unwrap_or_else(|_| root.clone()))
+                .any(|root| canonical.starts_with(root));
+            if !within_workspace {
                 return Err(ShellError {
                     message: format!(
                         "working_dir must be within the workspace ({}).",
@@ -121,7 +218,9 @@ impl Tool for ShellTool {
             }
             canonical
         } else {
-            self.workspace.clone()
+            self.worker_workspace
+                .clone()
+                .unwrap_or_else(|| self.workspace.clone())
         };
 
         let mut cmd = if cfg!(target_os = "windows") {
@@ -134,31 +233,52 @@ impl Tool for ShellTool {
                 .wrap("sh", &["-c", &args.command], &working_dir)
         };
 
-        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+        cmd.stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        // Run the command in its own process group so a timeout can take
+        // down everything it started, not just the shell.
+        #[cfg(unix)]
+        cmd.process_group(0);
 
-        let timeout = tokio::time::Duration::from_secs(args.timeout_seconds);
+        let timeout = Duration::from_secs(args.timeout_seconds);
+        let progress = self
+            .progress
+            .as_ref()
+            .map(|progress| progress.for_command(&args.command));
 
-        let output = tokio::time::timeout(timeout, cmd.output())
-            .await
-            .map_err(|_| ShellError {
-                message: "Command timed out".to_string(),
-                exit_code: -1,
-            })?
-            .map_err(|e| ShellError {
+        let mut child = cmd.spawn().map_err(|e| ShellError {
+            message: format!("Failed to execute command: {e}"),
+            exit_code: -1,
+        })?;
+        let pid = child.id();
+
+        let result = tokio::time::timeout(timeout, async {
+            let (stdout, stderr) = collect_output(&mut child, progress.as_ref()).await?;
+            let status = child.wait().await?;
+            Ok::<_, std::io::Error>((status, stdout, stderr))
+        })
+        .await;
+
+        let (status, stdout, stderr) = match result {
+            Ok(result) => result.map_err(|e| ShellError {
                 message: format!("Failed to execute command: {e}"),
                 exit_code: -1,
-            })?;
-
-        let stdout = crate::tools::truncate_output(
-            &String::from_utf8_lossy(&output.stdout),
-            crate::tools::MAX_TOOL_OUTPUT_BYTES,
-        );
-        let stderr = crate::tools::truncate_output(
-            &String::from_utf8_lossy(&output.stderr),
-            crate::tools::MAX_TOOL_OUTPUT_BYTES,
-        );
-        let exit_code = output.status.code().unwrap_or(-1);
-        let success = output.status.success();
+            })?,
+            Err(_) => {
+                kill_process_group(pid);
+                child.kill().await.ok();
+                return Err(ShellError {
+                    message: "Command timed out".to_string(),
+                    exit_code: -1,
+                });
+            }
+        };
+
+        let stdout = stdout.into_string();
+        let stderr = stderr.into_string();
+        let exit_code = status.code().unwrap_or(-1);
+        let success = status.success();
 
         let summary = format_shell_output(exit_code, &stdout, &stderr);
 
@@ -172,6 +292,121 @@ impl Tool for ShellTool {
     }
 }
 
+/// Kill every process in the command's process group.
+#[cfg(unix)]
+fn kill_process_group(pid: Option<u32>) {
+    if let Some(pid) = pid {
+        // SAFETY: signalling a process group we created has no memory-safety
+        // preconditions.
+        unsafe {
+            libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(_pid: Option<u32>) {}
+
+/// Output of one stream, kept up to `MAX_TOOL_OUTPUT_BYTES`. Anything past
+/// the cap is counted and dropped as it is read.
+#[derive(Debug, Default)]
+struct CappedOutput {
+    bytes: Vec<u8>,
+    total: usize,
+}
+
+impl CappedOutput {
+    fn push(&mut self, data: &[u8]) {
+        self.total += data.len();
+        let room = crate::tools::MAX_TOOL_OUTPUT_BYTES.saturating_sub(self.bytes.len());
+        self.bytes.extend_from_slice(&data[..data.len().min(room)]);
+    }
+
+    fn into_string(self) -> String {
+        let text = String::from_utf8_lossy(&self.bytes).into_owned();
+        if self.total > self.bytes.len() {
+            text + &crate::tools::truncation_notice(self.bytes.len(), self.total)
+        } else {
+            text
+        }
+    }
+}
+
+/// Read a child's stdout and stderr to EOF, reporting output lines as
+/// progress at most once per `PROGRESS_INTERVAL`. Memory stays bounded no
+/// matter how much the command prints.
+async fn collect_output(
+    child: &mut Child,
+    progress: Option<&ShellProgress>,
+) -> std::io::Result<(CappedOutput, CappedOutput)> {
+    let mut stdout_reader = child.stdout.take().map(BufReader::new);
+    let mut stderr_reader = child.stderr.take().map(BufReader::new);
+    let (mut stdout, mut stderr) = (CappedOutput::default(), CappedOutput::default());
+    // Reads append to these, so a partial line survives losing a select race
+    // and is completed on the next read.
+    let (mut stdout_line, mut stderr_line) = (Vec::new(), Vec::new());
+    let mut last_report: Option<Instant> = None;
+
+    while stdout_reader.is_some() || stderr_reader.is_some() {
+        let (read, is_stdout) = tokio::select! {
+            read = read_chunk(&mut stdout_reader, &mut stdout_line) => (read?, true),
+            read = read_chunk(&mut stderr_reader, &mut stderr_line) => (read?, false),
+        };
+
+        let (line, output) = if is_stdout {
+            (&mut stdout_line, &mut stdout)
+        } else {
+            (&mut stderr_line, &mut stderr)
+        };
+
+        if read == 0 {
+            output.push(line);
+            line.clear();
+            if is_stdout {
+                stdout_reader = None;
+            } else {
+                stderr_reader = None;
+            }
+            continue;
+        }
+
+        if !line.ends_with(b"\n") && line.len() < MAX_LINE_BYTES {
+            continue;
+        }
+        if let Some(progress) = progress
+            && last_report.is_none_or(|at| at.elapsed() >= PROGRESS_INTERVAL)
+        {
+            progress.report(line);
+            last_report = Some(Instant::now());
+        }
+        output.push(line);
+        line.clear();
+    }
+
+    Ok((stdout, stderr))
+}
+
+/// Append buffered bytes up to and including the next newline to `line`, or
+/// wait forever once the stream has been closed. Returns 0 at EOF.
+async fn read_chunk<R: AsyncRead + Unpin>(
+    reader: &mut Option<BufReader<R>>,
+    line: &mut Vec<u8>,
+) -> std::io::Result<usize> {
+    match reader {
+        Some(reader) => {
+            let available = reader.fill_buf().await?;
+            let used = available
+                .iter()
+                .position(|byte| *byte == b'\n')
+                .map_or(available.len(), |newline| newline + 1);
+            line.extend_from_slice(&available[..used]);
+            reader.consume(used);
+            Ok(used)
+        }
+        None => std::future::pending().await,
+    }
+}
+
 /// Format shell output for display.
 fn format_shell_output(exit_code: i32, stdout: &str, stderr: &str) -> String {
     let mut output = String::new();
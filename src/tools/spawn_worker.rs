@@ -40,6 +40,11 @@ pub struct SpawnWorkerArgs {
     /// suggested skills are flagged as recommended for this task.
     #[serde(default)]
     pub suggested_skills: Vec<String>,
+    /// Keep the worker's working directory after it finishes so its files
+    /// can be fetched with `workspace_list` / `workspace_read`. Builtin
+    /// workers only.
+    #[serde(default)]
+    pub retain_workspace: bool,
     /// Worker type: "builtin" (default) runs a Rig agent loop with shell/file/exec
     /// tools. "opencode" spawns an OpenCode subprocess with full coding agent
     /// capabilities. Use "opencode" for complex coding tasks that benefit from
@@ -50,6 +55,11 @@ pub struct SpawnWorkerArgs {
     /// The OpenCode agent will operate in this directory.
     #[serde(default)]
     pub directory: Option<String>,
+    /// Name of a worker preset from the agent config. The preset adds its
+    /// instructions and picks the worker's tools, model, and timeout.
+    /// Builtin workers only.
+    #[serde(default)]
+    pub preset: Option<String>,
 }
 
 /// Output from spawn worker tool.
@@ -75,16 +85,28 @@ impl Tool for SpawnWorkerTool {
     async fn definition(&self, _prompt: String) -> ToolDefinition {
         let rc = &self.state.deps.runtime_config;
         let browser_enabled = rc.browser_config.load().enabled;
-        let web_search_enabled = rc.brave_search_key.load().is_some();
+        let web_search_enabled = rc.web_search.load().is_configured();
+        let container_enabled = rc.container.load().enabled;
+        let github_enabled = rc.github_token.load().is_some();
+        let coolify_enabled = rc.coolify_url.load().is_some() && rc.coolify_token.load().is_some();
         let opencode_enabled = rc.opencode.load().enabled;
 
         let mut tools_list = vec!["shell", "file", "exec"];
+        if container_enabled {
+            tools_list.push("container_run");
+        }
         if browser_enabled {
             tools_list.push("browser");
         }
         if web_search_enabled {
             tools_list.push("web_search");
         }
+        if github_enabled {
+            tools_list.push("github");
+        }
+        if coolify_enabled {
+            tools_list.push("coolify");
+        }
 
         let opencode_note = if opencode_enabled {
             " Set worker_type to \"opencode\" with a directory path for complex coding tasks — this spawns a full OpenCode coding agent with codebase exploration, context management, and its own tool suite."
@@ -111,9 +133,35 @@ impl Tool for SpawnWorkerTool {
                 "type": "array",
                 "items": { "type": "string" },
                 "description": "Skill names from <available_skills> that are likely relevant to this task. The worker sees all skills and decides what to read, but suggested skills are flagged as recommended."
+            },
+            "retain_workspace": {
+                "type": "boolean",
+                "default": false,
+                "description": "If true, the worker's working directory is kept after it finishes so you can fetch the files it produced with workspace_list and workspace_read. If false (default), the directory is deleted when the worker finishes."
             }
         });
 
+        let presets = rc.worker_presets.load();
+        if !presets.is_empty()
+            && let Some(obj) = properties.as_object_mut()
+        {
+            let mut names: Vec<&str> = presets.keys().map(String::as_str).collect();
+            names.sort_unstable();
+            let listing = names
+                .iter()
+                .map(|name| format!("\"{name}\": {}", presets[*name].description))
+                .collect::<Vec<_>>()
+                .join("; ");
+            obj.insert(
+                "preset".to_string(),
+                serde_json::json!({
+                    "type": "string",
+                    "enum": names,
+                    "description": format!("Run the worker with a tuned configuration instead of the default one. Pick the preset that fits the task: {listing}."),
+                }),
+            );
+        }
+
         if opencode_enabled && let Some(obj) = properties.as_object_mut() {
             obj.insert(
                 "worker_type".to_string(),
@@ -149,6 +197,11 @@ impl Tool for SpawnWorkerTool {
         let is_opencode = args.worker_type.as_deref() == Some("opencode");
 
         let worker_id = if is_opencode {
+            if args.preset.is_some() {
+                return Err(SpawnWorkerError(
+                    "presets only apply to builtin workers".into(),
+                ));
+            }
             let directory = args.directory.as_deref().ok_or_else(|| {
                 SpawnWorkerError("directory is required for opencode workers".into())
             })?;
@@ -166,12 +219,19 @@ impl Tool for SpawnWorkerTool {
                     .iter()
                     .map(String::as_str)
                     .collect::<Vec<_>>(),
+                args.retain_workspace,
+                args.preset.as_deref(),
+                *self.state.turn_id.read().await,
             )
             .await
             .map_err(|e| SpawnWorkerError(format!("{e}")))?
         };
 
-        let worker_type_label = if is_opencode { "OpenCode" } else { "builtin" };
+        let worker_type_label = match &args.preset {
+            Some(preset) => format!("\"{preset}\" preset"),
+            None if is_opencode => "OpenCode".to_string(),
+            None => "builtin".to_string(),
+        };
         let message = if args.interactive {
             format!(
                 "Interactive {worker_type_label} worker {worker_id} spawned for: {}. Route follow-ups with route_to_worker.",
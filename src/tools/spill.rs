@@ -0,0 +1,202 @@
+//! Spill oversized tool results into artifacts (branches, workers, cortex chat).
+//!
+//! Tools wrapped with [`OutputSpill::wrap`] behave as before until a result is
+//! estimated above `tool_output_max_tokens`. Then the full result is stored in
+//! the `tool_artifacts` table and the LLM gets a preview with the artifact id
+//! instead, which it can page through with `read_artifact`. The limit is read
+//! on every call, so config changes apply without a restart.
+//!
+//! Results are passed through the agent's [`Redactor`] first, so secrets a
+//! tool turns up never reach the LLM or the artifact store.
+
+use crate::config::RuntimeConfig;
+use crate::conversation::ToolArtifactStore;
+use crate::redaction::Redactor;
+use crate::tools::truncate_output;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+/// Bytes per token when estimating result size, same ratio as the compactor.
+const BYTES_PER_TOKEN: usize = 4;
+
+/// Max bytes of a spilled result shown inline.
+const PREVIEW_BYTES: usize = 2_000;
+
+/// Where oversized tool results go. Cheap to clone.
+#[derive(Clone)]
+pub struct OutputSpill {
+    store: ToolArtifactStore,
+    redactor: Redactor,
+    runtime_config: Arc<RuntimeConfig>,
+}
+
+impl OutputSpill {
+    pub fn new(pool: SqlitePool, runtime_config: Arc<RuntimeConfig>) -> Self {
+        Self {
+            store: ToolArtifactStore::new(pool.clone()),
+            redactor: Redactor::new(pool, runtime_config.clone()),
+            runtime_config,
+        }
+    }
+
+    /// The artifact store, for registering `read_artifact` next to the
+    /// wrapped tools.
+    pub fn store(&self) -> ToolArtifactStore {
+        self.store.clone()
+    }
+
+    /// Wrap a tool so its oversized results are spilled.
+    pub fn wrap<T: Tool>(&self, tool: T) -> Spilling<T> {
+        Spilling {
+            inner: tool,
+            spill: self.clone(),
+        }
+    }
+
+    async fn spill(&self, tool_name: &str, output: Value) -> Value {
+        let output = self.redactor.redact_tool_output(tool_name, output);
+        let max_tokens = **self.runtime_config.tool_output_max_tokens.load();
+        let size = output.to_string().len();
+        if max_tokens == 0 || size <= max_tokens.saturating_mul(BYTES_PER_TOKEN) {
+            return output;
+        }
+
+        let content = render_artifact(&output);
+        match self.store.save(tool_name, &content).await {
+            Ok(artifact_id) => {
+                tracing::debug!(tool_name, %artifact_id, size, "tool result spilled to artifact");
+                Value::String(preview(&content, &artifact_id))
+            }
+            Err(error) => {
+                tracing::warn!(%error, tool_name, "failed to store tool artifact, truncating");
+                Value::String(truncate_output(
+                    &content,
+                    max_tokens.saturating_mul(BYTES_PER_TOKEN),
+                ))
+            }
+        }
+    }
+}
+
+/// A tool whose oversized results are replaced by an artifact preview.
+pub struct Spilling<T> {
+    inner: T,
+    spill: OutputSpill,
+}
+
+impl<T: Tool> Tool for Spilling<T> {
+    const NAME: &'static str = T::NAME;
+
+    type Error = T::Error;
+    type Args = T::Args;
+    type Output = Value;
+
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    async fn definition(&self, prompt: String) -> ToolDefinition {
+        self.inner.definition(prompt).await
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let tool_name = self.inner.name();
+        let output = to_value(&tool_name, self.inner.call(args).await?);
+        Ok(self.spill.spill(&tool_name, output).await)
+    }
+}
+
+/// Consumes the output so it isn't held across the spill await (tool outputs
+/// aren't required to be `Send`).
+fn to_value<O: Serialize>(tool_name: &str, output: O) -> Value {
+    serde_json::to_value(output).unwrap_or_else(|error| {
+        Value::String(format!("failed to serialize {tool_name} output: {error}"))
+    })
+}
+
+/// Render a tool result as line-oriented text, so `read_artifact` can page
+/// through multi-line fields like stdout or page content.
+fn render_artifact(output: &Value) -> String {
+    match output {
+        Value::String(text) => text.clone(),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, value)| match value {
+                Value::String(text) if text.contains('\n') => format!("{key}:\n{text}"),
+                Value::String(text) => format!("{key}: {text}"),
+                Value::Array(_) | Value::Object(_) => format!("{key}: {}", pretty(value)),
+                scalar => format!("{key}: {scalar}"),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        other => pretty(other),
+    }
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+/// The start of a spilled result followed by where to find the rest.
+fn preview(content: &str, artifact_id: &str) -> String {
+    let mut end = PREVIEW_BYTES.min(content.len());
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    // Prefer ending on a full line.
+    if end < content.len()
+        && let Some(newline) = content[..end].rfind('\n')
+    {
+        end = newline;
+    }
+
+    let total_lines = content.lines().count();
+    format!(
+        "{}\n\n[output too large for context: {} bytes ({total_lines} lines, ~{} tokens). \
+         Showed the first {end} bytes. The full output is artifact {artifact_id}; \
+         read it with read_artifact]",
+        &content[..end],
+        content.len(),
+        content.len() / BYTES_PER_TOKEN,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn object_fields_render_as_lines() {
+        let output = serde_json::json!({
+            "exit_code": 0,
+            "stdout": "line one\nline two",
+            "success": true,
+        });
+        assert_eq!(
+            render_artifact(&output),
+            "exit_code: 0\nstdout:\nline one\nline two\nsuccess: true"
+        );
+    }
+
+    #[test]
+    fn preview_ends_on_a_line_and_names_the_artifact() {
+        let content = "x".repeat(1_500) + "\n" + &"y".repeat(1_500);
+        let text = preview(&content, "abc");
+        assert!(text.starts_with(&"x".repeat(1_500)));
+        assert!(!text.contains("yy"));
+        assert!(text.contains("Showed the first 1500 bytes"));
+        assert!(text.contains("artifact abc"));
+    }
+
+    #[test]
+    fn short_content_is_shown_whole() {
+        let text = preview(&"é".repeat(10), "abc");
+        assert!(text.starts_with(&"é".repeat(10)));
+        assert!(text.contains("Showed the first 20 bytes"));
+    }
+}
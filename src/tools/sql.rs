@@ -0,0 +1,861 @@
+//! SQL query tool for the agent's configured databases (task workers only).
+//!
+//! Databases come from the agent's `[[sql]]` config. SQLite is always
+//! available; Postgres and MySQL need the `postgres` and `mysql` features.
+//! Read-only databases (the default) take one statement that starts with a
+//! reading keyword, run it in a read-only transaction that is rolled back,
+//! and open SQLite files read-only. Results are capped at the database's
+//! `max_rows`. The tool description lists each database's tables and
+//! columns, read from the database the first time the tool is described.
+
+use crate::config::SqlDatabaseConfig;
+
+use futures::TryStreamExt as _;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::{Column as _, Row as _, TypeInfo as _};
+use std::str::FromStr as _;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+/// Statements a read-only database accepts, by first keyword.
+const READ_ONLY_STATEMENTS: &[&str] = &[
+    "select", "with", "values", "table", "explain", "show", "describe", "desc",
+];
+
+/// Keywords that write files even from a read-only transaction (MySQL's
+/// `SELECT ... INTO OUTFILE`).
+const FILE_WRITING_KEYWORDS: &[&str] = &["outfile", "dumpfile"];
+
+/// Connections kept per database.
+const POOL_SIZE: u32 = 2;
+
+/// Largest schema listing per database in the tool description, in bytes.
+const MAX_SCHEMA_BYTES: usize = 8_000;
+
+/// Longest the schema lookup may take before the tool is described without it.
+const SCHEMA_TIMEOUT: Duration = Duration::from_secs(10);
+
+const SQLITE_SCHEMA_QUERY: &str = "SELECT m.name, p.name, p.type \
+     FROM sqlite_master AS m JOIN pragma_table_info(m.name) AS p \
+     WHERE m.type IN ('table', 'view') AND m.name NOT LIKE 'sqlite_%' \
+     ORDER BY m.name, p.cid";
+
+#[cfg(feature = "postgres")]
+const POSTGRES_SCHEMA_QUERY: &str = "SELECT \
+     CASE WHEN table_schema = 'public' THEN table_name::text \
+     ELSE table_schema::text || '.' || table_name::text END, \
+     column_name::text, data_type::text \
+     FROM information_schema.columns \
+     WHERE table_schema NOT IN ('pg_catalog', 'information_schema') \
+     ORDER BY table_schema, table_name, ordinal_position";
+
+#[cfg(feature = "mysql")]
+const MYSQL_SCHEMA_QUERY: &str = "SELECT CAST(table_name AS CHAR), \
+     CAST(column_name AS CHAR), CAST(column_type AS CHAR) \
+     FROM information_schema.columns WHERE table_schema = DATABASE() \
+     ORDER BY table_name, ordinal_position";
+
+enum SqlPool {
+    Sqlite(sqlx::SqlitePool),
+    #[cfg(feature = "postgres")]
+    Postgres(sqlx::PgPool),
+    #[cfg(feature = "mysql")]
+    MySql(sqlx::MySqlPool),
+}
+
+impl SqlPool {
+    fn kind(&self) -> &'static str {
+        match self {
+            SqlPool::Sqlite(_) => "sqlite",
+            #[cfg(feature = "postgres")]
+            SqlPool::Postgres(_) => "postgres",
+            #[cfg(feature = "mysql")]
+            SqlPool::MySql(_) => "mysql",
+        }
+    }
+}
+
+/// A configured database. Connections are opened on first use.
+struct SqlDatabase {
+    config: SqlDatabaseConfig,
+    pool: SqlPool,
+    dialect: Dialect,
+    /// Tables and columns, looked up once.
+    schema: OnceCell<String>,
+}
+
+impl SqlDatabase {
+    fn connect(config: SqlDatabaseConfig) -> Result<Self, SqlError> {
+        let scheme = config.url.split(':').next().unwrap_or_default();
+        let pool = match scheme {
+            "sqlite" => {
+                let options = sqlx::sqlite::SqliteConnectOptions::from_str(&config.url)
+                    .map_err(|error| SqlError(format!("database '{}': {error}", config.name)))?
+                    .read_only(config.read_only);
+                SqlPool::Sqlite(
+                    sqlx::sqlite::SqlitePoolOptions::new()
+                        .max_connections(POOL_SIZE)
+                        .connect_lazy_with(options),
+                )
+            }
+            #[cfg(feature = "postgres")]
+            "postgres" | "postgresql" => SqlPool::Postgres(
+                sqlx::postgres::PgPoolOptions::new()
+                    .max_connections(POOL_SIZE)
+                    .connect_lazy(&config.url)
+                    .map_err(|error| SqlError(format!("database '{}': {error}", config.name)))?,
+            ),
+            #[cfg(feature = "mysql")]
+            "mysql" | "mariadb" => SqlPool::MySql(
+                sqlx::mysql::MySqlPoolOptions::new()
+                    .max_connections(POOL_SIZE)
+                    .connect_lazy(&config.url)
+                    .map_err(|error| SqlError(format!("database '{}': {error}", config.name)))?,
+            ),
+            other => {
+                return Err(SqlError(format!(
+                    "database '{}' uses {other}, which this build doesn't include",
+                    config.name
+                )));
+            }
+        };
+        Ok(Self {
+            dialect: Dialect::from_scheme(scheme),
+            config,
+            pool,
+            schema: OnceCell::new(),
+        })
+    }
+
+    /// The database's tables and columns, one table per line.
+    async fn schema(&self) -> Result<&str, SqlError> {
+        self.schema
+            .get_or_try_init(|| async {
+                let columns: Vec<(String, String, String)> = match &self.pool {
+                    SqlPool::Sqlite(pool) => {
+                        sqlx::query_as(SQLITE_SCHEMA_QUERY).fetch_all(pool).await
+                    }
+                    #[cfg(feature = "postgres")]
+                    SqlPool::Postgres(pool) => {
+                        sqlx::query_as(POSTGRES_SCHEMA_QUERY).fetch_all(pool).await
+                    }
+                    #[cfg(feature = "mysql")]
+                    SqlPool::MySql(pool) => {
+                        sqlx::query_as(MYSQL_SCHEMA_QUERY).fetch_all(pool).await
+                    }
+                }
+                .map_err(|error| SqlError(error.to_string()))?;
+                Ok::<_, SqlError>(format_schema(&columns))
+            })
+            .await
+            .map(String::as_str)
+    }
+
+    async fn query(&self, sql: &str) -> Result<QueryRows, SqlError> {
+        let words = statement_words(sql, self.dialect)?;
+        if self.config.read_only {
+            check_read_only(&words)?;
+        }
+        let timeout = Duration::from_secs(self.config.timeout_secs.max(1));
+        tokio::time::timeout(timeout, self.run(sql))
+            .await
+            .map_err(|_| SqlError(format!("query timed out after {}s", timeout.as_secs())))?
+            .map_err(|error| SqlError(error.to_string()))
+    }
+
+    /// Run one statement in a transaction that read-only databases roll back.
+    async fn run(&self, sql: &str) -> Result<QueryRows, sqlx::Error> {
+        let read_only = self.config.read_only;
+        let max_rows = self.config.max_rows;
+        match &self.pool {
+            SqlPool::Sqlite(pool) => {
+                let mut transaction = pool.begin().await?;
+                let rows = fetch_rows(&mut *transaction, sql, max_rows).await?;
+                if read_only {
+                    transaction.rollback().await?;
+                } else {
+                    transaction.commit().await?;
+                }
+                Ok(rows)
+            }
+            #[cfg(feature = "postgres")]
+            SqlPool::Postgres(pool) => {
+                let mut transaction = pool.begin().await?;
+                if read_only {
+                    sqlx::query("SET TRANSACTION READ ONLY")
+                        .execute(&mut *transaction)
+                        .await?;
+                }
+                let rows = fetch_rows(&mut *transaction, sql, max_rows).await?;
+                if read_only {
+                    transaction.rollback().await?;
+                } else {
+                    transaction.commit().await?;
+                }
+                Ok(rows)
+            }
+            #[cfg(feature = "mysql")]
+            SqlPool::MySql(pool) => {
+                let mut connection = pool.acquire().await?;
+                if read_only {
+                    // MySQL takes the access mode for the next transaction only
+                    // before it starts.
+                    sqlx::query("SET TRANSACTION READ ONLY")
+                        .execute(&mut *connection)
+                        .await?;
+                }
+                let mut transaction = sqlx::Connection::begin(&mut *connection).await?;
+                let rows = fetch_rows(&mut *transaction, sql, max_rows).await?;
+                if read_only {
+                    transaction.rollback().await?;
+                } else {
+                    transaction.commit().await?;
+                }
+                Ok(rows)
+            }
+        }
+    }
+}
+
+/// Rows returned by a query.
+#[derive(Debug, Default)]
+struct QueryRows {
+    columns: Vec<String>,
+    rows: Vec<Vec<serde_json::Value>>,
+    truncated: bool,
+}
+
+/// Databases whose result columns the tool can turn into JSON.
+trait JsonDatabase: sqlx::Database {
+    fn column_value(row: &Self::Row, index: usize) -> serde_json::Value;
+}
+
+impl JsonDatabase for sqlx::Sqlite {
+    fn column_value(row: &Self::Row, index: usize) -> serde_json::Value {
+        column_value::<Self>(row, index)
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl JsonDatabase for sqlx::Postgres {
+    fn column_value(row: &Self::Row, index: usize) -> serde_json::Value {
+        column_value::<Self>(row, index)
+    }
+}
+
+#[cfg(feature = "mysql")]
+impl JsonDatabase for sqlx::MySql {
+    fn column_value(row: &Self::Row, index: usize) -> serde_json::Value {
+        column_value::<Self>(row, index)
+    }
+}
+
+/// Column types that can be read from `DB`.
+trait Decodes<DB: sqlx::Database>: for<'r> sqlx::Decode<'r, DB> + sqlx::Type<DB> {}
+
+impl<DB: sqlx::Database, T> Decodes<DB> for T where T: for<'r> sqlx::Decode<'r, DB> + sqlx::Type<DB> {}
+
+/// A column as JSON. Types without a JSON mapping (numeric, json, arrays,
+/// ...) show as their type name; the query can cast them to text.
+fn column_value<DB>(row: &DB::Row, index: usize) -> serde_json::Value
+where
+    DB: sqlx::Database,
+    usize: sqlx::ColumnIndex<DB::Row>,
+    i64: Decodes<DB>,
+    i32: Decodes<DB>,
+    i16: Decodes<DB>,
+    f64: Decodes<DB>,
+    f32: Decodes<DB>,
+    bool: Decodes<DB>,
+    String: Decodes<DB>,
+    chrono::DateTime<chrono::Utc>: Decodes<DB>,
+    chrono::NaiveDateTime: Decodes<DB>,
+    chrono::NaiveDate: Decodes<DB>,
+    uuid::Uuid: Decodes<DB>,
+    Vec<u8>: Decodes<DB>,
+{
+    if let Ok(value) = row.try_get::<Option<i64>, _>(index) {
+        return value.into();
+    }
+    if let Ok(value) = row.try_get::<Option<i32>, _>(index) {
+        return value.into();
+    }
+    if let Ok(value) = row.try_get::<Option<i16>, _>(index) {
+        return value.into();
+    }
+    if let Ok(value) = row.try_get::<Option<f64>, _>(index) {
+        return value.into();
+    }
+    if let Ok(value) = row.try_get::<Option<f32>, _>(index) {
+        return value.into();
+    }
+    if let Ok(value) = row.try_get::<Option<bool>, _>(index) {
+        return value.into();
+    }
+    if let Ok(value) = row.try_get::<Option<String>, _>(index) {
+        return value.into();
+    }
+    if let Ok(value) = row.try_get::<Option<chrono::DateTime<chrono::Utc>>, _>(index) {
+        return value.map(|value| value.to_rfc3339()).into();
+    }
+    if let Ok(value) = row.try_get::<Option<chrono::NaiveDateTime>, _>(index) {
+        return value.map(|value| value.to_string()).into();
+    }
+    if let Ok(value) = row.try_get::<Option<chrono::NaiveDate>, _>(index) {
+        return value.map(|value| value.to_string()).into();
+    }
+    if let Ok(value) = row.try_get::<Option<uuid::Uuid>, _>(index) {
+        return value.map(|value| value.to_string()).into();
+    }
+    if let Ok(value) = row.try_get::<Option<Vec<u8>>, _>(index) {
+        return value.map(|bytes| format!("<{} bytes>", bytes.len())).into();
+    }
+    format!("<{}>", row.columns()[index].type_info().name()).into()
+}
+
+/// Run `sql` and collect up to `max_rows` rows.
+async fn fetch_rows<'c, E, DB>(
+    executor: E,
+    sql: &str,
+    max_rows: usize,
+) -> Result<QueryRows, sqlx::Error>
+where
+    E: sqlx::Executor<'c, Database = DB>,
+    DB: JsonDatabase,
+    for<'q> DB::Arguments<'q>: sqlx::IntoArguments<'q, DB>,
+{
+    let mut stream = sqlx::query::<DB>(sql).persistent(false).fetch(executor);
+    let mut result = QueryRows::default();
+    while let Some(row) = stream.try_next().await? {
+        if result.rows.len() >= max_rows {
+            result.truncated = true;
+            break;
+        }
+        if result.columns.is_empty() {
+            result.columns = row
+                .columns()
+                .iter()
+                .map(|column| column.name().to_string())
+                .collect();
+        }
+        result.rows.push(
+            (0..row.len())
+                .map(|index| DB::column_value(&row, index))
+                .collect(),
+        );
+    }
+    Ok(result)
+}
+
+/// The SQL dialect a database speaks, for the lexing rules that differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dialect {
+    Sqlite,
+    /// Nested block comments, `E'...'` escape strings, and `$tag$`
+    /// dollar-quoted strings.
+    Postgres,
+    /// `#` comments, `--` comments only before whitespace, and backslash
+    /// escapes in strings.
+    MySql,
+}
+
+impl Dialect {
+    fn from_scheme(scheme: &str) -> Self {
+        match scheme {
+            "postgres" | "postgresql" => Dialect::Postgres,
+            "mysql" | "mariadb" => Dialect::MySql,
+            _ => Dialect::Sqlite,
+        }
+    }
+}
+
+/// The lowercased words of a single SQL statement, skipping comments and
+/// quoted strings and identifiers as `dialect` lexes them. Refuses more than
+/// one statement, unterminated strings and comments, and the MySQL
+/// constructs whose meaning depends on server settings: backslashes in
+/// strings (`NO_BACKSLASH_ESCAPES`) and `/*! ... */` comments, which run.
+fn statement_words(sql: &str, dialect: Dialect) -> Result<Vec<String>, SqlError> {
+    let multiple = || SqlError("run one statement at a time".into());
+    let unterminated = |what: &str| SqlError(format!("the query has an unterminated {what}"));
+    let chars: Vec<char> = sql.chars().collect();
+    let at = |index: usize| chars.get(index).copied();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut ended = false;
+    let mut i = 0;
+    while let Some(c) = at(i) {
+        i += 1;
+        // Postgres and MySQL identifiers may continue with `$`.
+        if c.is_alphanumeric()
+            || c == '_'
+            || (c == '$' && !word.is_empty() && dialect != Dialect::Sqlite)
+        {
+            if ended {
+                return Err(multiple());
+            }
+            word.push(c);
+            continue;
+        }
+        let previous_word = std::mem::take(&mut word).to_lowercase();
+        let escape_string = dialect == Dialect::Postgres && c == '\'' && previous_word == "e";
+        if !previous_word.is_empty() {
+            words.push(previous_word);
+        }
+        match c {
+            '\'' | '"' | '`' => {
+                if ended {
+                    return Err(multiple());
+                }
+                let backslash_escapes = escape_string || (dialect == Dialect::MySql && c != '`');
+                let mut closed = false;
+                while let Some(next) = at(i) {
+                    i += 1;
+                    if next == '\\' && backslash_escapes {
+                        if dialect == Dialect::MySql {
+                            return Err(SqlError(
+                                "backslashes in MySQL strings aren't allowed; double quotes to \
+                                 escape them"
+                                    .into(),
+                            ));
+                        }
+                        i += 1;
+                    } else if next == c {
+                        // A doubled quote is an escaped quote, not the end.
+                        if at(i) == Some(c) {
+                            i += 1;
+                        } else {
+                            closed = true;
+                            break;
+                        }
+                    }
+                }
+                if !closed {
+                    return Err(unterminated("string"));
+                }
+            }
+            '-' if at(i) == Some('-')
+                && (dialect != Dialect::MySql
+                    || at(i + 1).is_none_or(|next| next.is_whitespace() || next.is_control())) =>
+            {
+                skip_line(&chars, &mut i);
+            }
+            '#' if dialect == Dialect::MySql => skip_line(&chars, &mut i),
+            '/' if at(i) == Some('*') => {
+                if dialect == Dialect::MySql && at(i + 1) == Some('!') {
+                    return Err(SqlError(
+                        "MySQL executable comments (/*! ... */) aren't allowed".into(),
+                    ));
+                }
+                i += 1;
+                let mut depth = 1;
+                while depth > 0 {
+                    match (at(i), at(i + 1)) {
+                        (Some('*'), Some('/')) => {
+                            depth -= 1;
+                            i += 2;
+                        }
+                        (Some('/'), Some('*')) if dialect == Dialect::Postgres => {
+                            depth += 1;
+                            i += 2;
+                        }
+                        (Some(_), _) => i += 1,
+                        (None, _) => return Err(unterminated("comment")),
+                    }
+                }
+            }
+            '$' if dialect == Dialect::Postgres => {
+                let tag_end = (i..chars.len()).find(|&index| chars[index] == '$');
+                let is_tag = |tag: &[char]| {
+                    tag.first().is_none_or(|first| !first.is_ascii_digit())
+                        && tag.iter().all(|c| c.is_alphanumeric() || *c == '_')
+                };
+                // `$1` and other non-tags are parameters or operators.
+                if let Some(tag_end) = tag_end.filter(|&end| is_tag(&chars[i..end])) {
+                    if ended {
+                        return Err(multiple());
+                    }
+                    let delimiter = &chars[i - 1..=tag_end];
+                    let body_start = tag_end + 1;
+                    let close = (body_start..chars.len())
+                        .find(|&index| chars[index..].starts_with(delimiter))
+                        .ok_or_else(|| unterminated("dollar-quoted string"))?;
+                    i = close + delimiter.len();
+                }
+            }
+            ';' => ended = true,
+            _ => {}
+        }
+    }
+    if !word.is_empty() {
+        words.push(word.to_lowercase());
+    }
+    if words.is_empty() {
+        return Err(SqlError("the query is empty".into()));
+    }
+    Ok(words)
+}
+
+/// Move `i` past the end of the current line.
+fn skip_line(chars: &[char], i: &mut usize) {
+    while let Some(&next) = chars.get(*i) {
+        *i += 1;
+        if next == '\n' {
+            break;
+        }
+    }
+}
+
+/// Refuse statements that don't start with a reading keyword.
+fn check_read_only(words: &[String]) -> Result<(), SqlError> {
+    let first = words.first().map(String::as_str).unwrap_or_default();
+    if !READ_ONLY_STATEMENTS.contains(&first) {
+        return Err(SqlError(format!(
+            "this database is read-only; '{}' statements aren't allowed",
+            first.to_uppercase()
+        )));
+    }
+    if let Some(keyword) = words
+        .iter()
+        .find(|word| FILE_WRITING_KEYWORDS.contains(&word.as_str()))
+    {
+        return Err(SqlError(format!(
+            "this database is read-only; {} isn't allowed",
+            keyword.to_uppercase()
+        )));
+    }
+    Ok(())
+}
+
+/// One `table(column type, ...)` line per table, cut off at
+/// `MAX_SCHEMA_BYTES`.
+fn format_schema(columns: &[(String, String, String)]) -> String {
+    let mut tables: Vec<(&str, Vec<String>)> = Vec::new();
+    for (table, column, data_type) in columns {
+        let column = format!("{column} {}", data_type.to_lowercase())
+            .trim_end()
+            .to_string();
+        match tables.last_mut() {
+            Some((name, table_columns)) if *name == table.as_str() => table_columns.push(column),
+            _ => tables.push((table, vec![column])),
+        }
+    }
+    if tables.is_empty() {
+        return "(no tables)".into();
+    }
+
+    let mut schema = String::new();
+    for (index, (name, table_columns)) in tables.iter().enumerate() {
+        let line = format!("{name}({})\n", table_columns.join(", "));
+        if schema.len() + line.len() > MAX_SCHEMA_BYTES {
+            schema.push_str(&format!("... and {} more tables\n", tables.len() - index));
+            break;
+        }
+        schema.push_str(&line);
+    }
+    schema
+}
+
+/// Tool for querying the agent's SQL databases.
+#[derive(Clone)]
+pub struct SqlQueryTool {
+    databases: Arc<[SqlDatabase]>,
+}
+
+impl std::fmt::Debug for SqlQueryTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqlQueryTool")
+            .field(
+                "databases",
+                &self
+                    .databases
+                    .iter()
+                    .map(|database| &database.config.name)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl SqlQueryTool {
+    /// Set up pools for the configured databases. Nothing connects until the
+    /// tool is described or called.
+    pub fn new(configs: &[SqlDatabaseConfig]) -> Result<Self, SqlError> {
+        let databases = configs
+            .iter()
+            .cloned()
+            .map(SqlDatabase::connect)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            databases: databases.into(),
+        })
+    }
+
+    fn database(&self, name: Option<&str>) -> Result<&SqlDatabase, SqlError> {
+        match name {
+            Some(name) => self
+                .databases
+                .iter()
+                .find(|database| database.config.name == name)
+                .ok_or_else(|| {
+                    SqlError(format!(
+                        "no database named '{name}'; available: {}",
+                        self.names().join(", ")
+                    ))
+                }),
+            None if self.databases.len() == 1 => Ok(&self.databases[0]),
+            None => Err(SqlError(format!(
+                "pass 'database'; available: {}",
+                self.names().join(", ")
+            ))),
+        }
+    }
+
+    fn names(&self) -> Vec<&str> {
+        self.databases
+            .iter()
+            .map(|database| database.config.name.as_str())
+            .collect()
+    }
+}
+
+/// Error type for SQL query tool.
+#[derive(Debug, thiserror::Error)]
+#[error("SQL query failed: {0}")]
+pub struct SqlError(String);
+
+/// Arguments for SQL query tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SqlQueryArgs {
+    /// Name of the database to query. Optional when there's only one.
+    #[serde(default)]
+    pub database: Option<String>,
+    /// The SQL statement to run.
+    pub query: String,
+}
+
+/// Output from SQL query tool.
+#[derive(Debug, Serialize)]
+pub struct SqlQueryOutput {
+    pub database: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+    /// True when more rows matched than the database's `max_rows`.
+    pub truncated: bool,
+}
+
+impl Tool for SqlQueryTool {
+    const NAME: &'static str = "sql_query";
+
+    type Error = SqlError;
+    type Args = SqlQueryArgs;
+    type Output = SqlQueryOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        let mut description = crate::prompts::text::get("tools/sql_query").to_string();
+        description.push_str("\n\nDatabases:");
+        for database in self.databases.iter() {
+            let schema = match tokio::time::timeout(SCHEMA_TIMEOUT, database.schema()).await {
+                Ok(Ok(schema)) => schema.to_string(),
+                Ok(Err(error)) => format!("(schema unavailable: {error})\n"),
+                Err(_) => "(schema unavailable: timed out)\n".to_string(),
+            };
+            description.push_str(&format!(
+                "\n\n{} ({}, {}, up to {} rows):\n{}",
+                database.config.name,
+                database.pool.kind(),
+                if database.config.read_only {
+                    "read-only"
+                } else {
+                    "read-write"
+                },
+                database.config.max_rows,
+                schema.trim_end(),
+            ));
+        }
+
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description,
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "database": {
+                        "type": "string",
+                        "enum": self.names(),
+                        "description": "Database to query. Optional when there's only one."
+                    },
+                    "query": {
+                        "type": "string",
+                        "description": "A single SQL statement in the database's dialect."
+                    }
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let database = self.database(args.database.as_deref())?;
+        let rows = database.query(&args.query).await?;
+        Ok(SqlQueryOutput {
+            database: database.config.name.clone(),
+            columns: rows.columns,
+            rows: rows.rows,
+            truncated: rows.truncated,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_only_databases_take_one_reading_statement() {
+        let check = |sql: &str| {
+            statement_words(sql, Dialect::Sqlite).and_then(|words| check_read_only(&words))
+        };
+
+        assert!(check("SELECT * FROM users;").is_ok());
+        assert!(check("-- top users\nWITH t AS (SELECT 1) SELECT * FROM t").is_ok());
+        assert!(check("/* ; */ select 'a;b', \"x;\" from t").is_ok());
+        assert!(check("EXPLAIN SELECT 1").is_ok());
+
+        assert!(check("DELETE FROM users").is_err());
+        assert!(check("select 1; drop table users").is_err());
+        assert!(check("select 1; 'x'").is_err());
+        assert!(check("SELECT * FROM t INTO OUTFILE '/tmp/x'").is_err());
+        assert!(check("  -- nothing\n").is_err());
+        assert_eq!(
+            statement_words("select 'it''s; fine' from t -- ; drop", Dialect::Sqlite).unwrap(),
+            ["select", "from", "t"]
+        );
+        assert!(check("select 'unterminated").is_err());
+    }
+
+    #[test]
+    fn mysql_comments_and_escapes_cant_hide_file_writes() {
+        let check = |sql: &str| {
+            statement_words(sql, Dialect::MySql).and_then(|words| check_read_only(&words))
+        };
+
+        // `#` starts a comment, so the quote after it doesn't open a string.
+        assert!(check("select * from t # '\n into outfile '/tmp/x' -- '").is_err());
+        assert!(check("select * from t # 'note\nwhere id = 1").is_ok());
+        // `--` only starts a comment before whitespace; `--1` is minus minus one.
+        assert!(check("select 1 --1 into outfile '/tmp/x'").is_err());
+        assert!(check("select 1 -- into outfile '/tmp/x'").is_ok());
+        // Whether a backslash escapes depends on the server's sql_mode.
+        assert!(check("select '\\' into outfile '/tmp/x' -- '").is_err());
+        assert!(check("select 'it''s' from t").is_ok());
+        // `/*! ... */` is executed, not ignored.
+        assert!(check("select 1 /*! into outfile '/tmp/x' */").is_err());
+        assert!(check("select 1 /* into outfile */").is_ok());
+    }
+
+    #[test]
+    fn postgres_strings_and_comments_are_lexed_as_postgres_does() {
+        let words = |sql: &str| statement_words(sql, Dialect::Postgres);
+
+        // Block comments nest.
+        assert_eq!(
+            words("select 1 /* /* */ ; delete from t */").unwrap(),
+            ["select", "1"]
+        );
+        assert!(statement_words("select 1 /* /* */ ; delete from t */", Dialect::Sqlite).is_err());
+        // `E'...'` strings take backslash escapes.
+        assert!(words("select E'\\'' ; delete from t; --'").is_err());
+        assert_eq!(
+            words("select e'a\\'b' from t").unwrap(),
+            ["select", "e", "from", "t"]
+        );
+        // Dollar-quoted strings end only at their own tag.
+        assert!(words("select $$ ' $$; delete from t").is_err());
+        assert_eq!(
+            words("select $body$ it's $$ $body$ from t where id = $1").unwrap(),
+            ["select", "from", "t", "where", "id", "1"]
+        );
+        assert!(words("select $tag$ never closed").is_err());
+        assert!(words("select 1 /* never closed").is_err());
+    }
+
+    #[test]
+    fn schema_lists_columns_by_table() {
+        let columns = [
+            ("orders".into(), "id".into(), "INTEGER".into()),
+            ("orders".into(), "total".into(), "REAL".into()),
+            ("users".into(), "email".into(), "".into()),
+        ];
+        assert_eq!(
+            format_schema(&columns),
+            "orders(id integer, total real)\nusers(email)\n"
+        );
+        assert_eq!(format_schema(&[]), "(no tables)");
+    }
+
+    #[tokio::test]
+    async fn sqlite_queries_are_capped_and_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.db");
+        let url = format!("sqlite:{}?mode=rwc", path.display());
+        let setup = sqlx::SqlitePool::connect(&url).await.unwrap();
+        sqlx::query("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, score REAL)")
+            .execute(&setup)
+            .await
+            .unwrap();
+        for (id, name) in [(1, "ada"), (2, "grace"), (3, "linus")] {
+            sqlx::query("INSERT INTO users (id, name, score) VALUES (?, ?, 1.5)")
+                .bind(id)
+                .bind(name)
+                .execute(&setup)
+                .await
+                .unwrap();
+        }
+        setup.close().await;
+
+        let config = SqlDatabaseConfig {
+            name: "app".into(),
+            url: format!("sqlite:{}", path.display()),
+            read_only: true,
+            max_rows: 2,
+            timeout_secs: 5,
+        };
+        let tool = SqlQueryTool::new(&[config]).unwrap();
+
+        let output = tool
+            .call(SqlQueryArgs {
+                database: None,
+                query: "SELECT id, name, score FROM users ORDER BY id".into(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(output.columns, ["id", "name", "score"]);
+        assert_eq!(
+            output.rows,
+            [
+                vec![serde_json::json!(1), "ada".into(), serde_json::json!(1.5)],
+                vec![serde_json::json!(2), "grace".into(), serde_json::json!(1.5)],
+            ]
+        );
+        assert!(output.truncated);
+
+        let refused = tool
+            .call(SqlQueryArgs {
+                database: Some("app".into()),
+                query: "UPDATE users SET name = 'x'".into(),
+            })
+            .await;
+        assert!(refused.is_err());
+
+        let definition = tool.definition(String::new()).await;
+        assert!(
+            definition
+                .description
+                .contains("users(id integer, name text, score real)")
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Structured reply tool: the channel's `reply` on structured turns.
+//!
+//! When a message asks for a structured answer (see
+//! [`InboundMessage::wants_structured_reply`](crate::InboundMessage::wants_structured_reply)),
+//! the channel registers this tool under the `reply` name instead of
+//! [`ReplyTool`](super::ReplyTool). Its arguments are a [`StructuredReply`],
+//! so the provider's tool-call schema holds the model to that shape, and the
+//! turn ends the same way a plain reply does. Supervised channels hold
+//! structured replies as drafts like any other reply; the sender chooses the
+//! format, so it can't be a way around review.
+
+use crate::conversation::ConversationLogger;
+use crate::conversation::drafts::DraftStore;
+use crate::tools::{RepliedFlag, SentReplies, SentReply};
+use crate::{ChannelId, OutboundResponse, ResponseSender, StructuredReply};
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+
+/// Tool for answering a structured turn.
+#[derive(Debug, Clone)]
+pub struct StructuredReplyTool {
+    response_tx: ResponseSender,
+    conversation_id: String,
+    conversation_logger: ConversationLogger,
+    channel_id: ChannelId,
+    replied_flag: RepliedFlag,
+    agent_display_name: String,
+    /// The message being answered; drafts are decided by reactions to it.
+    trigger_message_id: Option<String>,
+    /// Holds replies as drafts while the channel is supervised.
+    drafts: DraftStore,
+    sent_replies: SentReplies,
+}
+
+impl StructuredReplyTool {
+    /// Create a structured reply tool bound to a conversation's response channel.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        response_tx: ResponseSender,
+        conversation_id: impl Into<String>,
+        conversation_logger: ConversationLogger,
+        channel_id: ChannelId,
+        replied_flag: RepliedFlag,
+        agent_display_name: impl Into<String>,
+        trigger_message_id: Option<String>,
+        drafts: DraftStore,
+        sent_replies: SentReplies,
+    ) -> Self {
+        Self {
+            response_tx,
+            conversation_id: conversation_id.into(),
+            conversation_logger,
+            channel_id,
+            replied_flag,
+            agent_display_name: agent_display_name.into(),
+            trigger_message_id,
+            drafts,
+            sent_replies,
+        }
+    }
+}
+
+/// Error type for structured reply tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Structured reply failed: {0}")]
+pub struct StructuredReplyError(String);
+
+/// Output from structured reply tool.
+#[derive(Debug, Serialize)]
+pub struct StructuredReplyOutput {
+    pub success: bool,
+    pub conversation_id: String,
+}
+
+impl Tool for StructuredReplyTool {
+    const NAME: &'static str = "reply";
+
+    type Error = StructuredReplyError;
+    type Args = StructuredReply;
+    type Output = StructuredReplyOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        let parameters = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["reply", "clarify", "decline", "handoff"],
+                    "description": "reply: you answer the request. clarify: you need something from the user first. decline: you won't or can't help. handoff: a human should take over."
+                },
+                "message": {
+                    "type": "string",
+                    "description": "What the user reads: the answer, the question, or the reason."
+                },
+                "confidence": {
+                    "type": "number",
+                    "minimum": 0,
+                    "maximum": 1,
+                    "description": "How sure you are, from 0 to 1."
+                }
+            },
+            "required": ["action", "message", "confidence"],
+            "additionalProperties": false
+        });
+
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/structured_reply").to_string(),
+            parameters,
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        tracing::info!(
+            conversation_id = %self.conversation_id,
+            action = ?args.action,
+            confidence = ?args.confidence,
+            message_len = args.message.len(),
+            "structured reply tool called"
+        );
+
+        match args.confidence {
+            Some(confidence) if (0.0..=1.0).contains(&confidence) => {}
+            Some(confidence) => {
+                return Err(StructuredReplyError(format!(
+                    "confidence must be between 0 and 1, got {confidence}"
+                )));
+            }
+            None => return Err(StructuredReplyError("confidence is required".into())),
+        }
+        if crate::tools::should_block_user_visible_text(&args.message) {
+            tracing::warn!(
+                conversation_id = %self.conversation_id,
+                "structured reply tool blocked tool-like message"
+            );
+            return Err(StructuredReplyError(
+                "blocked reply message: looks like tool syntax or structured payload".into(),
+            ));
+        }
+
+        // Supervised channels hold the reply until an operator approves it;
+        // it's logged once it's actually sent.
+        let message = args.message.clone();
+        let response = self
+            .drafts
+            .hold(
+                &self.channel_id,
+                self.trigger_message_id.as_deref(),
+                self.response_tx.current_turn().await,
+                OutboundResponse::Structured(args),
+            )
+            .await
+            .map_err(|e| StructuredReplyError(format!("failed to hold reply for review: {e}")))?;
+        if !matches!(response, OutboundResponse::Draft { .. }) {
+            let message_id = self.conversation_logger.log_bot_message_with_name(
+                &self.channel_id,
+                &message,
+                Some(&self.agent_display_name),
+            );
+            self.sent_replies.write().await.push(SentReply {
+                message_id,
+                content: message,
+            });
+        }
+
+        self.response_tx
+            .send(response)
+            .await
+            .map_err(|e| StructuredReplyError(format!("failed to send reply: {e}")))?;
+
+        self.replied_flag.store(true, Ordering::Relaxed);
+
+        Ok(StructuredReplyOutput {
+            success: true,
+            conversation_id: self.conversation_id.clone(),
+        })
+    }
+}
@@ -0,0 +1,155 @@
+//! Sub-branch tool for splitting a branch's thinking into parts (branch only).
+
+use crate::agent::branch::{Branch, BranchConclusion};
+use crate::agent::channel::{ChannelState, run_sub_branch};
+use crate::{BranchId, TurnId};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
+
+/// Tool for spawning a sub-branch and waiting for its conclusion.
+///
+/// Registered on branches shallower than `max_branch_depth`. Sub-branches
+/// fork the same channel history as the branch that spawns them, and their
+/// conclusions are recorded on it so the channel gets them as one tree.
+#[derive(Clone)]
+pub struct SubBranchTool {
+    state: ChannelState,
+    parent_id: BranchId,
+    parent_depth: usize,
+    parent_cancel_token: CancellationToken,
+    /// The channel turn that spawned the parent, passed on to sub-branches.
+    parent_turn_id: Option<TurnId>,
+    history: Vec<rig::message::Message>,
+    sub_results: Arc<Mutex<Vec<BranchConclusion>>>,
+    /// Sub-branches of this parent currently running.
+    running: Arc<AtomicUsize>,
+}
+
+impl std::fmt::Debug for SubBranchTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SubBranchTool")
+            .field("parent_id", &self.parent_id)
+            .field("parent_depth", &self.parent_depth)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SubBranchTool {
+    /// Create the tool for `branch`, before it starts running.
+    pub fn new(state: ChannelState, branch: &Branch) -> Self {
+        Self {
+            state,
+            parent_id: branch.id,
+            parent_depth: branch.depth,
+            parent_cancel_token: branch.cancel_token.clone(),
+            parent_turn_id: branch.turn_id,
+            history: branch.history.clone(),
+            sub_results: branch.sub_results.clone(),
+            running: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+/// Error type for sub-branch tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Sub-branch failed: {0}")]
+pub struct SubBranchError(String);
+
+/// Arguments for sub-branch tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SubBranchArgs {
+    /// The part of the problem the sub-branch should work on.
+    pub description: String,
+}
+
+/// Output from sub-branch tool.
+#[derive(Debug, Serialize)]
+pub struct SubBranchOutput {
+    /// The ID of the sub-branch.
+    pub branch_id: BranchId,
+    /// The sub-branch's conclusion, with those of its own sub-branches.
+    pub conclusion: String,
+}
+
+/// Decrements the running count when a sub-branch call ends, however it ends.
+struct RunningGuard(Arc<AtomicUsize>);
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Tool for SubBranchTool {
+    const NAME: &'static str = "sub_branch";
+
+    type Error = SubBranchError;
+    type Args = SubBranchArgs;
+    type Output = SubBranchOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/sub_branch").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "description": {
+                        "type": "string",
+                        "description": "The part of the problem the sub-branch should work on. Be specific about what conclusion you want back."
+                    }
+                },
+                "required": ["description"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let runtime_config = &self.state.deps.runtime_config;
+        let max_depth = **runtime_config.max_branch_depth.load();
+        if self.parent_depth >= max_depth {
+            return Err(SubBranchError(format!(
+                "branch depth limit ({max_depth}) reached, work on this directly"
+            )));
+        }
+
+        let max_running = **runtime_config.max_concurrent_branches.load();
+        if self.running.fetch_add(1, Ordering::SeqCst) >= max_running {
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            return Err(SubBranchError(format!(
+                "at most {max_running} sub-branches can run at once"
+            )));
+        }
+        let _running = RunningGuard(self.running.clone());
+
+        let result = run_sub_branch(
+            &self.state,
+            self.parent_id,
+            self.parent_depth,
+            &self.parent_cancel_token,
+            self.parent_turn_id,
+            self.history.clone(),
+            &args.description,
+        )
+        .await
+        .map_err(|error| SubBranchError(error.to_string()))?;
+
+        let output = SubBranchOutput {
+            branch_id: result.branch_id,
+            conclusion: crate::agent::branch::render_conclusion_tree(
+                &result.conclusion,
+                &result.sub_results,
+            ),
+        };
+        self.sub_results
+            .lock()
+            .expect("sub-branch results lock poisoned")
+            .push(result);
+        Ok(output)
+    }
+}
@@ -0,0 +1,105 @@
+//! Unschedule tool for channels: remove a task created with `schedule`.
+
+use crate::ChannelId;
+use crate::conversation::ChannelStore;
+use crate::tools::cron::CronTool;
+use crate::tools::schedule::current_delivery_target;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool for removing a scheduled task that delivers to the current channel.
+///
+/// Jobs delivering elsewhere are left alone so one conversation can't cancel
+/// another's schedules; the `cron` tool manages jobs across channels.
+#[derive(Debug, Clone)]
+pub struct UnscheduleTool {
+    cron: CronTool,
+    channel_store: ChannelStore,
+    channel_id: ChannelId,
+}
+
+impl UnscheduleTool {
+    pub fn new(cron: CronTool, channel_store: ChannelStore, channel_id: ChannelId) -> Self {
+        Self {
+            cron,
+            channel_store,
+            channel_id,
+        }
+    }
+}
+
+/// Error type for unschedule tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Unschedule failed: {0}")]
+pub struct UnscheduleError(String);
+
+/// Arguments for unschedule tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UnscheduleArgs {
+    /// The ID passed to `schedule` when the job was created.
+    pub id: String,
+}
+
+/// Output from unschedule tool.
+#[derive(Debug, Serialize)]
+pub struct UnscheduleOutput {
+    pub success: bool,
+    pub message: String,
+}
+
+impl Tool for UnscheduleTool {
+    const NAME: &'static str = "unschedule";
+
+    type Error = UnscheduleError;
+    type Args = UnscheduleArgs;
+    type Output = UnscheduleOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/unschedule").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "The ID of the scheduled task to remove."
+                    }
+                },
+                "required": ["id"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let delivery_target = current_delivery_target(&self.channel_store, &self.channel_id)
+            .await
+            .map_err(UnscheduleError)?;
+
+        let removed = self
+            .cron
+            .delete_for_target(&args.id, &delivery_target)
+            .await
+            .map_err(|error| UnscheduleError(error.to_string()))?;
+
+        if !removed {
+            return Ok(UnscheduleOutput {
+                success: false,
+                message: format!("No task '{}' is scheduled for this channel.", args.id),
+            });
+        }
+
+        tracing::info!(
+            cron_id = %args.id,
+            channel_id = %self.channel_id,
+            "task unscheduled from channel"
+        );
+
+        Ok(UnscheduleOutput {
+            success: true,
+            message: format!("Task '{}' unscheduled.", args.id),
+        })
+    }
+}
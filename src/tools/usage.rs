@@ -0,0 +1,79 @@
+//! Usage tool for checking the channel's token spend and budget (channel only).
+
+use crate::agent::cost::CostTracker;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool for reading the channel's accumulated token usage and cost.
+#[derive(Debug, Clone)]
+pub struct UsageTool {
+    cost_tracker: CostTracker,
+    budget_usd: Option<f64>,
+}
+
+impl UsageTool {
+    pub fn new(cost_tracker: CostTracker, budget_usd: Option<f64>) -> Self {
+        Self {
+            cost_tracker,
+            budget_usd,
+        }
+    }
+}
+
+/// Error type for usage tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Usage lookup failed: {0}")]
+pub struct UsageError(String);
+
+/// Arguments for usage tool (none).
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UsageArgs {}
+
+/// Output from usage tool.
+#[derive(Debug, Serialize)]
+pub struct UsageOutput {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cached_input_tokens: u64,
+    pub estimated_cost_usd: f64,
+    pub last_turn_cost_usd: f64,
+    /// None when the channel has no spending limit.
+    pub budget_usd: Option<f64>,
+    pub remaining_usd: Option<f64>,
+}
+
+impl Tool for UsageTool {
+    const NAME: &'static str = "usage";
+
+    type Error = UsageError;
+    type Args = UsageArgs;
+    type Output = UsageOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/usage").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {}
+            }),
+        }
+    }
+
+    async fn call(&self, _args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let mut usage = self.cost_tracker.usage();
+        usage.budget_usd = self.budget_usd;
+
+        Ok(UsageOutput {
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cached_input_tokens: usage.cached_input_tokens,
+            estimated_cost_usd: usage.estimated_cost_usd,
+            last_turn_cost_usd: usage.last_turn_cost_usd,
+            budget_usd: usage.budget_usd,
+            remaining_usd: usage.remaining_usd(),
+        })
+    }
+}
@@ -0,0 +1,191 @@
+//! Use skill tool: activate a skill for the current turn and call its HTTP
+//! tools (channel only).
+//!
+//! Called with just a skill name, it returns the skill's instructions and the
+//! tools it declares, and marks the skill active. Called with `tool` and
+//! `arguments`, it runs one of an active skill's tools. The tool is rebuilt
+//! every turn, so activation lasts for one turn.
+
+use crate::config::RuntimeConfig;
+use crate::tools::{MAX_TOOL_OUTPUT_BYTES, truncate_output};
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const SKILL_TOOL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Tool for activating a skill and calling its tools.
+#[derive(Debug, Clone)]
+pub struct UseSkillTool {
+    runtime_config: Arc<RuntimeConfig>,
+    client: reqwest::Client,
+    /// Skills activated this turn (lowercase names).
+    active: Arc<Mutex<HashSet<String>>>,
+}
+
+impl UseSkillTool {
+    pub fn new(runtime_config: Arc<RuntimeConfig>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(SKILL_TOOL_TIMEOUT)
+            .build()
+            .expect("hardcoded reqwest client config");
+
+        Self {
+            runtime_config,
+            client,
+            active: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+}
+
+/// Error type for use_skill tool.
+#[derive(Debug, thiserror::Error)]
+#[error("use_skill failed: {0}")]
+pub struct UseSkillError(String);
+
+/// Arguments for use_skill tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UseSkillArgs {
+    /// Name of the skill, as listed in <available_skills>.
+    pub name: String,
+    /// One of the skill's tools to call. Omit to activate the skill.
+    #[serde(default)]
+    pub tool: Option<String>,
+    /// Arguments for the tool.
+    #[serde(default)]
+    pub arguments: serde_json::Value,
+}
+
+/// A tool a skill provides, as shown on activation.
+#[derive(Debug, Serialize)]
+pub struct SkillToolSummary {
+    pub name: String,
+    pub description: String,
+    pub parameters: serde_json::Value,
+}
+
+/// Output from use_skill tool.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum UseSkillOutput {
+    Activated {
+        skill: String,
+        instructions: String,
+        tools: Vec<SkillToolSummary>,
+    },
+    ToolResult {
+        skill: String,
+        tool: String,
+        status: u16,
+        body: String,
+    },
+}
+
+impl Tool for UseSkillTool {
+    const NAME: &'static str = "use_skill";
+
+    type Error = UseSkillError;
+    type Args = UseSkillArgs;
+    type Output = UseSkillOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/use_skill").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "The skill name, exactly as it appears in <available_skills>."
+                    },
+                    "tool": {
+                        "type": "string",
+                        "description": "A tool of an activated skill to call. Omit to activate the skill."
+                    },
+                    "arguments": {
+                        "type": "object",
+                        "description": "Arguments for the tool, matching the parameters returned on activation."
+                    }
+                },
+                "required": ["name"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // Held across the request, so take an owned snapshot.
+        let skills = self.runtime_config.skills.load_full();
+        let skill = skills.get(&args.name).ok_or_else(|| {
+            UseSkillError(format!(
+                "skill '{}' not found. Available skills are listed in <available_skills>.",
+                args.name
+            ))
+        })?;
+        let key = skill.name.to_lowercase();
+
+        let Some(tool_name) = args.tool else {
+            self.active
+                .lock()
+                .expect("use_skill lock poisoned")
+                .insert(key);
+            tracing::info!(skill = %skill.name, "skill activated");
+            return Ok(UseSkillOutput::Activated {
+                skill: skill.name.clone(),
+                instructions: skill.content.clone(),
+                tools: skill
+                    .tools
+                    .iter()
+                    .map(|tool| SkillToolSummary {
+                        name: tool.name.clone(),
+                        description: tool.description.clone(),
+                        parameters: tool.parameters_schema(),
+                    })
+                    .collect(),
+            });
+        };
+
+        if !self
+            .active
+            .lock()
+            .expect("use_skill lock poisoned")
+            .contains(&key)
+        {
+            return Err(UseSkillError(format!(
+                "skill '{}' is not active. Call use_skill with just the name first.",
+                skill.name
+            )));
+        }
+        let tool = skill.tool(&tool_name).ok_or_else(|| {
+            UseSkillError(format!("skill '{}' has no tool '{tool_name}'", skill.name))
+        })?;
+
+        let allowed_env = self.runtime_config.skill_env_allowlist.load();
+        let request = tool
+            .build_request(&self.client, &args.arguments, &allowed_env)
+            .map_err(UseSkillError)?;
+        let response = request
+            .send()
+            .await
+            .map_err(|error| UseSkillError(format!("request failed: {error}")))?;
+        let status = response.status().as_u16();
+        let body = response
+            .text()
+            .await
+            .map_err(|error| UseSkillError(format!("failed to read response: {error}")))?;
+
+        tracing::debug!(skill = %skill.name, tool = %tool_name, status, "skill tool called");
+
+        Ok(UseSkillOutput::ToolResult {
+            skill: skill.name.clone(),
+            tool: tool_name,
+            status,
+            body: truncate_output(&body, MAX_TOOL_OUTPUT_BYTES),
+        })
+    }
+}
@@ -0,0 +1,620 @@
+//! Web fetch tool: download a page and reduce it to readable text (branches
+//! and workers).
+
+use moka::sync::Cache;
+use regex::Regex;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+/// User agent sent with requests and matched against robots.txt groups.
+const USER_AGENT: &str = "spacebot";
+
+/// Max bytes read from a response body before the rest is dropped.
+const MAX_FETCH_BYTES: usize = 2 * 1024 * 1024;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Redirects followed before a fetch gives up.
+const MAX_REDIRECTS: usize = 10;
+
+const CACHE_CAPACITY: u64 = 256;
+const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Fetched pages and robots.txt bodies, keyed by URL. One cache is shared by
+/// every branch and worker of a channel, so the same page isn't downloaded
+/// repeatedly while a conversation works on it.
+pub type WebFetchCache = Cache<String, Arc<WebFetchOutput>>;
+
+/// Create an empty page cache.
+pub fn web_fetch_cache() -> WebFetchCache {
+    Cache::builder()
+        .max_capacity(CACHE_CAPACITY)
+        .time_to_live(CACHE_TTL)
+        .build()
+}
+
+/// Tool for fetching a web page as readable text.
+#[derive(Debug, Clone)]
+pub struct WebFetchTool {
+    client: reqwest::Client,
+    cache: WebFetchCache,
+}
+
+impl WebFetchTool {
+    pub fn new(cache: WebFetchCache) -> Self {
+        // Redirects are followed by hand so every hop is checked against the
+        // address blocklist and robots.txt. The resolver re-checks addresses
+        // at connect time, so a host can't pass the check and then rebind.
+        let client = reqwest::Client::builder()
+            .gzip(true)
+            .user_agent(USER_AGENT)
+            .timeout(FETCH_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .no_proxy()
+            .dns_resolver(Arc::new(PublicOnlyResolver))
+            .build()
+            .expect("hardcoded reqwest client config");
+
+        Self { client, cache }
+    }
+}
+
+/// Error type for web fetch tool.
+#[derive(Debug, thiserror::Error)]
+pub enum WebFetchError {
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+
+    #[error("Fetching {0} is disallowed by the site's robots.txt")]
+    DisallowedByRobots(String),
+
+    #[error("Fetching {0} is blocked: it points at a private or internal address")]
+    BlockedAddress(String),
+
+    #[error("Web fetch request failed: {0}")]
+    RequestFailed(String),
+
+    #[error("Unsupported content type: {0}")]
+    UnsupportedContent(String),
+}
+
+/// Arguments for web fetch tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct WebFetchArgs {
+    /// The http(s) URL to fetch.
+    pub url: String,
+}
+
+/// Output from web fetch tool.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebFetchOutput {
+    /// The final URL after redirects.
+    pub url: String,
+    /// Page title, when the page has one.
+    pub title: Option<String>,
+    /// Readable text content of the page.
+    pub content: String,
+    /// Whether the body or the extracted text was cut short.
+    pub truncated: bool,
+}
+
+impl Tool for WebFetchTool {
+    const NAME: &'static str = "web_fetch";
+
+    type Error = WebFetchError;
+    type Args = WebFetchArgs;
+    type Output = WebFetchOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/web_fetch").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "url": {
+                        "type": "string",
+                        "description": "The full http:// or https:// URL of the page to read."
+                    }
+                },
+                "required": ["url"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let url = reqwest::Url::parse(args.url.trim())
+            .map_err(|error| WebFetchError::InvalidUrl(error.to_string()))?;
+
+        if let Some(cached) = self.cache.get(url.as_str()) {
+            tracing::debug!(%url, "web fetch served from cache");
+            return Ok((*cached).clone());
+        }
+
+        let response = self.get(url.clone(), true).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(WebFetchError::RequestFailed(format!("HTTP {status}")));
+        }
+
+        let final_url = response.url().to_string();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("text/html")
+            .to_ascii_lowercase();
+        let is_html = content_type.contains("html");
+        if !is_html && !content_type.starts_with("text/") && !content_type.contains("json") {
+            return Err(WebFetchError::UnsupportedContent(content_type));
+        }
+
+        let (body, body_truncated) = read_limited(response, MAX_FETCH_BYTES).await?;
+        let body = String::from_utf8_lossy(&body);
+
+        let (title, text) = if is_html {
+            (extract_title(&body), extract_readable_text(&body))
+        } else {
+            (None, body.into_owned())
+        };
+        let content = crate::tools::truncate_output(&text, crate::tools::MAX_TOOL_OUTPUT_BYTES);
+
+        let output = WebFetchOutput {
+            url: final_url,
+            title,
+            truncated: body_truncated || content.len() < text.len(),
+            content,
+        };
+        self.cache.insert(url.to_string(), Arc::new(output.clone()));
+
+        Ok(output)
+    }
+}
+
+impl WebFetchTool {
+    /// GET `url`, following redirects. Every hop must be a public http(s)
+    /// address and, when `check_robots` is set, allowed by its robots.txt.
+    async fn get(
+        &self,
+        mut url: reqwest::Url,
+        check_robots: bool,
+    ) -> Result<reqwest::Response, WebFetchError> {
+        for _ in 0..=MAX_REDIRECTS {
+            check_url(&url).await?;
+            if check_robots && !self.robots_allows(&url).await {
+                return Err(WebFetchError::DisallowedByRobots(url.to_string()));
+            }
+
+            let response = self
+                .client
+                .get(url.clone())
+                .send()
+                .await
+                .map_err(|error| WebFetchError::RequestFailed(error.to_string()))?;
+
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|value| value.to_str().ok());
+            match location {
+                Some(location) if response.status().is_redirection() => {
+                    url = url
+                        .join(location)
+                        .map_err(|error| WebFetchError::InvalidUrl(error.to_string()))?;
+                }
+                _ => return Ok(response),
+            }
+        }
+
+        Err(WebFetchError::RequestFailed(format!(
+            "more than {MAX_REDIRECTS} redirects"
+        )))
+    }
+
+    /// Check the host's robots.txt. Hosts without a readable robots.txt allow
+    /// everything.
+    async fn robots_allows(&self, url: &reqwest::Url) -> bool {
+        let Ok(robots_url) = url.join("/robots.txt") else {
+            return true;
+        };
+
+        let robots_txt = match self.cache.get(robots_url.as_str()) {
+            Some(cached) => cached.content.clone(),
+            None => {
+                let content = self.fetch_robots(&robots_url).await.unwrap_or_default();
+                self.cache.insert(
+                    robots_url.to_string(),
+                    Arc::new(WebFetchOutput {
+                        url: robots_url.to_string(),
+                        title: None,
+                        content: content.clone(),
+                        truncated: false,
+                    }),
+                );
+                content
+            }
+        };
+
+        let path = match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_string(),
+        };
+        robots_allows(&robots_txt, USER_AGENT, &path)
+    }
+
+    async fn fetch_robots(&self, robots_url: &reqwest::Url) -> Option<String> {
+        // Boxed: `get` checks robots.txt, which comes back here.
+        let response = Box::pin(self.get(robots_url.clone(), false)).await.ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        let (body, _) = read_limited(response, 512 * 1024).await.ok()?;
+        Some(String::from_utf8_lossy(&body).into_owned())
+    }
+}
+
+/// Reject URLs that aren't http(s) or whose host is, or resolves to, a
+/// loopback, private, link-local or otherwise internal address.
+async fn check_url(url: &reqwest::Url) -> Result<(), WebFetchError> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(WebFetchError::InvalidUrl(format!(
+            "only http and https URLs can be fetched, got '{url}'"
+        )));
+    }
+
+    let Some(host) = url.host_str() else {
+        return Err(WebFetchError::InvalidUrl(format!("'{url}' has no host")));
+    };
+
+    // IPv6 literals keep their brackets in `host_str`.
+    let literal = host.trim_start_matches('[').trim_end_matches(']');
+    let addresses: Vec<SocketAddr> = match literal.parse::<std::net::IpAddr>() {
+        Ok(ip) => vec![SocketAddr::new(ip, 0)],
+        Err(_) => tokio::net::lookup_host((host, 0))
+            .await
+            .map_err(|error| WebFetchError::RequestFailed(format!("{host}: {error}")))?
+            .collect(),
+    };
+
+    if addresses
+        .iter()
+        .any(|address| crate::tools::browser::is_blocked_ip(address.ip()))
+    {
+        return Err(WebFetchError::BlockedAddress(url.to_string()));
+    }
+    Ok(())
+}
+
+/// DNS resolver for the fetch client that refuses hosts resolving to
+/// internal addresses.
+#[derive(Debug)]
+struct PublicOnlyResolver;
+
+impl reqwest::dns::Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> =
+                tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            if let Some(blocked) = addresses
+                .iter()
+                .find(|address| crate::tools::browser::is_blocked_ip(address.ip()))
+            {
+                return Err(format!("{host} resolves to internal address {}", blocked.ip()).into());
+            }
+            Ok(Box::new(addresses.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// Read at most `limit` bytes of a response body. Returns whether the body was
+/// cut short.
+async fn read_limited(
+    mut response: reqwest::Response,
+    limit: usize,
+) -> Result<(Vec<u8>, bool), WebFetchError> {
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|error| WebFetchError::RequestFailed(error.to_string()))?
+    {
+        let remaining = limit - body.len();
+        if chunk.len() > remaining {
+            body.extend_from_slice(&chunk[..remaining]);
+            return Ok((body, true));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok((body, false))
+}
+
+/// Evaluate robots.txt rules for `path`.
+///
+/// Uses the group for `user_agent` if there is one, otherwise the `*` group.
+/// The longest matching `Allow`/`Disallow` rule wins, with `Allow` winning
+/// ties. Supports `*` wildcards and the `$` end anchor.
+fn robots_allows(robots_txt: &str, user_agent: &str, path: &str) -> bool {
+    let user_agent = user_agent.to_ascii_lowercase();
+    let mut specific: Vec<(bool, String)> = Vec::new();
+    let mut wildcard: Vec<(bool, String)> = Vec::new();
+    let mut has_specific_group = false;
+
+    // Agents named by the current group, and whether its rules have started
+    // (a User-agent line after rules begins a new group).
+    let mut group_agents: Vec<String> = Vec::new();
+    let mut in_rules = false;
+
+    for line in robots_txt.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim();
+
+        match key.as_str() {
+            "user-agent" => {
+                if in_rules {
+                    group_agents.clear();
+                    in_rules = false;
+                }
+                let agent = value.to_ascii_lowercase();
+                if agent != "*" && user_agent.contains(&agent) {
+                    has_specific_group = true;
+                }
+                group_agents.push(agent);
+            }
+            "allow" | "disallow" => {
+                in_rules = true;
+                // An empty Disallow allows everything.
+                if value.is_empty() {
+                    continue;
+                }
+                let rule = (key == "allow", value.to_string());
+                if group_agents
+                    .iter()
+                    .any(|agent| agent != "*" && user_agent.contains(agent.as_str()))
+                {
+                    specific.push(rule.clone());
+                }
+                if group_agents.iter().any(|agent| agent == "*") {
+                    wildcard.push(rule);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let rules = if has_specific_group {
+        specific
+    } else {
+        wildcard
+    };
+
+    rules
+        .iter()
+        .filter(|(_, pattern)| robots_pattern_matches(pattern, path))
+        .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+        .is_none_or(|(allow, _)| *allow)
+}
+
+fn robots_pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let Some(mut rest) = path.strip_prefix(parts[0]) else {
+        return false;
+    };
+    let Some((last, middle)) = parts[1..].split_last() else {
+        return !anchored || rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    if anchored {
+        rest.ends_with(last)
+    } else {
+        rest.contains(last)
+    }
+}
+
+static TITLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title\s*>").expect("hardcoded regex"));
+
+/// Elements whose content is never readable text. Each needs its own regex
+/// since the regex crate has no backreferences.
+static BOILERPLATE: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    let mut patterns = vec![Regex::new(r"(?s)<!--.*?-->").expect("hardcoded regex")];
+    for tag in [
+        "head", "script", "style", "noscript", "template", "svg", "nav", "header", "footer",
+        "aside", "form", "iframe",
+    ] {
+        patterns.push(
+            Regex::new(&format!(r"(?is)<{tag}\b[^>]*>.*?</{tag}\s*>")).expect("hardcoded regex"),
+        );
+    }
+    patterns
+});
+
+static MAIN_CONTENT: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    ["article", "main"]
+        .iter()
+        .map(|tag| {
+            Regex::new(&format!(r"(?is)<{tag}\b[^>]*>(.*)</{tag}\s*>")).expect("hardcoded regex")
+        })
+        .collect()
+});
+
+static BLOCK_TAG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)<(br|/?p|/?div|/?h[1-6]|/?li|/?tr|/?section|/?blockquote|/?pre|/?ul|/?ol|/?table)\b[^>]*>",
+    )
+    .expect("hardcoded regex")
+});
+
+static ANY_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<[^>]*>").expect("hardcoded regex"));
+
+static ENTITY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").expect("hardcoded regex")
+});
+
+fn extract_title(html: &str) -> Option<String> {
+    let title = TITLE.captures(html)?.get(1)?.as_str();
+    let title = collapse_whitespace(&decode_entities(&ANY_TAG.replace_all(title, "")));
+    (!title.is_empty()).then_some(title)
+}
+
+/// Reduce an HTML document to its readable text: drop boilerplate elements,
+/// prefer `<article>`/`<main>` when present, keep block structure as line
+/// breaks, and decode entities.
+fn extract_readable_text(html: &str) -> String {
+    let mut html = html.to_string();
+    for pattern in BOILERPLATE.iter() {
+        html = pattern.replace_all(&html, " ").into_owned();
+    }
+
+    let body = MAIN_CONTENT
+        .iter()
+        .find_map(|pattern| {
+            pattern
+                .captures(&html)?
+                .get(1)
+                .map(|m| m.as_str().to_string())
+        })
+        .unwrap_or(html);
+
+    let text = BLOCK_TAG.replace_all(&body, "\n");
+    let text = ANY_TAG.replace_all(&text, "");
+    let text = decode_entities(&text);
+
+    let mut output = String::with_capacity(text.len());
+    let mut blank_run = 0;
+    for line in text.lines() {
+        let line = collapse_whitespace(line);
+        if line.is_empty() {
+            blank_run += 1;
+            if blank_run == 1 && !output.is_empty() {
+                output.push('\n');
+            }
+            continue;
+        }
+        blank_run = 0;
+        output.push_str(&line);
+        output.push('\n');
+    }
+    output.trim_end().to_string()
+}
+
+fn decode_entities(text: &str) -> String {
+    ENTITY
+        .replace_all(text, |captures: &regex::Captures| {
+            let entity = &captures[1];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#')?.parse().ok())
+                    .and_then(char::from_u32),
+            };
+            decoded.map_or_else(|| captures[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_readable_text() {
+        let html = r#"<html><head><title>Release &amp; Notes</title>
+            <style>body { color: red }</style></head>
+            <body><nav><a href="/">Home</a></nav>
+            <main><h1>Version 2</h1><p>Faster   startup.</p>
+            <script>track()</script><p>Fixes &lt;bugs&gt; &#8212; see&nbsp;below.</p></main>
+            <footer>Copyright</footer></body></html>"#;
+
+        assert_eq!(extract_title(html).as_deref(), Some("Release & Notes"));
+        assert_eq!(
+            extract_readable_text(html),
+            "Version 2\n\nFaster startup.\n\nFixes <bugs> \u{2014} see below."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_url_blocks_internal_addresses() {
+        for url in [
+            "http://127.0.0.1/",
+            "http://10.0.0.5:8080/admin",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://[::1]/",
+            "http://[::ffff:192.168.1.1]/",
+            "http://localhost/",
+        ] {
+            let url = reqwest::Url::parse(url).unwrap();
+            assert!(
+                matches!(check_url(&url).await, Err(WebFetchError::BlockedAddress(_))),
+                "{url} should be blocked"
+            );
+        }
+
+        let url = reqwest::Url::parse("ftp://example.com/file").unwrap();
+        assert!(matches!(
+            check_url(&url).await,
+            Err(WebFetchError::InvalidUrl(_))
+        ));
+        let url = reqwest::Url::parse("http://93.184.215.14/").unwrap();
+        assert!(check_url(&url).await.is_ok());
+    }
+
+    #[test]
+    fn test_robots_allows() {
+        let robots = "\
+User-agent: *
+Disallow: /private/
+Allow: /private/public-*.html$
+
+User-agent: SpaceBot
+Disallow: /no-bots
+";
+        // The spacebot group applies instead of `*`.
+        assert!(!robots_allows(robots, "spacebot", "/no-bots/page"));
+        assert!(robots_allows(robots, "spacebot", "/private/page"));
+
+        assert!(!robots_allows(robots, "otherbot", "/private/page"));
+        assert!(robots_allows(robots, "otherbot", "/private/public-1.html"));
+        assert!(!robots_allows(
+            robots,
+            "otherbot",
+            "/private/public-1.html?x=1"
+        ));
+        assert!(robots_allows(robots, "otherbot", "/no-bots"));
+        assert!(robots_allows("", "spacebot", "/anything"));
+    }
+}
@@ -1,31 +1,51 @@
-//! Web search tool using the Brave Search API (task workers only).
+//! Web search tool backed by a configurable search provider (task workers and
+//! cortex chat).
+//!
+//! The provider comes from the agent's `[web_search]` config: Brave, a
+//! SearXNG instance, or Kagi. Each provider module maps its API onto the
+//! same structured results (title, URL, snippet), so the tool looks the same
+//! to the LLM whichever backend answers. When `web_fetch` sits on the same
+//! ToolServer, the tool points the LLM at it for reading results in full.
+
+mod brave;
+mod kagi;
+mod searxng;
+
+use crate::config::{WebSearchConfig, WebSearchProvider};
 
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-const BRAVE_WEB_SEARCH_URL: &str = "https://api.search.brave.com/res/v1/web/search";
-
-/// Tool for searching the web via Brave Search.
+/// Tool for searching the web via the configured provider.
 #[derive(Debug, Clone)]
 pub struct WebSearchTool {
     client: reqwest::Client,
-    api_key: String,
+    config: WebSearchConfig,
+    web_fetch: bool,
 }
 
 impl WebSearchTool {
-    pub fn new(api_key: impl Into<String>) -> Self {
+    pub fn new(config: WebSearchConfig) -> Self {
         let client = reqwest::Client::builder()
             .gzip(true)
+            .user_agent(concat!("spacebot/", env!("CARGO_PKG_VERSION")))
             .build()
             .expect("hardcoded reqwest client config");
 
         Self {
             client,
-            api_key: api_key.into(),
+            config,
+            web_fetch: false,
         }
     }
+
+    /// Note that `web_fetch` is available for reading results in full.
+    pub fn with_web_fetch(mut self) -> Self {
+        self.web_fetch = true;
+        self
+    }
 }
 
 /// Error type for web search tool.
@@ -37,8 +57,11 @@ pub enum WebSearchError {
     #[error("Failed to parse search response: {0}")]
     InvalidResponse(String),
 
-    #[error("Rate limited by Brave Search API")]
+    #[error("Rate limited by the search provider")]
     RateLimited,
+
+    #[error("Web search is misconfigured: {0}")]
+    Misconfigured(String),
 }
 
 /// Arguments for web search tool.
@@ -71,6 +94,9 @@ pub struct WebSearchOutput {
     pub query: String,
     /// Total number of results returned.
     pub result_count: usize,
+    /// How to read a result in full, when `web_fetch` is available.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_step: Option<&'static str>,
 }
 
 /// A single web search result.
@@ -80,37 +106,13 @@ pub struct SearchResult {
     pub title: String,
     /// Page URL.
     pub url: String,
-    /// Description snippet from the page.
-    pub description: String,
-    /// How old the result is (e.g. "2 days ago"), when available.
+    /// Snippet of the page's text, as the provider returned it.
+    pub snippet: String,
+    /// How old the result is (e.g. "2 days ago" or a publish date), when
+    /// available.
     pub age: Option<String>,
 }
 
-// -- Brave API response types (private, only model what we need) --
-
-#[derive(Debug, Deserialize)]
-struct BraveApiResponse {
-    #[serde(default)]
-    web: Option<BraveWebResults>,
-}
-
-#[derive(Debug, Deserialize)]
-struct BraveWebResults {
-    #[serde(default)]
-    results: Vec<BraveWebResult>,
-}
-
-#[derive(Debug, Deserialize)]
-struct BraveWebResult {
-    #[serde(default)]
-    title: String,
-    #[serde(default)]
-    url: String,
-    #[serde(default)]
-    description: String,
-    age: Option<String>,
-}
-
 impl Tool for WebSearchTool {
     const NAME: &'static str = "web_search";
 
@@ -119,9 +121,15 @@ impl Tool for WebSearchTool {
     type Output = WebSearchOutput;
 
     async fn definition(&self, _prompt: String) -> ToolDefinition {
+        let mut description = crate::prompts::text::get("tools/web_search").to_string();
+        if self.web_fetch {
+            description.push_str(
+                "\n\nSnippets are short: call web_fetch with a result's url to read the page in full.",
+            );
+        }
         ToolDefinition {
             name: Self::NAME.to_string(),
-            description: crate::prompts::text::get("tools/web_search").to_string(),
+            description,
             parameters: serde_json::json!({
                 "type": "object",
                 "properties": {
@@ -157,75 +165,72 @@ impl Tool for WebSearchTool {
 
     async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
         let count = args.count.clamp(1, 20);
-
-        let mut request = self
-            .client
-            .get(BRAVE_WEB_SEARCH_URL)
-            .header("Accept", "application/json")
-            .header("X-Subscription-Token", &self.api_key)
-            .query(&[("q", &args.query)])
-            .query(&[("count", &count.to_string())]);
-
-        if let Some(country) = &args.country {
-            request = request.query(&[("country", country)]);
-        }
-        if let Some(search_lang) = &args.search_lang {
-            request = request.query(&[("search_lang", search_lang)]);
-        }
-        if let Some(freshness) = &args.freshness {
-            request = request.query(&[("freshness", freshness)]);
-        }
-
-        let response = request
-            .send()
-            .await
-            .map_err(|error| WebSearchError::RequestFailed(error.to_string()))?;
-
-        let status = response.status();
-        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
-            return Err(WebSearchError::RateLimited);
-        }
-        if !status.is_success() {
-            let body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "failed to read response body".into());
-            return Err(WebSearchError::RequestFailed(format!(
-                "HTTP {status}: {body}"
-            )));
-        }
-
-        let api_response: BraveApiResponse = response
-            .json()
-            .await
-            .map_err(|error| WebSearchError::InvalidResponse(error.to_string()))?;
-
-        let results: Vec<SearchResult> = api_response
-            .web
-            .map(|web| {
-                web.results
-                    .into_iter()
-                    .map(|result| SearchResult {
-                        title: clean_html_tags(&result.title),
-                        url: result.url,
-                        description: clean_html_tags(&result.description),
-                        age: result.age,
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
+        let config = &self.config;
+
+        let results = match config.provider {
+            WebSearchProvider::Brave => {
+                let api_key = required(config.api_key.as_deref(), "Brave needs an api_key")?;
+                brave::search(&self.client, api_key, &args, count).await?
+            }
+            WebSearchProvider::Searxng => {
+                let url = required(config.url.as_deref(), "SearXNG needs a url")?;
+                searxng::search(&self.client, url, &args, count).await?
+            }
+            WebSearchProvider::Kagi => {
+                let api_key = required(config.api_key.as_deref(), "Kagi needs an api_key")?;
+                kagi::search(&self.client, api_key, &args, count).await?
+            }
+        };
 
         let result_count = results.len();
+        let next_step = (self.web_fetch && result_count > 0)
+            .then_some("Call web_fetch with a result's url to read the full page.");
 
         Ok(WebSearchOutput {
             results,
             query: args.query,
             result_count,
+            next_step,
         })
     }
 }
 
-/// Strip basic HTML tags (like <strong>) from Brave API text fields.
+fn required<'a>(value: Option<&'a str>, message: &str) -> Result<&'a str, WebSearchError> {
+    value.ok_or_else(|| WebSearchError::Misconfigured(message.into()))
+}
+
+/// Send a provider request and parse its JSON body, mapping HTTP failures to
+/// `WebSearchError`.
+async fn send_json<T: serde::de::DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+) -> Result<T, WebSearchError> {
+    let response = request
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .map_err(|error| WebSearchError::RequestFailed(error.to_string()))?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(WebSearchError::RateLimited);
+    }
+    if !status.is_success() {
+        let body = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "failed to read response body".into());
+        return Err(WebSearchError::RequestFailed(format!(
+            "HTTP {status}: {body}"
+        )));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|error| WebSearchError::InvalidResponse(error.to_string()))
+}
+
+/// Strip basic HTML tags (like <strong>) from provider text fields.
 fn clean_html_tags(text: &str) -> String {
     let mut output = String::with_capacity(text.len());
     let mut in_tag = false;
@@ -262,4 +267,17 @@ mod tests {
         let args: WebSearchArgs = serde_json::from_str(r#"{"query": "test"}"#).unwrap();
         assert_eq!(args.count, 5);
     }
+
+    #[tokio::test]
+    async fn test_missing_credentials_are_reported() {
+        let tool = WebSearchTool::new(WebSearchConfig {
+            provider: WebSearchProvider::Searxng,
+            ..WebSearchConfig::default()
+        });
+        let args: WebSearchArgs = serde_json::from_str(r#"{"query": "test"}"#).unwrap();
+        assert!(matches!(
+            tool.call(args).await,
+            Err(WebSearchError::Misconfigured(_))
+        ));
+    }
 }
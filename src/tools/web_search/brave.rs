@@ -0,0 +1,75 @@
+//! Brave Search API provider.
+
+use super::{SearchResult, WebSearchArgs, WebSearchError, clean_html_tags, send_json};
+
+use serde::Deserialize;
+
+const BRAVE_WEB_SEARCH_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+
+// -- Brave API response types (private, only model what we need) --
+
+#[derive(Debug, Deserialize)]
+struct BraveApiResponse {
+    #[serde(default)]
+    web: Option<BraveWebResults>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BraveWebResults {
+    #[serde(default)]
+    results: Vec<BraveWebResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BraveWebResult {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    description: String,
+    age: Option<String>,
+}
+
+pub(super) async fn search(
+    client: &reqwest::Client,
+    api_key: &str,
+    args: &WebSearchArgs,
+    count: u8,
+) -> Result<Vec<SearchResult>, WebSearchError> {
+    let mut request = client
+        .get(BRAVE_WEB_SEARCH_URL)
+        .header("X-Subscription-Token", api_key)
+        .query(&[("q", &args.query)])
+        .query(&[("count", &count.to_string())]);
+
+    if let Some(country) = &args.country {
+        request = request.query(&[("country", country)]);
+    }
+    if let Some(search_lang) = &args.search_lang {
+        request = request.query(&[("search_lang", search_lang)]);
+    }
+    if let Some(freshness) = &args.freshness {
+        request = request.query(&[("freshness", freshness)]);
+    }
+
+    let response: BraveApiResponse = send_json(request).await?;
+    Ok(results(response))
+}
+
+fn results(response: BraveApiResponse) -> Vec<SearchResult> {
+    response
+        .web
+        .map(|web| {
+            web.results
+                .into_iter()
+                .map(|result| SearchResult {
+                    title: clean_html_tags(&result.title),
+                    url: result.url,
+                    snippet: clean_html_tags(&result.description),
+                    age: result.age,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,86 @@
+//! Kagi Search API provider. Kagi has no country, language, or freshness
+//! filters, so those arguments are ignored.
+
+use super::{SearchResult, WebSearchArgs, WebSearchError, clean_html_tags, send_json};
+
+use serde::Deserialize;
+
+const KAGI_SEARCH_URL: &str = "https://kagi.com/api/v0/search";
+
+/// Kagi's `t` value for a search result; other values are related searches.
+const KAGI_SEARCH_RESULT: u8 = 0;
+
+#[derive(Debug, Deserialize)]
+struct KagiResponse {
+    #[serde(default)]
+    data: Vec<KagiItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KagiItem {
+    t: u8,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    snippet: String,
+    published: Option<String>,
+}
+
+pub(super) async fn search(
+    client: &reqwest::Client,
+    api_key: &str,
+    args: &WebSearchArgs,
+    count: u8,
+) -> Result<Vec<SearchResult>, WebSearchError> {
+    let request = client
+        .get(KAGI_SEARCH_URL)
+        .header("Authorization", format!("Bot {api_key}"))
+        .query(&[("q", &args.query)])
+        .query(&[("limit", &count.to_string())]);
+
+    let response: KagiResponse = send_json(request).await?;
+    Ok(results(response, count))
+}
+
+fn results(response: KagiResponse, count: u8) -> Vec<SearchResult> {
+    response
+        .data
+        .into_iter()
+        .filter(|item| item.t == KAGI_SEARCH_RESULT)
+        .take(count as usize)
+        .map(|item| SearchResult {
+            title: clean_html_tags(&item.title),
+            url: item.url,
+            snippet: clean_html_tags(&item.snippet),
+            age: item.published,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn related_searches_are_skipped() {
+        let response: KagiResponse = serde_json::from_str(
+            r#"{
+                "meta": {"id": "abc"},
+                "data": [
+                    {"t": 0, "rank": 1, "url": "https://www.rust-lang.org/", "title": "Rust", "snippet": "A <b>language</b>", "published": "2026-02-01T00:00:00Z"},
+                    {"t": 1, "list": ["rust book", "rust async"]},
+                    {"t": 0, "rank": 2, "url": "https://doc.rust-lang.org/", "title": "Docs"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let results = results(response, 5);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].snippet, "A language");
+        assert_eq!(results[1].url, "https://doc.rust-lang.org/");
+        assert_eq!(results[1].age, None);
+    }
+}
@@ -0,0 +1,116 @@
+//! SearXNG provider. The instance must list `json` under `search.formats`
+//! in its settings, or it answers API requests with 403.
+
+use super::{SearchResult, WebSearchArgs, WebSearchError, clean_html_tags, send_json};
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct SearxngResponse {
+    #[serde(default)]
+    results: Vec<SearxngResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearxngResult {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    content: String,
+    #[serde(rename = "publishedDate")]
+    published_date: Option<String>,
+}
+
+pub(super) async fn search(
+    client: &reqwest::Client,
+    base_url: &str,
+    args: &WebSearchArgs,
+    count: u8,
+) -> Result<Vec<SearchResult>, WebSearchError> {
+    let endpoint = format!("{}/search", base_url.trim_end_matches('/'));
+    let mut request = client
+        .get(&endpoint)
+        .query(&[("q", args.query.as_str()), ("format", "json")]);
+
+    // SearXNG takes a locale such as "en-US" rather than separate language
+    // and country parameters.
+    let language = match (&args.search_lang, &args.country) {
+        (Some(language), Some(country)) => Some(format!(
+            "{}-{}",
+            language.to_lowercase(),
+            country.to_uppercase()
+        )),
+        (Some(language), None) => Some(language.to_lowercase()),
+        (None, _) => None,
+    };
+    if let Some(language) = &language {
+        request = request.query(&[("language", language)]);
+    }
+    if let Some(time_range) = args.freshness.as_deref().and_then(time_range) {
+        request = request.query(&[("time_range", time_range)]);
+    }
+
+    let response: SearxngResponse = send_json(request).await.map_err(|error| match error {
+        WebSearchError::RequestFailed(message) if message.starts_with("HTTP 403") => {
+            WebSearchError::RequestFailed(format!(
+                "{message} (is `json` enabled in the instance's search.formats?)"
+            ))
+        }
+        other => other,
+    })?;
+    Ok(results(response, count))
+}
+
+/// Map Brave-style freshness codes onto SearXNG's `time_range`.
+fn time_range(freshness: &str) -> Option<&'static str> {
+    match freshness {
+        "pd" => Some("day"),
+        "pw" => Some("week"),
+        "pm" => Some("month"),
+        "py" => Some("year"),
+        _ => None,
+    }
+}
+
+fn results(response: SearxngResponse, count: u8) -> Vec<SearchResult> {
+    response
+        .results
+        .into_iter()
+        .take(count as usize)
+        .map(|result| SearchResult {
+            title: clean_html_tags(&result.title),
+            url: result.url,
+            snippet: clean_html_tags(&result.content),
+            age: result.published_date,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_are_capped_and_cleaned() {
+        let response: SearxngResponse = serde_json::from_str(
+            r#"{
+                "query": "rust",
+                "results": [
+                    {"title": "The <b>Rust</b> Book", "url": "https://doc.rust-lang.org/book/", "content": "Learn Rust", "publishedDate": "2026-01-02T00:00:00"},
+                    {"title": "Rust", "url": "https://www.rust-lang.org/", "content": "A language"},
+                    {"title": "Extra", "url": "https://example.com/"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let results = results(response, 2);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "The Rust Book");
+        assert_eq!(results[0].age.as_deref(), Some("2026-01-02T00:00:00"));
+        assert_eq!(results[1].snippet, "A language");
+        assert_eq!(time_range("pw"), Some("week"));
+    }
+}
@@ -0,0 +1,209 @@
+//! Workspace list tool for browsing worker working directories (channel only).
+
+use crate::agent::worker_workspace::workspace_path;
+use crate::tools::MAX_DIR_ENTRIES;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Tool for listing worker workspaces and the files inside them.
+#[derive(Debug, Clone)]
+pub struct WorkspaceListTool {
+    workspaces_dir: PathBuf,
+}
+
+impl WorkspaceListTool {
+    pub fn new(workspaces_dir: PathBuf) -> Self {
+        Self { workspaces_dir }
+    }
+}
+
+/// Error type for workspace list tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Workspace list failed: {0}")]
+pub struct WorkspaceListError(String);
+
+/// Arguments for workspace list tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct WorkspaceListArgs {
+    /// The worker whose files to list. Omit to list workspaces.
+    #[serde(default)]
+    pub worker_id: Option<String>,
+}
+
+/// A workspace or a file inside one.
+#[derive(Debug, Serialize)]
+pub struct WorkspaceEntry {
+    /// Worker ID when listing workspaces, relative file path otherwise.
+    pub name: String,
+    /// File size in bytes; `None` for workspaces.
+    pub size: Option<u64>,
+    /// Last modification time (RFC 3339).
+    pub modified: Option<String>,
+}
+
+/// Output from workspace list tool.
+#[derive(Debug, Serialize)]
+pub struct WorkspaceListOutput {
+    pub entries: Vec<WorkspaceEntry>,
+    /// True when more entries exist than were returned.
+    pub truncated: bool,
+}
+
+impl Tool for WorkspaceListTool {
+    const NAME: &'static str = "workspace_list";
+
+    type Error = WorkspaceListError;
+    type Args = WorkspaceListArgs;
+    type Output = WorkspaceListOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/workspace_list").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "worker_id": {
+                        "type": "string",
+                        "description": "ID of the worker whose files to list. Omit to list the retained workspaces."
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let mut entries = Vec::new();
+        let mut truncated = false;
+
+        match args.worker_id {
+            None => {
+                let mut reader = match tokio::fs::read_dir(&self.workspaces_dir).await {
+                    Ok(reader) => reader,
+                    Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                        return Ok(WorkspaceListOutput { entries, truncated });
+                    }
+                    Err(error) => return Err(WorkspaceListError(error.to_string())),
+                };
+                while let Some(entry) = reader
+                    .next_entry()
+                    .await
+                    .map_err(|error| WorkspaceListError(error.to_string()))?
+                {
+                    let Ok(metadata) = entry.metadata().await else {
+                        continue;
+                    };
+                    if !metadata.is_dir() {
+                        continue;
+                    }
+                    if entries.len() >= MAX_DIR_ENTRIES {
+                        truncated = true;
+                        break;
+                    }
+                    entries.push(WorkspaceEntry {
+                        name: entry.file_name().to_string_lossy().into_owned(),
+                        size: None,
+                        modified: modified_rfc3339(&metadata),
+                    });
+                }
+            }
+            Some(worker_id) => {
+                let worker_id = uuid::Uuid::parse_str(&worker_id)
+                    .map_err(|_| WorkspaceListError(format!("invalid worker ID: {worker_id}")))?;
+                let root = workspace_path(&self.workspaces_dir, worker_id);
+                if !root.is_dir() {
+                    return Err(WorkspaceListError(format!(
+                        "worker {worker_id} has no workspace (it was not retained or never existed)"
+                    )));
+                }
+                truncated = list_files(&root, &mut entries)
+                    .await
+                    .map_err(|error| WorkspaceListError(error.to_string()))?;
+            }
+        }
+
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(WorkspaceListOutput { entries, truncated })
+    }
+}
+
+/// Walk `root` breadth-first, collecting files with paths relative to it.
+/// Symlinks are skipped. Returns true when the listing hit `MAX_DIR_ENTRIES`.
+async fn list_files(root: &Path, entries: &mut Vec<WorkspaceEntry>) -> std::io::Result<bool> {
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let mut reader = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = reader.next_entry().await? {
+            let metadata = entry.metadata().await?;
+            if metadata.is_symlink() {
+                continue;
+            }
+            if metadata.is_dir() {
+                pending.push(entry.path());
+                continue;
+            }
+            if entries.len() >= MAX_DIR_ENTRIES {
+                return Ok(true);
+            }
+            let path = entry.path();
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            entries.push(WorkspaceEntry {
+                name: relative.display().to_string(),
+                size: Some(metadata.len()),
+                modified: modified_rfc3339(&metadata),
+            });
+        }
+    }
+    Ok(false)
+}
+
+fn modified_rfc3339(metadata: &std::fs::Metadata) -> Option<String> {
+    metadata
+        .modified()
+        .ok()
+        .map(|time| chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_listing_skips_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspaces_dir = dir.path().join("workspaces");
+        let worker_id = uuid::Uuid::new_v4();
+        let root = workspace_path(&workspaces_dir, worker_id);
+        std::fs::create_dir_all(root.join("out")).unwrap();
+        std::fs::write(root.join("out/result.txt"), "done").unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "hunter2").unwrap();
+        std::os::unix::fs::symlink(dir.path(), root.join("escape")).unwrap();
+
+        let tool = WorkspaceListTool::new(workspaces_dir);
+        let output = tool
+            .call(WorkspaceListArgs {
+                worker_id: Some(worker_id.to_string()),
+            })
+            .await
+            .unwrap();
+
+        let names: Vec<&str> = output
+            .entries
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect();
+        assert_eq!(names, ["out/result.txt"]);
+
+        let workspaces = tool
+            .call(WorkspaceListArgs { worker_id: None })
+            .await
+            .unwrap();
+        assert_eq!(workspaces.entries.len(), 1);
+        assert_eq!(workspaces.entries[0].name, worker_id.to_string());
+    }
+}
@@ -0,0 +1,195 @@
+//! Workspace read tool for fetching files from worker working directories
+//! (channel only).
+
+use crate::agent::worker_workspace::workspace_path;
+use crate::tools::{MAX_TOOL_OUTPUT_BYTES, truncate_output};
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Tool for reading a file from a worker's workspace.
+#[derive(Debug, Clone)]
+pub struct WorkspaceReadTool {
+    workspaces_dir: PathBuf,
+}
+
+impl WorkspaceReadTool {
+    pub fn new(workspaces_dir: PathBuf) -> Self {
+        Self { workspaces_dir }
+    }
+}
+
+/// Error type for workspace read tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Workspace read failed: {0}")]
+pub struct WorkspaceReadError(String);
+
+/// Arguments for workspace read tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct WorkspaceReadArgs {
+    /// The worker whose workspace holds the file.
+    pub worker_id: String,
+    /// Path of the file, relative to the worker's workspace.
+    pub path: String,
+}
+
+/// Output from workspace read tool.
+#[derive(Debug, Serialize)]
+pub struct WorkspaceReadOutput {
+    pub path: String,
+    pub content: String,
+}
+
+impl Tool for WorkspaceReadTool {
+    const NAME: &'static str = "workspace_read";
+
+    type Error = WorkspaceReadError;
+    type Args = WorkspaceReadArgs;
+    type Output = WorkspaceReadOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/workspace_read").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "worker_id": {
+                        "type": "string",
+                        "description": "ID of the worker whose workspace holds the file."
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Path of the file relative to the worker's workspace, as listed by workspace_list."
+                    }
+                },
+                "required": ["worker_id", "path"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let worker_id = uuid::Uuid::parse_str(&args.worker_id)
+            .map_err(|_| WorkspaceReadError(format!("invalid worker ID: {}", args.worker_id)))?;
+        let root = workspace_path(&self.workspaces_dir, worker_id)
+            .canonicalize()
+            .map_err(|_| {
+                WorkspaceReadError(format!(
+                    "worker {worker_id} has no workspace (it was not retained or never existed)"
+                ))
+            })?;
+
+        // Canonicalizing resolves `..` and symlinks, so anything that
+        // escapes the workspace is caught by the prefix check.
+        let path = root
+            .join(&args.path)
+            .canonicalize()
+            .map_err(|error| WorkspaceReadError(format!("{}: {error}", args.path)))?;
+        if !path.starts_with(&root) {
+            return Err(WorkspaceReadError(format!(
+                "{} is outside the worker's workspace",
+                args.path
+            )));
+        }
+
+        let bytes = tokio::fs::read(&path)
+            .await
+            .map_err(|error| WorkspaceReadError(format!("{}: {error}", args.path)))?;
+        let content = truncate_output(&String::from_utf8_lossy(&bytes), MAX_TOOL_OUTPUT_BYTES);
+
+        Ok(WorkspaceReadOutput {
+            path: args.path,
+            content,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_args(worker_id: uuid::Uuid, path: &str) -> WorkspaceReadArgs {
+        WorkspaceReadArgs {
+            worker_id: worker_id.to_string(),
+            path: path.into(),
+        }
+    }
+
+    /// A workspaces dir holding one worker workspace with `report.md`, and a
+    /// secret file next to it that no workspace should reach.
+    fn setup() -> (tempfile::TempDir, WorkspaceReadTool, uuid::Uuid) {
+        let dir = tempfile::tempdir().unwrap();
+        let workspaces_dir = dir.path().join("workspaces");
+        let worker_id = uuid::Uuid::new_v4();
+        let root = workspace_path(&workspaces_dir, worker_id);
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("report.md"), "# Findings").unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "hunter2").unwrap();
+        (dir, WorkspaceReadTool::new(workspaces_dir), worker_id)
+    }
+
+    #[tokio::test]
+    async fn test_reads_file_in_workspace() {
+        let (_dir, tool, worker_id) = setup();
+
+        let output = tool.call(read_args(worker_id, "report.md")).await.unwrap();
+
+        assert_eq!(output.content, "# Findings");
+    }
+
+    #[tokio::test]
+    async fn test_rejects_parent_dir_escape() {
+        let (_dir, tool, worker_id) = setup();
+
+        let error = tool
+            .call(read_args(worker_id, "../../secret.txt"))
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("outside the worker's workspace"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_absolute_path() {
+        let (dir, tool, worker_id) = setup();
+        let secret = dir.path().join("secret.txt");
+
+        let result = tool
+            .call(read_args(worker_id, &secret.display().to_string()))
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_rejects_symlink_escape() {
+        let (dir, tool, worker_id) = setup();
+        let root = workspace_path(&tool.workspaces_dir, worker_id);
+        std::os::unix::fs::symlink(dir.path().join("secret.txt"), root.join("link.txt")).unwrap();
+        std::os::unix::fs::symlink(dir.path(), root.join("outside")).unwrap();
+
+        for path in ["link.txt", "outside/secret.txt"] {
+            let error = tool.call(read_args(worker_id, path)).await.unwrap_err();
+            assert!(
+                error.to_string().contains("outside the worker's workspace"),
+                "{path}: {error}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_missing_workspace() {
+        let (_dir, tool, _worker_id) = setup();
+
+        let error = tool
+            .call(read_args(uuid::Uuid::new_v4(), "report.md"))
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("has no workspace"));
+    }
+}
@@ -30,26 +30,35 @@ async fn bootstrap_deps() -> anyhow::Result<spacebot::AgentDeps> {
     let resolved_agents = config.resolve_agents();
     let agent_config = resolved_agents.first().context("no agents configured")?;
 
-    let db = spacebot::db::Db::connect(&agent_config.data_dir)
+    let db = spacebot::db::Db::connect(&agent_config.data_dir, &agent_config.id, &config.storage)
         .await
         .context("failed to connect databases")?;
 
     let memory_store = spacebot::memory::MemoryStore::new(db.sqlite.clone());
 
-    let embedding_table = spacebot::memory::EmbeddingTable::open_or_create(&db.lance)
+    let embeddings = db
+        .open_embeddings()
         .await
-        .context("failed to init embedding table")?;
+        .context("failed to init embedding store")?;
 
-    if let Err(error) = embedding_table.ensure_fts_index().await {
+    if let Err(error) = embeddings.ensure_fts_index().await {
         eprintln!("warning: FTS index creation failed: {error}");
     }
 
     let memory_search = Arc::new(spacebot::memory::MemorySearch::new(
         memory_store,
-        embedding_table,
-        embedding_model,
+        embeddings,
+        embedding_model.clone(),
     ));
 
+    let knowledge = spacebot::knowledge::KnowledgeBase::new(
+        db.sqlite.clone(),
+        db.open_knowledge_chunks()
+            .await
+            .context("failed to init knowledge base")?,
+        embedding_model,
+    );
+
     let identity = spacebot::identity::Identity::load(&agent_config.workspace).await;
     let prompts =
         spacebot::prompts::PromptEngine::new("en").context("failed to init prompt engine")?;
@@ -80,9 +89,25 @@ async fn bootstrap_deps() -> anyhow::Result<spacebot::AgentDeps> {
         .await,
     );
 
+    let media = spacebot::media::MediaStore::open(
+        db.sqlite.clone(),
+        agent_id.clone(),
+        &config.media,
+        &config.instance_dir,
+        &agent_config.data_dir,
+    )
+    .await
+    .context("failed to init media store")?;
+
+    let worker_pool = spacebot::agent::worker_pool::WorkerPool::new(
+        agent_id.clone(),
+        runtime_config.clone(),
+        event_tx.clone(),
+    );
     Ok(spacebot::AgentDeps {
         agent_id,
         memory_search,
+        knowledge,
         llm_manager,
         mcp_manager,
         cron_tool: None,
@@ -93,6 +118,10 @@ async fn bootstrap_deps() -> anyhow::Result<spacebot::AgentDeps> {
         sandbox,
         links: Arc::new(arc_swap::ArcSwap::from_pointee(Vec::new())),
         agent_names: Arc::new(std::collections::HashMap::new()),
+        worker_pool,
+        media,
+        inbound_middleware: Default::default(),
+        outbound_middleware: Default::default(),
     })
 }
 
@@ -30,26 +30,35 @@ async fn bootstrap_deps() -> anyhow::Result<(spacebot::AgentDeps, spacebot::conf
     let resolved_agents = config.resolve_agents();
     let agent_config = resolved_agents.first().context("no agents configured")?;
 
-    let db = spacebot::db::Db::connect(&agent_config.data_dir)
+    let db = spacebot::db::Db::connect(&agent_config.data_dir, &agent_config.id, &config.storage)
         .await
         .context("failed to connect databases")?;
 
     let memory_store = spacebot::memory::MemoryStore::new(db.sqlite.clone());
 
-    let embedding_table = spacebot::memory::EmbeddingTable::open_or_create(&db.lance)
+    let embeddings = db
+        .open_embeddings()
         .await
-        .context("failed to init embedding table")?;
+        .context("failed to init embedding store")?;
 
-    if let Err(error) = embedding_table.ensure_fts_index().await {
+    if let Err(error) = embeddings.ensure_fts_index().await {
         eprintln!("warning: FTS index creation failed: {error}");
     }
 
     let memory_search = Arc::new(spacebot::memory::MemorySearch::new(
         memory_store,
-        embedding_table,
-        embedding_model,
+        embeddings,
+        embedding_model.clone(),
     ));
 
+    let knowledge = spacebot::knowledge::KnowledgeBase::new(
+        db.sqlite.clone(),
+        db.open_knowledge_chunks()
+            .await
+            .context("failed to init knowledge base")?,
+        embedding_model,
+    );
+
     let identity = spacebot::identity::Identity::load(&agent_config.workspace).await;
     let prompts =
         spacebot::prompts::PromptEngine::new("en").context("failed to init prompt engine")?;
@@ -80,9 +89,25 @@ async fn bootstrap_deps() -> anyhow::Result<(spacebot::AgentDeps, spacebot::conf
         .await,
     );
 
+    let media = spacebot::media::MediaStore::open(
+        db.sqlite.clone(),
+        agent_id.clone(),
+        &config.media,
+        &config.instance_dir,
+        &agent_config.data_dir,
+    )
+    .await
+    .context("failed to init media store")?;
+
+    let worker_pool = spacebot::agent::worker_pool::WorkerPool::new(
+        agent_id.clone(),
+        runtime_config.clone(),
+        event_tx.clone(),
+    );
     let deps = spacebot::AgentDeps {
         agent_id,
         memory_search,
+        knowledge,
         llm_manager,
         mcp_manager,
         cron_tool: None,
@@ -93,6 +118,10 @@ async fn bootstrap_deps() -> anyhow::Result<(spacebot::AgentDeps, spacebot::conf
         sandbox,
         links: Arc::new(arc_swap::ArcSwap::from_pointee(Vec::new())),
         agent_names: Arc::new(std::collections::HashMap::new()),
+        worker_pool,
+        media,
+        inbound_middleware: Default::default(),
+        outbound_middleware: Default::default(),
     };
 
     Ok((deps, config))
@@ -143,10 +172,26 @@ fn build_channel_system_prompt(rc: &spacebot::config::RuntimeConfig) -> String {
         .unwrap_or_default();
 
     let browser_enabled = rc.browser_config.load().enabled;
-    let web_search_enabled = rc.brave_search_key.load().is_some();
+    let web_search_enabled = rc.web_search.load().is_configured();
+    let container_enabled = rc.container.load().enabled;
+    let github_enabled = rc.github_token.load().is_some();
+    let coolify_enabled = rc.coolify_url.load().is_some() && rc.coolify_token.load().is_some();
+    let sql_enabled = !rc.sql.load().is_empty();
+    let http_request_enabled = !rc.http_request.load().allowed_domains.is_empty();
+    let calendar_enabled = rc.calendar.load().is_configured();
     let opencode_enabled = rc.opencode.load().enabled;
     let worker_capabilities = prompt_engine
-        .render_worker_capabilities(browser_enabled, web_search_enabled, opencode_enabled)
+        .render_worker_capabilities(
+            browser_enabled,
+            web_search_enabled,
+            container_enabled,
+            github_enabled,
+            coolify_enabled,
+            sql_enabled,
+            http_request_enabled,
+            calendar_enabled,
+            opencode_enabled,
+        )
         .expect("failed to render worker capabilities");
 
     let conversation_context = prompt_engine
@@ -189,6 +234,8 @@ async fn dump_channel_context() {
         spacebot::agent::status::StatusBlock::new(),
     ));
     let (response_tx, _response_rx) = tokio::sync::mpsc::channel(16);
+    let turn_id = Arc::new(tokio::sync::RwLock::new(None));
+    let response_tx = spacebot::ResponseSender::new(response_tx, turn_id.clone());
 
     let state = spacebot::agent::channel::ChannelState {
         channel_id,
@@ -198,13 +245,30 @@ async fn dump_channel_context() {
         process_run_logger: spacebot::conversation::ProcessRunLogger::new(deps.sqlite_pool.clone()),
         active_workers: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
         worker_inputs: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
+        cancel_tokens: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
         status_block,
         deps: deps.clone(),
         conversation_logger,
         channel_store,
+        history_store: Arc::new(spacebot::conversation::SqliteHistoryStore::new(
+            deps.sqlite_pool.clone(),
+        )),
+        history_save_lock: Arc::new(tokio::sync::Mutex::new(())),
+        cost_tracker: spacebot::agent::cost::CostTracker::new(),
         screenshot_dir: std::path::PathBuf::from("/tmp/screenshots"),
         logs_dir: std::path::PathBuf::from("/tmp/logs"),
+        web_fetch_cache: spacebot::tools::web_fetch_cache(),
         reply_target_message_id: Arc::new(tokio::sync::RwLock::new(None)),
+        latest_message: Arc::new(tokio::sync::RwLock::new(None)),
+        profile_store: spacebot::profiles::ProfileStore::new(deps.sqlite_pool.clone()),
+        turn_id,
+        turn_span: Arc::new(tokio::sync::RwLock::new(tracing::Span::none())),
+        sent_replies: Arc::new(tokio::sync::RwLock::new(Vec::new())),
+        response_tx: response_tx.clone(),
+        drafts: spacebot::conversation::DraftStore::new(deps.sqlite_pool.clone()),
+        language: Arc::new(tokio::sync::RwLock::new(None)),
+        plan: Arc::new(spacebot::agent::plan::Plan::new()),
+        approvals: Arc::new(spacebot::agent::approval::Approvals::new()),
     };
 
     let tool_server = rig::tool::server::ToolServer::new().run();
@@ -223,6 +287,9 @@ async fn dump_channel_context() {
         None,
         None,
         None,
+        None,
+        spacebot::auth::AuthTier::Admin,
+        false,
     )
     .await
     .expect("failed to add channel tools");
@@ -277,10 +344,15 @@ async fn dump_branch_context() {
     let run_logger = spacebot::conversation::ProcessRunLogger::new(deps.sqlite_pool.clone());
     let branch_tool_server = spacebot::tools::create_branch_tool_server(
         deps.memory_search.clone(),
+        None,
+        None,
+        deps.knowledge.clone(),
         conversation_logger,
         channel_store,
         run_logger,
         "test-agent",
+        spacebot::tools::web_fetch_cache(),
+        spacebot::tools::OutputSpill::new(deps.sqlite_pool.clone(), deps.runtime_config.clone()),
     );
 
     let tool_defs = branch_tool_server
@@ -328,21 +400,30 @@ async fn dump_worker_context() {
 
     // Build the actual worker tool server
     let browser_config = (**rc.browser_config.load()).clone();
-    let brave_search_key = (**rc.brave_search_key.load()).clone();
+    let web_search = rc.web_search.load().configured();
     let worker_id = uuid::Uuid::new_v4();
 
     let worker_tool_server = spacebot::tools::create_worker_tool_server(
         deps.agent_id.clone(),
         worker_id,
         None,
+        None,
         deps.event_tx.clone(),
         browser_config,
         std::path::PathBuf::from("/tmp/screenshots"),
-        brave_search_key,
+        web_search,
         std::path::PathBuf::from("/tmp"),
+        None,
         deps.sandbox.clone(),
         vec![],
+        deps.knowledge.clone(),
+        deps.media.clone(),
         deps.runtime_config.clone(),
+        Default::default(),
+        spacebot::tools::web_fetch_cache(),
+        &[],
+        spacebot::auth::AuthTier::Admin,
+        spacebot::tools::OutputSpill::new(deps.sqlite_pool.clone(), deps.runtime_config.clone()),
     );
 
     let tool_defs = worker_tool_server
@@ -408,6 +489,8 @@ async fn dump_all_contexts() {
 
     let channel_id: spacebot::ChannelId = Arc::from("test-channel");
     let (response_tx, _response_rx) = tokio::sync::mpsc::channel(16);
+    let turn_id = Arc::new(tokio::sync::RwLock::new(None));
+    let response_tx = spacebot::ResponseSender::new(response_tx, turn_id.clone());
     let state = spacebot::agent::channel::ChannelState {
         channel_id,
         history: Arc::new(tokio::sync::RwLock::new(Vec::new())),
@@ -416,15 +499,32 @@ async fn dump_all_contexts() {
         process_run_logger: spacebot::conversation::ProcessRunLogger::new(deps.sqlite_pool.clone()),
         active_workers: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
         worker_inputs: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
+        cancel_tokens: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
         status_block: Arc::new(tokio::sync::RwLock::new(
             spacebot::agent::status::StatusBlock::new(),
         )),
         deps: deps.clone(),
         conversation_logger: conversation_logger.clone(),
         channel_store: channel_store.clone(),
+        history_store: Arc::new(spacebot::conversation::SqliteHistoryStore::new(
+            deps.sqlite_pool.clone(),
+        )),
+        history_save_lock: Arc::new(tokio::sync::Mutex::new(())),
+        cost_tracker: spacebot::agent::cost::CostTracker::new(),
         screenshot_dir: std::path::PathBuf::from("/tmp/screenshots"),
         logs_dir: std::path::PathBuf::from("/tmp/logs"),
+        web_fetch_cache: spacebot::tools::web_fetch_cache(),
         reply_target_message_id: Arc::new(tokio::sync::RwLock::new(None)),
+        latest_message: Arc::new(tokio::sync::RwLock::new(None)),
+        profile_store: spacebot::profiles::ProfileStore::new(deps.sqlite_pool.clone()),
+        turn_id,
+        turn_span: Arc::new(tokio::sync::RwLock::new(tracing::Span::none())),
+        sent_replies: Arc::new(tokio::sync::RwLock::new(Vec::new())),
+        response_tx: response_tx.clone(),
+        drafts: spacebot::conversation::DraftStore::new(deps.sqlite_pool.clone()),
+        language: Arc::new(tokio::sync::RwLock::new(None)),
+        plan: Arc::new(spacebot::agent::plan::Plan::new()),
+        approvals: Arc::new(spacebot::agent::approval::Approvals::new()),
     };
     let channel_tool_server = rig::tool::server::ToolServer::new().run();
     let skip_flag = spacebot::tools::new_skip_flag();
@@ -442,6 +542,9 @@ async fn dump_all_contexts() {
         None,
         None,
         None,
+        None,
+        spacebot::auth::AuthTier::Admin,
+        false,
     )
     .await
     .expect("failed to add channel tools");
@@ -465,10 +568,15 @@ async fn dump_all_contexts() {
     let run_logger = spacebot::conversation::ProcessRunLogger::new(deps.sqlite_pool.clone());
     let branch_tool_server = spacebot::tools::create_branch_tool_server(
         deps.memory_search.clone(),
+        None,
+        None,
+        deps.knowledge.clone(),
         conversation_logger,
         channel_store,
         run_logger,
         "test-agent",
+        spacebot::tools::web_fetch_cache(),
+        spacebot::tools::OutputSpill::new(deps.sqlite_pool.clone(), deps.runtime_config.clone()),
     );
     let branch_tool_defs = branch_tool_server.get_tool_defs(None).await.unwrap();
     let branch_tools_text = format_tool_defs(&branch_tool_defs);
@@ -488,19 +596,28 @@ async fn dump_all_contexts() {
         .render_worker_prompt(&instance_dir, &workspace_dir)
         .expect("failed to render worker prompt");
     let browser_config = (**rc.browser_config.load()).clone();
-    let brave_search_key = (**rc.brave_search_key.load()).clone();
+    let web_search = rc.web_search.load().configured();
     let worker_tool_server = spacebot::tools::create_worker_tool_server(
         deps.agent_id.clone(),
         uuid::Uuid::new_v4(),
         None,
+        None,
         deps.event_tx.clone(),
         browser_config,
         std::path::PathBuf::from("/tmp/screenshots"),
-        brave_search_key,
+        web_search,
         std::path::PathBuf::from("/tmp"),
+        None,
         deps.sandbox.clone(),
         vec![],
+        deps.knowledge.clone(),
+        deps.media.clone(),
         deps.runtime_config.clone(),
+        Default::default(),
+        spacebot::tools::web_fetch_cache(),
+        &[],
+        spacebot::auth::AuthTier::Admin,
+        spacebot::tools::OutputSpill::new(deps.sqlite_pool.clone(), deps.runtime_config.clone()),
     );
     let worker_tool_defs = worker_tool_server.get_tool_defs(None).await.unwrap();
     let worker_tools_text = format_tool_defs(&worker_tool_defs);